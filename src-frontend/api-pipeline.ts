@@ -11,7 +11,9 @@
  * - `load_pipeline_spec`: Load pipeline from JSON
  * - `save_pipeline_spec`: Save pipeline to JSON
  * - `validate_pipeline_spec`: Check pipeline validity
+ * - `resolve_pipeline_columns`: Match renamed input columns for confirmation
  * - `execute_pipeline_spec`: Run pipeline on dataset
+ * - `run_pipeline_on_dataset`: Run pipeline on a lifecycle dataset's active version
  * - `generate_powershell`: Export pipeline as PowerShell script
  * - `pipeline_from_configs`: Create pipeline from clean configs
  *
@@ -29,7 +31,10 @@
  * ```
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './invoke';
+
+import type { ColumnMatchKind } from './types/config';
+import type { LifecycleStage } from './types/dataset';
 
 /**
  * Information about a saved pipeline file.
@@ -74,18 +79,65 @@ export interface PipelineSpec {
   input?: {
     format?: string;
     path?: string;
+    /** Named inputs that concat/union steps stack onto the main input */
+    sources?: { name: string; path?: string }[];
+    /** New names for input columns, keyed by their name in the file */
+    column_renames?: Record<string, string>;
   };
 
   /** Output configuration */
   output?: {
     format?: string;
     path?: string;
+    /** Copy outputs to these destinations after the run */
+    deliver?: DeliveryTarget[];
+    /** Folder for step checkpoints (default .checkpoints next to the output) */
+    checkpoint_dir?: string;
+    /** Count rows and nulls after every step, not just checkpointed ones */
+    step_metrics?: boolean;
   };
 
+  /** Template parameters usable as {name} in any field, with default values */
+  params?: Record<string, string>;
+
   /** Spec version */
   version?: string;
 }
 
+/**
+ * Destination that pipeline outputs are copied to after a run.
+ */
+export type DeliveryTarget = (
+  | { type: 'path'; dir: string }
+  | {
+      type: 'sftp';
+      host: string;
+      port?: number;
+      user: string;
+      remote_dir: string;
+      identity_file?: string;
+    }
+) & {
+  /** Extra attempts after a transient failure (default 3) */
+  retries?: number;
+  /** Seconds before the first retry, growing with backoff (default 10) */
+  retry_delay_secs?: number;
+  /** How the delivered copy is checked (default "hash") */
+  verify?: 'none' | 'size' | 'hash';
+};
+
+/**
+ * Outcome of delivering one output file to one target.
+ */
+export interface DeliveryStatus {
+  file: string;
+  destination: string;
+  attempts: number;
+  verified: boolean;
+  /** Set when every attempt failed */
+  error?: string | null;
+}
+
 /**
  * A single transformation step in a pipeline.
  *
@@ -121,6 +173,15 @@ export interface ExecutionResult {
   /** Warning messages generated during execution */
   warnings: string[];
 
+  /** Metrics for each step, in step order */
+  steps?: StepMetrics[];
+
+  /** Result of each output delivery */
+  deliveries?: DeliveryStatus[];
+
+  /** Index of the checkpointed step this run resumed after, if it did */
+  resumed_after_step?: number | null;
+
   /** Total execution time in seconds */
   duration_secs: number;
 
@@ -128,6 +189,41 @@ export interface ExecutionResult {
   summary: string;
 }
 
+/**
+ * Where a running pipeline has got to, emitted as `pipeline:progress` events.
+ */
+export type PipelineProgress =
+  | {
+      stage: 'step';
+      /** Zero-based index of the step starting */
+      step_index: number;
+      steps: number;
+      op: string;
+      /** Rows going into the step, when counted */
+      rows: number | null;
+    }
+  | { stage: 'writing'; rows: number };
+
+/**
+ * Metrics recorded for one step of a pipeline run.
+ */
+export interface StepMetrics {
+  step_index: number;
+  /** Step `op` tag, e.g. "cast_types" */
+  op: string;
+  /** "skipped" when the step failed and the data was left unchanged */
+  status: 'applied' | 'skipped';
+  /** Counts are null unless the step was checkpointed or `output.step_metrics` is set */
+  rows_before: number | null;
+  rows_after: number | null;
+  /** Rows moved to the reject file by this step */
+  rows_rejected: number | null;
+  /** Null cells across all columns before and after the step */
+  nulls_before: number | null;
+  nulls_after: number | null;
+  duration_secs: number;
+}
+
 /**
  * Pipeline validation result.
  */
@@ -136,6 +232,48 @@ export interface ValidationResult {
   errors: string[];
 }
 
+/**
+ * A structured validation finding from `validate_pipeline_spec_detailed`.
+ *
+ * `code` and `params` are stable, so the UI can render its own (localised)
+ * message instead of the English `message`.
+ */
+export interface ValidationIssue {
+  /** Zero-based index of the offending step, or null for spec-level issues */
+  step_index: number | null;
+  /** English description */
+  message: string;
+  /** Stable error code, e.g. "unknown_column" */
+  code: string;
+  severity: 'error' | 'warning';
+  /** Offending step field, e.g. "columns" */
+  field: string | null;
+  /** Suggested replacement value, e.g. the closest column name */
+  suggestion: string | null;
+  /** Values interpolated into the message */
+  params: Record<string, string>;
+}
+
+/** An input column matched to a column the pipeline refers to */
+export interface ColumnMatch {
+  /** Name the pipeline uses */
+  expected: string;
+  /** Name in the input file */
+  column: string;
+  kind: ColumnMatchKind;
+  /** Edit distance between the sanitised names */
+  distance: number;
+}
+
+/** Result of `resolve_pipeline_columns`: matches for the user to confirm */
+export interface ColumnResolution {
+  matches: ColumnMatch[];
+  /** Columns the pipeline refers to that nothing in the input matched */
+  unmatched_expected: string[];
+  /** Input columns matched to nothing */
+  unmatched_columns: string[];
+}
+
 /**
  * Lists all saved pipeline specifications.
  *
@@ -276,6 +414,98 @@ export async function validatePipeline(
   }
 }
 
+/**
+ * Validates a pipeline and returns structured issues for field highlighting.
+ *
+ * **Backend**: Calls `validate_pipeline_spec_detailed` in `src/tauri_app.rs`
+ *
+ * @param spec - Pipeline specification to validate
+ * @param inputPath - Path to input dataset for schema validation
+ * @returns Promise resolving to the issues found (empty if valid)
+ */
+export async function validatePipelineDetailed(
+  spec: PipelineSpec,
+  inputPath: string
+): Promise<ValidationIssue[]> {
+  try {
+    const specJson = JSON.stringify(spec);
+    return await invoke<ValidationIssue[]>('validate_pipeline_spec_detailed', {
+      specJson,
+      inputPath,
+    });
+  } catch (error) {
+    console.error('Failed to validate pipeline:', error);
+    throw error;
+  }
+}
+
+/**
+ * Matches the columns a pipeline refers to but the input lacks with the
+ * input's columns, ignoring case and spacing or within a small edit distance.
+ *
+ * **Backend**: Calls `resolve_pipeline_columns` in `src/tauri_app.rs`
+ *
+ * Confirmed matches go in `spec.input.column_renames`.
+ *
+ * @param spec - Pipeline specification
+ * @param inputPath - Path to the input dataset
+ * @returns Promise resolving to the suggested matches
+ */
+export async function resolvePipelineColumns(
+  spec: PipelineSpec,
+  inputPath: string
+): Promise<ColumnResolution> {
+  try {
+    const specJson = JSON.stringify(spec);
+    return await invoke<ColumnResolution>('resolve_pipeline_columns', { specJson, inputPath });
+  } catch (error) {
+    console.error('Failed to resolve pipeline columns:', error);
+    throw error;
+  }
+}
+
+/** A column name and type in a dry-run preview */
+export interface ColumnPreview {
+  name: string;
+  dtype: string;
+}
+
+/** Schema after one step of a dry run */
+export interface StepPreview {
+  step_index: number;
+  op: string;
+  columns: ColumnPreview[];
+  /** Why the step would be skipped during a real run */
+  error: string | null;
+  /** Output columns depend on the data, so `columns` is approximate */
+  data_dependent: boolean;
+}
+
+/** Result of `dry_run_pipeline_spec` */
+export interface DryRunReport {
+  input_columns: ColumnPreview[];
+  steps: StepPreview[];
+}
+
+/**
+ * Previews the schema after every pipeline step without reading any rows.
+ *
+ * **Backend**: Calls `dry_run_pipeline_spec` in `src/tauri_app.rs`
+ *
+ * @param spec - Pipeline specification to preview
+ * @param inputPath - Path to input dataset
+ * @returns Promise resolving to per-step column previews
+ */
+export async function dryRunPipeline(spec: PipelineSpec, inputPath: string): Promise<DryRunReport> {
+  try {
+    const specJson = JSON.stringify(spec);
+    return await invoke<DryRunReport>('dry_run_pipeline_spec', { specJson, inputPath });
+  } catch (error) {
+    console.error('Failed to dry-run pipeline:', error);
+    throw error;
+  }
+}
+
 /**
  * Executes a pipeline on a dataset.
  *
@@ -286,7 +516,9 @@ export async function validatePipeline(
  *
  * ## Progress Tracking
  *
- * Pipeline execution can be long-running. The result includes
+ * Pipeline execution can be long-running. A `pipeline:progress` event
+ * ({@link PipelineProgress}) is emitted as each step starts, and the run
+ * stops between steps after `abortProcessing()`. The result includes
  * timing information and step counts.
  *
  * @param spec - Pipeline specification to execute
@@ -447,3 +679,118 @@ export async function loadTemplate(templateName: string): Promise<PipelineSpec>
     throw error;
   }
 }
+
+/**
+ * One recorded pipeline run from the run history.
+ */
+export interface RunRecord {
+  id: string;
+  /** Spec name at the time of the run */
+  pipeline: string;
+  /** SHA-256 of the spec, to tell edited versions apart */
+  spec_hash: string;
+  input_path: string;
+  output_path?: string | null;
+  /** ISO datetime */
+  started_at: string;
+  /** ISO datetime */
+  finished_at: string;
+  status: 'succeeded' | 'failed';
+  error?: string | null;
+  warnings: string[];
+  /** Full execution report of a successful run */
+  report?: Partial<ExecutionResult> | null;
+  /** Set when the run read a lifecycle dataset rather than a file */
+  dataset?: DatasetRun | null;
+}
+
+/**
+ * The lifecycle dataset a run read and the version it registered.
+ */
+export interface DatasetRun {
+  dataset_id: string;
+  /** The version that was active when the run started */
+  version_id: string;
+  /** Version registered from the output, if the run was asked to */
+  output_version_id: string | null;
+}
+
+/**
+ * Result of running a pipeline on a lifecycle dataset.
+ */
+export type DatasetRunReport = Partial<ExecutionResult> & {
+  /** Id of the run in the run history */
+  run_id: string;
+  dataset: DatasetRun;
+};
+
+/**
+ * Run a pipeline on the active version of a lifecycle dataset.
+ *
+ * **Backend**: Calls `run_pipeline_on_dataset` in `src/tauri_app.rs`
+ *
+ * The run is recorded in the run history against the dataset. With
+ * `registerAs` the output is also stored as a new version at that stage,
+ * derived from the version read, and becomes the active version.
+ *
+ * @param spec - Pipeline specification to execute
+ * @param datasetId - Lifecycle dataset to read
+ * @param outputPath - Optional path for output (uses spec default if omitted)
+ * @param registerAs - Stage of the version to register from the output
+ */
+export async function runPipelineOnDataset(
+  spec: PipelineSpec,
+  datasetId: string,
+  outputPath?: string,
+  registerAs?: LifecycleStage
+): Promise<DatasetRunReport> {
+  try {
+    return await invoke<DatasetRunReport>('run_pipeline_on_dataset', {
+      specJson: JSON.stringify(spec),
+      datasetId,
+      outputPath: outputPath ?? null,
+      registerAs: registerAs ?? null,
+    });
+  } catch (error) {
+    console.error('Failed to run pipeline on dataset:', error);
+    throw error;
+  }
+}
+
+/**
+ * Filters for the run history; omitted fields match every run.
+ */
+export interface RunHistoryQuery {
+  pipeline?: string;
+  spec_hash?: string;
+  status?: 'succeeded' | 'failed';
+  /** Only runs on this lifecycle dataset */
+  dataset_id?: string;
+  /** ISO datetime; only runs started at or after it */
+  since?: string;
+  limit?: number;
+}
+
+/**
+ * List recorded pipeline runs, most recent first.
+ */
+export async function listPipelineRuns(query?: RunHistoryQuery): Promise<RunRecord[]> {
+  try {
+    return await invoke<RunRecord[]>('list_pipeline_runs', { query: query ?? null });
+  } catch (error) {
+    console.error('Failed to list pipeline runs:', error);
+    throw error;
+  }
+}
+
+/**
+ * Load a single recorded pipeline run.
+ */
+export async function getPipelineRun(id: string): Promise<RunRecord> {
+  try {
+    return await invoke<RunRecord>('get_pipeline_run', { id });
+  } catch (error) {
+    console.error('Failed to load pipeline run:', error);
+    throw error;
+  }
+}
@@ -19,6 +19,7 @@ vi.mock('@tauri-apps/plugin-dialog', () => ({
 import { invoke } from '@tauri-apps/api/core';
 import { open, save } from '@tauri-apps/plugin-dialog';
 import * as api from './api';
+import { CommandError, isCommandError } from './invoke';
 /* eslint-enable import/order */
 
 describe('API', () => {
@@ -55,6 +56,66 @@ describe('API', () => {
 
       await expect(api.analyseFile('/invalid/path.csv')).rejects.toBe('File not found');
     });
+
+    test('should pass how to name the columns of a file without a header row', async () => {
+      vi.mocked(invoke).mockResolvedValue({});
+
+      await api.analyseFile('/path/to/file.csv', { naming: 'inferred' });
+
+      expect(invoke).toHaveBeenCalledWith('analyze_file', {
+        path: '/path/to/file.csv',
+        headerNames: { naming: 'inferred' },
+      });
+    });
+
+    test('should turn coded backend errors into CommandErrors', async () => {
+      vi.mocked(invoke).mockRejectedValue({
+        code: 'not_found',
+        message: 'Failed to load data',
+        detail: 'Failed to load data: No such file or directory',
+      });
+
+      const err: unknown = await api.analyseFile('/invalid/path.csv').catch((e: unknown) => e);
+
+      expect(err).toBeInstanceOf(CommandError);
+      expect(isCommandError(err, 'not_found')).toBe(true);
+      expect(isCommandError(err, 'aborted')).toBe(false);
+      expect(String(err)).toBe('Failed to load data');
+      expect((err as CommandError).detail).toContain('No such file');
+    });
+  });
+
+  describe('previewClean', () => {
+    test('should send configs and row count', async () => {
+      const configs: Record<string, ColumnCleanConfig> = {};
+      vi.mocked(invoke).mockResolvedValue({ rows: 0, columns: [] });
+
+      await api.previewClean('/data/sales.csv', configs, 10);
+
+      expect(invoke).toHaveBeenCalledWith('preview_clean', {
+        path: '/data/sales.csv',
+        configs,
+        nRows: 10,
+      });
+    });
+  });
+
+  describe('workbooks', () => {
+    test('should list sheets and analyse all of them', async () => {
+      vi.mocked(invoke).mockResolvedValueOnce(['2023', '2024']);
+      expect(await api.listWorkbookSheets('/data/sales.xlsx')).toEqual(['2023', '2024']);
+      expect(invoke).toHaveBeenCalledWith('list_workbook_sheets', { path: '/data/sales.xlsx' });
+
+      vi.mocked(invoke).mockResolvedValueOnce({
+        path: '/data/sales.xlsx',
+        overview: { sheets: [], shared_columns: [] },
+        sheets: [],
+        failed: [],
+      });
+      const workbook = await api.analyseWorkbook('/data/sales.xlsx');
+      expect(invoke).toHaveBeenCalledWith('analyze_workbook', { path: '/data/sales.xlsx' });
+      expect(workbook.failed).toEqual([]);
+    });
   });
 
   describe('getAppVersion', () => {
@@ -181,6 +242,72 @@ describe('API', () => {
       expect(result).toBe('Data processed');
     });
 
+    test('should build AI context from an analysis', async () => {
+      vi.mocked(invoke).mockResolvedValue('Dataset: test.csv (10 rows, 1 columns)');
+      const analysis = { file_name: 'test.csv', summary: [] } as unknown as AnalysisResponse;
+
+      const context = await api.aiBuildContext(analysis);
+
+      expect(invoke).toHaveBeenCalledWith('ai_build_context', { analysis, options: undefined });
+      expect(context).toContain('test.csv');
+    });
+
+    test('should stream an AI query', async () => {
+      vi.mocked(invoke).mockResolvedValue('Mean is 4.2');
+
+      const answer = await api.aiSendQueryStream('req-1', 'What is the mean?', 'ctx');
+
+      expect(invoke).toHaveBeenCalledWith('ai_send_query_stream', {
+        requestId: 'req-1',
+        query: 'What is the mean?',
+        context: 'ctx',
+      });
+      expect(answer).toBe('Mean is 4.2');
+    });
+
+    test('should continue and manage AI conversations', async () => {
+      vi.mocked(invoke).mockResolvedValue({ id: 'c1', messages: [] });
+
+      await api.aiSendConversationStream('req-2', 'c1', null, 'And the median?');
+      expect(invoke).toHaveBeenCalledWith('ai_send_conversation_stream', {
+        requestId: 'req-2',
+        conversationId: 'c1',
+        dataPath: null,
+        query: 'And the median?',
+        context: undefined,
+      });
+
+      vi.mocked(invoke).mockResolvedValue([]);
+      await api.aiListConversations('/data/sales.csv');
+      expect(invoke).toHaveBeenCalledWith('ai_list_conversations', {
+        dataPath: '/data/sales.csv',
+      });
+
+      vi.mocked(invoke).mockResolvedValue(true);
+      expect(await api.aiDeleteConversation('c1')).toBe(true);
+      expect(invoke).toHaveBeenCalledWith('ai_delete_conversation', { id: 'c1' });
+    });
+
+    test('should report sandbox restrictions', async () => {
+      const run = {
+        output: 'ok',
+        restrictions: [
+          { name: 'working_dir', applied: true, detail: '/tmp/beefcake-sandbox-1' },
+          { name: 'memory', applied: false, detail: 'Not supported on this platform' },
+        ],
+      };
+      vi.mocked(invoke).mockResolvedValue(run);
+
+      const result = await api.runPythonSandboxed('print("ok")');
+
+      expect(invoke).toHaveBeenCalledWith('run_python_sandboxed', {
+        script: 'print("ok")',
+        dataPath: undefined,
+        configs: undefined,
+      });
+      expect(result.restrictions.filter(r => !r.applied)).toHaveLength(1);
+    });
+
     test('should execute Python script with configs', async () => {
       const configs: Record<string, ColumnCleanConfig> = {
         age: {
@@ -237,6 +364,119 @@ describe('API', () => {
     });
   });
 
+  describe('SQL workspace', () => {
+    test('should run a query and page its result', async () => {
+      const page = {
+        query_id: 'q-1',
+        columns: [{ name: 'n', dtype: 'i64' }],
+        rows: [{ n: 1 }],
+        offset: 0,
+        total_rows: 250,
+      };
+      vi.mocked(invoke).mockResolvedValue({ record: { id: 'q-1' }, page });
+
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      const result = await api.runSqlQuery('SELECT n FROM data', '/data.csv', undefined, 50);
+
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      expect(invoke).toHaveBeenCalledWith('run_sql_query', {
+        query: 'SELECT n FROM data',
+        dataPath: '/data.csv',
+        configs: undefined,
+        pageSize: 50,
+      });
+      expect(result.page.total_rows).toBe(250);
+
+      vi.mocked(invoke).mockResolvedValue({ ...page, offset: 50 });
+      await api.getSqlResultPage('q-1', 50, 50);
+      expect(invoke).toHaveBeenCalledWith('get_sql_result_page', {
+        queryId: 'q-1',
+        offset: 50,
+        limit: 50,
+      });
+    });
+
+    test('should export results and manage saved queries', async () => {
+      vi.mocked(invoke).mockResolvedValue(250);
+      const rows = await api.exportSqlResult('q-1', '/out/result.csv');
+      expect(invoke).toHaveBeenCalledWith('export_sql_result', {
+        queryId: 'q-1',
+        path: '/out/result.csv',
+      });
+      expect(rows).toBe(250);
+
+      vi.mocked(invoke).mockResolvedValue({ id: 's-1', name: 'adults' });
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      await api.saveSqlQuery('adults', 'SELECT * FROM data WHERE age >= 18');
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      expect(invoke).toHaveBeenCalledWith('save_sql_query', {
+        name: 'adults',
+        query: 'SELECT * FROM data WHERE age >= 18',
+        dataPath: undefined,
+      });
+
+      vi.mocked(invoke).mockResolvedValue([]);
+      await api.listSqlHistory(20);
+      expect(invoke).toHaveBeenCalledWith('list_sql_history', { limit: 20 });
+
+      vi.mocked(invoke).mockResolvedValue(true);
+      expect(await api.deleteSavedQuery('s-1')).toBe(true);
+      expect(invoke).toHaveBeenCalledWith('delete_saved_query', { id: 's-1' });
+    });
+
+    test('should save and render script templates', async () => {
+      const template = {
+        name: 'Archive',
+        language: 'powershell' as const,
+        body: 'Compress-Archive -Path {{path}}',
+        params: [{ name: 'path', type: 'string' as const }],
+      };
+      vi.mocked(invoke).mockResolvedValue({ ...template, id: 't-1', version: 1 });
+      const saved = await api.saveScriptTemplate(template);
+      expect(invoke).toHaveBeenCalledWith('save_script_template', { template });
+      expect(saved.version).toBe(1);
+
+      const rendered = {
+        template_id: 't-1',
+        version: 1,
+        language: 'powershell',
+        script: "Compress-Archive -Path 'C:\\out'",
+      };
+      vi.mocked(invoke).mockResolvedValue(rendered);
+      expect(await api.renderScriptTemplate('t-1', { path: 'C:\\out' })).toEqual(rendered);
+      expect(invoke).toHaveBeenCalledWith('render_script_template', {
+        id: 't-1',
+        version: undefined,
+        values: { path: 'C:\\out' },
+      });
+    });
+
+    test('should save and run parameterised queries', async () => {
+      const params = [{ name: 'from', type: 'date' as const, prompt: 'Start date' }];
+      vi.mocked(invoke).mockResolvedValue({ id: 's-2', name: 'since', params });
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      await api.saveSqlQuery('since', 'SELECT * FROM data WHERE day >= {from}', undefined, params);
+      //noinspection SqlNoDataSourceInspection,SqlDialectInspection
+      expect(invoke).toHaveBeenCalledWith('save_sql_query', {
+        name: 'since',
+        query: 'SELECT * FROM data WHERE day >= {from}',
+        dataPath: undefined,
+        params,
+      });
+
+      const result = { record: { id: 'q-2' }, page: { rows: [], total_rows: 0 } };
+      vi.mocked(invoke).mockResolvedValue(result);
+      expect(await api.runSavedQuery('s-2', { from: '2024-07-01' })).toEqual(result);
+      expect(invoke).toHaveBeenCalledWith('run_saved_query', {
+        id: 's-2',
+        values: { from: '2024-07-01' },
+        dataPath: undefined,
+        configs: undefined,
+        pageSize: undefined,
+      });
+    });
+  });
+
   describe('exportData', () => {
     test('should export data with options', async () => {
       vi.mocked(invoke).mockResolvedValue(undefined);
@@ -380,6 +620,51 @@ describe('API', () => {
     });
   });
 
+  describe('columnDeepDive', () => {
+    test('should request a deep dive for one column', async () => {
+      const dive = {
+        column: 'status',
+        distinct_values: { values: [['a', 4]], total_distinct: 2, truncated: true },
+        longest_runs: [{ value: 'a', length: 3, start_row: 0 }],
+        decile_means: [],
+      };
+      vi.mocked(invoke).mockResolvedValue(dive);
+
+      const result = await api.columnDeepDive('/data.csv', 'status', { max_distinct_values: 1 });
+
+      expect(invoke).toHaveBeenCalledWith('column_deep_dive', {
+        path: '/data.csv',
+        column: 'status',
+        options: { max_distinct_values: 1 },
+      });
+      expect(result).toEqual(dive);
+    });
+  });
+
+  describe('getGridPage', () => {
+    test('should request one sorted, filtered page of rows', async () => {
+      const page = {
+        columns: [{ name: 'city', dtype: 'str' }],
+        offset: 200,
+        rows: [['Leeds'], [null]],
+        total_rows: null,
+      };
+      vi.mocked(invoke).mockResolvedValue(page);
+      const query = {
+        offset: 200,
+        limit: 200,
+        sort: { column: 'city', descending: true },
+        filters: [{ column: 'city', op: 'contains' as const, value: 'lee' }],
+        count: false,
+      };
+
+      const result = await api.getGridPage('/data.csv', query);
+
+      expect(invoke).toHaveBeenCalledWith('get_grid_page', { path: '/data.csv', query });
+      expect(result).toEqual(page);
+    });
+  });
+
   describe('sanitizeHeaders', () => {
     test('should sanitize column names', async () => {
       vi.mocked(invoke).mockResolvedValue(['user_name', 'email_address', 'age']);
@@ -393,6 +678,54 @@ describe('API', () => {
     });
   });
 
+  describe('generateTableDdl', () => {
+    test('should generate dbt YAML for the analysed columns', async () => {
+      const yaml = 'version: 2\n\nmodels:\n  - name: orders\n    columns:\n';
+      vi.mocked(invoke).mockResolvedValue(yaml);
+
+      const result = await api.generateTableDdl(
+        'orders.csv',
+        [],
+        undefined,
+        undefined,
+        'sales',
+        true
+      );
+
+      expect(invoke).toHaveBeenCalledWith('generate_table_ddl', {
+        fileName: 'orders.csv',
+        summary: [],
+        configs: undefined,
+        table: undefined,
+        schema: 'sales',
+        dbt: true,
+      });
+      expect(result).toBe(yaml);
+    });
+  });
+
+  describe('cleanInPlace', () => {
+    test('should replace the original after a backup', async () => {
+      const report = {
+        output: '/data/orders.csv',
+        backup: '/data/orders.csv.20240131_120000.bak',
+        rows: 120,
+        dialect: { delimiter: 59, encoding: 'utf8_bom', line_ending: 'crlf' },
+        header: true,
+      };
+      vi.mocked(invoke).mockResolvedValue(report);
+
+      const result = await api.cleanInPlace('/data/orders.csv', {}, { mode: 'replace' });
+
+      expect(invoke).toHaveBeenCalledWith('clean_in_place', {
+        path: '/data/orders.csv',
+        configs: {},
+        mode: { mode: 'replace' },
+      });
+      expect(result).toEqual(report);
+    });
+  });
+
   describe('applyTransforms', () => {
     test('should apply transforms to dataset', async () => {
       vi.mocked(invoke).mockResolvedValue('version-456');
@@ -478,6 +811,123 @@ describe('API', () => {
     });
   });
 
+  describe('deleteVersion', () => {
+    test('should delete a version', async () => {
+      vi.mocked(invoke).mockResolvedValue(undefined);
+
+      await api.deleteVersion('dataset-123', 'version-456');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_delete_version', {
+        request: { dataset_id: 'dataset-123', version_id: 'version-456' },
+      });
+    });
+  });
+
+  describe('rollbackToVersion', () => {
+    test('should roll back and return deleted versions', async () => {
+      vi.mocked(invoke).mockResolvedValue(['v3', 'v2']);
+
+      const result = await api.rollbackToVersion('dataset-123', 'v1');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_rollback_to', {
+        request: { dataset_id: 'dataset-123', version_id: 'v1' },
+      });
+      expect(result).toEqual(['v3', 'v2']);
+    });
+  });
+
+  describe('tagVersion', () => {
+    test('should tag the version and return it', async () => {
+      const version = { id: 'v2', metadata: { tags: ['pre-Q3-restatement'] } };
+      vi.mocked(invoke).mockResolvedValue(version);
+
+      const result = await api.tagVersion('dataset-123', 'v2', 'pre-Q3-restatement');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_tag_version', {
+        request: { dataset_id: 'dataset-123', version_id: 'v2', tag: 'pre-Q3-restatement' },
+      });
+      expect(result).toEqual(version);
+    });
+  });
+
+  describe('searchVersions', () => {
+    test('should pass the filter through', async () => {
+      vi.mocked(invoke).mockResolvedValue([]);
+
+      await api.searchVersions({ tag: 'golden', stage: 'Validated' });
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_search_versions', {
+        filter: { tag: 'golden', stage: 'Validated' },
+      });
+    });
+  });
+
+  describe('getStorageUsage', () => {
+    test('should request storage usage for the dataset', async () => {
+      const usage = {
+        versions: 3,
+        chunks: 4,
+        logical_bytes: 3000,
+        stored_bytes: 1200,
+        saved_bytes: 1800,
+      };
+      vi.mocked(invoke).mockResolvedValue(usage);
+
+      const result = await api.getStorageUsage('dataset-123');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_storage_usage', {
+        request: { dataset_id: 'dataset-123' },
+      });
+      expect(result).toEqual(usage);
+    });
+  });
+
+  describe('getLineage', () => {
+    test('should request the lineage graph', async () => {
+      const graph = { dataset_id: 'dataset-123', dataset_name: 'Sales', nodes: [], edges: [] };
+      vi.mocked(invoke).mockResolvedValue(graph);
+
+      const result = await api.getLineage('dataset-123');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_get_lineage', {
+        request: { dataset_id: 'dataset-123' },
+      });
+      expect(result).toEqual(graph);
+    });
+  });
+
+  describe('collectVersionGarbage', () => {
+    test('should return the garbage collection report', async () => {
+      const report = { files_removed: 2, bytes_freed: 4096 };
+      vi.mocked(invoke).mockResolvedValue(report);
+
+      const result = await api.collectVersionGarbage();
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_collect_garbage');
+      expect(result).toEqual(report);
+    });
+  });
+
+  describe('refreshDataset', () => {
+    test('should return the refresh report', async () => {
+      const report = {
+        dataset_id: 'dataset-123',
+        source_path: '/data/sales.csv',
+        versions: [{ stage: 'Cleaned', previous_id: 'v1', new_id: 'v2' }],
+        active_version_id: 'v2',
+        refreshed_at: '2024-01-01T00:00:00Z',
+      };
+      vi.mocked(invoke).mockResolvedValue(report);
+
+      const result = await api.refreshDataset('dataset-123');
+
+      expect(invoke).toHaveBeenCalledWith('lifecycle_refresh_dataset', {
+        request: { dataset_id: 'dataset-123' },
+      });
+      expect(result).toEqual(report);
+    });
+  });
+
   describe('savePipelineSpec', () => {
     test('should save pipeline to file', async () => {
       vi.mocked(invoke).mockResolvedValue(undefined);
@@ -679,6 +1129,95 @@ describe('API', () => {
         outputPath: '/output.md',
       });
     });
+
+    test('should export html and pdf', async () => {
+      vi.mocked(invoke).mockResolvedValue(undefined);
+
+      await api.dictionaryExportHtml('s1', '/output.html');
+      expect(invoke).toHaveBeenCalledWith('dictionary_export_html', {
+        snapshotId: 's1',
+        outputPath: '/output.html',
+      });
+
+      await api.dictionaryExportPdf('s1', '/output.pdf');
+      expect(invoke).toHaveBeenCalledWith('dictionary_export_pdf', {
+        snapshotId: 's1',
+        outputPath: '/output.pdf',
+      });
+    });
+
+    test('should create a snapshot from an analysis', async () => {
+      const snapshot = { snapshot_id: 's2', dataset_name: 'orders' };
+      vi.mocked(invoke).mockResolvedValue(snapshot);
+      const analysis = {
+        path: '/data/orders.csv',
+        total_row_count: 1200,
+        summary: [],
+        health: { score: 90, risks: [] },
+      } as unknown as AnalysisResponse;
+
+      const result = await api.dictionaryCreateFromAnalysis(analysis);
+
+      expect(invoke).toHaveBeenCalledWith('dictionary_create_from_analysis', {
+        request: {
+          path: '/data/orders.csv',
+          summary: [],
+          total_row_count: 1200,
+          health: { score: 90, risks: [] },
+        },
+      });
+      expect(result).toEqual(snapshot);
+    });
+
+    test('should export and import a business metadata template', async () => {
+      vi.mocked(invoke).mockResolvedValue(undefined);
+      await api.dictionaryExportTemplate('s1', '/template.csv', true);
+      expect(invoke).toHaveBeenCalledWith('dictionary_export_template', {
+        snapshotId: 's1',
+        outputPath: '/template.csv',
+        filled: true,
+      });
+
+      const report = {
+        snapshot_id: 's3',
+        updated_columns: ['region'],
+        unchanged_columns: [],
+        missing_columns: ['amount'],
+        ignored_headers: [],
+        errors: [{ row: 4, message: "Column 'revenue' is not in the snapshot" }],
+      };
+      vi.mocked(invoke).mockResolvedValue(report);
+      const result = await api.dictionaryImportTemplate('s1', '/template.csv');
+      expect(invoke).toHaveBeenCalledWith('dictionary_import_template', {
+        snapshotId: 's1',
+        inputPath: '/template.csv',
+      });
+      expect(result).toEqual(report);
+    });
+
+    test('should save glossary terms and link them to columns', async () => {
+      const term = {
+        id: 't1',
+        term: 'Customer',
+        definition: 'A party that has placed an order',
+        owner: 'Sales Ops',
+        synonyms: ['Client'],
+      };
+      vi.mocked(invoke).mockResolvedValue(term);
+      const saved = await api.dictionarySaveGlossaryTerm({ ...term, id: undefined });
+      expect(invoke).toHaveBeenCalledWith('dictionary_save_glossary_term', {
+        term: { ...term, id: undefined },
+      });
+      expect(saved.id).toBe('t1');
+
+      vi.mocked(invoke).mockResolvedValue(undefined);
+      await api.dictionaryLinkGlossaryTerms('s1', 'cust_id', ['t1']);
+      expect(invoke).toHaveBeenCalledWith('dictionary_link_glossary_terms', {
+        snapshotId: 's1',
+        columnName: 'cust_id',
+        termIds: ['t1'],
+      });
+    });
   });
 
   describe('documentation API', () => {
@@ -729,6 +1268,34 @@ describe('API', () => {
     });
   });
 
+  describe('path rules', () => {
+    test('should save path rules', async () => {
+      const rules = [
+        { pattern: '/data/**/*.csv', effect: 'allow' as const, capabilities: ['read' as const] },
+        { pattern: '/data/secret', effect: 'deny' as const, capabilities: [] },
+      ];
+      vi.mocked(invoke).mockResolvedValue(rules);
+
+      const result = await api.savePathRules(rules);
+
+      expect(invoke).toHaveBeenCalledWith('save_path_rules', { rules });
+      expect(result).toEqual(rules);
+    });
+
+    test('should check access to a path', async () => {
+      const decision = { decision: 'denied', pattern: '/data/secret' };
+      vi.mocked(invoke).mockResolvedValue(decision);
+
+      const result = await api.checkPathAccess('/data/secret/a.csv', 'write');
+
+      expect(invoke).toHaveBeenCalledWith('check_path_access', {
+        path: '/data/secret/a.csv',
+        capability: 'write',
+      });
+      expect(result).toEqual(decision);
+    });
+  });
+
   describe('installPythonPackage', () => {
     test('should install Python package', async () => {
       vi.mocked(invoke).mockResolvedValue('Successfully installed pandas');
@@ -776,7 +1343,65 @@ describe('API', () => {
         path: '/data.csv',
         connectionId: 'conn-123',
         configs,
+        includeProfile: false,
+        ddl: 'off',
+      });
+    });
+
+    test('should request a profile table', async () => {
+      vi.mocked(invoke).mockResolvedValue(undefined);
+
+      await api.pushToDb('/data.csv', 'conn-123', {}, true);
+
+      expect(invoke).toHaveBeenCalledWith('push_to_db', {
+        path: '/data.csv',
+        connectionId: 'conn-123',
+        configs: {},
+        includeProfile: true,
+        ddl: 'off',
+      });
+    });
+
+    test('should return suggested indexes and constraints', async () => {
+      const report = {
+        suggestions: { table: '"public"."orders"', profiled_rows: 5000, suggestions: [] },
+        script: '-- Suggested indexes and constraints for "public"."orders"\n',
+        applied: false,
+        failures: [],
+      };
+      vi.mocked(invoke).mockResolvedValue(report);
+
+      const result = await api.pushToDb('/data.csv', 'conn-123', {}, false, 'suggest');
+
+      expect(invoke).toHaveBeenCalledWith('push_to_db', {
+        path: '/data.csv',
+        connectionId: 'conn-123',
+        configs: {},
+        includeProfile: false,
+        ddl: 'suggest',
+      });
+      expect(result).toEqual(report);
+    });
+  });
+
+  describe('generateCleaningView', () => {
+    test('should generate view SQL with value maps', async () => {
+      const sql = 'CREATE OR REPLACE VIEW "public"."orders_clean" AS SELECT "id" FROM "public"."orders";';
+      vi.mocked(invoke).mockResolvedValue(sql);
+
+      const result = await api.generateCleaningView('/data.csv', 'conn-123', {}, {
+        status: { C: 'Closed' },
       });
+
+      expect(invoke).toHaveBeenCalledWith('generate_cleaning_view', {
+        path: '/data.csv',
+        connectionId: 'conn-123',
+        configs: {},
+        valueMaps: { status: { C: 'Closed' } },
+        specJson: undefined,
+        viewName: undefined,
+      });
+      expect(result).toBe(sql);
     });
   });
 
@@ -837,6 +1462,35 @@ describe('API', () => {
     });
   });
 
+  describe('connection export and import', () => {
+    test('should export selected connections under a passphrase', async () => {
+      vi.mocked(invoke).mockResolvedValue(1);
+
+      const count = await api.exportConnections('/tmp/conns.json', 'passphrase', ['conn-123']);
+
+      expect(count).toBe(1);
+      expect(invoke).toHaveBeenCalledWith('export_connections', {
+        path: '/tmp/conns.json',
+        passphrase: 'passphrase',
+        connectionIds: ['conn-123'],
+      });
+    });
+
+    test('should import connections and return the merge report', async () => {
+      const report = { added: ['Production'], replaced: [], skipped: ['Local'] };
+      vi.mocked(invoke).mockResolvedValue(report);
+
+      const result = await api.importConnections('/tmp/conns.json', 'passphrase', true);
+
+      expect(result).toEqual(report);
+      expect(invoke).toHaveBeenCalledWith('import_connections', {
+        path: '/tmp/conns.json',
+        passphrase: 'passphrase',
+        replaceExisting: true,
+      });
+    });
+  });
+
   describe('error handling', () => {
     test('should propagate errors from backend', async () => {
       vi.mocked(invoke).mockRejectedValue('Database connection failed');
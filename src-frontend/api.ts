@@ -33,13 +33,16 @@
  *
  * ## Error Handling
  *
- * All async functions can throw errors (returned as Promise rejection):
+ * All async functions can throw errors (returned as Promise rejection). A
+ * failed command rejects with a `CommandError` (see `./invoke`) carrying a
+ * stable `code`; its string form is the message for the user:
  *
  * ```typescript
  * try {
  *   const data = await api.analyseFile(path);
  * } catch (error) {
- *   console.error('Backend error:', error);  // error is a string
+ *   if (isCommandError(error, 'aborted')) return;
+ *   console.error('Backend error:', String(error));
  * }
  * ```
  *
@@ -54,27 +57,86 @@
  * @see TypeScript Patterns: ../docs/TYPESCRIPT_PATTERNS.md
  */
 
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from './invoke';
 import { open, save } from '@tauri-apps/plugin-dialog';
 
 import {
   AnalysisResponse,
   AppConfig,
+  AppliedPreset,
+  AuditEntry,
+  AuditExportFormat,
+  AuditQuery,
+  CleaningPreset,
+  UsageSuggestion,
+  StandardiseOptions,
+  ValueStandardisation,
+  CleanPreview,
   ColumnCleanConfig,
+  ColumnSummary,
+  CorrelationMatrix,
+  CorrelationOptions,
   ExportOptions,
+  FeatureImportanceReport,
+  GridPage,
+  GridQuery,
+  HeaderlessNaming,
+  IncrementalUpdate,
+  InPlaceMode,
+  InPlaceReport,
+  QueryRecord,
+  RunLogEntry,
+  RunSummary,
+  ResultPage,
+  QueryParam,
+  SavedQuery,
+  SqlQueryResult,
   WatcherState,
+  TableWatch,
   DataDictionary,
   DatasetBusinessMetadata,
+  DatasetVersion,
   ColumnBusinessMetadata,
   SnapshotMetadata,
+  SnapshotRetention,
+  DictionaryStorageUsage,
+  DictionarySearchHit,
+  DictionarySearchOptions,
+  ReviewStatus,
+  ApprovalState,
+  ViewerOptions,
+  PruneReport,
+  ConnectionImportReport,
   DbConnection,
+  DdlMode,
+  DdlReport,
   DiffSummary,
+  GarbageReport,
+  LineageGraph,
+  PathCapability,
+  PathDecision,
+  PathRule,
+  RefreshReport,
+  StorageUsage,
+  TemplateMergeReport,
+  ScriptTemplate,
+  ColumnDeepDive,
+  DeepDiveOptions,
+  RenderedScript,
+  AIContextOptions,
+  Conversation,
+  ConversationSummary,
+  SandboxedOutput,
+  GlossaryTerm,
   DocFileMetadata,
   ColumnInfo,
   StandardPaths,
   VerificationResult,
   TransformPipeline,
   TransformSpec,
+  VersionFilter,
+  WorkbookAnalysis,
+  VersionSearchHit,
 } from './types';
 
 /**
@@ -104,9 +166,29 @@ import {
  *   console.error('Analysis failed:', error);
  * }
  * ```
+ *
+ * With `headerNames`, a CSV file is read without a header row and its
+ * columns named by position. Later calls on the same file pass the naming
+ * the response records in `header_names`.
+ */
+export async function analyseFile(
+  path: string,
+  headerNames?: HeaderlessNaming | null
+): Promise<AnalysisResponse> {
+  return await invoke('analyze_file', { path, headerNames });
+}
+
+/** Sheet names of an Excel or OpenDocument workbook, in workbook order */
+export async function listWorkbookSheets(path: string): Promise<string[]> {
+  return await invoke('list_workbook_sheets', { path });
+}
+
+/**
+ * Analyse every sheet of a workbook, with an overview of sheet sizes and
+ * the columns they share. Sheets that fail are listed in `failed`.
  */
-export async function analyseFile(path: string): Promise<AnalysisResponse> {
-  return await invoke('analyze_file', { path });
+export async function analyseWorkbook(path: string): Promise<WorkbookAnalysis> {
+  return await invoke('analyze_workbook', { path });
 }
 
 export async function getAppVersion(): Promise<string> {
@@ -145,6 +227,77 @@ export async function runPython(
   return await invoke('run_python', { script, dataPath, configs });
 }
 
+/**
+ * Runs a Python script in the sandbox with the configured limits, even when
+ * sandboxing is off in the settings, and reports which restrictions applied.
+ */
+export async function runPythonSandboxed(
+  script: string,
+  dataPath?: string,
+  configs?: Record<string, ColumnCleanConfig>
+): Promise<SandboxedOutput> {
+  return await invoke('run_python_sandboxed', { script, dataPath, configs });
+}
+
+/**
+ * Summarises an analysis as prompt context for the AI assistant. Sample
+ * values are redacted as configured in the AI settings unless `options` are
+ * given.
+ */
+export async function aiBuildContext(
+  analysis: AnalysisResponse,
+  options?: AIContextOptions
+): Promise<string> {
+  return await invoke('ai_build_context', { analysis, options });
+}
+
+/**
+ * Sends a query to the AI assistant, streaming the answer as `ai:token`
+ * events tagged with `requestId`. Resolves with the whole answer; rejects
+ * if cancelled with {@link abortProcessing}.
+ */
+export async function aiSendQueryStream(
+  requestId: string,
+  query: string,
+  context?: string
+): Promise<string> {
+  return await invoke('ai_send_query_stream', { requestId, query, context });
+}
+
+/**
+ * Asks a question within a saved conversation, streaming the answer like
+ * {@link aiSendQueryStream}. Without a `conversationId` a new conversation is
+ * started on `dataPath`. Resolves with the conversation as saved.
+ */
+export async function aiSendConversationStream(
+  requestId: string,
+  conversationId: string | null,
+  dataPath: string | null,
+  query: string,
+  context?: string
+): Promise<Conversation> {
+  return await invoke('ai_send_conversation_stream', {
+    requestId,
+    conversationId,
+    dataPath,
+    query,
+    context,
+  });
+}
+
+/** Saved AI conversations, most recent first, optionally for one file */
+export async function aiListConversations(dataPath?: string): Promise<ConversationSummary[]> {
+  return await invoke('ai_list_conversations', { dataPath });
+}
+
+export async function aiGetConversation(id: string): Promise<Conversation> {
+  return await invoke('ai_get_conversation', { id });
+}
+
+export async function aiDeleteConversation(id: string): Promise<boolean> {
+  return await invoke('ai_delete_conversation', { id });
+}
+
 export async function runSql(
   query: string,
   dataPath?: string,
@@ -153,16 +306,157 @@ export async function runSql(
   return await invoke('run_sql', { query, dataPath, configs });
 }
 
+/**
+ * Runs a SQL query against a data file (available as the `data` table) and
+ * returns the first page of the result. The full result is kept so it can be
+ * paged with `getSqlResultPage` and saved with `exportSqlResult`; the run is
+ * added to the query history whether it succeeds or not.
+ */
+export async function runSqlQuery(
+  query: string,
+  dataPath?: string,
+  configs?: Record<string, ColumnCleanConfig>,
+  pageSize?: number
+): Promise<SqlQueryResult> {
+  return await invoke('run_sql_query', { query, dataPath, configs, pageSize });
+}
+
+export async function getSqlResultPage(
+  queryId: string,
+  offset: number,
+  limit?: number
+): Promise<ResultPage> {
+  return await invoke('get_sql_result_page', { queryId, offset, limit });
+}
+
+/**
+ * Writes the full result of a query to CSV, JSON or Parquet (by extension)
+ * and returns the number of rows written.
+ */
+export async function exportSqlResult(queryId: string, path: string): Promise<number> {
+  return await invoke('export_sql_result', { queryId, path });
+}
+
+export async function listSqlHistory(limit?: number): Promise<QueryRecord[]> {
+  return await invoke('list_sql_history', { limit });
+}
+
+export async function listSavedQueries(): Promise<SavedQuery[]> {
+  return await invoke('list_saved_queries');
+}
+
+/**
+ * Saves a query under `name`, replacing any saved query with that name.
+ * `params` declares the typed `{name}` placeholders in the query.
+ */
+export async function saveSqlQuery(
+  name: string,
+  query: string,
+  dataPath?: string,
+  params?: QueryParam[]
+): Promise<SavedQuery> {
+  return await invoke('save_sql_query', { name, query, dataPath, params });
+}
+
+/**
+ * Runs a saved query with values for its parameters. Values are checked
+ * against the parameter types and substituted as SQL literals.
+ */
+export async function runSavedQuery(
+  id: string,
+  values: Record<string, string>,
+  dataPath?: string,
+  configs?: Record<string, ColumnCleanConfig>,
+  pageSize?: number
+): Promise<SqlQueryResult> {
+  return await invoke('run_saved_query', { id, values, dataPath, configs, pageSize });
+}
+
+export async function deleteSavedQuery(id: string): Promise<boolean> {
+  return await invoke('delete_saved_query', { id });
+}
+
+export async function listScriptTemplates(): Promise<ScriptTemplate[]> {
+  return await invoke('list_script_templates');
+}
+
+export async function listScriptTemplateVersions(id: string): Promise<ScriptTemplate[]> {
+  return await invoke('list_script_template_versions', { id });
+}
+
+/**
+ * Saves a script template as a new version; a template without an id is
+ * created. Built-in templates are read-only.
+ */
+export async function saveScriptTemplate(template: ScriptTemplate): Promise<ScriptTemplate> {
+  return await invoke('save_script_template', { template });
+}
+
+export async function deleteScriptTemplate(id: string): Promise<boolean> {
+  return await invoke('delete_script_template', { id });
+}
+
+/**
+ * Fills in a script template with form values, each checked against its
+ * parameter type and quoted for the script's language. Run the result with
+ * `runPython` or `runPowerShell`.
+ */
+export async function renderScriptTemplate(
+  id: string,
+  values: Record<string, string>,
+  version?: number
+): Promise<RenderedScript> {
+  return await invoke('render_script_template', { id, version, values });
+}
+
 export async function installPythonPackage(pkg: string): Promise<string> {
   return await invoke('install_python_package', { package: pkg });
 }
 
+/**
+ * Pushes cleaned data to a database table. With `includeProfile`, column
+ * statistics and health metrics are also appended to `<table>_profile`.
+ */
 export async function pushToDb(
   path: string,
   connectionId: string,
-  configs: Record<string, ColumnCleanConfig>
-): Promise<void> {
-  await invoke('push_to_db', { path, connectionId, configs });
+  configs: Record<string, ColumnCleanConfig>,
+  includeProfile = false,
+  ddl: DdlMode = 'off',
+  headerNames?: HeaderlessNaming | null
+): Promise<DdlReport | null> {
+  return await invoke('push_to_db', {
+    path,
+    connectionId,
+    configs,
+    includeProfile,
+    ddl,
+    headerNames,
+  });
+}
+
+/**
+ * Generates a `CREATE OR REPLACE VIEW` statement that applies the cleaning
+ * to the raw table of a connection, for cleaning inside the database.
+ * Renames, casts, trims, null standardisation, case changes and value maps
+ * are translated; anything else is listed in comments at the top.
+ */
+export async function generateCleaningView(
+  path: string,
+  connectionId: string,
+  configs: Record<string, ColumnCleanConfig>,
+  valueMaps: Record<string, Record<string, string>> = {},
+  specJson?: string,
+  viewName?: string
+): Promise<string> {
+  return await invoke('generate_cleaning_view', {
+    path,
+    connectionId,
+    configs,
+    valueMaps,
+    specJson,
+    viewName,
+  });
 }
 
 export async function testConnection(
@@ -176,6 +470,27 @@ export async function deleteConnection(id: string): Promise<void> {
   await invoke('delete_connection', { id });
 }
 
+/**
+ * Write saved connections (all, or those in `connectionIds`) to `path` with
+ * their passwords encrypted under `passphrase`, for moving to another machine
+ */
+export async function exportConnections(
+  path: string,
+  passphrase: string,
+  connectionIds?: string[]
+): Promise<number> {
+  return await invoke('export_connections', { path, passphrase, connectionIds });
+}
+
+/** Import a connection export, storing its passwords in the local keyring */
+export async function importConnections(
+  path: string,
+  passphrase: string,
+  replaceExisting?: boolean
+): Promise<ConnectionImportReport> {
+  return await invoke('import_connections', { path, passphrase, replaceExisting });
+}
+
 export async function exportData(options: ExportOptions): Promise<void> {
   await invoke('export_data', { options });
 }
@@ -223,10 +538,162 @@ export async function writeTextFile(path: string, contents: string): Promise<voi
   await invoke('write_text_file', { path, contents });
 }
 
+/**
+ * Re-analyses only the columns whose cleaning config changed.
+ *
+ * **Backend**: Calls `reanalyze_columns` in `src/commands/analysis.rs`
+ */
+export async function reanalyseColumns(
+  path: string,
+  summary: ColumnSummary[],
+  oldConfigs: Record<string, ColumnCleanConfig>,
+  newConfigs: Record<string, ColumnCleanConfig>
+): Promise<IncrementalUpdate> {
+  return await invoke('reanalyze_columns', { path, summary, oldConfigs, newConfigs });
+}
+
+/**
+ * Clean the first `nRows` rows (default 20, at most 1000) with `configs` and
+ * return each configured column's values before and after, to check regex
+ * replacements and casts before cleaning the whole file.
+ */
+export async function previewClean(
+  path: string,
+  configs: Record<string, ColumnCleanConfig>,
+  nRows?: number,
+  headerNames?: HeaderlessNaming | null
+): Promise<CleanPreview> {
+  return await invoke('preview_clean', { path, configs, nRows, headerNames });
+}
+
+/**
+ * Reads one page of the rows of `path` for the data grid, filtered and
+ * sorted as `query` says. Values come back as text.
+ */
+export async function getGridPage(
+  path: string,
+  query: GridQuery,
+  headerNames?: HeaderlessNaming | null
+): Promise<GridPage> {
+  return await invoke('get_grid_page', { path, query, headerNames });
+}
+
+export async function listCleaningPresets(): Promise<CleaningPreset[]> {
+  return await invoke('list_cleaning_presets');
+}
+
+/** Saves configs as a named preset, replacing any preset with that name. */
+export async function saveCleaningPreset(
+  name: string,
+  configs: Record<string, ColumnCleanConfig>
+): Promise<CleaningPreset> {
+  return await invoke('save_cleaning_preset', { name, configs });
+}
+
+export async function deleteCleaningPreset(id: string): Promise<boolean> {
+  return await invoke('delete_cleaning_preset', { id });
+}
+
+/** Maps a preset onto a file's columns by exact, then fuzzy, name matching. */
+export async function applyCleaningPreset(id: string, columns: string[]): Promise<AppliedPreset> {
+  return await invoke('apply_cleaning_preset', { id, columns });
+}
+
+/** Defaults suggested by how the file's columns were cleaned before. */
+export async function columnUsageSuggestions(path: string): Promise<UsageSuggestion[]> {
+  return await invoke('column_usage_suggestions', { path });
+}
+
+/** Forgets how the file's columns were cleaned before. */
+export async function clearColumnUsage(path: string): Promise<boolean> {
+  return await invoke('clear_column_usage', { path });
+}
+
+/**
+ * Clusters near-duplicate values of a column and proposes a mapping onto one
+ * spelling per cluster. With `config`, values are compared as its text
+ * options leave them.
+ */
+export async function suggestValueStandardisation(
+  path: string,
+  column: string,
+  config?: ColumnCleanConfig,
+  options?: StandardiseOptions,
+  headerNames?: HeaderlessNaming | null
+): Promise<ValueStandardisation> {
+  return await invoke('suggest_value_standardisation', {
+    path,
+    column,
+    config,
+    options,
+    headerNames,
+  });
+}
+
+export async function computeCorrelationMatrix(
+  path: string,
+  options: CorrelationOptions
+): Promise<CorrelationMatrix | null> {
+  return await invoke('compute_correlation_matrix', { path, options });
+}
+
+export async function computeFeatureImportance(
+  path: string,
+  target: string,
+  headerNames?: HeaderlessNaming | null
+): Promise<FeatureImportanceReport> {
+  return await invoke('compute_feature_importance', { path, target, headerNames });
+}
+
+/**
+ * Computes the expensive per-column extras left out of the default analysis.
+ * Parts are also emitted as `analysis:deep_dive_part` events as they finish.
+ */
+export async function columnDeepDive(
+  path: string,
+  column: string,
+  options?: DeepDiveOptions,
+  headerNames?: HeaderlessNaming | null
+): Promise<ColumnDeepDive> {
+  return await invoke('column_deep_dive', { path, column, options, headerNames });
+}
+
 export async function sanitizeHeaders(names: string[]): Promise<string[]> {
   return await invoke('sanitize_headers', { names });
 }
 
+/**
+ * Generates a Postgres `CREATE TABLE` statement for the analysed columns,
+ * or a dbt model YAML file with `dbt`. With configs the table describes the
+ * cleaned output. The table is named after the file unless one is given.
+ */
+export async function generateTableDdl(
+  fileName: string,
+  summary: ColumnSummary[],
+  configs?: Record<string, ColumnCleanConfig>,
+  table?: string,
+  schema?: string,
+  dbt = false
+): Promise<string> {
+  return await invoke('generate_table_ddl', { fileName, summary, configs, table, schema, dbt });
+}
+
+/**
+ * Cleans a file and writes it back in its own format, CSV delimiter and
+ * encoding: beside the original with a suffix by default, or over it after
+ * a backup with `{ mode: 'replace' }`.
+ *
+ * **Backend**: Calls `clean_in_place` in `src/commands/analysis.rs`
+ */
+export async function cleanInPlace(
+  path: string,
+  configs: Record<string, ColumnCleanConfig>,
+  mode?: InPlaceMode,
+  headerNames?: HeaderlessNaming | null
+): Promise<InPlaceReport> {
+  return await invoke('clean_in_place', { path, configs, mode, headerNames });
+}
+
 export async function openFileDialog(
   filters?: { name: string; extensions: string[] }[]
 ): Promise<string | null> {
@@ -235,7 +702,7 @@ export async function openFileDialog(
     filters: filters ?? [
       {
         name: 'Data Files',
-        extensions: ['csv', 'json', 'parquet'],
+        extensions: ['csv', 'json', 'parquet', 'xlsx', 'xlsm', 'xls', 'ods'],
       },
     ],
   });
@@ -275,13 +742,34 @@ export async function removeTrustedPath(path: string): Promise<string[]> {
   return await invoke('remove_trusted_path', { path });
 }
 
+export async function listPathRules(): Promise<PathRule[]> {
+  return await invoke('list_path_rules');
+}
+
+/** Replaces all path rules; fails without saving if any rule is invalid */
+export async function savePathRules(rules: PathRule[]): Promise<PathRule[]> {
+  return await invoke('save_path_rules', { rules });
+}
+
+/** Previews whether the path policy allows `capability` on `path` */
+export async function checkPathAccess(
+  path: string,
+  capability: PathCapability
+): Promise<PathDecision> {
+  return await invoke('check_path_access', { path, capability });
+}
+
 // ============================================================================
 // Dataset Lifecycle API
 // ============================================================================
 
-export async function createDataset(name: string, path: string): Promise<string> {
+export async function createDataset(
+  name: string,
+  path: string,
+  headerNames?: HeaderlessNaming | null
+): Promise<string> {
   return await invoke('lifecycle_create_dataset', {
-    request: { name, source_path: path },
+    request: { name, source_path: path, header_names: headerNames },
   });
 }
 
@@ -321,6 +809,42 @@ export async function publishVersion(
   });
 }
 
+/**
+ * Moves a version through the approval workflow: Draft to InReview,
+ * InReview to Approved or back to Draft, Approved back to Draft. Versions
+ * must be Approved before they can be published.
+ */
+export async function transitionApproval(
+  datasetId: string,
+  versionId: string,
+  state: ApprovalState,
+  comment = ''
+): Promise<DatasetVersion> {
+  return await invoke('lifecycle_transition_approval', {
+    request: { dataset_id: datasetId, version_id: versionId, state, comment },
+  });
+}
+
+/**
+ * Writes a standalone HTML viewer of a version (schema, documentation,
+ * health and a paginated preview of the first rows) to `outputPath`.
+ */
+export async function exportVersionViewer(
+  datasetId: string,
+  versionId: string,
+  outputPath: string,
+  options: Partial<ViewerOptions> = {}
+): Promise<void> {
+  return await invoke('lifecycle_export_viewer', {
+    request: {
+      dataset_id: datasetId,
+      version_id: versionId,
+      output_path: outputPath,
+      options,
+    },
+  });
+}
+
 export async function getVersionDiff(
   datasetId: string,
   version1Id: string,
@@ -331,12 +855,107 @@ export async function getVersionDiff(
   });
 }
 
-export async function listVersions(datasetId: string): Promise<string> {
+/** Lists a dataset's versions as JSON; with a filter, only matches, newest first. */
+export async function listVersions(datasetId: string, filter?: VersionFilter): Promise<string> {
   return await invoke('lifecycle_list_versions', {
-    request: { dataset_id: datasetId },
+    request: filter ? { dataset_id: datasetId, filter } : { dataset_id: datasetId },
+  });
+}
+
+export async function tagVersion(
+  datasetId: string,
+  versionId: string,
+  tag: string
+): Promise<DatasetVersion> {
+  return await invoke('lifecycle_tag_version', {
+    request: { dataset_id: datasetId, version_id: versionId, tag },
   });
 }
 
+export async function untagVersion(
+  datasetId: string,
+  versionId: string,
+  tag: string
+): Promise<DatasetVersion> {
+  return await invoke('lifecycle_untag_version', {
+    request: { dataset_id: datasetId, version_id: versionId, tag },
+  });
+}
+
+/** Replaces the free-text notes on a version. */
+export async function annotateVersion(
+  datasetId: string,
+  versionId: string,
+  notes: string
+): Promise<DatasetVersion> {
+  return await invoke('lifecycle_annotate_version', {
+    request: { dataset_id: datasetId, version_id: versionId, notes },
+  });
+}
+
+/** Finds versions of every dataset matching the filter, newest first. */
+export async function searchVersions(filter: VersionFilter): Promise<VersionSearchHit[]> {
+  return await invoke('lifecycle_search_versions', { filter });
+}
+
+/**
+ * Deletes a version. Fails for the raw, active and published versions and
+ * for versions whose data another dataset uses.
+ */
+export async function deleteVersion(datasetId: string, versionId: string): Promise<void> {
+  await invoke('lifecycle_delete_version', {
+    request: { dataset_id: datasetId, version_id: versionId },
+  });
+}
+
+/**
+ * Makes an earlier version active again and deletes the versions after it.
+ * Returns the ids of the deleted versions.
+ */
+export async function rollbackToVersion(datasetId: string, versionId: string): Promise<string[]> {
+  return await invoke('lifecycle_rollback_to', {
+    request: { dataset_id: datasetId, version_id: versionId },
+  });
+}
+
+/** Removes version store files that no version refers to. */
+export async function collectVersionGarbage(): Promise<GarbageReport> {
+  return await invoke('lifecycle_collect_garbage');
+}
+
+/**
+ * Re-reads a dataset's source file and re-applies the pipelines leading to
+ * its active version, creating a new version for each stage.
+ */
+export async function refreshDataset(datasetId: string): Promise<RefreshReport> {
+  return await invoke('lifecycle_refresh_dataset', { request: { dataset_id: datasetId } });
+}
+
+/**
+ * Sets the file pattern (e.g. `sales_*.csv`) of new files the watcher adds
+ * as raw versions of the dataset. Null or blank clears it.
+ */
+export async function setSourcePattern(datasetId: string, pattern: string | null): Promise<void> {
+  await invoke('lifecycle_set_source_pattern', {
+    request: { dataset_id: datasetId, pattern },
+  });
+}
+
+/** Returns the space a dataset's versions take, with and without deduplication. */
+export async function getStorageUsage(datasetId: string): Promise<StorageUsage> {
+  return await invoke('lifecycle_storage_usage', { request: { dataset_id: datasetId } });
+}
+
+/** Returns the lineage graph of a dataset's versions and source files. */
+export async function getLineage(datasetId: string): Promise<LineageGraph> {
+  return await invoke('lifecycle_get_lineage', { request: { dataset_id: datasetId } });
+}
+
+/** Returns the lineage graph in Graphviz DOT format. */
+export async function getLineageDot(datasetId: string): Promise<string> {
+  return await invoke('lifecycle_get_lineage_dot', { request: { dataset_id: datasetId } });
+}
+
 export async function getVersionSchema(
   datasetId: string,
   versionId: string
@@ -396,6 +1015,18 @@ export async function watcherIngestNow(path: string): Promise<void> {
   return await invoke('watcher_ingest_now', { path });
 }
 
+export async function watcherListTables(): Promise<TableWatch[]> {
+  return await invoke('watcher_list_tables');
+}
+
+export async function watcherAddTable(watch: TableWatch): Promise<TableWatch> {
+  return await invoke('watcher_add_table', { watch });
+}
+
+export async function watcherRemoveTable(id: string): Promise<void> {
+  return await invoke('watcher_remove_table', { id });
+}
+
 // Data Dictionary API
 export async function dictionaryLoadSnapshot(snapshotId: string): Promise<DataDictionary> {
   return await invoke('dictionary_load_snapshot', { snapshotId });
@@ -405,6 +1036,49 @@ export async function dictionaryListSnapshots(datasetHash?: string): Promise<Sna
   return await invoke('dictionary_list_snapshots', { datasetHash });
 }
 
+export async function dictionaryStorageUsage(): Promise<DictionaryStorageUsage> {
+  return await invoke('dictionary_storage_usage');
+}
+
+/**
+ * Compacts dictionary snapshots and applies a retention policy, the one in
+ * the settings unless `policy` is given. The newest snapshot of each dataset
+ * is always kept.
+ */
+export async function dictionaryPruneSnapshots(policy?: SnapshotRetention): Promise<PruneReport> {
+  return await invoke('dictionary_prune_snapshots', { policy });
+}
+
+/**
+ * Searches dataset and column names, descriptions, tags and linked glossary
+ * terms across dictionary snapshots, best match first.
+ */
+export async function dictionarySearch(
+  query: string,
+  options?: DictionarySearchOptions
+): Promise<DictionarySearchHit[]> {
+  return await invoke('dictionary_search', { query, options });
+}
+
+/**
+ * Review state of every dataset with a review cadence, most overdue first.
+ * With `notify`, the datasets due for review are recorded in the audit log.
+ */
+export async function dictionaryReviewSchedule(
+  overdueOnly = false,
+  notify = false
+): Promise<ReviewStatus[]> {
+  return await invoke('dictionary_review_schedule', { overdueOnly, notify });
+}
+
+/** Records a review of a dataset and returns the new snapshot version */
+export async function dictionaryMarkReviewed(
+  snapshotId: string,
+  reviewedOn?: string
+): Promise<DataDictionary> {
+  return await invoke('dictionary_mark_reviewed', { snapshotId, reviewedOn });
+}
+
 export async function dictionaryUpdateBusinessMetadata(
   snapshotId: string,
   datasetBusiness?: DatasetBusinessMetadata,
@@ -426,6 +1100,80 @@ export async function dictionaryExportMarkdown(
   return await invoke('dictionary_export_markdown', { snapshotId, outputPath });
 }
 
+/** Exports a snapshot as a self-contained HTML page with distribution sparklines. */
+export async function dictionaryExportHtml(snapshotId: string, outputPath: string): Promise<void> {
+  return await invoke('dictionary_export_html', { snapshotId, outputPath });
+}
+
+export async function dictionaryExportPdf(snapshotId: string, outputPath: string): Promise<void> {
+  return await invoke('dictionary_export_pdf', { snapshotId, outputPath });
+}
+
+/**
+ * Writes a CSV template of a snapshot's column business metadata for editing
+ * in a spreadsheet; business fields are blank unless `filled`.
+ */
+export async function dictionaryExportTemplate(
+  snapshotId: string,
+  outputPath: string,
+  filled?: boolean
+): Promise<void> {
+  return await invoke('dictionary_export_template', { snapshotId, outputPath, filled });
+}
+
+/**
+ * Merges an edited template into a new snapshot version. Empty cells keep the
+ * current value; rows with errors are skipped and listed in the report.
+ */
+export async function dictionaryImportTemplate(
+  snapshotId: string,
+  inputPath: string
+): Promise<TemplateMergeReport> {
+  return await invoke('dictionary_import_template', { snapshotId, inputPath });
+}
+
+export async function dictionaryListGlossary(): Promise<GlossaryTerm[]> {
+  return await invoke('dictionary_list_glossary');
+}
+
+/**
+ * Adds a glossary term, or updates the term with the same id. Fails when the
+ * term or one of its synonyms is already used by another term.
+ */
+export async function dictionarySaveGlossaryTerm(term: GlossaryTerm): Promise<GlossaryTerm> {
+  return await invoke('dictionary_save_glossary_term', { term });
+}
+
+export async function dictionaryDeleteGlossaryTerm(termId: string): Promise<boolean> {
+  return await invoke('dictionary_delete_glossary_term', { termId });
+}
+
+/** Replaces the glossary terms a snapshot column links to */
+export async function dictionaryLinkGlossaryTerms(
+  snapshotId: string,
+  columnName: string,
+  termIds: string[]
+): Promise<void> {
+  return await invoke('dictionary_link_glossary_terms', { snapshotId, columnName, termIds });
+}
+
+/**
+ * Saves a baseline dictionary snapshot of an analysed file, linked to the
+ * file's previous snapshot with its business metadata carried over.
+ */
+export async function dictionaryCreateFromAnalysis(
+  analysis: AnalysisResponse
+): Promise<DataDictionary> {
+  return await invoke('dictionary_create_from_analysis', {
+    request: {
+      path: analysis.path,
+      summary: analysis.summary,
+      total_row_count: analysis.total_row_count,
+      health: analysis.health,
+    },
+  });
+}
+
 /**
  * List all available documentation files with metadata.
  *
@@ -503,3 +1251,53 @@ export async function getCurrentLogFile(): Promise<string> {
 export async function getCurrentErrorLogFile(): Promise<string> {
   return await invoke('get_current_error_log_file');
 }
+
+/**
+ * List recent analysis, pipeline and export runs from the structured log.
+ *
+ * @param limit - Most runs to return (default 50)
+ * @returns Runs, newest first
+ */
+export async function listRuns(limit?: number): Promise<RunSummary[]> {
+  return await invoke('list_runs', { limit });
+}
+
+/**
+ * Read the log events of one run.
+ *
+ * @param runId - Correlation ID of the run, as in {@link RunSummary}
+ * @param limit - Most events to return, the latest kept (default 1000)
+ * @returns Events, oldest first
+ */
+export async function readRunLog(runId: string, limit?: number): Promise<RunLogEntry[]> {
+  return await invoke('read_run_log', { runId, limit });
+}
+
+/**
+ * Query the on-disk audit trail by time range and category.
+ *
+ * Unlike `config.audit_log`, the trail holds every entry within the
+ * retention period, including those cleared from the Activity Log.
+ *
+ * @param query - Time range, categories (actions) and limit
+ * @returns Matching entries, oldest first
+ */
+export async function queryAuditLog(query: AuditQuery = {}): Promise<AuditEntry[]> {
+  return await invoke('query_audit_log', { query });
+}
+
+/**
+ * Export the audit entries matching a query to a file.
+ *
+ * @param query - Time range, categories (actions) and limit
+ * @param path - File to write
+ * @param format - `csv` or `jsonl` (one JSON object per line)
+ * @returns Number of entries written
+ */
+export async function exportAuditLog(
+  query: AuditQuery,
+  path: string,
+  format: AuditExportFormat
+): Promise<number> {
+  return await invoke('export_audit_log', { query, path, format });
+}
@@ -1,7 +1,17 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../invoke';
+import { listen } from '@tauri-apps/api/event';
 import DOMPurify from 'dompurify';
 
-import { AppState, ColumnSummary } from '../types';
+import * as api from '../api';
+import {
+  AIConfig,
+  AITokenEvent,
+  AppState,
+  ChatMessage,
+  ConversationSummary,
+  aiRequiresApiKey,
+} from '../types';
+import { escapeHtml } from '../utils';
 
 import { Component, ComponentActions } from './Component';
 
@@ -11,17 +21,15 @@ interface AIMessage {
   timestamp: Date;
 }
 
-interface AIConfig {
-  enabled: boolean;
-  model: string;
-  temperature: number;
-  max_tokens: number;
-}
-
 export class AIAssistantComponent extends Component {
   private messages: AIMessage[] = [];
   private isEnabled = false;
   private currentContext: string | null = null;
+  private contextSource: AppState['analysisResponse'] = null;
+  /** Request id of the answer currently streaming in */
+  private activeRequestId: string | null = null;
+  /** Saved conversation the chat continues; null starts a new one */
+  private conversationId: string | null = null;
 
   constructor(containerId: string, actions: ComponentActions) {
     super(containerId, actions);
@@ -32,8 +40,9 @@ export class AIAssistantComponent extends Component {
     try {
       const config = await invoke<AIConfig>('ai_get_config');
       const hasKey = await invoke<boolean>('ai_has_api_key');
-      // AI is only enabled if both the config is enabled AND an API key is configured
-      this.isEnabled = config.enabled && hasKey;
+      // AI is only enabled if the config is enabled AND an API key is configured,
+      // unless the backend needs none
+      this.isEnabled = config.enabled && (hasKey || !aiRequiresApiKey(config));
       this.updateUIState();
     } catch (error) {
       console.error('Failed to load AI config:', error);
@@ -55,12 +64,17 @@ export class AIAssistantComponent extends Component {
             <div class="ai-status-compact" data-testid="ai-status">
               <span class="status-indicator-small"></span>
             </div>
+            <button class="ai-history-btn" id="ai-history-btn" title="Conversation history">
+              <i class="ph ph-clock-counter-clockwise"></i>
+            </button>
             <button class="ai-collapse-btn" id="ai-collapse-btn" title="Collapse sidebar">
               <i class="ph ph-caret-right"></i>
             </button>
           </div>
         </div>
 
+        <div class="ai-history-panel" data-testid="ai-history" hidden></div>
+
         <div class="ai-messages-sidebar" data-testid="ai-messages">
           <div class="welcome-message-sidebar">
             <p>👋 Hi! I'm your AI assistant.</p>
@@ -113,10 +127,15 @@ export class AIAssistantComponent extends Component {
     const input = container.querySelector('.ai-input-compact') as HTMLTextAreaElement;
     const sendButton = container.querySelector('.btn-send-sidebar') as HTMLButtonElement;
     const clearButton = container.querySelector('.btn-clear-sidebar') as HTMLButtonElement;
+    const historyButton = container.querySelector('.ai-history-btn') as HTMLButtonElement;
 
     if (sendButton) {
       sendButton.addEventListener('click', () => {
-        void this.sendMessage();
+        if (this.activeRequestId) {
+          void api.abortProcessing();
+        } else {
+          void this.sendMessage();
+        }
       });
     }
 
@@ -126,11 +145,17 @@ export class AIAssistantComponent extends Component {
       });
     }
 
+    if (historyButton) {
+      historyButton.addEventListener('click', () => {
+        void this.toggleHistory();
+      });
+    }
+
     if (input) {
       input.addEventListener('keydown', (e: KeyboardEvent) => {
         if (e.key === 'Enter' && !e.shiftKey) {
           e.preventDefault();
-          void this.sendMessage();
+          if (!this.activeRequestId) void this.sendMessage();
         }
       });
     }
@@ -164,25 +189,60 @@ export class AIAssistantComponent extends Component {
     this.addMessage('user', query);
     input.value = '';
 
-    // Show loading state
+    // Show loading state until the first token arrives
+    const requestId = crypto.randomUUID();
+    this.activeRequestId = requestId;
     this.showLoading(true);
 
+    // Assigned from the listener, so keep the declared type unnarrowed
+    let reply = null as AIMessage | null;
+    const unlisten = await listen<AITokenEvent>('ai:token', event => {
+      if (event.payload.request_id !== requestId) return;
+      if (!reply) {
+        reply = { role: 'assistant', content: '', timestamp: new Date() };
+        this.messages.push(reply);
+      }
+      reply.content += event.payload.token;
+      this.renderMessages();
+    });
+
     try {
-      // Send query to backend with current context
-      const response = await invoke<string>('ai_send_query', {
+      // Send query to backend with current context, continuing the conversation
+      const conversation = await api.aiSendConversationStream(
+        requestId,
+        this.conversationId,
+        this.contextSource?.path ?? null,
         query,
-        context: this.currentContext ?? undefined,
-      });
-
-      // Add assistant response
-      this.addMessage('assistant', response);
+        this.currentContext ?? undefined
+      );
+      this.conversationId = conversation.id;
+      const response = conversation.messages.at(-1)?.content ?? '';
+
+      if (reply) {
+        reply.content = response;
+        this.renderMessages();
+      } else {
+        this.addMessage('assistant', response);
+      }
     } catch (error) {
       const errorMessage = error instanceof Error ? error.message : String(error);
-      this.addMessage(
-        'assistant',
-        `❌ Error: ${errorMessage}\n\nPlease check your API key and configuration in Settings.`
-      );
+      if (errorMessage.includes('cancelled by user')) {
+        // Keep whatever arrived before the user pressed stop
+        if (reply) {
+          reply.content += '\n\n*(stopped)*';
+          this.renderMessages();
+        } else {
+          this.addMessage('assistant', '*(stopped)*');
+        }
+      } else {
+        this.addMessage(
+          'assistant',
+          `❌ Error: ${errorMessage}\n\nPlease check your API key and configuration in Settings.`
+        );
+      }
     } finally {
+      unlisten();
+      this.activeRequestId = null;
       this.showLoading(false);
     }
   }
@@ -260,9 +320,11 @@ export class AIAssistantComponent extends Component {
     const sendButton = container.querySelector('.btn-send-sidebar') as HTMLButtonElement;
 
     if (sendButton) {
-      sendButton.disabled = show;
+      // While an answer streams the send button stops it
+      sendButton.disabled = !this.isEnabled;
+      sendButton.title = show ? 'Stop response' : 'Send message';
       sendButton.innerHTML = show
-        ? '<i class="ph ph-spinner"></i>'
+        ? '<i class="ph ph-stop"></i>'
         : '<i class="ph ph-paper-plane-tilt"></i>';
     }
 
@@ -290,32 +352,110 @@ export class AIAssistantComponent extends Component {
     }
   }
 
+  private async toggleHistory(): Promise<void> {
+    const panel = this.getContainer().querySelector<HTMLElement>('.ai-history-panel');
+    if (!panel) return;
+    if (!panel.hidden) {
+      panel.hidden = true;
+      return;
+    }
+
+    try {
+      // Conversations about the open file, or all of them when none is open
+      const conversations = await api.aiListConversations(this.contextSource?.path);
+      this.renderHistory(panel, conversations);
+      panel.hidden = false;
+    } catch (error) {
+      console.error('Failed to list AI conversations:', error);
+    }
+  }
+
+  private renderHistory(panel: HTMLElement, conversations: ConversationSummary[]): void {
+    panel.innerHTML = conversations.length
+      ? conversations
+          .map(
+            c => `
+        <div class="ai-history-item ${c.id === this.conversationId ? 'active' : ''}" data-id="${c.id}">
+          <button class="ai-history-resume" title="Resume conversation">
+            <span class="ai-history-title">${escapeHtml(c.title || 'Untitled')}</span>
+            <span class="ai-history-meta">${c.message_count} messages · ${this.formatTime(new Date(c.updated_at))}</span>
+          </button>
+          <button class="ai-history-delete" title="Delete conversation">
+            <i class="ph ph-trash"></i>
+          </button>
+        </div>
+      `
+          )
+          .join('')
+      : '<p class="sidebar-help-text">No saved conversations yet.</p>';
+
+    panel.querySelectorAll<HTMLElement>('.ai-history-item').forEach(item => {
+      const id = item.dataset.id;
+      if (!id) return;
+      item.querySelector('.ai-history-resume')?.addEventListener('click', () => {
+        void this.resumeConversation(id);
+      });
+      item.querySelector('.ai-history-delete')?.addEventListener('click', () => {
+        void this.deleteConversation(id, panel);
+      });
+    });
+  }
+
+  private async resumeConversation(id: string): Promise<void> {
+    if (this.activeRequestId) return;
+    try {
+      const conversation = await api.aiGetConversation(id);
+      this.conversationId = conversation.id;
+      this.messages = conversation.messages.map((m: ChatMessage) => ({
+        role: m.role,
+        content: m.content,
+        timestamp: new Date(m.timestamp),
+      }));
+      this.renderMessages();
+      const panel = this.getContainer().querySelector<HTMLElement>('.ai-history-panel');
+      if (panel) panel.hidden = true;
+    } catch (error) {
+      console.error('Failed to load AI conversation:', error);
+    }
+  }
+
+  private async deleteConversation(id: string, panel: HTMLElement): Promise<void> {
+    try {
+      await api.aiDeleteConversation(id);
+      if (id === this.conversationId) this.clearChat();
+      const conversations = await api.aiListConversations(this.contextSource?.path);
+      this.renderHistory(panel, conversations);
+    } catch (error) {
+      console.error('Failed to delete AI conversation:', error);
+    }
+  }
+
   private clearChat(): void {
+    // The conversation stays saved; the next message starts a new one
+    this.conversationId = null;
     this.messages = [];
     this.render({} as AppState);
     this.updateUIState();
   }
 
   public updateContext(state: AppState): void {
-    if (!state.analysisResponse) {
+    const analysis = state.analysisResponse;
+    this.contextSource = analysis;
+    if (!analysis) {
       this.currentContext = null;
       return;
     }
 
-    // Build context from current analysis
-    const context = {
-      fileName: state.analysisResponse.file_name,
-      rowCount: state.analysisResponse.row_count,
-      columnCount: state.analysisResponse.column_count,
-      columns: (state.analysisResponse.summary || []).slice(0, 20).map((col: ColumnSummary) => ({
-        name: col.name,
-        type: col.kind,
-        nullCount: col.nulls,
-        nullPercent: ((col.nulls / state.analysisResponse!.row_count) * 100).toFixed(1),
-      })),
-    };
-
-    this.currentContext = JSON.stringify(context, null, 2);
+    // Summarised by the backend, which applies the configured redaction
+    api
+      .aiBuildContext(analysis)
+      .then(context => {
+        if (this.contextSource === analysis) this.currentContext = context;
+      })
+      .catch(error => {
+        console.error('Failed to build AI context:', error);
+        if (this.contextSource === analysis) this.currentContext = null;
+      });
   }
 
   public enable(): void {
@@ -13,6 +13,7 @@ import { type ComponentActions } from './Component';
 vi.mock('../api', () => ({
   saveAppConfig: vi.fn(),
   logFrontendEvent: vi.fn(),
+  queryAuditLog: vi.fn(),
 }));
 
 // Create mock actions
@@ -250,6 +251,43 @@ describe('ActivityLogComponent', () => {
       });
     });
 
+    test('should search the audit trail by category and show the results', async () => {
+      const config: AppConfig = {
+        ...getDefaultAppConfig(),
+        audit_log: {
+          entries: [
+            { timestamp: '2025-01-27T10:00:00Z', action: 'Export', details: 'Recent export' },
+            { timestamp: '2025-01-27T10:01:00Z', action: 'Toast', details: 'Recent toast' },
+          ],
+        },
+      };
+      const state = createMockState({ config });
+      component.render(state);
+
+      vi.mocked(api.queryAuditLog).mockResolvedValue([
+        { timestamp: '2024-12-01T09:00:00Z', action: 'Export', details: 'Older export' },
+      ]);
+
+      const category = container.querySelector('#audit-category') as HTMLSelectElement;
+      category.value = 'Export';
+      (container.querySelector('#btn-audit-search') as HTMLButtonElement).click();
+
+      await vi.waitFor(() => {
+        expect(container.querySelector('#btn-audit-reset')).toBeTruthy();
+      });
+      expect(api.queryAuditLog).toHaveBeenCalledWith({
+        from: undefined,
+        to: undefined,
+        categories: ['Export'],
+      });
+      const entries = container.querySelectorAll('.activity-entry');
+      expect(entries).toHaveLength(1);
+      expect(entries[0]?.textContent).toContain('Older export');
+
+      (container.querySelector('#btn-audit-reset') as HTMLButtonElement).click();
+      expect(container.querySelectorAll('.activity-entry')).toHaveLength(2);
+    });
+
     test('should not throw if config is undefined', () => {
       const state = createMockState({ config: undefined as any });
 
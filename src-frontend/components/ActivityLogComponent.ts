@@ -1,10 +1,14 @@
 import * as api from '../api';
 import * as renderers from '../renderers';
-import { AppState } from '../types';
+import { AppState, AuditEntry, AuditExportFormat, AuditQuery } from '../types';
 
 import { Component, ComponentActions } from './Component';
 
 export class ActivityLogComponent extends Component {
+  /** Entries found in the audit files, shown instead of the recent ones */
+  private trail: AuditEntry[] | null = null;
+  private filter: renderers.ActivityFilter = { category: '', from: '', to: '' };
+
   constructor(containerId: string, actions: ComponentActions) {
     super(containerId, actions);
   }
@@ -12,7 +16,7 @@ export class ActivityLogComponent extends Component {
   render(state: AppState): void {
     if (!state.config) return;
     const container = this.getContainer();
-    container.innerHTML = renderers.renderActivityLogView(state.config);
+    container.innerHTML = renderers.renderActivityLogView(state.config, this.trail, this.filter);
     this.bindEvents(state);
   }
 
@@ -27,5 +31,63 @@ export class ActivityLogComponent extends Component {
         });
       }
     });
+
+    document.getElementById('btn-audit-search')?.addEventListener('click', () => {
+      void this.searchTrail(state);
+    });
+
+    document.getElementById('btn-audit-reset')?.addEventListener('click', () => {
+      this.trail = null;
+      this.filter = { category: '', from: '', to: '' };
+      this.render(state);
+    });
+
+    document.getElementById('btn-export-audit-csv')?.addEventListener('click', () => {
+      void this.exportTrail('csv');
+    });
+
+    document.getElementById('btn-export-audit-jsonl')?.addEventListener('click', () => {
+      void this.exportTrail('jsonl');
+    });
+  }
+
+  /** The filter as entered, as a query of the audit files */
+  private readQuery(): AuditQuery {
+    const value = (id: string): string =>
+      (document.getElementById(id) as HTMLInputElement | null)?.value ?? '';
+    this.filter = {
+      category: value('audit-category'),
+      from: value('audit-from'),
+      to: value('audit-to'),
+    };
+    // Dates are local days; the backend compares UTC timestamps
+    return {
+      from: this.filter.from ? new Date(`${this.filter.from}T00:00:00`).toISOString() : undefined,
+      to: this.filter.to ? new Date(`${this.filter.to}T23:59:59.999`).toISOString() : undefined,
+      categories: this.filter.category ? [this.filter.category] : [],
+    };
+  }
+
+  private async searchTrail(state: AppState): Promise<void> {
+    try {
+      this.trail = await api.queryAuditLog(this.readQuery());
+      this.render(state);
+    } catch (error) {
+      this.actions.showToast(`Failed to search audit trail: ${String(error)}`, 'error');
+    }
+  }
+
+  private async exportTrail(format: AuditExportFormat): Promise<void> {
+    const query = this.readQuery();
+    try {
+      const path = await api.saveFileDialog([
+        { name: format === 'csv' ? 'CSV' : 'JSON Lines', extensions: [format] },
+      ]);
+      if (!path) return;
+      const written = await api.exportAuditLog(query, path, format);
+      this.actions.showToast(`Exported ${written} audit entries`, 'success');
+    } catch (error) {
+      this.actions.showToast(`Failed to export audit log: ${String(error)}`, 'error');
+    }
   }
 }
@@ -1,15 +1,41 @@
+import { listen } from '@tauri-apps/api/event';
 import Chart, { ChartConfiguration } from 'chart.js/auto';
 
 import * as api from '../api';
 import * as renderers from '../renderers';
-import { AppState, ColumnCleanConfig, DatasetVersion, LifecycleStage } from '../types';
+import {
+  AppState,
+  CleaningPreset,
+  BinStrategy,
+  CategoryEncoding,
+  ColumnCleanConfig,
+  DatasetVersion,
+  DatePart,
+  DeepDivePartEvent,
+  LifecycleStage,
+  UsageSuggestion,
+  ValueStandardisation,
+} from '../types';
+import { ConfigHistory } from '../utils/config-history';
 
 import { Component, ComponentActions } from './Component';
+import { DataGrid } from './DataGrid';
 import { ExportModal } from './ExportModal';
 
 export class AnalyserComponent extends Component {
   private charts: Map<string, Chart> = new Map();
   private isTransitioning: boolean = false;
+  /** Deep dive results by column, kept across re-renders of the same file */
+  private deepDives: Map<
+    string,
+    { path: string; progress: renderers.DeepDiveProgress; loading: boolean }
+  > = new Map();
+  /** Undo/redo of cleaning config changes for the analysis on screen */
+  private configHistory = new ConfigHistory();
+  private lastState: AppState | null = null;
+  /** Rows of the analysed file, kept across re-renders while the grid is open */
+  private dataGrid: DataGrid | null = null;
+  private showDataGrid = false;
 
   private getCurrentStage(state: AppState): LifecycleStage | null {
     // If we have a dataset, use its active version's stage
@@ -33,10 +59,178 @@ export class AnalyserComponent extends Component {
 
   constructor(containerId: string, actions: ComponentActions) {
     super(containerId, actions);
+    document.addEventListener('keydown', e => this.handleHistoryShortcut(e));
+  }
+
+  /** Ctrl/Cmd+Z undoes a config change; Ctrl/Cmd+Shift+Z or Ctrl+Y redoes it */
+  private handleHistoryShortcut(e: KeyboardEvent): void {
+    const state = this.lastState;
+    if (!state?.analysisResponse || state.currentView !== 'Analyser') return;
+    if (!(e.ctrlKey || e.metaKey) || e.altKey) return;
+    // Leave text fields their own undo
+    const target = e.target as HTMLElement | null;
+    if (target?.closest('input[type="text"], input:not([type]), textarea, [contenteditable]')) {
+      return;
+    }
+
+    const key = e.key.toLowerCase();
+    if (key === 'z' && !e.shiftKey) {
+      e.preventDefault();
+      this.undoConfigChange(state);
+    } else if ((key === 'z' && e.shiftKey) || key === 'y') {
+      e.preventDefault();
+      this.redoConfigChange(state);
+    }
+  }
+
+  /** Snapshot the cleaning configs before changing them */
+  private recordConfigChange(state: AppState): void {
+    this.configHistory.record(state.cleaningConfigs);
+  }
+
+  private undoConfigChange(state: AppState): void {
+    const configs = this.configHistory.undo(state.cleaningConfigs);
+    if (!configs) return;
+    state.cleaningConfigs = configs;
+    this.render(state);
+    this.actions.onStateChange();
+  }
+
+  private redoConfigChange(state: AppState): void {
+    const configs = this.configHistory.redo(state.cleaningConfigs);
+    if (!configs) return;
+    state.cleaningConfigs = configs;
+    this.render(state);
+    this.actions.onStateChange();
+  }
+
+  /**
+   * Apply a split_* or merge_* row control. A split without new column
+   * names, or a merge without a name or template, is removed.
+   */
+  private updateSplitMerge(
+    config: ColumnCleanConfig,
+    field: string,
+    target: HTMLInputElement | HTMLSelectElement | HTMLTextAreaElement
+  ): void {
+    const checked = target instanceof HTMLInputElement && target.checked;
+    if (field.startsWith('split_')) {
+      const split = config.split ?? { delimiter: ' ', regex: false, into: [], drop_original: false };
+      if (field === 'split_into') {
+        split.into = target.value
+          .split(',')
+          .map(c => c.trim())
+          .filter(c => c);
+      } else if (field === 'split_delimiter') {
+        split.delimiter = target.value;
+      } else if (field === 'split_regex') {
+        split.regex = checked;
+      } else if (field === 'split_drop_original') {
+        split.drop_original = checked;
+      }
+      config.split = split.into.length > 0 ? split : null;
+    } else {
+      const merge = config.merge ?? { template: '', into: '', drop_sources: false };
+      if (field === 'merge_into') {
+        merge.into = target.value.trim();
+      } else if (field === 'merge_template') {
+        merge.template = target.value;
+      } else if (field === 'merge_drop_sources') {
+        merge.drop_sources = checked;
+      }
+      config.merge = merge.into || merge.template ? merge : null;
+    }
+  }
+
+  /**
+   * Apply a bin_* row control. Choosing no strategy removes the binning;
+   * switching strategy keeps the labels.
+   */
+  private updateBinning(
+    config: ColumnCleanConfig,
+    field: string,
+    target: HTMLInputElement | HTMLSelectElement | HTMLTextAreaElement
+  ): void {
+    const list = (text: string): string[] =>
+      text
+        .split(',')
+        .map(v => v.trim())
+        .filter(v => v);
+    const binning = config.binning ?? {
+      strategy: { kind: 'equal_width', bins: 4 } as BinStrategy,
+      labels: [],
+    };
+    if (field === 'bin_strategy') {
+      if (target.value === 'none') {
+        config.binning = null;
+        return;
+      }
+      binning.strategy =
+        target.value === 'custom'
+          ? { kind: 'custom', edges: [] }
+          : {
+              kind: target.value as 'equal_width' | 'quantile',
+              bins: binning.strategy.kind === 'custom' ? 4 : binning.strategy.bins,
+            };
+    } else if (field === 'bin_count' && binning.strategy.kind !== 'custom') {
+      binning.strategy.bins = Math.max(1, parseInt(target.value, 10) || 1);
+    } else if (field === 'bin_edges') {
+      binning.strategy = {
+        kind: 'custom',
+        edges: list(target.value)
+          .map(Number)
+          .filter(v => Number.isFinite(v)),
+      };
+    } else if (field === 'bin_labels') {
+      binning.labels = list(target.value);
+    }
+    config.binning = binning;
+  }
+
+  /**
+   * Apply an encode_* row control. Choosing one-hot removes the encoding;
+   * any change drops the learned mapping so it is learned again.
+   */
+  private updateEncoding(
+    config: ColumnCleanConfig,
+    field: string,
+    target: HTMLInputElement | HTMLSelectElement | HTMLTextAreaElement
+  ): void {
+    const method = config.encoding?.method;
+    let next: CategoryEncoding['method'] | null = method ?? null;
+    if (field === 'encode_method') {
+      next =
+        target.value === 'ordinal'
+          ? { kind: 'ordinal' }
+          : target.value === 'target'
+            ? { kind: 'target', target: '', smoothing: 10, folds: 5 }
+            : null;
+    } else if (method?.kind === 'target') {
+      if (field === 'encode_target') method.target = target.value.trim();
+      else if (field === 'encode_smoothing')
+        method.smoothing = Math.max(0, parseFloat(target.value) || 0);
+      else if (field === 'encode_folds')
+        method.folds = Math.max(2, parseInt(target.value, 10) || 2);
+    }
+    config.encoding = next ? { method: next } : null;
+  }
+
+  private bindHistoryButtons(state: AppState): void {
+    const undo = document.getElementById('btn-undo-config') as HTMLButtonElement | null;
+    const redo = document.getElementById('btn-redo-config') as HTMLButtonElement | null;
+    if (undo) {
+      undo.disabled = !this.configHistory.canUndo();
+      undo.addEventListener('click', () => this.undoConfigChange(state));
+    }
+    if (redo) {
+      redo.disabled = !this.configHistory.canRedo();
+      redo.addEventListener('click', () => this.redoConfigChange(state));
+    }
   }
 
   render(state: AppState): void {
     const container = this.getContainer();
+    this.lastState = state;
 
     if (state.isLoading) {
       container.innerHTML = renderers.renderLoading(state.loadingMessage, state.isAborting);
@@ -57,6 +251,7 @@ export class AnalyserComponent extends Component {
 
     const currentStage = this.getCurrentStage(state);
     const isReadOnly = this.isReadOnlyStage(currentStage);
+    this.configHistory.track(state.analysisResponse);
 
     // Initialize selectedColumns if empty (default to all selected)
     if (state.selectedColumns.size === 0 && (state.analysisResponse.summary || []).length > 0) {
@@ -112,8 +307,14 @@ export class AnalyserComponent extends Component {
       );
     }
 
+    if (state.workbook) {
+      contentHTML =
+        renderers.renderWorkbookPanel(state.workbook, state.analysisResponse.path) + contentHTML;
+    }
+
     // Update content
     targetContentContainer.innerHTML = contentHTML;
+    this.bindWorkbookEvents(state);
 
     // Post-render bindings
     if (currentStage === 'Validated') {
@@ -122,7 +323,54 @@ export class AnalyserComponent extends Component {
       this.bindPublishedEvents(state);
     } else {
       this.bindEvents(state);
+      this.bindHistoryButtons(state);
       this.initCharts(state);
+      this.mountDataGrid(state);
+    }
+  }
+
+  /** Show the data grid of the analysed file, if open */
+  private mountDataGrid(state: AppState): void {
+    document.getElementById('btn-data-grid')?.classList.toggle('active', this.showDataGrid);
+    const container = document.getElementById('analyser-data-grid-container');
+    const path = state.analysisResponse?.path;
+    if (!container || !path || !this.showDataGrid) return;
+
+    const headerNames = state.analysisResponse?.header_names ?? null;
+    if (
+      !this.dataGrid ||
+      this.dataGrid.path !== path ||
+      JSON.stringify(this.dataGrid.headerNames) !== JSON.stringify(headerNames)
+    ) {
+      this.dataGrid = new DataGrid(path, headerNames);
+    }
+    this.dataGrid.attach(container);
+  }
+
+  private bindWorkbookEvents(state: AppState): void {
+    document.getElementById('btn-analyse-workbook')?.addEventListener('click', () => {
+      if (state.workbook) this.actions.analyseWorkbook?.(state.workbook.path);
+    });
+    document.querySelectorAll<HTMLElement>('.btn-workbook-sheet').forEach(btn => {
+      btn.addEventListener('click', () => {
+        this.actions.selectWorkbookSheet?.(Number(btn.dataset.index));
+      });
+    });
+  }
+
+  /** Analyse the file again with or without its first row as the header */
+  private async reanalyseHeaderRow(state: AppState, naming: string): Promise<void> {
+    const path = state.analysisResponse?.path;
+    if (!path) return;
+    if (naming === 'header') {
+      this.actions.runAnalysis(path);
+    } else if (naming === 'schema') {
+      const schema = await api.openFileDialog([
+        { name: 'Column names', extensions: ['txt', 'csv', 'json'] },
+      ]);
+      if (schema) this.actions.runAnalysis(path, { naming: 'schema', path: schema });
+    } else {
+      this.actions.runAnalysis(path, { naming: naming === 'inferred' ? 'inferred' : 'auto' });
     }
   }
 
@@ -194,6 +442,89 @@ export class AnalyserComponent extends Component {
       });
     });
 
+    // Histogram view toggle (full range / log / P05–P95)
+    document.querySelectorAll('.hist-view-btn').forEach(btn => {
+      btn.addEventListener('click', e => {
+        e.stopPropagation();
+        const target = e.currentTarget as HTMLElement;
+        const chart = target.closest('.distribution-chart');
+        if (!chart) return;
+        chart.querySelectorAll('.hist-view-btn').forEach(b => {
+          b.classList.toggle('active', b === target);
+        });
+        chart.querySelectorAll<HTMLElement>('.histogram').forEach(h => {
+          h.hidden = h.dataset.view !== target.dataset.view;
+        });
+      });
+    });
+
+    // Names suggested from content for placeholder headers
+    document.querySelectorAll<HTMLElement>('.btn-use-suggested-name').forEach(btn => {
+      btn.addEventListener('click', e => {
+        e.stopPropagation();
+        const config = state.cleaningConfigs[btn.dataset.col ?? ''];
+        if (!config || !btn.dataset.name) return;
+        this.recordConfigChange(state);
+        config.new_name = btn.dataset.name;
+        this.render(state);
+        this.actions.onStateChange();
+      });
+    });
+
+    // On-demand deep dive per column
+    this.renderDeepDives(state);
+    document.querySelectorAll<HTMLElement>('.btn-deep-dive').forEach(btn => {
+      btn.addEventListener('click', e => {
+        e.stopPropagation();
+        const column = btn.dataset.col;
+        if (column) void this.runDeepDive(state, column);
+      });
+    });
+
+    // Before/after preview of a column's cleaning config
+    document.querySelectorAll<HTMLButtonElement>('.btn-preview-clean').forEach(btn => {
+      btn.addEventListener('click', e => {
+        e.stopPropagation();
+        const column = btn.dataset.col;
+        if (column) void this.runCleanPreview(state, column, btn);
+      });
+    });
+
+    // Fuzzy clustering of a column's values into one spelling each
+    document.querySelectorAll<HTMLButtonElement>('.btn-standardise-values').forEach(btn => {
+      btn.addEventListener('click', e => {
+        e.stopPropagation();
+        const column = btn.dataset.col;
+        if (column) void this.showStandardiseModal(state, column, btn);
+      });
+    });
+
+    // Feature importance for the selected target column
+    document.getElementById('btn-feature-importance')?.addEventListener('click', () => {
+      void (async () => {
+        const path = state.analysisResponse?.path;
+        const select = document.getElementById(
+          'feature-importance-target'
+        ) as HTMLSelectElement | null;
+        const target = select?.value;
+        const results = document.getElementById('feature-importance-results');
+        if (!path || !target || !results) return;
+
+        results.innerHTML = '<p class="ml-panel-empty">Ranking features...</p>';
+        try {
+          const report = await api.computeFeatureImportance(
+            path,
+            target,
+            state.analysisResponse?.header_names
+          );
+          results.innerHTML = renderers.renderFeatureImportanceResults(report);
+        } catch (err) {
+          results.innerHTML = '';
+          this.actions.showToast(`Feature importance failed: ${String(err)}`, 'error');
+        }
+      })();
+    });
+
     // Column selection checkboxes
     document.querySelectorAll('.col-select-checkbox').forEach(checkbox => {
       checkbox.addEventListener('change', e => {
@@ -212,12 +543,40 @@ export class AnalyserComponent extends Component {
     // Row actions (Active, Impute, Round, etc.)
     document.querySelectorAll('.row-action').forEach(el => {
       el.addEventListener('change', e => {
-        const target = e.target as HTMLInputElement | HTMLSelectElement;
+        const target = e.target as HTMLInputElement | HTMLSelectElement | HTMLTextAreaElement;
         const colName = target.dataset.col!;
         const prop = target.dataset.prop as keyof ColumnCleanConfig;
 
         const config = state.cleaningConfigs[colName];
         if (!config) return;
+        this.recordConfigChange(state);
+
+        const field = target.dataset.prop ?? '';
+        if (field.startsWith('split_') || field.startsWith('merge_')) {
+          this.updateSplitMerge(config, field, target);
+          this.actions.onStateChange();
+          return;
+        }
+        if (field.startsWith('bin_')) {
+          this.updateBinning(config, field, target);
+          this.render(state);
+          this.actions.onStateChange();
+          return;
+        }
+        if (field.startsWith('encode_')) {
+          this.updateEncoding(config, field, target);
+          this.render(state);
+          this.actions.onStateChange();
+          return;
+        }
+        if (field === 'date_parts') {
+          const part = target.dataset.part as DatePart;
+          const parts = (config.date_parts ?? []).filter(p => p !== part);
+          if ((target as HTMLInputElement).checked) parts.push(part);
+          config.date_parts = parts;
+          this.actions.onStateChange();
+          return;
+        }
 
         if (target.type === 'checkbox') {
           const checked = (target as HTMLInputElement).checked;
@@ -237,6 +596,15 @@ export class AnalyserComponent extends Component {
             config.text_case = value as ColumnCleanConfig['text_case'];
           } else if (prop === 'new_name') {
             config.new_name = value;
+          } else if (prop === 'value_mapping') {
+            const mapping: Record<string, string> = {};
+            value.split('\n').forEach(line => {
+              const parts = line.split('->').map(p => p.trim());
+              if (parts.length === 2 && parts[0]) {
+                mapping[parts[0]] = parts[1] ?? '';
+              }
+            });
+            config.value_mapping = mapping;
           }
         }
 
@@ -249,6 +617,7 @@ export class AnalyserComponent extends Component {
       el.addEventListener('change', e => {
         const target = e.target as HTMLInputElement | HTMLSelectElement;
         const action = target.dataset.action!;
+        this.recordConfigChange(state);
 
         if (action === 'activate-advanced') {
           const checked = (target as HTMLInputElement).checked;
@@ -335,6 +704,7 @@ export class AnalyserComponent extends Component {
         const target = e.currentTarget as HTMLElement;
         const action = target.dataset.action!;
         if (action === 'standardize-all' && state.analysisResponse) {
+          this.recordConfigChange(state);
           (state.analysisResponse.summary || []).forEach(s => {
             const config = state.cleaningConfigs[s.name];
             if (config) {
@@ -360,11 +730,29 @@ export class AnalyserComponent extends Component {
 
     document.getElementById('btn-reanalyze')?.addEventListener('click', () => {
       if (state.analysisResponse) {
-        this.actions.runAnalysis(state.analysisResponse.path);
+        this.actions.runAnalysis(
+          state.analysisResponse.path,
+          state.analysisResponse.header_names
+        );
       }
     });
 
+    document.querySelectorAll<HTMLElement>('.btn-header-row').forEach(btn => {
+      btn.addEventListener('click', () => {
+        void this.reanalyseHeaderRow(state, btn.dataset.naming ?? 'auto');
+      });
+    });
+
+    document.getElementById('btn-presets')?.addEventListener('click', () => {
+      void this.showPresetModal(state);
+    });
+
+    document.getElementById('btn-usage-suggestions')?.addEventListener('click', () => {
+      void this.showUsageModal(state);
+    });
+
     document.getElementById('btn-toggle-names')?.addEventListener('click', () => {
+      this.recordConfigChange(state);
       state.useOriginalColumnNames = !state.useOriginalColumnNames;
       // Update all configs to use either original or standardized names
       if (state.analysisResponse) {
@@ -380,12 +768,18 @@ export class AnalyserComponent extends Component {
     });
 
     document.getElementById('btn-clean-all')?.addEventListener('click', () => {
+      this.recordConfigChange(state);
       state.cleanAllActive = !state.cleanAllActive;
       Object.values(state.cleaningConfigs).forEach(c => (c.active = state.cleanAllActive));
       this.render(state);
       this.actions.onStateChange();
     });
 
+    document.getElementById('btn-data-grid')?.addEventListener('click', () => {
+      this.showDataGrid = !this.showDataGrid;
+      this.render(state);
+    });
+
     document.getElementById('btn-export')?.addEventListener('click', () => {
       void this.handleExport(state);
     });
@@ -492,12 +886,160 @@ export class AnalyserComponent extends Component {
     }
   }
 
+  private renderDeepDives(state: AppState): void {
+    const path = state.analysisResponse?.path;
+    document.querySelectorAll<HTMLElement>('.deep-dive-panel').forEach(panel => {
+      const column = panel.dataset.col;
+      const results = panel.querySelector('.deep-dive-results');
+      const button = panel.querySelector<HTMLButtonElement>('.btn-deep-dive');
+      if (!column || !results || !button) return;
+
+      const dive = this.deepDives.get(column);
+      if (!dive || dive.path !== path) {
+        results.innerHTML = '';
+        button.disabled = false;
+        return;
+      }
+
+      const col = state.analysisResponse?.summary.find(c => c.name === column);
+      results.innerHTML = renderers.renderDeepDiveResults(
+        dive.progress,
+        col?.kind === 'Numeric',
+        dive.loading
+      );
+      button.disabled = dive.loading;
+    });
+  }
+
+  private async runCleanPreview(
+    state: AppState,
+    column: string,
+    button: HTMLButtonElement
+  ): Promise<void> {
+    const path = state.analysisResponse?.path;
+    const config = state.cleaningConfigs[column];
+    const results = button.closest('.clean-preview-panel')?.querySelector('.clean-preview-results');
+    if (!path || !config || !results) return;
+
+    button.disabled = true;
+    results.innerHTML = '<p class="clean-preview-note"><i class="ph ph-spinner"></i> Cleaning sample...</p>';
+    try {
+      const preview = await api.previewClean(
+        path,
+        { [column]: config },
+        renderers.CLEAN_PREVIEW_ROWS,
+        state.analysisResponse?.header_names
+      );
+      const columnPreview = preview.columns[0];
+      results.innerHTML = columnPreview ? renderers.renderCleanPreviewResults(columnPreview) : '';
+    } catch (err) {
+      results.innerHTML = '';
+      this.actions.showToast(`Cleaning preview failed: ${String(err)}`, 'error');
+    } finally {
+      button.disabled = false;
+    }
+  }
+
+  private async showStandardiseModal(
+    state: AppState,
+    column: string,
+    button: HTMLButtonElement
+  ): Promise<void> {
+    const path = state.analysisResponse?.path;
+    const config = state.cleaningConfigs[column];
+    const modalContainer = document.getElementById('modal-container');
+    if (!path || !config || !modalContainer) return;
+
+    button.disabled = true;
+    let result: ValueStandardisation;
+    try {
+      result = await api.suggestValueStandardisation(
+        path,
+        column,
+        config,
+        undefined,
+        state.analysisResponse?.header_names
+      );
+    } catch (err) {
+      this.actions.showToast(`Failed to cluster values: ${String(err)}`, 'error');
+      return;
+    } finally {
+      button.disabled = false;
+    }
+
+    const close = (): void => {
+      modalContainer.classList.remove('active');
+      modalContainer.innerHTML = '';
+    };
+    modalContainer.innerHTML = renderers.renderStandardiseModal(result);
+    modalContainer.classList.add('active');
+    document.getElementById('modal-close')?.addEventListener('click', close);
+
+    document.getElementById('btn-apply-standardise')?.addEventListener('click', () => {
+      const mapping: Record<string, string> = {};
+      modalContainer.querySelectorAll<HTMLElement>('tr[data-cluster]').forEach(row => {
+        const cluster = result.clusters[Number(row.dataset.cluster)];
+        const include = row.querySelector<HTMLInputElement>('.standardise-include');
+        const canonical = row.querySelector<HTMLInputElement>('.standardise-canonical')?.value;
+        if (!cluster || !include?.checked || !canonical) return;
+        for (const { value } of cluster.values) {
+          if (value !== canonical) mapping[value] = canonical;
+        }
+      });
+      close();
+      const count = Object.keys(mapping).length;
+      if (count === 0) return;
+
+      this.recordConfigChange(state);
+      config.value_mapping = { ...config.value_mapping, ...mapping };
+      this.render(state);
+      this.actions.onStateChange();
+      this.actions.showToast(`Mapped ${count} values of '${column}'`, 'success');
+    });
+  }
+
+  private async runDeepDive(state: AppState, column: string): Promise<void> {
+    const path = state.analysisResponse?.path;
+    if (!path) return;
+
+    const dive = { path, progress: {} as renderers.DeepDiveProgress, loading: true };
+    this.deepDives.set(column, dive);
+    this.renderDeepDives(state);
+
+    const unlisten = await listen<DeepDivePartEvent>('analysis:deep_dive_part', event => {
+      const payload = event.payload;
+      if (payload.path !== path || payload.column !== column) return;
+      if (payload.part === 'DistinctValues') dive.progress.distinct_values = payload.data;
+      if (payload.part === 'LongestRuns') dive.progress.longest_runs = payload.data;
+      if (payload.part === 'DecileMeans') dive.progress.decile_means = payload.data;
+      this.renderDeepDives(state);
+    });
+
+    try {
+      const { column: _, ...result } = await api.columnDeepDive(
+        path,
+        column,
+        undefined,
+        state.analysisResponse?.header_names
+      );
+      dive.progress = result;
+    } catch (err) {
+      this.deepDives.delete(column);
+      this.actions.showToast(`Deep dive failed: ${String(err)}`, 'error');
+    } finally {
+      unlisten();
+      dive.loading = false;
+      this.renderDeepDives(state);
+    }
+  }
+
   private async handleExport(state: AppState): Promise<void> {
     if (!state.analysisResponse) return;
 
     const modal = new ExportModal('modal-container', this.actions, {
       type: 'Analyser',
       path: state.analysisResponse.path,
+      header_names: state.analysisResponse.header_names,
     });
 
     document.getElementById('modal-container')?.classList.add('active');
@@ -898,6 +1440,7 @@ export class AnalyserComponent extends Component {
         const modal = new ExportModal('modal-container', this.actions, {
           type: 'Analyser',
           path: state.analysisResponse.path,
+          header_names: state.analysisResponse.header_names,
         });
 
         document.getElementById('modal-container')?.classList.add('active');
@@ -912,6 +1455,151 @@ export class AnalyserComponent extends Component {
     });
   }
 
+  private async showPresetModal(state: AppState): Promise<void> {
+    const modalContainer = document.getElementById('modal-container');
+    if (!modalContainer) return;
+
+    let presets: CleaningPreset[];
+    try {
+      presets = await api.listCleaningPresets();
+    } catch (err) {
+      this.actions.showToast(`Failed to load presets: ${String(err)}`, 'error');
+      return;
+    }
+
+    const close = (): void => {
+      modalContainer.classList.remove('active');
+      modalContainer.innerHTML = '';
+    };
+    modalContainer.innerHTML = renderers.renderPresetModal(
+      presets,
+      Object.keys(state.cleaningConfigs).length
+    );
+    modalContainer.classList.add('active');
+    document.getElementById('modal-close')?.addEventListener('click', close);
+
+    document.getElementById('btn-save-preset')?.addEventListener('click', () => {
+      const input = document.getElementById('preset-name') as HTMLInputElement | null;
+      const name = input?.value.trim() ?? '';
+      if (!name) {
+        this.actions.showToast('Enter a name for the preset', 'error');
+        return;
+      }
+      api
+        .saveCleaningPreset(name, state.cleaningConfigs)
+        .then(preset => {
+          this.actions.showToast(`Saved preset '${preset.name}'`, 'success');
+          void this.showPresetModal(state);
+        })
+        .catch(err => this.actions.showToast(`Failed to save preset: ${String(err)}`, 'error'));
+    });
+
+    modalContainer.querySelectorAll('.btn-apply-preset').forEach(btn => {
+      btn.addEventListener('click', () => {
+        const id = (btn as HTMLElement).dataset.id!;
+        close();
+        void this.applyPreset(state, id);
+      });
+    });
+
+    modalContainer.querySelectorAll('.btn-delete-preset').forEach(btn => {
+      btn.addEventListener('click', () => {
+        const id = (btn as HTMLElement).dataset.id!;
+        api
+          .deleteCleaningPreset(id)
+          .then(() => this.showPresetModal(state))
+          .catch(err => this.actions.showToast(`Failed to delete preset: ${String(err)}`, 'error'));
+      });
+    });
+  }
+
+  private async applyPreset(state: AppState, id: string): Promise<void> {
+    if (!state.analysisResponse) return;
+    const columns = (state.analysisResponse.summary || []).map(col => col.name);
+    try {
+      const applied = await api.applyCleaningPreset(id, columns);
+      this.recordConfigChange(state);
+      Object.assign(state.cleaningConfigs, applied.configs);
+      this.render(state);
+      this.actions.onStateChange();
+
+      const fuzzy = applied.matches.filter(m => m.kind === 'fuzzy').length;
+      let message = `Applied preset to ${applied.matches.length} of ${columns.length} columns`;
+      if (fuzzy > 0) message += ` (${fuzzy} matched approximately)`;
+      this.actions.showToast(message, applied.matches.length > 0 ? 'success' : 'info');
+    } catch (err) {
+      this.actions.showToast(`Failed to apply preset: ${String(err)}`, 'error');
+    }
+  }
+
+  private async showUsageModal(state: AppState): Promise<void> {
+    const modalContainer = document.getElementById('modal-container');
+    const path = state.analysisResponse?.path;
+    if (!modalContainer || !path) return;
+
+    let suggestions: UsageSuggestion[];
+    try {
+      suggestions = await api.columnUsageSuggestions(path);
+    } catch (err) {
+      this.actions.showToast(`Failed to load suggestions: ${String(err)}`, 'error');
+      return;
+    }
+
+    const close = (): void => {
+      modalContainer.classList.remove('active');
+      modalContainer.innerHTML = '';
+    };
+    modalContainer.innerHTML = renderers.renderUsageModal(suggestions);
+    modalContainer.classList.add('active');
+    document.getElementById('modal-close')?.addEventListener('click', close);
+
+    document.getElementById('btn-apply-usage')?.addEventListener('click', () => {
+      const selected = Array.from(
+        modalContainer.querySelectorAll<HTMLInputElement>('.usage-suggestion:checked')
+      ).map(input => suggestions[Number(input.dataset.index)]!);
+      close();
+      this.applyUsageSuggestions(state, selected);
+    });
+
+    document.getElementById('btn-clear-usage')?.addEventListener('click', () => {
+      api
+        .clearColumnUsage(path)
+        .then(() => {
+          this.actions.showToast('Forgot earlier cleans of this file', 'success');
+          void this.showUsageModal(state);
+        })
+        .catch(err => this.actions.showToast(`Failed to forget history: ${String(err)}`, 'error'));
+    });
+  }
+
+  private applyUsageSuggestions(state: AppState, suggestions: UsageSuggestion[]): void {
+    this.recordConfigChange(state);
+    let applied = 0;
+    for (const suggestion of suggestions) {
+      const config = state.cleaningConfigs[suggestion.column];
+      if (!config) continue;
+      switch (suggestion.action.kind) {
+        case 'drop':
+          config.active = false;
+          break;
+        case 'rename':
+          config.new_name = suggestion.action.to;
+          break;
+        case 'one_hot_encode':
+          config.ml_preprocessing = true;
+          config.one_hot_encode = true;
+          break;
+      }
+      applied++;
+    }
+    this.render(state);
+    this.actions.onStateChange();
+    this.actions.showToast(
+      `Applied ${applied} suggestion${applied === 1 ? '' : 's'}`,
+      applied > 0 ? 'success' : 'info'
+    );
+  }
+
   private showPublishModal(state: AppState): void {
     if (!state.currentDataset) return;
 
@@ -1,12 +1,14 @@
-import { AppState, View } from '../types';
+import { AppState, HeaderlessNaming, View } from '../types';
 
 export interface ComponentActions {
   switchView: (view: View) => void;
   showToast: (message: string, type?: 'info' | 'error' | 'success') => void;
   onStateChange: () => void;
-  runAnalysis: (path: string) => void;
+  runAnalysis: (path: string, headerNames?: HeaderlessNaming | null) => void;
   navigateTo?: (view: string, datasetId?: string) => void;
   showFirstRunWizard?: () => void;
+  analyseWorkbook?: (path: string) => void;
+  selectWorkbookSheet?: (index: number) => void;
 }
 
 export abstract class Component {
@@ -0,0 +1,276 @@
+/**
+ * Data Grid
+ *
+ * The raw rows of the analysed file, shown next to the column statistics.
+ * Rows are fetched a page at a time (`get_grid_page`) as they scroll into
+ * view, and only the rows on screen are in the DOM, so files of any length
+ * scroll smoothly. Clicking a column header sorts by it; filters are applied
+ * by the backend before paging.
+ */
+
+import * as api from '../api';
+import { ColumnInfo, GridFilter, GridFilterOp, GridSort, HeaderlessNaming } from '../types';
+import { escapeHtml } from '../utils';
+
+/** Rows fetched per request */
+export const GRID_PAGE_ROWS = 200;
+
+/** Height of a row in pixels; rows have a fixed height so their position can be computed */
+const ROW_HEIGHT = 28;
+
+/** Rows rendered above and below those on screen */
+const OVERSCAN_ROWS = 10;
+
+export const GRID_FILTER_LABELS: Record<GridFilterOp, string> = {
+  contains: 'contains',
+  equals: 'equals',
+  not_equals: 'does not equal',
+  greater_than: 'is greater than',
+  less_than: 'is less than',
+  is_null: 'is empty',
+  not_null: 'is not empty',
+};
+
+export class DataGrid {
+  readonly element: HTMLElement;
+  readonly path: string;
+  /** How the file's columns are named when it has no header row */
+  readonly headerNames: HeaderlessNaming | null;
+  private columns: ColumnInfo[] = [];
+  /** Rows passing the filters */
+  private totalRows = 0;
+  /** Fetched rows by page index */
+  private pages = new Map<number, (string | null)[][]>();
+  private loading = new Set<number>();
+  private sort: GridSort | null = null;
+  private filters: GridFilter[] = [];
+  /** Bumped when the sort or filters change, so pages of an earlier query are dropped */
+  private generation = 0;
+  private error: string | null = null;
+  private scrollTop = 0;
+  private frame: number | null = null;
+
+  constructor(path: string, headerNames: HeaderlessNaming | null = null) {
+    this.path = path;
+    this.headerNames = headerNames;
+    this.element = document.createElement('div');
+    this.element.className = 'data-grid';
+    this.element.dataset.testid = 'analyser-data-grid';
+    this.reload();
+  }
+
+  /**
+   * Show the grid in `container`, keeping its scroll position and fetched pages
+   */
+  attach(container: HTMLElement): void {
+    container.appendChild(this.element);
+    const viewport = this.viewport();
+    if (viewport) viewport.scrollTop = this.scrollTop;
+    this.renderRows();
+  }
+
+  /**
+   * Drop the fetched pages and read again from the first row
+   */
+  private reload(): void {
+    this.generation++;
+    this.pages.clear();
+    this.loading.clear();
+    this.scrollTop = 0;
+    this.render();
+    void this.fetchPage(0, true);
+  }
+
+  private async fetchPage(index: number, count = false): Promise<void> {
+    if (this.pages.has(index) || this.loading.has(index)) return;
+    const generation = this.generation;
+    this.loading.add(index);
+    try {
+      const page = await api.getGridPage(
+        this.path,
+        {
+          offset: index * GRID_PAGE_ROWS,
+          limit: GRID_PAGE_ROWS,
+          sort: this.sort,
+          filters: this.filters,
+          count,
+        },
+        this.headerNames
+      );
+      if (generation !== this.generation) return;
+      this.columns = page.columns;
+      if (page.total_rows !== null) this.totalRows = page.total_rows;
+      this.pages.set(index, page.rows);
+      this.error = null;
+    } catch (err) {
+      if (generation !== this.generation) return;
+      this.error = String(err);
+    } finally {
+      if (generation === this.generation) this.loading.delete(index);
+    }
+
+    if (count || this.error) {
+      this.render();
+    } else {
+      this.renderRows();
+    }
+  }
+
+  private viewport(): HTMLElement | null {
+    return this.element.querySelector<HTMLElement>('.data-grid-viewport');
+  }
+
+  private render(): void {
+    const template = `64px repeat(${this.columns.length}, 160px)`;
+    const sortIcon = (column: string): string => {
+      if (this.sort?.column !== column) return '';
+      return `<i class="ph ${this.sort.descending ? 'ph-caret-down' : 'ph-caret-up'}"></i>`;
+    };
+    const counting = this.loading.has(0) && this.pages.size === 0;
+
+    this.element.innerHTML = `
+      <div class="data-grid-toolbar">
+        <select class="data-grid-filter-column" aria-label="Filter column">
+          ${this.columns.map(c => `<option value="${escapeHtml(c.name)}">${escapeHtml(c.name)}</option>`).join('')}
+        </select>
+        <select class="data-grid-filter-op" aria-label="Filter condition">
+          ${Object.entries(GRID_FILTER_LABELS)
+            .map(([op, label]) => `<option value="${op}">${label}</option>`)
+            .join('')}
+        </select>
+        <input type="text" class="data-grid-filter-value" placeholder="Value" aria-label="Filter value">
+        <button type="button" class="btn-secondary btn-small btn-add-grid-filter">
+          <i class="ph ph-funnel"></i> Filter
+        </button>
+        <span class="data-grid-count">
+          ${counting ? 'Counting rows...' : `${this.totalRows.toLocaleString()} rows`}
+        </span>
+      </div>
+      ${
+        this.filters.length
+          ? `
+      <div class="data-grid-filters">
+        ${this.filters
+          .map(
+            (f, i) => `
+          <span class="data-grid-filter-chip">
+            ${escapeHtml(f.column)} ${GRID_FILTER_LABELS[f.op]}${f.op === 'is_null' || f.op === 'not_null' ? '' : ` "${escapeHtml(f.value)}"`}
+            <button type="button" class="btn-remove-grid-filter" data-index="${i}" aria-label="Remove filter">&times;</button>
+          </span>
+        `
+          )
+          .join('')}
+      </div>`
+          : ''
+      }
+      ${this.error ? `<p class="data-grid-error">${escapeHtml(this.error)}</p>` : ''}
+      <div class="data-grid-viewport">
+        <div class="data-grid-header" style="grid-template-columns: ${template}">
+          <div class="data-grid-cell data-grid-index">#</div>
+          ${this.columns
+            .map(
+              c => `
+            <button type="button" class="data-grid-cell data-grid-sort" data-col="${escapeHtml(c.name)}" title="${escapeHtml(c.dtype)}. Click to sort.">
+              <span>${escapeHtml(c.name)}</span>${sortIcon(c.name)}
+            </button>
+          `
+            )
+            .join('')}
+        </div>
+        <div class="data-grid-spacer" style="height: ${this.totalRows * ROW_HEIGHT}px">
+          <div class="data-grid-rows"></div>
+        </div>
+      </div>
+    `;
+    this.bindEvents();
+    this.renderRows();
+  }
+
+  /**
+   * Render the rows on screen, fetching the pages they are on
+   */
+  private renderRows(): void {
+    const viewport = this.viewport();
+    const rowsEl = this.element.querySelector<HTMLElement>('.data-grid-rows');
+    if (!viewport || !rowsEl) return;
+
+    const header = this.element.querySelector<HTMLElement>('.data-grid-header');
+    const scrolled = Math.max(0, viewport.scrollTop - (header?.offsetHeight ?? 0));
+    const first = Math.max(0, Math.floor(scrolled / ROW_HEIGHT) - OVERSCAN_ROWS);
+    const shown = Math.ceil(viewport.clientHeight / ROW_HEIGHT) + 2 * OVERSCAN_ROWS;
+    const last = Math.min(this.totalRows, first + shown);
+
+    for (let page = Math.floor(first / GRID_PAGE_ROWS); page * GRID_PAGE_ROWS < last; page++) {
+      if (!this.pages.has(page)) void this.fetchPage(page);
+    }
+
+    const template = `64px repeat(${this.columns.length}, 160px)`;
+    const cell = (value: string | null): string =>
+      value === null
+        ? '<div class="data-grid-cell"><span class="null-value">null</span></div>'
+        : `<div class="data-grid-cell mono" title="${escapeHtml(value)}">${escapeHtml(value)}</div>`;
+    const rows: string[] = [];
+    for (let i = first; i < last; i++) {
+      const row = this.pages.get(Math.floor(i / GRID_PAGE_ROWS))?.[i % GRID_PAGE_ROWS];
+      rows.push(`
+        <div class="data-grid-row" style="grid-template-columns: ${template}">
+          <div class="data-grid-cell data-grid-index">${(i + 1).toLocaleString()}</div>
+          ${row ? row.map(cell).join('') : '<div class="data-grid-cell data-grid-pending">Loading...</div>'}
+        </div>
+      `);
+    }
+    rowsEl.style.transform = `translateY(${first * ROW_HEIGHT}px)`;
+    rowsEl.innerHTML = rows.join('');
+  }
+
+  private bindEvents(): void {
+    const viewport = this.viewport();
+    viewport?.addEventListener('scroll', () => {
+      this.scrollTop = viewport.scrollTop;
+      if (this.frame !== null) return;
+      this.frame = requestAnimationFrame(() => {
+        this.frame = null;
+        this.renderRows();
+      });
+    });
+
+    this.element.querySelectorAll<HTMLElement>('.data-grid-sort').forEach(btn => {
+      btn.addEventListener('click', () => {
+        const column = btn.dataset.col ?? '';
+        // Ascending, then descending, then unsorted
+        if (this.sort?.column !== column) {
+          this.sort = { column, descending: false };
+        } else if (!this.sort.descending) {
+          this.sort = { column, descending: true };
+        } else {
+          this.sort = null;
+        }
+        this.reload();
+      });
+    });
+
+    const valueInput = this.element.querySelector<HTMLInputElement>('.data-grid-filter-value');
+    const addFilter = (): void => {
+      const column = this.element.querySelector<HTMLSelectElement>('.data-grid-filter-column');
+      const op = this.element.querySelector<HTMLSelectElement>('.data-grid-filter-op');
+      if (!column?.value || !op) return;
+      this.filters.push({
+        column: column.value,
+        op: op.value as GridFilterOp,
+        value: valueInput?.value ?? '',
+      });
+      this.reload();
+    };
+    this.element.querySelector('.btn-add-grid-filter')?.addEventListener('click', addFilter);
+    valueInput?.addEventListener('keydown', e => {
+      if (e.key === 'Enter') addFilter();
+    });
+
+    this.element.querySelectorAll<HTMLElement>('.btn-remove-grid-filter').forEach(btn => {
+      btn.addEventListener('click', () => {
+        this.filters.splice(Number(btn.dataset.index), 1);
+        this.reload();
+      });
+    });
+  }
+}
@@ -3,6 +3,9 @@ import * as renderers from '../renderers';
 import {
   AppState,
   DataDictionary,
+  DictionarySearchHit,
+  DictionaryStorageUsage,
+  ReviewStatus,
   SnapshotMetadata,
   DatasetBusinessMetadata,
   ColumnBusinessMetadata,
@@ -15,12 +18,19 @@ import { Component, ComponentActions } from './Component';
  *
  * Features:
  * - List all dictionary snapshots
+ * - Search datasets and columns across snapshots
+ * - List datasets due for a documentation review
  * - View snapshot details (technical + business metadata)
  * - Edit business metadata (dataset and column level)
  * - Export snapshots to markdown
  */
 export class DictionaryComponent extends Component {
   private snapshots: SnapshotMetadata[] = [];
+  private usage: DictionaryStorageUsage | null = null;
+  private reviews: ReviewStatus[] = [];
+  private searchQuery = '';
+  private searchHits: DictionarySearchHit[] = [];
+  private searchTimer: number | null = null;
   private currentSnapshot: DataDictionary | null = null;
   private viewMode: 'list' | 'detail' = 'list';
 
@@ -33,7 +43,13 @@ export class DictionaryComponent extends Component {
 
     if (this.viewMode === 'list') {
       // Show snapshot list
-      container.innerHTML = renderers.renderDictionaryList(this.snapshots);
+      container.innerHTML = renderers.renderDictionaryList(
+        this.snapshots,
+        this.usage,
+        this.searchQuery,
+        this.reviews
+      );
+      this.renderSearchResults();
       this.bindListEvents();
     } else if (this.viewMode === 'detail' && this.currentSnapshot) {
       // Show snapshot detail
@@ -50,6 +66,8 @@ export class DictionaryComponent extends Component {
   async loadSnapshots(): Promise<void> {
     try {
       this.snapshots = await api.dictionaryListSnapshots();
+      this.usage = await api.dictionaryStorageUsage().catch(() => null);
+      this.reviews = await api.dictionaryReviewSchedule(true).catch(() => []);
       this.viewMode = 'list';
       this.currentSnapshot = null;
     } catch (err) {
@@ -71,10 +89,48 @@ export class DictionaryComponent extends Component {
     }
   }
 
+  /**
+   * Run the dictionary search for `query` and show its results.
+   */
+  private async search(query: string): Promise<void> {
+    this.searchQuery = query;
+    try {
+      this.searchHits = query.trim() ? await api.dictionarySearch(query) : [];
+    } catch (err) {
+      this.actions.showToast(`Search failed: ${String(err)}`, 'error');
+      this.searchHits = [];
+    }
+    // Results are redrawn in place so the search box keeps focus
+    this.renderSearchResults();
+  }
+
+  private renderSearchResults(): void {
+    const results = document.getElementById('dictionary-search-results');
+    if (!results) return;
+    results.innerHTML = renderers.renderDictionarySearchResults(this.searchQuery, this.searchHits);
+    results.querySelectorAll<HTMLElement>('.search-hit').forEach(hit => {
+      hit.addEventListener('click', () => {
+        void (async () => {
+          await this.loadSnapshot(hit.dataset.snapshotId!);
+          this.actions.onStateChange();
+        })();
+      });
+    });
+  }
+
   /**
    * Bind events for list view.
    */
   private bindListEvents(): void {
+    const searchInput = document.getElementById('dictionary-search-input') as HTMLInputElement;
+    searchInput?.addEventListener('input', () => {
+      if (this.searchTimer !== null) window.clearTimeout(this.searchTimer);
+      this.searchTimer = window.setTimeout(() => {
+        this.searchTimer = null;
+        void this.search(searchInput.value);
+      }, 250);
+    });
+
     // Refresh button
     document.getElementById('btn-refresh-snapshots')?.addEventListener('click', () => {
       void (async () => {
@@ -83,6 +139,41 @@ export class DictionaryComponent extends Component {
       })();
     });
 
+    document.getElementById('btn-compact-snapshots')?.addEventListener('click', () => {
+      void (async () => {
+        try {
+          const report = await api.dictionaryPruneSnapshots({
+            keep_last: null,
+            max_age_days: null,
+            compact: true,
+          });
+          this.actions.showToast(
+            `Removed ${report.duplicates_removed.length} duplicate snapshots`,
+            'success'
+          );
+        } catch (err) {
+          this.actions.showToast(`Failed to remove duplicates: ${String(err)}`, 'error');
+        }
+        await this.loadSnapshots();
+        this.actions.onStateChange();
+      })();
+    });
+
+    document.querySelectorAll<HTMLElement>('.btn-mark-reviewed').forEach(btn => {
+      btn.addEventListener('click', () => {
+        void (async () => {
+          try {
+            const snapshot = await api.dictionaryMarkReviewed(btn.dataset.snapshotId!);
+            this.actions.showToast(`Marked ${snapshot.dataset_name} as reviewed`, 'success');
+          } catch (err) {
+            this.actions.showToast(`Failed to mark reviewed: ${String(err)}`, 'error');
+          }
+          await this.loadSnapshots();
+          this.actions.onStateChange();
+        })();
+      });
+    });
+
     // View snapshot buttons
     document.querySelectorAll('.btn-view').forEach(btn => {
       btn.addEventListener('click', e => {
@@ -167,6 +258,10 @@ export class DictionaryComponent extends Component {
         sensitivity_classification: (formData.get('sensitivity_classification') as string) || '',
         known_limitations: (formData.get('known_limitations') as string) || '',
         tags: [],
+        owner: (formData.get('owner') as string) || null,
+        steward: (formData.get('steward') as string) || null,
+        review_cadence_days: parseInt(formData.get('review_cadence_days') as string) || null,
+        last_reviewed: (formData.get('last_reviewed') as string) || null,
       };
 
       // Collect column business metadata
@@ -1,4 +1,5 @@
 import * as api from '../api';
+import { isCommandError } from '../invoke';
 import * as renderers from '../renderers';
 import { AppState, ExportOptions, ExportSource } from '../types';
 
@@ -11,7 +12,7 @@ export interface ExportModalActions extends ComponentActions {
 export class ExportModal extends Component {
   private source: ExportSource;
   private resolve?: (value: boolean) => void;
-  private currentDestType: 'File' | 'Database' = 'File';
+  private currentDestType: renderers.ExportDestChoice = 'File';
   private isExporting: boolean = false;
   private isAborting: boolean = false;
 
@@ -103,7 +104,7 @@ export class ExportModal extends Component {
       btn.addEventListener('click', e => {
         if (!this.isExporting) {
           const target = e.currentTarget as HTMLElement;
-          this.currentDestType = target.dataset.dest as 'File' | 'Database';
+          this.currentDestType = target.dataset.dest as renderers.ExportDestChoice;
           this.updateConfigSection(state);
         }
       });
@@ -123,6 +124,10 @@ export class ExportModal extends Component {
   }
 
   private async handleExport(state: AppState): Promise<void> {
+    if (this.currentDestType === 'InPlace') {
+      await this.handleCleanInPlace(state);
+      return;
+    }
     let target = '';
     let format: 'csv' | 'json' | 'parquet' | undefined;
 
@@ -168,6 +173,22 @@ export class ExportModal extends Component {
       }
     }
 
+    // Rejected rows go next to the export, e.g. data.rejects.csv (parquet or csv only)
+    let rejectPath: string | undefined;
+    if (this.currentDestType === 'File') {
+      const rejectsCheckbox = document.getElementById('export-write-rejects') as HTMLInputElement;
+      if (rejectsCheckbox?.checked) {
+        const dot = target.lastIndexOf('.');
+        const base = dot > 0 ? target.slice(0, dot) : target;
+        rejectPath = `${base}.rejects.${format === 'parquet' ? 'parquet' : 'csv'}`;
+      }
+    }
+
+    const completenessCheckbox = document.getElementById(
+      'export-completeness-score'
+    ) as HTMLInputElement | null;
+    const completenessScore = completenessCheckbox?.checked ?? false;
+
     const options: ExportOptions = {
       source: this.source,
       configs: state.cleaningConfigs,
@@ -178,6 +199,8 @@ export class ExportModal extends Component {
       },
       create_dictionary: createDictionary,
       create_receipt: createReceipt,
+      completeness_score: completenessScore,
+      ...(rejectPath && { reject_path: rejectPath }),
     };
 
     try {
@@ -192,11 +215,51 @@ export class ExportModal extends Component {
     } catch (err) {
       this.isExporting = false;
       this.render(state);
+      if (isCommandError(err, 'aborted')) {
+        this.actions.showToast('Export aborted', 'info');
+        return;
+      }
       console.error('Export failed:', err);
       this.actions.showToast(`Export failed: ${String(err)}`, 'error');
     }
   }
 
+  private async handleCleanInPlace(state: AppState): Promise<void> {
+    const path = this.source.path;
+    if (!path) return;
+    const replaceInput = document.getElementById('in-place-replace') as HTMLInputElement | null;
+    const suffixInput = document.getElementById('in-place-suffix-text') as HTMLInputElement | null;
+    const replace = replaceInput?.checked ?? false;
+    const suffix = suffixInput?.value.trim() ?? '';
+    if (!replace && !suffix) {
+      this.actions.showToast('Please enter a suffix for the cleaned file', 'error');
+      return;
+    }
+
+    try {
+      this.isExporting = true;
+      this.render(state);
+
+      const report = await api.cleanInPlace(
+        path,
+        state.cleaningConfigs,
+        replace ? { mode: 'replace' } : { mode: 'suffix', suffix },
+        this.source.header_names
+      );
+      const backup = report.backup ? ` (original backed up to ${report.backup})` : '';
+      this.actions.showToast(
+        `Cleaned ${report.rows} rows into ${report.output}${backup}`,
+        'success'
+      );
+      this.close(true);
+    } catch (err) {
+      this.isExporting = false;
+      this.render(state);
+      console.error('Clean in place failed:', err);
+      this.actions.showToast(`Clean in place failed: ${String(err)}`, 'error');
+    }
+  }
+
   private close(success: boolean): void {
     const container = this.getContainer();
     container.classList.remove('active');
@@ -1,6 +1,6 @@
 import * as api from '../api';
 import * as renderers from '../renderers';
-import { AppState, DiffSummary } from '../types';
+import { AppState, ApprovalState, DiffSummary } from '../types';
 
 import { Component, ComponentActions } from './Component';
 
@@ -52,6 +52,55 @@ export class LifecycleComponent extends Component {
       });
     });
 
+    document.querySelectorAll('[data-action="approval"]').forEach(btn => {
+      btn.addEventListener('click', e => {
+        void (async () => {
+          const target = e.currentTarget as HTMLElement;
+          const versionId = target.dataset.versionId!;
+          const to = target.dataset.approvalState as ApprovalState;
+          if (!state.currentDataset) return;
+
+          const comment = to === 'Draft' ? (window.prompt('Reason (optional)') ?? '') : '';
+          try {
+            const updated = await api.transitionApproval(
+              state.currentDataset.id,
+              versionId,
+              to,
+              comment
+            );
+            state.currentDataset.versions = state.currentDataset.versions.map(v =>
+              v.id === updated.id ? updated : v
+            );
+            this.actions.showToast(`Version moved to ${target.textContent ?? to}`, 'success');
+            this.actions.onStateChange();
+          } catch (err) {
+            this.actions.showToast(`Failed to update approval: ${String(err)}`, 'error');
+          }
+        })();
+      });
+    });
+
+    document.querySelectorAll('[data-action="export-viewer"]').forEach(btn => {
+      btn.addEventListener('click', e => {
+        void (async () => {
+          const versionId = (e.currentTarget as HTMLElement).dataset.versionId!;
+          if (!state.currentDataset) return;
+
+          const outputPath = await api.saveFileDialog([
+            { name: 'HTML Viewer', extensions: ['html'] },
+          ]);
+          if (!outputPath) return;
+          try {
+            this.actions.showToast('Exporting viewer...', 'info');
+            await api.exportVersionViewer(state.currentDataset.id, versionId, outputPath);
+            this.actions.showToast(`Viewer saved to ${outputPath}`, 'success');
+          } catch (err) {
+            this.actions.showToast(`Failed to export viewer: ${String(err)}`, 'error');
+          }
+        })();
+      });
+    });
+
     document.querySelectorAll('[data-action="view-diff"]').forEach(btn => {
       btn.addEventListener('click', e => {
         void (async () => {
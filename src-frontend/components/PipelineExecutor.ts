@@ -4,9 +4,18 @@
  * Modal dialog for executing pipelines with progress tracking and result display.
  */
 
+import { listen } from '@tauri-apps/api/event';
 import { open } from '@tauri-apps/plugin-dialog';
 
-import { PipelineSpec, executePipeline, ExecutionResult } from '../api-pipeline';
+import * as api from '../api';
+import {
+  ColumnResolution,
+  PipelineSpec,
+  PipelineProgress,
+  executePipeline,
+  ExecutionResult,
+  resolvePipelineColumns,
+} from '../api-pipeline';
 
 export type ExecutionState = 'idle' | 'selecting' | 'running' | 'success' | 'error';
 
@@ -17,7 +26,13 @@ export class PipelineExecutor {
   private inputPath: string | null = null;
   private outputPath: string | null = null;
   private result: ExecutionResult | null = null;
+  private progress: PipelineProgress | null = null;
+  private aborting = false;
   private error: string | null = null;
+  /** Input columns matched to columns the pipeline expects under another name */
+  private resolution: ColumnResolution | null = null;
+  /** Input columns whose match the user confirmed */
+  private confirmed = new Set<string>();
   private onClose?: () => void;
 
   constructor(container: HTMLElement, spec: PipelineSpec) {
@@ -133,6 +148,8 @@ export class PipelineExecutor {
                 <button id="select-output-btn" class="btn-secondary">Specify Output File... (Optional)</button>
             </div>
 
+            ${this.renderColumnMatches()}
+
             <div class="executor-section">
                 <h4>Pipeline Steps</h4>
                 <p class="text-secondary">${this.spec.steps.length} transformation step(s)</p>
@@ -140,6 +157,51 @@ export class PipelineExecutor {
         `;
   }
 
+  /**
+   * Render the confirmation table for columns the input names differently
+   */
+  private renderColumnMatches(): string {
+    const resolution = this.resolution;
+    if (!resolution || (!resolution.matches.length && !resolution.unmatched_expected.length)) {
+      return '';
+    }
+    const rows = resolution.matches
+      .map(
+        m => `
+                <tr>
+                    <td>${this.escapeHtml(m.expected)}</td>
+                    <td>${this.escapeHtml(m.column)}</td>
+                    <td>${m.kind === 'fuzzy' ? `Similar (${m.distance} edit${m.distance === 1 ? '' : 's'})` : 'Case or spacing'}</td>
+                    <td>
+                        <input type="checkbox" class="column-match-confirm" data-column="${this.escapeHtml(m.column)}" ${this.confirmed.has(m.column) ? 'checked' : ''}>
+                    </td>
+                </tr>`
+      )
+      .join('');
+    return `
+            <div class="executor-section">
+                <h4>Column Matches</h4>
+                <p class="text-secondary">This file names some of the pipeline's columns differently. Confirm the matches to rename them before the first step.</p>
+                ${
+                  rows
+                    ? `
+                <table class="column-match-table">
+                    <thead>
+                        <tr><th>Pipeline column</th><th>File column</th><th>Match</th><th>Use</th></tr>
+                    </thead>
+                    <tbody>${rows}</tbody>
+                </table>`
+                    : ''
+                }
+                ${
+                  resolution.unmatched_expected.length
+                    ? `<p class="text-secondary">Not found: ${this.escapeHtml(resolution.unmatched_expected.join(', '))}</p>`
+                    : ''
+                }
+            </div>
+        `;
+  }
+
   /**
    * Render progress UI
    */
@@ -149,11 +211,25 @@ export class PipelineExecutor {
                 <div class="progress-spinner"></div>
                 <h4>Executing Pipeline...</h4>
                 <p>Processing ${this.escapeHtml(this.inputPath ?? '')}...</p>
-                <p class="text-secondary">This may take a few moments</p>
+                <p class="text-secondary" id="executor-progress-text">${this.escapeHtml(this.progressText())}</p>
             </div>
         `;
   }
 
+  /**
+   * Describe the latest progress event
+   */
+  private progressText(): string {
+    if (this.aborting) return 'Stopping after the current step...';
+    const progress = this.progress;
+    if (!progress) return 'This may take a few moments';
+    if (progress.stage === 'writing') {
+      return `Writing ${progress.rows.toLocaleString()} rows...`;
+    }
+    const rows = progress.rows === null ? '' : ` (${progress.rows.toLocaleString()} rows)`;
+    return `Step ${progress.step_index + 1} of ${progress.steps}: ${progress.op}${rows}`;
+  }
+
   /**
    * Render success UI
    */
@@ -230,7 +306,7 @@ export class PipelineExecutor {
                 `;
       case 'running':
         return `
-                    <button class="btn-secondary" disabled>Executing...</button>
+                    <button id="abort-btn" class="btn-secondary" ${this.aborting ? 'disabled' : ''}>Abort</button>
                 `;
       case 'success':
         return `
@@ -268,6 +344,17 @@ export class PipelineExecutor {
       void this.selectInputFile();
     });
 
+    this.container.querySelectorAll<HTMLInputElement>('.column-match-confirm').forEach(box => {
+      box.addEventListener('change', () => {
+        const column = box.dataset.column ?? '';
+        if (box.checked) {
+          this.confirmed.add(column);
+        } else {
+          this.confirmed.delete(column);
+        }
+      });
+    });
+
     const selectOutputBtn = this.container.querySelector('#select-output-btn');
     selectOutputBtn?.addEventListener('click', () => {
       void this.selectOutputFile();
@@ -282,6 +369,14 @@ export class PipelineExecutor {
     const cancelBtn = this.container.querySelector('#cancel-btn');
     cancelBtn?.addEventListener('click', () => this.close());
 
+    const abortBtn = this.container.querySelector('#abort-btn');
+    abortBtn?.addEventListener('click', () => {
+      this.aborting = true;
+      this.render();
+      this.attachEventListeners();
+      void api.abortProcessing();
+    });
+
     const doneBtn = this.container.querySelector('#done-btn');
     doneBtn?.addEventListener('click', () => this.close());
 
@@ -312,6 +407,7 @@ export class PipelineExecutor {
 
       if (selected) {
         this.inputPath = selected;
+        await this.resolveColumns();
         this.render();
         this.attachEventListeners();
       }
@@ -320,6 +416,38 @@ export class PipelineExecutor {
     }
   }
 
+  /**
+   * Match the columns the pipeline expects against the selected input,
+   * confirming matches that differ only in case or spacing
+   */
+  private async resolveColumns(): Promise<void> {
+    this.resolution = null;
+    this.confirmed.clear();
+    if (!this.inputPath) return;
+    try {
+      this.resolution = await resolvePipelineColumns(this.spec, this.inputPath);
+      for (const m of this.resolution.matches) {
+        if (m.kind !== 'fuzzy') this.confirmed.add(m.column);
+      }
+    } catch (error) {
+      // Validation during the run reports the same problems
+      console.error('Error matching pipeline columns:', error);
+    }
+  }
+
+  /**
+   * The spec with the confirmed column matches as input renames
+   */
+  private specWithConfirmedColumns(): PipelineSpec {
+    const matches = this.resolution?.matches.filter(m => this.confirmed.has(m.column)) ?? [];
+    if (!matches.length) return this.spec;
+    const renames = { ...this.spec.input?.column_renames };
+    for (const m of matches) {
+      renames[m.column] = m.expected;
+    }
+    return { ...this.spec, input: { ...this.spec.input, column_renames: renames } };
+  }
+
   /**
    * Select output file
    */
@@ -352,16 +480,31 @@ export class PipelineExecutor {
     if (!this.inputPath) return;
 
     this.state = 'running';
+    this.progress = null;
+    this.aborting = false;
     this.render();
     this.attachEventListeners();
 
+    const unlisten = await listen<PipelineProgress>('pipeline:progress', event => {
+      this.progress = event.payload;
+      const text = this.container.querySelector('#executor-progress-text');
+      if (text) text.textContent = this.progressText();
+    });
+
     try {
-      this.result = await executePipeline(this.spec, this.inputPath, this.outputPath ?? undefined);
+      this.result = await executePipeline(
+        this.specWithConfirmedColumns(),
+        this.inputPath,
+        this.outputPath ?? undefined
+      );
 
       this.state = 'success';
     } catch (error) {
       this.error = String(error);
       this.state = 'error';
+    } finally {
+      unlisten();
+      this.aborting = false;
     }
 
     this.render();
@@ -1,8 +1,15 @@
-import { invoke } from '@tauri-apps/api/core';
+import { invoke } from '../invoke';
 
 import * as api from '../api';
 import * as renderers from '../renderers';
-import { AppState, DbConnection, StandardPaths } from '../types';
+import {
+  AIAuthScheme,
+  AIProvider,
+  AppState,
+  DbConnection,
+  DuplicateColumnPolicy,
+  StandardPaths,
+} from '../types';
 
 import { Component, ComponentActions } from './Component';
 
@@ -29,6 +36,7 @@ export class SettingsComponent extends Component {
     void this.updateAPIKeyStatus();
     void this.loadPaths();
     void this.loadLogPath();
+    void this.loadRuns();
   }
 
   override bindEvents(state: AppState): void {
@@ -121,6 +129,96 @@ export class SettingsComponent extends Component {
       });
     }
 
+    const duplicatePolicySelect = document.getElementById(
+      'duplicate-column-policy'
+    ) as HTMLSelectElement | null;
+    duplicatePolicySelect?.addEventListener('change', () => {
+      if (!state.config) return;
+      state.config.settings.duplicate_column_policy =
+        duplicatePolicySelect.value as DuplicateColumnPolicy;
+      void api
+        .saveAppConfig(state.config)
+        .then(() => this.actions.showToast('Repeated column name policy updated', 'success'))
+        .catch(err => this.actions.showToast(`Failed to save settings: ${String(err)}`, 'error'));
+    });
+
+    const autoTransposeToggle = document.getElementById(
+      'auto-transpose'
+    ) as HTMLInputElement | null;
+    autoTransposeToggle?.addEventListener('change', () => {
+      if (!state.config) return;
+      state.config.settings.auto_transpose = autoTransposeToggle.checked;
+      void api
+        .saveAppConfig(state.config)
+        .then(() =>
+          this.actions.showToast(
+            `Auto-transpose ${autoTransposeToggle.checked ? 'enabled' : 'disabled'}`,
+            'success'
+          )
+        )
+        .catch(err => this.actions.showToast(`Failed to save settings: ${String(err)}`, 'error'));
+    });
+
+    const fillMergedToggle = document.getElementById(
+      'layout-fill-merged-headers'
+    ) as HTMLInputElement | null;
+    const dropFooterToggle = document.getElementById(
+      'layout-drop-footer-rows'
+    ) as HTMLInputElement | null;
+    const saveLayout = (): void => {
+      if (!state.config) return;
+      state.config.settings.layout = {
+        fill_merged_headers: fillMergedToggle?.checked ?? true,
+        drop_footer_rows: dropFooterToggle?.checked ?? true,
+      };
+      void api
+        .saveAppConfig(state.config)
+        .then(() => this.actions.showToast('Spreadsheet layout fixes updated', 'success'))
+        .catch(err => this.actions.showToast(`Failed to save settings: ${String(err)}`, 'error'));
+    };
+    fillMergedToggle?.addEventListener('change', saveLayout);
+    dropFooterToggle?.addEventListener('change', saveLayout);
+
+    const keepLastInput = document.getElementById('dictionary-keep-last') as HTMLInputElement | null;
+    const maxAgeInput = document.getElementById('dictionary-max-age') as HTMLInputElement | null;
+    const compactToggle = document.getElementById('dictionary-compact') as HTMLInputElement | null;
+    const positiveOrNull = (input: HTMLInputElement | null): number | null => {
+      const value = parseInt(input?.value ?? '', 10);
+      return Number.isFinite(value) && value > 0 ? value : null;
+    };
+    const saveRetention = (): void => {
+      if (!state.config) return;
+      state.config.settings.dictionary_retention = {
+        keep_last: positiveOrNull(keepLastInput),
+        max_age_days: positiveOrNull(maxAgeInput),
+        compact: compactToggle?.checked ?? false,
+      };
+      void api
+        .saveAppConfig(state.config)
+        .then(() => this.actions.showToast('Dictionary snapshot retention updated', 'success'))
+        .catch(err => this.actions.showToast(`Failed to save settings: ${String(err)}`, 'error'));
+    };
+    keepLastInput?.addEventListener('change', saveRetention);
+    maxAgeInput?.addEventListener('change', saveRetention);
+    compactToggle?.addEventListener('change', saveRetention);
+
+    const reviewToggle = document.getElementById(
+      'dictionary-review-reminders'
+    ) as HTMLInputElement | null;
+    reviewToggle?.addEventListener('change', () => {
+      if (!state.config) return;
+      state.config.settings.dictionary_review_reminders = reviewToggle.checked;
+      void api
+        .saveAppConfig(state.config)
+        .then(() =>
+          this.actions.showToast(
+            `Review reminders ${reviewToggle.checked ? 'enabled' : 'disabled'}`,
+            'success'
+          )
+        )
+        .catch(err => this.actions.showToast(`Failed to save settings: ${String(err)}`, 'error'));
+    });
+
     // Handle sampling strategy changes
     const samplingStrategySelect = document.getElementById(
       'sampling-strategy'
@@ -208,6 +306,10 @@ export class SettingsComponent extends Component {
       void this.openLogFile('error');
     });
 
+    document.getElementById('select-run-log')?.addEventListener('change', e => {
+      void this.loadRunLog((e.target as HTMLSelectElement).value);
+    });
+
     document.querySelectorAll<HTMLButtonElement>('.btn-open-trusted-path').forEach(btn => {
       btn.addEventListener('click', () => {
         const path = btn.dataset.path;
@@ -286,9 +388,14 @@ export class SettingsComponent extends Component {
     const btnSaveApiKey = document.getElementById('btn-save-api-key');
     const btnTestAI = document.getElementById('btn-test-ai');
     const btnDeleteApiKey = document.getElementById('btn-delete-api-key');
-    const aiModel = document.getElementById('ai-model') as HTMLSelectElement;
+    const aiProvider = document.getElementById('ai-provider') as HTMLSelectElement;
+    const aiBaseUrl = document.getElementById('ai-base-url') as HTMLInputElement;
+    const aiAuthScheme = document.getElementById('ai-auth-scheme') as HTMLSelectElement;
+    const aiApiVersion = document.getElementById('ai-api-version') as HTMLInputElement;
+    const aiModel = document.getElementById('ai-model') as HTMLInputElement;
     const aiTemperature = document.getElementById('ai-temperature') as HTMLInputElement;
     const aiMaxTokens = document.getElementById('ai-max-tokens') as HTMLInputElement;
+    const aiContextWindow = document.getElementById('ai-context-window') as HTMLInputElement;
 
     // Toggle AI enabled
     aiEnabled?.addEventListener('change', () => {
@@ -386,11 +493,18 @@ export class SettingsComponent extends Component {
     // Update AI config on change
     const updateAIConfig = async (): Promise<void> => {
       if (state.config) {
+        const optional = (value: string | undefined): string | null => value?.trim() || null;
         state.config.settings.ai_config = {
+          ...state.config.settings.ai_config,
           enabled: aiEnabled?.checked ?? false,
-          model: aiModel?.value ?? 'gpt-3.5-turbo',
+          provider: (aiProvider?.value as AIProvider | undefined) ?? 'openai',
+          base_url: optional(aiBaseUrl?.value),
+          auth_scheme: optional(aiAuthScheme?.value) as AIAuthScheme | null,
+          api_version: optional(aiApiVersion?.value),
+          model: aiModel?.value.trim() || 'gpt-4o-mini',
           temperature: parseFloat(aiTemperature?.value ?? '0.7'),
           max_tokens: parseInt(aiMaxTokens?.value ?? '1000'),
+          context_window: parseInt(aiContextWindow?.value ?? '16000'),
         };
 
         try {
@@ -404,9 +518,14 @@ export class SettingsComponent extends Component {
       }
     };
 
+    aiProvider?.addEventListener('change', () => void updateAIConfig());
+    aiBaseUrl?.addEventListener('change', () => void updateAIConfig());
+    aiAuthScheme?.addEventListener('change', () => void updateAIConfig());
+    aiApiVersion?.addEventListener('change', () => void updateAIConfig());
     aiModel?.addEventListener('change', () => void updateAIConfig());
     aiTemperature?.addEventListener('change', () => void updateAIConfig());
     aiMaxTokens?.addEventListener('change', () => void updateAIConfig());
+    aiContextWindow?.addEventListener('change', () => void updateAIConfig());
   }
 
   private async updateAPIKeyStatus(): Promise<void> {
@@ -549,6 +668,8 @@ export class SettingsComponent extends Component {
       id: crypto.randomUUID(),
       name,
       settings: this.getNewConnectionSettings(),
+      read_only:
+        (document.getElementById('new-conn-read-only') as HTMLInputElement)?.checked ?? false,
     };
 
     if (state.config) {
@@ -667,4 +788,30 @@ export class SettingsComponent extends Component {
       console.error('Failed to load log directory path:', error);
     }
   }
+
+  private async loadRuns(): Promise<void> {
+    const select = document.getElementById('select-run-log');
+    if (!select) return;
+    try {
+      select.innerHTML = renderers.renderRunOptions(await api.listRuns());
+    } catch (error) {
+      console.error('Failed to list runs:', error);
+      select.innerHTML = '<option value="">Run logs unavailable</option>';
+    }
+  }
+
+  private async loadRunLog(runId: string): Promise<void> {
+    const container = document.getElementById('run-log-entries');
+    if (!container) return;
+    if (!runId) {
+      container.innerHTML = '';
+      return;
+    }
+    try {
+      container.innerHTML = renderers.renderRunLogEntries(await api.readRunLog(runId));
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      this.actions.showToast(`Failed to read run log: ${message}`, 'error');
+    }
+  }
 }
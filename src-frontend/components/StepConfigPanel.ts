@@ -6,6 +6,7 @@
  */
 
 import { PipelineStep } from '../api-pipeline';
+import { DATE_PART_OPTIONS } from '../renderers/common';
 
 export interface StepConfigPanelState {
   step: PipelineStep | null;
@@ -13,6 +14,14 @@ export interface StepConfigPanelState {
   errors: Map<string, string>;
 }
 
+/** Fields of an encode_categories method; target ones only for `kind: 'target'` */
+interface EncodeMethodFields {
+  kind: string;
+  target?: string;
+  smoothing?: number;
+  folds?: number;
+}
+
 export class StepConfigPanel {
   private state: StepConfigPanelState;
   private container: HTMLElement;
@@ -69,6 +78,7 @@ export class StepConfigPanel {
                 </div>
                 <div class="config-body">
                     ${this.renderConfigForm(stepType, stepObj)}
+                    ${this.renderErrorPolicyField(stepObj)}
                 </div>
                 ${this.renderErrors()}
             </div>
@@ -107,8 +117,49 @@ export class StepConfigPanel {
         return this.renderOneHotEncodeForm(stepObj);
       case 'extract_numbers':
         return this.renderExtractNumbersForm(stepObj);
+      case 'bin':
+        return this.renderBinForm(stepObj);
+      case 'encode_categories':
+        return this.renderEncodeCategoriesForm(stepObj);
+      case 'vectorise_text':
+        return this.renderVectoriseTextForm(stepObj);
       case 'regex_replace':
         return this.renderRegexReplaceForm(stepObj);
+      case 'map_values':
+        return this.renderMapValuesForm(stepObj);
+      case 'split_column':
+        return this.renderSplitColumnForm(stepObj);
+      case 'merge_columns':
+        return this.renderMergeColumnsForm(stepObj);
+      case 'extract_date_parts':
+        return this.renderExtractDatePartsForm(stepObj);
+      case 'with_column':
+        return this.renderWithColumnForm(stepObj);
+      case 'aggregate':
+        return this.renderAggregateForm(stepObj);
+      case 'concat':
+      case 'union':
+        return this.renderStackInputsForm(stepObj);
+      case 'sort':
+        return this.renderSortForm(stepObj);
+      case 'limit':
+        return this.renderLimitForm(stepObj);
+      case 'sample':
+        return this.renderSampleForm(stepObj);
+      case 'pivot':
+        return this.renderPivotForm(stepObj);
+      case 'unpivot':
+        return this.renderUnpivotForm(stepObj);
+      case 'transpose':
+        return this.renderTransposeForm(stepObj);
+      case 'expect_schema':
+        return this.renderExpectSchemaForm(stepObj);
+      case 'completeness_score':
+        return this.renderCompletenessScoreForm(stepObj);
+      case 'write_output':
+        return this.renderWriteOutputForm(stepObj);
+      case 'external':
+        return this.renderExternalForm(stepObj);
       default:
         return '<p>Configuration for this step type is not yet implemented.</p>';
     }
@@ -386,6 +437,206 @@ export class StepConfigPanel {
         `;
   }
 
+  /**
+   * Render form for bin step
+   */
+  private renderBinForm(stepObj: Record<string, unknown>): string {
+    const column = (stepObj.column as string) || '';
+    const strategy = (stepObj.strategy as { kind: string; bins?: number; edges?: number[] }) || {
+      kind: 'equal_width',
+      bins: 4,
+    };
+    const labels = (stepObj.labels as string[]) || [];
+    const bins = strategy.bins ?? 4;
+    const edges = (strategy.edges ?? []).join(', ');
+
+    return `
+            <div class="form-group">
+                <label for="bin-column-input">Column</label>
+                <input
+                    type="text"
+                    id="bin-column-input"
+                    class="form-control"
+                    placeholder="e.g., age"
+                    value="${this.escapeHtml(column)}"
+                />
+            </div>
+            <div class="form-row">
+                <div class="form-group">
+                    <label for="bin-strategy-select">Strategy</label>
+                    <select id="bin-strategy-select" class="form-control">
+                        <option value="equal_width" ${strategy.kind === 'equal_width' ? 'selected' : ''}>Equal width</option>
+                        <option value="quantile" ${strategy.kind === 'quantile' ? 'selected' : ''}>Quantiles (equal counts)</option>
+                        <option value="custom" ${strategy.kind === 'custom' ? 'selected' : ''}>Custom edges</option>
+                    </select>
+                </div>
+                <div class="form-group">
+                    <label for="bin-count-input">Bins</label>
+                    <input
+                        type="number"
+                        id="bin-count-input"
+                        class="form-control"
+                        min="1"
+                        step="1"
+                        value="${bins}"
+                    />
+                </div>
+            </div>
+            <div class="form-group">
+                <label for="bin-edges-input">Custom Edges</label>
+                <input
+                    type="text"
+                    id="bin-edges-input"
+                    class="form-control"
+                    placeholder="e.g., 0, 18, 65, 120"
+                    value="${this.escapeHtml(edges)}"
+                />
+                <small class="form-hint">Used by the custom strategy. Values outside the edges become empty.</small>
+            </div>
+            <div class="form-group">
+                <label for="bin-labels-input">Labels</label>
+                <input
+                    type="text"
+                    id="bin-labels-input"
+                    class="form-control"
+                    placeholder="Optional, one per bin, e.g., child, adult, senior"
+                    value="${this.escapeHtml(labels.join(', '))}"
+                />
+                <small class="form-hint">Leave empty to name bins by their range, e.g. 18 to 30</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for encode_categories step
+   */
+  private renderEncodeCategoriesForm(stepObj: Record<string, unknown>): string {
+    const column = (stepObj.column as string) || '';
+    const encoding = (stepObj.encoding as { method?: EncodeMethodFields }) || {};
+    const method = encoding.method ?? { kind: 'ordinal' };
+    const mappingPath = (stepObj.mapping_path as string) || '';
+
+    return `
+            <div class="form-group">
+                <label for="encode-column-input">Column</label>
+                <input
+                    type="text"
+                    id="encode-column-input"
+                    class="form-control"
+                    placeholder="e.g., city"
+                    value="${this.escapeHtml(column)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="encode-method-select">Method</label>
+                <select id="encode-method-select" class="form-control">
+                    <option value="ordinal" ${method.kind === 'ordinal' ? 'selected' : ''}>Ordinal (sorted position)</option>
+                    <option value="target" ${method.kind === 'target' ? 'selected' : ''}>Target mean</option>
+                </select>
+            </div>
+            <div class="form-row">
+                <div class="form-group">
+                    <label for="encode-target-input">Target Column</label>
+                    <input
+                        type="text"
+                        id="encode-target-input"
+                        class="form-control"
+                        placeholder="e.g., churned"
+                        value="${this.escapeHtml(method.target ?? '')}"
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="encode-smoothing-input">Smoothing</label>
+                    <input
+                        type="number"
+                        id="encode-smoothing-input"
+                        class="form-control"
+                        min="0"
+                        step="1"
+                        value="${method.smoothing ?? 10}"
+                    />
+                </div>
+                <div class="form-group">
+                    <label for="encode-folds-input">Folds</label>
+                    <input
+                        type="number"
+                        id="encode-folds-input"
+                        class="form-control"
+                        min="2"
+                        step="1"
+                        value="${method.folds ?? 5}"
+                    />
+                </div>
+            </div>
+            <small class="form-hint">Target settings are only used by the target mean method.</small>
+            <div class="form-group">
+                <label for="encode-mapping-input">Mapping File</label>
+                <input
+                    type="text"
+                    id="encode-mapping-input"
+                    class="form-control"
+                    placeholder="Optional, e.g., mappings/city.json"
+                    value="${this.escapeHtml(mappingPath)}"
+                />
+                <small class="form-hint">Saved on the first run and reused after, so new data gets the same numbers</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for vectorise_text step
+   */
+  private renderVectoriseTextForm(stepObj: Record<string, unknown>): string {
+    const columns = (stepObj.columns as string[]) || [];
+    const method = (stepObj.method as string) || 'tfidf';
+    const features = (stepObj.features as number) || 20;
+    const dropOriginal = (stepObj.drop_original as boolean) ?? false;
+    const columnsText = columns.join(', ');
+
+    return `
+            <div class="form-group">
+                <label for="vectorise-columns-input">Text Columns</label>
+                <textarea
+                    id="vectorise-columns-input"
+                    class="form-control"
+                    rows="3"
+                    placeholder="Column names (comma-separated)"
+                >${this.escapeHtml(columnsText)}</textarea>
+            </div>
+            <div class="form-row">
+                <div class="form-group">
+                    <label for="vectorise-method-select">Method</label>
+                    <select id="vectorise-method-select" class="form-control">
+                        <option value="tfidf" ${method === 'tfidf' ? 'selected' : ''}>TF-IDF (most common terms)</option>
+                        <option value="hashing" ${method === 'hashing' ? 'selected' : ''}>Hashing (fixed buckets)</option>
+                    </select>
+                </div>
+                <div class="form-group">
+                    <label for="vectorise-features-input">Features</label>
+                    <input
+                        type="number"
+                        id="vectorise-features-input"
+                        class="form-control"
+                        min="1"
+                        step="1"
+                        value="${features}"
+                    />
+                    <small class="form-hint">Terms kept (TF-IDF) or hash buckets per column</small>
+                </div>
+            </div>
+            <div class="form-group">
+                <label class="form-checkbox">
+                    <input
+                        type="checkbox"
+                        id="vectorise-drop-original"
+                        ${dropOriginal ? 'checked' : ''}
+                    />
+                    Drop original text columns
+                </label>
+            </div>
+        `;
+  }
+
   /**
    * Render form for regex_replace step
    */
@@ -429,95 +680,763 @@ export class StepConfigPanel {
   }
 
   /**
-   * Render validation errors
+   * Render form for map_values step
    */
-  private renderErrors(): string {
-    if (this.state.errors.size === 0) {
-      return '';
-    }
+  private renderMapValuesForm(stepObj: Record<string, unknown>): string {
+    const columns = (stepObj.columns as string[]) || [];
+    const mapping = (stepObj.mapping as Record<string, string>) || {};
+    const columnsText = columns.join(', ');
+    const mappingText = Object.entries(mapping)
+      .map(([from, to]) => `${from} -> ${to}`)
+      .join('\n');
 
-    const errors = Array.from(this.state.errors.values());
     return `
-            <div class="config-errors">
-                ${errors.map(err => `<div class="error-message">${this.escapeHtml(err)}</div>`).join('')}
+            <div class="form-group">
+                <label for="map-values-columns-input">Columns</label>
+                <textarea
+                    id="map-values-columns-input"
+                    class="form-control"
+                    rows="2"
+                    placeholder="Column names (comma-separated)"
+                >${this.escapeHtml(columnsText)}</textarea>
+            </div>
+            <div class="form-group">
+                <label for="map-values-mapping-input">Value Mapping</label>
+                <textarea
+                    id="map-values-mapping-input"
+                    class="form-control"
+                    rows="5"
+                    placeholder="Enter one mapping per line: N.Y. -> New York"
+                >${this.escapeHtml(mappingText)}</textarea>
+                <small class="form-hint">One mapping per line: value -> replacement. Other values are kept.</small>
             </div>
         `;
   }
 
   /**
-   * Escape HTML
+   * Render form for split_column step
    */
-  private escapeHtml(text: string): string {
-    const div = document.createElement('div');
-    div.textContent = text;
-    return div.innerHTML;
+  private renderSplitColumnForm(stepObj: Record<string, unknown>): string {
+    const column = (stepObj.column as string) || '';
+    const delimiter = (stepObj.delimiter as string) ?? ',';
+    const regex = (stepObj.regex as boolean) ?? false;
+    const into = (stepObj.into as string[]) || [];
+    const dropOriginal = (stepObj.drop_original as boolean) ?? false;
+
+    return `
+            <div class="form-group">
+                <label for="split-column-input">Column</label>
+                <input
+                    type="text"
+                    id="split-column-input"
+                    class="form-control"
+                    placeholder="e.g., full_name"
+                    value="${this.escapeHtml(column)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="split-delimiter-input">Delimiter</label>
+                <input
+                    type="text"
+                    id="split-delimiter-input"
+                    class="form-control"
+                    placeholder="e.g., ,"
+                    value="${this.escapeHtml(delimiter)}"
+                />
+                <label class="form-checkbox">
+                    <input type="checkbox" id="split-regex" ${regex ? 'checked' : ''} />
+                    Delimiter is a regular expression
+                </label>
+            </div>
+            <div class="form-group">
+                <label for="split-into-input">New Columns</label>
+                <textarea
+                    id="split-into-input"
+                    class="form-control"
+                    rows="2"
+                    placeholder="Column names (comma-separated), e.g., first, last"
+                >${this.escapeHtml(into.join(', '))}</textarea>
+                <small class="form-hint">Parts are trimmed. Extra parts are dropped; missing parts are empty.</small>
+            </div>
+            <div class="form-group">
+                <label class="form-checkbox">
+                    <input
+                        type="checkbox"
+                        id="split-drop-original"
+                        ${dropOriginal ? 'checked' : ''}
+                    />
+                    Drop the original column
+                </label>
+            </div>
+        `;
   }
 
   /**
-   * Attach event listeners
+   * Render form for merge_columns step
    */
-  private attachEventListeners(): void {
-    if (!this.state.step) return;
-
-    const stepObj = this.state.step as Record<string, unknown>;
-    const stepType = stepObj.op as string;
+  private renderMergeColumnsForm(stepObj: Record<string, unknown>): string {
+    const template = (stepObj.template as string) || '';
+    const into = (stepObj.into as string) || '';
+    const dropSources = (stepObj.drop_sources as boolean) ?? false;
 
-    // Add change listeners based on step type
-    switch (stepType) {
-      case 'drop_columns':
-        this.attachDropColumnsListeners();
-        break;
-      case 'rename_columns':
-        this.attachRenameColumnsListeners();
-        break;
-      case 'trim_whitespace':
-        this.attachTrimWhitespaceListeners();
-        break;
-      case 'cast_types':
-        this.attachCastTypesListeners();
-        break;
-      case 'parse_dates':
-        this.attachParseDatesListeners();
-        break;
-      case 'impute':
-        this.attachImputeListeners();
-        break;
-      case 'normalise_columns':
-        this.attachNormaliseListeners();
-        break;
-      case 'clip_outliers':
-        this.attachClipOutliersListeners();
-        break;
-      case 'one_hot_encode':
-        this.attachOneHotEncodeListeners();
-        break;
-      case 'extract_numbers':
-        this.attachExtractNumbersListeners();
-        break;
-      case 'regex_replace':
-        this.attachRegexReplaceListeners();
-        break;
-    }
+    return `
+            <div class="form-group">
+                <label for="merge-template-input">Template</label>
+                <input
+                    type="text"
+                    id="merge-template-input"
+                    class="form-control"
+                    placeholder="e.g., {first} {last}"
+                    value="${this.escapeHtml(template)}"
+                />
+                <small class="form-hint">Column names in braces are replaced by their values. Use {{ and }} for literal braces.</small>
+            </div>
+            <div class="form-group">
+                <label for="merge-into-input">New Column Name</label>
+                <input
+                    type="text"
+                    id="merge-into-input"
+                    class="form-control"
+                    placeholder="e.g., full_name"
+                    value="${this.escapeHtml(into)}"
+                />
+            </div>
+            <div class="form-group">
+                <label class="form-checkbox">
+                    <input
+                        type="checkbox"
+                        id="merge-drop-sources"
+                        ${dropSources ? 'checked' : ''}
+                    />
+                    Drop the merged columns
+                </label>
+            </div>
+        `;
   }
 
-  // Individual attachment methods for each step type
-  private attachDropColumnsListeners(): void {
-    const input = this.container.querySelector<HTMLTextAreaElement>('#drop-columns-input');
-    input?.addEventListener('blur', () => {
-      const columnsText = input.value.trim();
-      const columns = columnsText
-        ? columnsText
-            .split(',')
-            .map(c => c.trim())
-            .filter(c => c)
-        : [];
-      this.updateStep({ columns });
-    });
-  }
+  /**
+   * Render form for extract_date_parts step
+   */
+  private renderExtractDatePartsForm(stepObj: Record<string, unknown>): string {
+    const column = (stepObj.column as string) || '';
+    const parts = (stepObj.parts as string[]) || [];
 
-  private attachRenameColumnsListeners(): void {
-    const input = this.container.querySelector<HTMLTextAreaElement>('#rename-mapping-input');
-    input?.addEventListener('blur', () => {
+    return `
+            <div class="form-group">
+                <label for="date-parts-column-input">Column</label>
+                <input
+                    type="text"
+                    id="date-parts-column-input"
+                    class="form-control"
+                    placeholder="e.g., order_date"
+                    value="${this.escapeHtml(column)}"
+                />
+                <small class="form-hint">Must be a date or time column; text needs Parse Dates first.</small>
+            </div>
+            <div class="form-group">
+                <label>Parts</label>
+                ${DATE_PART_OPTIONS.map(
+                  opt => `
+                <label class="form-checkbox">
+                    <input
+                        type="checkbox"
+                        class="date-part-checkbox"
+                        value="${opt.value}"
+                        ${parts.includes(opt.value) ? 'checked' : ''}
+                    />
+                    ${opt.label}
+                </label>`
+                ).join('')}
+                <small class="form-hint">Each part is added as a column named column_part, e.g. order_date_month.</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for with_column step
+   */
+  private renderWithColumnForm(stepObj: Record<string, unknown>): string {
+    const name = (stepObj.name as string) || '';
+    const expression = (stepObj.expression as string) || '';
+
+    return `
+            <div class="form-group">
+                <label for="with-column-name-input">New Column Name</label>
+                <input
+                    type="text"
+                    id="with-column-name-input"
+                    class="form-control"
+                    placeholder="e.g., order_total"
+                    value="${this.escapeHtml(name)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="with-column-expression-input">Expression</label>
+                <textarea
+                    id="with-column-expression-input"
+                    class="form-control"
+                    rows="3"
+                    placeholder="e.g., price * quantity"
+                >${this.escapeHtml(expression)}</textarea>
+                <small class="form-hint">
+                    Supports + - * / %, &amp; (join text), comparisons, and/or/not,
+                    if(cond, a, b), date_diff(end, start, "days"), abs, round, coalesce.
+                    Quote names with spaces using backticks.
+                </small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for aggregate step
+   */
+  private renderAggregateForm(stepObj: Record<string, unknown>): string {
+    const groupBy = (stepObj.group_by as string[]) || [];
+    const aggregations =
+      (stepObj.aggregations as { column: string; function: string; alias?: string | null }[]) ||
+      [];
+    const aggregationsText = aggregations
+      .map(a => `${a.column}: ${a.function}${a.alias ? ` -> ${a.alias}` : ''}`)
+      .join('\n');
+
+    return `
+            <div class="form-group">
+                <label for="aggregate-group-input">Group By</label>
+                <textarea
+                    id="aggregate-group-input"
+                    class="form-control"
+                    rows="2"
+                    placeholder="Column names (comma-separated), or empty for a single total row"
+                >${this.escapeHtml(groupBy.join(', '))}</textarea>
+            </div>
+            <div class="form-group">
+                <label for="aggregate-aggs-input">Aggregations</label>
+                <textarea
+                    id="aggregate-aggs-input"
+                    class="form-control"
+                    rows="4"
+                    placeholder="One per line: column: function -> alias"
+                >${this.escapeHtml(aggregationsText)}</textarea>
+                <small class="form-hint">Functions: sum, mean, min, max, count, n_unique. Alias is optional.</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for concat/union steps
+   */
+  private renderStackInputsForm(stepObj: Record<string, unknown>): string {
+    const inputs = (stepObj.inputs as string[]) || [];
+
+    return `
+            <div class="form-group">
+                <label for="stack-inputs-input">Inputs to Stack</label>
+                <input
+                    type="text"
+                    id="stack-inputs-input"
+                    class="form-control"
+                    placeholder="Input names from the pipeline's sources (comma-separated)"
+                    value="${this.escapeHtml(inputs.join(', '))}"
+                />
+                <small class="form-hint">Each input must have the same columns as the current data</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for sort step
+   */
+  private renderSortForm(stepObj: Record<string, unknown>): string {
+    const by = (stepObj.by as string[]) || [];
+    const descending = (stepObj.descending as boolean[]) || [];
+    const sortText = by
+      .map((col, i) => `${col} -> ${(descending[i] ?? descending[0]) ? 'desc' : 'asc'}`)
+      .join('\n');
+
+    return `
+            <div class="form-group">
+                <label for="sort-by-input">Sort Columns</label>
+                <textarea
+                    id="sort-by-input"
+                    class="form-control"
+                    rows="3"
+                    placeholder="One per line: column_name -> asc|desc"
+                >${this.escapeHtml(sortText)}</textarea>
+                <small class="form-hint">Earlier lines take priority. Direction defaults to asc.</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for limit step
+   */
+  private renderLimitForm(stepObj: Record<string, unknown>): string {
+    const n = (stepObj.n as number) ?? 1000;
+
+    return `
+            <div class="form-group">
+                <label for="limit-n-input">Number of Rows</label>
+                <input type="number" id="limit-n-input" class="form-control" min="0" step="1" value="${n}" />
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for sample step
+   */
+  private renderSampleForm(stepObj: Record<string, unknown>): string {
+    const fraction = stepObj.fraction as number | null | undefined;
+    const n = stepObj.n as number | null | undefined;
+    const seed = (stepObj.seed as number) ?? 42;
+
+    return `
+            <div class="form-group">
+                <label for="sample-fraction-input">Fraction of Rows</label>
+                <input type="number" id="sample-fraction-input" class="form-control" min="0" max="1" step="0.01" value="${fraction ?? ''}" />
+            </div>
+            <div class="form-group">
+                <label for="sample-n-input">Or Number of Rows</label>
+                <input type="number" id="sample-n-input" class="form-control" min="0" step="1" value="${n ?? ''}" />
+                <small class="form-hint">Set either a fraction or a row count, not both</small>
+            </div>
+            <div class="form-group">
+                <label for="sample-seed-input">Seed</label>
+                <input type="number" id="sample-seed-input" class="form-control" min="0" step="1" value="${seed}" />
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for pivot step
+   */
+  private renderPivotForm(stepObj: Record<string, unknown>): string {
+    const index = (stepObj.index as string[]) || [];
+    const columns = (stepObj.columns as string) || '';
+    const values = (stepObj.values as string) || '';
+    const agg = (stepObj.agg as string) || 'sum';
+    const functions = ['sum', 'mean', 'min', 'max', 'count', 'n_unique'];
+
+    return `
+            <div class="form-group">
+                <label for="pivot-index-input">Index Columns</label>
+                <input
+                    type="text"
+                    id="pivot-index-input"
+                    class="form-control"
+                    placeholder="Columns that identify a row (comma-separated)"
+                    value="${this.escapeHtml(index.join(', '))}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="pivot-columns-input">Column Names From</label>
+                <input
+                    type="text"
+                    id="pivot-columns-input"
+                    class="form-control"
+                    placeholder="e.g., month"
+                    value="${this.escapeHtml(columns)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="pivot-values-input">Values From</label>
+                <input
+                    type="text"
+                    id="pivot-values-input"
+                    class="form-control"
+                    placeholder="e.g., sales"
+                    value="${this.escapeHtml(values)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="pivot-agg-select">Aggregation</label>
+                <select id="pivot-agg-select" class="form-control">
+                    ${functions.map(f => `<option value="${f}" ${agg === f ? 'selected' : ''}>${f}</option>`).join('')}
+                </select>
+                <small class="form-hint">Used when several rows share the same index and column value</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for unpivot step
+   */
+  private renderUnpivotForm(stepObj: Record<string, unknown>): string {
+    const idVars = (stepObj.id_vars as string[]) || [];
+    const valueVars = (stepObj.value_vars as string[]) || [];
+
+    return `
+            <div class="form-group">
+                <label for="unpivot-id-input">ID Columns</label>
+                <input
+                    type="text"
+                    id="unpivot-id-input"
+                    class="form-control"
+                    placeholder="Columns to keep as-is (comma-separated)"
+                    value="${this.escapeHtml(idVars.join(', '))}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="unpivot-value-input">Value Columns</label>
+                <textarea
+                    id="unpivot-value-input"
+                    class="form-control"
+                    rows="3"
+                    placeholder="Columns to stack (comma-separated), or leave empty for all others"
+                >${this.escapeHtml(valueVars.join(', '))}</textarea>
+                <small class="form-hint">Produces "variable" and "value" columns</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for transpose step
+   */
+  private renderTransposeForm(stepObj: Record<string, unknown>): string {
+    const headerColumn = (stepObj.header_column as string | null) ?? '';
+    const namesColumn = (stepObj.names_column as string) || 'field';
+
+    return `
+            <div class="form-group">
+                <label for="transpose-header-input">Header Column</label>
+                <input
+                    type="text"
+                    id="transpose-header-input"
+                    class="form-control"
+                    placeholder="Column whose values become the new column names"
+                    value="${this.escapeHtml(headerColumn)}"
+                />
+                <small class="form-hint">Leave empty to name the new columns column_1, column_2, ...</small>
+            </div>
+            <div class="form-group">
+                <label for="transpose-names-input">Names Column</label>
+                <input
+                    type="text"
+                    id="transpose-names-input"
+                    class="form-control"
+                    placeholder="field"
+                    value="${this.escapeHtml(namesColumn)}"
+                />
+                <small class="form-hint">Holds the old column names. At most 500 rows can be transposed.</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for expect_schema step
+   */
+  private renderExpectSchemaForm(stepObj: Record<string, unknown>): string {
+    const columns =
+      (stepObj.columns as { name: string; dtype?: string | null; nullable?: boolean }[]) || [];
+    const onViolation = (stepObj.on_violation as string) || 'fail';
+    const columnsText = columns
+      .map(
+        c =>
+          `${c.name}${c.dtype ? `: ${c.dtype}` : ''}${c.nullable === false ? ' not null' : ''}`
+      )
+      .join('\n');
+
+    return `
+            <div class="form-group">
+                <label for="expect-schema-columns-input">Expected Columns</label>
+                <textarea
+                    id="expect-schema-columns-input"
+                    class="form-control"
+                    rows="5"
+                    placeholder="One per line: name: type, e.g.\norder_id: Integer not null\nemail: Text\norder_date: Temporal"
+                >${this.escapeHtml(columnsText)}</textarea>
+                <small class="form-hint">Types: Numeric, Integer, Float, Text, Categorical, Boolean, Temporal, Date. Leave the type out to accept any. Add "not null" to forbid nulls.</small>
+            </div>
+            <div class="form-group">
+                <label for="expect-schema-violation-select">On Violation</label>
+                <select id="expect-schema-violation-select" class="form-control">
+                    <option value="fail" ${onViolation === 'fail' ? 'selected' : ''}>Fail the run with a report</option>
+                    <option value="warn" ${onViolation === 'warn' ? 'selected' : ''}>Warn and continue</option>
+                </select>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for completeness_score step
+   */
+  private renderCompletenessScoreForm(stepObj: Record<string, unknown>): string {
+    const name = (stepObj.name as string) || '';
+    const weights = (stepObj.weights as Record<string, number>) || {};
+    const weightsText = Object.entries(weights)
+      .map(([col, weight]) => `${col} -> ${weight}`)
+      .join('\n');
+
+    return `
+            <div class="form-group">
+                <label for="completeness-name-input">Score Column Name</label>
+                <input
+                    type="text"
+                    id="completeness-name-input"
+                    class="form-control"
+                    placeholder="row_completeness"
+                    value="${this.escapeHtml(name)}"
+                />
+            </div>
+            <div class="form-group">
+                <label for="completeness-weights-input">Column Weights (optional)</label>
+                <textarea
+                    id="completeness-weights-input"
+                    class="form-control"
+                    rows="4"
+                    placeholder="One per line: column_name -> weight"
+                >${this.escapeHtml(weightsText)}</textarea>
+                <small class="form-hint">Unlisted columns count as 1. Use 0 to ignore a column.</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for write_output step
+   */
+  private renderWriteOutputForm(stepObj: Record<string, unknown>): string {
+    const pathTemplate = (stepObj.path_template as string) || '';
+    const format = (stepObj.format as string | null) ?? '';
+
+    return `
+            <div class="form-group">
+                <label for="write-output-path-input">Output Path</label>
+                <input
+                    type="text"
+                    id="write-output-path-input"
+                    class="form-control"
+                    placeholder="e.g. output/detail_{date}.parquet"
+                    value="${this.escapeHtml(pathTemplate)}"
+                />
+                <small class="form-hint">Supports {date}. The pipeline continues with the same data.</small>
+            </div>
+            <div class="form-group">
+                <label for="write-output-format-select">Format</label>
+                <select id="write-output-format-select" class="form-control">
+                    <option value="" ${format === '' ? 'selected' : ''}>Same as pipeline output</option>
+                    <option value="parquet" ${format === 'parquet' ? 'selected' : ''}>Parquet</option>
+                    <option value="csv" ${format === 'csv' ? 'selected' : ''}>CSV</option>
+                    <option value="json" ${format === 'json' ? 'selected' : ''}>JSON</option>
+                </select>
+            </div>
+        `;
+  }
+
+  /**
+   * Render form for external step
+   */
+  private renderExternalForm(stepObj: Record<string, unknown>): string {
+    const command = (stepObj.command as string) || '';
+    const args = (stepObj.args as string[]) || [];
+    const format = (stepObj.data_format as string) || 'parquet';
+
+    return `
+            <div class="form-group">
+                <label for="external-command-input">Command</label>
+                <input
+                    type="text"
+                    id="external-command-input"
+                    class="form-control"
+                    placeholder="Full path to the executable"
+                    value="${this.escapeHtml(command)}"
+                />
+                <small class="form-hint">Must be inside a trusted folder (Settings)</small>
+            </div>
+            <div class="form-group">
+                <label for="external-args-input">Arguments</label>
+                <textarea
+                    id="external-args-input"
+                    class="form-control"
+                    rows="3"
+                    placeholder="One per line, e.g. {input} and {output}"
+                >${this.escapeHtml(args.join('\n'))}</textarea>
+                <small class="form-hint">{input} and {output} are replaced with temporary file paths</small>
+            </div>
+            <div class="form-group">
+                <label for="external-format-select">Data Exchange</label>
+                <select id="external-format-select" class="form-control">
+                    <option value="parquet" ${format === 'parquet' ? 'selected' : ''}>Parquet files</option>
+                    <option value="csv" ${format === 'csv' ? 'selected' : ''}>CSV files</option>
+                    <option value="stdin" ${format === 'stdin' ? 'selected' : ''}>CSV via stdin/stdout</option>
+                </select>
+            </div>
+        `;
+  }
+
+  /**
+   * Render the on_error policy select shared by every step
+   */
+  private renderErrorPolicyField(stepObj: Record<string, unknown>): string {
+    const policy = (stepObj.on_error as string) || 'skip';
+
+    return `
+            <div class="form-group">
+                <label for="step-on-error-select">If This Step Fails</label>
+                <select id="step-on-error-select" class="form-control">
+                    <option value="skip" ${policy === 'skip' ? 'selected' : ''}>Skip step and warn</option>
+                    <option value="fail" ${policy === 'fail' ? 'selected' : ''}>Stop the pipeline</option>
+                    <option value="quarantine" ${policy === 'quarantine' ? 'selected' : ''}>Move failing rows to reject file</option>
+                </select>
+                <small class="form-hint">Quarantine applies to type casts and date parsing</small>
+            </div>
+        `;
+  }
+
+  /**
+   * Render validation errors
+   */
+  private renderErrors(): string {
+    if (this.state.errors.size === 0) {
+      return '';
+    }
+
+    const errors = Array.from(this.state.errors.values());
+    return `
+            <div class="config-errors">
+                ${errors.map(err => `<div class="error-message">${this.escapeHtml(err)}</div>`).join('')}
+            </div>
+        `;
+  }
+
+  /**
+   * Escape HTML
+   */
+  private escapeHtml(text: string): string {
+    const div = document.createElement('div');
+    div.textContent = text;
+    return div.innerHTML;
+  }
+
+  /**
+   * Attach event listeners
+   */
+  private attachEventListeners(): void {
+    if (!this.state.step) return;
+
+    const stepObj = this.state.step as Record<string, unknown>;
+    const stepType = stepObj.op as string;
+
+    const policySelect = this.container.querySelector<HTMLSelectElement>('#step-on-error-select');
+    policySelect?.addEventListener('change', () => {
+      this.updateStep({ on_error: policySelect.value });
+    });
+
+    // Add change listeners based on step type
+    switch (stepType) {
+      case 'drop_columns':
+        this.attachDropColumnsListeners();
+        break;
+      case 'rename_columns':
+        this.attachRenameColumnsListeners();
+        break;
+      case 'trim_whitespace':
+        this.attachTrimWhitespaceListeners();
+        break;
+      case 'cast_types':
+        this.attachCastTypesListeners();
+        break;
+      case 'parse_dates':
+        this.attachParseDatesListeners();
+        break;
+      case 'impute':
+        this.attachImputeListeners();
+        break;
+      case 'normalise_columns':
+        this.attachNormaliseListeners();
+        break;
+      case 'clip_outliers':
+        this.attachClipOutliersListeners();
+        break;
+      case 'one_hot_encode':
+        this.attachOneHotEncodeListeners();
+        break;
+      case 'extract_numbers':
+        this.attachExtractNumbersListeners();
+        break;
+      case 'bin':
+        this.attachBinListeners();
+        break;
+      case 'encode_categories':
+        this.attachEncodeCategoriesListeners();
+        break;
+      case 'vectorise_text':
+        this.attachVectoriseTextListeners();
+        break;
+      case 'regex_replace':
+        this.attachRegexReplaceListeners();
+        break;
+      case 'map_values':
+        this.attachMapValuesListeners();
+        break;
+      case 'split_column':
+        this.attachSplitColumnListeners();
+        break;
+      case 'merge_columns':
+        this.attachMergeColumnsListeners();
+        break;
+      case 'extract_date_parts':
+        this.attachExtractDatePartsListeners();
+        break;
+      case 'with_column':
+        this.attachWithColumnListeners();
+        break;
+      case 'aggregate':
+        this.attachAggregateListeners();
+        break;
+      case 'concat':
+      case 'union':
+        this.attachStackInputsListeners();
+        break;
+      case 'sort':
+        this.attachSortListeners();
+        break;
+      case 'limit':
+        this.attachLimitListeners();
+        break;
+      case 'sample':
+        this.attachSampleListeners();
+        break;
+      case 'pivot':
+        this.attachPivotListeners();
+        break;
+      case 'unpivot':
+        this.attachUnpivotListeners();
+        break;
+      case 'transpose':
+        this.attachTransposeListeners();
+        break;
+      case 'expect_schema':
+        this.attachExpectSchemaListeners();
+        break;
+      case 'completeness_score':
+        this.attachCompletenessScoreListeners();
+        break;
+      case 'write_output':
+        this.attachWriteOutputListeners();
+        break;
+      case 'external':
+        this.attachExternalListeners();
+        break;
+    }
+  }
+
+  // Individual attachment methods for each step type
+  private attachDropColumnsListeners(): void {
+    const input = this.container.querySelector<HTMLTextAreaElement>('#drop-columns-input');
+    input?.addEventListener('blur', () => {
+      const columnsText = input.value.trim();
+      const columns = columnsText
+        ? columnsText
+            .split(',')
+            .map(c => c.trim())
+            .filter(c => c)
+        : [];
+      this.updateStep({ columns });
+    });
+  }
+
+  private attachRenameColumnsListeners(): void {
+    const input = this.container.querySelector<HTMLTextAreaElement>('#rename-mapping-input');
+    input?.addEventListener('blur', () => {
       const mappingText = input.value.trim();
       const mapping: Record<string, string> = {};
       mappingText.split('\n').forEach(line => {
@@ -678,6 +1597,113 @@ export class StepConfigPanel {
     });
   }
 
+  private attachBinListeners(): void {
+    const columnInput = this.container.querySelector<HTMLInputElement>('#bin-column-input');
+    const strategySelect = this.container.querySelector<HTMLSelectElement>('#bin-strategy-select');
+    const countInput = this.container.querySelector<HTMLInputElement>('#bin-count-input');
+    const edgesInput = this.container.querySelector<HTMLInputElement>('#bin-edges-input');
+    const labelsInput = this.container.querySelector<HTMLInputElement>('#bin-labels-input');
+
+    const list = (text: string): string[] =>
+      text
+        .split(',')
+        .map(v => v.trim())
+        .filter(v => v);
+    const updateStrategy = (): void => {
+      const kind = strategySelect?.value ?? 'equal_width';
+      const strategy =
+        kind === 'custom'
+          ? {
+              kind,
+              edges: list(edgesInput?.value ?? '')
+                .map(Number)
+                .filter(v => Number.isFinite(v)),
+            }
+          : { kind, bins: parseInt(countInput?.value ?? '', 10) || 0 };
+      this.updateStep({ strategy });
+    };
+
+    columnInput?.addEventListener('blur', () => {
+      this.updateStep({ column: columnInput.value.trim() });
+    });
+    strategySelect?.addEventListener('change', updateStrategy);
+    countInput?.addEventListener('change', updateStrategy);
+    edgesInput?.addEventListener('blur', updateStrategy);
+    labelsInput?.addEventListener('blur', () => {
+      this.updateStep({ labels: list(labelsInput.value) });
+    });
+  }
+
+  private attachEncodeCategoriesListeners(): void {
+    const columnInput = this.container.querySelector<HTMLInputElement>('#encode-column-input');
+    const methodSelect = this.container.querySelector<HTMLSelectElement>('#encode-method-select');
+    const targetInput = this.container.querySelector<HTMLInputElement>('#encode-target-input');
+    const smoothingInput =
+      this.container.querySelector<HTMLInputElement>('#encode-smoothing-input');
+    const foldsInput = this.container.querySelector<HTMLInputElement>('#encode-folds-input');
+    const mappingInput = this.container.querySelector<HTMLInputElement>('#encode-mapping-input');
+
+    const updateMethod = (): void => {
+      const method: EncodeMethodFields =
+        methodSelect?.value === 'target'
+          ? {
+              kind: 'target',
+              target: targetInput?.value.trim() ?? '',
+              smoothing: parseFloat(smoothingInput?.value ?? '') || 0,
+              folds: parseInt(foldsInput?.value ?? '', 10) || 0,
+            }
+          : { kind: 'ordinal' };
+      this.updateStep({ encoding: { method } });
+    };
+
+    columnInput?.addEventListener('blur', () => {
+      this.updateStep({ column: columnInput.value.trim() });
+    });
+    methodSelect?.addEventListener('change', updateMethod);
+    targetInput?.addEventListener('blur', updateMethod);
+    smoothingInput?.addEventListener('change', updateMethod);
+    foldsInput?.addEventListener('change', updateMethod);
+    mappingInput?.addEventListener('blur', () => {
+      this.updateStep({ mapping_path: mappingInput.value.trim() || null });
+    });
+  }
+
+  private attachVectoriseTextListeners(): void {
+    const columnsInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#vectorise-columns-input'
+    );
+    const methodSelect = this.container.querySelector<HTMLSelectElement>(
+      '#vectorise-method-select'
+    );
+    const featuresInput = this.container.querySelector<HTMLInputElement>(
+      '#vectorise-features-input'
+    );
+    const dropCheckbox = this.container.querySelector<HTMLInputElement>('#vectorise-drop-original');
+
+    columnsInput?.addEventListener('blur', () => {
+      const columnsText = columnsInput.value.trim();
+      const columns = columnsText
+        ? columnsText
+            .split(',')
+            .map(c => c.trim())
+            .filter(c => c)
+        : [];
+      this.updateStep({ columns });
+    });
+
+    methodSelect?.addEventListener('change', () => {
+      this.updateStep({ method: methodSelect.value });
+    });
+
+    featuresInput?.addEventListener('change', () => {
+      this.updateStep({ features: parseInt(featuresInput.value, 10) });
+    });
+
+    dropCheckbox?.addEventListener('change', () => {
+      this.updateStep({ drop_original: dropCheckbox.checked });
+    });
+  }
+
   private attachRegexReplaceListeners(): void {
     const columnsInput = this.container.querySelector<HTMLTextAreaElement>('#regex-columns-input');
     const patternInput = this.container.querySelector<HTMLInputElement>('#regex-pattern-input');
@@ -705,6 +1731,349 @@ export class StepConfigPanel {
     });
   }
 
+  private attachMapValuesListeners(): void {
+    const columnsInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#map-values-columns-input'
+    );
+    const mappingInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#map-values-mapping-input'
+    );
+
+    columnsInput?.addEventListener('blur', () => {
+      const columnsText = columnsInput.value.trim();
+      const columns = columnsText
+        ? columnsText
+            .split(',')
+            .map(c => c.trim())
+            .filter(c => c)
+        : [];
+      this.updateStep({ columns });
+    });
+
+    mappingInput?.addEventListener('blur', () => {
+      const mapping: Record<string, string> = {};
+      mappingInput.value.split('\n').forEach(line => {
+        const parts = line.split('->').map(p => p.trim());
+        if (parts.length === 2 && parts[0] && parts[1] !== undefined) {
+          mapping[parts[0]] = parts[1];
+        }
+      });
+      this.updateStep({ mapping });
+    });
+  }
+
+  private attachSplitColumnListeners(): void {
+    const columnInput = this.container.querySelector<HTMLInputElement>('#split-column-input');
+    const delimiterInput = this.container.querySelector<HTMLInputElement>('#split-delimiter-input');
+    const regexCheckbox = this.container.querySelector<HTMLInputElement>('#split-regex');
+    const intoInput = this.container.querySelector<HTMLTextAreaElement>('#split-into-input');
+    const dropCheckbox = this.container.querySelector<HTMLInputElement>('#split-drop-original');
+
+    columnInput?.addEventListener('blur', () => {
+      this.updateStep({ column: columnInput.value.trim() });
+    });
+
+    delimiterInput?.addEventListener('blur', () => {
+      this.updateStep({ delimiter: delimiterInput.value });
+    });
+
+    regexCheckbox?.addEventListener('change', () => {
+      this.updateStep({ regex: regexCheckbox.checked });
+    });
+
+    intoInput?.addEventListener('blur', () => {
+      const into = intoInput.value
+        .split(',')
+        .map(c => c.trim())
+        .filter(c => c);
+      this.updateStep({ into });
+    });
+
+    dropCheckbox?.addEventListener('change', () => {
+      this.updateStep({ drop_original: dropCheckbox.checked });
+    });
+  }
+
+  private attachMergeColumnsListeners(): void {
+    const templateInput = this.container.querySelector<HTMLInputElement>('#merge-template-input');
+    const intoInput = this.container.querySelector<HTMLInputElement>('#merge-into-input');
+    const dropCheckbox = this.container.querySelector<HTMLInputElement>('#merge-drop-sources');
+
+    templateInput?.addEventListener('blur', () => {
+      this.updateStep({ template: templateInput.value });
+    });
+
+    intoInput?.addEventListener('blur', () => {
+      this.updateStep({ into: intoInput.value.trim() });
+    });
+
+    dropCheckbox?.addEventListener('change', () => {
+      this.updateStep({ drop_sources: dropCheckbox.checked });
+    });
+  }
+
+  private attachExtractDatePartsListeners(): void {
+    const columnInput = this.container.querySelector<HTMLInputElement>('#date-parts-column-input');
+    const checkboxes = this.container.querySelectorAll<HTMLInputElement>('.date-part-checkbox');
+
+    columnInput?.addEventListener('blur', () => {
+      this.updateStep({ column: columnInput.value.trim() });
+    });
+
+    checkboxes.forEach(checkbox => {
+      checkbox.addEventListener('change', () => {
+        const parts = Array.from(checkboxes)
+          .filter(c => c.checked)
+          .map(c => c.value);
+        this.updateStep({ parts });
+      });
+    });
+  }
+
+  private attachWithColumnListeners(): void {
+    const nameInput = this.container.querySelector<HTMLInputElement>('#with-column-name-input');
+    const expressionInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#with-column-expression-input'
+    );
+
+    nameInput?.addEventListener('blur', () => {
+      this.updateStep({ name: nameInput.value.trim() });
+    });
+
+    expressionInput?.addEventListener('blur', () => {
+      this.updateStep({ expression: expressionInput.value.trim() });
+    });
+  }
+
+  private attachAggregateListeners(): void {
+    const groupInput = this.container.querySelector<HTMLTextAreaElement>('#aggregate-group-input');
+    const aggsInput = this.container.querySelector<HTMLTextAreaElement>('#aggregate-aggs-input');
+
+    groupInput?.addEventListener('blur', () => {
+      const group_by = groupInput.value
+        .split(',')
+        .map(c => c.trim())
+        .filter(c => c);
+      this.updateStep({ group_by });
+    });
+
+    aggsInput?.addEventListener('blur', () => {
+      const aggregations = aggsInput.value
+        .split('\n')
+        .map(line => {
+          const [spec = '', alias] = line.split('->').map(p => p.trim());
+          const [column = '', fn = ''] = spec.split(':').map(p => p.trim());
+          return { column, function: fn, alias: alias || null };
+        })
+        .filter(a => a.column && a.function);
+      this.updateStep({ aggregations });
+    });
+  }
+
+  private attachStackInputsListeners(): void {
+    const inputsInput = this.container.querySelector<HTMLInputElement>('#stack-inputs-input');
+
+    inputsInput?.addEventListener('blur', () => {
+      const inputs = inputsInput.value
+        .split(',')
+        .map(c => c.trim())
+        .filter(c => c);
+      this.updateStep({ inputs });
+    });
+  }
+
+  private attachSortListeners(): void {
+    const sortInput = this.container.querySelector<HTMLTextAreaElement>('#sort-by-input');
+
+    sortInput?.addEventListener('blur', () => {
+      const by: string[] = [];
+      const descending: boolean[] = [];
+      sortInput.value.split('\n').forEach(line => {
+        const [col, direction] = line.split('->').map(s => s.trim());
+        if (col) {
+          by.push(col);
+          descending.push(direction?.toLowerCase() === 'desc');
+        }
+      });
+      this.updateStep({ by, descending });
+    });
+  }
+
+  private attachLimitListeners(): void {
+    const nInput = this.container.querySelector<HTMLInputElement>('#limit-n-input');
+
+    nInput?.addEventListener('change', () => {
+      this.updateStep({ n: Math.max(0, Math.floor(Number(nInput.value) || 0)) });
+    });
+  }
+
+  private attachSampleListeners(): void {
+    const fractionInput = this.container.querySelector<HTMLInputElement>('#sample-fraction-input');
+    const nInput = this.container.querySelector<HTMLInputElement>('#sample-n-input');
+    const seedInput = this.container.querySelector<HTMLInputElement>('#sample-seed-input');
+
+    fractionInput?.addEventListener('change', () => {
+      const value = fractionInput.value.trim();
+      this.updateStep({ fraction: value === '' ? null : Number(value) });
+    });
+
+    nInput?.addEventListener('change', () => {
+      const value = nInput.value.trim();
+      this.updateStep({ n: value === '' ? null : Math.floor(Number(value)) });
+    });
+
+    seedInput?.addEventListener('change', () => {
+      this.updateStep({ seed: Math.max(0, Math.floor(Number(seedInput.value) || 0)) });
+    });
+  }
+
+  private attachPivotListeners(): void {
+    const indexInput = this.container.querySelector<HTMLInputElement>('#pivot-index-input');
+    const columnsInput = this.container.querySelector<HTMLInputElement>('#pivot-columns-input');
+    const valuesInput = this.container.querySelector<HTMLInputElement>('#pivot-values-input');
+    const aggSelect = this.container.querySelector<HTMLSelectElement>('#pivot-agg-select');
+
+    indexInput?.addEventListener('blur', () => {
+      const index = indexInput.value
+        .split(',')
+        .map(c => c.trim())
+        .filter(c => c);
+      this.updateStep({ index });
+    });
+
+    columnsInput?.addEventListener('blur', () => {
+      this.updateStep({ columns: columnsInput.value.trim() });
+    });
+
+    valuesInput?.addEventListener('blur', () => {
+      this.updateStep({ values: valuesInput.value.trim() });
+    });
+
+    aggSelect?.addEventListener('change', () => {
+      this.updateStep({ agg: aggSelect.value });
+    });
+  }
+
+  private attachUnpivotListeners(): void {
+    const idInput = this.container.querySelector<HTMLInputElement>('#unpivot-id-input');
+    const valueInput = this.container.querySelector<HTMLTextAreaElement>('#unpivot-value-input');
+
+    const parse = (value: string): string[] =>
+      value
+        .split(',')
+        .map(c => c.trim())
+        .filter(c => c);
+
+    idInput?.addEventListener('blur', () => {
+      this.updateStep({ id_vars: parse(idInput.value) });
+    });
+
+    valueInput?.addEventListener('blur', () => {
+      this.updateStep({ value_vars: parse(valueInput.value) });
+    });
+  }
+
+  private attachTransposeListeners(): void {
+    const headerInput = this.container.querySelector<HTMLInputElement>('#transpose-header-input');
+    const namesInput = this.container.querySelector<HTMLInputElement>('#transpose-names-input');
+
+    headerInput?.addEventListener('blur', () => {
+      this.updateStep({ header_column: headerInput.value.trim() || null });
+    });
+
+    namesInput?.addEventListener('blur', () => {
+      this.updateStep({ names_column: namesInput.value.trim() || 'field' });
+    });
+  }
+
+  private attachExpectSchemaListeners(): void {
+    const columnsInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#expect-schema-columns-input'
+    );
+    const violationSelect = this.container.querySelector<HTMLSelectElement>(
+      '#expect-schema-violation-select'
+    );
+
+    columnsInput?.addEventListener('blur', () => {
+      const columns = columnsInput.value
+        .split('\n')
+        .map(line => line.trim())
+        .filter(line => line)
+        .map(line => {
+          const notNull = /\s+not\s+null$/i.test(line);
+          const [name, dtype] = line
+            .replace(/\s+not\s+null$/i, '')
+            .split(':')
+            .map(s => s.trim());
+          return { name: name ?? '', dtype: dtype || null, nullable: !notNull };
+        });
+      this.updateStep({ columns });
+    });
+    violationSelect?.addEventListener('change', () => {
+      this.updateStep({ on_violation: violationSelect.value });
+    });
+  }
+
+  private attachCompletenessScoreListeners(): void {
+    const nameInput = this.container.querySelector<HTMLInputElement>('#completeness-name-input');
+    const weightsInput = this.container.querySelector<HTMLTextAreaElement>(
+      '#completeness-weights-input'
+    );
+
+    nameInput?.addEventListener('blur', () => {
+      this.updateStep({ name: nameInput.value.trim() });
+    });
+
+    weightsInput?.addEventListener('blur', () => {
+      const weights: Record<string, number> = {};
+      weightsInput.value.split('\n').forEach(line => {
+        const [col, weight] = line.split('->').map(s => s.trim());
+        const value = Number(weight);
+        if (col && weight && Number.isFinite(value)) {
+          weights[col] = value;
+        }
+      });
+      this.updateStep({ weights });
+    });
+  }
+
+  private attachWriteOutputListeners(): void {
+    const pathInput = this.container.querySelector<HTMLInputElement>('#write-output-path-input');
+    const formatSelect = this.container.querySelector<HTMLSelectElement>(
+      '#write-output-format-select'
+    );
+
+    pathInput?.addEventListener('blur', () => {
+      this.updateStep({ path_template: pathInput.value.trim() });
+    });
+
+    formatSelect?.addEventListener('change', () => {
+      this.updateStep({ format: formatSelect.value || null });
+    });
+  }
+
+  private attachExternalListeners(): void {
+    const commandInput = this.container.querySelector<HTMLInputElement>('#external-command-input');
+    const argsInput = this.container.querySelector<HTMLTextAreaElement>('#external-args-input');
+    const formatSelect = this.container.querySelector<HTMLSelectElement>('#external-format-select');
+
+    commandInput?.addEventListener('blur', () => {
+      this.updateStep({ command: commandInput.value.trim() });
+    });
+
+    argsInput?.addEventListener('blur', () => {
+      const args = argsInput.value
+        .split('\n')
+        .map(a => a.trim())
+        .filter(a => a);
+      this.updateStep({ args });
+    });
+
+    formatSelect?.addEventListener('change', () => {
+      this.updateStep({ data_format: formatSelect.value });
+    });
+  }
+
   /**
    * Update step with new values
    */
@@ -744,6 +2113,42 @@ export class StepConfigPanel {
           this.state.errors.set('columns', 'At least one column is required');
         }
         break;
+      case 'bin': {
+        if (!stepObj.column || (stepObj.column as string).trim() === '') {
+          this.state.errors.set('column', 'Column to bin is required');
+        }
+        const strategy = stepObj.strategy as { kind: string; bins?: number; edges?: number[] };
+        if (strategy?.kind === 'custom') {
+          if ((strategy.edges ?? []).length < 2) {
+            this.state.errors.set('strategy', 'Give at least two edges');
+          }
+        } else if (!strategy?.bins || strategy.bins < 1) {
+          this.state.errors.set('strategy', 'At least one bin is required');
+        }
+        break;
+      }
+      case 'encode_categories': {
+        if (!stepObj.column || (stepObj.column as string).trim() === '') {
+          this.state.errors.set('column', 'Column to encode is required');
+        }
+        const method = (stepObj.encoding as { method?: EncodeMethodFields })?.method;
+        if (method?.kind === 'target') {
+          if (!method.target) {
+            this.state.errors.set('target', 'Target column is required');
+          } else if ((method.folds ?? 0) < 2) {
+            this.state.errors.set('folds', 'At least 2 folds are required');
+          }
+        }
+        break;
+      }
+      case 'vectorise_text':
+        if (!stepObj.columns || (stepObj.columns as unknown[]).length === 0) {
+          this.state.errors.set('columns', 'At least one column is required');
+        }
+        if (!stepObj.features || (stepObj.features as number) < 1) {
+          this.state.errors.set('features', 'Features must be at least 1');
+        }
+        break;
       case 'rename_columns':
       case 'cast_types':
       case 'parse_dates':
@@ -751,11 +2156,106 @@ export class StepConfigPanel {
           this.state.errors.set('mapping', 'At least one mapping is required');
         }
         break;
+      case 'map_values':
+        if (!stepObj.columns || (stepObj.columns as unknown[]).length === 0) {
+          this.state.errors.set('columns', 'At least one column is required');
+        }
+        if (!stepObj.mapping || Object.keys(stepObj.mapping as object).length === 0) {
+          this.state.errors.set('mapping', 'At least one mapping is required');
+        }
+        break;
       case 'regex_replace':
         if (!stepObj.pattern || (stepObj.pattern as string).trim() === '') {
           this.state.errors.set('pattern', 'Pattern is required');
         }
         break;
+      case 'concat':
+      case 'union':
+        if (!stepObj.inputs || (stepObj.inputs as string[]).length === 0) {
+          this.state.errors.set('inputs', 'At least one input is required');
+        }
+        break;
+      case 'sort':
+        if (!stepObj.by || (stepObj.by as string[]).length === 0) {
+          this.state.errors.set('by', 'At least one sort column is required');
+        }
+        break;
+      case 'sample': {
+        const hasFraction = stepObj.fraction !== null && stepObj.fraction !== undefined;
+        const hasN = stepObj.n !== null && stepObj.n !== undefined;
+        if (hasFraction === hasN) {
+          this.state.errors.set('fraction', 'Set either a fraction or a row count');
+        }
+        break;
+      }
+      case 'pivot':
+        if (!stepObj.columns || (stepObj.columns as string).trim() === '') {
+          this.state.errors.set('columns', 'Column to take new column names from is required');
+        }
+        if (!stepObj.values || (stepObj.values as string).trim() === '') {
+          this.state.errors.set('values', 'Values column is required');
+        }
+        break;
+      case 'expect_schema':
+        if (!stepObj.columns || (stepObj.columns as unknown[]).length === 0) {
+          this.state.errors.set('columns', 'At least one expected column is required');
+        }
+        break;
+      case 'completeness_score':
+        if (!stepObj.name || (stepObj.name as string).trim() === '') {
+          this.state.errors.set('name', 'Score column name is required');
+        }
+        break;
+      case 'write_output':
+        if (!stepObj.path_template || (stepObj.path_template as string).trim() === '') {
+          this.state.errors.set('path_template', 'Output path is required');
+        }
+        break;
+      case 'external':
+        if (!stepObj.command || (stepObj.command as string).trim() === '') {
+          this.state.errors.set('command', 'Command is required');
+        }
+        break;
+      case 'aggregate':
+        if (!stepObj.aggregations || (stepObj.aggregations as unknown[]).length === 0) {
+          this.state.errors.set('aggregations', 'At least one aggregation is required');
+        }
+        break;
+      case 'split_column':
+        if (!stepObj.column || (stepObj.column as string).trim() === '') {
+          this.state.errors.set('column', 'Column to split is required');
+        }
+        if (!stepObj.delimiter) {
+          this.state.errors.set('delimiter', 'Delimiter is required');
+        }
+        if (!stepObj.into || (stepObj.into as string[]).length === 0) {
+          this.state.errors.set('into', 'At least one new column is required');
+        }
+        break;
+      case 'merge_columns':
+        if (!stepObj.template || !(stepObj.template as string).includes('{')) {
+          this.state.errors.set('template', 'Template must name at least one {column}');
+        }
+        if (!stepObj.into || (stepObj.into as string).trim() === '') {
+          this.state.errors.set('into', 'New column name is required');
+        }
+        break;
+      case 'extract_date_parts':
+        if (!stepObj.column || (stepObj.column as string).trim() === '') {
+          this.state.errors.set('column', 'Date column is required');
+        }
+        if (!stepObj.parts || (stepObj.parts as string[]).length === 0) {
+          this.state.errors.set('parts', 'Choose at least one date part');
+        }
+        break;
+      case 'with_column':
+        if (!stepObj.name || (stepObj.name as string).trim() === '') {
+          this.state.errors.set('name', 'Column name is required');
+        }
+        if (!stepObj.expression || (stepObj.expression as string).trim() === '') {
+          this.state.errors.set('expression', 'Expression is required');
+        }
+        break;
     }
 
     // Re-render if there are errors
@@ -158,6 +158,49 @@ export class StepPalette {
           columns: [],
         }) as unknown as PipelineStep,
     },
+    {
+      id: 'bin',
+      name: 'Bin Values',
+      category: 'Feature Engineering',
+      description: 'Bucket numbers into labelled ranges, e.g. age bands',
+      icon: '🗂️',
+      createStep: () =>
+        ({
+          op: 'bin',
+          column: '',
+          strategy: { kind: 'equal_width', bins: 4 },
+          labels: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'encode_categories',
+      name: 'Encode Categories',
+      category: 'Feature Engineering',
+      description: 'Replace categories with ordinal codes or target means',
+      icon: '🔢',
+      createStep: () =>
+        ({
+          op: 'encode_categories',
+          column: '',
+          encoding: { method: { kind: 'ordinal' } },
+          mapping_path: null,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'vectorise_text',
+      name: 'Vectorise Text',
+      category: 'Feature Engineering',
+      description: 'Turn free text into numeric features (hashing or TF-IDF)',
+      icon: '🔤',
+      createStep: () =>
+        ({
+          op: 'vectorise_text',
+          columns: [],
+          method: 'tfidf',
+          features: 20,
+          drop_original: true,
+        }) as unknown as PipelineStep,
+    },
     {
       id: 'regex_replace',
       name: 'Regex Replace',
@@ -172,6 +215,245 @@ export class StepPalette {
           replacement: '',
         }) as unknown as PipelineStep,
     },
+    {
+      id: 'map_values',
+      name: 'Map Values',
+      category: 'Feature Engineering',
+      description: 'Replace whole values, e.g. spellings of one category',
+      icon: '🔀',
+      createStep: () =>
+        ({
+          op: 'map_values',
+          columns: [],
+          mapping: {},
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'split_column',
+      name: 'Split Column',
+      category: 'Feature Engineering',
+      description: 'Split a column on a delimiter into several columns',
+      icon: '✂️',
+      createStep: () =>
+        ({
+          op: 'split_column',
+          column: '',
+          delimiter: ',',
+          regex: false,
+          into: [],
+          drop_original: false,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'merge_columns',
+      name: 'Merge Columns',
+      category: 'Feature Engineering',
+      description: 'Combine columns into one with a template like {first} {last}',
+      icon: '🔗',
+      createStep: () =>
+        ({
+          op: 'merge_columns',
+          template: '',
+          into: '',
+          drop_sources: false,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'extract_date_parts',
+      name: 'Extract Date Parts',
+      category: 'Feature Engineering',
+      description: 'Add year, month, weekday, hour and other calendar columns',
+      icon: '📅',
+      createStep: () =>
+        ({
+          op: 'extract_date_parts',
+          column: '',
+          parts: ['year', 'month', 'weekday'],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'with_column',
+      name: 'Derived Column',
+      category: 'Feature Engineering',
+      description: 'Compute a new column from an expression',
+      icon: '🧮',
+      createStep: () =>
+        ({
+          op: 'with_column',
+          name: '',
+          expression: '',
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'aggregate',
+      name: 'Group & Aggregate',
+      category: 'Feature Engineering',
+      description: 'Summarise rows per group (sum, mean, count, ...)',
+      icon: '📊',
+      createStep: () =>
+        ({
+          op: 'aggregate',
+          group_by: [],
+          aggregations: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'concat',
+      name: 'Stack Inputs',
+      category: 'Column Operations',
+      description: 'Append rows from named inputs (e.g., monthly files)',
+      icon: '📚',
+      createStep: () =>
+        ({
+          op: 'concat',
+          inputs: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'union',
+      name: 'Union Inputs',
+      category: 'Column Operations',
+      description: 'Append rows from named inputs and drop duplicates',
+      icon: '🔗',
+      createStep: () =>
+        ({
+          op: 'union',
+          inputs: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'sort',
+      name: 'Sort Rows',
+      category: 'Column Operations',
+      description: 'Order rows by one or more columns',
+      icon: '↕️',
+      createStep: () =>
+        ({
+          op: 'sort',
+          by: [],
+          descending: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'limit',
+      name: 'Limit Rows',
+      category: 'Column Operations',
+      description: 'Keep only the first N rows',
+      icon: '✂️',
+      createStep: () =>
+        ({
+          op: 'limit',
+          n: 1000,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'sample',
+      name: 'Sample Rows',
+      category: 'Column Operations',
+      description: 'Keep a reproducible random sample of rows',
+      icon: '🎲',
+      createStep: () =>
+        ({
+          op: 'sample',
+          fraction: 0.1,
+          n: null,
+          seed: 42,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'pivot',
+      name: 'Pivot (Long to Wide)',
+      category: 'Feature Engineering',
+      description: 'Turn distinct values of a column into new columns',
+      icon: '🔀',
+      createStep: () =>
+        ({
+          op: 'pivot',
+          index: [],
+          columns: '',
+          values: '',
+          agg: 'sum',
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'unpivot',
+      name: 'Unpivot (Wide to Long)',
+      category: 'Feature Engineering',
+      description: 'Stack columns into variable/value rows',
+      icon: '🔃',
+      createStep: () =>
+        ({
+          op: 'unpivot',
+          id_vars: [],
+          value_vars: [],
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'transpose',
+      name: 'Transpose',
+      category: 'Feature Engineering',
+      description: 'Swap rows and columns for files with variables as rows',
+      icon: '🔄',
+      createStep: () =>
+        ({
+          op: 'transpose',
+          header_column: null,
+          names_column: 'field',
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'expect_schema',
+      name: 'Expect Schema',
+      category: 'Data Cleaning',
+      description: 'Stop the run when columns, types or nulls break a contract',
+      icon: '📋',
+      createStep: () =>
+        ({
+          op: 'expect_schema',
+          columns: [],
+          on_violation: 'fail',
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'completeness_score',
+      name: 'Row Completeness Score',
+      category: 'Feature Engineering',
+      description: 'Add a column with the fraction of non-null fields per row',
+      icon: '✅',
+      createStep: () =>
+        ({
+          op: 'completeness_score',
+          name: 'row_completeness',
+          weights: {},
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'write_output',
+      name: 'Write Intermediate Output',
+      category: 'Column Operations',
+      description: 'Save the data at this point to an extra file and continue',
+      icon: '💾',
+      createStep: () =>
+        ({
+          op: 'write_output',
+          path_template: '',
+          format: null,
+        }) as unknown as PipelineStep,
+    },
+    {
+      id: 'external',
+      name: 'External Command',
+      category: 'Data Cleaning',
+      description: 'Run a trusted in-house tool over the data and use its output',
+      icon: '🛠️',
+      createStep: () =>
+        ({
+          op: 'external',
+          command: '',
+          args: ['{input}', '{output}'],
+          data_format: 'parquet',
+        }) as unknown as PipelineStep,
+    },
   ];
 
   constructor(container: HTMLElement) {
@@ -190,7 +190,9 @@ export class WatcherComponent extends Component {
       case 'watcher:ingest_succeeded':
         this.updateActivityStatus(state, p.path ?? '', 'success', {
           status: 'success',
-          message: `Ingested ${p.rows ?? 0} rows, ${p.cols ?? 0} columns`,
+          message: p.version_id
+            ? `Added as a new raw version (${p.rows ?? 0} rows, ${p.cols ?? 0} columns)`
+            : `Ingested ${p.rows ?? 0} rows, ${p.cols ?? 0} columns`,
           datasetId: p.datasetId ?? undefined,
           rows: p.rows ?? undefined,
           cols: p.cols ?? undefined,
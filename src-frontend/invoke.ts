@@ -0,0 +1,90 @@
+/**
+ * # Typed Command Errors
+ *
+ * Tauri commands fail with a `BeefcakeError` serialized as
+ * `{ code, message, detail }` (see `src/error.rs`). This module wraps
+ * Tauri's `invoke()` so those rejections become {@link CommandError}s: real
+ * `Error`s whose string form is the user-facing message, so existing
+ * `String(err)` and `${err}` call sites keep reading well, while handlers
+ * that care can branch on `code`.
+ *
+ * ```typescript
+ * try {
+ *   await api.exportData(options);
+ * } catch (err) {
+ *   if (isCommandError(err, 'aborted')) return;
+ *   showToast(`Export failed: ${String(err)}`, 'error');
+ * }
+ * ```
+ *
+ * @module invoke
+ */
+
+import { invoke as tauriInvoke } from '@tauri-apps/api/core';
+
+/** Stable error categories, matching `ErrorCode` in `src/error.rs` */
+export type ErrorCode =
+  | 'io'
+  | 'not_found'
+  | 'permission_denied'
+  | 'invalid_path'
+  | 'parse'
+  | 'validation'
+  | 'data_processing'
+  | 'database'
+  | 'python'
+  | 'ai'
+  | 'config'
+  | 'aborted'
+  | 'internal';
+
+/** A failed command as the backend sends it */
+export interface CommandErrorPayload {
+  code: ErrorCode;
+  /** What to show the user */
+  message: string;
+  /** The whole chain of causes, for logs and bug reports */
+  detail: string;
+}
+
+export class CommandError extends Error {
+  readonly code: ErrorCode;
+  readonly detail: string;
+
+  constructor(payload: CommandErrorPayload) {
+    super(payload.message);
+    this.name = 'CommandError';
+    this.code = payload.code;
+    this.detail = payload.detail;
+  }
+
+  override toString(): string {
+    return this.message;
+  }
+}
+
+function isCommandErrorPayload(value: unknown): value is CommandErrorPayload {
+  if (typeof value !== 'object' || value === null) return false;
+  const v = value as Record<string, unknown>;
+  return typeof v.code === 'string' && typeof v.message === 'string';
+}
+
+/** Whether `err` is a {@link CommandError}, optionally with code `code` */
+export function isCommandError(err: unknown, code?: ErrorCode): err is CommandError {
+  return err instanceof CommandError && (code === undefined || err.code === code);
+}
+
+/**
+ * Call a Tauri command like `invoke()` from `@tauri-apps/api/core`,
+ * rejecting with a {@link CommandError} when the command fails
+ */
+export async function invoke<T>(...args: Parameters<typeof tauriInvoke>): Promise<T> {
+  try {
+    return await tauriInvoke<T>(...args);
+  } catch (err) {
+    if (isCommandErrorPayload(err)) {
+      throw new CommandError(err);
+    }
+    throw err;
+  }
+}
@@ -68,6 +68,7 @@ import { ReferenceComponent } from './components/ReferenceComponent';
 import { SettingsComponent } from './components/SettingsComponent';
 import { SQLComponent } from './components/SQLComponent';
 import { WatcherComponent } from './components/WatcherComponent';
+import { isCommandError } from './invoke';
 import * as renderers from './renderers';
 import { WatcherService } from './services/WatcherService';
 import { WizardService } from './services/WizardService';
@@ -78,7 +79,9 @@ import {
   getDefaultColumnCleanConfig,
   getDefaultAppConfig,
   DatasetVersion,
+  HeaderlessNaming,
 } from './types';
+import { isWorkbookPath } from './utils';
 import { setupIDESidebarToggle } from './utils/ide-sidebar';
 import { createLogger } from './utils/logger';
 
@@ -152,6 +155,7 @@ class BeefcakeApp {
     selectedVersionId: null,
     currentIdeColumns: null,
     previousVersionId: null,
+    workbook: null,
   };
 
   private components: Partial<Record<View, Component>> = {};
@@ -232,6 +236,7 @@ class BeefcakeApp {
       );
       console.log('[BeefcakeApp] Starting Polars version check...');
       void this.checkPolarsVersion(); // Non-blocking Polars version check
+      void this.checkDictionaryReviews();
 
       console.log('[BeefcakeApp] First render...');
       this.render();
@@ -276,6 +281,27 @@ class BeefcakeApp {
     }
   }
 
+  /**
+   * Announce datasets whose documentation is due for review, when review
+   * reminders are enabled.
+   */
+  private async checkDictionaryReviews(): Promise<void> {
+    if (!this.state.config?.settings.dictionary_review_reminders) return;
+    try {
+      const due = await api.dictionaryReviewSchedule(true, true);
+      if (due.length > 0) {
+        const names = due.map(r => r.dataset_name).join(', ');
+        this.showToast(
+          `${due.length} dataset${due.length === 1 ? ' is' : 's are'} due for review: ${names}`,
+          'info'
+        );
+      }
+    } catch (err) {
+      /* eslint-disable-next-line no-console */
+      console.debug('Failed to check dictionary reviews:', err);
+    }
+  }
+
   private async checkPolarsVersion(): Promise<void> {
     try {
       const result = await api.checkPythonEnvironment();
@@ -302,8 +328,14 @@ class BeefcakeApp {
     const actions = {
       onStateChange: () => this.render(),
       showToast: (msg: string, type?: 'info' | 'error' | 'success') => this.showToast(msg, type),
-      runAnalysis: (path: string) => {
-        void this.handleAnalysis(path);
+      runAnalysis: (path: string, headerNames?: HeaderlessNaming | null) => {
+        void this.handleAnalysis(path, headerNames);
+      },
+      analyseWorkbook: (path: string) => {
+        void this.handleWorkbookAnalysis(path);
+      },
+      selectWorkbookSheet: (index: number) => {
+        void this.selectWorkbookSheet(index);
       },
       switchView: (view: View) => {
         void this.switchView(view);
@@ -418,7 +450,7 @@ class BeefcakeApp {
     }
   }
 
-  public async handleAnalysis(path: string): Promise<void> {
+  public async handleAnalysis(path: string, headerNames?: HeaderlessNaming | null): Promise<void> {
     try {
       this.state.isLoading = true;
       this.state.isAborting = false;
@@ -426,8 +458,9 @@ class BeefcakeApp {
       await this.switchView('Analyser');
 
       this.showToast(`Analysing ${path}...`, 'info');
-      const response = await api.analyseFile(path);
+      const response = await api.analyseFile(path, headerNames);
       this.state.analysisResponse = response;
+      this.state.workbook = await this.workbookSheets(path);
 
       // Initialize cleaning configs
       this.state.cleaningConfigs = {};
@@ -442,21 +475,95 @@ class BeefcakeApp {
       this.showToast('Analysis complete', 'success');
 
       // Create lifecycle dataset (now awaited to track progress)
-      await this.createLifecycleDatasetAsync(response.file_name, path);
+      await this.createLifecycleDatasetAsync(response.file_name, path, response.header_names);
       this.state.isCreatingLifecycle = false;
       // render() is already called inside createLifecycleDatasetAsync
     } catch (err) {
       this.state.isLoading = false;
       this.state.isCreatingLifecycle = false;
       this.render();
+      if (isCommandError(err, 'aborted')) {
+        this.showToast('Analysis aborted', 'info');
+        return;
+      }
       this.showToast(`Analysis failed: ${String(err)}`, 'error');
     }
   }
 
-  private async createLifecycleDatasetAsync(fileName: string, path: string): Promise<void> {
+  /** Sheets of `path` if it is a workbook with more than one, to offer analysing them all */
+  private async workbookSheets(path: string): Promise<AppState['workbook']> {
+    if (!isWorkbookPath(path)) return null;
+    try {
+      const sheets = await api.listWorkbookSheets(path);
+      return sheets.length > 1 ? { path, sheets, analysis: null } : null;
+    } catch {
+      // The first sheet is still usable on its own
+      return null;
+    }
+  }
+
+  private async handleWorkbookAnalysis(path: string): Promise<void> {
+    const workbook = this.state.workbook;
+    if (!workbook) return;
+    try {
+      this.state.isLoading = true;
+      this.state.isAborting = false;
+      this.state.loadingMessage = `Analysing ${workbook.sheets.length} sheets...`;
+      this.render();
+
+      workbook.analysis = await api.analyseWorkbook(path);
+      this.state.isLoading = false;
+      this.render();
+
+      const failed = workbook.analysis.failed.length;
+      this.showToast(
+        failed
+          ? `Analysed ${workbook.analysis.sheets.length} sheets, ${failed} failed`
+          : `Analysed ${workbook.analysis.sheets.length} sheets`,
+        failed ? 'error' : 'success'
+      );
+    } catch (err) {
+      this.state.isLoading = false;
+      this.render();
+      this.showToast(`Workbook analysis failed: ${String(err)}`, 'error');
+    }
+  }
+
+  /** Show the analysis of one sheet and make it the current dataset */
+  private async selectWorkbookSheet(index: number): Promise<void> {
+    const response = this.state.workbook?.analysis?.sheets[index];
+    if (!response) return;
+
+    this.state.analysisResponse = response;
+    this.state.cleaningConfigs = {};
+    (response.summary || []).forEach(col => {
+      this.state.cleaningConfigs[col.name] = getDefaultColumnCleanConfig(col);
+    });
+    this.state.selectedColumns = new Set();
+    this.state.expandedRows = new Set();
+    this.state.currentDataset = null;
+    this.state.isCreatingLifecycle = true;
+    this.render();
+
+    try {
+      await this.createLifecycleDatasetAsync(
+        response.file_name,
+        response.path,
+        response.header_names
+      );
+    } finally {
+      this.state.isCreatingLifecycle = false;
+    }
+  }
+
+  private async createLifecycleDatasetAsync(
+    fileName: string,
+    path: string,
+    headerNames?: HeaderlessNaming | null
+  ): Promise<void> {
     try {
       this.logger.info('Creating dataset:', { fileName, path });
-      const datasetId = await api.createDataset(fileName, path);
+      const datasetId = await api.createDataset(fileName, path, headerNames);
       this.logger.info('Dataset created with ID:', datasetId);
 
       this.logger.info('Listing versions for dataset:', datasetId);
@@ -655,6 +762,9 @@ window.addEventListener('unhandledrejection', event => {
   const context = {
     reason: reasonObj?.stack ?? String(event.reason),
     promise: String(event.promise),
+    ...(isCommandError(event.reason)
+      ? { code: event.reason.code, detail: event.reason.detail }
+      : {}),
   };
   console.error(errorMessage, context);
   void api.logFrontendError('error', errorMessage, context);
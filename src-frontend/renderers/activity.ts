@@ -1,19 +1,65 @@
-import { AppConfig } from '../types';
+import { AppConfig, AuditEntry } from '../types';
 import { escapeHtml } from '../utils';
 
-export function renderActivityLogView(config: AppConfig): string {
+/** Filter of the audit trail search, as entered: dates are `YYYY-MM-DD` */
+export interface ActivityFilter {
+  category: string;
+  from: string;
+  to: string;
+}
+
+/**
+ * Render the Activity Log: the recent entries in the app config, or `trail`,
+ * the entries found by searching the audit files with `filter`
+ */
+export function renderActivityLogView(
+  config: AppConfig,
+  trail: AuditEntry[] | null = null,
+  filter: ActivityFilter = { category: '', from: '', to: '' }
+): string {
   // Safely handle audit_log which might be undefined or have no entries
-  const auditLogArray = config.audit_log?.entries || [];
+  const auditLogArray = trail ?? (config.audit_log?.entries || []);
   const auditLogs = [...auditLogArray].reverse();
+  const categories = [
+    ...new Set([...(config.audit_log?.entries || []), ...(trail ?? [])].map(e => e.action)),
+  ].sort();
+  if (filter.category && !categories.includes(filter.category)) {
+    categories.push(filter.category);
+  }
 
   return `
     <div class="activity-view">
       <div class="activity-header">
         <h3><i class="ph ph-list-bullets"></i> Activity Log</h3>
-        <button id="btn-clear-log" class="btn-secondary btn-small">Clear History</button>
+        <div class="activity-actions">
+          <button id="btn-export-audit-csv" class="btn-secondary btn-small">
+            <i class="ph ph-export"></i> Export CSV
+          </button>
+          <button id="btn-export-audit-jsonl" class="btn-secondary btn-small">
+            <i class="ph ph-export"></i> Export JSONL
+          </button>
+          <button id="btn-clear-log" class="btn-secondary btn-small">Clear History</button>
+        </div>
+      </div>
+      <div class="activity-filter">
+        <select id="audit-category" aria-label="Category">
+          <option value="">All categories</option>
+          ${categories
+            .map(
+              c =>
+                `<option value="${escapeHtml(c)}" ${c === filter.category ? 'selected' : ''}>${escapeHtml(c)}</option>`
+            )
+            .join('')}
+        </select>
+        <label>From <input type="date" id="audit-from" value="${escapeHtml(filter.from)}"></label>
+        <label>To <input type="date" id="audit-to" value="${escapeHtml(filter.to)}"></label>
+        <button id="btn-audit-search" class="btn-secondary btn-small">
+          <i class="ph ph-magnifying-glass"></i> Search Audit Trail
+        </button>
+        ${trail ? '<button id="btn-audit-reset" class="btn-secondary btn-small">Show Recent</button>' : ''}
       </div>
       <div class="activity-list">
-        ${auditLogs.length === 0 ? '<p class="empty-msg">No recent activity.</p>' : ''}
+        ${auditLogs.length === 0 ? `<p class="empty-msg">${trail ? 'No entries match.' : 'No recent activity.'}</p>` : ''}
         ${auditLogs
           .map(
             entry => `
@@ -13,6 +13,16 @@ import {
   renderStageProgressBar,
   renderFilterToolbar,
   renderInsightsPanel,
+  renderFeatureImportancePanel,
+  renderFeatureImportanceResults,
+  renderWorkbookPanel,
+  renderDeepDiveResults,
+  renderCleanPreviewResults,
+  renderPresetModal,
+  renderStandardiseModal,
+  renderUsageModal,
+  CLEAN_PREVIEW_ROWS,
+  type DeepDiveProgress,
   createDefaultFilterState,
 } from './analyser/index';
 
@@ -27,7 +37,17 @@ export {
   renderStageProgressBar,
   renderFilterToolbar,
   renderInsightsPanel,
+  renderFeatureImportancePanel,
+  renderFeatureImportanceResults,
+  renderWorkbookPanel,
+  renderDeepDiveResults,
+  renderCleanPreviewResults,
+  renderPresetModal,
+  renderStandardiseModal,
+  renderUsageModal,
+  CLEAN_PREVIEW_ROWS,
   createDefaultFilterState,
+  type DeepDiveProgress,
   type FilterState,
 };
 
@@ -78,8 +98,12 @@ export function renderAnalyser(
 
       ${renderDatasetOverview(response)}
 
+      <div id="analyser-data-grid-container"></div>
+
       ${!isReadOnly ? renderInsightsPanel(response) : ''}
 
+      ${!isReadOnly ? renderFeatureImportancePanel(response) : ''}
+
       ${renderFilterToolbar(response, filterState)}
 
       ${
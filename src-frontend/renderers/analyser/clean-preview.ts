@@ -0,0 +1,53 @@
+import { ColumnCleanPreview, ColumnSummary } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/** Rows cleaned for a column preview */
+export const CLEAN_PREVIEW_ROWS = 10;
+
+export function renderCleanPreviewPanel(col: ColumnSummary): string {
+  return `
+    <div class="clean-preview-panel" data-col="${escapeHtml(col.name)}">
+      <button type="button" class="btn-secondary btn-small btn-preview-clean row-action" data-col="${escapeHtml(col.name)}" title="Apply these transformations to the first ${CLEAN_PREVIEW_ROWS} rows and compare the values">
+        <i class="ph ph-arrows-left-right"></i> Preview Cleaning
+      </button>
+      <div class="clean-preview-results"></div>
+    </div>
+  `;
+}
+
+export function renderCleanPreviewResults(preview: ColumnCleanPreview): string {
+  if (preview.cleaned_name === null) {
+    return '<p class="clean-preview-note">This column is removed or one-hot encoded by cleaning.</p>';
+  }
+
+  const cell = (value: string | null): string =>
+    value === null ? '<span class="null-value">null</span>' : escapeHtml(value);
+  const rows = preview.before
+    .map((before, i) => {
+      const after = preview.after[i] ?? null;
+      return `
+        <tr class="${before !== after ? 'changed' : ''}">
+          <td class="mono">${cell(before)}</td>
+          <td class="mono">${cell(after)}</td>
+        </tr>
+      `;
+    })
+    .join('');
+
+  return `
+    <p class="clean-preview-note">
+      ${preview.changed} of ${preview.before.length} values changed
+      ${
+        preview.dtype_after !== preview.dtype_before
+          ? `· type ${escapeHtml(preview.dtype_before)} → ${escapeHtml(preview.dtype_after)}`
+          : ''
+      }
+    </p>
+    <table class="clean-preview-table">
+      <thead>
+        <tr><th>Before</th><th>After (${escapeHtml(preview.cleaned_name)})</th></tr>
+      </thead>
+      <tbody>${rows}</tbody>
+    </table>
+  `;
+}
@@ -0,0 +1,100 @@
+import { ColumnDeepDive, ColumnSummary } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/** Parts of a deep dive received so far */
+export type DeepDiveProgress = Partial<Omit<ColumnDeepDive, 'column'>>;
+
+export function renderDeepDivePanel(col: ColumnSummary): string {
+  return `
+    <div class="deep-dive-panel" data-col="${escapeHtml(col.name)}">
+      <button type="button" class="btn-secondary btn-small btn-deep-dive row-action" data-col="${escapeHtml(col.name)}" title="Scan the whole column for exact distinct values, repeated runs and decile means. Not part of the default analysis, so it may take a while on large files.">
+        <i class="ph ph-magnifying-glass-plus"></i> Deep Dive
+      </button>
+      <div class="deep-dive-results"></div>
+    </div>
+  `;
+}
+
+export function renderDeepDiveResults(
+  progress: DeepDiveProgress,
+  isNumeric: boolean,
+  loading: boolean
+): string {
+  const pending = (label: string): string =>
+    loading ? `<p class="deep-dive-pending"><i class="ph ph-spinner"></i> ${label}...</p>` : '';
+
+  const distinct = progress.distinct_values;
+  const runs = progress.longest_runs;
+  const deciles = progress.decile_means;
+
+  return `
+    <div class="deep-dive-grid">
+      <div class="stat-group">
+        <h5 title="Every distinct non-null value with its row count, most frequent first">Distinct Values</h5>
+        ${
+          distinct
+            ? `
+          <p class="deep-dive-note">${distinct.total_distinct.toLocaleString()} distinct${distinct.truncated ? `, showing top ${distinct.values.length.toLocaleString()}` : ''}</p>
+          <div class="top-values-list deep-dive-scroll">
+            ${distinct.values
+              .map(
+                ([val, count]) => `
+              <div class="top-value-item">
+                <span class="val mono">${escapeHtml(val)}</span>
+                <span class="count">${count.toLocaleString()}</span>
+              </div>
+            `
+              )
+              .join('')}
+          </div>
+        `
+            : pending('Listing values')
+        }
+      </div>
+
+      <div class="stat-group">
+        <h5 title="Longest stretches of consecutive rows holding the same value. Long runs can point to sorted data, fill-down or repeated imports.">Longest Runs</h5>
+        ${
+          runs
+            ? runs.length
+              ? runs
+                  .map(
+                    r => `
+              <div class="stat-row" title="Rows ${(r.start_row + 1).toLocaleString()} to ${(r.start_row + r.length).toLocaleString()}">
+                <span class="mono">${escapeHtml(r.value)}</span>
+                <span class="stat-value">${r.length.toLocaleString()} rows</span>
+              </div>
+            `
+                  )
+                  .join('')
+              : '<p class="deep-dive-note">No value repeats on consecutive rows.</p>'
+            : pending('Finding runs')
+        }
+      </div>
+
+      ${
+        isNumeric
+          ? `
+      <div class="stat-group">
+        <h5 title="Sorted non-null values split into ten equal-sized groups, with the range and mean of each">Decile Means</h5>
+        ${
+          deciles
+            ? deciles
+                .map(
+                  d => `
+              <div class="stat-row" title="${d.count.toLocaleString()} values from ${d.lower} to ${d.upper}">
+                <span>D${d.decile}:</span>
+                <span class="stat-value mono">${d.mean.toFixed(4)}</span>
+              </div>
+            `
+                )
+                .join('')
+            : pending('Computing deciles')
+        }
+      </div>
+      `
+          : ''
+      }
+    </div>
+  `;
+}
@@ -1,4 +1,11 @@
-import { AnalysisResponse, LifecycleStage } from '../../types';
+import {
+  AnalysisResponse,
+  ColumnMapping,
+  ColumnOrigin,
+  LayoutReport,
+  LifecycleStage,
+  TransposeDetection,
+} from '../../types';
 import { escapeHtml, fmtBytes, fmtDuration } from '../../utils';
 
 function renderCleaningInfoBox(): string {
@@ -52,6 +59,141 @@ function renderCleaningInfoBox(): string {
   `;
 }
 
+/** Which file column each name came from, when the header repeats names */
+export function renderColumnMappingNotice(mapping: ColumnMapping | null | undefined): string {
+  if (!mapping?.duplicates.length) return '';
+
+  const repeated = new Set(mapping.duplicates);
+  const origin = (o: ColumnOrigin): string =>
+    `<li><span class="mono">${escapeHtml(o.name)}</span> ← column ${o.position + 1} (<span class="mono">${escapeHtml(o.source_name)}</span>)</li>`;
+  const outcome =
+    mapping.policy === 'auto_suffix'
+      ? 'Repeats were renamed with a numeric suffix.'
+      : `Kept the ${mapping.policy === 'keep_first' ? 'first' : 'last'} column with each name and dropped ${mapping.dropped.length}.`;
+
+  return `
+    <div class="stage-banner stage-banner-warning" data-testid="analyser-column-mapping">
+      <i class="ph ph-columns"></i>
+      <div>
+        <strong>Repeated header names: ${mapping.duplicates.map(escapeHtml).join(', ')}</strong>
+        <span>${outcome} The policy can be changed in Settings.</span>
+        <details>
+          <summary>Column mapping</summary>
+          <ul>
+            ${mapping.columns
+              .filter(o => repeated.has(o.source_name))
+              .map(origin)
+              .join('')}
+          </ul>
+          ${
+            mapping.dropped.length
+              ? `<span>Dropped:</span><ul>${mapping.dropped.map(origin).join('')}</ul>`
+              : ''
+          }
+        </details>
+      </div>
+    </div>
+  `;
+}
+
+/** Warning for files that look like they hold variables as rows */
+export function renderOrientationNotice(detection: TransposeDetection | null | undefined): string {
+  if (!detection) return '';
+
+  const outcome = detection.applied
+    ? `Rows and columns were swapped before analysis, using <span class="mono">${escapeHtml(detection.label_column)}</span> values as column names.`
+    : 'Turn on auto-transpose in Settings, or add a Transpose step to a pipeline, to profile one variable per column.';
+
+  return `
+    <div class="stage-banner stage-banner-warning" data-testid="analyser-orientation">
+      <i class="ph ph-arrows-counter-clockwise"></i>
+      <div>
+        <strong>${detection.applied ? 'Transposed file' : 'File looks transposed'}</strong>
+        <span>${outcome}</span>
+        <details>
+          <summary>Why</summary>
+          <ul>${detection.reasons.map(r => `<li>${escapeHtml(r)}</li>`).join('')}</ul>
+        </details>
+      </div>
+    </div>
+  `;
+}
+
+/** Merged header cells named and footer rows dropped while loading */
+export function renderLayoutNotice(report: LayoutReport | null | undefined): string {
+  if (!report) return '';
+
+  const changes: string[] = [];
+  if (report.filled_headers.length) {
+    const names = report.filled_headers
+      .map(h => `<span class="mono">${escapeHtml(h.name)}</span> (column ${h.position + 1})`)
+      .join(', ');
+    changes.push(`Blank header cells after merged headers were named ${names}.`);
+  }
+  if (report.dropped_footer_rows > 0) {
+    const rows =
+      report.dropped_footer_rows === 1 ? 'row' : `${report.dropped_footer_rows} rows`;
+    const label = report.footer_label
+      ? `, including a <span class="mono">${escapeHtml(report.footer_label)}</span> row`
+      : '';
+    changes.push(`Dropped the last ${rows} as a totals or blank footer${label}.`);
+  }
+  if (!changes.length) return '';
+
+  return `
+    <div class="stage-banner stage-banner-warning" data-testid="analyser-layout">
+      <i class="ph ph-table"></i>
+      <div>
+        <strong>Spreadsheet layout adjusted</strong>
+        <ul>${changes.map(c => `<li>${c}</li>`).join('')}</ul>
+        <span>Turn these fixes off under Settings if the file was read wrongly.</span>
+      </div>
+    </div>
+  `;
+}
+
+/**
+ * How a CSV file's header row was read: without one, with a way back, or
+ * with one that looks like data, with ways to read the file without it
+ */
+export function renderHeaderNotice(response: AnalysisResponse): string {
+  const naming = response.header_names;
+  if (naming) {
+    const names =
+      naming.naming === 'auto'
+        ? 'numbered <span class="mono">column_1</span>, <span class="mono">column_2</span>, ...'
+        : naming.naming === 'inferred'
+          ? 'from their content'
+          : `from <span class="mono">${escapeHtml(naming.path)}</span>`;
+    return `
+      <div class="stage-banner stage-banner-readonly" data-testid="analyser-headerless">
+        <i class="ph ph-rows"></i>
+        <div>
+          <strong>Read without a header row</strong>
+          <span>Every row is data; columns are named ${names}.</span>
+        </div>
+        <button class="btn-secondary btn-small btn-header-row" data-naming="header">
+          First row is a header
+        </button>
+      </div>
+    `;
+  }
+  if (!response.header_looks_like_data) return '';
+
+  return `
+    <div class="stage-banner stage-banner-warning" data-testid="analyser-headerless">
+      <i class="ph ph-rows"></i>
+      <div>
+        <strong>The first row looks like data</strong>
+        <span>If the file has no header row, read it again with every row as data and name the columns by position.</span>
+      </div>
+      <button class="btn-secondary btn-small btn-header-row" data-naming="auto">Numbered names</button>
+      <button class="btn-secondary btn-small btn-header-row" data-naming="inferred">Names from content</button>
+      <button class="btn-secondary btn-small btn-header-row" data-naming="schema">Names from schema file</button>
+    </div>
+  `;
+}
+
 export function renderAnalyserHeader(
   response: AnalysisResponse,
   currentStage: LifecycleStage | null = null,
@@ -82,6 +224,10 @@ export function renderAnalyserHeader(
         : ''
     }
     ${currentStage === 'Cleaned' && !isReadOnly ? renderCleaningInfoBox() : ''}
+    ${renderColumnMappingNotice(response.column_mapping)}
+    ${renderOrientationNotice(response.orientation)}
+    ${renderLayoutNotice(response.layout)}
+    ${renderHeaderNotice(response)}
     <div class="analyser-header" data-testid="analyser-header">
       <div class="header-main">
         <h2 data-testid="analyser-file-name">${escapeHtml(response.file_name)} <small data-testid="analyser-file-size">(${fmtBytes(response.file_size)})</small></h2>
@@ -111,6 +257,18 @@ export function renderAnalyserHeader(
           !isReadOnly
             ? `
           <div class="action-divider"></div>
+          <button id="btn-undo-config" class="btn-ghost btn-small btn-icon" title="Undo config change (Ctrl+Z)" aria-label="Undo config change" disabled>
+            <i class="ph ph-arrow-counter-clockwise"></i>
+          </button>
+          <button id="btn-redo-config" class="btn-ghost btn-small btn-icon" title="Redo config change (Ctrl+Shift+Z)" aria-label="Redo config change" disabled>
+            <i class="ph ph-arrow-clockwise"></i>
+          </button>
+          <button id="btn-presets" class="btn-ghost btn-small" title="Save or apply cleaning presets">
+            <i class="ph ph-bookmarks"></i> Presets
+          </button>
+          <button id="btn-usage-suggestions" class="btn-ghost btn-small" title="Defaults suggested by earlier cleans of this file">
+            <i class="ph ph-lightbulb"></i> Suggestions
+          </button>
           <button id="btn-toggle-names" class="btn-ghost btn-small ${useOriginalColumnNames ? 'active' : ''}">
             <i class="ph ${useOriginalColumnNames ? 'ph-tag-simple' : 'ph-tag'}"></i>
             ${useOriginalColumnNames ? 'Using Original Names' : 'Using Standardised Names'}
@@ -123,6 +281,9 @@ export function renderAnalyserHeader(
             : ''
         }
         <div class="action-divider"></div>
+        <button id="btn-data-grid" class="btn-ghost btn-small" title="Show the rows of the file" data-testid="analyser-data-grid-button">
+          <i class="ph ph-table"></i> Data
+        </button>
         <button id="btn-export" class="btn-primary btn-small" data-testid="analyser-export-button">
           <i class="ph ph-export"></i> Export
         </button>
@@ -7,3 +7,10 @@ export * from './sidebar';
 export * from './progress-bar';
 export * from './filters';
 export * from './insights';
+export * from './ml';
+export * from './deep-dive';
+export * from './clean-preview';
+export * from './workbook';
+export * from './presets';
+export * from './standardise';
+export * from './usage';
@@ -0,0 +1,79 @@
+import { AnalysisResponse, FeatureImportanceReport } from '../../types';
+import { escapeHtml } from '../../utils';
+
+export function renderFeatureImportancePanel(response: AnalysisResponse): string {
+  const columns = response.summary || [];
+  if (columns.length < 2) {
+    return '';
+  }
+
+  return `
+    <div class="ml-panel" data-testid="ml-panel">
+      <div class="ml-panel-header">
+        <i class="ph ph-brain"></i>
+        <h3>Feature Importance</h3>
+      </div>
+      <div class="ml-panel-controls">
+        <label for="feature-importance-target">Target column</label>
+        <select id="feature-importance-target">
+          ${columns
+            .map(
+              col =>
+                `<option value="${escapeHtml(col.name)}">${escapeHtml(col.name)} (${escapeHtml(col.kind)})</option>`
+            )
+            .join('')}
+        </select>
+        <button id="btn-feature-importance" class="btn-secondary">
+          <i class="ph ph-chart-bar-horizontal"></i> Rank Features
+        </button>
+      </div>
+      <div id="feature-importance-results"></div>
+    </div>
+  `;
+}
+
+export function renderFeatureImportanceResults(report: FeatureImportanceReport): string {
+  if (report.features.length === 0) {
+    return '<p class="ml-panel-empty">No features could be scored.</p>';
+  }
+
+  const maxMi = Math.max(...report.features.map(f => f.mutual_information));
+  const modelLabel = report.model_kind === 'DecisionTree' ? 'Tree importance' : '|Std. coef.|';
+
+  return `
+    <ul class="ml-interpretation">
+      ${report.interpretation.map(line => `<li>${escapeHtml(line)}</li>`).join('')}
+    </ul>
+    <table class="ml-importance-table">
+      <thead>
+        <tr>
+          <th>#</th>
+          <th>Feature</th>
+          <th>Mutual information</th>
+          <th>${modelLabel}</th>
+        </tr>
+      </thead>
+      <tbody>
+        ${report.features
+          .map(
+            f => `
+          <tr>
+            <td>${f.rank}</td>
+            <td class="mono">${escapeHtml(f.feature)}</td>
+            <td>
+              <div class="ml-importance-bar" style="width: ${maxMi > 0 ? (f.mutual_information / maxMi) * 100 : 0}%"></div>
+              <span>${f.mutual_information.toFixed(3)}</span>
+            </td>
+            <td>${f.model_importance !== null ? `${(f.model_importance * 100).toFixed(1)}%` : '—'}</td>
+          </tr>
+        `
+          )
+          .join('')}
+      </tbody>
+    </table>
+    <p class="ml-panel-footnote">
+      ${report.task} on ${report.row_count.toLocaleString()} rows.
+      ${report.skipped_columns.length > 0 ? `Skipped: ${report.skipped_columns.map(escapeHtml).join(', ')}.` : ''}
+    </p>
+  `;
+}
@@ -0,0 +1,61 @@
+import { CleaningPreset } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/**
+ * Saved cleaning presets: save the current configs under a name, or apply
+ * or delete a saved preset.
+ */
+export function renderPresetModal(presets: CleaningPreset[], configuredColumns: number): string {
+  const list =
+    presets.length === 0
+      ? '<p class="modal-description">No presets saved yet.</p>'
+      : `
+        <table class="preset-table">
+          <thead>
+            <tr><th>Name</th><th>Columns</th><th>Updated</th><th></th></tr>
+          </thead>
+          <tbody>
+            ${presets
+              .map(
+                preset => `
+              <tr>
+                <td>${escapeHtml(preset.name)}</td>
+                <td>${Object.keys(preset.configs).length}</td>
+                <td>${new Date(preset.updated_at).toLocaleDateString()}</td>
+                <td>
+                  <button class="btn-secondary btn-small btn-apply-preset" data-id="${escapeHtml(preset.id)}">Apply</button>
+                  <button class="btn-ghost btn-small btn-icon btn-delete-preset" data-id="${escapeHtml(preset.id)}" title="Delete preset" aria-label="Delete preset">
+                    <i class="ph ph-trash"></i>
+                  </button>
+                </td>
+              </tr>`
+              )
+              .join('')}
+          </tbody>
+        </table>`;
+
+  return `
+    <div class="modal-overlay" data-testid="preset-modal-overlay">
+      <div class="modal-content" data-testid="preset-modal">
+        <div class="modal-header">
+          <h3>Cleaning Presets</h3>
+          <button class="modal-close" id="modal-close">
+            <i class="ph ph-x"></i>
+          </button>
+        </div>
+        <div class="modal-body">
+          <p class="modal-description">
+            Applying a preset matches its columns to this file by name, allowing for differences in case, spacing and small typos.
+          </p>
+          ${list}
+          <div class="preset-save">
+            <input type="text" id="preset-name" placeholder="Preset name" aria-label="Preset name">
+            <button id="btn-save-preset" class="btn-primary btn-small" ${configuredColumns === 0 ? 'disabled' : ''}>
+              <i class="ph ph-floppy-disk"></i> Save current (${configuredColumns} columns)
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  `;
+}
@@ -258,6 +258,76 @@ describe('renderDistribution', () => {
     expect(result).toContain('distribution-chart');
   });
 
+  it('should render view toggle when skewed histogram variants are present', () => {
+    const col: ColumnSummary = {
+      name: 'income',
+      standardized_name: 'income',
+      kind: 'Numeric',
+      count: 100,
+      nulls: 0,
+      stats: {
+        Numeric: {
+          distinct_count: 90,
+          min: 1,
+          max: 1000000,
+          p05: 10,
+          q1: 20,
+          median: 40,
+          mean: 20000,
+          trimmed_mean: 50,
+          q3: 80,
+          p95: 500,
+          std_dev: 100000,
+          skew: 3.2,
+          zero_count: 0,
+          negative_count: 0,
+          is_integer: true,
+          is_sorted: false,
+          is_sorted_rev: false,
+          bin_width: 50000,
+          histogram: [
+            [1, 99],
+            [500000, 1],
+          ],
+          log_histogram: {
+            bin_width: 1,
+            histogram: [
+              [1, 40],
+              [10, 50],
+              [100, 10],
+            ],
+            lower: 1,
+            upper: 1000000,
+            excluded_count: 0,
+          },
+          zoomed_histogram: {
+            bin_width: 245,
+            histogram: [
+              [10, 80],
+              [255, 10],
+            ],
+            lower: 10,
+            upper: 500,
+            excluded_count: 10,
+          },
+        },
+      },
+      interpretation: [],
+      ml_advice: [],
+      business_summary: [],
+      samples: [],
+    };
+
+    const result = renderDistribution(col);
+
+    expect(result).toContain('hist-view-toggle');
+    expect(result).toContain('data-view="log"');
+    expect(result).toContain('data-view="zoom"');
+    expect(result).toContain('P05–P95');
+    // Only the full-range view is visible initially
+    expect(result.match(/class="histogram" data-view="[a-z]+" hidden/g)).toHaveLength(2);
+  });
+
   it('should render categorical distribution correctly', () => {
     const col: ColumnSummary = {
       name: 'category',
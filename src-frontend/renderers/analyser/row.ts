@@ -1,6 +1,23 @@
-import { ColumnCleanConfig, ColumnSummary, LifecycleStage } from '../../types';
+import {
+  ColumnCleanConfig,
+  ColumnSummary,
+  GEO_ENCODING_LABELS,
+  LifecycleStage,
+  SEMANTIC_TYPE_LABELS,
+} from '../../types';
 import { escapeHtml } from '../../utils';
-import { CASE_OPTIONS, getImputeOptionsForColumn, NORM_OPTIONS, renderSelect } from '../common';
+import {
+  BIN_STRATEGY_OPTIONS,
+  CASE_OPTIONS,
+  DATE_PART_OPTIONS,
+  ENCODING_OPTIONS,
+  getImputeOptionsForColumn,
+  NORM_OPTIONS,
+  renderSelect,
+} from '../common';
+
+import { renderCleanPreviewPanel } from './clean-preview';
+import { renderDeepDivePanel } from './deep-dive';
 
 export function getUniqueCount(col: ColumnSummary): number {
   // Extract distinct count based on column stats type
@@ -13,6 +30,9 @@ export function getUniqueCount(col: ColumnSummary): number {
   if (col.stats.Temporal?.distinct_count !== undefined) {
     return col.stats.Temporal.distinct_count;
   }
+  if (col.stats.Geo?.distinct_count !== undefined) {
+    return col.stats.Geo.distinct_count;
+  }
   if (col.stats.Categorical) {
     return Object.keys(col.stats.Categorical).length;
   }
@@ -75,6 +95,11 @@ export function getMinMax(col: ColumnSummary): string {
   return '-';
 }
 
+export function getPercentile(col: ColumnSummary, percentile: number): string {
+  const value = col.stats.Numeric?.quantiles?.find(([p]) => p === percentile)?.[1];
+  return value !== undefined ? value.toFixed(1) : '-';
+}
+
 export function renderEnhancedStats(
   col: ColumnSummary,
   nullPct: number,
@@ -129,6 +154,20 @@ export function renderEnhancedStats(
             <span>Min / Max:</span>
             <span class="stat-value mono">${getMinMax(col)}</span>
           </div>
+          ${
+            col.stats.Numeric?.quantiles?.length
+              ? `
+          <div class="stat-row">
+            <span>P1 / P99:</span>
+            <span class="stat-value mono">${getPercentile(col, 1)} / ${getPercentile(col, 99)}</span>
+          </div>
+          <div class="stat-row">
+            <span>P10 / P90:</span>
+            <span class="stat-value mono">${getPercentile(col, 10)} / ${getPercentile(col, 90)}</span>
+          </div>
+          `
+              : ''
+          }
         </div>
       `
           : ''
@@ -178,6 +217,10 @@ export function renderAnalyserRow(
   const nullPct = (col.nulls / col.count) * 100;
   const uniqueCount = getUniqueCount(col);
   const uniquePct = (uniqueCount / col.count) * 100;
+  const temporal = col.stats.Temporal;
+  const dateFormat = temporal?.format
+    ? { format: temporal.format, ambiguous: temporal.format_ambiguous ?? false }
+    : null;
 
   const qualityClass =
     nullPct > 20 ? 'quality-poor' : nullPct > 5 ? 'quality-warn' : 'quality-good';
@@ -192,6 +235,20 @@ export function renderAnalyserRow(
           : 'ph-check-square';
 
   const isAdvancedStage = currentStage === 'Advanced' || advancedProcessingEnabled;
+  const mappedCount = Object.keys(config?.value_mapping ?? {}).length;
+  const mappingText = Object.entries(config?.value_mapping ?? {})
+    .map(([from, to]) => `${from} -> ${to}`)
+    .join('\n');
+  const split = config?.split;
+  const merge = config?.merge;
+  const colAttr = escapeHtml(col.name);
+  const isTemporal = col.kind === 'Temporal' || config?.target_dtype === 'Temporal';
+  const dateParts = config?.date_parts ?? [];
+  const isNumeric = col.kind === 'Numeric' || config?.target_dtype === 'Numeric';
+  const binning = config?.binning;
+  const binCount = binning && binning.strategy.kind !== 'custom' ? binning.strategy.bins : 4;
+  const binEdges = binning?.strategy.kind === 'custom' ? binning.strategy.edges.join(', ') : '';
+  const encoding = config?.encoding?.method;
 
   return `
     <div class="analyser-row ${isExpanded ? 'expanded' : ''} ${!config?.active ? 'inactive' : ''} ${isSelected ? 'selected' : ''}" data-col="${escapeHtml(col.name)}" data-testid="analyser-column-row">
@@ -209,6 +266,8 @@ export function renderAnalyserRow(
         </div>
         <div class="col-type" data-testid="analyser-column-type">
           <span class="type-badge">${col.kind}</span>
+          ${col.semantic_type ? `<span class="type-badge semantic-badge" data-testid="analyser-column-semantic-type">${SEMANTIC_TYPE_LABELS[col.semantic_type]}</span>` : ''}
+          ${col.stats.Geo ? `<span class="type-badge semantic-badge" data-testid="analyser-column-geo">${GEO_ENCODING_LABELS[col.stats.Geo.encoding.kind]}</span>` : ''}
         </div>
         <div class="col-quality" data-testid="analyser-column-quality">
           <div class="quality-bar-container">
@@ -219,6 +278,7 @@ export function renderAnalyserRow(
         <div class="col-stats-summary" data-testid="analyser-column-stats">
           <span class="stat-pill" data-testid="analyser-column-null-pct">Nulls: ${nullPct.toFixed(1)}%</span>
           <span class="stat-pill" data-testid="analyser-column-unique-pct">Unique: ${uniquePct.toFixed(1)}%</span>
+          ${dateFormat ? `<span class="stat-pill" data-testid="analyser-column-date-format" title="${dateFormat.ambiguous ? 'Day and month order chosen by the date order setting' : 'Read from text'}">Format: ${escapeHtml(dateFormat.format)}${dateFormat.ambiguous ? ' ?' : ''}</span>` : ''}
         </div>
         <div class="col-actions">
            ${
@@ -239,6 +299,7 @@ export function renderAnalyserRow(
             ${renderEnhancedStats(col, nullPct, uniqueCount, uniquePct)}
             ${renderDistribution(col)}
             ${renderInsights(col)}
+            ${renderDeepDivePanel(col)}
           </div>
 
           ${
@@ -249,7 +310,12 @@ export function renderAnalyserRow(
 
             <div class="config-section">
               <label>Target Name</label>
-              <input type="text" class="config-input config-name" value="${escapeHtml(config.new_name)}" placeholder="Enter new name...">
+              <input type="text" class="config-input config-name row-action" data-col="${colAttr}" data-prop="new_name" value="${escapeHtml(config.new_name)}" placeholder="Enter new name...">
+              ${
+                col.suggested_name && col.suggested_name !== config.new_name
+                  ? `<button type="button" class="btn-secondary btn-small btn-use-suggested-name row-action" data-col="${colAttr}" data-name="${escapeHtml(col.suggested_name)}" title="Suggested from the column's values" data-testid="analyser-suggested-name">Use <code>${escapeHtml(col.suggested_name)}</code></button>`
+                  : ''
+              }
             </div>
 
             <div class="config-grid">
@@ -269,6 +335,9 @@ export function renderAnalyserRow(
                     <span>Remove Special Chars</span>
                   </label>
                 </div>
+                <button type="button" class="btn-secondary btn-small btn-standardise-values row-action" data-col="${escapeHtml(col.name)}" title="Group near-duplicate spellings of the same value and map them to one">
+                  <i class="ph ph-arrows-merge"></i> Standardise Values${mappedCount > 0 ? ` (${mappedCount} mapped)` : ''}
+                </button>
               </div>
 
               <div class="config-section">
@@ -277,6 +346,83 @@ export function renderAnalyserRow(
               </div>
             </div>
 
+            <div class="config-section">
+              <label>Recode Values</label>
+              <textarea class="config-input config-value-mapping row-action" data-col="${escapeHtml(col.name)}" data-prop="value_mapping" rows="3" placeholder="One per line: M -> Male">${escapeHtml(mappingText)}</textarea>
+            </div>
+
+            <div class="config-grid">
+              <div class="config-section">
+                <label>Split Into Columns</label>
+                <input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="split_into" value="${escapeHtml(split?.into.join(', ') ?? '')}" placeholder="New names, e.g. first, last">
+                <input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="split_delimiter" value="${escapeHtml(split?.delimiter ?? ' ')}" placeholder="Delimiter">
+                <div class="checkbox-group">
+                  <label class="checkbox-control">
+                    <input type="checkbox" class="row-action" data-col="${colAttr}" data-prop="split_regex" ${split?.regex ? 'checked' : ''}>
+                    <span>Delimiter is a regex</span>
+                  </label>
+                  <label class="checkbox-control">
+                    <input type="checkbox" class="row-action" data-col="${colAttr}" data-prop="split_drop_original" ${split?.drop_original ? 'checked' : ''}>
+                    <span>Drop this column</span>
+                  </label>
+                </div>
+              </div>
+
+              <div class="config-section">
+                <label>Merge Into Column</label>
+                <input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="merge_into" value="${escapeHtml(merge?.into ?? '')}" placeholder="New column name">
+                <input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="merge_template" value="${escapeHtml(merge?.template ?? '')}" placeholder="Template, e.g. {first} {last}">
+                <div class="checkbox-group">
+                  <label class="checkbox-control">
+                    <input type="checkbox" class="row-action" data-col="${colAttr}" data-prop="merge_drop_sources" ${merge?.drop_sources ? 'checked' : ''}>
+                    <span>Drop merged columns</span>
+                  </label>
+                </div>
+              </div>
+            </div>
+
+            ${
+              isTemporal
+                ? `
+            <div class="config-section">
+              <label>Date Features</label>
+              <div class="checkbox-group">
+                ${DATE_PART_OPTIONS.map(
+                  opt => `
+                <label class="checkbox-control">
+                  <input type="checkbox" class="row-action" data-col="${colAttr}" data-prop="date_parts" data-part="${opt.value}" ${dateParts.includes(opt.value) ? 'checked' : ''}>
+                  <span>${opt.label}</span>
+                </label>`
+                ).join('')}
+              </div>
+            </div>
+            `
+                : ''
+            }
+
+            ${
+              isNumeric
+                ? `
+            <div class="config-section">
+              <label>Bins</label>
+              ${renderSelect(BIN_STRATEGY_OPTIONS, binning?.strategy.kind ?? 'none', 'config-input row-action', { col: col.name, prop: 'bin_strategy' })}
+              ${
+                binning?.strategy.kind === 'custom'
+                  ? `<input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="bin_edges" value="${escapeHtml(binEdges)}" placeholder="Edges, e.g. 0, 18, 65, 120">`
+                  : binning
+                    ? `<input type="number" class="config-input row-action" data-col="${colAttr}" data-prop="bin_count" min="1" step="1" value="${binCount}">`
+                    : ''
+              }
+              ${
+                binning
+                  ? `<input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="bin_labels" value="${escapeHtml(binning.labels.join(', '))}" placeholder="Labels (optional), e.g. low, medium, high">`
+                  : ''
+              }
+            </div>
+            `
+                : ''
+            }
+
             ${
               isAdvancedStage
                 ? `
@@ -307,9 +453,30 @@ export function renderAnalyserRow(
                   </label>
                 </div>
               </div>
+              ${
+                !isNumeric
+                  ? `
+              <div class="config-section" title="How ML preprocessing turns categories into numbers">
+                <label>Category Encoding</label>
+                ${renderSelect(ENCODING_OPTIONS, encoding?.kind ?? 'one_hot', 'config-input row-action', { col: col.name, prop: 'encode_method' })}
+                ${
+                  encoding?.kind === 'target'
+                    ? `
+                <input type="text" class="config-input row-action" data-col="${colAttr}" data-prop="encode_target" value="${escapeHtml(encoding.target)}" placeholder="Target column">
+                <input type="number" class="config-input row-action" data-col="${colAttr}" data-prop="encode_smoothing" min="0" step="1" value="${encoding.smoothing}" title="Smoothing towards the overall mean">
+                <input type="number" class="config-input row-action" data-col="${colAttr}" data-prop="encode_folds" min="2" step="1" value="${encoding.folds}" title="Folds">
+                `
+                    : ''
+                }
+              </div>
+              `
+                  : ''
+              }
             `
                 : ''
             }
+
+            ${renderCleanPreviewPanel(col)}
           </div>
           `
               : ''
@@ -320,15 +487,10 @@ export function renderAnalyserRow(
   `;
 }
 
-export function renderDistribution(col: ColumnSummary): string {
-  if (col.kind === 'Numeric' && col.stats.Numeric?.histogram) {
-    const hist = col.stats.Numeric.histogram;
-    const maxCount = Math.max(...hist.map(h => h[1])); // h[1] is count in 2-tuple
-
-    return `
-      <div class="distribution-chart">
-        <h5>Distribution</h5>
-        <div class="histogram">
+function renderHistogramBars(hist: [number, number][], view: string, hidden: boolean): string {
+  const maxCount = Math.max(...hist.map(h => h[1])); // h[1] is count in 2-tuple
+  return `
+        <div class="histogram" data-view="${view}"${hidden ? ' hidden' : ''}>
           ${hist
             .map(
               ([binCentre, count]) => `
@@ -336,7 +498,51 @@ export function renderDistribution(col: ColumnSummary): string {
           `
             )
             .join('')}
-        </div>
+        </div>`;
+}
+
+export function renderDistribution(col: ColumnSummary): string {
+  if (col.kind === 'Numeric' && col.stats.Numeric?.histogram) {
+    const stats = col.stats.Numeric;
+    const hist = stats.histogram ?? [];
+    const views: { id: string; label: string; title: string; hist: [number, number][] }[] = [
+      { id: 'full', label: 'Full', title: 'Full value range', hist },
+    ];
+    if (stats.log_histogram) {
+      views.push({
+        id: 'log',
+        label: 'Log',
+        title: 'Bins spaced on a log10 scale',
+        hist: stats.log_histogram.histogram,
+      });
+    }
+    if (stats.zoomed_histogram) {
+      const excluded = stats.zoomed_histogram.excluded_count;
+      views.push({
+        id: 'zoom',
+        label: 'P05–P95',
+        title: `Middle 90% of values (${excluded.toLocaleString()} outside range)`,
+        hist: stats.zoomed_histogram.histogram,
+      });
+    }
+
+    const toggle =
+      views.length > 1
+        ? `
+        <div class="hist-view-toggle">
+          ${views
+            .map(
+              (v, i) =>
+                `<button type="button" class="hist-view-btn${i === 0 ? ' active' : ''}" data-view="${v.id}" title="${v.title}">${v.label}</button>`
+            )
+            .join('')}
+        </div>`
+        : '';
+
+    return `
+      <div class="distribution-chart">
+        <h5>Distribution</h5>${toggle}
+        ${views.map((v, i) => renderHistogramBars(v.hist, v.id, i > 0)).join('')}
       </div>
     `;
   }
@@ -0,0 +1,69 @@
+import { ValueStandardisation } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/**
+ * Clusters of near-duplicate values in one column. Each cluster can be
+ * left out or given another canonical spelling before the mapping is
+ * applied to the column's config.
+ */
+export function renderStandardiseModal(result: ValueStandardisation): string {
+  const clusters =
+    result.clusters.length === 0
+      ? '<p class="modal-description">No near-duplicate values found.</p>'
+      : `
+        <table class="standardise-table">
+          <thead>
+            <tr><th></th><th>Map to</th><th>Values</th><th>Rows</th></tr>
+          </thead>
+          <tbody>
+            ${result.clusters
+              .map(
+                (cluster, idx) => `
+              <tr data-cluster="${idx}">
+                <td><input type="checkbox" class="standardise-include" checked aria-label="Apply this cluster"></td>
+                <td>
+                  <input type="text" class="standardise-canonical" value="${escapeHtml(cluster.canonical)}" aria-label="Canonical value">
+                </td>
+                <td class="standardise-values">
+                  ${cluster.values
+                    .map(
+                      v =>
+                        `<span class="standardise-value">${escapeHtml(v.value)} <small>(${v.count})</small></span>`
+                    )
+                    .join('')}
+                </td>
+                <td>${cluster.total}</td>
+              </tr>`
+              )
+              .join('')}
+          </tbody>
+        </table>`;
+
+  const truncated = result.truncated
+    ? ` Only the most frequent of its ${result.distinct_values} distinct values were compared.`
+    : '';
+
+  return `
+    <div class="modal-overlay" data-testid="standardise-modal-overlay">
+      <div class="modal-content" data-testid="standardise-modal">
+        <div class="modal-header">
+          <h3>Standardise Values: ${escapeHtml(result.column)}</h3>
+          <button class="modal-close" id="modal-close">
+            <i class="ph ph-x"></i>
+          </button>
+        </div>
+        <div class="modal-body">
+          <p class="modal-description">
+            Values that differ only in case, punctuation, word order or a small typo, and abbreviations of a longer value, are grouped below.${truncated}
+          </p>
+          ${clusters}
+        </div>
+        <div class="modal-footer">
+          <button id="btn-apply-standardise" class="btn-primary btn-small" ${result.clusters.length === 0 ? 'disabled' : ''}>
+            <i class="ph ph-check"></i> Apply mapping
+          </button>
+        </div>
+      </div>
+    </div>
+  `;
+}
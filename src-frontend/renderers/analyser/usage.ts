@@ -0,0 +1,55 @@
+import { UsageSuggestion } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/**
+ * Defaults suggested by how this file's columns were cleaned before, each
+ * with a checkbox to pick which to apply.
+ */
+export function renderUsageModal(suggestions: UsageSuggestion[]): string {
+  const list =
+    suggestions.length === 0
+      ? '<p class="modal-description">No suggestions yet. They appear once this file has been cleaned a few times the same way.</p>'
+      : `
+        <table class="preset-table">
+          <tbody>
+            ${suggestions
+              .map(
+                (suggestion, i) => `
+              <tr>
+                <td>
+                  <input type="checkbox" class="usage-suggestion" data-index="${i}" id="usage-suggestion-${i}" checked>
+                </td>
+                <td><label for="usage-suggestion-${i}">${escapeHtml(suggestion.message)}</label></td>
+              </tr>`
+              )
+              .join('')}
+          </tbody>
+        </table>`;
+
+  return `
+    <div class="modal-overlay" data-testid="usage-modal-overlay">
+      <div class="modal-content" data-testid="usage-modal">
+        <div class="modal-header">
+          <h3>Suggested Defaults</h3>
+          <button class="modal-close" id="modal-close">
+            <i class="ph ph-x"></i>
+          </button>
+        </div>
+        <div class="modal-body">
+          <p class="modal-description">
+            Based on the choices made in most earlier cleans of this file.
+          </p>
+          ${list}
+          <div class="preset-save">
+            <button id="btn-clear-usage" class="btn-ghost btn-small">
+              <i class="ph ph-eraser"></i> Forget history
+            </button>
+            <button id="btn-apply-usage" class="btn-primary btn-small" ${suggestions.length === 0 ? 'disabled' : ''}>
+              <i class="ph ph-check"></i> Apply selected
+            </button>
+          </div>
+        </div>
+      </div>
+    </div>
+  `;
+}
@@ -0,0 +1,71 @@
+import { WorkbookState } from '../../types';
+import { escapeHtml } from '../../utils';
+
+/**
+ * Sheets of the open workbook. Before every sheet is analysed this offers to
+ * do so; afterwards it compares the sheets and switches between them.
+ */
+export function renderWorkbookPanel(workbook: WorkbookState, currentPath: string): string {
+  const { analysis } = workbook;
+  if (!analysis) {
+    return `
+      <div class="stage-banner stage-banner-readonly workbook-banner" data-testid="analyser-workbook">
+        <i class="ph ph-microsoft-excel-logo"></i>
+        <div>
+          <strong>Workbook with ${workbook.sheets.length} sheets</strong>
+          <span>Showing <span class="mono">${escapeHtml(workbook.sheets[0])}</span>. Analyse every sheet to compare them and switch between their results.</span>
+        </div>
+        <button id="btn-analyse-workbook" class="btn-secondary btn-small">
+          <i class="ph ph-stack"></i> Analyse all sheets
+        </button>
+      </div>
+    `;
+  }
+
+  const rows = analysis.overview.sheets
+    .map((sheet, index) => {
+      const response = analysis.sheets[index];
+      const active =
+        response?.path === currentPath || (index === 0 && currentPath === workbook.path);
+      return `
+        <tr class="${active ? 'active' : ''}">
+          <td>${escapeHtml(sheet.name)}</td>
+          <td>${sheet.row_count.toLocaleString()}</td>
+          <td>${sheet.column_count}</td>
+          <td>
+            <button class="btn-ghost btn-small btn-workbook-sheet" data-index="${index}" ${active ? 'disabled' : ''}>
+              ${active ? 'Showing' : 'View'}
+            </button>
+          </td>
+        </tr>
+      `;
+    })
+    .join('');
+
+  const shared = analysis.overview.shared_columns.length
+    ? `<p class="workbook-shared"><strong>Shared columns:</strong> ${analysis.overview.shared_columns
+        .map(
+          c =>
+            `<span class="mono" title="${escapeHtml(c.sheets.join(', '))}">${escapeHtml(c.name)} (${c.sheets.length})</span>`
+        )
+        .join(', ')}</p>`
+    : '<p class="workbook-shared">No column names are shared between sheets.</p>';
+
+  const failed = analysis.failed.length
+    ? `<ul class="workbook-failed">${analysis.failed
+        .map(f => `<li><strong>${escapeHtml(f.sheet)}:</strong> ${escapeHtml(f.error)}</li>`)
+        .join('')}</ul>`
+    : '';
+
+  return `
+    <div class="workbook-panel" data-testid="analyser-workbook">
+      <h3><i class="ph ph-microsoft-excel-logo"></i> Workbook overview</h3>
+      <table class="workbook-sheets">
+        <thead><tr><th>Sheet</th><th>Rows</th><th>Columns</th><th></th></tr></thead>
+        <tbody>${rows}</tbody>
+      </table>
+      ${shared}
+      ${failed}
+    </div>
+  `;
+}
@@ -1,3 +1,4 @@
+import { BinStrategy, DatePart, EncodingMethod } from '../types';
 import { escapeHtml } from '../utils';
 
 export const IMPUTE_OPTIONS = [
@@ -43,6 +44,32 @@ export const CASE_OPTIONS = [
   { value: 'TitleCase', label: 'Title' },
 ];
 
+export const DATE_PART_OPTIONS: { value: DatePart; label: string }[] = [
+  { value: 'year', label: 'Year' },
+  { value: 'quarter', label: 'Quarter' },
+  { value: 'month', label: 'Month' },
+  { value: 'week', label: 'ISO week' },
+  { value: 'day', label: 'Day of month' },
+  { value: 'weekday', label: 'Weekday' },
+  { value: 'day_of_year', label: 'Day of year' },
+  { value: 'hour', label: 'Hour' },
+  { value: 'minute', label: 'Minute' },
+  { value: 'is_weekend', label: 'Is weekend' },
+];
+
+export const BIN_STRATEGY_OPTIONS: { value: BinStrategy['kind'] | 'none'; label: string }[] = [
+  { value: 'none', label: 'None' },
+  { value: 'equal_width', label: 'Equal width' },
+  { value: 'quantile', label: 'Quantiles' },
+  { value: 'custom', label: 'Custom edges' },
+];
+
+export const ENCODING_OPTIONS: { value: EncodingMethod['kind'] | 'one_hot'; label: string }[] = [
+  { value: 'one_hot', label: 'One-hot' },
+  { value: 'ordinal', label: 'Ordinal' },
+  { value: 'target', label: 'Target mean' },
+];
+
 export const ROUND_OPTIONS = [
   { value: 'none', label: 'None' },
   { value: '0', label: '0' },
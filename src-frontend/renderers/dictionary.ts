@@ -1,9 +1,21 @@
-import { DataDictionary, SnapshotMetadata } from '../types';
+import {
+  DataDictionary,
+  DictionarySearchHit,
+  DictionaryStorageUsage,
+  ReviewStatus,
+  SnapshotMetadata,
+} from '../types';
+import { escapeHtml, fmtBytes } from '../utils';
 
 /**
  * Render the Data Dictionary list view showing all snapshots.
  */
-export function renderDictionaryList(snapshots: SnapshotMetadata[]): string {
+export function renderDictionaryList(
+  snapshots: SnapshotMetadata[],
+  usage: DictionaryStorageUsage | null = null,
+  searchQuery = '',
+  reviews: ReviewStatus[] = []
+): string {
   if (snapshots.length === 0) {
     return `
       <div class="empty-state">
@@ -53,12 +65,20 @@ export function renderDictionaryList(snapshots: SnapshotMetadata[]): string {
       <div class="dictionary-header">
         <h1>Data Dictionary</h1>
         <div class="header-actions">
+          ${renderStorageUsage(usage)}
           <button id="btn-refresh-snapshots" class="btn-secondary">
             <i class="ph ph-arrow-clockwise"></i> Refresh
           </button>
         </div>
       </div>
 
+      <div class="dictionary-search">
+        <i class="ph ph-magnifying-glass"></i>
+        <input type="search" id="dictionary-search-input" value="${escapeHtml(searchQuery)}" placeholder="Search datasets and columns, e.g. VAT rate">
+      </div>
+      <div id="dictionary-search-results"></div>
+      ${renderReviewReminders(reviews)}
+
       <table class="snapshots-table">
         <thead>
           <tr>
@@ -77,6 +97,99 @@ export function renderDictionaryList(snapshots: SnapshotMetadata[]): string {
   `;
 }
 
+/**
+ * Matches of a dictionary search; clicking one opens its snapshot.
+ */
+export function renderDictionarySearchResults(query: string, hits: DictionarySearchHit[]): string {
+  if (!query.trim()) return '';
+  if (hits.length === 0) {
+    return `<p class="dictionary-search-empty">No datasets or columns match "${escapeHtml(query)}".</p>`;
+  }
+
+  const rows = hits
+    .map(hit => {
+      const target = hit.column
+        ? `${escapeHtml(hit.dataset_name)}.<strong>${escapeHtml(hit.column)}</strong>`
+        : `<strong>${escapeHtml(hit.dataset_name)}</strong>`;
+      const snippet =
+        hit.snippet && hit.snippet !== (hit.column ?? hit.dataset_name)
+          ? `<div class="search-hit-snippet">${escapeHtml(hit.snippet)}</div>`
+          : '';
+      return `
+      <li class="search-hit" data-snapshot-id="${hit.snapshot_id}">
+        <div class="search-hit-target">
+          <i class="ph ${hit.column ? 'ph-columns' : 'ph-table'}"></i> ${target}
+          <span class="search-hit-fields">${hit.matched_fields.map(f => f.replace(/_/g, ' ')).join(', ')}</span>
+        </div>
+        ${snippet}
+      </li>
+    `;
+    })
+    .join('');
+
+  return `<ul class="dictionary-search-results">${rows}</ul>`;
+}
+
+/**
+ * Datasets due for a documentation review, each with a button to mark it
+ * reviewed.
+ */
+function renderReviewReminders(reviews: ReviewStatus[]): string {
+  const due = reviews.filter(r => r.days_overdue >= 0);
+  if (due.length === 0) return '';
+
+  const rows = due
+    .map(r => {
+      const when = r.last_reviewed
+        ? r.days_overdue === 0
+          ? 'due today'
+          : `${r.days_overdue} day${r.days_overdue === 1 ? '' : 's'} overdue`
+        : 'never reviewed';
+      const people = [r.owner && `Owner: ${r.owner}`, r.steward && `Steward: ${r.steward}`]
+        .filter(Boolean)
+        .join(', ');
+      return `
+      <li>
+        <strong>${escapeHtml(r.dataset_name)}</strong>
+        <span class="review-due">${when}</span>
+        ${people ? `<span class="review-people">${escapeHtml(people)}</span>` : ''}
+        <button class="btn-secondary btn-small btn-mark-reviewed" data-snapshot-id="${r.snapshot_id}">
+          <i class="ph ph-check"></i> Mark Reviewed
+        </button>
+      </li>
+    `;
+    })
+    .join('');
+
+  return `
+    <div class="dictionary-reviews">
+      <h3><i class="ph ph-calendar-check"></i> Due for Review (${due.length})</h3>
+      <ul>${rows}</ul>
+    </div>
+  `;
+}
+
+/**
+ * Snapshot count and size, with a button to remove duplicate snapshots when
+ * there are any.
+ */
+function renderStorageUsage(usage: DictionaryStorageUsage | null): string {
+  if (!usage) return '';
+  const duplicates = usage.datasets.reduce((sum, d) => sum + d.duplicates, 0);
+  const compact =
+    duplicates > 0
+      ? `<button id="btn-compact-snapshots" class="btn-secondary" title="Remove snapshots identical to a newer snapshot of the same dataset">
+          <i class="ph ph-broom"></i> Remove ${duplicates} Duplicate${duplicates === 1 ? '' : 's'}
+        </button>`
+      : '';
+  return `
+    <span class="dictionary-storage" title="${usage.datasets.length} datasets">
+      <i class="ph ph-hard-drives"></i> ${usage.snapshot_count} snapshots, ${fmtBytes(usage.total_bytes)}
+    </span>
+    ${compact}
+  `;
+}
+
 /**
  * Calculate documentation completeness percentage.
  */
@@ -155,6 +268,30 @@ export function renderDictionaryDetail(snapshot: DataDictionary): string {
           </div>
         </div>
 
+        <div class="form-row">
+          <div class="form-group">
+            <label for="owner">Owner</label>
+            <input type="text" id="owner" name="owner" placeholder="Person or team accountable" value="${escapeHtml(business.owner ?? '')}">
+          </div>
+
+          <div class="form-group">
+            <label for="steward">Steward</label>
+            <input type="text" id="steward" name="steward" placeholder="Person keeping it current" value="${escapeHtml(business.steward ?? '')}">
+          </div>
+        </div>
+
+        <div class="form-row">
+          <div class="form-group">
+            <label for="review_cadence_days">Review Every (days)</label>
+            <input type="number" id="review_cadence_days" name="review_cadence_days" min="1" placeholder="Not tracked" value="${business.review_cadence_days ?? ''}">
+          </div>
+
+          <div class="form-group">
+            <label for="last_reviewed">Last Reviewed</label>
+            <input type="date" id="last_reviewed" name="last_reviewed" value="${business.last_reviewed ?? ''}">
+          </div>
+        </div>
+
         <div class="form-row">
           <div class="form-group">
             <label for="sensitivity_classification">Sensitivity</label>
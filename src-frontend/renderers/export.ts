@@ -1,8 +1,11 @@
 import { DbConnection, ExportSource } from '../types';
 import { escapeHtml } from '../utils';
 
+/** Where the export modal writes: a new file, a database, or the source file's own location */
+export type ExportDestChoice = 'File' | 'Database' | 'InPlace';
+
 export function renderExportConfig(
-  destType: 'File' | 'Database',
+  destType: ExportDestChoice,
   connections: DbConnection[],
   activeExportId: string | null | undefined
 ): string {
@@ -30,6 +33,29 @@ export function renderExportConfig(
         </label>
         <p class="help-text">SHA-256 cryptographic hash for tamper detection (.receipt.json)</p>
       </div>
+      <div class="export-step">
+        <label class="checkbox-label">
+          <input type="checkbox" id="export-write-rejects">
+          <span>Write rejected rows to a separate file</span>
+        </label>
+        <p class="help-text">Rows with values that fail type conversion go to a .rejects file with a reject_reason column</p>
+      </div>
+    `;
+  } else if (destType === 'InPlace') {
+    return `
+      <div class="export-step">
+        <label>2. Choose How to Write Back</label>
+        <label class="checkbox-label">
+          <input type="radio" name="in-place-mode" id="in-place-suffix" value="suffix" checked>
+          <span>Save beside the original with the suffix</span>
+          <input type="text" id="in-place-suffix-text" value="_cleaned" data-testid="in-place-suffix-input">
+        </label>
+        <label class="checkbox-label">
+          <input type="radio" name="in-place-mode" id="in-place-replace" value="replace">
+          <span>Replace the original (a timestamped .bak copy is kept beside it)</span>
+        </label>
+        <p class="help-text">Keeps the original format, CSV delimiter, encoding and line endings</p>
+      </div>
     `;
   } else {
     return `
@@ -54,7 +80,7 @@ export function renderExportModal(
   source: ExportSource,
   connections: DbConnection[],
   activeExportId: string | null | undefined,
-  destType: 'File' | 'Database',
+  destType: ExportDestChoice,
   isLoading: boolean,
   isAborting: boolean
 ): string {
@@ -75,6 +101,13 @@ export function renderExportModal(
               <button type="button" class="toggle-btn ${destType === 'Database' ? 'active' : ''}" data-dest="Database" data-testid="export-dest-database">
                 <i class="ph ph-database"></i> Database
               </button>
+              ${
+                source.type === 'Analyser' && source.path
+                  ? `<button type="button" class="toggle-btn ${destType === 'InPlace' ? 'active' : ''}" data-dest="InPlace" data-testid="export-dest-in-place">
+                <i class="ph ph-arrows-clockwise"></i> Original Location
+              </button>`
+                  : ''
+              }
             </div>
           </div>
 
@@ -82,6 +115,14 @@ export function renderExportModal(
             ${renderExportConfig(destType, connections, activeExportId)}
           </div>
 
+          <div class="export-step">
+            <label class="checkbox-label">
+              <input type="checkbox" id="export-completeness-score">
+              <span>Add row completeness score</span>
+            </label>
+            <p class="help-text">Appends a row_completeness column (fraction of non-null fields) for filtering low-quality records</p>
+          </div>
+
           <div class="export-summary">
             <h4>Export Summary</h4>
             <div class="summary-grid">
@@ -1,4 +1,10 @@
-import { DatasetVersion, LifecycleStage, DiffSummary, CurrentDataset } from '../types';
+import {
+  ApprovalState,
+  DatasetVersion,
+  LifecycleStage,
+  DiffSummary,
+  CurrentDataset,
+} from '../types';
 import { escapeHtml } from '../utils';
 
 export interface StageConfig {
@@ -264,6 +270,7 @@ export function renderPublishModal(): string {
         <div class="modal-body">
           <p class="modal-description">
             Choose how to publish this dataset version. This action creates a new published version.
+            Only versions that have been submitted for review and approved can be published.
           </p>
 
           <div class="publish-mode-options">
@@ -308,6 +315,38 @@ export function renderPublishModal(): string {
   `;
 }
 
+const APPROVAL_LABELS: Record<ApprovalState, string> = {
+  Draft: 'Draft',
+  InReview: 'In Review',
+  Approved: 'Approved',
+  Published: 'Published',
+};
+
+/** Approval badge plus the buttons for the moves open to the version */
+function renderApproval(version: DatasetVersion): { badge: string; actions: string } {
+  const approval = version.approval ?? { state: 'Draft', transitions: [] };
+  const last = approval.transitions[approval.transitions.length - 1];
+  const title = last
+    ? `${APPROVAL_LABELS[last.to]} by ${last.actor} on ${new Date(last.at).toLocaleString()}${last.comment ? `: ${last.comment}` : ''}`
+    : 'Not yet submitted for review';
+  const badge = `<span class="badge badge-approval badge-approval-${approval.state.toLowerCase()}" title="${escapeHtml(title)}">${APPROVAL_LABELS[approval.state]}</span>`;
+
+  if (version.stage === 'Published' || version.stage === 'Raw') {
+    return { badge: version.stage === 'Raw' ? '' : badge, actions: '' };
+  }
+  const button = (to: ApprovalState, label: string): string =>
+    `<button class="btn btn-small" data-action="approval" data-approval-state="${to}" data-version-id="${escapeHtml(version.id)}">${label}</button>`;
+  const actions =
+    approval.state === 'Draft'
+      ? button('InReview', 'Submit for Review')
+      : approval.state === 'InReview'
+        ? `${button('Approved', 'Approve')}${button('Draft', 'Request Changes')}`
+        : approval.state === 'Approved'
+          ? button('Draft', 'Withdraw Approval')
+          : '';
+  return { badge, actions };
+}
+
 export function renderVersionTree(dataset: CurrentDataset): string {
   // Sort versions by stage order first, then by creation time
   const stageOrder: Record<string, number> = {
@@ -334,6 +373,7 @@ export function renderVersionTree(dataset: CurrentDataset): string {
       const isActive = version.id === dataset.activeVersionId;
       const stageConfig = getStageConfig(version.stage);
       const stageColor = getStageColor(version.stage);
+      const approval = renderApproval(version);
 
       return `
       <div class="version-tree-node ${isActive ? 'version-tree-node-active' : ''}" data-version-id="${escapeHtml(version.id)}">
@@ -346,6 +386,7 @@ export function renderVersionTree(dataset: CurrentDataset): string {
             <div class="version-tree-title">
               <strong>v${index} ${escapeHtml(stageConfig.label)}</strong>
               ${isActive ? '<span class="badge badge-active">Active</span>' : ''}
+              ${approval.badge}
             </div>
             <div class="version-tree-meta">
               ${new Date(version.created_at).toLocaleString()}
@@ -355,7 +396,9 @@ export function renderVersionTree(dataset: CurrentDataset): string {
             <div class="version-tree-description">${escapeHtml(version.metadata.description)}</div>
           </div>
           <div class="version-tree-actions">
+            ${approval.actions}
             ${!isActive ? `<button class="btn btn-small" data-action="set-active" data-version-id="${escapeHtml(version.id)}" data-testid="lifecycle-set-active-${escapeHtml(version.id)}">Set Active</button>` : ''}
+            <button class="btn btn-small" data-action="export-viewer" data-version-id="${escapeHtml(version.id)}" title="Save a standalone HTML page for people without the app">Export Viewer</button>
             ${index > 0 ? `<button class="btn btn-small" data-action="view-diff" data-version-id="${escapeHtml(version.id)}" data-testid="lifecycle-view-diff-${escapeHtml(version.id)}">View Diff</button>` : `<button class="btn btn-small" disabled title="Cannot compute diff - this is the first version with no parent">View Diff</button>`}
           </div>
         </div>
@@ -1,4 +1,4 @@
-import { AppConfig, StandardPaths } from '../types';
+import { AppConfig, RunLogEntry, RunSummary, StandardPaths } from '../types';
 import { escapeHtml } from '../utils';
 
 export function renderSettingsView(
@@ -22,7 +22,7 @@ export function renderSettingsView(
               conn => `
             <div class="connection-card">
               <div class="conn-info">
-                <strong>${escapeHtml(conn.name)}</strong>
+                <strong>${escapeHtml(conn.name)}</strong>${conn.read_only ? ' <span class="badge">Read-only</span>' : ''}
                 <span>${escapeHtml(conn.settings.host)}:${conn.settings.port} / ${escapeHtml(conn.settings.database)}</span>
               </div>
               <div class="conn-actions">
@@ -49,6 +49,9 @@ export function renderSettingsView(
               <input type="text" id="new-conn-db" placeholder="Database">
               <input type="text" id="new-conn-schema" value="public" placeholder="Schema (public)">
               <input type="text" id="new-conn-table" placeholder="Table">
+              <label for="new-conn-read-only">
+                <input type="checkbox" id="new-conn-read-only"> Read-only (block pushes and DDL)
+              </label>
             </div>
             <div class="form-actions">
               <button id="btn-test-new-conn" class="btn-secondary"><i class="ph ph-plugs-connected"></i> Test</button>
@@ -125,6 +128,76 @@ export function renderSettingsView(
             </select>
             <div id="sampling-strategy-info" class="sampling-strategy-info" role="status" aria-live="polite"></div>
           </div>
+          <div class="pref-item">
+            <label for="duplicate-column-policy">
+              Repeated Column Names
+              <i class="ph ph-info help-icon" title="What loading a CSV does when the header repeats a name. The analyser shows which file column each name came from." aria-label="Help: Repeated column names"></i>
+            </label>
+            <select id="duplicate-column-policy">
+              ${(
+                [
+                  ['auto_suffix', 'Keep all, rename repeats (name_2, name_3...)'],
+                  ['keep_first', 'Keep the first column with each name'],
+                  ['keep_last', 'Keep the last column with each name'],
+                  ['error', 'Refuse to load the file'],
+                ] as const
+              )
+                .map(
+                  ([value, label]) =>
+                    `<option value="${value}" ${(config.settings.duplicate_column_policy ?? 'auto_suffix') === value ? 'selected' : ''}>${label}</option>`
+                )
+                .join('')}
+            </select>
+          </div>
+          <div class="pref-item">
+            <label for="auto-transpose">
+              Auto-transpose Record-Oriented Files
+              <i class="ph ph-info help-icon" title="Swap rows and columns before analysis when a file looks like it holds variables as rows and observations as columns" aria-label="Help: Auto-transpose"></i>
+            </label>
+            <input type="checkbox" id="auto-transpose" ${config.settings.auto_transpose ? 'checked' : ''}>
+          </div>
+          <div class="pref-item">
+            <label for="layout-fill-merged-headers">
+              Name Merged Header Cells
+              <i class="ph ph-info help-icon" title="Name blank header cells after a merged header in CSV files and workbooks, e.g. Q1, Q1_2, Q1_3" aria-label="Help: Name merged header cells"></i>
+            </label>
+            <input type="checkbox" id="layout-fill-merged-headers" ${(config.settings.layout?.fill_merged_headers ?? true) ? 'checked' : ''}>
+          </div>
+          <div class="pref-item">
+            <label for="layout-drop-footer-rows">
+              Drop Totals and Footer Rows
+              <i class="ph ph-info help-icon" title="Drop trailing Total rows and blank rows from CSV files and workbooks when loading" aria-label="Help: Drop totals and footer rows"></i>
+            </label>
+            <input type="checkbox" id="layout-drop-footer-rows" ${(config.settings.layout?.drop_footer_rows ?? true) ? 'checked' : ''}>
+          </div>
+          <div class="pref-item">
+            <label for="dictionary-keep-last">
+              Dictionary Snapshots Kept per Dataset
+              <i class="ph ph-info help-icon" title="Newest snapshots kept for each dataset after an automatic snapshot. Leave blank to keep all; the newest is always kept." aria-label="Help: Dictionary snapshots kept"></i>
+            </label>
+            <input type="number" id="dictionary-keep-last" min="1" placeholder="All" value="${config.settings.dictionary_retention?.keep_last ?? ''}">
+          </div>
+          <div class="pref-item">
+            <label for="dictionary-max-age">
+              Dictionary Snapshot Max Age (days)
+              <i class="ph ph-info help-icon" title="Older snapshots are removed after an automatic snapshot. Leave blank to keep them; the newest of each dataset is always kept." aria-label="Help: Dictionary snapshot max age"></i>
+            </label>
+            <input type="number" id="dictionary-max-age" min="1" placeholder="No limit" value="${config.settings.dictionary_retention?.max_age_days ?? ''}">
+          </div>
+          <div class="pref-item">
+            <label for="dictionary-compact">
+              Remove Duplicate Dictionary Snapshots
+              <i class="ph ph-info help-icon" title="Remove a snapshot when the next one of the same dataset is identical apart from its time" aria-label="Help: Remove duplicate dictionary snapshots"></i>
+            </label>
+            <input type="checkbox" id="dictionary-compact" ${config.settings.dictionary_retention?.compact ? 'checked' : ''}>
+          </div>
+          <div class="pref-item">
+            <label for="dictionary-review-reminders">
+              Dataset Review Reminders
+              <i class="ph ph-info help-icon" title="At startup, show and log datasets whose documentation is due for review under their review cadence" aria-label="Help: Dataset review reminders"></i>
+            </label>
+            <input type="checkbox" id="dictionary-review-reminders" ${config.settings.dictionary_review_reminders ? 'checked' : ''}>
+          </div>
         </div>
 
         <div class="pref-item">
@@ -242,10 +315,72 @@ export function renderSettingsView(
           <input type="checkbox" id="ai-enabled" data-testid="settings-ai-enabled-toggle" ${config.settings.ai_config?.enabled ? 'checked' : ''}>
         </div>
 
+        <div class="pref-item">
+          <label for="ai-provider">
+            Provider
+            <i class="ph ph-info help-icon" title="Where requests are sent. Ollama and OpenAI-compatible servers can run on your own network, so no data leaves it." aria-label="Help: AI provider"></i>
+          </label>
+          <select id="ai-provider">
+            ${(
+              [
+                ['openai', 'OpenAI'],
+                ['azure_openai', 'Azure OpenAI'],
+                ['ollama', 'Ollama (local)'],
+                ['openai_compatible', 'OpenAI-compatible server'],
+              ] as const
+            )
+              .map(
+                ([value, label]) =>
+                  `<option value="${value}" ${(config.settings.ai_config?.provider ?? 'openai') === value ? 'selected' : ''}>${label}</option>`
+              )
+              .join('')}
+          </select>
+        </div>
+
+        <div class="pref-item">
+          <label for="ai-base-url">
+            Base URL
+            <i class="ph ph-info help-icon" title="Leave empty for the provider default (OpenAI: https://api.openai.com/v1, Ollama: http://localhost:11434/v1). Required for Azure OpenAI and other servers." aria-label="Help: Base URL"></i>
+          </label>
+          <input type="text" id="ai-base-url" placeholder="Provider default"
+            value="${escapeHtml(config.settings.ai_config?.base_url ?? '')}">
+        </div>
+
+        <div class="pref-item">
+          <label for="ai-auth-scheme">
+            Authentication
+            <i class="ph ph-info help-icon" title="How the API key is sent. Provider default uses a bearer token for OpenAI, the api-key header for Azure and no key for Ollama." aria-label="Help: Authentication"></i>
+          </label>
+          <select id="ai-auth-scheme">
+            ${(
+              [
+                ['', 'Provider default'],
+                ['bearer', 'Bearer token'],
+                ['api_key_header', 'api-key header (Azure)'],
+                ['none', 'No key'],
+              ] as const
+            )
+              .map(
+                ([value, label]) =>
+                  `<option value="${value}" ${(config.settings.ai_config?.auth_scheme ?? '') === value ? 'selected' : ''}>${label}</option>`
+              )
+              .join('')}
+          </select>
+        </div>
+
+        <div class="pref-item">
+          <label for="ai-api-version">
+            API Version
+            <i class="ph ph-info help-icon" title="Azure OpenAI api-version; leave empty for 2024-06-01" aria-label="Help: API version"></i>
+          </label>
+          <input type="text" id="ai-api-version" placeholder="2024-06-01"
+            value="${escapeHtml(config.settings.ai_config?.api_version ?? '')}">
+        </div>
+
         <div class="pref-item">
           <label for="ai-api-key">
-            OpenAI API Key
-            <i class="ph ph-info help-icon" title="Your OpenAI API key (stored securely in system keyring)" aria-label="Help: OpenAI API key"></i>
+            API Key
+            <i class="ph ph-info help-icon" title="Your API key (stored securely in system keyring). Not needed for local servers without authentication." aria-label="Help: API key"></i>
           </label>
           <div class="api-key-input-group">
             <input type="password" id="ai-api-key" placeholder="sk-..." autocomplete="off">
@@ -264,25 +399,19 @@ export function renderSettingsView(
         <div class="pref-item">
           <label for="ai-model">
             Model
-            <i class="ph ph-info help-icon" title="OpenAI model to use" aria-label="Help: OpenAI model"></i>
+            <i class="ph ph-info help-icon" title="Model name, e.g. gpt-4o-mini or llama3.1. For Azure OpenAI, the deployment name." aria-label="Help: Model"></i>
           </label>
-          <select id="ai-model">
-            <option value="gpt-4o-mini" ${(config.settings.ai_config?.model ?? 'gpt-4o-mini') === 'gpt-4o-mini' ? 'selected' : ''}>
-              GPT-4o Mini (recommended - fast & affordable)
-            </option>
-            <option value="gpt-4o" ${(config.settings.ai_config?.model ?? 'gpt-4o-mini') === 'gpt-4o' ? 'selected' : ''}>
-              GPT-4o (flagship model)
-            </option>
-            <option value="gpt-4.1" ${(config.settings.ai_config?.model ?? 'gpt-4o-mini') === 'gpt-4.1' ? 'selected' : ''}>
-              GPT-4.1 (latest, best performance)
-            </option>
-            <option value="gpt-4-turbo" ${(config.settings.ai_config?.model ?? 'gpt-4o-mini') === 'gpt-4-turbo' ? 'selected' : ''}>
-              GPT-4 Turbo (legacy)
-            </option>
-            <option value="gpt-3.5-turbo" ${(config.settings.ai_config?.model ?? 'gpt-4o-mini') === 'gpt-3.5-turbo' ? 'selected' : ''}>
-              GPT-3.5 Turbo (legacy, cheapest)
-            </option>
-          </select>
+          <input type="text" id="ai-model" list="ai-model-suggestions"
+            value="${escapeHtml(config.settings.ai_config?.model ?? 'gpt-4o-mini')}">
+          <datalist id="ai-model-suggestions">
+            <option value="gpt-4o-mini">GPT-4o Mini (recommended - fast & affordable)</option>
+            <option value="gpt-4o">GPT-4o (flagship model)</option>
+            <option value="gpt-4.1">GPT-4.1 (latest, best performance)</option>
+            <option value="gpt-4-turbo">GPT-4 Turbo (legacy)</option>
+            <option value="gpt-3.5-turbo">GPT-3.5 Turbo (legacy, cheapest)</option>
+            <option value="llama3.1">Llama 3.1 (Ollama)</option>
+            <option value="mistral">Mistral (Ollama)</option>
+          </datalist>
         </div>
 
         <div class="pref-item">
@@ -304,6 +433,16 @@ export function renderSettingsView(
             min="100" max="4000" step="100"
             value="${config.settings.ai_config?.max_tokens ?? 1000}">
         </div>
+
+        <div class="pref-item">
+          <label for="ai-context-window">
+            Context Window (tokens)
+            <i class="ph ph-info help-icon" title="How many tokens the model accepts per request. Older conversation turns are summarised to stay within it." aria-label="Help: Context window"></i>
+          </label>
+          <input type="number" id="ai-context-window"
+            min="2000" step="1000"
+            value="${config.settings.ai_config?.context_window ?? 16000}">
+        </div>
       </div>
 
       <div class="settings-section" data-testid="settings-font-size-section">
@@ -371,6 +510,15 @@ export function renderSettingsView(
           </div>
         </div>
 
+        <div class="pref-item">
+          <label for="select-run-log">Run Logs</label>
+          <select id="select-run-log">
+            <option value="">Loading...</option>
+          </select>
+          <div id="run-log-entries" class="log-files-list"></div>
+          <p class="hint-text">Everything logged by one analysis, pipeline or export, by its run ID</p>
+        </div>
+
         <div class="log-info">
           <p><i class="ph ph-info"></i> Logs capture all application activity including errors, warnings, and diagnostic information.</p>
           <p>Log files rotate daily and are kept for 10 days.</p>
@@ -379,3 +527,34 @@ export function renderSettingsView(
     </div>
   `;
 }
+
+/** Options of the run log picker, newest run first */
+export function renderRunOptions(runs: RunSummary[]): string {
+  if (runs.length === 0) {
+    return '<option value="">No runs logged yet</option>';
+  }
+  return [
+    '<option value="">Select a run...</option>',
+    ...runs.map(run => {
+      const started = run.started_at.slice(0, 19).replace('T', ' ');
+      const label = `${started} · ${run.kind} · ${run.label} · ${run.status ?? 'running'}`;
+      return `<option value="${escapeHtml(run.run_id)}">${escapeHtml(label)}</option>`;
+    }),
+  ].join('');
+}
+
+/** Events logged during one run, oldest first */
+export function renderRunLogEntries(entries: RunLogEntry[]): string {
+  if (entries.length === 0) {
+    return '<p class="hint-text">Nothing was logged for this run</p>';
+  }
+  return entries
+    .map(
+      entry => `
+        <div class="log-file-row">
+          <span>${escapeHtml(entry.timestamp.slice(11, 19))} ${escapeHtml(entry.level)}</span>
+          <span>${escapeHtml(entry.message)}</span>
+        </div>`
+    )
+    .join('');
+}
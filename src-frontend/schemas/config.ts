@@ -28,14 +28,21 @@ export const DbConnectionSchema = z.object({
     schema: z.string(),
     table: z.string(),
   }),
+  read_only: z.boolean().optional(),
 });
 
 // AI Configuration Schema
 export const AIConfigSchema = z.object({
   enabled: z.boolean(),
+  provider: z.enum(['openai', 'azure_openai', 'ollama', 'openai_compatible']).optional(),
+  base_url: z.string().nullable().optional(),
+  auth_scheme: z.enum(['bearer', 'api_key_header', 'none']).nullable().optional(),
+  api_version: z.string().nullable().optional(),
   model: z.string(),
   temperature: z.number(),
   max_tokens: z.number(),
+  context_window: z.number().optional(),
+  context: z.record(z.unknown()).optional(),
 });
 
 // Audit Entry Schema
@@ -194,6 +201,8 @@ export const TemporalStatsSchema = z.object({
   is_sorted_rev: z.boolean(),
   bin_width: z.number(),
   histogram: z.array(z.tuple([z.number(), z.number(), z.number()])).nullable(),
+  format: z.string().nullable().optional(),
+  format_ambiguous: z.boolean().optional(),
 });
 
 export const CategoricalStatsSchema = z.object({
@@ -206,6 +215,22 @@ export const BooleanStatsSchema = z.object({
   false_count: z.number(),
 });
 
+export const GeoStatsSchema = z.object({
+  encoding: z.object({ kind: z.string() }).passthrough(),
+  distinct_count: z.number(),
+  valid_count: z.number(),
+  invalid_count: z.number(),
+  bounds: z
+    .object({
+      min_lat: z.number(),
+      max_lat: z.number(),
+      min_lon: z.number(),
+      max_lon: z.number(),
+    })
+    .nullable(),
+  geometry_types: z.record(z.number()).optional(),
+});
+
 export const TextStatsSchema = z.object({
   distinct_count: z.number(),
   top_value: z.tuple([z.string(), z.number()]).nullable(),
@@ -223,6 +248,7 @@ export const ColumnStatsSchema = z.object({
   Temporal: TemporalStatsSchema.optional(),
   Categorical: CategoricalStatsSchema.optional(),
   Boolean: BooleanStatsSchema.optional(),
+  Geo: GeoStatsSchema.optional(),
   Text: TextStatsSchema.optional(),
 });
 
@@ -237,6 +263,11 @@ export const ColumnSummarySchema = z.object({
   ml_advice: z.array(z.string()),
   business_summary: z.array(z.string()),
   samples: z.array(z.string()),
+  semantic_type: z
+    .enum(['email', 'country_code', 'currency_code', 'currency_amount', 'postcode', 'ip_address'])
+    .nullable()
+    .optional(),
+  suggested_name: z.string().nullable().optional(),
 });
 
 export const FileHealthSchema = z.object({
@@ -1,6 +1,11 @@
 import { describe, test, expect } from 'vitest';
 
-import { getDefaultColumnCleanConfig, type ColumnSummary } from './types';
+import {
+  aiRequiresApiKey,
+  getDefaultAppConfig,
+  getDefaultColumnCleanConfig,
+  type ColumnSummary,
+} from './types';
 
 describe('types', () => {
   describe('getDefaultColumnCleanConfig', () => {
@@ -98,4 +103,16 @@ describe('types', () => {
       expect(config.remove_non_ascii).toBe(false);
     });
   });
+
+  describe('aiRequiresApiKey', () => {
+    test('should follow the provider default unless overridden', () => {
+      const base = getDefaultAppConfig().settings.ai_config;
+      expect(aiRequiresApiKey(base)).toBe(true);
+      expect(aiRequiresApiKey({ ...base, provider: 'ollama' })).toBe(false);
+      expect(aiRequiresApiKey({ ...base, provider: 'ollama', auth_scheme: 'bearer' })).toBe(true);
+      expect(aiRequiresApiKey({ ...base, provider: 'openai_compatible', auth_scheme: 'none' })).toBe(
+        false
+      );
+    });
+  });
 });
@@ -18,6 +18,19 @@ export interface NumericStats {
   is_sorted_rev: boolean;
   bin_width: number;
   histogram: [number, number][] | null; // [bin_centre, count] from Rust Vec<(f64, usize)>
+  histogram_strategy?: HistogramStrategy;
+  log_histogram?: HistogramVariant | null;
+  zoomed_histogram?: HistogramVariant | null;
+  quantiles?: [number, number][]; // [percentile, value] for P1..P99
+  ecdf?: [number, number][]; // [value, fraction <= value]
+}
+
+export interface HistogramVariant {
+  bin_width: number;
+  histogram: [number, number][];
+  lower: number;
+  upper: number;
+  excluded_count: number;
 }
 
 export interface TemporalStats {
@@ -30,13 +43,56 @@ export interface TemporalStats {
   is_sorted_rev: boolean;
   bin_width: number;
   histogram: [number, number][] | null; // [timestamp_ms, count] from Rust Vec<(f64, usize)>
+  weekday_counts?: number[]; // Monday first
+  hour_counts?: number[]; // UTC, empty for date-only columns
+  future_count?: number;
+  implausible_count?: number;
+  min_gap_ms?: number | null;
+  max_gap_ms?: number | null;
+  /** strptime format the values were read with, when they are text */
+  format?: string | null;
+  /** Whether the text values read as well day first as month first */
+  format_ambiguous?: boolean;
+}
+
+/** How a column holds locations; latitudes and longitudes name their pair */
+export type GeoEncoding =
+  | { kind: 'latitude'; longitude: string }
+  | { kind: 'longitude'; latitude: string }
+  | { kind: 'wkt' }
+  | { kind: 'geo_json' };
+
+export const GEO_ENCODING_LABELS: Record<GeoEncoding['kind'], string> = {
+  latitude: 'Latitude',
+  longitude: 'Longitude',
+  wkt: 'WKT geometry',
+  geo_json: 'GeoJSON geometry',
+};
+
+export interface BoundingBox {
+  min_lat: number;
+  max_lat: number;
+  min_lon: number;
+  max_lon: number;
+}
+
+export interface GeoStats {
+  encoding: GeoEncoding;
+  distinct_count: number;
+  valid_count: number;
+  /** Out-of-range coordinates and unreadable geometries */
+  invalid_count: number;
+  bounds: BoundingBox | null;
+  /** Valid geometries by GeoJSON type name, e.g. `Point` */
+  geometry_types?: Record<string, number>;
 }
 
 export interface ColumnStats {
   Numeric?: NumericStats;
   Temporal?: TemporalStats;
   Categorical?: Record<string, number>; // HashMap<String, usize> from Rust
-  Boolean?: { true_count: number; false_count: number };
+  Boolean?: { true_count: number; false_count: number; null_count?: number };
+  Geo?: GeoStats;
   Text?: {
     distinct: number; // Note: Rust uses 'distinct', not 'distinct_count'
     top_value: [string, number] | null;
@@ -46,6 +102,23 @@ export interface ColumnStats {
   };
 }
 
+export type SemanticType =
+  | 'email'
+  | 'country_code'
+  | 'currency_code'
+  | 'currency_amount'
+  | 'postcode'
+  | 'ip_address';
+
+export const SEMANTIC_TYPE_LABELS: Record<SemanticType, string> = {
+  email: 'Email address',
+  country_code: 'Country code',
+  currency_code: 'Currency code',
+  currency_amount: 'Currency amount',
+  postcode: 'Postcode',
+  ip_address: 'IP address',
+};
+
 export interface ColumnSummary {
   name: string;
   standardized_name: string;
@@ -57,6 +130,10 @@ export interface ColumnSummary {
   ml_advice: string[];
   business_summary: string[];
   samples: string[];
+  /** What the values are beyond their type, when recognised */
+  semantic_type?: SemanticType | null;
+  /** A name from the content, for placeholder headers such as `Column1` */
+  suggested_name?: string | null;
 }
 
 export interface ColumnInfo {
@@ -67,6 +144,25 @@ export interface ColumnInfo {
 export interface CorrelationMatrix {
   columns: string[];
   data: number[][];
+  omitted_columns?: string[];
+}
+
+export interface CorrelationOptions {
+  max_columns: number | null;
+  columns: string[];
+  select_by_variance: boolean;
+}
+
+export type HistogramStrategy = 'Auto' | 'FixedBins' | 'FreedmanDiaconis' | 'Sturges' | 'LogScale';
+
+export interface HistogramOptions {
+  strategy: HistogramStrategy;
+  bin_count: number | null;
+}
+
+export interface AnalysisOptions {
+  correlation: CorrelationOptions;
+  histogram: HistogramOptions;
 }
 
 export interface FileHealth {
@@ -86,4 +182,336 @@ export interface AnalysisResponse {
   health: FileHealth;
   analysis_duration: { secs: number; nanos: number };
   correlation_matrix: CorrelationMatrix | null;
+  /** Set when the header repeats a column name */
+  column_mapping?: ColumnMapping | null;
+  /** Set when the file looks like it holds variables as rows */
+  orientation?: TransposeDetection | null;
+  /** Set when loading named merged header cells or dropped footer rows */
+  layout?: LayoutReport | null;
+  /** Set when the header of a CSV file looks like a row of data */
+  header_looks_like_data?: boolean;
+  /** Set when the file was read without a header row, naming its columns this way */
+  header_names?: HeaderlessNaming | null;
+}
+
+/**
+ * How to name the columns of a CSV file read without a header row: numbered,
+ * from their content, or from a schema file listing the names in order
+ */
+export type HeaderlessNaming =
+  | { naming: 'auto' }
+  | { naming: 'inferred' }
+  | { naming: 'schema'; path: string };
+
+/** Delimiter (as a byte), encoding and line endings of a CSV file */
+export interface CsvDialect {
+  delimiter: number;
+  encoding: 'utf8' | 'utf8_bom' | 'other';
+  line_ending: 'lf' | 'crlf';
+}
+
+/**
+ * Where cleaning in place writes: a new file beside the original named with
+ * `suffix`, or over the original after backing it up
+ */
+export type InPlaceMode = { mode: 'suffix'; suffix: string } | { mode: 'replace' };
+
+export interface InPlaceReport {
+  output: string;
+  /** Copy of the original, when it was replaced */
+  backup: string | null;
+  rows: number;
+  dialect: CsvDialect | null;
+  header: boolean;
+}
+
+/** Which layout fixes loading applies to CSV files and workbooks */
+export interface LayoutOptions {
+  /** Name blank header cells after a merged header cell */
+  fill_merged_headers: boolean;
+  /** Drop trailing totals and blank rows */
+  drop_footer_rows: boolean;
+}
+
+/** What loading changed about a file's layout */
+export interface LayoutReport {
+  /** Blank header cells that were named, by zero-based position */
+  filled_headers: { position: number; name: string }[];
+  /** Rows dropped from the end of the file */
+  dropped_footer_rows: number;
+  /** Label of the dropped totals row, if there was one */
+  footer_label: string | null;
+}
+
+/** Why a file looks transposed (variables as rows, observations as columns) */
+export interface TransposeDetection {
+  /** First column, whose values look like variable names */
+  label_column: string;
+  reasons: string[];
+  /** Whether rows and columns were swapped before analysis */
+  applied: boolean;
+}
+
+/** Size and columns of one sheet of a workbook */
+export interface SheetOverview {
+  name: string;
+  row_count: number;
+  column_count: number;
+  columns: string[];
+}
+
+/** A column name found in more than one sheet */
+export interface SharedColumn {
+  name: string;
+  sheets: string[];
+}
+
+export interface WorkbookOverview {
+  sheets: SheetOverview[];
+  /** Columns in two or more sheets, most widely shared first */
+  shared_columns: SharedColumn[];
+}
+
+/** Every sheet of a workbook analysed in one go */
+export interface WorkbookAnalysis {
+  path: string;
+  overview: WorkbookOverview;
+  /** One analysis per sheet; each `path` addresses its sheet as `book.xlsx#Sheet` */
+  sheets: AnalysisResponse[];
+  /** Sheets that could not be read or analysed */
+  failed: { sheet: string; error: string }[];
+}
+
+/** What loading does with repeated CSV header names */
+/** Order of day and month in dates such as `03/04/2024`; `auto` follows the system locale */
+export type DateOrder = 'auto' | 'day_first' | 'month_first';
+
+export type DuplicateColumnPolicy = 'auto_suffix' | 'keep_first' | 'keep_last' | 'error';
+
+/** Where a loaded column came from in the file */
+export interface ColumnOrigin {
+  name: string;
+  source_name: string;
+  /** Zero-based position in the file */
+  position: number;
+}
+
+export interface ColumnMapping {
+  policy: DuplicateColumnPolicy;
+  columns: ColumnOrigin[];
+  /** Columns left out by keep_first or keep_last */
+  dropped: ColumnOrigin[];
+  duplicates: string[];
+}
+
+export interface IncrementalUpdate {
+  summary: ColumnSummary[];
+  health: FileHealth;
+  changed_columns: string[];
+  recomputed: string[];
+}
+
+/** Values of one configured column before and after cleaning */
+export interface ColumnCleanPreview {
+  name: string;
+  /** `null` if cleaning drops the column (inactive) or replaces it (one-hot encoding) */
+  cleaned_name: string | null;
+  dtype_before: string;
+  dtype_after: string | null;
+  before: (string | null)[];
+  /** Same rows after cleaning; empty when `cleaned_name` is `null` */
+  after: (string | null)[];
+  /** Rows whose value changed */
+  changed: number;
+}
+
+/** Cleaning applied to the first rows of a file */
+export interface CleanPreview {
+  rows: number;
+  columns: ColumnCleanPreview[];
+}
+
+/** How a data grid filter compares a column with its value */
+export type GridFilterOp =
+  | 'contains'
+  | 'equals'
+  | 'not_equals'
+  | 'greater_than'
+  | 'less_than'
+  | 'is_null'
+  | 'not_null';
+
+export interface GridFilter {
+  column: string;
+  op: GridFilterOp;
+  /** Unused by `is_null` and `not_null` */
+  value: string;
+}
+
+export interface GridSort {
+  column: string;
+  descending: boolean;
+}
+
+/** One page of the data grid; rows count after filtering and sorting */
+export interface GridQuery {
+  offset: number;
+  limit: number;
+  sort: GridSort | null;
+  /** Filters a row must pass, all of them */
+  filters: GridFilter[];
+  /** Whether to count the rows passing the filters */
+  count: boolean;
+}
+
+export interface GridPage {
+  columns: ColumnInfo[];
+  offset: number;
+  /** Values as text by row, then column */
+  rows: (string | null)[][];
+  /** Rows passing the filters, when the query asked for the count */
+  total_rows: number | null;
+}
+
+export type MlTask = 'Classification' | 'Regression';
+
+export type MlModelKind =
+  | 'LinearRegression'
+  | 'DecisionTree'
+  | 'LogisticRegression'
+  | 'RandomForest'
+  | 'KMeans'
+  | 'Ridge'
+  | 'Lasso';
+
+export interface FeatureImportance {
+  feature: string;
+  rank: number;
+  mutual_information: number;
+  model_importance: number | null;
+}
+
+export interface FeatureImportanceReport {
+  target_column: string;
+  task: MlTask;
+  model_kind: MlModelKind;
+  features: FeatureImportance[];
+  skipped_columns: string[];
+  row_count: number;
+  duration: { secs: number; nanos: number };
+  interpretation: string[];
+}
+
+export interface DeepDiveOptions {
+  max_distinct_values?: number;
+  max_runs?: number;
+}
+
+export interface DistinctValues {
+  /** [value, row count], most frequent first */
+  values: [string, number][];
+  total_distinct: number;
+  truncated: boolean;
+}
+
+export interface ValueRun {
+  value: string;
+  length: number;
+  start_row: number;
+}
+
+export interface DecileMean {
+  decile: number;
+  lower: number;
+  upper: number;
+  mean: number;
+  count: number;
+}
+
+export interface ColumnDeepDive {
+  column: string;
+  distinct_values: DistinctValues;
+  longest_runs: ValueRun[];
+  /** Empty for non-numeric columns */
+  decile_means: DecileMean[];
+}
+
+/** Payload of the `analysis:deep_dive_part` event */
+export type DeepDivePartEvent = { path: string; column: string } & (
+  | { part: 'DistinctValues'; data: DistinctValues }
+  | { part: 'LongestRuns'; data: ValueRun[] }
+  | { part: 'DecileMeans'; data: DecileMean[] }
+);
+
+export type ParamType = 'string' | 'number' | 'date';
+
+/** A saved query parameter, written as `{name}` in the query text */
+export interface QueryParam {
+  name: string;
+  type: ParamType;
+  /** Shown when asking for the value */
+  prompt?: string;
+  default?: string | null;
+}
+
+export interface SavedQuery {
+  id: string;
+  name: string;
+  query: string;
+  data_path?: string | null;
+  params?: QueryParam[];
+  created_at: string;
+  updated_at: string;
+}
+
+export type ScriptLanguage = 'python' | 'powershell';
+
+/** One version of a script template with `{{name}}` parameter slots */
+export interface ScriptTemplate {
+  /** Empty or omitted for a new template */
+  id?: string;
+  name: string;
+  description?: string;
+  language: ScriptLanguage;
+  body: string;
+  params?: QueryParam[];
+  version?: number;
+  /** Shipped with the app and read-only */
+  builtin?: boolean;
+  updated_at?: string;
+}
+
+/** A template filled in with form values, ready to run */
+export interface RenderedScript {
+  template_id: string;
+  version: number;
+  language: ScriptLanguage;
+  script: string;
+}
+
+export type QueryStatus = 'succeeded' | 'failed';
+
+export interface QueryRecord {
+  id: string;
+  query: string;
+  data_path: string | null;
+  started_at: string;
+  duration_ms: number;
+  status: QueryStatus;
+  row_count: number | null;
+  error: string | null;
+}
+
+/** A slice of a saved query result; rows are keyed by column name */
+export interface ResultPage {
+  query_id: string;
+  columns: ColumnInfo[];
+  rows: Record<string, unknown>[];
+  offset: number;
+  total_rows: number;
+}
+
+export interface SqlQueryResult {
+  record: QueryRecord;
+  page: ResultPage;
 }
@@ -1,4 +1,10 @@
-import { ColumnSummary } from './analysis';
+import {
+  AnalysisOptions,
+  ColumnSummary,
+  DateOrder,
+  DuplicateColumnPolicy,
+  LayoutOptions,
+} from './analysis';
 
 export type NormalisationMethod = 'None' | 'ZScore' | 'MinMax';
 export type ImputeMode = 'None' | 'Mean' | 'Median' | 'Zero' | 'Mode';
@@ -26,6 +32,70 @@ export interface ColumnCleanConfig {
   normalisation: NormalisationMethod;
   one_hot_encode: boolean;
   impute_mode: ImputeMode;
+  /** Whole values replaced after the other text options, e.g. `N.Y.` to `New York` */
+  value_mapping?: Record<string, string>;
+  /** Split the cleaned column into new columns */
+  split?: ColumnSplit | null;
+  /** Add a column filled from a template of other columns */
+  merge?: ColumnMerge | null;
+  /** Calendar features added as `<column>_<part>` columns */
+  date_parts?: DatePart[];
+  /** Replace the cleaned numbers with the label of their bin */
+  binning?: ColumnBinning | null;
+  /** With ML preprocessing, encode categories this way instead of one-hot */
+  encoding?: CategoryEncoding | null;
+}
+
+/** A calendar feature of a date or time column; weekdays run 1 (Monday) to 7 */
+export type DatePart =
+  | 'year'
+  | 'quarter'
+  | 'month'
+  | 'week'
+  | 'day'
+  | 'weekday'
+  | 'day_of_year'
+  | 'hour'
+  | 'minute'
+  | 'is_weekend';
+
+/** Split a column on a delimiter (or regex) into the columns named in `into` */
+export interface ColumnSplit {
+  delimiter: string;
+  regex: boolean;
+  into: string[];
+  drop_original: boolean;
+}
+
+/** Where bin edges go: evenly spaced, at quantiles, or at the given `edges` */
+export type BinStrategy =
+  | { kind: 'equal_width'; bins: number }
+  | { kind: 'quantile'; bins: number }
+  | { kind: 'custom'; edges: number[] };
+
+/** Bucket a numeric column; empty `labels` names bins by their edges, e.g. `18 to 30` */
+export interface ColumnBinning {
+  strategy: BinStrategy;
+  labels: string[];
+}
+
+/** Ordinal codes values by sorted position; target by the smoothed mean of `target` */
+export type EncodingMethod =
+  | { kind: 'ordinal' }
+  | { kind: 'target'; target: string; smoothing: number; folds: number };
+
+/** Category encoding; `mapping` is learned on first use and reused for new data */
+export interface CategoryEncoding {
+  method: EncodingMethod;
+  mapping?: Record<string, number>;
+  unknown?: number | null;
+}
+
+/** Fill `template`, e.g. `{first} {last}`, into a new column `into` */
+export interface ColumnMerge {
+  template: string;
+  into: string;
+  drop_sources: boolean;
 }
 
 export function getDefaultColumnCleanConfig(col: ColumnSummary): ColumnCleanConfig {
@@ -54,6 +124,73 @@ export function getDefaultColumnCleanConfig(col: ColumnSummary): ColumnCleanConf
   };
 }
 
+/** Column cleaning configs saved under a name for reuse on other files */
+export interface CleaningPreset {
+  id: string;
+  name: string;
+  /** Configs by the name of the column they were made for */
+  configs: Record<string, ColumnCleanConfig>;
+  updated_at: string;
+}
+
+/** How a column was matched to an expected name: by name, sanitised name or edit distance */
+export type ColumnMatchKind = 'exact' | 'sanitised' | 'fuzzy';
+
+export type PresetMatchKind = ColumnMatchKind;
+
+/** A preset mapped onto the columns of a file */
+export interface AppliedPreset {
+  /** Configs for the matched columns, by file column name */
+  configs: Record<string, ColumnCleanConfig>;
+  matches: { column: string; preset_column: string; kind: PresetMatchKind }[];
+  /** File columns the preset has no config for */
+  unmatched_columns: string[];
+  /** Preset columns no file column matched */
+  unused_preset_columns: string[];
+}
+
+/** A default suggested by how a column was cleaned before */
+export type UsageAction =
+  | { kind: 'drop' }
+  | { kind: 'rename'; to: string }
+  | { kind: 'one_hot_encode' };
+
+export interface UsageSuggestion {
+  column: string;
+  action: UsageAction;
+  /** Cleans the action was taken in */
+  count: number;
+  /** Cleans the column appeared in */
+  cleans: number;
+  message: string;
+}
+
+/** How similar values must be to be clustered as spellings of one value */
+export interface StandardiseOptions {
+  /** Largest edit distance between fingerprints, as a fraction of the longer one */
+  max_distance: number;
+  /** Most distinct values compared, the most frequent first */
+  max_values: number;
+}
+
+export interface ValueCluster {
+  /** The spelling the others are mapped to */
+  canonical: string;
+  /** The most frequent first */
+  values: { value: string; count: number }[];
+  total: number;
+}
+
+/** Clusters of near-duplicate values in one column and the mapping they propose */
+export interface ValueStandardisation {
+  column: string;
+  distinct_values: number;
+  /** Whether only the `max_values` most frequent values were compared */
+  truncated: boolean;
+  clusters: ValueCluster[];
+  mapping: Record<string, string>;
+}
+
 export interface DbConnection {
   id: string;
   name: string;
@@ -67,13 +204,79 @@ export interface DbConnection {
     schema: string;
     table: string;
   };
+  read_only?: boolean;
+}
+
+/** Outcome of importing a connection export, by connection name */
+export interface ConnectionImportReport {
+  added: string[];
+  replaced: string[];
+  /** Already saved on this machine and left unchanged */
+  skipped: string[];
+}
+
+/** Whether a database push suggests indexes and constraints, and runs them */
+export type DdlMode = 'off' | 'suggest' | 'apply';
+
+export interface DdlSuggestion {
+  kind: 'index' | 'not_null' | 'check';
+  column: string;
+  statement: string;
+  reason: string;
+}
+
+export interface DdlReport {
+  suggestions: {
+    table: string;
+    profiled_rows: number;
+    suggestions: DdlSuggestion[];
+  };
+  /** The suggestions as a commented SQL script for review */
+  script: string;
+  applied: boolean;
+  failures: { statement: string; error: string }[];
 }
 
+/** Backend serving chat completions */
+export type AIProvider = 'openai' | 'azure_openai' | 'ollama' | 'openai_compatible';
+
+/**
+ * How requests carry the API key: `Authorization: Bearer`, Azure's
+ * `api-key` header, or no key for local servers
+ */
+export type AIAuthScheme = 'bearer' | 'api_key_header' | 'none';
+
 export interface AIConfig {
   enabled: boolean;
+  provider?: AIProvider;
+  /** Overrides the provider's default base URL */
+  base_url?: string | null;
+  /** Overrides the provider's default auth scheme */
+  auth_scheme?: AIAuthScheme | null;
+  /** Azure OpenAI `api-version` */
+  api_version?: string | null;
+  /** Model name, or the deployment name for Azure OpenAI */
   model: string;
   temperature: number;
   max_tokens: number;
+  /** Tokens the model accepts per request; older turns are summarised to fit */
+  context_window?: number;
+  /** What the analysis context sent with queries includes */
+  context?: AIContextOptions;
+}
+
+/** What the AI prompt context includes and how much of it */
+export interface AIContextOptions {
+  /** Leave out values copied from the data (samples, top categories) */
+  redact_samples: boolean;
+  /** Columns whose values are always left out, e.g. personal data */
+  redacted_columns: string[];
+  max_columns: number;
+  max_categories: number;
+  max_samples: number;
+  max_correlations: number;
+  /** Weakest absolute correlation worth listing */
+  min_correlation: number;
 }
 
 export interface AuditEntry {
@@ -82,6 +285,52 @@ export interface AuditEntry {
   details: string;
 }
 
+/** How long audit entries are kept */
+export interface AuditRetention {
+  /** Entries kept in the app config; 0 for no limit */
+  max_entries: number;
+  /** Days entries are kept, in the app config and the audit files; 0 for no limit */
+  max_age_days: number;
+}
+
+/** Entries to read back from the audit files; every field is optional */
+export interface AuditQuery {
+  /** Earliest timestamp (RFC 3339), inclusive */
+  from?: string;
+  /** Latest timestamp (RFC 3339), inclusive */
+  to?: string;
+  /** Actions to include, compared without case; all when empty */
+  categories?: string[];
+  /** Most entries returned, the latest kept; 0 for no limit */
+  limit?: number;
+}
+
+export type AuditExportFormat = 'csv' | 'jsonl';
+
+/** An analysis, pipeline or export run found in the structured log */
+export interface RunSummary {
+  /** Correlation ID, such as `pipeline-1f3a9c02` */
+  run_id: string;
+  kind: 'analysis' | 'pipeline' | 'export';
+  /** File or pipeline the run worked on */
+  label: string;
+  started_at: string;
+  finished_at: string | null;
+  /** `null` while running, or if the app stopped during the run */
+  status: 'ok' | 'failed' | null;
+  error: string | null;
+  duration_ms: number | null;
+}
+
+/** One event logged during a run */
+export interface RunLogEntry {
+  timestamp: string;
+  level: string;
+  target: string;
+  message: string;
+  fields: Record<string, unknown>;
+}
+
 export interface AppSettings {
   connections: DbConnection[];
   active_import_id: string | null;
@@ -91,12 +340,121 @@ export interface AppSettings {
   sql_font_size: number;
   first_run_completed: boolean;
   trusted_paths: string[];
+  /** Glob allow/deny rules checked together with `trusted_paths` */
+  path_rules?: PathRule[];
   preview_row_limit: number;
   security_warning_acknowledged: boolean;
   skip_full_row_count: boolean;
   analysis_sample_size: number;
   sampling_strategy: string;
   ai_config: AIConfig;
+  analysis_options?: AnalysisOptions;
+  retry_policy?: RetryPolicy;
+  limits?: OperationLimits;
+  /** Save a data dictionary snapshot of every analysed file */
+  auto_dictionary_snapshot?: boolean;
+  /** Applied after each automatic snapshot */
+  dictionary_retention?: SnapshotRetention;
+  /** Announce datasets due for a documentation review at startup */
+  dictionary_review_reminders?: boolean;
+  python_sandbox?: PythonSandbox;
+  /** What loading a CSV does with repeated header names */
+  duplicate_column_policy?: DuplicateColumnPolicy;
+  /** Swap rows and columns before analysis when a file looks transposed */
+  auto_transpose?: boolean;
+  /** Fixes for merged header cells and footer rows in CSV files and workbooks */
+  layout?: LayoutOptions;
+  memory_budget?: MemoryBudget;
+  audit_retention?: AuditRetention;
+  /** Whether dates such as `03/04/2024` are read day or month first when both fit */
+  date_order?: DateOrder;
+}
+
+/** How many dictionary snapshots are kept per dataset hash */
+export interface SnapshotRetention {
+  /** Newest snapshots kept per dataset; null keeps all */
+  keep_last: number | null;
+  /** Older snapshots are removed; null keeps them */
+  max_age_days: number | null;
+  /** Remove snapshots identical to the next one of the same dataset */
+  compact: boolean;
+}
+
+/** Restrictions for Python scripts; 0 disables a limit */
+export interface PythonSandbox {
+  enabled: boolean;
+  memory_mb: number;
+  cpu_seconds: number;
+  /** 0 uses the default Python timeout */
+  timeout_secs: number;
+  block_network: boolean;
+}
+
+/** One restriction of a sandboxed run and whether it took effect */
+export interface SandboxRestriction {
+  name: string;
+  applied: boolean;
+  detail: string;
+}
+
+export interface SandboxedOutput {
+  output: string;
+  restrictions: SandboxRestriction[];
+}
+
+export type PathCapability = 'read' | 'write' | 'execute';
+
+/**
+ * A folder or glob pattern (`*`, `?` within a segment, `**` across segments)
+ * that allows or denies access. Deny rules win over allow rules.
+ */
+export interface PathRule {
+  pattern: string;
+  effect?: 'allow' | 'deny';
+  /** Capabilities the rule applies to; empty for all */
+  capabilities?: PathCapability[];
+}
+
+/** How the path policy treats one path and capability */
+export type PathDecision =
+  | { decision: 'allowed'; pattern: string }
+  | { decision: 'denied'; pattern: string }
+  | { decision: 'not_trusted' };
+
+/** Cap on one kind of operation; 0 disables either limit */
+export interface OperationLimit {
+  /** Operations allowed to run at the same time */
+  max_concurrent: number;
+  /** Operations allowed to start in any 60-second window */
+  per_minute: number;
+}
+
+/** Concurrency and rate limits for AI requests and database pushes */
+export interface OperationLimits {
+  ai: OperationLimit;
+  db_push: OperationLimit;
+}
+
+/** Memory analysis, cleaning and export may use before they stream, sample or spill to disk */
+export interface MemoryBudget {
+  /** Budget in MiB; 0 for no limit */
+  limit_mb: number;
+  /** Folder for temporary Parquet files; the system temp folder when null */
+  spill_dir: string | null;
+}
+
+/** Retries for transient database, network and delivery failures */
+export interface RetryPolicy {
+  /** Attempts in total, including the first; 1 disables retrying */
+  max_attempts: number;
+  /** Delay before the first retry */
+  initial_delay_ms: number;
+  /** Upper bound for any single delay */
+  max_delay_ms: number;
+  /** Factor the delay grows by after each retry */
+  multiplier: number;
+  /** Fraction of each delay added or removed at random (0-1) */
+  jitter: number;
 }
 
 export interface AppConfig {
@@ -124,10 +482,36 @@ export function getDefaultAppConfig(): AppConfig {
       sampling_strategy: 'balanced',
       ai_config: {
         enabled: true,
+        provider: 'openai',
         model: 'gpt-4o',
         temperature: 0.7,
         max_tokens: 2000,
+        context_window: 16000,
+      },
+      retry_policy: {
+        max_attempts: 4,
+        initial_delay_ms: 1000,
+        max_delay_ms: 30000,
+        multiplier: 2,
+        jitter: 0.2,
+      },
+      limits: {
+        ai: { max_concurrent: 2, per_minute: 20 },
+        db_push: { max_concurrent: 2, per_minute: 0 },
       },
+      python_sandbox: {
+        enabled: false,
+        memory_mb: 4096,
+        cpu_seconds: 300,
+        timeout_secs: 300,
+        block_network: true,
+      },
+      duplicate_column_policy: 'auto_suffix',
+      auto_transpose: false,
+      layout: { fill_merged_headers: true, drop_footer_rows: true },
+      memory_budget: { limit_mb: 2048, spill_dir: null },
+      audit_retention: { max_entries: 1000, max_age_days: 90 },
+      date_order: 'auto',
     },
     audit_log: {
       entries: [],
@@ -151,6 +535,23 @@ export interface WatcherState {
   message?: string;
 }
 
+/** A database table polled by the watcher */
+export interface TableWatch {
+  /** Assigned by the backend when empty */
+  id: string;
+  enabled: boolean;
+  connection_id: string;
+  schema: string;
+  table: string;
+  /** Timestamp column whose maximum is compared between polls */
+  updated_at_column?: string | null;
+  /** Seconds between polls (minimum 10) */
+  interval_secs: number;
+  action:
+    | { type: 'analyse' }
+    | { type: 'pipeline'; spec_path: string; output_path?: string | null };
+}
+
 export interface WatcherActivity {
   id: string;
   timestamp: string;
@@ -170,6 +571,8 @@ export interface WatcherEventPayload {
   rows?: number;
   cols?: number;
   datasetId?: string;
+  /** Set when the file was added as a raw version of an existing dataset */
+  version_id?: string;
   message?: string;
 }
 
@@ -178,3 +581,50 @@ export interface DocFileMetadata {
   title: string;
   category: string;
 }
+
+/** Payload of the `ai:token` event emitted while an answer streams */
+export interface AITokenEvent {
+  request_id: string;
+  token: string;
+}
+
+export type ChatRole = 'user' | 'assistant';
+
+export interface ChatMessage {
+  role: ChatRole;
+  content: string;
+  timestamp: string;
+}
+
+/** A saved multi-turn conversation with the AI assistant */
+export interface Conversation {
+  id: string;
+  title: string;
+  /** File that was open when the conversation started */
+  data_path: string | null;
+  messages: ChatMessage[];
+  /** Summary sent in place of the first `summarised` messages */
+  summary: string | null;
+  summarised: number;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface ConversationSummary {
+  id: string;
+  title: string;
+  data_path: string | null;
+  message_count: number;
+  updated_at: string;
+}
+
+/** Whether `config` needs an API key from the keyring */
+export function aiRequiresApiKey(config: AIConfig): boolean {
+  const defaultScheme: AIAuthScheme =
+    config.provider === 'ollama'
+      ? 'none'
+      : config.provider === 'azure_openai'
+        ? 'api_key_header'
+        : 'bearer';
+  return (config.auth_scheme ?? defaultScheme) !== 'none';
+}
@@ -1,5 +1,6 @@
+import { HeaderlessNaming } from './analysis';
 import { ColumnCleanConfig } from './config';
-import { TransformPipeline } from './pipeline';
+import { TransformPipeline, TransformSpec } from './pipeline';
 
 export type LifecycleStage =
   | 'Raw'
@@ -11,9 +12,34 @@ export type LifecycleStage =
 
 export type PublishMode = 'View' | 'Snapshot';
 
+export type ApprovalState = 'Draft' | 'InReview' | 'Approved' | 'Published';
+
+export interface ApprovalTransition {
+  from: ApprovalState;
+  to: ApprovalState;
+  actor: string;
+  at: string;
+  comment: string;
+}
+
+/** Where a version is in the publishing approval workflow */
+export interface Approval {
+  state: ApprovalState;
+  transitions: ApprovalTransition[];
+}
+
+/** What a standalone HTML viewer of a version embeds */
+export interface ViewerOptions {
+  /** Preview rows embedded, at most 100,000 (default 1,000) */
+  max_rows: number;
+  /** Preview rows per page (default 50) */
+  page_size: number;
+}
+
 export interface VersionMetadata {
   description: string;
   tags: string[];
+  notes?: string;
   row_count: number | null;
   column_count: number | null;
   file_size_bytes: number | null;
@@ -36,6 +62,68 @@ export interface DatasetVersion {
   data_location: DataLocation;
   metadata: VersionMetadata;
   created_at: string;
+  approval?: Approval;
+}
+
+/** Filter for listing and searching versions; omitted fields match everything */
+export interface VersionFilter {
+  stage?: LifecycleStage;
+  /** Case-insensitive tag */
+  tag?: string;
+  /** RFC 3339 timestamps */
+  created_after?: string;
+  created_before?: string;
+  /** Words that must all appear in the tags, notes or description */
+  text?: string;
+}
+
+export interface VersionSearchHit {
+  dataset_id: string;
+  dataset_name: string;
+  version: DatasetVersion;
+}
+
+export interface LineageNode {
+  /** Version id, or `source:<path>` for source files */
+  id: string;
+  kind: 'source' | 'version';
+  label: string;
+  stage: LifecycleStage | null;
+  path: string | null;
+  created_at: string | null;
+  tags: string[];
+  active: boolean;
+}
+
+export interface LineageEdge {
+  from: string;
+  to: string;
+  transforms: TransformSpec[];
+  publish_mode: 'view' | 'snapshot' | null;
+}
+
+/** Provenance of a dataset from its source files through every version */
+export interface LineageGraph {
+  dataset_id: string;
+  dataset_name: string;
+  nodes: LineageNode[];
+  edges: LineageEdge[];
+}
+
+export interface RefreshedVersion {
+  stage: LifecycleStage;
+  /** The version whose pipeline was re-applied */
+  previous_id: string;
+  new_id: string;
+}
+
+/** Versions recreated by re-reading a dataset's source file */
+export interface RefreshReport {
+  dataset_id: string;
+  source_path: string;
+  versions: RefreshedVersion[];
+  active_version_id: string;
+  refreshed_at: string;
 }
 
 export interface CurrentDataset {
@@ -50,6 +138,8 @@ export interface ExportSource {
   type: 'Analyser' | 'Python' | 'SQL';
   content?: string;
   path?: string;
+  /** How an analysed file without a header row names its columns */
+  header_names?: HeaderlessNaming | null;
 }
 
 export interface ExportDestination {
@@ -64,6 +154,11 @@ export interface ExportOptions {
   destination: ExportDestination;
   create_dictionary?: boolean;
   create_receipt?: boolean;
+  completeness_score?: boolean;
+  completeness_weights?: Record<string, number>;
+  reject_path?: string;
+  /** Database exports: also append column statistics to <table>_profile */
+  profile_table?: boolean;
 }
 
 // Data Dictionary Types
@@ -114,6 +209,27 @@ export interface DatasetBusinessMetadata {
   sensitivity_classification?: string;
   known_limitations?: string;
   tags: string[];
+  /** Person or team accountable for the dataset */
+  owner?: string | null;
+  /** Person who keeps the dataset and its documentation current */
+  steward?: string | null;
+  /** Days between documentation reviews; null if reviews are not tracked */
+  review_cadence_days?: number | null;
+  /** YYYY-MM-DD */
+  last_reviewed?: string | null;
+}
+
+/** Documentation review state of a dataset */
+export interface ReviewStatus {
+  snapshot_id: string;
+  dataset_name: string;
+  owner: string | null;
+  steward: string | null;
+  review_cadence_days: number;
+  last_reviewed: string | null;
+  due: string;
+  /** Days past `due`; negative while the review is not yet due */
+  days_overdue: number;
 }
 
 export interface ColumnMetadata {
@@ -134,6 +250,8 @@ export interface ColumnTechnicalMetadata {
   sample_values: string[];
   warnings: string[];
   stats_json: string | null;
+  /** Plain-English description generated by the profiler */
+  inferred_description?: string | null;
 }
 
 export interface ColumnBusinessMetadata {
@@ -142,6 +260,32 @@ export interface ColumnBusinessMetadata {
   sensitivity_tag?: string;
   approved_examples: string[];
   notes?: string;
+  /** Ids of linked glossary terms */
+  glossary_terms?: string[];
+}
+
+/** An organisation-wide business term that columns can link to */
+export interface GlossaryTerm {
+  /** Omit when adding a term; one is generated */
+  id?: string;
+  term: string;
+  definition: string;
+  owner?: string | null;
+  synonyms: string[];
+  updated_at?: string;
+}
+
+/** Outcome of importing an edited business metadata template */
+export interface TemplateMergeReport {
+  /** The new snapshot version, or null when nothing changed */
+  snapshot_id: string | null;
+  updated_columns: string[];
+  unchanged_columns: string[];
+  /** Snapshot columns with no row in the template */
+  missing_columns: string[];
+  ignored_headers: string[];
+  /** Rows left out, numbered as in a spreadsheet (header is row 1) */
+  errors: { row: number; message: string }[];
 }
 
 export interface SnapshotMetadata {
@@ -153,3 +297,55 @@ export interface SnapshotMetadata {
   column_count: number;
   completeness_pct: number;
 }
+
+/** Disk used by dictionary snapshots, per dataset hash (largest first) */
+export interface DictionaryStorageUsage {
+  snapshot_count: number;
+  total_bytes: number;
+  datasets: {
+    output_hash: string;
+    dataset_name: string;
+    snapshot_count: number;
+    bytes: number;
+    oldest: string;
+    newest: string;
+    /** Snapshots identical to a newer one, which compaction removes */
+    duplicates: number;
+  }[];
+}
+
+/** A dataset or column matching a dictionary search */
+export interface DictionarySearchHit {
+  snapshot_id: string;
+  dataset_name: string;
+  output_hash: string;
+  export_timestamp: string;
+  /** The matching column; null when the dataset itself matched */
+  column: string | null;
+  score: number;
+  matched_fields: (
+    | 'dataset_name'
+    | 'description'
+    | 'tag'
+    | 'column_name'
+    | 'column_description'
+    | 'glossary_term'
+  )[];
+  /** Text of the most important matching field */
+  snippet: string;
+}
+
+export interface DictionarySearchOptions {
+  limit?: number;
+  /** Also search superseded versions of each dataset */
+  all_versions?: boolean;
+}
+
+/** What pruning the dictionary store removed */
+export interface PruneReport {
+  duplicates_removed: string[];
+  expired_removed: string[];
+  relinked: number;
+  bytes_freed: number;
+  remaining: number;
+}
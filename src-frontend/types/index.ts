@@ -4,7 +4,7 @@ export * from './dataset';
 export * from './config';
 export * from './integrity';
 
-import { AnalysisResponse, ColumnInfo } from './analysis';
+import { AnalysisResponse, ColumnInfo, WorkbookAnalysis } from './analysis';
 import {
   ColumnCleanConfig,
   AppConfig,
@@ -58,4 +58,12 @@ export interface AppState {
   currentIdeColumns: ColumnInfo[] | null;
   previousVersionId: string | null;
   paths?: StandardPaths;
+  /** Sheets of the open workbook, and their analyses once all are analysed */
+  workbook?: WorkbookState | null;
+}
+
+export interface WorkbookState {
+  path: string;
+  sheets: string[];
+  analysis: WorkbookAnalysis | null;
 }
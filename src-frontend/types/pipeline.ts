@@ -51,3 +51,20 @@ export interface DiffSummary {
   statistical_changes: StatisticalChange[];
   sample_changes: SampleChange[];
 }
+
+/** Space a dataset's versions take in the deduplicated version store */
+export interface StorageUsage {
+  versions: number;
+  chunks: number;
+  /** Size if every version had its own copy */
+  logical_bytes: number;
+  /** Size with shared chunks counted once */
+  stored_bytes: number;
+  saved_bytes: number;
+}
+
+/** Files removed by lifecycle garbage collection */
+export interface GarbageReport {
+  files_removed: number;
+  bytes_freed: number;
+}
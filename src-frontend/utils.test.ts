@@ -1,6 +1,6 @@
 import { describe, test, expect } from 'vitest';
 
-import { fmtBytes, fmtDuration, escapeHtml } from './utils';
+import { fmtBytes, fmtDuration, escapeHtml, isWorkbookPath } from './utils';
 
 describe('utils', () => {
   describe('fmtBytes', () => {
@@ -118,4 +118,13 @@ describe('utils', () => {
       expect(escaped).toBe('&lt;script&gt;alert(1)&lt;/script&gt;');
     });
   });
+
+  describe('isWorkbookPath', () => {
+    test('should recognise Excel and OpenDocument files', () => {
+      expect(isWorkbookPath('C:/data/Sales.XLSX')).toBe(true);
+      expect(isWorkbookPath('/data/budget.ods')).toBe(true);
+      expect(isWorkbookPath('/data/sales.csv')).toBe(false);
+      expect(isWorkbookPath('/data/xlsx')).toBe(false);
+    });
+  });
 });
@@ -12,6 +12,14 @@ export function fmtDuration(duration: { secs: number; nanos: number }): string {
   return `${(ms / 1000).toFixed(2)}s`;
 }
 
+/** Extensions the backend reads as Excel or OpenDocument workbooks */
+const WORKBOOK_EXTENSIONS = ['xlsx', 'xlsm', 'xls', 'ods'];
+
+export function isWorkbookPath(path: string): boolean {
+  const ext = path.split('.').pop()?.toLowerCase() ?? '';
+  return WORKBOOK_EXTENSIONS.includes(ext);
+}
+
 export function escapeHtml(unsafe: string | null | undefined): string {
   if (unsafe === null || unsafe === undefined) return '';
   return unsafe
@@ -0,0 +1,77 @@
+import { describe, test, expect, beforeEach } from 'vitest';
+
+import type { ColumnCleanConfig } from '../types';
+
+import { CONFIG_HISTORY_LIMIT, ConfigHistory, type CleaningConfigs } from './config-history';
+
+function configs(name: string): CleaningConfigs {
+  return { col: { new_name: name, active: true } as ColumnCleanConfig };
+}
+
+describe('ConfigHistory', () => {
+  let history: ConfigHistory;
+
+  beforeEach(() => {
+    history = new ConfigHistory();
+    history.track({});
+  });
+
+  test('undo and redo restore snapshots', () => {
+    history.record(configs('a'));
+    history.record(configs('b'));
+
+    expect(history.undo(configs('c'))).toEqual(configs('b'));
+    expect(history.undo(configs('b'))).toEqual(configs('a'));
+    expect(history.canUndo()).toBe(false);
+    expect(history.undo(configs('a'))).toBeNull();
+
+    expect(history.redo(configs('a'))).toEqual(configs('b'));
+    expect(history.redo(configs('b'))).toEqual(configs('c'));
+    expect(history.canRedo()).toBe(false);
+  });
+
+  test('snapshots are not affected by later mutation', () => {
+    const current = configs('a');
+    history.record(current);
+    current.col!.new_name = 'b';
+
+    expect(history.undo(current)).toEqual(configs('a'));
+  });
+
+  test('recording a change clears redo', () => {
+    history.record(configs('a'));
+    history.undo(configs('b'));
+    expect(history.canRedo()).toBe(true);
+
+    history.record(configs('a'));
+    expect(history.canRedo()).toBe(false);
+  });
+
+  test('unchanged configs are recorded once', () => {
+    history.record(configs('a'));
+    history.record(configs('a'));
+
+    history.undo(configs('b'));
+    expect(history.canUndo()).toBe(false);
+  });
+
+  test('oldest snapshots are dropped past the limit', () => {
+    for (let i = 0; i <= CONFIG_HISTORY_LIMIT; i++) {
+      history.record(configs(String(i)));
+    }
+    let undone = 0;
+    while (history.undo(configs('current'))) undone++;
+    expect(undone).toBe(CONFIG_HISTORY_LIMIT);
+  });
+
+  test('tracking another analysis clears the history', () => {
+    const analysis = {};
+    history.track(analysis);
+    history.record(configs('a'));
+    history.track(analysis);
+    expect(history.canUndo()).toBe(true);
+
+    history.track({});
+    expect(history.canUndo()).toBe(false);
+  });
+});
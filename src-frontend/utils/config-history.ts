@@ -0,0 +1,71 @@
+/**
+ * Undo/redo history for the Analyser's column cleaning configs.
+ *
+ * Every change records a snapshot of the whole config map as it was before
+ * the change, so one undo reverts everything a bulk action touched.
+ * Snapshots are stored as JSON, which also keeps them independent of the
+ * config objects the Analyser goes on mutating.
+ */
+
+import type { ColumnCleanConfig } from '../types';
+
+export type CleaningConfigs = Record<string, ColumnCleanConfig>;
+
+/** Changes that can be undone; the oldest are dropped beyond this */
+export const CONFIG_HISTORY_LIMIT = 100;
+
+export class ConfigHistory {
+  private undoStack: string[] = [];
+  private redoStack: string[] = [];
+  private owner: object | null = null;
+
+  /**
+   * Tie the history to `owner` (the analysis the configs belong to),
+   * forgetting it if it was recorded for another one.
+   */
+  track(owner: object): void {
+    if (owner !== this.owner) {
+      this.owner = owner;
+      this.clear();
+    }
+  }
+
+  clear(): void {
+    this.undoStack = [];
+    this.redoStack = [];
+  }
+
+  /** Record `configs` as they are just before a change */
+  record(configs: CleaningConfigs): void {
+    const snapshot = JSON.stringify(configs);
+    if (this.undoStack[this.undoStack.length - 1] !== snapshot) {
+      this.undoStack.push(snapshot);
+      if (this.undoStack.length > CONFIG_HISTORY_LIMIT) this.undoStack.shift();
+    }
+    this.redoStack = [];
+  }
+
+  canUndo(): boolean {
+    return this.undoStack.length > 0;
+  }
+
+  canRedo(): boolean {
+    return this.redoStack.length > 0;
+  }
+
+  /** The configs before the last change, or null if there is none */
+  undo(current: CleaningConfigs): CleaningConfigs | null {
+    const snapshot = this.undoStack.pop();
+    if (snapshot === undefined) return null;
+    this.redoStack.push(JSON.stringify(current));
+    return JSON.parse(snapshot) as CleaningConfigs;
+  }
+
+  /** The configs of the last undone change, or null if there is none */
+  redo(current: CleaningConfigs): CleaningConfigs | null {
+    const snapshot = this.redoStack.pop();
+    if (snapshot === undefined) return null;
+    this.undoStack.push(JSON.stringify(current));
+    return JSON.parse(snapshot) as CleaningConfigs;
+  }
+}
@@ -1,8 +1,12 @@
 //! AI module for Beefcake.
 //!
 //! This module contains the AI client and related functionality for
-//! in-app assistance and data analysis guidance.
+//! in-app assistance and data analysis guidance. The client talks to OpenAI,
+//! Azure OpenAI, Ollama or any OpenAI-compatible server (see [`provider`]).
 
 #![allow(clippy::module_name_repetitions)]
 
 pub mod client;
+pub mod context;
+pub mod conversation;
+pub mod provider;
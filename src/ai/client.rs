@@ -1,34 +1,68 @@
 //! AI Client module for integrating OpenAI API into Beefcake.
 //!
-//! This module provides functionality to interact with OpenAI's API for
-//! providing in-application AI support, data analysis guidance, and
-//! statistical interpretation.
+//! This module provides functionality to interact with OpenAI's API, or a
+//! compatible backend chosen in [`AIConfig::provider`], for providing
+//! in-application AI support, data analysis guidance, and statistical
+//! interpretation.
 
 use anyhow::{Context as _, Result};
-use async_openai::{
-    Client,
-    config::OpenAIConfig,
-    types::{
-        ChatCompletionRequestMessage, ChatCompletionRequestSystemMessageArgs,
-        ChatCompletionRequestUserMessageArgs, CreateChatCompletionRequestArgs,
-    },
+use async_openai::types::{
+    ChatCompletionRequestAssistantMessageArgs, ChatCompletionRequestMessage,
+    ChatCompletionRequestSystemMessageArgs, ChatCompletionRequestUserMessageArgs,
+    CreateChatCompletionRequest, CreateChatCompletionRequestArgs,
 };
+use futures::StreamExt as _;
+use std::fmt::Write as _;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
 
+use super::conversation::{ChatRole, Conversation, estimate_tokens};
+use super::provider::ProviderClient;
 pub use crate::config::AIConfig;
+use crate::utils::retry::RetryPolicy;
+
+/// How often a streaming answer checks the abort signal while waiting
+const ABORT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest summary of earlier conversation turns
+const SUMMARY_MAX_TOKENS: u32 = 400;
+
+const SUMMARY_PROMPT: &str = "Summarise this conversation between a user and a data analysis \
+assistant in under 200 words. Keep facts about the dataset, decisions made and open questions; \
+drop pleasantries.";
+
+/// Resolves once the user aborts processing
+async fn wait_for_abort() {
+    while !crate::utils::is_aborted() {
+        tokio::time::sleep(ABORT_POLL_INTERVAL).await;
+    }
+}
 
 /// AI Assistant client for interacting with OpenAI API
 pub struct AIAssistant {
-    client: Client<OpenAIConfig>,
+    client: ProviderClient,
     config: AIConfig,
+    retry: RetryPolicy,
 }
 
 impl AIAssistant {
-    /// Create a new AI Assistant with the provided API key and configuration
+    /// Create a new AI Assistant with the provided API key and configuration.
+    /// The key is ignored for backends that need none.
     pub fn new(api_key: String, config: AIConfig) -> Result<Self> {
-        let openai_config = OpenAIConfig::new().with_api_key(api_key);
-        let client = Client::with_config(openai_config);
+        let client = ProviderClient::new(api_key, &config)?;
+
+        Ok(Self {
+            client,
+            config,
+            retry: RetryPolicy::default(),
+        })
+    }
 
-        Ok(Self { client, config })
+    /// Retry transient API failures (network errors, rate limiting) with
+    /// `retry` instead of the default policy
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
     }
 
     /// Send a query to the AI assistant with optional context
@@ -40,6 +74,211 @@ impl AIAssistant {
     /// # Returns
     /// The AI's response as a string
     pub async fn send_query(&self, query: &str, context: Option<&str>) -> Result<String> {
+        let request = self.build_request(query, context, None)?;
+        self.complete(request).await
+    }
+
+    /// Like [`Self::send_query`], sending each piece of the answer to
+    /// `tokens` as it arrives. Returns the whole answer. Stops early with an
+    /// error when the user aborts processing (see [`crate::utils::trigger_abort`]).
+    pub async fn send_query_streaming(
+        &self,
+        query: &str,
+        context: Option<&str>,
+        tokens: &UnboundedSender<String>,
+    ) -> Result<String> {
+        let request = self.build_request(query, context, None)?;
+        self.complete_streaming(request, tokens).await
+    }
+
+    /// Like [`Self::send_query`], sending the earlier turns of
+    /// `conversation` along with the query and appending both the query and
+    /// the answer to it. Old turns are summarised first if the history would
+    /// not fit in the model's context window.
+    pub async fn send_conversation(
+        &self,
+        conversation: &mut Conversation,
+        query: &str,
+        context: Option<&str>,
+    ) -> Result<String> {
+        let request = self
+            .prepare_conversation(conversation, query, context)
+            .await?;
+        let response = self.complete(request).await?;
+        conversation.push(ChatRole::User, query);
+        conversation.push(ChatRole::Assistant, response.clone());
+        Ok(response)
+    }
+
+    /// [`Self::send_conversation`] with the answer streamed as in
+    /// [`Self::send_query_streaming`]. The conversation is only updated once
+    /// the whole answer has arrived.
+    pub async fn send_conversation_streaming(
+        &self,
+        conversation: &mut Conversation,
+        query: &str,
+        context: Option<&str>,
+        tokens: &UnboundedSender<String>,
+    ) -> Result<String> {
+        let request = self
+            .prepare_conversation(conversation, query, context)
+            .await?;
+        let response = self.complete_streaming(request, tokens).await?;
+        conversation.push(ChatRole::User, query);
+        conversation.push(ChatRole::Assistant, response.clone());
+        Ok(response)
+    }
+
+    /// Tokens left for conversation history once the system prompt,
+    /// `context`, `query` and the answer are accounted for
+    fn history_budget(&self, query: &str, context: Option<&str>) -> usize {
+        (self.config.context_window as usize).saturating_sub(
+            self.config.max_tokens as usize
+                + estimate_tokens(&Self::system_prompt())
+                + context.map_or(0, estimate_tokens)
+                + estimate_tokens(query),
+        )
+    }
+
+    /// Compact `conversation` to fit the budget for `query`, then build the
+    /// request. If summarising fails the oldest turns are dropped instead.
+    async fn prepare_conversation(
+        &self,
+        conversation: &mut Conversation,
+        query: &str,
+        context: Option<&str>,
+    ) -> Result<CreateChatCompletionRequest> {
+        let overflow = conversation.overflow(self.history_budget(query, context));
+        if overflow > 0 {
+            let summary = match self.summarise(conversation, overflow).await {
+                Ok(summary) => Some(summary),
+                Err(e) => {
+                    crate::config::log_event(
+                        "AI",
+                        &format!("Failed to summarise conversation, dropping old turns: {e:#}"),
+                    );
+                    None
+                }
+            };
+            conversation.compact(overflow, summary);
+        }
+        self.build_request(query, context, Some(conversation))
+    }
+
+    /// Summary of the existing summary and the `count` oldest active
+    /// messages of `conversation`
+    async fn summarise(&self, conversation: &Conversation, count: usize) -> Result<String> {
+        let mut transcript = String::new();
+        if let Some(summary) = &conversation.summary {
+            let _ = writeln!(transcript, "Earlier summary: {summary}\n");
+        }
+        for message in conversation.active_messages().iter().take(count) {
+            let speaker = match message.role {
+                ChatRole::User => "User",
+                ChatRole::Assistant => "Assistant",
+            };
+            let _ = writeln!(transcript, "{speaker}: {}\n", message.content);
+        }
+        // Keep the newest part if even the transcript is too long to send
+        let limit = self.history_budget("", None).max(1) * 4;
+        if let Some((start, c)) = transcript.char_indices().rev().nth(limit) {
+            transcript = transcript.split_off(start + c.len_utf8());
+        }
+
+        let request = CreateChatCompletionRequestArgs::default()
+            .model(&self.config.model)
+            .messages(vec![
+                ChatCompletionRequestSystemMessageArgs::default()
+                    .content(SUMMARY_PROMPT)
+                    .build()
+                    .context("Failed to build system message")?
+                    .into(),
+                ChatCompletionRequestUserMessageArgs::default()
+                    .content(transcript)
+                    .build()
+                    .context("Failed to build user message")?
+                    .into(),
+            ])
+            .temperature(0.0)
+            .max_tokens(SUMMARY_MAX_TOKENS.min(self.config.max_tokens))
+            .build()
+            .context("Failed to build summary request")?;
+        self.complete(request).await
+    }
+
+    async fn complete(&self, request: CreateChatCompletionRequest) -> Result<String> {
+        let provider = self.config.provider.label();
+        let response = self
+            .retry
+            .retry_async(&format!("{provider} request"), || async {
+                self.client
+                    .create(request.clone())
+                    .await
+                    .map_err(|e| anyhow::Error::new(e).context(format!("{provider} API error")))
+            })
+            .await?;
+
+        response
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.clone())
+            .ok_or_else(|| anyhow::anyhow!("No response content received"))
+    }
+
+    async fn complete_streaming(
+        &self,
+        request: CreateChatCompletionRequest,
+        tokens: &UnboundedSender<String>,
+    ) -> Result<String> {
+        let provider = self.config.provider.label();
+        // Only opening the stream is retried; a broken stream would repeat
+        // tokens already sent
+        let mut stream = self
+            .retry
+            .retry_async(&format!("{provider} request"), || async {
+                self.client
+                    .create_stream(request.clone())
+                    .await
+                    .map_err(|e| anyhow::Error::new(e).context(format!("{provider} API error")))
+            })
+            .await?;
+
+        let mut response = String::new();
+        loop {
+            let chunk = tokio::select! {
+                chunk = stream.next() => chunk,
+                () = wait_for_abort() => anyhow::bail!("AI response cancelled by user"),
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            let chunk = chunk
+                .map_err(|e| anyhow::Error::new(e).context(format!("{provider} stream error")))?;
+            if let Some(token) = chunk
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.delta.content)
+                && !token.is_empty()
+            {
+                response.push_str(&token);
+                // The receiver going away only means nobody is watching
+                let _ = tokens.send(token);
+            }
+        }
+
+        if response.is_empty() {
+            anyhow::bail!("No response content received");
+        }
+        Ok(response)
+    }
+
+    fn build_request(
+        &self,
+        query: &str,
+        context: Option<&str>,
+        history: Option<&Conversation>,
+    ) -> Result<CreateChatCompletionRequest> {
         let mut messages: Vec<ChatCompletionRequestMessage> = vec![
             ChatCompletionRequestSystemMessageArgs::default()
                 .content(Self::system_prompt())
@@ -59,6 +298,33 @@ impl AIAssistant {
             );
         }
 
+        // Add earlier turns, with older ones as a summary
+        if let Some(conversation) = history {
+            if let Some(summary) = &conversation.summary {
+                messages.push(
+                    ChatCompletionRequestSystemMessageArgs::default()
+                        .content(format!("Summary of the earlier conversation:\n{summary}"))
+                        .build()
+                        .context("Failed to build summary message")?
+                        .into(),
+                );
+            }
+            for message in conversation.active_messages() {
+                messages.push(match message.role {
+                    ChatRole::User => ChatCompletionRequestUserMessageArgs::default()
+                        .content(message.content.as_str())
+                        .build()
+                        .context("Failed to build user message")?
+                        .into(),
+                    ChatRole::Assistant => ChatCompletionRequestAssistantMessageArgs::default()
+                        .content(message.content.as_str())
+                        .build()
+                        .context("Failed to build assistant message")?
+                        .into(),
+                });
+            }
+        }
+
         // Add user query
         messages.push(
             ChatCompletionRequestUserMessageArgs::default()
@@ -68,34 +334,24 @@ impl AIAssistant {
                 .into(),
         );
 
-        let request = CreateChatCompletionRequestArgs::default()
+        CreateChatCompletionRequestArgs::default()
             .model(&self.config.model)
             .messages(messages)
             .temperature(self.config.temperature)
             .max_tokens(self.config.max_tokens)
             .build()
-            .context("Failed to build chat completion request")?;
-
-        let response = self
-            .client
-            .chat()
-            .create(request)
-            .await
-            .map_err(|e| anyhow::anyhow!("OpenAI API error: {e}"))?;
-
-        response
-            .choices
-            .first()
-            .and_then(|choice| choice.message.content.clone())
-            .ok_or_else(|| anyhow::anyhow!("No response content received"))
+            .context("Failed to build chat completion request")
     }
 
-    /// Test the connection to OpenAI API
+    /// Test the connection to the configured backend
     pub async fn test_connection(&self) -> Result<()> {
         // Use a minimal query to test connection
         let response = self.send_query("Hi", None).await?;
         if response.is_empty() {
-            anyhow::bail!("Received empty response from OpenAI API");
+            anyhow::bail!(
+                "Received empty response from {}",
+                self.config.provider.label()
+            );
         }
         Ok(())
     }
@@ -0,0 +1,282 @@
+//! Prompt context built from an analysis.
+//!
+//! [`build_context`] turns the column summaries, health risks and strongest
+//! correlations of an analysis into a compact plain-text block for
+//! [`super::client::AIAssistant::send_query`], so answers refer to the
+//! user's actual columns. Values taken from the data itself (samples, most
+//! frequent categories and text values) are left out unless
+//! [`ContextOptions::redact_samples`] is turned off, and always for the
+//! columns listed in [`ContextOptions::redacted_columns`].
+
+use crate::analyser::logic::{
+    AnalysisResponse, ColumnStats, ColumnSummary, CorrelationMatrix, FileHealth,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+const REDACTED: &str = "[redacted]";
+
+/// What goes into the prompt context and how much of it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContextOptions {
+    /// Leave out values copied from the data
+    pub redact_samples: bool,
+    /// Columns whose values are always left out, e.g. personal data
+    pub redacted_columns: Vec<String>,
+    /// Columns described; the rest are only counted
+    pub max_columns: usize,
+    /// Most frequent values listed per categorical column
+    pub max_categories: usize,
+    /// Sample values listed per column
+    pub max_samples: usize,
+    /// Correlated pairs listed, strongest first
+    pub max_correlations: usize,
+    /// Weakest absolute correlation worth listing
+    pub min_correlation: f64,
+}
+
+impl Default for ContextOptions {
+    fn default() -> Self {
+        Self {
+            redact_samples: true,
+            redacted_columns: Vec::new(),
+            max_columns: 50,
+            max_categories: 5,
+            max_samples: 3,
+            max_correlations: 5,
+            min_correlation: 0.5,
+        }
+    }
+}
+
+impl ContextOptions {
+    fn redacts(&self, column: &str) -> bool {
+        self.redact_samples
+            || self
+                .redacted_columns
+                .iter()
+                .any(|c| c.eq_ignore_ascii_case(column))
+    }
+}
+
+/// The parts of an analysis the context is built from. Deserializes from an
+/// `AnalysisResponse` as sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisContext {
+    pub file_name: String,
+    pub total_row_count: usize,
+    pub column_count: usize,
+    pub summary: Vec<ColumnSummary>,
+    pub health: FileHealth,
+    #[serde(default)]
+    pub correlation_matrix: Option<CorrelationMatrix>,
+}
+
+impl From<&AnalysisResponse> for AnalysisContext {
+    fn from(response: &AnalysisResponse) -> Self {
+        Self {
+            file_name: response.file_name.clone(),
+            total_row_count: response.total_row_count,
+            column_count: response.column_count,
+            summary: response.summary.clone(),
+            health: response.health.clone(),
+            correlation_matrix: response.correlation_matrix.clone(),
+        }
+    }
+}
+
+/// Compact plain-text description of `analysis` for an AI prompt
+pub fn build_context(analysis: &AnalysisContext, options: &ContextOptions) -> String {
+    let mut ctx = String::new();
+    let _ = writeln!(
+        ctx,
+        "Dataset: {} ({} rows, {} columns)",
+        analysis.file_name, analysis.total_row_count, analysis.column_count
+    );
+    let _ = writeln!(ctx, "Health score: {:.0}/100", analysis.health.score);
+    if !analysis.health.risks.is_empty() {
+        ctx.push_str("Risks:\n");
+        for risk in &analysis.health.risks {
+            let _ = writeln!(ctx, "- {risk}");
+        }
+    }
+
+    ctx.push_str("Columns (name | type | nulls | distinct | details):\n");
+    for col in analysis.summary.iter().take(options.max_columns) {
+        let _ = writeln!(
+            ctx,
+            "- {} | {} | {:.1}% | {} | {}",
+            col.name,
+            col.kind.as_str(),
+            col.null_pct(),
+            col.stats.n_distinct(),
+            column_details(col, options)
+        );
+    }
+    let omitted = analysis.summary.len().saturating_sub(options.max_columns);
+    if omitted > 0 {
+        let _ = writeln!(ctx, "- ... {omitted} more columns not shown");
+    }
+
+    if let Some(matrix) = &analysis.correlation_matrix {
+        let pairs = strongest_correlations(matrix, options);
+        if !pairs.is_empty() {
+            ctx.push_str("Strongest correlations:\n");
+            for (a, b, r) in pairs {
+                let _ = writeln!(ctx, "- {a} ~ {b}: {r:.2}");
+            }
+        }
+    }
+    ctx
+}
+
+fn column_details(col: &ColumnSummary, options: &ContextOptions) -> String {
+    let redact = options.redacts(&col.name);
+    let mut details = match &col.stats {
+        ColumnStats::Numeric(s) => {
+            let fmt = |label: &str, v: Option<f64>| v.map(|v| format!("{label} {v:.4}"));
+            [
+                fmt("min", s.min),
+                fmt("median", s.median),
+                fmt("mean", s.mean),
+                fmt("max", s.max),
+                fmt("std", s.std_dev),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ")
+        }
+        ColumnStats::Temporal(s) => format!(
+            "from {} to {}",
+            s.min.as_deref().unwrap_or("?"),
+            s.max.as_deref().unwrap_or("?")
+        ),
+        ColumnStats::Boolean(s) => s.positive_rate().map_or_else(
+            || "no values".to_owned(),
+            |rate| format!("{:.1}% true", rate * 100.0),
+        ),
+        ColumnStats::Text(s) => {
+            let mut text = format!("length {}-{}", s.min_length, s.max_length);
+            if let Some((value, count)) = &s.top_value {
+                let value = if redact { REDACTED } else { value.as_str() };
+                let _ = write!(text, ", most common {value} ({count})");
+            }
+            text
+        }
+        ColumnStats::Categorical(freq) => {
+            if redact {
+                format!("{} categories, values {REDACTED}", freq.len())
+            } else {
+                let mut top: Vec<(&String, &usize)> = freq.iter().collect();
+                top.sort_unstable_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+                let top: Vec<String> = top
+                    .into_iter()
+                    .take(options.max_categories)
+                    .map(|(value, count)| format!("{value} ({count})"))
+                    .collect();
+                format!("top: {}", top.join(", "))
+            }
+        }
+        ColumnStats::Geo(s) => {
+            let mut text = format!(
+                "{} valid, {} invalid locations",
+                s.valid_count, s.invalid_count
+            );
+            if let Some(b) = &s.bounds {
+                let _ = write!(
+                    text,
+                    ", latitude {:.4} to {:.4}, longitude {:.4} to {:.4}",
+                    b.min_lat, b.max_lat, b.min_lon, b.max_lon
+                );
+            }
+            text
+        }
+    };
+
+    if !redact && !col.samples.is_empty() && options.max_samples > 0 {
+        let samples: Vec<&str> = col
+            .samples
+            .iter()
+            .take(options.max_samples)
+            .map(String::as_str)
+            .collect();
+        let _ = write!(details, "; e.g. {}", samples.join(", "));
+    }
+    if !col.interpretation.is_empty() {
+        let _ = write!(details, "; {}", col.interpretation.join(" "));
+    }
+    details
+}
+
+/// Distinct column pairs by descending absolute correlation
+fn strongest_correlations<'a>(
+    matrix: &'a CorrelationMatrix,
+    options: &ContextOptions,
+) -> Vec<(&'a str, &'a str, f64)> {
+    let mut pairs = Vec::new();
+    for (i, row) in matrix.data.iter().enumerate() {
+        for (j, &r) in row.iter().enumerate().skip(i + 1) {
+            if r.is_finite()
+                && r.abs() >= options.min_correlation
+                && let (Some(a), Some(b)) = (matrix.columns.get(i), matrix.columns.get(j))
+            {
+                pairs.push((a.as_str(), b.as_str(), r));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.2.abs().total_cmp(&a.2.abs()));
+    pairs.truncate(options.max_correlations);
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::logic::analyse_df_lazy;
+    use polars::prelude::*;
+
+    #[test]
+    fn test_build_context_redacts_values() -> anyhow::Result<()> {
+        let df = df! {
+            "email" => &["a@example.com", "b@example.com", "a@example.com", "c@example.com"],
+            "amount" => &[10.0, 20.0, 30.0, 40.0],
+            "quantity" => &[1_i64, 2, 3, 4],
+        }?;
+        let analysis = AnalysisContext {
+            file_name: "orders.csv".to_owned(),
+            total_row_count: 4,
+            column_count: 3,
+            summary: analyse_df_lazy(df.lazy(), 0.0, 100)?,
+            health: FileHealth {
+                score: 82.0,
+                risks: vec!["Small sample".to_owned()],
+            },
+            correlation_matrix: Some(CorrelationMatrix {
+                columns: vec!["amount".to_owned(), "quantity".to_owned()],
+                data: vec![vec![1.0, 0.98], vec![0.98, 1.0]],
+                omitted_columns: vec![],
+            }),
+        };
+
+        let redacted = build_context(&analysis, &ContextOptions::default());
+        assert!(redacted.starts_with("Dataset: orders.csv (4 rows, 3 columns)"));
+        assert!(redacted.contains("- Small sample"));
+        assert!(redacted.contains("- amount ~ quantity: 0.98"));
+        assert!(!redacted.contains("example.com"));
+
+        let open = ContextOptions {
+            redact_samples: false,
+            ..ContextOptions::default()
+        };
+        assert!(build_context(&analysis, &open).contains("a@example.com"));
+
+        let per_column = ContextOptions {
+            redacted_columns: vec!["EMAIL".to_owned()],
+            ..open
+        };
+        assert!(!build_context(&analysis, &per_column).contains("example.com"));
+        Ok(())
+    }
+}
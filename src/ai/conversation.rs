@@ -0,0 +1,287 @@
+//! Multi-turn conversations with the AI assistant.
+//!
+//! Each conversation is stored as `ai/conversations/<id>.json` under the app
+//! data directory, tagged with the data file it was started on so the
+//! assistant can list the conversations for the file that is open.
+//!
+//! Models only accept a limited number of tokens, so a long conversation
+//! cannot be resent in full. When the history no longer fits,
+//! [`Conversation::overflow`] says how many of the oldest messages must go
+//! and [`Conversation::compact`] folds them into a running summary that is
+//! sent in their place. Every message is kept on disk for display.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Characters of the first question used as a conversation's title
+const TITLE_MAX_CHARS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatRole {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    /// File that was open when the conversation started
+    #[serde(default)]
+    pub data_path: Option<String>,
+    /// Every message, oldest first
+    pub messages: Vec<ChatMessage>,
+    /// Summary sent in place of the messages before `summarised`
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Number of leading messages covered by `summary` (or dropped)
+    #[serde(default)]
+    pub summarised: usize,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A conversation without its messages, for listing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    pub id: String,
+    pub title: String,
+    pub data_path: Option<String>,
+    pub message_count: usize,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Conversation {
+    pub fn new(data_path: Option<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title: String::new(),
+            data_path,
+            messages: Vec::new(),
+            summary: None,
+            summarised: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Append a message. The first question becomes the title.
+    pub fn push(&mut self, role: ChatRole, content: impl Into<String>) {
+        let content = content.into();
+        if self.title.is_empty() && role == ChatRole::User {
+            let line = content.lines().next().unwrap_or_default().trim();
+            self.title = if line.chars().count() > TITLE_MAX_CHARS {
+                let cut: String = line.chars().take(TITLE_MAX_CHARS).collect();
+                format!("{}…", cut.trim_end())
+            } else {
+                line.to_owned()
+            };
+        }
+        self.updated_at = Utc::now();
+        self.messages.push(ChatMessage {
+            role,
+            content,
+            timestamp: self.updated_at,
+        });
+    }
+
+    /// Messages still sent to the model verbatim
+    pub fn active_messages(&self) -> &[ChatMessage] {
+        &self.messages[self.summarised.min(self.messages.len())..]
+    }
+
+    /// Estimated tokens of the summary and the active messages
+    pub fn history_tokens(&self) -> usize {
+        self.summary.as_deref().map_or(0, estimate_tokens)
+            + self
+                .active_messages()
+                .iter()
+                .map(|m| estimate_tokens(&m.content))
+                .sum::<usize>()
+    }
+
+    /// How many of the oldest active messages must be summarised or dropped
+    /// for the history to fit in `budget` tokens
+    pub fn overflow(&self, budget: usize) -> usize {
+        let mut tokens = self.history_tokens();
+        let mut count = 0;
+        for message in self.active_messages() {
+            if tokens <= budget {
+                break;
+            }
+            tokens -= estimate_tokens(&message.content);
+            count += 1;
+        }
+        count
+    }
+
+    /// Fold the `count` oldest active messages into `summary`. Without a
+    /// summary the messages are simply no longer sent and the previous
+    /// summary, if any, is kept.
+    pub fn compact(&mut self, count: usize, summary: Option<String>) {
+        self.summarised = (self.summarised + count).min(self.messages.len());
+        if let Some(summary) = summary.filter(|s| !s.trim().is_empty()) {
+            self.summary = Some(summary);
+        }
+        self.updated_at = Utc::now();
+    }
+
+    pub fn summary_entry(&self) -> ConversationSummary {
+        ConversationSummary {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            data_path: self.data_path.clone(),
+            message_count: self.messages.len(),
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Rough token count: about four characters per token for English text
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Conversations stored as one JSON file each in a directory
+#[derive(Debug, Clone)]
+pub struct ConversationStore {
+    dir: PathBuf,
+}
+
+impl ConversationStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The store in the app data directory
+    pub fn open_default() -> Self {
+        Self::new(
+            crate::utils::app_data_dir()
+                .join("ai")
+                .join("conversations"),
+        )
+    }
+
+    fn path(&self, id: &str) -> Result<PathBuf> {
+        crate::utils::id_path(&self.dir, id, "json", "conversation")
+    }
+
+    /// Conversations, most recently updated first, optionally only those
+    /// started on `data_path`. Unreadable files are skipped.
+    pub fn list(&self, data_path: Option<&str>) -> Result<Vec<ConversationSummary>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()));
+            }
+        };
+        let mut conversations: Vec<ConversationSummary> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    return None;
+                }
+                let json = std::fs::read_to_string(&path).ok()?;
+                serde_json::from_str::<Conversation>(&json).ok()
+            })
+            .filter(|c| data_path.is_none_or(|p| c.data_path.as_deref() == Some(p)))
+            .map(|c| c.summary_entry())
+            .collect();
+        conversations.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        Ok(conversations)
+    }
+
+    pub fn load(&self, id: &str) -> Result<Conversation> {
+        let path = self.path(id)?;
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Conversation {id} not found"))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, conversation: &Conversation) -> Result<()> {
+        let path = self.path(&conversation.id)?;
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let json = serde_json::to_string_pretty(conversation)
+            .context("Failed to serialize conversation")?;
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Delete conversation `id`. Returns false if it did not exist.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let path = self.path(id)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversation_overflow_and_compact() {
+        let mut conversation = Conversation::new(None);
+        conversation.push(ChatRole::User, "a".repeat(400));
+        conversation.push(ChatRole::Assistant, "b".repeat(400));
+        conversation.push(ChatRole::User, "c".repeat(40));
+        assert_eq!(conversation.title.chars().count(), TITLE_MAX_CHARS + 1);
+        assert_eq!(conversation.history_tokens(), 210);
+
+        assert_eq!(conversation.overflow(1000), 0);
+        assert_eq!(conversation.overflow(150), 1);
+        assert_eq!(conversation.overflow(10), 2);
+        assert_eq!(conversation.overflow(0), 3);
+
+        conversation.compact(2, Some("Asked about a and b".to_owned()));
+        assert_eq!(conversation.active_messages().len(), 1);
+        assert_eq!(conversation.messages.len(), 3);
+        assert_eq!(conversation.history_tokens(), 5 + 10);
+
+        // Truncation without a new summary keeps the old one
+        conversation.compact(1, None);
+        assert!(conversation.active_messages().is_empty());
+        assert_eq!(conversation.summary.as_deref(), Some("Asked about a and b"));
+    }
+
+    #[test]
+    fn test_conversation_store_roundtrip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = ConversationStore::new(dir.path());
+        assert!(store.list(None)?.is_empty());
+
+        let mut first = Conversation::new(Some("sales.csv".to_owned()));
+        first.push(ChatRole::User, "What is the mean price?");
+        store.save(&first)?;
+        let mut second = Conversation::new(Some("stock.csv".to_owned()));
+        second.push(ChatRole::User, "Any outliers?");
+        store.save(&second)?;
+
+        assert_eq!(store.list(None)?.len(), 2);
+        let for_sales = store.list(Some("sales.csv"))?;
+        assert_eq!(for_sales.len(), 1);
+        assert_eq!(for_sales[0].title, "What is the mean price?");
+        assert_eq!(store.load(&first.id)?.messages.len(), 1);
+
+        assert!(store.delete(&first.id)?);
+        assert!(!store.delete(&first.id)?);
+        assert!(store.load("../../secrets").is_err());
+        Ok(())
+    }
+}
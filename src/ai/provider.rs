@@ -0,0 +1,182 @@
+//! Chat completion backends.
+//!
+//! Every supported backend speaks the OpenAI chat completions API; they
+//! differ in base URL and in how requests authenticate. [`AIProvider`] picks
+//! sensible defaults for both, and `AIConfig::base_url` and
+//! `AIConfig::auth_scheme` override them, so Azure OpenAI, Ollama or any
+//! OpenAI-compatible server on the local network can be used without
+//! sending data to a public cloud.
+
+use anyhow::Result;
+use async_openai::{
+    Client,
+    config::{AzureConfig, OpenAIConfig},
+    error::OpenAIError,
+    types::{
+        ChatCompletionResponseStream, CreateChatCompletionRequest, CreateChatCompletionResponse,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::AIConfig;
+
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434/v1";
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-06-01";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AIProvider {
+    #[default]
+    #[serde(rename = "openai")]
+    OpenAI,
+    /// Azure OpenAI resource; the model is the deployment name
+    #[serde(rename = "azure_openai")]
+    AzureOpenAI,
+    /// Local Ollama server
+    Ollama,
+    /// Any other server implementing the OpenAI chat completions API,
+    /// e.g. vLLM, LM Studio or LocalAI
+    #[serde(rename = "openai_compatible")]
+    OpenAICompatible,
+}
+
+impl AIProvider {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OpenAI => "OpenAI",
+            Self::AzureOpenAI => "Azure OpenAI",
+            Self::Ollama => "Ollama",
+            Self::OpenAICompatible => "OpenAI-compatible server",
+        }
+    }
+
+    /// Base URL used when none is configured
+    pub fn default_base_url(self) -> Option<&'static str> {
+        match self {
+            Self::OpenAI => Some(OPENAI_BASE_URL),
+            Self::Ollama => Some(OLLAMA_BASE_URL),
+            Self::AzureOpenAI | Self::OpenAICompatible => None,
+        }
+    }
+
+    pub fn default_auth_scheme(self) -> AIAuthScheme {
+        match self {
+            Self::OpenAI | Self::OpenAICompatible => AIAuthScheme::Bearer,
+            Self::AzureOpenAI => AIAuthScheme::ApiKeyHeader,
+            Self::Ollama => AIAuthScheme::None,
+        }
+    }
+}
+
+/// How requests carry the API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AIAuthScheme {
+    /// `Authorization: Bearer <key>`
+    Bearer,
+    /// `api-key: <key>` as used by Azure OpenAI, with the model as the
+    /// deployment name and an `api-version` query parameter
+    ApiKeyHeader,
+    /// No key; for local servers
+    None,
+}
+
+/// Chat completions client for the configured backend
+#[derive(Clone)]
+pub enum ProviderClient {
+    OpenAI(Client<OpenAIConfig>),
+    Azure(Client<AzureConfig>),
+}
+
+impl ProviderClient {
+    /// Client for `config`. `api_key` is ignored when the auth scheme is
+    /// [`AIAuthScheme::None`].
+    pub fn new(api_key: String, config: &AIConfig) -> Result<Self> {
+        let base_url = config.resolved_base_url()?;
+        Ok(match config.resolved_auth_scheme() {
+            AIAuthScheme::Bearer => Self::OpenAI(Client::with_config(
+                OpenAIConfig::new()
+                    .with_api_base(base_url)
+                    .with_api_key(api_key),
+            )),
+            AIAuthScheme::None => Self::OpenAI(Client::with_config(
+                OpenAIConfig::new()
+                    .with_api_base(base_url)
+                    .with_api_key(String::new()),
+            )),
+            AIAuthScheme::ApiKeyHeader => Self::Azure(Client::with_config(
+                AzureConfig::new()
+                    .with_api_base(base_url)
+                    .with_api_key(api_key)
+                    .with_deployment_id(config.model.clone())
+                    .with_api_version(
+                        config
+                            .api_version
+                            .clone()
+                            .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_owned()),
+                    ),
+            )),
+        })
+    }
+
+    pub async fn create(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<CreateChatCompletionResponse, OpenAIError> {
+        match self {
+            Self::OpenAI(client) => client.chat().create(request).await,
+            Self::Azure(client) => client.chat().create(request).await,
+        }
+    }
+
+    /// Like [`Self::create`], with the response streamed in chunks
+    pub async fn create_stream(
+        &self,
+        request: CreateChatCompletionRequest,
+    ) -> Result<ChatCompletionResponseStream, OpenAIError> {
+        match self {
+            Self::OpenAI(client) => client.chat().create_stream(request).await,
+            Self::Azure(client) => client.chat().create_stream(request).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_defaults_and_overrides() -> Result<()> {
+        let mut config = AIConfig {
+            provider: AIProvider::Ollama,
+            ..AIConfig::default()
+        };
+        assert_eq!(config.resolved_base_url()?, OLLAMA_BASE_URL);
+        assert!(!config.requires_api_key());
+
+        config.provider = AIProvider::OpenAICompatible;
+        assert!(config.resolved_base_url().is_err());
+        config.base_url = Some("http://llm.internal:8000/v1/".to_owned());
+        assert_eq!(config.resolved_base_url()?, "http://llm.internal:8000/v1");
+        assert_eq!(config.resolved_auth_scheme(), AIAuthScheme::Bearer);
+
+        config.auth_scheme = Some(AIAuthScheme::None);
+        assert!(!config.requires_api_key());
+        assert!(matches!(
+            ProviderClient::new(String::new(), &config)?,
+            ProviderClient::OpenAI(_)
+        ));
+
+        config.provider = AIProvider::AzureOpenAI;
+        config.auth_scheme = None;
+        config.base_url = Some("ftp://example.com".to_owned());
+        assert!(config.resolved_base_url().is_err());
+        config.base_url = Some("https://corp.openai.azure.com".to_owned());
+        assert!(matches!(
+            ProviderClient::new("key".to_owned(), &config)?,
+            ProviderClient::Azure(_)
+        ));
+        Ok(())
+    }
+}
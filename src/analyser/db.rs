@@ -1,35 +1,98 @@
+pub mod ddl;
+pub mod view;
+
+pub use ddl::{DdlFailure, DdlKind, DdlMode, DdlReport, DdlSuggestion, DdlSuggestions};
+pub use view::{CleaningView, ViewColumn};
+
+use super::logic::health::{calculate_file_health, column_health_component};
+use super::logic::types::{ColumnStats, ColumnSummary};
+use crate::utils::retry::RetryPolicy;
 use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
 use polars::prelude::*;
 use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
 use sqlx::{Pool, Postgres};
 
 pub struct DbClient {
     pool: Pool<Postgres>,
+    retry: RetryPolicy,
+    read_only: bool,
 }
 
 impl DbClient {
+    /// Connect using the retry policy from the app settings
     pub async fn connect(options: PgConnectOptions) -> Result<Self> {
-        let pool = PgPoolOptions::new()
-            .max_connections(5)
-            .acquire_timeout(std::time::Duration::from_secs(10))
-            .idle_timeout(Some(std::time::Duration::from_secs(300))) // Close idle connections after 5 minutes
-            .max_lifetime(Some(std::time::Duration::from_secs(1800))) // Recycle connections after 30 minutes
-            .connect_with(options)
-            .await
-            .context("Failed to connect to PostgreSQL (timeout after 10s)")?;
-        Ok(Self { pool })
+        Self::connect_with_retry(options, RetryPolicy::from_settings()).await
     }
 
-    pub async fn init_schema(&self) -> Result<()> {
+    /// Connect, retrying transient failures of the connection and of later
+    /// pushes according to `retry`
+    pub async fn connect_with_retry(options: PgConnectOptions, retry: RetryPolicy) -> Result<Self> {
+        let pool = retry
+            .retry_async("Connecting to PostgreSQL", || async {
+                PgPoolOptions::new()
+                    .max_connections(5)
+                    .acquire_timeout(std::time::Duration::from_secs(10))
+                    .idle_timeout(Some(std::time::Duration::from_secs(300))) // Close idle connections after 5 minutes
+                    .max_lifetime(Some(std::time::Duration::from_secs(1800))) // Recycle connections after 30 minutes
+                    .connect_with(options.clone())
+                    .await
+                    .context("Failed to connect to PostgreSQL (timeout after 10s)")
+            })
+            .await?;
+        Ok(Self {
+            pool,
+            retry,
+            read_only: false,
+        })
+    }
+
+    /// Block every write and DDL operation of this client, for connections
+    /// saved as read-only
+    #[must_use]
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn ensure_writable(&self, operation: &str) -> Result<()> {
+        if self.read_only {
+            anyhow::bail!("Connection is read-only: {operation} is blocked");
+        }
         Ok(())
     }
 
+    pub async fn init_schema(&self) -> Result<()> {
+        self.ensure_writable("schema initialisation")
+    }
+
+    /// Create the table if needed and COPY `df` into it. A failed COPY
+    /// leaves no rows behind, so transient failures retry the whole push.
     pub async fn push_dataframe(
         &self,
         analysis_id: i32,
         df: &DataFrame,
         schema_name: Option<&str>,
         table_name: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_writable("pushing data")?;
+        self.retry
+            .retry_async("Pushing data to PostgreSQL", || {
+                self.copy_dataframe(analysis_id, df, schema_name, table_name)
+            })
+            .await
+    }
+
+    async fn copy_dataframe(
+        &self,
+        analysis_id: i32,
+        df: &DataFrame,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
     ) -> Result<()> {
         let schema = df.schema();
         self.prepare_table(&schema, analysis_id, schema_name, table_name)
@@ -75,12 +138,29 @@ impl DbClient {
         Ok(())
     }
 
+    /// Create the table if needed and COPY the CSV file at `path` into it,
+    /// retrying transient failures like [`DbClient::push_dataframe`]
     pub async fn push_from_csv_file(
         &self,
         path: &std::path::Path,
         schema: &Schema,
         schema_name: Option<&str>,
         table_name: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_writable("pushing data")?;
+        self.retry
+            .retry_async("Pushing CSV to PostgreSQL", || {
+                self.copy_csv_file(path, schema, schema_name, table_name)
+            })
+            .await
+    }
+
+    async fn copy_csv_file(
+        &self,
+        path: &std::path::Path,
+        schema: &Schema,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
     ) -> Result<()> {
         self.prepare_table(schema, 0, schema_name, table_name)
             .await?;
@@ -100,6 +180,14 @@ impl DbClient {
         let mut buf = vec![0u8; 1024 * 1024]; // 1MB buffer
 
         loop {
+            // Between chunks, so Abort stops a long COPY without committing it
+            if crate::utils::is_aborted() {
+                writer
+                    .abort("Aborted by user")
+                    .await
+                    .context("Failed to abort COPY command")?;
+                anyhow::bail!("Database push aborted by user");
+            }
             let n = file
                 .read(&mut buf)
                 .context("Failed to read from CSV file")?;
@@ -123,6 +211,138 @@ impl DbClient {
         Ok(())
     }
 
+    /// Append one row per column of `summaries` to `<table>_profile`
+    /// (created if needed): counts, nulls, distinct values, numeric
+    /// statistics and health metrics, all stamped with the same
+    /// `profiled_at` so BI tools can track quality across loads.
+    pub async fn push_profile(
+        &self,
+        summaries: &[ColumnSummary],
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Result<()> {
+        self.ensure_writable("pushing the column profile")?;
+        let profiled_at = Utc::now();
+        self.retry
+            .retry_async("Pushing column profile to PostgreSQL", || {
+                self.insert_profile(summaries, profiled_at, schema_name, table_name)
+            })
+            .await
+    }
+
+    async fn insert_profile(
+        &self,
+        summaries: &[ColumnSummary],
+        profiled_at: DateTime<Utc>,
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Result<()> {
+        let profile_table = format!("{}_profile", table_name.unwrap_or("data_0"));
+        let full_identifier = Self::get_full_identifier(0, schema_name, Some(&profile_table));
+
+        sqlx::query(&format!(
+            "CREATE TABLE IF NOT EXISTS {full_identifier} (\
+             profiled_at TIMESTAMPTZ NOT NULL, \
+             column_name TEXT NOT NULL, \
+             standardized_name TEXT, \
+             kind TEXT, \
+             row_count BIGINT, \
+             null_count BIGINT, \
+             null_pct DOUBLE PRECISION, \
+             distinct_count BIGINT, \
+             uniqueness_ratio DOUBLE PRECISION, \
+             has_special BOOLEAN, \
+             min_value DOUBLE PRECISION, \
+             max_value DOUBLE PRECISION, \
+             mean DOUBLE PRECISION, \
+             median DOUBLE PRECISION, \
+             std_dev DOUBLE PRECISION, \
+             health_penalty DOUBLE PRECISION, \
+             health_risks TEXT, \
+             file_health_score DOUBLE PRECISION)"
+        ))
+        .execute(&self.pool)
+        .await
+        .with_context(|| format!("Failed to create profile table '{full_identifier}'"))?;
+
+        let file_score = f64::from(calculate_file_health(summaries).score);
+        let insert = format!(
+            "INSERT INTO {full_identifier} VALUES \
+             ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)"
+        );
+
+        // One transaction so a failed push leaves no partial profile behind
+        let mut tx = self.pool.begin().await?;
+        for summary in summaries {
+            let health = column_health_component(summary);
+            let numeric = match &summary.stats {
+                ColumnStats::Numeric(s) => Some(s),
+                _ => None,
+            };
+            sqlx::query(&insert)
+                .bind(profiled_at)
+                .bind(&summary.name)
+                .bind(&summary.standardised_name)
+                .bind(summary.kind.as_str())
+                .bind(i64::try_from(summary.count).unwrap_or(i64::MAX))
+                .bind(i64::try_from(summary.nulls).unwrap_or(i64::MAX))
+                .bind(summary.null_pct())
+                .bind(i64::try_from(summary.stats.n_distinct()).unwrap_or(i64::MAX))
+                .bind(summary.uniqueness_ratio())
+                .bind(summary.has_special)
+                .bind(numeric.and_then(|s| s.min))
+                .bind(numeric.and_then(|s| s.max))
+                .bind(numeric.and_then(|s| s.mean))
+                .bind(numeric.and_then(|s| s.median))
+                .bind(numeric.and_then(|s| s.std_dev))
+                .bind(health.penalty)
+                .bind(health.risks.join(" "))
+                .bind(file_score)
+                .execute(&mut *tx)
+                .await
+                .with_context(|| format!("Failed to write profile of column '{}'", summary.name))?;
+        }
+        tx.commit()
+            .await
+            .context("Failed to commit column profile")?;
+        Ok(())
+    }
+
+    /// Execute each suggested statement on its own, so one constraint the
+    /// full table violates does not hold back the others. Returns the
+    /// statements that failed.
+    pub async fn apply_ddl(&self, suggestions: &DdlSuggestions) -> Vec<DdlFailure> {
+        if let Err(e) = self.ensure_writable("applying DDL") {
+            return suggestions
+                .suggestions
+                .iter()
+                .map(|s| DdlFailure {
+                    statement: s.statement.clone(),
+                    error: format!("{e:#}"),
+                })
+                .collect();
+        }
+        let mut failures = Vec::new();
+        for suggestion in &suggestions.suggestions {
+            let result = self
+                .retry
+                .retry_async("Applying suggested DDL", || async {
+                    sqlx::query(&suggestion.statement)
+                        .execute(&self.pool)
+                        .await
+                        .map_err(anyhow::Error::from)
+                })
+                .await;
+            if let Err(e) = result {
+                failures.push(DdlFailure {
+                    statement: suggestion.statement.clone(),
+                    error: format!("{e:#}"),
+                });
+            }
+        }
+        failures
+    }
+
     fn get_full_identifier(
         analysis_id: i32,
         schema_name: Option<&str>,
@@ -144,6 +364,7 @@ impl DbClient {
         schema_name: Option<&str>,
         table_name: Option<&str>,
     ) -> Result<()> {
+        self.ensure_writable("creating tables")?;
         let full_identifier = Self::get_full_identifier(analysis_id, schema_name, table_name);
         let quote = |s: &str| format!("\"{}\"", s.replace('"', "\"\""));
 
@@ -177,3 +398,55 @@ impl DbClient {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A read-only client whose pool would fail to connect, so any error
+    /// other than the read-only one means a connection was attempted
+    fn read_only_client() -> DbClient {
+        let options = PgConnectOptions::new().host("127.0.0.1").port(1);
+        DbClient {
+            pool: PgPoolOptions::new().connect_lazy_with(options),
+            retry: RetryPolicy::default(),
+            read_only: false,
+        }
+        .with_read_only(true)
+    }
+
+    #[tokio::test]
+    async fn test_read_only_refuses_writes_without_connecting() {
+        let client = read_only_client();
+        assert!(client.is_read_only());
+
+        let err = client.init_schema().await.unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+
+        let df = df!("id" => [1, 2]).unwrap();
+        let err = client
+            .push_dataframe(1, &df, Some("public"), Some("data"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+
+        let suggestions = DdlSuggestions {
+            table: "\"public\".\"data\"".to_owned(),
+            profiled_rows: 2,
+            suggestions: vec![DdlSuggestion {
+                kind: DdlKind::NotNull,
+                column: "id".to_owned(),
+                statement: "ALTER TABLE \"public\".\"data\" ALTER COLUMN \"id\" SET NOT NULL"
+                    .to_owned(),
+                reason: "No nulls".to_owned(),
+            }],
+        };
+        let failures = client.apply_ddl(&suggestions).await;
+        assert_eq!(failures.len(), 1);
+        assert!(
+            failures[0].error.contains("read-only"),
+            "{}",
+            failures[0].error
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Index and constraint suggestions for imported tables.
+//!
+//! After an import the column profile says which columns are selective
+//! enough to be worth an index, which never held a null, and which numeric
+//! ranges were observed. [`DdlSuggestions::from_profile`] turns that into
+//! `CREATE INDEX` and `ALTER TABLE` statements that can be reviewed as a
+//! script or executed with [`DbClient::apply_ddl`]. The profile may come from
+//! a sample, so every statement is a suggestion: constraints are checked by
+//! PostgreSQL against all rows when applied, and later loads must satisfy
+//! them too.
+
+use super::DbClient;
+use crate::analyser::logic::types::{ColumnKind, ColumnStats, ColumnSummary};
+use serde::{Deserialize, Serialize};
+
+/// Share of distinct values above which a column is worth indexing
+const INDEX_MIN_UNIQUENESS: f64 = 0.9;
+
+/// Tables smaller than this are scanned faster than an index is maintained
+const INDEX_MIN_ROWS: usize = 1_000;
+
+/// Longer text values risk exceeding the B-tree entry size limit
+const INDEX_MAX_TEXT_LENGTH: usize = 1_000;
+
+/// Whether to suggest DDL after a database push, and whether to run it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DdlMode {
+    #[default]
+    Off,
+    /// Return the suggestions as a script
+    Suggest,
+    /// Return the suggestions and execute them
+    Apply,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DdlKind {
+    Index,
+    NotNull,
+    Check,
+}
+
+/// One suggested statement and the profile evidence behind it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DdlSuggestion {
+    pub kind: DdlKind,
+    pub column: String,
+    pub statement: String,
+    pub reason: String,
+}
+
+/// Suggested DDL for one table
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DdlSuggestions {
+    /// Quoted, schema-qualified table name
+    pub table: String,
+    /// Rows the profile was computed from
+    pub profiled_rows: usize,
+    pub suggestions: Vec<DdlSuggestion>,
+}
+
+/// A statement that failed when applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DdlFailure {
+    pub statement: String,
+    pub error: String,
+}
+
+/// Suggestions for a pushed table and, in [`DdlMode::Apply`], the outcome
+/// of executing them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DdlReport {
+    pub suggestions: DdlSuggestions,
+    /// The suggestions as a commented SQL script
+    pub script: String,
+    pub applied: bool,
+    pub failures: Vec<DdlFailure>,
+}
+
+impl DdlSuggestions {
+    /// Suggest indexes, NOT NULL and CHECK constraints for the table the
+    /// profiled columns were pushed to
+    pub fn from_profile(
+        summaries: &[ColumnSummary],
+        schema_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Self {
+        let table = DbClient::get_full_identifier(0, schema_name, table_name);
+        let bare_table = table_name.unwrap_or("data_0");
+        let mut suggestions = Vec::new();
+
+        for summary in summaries {
+            let column = quote(&summary.name);
+            if let Some(reason) = index_reason(summary) {
+                let index = quote(&format!("{bare_table}_{}_idx", summary.name));
+                suggestions.push(DdlSuggestion {
+                    kind: DdlKind::Index,
+                    column: summary.name.clone(),
+                    statement: format!("CREATE INDEX IF NOT EXISTS {index} ON {table} ({column});"),
+                    reason,
+                });
+            }
+            if summary.count > 0 && summary.nulls == 0 && summary.kind != ColumnKind::Nested {
+                suggestions.push(DdlSuggestion {
+                    kind: DdlKind::NotNull,
+                    column: summary.name.clone(),
+                    statement: format!("ALTER TABLE {table} ALTER COLUMN {column} SET NOT NULL;"),
+                    reason: format!("no nulls in {} profiled rows", summary.count),
+                });
+            }
+            if let Some((min, max)) = numeric_range(summary) {
+                let constraint = quote(&format!("{bare_table}_{}_range", summary.name));
+                suggestions.push(DdlSuggestion {
+                    kind: DdlKind::Check,
+                    column: summary.name.clone(),
+                    statement: format!(
+                        "ALTER TABLE {table} ADD CONSTRAINT {constraint} \
+                         CHECK ({column} BETWEEN {min} AND {max});"
+                    ),
+                    reason: format!("observed range {min} to {max}"),
+                });
+            }
+        }
+
+        Self {
+            table,
+            profiled_rows: summaries.iter().map(|s| s.count).max().unwrap_or(0),
+            suggestions,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.suggestions.is_empty()
+    }
+
+    /// The suggestions as a script to review before running, each statement
+    /// preceded by the reason it was suggested
+    pub fn script(&self) -> String {
+        let mut script = format!(
+            "-- Suggested indexes and constraints for {}\n\
+             -- Based on a profile of {} rows; review before running.\n",
+            self.table, self.profiled_rows
+        );
+        for suggestion in &self.suggestions {
+            script.push_str(&format!(
+                "\n-- {}: {}\n{}\n",
+                suggestion.column, suggestion.reason, suggestion.statement
+            ));
+        }
+        script
+    }
+}
+
+fn index_reason(summary: &ColumnSummary) -> Option<String> {
+    let indexable = match &summary.stats {
+        ColumnStats::Numeric(s) => s.is_integer,
+        ColumnStats::Text(s) => s.max_length <= INDEX_MAX_TEXT_LENGTH,
+        ColumnStats::Temporal(_) => true,
+        ColumnStats::Categorical(_) | ColumnStats::Boolean(_) | ColumnStats::Geo(_) => false,
+    };
+    let uniqueness = summary.uniqueness_ratio();
+    (indexable && summary.count >= INDEX_MIN_ROWS && uniqueness >= INDEX_MIN_UNIQUENESS).then(
+        || {
+            format!(
+                "{:.1}% of {} profiled values are distinct",
+                uniqueness * 100.0,
+                summary.count
+            )
+        },
+    )
+}
+
+/// Observed minimum and maximum as SQL literals
+fn numeric_range(summary: &ColumnSummary) -> Option<(String, String)> {
+    let ColumnStats::Numeric(stats) = &summary.stats else {
+        return None;
+    };
+    let (min, max) = (stats.min?, stats.max?);
+    if !min.is_finite() || !max.is_finite() {
+        return None;
+    }
+    let literal = |v: f64| {
+        if stats.is_integer {
+            format!("{v:.0}")
+        } else {
+            v.to_string()
+        }
+    };
+    Some((literal(min), literal(max)))
+}
+
+pub(crate) fn quote(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::logic::types::{NumericStats, TextStats};
+
+    fn summary(name: &str, nulls: usize, stats: ColumnStats) -> ColumnSummary {
+        let kind = match stats {
+            ColumnStats::Numeric(_) => ColumnKind::Numeric,
+            _ => ColumnKind::Text,
+        };
+        ColumnSummary {
+            name: name.to_owned(),
+            standardised_name: name.to_owned(),
+            kind,
+            count: 5_000,
+            nulls,
+            has_special: false,
+            stats,
+            interpretation: Vec::new(),
+            business_summary: Vec::new(),
+            ml_advice: Vec::new(),
+            samples: Vec::new(),
+            semantic_type: None,
+            suggested_name: None,
+        }
+    }
+
+    #[test]
+    fn test_suggestions_from_profile() {
+        let id = summary(
+            "order_id",
+            0,
+            ColumnStats::Numeric(NumericStats {
+                min: Some(1.0),
+                max: Some(5_000.0),
+                distinct_count: 5_000,
+                is_integer: true,
+                ..NumericStats::default()
+            }),
+        );
+        let note = summary(
+            "note",
+            12,
+            ColumnStats::Text(TextStats {
+                distinct: 40,
+                max_length: 80,
+                ..TextStats::default()
+            }),
+        );
+        let ddl = DdlSuggestions::from_profile(&[id, note], Some("sales"), Some("orders"));
+
+        let kinds: Vec<(DdlKind, &str)> = ddl
+            .suggestions
+            .iter()
+            .map(|s| (s.kind, s.column.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DdlKind::Index, "order_id"),
+                (DdlKind::NotNull, "order_id"),
+                (DdlKind::Check, "order_id"),
+            ]
+        );
+        assert_eq!(
+            ddl.suggestions[0].statement,
+            "CREATE INDEX IF NOT EXISTS \"orders_order_id_idx\" ON \"sales\".\"orders\" (\"order_id\");"
+        );
+        assert!(
+            ddl.suggestions[2]
+                .statement
+                .ends_with("CHECK (\"order_id\" BETWEEN 1 AND 5000);")
+        );
+
+        let script = ddl.script();
+        assert!(
+            script.starts_with("-- Suggested indexes and constraints for \"sales\".\"orders\"")
+        );
+        assert!(script.contains("-- order_id: no nulls in 5000 profiled rows\nALTER TABLE"));
+    }
+}
@@ -0,0 +1,447 @@
+//! Cleaning as a SQL view over an imported raw table.
+//!
+//! For ELT setups the raw data is pushed unchanged and the cleaning lives in
+//! the database. [`CleaningView`] translates the parts of the column configs
+//! and pipeline steps that have a direct SQL equivalent (renames, casts,
+//! trims, null standardisation, case changes and value maps) into a
+//! `CREATE OR REPLACE VIEW` statement. Anything else is listed at the top of
+//! the script so the view is never mistaken for the full cleaning.
+
+use super::DbClient;
+use super::ddl::quote;
+use crate::analyser::logic::types::{
+    ColumnCleanConfig, ColumnKind, ImputeMode, NormalisationMethod, TextCase,
+};
+use crate::pipeline::executor::step_op;
+use crate::pipeline::spec::Step;
+use polars::prelude::{DataType, Schema};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Values replaced with NULL by `standardise_nulls`, as in the in-app cleaning
+const NULL_TOKENS: &[&str] = &["null", "NULL", "", "N/A", "nan", "NaN"];
+
+/// One output column of the view
+#[derive(Debug, Clone, Serialize)]
+pub struct ViewColumn {
+    pub name: String,
+    /// SQL expression over the raw table's columns
+    pub expression: String,
+    /// Whether the value is text, which the string functions need
+    #[serde(skip)]
+    text: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleaningView {
+    /// Quoted, schema-qualified view name
+    pub view: String,
+    /// Quoted, schema-qualified raw table
+    pub source: String,
+    pub columns: Vec<ViewColumn>,
+    /// Options and steps with no SQL translation, left out of the view
+    pub skipped: Vec<String>,
+}
+
+impl CleaningView {
+    /// A view passing every column of the raw table through unchanged.
+    /// `schema` is the schema the raw table was created from.
+    pub fn new(
+        schema: &Schema,
+        schema_name: Option<&str>,
+        source_table: &str,
+        view_name: &str,
+    ) -> Self {
+        let columns = schema
+            .iter()
+            .map(|(name, dtype)| ViewColumn {
+                name: name.to_string(),
+                expression: quote(name),
+                text: is_text(dtype),
+            })
+            .collect();
+        Self {
+            view: DbClient::get_full_identifier(0, schema_name, Some(view_name)),
+            source: DbClient::get_full_identifier(0, schema_name, Some(source_table)),
+            columns,
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Apply column configs keyed by raw column name, in the order the
+    /// in-app cleaning uses: trim, case, null standardisation, value map,
+    /// cast, rename. `value_maps` replaces whole values, e.g. `"M"` to
+    /// `"Male"`, after a config's own `value_mapping`; values not in a map
+    /// are kept.
+    pub fn with_configs(
+        mut self,
+        configs: &HashMap<String, ColumnCleanConfig>,
+        value_maps: &HashMap<String, BTreeMap<String, String>>,
+    ) -> Self {
+        let mut columns = Vec::with_capacity(self.columns.len());
+        for mut column in std::mem::take(&mut self.columns) {
+            let raw_name = column.name.clone();
+            if let Some(config) = configs.get(&raw_name) {
+                if !config.active {
+                    continue;
+                }
+                self.skipped.extend(
+                    unsupported_options(config)
+                        .into_iter()
+                        .map(|option| format!("{raw_name}: {option}")),
+                );
+                if column.text {
+                    apply_text_options(&mut column, config);
+                }
+                apply_value_map(&mut column, &config.value_mapping);
+            }
+            if let Some(map) = value_maps.get(&raw_name) {
+                apply_value_map(&mut column, map);
+            }
+            if let Some(config) = configs.get(&raw_name) {
+                match config.target_dtype {
+                    Some(ColumnKind::Numeric) => cast(&mut column, "DOUBLE PRECISION"),
+                    Some(ColumnKind::Text | ColumnKind::Categorical) => cast(&mut column, "TEXT"),
+                    Some(ColumnKind::Temporal) => cast(&mut column, "TIMESTAMPTZ"),
+                    Some(ColumnKind::Boolean) => cast_boolean(&mut column),
+                    Some(ColumnKind::Nested) | None => {}
+                }
+                if !config.new_name.is_empty() {
+                    column.name.clone_from(&config.new_name);
+                }
+            }
+            columns.push(column);
+        }
+        self.columns = columns;
+        self
+    }
+
+    /// Apply pipeline steps in order. Steps other than dropping, renaming,
+    /// trimming, mapping values of and casting columns are listed in
+    /// `skipped`.
+    pub fn with_steps(mut self, steps: &[Step]) -> Self {
+        for (idx, step) in steps.iter().enumerate() {
+            match step {
+                Step::DropColumns { columns } => {
+                    self.columns.retain(|c| !columns.contains(&c.name));
+                }
+                Step::RenameColumns { mapping } => {
+                    for column in &mut self.columns {
+                        if let Some(new_name) = mapping.get(&column.name) {
+                            column.name.clone_from(new_name);
+                        }
+                    }
+                }
+                Step::TrimWhitespace { columns } => {
+                    for column in &mut self.columns {
+                        if column.text && columns.contains(&column.name) {
+                            column.expression = format!("TRIM({})", column.expression);
+                        }
+                    }
+                }
+                Step::MapValues { columns, mapping } => {
+                    let map: BTreeMap<String, String> = mapping
+                        .iter()
+                        .map(|(from, to)| (from.clone(), to.clone()))
+                        .collect();
+                    for column in &mut self.columns {
+                        if columns.contains(&column.name) {
+                            apply_value_map(column, &map);
+                        }
+                    }
+                }
+                Step::CastTypes { columns } => {
+                    for column in &mut self.columns {
+                        let Some(type_str) = columns.get(&column.name) else {
+                            continue;
+                        };
+                        match type_str.as_str() {
+                            "i64" | "Numeric" => cast(column, "BIGINT"),
+                            "f64" => cast(column, "DOUBLE PRECISION"),
+                            "String" | "Text" | "Categorical" => cast(column, "TEXT"),
+                            "Temporal" => cast(column, "TIMESTAMPTZ"),
+                            "Boolean" => cast_boolean(column),
+                            _ => self.skipped.push(format!(
+                                "Step {}: unknown type '{type_str}' for '{}'",
+                                idx + 1,
+                                column.name
+                            )),
+                        }
+                    }
+                }
+                _ => self.skipped.push(format!(
+                    "Step {}: {} has no SQL translation",
+                    idx + 1,
+                    step_op(step)
+                )),
+            }
+        }
+        self
+    }
+
+    /// The `CREATE OR REPLACE VIEW` statement, preceded by comments listing
+    /// anything that was left out
+    pub fn sql(&self) -> String {
+        let mut sql = format!("-- Cleaning view over {}\n", comment(&self.source));
+        if !self.skipped.is_empty() {
+            sql.push_str("-- Not translated to SQL:\n");
+            for note in &self.skipped {
+                sql.push_str(&format!("--   {}\n", comment(note)));
+            }
+        }
+        let select: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                let alias = quote(&c.name);
+                if c.expression == alias {
+                    format!("    {alias}")
+                } else {
+                    format!("    {} AS {alias}", c.expression)
+                }
+            })
+            .collect();
+        sql.push_str(&format!(
+            "CREATE OR REPLACE VIEW {} AS\nSELECT\n{}\nFROM {};\n",
+            self.view,
+            select.join(",\n"),
+            self.source
+        ));
+        sql
+    }
+}
+
+fn apply_text_options(column: &mut ViewColumn, config: &ColumnCleanConfig) {
+    if config.trim_whitespace {
+        column.expression = format!("TRIM({})", column.expression);
+    }
+    match config.text_case {
+        TextCase::Lowercase => column.expression = format!("LOWER({})", column.expression),
+        TextCase::Uppercase => column.expression = format!("UPPER({})", column.expression),
+        TextCase::TitleCase | TextCase::None => {}
+    }
+    if config.standardise_nulls {
+        let tokens: Vec<String> = NULL_TOKENS.iter().map(|t| literal(t)).collect();
+        column.expression = format!(
+            "CASE WHEN {expr} IN ({}) THEN NULL ELSE {expr} END",
+            tokens.join(", "),
+            expr = column.expression
+        );
+    }
+}
+
+fn apply_value_map(column: &mut ViewColumn, map: &BTreeMap<String, String>) {
+    if map.is_empty() {
+        return;
+    }
+    let expr = if column.text {
+        column.expression.clone()
+    } else {
+        format!("CAST({} AS TEXT)", column.expression)
+    };
+    let mut case = format!("CASE {expr}");
+    for (from, to) in map {
+        case.push_str(&format!(" WHEN {} THEN {}", literal(from), literal(to)));
+    }
+    case.push_str(&format!(" ELSE {expr} END"));
+    column.expression = case;
+    column.text = true;
+}
+
+fn cast(column: &mut ViewColumn, sql_type: &str) {
+    column.expression = format!("CAST({} AS {sql_type})", column.expression);
+    column.text = sql_type == "TEXT";
+}
+
+/// The same truthy and falsy spellings as the in-app boolean cast; anything
+/// else becomes NULL
+fn cast_boolean(column: &mut ViewColumn) {
+    let lower = format!("LOWER(CAST({} AS TEXT))", column.expression);
+    column.expression = format!(
+        "CASE WHEN {lower} IN ('true', '1', 'yes') THEN TRUE \
+         WHEN {lower} IN ('false', '0', 'no') THEN FALSE END"
+    );
+    column.text = false;
+}
+
+/// `text` with line breaks replaced, so it cannot end a `--` comment; column
+/// names and types come from the file
+fn comment(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// Options of an active config that the view leaves out
+fn unsupported_options(config: &ColumnCleanConfig) -> Vec<&'static str> {
+    let mut options = Vec::new();
+    if config.text_case == TextCase::TitleCase {
+        options.push("title case");
+    }
+    if config.remove_special_chars {
+        options.push("remove special characters");
+    }
+    if config.remove_non_ascii {
+        options.push("remove non-ASCII");
+    }
+    if !config.regex_find.is_empty() {
+        options.push("regex replace");
+    }
+    if config.extract_numbers {
+        options.push("extract numbers");
+    }
+    if config.rounding.is_some() {
+        options.push("rounding");
+    }
+    if !config.temporal_format.is_empty() {
+        options.push("date format");
+    }
+    if config.timezone_utc {
+        options.push("UTC conversion");
+    }
+    if config.clip_outliers {
+        options.push("clip outliers");
+    }
+    if config.impute_mode != ImputeMode::None {
+        options.push("impute missing values");
+    }
+    if config.normalisation != NormalisationMethod::None {
+        options.push("normalisation");
+    }
+    if config.one_hot_encode {
+        options.push("one-hot encoding");
+    }
+    if config.encoding.is_some() {
+        options.push("category encoding");
+    }
+    if config.freq_threshold.is_some() {
+        options.push("rare value threshold");
+    }
+    if config.split.is_some() {
+        options.push("split column");
+    }
+    if config.merge.is_some() {
+        options.push("merge columns");
+    }
+    if !config.date_parts.is_empty() {
+        options.push("date parts");
+    }
+    if config.binning.is_some() {
+        options.push("binning");
+    }
+    options
+}
+
+fn is_text(dtype: &DataType) -> bool {
+    // Everything `prepare_table` does not give a numeric, boolean or
+    // date/time type is stored as TEXT
+    !(dtype.is_numeric()
+        || matches!(
+            dtype,
+            DataType::Boolean | DataType::Date | DataType::Datetime(_, _)
+        ))
+}
+
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use polars::prelude::Field;
+
+    #[test]
+    fn test_view_from_configs_and_steps() {
+        let schema = Schema::from_iter([
+            Field::new("Gender".into(), DataType::String),
+            Field::new("age".into(), DataType::String),
+            Field::new("notes".into(), DataType::String),
+            Field::new("id".into(), DataType::Int64),
+        ]);
+        let configs = HashMap::from([
+            (
+                "Gender".to_owned(),
+                ColumnCleanConfig {
+                    new_name: "gender".to_owned(),
+                    trim_whitespace: true,
+                    text_case: TextCase::Uppercase,
+                    ..ColumnCleanConfig::default()
+                },
+            ),
+            (
+                "age".to_owned(),
+                ColumnCleanConfig {
+                    standardise_nulls: true,
+                    target_dtype: Some(ColumnKind::Numeric),
+                    rounding: Some(0),
+                    clip_outliers: true,
+                    impute_mode: ImputeMode::Median,
+                    ..ColumnCleanConfig::default()
+                },
+            ),
+        ]);
+        let value_maps = HashMap::from([(
+            "Gender".to_owned(),
+            BTreeMap::from([("M".to_owned(), "Male".to_owned())]),
+        )]);
+        let steps = vec![
+            Step::DropColumns {
+                columns: vec!["notes".to_owned()],
+            },
+            Step::CastTypes {
+                columns: HashMap::from([("id".to_owned(), "String".to_owned())]),
+            },
+            Step::Limit { n: 10 },
+        ];
+
+        let view = CleaningView::new(&schema, Some("raw"), "people", "people_clean")
+            .with_configs(&configs, &value_maps)
+            .with_steps(&steps);
+
+        let names: Vec<&str> = view.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["gender", "age", "id"]);
+        assert_eq!(
+            view.columns[0].expression,
+            "CASE UPPER(TRIM(\"Gender\")) WHEN 'M' THEN 'Male' ELSE UPPER(TRIM(\"Gender\")) END"
+        );
+        assert_eq!(
+            view.columns[1].expression,
+            "CAST(CASE WHEN \"age\" IN ('null', 'NULL', '', 'N/A', 'nan', 'NaN') \
+             THEN NULL ELSE \"age\" END AS DOUBLE PRECISION)"
+        );
+        assert_eq!(
+            view.skipped,
+            vec![
+                "age: rounding",
+                "age: clip outliers",
+                "age: impute missing values",
+                "Step 3: limit has no SQL translation"
+            ]
+        );
+
+        let sql = view.sql();
+        assert!(sql.contains("--   age: rounding\n"));
+        assert!(sql.contains("CREATE OR REPLACE VIEW \"raw\".\"people_clean\" AS\nSELECT\n"));
+        assert!(sql.contains("    CAST(\"id\" AS TEXT) AS \"id\"\nFROM \"raw\".\"people\";\n"));
+    }
+
+    #[test]
+    fn test_skipped_notes_cannot_end_their_comment() {
+        let schema = Schema::from_iter([Field::new(
+            "x\nDROP TABLE people; --".into(),
+            DataType::String,
+        )]);
+        let steps = vec![Step::CastTypes {
+            columns: HashMap::from([(
+                "x\nDROP TABLE people; --".to_owned(),
+                "Weird\r\nGRANT ALL".to_owned(),
+            )]),
+        }];
+        let sql = CleaningView::new(&schema, None, "people", "people_clean")
+            .with_steps(&steps)
+            .sql();
+        // The column name is quoted in the SELECT, where a line break is safe
+        let (header, _) = sql.split_once("CREATE OR REPLACE VIEW").unwrap();
+        assert!(header.lines().all(|line| line.starts_with("--")), "{sql}");
+        assert!(header.contains("GRANT ALL"));
+    }
+}
@@ -8,6 +8,13 @@
 //! - **Validated**: Quality gates passed, ready for production
 //! - **Published**: Finalized as view (lazy) or snapshot (materialized)
 //!
+//! Publishing requires approval: a version is submitted for review and
+//! approved, with the actor and time of each step recorded, before it can be
+//! published; see [`approval`].
+//!
+//! Any version can be exported as a standalone HTML viewer with its schema,
+//! documentation, health and a paginated preview; see [`viewer`].
+//!
 //! ## Key Principles
 //!
 //! - **Immutability**: Raw data is never modified; all transformations create new versions
@@ -15,12 +22,16 @@
 //! - **Diff Tracking**: Each version includes diff summary (schema + statistical changes)
 //! - **Active Pointer**: Single "active version" for consumption by downstream systems
 //! - **Storage Modes**: Views (lazy `LazyFrame`) vs Snapshots (materialized Parquet)
+//! - **Pruning**: Versions can be deleted or rolled back, but never the raw, active or
+//!   published versions, or ones whose data another dataset uses
+//! - **Deduplication**: Version data is stored as content-addressed chunks shared by
+//!   every version (of any dataset) containing the same rows
 //!
 //! ## Example Usage
 //!
 //! ```no_run
 //! use beefcake::analyser::lifecycle::{DatasetRegistry, TransformPipeline, LifecycleStage};
-//! use std::path::PathBuf;
+//! use std::path::{Path, PathBuf};
 //!
 //! # fn example() -> anyhow::Result<()> {
 //! // Create registry
@@ -64,23 +75,32 @@
 //! # }
 //! ```
 
+pub mod approval;
 pub mod diff;
+pub mod lineage;
 pub mod query;
 pub mod stages;
 pub mod storage;
 pub mod transforms;
 pub mod version;
+pub mod viewer;
 
-pub use diff::{DiffSummary, compute_version_diff};
-pub use query::VersionQuery;
+pub use approval::{Approval, ApprovalState, ApprovalTransition};
+pub use diff::{DiffSummary, compute_file_diff, compute_frame_diff, compute_version_diff};
+pub use lineage::{LineageEdge, LineageGraph, LineageNode, LineageNodeKind};
+pub use query::{VersionFilter, VersionQuery, VersionSearchHit};
 pub use stages::{LifecycleStage, PublishMode, StageExecutor};
-pub use storage::{DataLocation, VersionStore};
+pub use storage::{DataLocation, GarbageReport, StorageUsage, VersionStore};
 pub use transforms::{Transform, TransformPipeline};
-pub use version::{Dataset, DatasetVersion, VersionMetadata, VersionTree};
+pub use version::{
+    Dataset, DatasetVersion, PIPELINE_RUN_FIELD, RefreshReport, RefreshedVersion, VersionMetadata,
+    VersionTree,
+};
+pub use viewer::{VIEWER_MAX_ROWS, ViewerOptions, render_viewer};
 
 use anyhow::Result;
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -114,6 +134,25 @@ impl DatasetRegistry {
         Ok(id)
     }
 
+    /// Create a new dataset from data a path alone cannot load again; see
+    /// [`Dataset::from_data`]
+    pub fn create_dataset_from_data(
+        &self,
+        name: String,
+        lf: &polars::prelude::LazyFrame,
+    ) -> Result<Uuid> {
+        let dataset = Dataset::from_data(name, lf, Arc::clone(&self.store))?;
+        let id = dataset.id;
+
+        let mut datasets = self
+            .datasets
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+        datasets.insert(id, dataset);
+
+        Ok(id)
+    }
+
     /// Get a dataset by ID
     pub fn get_dataset(&self, id: &Uuid) -> Result<Dataset> {
         let datasets = self
@@ -145,6 +184,21 @@ impl DatasetRegistry {
         dataset.apply_pipeline(pipeline, stage)
     }
 
+    /// Store data produced outside the lifecycle as a new version; see
+    /// [`Dataset::add_data_version`]
+    pub fn add_data_version(
+        &self,
+        dataset_id: &Uuid,
+        parent_id: &Uuid,
+        lf: &polars::prelude::LazyFrame,
+        stage: LifecycleStage,
+        metadata: VersionMetadata,
+    ) -> Result<Uuid> {
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.add_data_version(parent_id, lf, stage, metadata)
+        })
+    }
+
     /// Set the active version for a dataset
     pub fn set_active_version(&self, dataset_id: &Uuid, version_id: &Uuid) -> Result<()> {
         let mut datasets = self
@@ -165,23 +219,45 @@ impl DatasetRegistry {
         dataset.get_active_data()
     }
 
-    /// Publish a version as a snapshot or view
+    /// Publish an approved version as a snapshot or view
     pub fn publish_version(
         &self,
         dataset_id: &Uuid,
         version_id: &Uuid,
         mode: stages::PublishMode,
     ) -> Result<Uuid> {
-        let mut datasets = self
-            .datasets
-            .write()
-            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.publish_version(version_id, mode)
+        })
+    }
 
-        let dataset = datasets
-            .get_mut(dataset_id)
-            .ok_or_else(|| anyhow::anyhow!("Dataset not found: {dataset_id}"))?;
+    /// Publish an approved version as `actor`; see
+    /// [`Dataset::publish_version_as`]
+    pub fn publish_version_as(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        mode: stages::PublishMode,
+        actor: &str,
+    ) -> Result<Uuid> {
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.publish_version_as(version_id, mode, actor)
+        })
+    }
 
-        dataset.publish_version(version_id, mode)
+    /// Move a version through the approval workflow; see
+    /// [`Dataset::transition_approval`]
+    pub fn transition_approval(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        to: ApprovalState,
+        actor: &str,
+        comment: &str,
+    ) -> Result<DatasetVersion> {
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.transition_approval(version_id, to, actor, comment)
+        })
     }
 
     /// Compute diff between two versions
@@ -209,13 +285,273 @@ impl DatasetRegistry {
         let dataset = self.get_dataset(dataset_id)?;
         Ok(dataset.list_versions())
     }
+
+    /// A standalone HTML viewer of a version; see [`viewer`]
+    pub fn render_viewer(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        dictionary: Option<&crate::dictionary::DataDictionary>,
+        options: &ViewerOptions,
+    ) -> Result<String> {
+        let dataset = self.get_dataset(dataset_id)?;
+        let version = dataset.get_version(version_id)?;
+        render_viewer(&dataset, &version, dictionary, options)
+    }
+
+    /// Space a dataset's versions take in the store, with and without
+    /// counting shared chunks once
+    pub fn storage_usage(&self, dataset_id: &Uuid) -> Result<StorageUsage> {
+        Ok(self.get_dataset(dataset_id)?.storage_usage())
+    }
+
+    /// Lineage of a dataset from its source files through every version
+    pub fn lineage(&self, dataset_id: &Uuid) -> Result<LineageGraph> {
+        let dataset = self.get_dataset(dataset_id)?;
+        Ok(LineageGraph::from_dataset(&dataset))
+    }
+
+    /// Versions of a dataset matching `filter`, newest first
+    pub fn list_versions_filtered(
+        &self,
+        dataset_id: &Uuid,
+        filter: &VersionFilter,
+    ) -> Result<Vec<DatasetVersion>> {
+        Ok(filter.apply(self.list_versions(dataset_id)?))
+    }
+
+    /// Versions of every dataset matching `filter`, newest first
+    pub fn search_versions(&self, filter: &VersionFilter) -> Result<Vec<VersionSearchHit>> {
+        let datasets = self
+            .datasets
+            .read()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let mut hits: Vec<VersionSearchHit> = datasets
+            .values()
+            .flat_map(|dataset| {
+                filter
+                    .apply(dataset.list_versions())
+                    .into_iter()
+                    .map(|version| VersionSearchHit {
+                        dataset_id: dataset.id,
+                        dataset_name: dataset.name.clone(),
+                        version,
+                    })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.version.created_at.cmp(&a.version.created_at));
+        Ok(hits)
+    }
+
+    /// Add a tag to a version
+    pub fn tag_version(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        tag: &str,
+    ) -> Result<DatasetVersion> {
+        self.with_dataset_mut(dataset_id, |dataset| dataset.tag_version(version_id, tag))
+    }
+
+    /// Remove a tag from a version
+    pub fn untag_version(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        tag: &str,
+    ) -> Result<DatasetVersion> {
+        self.with_dataset_mut(dataset_id, |dataset| dataset.untag_version(version_id, tag))
+    }
+
+    /// Replace the notes on a version
+    pub fn annotate_version(
+        &self,
+        dataset_id: &Uuid,
+        version_id: &Uuid,
+        notes: &str,
+    ) -> Result<DatasetVersion> {
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.annotate_version(version_id, notes)
+        })
+    }
+
+    /// Re-read a dataset's source file and recreate its versions; see
+    /// [`Dataset::refresh_from_source`]
+    pub fn refresh_dataset(&self, dataset_id: &Uuid) -> Result<RefreshReport> {
+        self.with_dataset_mut(dataset_id, Dataset::refresh_from_source)
+    }
+
+    /// Ids of the datasets ingested from `path`
+    pub fn datasets_with_source(&self, path: &Path) -> Result<Vec<Uuid>> {
+        let datasets = self
+            .datasets
+            .read()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let target = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let mut ids: Vec<Uuid> = datasets
+            .values()
+            .filter(|d| {
+                d.source_path().is_some_and(|source| {
+                    source
+                        .canonicalize()
+                        .unwrap_or_else(|_| source.to_path_buf())
+                        == target
+                })
+            })
+            .map(|d| d.id)
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Refresh every dataset ingested from `path`. A failure for one dataset
+    /// does not stop the others; each outcome is returned.
+    pub fn refresh_from_path(&self, path: &Path) -> Result<Vec<(Uuid, Result<RefreshReport>)>> {
+        Ok(self
+            .datasets_with_source(path)?
+            .into_iter()
+            .map(|id| (id, self.refresh_dataset(&id)))
+            .collect())
+    }
+
+    /// Set or clear the file pattern whose new files become raw versions
+    /// of the dataset. A blank pattern clears it.
+    pub fn set_source_pattern(&self, dataset_id: &Uuid, pattern: Option<String>) -> Result<()> {
+        let pattern = pattern
+            .map(|p| p.trim().to_owned())
+            .filter(|p| !p.is_empty());
+        self.with_dataset_mut(dataset_id, |dataset| {
+            dataset.source_pattern = pattern;
+            Ok(())
+        })
+    }
+
+    /// Ids of the datasets whose source pattern matches `path`
+    pub fn datasets_matching_pattern(&self, path: &Path) -> Result<Vec<Uuid>> {
+        let datasets = self
+            .datasets
+            .read()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let mut ids: Vec<Uuid> = datasets
+            .values()
+            .filter(|d| d.matches_source_pattern(path))
+            .map(|d| d.id)
+            .collect();
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Add a newer source file as a raw version; see
+    /// [`Dataset::add_source_version`]
+    pub fn add_source_version(&self, dataset_id: &Uuid, path: &Path) -> Result<Uuid> {
+        self.with_dataset_mut(dataset_id, |dataset| dataset.add_source_version(path))
+    }
+
+    fn with_dataset_mut<T>(
+        &self,
+        dataset_id: &Uuid,
+        f: impl FnOnce(&mut Dataset) -> Result<T>,
+    ) -> Result<T> {
+        let mut datasets = self
+            .datasets
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let dataset = datasets
+            .get_mut(dataset_id)
+            .ok_or_else(|| anyhow::anyhow!("Dataset not found: {dataset_id}"))?;
+
+        f(dataset)
+    }
+
+    /// Delete a version; see [`Dataset::delete_version`] for what is refused
+    pub fn delete_version(&self, dataset_id: &Uuid, version_id: &Uuid) -> Result<()> {
+        let mut datasets = self
+            .datasets
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let external_paths = data_paths_outside(&datasets, dataset_id);
+        let dataset = datasets
+            .get_mut(dataset_id)
+            .ok_or_else(|| anyhow::anyhow!("Dataset not found: {dataset_id}"))?;
+
+        dataset.delete_version(version_id, &external_paths)
+    }
+
+    /// Make an earlier version active and delete the versions after it; see
+    /// [`Dataset::rollback_to`]. Returns the deleted version ids.
+    pub fn rollback_to(&self, dataset_id: &Uuid, version_id: &Uuid) -> Result<Vec<Uuid>> {
+        let mut datasets = self
+            .datasets
+            .write()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let external_paths = data_paths_outside(&datasets, dataset_id);
+        let dataset = datasets
+            .get_mut(dataset_id)
+            .ok_or_else(|| anyhow::anyhow!("Dataset not found: {dataset_id}"))?;
+
+        dataset.rollback_to(version_id, &external_paths)
+    }
+
+    /// Remove store files of registered datasets that no version refers to.
+    /// Folders of datasets not in the registry are left alone.
+    pub fn collect_garbage(&self) -> Result<GarbageReport> {
+        let datasets = self
+            .datasets
+            .read()
+            .map_err(|e| anyhow::anyhow!("Lock poisoned: {e}"))?;
+
+        let referenced: HashSet<PathBuf> =
+            datasets.values().flat_map(Dataset::data_paths).collect();
+        let mut report = GarbageReport::default();
+        #[expect(clippy::iter_over_hash_type)]
+        for dataset in datasets.values() {
+            let versions: HashSet<Uuid> = dataset.list_versions().iter().map(|v| v.id).collect();
+            report.merge(
+                self.store
+                    .collect_garbage(&dataset.id, &versions, &referenced)?,
+            );
+        }
+        report.merge(self.store.collect_chunk_garbage()?);
+
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "Garbage collection removed {} file(s), freeing {} bytes",
+                report.files_removed, report.bytes_freed
+            ),
+        );
+        Ok(report)
+    }
+}
+
+/// Data files used by datasets other than `dataset_id`
+fn data_paths_outside(datasets: &HashMap<Uuid, Dataset>, dataset_id: &Uuid) -> HashSet<PathBuf> {
+    datasets
+        .values()
+        .filter(|d| d.id != *dataset_id)
+        .flat_map(Dataset::data_paths)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyser::lifecycle::transforms::TransformSpec;
     use tempfile::TempDir;
 
+    fn sort_by(column: &str) -> TransformPipeline {
+        TransformPipeline::new(vec![TransformSpec {
+            transform_type: "sort".to_owned(),
+            parameters: HashMap::from([("by_columns".to_owned(), serde_json::json!([column]))]),
+        }])
+    }
+
     #[test]
     fn test_registry_creation() -> Result<()> {
         let temp = TempDir::new()?;
@@ -229,4 +565,302 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_dataset_from_data_stores_its_raw_version() -> Result<()> {
+        use polars::prelude::{IntoLazy as _, df};
+
+        let temp = TempDir::new()?;
+        let registry = DatasetRegistry::new(temp.path().to_path_buf())?;
+        let df = df!("column_1" => [1i64, 2, 3])?;
+        let dataset_id =
+            registry.create_dataset_from_data("headerless".to_owned(), &df.clone().lazy())?;
+
+        let dataset = registry.get_dataset(&dataset_id)?;
+        assert!(dataset.source_path().is_none());
+        assert_eq!(registry.get_active_data(&dataset_id)?.collect()?, df);
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_rollback_and_garbage_collection() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("source.csv");
+        std::fs::write(&source, "a,b\n3,x\n1,y\n2,z\n")?;
+        let registry = DatasetRegistry::new(temp.path().join("store"))?;
+        let dataset_id = registry.create_dataset("ds".to_owned(), source)?;
+        let raw_id = registry.get_dataset(&dataset_id)?.raw_version_id;
+
+        let v1 = registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+        let v2 = registry.apply_transforms(&dataset_id, sort_by("b"), LifecycleStage::Advanced)?;
+        let v3 = registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Validated)?;
+        let v1_path = registry
+            .get_version(&dataset_id, &v1)?
+            .data_location
+            .path()
+            .to_path_buf();
+        let v2_path = registry
+            .get_version(&dataset_id, &v2)?
+            .data_location
+            .path()
+            .to_path_buf();
+
+        // The raw and active versions are protected
+        assert!(registry.delete_version(&dataset_id, &raw_id).is_err());
+        assert!(registry.delete_version(&dataset_id, &v3).is_err());
+
+        // Deleting a middle version reattaches its child to its parent
+        registry.delete_version(&dataset_id, &v2)?;
+        assert!(!v2_path.exists());
+        assert_eq!(registry.get_version(&dataset_id, &v3)?.parent_id, Some(v1));
+
+        // A version whose data another dataset uses is kept
+        let other = registry.create_dataset("copy".to_owned(), v1_path.clone())?;
+        assert!(registry.delete_version(&dataset_id, &v1).is_err());
+
+        let deleted = registry.rollback_to(&dataset_id, &raw_id)?;
+        assert_eq!(deleted, vec![v3]);
+        let dataset = registry.get_dataset(&dataset_id)?;
+        assert_eq!(dataset.active_version_id, raw_id);
+        assert_eq!(dataset.list_versions().len(), 2);
+        assert!(v1_path.exists());
+        assert!(registry.get_dataset(&other).is_ok());
+
+        // Stray files in a dataset's folder are collected
+        let stray = v1_path.with_file_name(format!("{}.parquet", Uuid::new_v4()));
+        std::fs::write(&stray, b"partial")?;
+        // The stray file, and the chunk only the deleted v2 linked to
+        let report = registry.collect_garbage()?;
+        assert_eq!(report.files_removed, 2);
+        assert!(!stray.exists());
+        assert!(v1_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_from_source() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("source.csv");
+        std::fs::write(&source, "a,b\n3,x\n1,y\n")?;
+        let registry = DatasetRegistry::new(temp.path().join("store"))?;
+        let dataset_id = registry.create_dataset("ds".to_owned(), source.clone())?;
+        let cleaned =
+            registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+        let profiled = registry.apply_transforms(
+            &dataset_id,
+            TransformPipeline::empty(),
+            LifecycleStage::Profiled,
+        )?;
+
+        std::fs::write(&source, "a,b\n5,x\n1,y\n4,z\n")?;
+        assert_eq!(registry.datasets_with_source(&source)?, vec![dataset_id]);
+        let mut outcomes = registry.refresh_from_path(&source)?;
+        let (id, report) = outcomes.pop().expect("one dataset refreshed");
+        let report = report?;
+        assert_eq!(id, dataset_id);
+
+        let stages: Vec<(LifecycleStage, Uuid)> = report
+            .versions
+            .iter()
+            .map(|v| (v.stage, v.previous_id))
+            .collect();
+        assert_eq!(
+            stages,
+            vec![
+                (LifecycleStage::Cleaned, cleaned),
+                (LifecycleStage::Profiled, profiled),
+            ]
+        );
+
+        let dataset = registry.get_dataset(&dataset_id)?;
+        assert_eq!(dataset.active_version_id, report.active_version_id);
+        let refreshed_cleaned = dataset.get_version(&report.versions[0].new_id)?;
+        assert_eq!(refreshed_cleaned.parent_id, Some(dataset.raw_version_id));
+        assert_eq!(
+            refreshed_cleaned.metadata.custom_fields["refreshed_from"],
+            cleaned.to_string()
+        );
+        let data = registry.get_active_data(&dataset_id)?.collect()?;
+        assert_eq!(data.height(), 3);
+        assert_eq!(dataset.list_versions().len(), 5);
+
+        std::fs::remove_file(&source)?;
+        assert!(registry.refresh_dataset(&dataset_id).is_err());
+        assert_eq!(
+            registry.get_dataset(&dataset_id)?.active_version_id,
+            report.active_version_id
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_source_pattern_adds_raw_versions() -> Result<()> {
+        let temp = TempDir::new()?;
+        let first = temp.path().join("sales_2024_01.csv");
+        std::fs::write(&first, "a,b\n3,x\n1,y\n")?;
+        let registry = DatasetRegistry::new(temp.path().join("store"))?;
+        let dataset_id = registry.create_dataset("sales".to_owned(), first.clone())?;
+        let raw_id = registry.get_dataset(&dataset_id)?.raw_version_id;
+        registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+
+        let next = temp.path().join("sales_2024_02.csv");
+        std::fs::write(&next, "a,b\n5,x\n2,y\n4,z\n")?;
+        assert!(registry.datasets_matching_pattern(&next)?.is_empty());
+        registry.set_source_pattern(&dataset_id, Some(" sales_*.csv ".to_owned()))?;
+        assert_eq!(registry.datasets_matching_pattern(&next)?, vec![dataset_id]);
+        assert!(
+            registry
+                .datasets_matching_pattern(&temp.path().join("costs_2024_02.csv"))?
+                .is_empty()
+        );
+
+        let version_id = registry.add_source_version(&dataset_id, &next)?;
+        let dataset = registry.get_dataset(&dataset_id)?;
+        assert_eq!(dataset.active_version_id, version_id);
+        assert_eq!(dataset.raw_version_id, raw_id);
+        let version = dataset.get_version(&version_id)?;
+        assert_eq!(version.stage, LifecycleStage::Raw);
+        assert_eq!(version.parent_id, Some(raw_id));
+        assert_eq!(dataset.source_path(), Some(next.as_path()));
+        assert_eq!(
+            registry.get_active_data(&dataset_id)?.collect()?.height(),
+            3
+        );
+
+        // Stages derived from the new version refresh from the new file
+        registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+        std::fs::write(&next, "a,b\n5,x\n2,y\n")?;
+        let report = registry.refresh_dataset(&dataset_id)?;
+        assert_eq!(report.source_path, next);
+        assert_eq!(report.versions.len(), 1);
+        let refreshed = registry.get_version(&dataset_id, &report.active_version_id)?;
+        assert_eq!(refreshed.parent_id, Some(version_id));
+        assert_eq!(
+            registry.get_active_data(&dataset_id)?.collect()?.height(),
+            2
+        );
+
+        registry.set_source_pattern(&dataset_id, Some("  ".to_owned()))?;
+        assert!(registry.datasets_matching_pattern(&next)?.is_empty());
+        Ok(())
+    }
+
+    fn approve(registry: &DatasetRegistry, dataset_id: &Uuid, version_id: &Uuid) -> Result<()> {
+        registry.transition_approval(dataset_id, version_id, ApprovalState::InReview, "ann", "")?;
+        registry.transition_approval(dataset_id, version_id, ApprovalState::Approved, "bob", "")?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_publish_requires_approval() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("sales.csv");
+        std::fs::write(&source, "a,b\n3,x\n1,y\n")?;
+        let registry = DatasetRegistry::new(temp.path().join("store"))?;
+        let dataset_id = registry.create_dataset("Sales".to_owned(), source.clone())?;
+        let cleaned =
+            registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+
+        let err = registry
+            .publish_version(&dataset_id, &cleaned, PublishMode::Snapshot)
+            .unwrap_err();
+        assert!(err.to_string().contains("is Draft"));
+        registry.transition_approval(&dataset_id, &cleaned, ApprovalState::InReview, "ann", "")?;
+        assert!(
+            registry
+                .publish_version(&dataset_id, &cleaned, PublishMode::Snapshot)
+                .is_err()
+        );
+        assert!(
+            registry
+                .transition_approval(&dataset_id, &cleaned, ApprovalState::Published, "bob", "")
+                .is_err()
+        );
+        registry.transition_approval(
+            &dataset_id,
+            &cleaned,
+            ApprovalState::Approved,
+            "bob",
+            "ok",
+        )?;
+
+        let published =
+            registry.publish_version_as(&dataset_id, &cleaned, PublishMode::Snapshot, "cat")?;
+        let source_version = registry.get_version(&dataset_id, &cleaned)?;
+        assert_eq!(source_version.approval.state, ApprovalState::Published);
+        let version = registry.get_version(&dataset_id, &published)?;
+        assert_eq!(version.stage, LifecycleStage::Published);
+        let actors: Vec<_> = version
+            .approval
+            .transitions
+            .iter()
+            .map(|t| (t.actor.as_str(), t.to))
+            .collect();
+        assert_eq!(
+            actors,
+            [
+                ("ann", ApprovalState::InReview),
+                ("bob", ApprovalState::Approved),
+                ("cat", ApprovalState::Published),
+            ]
+        );
+        assert_eq!(
+            version.approval.approval().map(|t| t.actor.as_str()),
+            Some("bob")
+        );
+
+        // Publishing again needs a new approval
+        assert!(
+            registry
+                .publish_version(&dataset_id, &cleaned, PublishMode::View)
+                .is_err()
+        );
+
+        // A refresh republishes with the approval of the version it replaces
+        registry.set_active_version(&dataset_id, &published)?;
+        let report = registry.refresh_dataset(&dataset_id)?;
+        let republished = registry.get_version(&dataset_id, &report.active_version_id)?;
+        assert_eq!(republished.stage, LifecycleStage::Published);
+        assert_eq!(republished.approval, version.approval);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lineage_graph_and_dot() -> Result<()> {
+        let temp = TempDir::new()?;
+        let source = temp.path().join("sales.csv");
+        std::fs::write(&source, "a,b\n3,x\n1,y\n")?;
+        let registry = DatasetRegistry::new(temp.path().join("store"))?;
+        let dataset_id = registry.create_dataset("Sales \"EU\"".to_owned(), source)?;
+        let raw_id = registry.get_dataset(&dataset_id)?.raw_version_id;
+        let cleaned =
+            registry.apply_transforms(&dataset_id, sort_by("a"), LifecycleStage::Cleaned)?;
+        approve(&registry, &dataset_id, &cleaned)?;
+        let published = registry.publish_version(&dataset_id, &cleaned, PublishMode::View)?;
+
+        let graph = registry.lineage(&dataset_id)?;
+        assert_eq!(graph.nodes.len(), 4);
+        assert_eq!(graph.nodes[0].kind, LineageNodeKind::Source);
+        assert_eq!(graph.nodes[0].label, "sales.csv");
+        let edges: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+        let (raw, cleaned, published) = (
+            raw_id.to_string(),
+            cleaned.to_string(),
+            published.to_string(),
+        );
+        assert_eq!(edges[1], (raw.as_str(), cleaned.as_str()));
+        assert_eq!(edges[2], (cleaned.as_str(), published.as_str()));
+        assert_eq!(graph.edges[1].transforms[0].transform_type, "sort");
+        assert_eq!(graph.edges[2].publish_mode, Some(PublishMode::View));
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph \"Sales \\\"EU\\\"\" {"));
+        assert!(dot.contains(&format!("\"{raw}\" -> \"{cleaned}\" [label=\"sort\"];")));
+        assert!(dot.contains("[label=\"publish (View)\"]"));
+        Ok(())
+    }
 }
@@ -0,0 +1,154 @@
+//! Approval workflow for publishing versions.
+//!
+//! A version moves Draft → In Review → Approved → Published. A reviewer can
+//! send a version in review back to Draft, and an approval can be withdrawn
+//! the same way until the version is published. Every transition records who
+//! made it and when. Publishing is refused unless the version is Approved,
+//! and the published version carries the approval record, so a Published
+//! version always names its approver.
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Where a version is in the approval workflow
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalState {
+    #[default]
+    Draft,
+    InReview,
+    Approved,
+    Published,
+}
+
+impl ApprovalState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Draft => "Draft",
+            Self::InReview => "In Review",
+            Self::Approved => "Approved",
+            Self::Published => "Published",
+        }
+    }
+
+    pub fn parse_state(s: &str) -> Option<Self> {
+        match s.to_lowercase().replace([' ', '_', '-'], "").as_str() {
+            "draft" => Some(Self::Draft),
+            "inreview" | "review" => Some(Self::InReview),
+            "approved" => Some(Self::Approved),
+            "published" => Some(Self::Published),
+            _ => None,
+        }
+    }
+
+    /// Whether a version in this state may move to `target`
+    pub fn can_transition_to(self, target: Self) -> bool {
+        matches!(
+            (self, target),
+            (Self::Draft, Self::InReview)
+                | (Self::InReview, Self::Approved | Self::Draft)
+                | (Self::Approved, Self::Published | Self::Draft)
+        )
+    }
+}
+
+/// One recorded move between approval states
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalTransition {
+    pub from: ApprovalState,
+    pub to: ApprovalState,
+    pub actor: String,
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// Approval state of a version and how it got there
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Approval {
+    pub state: ApprovalState,
+    /// Oldest first
+    pub transitions: Vec<ApprovalTransition>,
+}
+
+impl Approval {
+    /// Move to `to`, recording `actor` and the time
+    pub fn transition(&mut self, to: ApprovalState, actor: &str, comment: &str) -> Result<()> {
+        let actor = actor.trim();
+        if actor.is_empty() {
+            bail!("An approval transition needs an actor");
+        }
+        if !self.state.can_transition_to(to) {
+            bail!(
+                "Cannot move a version from {} to {}",
+                self.state.as_str(),
+                to.as_str()
+            );
+        }
+        self.transitions.push(ApprovalTransition {
+            from: self.state,
+            to,
+            actor: actor.to_owned(),
+            at: Utc::now(),
+            comment: comment.trim().to_owned(),
+        });
+        self.state = to;
+        Ok(())
+    }
+
+    /// The transition that approved the version in its current approval,
+    /// if it is Approved or Published
+    pub fn approval(&self) -> Option<&ApprovalTransition> {
+        if !matches!(
+            self.state,
+            ApprovalState::Approved | ApprovalState::Published
+        ) {
+            return None;
+        }
+        self.transitions
+            .iter()
+            .rev()
+            .find(|t| t.to == ApprovalState::Approved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_transitions() -> Result<()> {
+        let mut approval = Approval::default();
+        assert!(
+            approval
+                .transition(ApprovalState::Approved, "ann", "")
+                .is_err()
+        );
+        assert!(
+            approval
+                .transition(ApprovalState::InReview, " ", "")
+                .is_err()
+        );
+
+        approval.transition(ApprovalState::InReview, "ann", "ready")?;
+        approval.transition(ApprovalState::Draft, "bob", "fix the dates")?;
+        approval.transition(ApprovalState::InReview, "ann", "")?;
+        assert_eq!(approval.approval(), None);
+        approval.transition(ApprovalState::Approved, "bob", "")?;
+        approval.transition(ApprovalState::Published, "ann", "")?;
+
+        assert_eq!(approval.state, ApprovalState::Published);
+        assert_eq!(approval.transitions.len(), 5);
+        assert_eq!(approval.approval().map(|t| t.actor.as_str()), Some("bob"));
+        assert!(
+            approval
+                .transition(ApprovalState::Draft, "ann", "")
+                .is_err()
+        );
+        assert_eq!(
+            ApprovalState::parse_state("in review"),
+            Some(ApprovalState::InReview)
+        );
+        Ok(())
+    }
+}
@@ -1,11 +1,14 @@
 //! Version diff computation
+//!
+//! The same diff compares any two tables: [`compute_file_diff`] compares two
+//! files, as `beefcake diff` does to gate data deliveries in CI.
 
 use super::storage::VersionStore;
 use super::version::DatasetVersion;
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::path::Path;
 
 /// Summary of differences between two dataset versions
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,9 +73,35 @@ pub fn compute_version_diff(
     v2: &DatasetVersion,
     store: &VersionStore,
 ) -> Result<DiffSummary> {
-    let mut lf1 = v1.load_data(store)?;
-    let mut lf2 = v2.load_data(store)?;
+    compute_frame_diff(
+        v1.load_data(store)?,
+        v2.load_data(store)?,
+        v1.id.to_string(),
+        v2.id.to_string(),
+    )
+}
+
+/// Compute diff between two files, identified in the summary by their paths
+pub fn compute_file_diff(path1: &Path, path2: &Path) -> Result<DiffSummary> {
+    use crate::analyser::logic::load_df_lazy;
+
+    let lf1 = load_df_lazy(path1).with_context(|| format!("Failed to load {}", path1.display()))?;
+    let lf2 = load_df_lazy(path2).with_context(|| format!("Failed to load {}", path2.display()))?;
+    compute_frame_diff(
+        lf1,
+        lf2,
+        path1.display().to_string(),
+        path2.display().to_string(),
+    )
+}
 
+/// Compute diff between two tables, named `id1` and `id2` in the summary
+pub fn compute_frame_diff(
+    mut lf1: LazyFrame,
+    mut lf2: LazyFrame,
+    id1: String,
+    id2: String,
+) -> Result<DiffSummary> {
     let schema1 = lf1.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
     let schema2 = lf2.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
 
@@ -89,8 +118,8 @@ pub fn compute_version_diff(
     let sample_changes = compute_sample_changes(&lf1, &lf2, &schema1, &schema2);
 
     Ok(DiffSummary {
-        version1_id: v1.id.to_string(),
-        version2_id: v2.id.to_string(),
+        version1_id: id1,
+        version2_id: id2,
         schema_changes,
         row_changes,
         statistical_changes,
@@ -98,15 +127,30 @@ pub fn compute_version_diff(
     })
 }
 
-fn compute_schema_changes(schema1: &Schema, schema2: &Schema) -> SchemaChanges {
-    let cols1: HashSet<String> = schema1.iter_names().map(|s| s.to_string()).collect();
-    let cols2: HashSet<String> = schema2.iter_names().map(|s| s.to_string()).collect();
+/// Names of `schema` that `other` lacks, in `schema` order
+fn names_not_in(schema: &Schema, other: &Schema) -> Vec<String> {
+    schema
+        .iter_names()
+        .filter(|name| !other.contains(name))
+        .map(|name| name.to_string())
+        .collect()
+}
+
+/// Names in both schemas, in `schema1` order
+fn common_names(schema1: &Schema, schema2: &Schema) -> Vec<String> {
+    schema1
+        .iter_names()
+        .filter(|name| schema2.contains(name))
+        .map(|name| name.to_string())
+        .collect()
+}
 
-    let columns_added: Vec<String> = cols2.difference(&cols1).cloned().collect();
-    let columns_removed: Vec<String> = cols1.difference(&cols2).cloned().collect();
+fn compute_schema_changes(schema1: &Schema, schema2: &Schema) -> SchemaChanges {
+    let columns_added = names_not_in(schema2, schema1);
+    let columns_removed = names_not_in(schema1, schema2);
 
     let mut type_changes = Vec::new();
-    for name in cols1.intersection(&cols2) {
+    for name in common_names(schema1, schema2) {
         let type1 = schema1.get(name).map(|dt| format!("{dt:?}"));
         let type2 = schema2.get(name).map(|dt| format!("{dt:?}"));
 
@@ -114,7 +158,7 @@ fn compute_schema_changes(schema1: &Schema, schema2: &Schema) -> SchemaChanges {
             && let (Some(t1), Some(t2)) = (type1, type2)
         {
             type_changes.push(TypeChange {
-                column: name.clone(),
+                column: name,
                 old_type: t1,
                 new_type: t2,
             });
@@ -185,9 +229,7 @@ fn compute_statistical_changes(
     let mut changes = Vec::new();
 
     // Find common numeric columns
-    let cols1: HashSet<String> = schema1.iter_names().map(|s| s.to_string()).collect();
-    let cols2: HashSet<String> = schema2.iter_names().map(|s| s.to_string()).collect();
-    let common_cols: Vec<String> = cols1.intersection(&cols2).cloned().collect();
+    let common_cols = common_names(schema1, schema2);
 
     for col_name in common_cols.iter().take(20) {
         // Limit to 20 columns for performance
@@ -320,6 +362,27 @@ impl DiffSummary {
         }
     }
 
+    /// Whether columns were added, removed or changed type
+    pub fn schema_changed(&self) -> bool {
+        let schema = &self.schema_changes;
+        !schema.columns_added.is_empty()
+            || !schema.columns_removed.is_empty()
+            || !schema.columns_renamed.is_empty()
+            || !schema.type_changes.is_empty()
+    }
+
+    /// Statistics that moved by more than `threshold_percent`, including
+    /// any that moved away from zero
+    pub fn distribution_shifts(&self, threshold_percent: f64) -> Vec<&StatisticalChange> {
+        self.statistical_changes
+            .iter()
+            .filter(|change| match change.change_percent {
+                Some(pct) => pct.abs() > threshold_percent,
+                None => change.value_v1 != change.value_v2,
+            })
+            .collect()
+    }
+
     /// Serialize to JSON
     pub fn to_json(&self) -> Result<String> {
         serde_json::to_string_pretty(self).context("Failed to serialize diff")
@@ -351,4 +414,29 @@ mod tests {
         assert_eq!(changes.type_changes.len(), 1);
         assert_eq!(changes.type_changes[0].column, "col1");
     }
+
+    #[test]
+    fn test_compute_file_diff() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let before = dir.path().join("before.csv");
+        let after = dir.path().join("after.csv");
+        std::fs::write(&before, "id,amount,region\n1,10,N\n2,20,S\n")?;
+        std::fs::write(&after, "id,amount,channel\n1,10,web\n2,30,shop\n3,50,web\n")?;
+
+        let diff = compute_file_diff(&before, &after)?;
+        assert!(diff.schema_changed());
+        assert_eq!(diff.schema_changes.columns_added, ["channel"]);
+        assert_eq!(diff.schema_changes.columns_removed, ["region"]);
+        assert_eq!((diff.row_changes.rows_v1, diff.row_changes.rows_v2), (2, 3));
+
+        // Mean amount moves from 15 to 30; id from 1.5 to 2
+        let shifted: Vec<_> = diff
+            .distribution_shifts(50.0)
+            .iter()
+            .map(|c| c.column.as_str())
+            .collect();
+        assert_eq!(shifted, ["amount"]);
+        assert!(diff.distribution_shifts(100.0).is_empty());
+        Ok(())
+    }
 }
@@ -0,0 +1,218 @@
+//! Lineage graph of a dataset's versions for provenance views
+
+use super::stages::{LifecycleStage, PublishMode};
+use super::storage::DataLocation;
+use super::transforms::TransformSpec;
+use super::version::{Dataset, DatasetVersion};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineageNodeKind {
+    /// A file the dataset was ingested from
+    Source,
+    Version,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageNode {
+    /// Version id, or `source:<path>` for source files
+    pub id: String,
+    pub kind: LineageNodeKind,
+    pub label: String,
+    pub stage: Option<LifecycleStage>,
+    /// Source file, or the file holding the version's data
+    pub path: Option<PathBuf>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub tags: Vec<String>,
+    pub active: bool,
+}
+
+/// How a node was derived from its parent
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageEdge {
+    pub from: String,
+    pub to: String,
+    /// Transforms applied, in order; empty for ingestion and publishing
+    pub transforms: Vec<TransformSpec>,
+    /// Set when the edge publishes the parent version
+    pub publish_mode: Option<PublishMode>,
+}
+
+/// Directed acyclic graph from source files through every version of a
+/// dataset, with the transforms applied along each edge
+#[derive(Debug, Clone, Serialize)]
+pub struct LineageGraph {
+    pub dataset_id: Uuid,
+    pub dataset_name: String,
+    /// Oldest first; sources before versions
+    pub nodes: Vec<LineageNode>,
+    pub edges: Vec<LineageEdge>,
+}
+
+impl LineageGraph {
+    pub fn from_dataset(dataset: &Dataset) -> Self {
+        let mut versions = dataset.list_versions();
+        versions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+        let mut sources = BTreeSet::new();
+
+        for version in &versions {
+            let id = version.id.to_string();
+            if let Some(parent_id) = version.parent_id {
+                let parent = versions.iter().find(|v| v.id == parent_id);
+                edges.push(version_edge(version, parent_id, parent));
+            }
+            // Raw versions after the first come from newer source files
+            if version.stage == LifecycleStage::Raw
+                && let DataLocation::OriginalFile(path) = &version.data_location
+            {
+                let source_id = source_node_id(path);
+                if sources.insert(path.clone()) {
+                    nodes.push(LineageNode {
+                        id: source_id.clone(),
+                        kind: LineageNodeKind::Source,
+                        label: path.file_name().map_or_else(
+                            || path.display().to_string(),
+                            |n| n.to_string_lossy().into_owned(),
+                        ),
+                        stage: None,
+                        path: Some(path.clone()),
+                        created_at: None,
+                        tags: Vec::new(),
+                        active: false,
+                    });
+                }
+                edges.push(LineageEdge {
+                    from: source_id,
+                    to: id.clone(),
+                    transforms: Vec::new(),
+                    publish_mode: None,
+                });
+            }
+
+            nodes.push(LineageNode {
+                id,
+                kind: LineageNodeKind::Version,
+                label: version.stage.as_str().to_owned(),
+                stage: Some(version.stage),
+                path: Some(version.data_location.path().to_path_buf()),
+                created_at: Some(version.created_at),
+                tags: version.metadata.tags.clone(),
+                active: version.id == dataset.active_version_id,
+            });
+        }
+
+        // Sources first so renderers that place nodes in order start with them
+        nodes.sort_by_key(|n| n.kind != LineageNodeKind::Source);
+
+        Self {
+            dataset_id: dataset.id,
+            dataset_name: dataset.name.clone(),
+            nodes,
+            edges,
+        }
+    }
+
+    /// The graph in Graphviz DOT format, e.g. for `dot -Tsvg`
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!(
+            "digraph \"{}\" {{\n  rankdir=LR;\n  node [fontname=\"Helvetica\"];\n",
+            escape(&self.dataset_name)
+        );
+        for node in &self.nodes {
+            let (shape, label) = match node.kind {
+                LineageNodeKind::Source => ("note", node.label.clone()),
+                LineageNodeKind::Version => {
+                    let short_id = node.id.get(..8).unwrap_or(&node.id);
+                    let mut label = format!("{}\n{short_id}", node.label);
+                    if let Some(created_at) = node.created_at {
+                        let _ = write!(label, "\n{}", created_at.format("%Y-%m-%d %H:%M"));
+                    }
+                    if !node.tags.is_empty() {
+                        let _ = write!(label, "\n[{}]", node.tags.join(", "));
+                    }
+                    ("box", label)
+                }
+            };
+            let style = if node.active {
+                ", style=bold, penwidth=2"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [shape={shape}, label=\"{}\"{style}];",
+                escape(&node.id),
+                escape(&label)
+            );
+        }
+        for edge in &self.edges {
+            let label = match edge.publish_mode {
+                Some(mode) => format!("publish ({})", mode.as_str()),
+                None => edge
+                    .transforms
+                    .iter()
+                    .map(|t| t.transform_type.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape(&edge.from),
+                escape(&edge.to),
+                escape(&label)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn version_edge(
+    version: &DatasetVersion,
+    parent_id: Uuid,
+    parent: Option<&DatasetVersion>,
+) -> LineageEdge {
+    let from = parent_id.to_string();
+    // Publishing copies the parent's pipeline, so show the mode instead
+    if version.stage == LifecycleStage::Published {
+        // A view shares its parent's data; a snapshot has its own copy
+        let mode = if parent.is_some_and(|p| p.data_location == version.data_location) {
+            PublishMode::View
+        } else {
+            PublishMode::Snapshot
+        };
+        return LineageEdge {
+            from,
+            to: version.id.to_string(),
+            transforms: Vec::new(),
+            publish_mode: Some(mode),
+        };
+    }
+    LineageEdge {
+        from,
+        to: version.id.to_string(),
+        transforms: version.pipeline.iter().cloned().collect(),
+        publish_mode: None,
+    }
+}
+
+fn source_node_id(path: &std::path::Path) -> String {
+    format!("source:{}", path.display())
+}
+
+/// Escape a string for a quoted DOT identifier or label
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,7 +1,9 @@
 //! Query interface for version selection
 
+use super::stages::LifecycleStage;
 use super::version::{Dataset, DatasetVersion};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -116,6 +118,94 @@ impl VersionQuery {
     }
 }
 
+/// Filter for listing and searching versions; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VersionFilter {
+    pub stage: Option<LifecycleStage>,
+    /// Versions carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    /// Versions created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Versions created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Words that must all appear (case-insensitive) in the version's
+    /// tags, notes or description
+    pub text: Option<String>,
+}
+
+impl VersionFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stage(mut self, stage: LifecycleStage) -> Self {
+        self.stage = Some(stage);
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn created_between(
+        mut self,
+        after: Option<DateTime<Utc>>,
+        before: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.created_after = after;
+        self.created_before = before;
+        self
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn matches(&self, version: &DatasetVersion) -> bool {
+        let metadata = &version.metadata;
+        self.stage.is_none_or(|s| s == version.stage)
+            && self.tag.as_ref().is_none_or(|tag| {
+                metadata
+                    .tags
+                    .iter()
+                    .any(|t| t.eq_ignore_ascii_case(tag.trim()))
+            })
+            && self.created_after.is_none_or(|t| version.created_at >= t)
+            && self.created_before.is_none_or(|t| version.created_at < t)
+            && self.text.as_ref().is_none_or(|text| {
+                let haystack = format!(
+                    "{} {} {}",
+                    metadata.tags.join(" "),
+                    metadata.notes,
+                    metadata.description
+                )
+                .to_lowercase();
+                text.to_lowercase()
+                    .split_whitespace()
+                    .all(|word| haystack.contains(word))
+            })
+    }
+
+    /// The matching versions, newest first
+    pub fn apply(&self, versions: impl IntoIterator<Item = DatasetVersion>) -> Vec<DatasetVersion> {
+        let mut matching: Vec<DatasetVersion> =
+            versions.into_iter().filter(|v| self.matches(v)).collect();
+        matching.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        matching
+    }
+}
+
+/// A version found by [`super::DatasetRegistry::search_versions`]
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionSearchHit {
+    pub dataset_id: Uuid,
+    pub dataset_name: String,
+    pub version: DatasetVersion,
+}
+
 /// Builder for Sql-like queries on datasets
 pub struct DatasetQueryBuilder {
     dataset: Dataset,
@@ -193,6 +283,47 @@ mod tests {
         assert!(query.latest);
     }
 
+    #[test]
+    fn test_version_filter_matches_tags_notes_and_dates() {
+        let mut version = DatasetVersion::new_raw(
+            Uuid::new_v4(),
+            super::super::DataLocation::OriginalFile("a.csv".into()),
+        );
+        version.metadata.tags.push("pre-Q3-restatement".to_owned());
+        version.metadata.notes = "Used for the board pack".to_owned();
+
+        assert!(VersionFilter::new().matches(&version));
+        assert!(
+            VersionFilter::new()
+                .tag("PRE-q3-restatement")
+                .matches(&version)
+        );
+        assert!(!VersionFilter::new().tag("q3").matches(&version));
+        assert!(
+            VersionFilter::new()
+                .text("board restatement")
+                .matches(&version)
+        );
+        assert!(!VersionFilter::new().text("board audit").matches(&version));
+        assert!(
+            !VersionFilter::new()
+                .stage(LifecycleStage::Cleaned)
+                .matches(&version)
+        );
+
+        let created = version.created_at;
+        assert!(
+            VersionFilter::new()
+                .created_between(Some(created), Some(created + chrono::Duration::days(1)))
+                .matches(&version)
+        );
+        assert!(
+            !VersionFilter::new()
+                .created_between(None, Some(created))
+                .matches(&version)
+        );
+    }
+
     #[test]
     fn test_query_serialization() -> Result<()> {
         let query = VersionQuery::new().raw();
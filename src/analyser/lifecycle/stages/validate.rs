@@ -1,10 +1,17 @@
 //! Validate stage - QA gates and schema validation
 
 use super::{LifecycleStage, StageExecutor};
-use crate::analyser::lifecycle::transforms::TransformPipeline;
+use crate::analyser::lifecycle::transforms::{
+    QualityFlagsTransform, Transform as _, TransformPipeline, TransformSpec,
+};
+use crate::analyser::logic::RejectCondition;
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Name of the packed violations column written in [`QualityFlagMode::Packed`]
+pub const VIOLATIONS_COLUMN: &str = "qa_violations";
 
 /// Validation rule types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +32,131 @@ pub enum ValidationRule {
     CustomCondition { condition: String },
 }
 
+impl ValidationRule {
+    /// Short identifier used for flag column names, e.g. `value_range_age`
+    pub fn label(&self) -> String {
+        match self {
+            Self::MaxNullPercent { column, .. } => format!("max_null_percent_{column}"),
+            Self::ValueRange { column, .. } => format!("value_range_{column}"),
+            Self::ColumnExists { column } => format!("column_exists_{column}"),
+            Self::RowCountRange { .. } => "row_count_range".to_owned(),
+            Self::NoDuplicates { column } => format!("no_duplicates_{column}"),
+            Self::MatchesPattern { column, .. } => format!("matches_pattern_{column}"),
+            Self::CustomCondition { .. } => "custom_condition".to_owned(),
+        }
+    }
+
+    /// Boolean expression that is true for rows violating this rule, or `None`
+    /// for dataset-level rules (column existence, row counts) that have no
+    /// per-row meaning. Nulls never count as violations except for null checks.
+    pub fn violation_expr(&self, schema: &Schema) -> Result<Option<Expr>> {
+        let expr = match self {
+            Self::MaxNullPercent { column, .. } => col(column.as_str()).is_null(),
+            Self::ValueRange { column, min, max } => {
+                let value = col(column.as_str()).cast(DataType::Float64);
+                value.clone().lt(lit(*min)).or(value.gt(lit(*max)))
+            }
+            Self::NoDuplicates { column } => col(column.as_str()).is_duplicated(),
+            Self::MatchesPattern { column, pattern } => {
+                regex::Regex::new(pattern)
+                    .with_context(|| format!("Invalid pattern for '{column}'"))?;
+                col(column.as_str())
+                    .cast(DataType::String)
+                    .str()
+                    .contains(lit(pattern.as_str()), true)
+                    .not()
+            }
+            Self::CustomCondition { condition } => {
+                crate::pipeline::expression::compile(condition, schema)
+                    .with_context(|| format!("Invalid condition '{condition}'"))?
+                    .not()
+            }
+            Self::ColumnExists { .. } | Self::RowCountRange { .. } => return Ok(None),
+        };
+        Ok(Some(expr.fill_null(lit(false))))
+    }
+}
+
+/// Row-level rules as reject conditions, for routing violating rows to a
+/// reject file with [`crate::analyser::logic::split_rejects`]
+pub fn rule_reject_conditions(
+    rules: &[ValidationRule],
+    schema: &Schema,
+) -> Result<Vec<RejectCondition>> {
+    let mut conditions = Vec::new();
+    for rule in rules {
+        if let Some(condition) = rule.violation_expr(schema)? {
+            conditions.push(RejectCondition {
+                reason: rule.label(),
+                condition,
+            });
+        }
+    }
+    Ok(conditions)
+}
+
+/// How per-row rule violations are written into the dataset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityFlagMode {
+    /// One boolean `qa_<rule>` column per row-level rule (true = violated)
+    PerRule,
+    /// A single [`VIOLATIONS_COLUMN`] listing violated rules separated by `;`,
+    /// null when the row passes every rule
+    Packed,
+}
+
+/// Add quality flag columns for `rules` to `lf`
+pub fn materialise_quality_flags(
+    mut lf: LazyFrame,
+    rules: &[ValidationRule],
+    mode: QualityFlagMode,
+) -> Result<LazyFrame> {
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let mut used_names: HashSet<String> = schema.iter_names().map(|n| n.to_string()).collect();
+
+    let mut flags = Vec::new();
+    for rule in rules {
+        if let Some(expr) = rule.violation_expr(&schema)? {
+            let base = format!("qa_{}", rule.label());
+            let mut name = base.clone();
+            let mut counter = 1;
+            while used_names.contains(&name) {
+                name = format!("{base}_{counter}");
+                counter += 1;
+            }
+            used_names.insert(name.clone());
+            flags.push((name, rule.label(), expr));
+        }
+    }
+
+    if flags.is_empty() {
+        return Ok(lf);
+    }
+
+    match mode {
+        QualityFlagMode::PerRule => Ok(lf.with_columns(
+            flags
+                .into_iter()
+                .map(|(name, _, expr)| expr.alias(name))
+                .collect::<Vec<_>>(),
+        )),
+        QualityFlagMode::Packed => {
+            let parts: Vec<Expr> = flags
+                .into_iter()
+                .map(|(_, label, expr)| when(expr).then(lit(label)).otherwise(lit(NULL)))
+                .collect();
+            let packed = concat_str(parts, ";", true);
+            Ok(lf.with_column(
+                when(packed.clone().eq(lit("")))
+                    .then(lit(NULL).cast(DataType::String))
+                    .otherwise(packed)
+                    .alias(VIOLATIONS_COLUMN),
+            ))
+        }
+    }
+}
+
 /// Validation result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
@@ -35,14 +167,25 @@ pub struct ValidationResult {
 
 /// Validate stage executor
 /// Runs QA checks and validation rules
-/// This stage doesn't transform data, just validates it
+/// This stage doesn't transform data, just validates it, unless `flag_mode`
+/// is set: then failing rules don't abort the stage and per-row flags are
+/// added instead so rejected records can be routed downstream
 pub struct ValidateStageExecutor {
     pub rules: Vec<ValidationRule>,
+    pub flag_mode: Option<QualityFlagMode>,
 }
 
 impl ValidateStageExecutor {
     pub fn new(rules: Vec<ValidationRule>) -> Self {
-        Self { rules }
+        Self {
+            rules,
+            flag_mode: None,
+        }
+    }
+
+    pub fn with_flags(mut self, mode: QualityFlagMode) -> Self {
+        self.flag_mode = Some(mode);
+        self
     }
 
     pub fn default_rules() -> Vec<ValidationRule> {
@@ -222,6 +365,14 @@ impl ValidateStageExecutor {
 
 impl StageExecutor for ValidateStageExecutor {
     fn execute(&self, lf: LazyFrame) -> Result<TransformPipeline> {
+        if let Some(mode) = self.flag_mode {
+            let transform = QualityFlagsTransform::new(self.rules.clone(), mode);
+            return Ok(TransformPipeline::new(vec![TransformSpec {
+                transform_type: transform.name().to_owned(),
+                parameters: transform.parameters(),
+            }]));
+        }
+
         // Run validation
         let results = self.validate(lf.clone())?;
 
@@ -266,4 +417,96 @@ mod tests {
         let executor = ValidateStageExecutor::new(rules);
         assert_eq!(executor.stage(), LifecycleStage::Validated);
     }
+
+    #[test]
+    fn test_quality_flags() -> Result<()> {
+        let df = df!(
+            "id" => [1i64, 2, 2, 4],
+            "age" => [Some(30.0), None, Some(150.0), Some(40.0)],
+            "email" => ["a@x.com", "bad", "c@x.com", "d@x.com"],
+        )?;
+        let rules = vec![
+            ValidationRule::ColumnExists {
+                column: "id".to_owned(),
+            },
+            ValidationRule::MaxNullPercent {
+                column: "age".to_owned(),
+                max_percent: 0.0,
+            },
+            ValidationRule::ValueRange {
+                column: "age".to_owned(),
+                min: 0.0,
+                max: 120.0,
+            },
+            ValidationRule::NoDuplicates {
+                column: "id".to_owned(),
+            },
+            ValidationRule::MatchesPattern {
+                column: "email".to_owned(),
+                pattern: "@".to_owned(),
+            },
+        ];
+
+        let flagged =
+            materialise_quality_flags(df.clone().lazy(), &rules, QualityFlagMode::PerRule)?
+                .collect()?;
+        assert!(flagged.column("qa_column_exists_id").is_err());
+        let range = flagged.column("qa_value_range_age")?.bool()?.clone();
+        assert_eq!(range.get(1), Some(false));
+        assert_eq!(range.get(2), Some(true));
+        let dupes = flagged.column("qa_no_duplicates_id")?.bool()?.clone();
+        assert_eq!(dupes.sum(), Some(2));
+
+        let packed =
+            materialise_quality_flags(df.lazy(), &rules, QualityFlagMode::Packed)?.collect()?;
+        let violations = packed.column(VIOLATIONS_COLUMN)?.str()?.clone();
+        assert_eq!(violations.get(0), None);
+        assert_eq!(
+            violations.get(1),
+            Some("max_null_percent_age;no_duplicates_id;matches_pattern_email")
+        );
+        assert_eq!(violations.get(2), Some("value_range_age;no_duplicates_id"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_rejects() -> Result<()> {
+        let df = df!("age" => [30.0, 200.0, -1.0])?;
+        let rules = vec![ValidationRule::ValueRange {
+            column: "age".to_owned(),
+            min: 0.0,
+            max: 120.0,
+        }];
+
+        let mut lf = df.lazy();
+        let conditions = rule_reject_conditions(&rules, &lf.collect_schema()?)?;
+        let (kept, rejects) = crate::analyser::logic::split_rejects(lf, &conditions);
+
+        assert_eq!(kept.collect()?.height(), 1);
+        assert_eq!(rejects.collect()?.height(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_flag_mode_emits_transform_instead_of_failing() -> Result<()> {
+        let df = df!("age" => [200.0])?;
+        let rules = vec![ValidationRule::ValueRange {
+            column: "age".to_owned(),
+            min: 0.0,
+            max: 120.0,
+        }];
+
+        assert!(
+            ValidateStageExecutor::new(rules.clone())
+                .execute(df.clone().lazy())
+                .is_err()
+        );
+
+        let pipeline = ValidateStageExecutor::new(rules)
+            .with_flags(QualityFlagMode::PerRule)
+            .execute(df.clone().lazy())?;
+        let out = pipeline.apply(df.lazy())?.collect()?;
+        assert_eq!(out.column("qa_value_range_age")?.bool()?.get(0), Some(true));
+        Ok(())
+    }
 }
@@ -1,14 +1,28 @@
 //! Storage backend for dataset versions
+//!
+//! Version data is stored as content-addressed chunks: runs of
+//! [`CHUNK_ROWS`] rows, each written as a single-row-group Parquet file named
+//! by its SHA-256 under `chunks/`. A version is a `<version>.parquet` folder
+//! of hard links to its chunks, so versions that share most of their rows
+//! share most of their storage while still reading as ordinary Parquet.
 
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
 use super::version::DatasetVersion;
 
+/// Rows per content-addressed chunk
+pub const CHUNK_ROWS: IdxSize = 65_536;
+
+/// Folder under the store's base path holding the shared chunks
+const CHUNK_DIR: &str = "chunks";
+
 /// Location of version data
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DataLocation {
@@ -43,7 +57,15 @@ impl VersionStore {
         self.base_path.join(dataset_id.to_string())
     }
 
-    /// Get the path for a version's data file
+    /// Path of a chunk, fanned out by the first two hex digits of its hash
+    fn chunk_path(&self, hash: &str) -> PathBuf {
+        self.base_path
+            .join(CHUNK_DIR)
+            .join(hash.get(..2).unwrap_or("00"))
+            .join(format!("{hash}.parquet"))
+    }
+
+    /// Get the path for a version's data (a folder of chunk links)
     fn version_data_path(&self, dataset_id: &Uuid, version_id: &Uuid) -> PathBuf {
         self.dataset_dir(dataset_id)
             .join(format!("{version_id}.parquet"))
@@ -74,7 +96,8 @@ impl VersionStore {
         Ok(DataLocation::OriginalFile(source_path.to_path_buf()))
     }
 
-    /// Store transformed version data
+    /// Store transformed version data as content-addressed chunks, reusing
+    /// any chunk already stored by another version
     pub fn store_version_data(
         &self,
         dataset_id: &Uuid,
@@ -84,21 +107,113 @@ impl VersionStore {
         let dataset_dir = self.dataset_dir(dataset_id);
         fs::create_dir_all(&dataset_dir).context("Failed to create dataset directory")?;
 
-        let dest_path = self.version_data_path(dataset_id, version_id);
-
-        // Write with streaming and compression
-        let write_opts = crate::analyser::logic::get_parquet_write_options(lf)?;
+        // Stream to one file first so large data never has to fit in memory,
+        // keeping row order so unchanged rows fall into identical chunks
+        let staging = dataset_dir.join(format!("{version_id}.staging.parquet"));
+        let _staging_guard = crate::utils::TempFileGuard::new(staging.clone());
+        let write_opts = ParquetWriteOptions {
+            maintain_order: true,
+            ..crate::analyser::logic::get_parquet_write_options(lf)?
+        };
         lf.clone()
             .with_streaming(true)
-            .sink_parquet(&dest_path, write_opts, None)
+            .sink_parquet(&staging, write_opts, None)
             .context("Failed to sink version data to parquet")?;
 
+        let metadata = ParquetReader::new(fs::File::open(&staging)?)
+            .get_metadata()
+            .context("Failed to read staged version data")?
+            .clone();
+        let read_staged = |offset: usize, rows: usize| -> Result<DataFrame> {
+            ParquetReader::new(fs::File::open(&staging)?)
+                .set_metadata(metadata.clone())
+                .with_slice(Some((offset, rows)))
+                .finish()
+                .context("Failed to read staged version data")
+        };
+        let dest_path = self.version_data_path(dataset_id, version_id);
+        fs::create_dir_all(&dest_path).context("Failed to create version data directory")?;
+
+        // Read each staged row group once, cutting chunks at multiples of
+        // CHUNK_ROWS whatever the row groups' sizes
+        let chunk_rows = CHUNK_ROWS as usize;
+        let mut pending: Option<DataFrame> = None;
+        let mut offset = 0_usize;
+        let mut part = 0_usize;
+        for row_group in &metadata.row_groups {
+            let rows = row_group.num_rows();
+            let group = read_staged(offset, rows)?;
+            offset += rows;
+            let mut buffered = match pending.take() {
+                Some(mut df) => {
+                    df.vstack_mut(&group)?;
+                    df
+                }
+                None => group,
+            };
+            while buffered.height() >= chunk_rows {
+                let rest = buffered.slice(i64::from(CHUNK_ROWS), usize::MAX);
+                self.store_part(&mut buffered.slice(0, chunk_rows), part, &dest_path)?;
+                part += 1;
+                buffered = rest;
+            }
+            pending = Some(buffered);
+        }
+
+        // The remaining rows; an empty frame still gets one chunk to carry
+        // the schema
+        let last = match pending {
+            Some(df) if df.height() > 0 || part == 0 => Some(df),
+            None => Some(read_staged(0, 0)?),
+            _ => None,
+        };
+        if let Some(mut last) = last {
+            self.store_part(&mut last, part, &dest_path)?;
+        }
+
         Ok(DataLocation::ParquetFile(dest_path))
     }
 
+    /// Store `chunk` and link it into the version folder `dest` as part
+    /// number `part`
+    fn store_part(&self, chunk: &mut DataFrame, part: usize, dest: &Path) -> Result<()> {
+        let hash = self.store_chunk(chunk)?;
+        link_chunk(
+            &self.chunk_path(&hash),
+            &dest.join(format!("{part:05}-{hash}.parquet")),
+        )
+    }
+
+    /// Add `chunk` to the chunk store unless an identical chunk is already
+    /// there, and return its hash
+    fn store_chunk(&self, chunk: &mut DataFrame) -> Result<String> {
+        let mut bytes = Vec::new();
+        ParquetWriter::new(&mut bytes)
+            .with_row_group_size(Some(CHUNK_ROWS as usize))
+            .finish(chunk)
+            .context("Failed to encode chunk")?;
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+
+        let path = self.chunk_path(&hash);
+        if !path.exists() {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).context("Failed to create chunk directory")?;
+            }
+            // Write under a temporary name so a crash never leaves a
+            // truncated chunk under a valid hash
+            let partial = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+            fs::write(&partial, &bytes).context("Failed to write chunk")?;
+            fs::rename(&partial, &path).context("Failed to write chunk")?;
+        }
+        Ok(hash)
+    }
+
     /// Load data for a version
     pub fn load_version_data(&self, location: &DataLocation) -> Result<LazyFrame> {
         match location {
+            DataLocation::ParquetFile(path) if path.is_dir() => {
+                crate::analyser::logic::scan_parquet_dir(path)
+            }
             DataLocation::ParquetFile(path) => LazyFrame::scan_parquet(path, Default::default())
                 .context("Failed to scan parquet file"),
             DataLocation::OriginalFile(path) => {
@@ -138,16 +253,163 @@ impl VersionStore {
         let meta_path = self.version_metadata_path(dataset_id, version_id);
 
         if data_path.exists() {
-            fs::remove_file(&data_path).context("Failed to delete version data")?;
+            remove_data(&data_path).context("Failed to delete version data")?;
+        }
+
+        if meta_path.exists() {
+            fs::remove_file(&meta_path).context("Failed to delete version metadata")?;
         }
 
+        Ok(())
+    }
+
+    /// Delete a version's metadata file, leaving its data alone
+    pub fn delete_version_metadata(&self, dataset_id: &Uuid, version_id: &Uuid) -> Result<()> {
+        let meta_path = self.version_metadata_path(dataset_id, version_id);
         if meta_path.exists() {
             fs::remove_file(&meta_path).context("Failed to delete version metadata")?;
         }
+        Ok(())
+    }
 
+    /// Delete a data file written by this store. Files outside the store
+    /// (such as the original file behind a raw version) are never touched.
+    pub fn delete_data_file(&self, path: &Path) -> Result<()> {
+        if !path.starts_with(&self.base_path) {
+            anyhow::bail!(
+                "Refusing to delete {} outside the version store",
+                path.display()
+            );
+        }
+        if path.exists() {
+            remove_data(path)
+                .with_context(|| format!("Failed to delete version data {}", path.display()))?;
+        }
         Ok(())
     }
 
+    /// Remove files in a dataset's folder that nothing refers to: metadata
+    /// of versions not in `versions`, and Parquet data not in
+    /// `referenced_paths` (e.g. left behind by an interrupted write). Chunks
+    /// are only linked from here, so their space is freed by
+    /// [`VersionStore::collect_chunk_garbage`].
+    pub fn collect_garbage(
+        &self,
+        dataset_id: &Uuid,
+        versions: &HashSet<Uuid>,
+        referenced_paths: &HashSet<PathBuf>,
+    ) -> Result<GarbageReport> {
+        let dataset_dir = self.dataset_dir(dataset_id);
+        let mut report = GarbageReport::default();
+        if !dataset_dir.exists() {
+            return Ok(report);
+        }
+
+        for entry in fs::read_dir(&dataset_dir).context("Failed to read dataset directory")? {
+            let entry = entry?;
+            let path = entry.path();
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+
+            let orphaned = if let Some(uuid_str) = file_name.strip_suffix(".meta.json") {
+                Uuid::parse_str(uuid_str).is_ok_and(|id| !versions.contains(&id))
+            } else {
+                file_name.ends_with(".parquet") && !referenced_paths.contains(&path)
+            };
+            if orphaned {
+                if path.is_dir() {
+                    report.files_removed += data_files(&path).len();
+                } else {
+                    report.files_removed += 1;
+                    report.bytes_freed += entry.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+                remove_data(&path)
+                    .with_context(|| format!("Failed to remove {}", path.display()))?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Remove chunks that no version folder in the store links to, whether
+    /// or not its dataset is registered, along with partial chunk writes
+    pub fn collect_chunk_garbage(&self) -> Result<GarbageReport> {
+        let mut report = GarbageReport::default();
+        let chunk_root = self.base_path.join(CHUNK_DIR);
+        if !chunk_root.exists() {
+            return Ok(report);
+        }
+
+        let mut referenced = HashSet::new();
+        for dataset_dir in fs::read_dir(&self.base_path).context("Failed to read version store")? {
+            let dataset_dir = dataset_dir?.path();
+            if !dataset_dir.is_dir() || dataset_dir == chunk_root {
+                continue;
+            }
+            for version_dir in
+                fs::read_dir(&dataset_dir).context("Failed to read dataset directory")?
+            {
+                let version_dir = version_dir?.path();
+                if version_dir.is_dir() {
+                    referenced.extend(
+                        data_files(&version_dir)
+                            .iter()
+                            .filter_map(|(part, _)| chunk_hash(part)),
+                    );
+                }
+            }
+        }
+
+        for fan_out in fs::read_dir(&chunk_root).context("Failed to read chunk directory")? {
+            let fan_out = fan_out?.path();
+            for (chunk, bytes) in data_files(&fan_out) {
+                let file_name = chunk.file_name().unwrap_or_default().to_string_lossy();
+                let in_use = file_name
+                    .strip_suffix(".parquet")
+                    .is_some_and(|hash| referenced.contains(hash));
+                if !in_use {
+                    fs::remove_file(&chunk)
+                        .with_context(|| format!("Failed to remove {}", chunk.display()))?;
+                    report.files_removed += 1;
+                    report.bytes_freed += bytes;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Space taken by `versions`, counting each stored chunk or file once
+    /// however many versions use it
+    pub fn storage_usage(&self, versions: &[DatasetVersion]) -> StorageUsage {
+        let mut usage = StorageUsage::default();
+        let mut seen = HashSet::new();
+        for version in versions {
+            let DataLocation::ParquetFile(path) = &version.data_location else {
+                continue;
+            };
+            usage.versions += 1;
+            let files = if path.is_dir() {
+                data_files(path)
+            } else {
+                fs::metadata(path)
+                    .map(|m| vec![(path.clone(), m.len())])
+                    .unwrap_or_default()
+            };
+            for (file, bytes) in files {
+                usage.logical_bytes += bytes;
+                let key =
+                    chunk_hash(&file).map_or_else(|| file.display().to_string(), str::to_owned);
+                if seen.insert(key) {
+                    usage.chunks += 1;
+                    usage.stored_bytes += bytes;
+                }
+            }
+        }
+        usage.saved_bytes = usage.logical_bytes.saturating_sub(usage.stored_bytes);
+        usage
+    }
+
     /// Get storage statistics for a dataset
     pub fn get_dataset_stats(&self, dataset_id: &Uuid) -> Result<DatasetStorageStats> {
         let dataset_dir = self.dataset_dir(dataset_id);
@@ -165,7 +427,9 @@ impl VersionStore {
 
             if path.extension().and_then(|s| s.to_str()) == Some("parquet") {
                 version_count += 1;
-                if let Ok(metadata) = fs::metadata(&path) {
+                if path.is_dir() {
+                    total_bytes += data_files(&path).iter().map(|(_, len)| len).sum::<u64>();
+                } else if let Ok(metadata) = fs::metadata(&path) {
                     total_bytes += metadata.len();
                 }
             }
@@ -200,7 +464,7 @@ impl VersionStore {
                 && let Ok(version_id) = Uuid::parse_str(uuid_str)
                 && !keep_versions.contains(&version_id)
             {
-                fs::remove_file(&path)?;
+                remove_data(&path)?;
                 deleted_count += 1;
             }
         }
@@ -209,6 +473,35 @@ impl VersionStore {
     }
 }
 
+/// Files removed by [`VersionStore::collect_garbage`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GarbageReport {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+impl GarbageReport {
+    pub fn merge(&mut self, other: Self) {
+        self.files_removed += other.files_removed;
+        self.bytes_freed += other.bytes_freed;
+    }
+}
+
+/// Space used by a dataset's stored versions; see
+/// [`VersionStore::storage_usage`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StorageUsage {
+    /// Versions with data in the store (raw versions reference their source)
+    pub versions: usize,
+    /// Distinct chunks (or legacy single files) those versions use
+    pub chunks: usize,
+    /// Size if every version had its own copy
+    pub logical_bytes: u64,
+    /// Size with shared chunks counted once
+    pub stored_bytes: u64,
+    pub saved_bytes: u64,
+}
+
 /// Storage statistics for a dataset
 #[derive(Debug, Clone, Default)]
 pub struct DatasetStorageStats {
@@ -230,6 +523,51 @@ impl DatasetStorageStats {
     }
 }
 
+/// Hard-link a stored chunk into a version folder, copying it where links
+/// are not supported
+fn link_chunk(chunk: &Path, dest: &Path) -> Result<()> {
+    if fs::hard_link(chunk, dest).is_err() {
+        fs::copy(chunk, dest)
+            .with_context(|| format!("Failed to link chunk into {}", dest.display()))?;
+    }
+    Ok(())
+}
+
+/// Hash of the chunk behind a `<part>-<hash>.parquet` link, or `None` for
+/// any other file, such as the `<uuid>.parquet` file of a legacy version
+fn chunk_hash(part: &Path) -> Option<&str> {
+    let stem = part.file_name()?.to_str()?.strip_suffix(".parquet")?;
+    let (index, hash) = stem.split_once('-')?;
+    let is_index = index.len() >= 5 && index.bytes().all(|b| b.is_ascii_digit());
+    let is_hash = hash.len() == 64
+        && hash
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+    (is_index && is_hash).then_some(hash)
+}
+
+/// Files directly inside `dir` with their sizes
+fn data_files(dir: &Path) -> Vec<(PathBuf, u64)> {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| (entry.path(), metadata.len()))
+        })
+        .collect()
+}
+
+/// Remove version data, a single file or a folder of chunk links
+fn remove_data(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +597,68 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_versions_share_chunks() -> Result<()> {
+        use crate::analyser::lifecycle::{LifecycleStage, TransformPipeline};
+
+        let temp = TempDir::new()?;
+        let store = VersionStore::new(temp.path().to_path_buf())?;
+        let dataset_id = Uuid::new_v4();
+        let rows = CHUNK_ROWS as i64 + 100;
+        let full = df!("n" => (0..rows).collect::<Vec<i64>>())?.lazy();
+        let trimmed = full.clone().filter(col("n").lt(lit(rows - 1)));
+
+        let mut versions = Vec::new();
+        for lf in [full, trimmed] {
+            let id = Uuid::new_v4();
+            let location = store.store_version_data(&dataset_id, &id, &lf)?;
+            versions.push(DatasetVersion::new_derived(
+                id,
+                dataset_id,
+                Uuid::new_v4(),
+                LifecycleStage::Cleaned,
+                TransformPipeline::empty(),
+                location,
+            ));
+        }
+
+        // The first chunk of both versions is identical and stored once
+        let usage = store.storage_usage(&versions);
+        assert_eq!(usage.versions, 2);
+        assert_eq!(usage.chunks, 3);
+        assert!(usage.saved_bytes > 0);
+        assert_eq!(usage.logical_bytes, usage.stored_bytes + usage.saved_bytes);
+
+        let loaded = store
+            .load_version_data(&versions[1].data_location)?
+            .collect()?;
+        assert_eq!(loaded.height(), rows as usize - 1);
+
+        store.delete_data_file(versions[1].data_location.path())?;
+        let report = store.collect_chunk_garbage()?;
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(store.storage_usage(&versions[..1]).chunks, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_hash_only_matches_chunk_links() {
+        let hash = "0f".repeat(32);
+        let link = PathBuf::from(format!("v.parquet/00003-{hash}.parquet"));
+        assert_eq!(chunk_hash(&link), Some(hash.as_str()));
+
+        // A legacy version file has dashes but no chunk hash
+        let legacy = PathBuf::from(format!("{}.parquet", Uuid::new_v4()));
+        assert_eq!(chunk_hash(&legacy), None);
+        assert_eq!(chunk_hash(Path::new("00003-abc.parquet")), None);
+        assert_eq!(
+            chunk_hash(Path::new(&format!("x0003-{hash}.parquet"))),
+            None
+        );
+        assert_eq!(
+            chunk_hash(Path::new(&format!("00003-{}.parquet", hash.to_uppercase()))),
+            None
+        );
+    }
 }
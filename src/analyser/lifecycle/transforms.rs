@@ -5,6 +5,9 @@ use polars::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::analyser::lifecycle::stages::validate::{
+    QualityFlagMode, ValidationRule, materialise_quality_flags,
+};
 use crate::analyser::logic::{ColumnCleanConfig, clean_df_lazy};
 
 /// Trait for all data transformations
@@ -116,6 +119,9 @@ fn instantiate_transform(spec: &TransformSpec) -> Result<Box<dyn Transform>> {
             &spec.parameters,
         )?)),
         "sort" => Ok(Box::new(SortTransform::from_parameters(&spec.parameters)?)),
+        "quality_flags" => Ok(Box::new(QualityFlagsTransform::from_parameters(
+            &spec.parameters,
+        )?)),
         _ => Err(anyhow::anyhow!(
             "Unknown transform type: {}",
             spec.transform_type
@@ -525,6 +531,71 @@ impl Transform for SortTransform {
     }
 }
 
+/// Materialise row-level quality rule violations as flag columns
+#[derive(Debug, Clone)]
+pub struct QualityFlagsTransform {
+    rules: Vec<ValidationRule>,
+    mode: QualityFlagMode,
+}
+
+impl QualityFlagsTransform {
+    pub fn new(rules: Vec<ValidationRule>, mode: QualityFlagMode) -> Self {
+        Self { rules, mode }
+    }
+
+    pub fn from_parameters(params: &HashMap<String, serde_json::Value>) -> Result<Self> {
+        let rules_json = params
+            .get("rules")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'rules' parameter"))?;
+        let rules: Vec<ValidationRule> =
+            serde_json::from_value(rules_json.clone()).context("Failed to deserialize rules")?;
+
+        let mode = params
+            .get("mode")
+            .map(|v| serde_json::from_value(v.clone()))
+            .transpose()
+            .context("Failed to deserialize flag mode")?
+            .unwrap_or(QualityFlagMode::PerRule);
+
+        Ok(Self { rules, mode })
+    }
+}
+
+impl Transform for QualityFlagsTransform {
+    fn apply(&self, lf: LazyFrame) -> Result<LazyFrame> {
+        materialise_quality_flags(lf, &self.rules, self.mode)
+    }
+
+    fn name(&self) -> &'static str {
+        "quality_flags"
+    }
+
+    fn parameters(&self) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert(
+            "rules".to_owned(),
+            serde_json::to_value(&self.rules).unwrap_or(serde_json::Value::Null),
+        );
+        params.insert(
+            "mode".to_owned(),
+            serde_json::to_value(self.mode).unwrap_or(serde_json::Value::Null),
+        );
+        params
+    }
+
+    fn description(&self) -> String {
+        format!("Flag rows violating {} quality rules", self.rules.len())
+    }
+
+    fn to_json(&self) -> Result<String> {
+        let spec = TransformSpec {
+            transform_type: self.name().to_owned(),
+            parameters: self.parameters(),
+        };
+        serde_json::to_string_pretty(&spec).context("Failed to serialize transform")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
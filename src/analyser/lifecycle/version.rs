@@ -1,22 +1,31 @@
 //! Version management for dataset lifecycle
 
+use super::approval::{Approval, ApprovalState};
 use super::stages::{LifecycleStage, PublishMode};
-use super::storage::{DataLocation, VersionStore};
+use super::storage::{DataLocation, StorageUsage, VersionStore};
 use super::transforms::TransformPipeline;
 use anyhow::{Context as _, Result};
 use chrono::{DateTime, Utc};
 use polars::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use uuid::Uuid;
 
+/// Custom field naming the pipeline run that produced a version, set on
+/// versions added with [`Dataset::add_data_version`] by a pipeline
+pub const PIPELINE_RUN_FIELD: &str = "pipeline_run";
+
 /// Metadata associated with a dataset version
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionMetadata {
     pub description: String,
+    /// User labels such as "pre-Q3-restatement"; unique, case-insensitively
     pub tags: Vec<String>,
+    /// Free-text notes, e.g. which report the version fed
+    #[serde(default)]
+    pub notes: String,
     pub row_count: Option<usize>,
     pub column_count: Option<usize>,
     pub file_size_bytes: Option<u64>,
@@ -29,6 +38,7 @@ impl Default for VersionMetadata {
         Self {
             description: String::new(),
             tags: Vec::new(),
+            notes: String::new(),
             row_count: None,
             column_count: None,
             file_size_bytes: None,
@@ -49,6 +59,9 @@ pub struct DatasetVersion {
     pub data_location: DataLocation,
     pub metadata: VersionMetadata,
     pub created_at: DateTime<Utc>,
+    /// Where the version is in the approval workflow; see [`super::approval`]
+    #[serde(default)]
+    pub approval: Approval,
 }
 
 impl DatasetVersion {
@@ -65,6 +78,7 @@ impl DatasetVersion {
                 ..Default::default()
             },
             created_at: Utc::now(),
+            approval: Approval::default(),
         }
     }
 
@@ -88,6 +102,7 @@ impl DatasetVersion {
                 ..Default::default()
             },
             created_at: Utc::now(),
+            approval: Approval::default(),
         }
     }
 
@@ -149,6 +164,10 @@ impl VersionTree {
         self.versions.get(id)
     }
 
+    pub fn get_version_mut(&mut self, id: &Uuid) -> Option<&mut DatasetVersion> {
+        self.versions.get_mut(id)
+    }
+
     pub fn root(&self) -> &DatasetVersion {
         self.versions
             .get(&self.root_id)
@@ -182,6 +201,45 @@ impl VersionTree {
             .filter(|v| v.parent_id == Some(*version_id))
             .collect()
     }
+
+    /// Remove a version, attaching its children to its own parent so the
+    /// lineage stays connected. Returns the removed version and the ids of
+    /// the reparented children; the root cannot be removed.
+    pub fn remove_version(&mut self, id: &Uuid) -> Option<(DatasetVersion, Vec<Uuid>)> {
+        if *id == self.root_id {
+            return None;
+        }
+        let removed = self.versions.remove(id)?;
+        let mut reparented = Vec::new();
+        #[expect(clippy::iter_over_hash_type)]
+        for version in self.versions.values_mut() {
+            if version.parent_id == Some(*id) {
+                version.parent_id = removed.parent_id;
+                reparented.push(version.id);
+            }
+        }
+        Some((removed, reparented))
+    }
+}
+
+/// A version recreated by [`Dataset::refresh_from_source`]
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshedVersion {
+    pub stage: LifecycleStage,
+    /// The version whose pipeline was re-applied
+    pub previous_id: Uuid,
+    pub new_id: Uuid,
+}
+
+/// Outcome of re-reading a dataset's source file
+#[derive(Debug, Clone, Serialize)]
+pub struct RefreshReport {
+    pub dataset_id: Uuid,
+    pub source_path: PathBuf,
+    /// In lineage order, from the version after raw to the new active one
+    pub versions: Vec<RefreshedVersion>,
+    pub active_version_id: Uuid,
+    pub refreshed_at: DateTime<Utc>,
 }
 
 /// Top-level dataset containing all versions
@@ -193,6 +251,9 @@ pub struct Dataset {
     pub active_version_id: Uuid,
     pub versions: VersionTree,
     pub created_at: DateTime<Utc>,
+    /// File pattern (e.g. `sales_*.csv`) for new files that the watcher adds
+    /// as raw versions of this dataset; see [`Self::add_source_version`]
+    pub source_pattern: Option<String>,
     #[serde(skip)]
     pub store: Arc<VersionStore>,
 }
@@ -206,6 +267,26 @@ impl Dataset {
 
         // Create raw version
         let raw_version = DatasetVersion::new_raw(id, data_location);
+        Self::with_raw_version(name, raw_version, store)
+    }
+
+    /// A dataset whose raw version stores `lf` itself instead of referencing
+    /// a file, for data a path alone cannot load again, such as a CSV file
+    /// read without a header row. It has no source file to refresh from.
+    pub fn from_data(name: String, lf: &LazyFrame, store: Arc<VersionStore>) -> Result<Self> {
+        let id = Uuid::new_v4();
+        let mut raw_version =
+            DatasetVersion::new_raw(id, DataLocation::ParquetFile(PathBuf::new()));
+        raw_version.data_location = store.store_version_data(&id, &raw_version.id, lf)?;
+        Self::with_raw_version(name, raw_version, store)
+    }
+
+    fn with_raw_version(
+        name: String,
+        raw_version: DatasetVersion,
+        store: Arc<VersionStore>,
+    ) -> Result<Self> {
+        let id = raw_version.dataset_id;
         let raw_version_id = raw_version.id;
 
         // Save version metadata
@@ -218,6 +299,7 @@ impl Dataset {
             active_version_id: raw_version_id,
             versions: VersionTree::new(raw_version),
             created_at: Utc::now(),
+            source_pattern: None,
             store,
         })
     }
@@ -227,6 +309,8 @@ impl Dataset {
         pipeline: TransformPipeline,
         stage: LifecycleStage,
     ) -> Result<Uuid> {
+        refuse_published_stage(stage)?;
+
         // Get active version
         let active_version = self
             .versions
@@ -282,6 +366,78 @@ impl Dataset {
         Ok(new_version_id)
     }
 
+    /// Store `lf` as a new `stage` version derived from `parent_id` and make
+    /// it active. Unlike [`Self::apply_pipeline`] the data was produced
+    /// outside the lifecycle (e.g. by a pipeline run), so no transform is
+    /// recorded and the version cannot be recreated by a refresh.
+    pub fn add_data_version(
+        &mut self,
+        parent_id: &Uuid,
+        lf: &LazyFrame,
+        stage: LifecycleStage,
+        metadata: VersionMetadata,
+    ) -> Result<Uuid> {
+        refuse_published_stage(stage)?;
+        if self.versions.get_version(parent_id).is_none() {
+            anyhow::bail!("Version {parent_id} not found");
+        }
+        let version_id = Uuid::new_v4();
+        let data_location = self.store.store_version_data(&self.id, &version_id, lf)?;
+
+        let mut version = DatasetVersion::new_derived(
+            version_id,
+            self.id,
+            *parent_id,
+            stage,
+            TransformPipeline::empty(),
+            data_location,
+        );
+        version.metadata = metadata;
+        self.store.save_version_metadata(&version)?;
+        self.versions.add_version(version)?;
+        self.active_version_id = version_id;
+        Ok(version_id)
+    }
+
+    /// Whether `path` is a new file for this dataset by its source pattern
+    pub fn matches_source_pattern(&self, path: &Path) -> bool {
+        self.source_pattern
+            .as_deref()
+            .is_some_and(|pattern| crate::config::policy::matches_file_pattern(pattern, path))
+    }
+
+    /// Add `path`, a newer delivery of the source data, as a raw version
+    /// derived from the current source version and make it active. Later
+    /// refreshes re-read this file.
+    pub fn add_source_version(&mut self, path: &Path) -> Result<Uuid> {
+        let parent_id = self.source_version().id;
+        let data_location = self.store.store_raw_data(&self.id, path)?;
+        let mut version = DatasetVersion::new_raw(self.id, data_location);
+        version.parent_id = Some(parent_id);
+        version.metadata.description = format!(
+            "Raw ingestion: {}",
+            path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().into_owned()
+            )
+        );
+        version.metadata.created_by = "watcher".to_owned();
+        let version_id = version.id;
+
+        self.store.save_version_metadata(&version)?;
+        self.versions.add_version(version)?;
+        self.active_version_id = version_id;
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "Added {} as raw version {version_id} of '{}'",
+                path.display(),
+                self.name
+            ),
+        );
+        Ok(version_id)
+    }
+
     pub fn set_active_version(&mut self, version_id: &Uuid) -> Result<()> {
         if self.versions.get_version(version_id).is_none() {
             return Err(anyhow::anyhow!("Version {version_id} not found"));
@@ -309,7 +465,392 @@ impl Dataset {
         self.versions.list_all().into_iter().cloned().collect()
     }
 
+    /// The file the active data was ingested from, if its source version
+    /// references one
+    pub fn source_path(&self) -> Option<&Path> {
+        match &self.source_version().data_location {
+            DataLocation::OriginalFile(path) => Some(path),
+            DataLocation::ParquetFile(_) => None,
+        }
+    }
+
+    /// The latest raw version the active version derives from: the first
+    /// raw version unless newer source files were added
+    fn source_version(&self) -> &DatasetVersion {
+        self.versions
+            .get_lineage(&self.active_version_id)
+            .into_iter()
+            .rev()
+            .find(|v| v.stage == LifecycleStage::Raw)
+            .unwrap_or_else(|| self.versions.root())
+    }
+
+    /// Re-read the source file and re-apply, in order, the pipeline of every
+    /// version between the raw version and the active one, creating a new
+    /// version for each. Published versions are published again in the same
+    /// mode. The new chain starts from the source raw version and its last
+    /// version becomes active; earlier versions are kept. If a stage fails, the
+    /// previous active version is restored.
+    pub fn refresh_from_source(&mut self) -> Result<RefreshReport> {
+        let source_path = self
+            .source_path()
+            .ok_or_else(|| anyhow::anyhow!("Dataset '{}' has no source file", self.name))?
+            .to_path_buf();
+        if !source_path.exists() {
+            anyhow::bail!("Source file not found: {}", source_path.display());
+        }
+
+        let source_id = self.source_version().id;
+        let chain: Vec<DatasetVersion> = self
+            .versions
+            .get_lineage(&self.active_version_id)
+            .into_iter()
+            .skip_while(|v| v.id != source_id)
+            .skip(1)
+            .cloned()
+            .collect();
+
+        let previous_active = self.active_version_id;
+        self.active_version_id = source_id;
+        let mut versions = Vec::with_capacity(chain.len());
+        for version in &chain {
+            match self.refresh_version(version) {
+                Ok(new_id) => versions.push(RefreshedVersion {
+                    stage: version.stage,
+                    previous_id: version.id,
+                    new_id,
+                }),
+                Err(e) => {
+                    self.active_version_id = previous_active;
+                    return Err(e.context(format!(
+                        "Failed to refresh the {} stage of '{}'",
+                        version.stage.as_str(),
+                        self.name
+                    )));
+                }
+            }
+        }
+
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "Refreshed '{}' from {}: {} version(s) recreated",
+                self.name,
+                source_path.display(),
+                versions.len()
+            ),
+        );
+        Ok(RefreshReport {
+            dataset_id: self.id,
+            source_path,
+            versions,
+            active_version_id: self.active_version_id,
+            refreshed_at: Utc::now(),
+        })
+    }
+
+    /// Recreate `version` on top of the active version and make it active
+    fn refresh_version(&mut self, version: &DatasetVersion) -> Result<Uuid> {
+        if let Some(pipeline) = version.metadata.custom_fields.get(PIPELINE_RUN_FIELD) {
+            anyhow::bail!(
+                "Version {} was produced by pipeline run {pipeline}; run the pipeline again instead",
+                version.id
+            );
+        }
+        let new_id = if version.stage == LifecycleStage::Published {
+            // A view shares its parent's data; a snapshot has its own copy
+            let parent_location = version
+                .parent_id
+                .and_then(|id| self.versions.get_version(&id))
+                .map(|parent| &parent.data_location);
+            let mode = if parent_location == Some(&version.data_location) {
+                PublishMode::View
+            } else {
+                PublishMode::Snapshot
+            };
+            // The recreated parent was never reviewed, so the new published
+            // version keeps the approval of the one it replaces
+            let parent_id = self.active_version_id;
+            let published =
+                self.publish_with_approval(&parent_id, mode, version.approval.clone())?;
+            self.active_version_id = published;
+            published
+        } else {
+            self.apply_pipeline(version.pipeline.clone(), version.stage)?
+        };
+        let previous_id = version.id.to_string();
+        self.update_metadata(&new_id, |metadata| {
+            metadata
+                .custom_fields
+                .insert("refreshed_from".to_owned(), previous_id.into());
+        })?;
+        Ok(new_id)
+    }
+
+    /// Add a tag to a version; tags already present (in any case) are kept as is
+    pub fn tag_version(&mut self, version_id: &Uuid, tag: &str) -> Result<DatasetVersion> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            anyhow::bail!("Tag cannot be empty");
+        }
+        self.update_metadata(version_id, |metadata| {
+            if !metadata.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                metadata.tags.push(tag.to_owned());
+            }
+        })
+    }
+
+    /// Remove a tag (matched case-insensitively) from a version
+    pub fn untag_version(&mut self, version_id: &Uuid, tag: &str) -> Result<DatasetVersion> {
+        let tag = tag.trim();
+        self.update_metadata(version_id, |metadata| {
+            metadata.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+        })
+    }
+
+    /// Replace a version's notes
+    pub fn annotate_version(&mut self, version_id: &Uuid, notes: &str) -> Result<DatasetVersion> {
+        self.update_metadata(version_id, |metadata| notes.clone_into(&mut metadata.notes))
+    }
+
+    /// Change a version's metadata and save it, returning the updated version
+    fn update_metadata(
+        &mut self,
+        version_id: &Uuid,
+        update: impl FnOnce(&mut VersionMetadata),
+    ) -> Result<DatasetVersion> {
+        let version = self
+            .versions
+            .get_version_mut(version_id)
+            .ok_or_else(|| anyhow::anyhow!("Version {version_id} not found"))?;
+        update(&mut version.metadata);
+        self.store.save_version_metadata(version)?;
+        Ok(version.clone())
+    }
+
+    /// Space this dataset's stored versions take, with and without sharing
+    pub fn storage_usage(&self) -> StorageUsage {
+        self.store.storage_usage(&self.list_versions())
+    }
+
+    /// Data files used by any version of this dataset
+    pub fn data_paths(&self) -> HashSet<PathBuf> {
+        self.versions
+            .list_all()
+            .into_iter()
+            .map(|v| v.data_location.path().to_path_buf())
+            .collect()
+    }
+
+    /// Delete a version and, unless another version still uses it, its data
+    /// file. Children of the version are attached to its parent. Refuses the
+    /// raw version, the active version, published versions, and versions
+    /// whose data another dataset uses (`external_paths`).
+    pub fn delete_version(
+        &mut self,
+        version_id: &Uuid,
+        external_paths: &HashSet<PathBuf>,
+    ) -> Result<()> {
+        let version = self.get_version(version_id)?;
+        if let Some(reason) = self.deletion_blocker(&version, external_paths) {
+            anyhow::bail!("Cannot delete version {version_id}: {reason}");
+        }
+        self.remove_version(version_id, external_paths)
+    }
+
+    /// Make `version_id` (the active version or one of its ancestors) active
+    /// again and delete the versions derived after it on the way to the
+    /// previous active version. Published versions and versions used by
+    /// other datasets are kept. Returns the ids of the deleted versions.
+    pub fn rollback_to(
+        &mut self,
+        version_id: &Uuid,
+        external_paths: &HashSet<PathBuf>,
+    ) -> Result<Vec<Uuid>> {
+        let lineage: Vec<Uuid> = self
+            .versions
+            .get_lineage(&self.active_version_id)
+            .iter()
+            .map(|v| v.id)
+            .collect();
+        let position = lineage
+            .iter()
+            .position(|id| id == version_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Version {version_id} is not an ancestor of the active version and cannot be rolled back to"
+                )
+            })?;
+
+        self.active_version_id = *version_id;
+
+        let mut deleted = Vec::new();
+        for id in lineage.iter().skip(position + 1).rev() {
+            let version = self.get_version(id)?;
+            if let Some(reason) = self.deletion_blocker(&version, external_paths) {
+                crate::config::log_event(
+                    "Lifecycle",
+                    &format!("Rollback kept version {id}: {reason}"),
+                );
+                continue;
+            }
+            self.remove_version(id, external_paths)?;
+            deleted.push(*id);
+        }
+
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "Rolled back dataset {} to version {version_id}, deleting {} version(s)",
+                self.id,
+                deleted.len()
+            ),
+        );
+        Ok(deleted)
+    }
+
+    /// Why `version` must not be deleted, if there is a reason
+    fn deletion_blocker(
+        &self,
+        version: &DatasetVersion,
+        external_paths: &HashSet<PathBuf>,
+    ) -> Option<&'static str> {
+        if version.id == self.raw_version_id {
+            Some("the raw version cannot be deleted")
+        } else if version.id == self.active_version_id {
+            Some("it is the active version")
+        } else if version.stage == LifecycleStage::Published {
+            Some("it is published")
+        } else if external_paths.contains(version.data_location.path()) {
+            Some("its data is used by another dataset")
+        } else {
+            None
+        }
+    }
+
+    fn remove_version(
+        &mut self,
+        version_id: &Uuid,
+        external_paths: &HashSet<PathBuf>,
+    ) -> Result<()> {
+        let (removed, reparented) = self
+            .versions
+            .remove_version(version_id)
+            .ok_or_else(|| anyhow::anyhow!("Version {version_id} not found"))?;
+
+        for child_id in &reparented {
+            if let Some(child) = self.versions.get_version(child_id) {
+                self.store.save_version_metadata(child)?;
+            }
+        }
+        self.store.delete_version_metadata(&self.id, version_id)?;
+
+        if let DataLocation::ParquetFile(path) = &removed.data_location
+            && !self.uses_data_path(path)
+            && !external_paths.contains(path)
+        {
+            self.store.delete_data_file(path)?;
+        }
+
+        crate::config::log_event(
+            "Lifecycle",
+            &format!("Deleted version {version_id} of dataset {}", self.id),
+        );
+        Ok(())
+    }
+
+    fn uses_data_path(&self, path: &Path) -> bool {
+        self.versions
+            .list_all()
+            .iter()
+            .any(|v| v.data_location.path() == path)
+    }
+
+    /// Move a version through the approval workflow as `actor`. Versions
+    /// reach Published only through [`Self::publish_version_as`].
+    pub fn transition_approval(
+        &mut self,
+        version_id: &Uuid,
+        to: ApprovalState,
+        actor: &str,
+        comment: &str,
+    ) -> Result<DatasetVersion> {
+        if to == ApprovalState::Published {
+            anyhow::bail!("Publish the version to move it to Published");
+        }
+        let version = self
+            .versions
+            .get_version_mut(version_id)
+            .ok_or_else(|| anyhow::anyhow!("Version {version_id} not found"))?;
+        if version.stage == LifecycleStage::Published {
+            anyhow::bail!(
+                "Version {version_id} is published and keeps the approval it was published with"
+            );
+        }
+        let from = version.approval.state;
+        version.approval.transition(to, actor, comment)?;
+        self.store.save_version_metadata(version)?;
+        let version = version.clone();
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "{} moved version {version_id} of '{}' from {} to {}",
+                actor.trim(),
+                self.name,
+                from.as_str(),
+                to.as_str()
+            ),
+        );
+        Ok(version)
+    }
+
+    /// Publish an approved version as the `system` actor; see
+    /// [`Self::publish_version_as`]
     pub fn publish_version(&mut self, version_id: &Uuid, mode: PublishMode) -> Result<Uuid> {
+        self.publish_version_as(version_id, mode, "system")
+    }
+
+    /// Publish an Approved version as `actor`. The version moves to
+    /// Published and the new published version gets its approval record.
+    pub fn publish_version_as(
+        &mut self,
+        version_id: &Uuid,
+        mode: PublishMode,
+        actor: &str,
+    ) -> Result<Uuid> {
+        let mut approval = self.get_version(version_id)?.approval;
+        if approval.state != ApprovalState::Approved {
+            anyhow::bail!(
+                "Version {version_id} is {}; it must be submitted for review and approved before it is published",
+                approval.state.as_str()
+            );
+        }
+        approval.transition(ApprovalState::Published, actor, "")?;
+
+        let published_id = self.publish_with_approval(version_id, mode, approval.clone())?;
+        let version = self
+            .versions
+            .get_version_mut(version_id)
+            .ok_or_else(|| anyhow::anyhow!("Version {version_id} not found"))?;
+        version.approval = approval;
+        self.store.save_version_metadata(version)?;
+        crate::config::log_event(
+            "Lifecycle",
+            &format!(
+                "{} published version {version_id} of '{}' as {published_id}",
+                actor.trim(),
+                self.name
+            ),
+        );
+        Ok(published_id)
+    }
+
+    fn publish_with_approval(
+        &mut self,
+        version_id: &Uuid,
+        mode: PublishMode,
+        approval: Approval,
+    ) -> Result<Uuid> {
         let version = self.get_version(version_id)?;
         let published_id = Uuid::new_v4();
 
@@ -326,7 +867,7 @@ impl Dataset {
             }
         };
 
-        let published_version = DatasetVersion::new_derived(
+        let mut published_version = DatasetVersion::new_derived(
             published_id,
             self.id,
             *version_id,
@@ -334,6 +875,7 @@ impl Dataset {
             version.pipeline.clone(),
             data_location,
         );
+        published_version.approval = approval;
 
         let published_id = published_version.id;
         self.store.save_version_metadata(&published_version)?;
@@ -343,6 +885,14 @@ impl Dataset {
     }
 }
 
+/// Published versions only come from publishing an approved version
+fn refuse_published_stage(stage: LifecycleStage) -> Result<()> {
+    if stage == LifecycleStage::Published {
+        anyhow::bail!("Published versions are created by publishing an approved version");
+    }
+    Ok(())
+}
+
 /// Check if a pipeline would result in a no-op (no data changes requiring file rewrite)
 /// This detects metadata-only operations that don't require materializing and rewriting data
 fn is_pipeline_no_op(pipeline: &TransformPipeline, active_version: &DatasetVersion) -> bool {
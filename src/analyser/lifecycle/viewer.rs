@@ -0,0 +1,348 @@
+//! Standalone HTML viewer for a dataset version.
+//!
+//! [`render_viewer`] writes a single HTML file with the version's schema,
+//! its data dictionary, a health report and the first rows of data, so people
+//! without the app can browse what was delivered. The rows are embedded as
+//! gzip-compressed JSON (base64 encoded), which the page unpacks with the
+//! browser's `DecompressionStream` and shows a page at a time. The file loads
+//! nothing from the network.
+
+use super::version::{Dataset, DatasetVersion};
+use crate::analyser::logic::{analyse_df_lazy, calculate_file_health};
+use crate::dictionary::DataDictionary;
+use crate::dictionary::html::escape;
+use anyhow::{Context as _, Result, bail};
+use base64::Engine as _;
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+/// Most rows a viewer can embed
+pub const VIEWER_MAX_ROWS: usize = 100_000;
+
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;\
+max-width:1200px;margin:2rem auto;padding:0 1rem;color:#1f2933;line-height:1.5}\
+h1{margin-bottom:.25rem}h2{border-bottom:2px solid #e4e7eb;padding-bottom:.25rem;margin-top:2rem}\
+.meta{color:#616e7c;font-size:.9rem}\
+table{border-collapse:collapse;width:100%;margin:.5rem 0 1rem}\
+th,td{border:1px solid #e4e7eb;padding:.35rem .6rem;text-align:left;vertical-align:top;font-size:.9rem}\
+th{background:#f5f7fa}code{background:#f5f7fa;padding:0 .2rem;border-radius:3px}\
+.warn{color:#b44d12}.muted{color:#9aa5b1;font-style:italic}.null{color:#9aa5b1}\
+.scroll{overflow-x:auto}.pager{display:flex;gap:.5rem;align-items:center}\
+.score{font-size:1.5rem;font-weight:600}";
+
+/// Browser code that unpacks the embedded rows and pages through them
+const SCRIPT: &str = r#"(async function () {
+  const source = document.getElementById('viewer-data');
+  const table = document.getElementById('preview');
+  const status = document.getElementById('page-status');
+  const pageSize = Number(source.dataset.pageSize);
+  let data;
+  try {
+    const bytes = Uint8Array.from(atob(source.textContent.trim()), c => c.charCodeAt(0));
+    const stream = new Blob([bytes]).stream().pipeThrough(new DecompressionStream('gzip'));
+    data = JSON.parse(await new Response(stream).text());
+  } catch (e) {
+    status.textContent = 'This browser cannot open the preview (' + e + ').';
+    return;
+  }
+  const pages = Math.max(1, Math.ceil(data.rows.length / pageSize));
+  let page = 0;
+  const cell = (tag, text, cls) => {
+    const el = document.createElement(tag);
+    el.textContent = text;
+    if (cls) el.className = cls;
+    return el;
+  };
+  function render() {
+    table.replaceChildren();
+    const head = table.createTHead().insertRow();
+    data.columns.forEach(name => head.appendChild(cell('th', name)));
+    const body = table.createTBody();
+    data.rows.slice(page * pageSize, (page + 1) * pageSize).forEach(values => {
+      const tr = body.insertRow();
+      values.forEach(v => tr.appendChild(v === null ? cell('td', 'null', 'null') : cell('td', v)));
+    });
+    status.textContent = 'Page ' + (page + 1) + ' of ' + pages;
+    document.getElementById('prev-page').disabled = page === 0;
+    document.getElementById('next-page').disabled = page >= pages - 1;
+  }
+  document.getElementById('prev-page').onclick = () => { page -= 1; render(); };
+  document.getElementById('next-page').onclick = () => { page += 1; render(); };
+  render();
+})();"#;
+
+/// What a viewer bundle includes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerOptions {
+    /// Rows embedded in the preview, at most [`VIEWER_MAX_ROWS`]
+    pub max_rows: usize,
+    /// Rows shown per page of the preview
+    pub page_size: usize,
+}
+
+impl Default for ViewerOptions {
+    fn default() -> Self {
+        Self {
+            max_rows: 1_000,
+            page_size: 50,
+        }
+    }
+}
+
+/// The rows embedded in a viewer, every value as text
+#[derive(Debug, Serialize)]
+struct PreviewData {
+    columns: Vec<String>,
+    rows: Vec<Vec<Option<String>>>,
+}
+
+/// Render `version` of `dataset` as a self-contained HTML viewer. Column
+/// documentation is taken from `dictionary` where its column names match.
+pub fn render_viewer(
+    dataset: &Dataset,
+    version: &DatasetVersion,
+    dictionary: Option<&DataDictionary>,
+    options: &ViewerOptions,
+) -> Result<String> {
+    if options.max_rows > VIEWER_MAX_ROWS {
+        bail!("A viewer can embed at most {VIEWER_MAX_ROWS} rows");
+    }
+    if options.page_size == 0 {
+        bail!("Viewer page size must be at least 1");
+    }
+
+    let mut lf = version.load_data(&dataset.store)?;
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let summaries =
+        analyse_df_lazy(lf.clone(), 0.0, 10_000).context("Failed to profile the version")?;
+    let health = calculate_file_health(&summaries);
+    let total_rows = lf
+        .clone()
+        .select([len()])
+        .collect()
+        .context("Failed to count rows")?
+        .column("len")?
+        .as_materialized_series()
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0);
+    let preview = lf
+        .limit(IdxSize::try_from(options.max_rows).unwrap_or(IdxSize::MAX))
+        .collect()
+        .context("Failed to read preview rows")?;
+    let payload = encode_preview(&preview)?;
+
+    let name = escape(&dataset.name);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{name}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{name}</h1>\n\
+         <p class=\"meta\">Version <code>{}</code> &middot; {} &middot; created {} &middot; \
+         exported {}</p>\n",
+        version.id,
+        version.stage.as_str(),
+        version.created_at.format("%Y-%m-%d %H:%M UTC"),
+        chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
+    );
+    if let Some(approved) = version.approval.approval() {
+        let _ = writeln!(
+            html,
+            "<p class=\"meta\">Approved by {} on {}</p>",
+            escape(&approved.actor),
+            approved.at.format("%Y-%m-%d")
+        );
+    }
+    if !version.metadata.description.is_empty() {
+        let _ = writeln!(html, "<p>{}</p>", escape(&version.metadata.description));
+    }
+
+    // Dataset documentation
+    if let Some(dict) = dictionary {
+        let business = &dict.dataset_metadata.business;
+        let review = business.review_summary();
+        let overview = [
+            ("Description", &business.description),
+            ("Intended Use", &business.intended_use),
+            ("Owner", &business.owner),
+            ("Steward", &business.steward),
+            ("Review", &review),
+            ("Refresh Cadence", &business.refresh_expectation),
+            ("Sensitivity", &business.sensitivity_classification),
+            ("Known Limitations", &business.known_limitations),
+        ];
+        if overview.iter().any(|(_, value)| value.is_some()) {
+            html.push_str("<h2>About This Dataset</h2>\n<table>\n");
+            for (label, value) in overview {
+                if let Some(value) = value {
+                    let _ = writeln!(html, "<tr><th>{label}</th><td>{}</td></tr>", escape(value));
+                }
+            }
+            html.push_str("</table>\n");
+        }
+    }
+
+    // Health
+    let _ = writeln!(
+        html,
+        "<h2>Health</h2>\n<p><span class=\"score\">{:.0}%</span> &middot; {} rows &middot; \
+         {} columns</p>",
+        health.score * 100.0,
+        total_rows,
+        schema.len(),
+    );
+    if health.risks.is_empty() {
+        html.push_str("<p class=\"muted\">No risks found.</p>\n");
+    } else {
+        html.push_str("<ul>\n");
+        for risk in &health.risks {
+            let _ = writeln!(html, "<li class=\"warn\">{}</li>", escape(risk));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    // Schema and column documentation
+    html.push_str(
+        "<h2>Columns</h2>\n<div class=\"scroll\"><table>\n<tr><th>Column</th><th>Type</th>\
+         <th>Nulls</th><th>Distinct</th><th>Definition</th><th>Sensitivity</th></tr>\n",
+    );
+    for (col_name, dtype) in schema.iter() {
+        let summary = summaries.iter().find(|s| s.name == col_name.as_str());
+        let documented = dictionary.and_then(|d| {
+            d.columns
+                .iter()
+                .find(|c| c.current_name == col_name.as_str())
+        });
+        let definition = documented.and_then(|c| c.business.business_definition.as_deref());
+        let sensitivity = documented.and_then(|c| c.business.sensitivity_tag.as_deref());
+        let _ = writeln!(
+            html,
+            "<tr><td><code>{}</code></td><td>{dtype}</td><td>{}</td><td>{}</td><td>{}</td>\
+             <td>{}</td></tr>",
+            escape(col_name),
+            summary.map_or_else(String::new, |s| format!("{:.1}%", s.null_pct())),
+            summary.map_or_else(String::new, |s| s.stats.n_distinct().to_string()),
+            definition.map_or_else(String::new, escape),
+            sensitivity.map_or_else(String::new, escape),
+        );
+    }
+    html.push_str("</table></div>\n");
+
+    // Preview
+    let shown = preview.height();
+    let _ = write!(
+        html,
+        "<h2>Preview</h2>\n<p class=\"meta\">First {shown} of {total_rows} rows</p>\n\
+         <div class=\"pager\"><button id=\"prev-page\" type=\"button\">Previous</button>\
+         <span id=\"page-status\">Loading&hellip;</span>\
+         <button id=\"next-page\" type=\"button\">Next</button></div>\n\
+         <div class=\"scroll\"><table id=\"preview\"></table></div>\n\
+         <script type=\"application/octet-stream\" id=\"viewer-data\" \
+         data-page-size=\"{}\">{payload}</script>\n<script>{SCRIPT}</script>\n</body>\n</html>\n",
+        options.page_size,
+    );
+    Ok(html)
+}
+
+/// `df` as gzip-compressed JSON [`PreviewData`], base64 encoded
+fn encode_preview(df: &DataFrame) -> Result<String> {
+    let mut columns = Vec::with_capacity(df.width());
+    let mut values: Vec<Vec<Option<String>>> = Vec::with_capacity(df.width());
+    for column in df.get_columns() {
+        columns.push(column.name().to_string());
+        values.push(column_text(column.as_materialized_series()));
+    }
+    let rows = (0..df.height())
+        .map(|idx| values.iter().map(|col| col[idx].clone()).collect())
+        .collect();
+    let json = serde_json::to_vec(&PreviewData { columns, rows })?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&json)?;
+    let compressed = encoder
+        .finish()
+        .context("Failed to compress preview rows")?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(compressed))
+}
+
+/// The values of `series` as text, `None` for nulls
+fn column_text(series: &Series) -> Vec<Option<String>> {
+    if let Ok(text) = series.cast(&DataType::String)
+        && let Ok(text) = text.str()
+    {
+        return text.into_iter().map(|v| v.map(str::to_owned)).collect();
+    }
+    // Nested values have no text cast; use their display form
+    (0..series.len())
+        .map(|idx| {
+            series
+                .get(idx)
+                .ok()
+                .filter(|value| !value.is_null())
+                .map(|value| value.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::lifecycle::DatasetRegistry;
+    use flate2::read::GzDecoder;
+    use std::io::Read as _;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_viewer() -> Result<()> {
+        let dir = tempdir()?;
+        let csv = dir.path().join("orders.csv");
+        std::fs::write(&csv, "id,customer,amount\n1,<Ann>,10.5\n2,Bob,\n3,Cy,7\n")?;
+        let registry = DatasetRegistry::new(dir.path().join("registry"))?;
+        let dataset_id = registry.create_dataset("Orders & Returns".to_owned(), csv)?;
+        let dataset = registry.get_dataset(&dataset_id)?;
+        let version = dataset.get_version(&dataset.raw_version_id)?;
+
+        let options = ViewerOptions {
+            max_rows: 2,
+            page_size: 1,
+        };
+        let html = render_viewer(&dataset, &version, None, &options)?;
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Orders &amp; Returns</title>"));
+        assert!(html.contains("First 2 of 3 rows"));
+        assert!(html.contains("data-page-size=\"1\""));
+        assert!(!html.contains("<Ann>"));
+        assert!(!html.contains("http://") && !html.contains("https://"));
+
+        // The embedded rows unpack to the first two rows as text
+        let start = html.find("data-page-size=\"1\">").unwrap() + "data-page-size=\"1\">".len();
+        let end = start + html[start..].find("</script>").unwrap();
+        let compressed = base64::engine::general_purpose::STANDARD.decode(&html[start..end])?;
+        let mut json = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut json)?;
+        let data: serde_json::Value = serde_json::from_str(&json)?;
+        assert_eq!(
+            data["columns"],
+            serde_json::json!(["id", "customer", "amount"])
+        );
+        assert_eq!(
+            data["rows"],
+            serde_json::json!([["1", "<Ann>", "10.5"], ["2", "Bob", null]])
+        );
+
+        let too_many = ViewerOptions {
+            max_rows: VIEWER_MAX_ROWS + 1,
+            ..ViewerOptions::default()
+        };
+        assert!(render_viewer(&dataset, &version, None, &too_many).is_err());
+        Ok(())
+    }
+}
@@ -1,27 +1,131 @@
 pub mod analysis;
+pub mod batches;
+pub mod binning;
 pub mod cleaning;
+pub mod column_match;
+pub mod csv_dialect;
+pub mod date_format;
+pub mod date_parts;
+pub mod deep_dive;
+pub mod encoding;
+pub mod excel;
 pub mod flows;
+pub mod geo;
+pub mod grid;
+pub mod headerless;
 pub mod health;
+pub mod in_place;
+pub mod incremental;
 pub mod interpretation;
 pub mod io;
+pub mod layout;
 pub mod ml;
 pub mod naming;
+pub mod orientation;
+pub mod presets;
 pub mod profiling;
+pub mod rejects;
+pub mod semantic;
+pub mod split_merge;
+pub mod standardise;
+pub mod table_def;
 pub mod types;
+pub mod usage;
 
 pub use analysis::{
-    analyse_df, analyse_df_lazy, calculate_correlation_matrix, run_full_analysis,
-    run_full_analysis_streaming,
+    CLEAN_PREVIEW_MAX_ROWS, CLEAN_PREVIEW_ROWS, analyse_df, analyse_df_lazy,
+    analyse_df_lazy_with_options, calculate_correlation_matrix,
+    calculate_correlation_matrix_with_options, preview_clean, run_full_analysis,
+    run_full_analysis_streaming, run_full_analysis_streaming_with_options,
+};
+pub use batches::{
+    ABORT_BATCH_ROWS, collect_in_batches, for_each_batch, write_csv_in_batches,
+    write_parquet_in_batches,
+};
+pub use binning::{bin_edges, bin_expr, check_bins};
+pub use cleaning::{
+    COMPLETENESS_COLUMN, append_completeness_score, auto_clean_df, clean_df, clean_df_lazy,
+    clean_df_lazy_with_encodings, clean_df_within,
+};
+pub use column_match::{ColumnMatch, ColumnMatchKind, ColumnResolution, resolve_columns};
+pub use csv_dialect::{CsvDialect, LineEnding, TextEncoding, detect_csv_dialect};
+pub use date_format::{
+    DATE_FORMAT_SAMPLE_ROWS, DATE_FORMATS, DateFormat, DateOrder, InferredDateFormat,
+    infer_column_date_format, infer_date_format, parse_dates_expr,
+};
+pub use date_parts::{date_part_column, date_part_exprs};
+pub use deep_dive::{
+    ColumnDeepDive, DecileMean, DeepDiveOptions, DeepDivePart, DistinctValues, ValueRun,
+    column_deep_dive,
+};
+pub use encoding::{ORDINAL_UNKNOWN, check_encoding, encode_column, encoding_expr, learn_encoding};
+pub use excel::{
+    SharedColumn, SheetFailure, SheetOverview, WorkbookAnalysis, WorkbookOverview, is_workbook,
+    list_sheets, read_sheet, read_sheet_with_layout, sheet_path,
+};
+pub use flows::{
+    PushOptions, analyze_file_flow, analyze_file_flow_with_header, analyze_workbook_flow,
+    generate_auto_clean_configs, generate_auto_clean_configs_with_usage, push_to_db_flow,
+    push_to_db_flow_with_options, push_to_db_flow_with_profile,
+};
+pub use geo::profile_geo_columns;
+pub use grid::{
+    GRID_MAX_PAGE_ROWS, GRID_PAGE_ROWS, GridFilter, GridFilterOp, GridPage, GridQuery, GridSort,
+    grid_page,
+};
+pub use headerless::{
+    HeaderlessNaming, header_looks_like_data, load_df_lazy_as, load_df_lazy_headerless,
+    name_headerless_columns, read_schema_names,
 };
-pub use cleaning::{auto_clean_df, clean_df, clean_df_lazy};
-pub use flows::{analyze_file_flow, generate_auto_clean_configs, push_to_db_flow};
 pub use health::calculate_file_health;
-pub use io::{get_parquet_write_options, load_df, load_df_lazy, save_df};
-pub use naming::{sanitize_column_name, sanitize_column_names};
+pub use in_place::{
+    DEFAULT_IN_PLACE_SUFFIX, InPlaceMode, InPlaceReport, clean_in_place, write_in_place,
+};
+pub use incremental::{IncrementalAnalysis, IncrementalUpdate, reanalyse_changed_columns};
+pub use io::{
+    ColumnMapping, ColumnOrigin, DuplicateColumnPolicy, LoadedFrame, get_parquet_write_options,
+    load_df, load_df_lazy, load_df_lazy_with_layout, load_df_lazy_with_mapping, read_csv_header,
+    save_df, scan_parquet_dir,
+};
+pub use layout::{
+    FOOTER_MAX_ROWS, FilledHeader, LayoutOptions, LayoutReport, drop_footer_rows,
+    fill_merged_headers, footer_rows,
+};
+pub use naming::{
+    edit_distance, headers_missing, is_generic_column_name, sanitize_column_name,
+    sanitize_column_names, suggest_column_name, suggest_column_names,
+};
+pub use orientation::{
+    TRANSPOSE_MAX_COLUMNS, TRANSPOSE_NAMES_COLUMN, TransposeDetection, apply_orientation,
+    detect_transposed, transpose_df,
+};
+pub use presets::{
+    AppliedPreset, CleaningPreset, PresetLibrary, PresetMatch, PresetMatchKind, apply_preset,
+};
+pub use rejects::{
+    REJECT_REASON_COLUMN, RejectCondition, cleaning_reject_conditions, split_rejects, union_rejects,
+};
+pub use semantic::{MIN_SEMANTIC_SAMPLES, SEMANTIC_MATCH_THRESHOLD, detect_semantic_type};
+pub use split_merge::{TemplatePart, merge_expr, parse_template, split_exprs, template_columns};
+pub use standardise::{
+    StandardiseOptions, ValueCluster, ValueCount, ValueStandardisation, cluster_values,
+    column_value_counts, fingerprint, map_values, suggest_value_mapping,
+};
+pub use table_def::{TableColumn, TableDefinition, table_name_for_file};
 pub use types::{
-    AnalysisResponse, BooleanStats, ColumnCleanConfig, ColumnKind, ColumnStats, ColumnSummary,
-    CorrelationMatrix, FileHealth, ImputeMode, MlModelKind, NormalisationMethod, NumericStats,
-    TemporalStats, TextCase, TextStats,
+    AnalysisOptions, AnalysisResponse, BinStrategy, BooleanStats, BoundingBox, CategoricalMetrics,
+    CategoryEncoding, CleanPreview, ColumnBinning, ColumnCleanConfig, ColumnCleanPreview,
+    ColumnKind, ColumnMerge, ColumnSplit, ColumnStats, ColumnSummary, CorrelationMatrix,
+    CorrelationOptions, CrossValidationResults, DatePart, EncodingMethod, FeatureImportance,
+    FeatureImportanceReport, FileHealth, GeoEncoding, GeoStats, HistogramOptions,
+    HistogramStrategy, HistogramVariant, ImputeMode, KMeansConfig, MlHyperparameters, MlModelKind,
+    MlTask, NormalisationMethod, NumericStats, RandomForestConfig, RegularisationConfig,
+    SemanticType, TemporalStats, TextCase, TextStats, TextVectorMethod, TextVectoriser,
+    ValidationConfig,
+};
+pub use usage::{
+    ColumnUsage, DatasetUsage, UsageAction, UsageLog, UsageSuggestion, apply_usage_suggestions,
+    usage_key,
 };
 
 #[cfg(test)]
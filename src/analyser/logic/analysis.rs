@@ -1,9 +1,15 @@
+use super::cleaning::clean_df;
+use super::date_format::{InferredDateFormat, infer_column_date_format, parse_dates_expr};
+use super::geo::profile_geo_columns;
 use super::naming;
 use super::profiling;
+use super::semantic::detect_semantic_type;
 use super::types::{
-    AnalysisResponse, BooleanStats, ColumnKind, ColumnStats, ColumnSummary, CorrelationMatrix,
-    NumericStats,
+    AnalysisOptions, AnalysisResponse, BooleanStats, CleanPreview, ColumnCleanConfig,
+    ColumnCleanPreview, ColumnKind, ColumnStats, ColumnSummary, CorrelationMatrix,
+    CorrelationOptions, HistogramOptions, NumericStats, SemanticType,
 };
+use crate::utils::memory::{MemoryGovernor, estimate_frame};
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
 use std::collections::HashMap;
@@ -19,9 +25,35 @@ pub fn run_full_analysis_streaming(
     custom_sample_size: usize,
     start_time: std::time::Instant,
 ) -> Result<AnalysisResponse> {
-    let summary = analyse_df_lazy(lf.clone(), trim_pct, custom_sample_size)?;
+    run_full_analysis_streaming_with_options(
+        lf,
+        path,
+        file_size,
+        total_row_count,
+        sampled_row_count,
+        trim_pct,
+        custom_sample_size,
+        start_time,
+        &AnalysisOptions::default(),
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_full_analysis_streaming_with_options(
+    lf: LazyFrame,
+    path: String,
+    file_size: u64,
+    total_row_count: usize,
+    sampled_row_count: usize,
+    trim_pct: f64,
+    custom_sample_size: usize,
+    start_time: std::time::Instant,
+    options: &AnalysisOptions,
+) -> Result<AnalysisResponse> {
+    let summary = analyse_df_lazy_with_options(lf.clone(), trim_pct, custom_sample_size, options)?;
     let health = super::health::calculate_file_health(&summary);
-    let correlation_matrix = calculate_correlation_matrix_lazy(lf.clone())?;
+    let correlation_matrix =
+        calculate_correlation_matrix_with_options(lf.clone(), &options.correlation)?;
 
     // Collect a small sample for the response (e.g. 100 rows)
     let df = lf.limit(100).collect()?;
@@ -46,6 +78,11 @@ pub fn run_full_analysis_streaming(
         duration: start_time.elapsed(),
         df,
         correlation_matrix,
+        column_mapping: None,
+        orientation: None,
+        layout: None,
+        header_looks_like_data: false,
+        header_names: None,
     })
 }
 
@@ -77,9 +114,23 @@ pub fn analyse_df(df: &DataFrame, trim_pct: f64) -> Result<Vec<ColumnSummary>> {
 }
 
 pub fn analyse_df_lazy(
+    lf: LazyFrame,
+    trim_pct: f64,
+    custom_sample_size: usize,
+) -> Result<Vec<ColumnSummary>> {
+    analyse_df_lazy_with_options(
+        lf,
+        trim_pct,
+        custom_sample_size,
+        &AnalysisOptions::default(),
+    )
+}
+
+pub fn analyse_df_lazy_with_options(
     mut lf: LazyFrame,
     trim_pct: f64,
     custom_sample_size: usize,
+    options: &AnalysisOptions,
 ) -> Result<Vec<ColumnSummary>> {
     let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
     let mut summaries = Vec::new();
@@ -101,11 +152,25 @@ pub fn analyse_df_lazy(
 
     let adaptive_sample_size = profiling::get_adaptive_sample_size(total_rows, custom_sample_size);
 
+    // Over the memory budget, run each column's queries on the streaming
+    // engine and keep samples small enough to fit
+    let governor = MemoryGovernor::new(options.memory_budget.clone());
+    let streaming = !governor.fits(estimate_frame(&schema, total_rows));
+    let adaptive_sample_size = match governor.rows_within(&schema) {
+        Some(fit) if streaming => adaptive_sample_size.min(fit.max(1)),
+        _ => adaptive_sample_size,
+    };
+
+    let date_order = options.date_order;
     for (name, dtype) in schema.iter() {
+        crate::utils::check_aborted("Analysis")?;
         let name_str = name.as_str();
-        let col_lf = lf.clone().select([col(name_str)]);
+        let col_lf = lf.clone().select([col(name_str)]).with_streaming(streaming);
+        let date_format = (dtype == &DataType::String)
+            .then(|| infer_column_date_format(sample_df.column(name_str).ok()?, date_order))
+            .flatten();
 
-        let summary = match dtype {
+        let mut summary = match dtype {
             DataType::Int64
             | DataType::Int32
             | DataType::Float64
@@ -118,6 +183,14 @@ pub fn analyse_df_lazy(
                 total_rows,
                 &sample_df,
                 adaptive_sample_size,
+                &options.histogram,
+            )?,
+            DataType::String if date_format.is_some() => compute_temporal_stats_streaming(
+                col_lf,
+                name_str,
+                total_rows,
+                &sample_df,
+                date_format.as_ref(),
             )?,
             DataType::String => compute_categorical_stats_bounded(
                 col_lf,
@@ -131,16 +204,22 @@ pub fn analyse_df_lazy(
             }
             _ => {
                 if dtype.is_temporal() {
-                    compute_temporal_stats_streaming(col_lf, name_str, total_rows, &sample_df)?
+                    compute_temporal_stats_streaming(
+                        col_lf, name_str, total_rows, &sample_df, None,
+                    )?
                 } else {
                     // Fallback for other types
                     compute_text_stats_streaming(col_lf, name_str, total_rows, &sample_df)?
                 }
             }
         };
+        if dtype == &DataType::String {
+            summary.semantic_type = sample_semantic_type(&sample_df, name_str);
+        }
 
         summaries.push(summary);
     }
+    profile_geo_columns(&lf, &schema, &sample_df, &mut summaries, streaming)?;
 
     let names: Vec<String> = summaries.iter().map(|s| s.name.clone()).collect();
     let sanitized_names = naming::sanitize_column_names(&names);
@@ -149,10 +228,96 @@ pub fn analyse_df_lazy(
             summary.standardised_name = sanitized.clone();
         }
     }
+    naming::suggest_column_names(&mut summaries);
 
     Ok(summaries)
 }
 
+/// Stop an analysis between columns once the user aborts processing
+/// Semantic type of the sampled values of text column `name`
+fn sample_semantic_type(sample_df: &DataFrame, name: &str) -> Option<SemanticType> {
+    let values = sample_df.column(name).ok()?.str().ok()?;
+    detect_semantic_type(values.into_iter().flatten())
+}
+
+/// Rows [`preview_clean`] uses when the caller does not say
+pub const CLEAN_PREVIEW_ROWS: usize = 20;
+
+/// Most rows [`preview_clean`] will clean
+pub const CLEAN_PREVIEW_MAX_ROWS: usize = 1000;
+
+/// Apply `configs` to the first `n_rows` rows (at most
+/// [`CLEAN_PREVIEW_MAX_ROWS`]) and pair each configured column's values
+/// before and after, so regex replacements and casts can be checked before
+/// cleaning a whole file. Imputation, normalisation and outlier clipping use
+/// the sample's statistics, so their values only approximate a full run.
+pub fn preview_clean(
+    lf: LazyFrame,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    n_rows: usize,
+) -> Result<CleanPreview> {
+    let sample = lf
+        .limit(n_rows.clamp(1, CLEAN_PREVIEW_MAX_ROWS) as IdxSize)
+        .collect()
+        .context("Failed to read preview rows")?;
+    let cleaned = clean_df(sample.clone(), configs, false).context("Cleaning preview failed")?;
+
+    let mut columns = Vec::new();
+    for column in sample.get_columns() {
+        let name = column.name().as_str();
+        let Some(config) = configs.get(name) else {
+            continue;
+        };
+        let after_column = config
+            .active
+            .then(|| {
+                if config.new_name.is_empty() {
+                    name
+                } else {
+                    config.new_name.as_str()
+                }
+            })
+            .and_then(|cleaned_name| cleaned.column(cleaned_name).ok());
+
+        let before = display_values(column);
+        let after = after_column.map(display_values).unwrap_or_default();
+        let changed = if after_column.is_some() {
+            before.iter().zip(&after).filter(|(b, a)| b != a).count()
+        } else {
+            0
+        };
+        columns.push(ColumnCleanPreview {
+            name: name.to_owned(),
+            cleaned_name: after_column.map(|c| c.name().to_string()),
+            dtype_before: column.dtype().to_string(),
+            dtype_after: after_column.map(|c| c.dtype().to_string()),
+            before,
+            after,
+            changed,
+        });
+    }
+
+    Ok(CleanPreview {
+        rows: sample.height(),
+        columns,
+    })
+}
+
+/// Every value of `column` as text, keeping nulls
+pub(super) fn display_values(column: &Column) -> Vec<Option<String>> {
+    let series = column.as_materialized_series();
+    match series.cast(&DataType::String) {
+        Ok(text) => text
+            .str()
+            .map(|ca| ca.into_iter().map(|v| v.map(str::to_owned)).collect())
+            .unwrap_or_default(),
+        Err(_) => series
+            .iter()
+            .map(|v| (!v.is_null()).then(|| v.to_string()))
+            .collect(),
+    }
+}
+
 fn extract_samples(sample_df: &DataFrame, name: &str) -> Result<Vec<String>> {
     let series = sample_df.column(name)?.as_materialized_series();
     let mut head = series.drop_nulls().head(Some(10));
@@ -196,8 +361,15 @@ fn compute_numeric_stats_streaming(
     total_rows: usize,
     sample_df: &DataFrame,
     adaptive_sample_size: usize,
+    histogram_options: &HistogramOptions,
 ) -> Result<ColumnSummary> {
-    let (kind, stats) = compute_numeric_stats(lf.clone(), name, trim_pct, adaptive_sample_size)?;
+    let (kind, stats) = compute_numeric_stats_with_options(
+        lf.clone(),
+        name,
+        trim_pct,
+        adaptive_sample_size,
+        histogram_options,
+    )?;
     let samples = extract_samples(sample_df, name)?;
 
     let null_count = lf
@@ -222,6 +394,8 @@ fn compute_numeric_stats_streaming(
         business_summary: Vec::new(),
         ml_advice: Vec::new(),
         samples,
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.interpretation = summary.generate_interpretation();
     summary.business_summary = summary.generate_business_summary();
@@ -282,6 +456,8 @@ fn compute_categorical_stats_bounded(
         business_summary: Vec::new(),
         ml_advice: Vec::new(),
         samples,
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.interpretation = summary.generate_interpretation();
     summary.business_summary = summary.generate_business_summary();
@@ -320,6 +496,8 @@ fn compute_boolean_stats_streaming(
         business_summary: Vec::new(),
         ml_advice: Vec::new(),
         samples,
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.interpretation = summary.generate_interpretation();
     summary.business_summary = summary.generate_business_summary();
@@ -327,17 +505,33 @@ fn compute_boolean_stats_streaming(
     Ok(summary)
 }
 
+/// Temporal stats of column `name`, parsing it as `format` when it is text
 fn compute_temporal_stats_streaming(
     lf: LazyFrame,
     name: &str,
     total_rows: usize,
     sample_df: &DataFrame,
+    format: Option<&InferredDateFormat>,
 ) -> Result<ColumnSummary> {
-    let (kind, stats) = profiling::analyse_temporal(sample_df.column(name)?)?;
+    let values = match format {
+        Some(f) => parse_dates_expr(col(name), &f.format).alias(name),
+        None => col(name),
+    };
+    let parsed_sample = sample_df
+        .clone()
+        .lazy()
+        .select([values.clone()])
+        .collect()?;
+    let (kind, mut stats) = profiling::analyse_temporal(parsed_sample.column(name)?)?;
+    if let (ColumnStats::Temporal(s), Some(f)) = (&mut stats, format) {
+        s.format = Some(f.format.clone());
+        s.format_ambiguous = f.ambiguous;
+    }
     let samples = extract_samples(sample_df, name)?;
 
+    // Text the format does not read counts as missing
     let null_count = lf
-        .select([col(name).null_count()])
+        .select([values.null_count()])
         .collect()?
         .column(name)?
         .as_materialized_series()
@@ -358,6 +552,8 @@ fn compute_temporal_stats_streaming(
         business_summary: Vec::new(),
         ml_advice: Vec::new(),
         samples,
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.interpretation = summary.generate_interpretation();
     summary.business_summary = summary.generate_business_summary();
@@ -396,6 +592,8 @@ fn compute_text_stats_streaming(
         business_summary: Vec::new(),
         ml_advice: Vec::new(),
         samples,
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.interpretation = summary.generate_interpretation();
     summary.business_summary = summary.generate_business_summary();
@@ -408,6 +606,22 @@ pub fn compute_numeric_stats(
     name: &str,
     trim_pct: f64,
     adaptive_sample_size: usize,
+) -> Result<(ColumnKind, ColumnStats)> {
+    compute_numeric_stats_with_options(
+        lf,
+        name,
+        trim_pct,
+        adaptive_sample_size,
+        &HistogramOptions::default(),
+    )
+}
+
+pub fn compute_numeric_stats_with_options(
+    lf: LazyFrame,
+    name: &str,
+    trim_pct: f64,
+    adaptive_sample_size: usize,
+    histogram_options: &HistogramOptions,
 ) -> Result<(ColumnKind, ColumnStats)> {
     let stats_df = lf
         .clone()
@@ -431,8 +645,8 @@ pub fn compute_numeric_stats(
                 .alias("p95"),
             col(name).n_unique().alias("distinct_count"),
             col(name).null_count().alias("null_count"),
-            col(name).sum().alias("sum"),
             col(name).eq(lit(0)).sum().alias("zero_count"),
+            col(name).eq(lit(1)).sum().alias("one_count"),
             col(name).lt(lit(0)).sum().alias("negative_count"),
             col(name)
                 .floor()
@@ -494,24 +708,23 @@ pub fn compute_numeric_stats(
     let p95 = get_f64("p95");
     let distinct_count = get_usize("distinct_count");
     let zero_count = get_usize("zero_count");
+    let one_count = get_usize("one_count");
     let negative_count = get_usize("negative_count");
     let is_integer = get_bool("is_integer");
     let null_count = get_usize("null_count");
     let count = get_usize("count");
-    let sum = get_f64("sum").unwrap_or(0.0);
-
     if distinct_count > 0
         && distinct_count <= 3
         && min.unwrap_or(0.0) >= 0.0
         && max.unwrap_or(0.0) <= 1.0
+        && zero_count + one_count + null_count == count
     {
-        let true_count = sum as usize;
-        let false_count = count.saturating_sub(null_count).saturating_sub(true_count);
         return Ok((
             ColumnKind::Boolean,
             ColumnStats::Boolean(BooleanStats {
-                true_count,
-                false_count,
+                true_count: one_count,
+                false_count: zero_count,
+                null_count,
             }),
         ));
     }
@@ -545,7 +758,30 @@ pub fn compute_numeric_stats(
         null_count,
         custom_sample_size: adaptive_sample_size,
     };
-    let (bin_width, histogram) = profiling::build_histogram_streaming(lf, name, histogram_config)?;
+    let (histogram_strategy, bin_width, histogram) =
+        profiling::build_histogram_streaming_with_options(
+            lf,
+            name,
+            histogram_config,
+            histogram_options,
+        )?;
+    // Quantile/ECDF tables and histogram variants come from the same leading
+    // sample the main histogram uses
+    let (quantiles, ecdf) = profiling::calculate_distribution_tables(sample_ca);
+    let (log_histogram, zoomed_histogram) = profiling::calculate_histogram_variants(
+        sample_ca,
+        profiling::VariantInputs {
+            min,
+            max,
+            p05,
+            p95,
+            q1,
+            q3,
+            skew,
+            strategy: histogram_strategy,
+        },
+        histogram_options,
+    );
 
     Ok((
         ColumnKind::Numeric,
@@ -569,6 +805,11 @@ pub fn compute_numeric_stats(
             is_sorted_rev,
             bin_width,
             histogram,
+            histogram_strategy,
+            log_histogram,
+            zoomed_histogram,
+            quantiles,
+            ecdf,
         }),
     ))
 }
@@ -672,40 +913,73 @@ pub fn compute_boolean_stats(lf: LazyFrame, name: &str) -> Result<(ColumnKind, C
         ColumnStats::Boolean(BooleanStats {
             true_count,
             false_count,
+            null_count,
         }),
     ))
 }
 
+const CORRELATION_SAMPLE_SIZE: usize = 10_000;
+
 pub fn calculate_correlation_matrix(df: &DataFrame) -> Result<Option<CorrelationMatrix>> {
     calculate_correlation_matrix_lazy(df.clone().lazy())
 }
 
+pub fn calculate_correlation_matrix_lazy(lf: LazyFrame) -> Result<Option<CorrelationMatrix>> {
+    calculate_correlation_matrix_with_options(lf, &CorrelationOptions::default())
+}
+
 #[expect(clippy::needless_range_loop, clippy::indexing_slicing)]
-pub fn calculate_correlation_matrix_lazy(mut lf: LazyFrame) -> Result<Option<CorrelationMatrix>> {
+pub fn calculate_correlation_matrix_with_options(
+    mut lf: LazyFrame,
+    options: &CorrelationOptions,
+) -> Result<Option<CorrelationMatrix>> {
     let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
-    let mut numeric_cols: Vec<String> = schema
+    let all_numeric: Vec<String> = schema
         .iter()
         .filter(|(_, dtype)| dtype.is_numeric())
         .map(|(name, _)| name.to_string())
         .collect();
 
-    if numeric_cols.len() < 2 {
+    // An explicit selection overrides automatic column picking, but only
+    // numeric columns that actually exist can be correlated.
+    let candidates: Vec<String> = if options.columns.is_empty() {
+        all_numeric
+    } else {
+        options
+            .columns
+            .iter()
+            .filter(|c| all_numeric.contains(c))
+            .cloned()
+            .collect()
+    };
+
+    if candidates.len() < 2 {
         return Ok(None);
     }
 
-    // Limit columns for correlation to avoid OOM and massive matrices
-    // Use adaptive limits: fewer columns for wide datasets to control memory
-    let max_cols = if numeric_cols.len() > 100 {
-        15 // Very wide: limit to 15 cols
-    } else if numeric_cols.len() > 50 {
-        20 // Wide: limit to 20 cols
+    // Limit columns for correlation to avoid OOM and massive matrices.
+    // Explicit selections are only capped when the user set a limit.
+    let max_cols = if options.columns.is_empty() || options.max_columns.is_some() {
+        options.effective_max_columns(candidates.len())
     } else {
-        30 // Normal: limit to 30 cols
+        candidates.len()
     };
 
-    if numeric_cols.len() > max_cols {
-        numeric_cols.truncate(max_cols);
-    }
+    let (numeric_cols, omitted_columns) = if candidates.len() > max_cols {
+        let selected = if options.select_by_variance {
+            select_top_variance_columns(lf.clone(), &candidates, max_cols)?
+        } else {
+            candidates.iter().take(max_cols).cloned().collect()
+        };
+        let omitted = candidates
+            .iter()
+            .filter(|c| !selected.contains(c))
+            .cloned()
+            .collect();
+        (selected, omitted)
+    } else {
+        (candidates, Vec::new())
+    };
 
     // Use a much smaller sample for correlation to save memory
     // Correlation calculations are memory-intensive (N^2 pairwise operations)
@@ -721,8 +995,8 @@ pub fn calculate_correlation_matrix_lazy(mut lf: LazyFrame) -> Result<Option<Cor
         .get(0)
         .unwrap_or(0) as usize;
 
-    let sample_size = if total_rows > 10_000 {
-        10_000 // Reduced from 100k to 10k (10x memory reduction)
+    let sample_size = if total_rows > CORRELATION_SAMPLE_SIZE {
+        CORRELATION_SAMPLE_SIZE // Reduced from 100k to 10k (10x memory reduction)
     } else {
         total_rows
     };
@@ -764,5 +1038,52 @@ pub fn calculate_correlation_matrix_lazy(mut lf: LazyFrame) -> Result<Option<Cor
     Ok(Some(CorrelationMatrix {
         columns: numeric_cols,
         data: matrix,
+        omitted_columns,
     }))
 }
+
+/// Pick the `limit` columns with the highest sample variance, preserving
+/// their original schema order.
+fn select_top_variance_columns(
+    lf: LazyFrame,
+    columns: &[String],
+    limit: usize,
+) -> Result<Vec<String>> {
+    let exprs: Vec<Expr> = columns
+        .iter()
+        .map(|c| {
+            col(c.as_str())
+                .cast(DataType::Float64)
+                .var(1)
+                .alias(c.as_str())
+        })
+        .collect();
+    let variances = lf
+        .limit(CORRELATION_SAMPLE_SIZE as u32)
+        .select(exprs)
+        .with_streaming(true)
+        .collect()
+        .context("Failed to compute column variances for correlation")?;
+
+    let mut ranked: Vec<(usize, f64)> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let var = variances
+                .column(c)
+                .ok()
+                .and_then(|s| s.as_materialized_series().f64().ok()?.get(0))
+                .filter(|v| v.is_finite())
+                .unwrap_or(0.0);
+            (i, var)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(limit);
+    ranked.sort_by_key(|(i, _)| *i);
+
+    Ok(ranked
+        .into_iter()
+        .filter_map(|(i, _)| columns.get(i).cloned())
+        .collect())
+}
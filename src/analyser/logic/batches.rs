@@ -0,0 +1,150 @@
+//! Collecting and writing data in batches that stop when the user aborts.
+//!
+//! Polars cannot interrupt a query once it runs, so a file collected or sunk
+//! in one go keeps going after the user presses Abort. [`for_each_batch`]
+//! instead runs the query a slice of rows at a time and checks the abort
+//! signal (see [`crate::utils::is_aborted`]) between slices, so a job stops
+//! within one batch. Each slice is its own query: the rows before it are
+//! skipped rather than kept, and statistics over whole columns (such as a
+//! median used for imputation) are computed again for each slice.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use std::path::Path;
+
+/// Rows collected between checks of the abort signal
+pub const ABORT_BATCH_ROWS: usize = 250_000;
+
+/// Collect `lf` `batch_rows` rows at a time, passing each batch to
+/// `on_batch`, and return the number of rows. The first batch is passed even
+/// when empty. Fails between batches once the user aborts.
+pub fn for_each_batch(
+    lf: LazyFrame,
+    batch_rows: usize,
+    mut on_batch: impl FnMut(DataFrame) -> Result<()>,
+) -> Result<usize> {
+    let batch_rows = batch_rows.max(1);
+    let len = IdxSize::try_from(batch_rows).unwrap_or(IdxSize::MAX);
+    let mut rows = 0;
+    loop {
+        crate::utils::check_aborted("Operation")?;
+        let offset = i64::try_from(rows).context("Too many rows")?;
+        let batch = lf
+            .clone()
+            .slice(offset, len)
+            .collect()
+            .with_context(|| format!("Failed to collect rows from {rows}"))?;
+        let height = batch.height();
+        if height > 0 || rows == 0 {
+            on_batch(batch)?;
+        }
+        rows += height;
+        if height < batch_rows {
+            return Ok(rows);
+        }
+    }
+}
+
+/// Collect `lf` in batches of [`ABORT_BATCH_ROWS`], stopping if the user
+/// aborts
+pub fn collect_in_batches(lf: LazyFrame) -> Result<DataFrame> {
+    let mut collected: Option<DataFrame> = None;
+    for_each_batch(lf, ABORT_BATCH_ROWS, |batch| {
+        match &mut collected {
+            Some(df) => {
+                df.vstack_mut(&batch)?;
+            }
+            None => collected = Some(batch),
+        }
+        Ok(())
+    })?;
+    let mut df = collected.context("No rows collected")?;
+    df.as_single_chunk_par();
+    Ok(df)
+}
+
+/// Write `lf` to a CSV file with a header row in batches, stopping if the
+/// user aborts. Returns the number of rows written. The header is written
+/// with the first batch, which is passed even when empty.
+pub fn write_csv_in_batches(lf: LazyFrame, path: &Path) -> Result<usize> {
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = CsvWriter::new(file)
+        .include_header(true)
+        .batched(&schema)
+        .context("Failed to start CSV file")?;
+    let rows = for_each_batch(lf, ABORT_BATCH_ROWS, |batch| {
+        writer
+            .write_batch(&batch)
+            .context("Failed to write CSV rows")
+    })?;
+    Ok(rows)
+}
+
+/// Write `lf` to a Parquet file with `options` in batches, stopping if the
+/// user aborts. Returns the number of rows written.
+pub fn write_parquet_in_batches(
+    lf: LazyFrame,
+    path: &Path,
+    options: &ParquetWriteOptions,
+) -> Result<usize> {
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = ParquetWriter::new(file)
+        .with_compression(options.compression)
+        .with_statistics(options.statistics)
+        .with_row_group_size(options.row_group_size)
+        .with_data_page_size(options.data_page_size)
+        .batched(&schema)
+        .context("Failed to start Parquet file")?;
+    let rows = for_each_batch(lf, ABORT_BATCH_ROWS, |batch| {
+        writer
+            .write_batch(&batch)
+            .context("Failed to write Parquet rows")
+    })?;
+    writer.finish().context("Failed to finish Parquet file")?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_each_batch_covers_every_row() {
+        let df = df!("n" => (0..10i64).collect::<Vec<_>>()).unwrap();
+
+        let mut heights = Vec::new();
+        let rows = for_each_batch(df.clone().lazy(), 4, |batch| {
+            heights.push(batch.height());
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(rows, 10);
+        assert_eq!(heights, [4, 4, 2]);
+
+        // An empty frame still passes one batch, for its schema
+        let mut batches = 0;
+        let rows = for_each_batch(df.clone().lazy().limit(0), 4, |batch| {
+            assert_eq!(batch.width(), 1);
+            batches += 1;
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!((rows, batches), (0, 1));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.csv");
+        assert_eq!(write_csv_in_batches(df.lazy(), &path).unwrap(), 10);
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(written.lines().count(), 11);
+    }
+}
@@ -0,0 +1,210 @@
+//! Binning (discretisation) of numeric columns.
+//!
+//! A [`BinStrategy`] places the edges of the bins and each value is replaced
+//! by the label of the bin it falls in, e.g. `18 to 30`. Bins include their
+//! lower edge, and the last one its upper edge too. Nulls and values outside
+//! the edges become null.
+
+use anyhow::{Context as _, Result, bail};
+use polars::prelude::*;
+
+use super::types::BinStrategy;
+
+/// Check that `strategy` makes at least one bin and that `labels`, if any,
+/// name each bin
+pub fn check_bins(strategy: &BinStrategy, labels: &[String]) -> Result<()> {
+    if let BinStrategy::Custom { edges } = strategy {
+        if edges.len() < 2 {
+            bail!("Custom bins need at least two edges");
+        }
+        if edges.iter().any(|e| !e.is_finite()) || edges.windows(2).any(|w| w[0] >= w[1]) {
+            bail!("Bin edges must be numbers in increasing order");
+        }
+    }
+    let bins = strategy.bins();
+    if bins == 0 {
+        bail!("At least one bin is needed");
+    }
+    if !labels.is_empty() && labels.len() != bins {
+        bail!("{} labels given for {bins} bins", labels.len());
+    }
+    Ok(())
+}
+
+/// Edges of the bins `strategy` makes of `expr`, a numeric expression over
+/// `lf`. Equal-width and quantile edges are read from the data: when all
+/// values are equal every edge is that value, and with no values there are
+/// no edges.
+pub fn bin_edges(lf: &LazyFrame, expr: Expr, strategy: &BinStrategy) -> Result<Vec<f64>> {
+    let value = expr.cast(DataType::Float64);
+    let edges = match strategy {
+        BinStrategy::Custom { edges } => edges.clone(),
+        BinStrategy::EqualWidth { bins } => {
+            let stats = aggregates(lf, vec![value.clone().min(), value.max()])?;
+            let (Some(min), Some(max)) = (stats[0], stats[1]) else {
+                return Ok(Vec::new());
+            };
+            let width = (max - min) / *bins as f64;
+            let mut edges: Vec<f64> = (0..*bins).map(|i| min + width * i as f64).collect();
+            edges.push(max);
+            edges
+        }
+        BinStrategy::Quantile { bins } => {
+            let quantiles: Vec<Expr> = (0..=*bins)
+                .map(|i| {
+                    value
+                        .clone()
+                        .quantile(lit(i as f64 / *bins as f64), QuantileMethod::Linear)
+                })
+                .collect();
+            let edges: Option<Vec<f64>> = aggregates(lf, quantiles)?.into_iter().collect();
+            edges.unwrap_or_default()
+        }
+    };
+    Ok(edges)
+}
+
+/// `expr`, a numeric column named `column`, replaced by the label of its bin
+pub fn bin_expr(
+    lf: &LazyFrame,
+    expr: Expr,
+    column: &str,
+    strategy: &BinStrategy,
+    labels: &[String],
+) -> Result<Expr> {
+    check_bins(strategy, labels)?;
+    let schema = lf
+        .clone()
+        .select([expr.clone()])
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let dtype = schema
+        .get_at_index(0)
+        .map(|(_, dtype)| dtype.clone())
+        .context("Expression produced no column")?;
+    if !dtype.is_numeric() {
+        bail!("Column '{column}' is {dtype}, not a number; cast it to Numeric first");
+    }
+
+    let edges = bin_edges(lf, expr.clone(), strategy)
+        .with_context(|| format!("Failed to find bins for '{column}'"))?;
+    let mut binned = lit(NULL).cast(DataType::String);
+    if edges.is_empty() {
+        return Ok(binned);
+    }
+    let labels: Vec<String> = if labels.is_empty() {
+        edges
+            .windows(2)
+            .map(|w| format!("{} to {}", edge_label(w[0]), edge_label(w[1])))
+            .collect()
+    } else {
+        labels.to_vec()
+    };
+
+    // Built from the last bin down so that earlier bins take precedence
+    let value = expr.cast(DataType::Float64);
+    let last = labels.len() - 1;
+    for (i, label) in labels.iter().enumerate().rev() {
+        let below_upper = if i == last {
+            value.clone().lt_eq(lit(edges[i + 1]))
+        } else {
+            value.clone().lt(lit(edges[i + 1]))
+        };
+        binned = when(value.clone().gt_eq(lit(edges[i])).and(below_upper))
+            .then(lit(label.as_str()))
+            .otherwise(binned);
+    }
+    Ok(binned)
+}
+
+/// Single-row aggregates of `lf` as floats
+fn aggregates(lf: &LazyFrame, exprs: Vec<Expr>) -> Result<Vec<Option<f64>>> {
+    let exprs: Vec<Expr> = exprs
+        .into_iter()
+        .enumerate()
+        .map(|(i, e)| e.cast(DataType::Float64).alias(format!("agg_{i}")))
+        .collect();
+    let df = lf.clone().select(exprs).collect()?;
+    df.get_columns()
+        .iter()
+        .map(|c| Ok(c.f64()?.get(0)))
+        .collect()
+}
+
+/// An edge for a bin label, with at most two decimals
+fn edge_label(edge: f64) -> String {
+    let text = format!("{edge:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binned(df: &DataFrame, strategy: &BinStrategy, labels: &[String]) -> Vec<Option<String>> {
+        let lf = df.clone().lazy();
+        let expr = bin_expr(&lf, col("age"), "age", strategy, labels).unwrap();
+        lf.select([expr.alias("band")])
+            .collect()
+            .unwrap()
+            .column("band")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.map(str::to_owned))
+            .collect()
+    }
+
+    #[test]
+    fn test_bin_expr() {
+        let df = df!("age" => [Some(10), Some(20), Some(30), Some(40), None]).unwrap();
+        let band = |s: &str| Some(s.to_owned());
+
+        let equal = binned(&df, &BinStrategy::EqualWidth { bins: 3 }, &[]);
+        assert_eq!(
+            equal,
+            [
+                band("10 to 20"),
+                band("20 to 30"),
+                band("30 to 40"),
+                band("30 to 40"),
+                None
+            ]
+        );
+
+        let quantile = BinStrategy::Quantile { bins: 2 };
+        assert_eq!(
+            bin_edges(&df.clone().lazy(), col("age"), &quantile).unwrap(),
+            [10.0, 25.0, 40.0]
+        );
+
+        let custom = BinStrategy::Custom {
+            edges: vec![0.0, 18.0, 35.0],
+        };
+        let labels = ["minor".to_owned(), "young adult".to_owned()];
+        assert_eq!(
+            binned(&df, &custom, &labels),
+            [
+                band("minor"),
+                band("young adult"),
+                band("young adult"),
+                None,
+                None
+            ]
+        );
+
+        let unsorted = BinStrategy::Custom {
+            edges: vec![10.0, 5.0],
+        };
+        assert!(check_bins(&unsorted, &[]).is_err());
+        assert!(check_bins(&custom, &labels[..1]).is_err());
+        assert!(check_bins(&BinStrategy::EqualWidth { bins: 0 }, &[]).is_err());
+
+        let empty = df!("age" => [None::<i32>]).unwrap();
+        assert_eq!(binned(&empty, &quantile, &[]), [None]);
+
+        let text = df!("age" => ["ten"]).unwrap().lazy();
+        assert!(bin_expr(&text, col("age"), "age", &custom, &[]).is_err());
+    }
+}
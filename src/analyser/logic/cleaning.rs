@@ -1,7 +1,17 @@
-use super::types::{ColumnCleanConfig, ColumnKind, ImputeMode, NormalisationMethod, TextCase};
+use super::batches::collect_in_batches;
+use super::binning::bin_expr;
+use super::date_format::parse_dates_expr;
+use super::date_parts::date_part_exprs;
+use super::encoding::encode_column;
+use super::split_merge::{merge_expr, split_exprs, template_columns};
+use super::standardise::map_values;
+use super::types::{
+    CategoryEncoding, ColumnCleanConfig, ColumnKind, ImputeMode, NormalisationMethod, TextCase,
+};
+use crate::utils::memory::{MemoryGovernor, estimate_df};
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub struct StatsValues {
@@ -13,15 +23,27 @@ pub struct StatsValues {
     pub max: Option<f64>,
 }
 
+/// [`clean_df_within`] with the default memory budget
 pub fn clean_df(
     df: DataFrame,
     configs: &HashMap<String, ColumnCleanConfig>,
     restricted: bool,
 ) -> Result<DataFrame> {
+    clean_df_within(df, configs, restricted, &MemoryGovernor::default())
+}
+
+pub fn clean_df_within(
+    df: DataFrame,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    restricted: bool,
+    governor: &MemoryGovernor,
+) -> Result<DataFrame> {
+    // The cleaned copy is about as large again; over the budget, build it
+    // on the streaming engine
+    let streaming = !governor.fits(estimate_df(&df).saturating_mul(2));
     let lf = df.lazy();
     let cleaned_lf = clean_df_lazy(lf, configs, restricted)?;
-    cleaned_lf
-        .collect()
+    collect_in_batches(cleaned_lf.with_streaming(streaming))
         .context("Failed to collect cleaned dataframe")
 }
 
@@ -30,75 +52,220 @@ pub fn clean_df_lazy(
     configs: &HashMap<String, ColumnCleanConfig>,
     restricted: bool,
 ) -> Result<LazyFrame> {
+    clean_df_lazy_with_encodings(lf, configs, restricted).map(|(lf, _)| lf)
+}
+
+/// [`clean_df_lazy`], also returning the category encodings used, with the
+/// mappings learned from this data, by column. Storing them in the configs
+/// encodes new data with the same numbers.
+pub fn clean_df_lazy_with_encodings(
+    lf: LazyFrame,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    restricted: bool,
+) -> Result<(LazyFrame, HashMap<String, CategoryEncoding>)> {
     let mut lf = lf;
     let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
     let mut expressions = Vec::new();
     let mut one_hot_cols = Vec::new();
+    // (column, name after cleaning, encoding)
+    let mut encoded_cols = Vec::new();
 
+    // Cleaned values by original name, before renaming; splits and merges
+    // read these
+    let mut cleaned: HashMap<&str, Expr> = HashMap::new();
     for (name, dtype) in schema.iter() {
+        if let Some(config) = configs.get(name.as_str())
+            && config.active
+        {
+            cleaned.insert(
+                name.as_str(),
+                clean_column_expr(name.as_str(), dtype, config, restricted),
+            );
+        }
+    }
+    let resolve = |name: &str| {
+        cleaned
+            .get(name)
+            .cloned()
+            .or_else(|| schema.contains(name).then(|| col(name)))
+    };
+    // Columns dropped because a merge replaces them
+    let merge_sources: HashSet<String> = configs
+        .values()
+        .filter(|c| c.active)
+        .filter_map(|c| c.merge.as_ref())
+        .filter(|m| m.drop_sources)
+        .map(|m| template_columns(&m.template))
+        .collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+
+    for (name, _) in schema.iter() {
         if let Some(config) = configs.get(name.as_str()) {
             if !config.active {
                 continue;
             }
 
-            let mut expr = col(name.as_str());
-
-            // 1. Text cleaning & Regex
-            expr = apply_text_cleaning(expr, config, dtype, restricted);
-
-            // 2. Extract numbers if requested (produces Float64)
-            if config.extract_numbers {
-                expr = expr
-                    .str()
-                    .extract(lit(r"(\d+\.?\d*)"), 1)
-                    .cast(DataType::Float64);
-            }
-
-            // 3. Casting to target type
-            expr = apply_dtype_casting(expr, config);
-
-            // 4. Imputation
-            expr = apply_imputation_with_stats(expr, config, None);
+            let expr = cleaned
+                .get(name.as_str())
+                .cloned()
+                .unwrap_or_else(|| col(name.as_str()));
+            let keep = !config.split.as_ref().is_some_and(|s| s.drop_original)
+                && !merge_sources.contains(name.as_str());
 
-            // 5. Numeric Refinement (Clips, Rounding - NO extract_numbers here anymore)
-            if !restricted {
-                expr = apply_numeric_refinement(expr, config);
+            // Rename if needed (column name standardization is a basic operation)
+            let out_name = if config.new_name.is_empty() {
+                name.as_str()
+            } else {
+                config.new_name.as_str()
+            };
+            if keep {
+                let value = match &config.binning {
+                    Some(binning) => {
+                        bin_expr(&lf, expr.clone(), name, &binning.strategy, &binning.labels)
+                            .with_context(|| format!("Failed to bin column '{name}'"))?
+                    }
+                    None => expr.clone(),
+                };
+                expressions.push(value.alias(out_name));
+
+                // Categorical Refinement (encodings are handled separately)
+                if config.ml_preprocessing
+                    && let Some(encoding) = &config.encoding
+                {
+                    encoded_cols.push((name.as_str(), out_name.to_owned(), encoding));
+                } else if config.ml_preprocessing && config.one_hot_encode {
+                    one_hot_cols.push(out_name.to_owned());
+                }
             }
 
-            // 6. Normalization
-            if !restricted {
-                expr = apply_normalisation_with_stats(expr, config, None);
+            if !config.date_parts.is_empty() {
+                let dtype = expr_dtype(&lf, &expr)?;
+                expressions.extend(
+                    date_part_exprs(expr.clone(), &dtype, out_name, &config.date_parts)
+                        .with_context(|| format!("Failed to extract date parts of '{name}'"))?,
+                );
             }
-
-            // 6. Rename if needed (column name standardization is a basic operation)
-            if !config.new_name.is_empty() && config.new_name != *name {
-                expr = expr.alias(&config.new_name);
-            } else {
-                expr = expr.alias(name.as_str());
+            if let Some(split) = &config.split {
+                expressions.extend(
+                    split_exprs(expr, split)
+                        .with_context(|| format!("Failed to split column '{name}'"))?,
+                );
             }
-
-            // 7. Categorical Refinement (One-hot encoding is handled separately)
-            if config.ml_preprocessing && config.one_hot_encode {
-                one_hot_cols.push(if config.new_name.is_empty() {
-                    name.to_string()
-                } else {
-                    config.new_name.clone()
-                });
+            if let Some(merge) = &config.merge {
+                expressions.push(
+                    merge_expr(merge, &resolve)
+                        .with_context(|| format!("Failed to merge into '{}'", merge.into))?,
+                );
             }
-
-            expressions.push(expr);
-        } else {
+        } else if !merge_sources.contains(name.as_str()) {
             expressions.push(col(name.as_str()));
         }
     }
 
     lf = lf.select(expressions);
 
+    // Before one-hot encoding, which may replace a target column
+    let mut encodings = HashMap::new();
+    for (name, out_name, encoding) in encoded_cols {
+        let (encoded, used) = encode_column(lf, &out_name, encoding)
+            .with_context(|| format!("Failed to encode column '{name}'"))?;
+        lf = encoded;
+        encodings.insert(name.to_owned(), used);
+    }
+
     if !one_hot_cols.is_empty() {
         lf = apply_one_hot_encoding_lazy(lf, one_hot_cols)?;
     }
 
-    Ok(lf)
+    Ok((lf, encodings))
+}
+
+/// The type `expr` evaluates to over `lf`
+fn expr_dtype(lf: &LazyFrame, expr: &Expr) -> Result<DataType> {
+    let schema = lf
+        .clone()
+        .select([expr.clone()])
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    schema
+        .get_at_index(0)
+        .map(|(_, dtype)| dtype.clone())
+        .context("Expression produced no column")
+}
+
+/// The cleaning steps of `config` applied to column `name`, without renaming
+fn clean_column_expr(
+    name: &str,
+    dtype: &DataType,
+    config: &ColumnCleanConfig,
+    restricted: bool,
+) -> Expr {
+    let mut expr = col(name);
+
+    // 1. Text cleaning & Regex
+    expr = apply_text_cleaning(expr, config, dtype, restricted);
+
+    // 2. Extract numbers if requested (produces Float64)
+    expr = apply_number_extraction(expr, config);
+
+    // 3. Casting to target type
+    expr = apply_dtype_casting_with_dtype(expr, config, dtype);
+
+    // 4. Imputation
+    expr = apply_imputation_with_stats(expr, config, None);
+
+    // 5. Numeric Refinement (Clips, Rounding - NO extract_numbers here anymore)
+    if !restricted {
+        expr = apply_numeric_refinement(expr, config);
+    }
+
+    // 6. Normalization
+    if !restricted {
+        expr = apply_normalisation_with_stats(expr, config, None);
+    }
+
+    expr
+}
+
+/// Default name of the column added by [`append_completeness_score`]
+pub const COMPLETENESS_COLUMN: &str = "row_completeness";
+
+/// Append a per-row completeness score: the fraction of non-null fields,
+/// each field weighted by `weights` (columns not listed count as 1.0, a weight
+/// of 0 excludes a column). Scores range from 0.0 to 1.0.
+pub fn append_completeness_score(
+    lf: LazyFrame,
+    name: &str,
+    weights: &HashMap<String, f64>,
+) -> Result<LazyFrame> {
+    let mut lf = lf;
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+
+    let mut numerator = lit(0.0);
+    let mut total_weight = 0.0;
+    for (col_name, _) in schema.iter() {
+        if col_name.as_str() == name {
+            continue;
+        }
+        let weight = weights.get(col_name.as_str()).copied().unwrap_or(1.0);
+        if !weight.is_finite() || weight < 0.0 {
+            anyhow::bail!("Completeness weight for '{col_name}' must be a non-negative number");
+        }
+        if weight == 0.0 {
+            continue;
+        }
+        total_weight += weight;
+        numerator =
+            numerator + col(col_name.as_str()).is_not_null().cast(DataType::Float64) * lit(weight);
+    }
+
+    if total_weight <= 0.0 {
+        anyhow::bail!("Completeness score needs at least one column with a positive weight");
+    }
+
+    Ok(lf.with_column((numerator / lit(total_weight)).alias(name)))
 }
 
 pub fn auto_clean_df(df: DataFrame, restricted: bool) -> Result<DataFrame> {
@@ -161,10 +328,44 @@ pub fn apply_text_cleaning(
         }
     }
 
+    // Recodes compare text, so other columns are mapped as their text form
+    if !config.value_mapping.is_empty() {
+        if !matches!(dtype, DataType::String) {
+            expr = expr.cast(DataType::String);
+        }
+        expr = map_values(expr, &config.value_mapping);
+    }
+
     expr
 }
 
+pub fn apply_number_extraction(expr: Expr, config: &ColumnCleanConfig) -> Expr {
+    if config.extract_numbers {
+        expr.str()
+            .extract(lit(r"(\d+\.?\d*)"), 1)
+            .cast(DataType::Float64)
+    } else {
+        expr
+    }
+}
+
 pub fn apply_dtype_casting(expr: Expr, config: &ColumnCleanConfig) -> Expr {
+    apply_dtype_casting_with_dtype(expr, config, &DataType::String)
+}
+
+/// [`apply_dtype_casting`] of a column of type `dtype`. Text becomes
+/// temporal by `temporal_format` when one is set.
+pub fn apply_dtype_casting_with_dtype(
+    expr: Expr,
+    config: &ColumnCleanConfig,
+    dtype: &DataType,
+) -> Expr {
+    if config.target_dtype == Some(ColumnKind::Temporal)
+        && !config.temporal_format.is_empty()
+        && dtype == &DataType::String
+    {
+        return parse_dates_expr(expr, &config.temporal_format);
+    }
     if let Some(kind) = config.target_dtype {
         match kind {
             ColumnKind::Numeric => expr.cast(DataType::Float64),
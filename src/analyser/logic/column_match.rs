@@ -0,0 +1,175 @@
+//! Matching the column names something expects to the columns a file has.
+//!
+//! Presets and saved pipelines name the columns of the file they were made
+//! for; a later file often spells them differently (`Customer ID` against
+//! `customer_id`). [`resolve_columns`] pairs expected names with present
+//! ones: by exact name first, then by the sanitised name, then by the
+//! closest sanitised name within a small edit distance, closest pairs
+//! first. Each name is used in one pair at most. Non-exact pairs are
+//! suggestions for the user to confirm.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use super::naming::{edit_distance, sanitize_column_name};
+
+/// How a present column was matched to an expected one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnMatchKind {
+    Exact,
+    /// Same name once sanitised, e.g. differing only in case or spacing
+    Sanitised,
+    /// Sanitised names within a small edit distance
+    Fuzzy,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMatch {
+    /// The name expected
+    pub expected: String,
+    /// The present column matched to it
+    pub column: String,
+    pub kind: ColumnMatchKind,
+    /// Edit distance between the sanitised names
+    pub distance: usize,
+}
+
+/// Expected column names paired with present ones
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnResolution {
+    /// Matches in present column order
+    pub matches: Vec<ColumnMatch>,
+    /// Expected names no present column matched, in the order given
+    pub unmatched_expected: Vec<String>,
+    /// Present columns matched to no expected name
+    pub unmatched_columns: Vec<String>,
+}
+
+impl ColumnResolution {
+    /// Whether any match needs confirming, not being exact
+    pub fn needs_confirmation(&self) -> bool {
+        self.matches
+            .iter()
+            .any(|m| m.kind != ColumnMatchKind::Exact)
+    }
+
+    /// The present name of each column matched under another name, keyed by
+    /// that present name, as for a rename
+    pub fn renames(&self) -> HashMap<String, String> {
+        self.matches
+            .iter()
+            .filter(|m| m.kind != ColumnMatchKind::Exact)
+            .map(|m| (m.column.clone(), m.expected.clone()))
+            .collect()
+    }
+}
+
+/// Pair `expected` column names with `columns`, the names present
+pub fn resolve_columns(expected: &[String], columns: &[String]) -> ColumnResolution {
+    let mut matched: HashMap<&str, (&str, ColumnMatchKind, usize)> = HashMap::new();
+    let mut used: HashSet<&str> = HashSet::new();
+
+    let wanted: HashSet<&str> = expected.iter().map(String::as_str).collect();
+    for column in columns {
+        if wanted.contains(column.as_str()) && used.insert(column) {
+            matched.insert(column, (column.as_str(), ColumnMatchKind::Exact, 0));
+        }
+    }
+
+    let sanitised: Vec<(&str, String)> = expected
+        .iter()
+        .map(|name| (name.as_str(), sanitize_column_name(name)))
+        .collect();
+    for column in columns {
+        if matched.contains_key(column.as_str()) {
+            continue;
+        }
+        let target = sanitize_column_name(column);
+        if let Some((name, _)) = sanitised
+            .iter()
+            .find(|(name, s)| !used.contains(name) && *s == target)
+        {
+            matched.insert(column, (*name, ColumnMatchKind::Sanitised, 0));
+            used.insert(*name);
+        }
+    }
+
+    // Closest pairs first, so a near-exact match is not taken by a worse one
+    let mut candidates: Vec<(usize, &str, &str)> = Vec::new();
+    for column in columns {
+        if matched.contains_key(column.as_str()) {
+            continue;
+        }
+        let target = sanitize_column_name(column);
+        let max_distance = target.chars().count() / 4;
+        for (name, s) in &sanitised {
+            if used.contains(name) {
+                continue;
+            }
+            let distance = edit_distance(&target, s);
+            if distance <= max_distance {
+                candidates.push((distance, column.as_str(), *name));
+            }
+        }
+    }
+    candidates.sort_unstable();
+    for (distance, column, name) in candidates {
+        if !matched.contains_key(column) && !used.contains(name) {
+            matched.insert(column, (name, ColumnMatchKind::Fuzzy, distance));
+            used.insert(name);
+        }
+    }
+
+    let mut resolution = ColumnResolution {
+        unmatched_expected: expected
+            .iter()
+            .filter(|name| !used.contains(name.as_str()))
+            .cloned()
+            .collect(),
+        ..ColumnResolution::default()
+    };
+    for column in columns {
+        match matched.get(column.as_str()) {
+            Some(&(expected, kind, distance)) => resolution.matches.push(ColumnMatch {
+                expected: expected.to_owned(),
+                column: column.clone(),
+                kind,
+                distance,
+            }),
+            None => resolution.unmatched_columns.push(column.clone()),
+        }
+    }
+    resolution
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_columns() {
+        let expected = ["Customer ID", "Order Total", "region", "Notes"].map(str::to_owned);
+        let columns = ["customer_id", "order_totl", "region", "ix"].map(str::to_owned);
+        let resolution = resolve_columns(&expected, &columns);
+        let pairs: Vec<(&str, &str, ColumnMatchKind)> = resolution
+            .matches
+            .iter()
+            .map(|m| (m.column.as_str(), m.expected.as_str(), m.kind))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("customer_id", "Customer ID", ColumnMatchKind::Sanitised),
+                ("order_totl", "Order Total", ColumnMatchKind::Fuzzy),
+                ("region", "region", ColumnMatchKind::Exact),
+            ]
+        );
+        assert_eq!(resolution.matches[1].distance, 1);
+        assert_eq!(resolution.unmatched_expected, ["Notes"]);
+        assert_eq!(resolution.unmatched_columns, ["ix"]);
+        assert!(resolution.needs_confirmation());
+        assert_eq!(resolution.renames().len(), 2);
+        assert_eq!(resolution.renames()["customer_id"], "Customer ID");
+    }
+}
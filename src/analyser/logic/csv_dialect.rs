@@ -0,0 +1,231 @@
+//! How a CSV file is written: its delimiter, byte order mark and line endings.
+//!
+//! Exports from spreadsheets in many locales separate fields with `;`, and
+//! other tools use tabs or pipes. [`detect_csv_dialect`] reads the start of a
+//! file and picks the delimiter that splits every line into the same number
+//! of fields, so such files load as columns rather than as one wide column,
+//! and cleaning in place can write them back the same way.
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read as _;
+use std::path::Path;
+
+/// Bytes read from the start of a file to detect its dialect
+const DIALECT_SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// Lines compared to find the delimiter
+const DIALECT_SAMPLE_LINES: usize = 50;
+
+/// Delimiters tried, in order of preference when several fit
+const CANDIDATE_DELIMITERS: [u8; 4] = [b',', b';', b'\t', b'|'];
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextEncoding {
+    #[default]
+    Utf8,
+    /// UTF-8 starting with a byte order mark, as Excel writes it
+    Utf8Bom,
+    /// Not valid UTF-8, such as Windows-1252; read lossily
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvDialect {
+    pub delimiter: u8,
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    #[serde(default)]
+    pub line_ending: LineEnding,
+}
+
+impl Default for CsvDialect {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            encoding: TextEncoding::default(),
+            line_ending: LineEnding::default(),
+        }
+    }
+}
+
+impl CsvDialect {
+    /// The delimiter as a character, for messages
+    pub fn delimiter_name(&self) -> &'static str {
+        match self.delimiter {
+            b';' => "semicolon",
+            b'\t' => "tab",
+            b'|' => "pipe",
+            _ => "comma",
+        }
+    }
+}
+
+/// The dialect of the CSV file at `path`, from its first 64 KB
+pub fn detect_csv_dialect(path: &Path) -> Result<CsvDialect> {
+    let mut sample = Vec::new();
+    std::fs::File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?
+        .take(DIALECT_SAMPLE_BYTES)
+        .read_to_end(&mut sample)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let complete = (sample.len() as u64) < DIALECT_SAMPLE_BYTES;
+    Ok(dialect_of_sample(&sample, complete))
+}
+
+/// The delimiter of the CSV file at `path`, a comma when it cannot be read
+pub fn csv_separator(path: &Path) -> u8 {
+    detect_csv_dialect(path).map_or(b',', |d| d.delimiter)
+}
+
+/// The dialect of the start of a file; the last line of `sample` is only
+/// used when `complete`, as it may be cut short otherwise
+fn dialect_of_sample(sample: &[u8], complete: bool) -> CsvDialect {
+    let (encoding, body) = match sample.strip_prefix(UTF8_BOM) {
+        Some(body) => (TextEncoding::Utf8Bom, body),
+        None => (TextEncoding::Utf8, sample),
+    };
+    // A multi-byte character cut off at the end of the sample is still UTF-8
+    let encoding = match std::str::from_utf8(body) {
+        Err(e) if e.error_len().is_some() => TextEncoding::Other,
+        _ => encoding,
+    };
+    let line_ending = match body.iter().position(|&b| b == b'\n') {
+        Some(i) if i > 0 && body[i - 1] == b'\r' => LineEnding::Crlf,
+        _ => LineEnding::Lf,
+    };
+
+    let mut lines = split_records(body);
+    if !complete && lines.len() > 1 {
+        lines.pop();
+    }
+    lines.retain(|l| !l.iter().all(u8::is_ascii_whitespace));
+    lines.truncate(DIALECT_SAMPLE_LINES);
+
+    CsvDialect {
+        delimiter: pick_delimiter(&lines),
+        encoding,
+        line_ending,
+    }
+}
+
+/// Lines of `body`, keeping line breaks inside quoted fields
+fn split_records(body: &[u8]) -> Vec<&[u8]> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, &b) in body.iter().enumerate() {
+        match b {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => {
+                records.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < body.len() {
+        records.push(&body[start..]);
+    }
+    records
+}
+
+/// Times `delimiter` occurs in `line` outside quoted fields
+fn count_outside_quotes(line: &[u8], delimiter: u8) -> usize {
+    let mut quoted = false;
+    let mut count = 0;
+    for &b in line {
+        if b == b'"' {
+            quoted = !quoted;
+        } else if b == delimiter && !quoted {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// The candidate splitting every line into the same number of fields, the
+/// most fields winning; failing that, the one splitting the header the
+/// most. Single-column files keep the comma.
+fn pick_delimiter(lines: &[&[u8]]) -> u8 {
+    let Some(header) = lines.first() else {
+        return b',';
+    };
+    let consistent = CANDIDATE_DELIMITERS
+        .iter()
+        .filter_map(|&d| {
+            let count = count_outside_quotes(header, d);
+            let same = lines.iter().all(|l| count_outside_quotes(l, d) == count);
+            (count > 0 && same).then_some((d, count))
+        })
+        // `max_by_key` keeps the last of equals, so prefer earlier candidates
+        .rev()
+        .max_by_key(|&(_, count)| count);
+    if let Some((delimiter, _)) = consistent {
+        return delimiter;
+    }
+    CANDIDATE_DELIMITERS
+        .iter()
+        .rev()
+        .map(|&d| (d, count_outside_quotes(header, d)))
+        .filter(|&(_, count)| count > 0)
+        .max_by_key(|&(_, count)| count)
+        .map_or(b',', |(d, _)| d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delimiter_of(text: &str) -> u8 {
+        dialect_of_sample(text.as_bytes(), true).delimiter
+    }
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(delimiter_of("a,b,c\n1,2,3\n"), b',');
+        assert_eq!(delimiter_of("a;b\n1,5;2,25\n3;4\n"), b';');
+        assert_eq!(delimiter_of("a\tb\n\"x,y\"\t2\n"), b'\t');
+        assert_eq!(delimiter_of("a|b|c\n1|2|3\n"), b'|');
+        // A ragged file still splits on what its header uses most
+        assert_eq!(delimiter_of("a;b;c\n1;2\n"), b';');
+        assert_eq!(delimiter_of("name\nAda\n"), b',');
+        assert_eq!(delimiter_of(""), b',');
+    }
+
+    #[test]
+    fn test_detect_encoding_and_line_ending() {
+        let dialect = dialect_of_sample(b"\xEF\xBB\xBFa;b\r\n1;2\r\n", true);
+        assert_eq!(dialect.encoding, TextEncoding::Utf8Bom);
+        assert_eq!(dialect.line_ending, LineEnding::Crlf);
+        assert_eq!(dialect.delimiter, b';');
+
+        let dialect = dialect_of_sample(b"name\nRen\xE9e\n", true);
+        assert_eq!(dialect.encoding, TextEncoding::Other);
+        assert_eq!(dialect.line_ending, LineEnding::Lf);
+
+        // A character cut off by the sample limit is not an encoding error
+        let dialect = dialect_of_sample(b"a,b\nx,\xC3", false);
+        assert_eq!(dialect.encoding, TextEncoding::Utf8);
+    }
+}
@@ -0,0 +1,299 @@
+//! Dates written as text: inferring their format and parsing them with it.
+//!
+//! A plain cast reads only ISO dates, and cannot tell whether `03/04/2024`
+//! is the 3rd of April or the 4th of March. [`infer_date_format`] tries every
+//! format in [`DATE_FORMATS`] on sampled values and keeps the one that reads
+//! the most of them. When a day-first and a month-first format read the same
+//! values, every sampled day is 12 or less and the `date_order` setting
+//! decides; by default it follows the system locale (`LC_ALL`, `LC_TIME` or
+//! `LANG`), reading dates month-first only in regions that write them so,
+//! such as `en_US`. The chosen format is reported in the column's
+//! [`TemporalStats`](super::types::TemporalStats) and becomes the
+//! `temporal_format` used by cleaning and the pipeline's date parsing.
+
+use chrono::{Datelike as _, NaiveDate, NaiveDateTime};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::semantic::{MIN_SEMANTIC_SAMPLES, SEMANTIC_MATCH_THRESHOLD};
+
+/// Values sampled from a column to infer its format
+pub const DATE_FORMAT_SAMPLE_ROWS: usize = 1000;
+
+/// Regions whose locales write dates month first
+const MONTH_FIRST_REGIONS: [&str; 6] = ["US", "PH", "BZ", "FM", "MH", "PW"];
+
+/// Order of day and month in dates such as `03/04/2024`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateOrder {
+    /// From the system locale, day first when it does not say
+    #[default]
+    Auto,
+    DayFirst,
+    MonthFirst,
+}
+
+impl DateOrder {
+    /// The order from the app settings
+    pub fn from_settings() -> Self {
+        crate::config::load_app_config().settings.date_order
+    }
+
+    /// The order of a locale name such as `en_US.UTF-8` or `en-GB`, if it
+    /// names a region
+    pub fn of_locale(locale: &str) -> Option<Self> {
+        let name = locale.split(['.', '@']).next()?;
+        let (_, region) = name.split_once(['_', '-'])?;
+        let region = region.to_ascii_uppercase();
+        Some(if MONTH_FIRST_REGIONS.contains(&region.as_str()) {
+            Self::MonthFirst
+        } else {
+            Self::DayFirst
+        })
+    }
+
+    /// [`Self::DayFirst`] or [`Self::MonthFirst`], resolving `Auto` from the
+    /// system locale
+    pub fn resolve(self) -> Self {
+        match self {
+            Self::Auto => ["LC_ALL", "LC_TIME", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|v| !v.is_empty())
+                .and_then(|v| Self::of_locale(&v))
+                .unwrap_or(Self::DayFirst),
+            order => order,
+        }
+    }
+}
+
+/// A strptime format tried on text dates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateFormat {
+    pub format: &'static str,
+    /// Which of day and month comes first, for all-numeric dates that could
+    /// be read either way
+    pub order: Option<DateOrder>,
+}
+
+const fn unordered(format: &'static str) -> DateFormat {
+    DateFormat {
+        format,
+        order: None,
+    }
+}
+
+const fn day_first(format: &'static str) -> DateFormat {
+    DateFormat {
+        format,
+        order: Some(DateOrder::DayFirst),
+    }
+}
+
+const fn month_first(format: &'static str) -> DateFormat {
+    DateFormat {
+        format,
+        order: Some(DateOrder::MonthFirst),
+    }
+}
+
+/// Formats tried, in order of preference among those reading as many values
+pub const DATE_FORMATS: [DateFormat; 30] = [
+    unordered("%Y-%m-%d"),
+    unordered("%Y-%m-%d %H:%M:%S%.f"),
+    unordered("%Y-%m-%dT%H:%M:%S%.f"),
+    unordered("%Y-%m-%dT%H:%M:%S%.fZ"),
+    unordered("%Y-%m-%d %H:%M"),
+    unordered("%Y-%m-%dT%H:%M"),
+    unordered("%Y/%m/%d"),
+    unordered("%Y/%m/%d %H:%M:%S"),
+    day_first("%d/%m/%Y"),
+    day_first("%d-%m-%Y"),
+    day_first("%d.%m.%Y"),
+    day_first("%d/%m/%y"),
+    day_first("%d/%m/%Y %H:%M"),
+    day_first("%d/%m/%Y %H:%M:%S"),
+    day_first("%d-%m-%Y %H:%M:%S"),
+    day_first("%d.%m.%Y %H:%M"),
+    month_first("%m/%d/%Y"),
+    month_first("%m-%d-%Y"),
+    month_first("%m/%d/%y"),
+    month_first("%m/%d/%Y %H:%M"),
+    month_first("%m/%d/%Y %H:%M:%S"),
+    month_first("%m/%d/%Y %I:%M %p"),
+    month_first("%m/%d/%Y %I:%M:%S %p"),
+    month_first("%m-%d-%Y %H:%M:%S"),
+    unordered("%d %b %Y"),
+    unordered("%d %B %Y"),
+    unordered("%d-%b-%Y"),
+    unordered("%b %d, %Y"),
+    unordered("%B %d, %Y"),
+    unordered("%a, %d %b %Y %H:%M:%S"),
+];
+
+/// The format inferred for a column
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InferredDateFormat {
+    pub format: String,
+    /// Sampled values the format reads
+    pub matched: usize,
+    /// Non-empty values sampled
+    pub sampled: usize,
+    /// Whether the values read as well day first as month first, so the
+    /// order came from the setting or locale
+    pub ambiguous: bool,
+}
+
+/// The format of [`DATE_FORMATS`] reading the most of `values`, if it reads
+/// nearly all of them. `order` breaks ties between day-first and
+/// month-first formats.
+pub fn infer_date_format(values: &[&str], order: DateOrder) -> Option<InferredDateFormat> {
+    let values: Vec<&str> = values
+        .iter()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.len() < MIN_SEMANTIC_SAMPLES {
+        return None;
+    }
+    let needed = values.len() as f64 * SEMANTIC_MATCH_THRESHOLD;
+    let scored: Vec<(DateFormat, usize)> = DATE_FORMATS
+        .iter()
+        .map(|f| (*f, values.iter().filter(|v| reads(v, f.format)).count()))
+        .filter(|&(_, matched)| matched as f64 >= needed)
+        .collect();
+    let best = scored.iter().map(|&(_, matched)| matched).max()?;
+    let tied: Vec<DateFormat> = scored
+        .into_iter()
+        .filter(|&(_, matched)| matched == best)
+        .map(|(f, _)| f)
+        .collect();
+
+    let order = order.resolve();
+    let chosen = tied
+        .iter()
+        .find(|f| f.order.is_none_or(|o| o == order))
+        .or(tied.first())?;
+    Some(InferredDateFormat {
+        format: chosen.format.to_owned(),
+        matched: best,
+        sampled: values.len(),
+        ambiguous: tied
+            .iter()
+            .any(|f| f.order.is_some() && f.order != chosen.order),
+    })
+}
+
+/// The format of the first [`DATE_FORMAT_SAMPLE_ROWS`] values of text
+/// column `column`, if they are dates
+pub fn infer_column_date_format(column: &Column, order: DateOrder) -> Option<InferredDateFormat> {
+    let values: Vec<&str> = column
+        .str()
+        .ok()?
+        .into_iter()
+        .flatten()
+        .take(DATE_FORMAT_SAMPLE_ROWS)
+        .collect();
+    infer_date_format(&values, order)
+}
+
+/// Whether `format` reads `value` as a date with a four-digit year
+fn reads(value: &str, format: &str) -> bool {
+    let year = NaiveDateTime::parse_from_str(value, format)
+        .map(|dt| dt.year())
+        .or_else(|_| NaiveDate::parse_from_str(value, format).map(|d| d.year()));
+    // `%Y` also reads `24` in `01/02/24`, as the year 24
+    year.is_ok_and(|y| (1000..=9999).contains(&y))
+}
+
+/// `expr`, text, parsed as `format` into millisecond datetimes. Values the
+/// format does not read become null.
+pub fn parse_dates_expr(expr: Expr, format: &str) -> Expr {
+    expr.cast(DataType::String)
+        .str()
+        .strip_chars(lit(NULL))
+        .str()
+        .to_datetime(
+            Some(TimeUnit::Milliseconds),
+            None,
+            StrptimeOptions {
+                format: Some(format.into()),
+                strict: false,
+                exact: true,
+                cache: true,
+            },
+            lit("raise"),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_date_format_resolves_day_and_month_order() {
+        let ambiguous = ["03/04/2024", "05/06/2024", "11/12/2023"];
+        let uk = infer_date_format(&ambiguous, DateOrder::DayFirst).unwrap();
+        assert_eq!(uk.format, "%d/%m/%Y");
+        assert!(uk.ambiguous);
+        let us = infer_date_format(&ambiguous, DateOrder::MonthFirst).unwrap();
+        assert_eq!(us.format, "%m/%d/%Y");
+
+        // A day over 12 settles it, whatever the setting
+        let us = infer_date_format(
+            &["03/15/2024", "04/01/2024", "12/31/2023"],
+            DateOrder::DayFirst,
+        )
+        .unwrap();
+        assert_eq!(us.format, "%m/%d/%Y");
+        assert!(!us.ambiguous);
+
+        let short = infer_date_format(&["31/01/24", "01/02/24", "15/03/24"], DateOrder::Auto);
+        assert_eq!(short.unwrap().format, "%d/%m/%y");
+        let iso = infer_date_format(
+            &["2024-01-31 10:00:00", "2024-02-01 09:30:15.250", " "],
+            DateOrder::Auto,
+        );
+        assert_eq!(iso, None, "two values are too few");
+        let iso = infer_date_format(
+            &[
+                "2024-01-31 10:00:00",
+                "2024-02-01 09:30:15.250",
+                "2024-02-02 00:00:00",
+            ],
+            DateOrder::Auto,
+        )
+        .unwrap();
+        assert_eq!(iso.format, "%Y-%m-%d %H:%M:%S%.f");
+        assert_eq!(
+            infer_date_format(&["12", "3.5", "abc"], DateOrder::Auto),
+            None
+        );
+
+        assert_eq!(
+            DateOrder::of_locale("en_US.UTF-8"),
+            Some(DateOrder::MonthFirst)
+        );
+        assert_eq!(DateOrder::of_locale("de-DE"), Some(DateOrder::DayFirst));
+        assert_eq!(DateOrder::of_locale("C"), None);
+    }
+
+    #[test]
+    fn test_parse_dates_expr() -> anyhow::Result<()> {
+        let df = df!("when" => ["15/03/2024", " 01/12/2023 ", "2024-03-15", ""])?;
+        let parsed = df
+            .lazy()
+            .select([parse_dates_expr(col("when"), "%d/%m/%Y")])
+            .collect()?;
+        let when = parsed.column("when")?;
+        assert_eq!(
+            when.dtype(),
+            &DataType::Datetime(TimeUnit::Milliseconds, None)
+        );
+        assert_eq!(when.null_count(), 2);
+        let ms = when.as_materialized_series().cast(&DataType::Int64)?;
+        assert_eq!(ms.i64()?.get(0), Some(1_710_460_800_000));
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Calendar features of date and time columns.
+//!
+//! Each [`DatePart`] becomes its own column named `<column>_<part>`, e.g.
+//! `order_date_month`. Parts are whole numbers, except `is_weekend`, which
+//! is true or false. A date has no hour or minute and a time of day has no
+//! calendar parts, so asking for those is an error.
+
+use anyhow::{Result, bail};
+use polars::prelude::*;
+
+use super::types::DatePart;
+
+/// Name of the column `part` of `column` is written to
+pub fn date_part_column(column: &str, part: DatePart) -> String {
+    format!("{column}_{}", part.as_str())
+}
+
+/// One expression per part of `expr`, a column of type `dtype`, named after
+/// `column`
+pub fn date_part_exprs(
+    expr: Expr,
+    dtype: &DataType,
+    column: &str,
+    parts: &[DatePart],
+) -> Result<Vec<Expr>> {
+    if parts.is_empty() {
+        bail!("No date parts chosen for '{column}'");
+    }
+    let missing = match dtype {
+        DataType::Date => parts.iter().find(|p| p.needs_time()).map(|p| ("dates", p)),
+        DataType::Time => parts
+            .iter()
+            .find(|p| !p.needs_time())
+            .map(|p| ("times of day", p)),
+        DataType::Datetime(..) => None,
+        other => {
+            bail!("Column '{column}' is {other}, not a date or time; parse it as a date first")
+        }
+    };
+    if let Some((kind, part)) = missing {
+        bail!(
+            "Column '{column}' holds {kind}, which have no {}",
+            part.as_str()
+        );
+    }
+
+    let mut exprs = Vec::with_capacity(parts.len());
+    let mut seen = Vec::with_capacity(parts.len());
+    for &part in parts {
+        if seen.contains(&part) {
+            continue;
+        }
+        seen.push(part);
+        let dt = expr.clone().dt();
+        let feature = match part {
+            DatePart::Year => dt.year(),
+            DatePart::Quarter => dt.quarter(),
+            DatePart::Month => dt.month(),
+            DatePart::Week => dt.week(),
+            DatePart::Day => dt.day(),
+            DatePart::Weekday => dt.weekday(),
+            DatePart::DayOfYear => dt.ordinal_day(),
+            DatePart::Hour => dt.hour(),
+            DatePart::Minute => dt.minute(),
+            DatePart::IsWeekend => dt.weekday().gt_eq(lit(6)),
+        };
+        let feature = if part == DatePart::IsWeekend {
+            feature
+        } else {
+            // The parts come back as assorted integer widths
+            feature.cast(DataType::Int32)
+        };
+        exprs.push(feature.alias(date_part_column(column, part)));
+    }
+    Ok(exprs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_date_part_exprs() -> Result<()> {
+        let dates = [
+            NaiveDate::from_ymd_opt(2024, 3, 9),
+            NaiveDate::from_ymd_opt(2024, 12, 31),
+            None,
+        ];
+        let df = df!("placed" => dates)?;
+        let exprs = date_part_exprs(
+            col("placed"),
+            &DataType::Date,
+            "placed",
+            &[
+                DatePart::Year,
+                DatePart::Quarter,
+                DatePart::Weekday,
+                DatePart::DayOfYear,
+                DatePart::IsWeekend,
+            ],
+        )?;
+        let out = df.lazy().select(exprs).collect()?;
+
+        let names: Vec<&str> = out.get_column_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "placed_year",
+                "placed_quarter",
+                "placed_weekday",
+                "placed_day_of_year",
+                "placed_is_weekend"
+            ]
+        );
+        let year: Vec<_> = out.column("placed_year")?.i32()?.into_iter().collect();
+        assert_eq!(year, [Some(2024), Some(2024), None]);
+        let quarter: Vec<_> = out.column("placed_quarter")?.i32()?.into_iter().collect();
+        assert_eq!(quarter, [Some(1), Some(4), None]);
+        // 9 March 2024 was a Saturday, 31 December a Tuesday
+        let weekday: Vec<_> = out.column("placed_weekday")?.i32()?.into_iter().collect();
+        assert_eq!(weekday, [Some(6), Some(2), None]);
+        let day: Vec<_> = out
+            .column("placed_day_of_year")?
+            .i32()?
+            .into_iter()
+            .collect();
+        assert_eq!(day, [Some(69), Some(366), None]);
+        let weekend: Vec<_> = out
+            .column("placed_is_weekend")?
+            .bool()?
+            .into_iter()
+            .collect();
+        assert_eq!(weekend, [Some(true), Some(false), None]);
+
+        assert!(
+            date_part_exprs(col("placed"), &DataType::Date, "placed", &[DatePart::Hour]).is_err()
+        );
+        assert!(
+            date_part_exprs(
+                col("placed"),
+                &DataType::String,
+                "placed",
+                &[DatePart::Year]
+            )
+            .is_err()
+        );
+        assert!(
+            date_part_exprs(col("placed"), &DataType::Time, "placed", &[DatePart::Month]).is_err()
+        );
+        Ok(())
+    }
+}
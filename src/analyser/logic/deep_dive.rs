@@ -0,0 +1,261 @@
+//! On-demand deep dive into a single column.
+//!
+//! The default analysis keeps to statistics that can be computed in one
+//! streaming pass per column. A deep dive adds the expensive extras for one
+//! column the user asks about: the exact list of distinct values (up to a
+//! cap), the longest runs of identical consecutive values, and the mean of
+//! each decile of a numeric column. Parts are computed one after another and
+//! handed to a callback as soon as each is ready, so the GUI can show them
+//! incrementally.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Limits for a deep dive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeepDiveOptions {
+    /// Distinct values listed, most frequent first
+    pub max_distinct_values: usize,
+    /// Runs of identical values listed, longest first
+    pub max_runs: usize,
+}
+
+impl Default for DeepDiveOptions {
+    fn default() -> Self {
+        Self {
+            max_distinct_values: 1000,
+            max_runs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistinctValues {
+    /// Value and row count, most frequent first
+    pub values: Vec<(String, usize)>,
+    /// Distinct non-null values in the whole column
+    pub total_distinct: usize,
+    /// Whether `values` stops at the cap
+    pub truncated: bool,
+}
+
+/// Consecutive rows holding the same value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueRun {
+    pub value: String,
+    pub length: usize,
+    /// Zero-based row where the run starts
+    pub start_row: usize,
+}
+
+/// One tenth of the sorted non-null values of a numeric column
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecileMean {
+    /// 1 to 10
+    pub decile: u8,
+    pub lower: f64,
+    pub upper: f64,
+    pub mean: f64,
+    pub count: usize,
+}
+
+/// A finished part of a deep dive, as passed to the progress callback
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "part", content = "data")]
+pub enum DeepDivePart {
+    DistinctValues(DistinctValues),
+    LongestRuns(Vec<ValueRun>),
+    DecileMeans(Vec<DecileMean>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnDeepDive {
+    pub column: String,
+    pub distinct_values: DistinctValues,
+    pub longest_runs: Vec<ValueRun>,
+    /// Empty for non-numeric columns
+    pub decile_means: Vec<DecileMean>,
+}
+
+/// Run a deep dive into column `name` of `lf`, calling `on_part` as each
+/// part completes. Stops between parts if the user aborts processing.
+pub fn column_deep_dive(
+    mut lf: LazyFrame,
+    name: &str,
+    options: &DeepDiveOptions,
+    mut on_part: impl FnMut(&DeepDivePart),
+) -> Result<ColumnDeepDive> {
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let dtype = schema
+        .get(name)
+        .with_context(|| format!("Column '{name}' not found"))?
+        .clone();
+    let lf = lf.select([col(name)]);
+
+    let distinct_values = distinct_values(lf.clone(), name, options.max_distinct_values)?;
+    on_part(&DeepDivePart::DistinctValues(distinct_values.clone()));
+    crate::utils::check_aborted("Deep dive")?;
+
+    let longest_runs = longest_runs(lf.clone(), name, options.max_runs)?;
+    on_part(&DeepDivePart::LongestRuns(longest_runs.clone()));
+    crate::utils::check_aborted("Deep dive")?;
+
+    let decile_means = if dtype.is_numeric() {
+        let deciles = decile_means(lf, name)?;
+        on_part(&DeepDivePart::DecileMeans(deciles.clone()));
+        deciles
+    } else {
+        Vec::new()
+    };
+
+    Ok(ColumnDeepDive {
+        column: name.to_owned(),
+        distinct_values,
+        longest_runs,
+        decile_means,
+    })
+}
+
+fn distinct_values(lf: LazyFrame, name: &str, cap: usize) -> Result<DistinctValues> {
+    let total_distinct = lf
+        .clone()
+        .select([col(name).drop_nulls().n_unique()])
+        .collect()?
+        .column(name)?
+        .as_materialized_series()
+        .cast(&DataType::UInt64)?
+        .u64()?
+        .get(0)
+        .unwrap_or(0) as usize;
+
+    let counts_df = lf
+        .filter(col(name).is_not_null())
+        .group_by([col(name)])
+        .agg([len().alias("count")])
+        .select([col(name).cast(DataType::String), col("count")])
+        .sort(
+            ["count", name],
+            SortMultipleOptions::default().with_order_descending_multi([true, false]),
+        )
+        .limit(cap as u32)
+        .collect()
+        .context("Failed to list distinct values")?;
+
+    let ca_val = counts_df.column(name)?.as_materialized_series().clone();
+    let ca_val = ca_val.str()?;
+    let ca_count = counts_df
+        .column("count")?
+        .cast(&DataType::UInt64)?
+        .as_materialized_series()
+        .clone();
+    let ca_count = ca_count.u64()?;
+
+    let values: Vec<(String, usize)> = ca_val
+        .into_iter()
+        .zip(ca_count)
+        .filter_map(|(v, c)| Some((v?.to_owned(), c? as usize)))
+        .collect();
+
+    Ok(DistinctValues {
+        truncated: values.len() < total_distinct,
+        values,
+        total_distinct,
+    })
+}
+
+/// The `max_runs` longest runs of two or more identical values. Nulls end a
+/// run and are never part of one.
+fn longest_runs(lf: LazyFrame, name: &str, max_runs: usize) -> Result<Vec<ValueRun>> {
+    if max_runs == 0 {
+        return Ok(Vec::new());
+    }
+    let df = lf
+        .select([col(name).cast(DataType::String)])
+        .collect()
+        .context("Failed to load column for run detection")?;
+    let series = df.column(name)?.as_materialized_series();
+    let ca = series.str()?;
+
+    // Min-heap on length (then latest start) holding the best runs so far
+    let mut heap: BinaryHeap<Reverse<(usize, Reverse<usize>, String)>> = BinaryHeap::new();
+    let mut push = |length: usize, start: usize, value: &str| {
+        if length < 2 {
+            return;
+        }
+        if heap.len() < max_runs {
+            heap.push(Reverse((length, Reverse(start), value.to_owned())));
+        } else if let Some(Reverse((shortest, ..))) = heap.peek()
+            && length > *shortest
+        {
+            heap.pop();
+            heap.push(Reverse((length, Reverse(start), value.to_owned())));
+        }
+    };
+
+    let mut current: Option<(&str, usize)> = None;
+    let mut length = 0;
+    for (row, value) in ca.into_iter().enumerate() {
+        match (current, value) {
+            (Some((prev, _)), Some(v)) if prev == v => length += 1,
+            _ => {
+                if let Some((prev, start)) = current {
+                    push(length, start, prev);
+                }
+                current = value.map(|v| (v, row));
+                length = 1;
+            }
+        }
+    }
+    if let Some((prev, start)) = current {
+        push(length, start, prev);
+    }
+
+    let mut runs: Vec<ValueRun> = heap
+        .into_iter()
+        .map(|Reverse((length, Reverse(start_row), value))| ValueRun {
+            value,
+            length,
+            start_row,
+        })
+        .collect();
+    runs.sort_by(|a, b| b.length.cmp(&a.length).then(a.start_row.cmp(&b.start_row)));
+    Ok(runs)
+}
+
+fn decile_means(lf: LazyFrame, name: &str) -> Result<Vec<DecileMean>> {
+    let df = lf
+        .select([col(name).cast(DataType::Float64)])
+        .filter(col(name).is_not_null().and(col(name).is_not_nan()))
+        .sort([name], SortMultipleOptions::default())
+        .collect()
+        .context("Failed to sort column for deciles")?;
+    let values: Vec<f64> = df
+        .column(name)?
+        .as_materialized_series()
+        .f64()?
+        .into_no_null_iter()
+        .collect();
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let n = values.len();
+    let mut deciles = Vec::with_capacity(10);
+    for decile in 0..10 {
+        let bucket = &values[decile * n / 10..(decile + 1) * n / 10];
+        if let (Some(&lower), Some(&upper)) = (bucket.first(), bucket.last()) {
+            deciles.push(DecileMean {
+                decile: decile as u8 + 1,
+                lower,
+                upper,
+                mean: bucket.iter().sum::<f64>() / bucket.len() as f64,
+                count: bucket.len(),
+            });
+        }
+    }
+    Ok(deciles)
+}
@@ -0,0 +1,311 @@
+//! Ordinal and target encoding of categorical columns.
+//!
+//! One-hot encoding adds a column per value, which explodes for columns
+//! with thousands of values. These encodings keep one column and replace
+//! each value with a number instead:
+//!
+//! - ordinal: the value's position in sorted order, from 0
+//! - target: the mean of a numeric target over the rows with the value,
+//!   smoothed towards the overall mean so rare values are not trusted
+//!
+//! The numbers are learned from the data an encoding is first applied to
+//! and kept in [`CategoryEncoding::mapping`], so new data can be encoded
+//! with the same numbers. While a target encoding is learned, rows are
+//! split into folds and each row is encoded from the other folds only, so
+//! a row's own target never leaks into its feature.
+
+use anyhow::{Context as _, Result, bail};
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+use super::types::{CategoryEncoding, EncodingMethod};
+
+/// Row number added while target encoding, to assign folds
+const ROW_COLUMN: &str = "__encoding_row";
+
+/// Number ordinal encoding gives values it has not seen, and nulls
+pub const ORDINAL_UNKNOWN: f64 = -1.0;
+
+/// Check the settings of `encoding` before any data is read
+pub fn check_encoding(encoding: &CategoryEncoding) -> Result<()> {
+    if let EncodingMethod::Target {
+        target,
+        smoothing,
+        folds,
+    } = &encoding.method
+    {
+        if target.is_empty() {
+            bail!("Target encoding needs a target column");
+        }
+        if !smoothing.is_finite() || *smoothing < 0.0 {
+            bail!("Smoothing must be zero or more, got {smoothing}");
+        }
+        if *folds < 2 {
+            bail!("Target encoding needs at least 2 folds, got {folds}");
+        }
+    }
+    Ok(())
+}
+
+/// `encoding` with its mapping learned from `column` of `lf`
+pub fn learn_encoding(
+    lf: &LazyFrame,
+    column: &str,
+    encoding: &CategoryEncoding,
+) -> Result<CategoryEncoding> {
+    check_encoding(encoding)?;
+    let values = col(column).cast(DataType::String).alias("value");
+    let (mapping, unknown) = match &encoding.method {
+        EncodingMethod::Ordinal => {
+            let df = lf
+                .clone()
+                .select([values.drop_nulls().unique()])
+                .collect()
+                .with_context(|| format!("Failed to read the values of '{column}'"))?;
+            let mut distinct: Vec<String> = df
+                .column("value")?
+                .str()?
+                .into_iter()
+                .flatten()
+                .map(str::to_owned)
+                .collect();
+            distinct.sort();
+            let mapping = distinct
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| (value, i as f64))
+                .collect();
+            (mapping, ORDINAL_UNKNOWN)
+        }
+        EncodingMethod::Target {
+            target, smoothing, ..
+        } => {
+            let y = target_expr(lf, target)?;
+            let prior = target_mean(lf, y.clone())?;
+            let df = lf
+                .clone()
+                .select([values, y.alias("y")])
+                .filter(col("value").is_not_null().and(col("y").is_not_null()))
+                .group_by([col("value")])
+                .agg([
+                    col("y").sum().alias("sum"),
+                    col("y").count().cast(DataType::Float64).alias("n"),
+                ])
+                .collect()
+                .with_context(|| format!("Failed to learn target means of '{column}'"))?;
+            let sums = df.column("sum")?.f64()?;
+            let counts = df.column("n")?.f64()?;
+            let mut mapping = BTreeMap::new();
+            for (i, value) in df.column("value")?.str()?.into_iter().enumerate() {
+                if let (Some(value), Some(sum), Some(n)) = (value, sums.get(i), counts.get(i)) {
+                    mapping.insert(
+                        value.to_owned(),
+                        (sum + prior * smoothing) / (n + smoothing),
+                    );
+                }
+            }
+            (mapping, prior)
+        }
+    };
+    Ok(CategoryEncoding {
+        method: encoding.method.clone(),
+        mapping,
+        unknown: Some(encoding.unknown.unwrap_or(unknown)),
+    })
+}
+
+/// `expr` with each value replaced by its number in the mapping of
+/// `encoding`; other values and nulls get its `unknown` number
+pub fn encoding_expr(expr: Expr, encoding: &CategoryEncoding) -> Expr {
+    let values = expr.cast(DataType::String);
+    let unknown = encoding.unknown.unwrap_or(match encoding.method {
+        EncodingMethod::Ordinal => ORDINAL_UNKNOWN,
+        EncodingMethod::Target { .. } => 0.0,
+    });
+
+    // One branch per number rather than per value, as in `map_values`
+    let mut by_code: BTreeMap<u64, (f64, Vec<&str>)> = BTreeMap::new();
+    for (value, &code) in &encoding.mapping {
+        by_code
+            .entry(code.to_bits())
+            .or_insert_with(|| (code, Vec::new()))
+            .1
+            .push(value.as_str());
+    }
+    let mut out = lit(unknown);
+    for (code, values_with_code) in by_code.into_values() {
+        let values_with_code = Series::new("values".into(), values_with_code);
+        out = when(values.clone().is_in(lit(values_with_code)))
+            .then(lit(code))
+            .otherwise(out);
+    }
+    match encoding.method {
+        EncodingMethod::Ordinal => out.cast(DataType::Int64),
+        EncodingMethod::Target { .. } => out.cast(DataType::Float64),
+    }
+}
+
+/// Replace `column` of `lf` with its encoding. An encoding without a
+/// mapping is learned from `lf` first, and target encodings then encode
+/// each row from the other folds. Returns the encoding used, with its
+/// mapping, so it can be stored and applied to new data.
+pub fn encode_column(
+    lf: LazyFrame,
+    column: &str,
+    encoding: &CategoryEncoding,
+) -> Result<(LazyFrame, CategoryEncoding)> {
+    if !encoding.mapping.is_empty() {
+        let encoded = lf.with_column(encoding_expr(col(column), encoding).alias(column));
+        return Ok((encoded, encoding.clone()));
+    }
+
+    let learned = learn_encoding(&lf, column, encoding)?;
+    let EncodingMethod::Target {
+        target,
+        smoothing,
+        folds,
+    } = &learned.method
+    else {
+        let encoded = lf.with_column(encoding_expr(col(column), &learned).alias(column));
+        return Ok((encoded, learned));
+    };
+
+    let y = target_expr(&lf, target)?;
+    let prior = target_mean(&lf, y.clone())?;
+    let encoded = out_of_fold_expr(column, y, *folds, *smoothing, prior).alias(column);
+    let lf = lf
+        .with_row_index(ROW_COLUMN, None)
+        .with_column(encoded)
+        .select([col("*").exclude([ROW_COLUMN])]);
+    Ok((lf, learned))
+}
+
+/// Smoothed target mean of each row's value over the rows in other folds.
+/// Folds are assigned round-robin by row number, so the result does not
+/// depend on a seed.
+fn out_of_fold_expr(column: &str, y: Expr, folds: u32, smoothing: f64, prior: f64) -> Expr {
+    let value = col(column).cast(DataType::String);
+    let fold = col(ROW_COLUMN).cast(DataType::Int64) % lit(i64::from(folds));
+    let known = y.clone().is_not_null().cast(DataType::Float64);
+    let y = y.fill_null(lit(0.0));
+
+    let sum = y.clone().sum().over([value.clone()]) - y.sum().over([value.clone(), fold.clone()]);
+    let n = known.clone().sum().over([value.clone()]) - known.sum().over([value.clone(), fold]);
+    // Values only seen in their own fold have nothing to learn from
+    let weight = n + lit(smoothing);
+    when(value.is_null().or(weight.clone().eq(lit(0.0))))
+        .then(lit(prior))
+        .otherwise((sum + lit(prior * smoothing)) / weight)
+        .cast(DataType::Float64)
+}
+
+/// `target` of `lf` as numbers; text targets cannot be averaged
+fn target_expr(lf: &LazyFrame, target: &str) -> Result<Expr> {
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let dtype = schema
+        .get(target)
+        .with_context(|| format!("Target column '{target}' not found"))?;
+    if !dtype.is_numeric() && dtype != &DataType::Boolean {
+        bail!("Target column '{target}' is {dtype}; target encoding needs numbers or true/false");
+    }
+    Ok(col(target).cast(DataType::Float64))
+}
+
+fn target_mean(lf: &LazyFrame, y: Expr) -> Result<f64> {
+    let df = lf
+        .clone()
+        .select([y.mean().alias("mean")])
+        .collect()
+        .context("Failed to average the target")?;
+    Ok(df.column("mean")?.f64()?.get(0).unwrap_or(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(
+        df: &DataFrame,
+        encoding: &CategoryEncoding,
+    ) -> (Vec<Option<f64>>, CategoryEncoding) {
+        let (lf, learned) = encode_column(df.clone().lazy(), "city", encoding).unwrap();
+        let out = lf.collect().unwrap();
+        let codes = out
+            .column("city")
+            .unwrap()
+            .cast(&DataType::Float64)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        (codes, learned)
+    }
+
+    fn encoding(method: EncodingMethod) -> CategoryEncoding {
+        CategoryEncoding {
+            method,
+            mapping: BTreeMap::new(),
+            unknown: None,
+        }
+    }
+
+    #[test]
+    fn test_ordinal_encoding_is_learned_then_reused() {
+        let df = df!("city" => [Some("york"), Some("bath"), None, Some("york")]).unwrap();
+        let (codes, learned) = encoded(&df, &encoding(EncodingMethod::Ordinal));
+        assert_eq!(codes, vec![Some(1.0), Some(0.0), Some(-1.0), Some(1.0)]);
+
+        let new = df!("city" => ["bath", "leeds"]).unwrap();
+        let (codes, reused) = encoded(&new, &learned);
+        assert_eq!(codes, vec![Some(0.0), Some(-1.0)]);
+        assert_eq!(reused, learned);
+    }
+
+    #[test]
+    fn test_target_encoding_leaves_out_own_fold() {
+        let df = df!(
+            "city" => ["a", "a", "a", "a", "b", "b"],
+            "sold" => [1.0, 0.0, 1.0, 0.0, 1.0, 1.0],
+        )
+        .unwrap();
+        let method = EncodingMethod::Target {
+            target: "sold".to_owned(),
+            smoothing: 0.0,
+            folds: 2,
+        };
+        let (codes, learned) = encoded(&df, &encoding(method));
+        // Rows alternate between folds: 'a' has 1s in fold 0 and 0s in fold 1
+        assert_eq!(
+            codes,
+            vec![
+                Some(0.0),
+                Some(1.0),
+                Some(0.0),
+                Some(1.0),
+                Some(1.0),
+                Some(1.0)
+            ]
+        );
+        assert_eq!(learned.mapping["a"], 0.5);
+        assert_eq!(learned.mapping["b"], 1.0);
+
+        let new = df!("city" => ["a", "c"], "sold" => [0.0, 0.0]).unwrap();
+        let (codes, _) = encoded(&new, &learned);
+        assert_eq!(codes, vec![Some(0.5), learned.unknown]);
+    }
+
+    #[test]
+    fn test_target_encoding_needs_numeric_target() {
+        let df = df!("city" => ["a", "b"], "label" => ["x", "y"]).unwrap();
+        let method = EncodingMethod::Target {
+            target: "label".to_owned(),
+            smoothing: 1.0,
+            folds: 5,
+        };
+        assert!(encode_column(df.lazy(), "city", &encoding(method)).is_err());
+    }
+}
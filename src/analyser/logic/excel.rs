@@ -0,0 +1,294 @@
+//! Excel and OpenDocument workbooks.
+//!
+//! A workbook path loads its first sheet. Other sheets are addressed as
+//! `book.xlsx#Sheet2`, so a single sheet can go anywhere a file path can:
+//! analysis, lifecycle datasets and pipelines. The suffix is only read as a
+//! sheet name when no file exists at the full path.
+//!
+//! The first row of a sheet is the header, with merged header cells and
+//! footer rows handled as in [`super::layout`]. Columns holding only whole
+//! numbers become Int64, other numeric columns Float64, and dates Datetime;
+//! anything mixed is read as text.
+
+use anyhow::{Context as _, Result};
+use calamine::{Data, DataType as _, Range, Reader as _, open_workbook_auto};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::io::{ColumnMapping, DuplicateColumnPolicy};
+use super::layout::{LayoutOptions, LayoutReport, drop_footer_rows, fill_merged_headers};
+use super::types::AnalysisResponse;
+
+/// File extensions read as workbooks
+pub const WORKBOOK_EXTENSIONS: [&str; 4] = ["xlsx", "xlsm", "xls", "ods"];
+
+pub fn is_workbook(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| WORKBOOK_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Workbook file and sheet named by `path`, or `None` if it is not a
+/// workbook. A plain workbook path has no sheet, meaning the first.
+pub fn workbook_source(path: &Path) -> Option<(PathBuf, Option<String>)> {
+    if is_workbook(path) {
+        return Some((path.to_path_buf(), None));
+    }
+    if path.exists() {
+        return None;
+    }
+    // Sheet names may contain '#' themselves, so split after the extension
+    let text = path.to_str()?;
+    let split = text
+        .match_indices('#')
+        .map(|(i, _)| i)
+        .find(|&i| is_workbook(Path::new(&text[..i])))?;
+    let sheet = &text[split + 1..];
+    (!sheet.is_empty()).then(|| (PathBuf::from(&text[..split]), Some(sheet.to_owned())))
+}
+
+/// Path addressing `sheet` of the workbook at `path`
+pub fn sheet_path(path: &Path, sheet: &str) -> String {
+    format!("{}#{sheet}", path.display())
+}
+
+/// File on disk behind `path`, which may name a sheet
+pub fn source_file(path: &Path) -> PathBuf {
+    workbook_source(path).map_or_else(|| path.to_path_buf(), |(file, _)| file)
+}
+
+/// Sheet names in workbook order
+pub fn list_sheets(path: &Path) -> Result<Vec<String>> {
+    let workbook = open_workbook_auto(path)
+        .with_context(|| format!("Failed to open workbook {}", path.display()))?;
+    Ok(workbook.sheet_names())
+}
+
+/// Read `sheet` of the workbook at `path`, or its first sheet, with the
+/// default layout fixes and duplicate column policy
+pub fn read_sheet(path: &Path, sheet: Option<&str>) -> Result<DataFrame> {
+    let (df, report) = read_sheet_with_layout(
+        path,
+        sheet,
+        LayoutOptions::default(),
+        DuplicateColumnPolicy::default(),
+    )?;
+    report.log(&path.display().to_string());
+    Ok(df)
+}
+
+/// Like [`read_sheet`] with explicit layout fixes and duplicate column
+/// policy, also returning what the fixes changed
+pub fn read_sheet_with_layout(
+    path: &Path,
+    sheet: Option<&str>,
+    layout: LayoutOptions,
+    policy: DuplicateColumnPolicy,
+) -> Result<(DataFrame, LayoutReport)> {
+    let mut workbook = open_workbook_auto(path)
+        .with_context(|| format!("Failed to open workbook {}", path.display()))?;
+    let name = match sheet {
+        Some(name) => name.to_owned(),
+        None => workbook
+            .sheet_names()
+            .first()
+            .cloned()
+            .with_context(|| format!("Workbook {} has no sheets", path.display()))?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .with_context(|| format!("Failed to read sheet '{name}' of {}", path.display()))?;
+    range_to_df_with_layout(&range, layout, policy)
+        .with_context(|| format!("Failed to load sheet '{name}'"))
+}
+
+/// Build a frame from a sheet's cells, taking the first row as the header,
+/// without layout fixes
+pub fn range_to_df(range: &Range<Data>) -> Result<DataFrame> {
+    let no_fixes = LayoutOptions {
+        fill_merged_headers: false,
+        drop_footer_rows: false,
+    };
+    Ok(range_to_df_with_layout(range, no_fixes, DuplicateColumnPolicy::default())?.0)
+}
+
+/// [`range_to_df`] with the `layout` fixes applied, and repeated header
+/// names resolved by `policy`
+pub fn range_to_df_with_layout(
+    range: &Range<Data>,
+    layout: LayoutOptions,
+    policy: DuplicateColumnPolicy,
+) -> Result<(DataFrame, LayoutReport)> {
+    let mut report = LayoutReport::default();
+    let mut rows = range.rows();
+    let Some(header) = rows.next() else {
+        return Ok((DataFrame::empty(), report));
+    };
+    let body: Vec<&[Data]> = rows.collect();
+
+    let mut headers: Vec<String> = header.iter().map(|cell| cell.to_string()).collect();
+    if layout.fill_merged_headers {
+        report.filled_headers = fill_merged_headers(&mut headers, |position| {
+            body.iter()
+                .any(|row| row.get(position).is_some_and(|cell| !cell.is_empty()))
+        });
+    }
+    let headers: Vec<String> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, name)| match name.trim() {
+            "" => format!("column_{}", i + 1),
+            name => name.to_owned(),
+        })
+        .collect();
+
+    // Same rule as CSV: the policy only matters if a name repeats
+    let unique: HashSet<&String> = headers.iter().collect();
+    let policy = if unique.len() == headers.len() {
+        DuplicateColumnPolicy::AutoSuffix
+    } else {
+        policy
+    };
+    let mapping = ColumnMapping::resolve(&headers, policy)?;
+    if let Some(notice) = mapping.notice() {
+        crate::config::log_event("Analyser", &notice);
+    }
+
+    let columns: Vec<Column> = mapping
+        .columns
+        .iter()
+        .map(|origin| {
+            let cells: Vec<&Data> = body
+                .iter()
+                .map(|row| row.get(origin.position).unwrap_or(&Data::Empty))
+                .collect();
+            cells_to_column(&origin.name, &cells)
+        })
+        .collect::<Result<_>>()?;
+    let mut df = DataFrame::new(columns).context("Failed to build sheet data")?;
+    if layout.drop_footer_rows {
+        df = drop_footer_rows(df, &mut report)?;
+    }
+    Ok((df, report))
+}
+
+/// One column of cells, typed by what its non-empty cells hold
+fn cells_to_column(name: &str, cells: &[&Data]) -> Result<Column> {
+    let name: PlSmallStr = name.into();
+    let filled: Vec<&Data> = cells
+        .iter()
+        .copied()
+        .filter(|cell| !matches!(cell, Data::Empty))
+        .collect();
+
+    let series = if filled.is_empty() {
+        Series::full_null(name, cells.len(), &DataType::String)
+    } else if filled.iter().all(|cell| whole_number(cell).is_some()) {
+        Series::new(
+            name,
+            cells.iter().map(|c| whole_number(c)).collect::<Vec<_>>(),
+        )
+    } else if filled
+        .iter()
+        .all(|cell| matches!(cell, Data::Int(_) | Data::Float(_)))
+    {
+        Series::new(name, cells.iter().map(|c| c.as_f64()).collect::<Vec<_>>())
+    } else if filled.iter().all(|cell| matches!(cell, Data::Bool(_))) {
+        Series::new(name, cells.iter().map(|c| c.get_bool()).collect::<Vec<_>>())
+    } else if filled.iter().all(|cell| cell.as_datetime().is_some()) {
+        let millis: Vec<Option<i64>> = cells
+            .iter()
+            .map(|c| c.as_datetime().map(|dt| dt.and_utc().timestamp_millis()))
+            .collect();
+        Series::new(name, millis).cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?
+    } else {
+        let text: Vec<Option<String>> = cells
+            .iter()
+            .map(|c| (!matches!(c, Data::Empty)).then(|| c.to_string()))
+            .collect();
+        Series::new(name, text)
+    };
+    Ok(series.into())
+}
+
+/// Integer value of a cell holding a whole number. Spreadsheets store most
+/// numbers as floats, so integral floats count.
+fn whole_number(cell: &Data) -> Option<i64> {
+    match cell {
+        Data::Int(n) => Some(*n),
+        Data::Float(f) if f.fract() == 0.0 && f.abs() < 9.0e15 => Some(*f as i64),
+        _ => None,
+    }
+}
+
+/// Size and columns of one sheet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SheetOverview {
+    pub name: String,
+    pub row_count: usize,
+    pub column_count: usize,
+    pub columns: Vec<String>,
+}
+
+/// A column name found in more than one sheet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SharedColumn {
+    pub name: String,
+    pub sheets: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkbookOverview {
+    pub sheets: Vec<SheetOverview>,
+    /// Columns in two or more sheets, most widely shared first
+    pub shared_columns: Vec<SharedColumn>,
+}
+
+impl WorkbookOverview {
+    pub fn new(sheets: Vec<SheetOverview>) -> Self {
+        let mut by_name: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for sheet in &sheets {
+            for column in &sheet.columns {
+                by_name
+                    .entry(column.as_str())
+                    .or_default()
+                    .push(sheet.name.clone());
+            }
+        }
+        let mut shared_columns: Vec<SharedColumn> = by_name
+            .into_iter()
+            .filter(|(_, in_sheets)| in_sheets.len() > 1)
+            .map(|(name, in_sheets)| SharedColumn {
+                name: name.to_owned(),
+                sheets: in_sheets,
+            })
+            .collect();
+        // Stable, so ties stay in name order
+        shared_columns.sort_by(|a, b| b.sheets.len().cmp(&a.sheets.len()));
+        Self {
+            sheets,
+            shared_columns,
+        }
+    }
+}
+
+/// A sheet that could not be analysed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetFailure {
+    pub sheet: String,
+    pub error: String,
+}
+
+/// Every sheet of a workbook analysed in one go
+#[derive(Debug, Serialize)]
+pub struct WorkbookAnalysis {
+    pub path: String,
+    pub overview: WorkbookOverview,
+    /// One analysis per sheet, in workbook order. Each `path` addresses its
+    /// sheet (see [`sheet_path`]).
+    pub sheets: Vec<AnalysisResponse>,
+    /// Sheets that could not be read or analysed
+    pub failed: Vec<SheetFailure>,
+}
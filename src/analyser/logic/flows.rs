@@ -1,8 +1,22 @@
-use super::analysis::analyse_df_lazy;
+use super::analysis::{analyse_df_lazy, analyse_df_lazy_with_options};
+use super::batches::write_csv_in_batches;
 use super::cleaning::clean_df_lazy;
-use super::io::load_df_lazy;
+use super::excel::{
+    SheetFailure, SheetOverview, WorkbookAnalysis, WorkbookOverview, list_sheets, sheet_path,
+    source_file,
+};
+use super::headerless::{
+    HeaderlessNaming, header_looks_like_data, load_df_lazy_as, load_df_lazy_headerless,
+};
+use super::io::{LoadedFrame, load_df_lazy_with_layout};
+use super::layout::LayoutReport;
+use super::naming::headers_missing;
+use super::orientation::apply_orientation;
 use super::types::{AnalysisResponse, ColumnCleanConfig};
-use crate::analyser::db::DbClient;
+use super::usage::{DatasetUsage, apply_usage_suggestions};
+use crate::analyser::db::{DbClient, DdlMode, DdlReport, DdlSuggestions};
+use crate::utils::memory::{MemoryGovernor, estimate_file};
+use crate::utils::runs::{Run, RunKind};
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
 use sqlx::postgres::PgConnectOptions;
@@ -56,10 +70,75 @@ pub async fn push_to_db_flow(
     table_name: String,
     configs: HashMap<String, ColumnCleanConfig>,
 ) -> Result<()> {
-    let lf = load_df_lazy(&path).context("Failed to load data")?;
+    push_to_db_flow_with_profile(path, opts, schema_name, table_name, configs, false).await
+}
+
+/// Like [`push_to_db_flow`], and with `profile` also appends per-column
+/// statistics and health metrics of the cleaned data to `<table>_profile`
+pub async fn push_to_db_flow_with_profile(
+    path: PathBuf,
+    opts: PgConnectOptions,
+    schema_name: String,
+    table_name: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+    profile: bool,
+) -> Result<()> {
+    let options = PushOptions {
+        profile,
+        ..PushOptions::default()
+    };
+    push_to_db_flow_with_options(path, opts, schema_name, table_name, configs, options)
+        .await
+        .map(drop)
+}
+
+/// Extras for [`push_to_db_flow_with_options`]
+#[derive(Debug, Clone, Default)]
+pub struct PushOptions {
+    /// Append column statistics to `<table>_profile`
+    pub profile: bool,
+    /// Suggest (or apply) indexes and constraints from the column profile
+    pub ddl: DdlMode,
+    /// The connection is saved as read-only, so the push is refused
+    pub read_only: bool,
+    /// Read the file as a CSV file without a header row, naming its columns
+    /// by position
+    pub header_names: Option<HeaderlessNaming>,
+}
+
+/// Like [`push_to_db_flow`], with the extras in `options`. Returns the DDL
+/// suggestions unless `options.ddl` is [`DdlMode::Off`].
+pub async fn push_to_db_flow_with_options(
+    path: PathBuf,
+    opts: PgConnectOptions,
+    schema_name: String,
+    table_name: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+    options: PushOptions,
+) -> Result<Option<DdlReport>> {
+    if options.read_only {
+        anyhow::bail!("Connection is read-only: pushing data is blocked");
+    }
+    let lf =
+        load_df_lazy_as(&path, options.header_names.as_ref()).context("Failed to load data")?;
 
     let mut cleaned_lf = clean_df_lazy(lf, &configs, false).context("Cleaning failed")?;
 
+    let summaries = if options.profile || options.ddl != DdlMode::Off {
+        let settings = crate::config::load_app_config().settings;
+        Some(
+            analyse_df_lazy_with_options(
+                cleaned_lf.clone(),
+                0.0,
+                settings.analysis_sample_size as usize,
+                &settings.resolved_analysis_options(),
+            )
+            .context("Failed to profile the pushed data")?,
+        )
+    } else {
+        None
+    };
+
     let schema = cleaned_lf
         .collect_schema()
         .map_err(|e| anyhow::anyhow!(e))?;
@@ -71,58 +150,161 @@ pub async fn push_to_db_flow(
 
     crate::config::log_event(
         "Database",
-        "Sinking to temp CSV for database push (streaming)...",
+        "Writing temp CSV for database push in batches...",
     );
 
-    cleaned_lf
-        .with_streaming(true)
-        .sink_csv(&temp_path, Default::default(), None)
-        .context("Failed to sink to CSV for DB push")?;
+    write_csv_in_batches(cleaned_lf.with_streaming(true), &temp_path)
+        .context("Failed to write CSV for DB push")?;
 
-    let client = DbClient::connect(opts).await?;
+    let client = DbClient::connect(opts)
+        .await?
+        .with_read_only(options.read_only);
     client
         .push_from_csv_file(&temp_path, &schema, Some(&schema_name), Some(&table_name))
         .await?;
+    let Some(summaries) = summaries else {
+        // _temp_guard will automatically clean up the temp file when dropped
+        return Ok(None);
+    };
+    if options.profile {
+        client
+            .push_profile(&summaries, Some(&schema_name), Some(&table_name))
+            .await?;
+    }
+    if options.ddl == DdlMode::Off {
+        return Ok(None);
+    }
 
-    // _temp_guard will automatically clean up the temp file when dropped
-    Ok(())
+    let suggestions =
+        DdlSuggestions::from_profile(&summaries, Some(&schema_name), Some(&table_name));
+    let mut report = DdlReport {
+        script: suggestions.script(),
+        ..DdlReport::default()
+    };
+    if options.ddl == DdlMode::Apply {
+        report.failures = client.apply_ddl(&suggestions).await;
+        report.applied = true;
+        crate::config::log_event(
+            "Database",
+            &format!(
+                "Applied {} suggested DDL statements to {} ({} failed)",
+                suggestions.suggestions.len(),
+                suggestions.table,
+                report.failures.len()
+            ),
+        );
+    }
+    report.suggestions = suggestions;
+    Ok(Some(report))
 }
 
 pub fn generate_auto_clean_configs(lf: LazyFrame) -> Result<HashMap<String, ColumnCleanConfig>> {
+    generate_auto_clean_configs_with_usage(lf, None)
+}
+
+/// Auto-clean configs with the defaults suggested by past cleans of the
+/// dataset applied on top
+pub fn generate_auto_clean_configs_with_usage(
+    lf: LazyFrame,
+    usage: Option<&DatasetUsage>,
+) -> Result<HashMap<String, ColumnCleanConfig>> {
     let summaries =
         analyse_df_lazy(lf, 0.0, 10_000).context("Failed to analyse for auto-cleaning")?;
 
+    // Without a header row, names from the content beat `column_1`
+    let use_suggestions = headers_missing(&summaries);
     let mut configs = HashMap::new();
     for summary in summaries {
+        let new_name = match &summary.suggested_name {
+            Some(suggested) if use_suggestions => suggested.clone(),
+            _ => summary.standardised_name.clone(),
+        };
         let mut config = ColumnCleanConfig {
-            new_name: summary.standardised_name.clone(),
+            new_name,
             ..Default::default()
         };
         summary.apply_advice_to_config(&mut config);
         configs.insert(summary.name.clone(), config);
     }
+    if let Some(usage) = usage {
+        apply_usage_suggestions(&mut configs, &usage.suggestions());
+    }
     Ok(configs)
 }
 
+/// Analyse the file at `path` as one analysis run (see [`crate::utils::runs`])
 pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
+    analyze_file_flow_with_header(path, None).await
+}
+
+/// Like [`analyze_file_flow`], reading a CSV file without a header row when
+/// `header_names` is set
+pub async fn analyze_file_flow_with_header(
+    path: PathBuf,
+    header_names: Option<HeaderlessNaming>,
+) -> Result<AnalysisResponse> {
+    let run = Run::start(RunKind::Analysis, &path.display().to_string());
+    let result = run.instrument(analyze_file(path, header_names)).await;
+    run.finish(&result);
+    result
+}
+
+async fn analyze_file(
+    path: PathBuf,
+    header_names: Option<HeaderlessNaming>,
+) -> Result<AnalysisResponse> {
     let start = std::time::Instant::now();
-    let file_size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    let file = source_file(&path);
+    let file_size = std::fs::metadata(&file).map(|m| m.len()).unwrap_or(0);
     let path_str = path.to_string_lossy().to_string();
 
     // Load config to get custom sample size
     let config = crate::config::load_app_config();
     let custom_sample_size = config.settings().analysis_sample_size as usize;
 
-    let lf = load_df_lazy(&path).context("Failed to probe file")?;
+    // Without a header there are no header cells to fix
+    let LoadedFrame {
+        lf,
+        column_mapping,
+        layout,
+    } = match &header_names {
+        Some(naming) => LoadedFrame {
+            lf: load_df_lazy_headerless(&path, naming).context("Failed to probe file")?,
+            column_mapping: None,
+            layout: LayoutReport::default(),
+        },
+        None => load_df_lazy_with_layout(
+            &path,
+            Some(config.settings().duplicate_column_policy),
+            config.settings().layout,
+            config.settings().date_order,
+        )
+        .context("Failed to probe file")?,
+    };
+    let (lf, orientation) = apply_orientation(lf, config.settings().auto_transpose)?;
     let mut lf_for_schema = lf.clone();
     let schema = lf_for_schema
         .collect_schema()
         .map_err(|e| anyhow::anyhow!(e))?;
     let col_count = schema.len();
+    let is_csv = file
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let header_is_data = is_csv
+        && header_names.is_none()
+        && header_looks_like_data(
+            &schema
+                .iter_names()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>(),
+        );
 
     // Use file size to determine sampling (avoid expensive row counting that materializes data)
-    // Rule of thumb: files > 20MB or wide schemas (>50 cols) should be sampled
-    let should_sample = file_size > 20 * 1024 * 1024 || col_count > 50;
+    // Rule of thumb: files > 20MB or wide schemas (>50 cols) should be sampled,
+    // as should anything over the memory budget
+    let governor = MemoryGovernor::new(config.settings().memory_budget.clone());
+    let over_budget = !governor.fits(estimate_file(&file));
+    let should_sample = file_size > 20 * 1024 * 1024 || col_count > 50 || over_budget;
 
     // Count true total rows from original LazyFrame (streaming, doesn't materialize)
     let true_total_rows = match lf
@@ -144,6 +326,8 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
         Err(_) => 0,
     };
 
+    crate::utils::check_aborted("Analysis")?;
+
     // Use custom sample size as the target, but scale for very wide datasets to prevent OOM
     let target_sample_rows = custom_sample_size;
     let sampling_strategy = config.settings().sampling_strategy.as_str();
@@ -161,6 +345,22 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
             // For normal datasets, use full target
             std::cmp::min(500_000, target_sample_rows as u32)
         };
+        // Fast sampling collects twice the sample before drawing from it
+        let sample_rows = match governor.rows_within(&schema) {
+            Some(fit) if (sample_rows as usize).saturating_mul(2) > fit => {
+                let capped = u32::try_from(fit / 2).unwrap_or(u32::MAX).max(1);
+                crate::config::log_event(
+                    "Analyser",
+                    &format!(
+                        "Sample reduced from {} to {} rows to stay within the memory budget",
+                        crate::utils::fmt_count(sample_rows as usize),
+                        crate::utils::fmt_count(capped as usize)
+                    ),
+                );
+                capped
+            }
+            _ => sample_rows,
+        };
 
         // Select sampling method based on strategy and file size
         let (sampled_df, method_used) = match (sampling_strategy, true_total_rows) {
@@ -214,6 +414,8 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
         (lf.clone(), false, 0, "none")
     };
 
+    crate::utils::check_aborted("Analysis")?;
+
     // Count sampled rows (what we're actually analyzing)
     let sampled_rows = if is_sampled {
         sampled_rows_count as usize
@@ -222,7 +424,7 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
     };
 
     // Use fixed 5% trim for trimmed_mean calculation
-    let mut response = crate::analyser::logic::analysis::run_full_analysis_streaming(
+    let mut response = crate::analyser::logic::analysis::run_full_analysis_streaming_with_options(
         lf_for_analysis,
         path_str,
         file_size,
@@ -231,6 +433,7 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
         0.05,
         custom_sample_size,
         start,
+        &config.settings().resolved_analysis_options(),
     )?;
 
     if is_sampled && let Some(first_col) = response.summary.get_mut(0) {
@@ -262,5 +465,97 @@ pub async fn analyze_file_flow(path: PathBuf) -> Result<AnalysisResponse> {
         ));
     }
 
+    response.column_mapping = column_mapping;
+    response.orientation = orientation;
+    response.layout = (!layout.is_empty()).then_some(layout);
+    response.header_looks_like_data = header_is_data;
+    response.header_names = header_names;
+
+    if config.settings().auto_dictionary_snapshot {
+        let dictionary_dir = crate::dictionary::default_dictionary_dir();
+        match crate::dictionary::save_analysis_snapshot(
+            &path,
+            &response.summary,
+            response.total_row_count,
+            &response.health,
+            &dictionary_dir,
+        ) {
+            Ok(snapshot) => crate::config::log_event(
+                "Dictionary",
+                &format!(
+                    "Created dictionary snapshot {} for {}",
+                    snapshot.snapshot_id,
+                    path.display()
+                ),
+            ),
+            // The analysis is still usable without its snapshot
+            Err(e) => crate::config::log_event(
+                "Dictionary",
+                &format!("Failed to create dictionary snapshot: {e:#}"),
+            ),
+        }
+        let retention = config.settings().dictionary_retention;
+        if retention.is_enabled() {
+            match crate::dictionary::prune_snapshots(&dictionary_dir, &retention) {
+                Ok(report) if report.removed() > 0 => crate::config::log_event(
+                    "Dictionary",
+                    &format!(
+                        "Pruned {} dictionary snapshots ({})",
+                        report.removed(),
+                        crate::utils::fmt_bytes(report.bytes_freed)
+                    ),
+                ),
+                Ok(_) => {}
+                Err(e) => crate::config::log_event(
+                    "Dictionary",
+                    &format!("Failed to prune dictionary snapshots: {e:#}"),
+                ),
+            }
+        }
+    }
+
     Ok(response)
 }
+
+/// Analyse every sheet of the workbook at `path` as its own file, and
+/// compare their sizes and column names. A sheet that fails is reported
+/// without stopping the others.
+pub async fn analyze_workbook_flow(path: PathBuf) -> Result<WorkbookAnalysis> {
+    let sheets = list_sheets(&path)?;
+    let mut analyses = Vec::with_capacity(sheets.len());
+    let mut failed = Vec::new();
+    for sheet in sheets {
+        if crate::utils::is_aborted() {
+            anyhow::bail!("Workbook analysis cancelled by user");
+        }
+        crate::config::log_event(
+            "Analyser",
+            &format!("Analysing sheet '{sheet}' of {}", path.display()),
+        );
+        match analyze_file_flow(PathBuf::from(sheet_path(&path, &sheet))).await {
+            Ok(response) => analyses.push((sheet, response)),
+            Err(e) => failed.push(SheetFailure {
+                sheet,
+                error: format!("{e:#}"),
+            }),
+        }
+    }
+
+    let overview = WorkbookOverview::new(
+        analyses
+            .iter()
+            .map(|(sheet, response)| SheetOverview {
+                name: sheet.clone(),
+                row_count: response.total_row_count,
+                column_count: response.column_count,
+                columns: response.summary.iter().map(|c| c.name.clone()).collect(),
+            })
+            .collect(),
+    );
+    Ok(WorkbookAnalysis {
+        path: path.to_string_lossy().to_string(),
+        overview,
+        sheets: analyses.into_iter().map(|(_, response)| response).collect(),
+        failed,
+    })
+}
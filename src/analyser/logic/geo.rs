@@ -0,0 +1,518 @@
+//! Locations in columns: latitude and longitude pairs, and geometries
+//! written as WKT or GeoJSON.
+//!
+//! [`profile_geo_columns`] runs after the per-column statistics of an
+//! analysis and gives the columns holding locations [`GeoStats`] instead.
+//! Numeric columns are paired by name: `lat` or `latitude` with `lon`,
+//! `lng`, `long` or `longitude` where the rest of the names agree, as in
+//! `pickup_lat` and `pickup_lon`. Text columns are recognised when nearly
+//! all sampled values are WKT or GeoJSON. Every value is then checked,
+//! counting coordinates out of range, and the valid ones give the bounding
+//! box.
+
+use anyhow::Result;
+use polars::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::batches::{ABORT_BATCH_ROWS, for_each_batch};
+use super::semantic::{MIN_SEMANTIC_SAMPLES, SEMANTIC_MATCH_THRESHOLD};
+use super::types::{BoundingBox, ColumnStats, ColumnSummary, GeoEncoding, GeoStats};
+
+const LATITUDE_NAMES: [&str; 2] = ["lat", "latitude"];
+const LONGITUDE_NAMES: [&str; 4] = ["lon", "lng", "long", "longitude"];
+
+/// Geometry types by their WKT keyword and GeoJSON name
+const GEOMETRY_TYPES: [(&str, &str); 7] = [
+    ("POINT", "Point"),
+    ("LINESTRING", "LineString"),
+    ("POLYGON", "Polygon"),
+    ("MULTIPOINT", "MultiPoint"),
+    ("MULTILINESTRING", "MultiLineString"),
+    ("MULTIPOLYGON", "MultiPolygon"),
+    ("GEOMETRYCOLLECTION", "GeometryCollection"),
+];
+
+/// A geometry read from a string
+#[derive(Debug, PartialEq)]
+struct Geometry {
+    /// GeoJSON name of the type
+    kind: &'static str,
+    /// Coordinates as (longitude, latitude)
+    positions: Vec<(f64, f64)>,
+}
+
+/// Give the columns among `summaries` that hold locations [`GeoStats`],
+/// computed from `lf` after detecting them by name and in `sample_df`
+pub fn profile_geo_columns(
+    lf: &LazyFrame,
+    schema: &Schema,
+    sample_df: &DataFrame,
+    summaries: &mut [ColumnSummary],
+    streaming: bool,
+) -> Result<()> {
+    for (latitude, longitude) in coordinate_pairs(schema) {
+        let (valid_count, invalid_count, bounds) =
+            pair_stats(lf, &latitude, &longitude, streaming)?;
+        let stats = |encoding| GeoStats {
+            encoding,
+            distinct_count: 0,
+            valid_count,
+            invalid_count,
+            bounds,
+            geometry_types: BTreeMap::new(),
+        };
+        set_geo_stats(
+            summaries,
+            &latitude,
+            stats(GeoEncoding::Latitude {
+                longitude: longitude.clone(),
+            }),
+        );
+        set_geo_stats(
+            summaries,
+            &longitude,
+            stats(GeoEncoding::Longitude { latitude }),
+        );
+    }
+
+    for (name, dtype) in schema.iter() {
+        if dtype != &DataType::String {
+            continue;
+        }
+        if let Some(encoding) = sample_geometry_encoding(sample_df, name) {
+            let stats = geometry_stats(lf, name, encoding, streaming)?;
+            set_geo_stats(summaries, name, stats);
+        }
+    }
+    Ok(())
+}
+
+/// Replace the stats of column `name` with `stats`, keeping its distinct
+/// count, and interpret it again
+fn set_geo_stats(summaries: &mut [ColumnSummary], name: &str, mut stats: GeoStats) {
+    let Some(summary) = summaries.iter_mut().find(|s| s.name == name) else {
+        return;
+    };
+    stats.distinct_count = summary.stats.n_distinct();
+    summary.stats = ColumnStats::Geo(stats);
+    summary.interpretation = summary.generate_interpretation();
+    summary.business_summary = summary.generate_business_summary();
+    summary.ml_advice = summary.generate_ml_advice();
+}
+
+/// Numeric columns named as latitudes and longitudes, paired where the
+/// rest of their names agree
+fn coordinate_pairs(schema: &Schema) -> Vec<(String, String)> {
+    let mut latitudes = Vec::new();
+    let mut longitudes = Vec::new();
+    for (name, dtype) in schema.iter() {
+        if !dtype.is_numeric() {
+            continue;
+        }
+        if let Some(key) = coordinate_key(name, &LATITUDE_NAMES) {
+            latitudes.push((key, name.to_string()));
+        } else if let Some(key) = coordinate_key(name, &LONGITUDE_NAMES) {
+            longitudes.push((key, name.to_string()));
+        }
+    }
+    latitudes
+        .into_iter()
+        .filter_map(|(key, latitude)| {
+            let (_, longitude) = longitudes.iter().find(|(k, _)| *k == key)?;
+            Some((latitude, longitude.clone()))
+        })
+        .collect()
+}
+
+/// `name` in lower case with the word from `words` replaced by `*`, so
+/// `Pickup Lat` and `pickup_lon` both give `pickup_*`
+fn coordinate_key(name: &str, words: &[&str]) -> Option<String> {
+    let lower = name.to_lowercase();
+    let tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect();
+    let position = tokens.iter().position(|t| words.contains(t))?;
+    let key: Vec<&str> = tokens
+        .iter()
+        .enumerate()
+        .map(|(i, t)| if i == position { "*" } else { *t })
+        .collect();
+    Some(key.join("_"))
+}
+
+/// Valid and invalid pairs, and the bounding box of the valid ones, of
+/// columns `latitude` and `longitude`. Rows missing either are not counted.
+fn pair_stats(
+    lf: &LazyFrame,
+    latitude: &str,
+    longitude: &str,
+    streaming: bool,
+) -> Result<(usize, usize, Option<BoundingBox>)> {
+    let lat = col(latitude).cast(DataType::Float64);
+    let lon = col(longitude).cast(DataType::Float64);
+    let both = lat.clone().is_not_null().and(lon.clone().is_not_null());
+    let valid = both
+        .clone()
+        .and(lat.clone().gt_eq(lit(-90.0)))
+        .and(lat.clone().lt_eq(lit(90.0)))
+        .and(lon.clone().gt_eq(lit(-180.0)))
+        .and(lon.clone().lt_eq(lit(180.0)));
+
+    let df = lf
+        .clone()
+        .select([
+            both.cast(DataType::UInt64).sum().alias("pairs"),
+            valid.clone().cast(DataType::UInt64).sum().alias("valid"),
+            lat.clone().filter(valid.clone()).min().alias("min_lat"),
+            lat.filter(valid.clone()).max().alias("max_lat"),
+            lon.clone().filter(valid.clone()).min().alias("min_lon"),
+            lon.filter(valid).max().alias("max_lon"),
+        ])
+        .with_streaming(streaming)
+        .collect()?;
+    let count = |name: &str| -> Result<usize> {
+        let value = df
+            .column(name)?
+            .as_materialized_series()
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0);
+        Ok(usize::try_from(value.unwrap_or(0)).unwrap_or(usize::MAX))
+    };
+    let degrees = |name: &str| -> Result<Option<f64>> {
+        Ok(df.column(name)?.as_materialized_series().f64()?.get(0))
+    };
+
+    let pairs = count("pairs")?;
+    let valid_count = count("valid")?;
+    let bounds = match (
+        degrees("min_lat")?,
+        degrees("max_lat")?,
+        degrees("min_lon")?,
+        degrees("max_lon")?,
+    ) {
+        (Some(min_lat), Some(max_lat), Some(min_lon), Some(max_lon)) => Some(BoundingBox {
+            min_lat,
+            max_lat,
+            min_lon,
+            max_lon,
+        }),
+        _ => None,
+    };
+    Ok((valid_count, pairs.saturating_sub(valid_count), bounds))
+}
+
+/// How the sampled values of text column `name` write geometries, if
+/// nearly all of them are geometries
+fn sample_geometry_encoding(sample_df: &DataFrame, name: &str) -> Option<GeoEncoding> {
+    let values: Vec<&str> = sample_df
+        .column(name)
+        .ok()?
+        .str()
+        .ok()?
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.len() < MIN_SEMANTIC_SAMPLES {
+        return None;
+    }
+    let needed = values.len() as f64 * SEMANTIC_MATCH_THRESHOLD;
+    let share = |parse: fn(&str) -> Option<Geometry>| {
+        values.iter().filter(|&&v| parse(v).is_some()).count() as f64
+    };
+    if share(parse_wkt) >= needed {
+        Some(GeoEncoding::Wkt)
+    } else if share(parse_geojson) >= needed {
+        Some(GeoEncoding::GeoJson)
+    } else {
+        None
+    }
+}
+
+/// Read every value of text column `name` as `encoding`, in batches that
+/// stop if the user aborts
+fn geometry_stats(
+    lf: &LazyFrame,
+    name: &str,
+    encoding: GeoEncoding,
+    streaming: bool,
+) -> Result<GeoStats> {
+    let parse: fn(&str) -> Option<Geometry> = match encoding {
+        GeoEncoding::GeoJson => parse_geojson,
+        _ => parse_wkt,
+    };
+    let mut stats = GeoStats {
+        encoding,
+        distinct_count: 0,
+        valid_count: 0,
+        invalid_count: 0,
+        bounds: None,
+        geometry_types: BTreeMap::new(),
+    };
+    let column = lf.clone().select([col(name)]).with_streaming(streaming);
+    for_each_batch(column, ABORT_BATCH_ROWS, |batch| {
+        for value in batch.column(name)?.str()?.into_iter().flatten() {
+            if value.trim().is_empty() {
+                continue;
+            }
+            let geometry =
+                parse(value).filter(|g| g.positions.iter().all(|&(lon, lat)| in_range(lat, lon)));
+            let Some(geometry) = geometry else {
+                stats.invalid_count += 1;
+                continue;
+            };
+            stats.valid_count += 1;
+            *stats
+                .geometry_types
+                .entry(geometry.kind.to_owned())
+                .or_default() += 1;
+            for (lon, lat) in geometry.positions {
+                extend(&mut stats.bounds, lat, lon);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(stats)
+}
+
+fn in_range(lat: f64, lon: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
+}
+
+fn extend(bounds: &mut Option<BoundingBox>, lat: f64, lon: f64) {
+    let b = bounds.get_or_insert(BoundingBox {
+        min_lat: lat,
+        max_lat: lat,
+        min_lon: lon,
+        max_lon: lon,
+    });
+    b.min_lat = b.min_lat.min(lat);
+    b.max_lat = b.max_lat.max(lat);
+    b.min_lon = b.min_lon.min(lon);
+    b.max_lon = b.max_lon.max(lon);
+}
+
+/// Read well-known text, such as `POINT (151.2 -33.9)`, optionally with an
+/// `SRID=...;` prefix and Z or M coordinates
+fn parse_wkt(value: &str) -> Option<Geometry> {
+    let mut text = value.trim().to_ascii_uppercase();
+    if text.starts_with("SRID=") {
+        text = text.split_once(';')?.1.trim_start().to_owned();
+    }
+    let &(keyword, kind) = GEOMETRY_TYPES
+        .iter()
+        .find(|(keyword, _)| text.starts_with(keyword))?;
+    let body = text[keyword.len()..].trim_start();
+    let body = ["ZM", "Z", "M"]
+        .iter()
+        .find_map(|d| body.strip_prefix(d))
+        .unwrap_or(body)
+        .trim();
+    if body == "EMPTY" {
+        return Some(Geometry {
+            kind,
+            positions: Vec::new(),
+        });
+    }
+    if !body.starts_with('(') || !body.ends_with(')') {
+        return None;
+    }
+    let mut depth = 0_i32;
+    for c in body.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth == 0 => return None,
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return None;
+    }
+
+    let mut positions = Vec::new();
+    for part in body.split(['(', ')', ',']) {
+        let mut numbers = Vec::new();
+        for token in part.split_whitespace() {
+            match token.parse::<f64>() {
+                Ok(n) => numbers.push(n),
+                // Members of a geometry collection are named
+                Err(_) if numbers.is_empty() && is_wkt_keyword(token) => {}
+                Err(_) => return None,
+            }
+        }
+        match numbers.as_slice() {
+            [] => {}
+            [x, y] | [x, y, _] | [x, y, _, _] => positions.push((*x, *y)),
+            _ => return None,
+        }
+    }
+    Some(Geometry { kind, positions })
+}
+
+fn is_wkt_keyword(token: &str) -> bool {
+    matches!(token, "Z" | "M" | "ZM" | "EMPTY") || GEOMETRY_TYPES.iter().any(|(k, _)| *k == token)
+}
+
+/// Read a GeoJSON geometry, feature or feature collection. Features are
+/// counted by the type of their geometry.
+fn parse_geojson(value: &str) -> Option<Geometry> {
+    let text = value.trim();
+    if !text.starts_with('{') {
+        return None;
+    }
+    let json: Value = serde_json::from_str(text).ok()?;
+    let mut positions = Vec::new();
+    let kind = geojson_positions(&json, &mut positions)?;
+    Some(Geometry { kind, positions })
+}
+
+/// Add the coordinates of GeoJSON object `json` to `out`, returning its type
+fn geojson_positions(json: &Value, out: &mut Vec<(f64, f64)>) -> Option<&'static str> {
+    match json.get("type")?.as_str()? {
+        "Feature" => match json.get("geometry")? {
+            Value::Null => Some("Feature"),
+            geometry => geojson_positions(geometry, out),
+        },
+        "FeatureCollection" => {
+            for feature in json.get("features")?.as_array()? {
+                geojson_positions(feature, out)?;
+            }
+            Some("FeatureCollection")
+        }
+        "GeometryCollection" => {
+            for geometry in json.get("geometries")?.as_array()? {
+                geojson_positions(geometry, out)?;
+            }
+            Some("GeometryCollection")
+        }
+        name => {
+            let &(_, kind) = GEOMETRY_TYPES.iter().find(|(_, n)| *n == name)?;
+            coordinates(json.get("coordinates")?, out)?;
+            Some(kind)
+        }
+    }
+}
+
+/// Add the positions in nested GeoJSON `coordinates` to `out`
+fn coordinates(value: &Value, out: &mut Vec<(f64, f64)>) -> Option<()> {
+    let items = value.as_array()?;
+    if items.first().is_some_and(Value::is_number) {
+        if !(2..=4).contains(&items.len()) {
+            return None;
+        }
+        out.push((items[0].as_f64()?, items[1].as_f64()?));
+    } else {
+        for item in items {
+            coordinates(item, out)?;
+        }
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wkt_and_geojson() {
+        let point = parse_wkt("POINT (151.2093 -33.8688)").unwrap();
+        assert_eq!(point.kind, "Point");
+        assert_eq!(point.positions, [(151.2093, -33.8688)]);
+
+        let polygon = parse_wkt("SRID=4326;polygon z ((0 0 1, 10 0 1, 10 10 1, 0 0 1))").unwrap();
+        assert_eq!(polygon.kind, "Polygon");
+        assert_eq!(polygon.positions.len(), 4);
+
+        let collection =
+            parse_wkt("GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (3 4, 5 6))").unwrap();
+        assert_eq!(collection.positions, [(1.0, 2.0), (3.0, 4.0), (5.0, 6.0)]);
+        assert!(parse_wkt("MULTIPOINT EMPTY").unwrap().positions.is_empty());
+        for bad in [
+            "POINT (1)",
+            "POINT (1 2",
+            "POINT 1 2",
+            "POINTLESS (1 2)",
+            "1 2",
+        ] {
+            assert_eq!(parse_wkt(bad), None, "{bad}");
+        }
+
+        let feature = parse_geojson(
+            r#"{"type":"Feature","properties":{},"geometry":{"type":"LineString","coordinates":[[1,2],[3,4]]}}"#,
+        )
+        .unwrap();
+        assert_eq!(feature.kind, "LineString");
+        assert_eq!(feature.positions, [(1.0, 2.0), (3.0, 4.0)]);
+        assert_eq!(parse_geojson(r#"{"type":"Point","coordinates":[1]}"#), None);
+        assert_eq!(
+            parse_geojson(r#"{"type":"Circle","coordinates":[1,2]}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_coordinate_pairs_by_name() {
+        let schema = Schema::from_iter([
+            Field::new("Pickup Lat".into(), DataType::Float64),
+            Field::new("pickup_lng".into(), DataType::Float64),
+            Field::new("latitude".into(), DataType::Float64),
+            Field::new("longitude".into(), DataType::Float64),
+            Field::new("lat_label".into(), DataType::String),
+            Field::new("long".into(), DataType::Float64),
+        ]);
+        assert_eq!(
+            coordinate_pairs(&schema),
+            [
+                ("Pickup Lat".to_owned(), "pickup_lng".to_owned()),
+                ("latitude".to_owned(), "longitude".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_analysis_profiles_locations() -> Result<()> {
+        let df = df!(
+            "lat" => [Some(-33.87), Some(-37.81), Some(95.0), None],
+            "lon" => [Some(151.21), Some(144.96), Some(10.0), Some(1.0)],
+            "shape" => [
+                "POINT (151.21 -33.87)",
+                "POINT (144.96 -37.81)",
+                "POINT (200 0)",
+                "LINESTRING (0 0, 1 1)",
+            ],
+        )?;
+        let summaries = super::super::analysis::analyse_df(&df, 0.0)?;
+
+        let ColumnStats::Geo(lat) = &summaries[0].stats else {
+            panic!("lat is not a location column");
+        };
+        assert_eq!(
+            lat.encoding,
+            GeoEncoding::Latitude {
+                longitude: "lon".to_owned()
+            }
+        );
+        assert_eq!((lat.valid_count, lat.invalid_count), (2, 1));
+        let bounds = lat.bounds.unwrap();
+        assert_eq!((bounds.min_lat, bounds.max_lon), (-37.81, 151.21));
+        assert!(
+            summaries[0]
+                .interpretation
+                .iter()
+                .any(|line| line.starts_with("Map extent"))
+        );
+
+        let ColumnStats::Geo(shape) = &summaries[2].stats else {
+            panic!("shape is not a location column");
+        };
+        assert_eq!(shape.encoding, GeoEncoding::Wkt);
+        assert_eq!((shape.valid_count, shape.invalid_count), (3, 1));
+        assert_eq!(shape.geometry_types["Point"], 2);
+        Ok(())
+    }
+}
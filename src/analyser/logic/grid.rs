@@ -0,0 +1,184 @@
+//! Pages of raw rows for the analyser's data grid.
+//!
+//! The grid shows the rows of the file next to the column statistics. It
+//! asks for one page at a time as the user scrolls, so [`grid_page`] reads
+//! only the rows of that page: the file is scanned lazily, filtered and
+//! sorted, then sliced. Values come back as text, as in the cleaning
+//! preview; the row count after filtering is computed only when asked for,
+//! as it needs a pass over the whole file.
+
+use anyhow::{Context as _, Result, bail};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::analysis::display_values;
+use crate::pipeline::dry_run::ColumnPreview;
+use crate::pipeline::executor::count_rows;
+
+/// Rows in a page when the caller does not say
+pub const GRID_PAGE_ROWS: usize = 200;
+
+/// Most rows one page holds
+pub const GRID_MAX_PAGE_ROWS: usize = 2000;
+
+/// How a [`GridFilter`] compares a column with its value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridFilterOp {
+    /// The value as text contains the filter value, ignoring case
+    #[default]
+    Contains,
+    /// The value as text is the filter value
+    Equals,
+    NotEquals,
+    /// Compared as numbers in numeric columns and as text otherwise, so ISO
+    /// dates compare by date
+    GreaterThan,
+    LessThan,
+    IsNull,
+    NotNull,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridFilter {
+    pub column: String,
+    #[serde(default)]
+    pub op: GridFilterOp,
+    /// Unused by `is_null` and `not_null`
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GridSort {
+    pub column: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+/// The rows of one page of the grid
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridQuery {
+    /// First row of the page, counted after filtering and sorting
+    pub offset: usize,
+    /// Rows in the page, at most [`GRID_MAX_PAGE_ROWS`]
+    pub limit: usize,
+    pub sort: Option<GridSort>,
+    /// Filters a row must pass, all of them
+    pub filters: Vec<GridFilter>,
+    /// Whether to count the rows passing the filters
+    pub count: bool,
+}
+
+impl Default for GridQuery {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: GRID_PAGE_ROWS,
+            sort: None,
+            filters: Vec::new(),
+            count: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GridPage {
+    pub columns: Vec<ColumnPreview>,
+    pub offset: usize,
+    /// Values as text by row, then column; `None` for nulls
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Rows passing the filters, when the query asked for the count
+    pub total_rows: Option<usize>,
+}
+
+/// The page of `lf` that `query` asks for
+pub fn grid_page(mut lf: LazyFrame, query: &GridQuery) -> Result<GridPage> {
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let dtype_of = |name: &str| {
+        schema
+            .get(name)
+            .with_context(|| format!("Column '{name}' not found"))
+    };
+
+    for filter in &query.filters {
+        let dtype = dtype_of(&filter.column)?;
+        lf = lf.filter(filter_expr(filter, dtype)?);
+    }
+    let total_rows = if query.count {
+        Some(count_rows(&lf)?)
+    } else {
+        None
+    };
+    if let Some(sort) = &query.sort {
+        dtype_of(&sort.column)?;
+        lf = lf.sort(
+            [sort.column.as_str()],
+            SortMultipleOptions::default()
+                .with_order_descending(sort.descending)
+                .with_nulls_last(true)
+                .with_maintain_order(true),
+        );
+    }
+
+    let limit = query.limit.clamp(1, GRID_MAX_PAGE_ROWS);
+    let page = lf
+        .slice(
+            i64::try_from(query.offset).unwrap_or(i64::MAX),
+            IdxSize::try_from(limit).unwrap_or(IdxSize::MAX),
+        )
+        .collect()
+        .context("Failed to read the rows of the page")?;
+
+    let values: Vec<Vec<Option<String>>> = page.get_columns().iter().map(display_values).collect();
+    let rows = (0..page.height())
+        .map(|row| values.iter().map(|column| column[row].clone()).collect())
+        .collect();
+    Ok(GridPage {
+        columns: schema
+            .iter()
+            .map(|(name, dtype)| ColumnPreview {
+                name: name.to_string(),
+                dtype: dtype.to_string(),
+            })
+            .collect(),
+        offset: query.offset,
+        rows,
+        total_rows,
+    })
+}
+
+fn filter_expr(filter: &GridFilter, dtype: &DataType) -> Result<Expr> {
+    let column = col(filter.column.as_str());
+    let text = || column.clone().cast(DataType::String);
+    let value = || lit(filter.value.clone());
+    Ok(match filter.op {
+        GridFilterOp::Contains => text()
+            .str()
+            .contains(lit(format!("(?i){}", regex::escape(&filter.value))), false),
+        GridFilterOp::Equals => text().eq(value()),
+        GridFilterOp::NotEquals => text().neq(value()).or(column.is_null()),
+        GridFilterOp::GreaterThan | GridFilterOp::LessThan => {
+            let (column, value) = if dtype.is_numeric() {
+                let Ok(number) = filter.value.trim().parse::<f64>() else {
+                    bail!(
+                        "'{}' is not a number, so cannot be compared with column '{}'",
+                        filter.value,
+                        filter.column
+                    );
+                };
+                (column.cast(DataType::Float64), lit(number))
+            } else {
+                (text(), value())
+            };
+            if filter.op == GridFilterOp::GreaterThan {
+                column.gt(value)
+            } else {
+                column.lt(value)
+            }
+        }
+        GridFilterOp::IsNull => column.is_null(),
+        GridFilterOp::NotNull => column.is_not_null(),
+    })
+}
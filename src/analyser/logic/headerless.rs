@@ -0,0 +1,189 @@
+//! Files without a header row.
+//!
+//! Some exports start straight with data. Read as usual, their first row
+//! becomes the column names and is lost as data. Reading with a
+//! [`HeaderlessNaming`] keeps every row and names the columns by position:
+//! numbered, from a schema file, or from their content. Loaders take the
+//! naming as an `Option<HeaderlessNaming>`, `None` for a file with a header
+//! row, so analysis, cleaning and exports all read the file the same way.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use super::analysis::analyse_df_lazy;
+use super::csv_dialect::csv_separator;
+use crate::config::policy::{Capability, PathPolicy};
+
+/// Rows profiled to infer names from content
+const INFER_SAMPLE_ROWS: u32 = 10_000;
+
+/// `2024-01-31`, `31/01/2024`, `1.2.24`
+static DATE_VALUE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d{1,4}[-/.]\d{1,2}[-/.]\d{1,4}(?:[T ]\d{1,2}:\d{2}.*)?$")
+        .expect("valid date value regex")
+});
+
+/// How to name the columns of a file read without a header row
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "naming", rename_all = "snake_case")]
+pub enum HeaderlessNaming {
+    /// `column_1`, `column_2`, ...
+    #[default]
+    Auto,
+    /// Names suggested from each column's content, as for placeholder headers
+    Inferred,
+    /// Names listed in a schema file, by position. A relative path is
+    /// relative to the data file.
+    Schema { path: String },
+}
+
+/// Column names listed in a schema file, in order. A JSON file holds an
+/// array of names or of objects with a `name`, alone or as `columns`, like
+/// an `expect_schema` step. Any other file lists one name per line, or all
+/// of them on one comma-separated line; blank lines and `#` comments are
+/// skipped.
+pub fn read_schema_names(path: &Path) -> Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read schema file {}", path.display()))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let names = if is_json {
+        let value: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse schema file {}", path.display()))?;
+        let columns = value.get("columns").unwrap_or(&value);
+        let Some(columns) = columns.as_array() else {
+            anyhow::bail!(
+                "Schema file {} must hold an array of column names",
+                path.display()
+            );
+        };
+        columns
+            .iter()
+            .map(|column| {
+                column
+                    .as_str()
+                    .or_else(|| column.get("name").and_then(|n| n.as_str()))
+                    .map(str::to_owned)
+                    .with_context(|| format!("Schema column without a name: {column}"))
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        let lines: Vec<&str> = content
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+        match lines.as_slice() {
+            [line] if line.contains(',') => line.split(',').map(|n| n.trim().to_owned()).collect(),
+            _ => lines.into_iter().map(str::to_owned).collect(),
+        }
+    };
+    if names.is_empty() {
+        anyhow::bail!("Schema file {} names no columns", path.display());
+    }
+    Ok(names)
+}
+
+/// Scan the file at `path`, as a CSV file without a header row when
+/// `header_names` is set
+pub fn load_df_lazy_as(path: &Path, header_names: Option<&HeaderlessNaming>) -> Result<LazyFrame> {
+    match header_names {
+        Some(naming) => load_df_lazy_headerless(path, naming),
+        None => super::io::load_df_lazy(path),
+    }
+}
+
+/// Scan a CSV file that has no header row, naming its columns by `naming`
+pub fn load_df_lazy_headerless(path: &Path, naming: &HeaderlessNaming) -> Result<LazyFrame> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    if !is_csv {
+        anyhow::bail!(
+            "Only CSV files can be read without a header row: {}",
+            path.display()
+        );
+    }
+    let lf = LazyCsvReader::new(path)
+        .with_infer_schema_length(Some(10000))
+        .with_has_header(false)
+        .with_separator(csv_separator(path))
+        .with_try_parse_dates(true)
+        .finish()
+        .context("Failed to scan CSV")?;
+    name_headerless_columns(lf, naming, path)
+}
+
+/// Rename the numbered columns of headerless data `lf`, read from `path`
+pub fn name_headerless_columns(
+    mut lf: LazyFrame,
+    naming: &HeaderlessNaming,
+    path: &Path,
+) -> Result<LazyFrame> {
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let current: Vec<&PlSmallStr> = schema.iter_names().collect();
+    let names = match naming {
+        HeaderlessNaming::Auto => return Ok(lf),
+        HeaderlessNaming::Inferred => {
+            let summaries = analyse_df_lazy(
+                lf.clone().limit(INFER_SAMPLE_ROWS),
+                0.0,
+                INFER_SAMPLE_ROWS as usize,
+            )
+            .context("Failed to profile columns to name them")?;
+            summaries
+                .into_iter()
+                .map(|s| s.suggested_name.unwrap_or(s.standardised_name))
+                .collect()
+        }
+        HeaderlessNaming::Schema { path: schema_path } => {
+            let schema_path = Path::new(schema_path);
+            let schema_path = match path.parent() {
+                Some(dir) if schema_path.is_relative() => dir.join(schema_path),
+                _ => schema_path.to_path_buf(),
+            };
+            PathPolicy::load().ensure_not_denied(&schema_path, Capability::Read)?;
+            let names = read_schema_names(&schema_path)?;
+            if names.len() != current.len() {
+                anyhow::bail!(
+                    "Schema file {} names {} columns but {} has {}",
+                    schema_path.display(),
+                    names.len(),
+                    path.display(),
+                    current.len()
+                );
+            }
+            let mut seen = HashSet::new();
+            if let Some(repeat) = names.iter().find(|n| !seen.insert(n.as_str())) {
+                anyhow::bail!(
+                    "Schema file {} names '{repeat}' more than once",
+                    schema_path.display()
+                );
+            }
+            names
+        }
+    };
+    let exprs: Vec<Expr> = current
+        .iter()
+        .zip(names)
+        .map(|(old, new)| col((*old).clone()).alias(new))
+        .collect();
+    Ok(lf.select(exprs))
+}
+
+/// Whether the header of a file looks like a row of data: most of its names
+/// are numbers, dates or email addresses rather than labels
+pub fn header_looks_like_data(names: &[String]) -> bool {
+    let values = names
+        .iter()
+        .map(|n| n.trim())
+        .filter(|n| n.parse::<f64>().is_ok() || DATE_VALUE.is_match(n) || n.contains('@'))
+        .count();
+    values * 2 > names.len()
+}
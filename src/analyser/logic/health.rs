@@ -1,53 +1,82 @@
 use super::types::{ColumnStats, ColumnSummary, FileHealth};
 
-pub fn calculate_file_health(summaries: &[ColumnSummary]) -> FileHealth {
+/// The contribution a single column makes to the overall file health score.
+///
+/// Components are computed independently per column so that callers which
+/// re-analyse a subset of columns (see [`super::incremental`]) only need to
+/// recompute the components for those columns.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HealthComponent {
+    pub penalty: f64,
+    pub risks: Vec<String>,
+}
+
+pub fn column_health_component(col: &ColumnSummary) -> HealthComponent {
     let mut risks = Vec::new();
-    let mut score: f64 = 100.0;
+    let mut penalty = 0.0;
 
-    for col in summaries {
-        let null_pct = if col.count > 0 {
-            (col.nulls as f64 / col.count as f64) * 100.0
-        } else {
-            0.0
-        };
-
-        if null_pct > 15.0 {
-            risks.push(format!(
-                "Column '{}' has significant missing data ({:.1}%).",
-                col.name, null_pct
-            ));
-            score -= 10.0;
-        } else if null_pct > 5.0 {
-            score -= 5.0;
-        }
+    let null_pct = if col.count > 0 {
+        (col.nulls as f64 / col.count as f64) * 100.0
+    } else {
+        0.0
+    };
 
-        if col.has_special {
-            risks.push(format!(
-                "Hidden/special characters detected in '{}'.",
-                col.name
-            ));
-            score -= 5.0;
-        }
+    if null_pct > 15.0 {
+        risks.push(format!(
+            "Column '{}' has significant missing data ({:.1}%).",
+            col.name, null_pct
+        ));
+        penalty += 10.0;
+    } else if null_pct > 5.0 {
+        penalty += 5.0;
+    }
 
-        if let ColumnStats::Numeric(s) = &col.stats
-            && let (Some(mean), Some(median), Some(min), Some(max)) =
-                (s.mean, s.median, s.min, s.max)
-        {
-            let range = max - min;
-            if range > 0.0 {
-                let diff_ratio = (mean - median).abs() / range;
-                if diff_ratio > 0.1 {
-                    risks.push(format!(
-                        "Column '{}' is heavily skewed; averages may be misleading.",
-                        col.name
-                    ));
-                    score -= 5.0;
-                }
+    if col.has_special {
+        risks.push(format!(
+            "Hidden/special characters detected in '{}'.",
+            col.name
+        ));
+        penalty += 5.0;
+    }
+
+    if let ColumnStats::Numeric(s) = &col.stats
+        && let (Some(mean), Some(median), Some(min), Some(max)) = (s.mean, s.median, s.min, s.max)
+    {
+        let range = max - min;
+        if range > 0.0 {
+            let diff_ratio = (mean - median).abs() / range;
+            if diff_ratio > 0.1 {
+                risks.push(format!(
+                    "Column '{}' is heavily skewed; averages may be misleading.",
+                    col.name
+                ));
+                penalty += 5.0;
             }
         }
     }
+
+    HealthComponent { penalty, risks }
+}
+
+/// Combine per-column components (in column order) into a file health score.
+pub fn combine_health_components<'a>(
+    components: impl IntoIterator<Item = &'a HealthComponent>,
+) -> FileHealth {
+    let mut risks = Vec::new();
+    let mut score: f64 = 100.0;
+
+    for component in components {
+        score -= component.penalty;
+        risks.extend(component.risks.iter().cloned());
+    }
+
     FileHealth {
         score: (score.max(0.0) / 100.0) as f32,
         risks,
     }
 }
+
+pub fn calculate_file_health(summaries: &[ColumnSummary]) -> FileHealth {
+    let components: Vec<HealthComponent> = summaries.iter().map(column_health_component).collect();
+    combine_health_components(&components)
+}
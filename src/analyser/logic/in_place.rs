@@ -0,0 +1,299 @@
+//! Cleaning a file in place: writing the cleaned data back next to the
+//! original in the same layout.
+//!
+//! Some downstream processes read a file by name and expect its format
+//! unchanged. [`write_in_place`] writes in the format of the original, with
+//! the delimiter, byte order mark and line endings of a CSV file, and
+//! without a header row if the file was read without one. The data is
+//! written to a temporary file in the same folder and read back to check
+//! its row count before it takes its final name, so a failed write never
+//! leaves a half-written file behind. Replacing the original first copies
+//! it to a timestamped backup beside it.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::batches::collect_in_batches;
+use super::cleaning::clean_df_lazy;
+use super::csv_dialect::{CsvDialect, TextEncoding, detect_csv_dialect};
+use super::excel::workbook_source;
+use super::headerless::{HeaderlessNaming, load_df_lazy_as};
+use super::types::ColumnCleanConfig;
+
+/// Suffix added to the file name of the cleaned copy by default
+pub const DEFAULT_IN_PLACE_SUFFIX: &str = "_cleaned";
+
+/// Where the cleaned data goes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum InPlaceMode {
+    /// A new file beside the original, `data_cleaned.csv` for `data.csv`.
+    /// An existing file is never overwritten; `_2`, `_3`, ... is added.
+    Suffix { suffix: String },
+    /// The original itself, after copying it to `data.csv.<timestamp>.bak`
+    Replace,
+}
+
+impl Default for InPlaceMode {
+    fn default() -> Self {
+        Self::Suffix {
+            suffix: DEFAULT_IN_PLACE_SUFFIX.to_owned(),
+        }
+    }
+}
+
+/// What cleaning in place wrote
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InPlaceReport {
+    pub output: String,
+    /// Copy of the original, when it was replaced
+    pub backup: Option<String>,
+    pub rows: usize,
+    /// Layout the CSV file was written in
+    pub dialect: Option<CsvDialect>,
+    pub header: bool,
+}
+
+/// Layout of a file to write back
+#[derive(Debug, Clone, Copy)]
+enum FileFormat {
+    Csv { dialect: CsvDialect, header: bool },
+    Parquet,
+    Json,
+}
+
+impl FileFormat {
+    fn of(path: &Path, header: bool) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "csv" => {
+                let dialect = detect_csv_dialect(path)?;
+                if dialect.encoding == TextEncoding::Other {
+                    anyhow::bail!(
+                        "{} is not UTF-8 encoded and cannot be written back in its encoding; \
+                         export it to a new file instead",
+                        path.display()
+                    );
+                }
+                Ok(Self::Csv { dialect, header })
+            }
+            "parquet" if path.is_dir() => anyhow::bail!(
+                "{} is a folder of Parquet parts and cannot be cleaned in place",
+                path.display()
+            ),
+            "parquet" => Ok(Self::Parquet),
+            "json" => Ok(Self::Json),
+            _ => anyhow::bail!("Cannot clean .{ext} files in place"),
+        }
+    }
+
+    fn write(&self, df: &mut DataFrame, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)
+            .with_context(|| format!("Failed to create {}", path.display()))?;
+        match self {
+            Self::Csv { dialect, header } => CsvWriter::new(file)
+                .include_header(*header)
+                .include_bom(dialect.encoding == TextEncoding::Utf8Bom)
+                .with_separator(dialect.delimiter)
+                .with_line_terminator(dialect.line_ending.as_str().to_owned())
+                .finish(df)
+                .context("Failed to write CSV file"),
+            Self::Parquet => ParquetWriter::new(file)
+                .finish(df)
+                .map(drop)
+                .context("Failed to write Parquet file"),
+            Self::Json => JsonWriter::new(file)
+                .with_json_format(JsonFormat::Json)
+                .finish(df)
+                .context("Failed to write JSON file"),
+        }
+    }
+
+    /// Rows in the file at `path`, read back as written
+    fn count_rows(&self, path: &Path) -> Result<usize> {
+        let rows = match self {
+            Self::Csv { dialect, header } => LazyCsvReader::new(path)
+                .with_has_header(*header)
+                .with_separator(dialect.delimiter)
+                .with_infer_schema_length(Some(0))
+                .finish()?
+                .collect()?
+                .height(),
+            Self::Parquet => ParquetReader::new(std::fs::File::open(path)?)
+                .finish()?
+                .height(),
+            Self::Json => JsonReader::new(std::fs::File::open(path)?)
+                .finish()?
+                .height(),
+        };
+        Ok(rows)
+    }
+}
+
+/// Clean the file at `path` with `configs` and write it back by `mode`. A
+/// CSV file read without a header row by `header_names` is written back
+/// without one.
+pub fn clean_in_place(
+    path: &Path,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    mode: &InPlaceMode,
+    header_names: Option<&HeaderlessNaming>,
+) -> Result<InPlaceReport> {
+    let lf = load_df_lazy_as(path, header_names).context("Failed to load data")?;
+    let cleaned = clean_df_lazy(lf, configs, false).context("Failed to apply cleaning")?;
+    write_in_place(path, cleaned, mode, header_names.is_none())
+}
+
+/// Write `lf`, the cleaned data of the file at `path`, back in the layout of
+/// that file by `mode`, without a header row unless `header` is set.
+/// Workbooks cannot be written.
+pub fn write_in_place(
+    path: &Path,
+    lf: LazyFrame,
+    mode: &InPlaceMode,
+    header: bool,
+) -> Result<InPlaceReport> {
+    if workbook_source(path).is_some() {
+        anyhow::bail!(
+            "Workbooks cannot be written back; export the sheet to a CSV or Parquet file instead"
+        );
+    }
+    let format = FileFormat::of(path, header)?;
+
+    let target = match mode {
+        InPlaceMode::Suffix { suffix } => free_sibling(path, suffix)?,
+        InPlaceMode::Replace => path.to_path_buf(),
+    };
+
+    let mut df = collect_in_batches(lf).context("Failed to clean data")?;
+    let temp = crate::utils::TempFileGuard::new(temp_sibling(path)?);
+    let temp_path = temp.path().context("Temporary file already released")?;
+    format.write(&mut df, temp_path)?;
+    let written = format
+        .count_rows(temp_path)
+        .context("Failed to read back the cleaned file")?;
+    if written != df.height() {
+        anyhow::bail!(
+            "Cleaned file holds {written} rows instead of {}; {} was left unchanged",
+            df.height(),
+            path.display()
+        );
+    }
+
+    let backup = match mode {
+        InPlaceMode::Replace => {
+            let backup = backup_path(path)?;
+            std::fs::copy(path, &backup)
+                .with_context(|| format!("Failed to back up {}", path.display()))?;
+            Some(backup)
+        }
+        InPlaceMode::Suffix { .. } => None,
+    };
+    std::fs::rename(temp_path, &target)
+        .with_context(|| format!("Failed to write {}", target.display()))?;
+
+    let report = InPlaceReport {
+        output: target.display().to_string(),
+        backup: backup.map(|b| b.display().to_string()),
+        rows: written,
+        dialect: match format {
+            FileFormat::Csv { dialect, .. } => Some(dialect),
+            _ => None,
+        },
+        header,
+    };
+    crate::config::log_event(
+        "Analyser",
+        &format!(
+            "Cleaned {} in place: {} rows written to {}{}",
+            path.display(),
+            report.rows,
+            report.output,
+            report
+                .backup
+                .as_ref()
+                .map(|b| format!(", original backed up to {b}"))
+                .unwrap_or_default()
+        ),
+    );
+    Ok(report)
+}
+
+/// `<stem><suffix>.<ext>` beside `file`, numbered if that name is taken
+fn free_sibling(file: &Path, suffix: &str) -> Result<PathBuf> {
+    if suffix.is_empty() || suffix.contains(['/', '\\']) {
+        anyhow::bail!("Invalid file name suffix '{suffix}'");
+    }
+    let stem = file
+        .file_stem()
+        .context("Invalid file name")?
+        .to_string_lossy();
+    let ext = file
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| match n {
+            1 => file.with_file_name(format!("{stem}{suffix}{ext}")),
+            n => file.with_file_name(format!("{stem}{suffix}_{n}{ext}")),
+        })
+        .find(|p| !p.exists())
+        .context("No free file name")
+}
+
+/// A hidden temporary file beside `file`, so the final rename stays on the
+/// same file system
+fn temp_sibling(file: &Path) -> Result<PathBuf> {
+    let name = file.file_name().context("Invalid file name")?;
+    Ok(file.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        uuid::Uuid::new_v4()
+    )))
+}
+
+/// `<name>.<timestamp>.bak` beside `file`
+fn backup_path(file: &Path) -> Result<PathBuf> {
+    let name = file.file_name().context("Invalid file name")?;
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    free_sibling_name(file, &format!("{}.{timestamp}.bak", name.to_string_lossy()))
+}
+
+/// `name` beside `file`, numbered if taken
+fn free_sibling_name(file: &Path, name: &str) -> Result<PathBuf> {
+    (1..)
+        .map(|n| match n {
+            1 => file.with_file_name(name),
+            n => file.with_file_name(format!("{name}.{n}")),
+        })
+        .find(|p| !p.exists())
+        .context("No free file name")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_sibling() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("data.csv");
+        assert_eq!(
+            free_sibling(&file, "_cleaned").unwrap(),
+            dir.path().join("data_cleaned.csv")
+        );
+        std::fs::write(dir.path().join("data_cleaned.csv"), "").unwrap();
+        assert_eq!(
+            free_sibling(&file, "_cleaned").unwrap(),
+            dir.path().join("data_cleaned_2.csv")
+        );
+        assert!(free_sibling(&file, "../x").is_err());
+    }
+}
@@ -0,0 +1,289 @@
+//! Incremental re-analysis after cleaning configuration changes.
+//!
+//! Re-running the full analysis every time a single column's cleaning config is
+//! edited is expensive on wide datasets. [`IncrementalAnalysis`] tracks which
+//! summary entries each source column produced, so that after a config change
+//! only the affected columns are re-cleaned, re-profiled and re-scored. Health
+//! score components for untouched columns are reused as-is.
+
+use super::analysis::analyse_df_lazy_with_options;
+use super::cleaning::clean_df_lazy;
+use super::health::{HealthComponent, column_health_component, combine_health_components};
+use super::naming;
+use super::types::{AnalysisOptions, ColumnCleanConfig, ColumnSummary, FileHealth};
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Result of an incremental update, suitable for returning to the frontend.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct IncrementalUpdate {
+    pub summary: Vec<ColumnSummary>,
+    pub health: FileHealth,
+    /// Source columns whose configuration changed and were re-analysed.
+    pub changed_columns: Vec<String>,
+    /// Summary entries that were recomputed (post-cleaning column names).
+    pub recomputed: Vec<String>,
+}
+
+/// Analysis state that can be updated column-by-column as configs change.
+#[derive(Debug)]
+pub struct IncrementalAnalysis {
+    summaries: Vec<ColumnSummary>,
+    /// Health components, parallel to `summaries`.
+    components: Vec<HealthComponent>,
+    configs: HashMap<String, ColumnCleanConfig>,
+    /// Source column -> names of the summary entries it produced.
+    dependencies: HashMap<String, Vec<String>>,
+    trim_pct: f64,
+    custom_sample_size: usize,
+    options: AnalysisOptions,
+}
+
+impl IncrementalAnalysis {
+    /// Build incremental state from an existing analysis and the configs that
+    /// were applied when it was produced.
+    pub fn new(
+        summaries: Vec<ColumnSummary>,
+        configs: HashMap<String, ColumnCleanConfig>,
+        trim_pct: f64,
+        custom_sample_size: usize,
+    ) -> Self {
+        let components = summaries.iter().map(column_health_component).collect();
+        let dependencies = build_dependencies(&summaries, &configs);
+        Self {
+            summaries,
+            components,
+            configs,
+            dependencies,
+            trim_pct,
+            custom_sample_size,
+            options: AnalysisOptions::default(),
+        }
+    }
+
+    /// Use non-default analysis options (e.g. histogram strategy) when
+    /// re-analysing columns.
+    pub fn with_options(mut self, options: AnalysisOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn summaries(&self) -> &[ColumnSummary] {
+        &self.summaries
+    }
+
+    pub fn into_summaries(self) -> Vec<ColumnSummary> {
+        self.summaries
+    }
+
+    pub fn configs(&self) -> &HashMap<String, ColumnCleanConfig> {
+        &self.configs
+    }
+
+    /// Summary entries currently produced by `source`.
+    pub fn dependents_of(&self, source: &str) -> &[String] {
+        self.dependencies
+            .get(source)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    pub fn health(&self) -> FileHealth {
+        combine_health_components(&self.components)
+    }
+
+    /// Apply a new set of cleaning configs, re-analysing only the source
+    /// columns whose config changed.
+    ///
+    /// `raw_lf` must be the original (uncleaned) data. Returns the names of
+    /// the summary entries that were recomputed.
+    pub fn apply_configs(
+        &mut self,
+        raw_lf: LazyFrame,
+        new_configs: HashMap<String, ColumnCleanConfig>,
+    ) -> Result<Vec<String>> {
+        let changed = changed_columns(&self.configs, &new_configs);
+        let mut recomputed = Vec::new();
+        if changed.is_empty() {
+            self.configs = new_configs;
+            return Ok(recomputed);
+        }
+
+        let mut raw_lf = raw_lf;
+        let schema = raw_lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+        let source_order: Vec<String> = schema.iter_names().map(|n| n.to_string()).collect();
+
+        for source in &changed {
+            let previous = self.dependencies.remove(source).unwrap_or_default();
+            let position = self.remove_entries(&previous);
+
+            if !schema.contains(source.as_str()) {
+                continue;
+            }
+            if new_configs.get(source).is_some_and(|c| !c.active) {
+                // Inactive columns are dropped by cleaning and have no summary
+                continue;
+            }
+
+            let col_lf = raw_lf.clone().select([col(source.as_str())]);
+            let cleaned = clean_df_lazy(col_lf, &new_configs, false)
+                .with_context(|| format!("Failed to clean column '{source}'"))?;
+            let fresh = analyse_df_lazy_with_options(
+                cleaned,
+                self.trim_pct,
+                self.custom_sample_size,
+                &self.options,
+            )
+            .with_context(|| format!("Failed to re-analyse column '{source}'"))?;
+
+            let insert_at = position.unwrap_or_else(|| self.insertion_index(source, &source_order));
+            let names: Vec<String> = fresh.iter().map(|s| s.name.clone()).collect();
+            for (offset, summary) in fresh.into_iter().enumerate() {
+                self.components
+                    .insert(insert_at + offset, column_health_component(&summary));
+                self.summaries.insert(insert_at + offset, summary);
+            }
+            recomputed.extend(names.iter().cloned());
+            self.dependencies.insert(source.clone(), names);
+        }
+
+        self.restandardise_names();
+        self.configs = new_configs;
+        Ok(recomputed)
+    }
+
+    /// Remove the given summary entries, returning the index of the first one.
+    fn remove_entries(&mut self, names: &[String]) -> Option<usize> {
+        let position = self.summaries.iter().position(|s| names.contains(&s.name));
+        let mut idx = 0;
+        while idx < self.summaries.len() {
+            if self
+                .summaries
+                .get(idx)
+                .is_some_and(|s| names.contains(&s.name))
+            {
+                self.summaries.remove(idx);
+                self.components.remove(idx);
+            } else {
+                idx += 1;
+            }
+        }
+        position
+    }
+
+    /// Where to insert summaries for a source that currently has none, based
+    /// on the position of the nearest preceding source column in the file.
+    fn insertion_index(&self, source: &str, source_order: &[String]) -> usize {
+        let preceding = source_order.iter().take_while(|name| *name != source);
+        for name in preceding.collect::<Vec<_>>().into_iter().rev() {
+            if let Some(last) = self.dependencies.get(name).and_then(|d| d.last())
+                && let Some(idx) = self.summaries.iter().position(|s| &s.name == last)
+            {
+                return idx + 1;
+            }
+        }
+        0
+    }
+
+    fn restandardise_names(&mut self) {
+        let names: Vec<String> = self.summaries.iter().map(|s| s.name.clone()).collect();
+        let sanitized = naming::sanitize_column_names(&names);
+        for (summary, clean) in self.summaries.iter_mut().zip(sanitized) {
+            summary.standardised_name = clean;
+        }
+        naming::suggest_column_names(&mut self.summaries);
+    }
+}
+
+/// Source columns whose cleaning config differs between `old` and `new`
+/// (including columns that gained or lost a config). Sorted for determinism.
+pub fn changed_columns(
+    old: &HashMap<String, ColumnCleanConfig>,
+    new: &HashMap<String, ColumnCleanConfig>,
+) -> Vec<String> {
+    let mut changed: Vec<String> = old
+        .keys()
+        .chain(new.keys())
+        .filter(|name| old.get(*name) != new.get(*name))
+        .cloned()
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
+/// Work out which summary entries each configured source column produced.
+///
+/// A column maps to its renamed output, or to the `{output}_{value}` columns
+/// when one-hot encoding was applied. Summaries not claimed by any config are
+/// treated as passthrough columns that depend only on themselves.
+fn build_dependencies(
+    summaries: &[ColumnSummary],
+    configs: &HashMap<String, ColumnCleanConfig>,
+) -> HashMap<String, Vec<String>> {
+    let mut dependencies: HashMap<String, Vec<String>> = HashMap::new();
+    let mut claimed = std::collections::HashSet::new();
+
+    #[expect(clippy::iter_over_hash_type)]
+    for (source, config) in configs {
+        if !config.active {
+            dependencies.insert(source.clone(), Vec::new());
+            continue;
+        }
+        let output = if config.new_name.is_empty() {
+            source.clone()
+        } else {
+            config.new_name.clone()
+        };
+        let one_hot = config.ml_preprocessing && config.one_hot_encode;
+        let prefix = format!("{output}_");
+        let outputs: Vec<String> = summaries
+            .iter()
+            .map(|s| &s.name)
+            .filter(|name| {
+                if one_hot {
+                    name.starts_with(&prefix)
+                } else {
+                    **name == output
+                }
+            })
+            .cloned()
+            .collect();
+        claimed.extend(outputs.iter().cloned());
+        dependencies.insert(source.clone(), outputs);
+    }
+
+    for summary in summaries {
+        if !claimed.contains(&summary.name) && !dependencies.contains_key(&summary.name) {
+            dependencies.insert(summary.name.clone(), vec![summary.name.clone()]);
+        }
+    }
+
+    dependencies
+}
+
+/// Stateless helper for callers (e.g. Tauri commands) that hold the previous
+/// summaries and configs rather than an [`IncrementalAnalysis`].
+pub fn reanalyse_changed_columns(
+    raw_lf: LazyFrame,
+    summary: Vec<ColumnSummary>,
+    old_configs: HashMap<String, ColumnCleanConfig>,
+    new_configs: HashMap<String, ColumnCleanConfig>,
+    trim_pct: f64,
+    custom_sample_size: usize,
+    options: &AnalysisOptions,
+) -> Result<IncrementalUpdate> {
+    let changed = changed_columns(&old_configs, &new_configs);
+    let mut state = IncrementalAnalysis::new(summary, old_configs, trim_pct, custom_sample_size)
+        .with_options(options.clone());
+    let recomputed = state.apply_configs(raw_lf, new_configs)?;
+    let health = state.health();
+    Ok(IncrementalUpdate {
+        summary: state.into_summaries(),
+        health,
+        changed_columns: changed,
+        recomputed,
+    })
+}
@@ -17,7 +17,9 @@
 //! feedback to data analysts.
 
 use super::naming::sanitize_column_name;
-use super::types::{ColumnKind, ColumnStats, ColumnSummary};
+use super::types::{
+    CategoricalMetrics, ColumnKind, ColumnStats, ColumnSummary, GeoEncoding, GeoStats,
+};
 use std::f64::consts::PI;
 
 pub const MISSING_DATA_CRITICAL: f64 = 40.0;
@@ -33,6 +35,9 @@ pub const TINY_BAR_THRESHOLD: f64 = 0.005;
 pub const DOMINANT_BIN_THRESHOLD: f64 = 0.9;
 pub const GAUSS_PEAK_CONCENTRATION: f64 = 1.5;
 pub const UNEVEN_DISTRIBUTION_THRESHOLD: f64 = 5.0;
+pub const EFFECTIVELY_CONSTANT_SHARE: f64 = 0.95;
+pub const LOW_ENTROPY_THRESHOLD: f64 = 0.5;
+pub const BOOLEAN_IMBALANCE_THRESHOLD: f64 = 0.01;
 
 impl ColumnSummary {
     pub fn generate_interpretation(&self) -> Vec<String> {
@@ -56,6 +61,7 @@ impl ColumnSummary {
             ColumnStats::Text(s) => self.collect_text_signals(s, &mut signals),
             ColumnStats::Temporal(s) => Self::collect_temporal_signals(s, &mut signals),
             ColumnStats::Boolean(s) => Self::collect_boolean_signals(s, &mut signals),
+            ColumnStats::Geo(s) => Self::collect_geo_signals(s, &mut signals),
         }
 
         // 3. Quality Signals
@@ -67,10 +73,17 @@ impl ColumnSummary {
             signals.push("Non-standard column name (contains spaces, symbols or mixed casing).");
         }
 
+        let mut signals: Vec<String> = signals.into_iter().map(|s| s.to_owned()).collect();
+        if let ColumnStats::Geo(s) = &self.stats
+            && let Some(extent) = Self::geo_extent(s)
+        {
+            signals.push(extent);
+        }
+
         if signals.is_empty() {
             vec!["No significant patterns detected.".to_owned()]
         } else {
-            signals.into_iter().map(|s| s.to_owned()).collect()
+            signals
         }
     }
 
@@ -88,8 +101,10 @@ impl ColumnSummary {
         let n_distinct = self.stats.n_distinct();
         let uniqueness_ratio = self.uniqueness_ratio();
 
-        // 1. ID Detection (Cross-type)
-        let is_likely_id = if (n_distinct == self.count && self.nulls == 0)
+        // 1. ID Detection (Cross-type); distinct locations are not IDs
+        let is_likely_id = if matches!(self.stats, ColumnStats::Geo(_)) {
+            false
+        } else if (n_distinct == self.count && self.nulls == 0)
             || (is_id_name && uniqueness_ratio > 0.3)
         {
             true // Perfectly unique OR ID name with moderate cardinality
@@ -134,6 +149,10 @@ impl ColumnSummary {
     }
 
     fn collect_ml_routine_advice(&self, is_likely_id: bool, advice: &mut Vec<String>) {
+        if matches!(self.stats, ColumnStats::Geo(_)) {
+            advice.push("Locations make weak raw features. Derive distances to key places, clusters or regions instead.".to_owned());
+            return;
+        }
         match self.kind {
             ColumnKind::Numeric => {
                 if is_likely_id {
@@ -165,6 +184,30 @@ impl ColumnSummary {
                 {
                     advice.push("High cardinality detected. Use Frequency Capping to group rare values before encoding.".to_owned());
                 }
+                if let ColumnStats::Boolean(b) = &self.stats {
+                    if let (Some(rate), Some(share)) = (b.positive_rate(), b.minority_share())
+                        && share > 0.0
+                        && share < BOOLEAN_IMBALANCE_THRESHOLD
+                    {
+                        advice.push(format!(
+                            "Extreme class imbalance ({:.2}% positive). As a target, use resampling or class weights and evaluate with precision/recall or F1 rather than accuracy.",
+                            rate * 100.0
+                        ));
+                    }
+                    if b.null_count > 0 {
+                        advice.push("Nulls are a third state here. Impute deliberately or add a missing-indicator column rather than treating them as false.".to_owned());
+                    }
+                }
+                if let Some(metrics) = self.categorical_metrics()
+                    && metrics.effectively_constant
+                    && metrics.modal_share < 1.0
+                {
+                    advice.push(format!(
+                        "Near-constant feature: '{}' covers {:.1}% of values. Consider dropping it, as it carries little predictive signal.",
+                        metrics.mode,
+                        metrics.modal_share * 100.0
+                    ));
+                }
             }
             ColumnKind::Text => {
                 if !is_likely_id {
@@ -198,7 +241,10 @@ impl ColumnSummary {
             }
         }
 
-        if self.kind == ColumnKind::Numeric && !is_likely_id {
+        if self.kind == ColumnKind::Numeric
+            && !is_likely_id
+            && !matches!(self.stats, ColumnStats::Geo(_))
+        {
             advice.push("Recommend Z-Score or Min-Max Normalization if other numeric features have different scales.".to_owned());
             if let ColumnStats::Numeric(s) = &self.stats
                 && s.zero_count as f64 / self.count as f64 > 0.3
@@ -410,6 +456,16 @@ impl ColumnSummary {
         if freq.len() == 2 {
             signals.push("Binary field; suggests a toggle or yes/no choice.");
         }
+        if let Some(metrics) = CategoricalMetrics::from_frequencies(freq)
+            && freq.len() > 1
+        {
+            if metrics.effectively_constant {
+                signals
+                    .push("Effectively constant: one category accounts for almost every record.");
+            } else if metrics.normalised_entropy < LOW_ENTROPY_THRESHOLD {
+                signals.push("Low diversity; a few categories dominate the values.");
+            }
+        }
         if freq.len() > 1
             && let (Some(max_v), Some(min_v)) = (freq.values().max(), freq.values().min())
             && (*max_v as f64 / *min_v as f64) > UNEVEN_DISTRIBUTION_THRESHOLD
@@ -431,6 +487,12 @@ impl ColumnSummary {
 
     fn collect_temporal_signals(s: &super::types::TemporalStats, signals: &mut Vec<&'static str>) {
         signals.push("Time-based data sequence.");
+        if s.format.is_some() {
+            signals.push("Dates are stored as text and need parsing before date operations.");
+        }
+        if s.format_ambiguous {
+            signals.push("Every sampled date reads day-first or month-first; the date order setting chose one.");
+        }
         if s.is_sorted {
             signals.push("Strictly chronological order.");
         } else if s.is_sorted_rev {
@@ -459,6 +521,20 @@ impl ColumnSummary {
                 }
             }
         }
+
+        if s.future_count > 0 {
+            signals.push("Contains dates in the future.");
+        }
+        if s.implausible_count > 0 {
+            signals
+                .push("Contains implausibly old dates (before 1900); possibly placeholder values.");
+        }
+        if let [.., saturday, sunday] = s.weekday_counts.as_slice()
+            && saturday + sunday == 0
+            && s.weekday_counts.iter().sum::<usize>() > 0
+        {
+            signals.push("Events only occur on weekdays.");
+        }
     }
 
     fn collect_text_signals(&self, s: &super::types::TextStats, signals: &mut Vec<&'static str>) {
@@ -481,9 +557,43 @@ impl ColumnSummary {
         signals.push("Binary field; suggests a toggle or yes/no choice.");
         if s.true_count == 0 || s.false_count == 0 {
             signals.push("Field is constant.");
+        } else if s
+            .minority_share()
+            .is_some_and(|share| share < BOOLEAN_IMBALANCE_THRESHOLD)
+        {
+            signals.push("Extremely imbalanced; one state accounts for over 99% of values.");
+        }
+        if s.null_count > 0 {
+            signals.push("Nulls form a third state alongside true and false.");
+        }
+    }
+
+    fn collect_geo_signals(s: &GeoStats, signals: &mut Vec<&'static str>) {
+        signals.push(match s.encoding {
+            GeoEncoding::Latitude { .. } => "Latitudes of a coordinate pair.",
+            GeoEncoding::Longitude { .. } => "Longitudes of a coordinate pair.",
+            GeoEncoding::Wkt => "Geometries written as WKT.",
+            GeoEncoding::GeoJson => "Geometries written as GeoJSON.",
+        });
+        if s.invalid_count > 0 {
+            signals.push("Some values are out-of-range coordinates or unreadable geometries.");
         }
     }
 
+    /// The bounding box of `s` as a sentence
+    fn geo_extent(s: &GeoStats) -> Option<String> {
+        let b = s.bounds?;
+        let paired = match &s.encoding {
+            GeoEncoding::Latitude { longitude } => format!(" (paired with {longitude})"),
+            GeoEncoding::Longitude { latitude } => format!(" (paired with {latitude})"),
+            GeoEncoding::Wkt | GeoEncoding::GeoJson => String::new(),
+        };
+        Some(format!(
+            "Map extent: latitude {:.4} to {:.4}, longitude {:.4} to {:.4}{paired}.",
+            b.min_lat, b.max_lat, b.min_lon, b.max_lon
+        ))
+    }
+
     pub fn generate_business_summary(&self) -> Vec<String> {
         let mut insights = Vec::new();
 
@@ -518,8 +628,24 @@ impl ColumnSummary {
             ColumnStats::Temporal(s) => {
                 Self::collect_temporal_insights(s, &mut insights);
             }
-            ColumnStats::Boolean(_) => {
+            ColumnStats::Boolean(s) => {
                 insights.push("This represents a simple toggle or true/false status.");
+                if s.positive_rate()
+                    .is_some_and(|rate| rate > 0.0 && rate < BOOLEAN_IMBALANCE_THRESHOLD)
+                {
+                    insights.push("Positive cases are rare (under 1% of records), so they are easy to overlook in totals and averages.");
+                }
+                if s.null_count > 0 {
+                    insights.push("Some records have no yes/no answer recorded; these are unknown rather than 'no'.");
+                }
+            }
+            ColumnStats::Geo(s) => {
+                insights.push(
+                    "These are map locations, which can be plotted or used to measure distances.",
+                );
+                if s.invalid_count > 0 {
+                    insights.push("Some locations are impossible coordinates or unreadable shapes, and will not appear on a map.");
+                }
             }
         }
 
@@ -613,6 +739,10 @@ impl ColumnSummary {
             return;
         }
 
+        if CategoricalMetrics::from_frequencies(freq).is_some_and(|m| m.effectively_constant) {
+            insights.push("Almost every record falls into the same category, so this column says little about differences between records.");
+        }
+
         if freq.len() == 2 {
             insights.push("This captures a simple choice or binary state (like Yes/No).");
         } else if freq.len() > 1
@@ -642,6 +772,15 @@ impl ColumnSummary {
         if Self::has_histogram_gaps(&s.histogram, s.bin_width, 2.5) {
             insights.push("There are significant periods of time with no recorded activity.");
         }
+
+        if s.future_count > 0 {
+            insights.push("Some dates are in the future, which may indicate scheduled events or data entry errors.");
+        }
+        if s.implausible_count > 0 {
+            insights.push(
+                "Some dates fall before 1900 and are likely placeholders for unknown values.",
+            );
+        }
     }
 
     fn collect_text_insights(&self, s: &super::types::TextStats, insights: &mut Vec<&'static str>) {
@@ -1,9 +1,310 @@
 use anyhow::{Context as _, Result};
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
+use super::csv_dialect::csv_separator;
+use super::date_format::{DateOrder, infer_column_date_format, parse_dates_expr};
+use super::layout::{
+    LAYOUT_MAX_CSV_BYTES, LayoutOptions, LayoutReport, drop_footer_rows, fill_merged_headers,
+};
+
+/// What to do when a header name appears more than once
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateColumnPolicy {
+    /// Keep every column, renaming repeats to `name_2`, `name_3`, ...
+    #[default]
+    AutoSuffix,
+    /// Keep the first column with each name and drop the others
+    KeepFirst,
+    /// Keep the last column with each name and drop the others
+    KeepLast,
+    /// Refuse to load the file
+    Error,
+}
+
+/// Where a loaded column came from in the file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnOrigin {
+    /// Name the column is loaded as
+    pub name: String,
+    /// Header name in the file
+    pub source_name: String,
+    /// Zero-based position in the file
+    pub position: usize,
+}
+
+/// How the columns of a file with repeated header names were loaded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnMapping {
+    pub policy: DuplicateColumnPolicy,
+    /// Loaded columns, in file order
+    pub columns: Vec<ColumnOrigin>,
+    /// Columns left out by `KeepFirst` or `KeepLast`
+    pub dropped: Vec<ColumnOrigin>,
+    /// Header names that appear more than once, in file order
+    pub duplicates: Vec<String>,
+}
+
+impl ColumnMapping {
+    /// Apply `policy` to the header names of a file. Fails under
+    /// [`DuplicateColumnPolicy::Error`] if any name repeats.
+    pub fn resolve(headers: &[String], policy: DuplicateColumnPolicy) -> Result<Self> {
+        let mut positions: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut duplicates = Vec::new();
+        for (position, name) in headers.iter().enumerate() {
+            let seen = positions.entry(name.as_str()).or_default();
+            seen.push(position);
+            if seen.len() == 2 {
+                duplicates.push(name.clone());
+            }
+        }
+
+        if policy == DuplicateColumnPolicy::Error && !duplicates.is_empty() {
+            let detail: Vec<String> = duplicates
+                .iter()
+                .map(|name| {
+                    let columns: Vec<String> = positions[name.as_str()]
+                        .iter()
+                        .map(|p| (p + 1).to_string())
+                        .collect();
+                    format!("'{name}' (columns {})", columns.join(", "))
+                })
+                .collect();
+            anyhow::bail!("Duplicate column names in header: {}", detail.join(", "));
+        }
+
+        let mut taken: HashSet<String> = headers.iter().cloned().collect();
+        let mut columns = Vec::with_capacity(headers.len());
+        let mut dropped = Vec::new();
+        for (position, source_name) in headers.iter().enumerate() {
+            let occurrences = &positions[source_name.as_str()];
+            let mut origin = ColumnOrigin {
+                name: source_name.clone(),
+                source_name: source_name.clone(),
+                position,
+            };
+            let keep = match policy {
+                DuplicateColumnPolicy::KeepFirst => occurrences.first() == Some(&position),
+                DuplicateColumnPolicy::KeepLast => occurrences.last() == Some(&position),
+                DuplicateColumnPolicy::AutoSuffix | DuplicateColumnPolicy::Error => true,
+            };
+            if !keep {
+                dropped.push(origin);
+                continue;
+            }
+            if policy == DuplicateColumnPolicy::AutoSuffix && occurrences[0] != position {
+                let mut n = occurrences.iter().position(|&p| p == position).unwrap_or(1) + 1;
+                while taken.contains(&format!("{source_name}_{n}")) {
+                    n += 1;
+                }
+                origin.name = format!("{source_name}_{n}");
+                taken.insert(origin.name.clone());
+            }
+            columns.push(origin);
+        }
+
+        Ok(Self {
+            policy,
+            columns,
+            dropped,
+            duplicates,
+        })
+    }
+
+    pub fn has_duplicates(&self) -> bool {
+        !self.duplicates.is_empty()
+    }
+
+    /// What happened to the repeated columns, for the activity log and the
+    /// analyser
+    pub fn notice(&self) -> Option<String> {
+        if !self.has_duplicates() {
+            return None;
+        }
+        let describe = |o: &ColumnOrigin| format!("'{}' (column {})", o.name, o.position + 1);
+        let outcome = match self.policy {
+            DuplicateColumnPolicy::AutoSuffix => {
+                let renamed: Vec<String> = self
+                    .columns
+                    .iter()
+                    .filter(|o| o.name != o.source_name)
+                    .map(describe)
+                    .collect();
+                format!("Repeats were renamed to {}", renamed.join(", "))
+            }
+            DuplicateColumnPolicy::KeepFirst | DuplicateColumnPolicy::KeepLast => {
+                let which = if self.policy == DuplicateColumnPolicy::KeepFirst {
+                    "first"
+                } else {
+                    "last"
+                };
+                let dropped: Vec<String> = self.dropped.iter().map(describe).collect();
+                format!(
+                    "Kept the {which} column with each name and dropped {}",
+                    dropped.join(", ")
+                )
+            }
+            // Never resolves a header with repeated names
+            DuplicateColumnPolicy::Error => return None,
+        };
+        Some(format!(
+            "Repeated header names: {}. {outcome}.",
+            self.duplicates.join(", ")
+        ))
+    }
+
+    /// Select and rename the columns of `lf`, a scan of the same file that
+    /// has one column per header position
+    pub fn apply(&self, mut lf: LazyFrame) -> Result<LazyFrame> {
+        let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+        let physical: Vec<&PlSmallStr> = schema.iter_names().collect();
+        let exprs = self
+            .columns
+            .iter()
+            .map(|origin| {
+                let name = physical.get(origin.position).with_context(|| {
+                    format!(
+                        "File has no column {} for '{}'",
+                        origin.position + 1,
+                        origin.name
+                    )
+                })?;
+                Ok(col((*name).clone()).alias(origin.name.as_str()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(lf.select(exprs))
+    }
+}
+
+/// Rows read to tell whether a column under a blank header cell holds data
+const MERGED_HEADER_SAMPLE_ROWS: IdxSize = 100;
+
+/// First `rows` lines of a CSV file as text, header included
+fn read_csv_lines(path: &std::path::Path, rows: IdxSize) -> Result<DataFrame> {
+    LazyCsvReader::new(path)
+        .with_has_header(false)
+        .with_separator(csv_separator(path))
+        .with_infer_schema_length(Some(0))
+        .finish()
+        .context("Failed to scan CSV header")?
+        .limit(rows)
+        .collect()
+        .context("Failed to read CSV header")
+}
+
+/// Header names of a CSV file exactly as written, repeats included
+pub fn read_csv_header(path: &std::path::Path) -> Result<Vec<String>> {
+    let df = read_csv_lines(path, 1)?;
+    df.get_columns()
+        .iter()
+        .map(|c| {
+            Ok(c.as_materialized_series()
+                .str()?
+                .get(0)
+                .unwrap_or_default()
+                .to_owned())
+        })
+        .collect()
+}
+
+/// Apply the duplicate column policy to a CSV scan, naming blank header
+/// cells after merged ones first if `fill_merged` is set. No mapping is
+/// returned when the header has no repeated names.
+fn resolve_csv_header(
+    path: &std::path::Path,
+    lf: LazyFrame,
+    policy: DuplicateColumnPolicy,
+    fill_merged: bool,
+    report: &mut LayoutReport,
+) -> Result<(LazyFrame, Option<ColumnMapping>)> {
+    // An unreadable header fails the scan itself later, with a better error
+    let Ok(mut headers) = read_csv_header(path) else {
+        return Ok((lf, None));
+    };
+    if fill_merged && headers.iter().any(|h| h.trim().is_empty()) {
+        let lines = read_csv_lines(path, MERGED_HEADER_SAMPLE_ROWS + 1)?;
+        let columns = lines.get_columns();
+        report.filled_headers = fill_merged_headers(&mut headers, |position| {
+            columns
+                .get(position)
+                .and_then(|c| c.as_materialized_series().str().ok())
+                .is_some_and(|ca| {
+                    ca.into_iter()
+                        .skip(1)
+                        .flatten()
+                        .any(|v| !v.trim().is_empty())
+                })
+        });
+    }
+    // Polars names blank header cells the same way
+    for (i, header) in headers.iter_mut().enumerate() {
+        if header.trim().is_empty() {
+            *header = format!("column_{}", i + 1);
+        }
+    }
+    let unique: HashSet<&String> = headers.iter().collect();
+    if unique.len() == headers.len() && report.filled_headers.is_empty() {
+        return Ok((lf, None));
+    }
+
+    let policy = if unique.len() == headers.len() {
+        DuplicateColumnPolicy::AutoSuffix
+    } else {
+        policy
+    };
+    let mapping = ColumnMapping::resolve(&headers, policy)
+        .with_context(|| format!("Cannot load {}", path.display()))?;
+    if let Some(notice) = mapping.notice() {
+        crate::config::log_event("Analyser", &format!("{}: {notice}", path.display()));
+    }
+    let lf = mapping.apply(lf)?;
+    Ok((lf, mapping.has_duplicates().then_some(mapping)))
+}
+
+/// A scanned file and what loading changed about it
+pub struct LoadedFrame {
+    pub lf: LazyFrame,
+    /// How columns were mapped when the header repeats a name
+    pub column_mapping: Option<ColumnMapping>,
+    /// Merged header cells named and footer rows dropped
+    pub layout: LayoutReport,
+}
+
+/// Resolve the header of a CSV scan and apply the `layout` fixes. Footer
+/// rows are only looked for in files up to [`LAYOUT_MAX_CSV_BYTES`].
+fn finish_csv_scan(
+    path: &std::path::Path,
+    lf: LazyFrame,
+    policy: DuplicateColumnPolicy,
+    layout: LayoutOptions,
+) -> Result<LoadedFrame> {
+    let mut report = LayoutReport::default();
+    let (mut lf, column_mapping) =
+        resolve_csv_header(path, lf, policy, layout.fill_merged_headers, &mut report)?;
+
+    let small = std::fs::metadata(path).is_ok_and(|m| m.len() <= LAYOUT_MAX_CSV_BYTES);
+    if layout.drop_footer_rows && small {
+        let df = lf.collect().context("Failed to read CSV")?;
+        lf = drop_footer_rows(df, &mut report)?.lazy();
+    }
+    report.log(&path.display().to_string());
+    Ok(LoadedFrame {
+        lf,
+        column_mapping,
+        layout: report,
+    })
+}
+
 pub fn load_df(path: &std::path::Path, _progress: &Arc<AtomicU64>) -> Result<DataFrame> {
+    if let Some((file, sheet)) = super::excel::workbook_source(path) {
+        let df = super::excel::read_sheet(&file, sheet.as_deref())?;
+        return try_parse_temporal_columns(df, DateOrder::default());
+    }
+
     let ext = path
         .extension()
         .and_then(|s| s.to_str())
@@ -11,12 +312,25 @@ pub fn load_df(path: &std::path::Path, _progress: &Arc<AtomicU64>) -> Result<Dat
         .to_lowercase();
 
     let df = match ext.as_str() {
-        "csv" => LazyCsvReader::new(path)
-            .with_infer_schema_length(Some(10000))
-            .with_has_header(true)
-            .finish()?
+        "csv" => {
+            let lf = LazyCsvReader::new(path)
+                .with_infer_schema_length(Some(10000))
+                .with_has_header(true)
+                .with_separator(csv_separator(path))
+                .finish()?;
+            finish_csv_scan(
+                path,
+                lf,
+                DuplicateColumnPolicy::default(),
+                LayoutOptions::default(),
+            )?
+            .lf
+            .collect()
+            .context("Failed to read CSV")?
+        }
+        "parquet" if path.is_dir() => scan_parquet_dir(path)?
             .collect()
-            .context("Failed to read CSV")?,
+            .context("Failed to read Parquet")?,
         "parquet" => ParquetReader::new(std::fs::File::open(path)?)
             .finish()
             .context("Failed to read Parquet")?,
@@ -26,29 +340,29 @@ pub fn load_df(path: &std::path::Path, _progress: &Arc<AtomicU64>) -> Result<Dat
         _ => return Err(anyhow::anyhow!("Unsupported file extension: {ext}")),
     };
 
-    try_parse_temporal_columns(df)
+    try_parse_temporal_columns(df, DateOrder::default())
 }
 
-pub fn try_parse_temporal_columns(df: DataFrame) -> Result<DataFrame> {
-    let mut df = df;
-    let schema = df.schema();
-
-    for (name, dtype) in schema.iter() {
-        if dtype.is_numeric() || dtype.is_temporal() || dtype.is_bool() {
-            continue;
-        }
-
-        // Try parsing string columns as datetime
-        if let Ok(s) = df.column(name) {
-            let s = s.as_materialized_series();
-            if let Ok(casted) = s.cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
-                && casted.null_count() < s.len() / 2
-            {
-                let _ = df.replace(name, casted);
-            }
-        }
+/// Parse the text columns of `df` holding dates, each with the format
+/// inferred from its first values (see [`super::date_format`]) and
+/// ambiguous day-month dates read in `order`
+pub fn try_parse_temporal_columns(df: DataFrame, order: DateOrder) -> Result<DataFrame> {
+    let parsed: Vec<Expr> = df
+        .get_columns()
+        .iter()
+        .filter(|c| c.dtype() == &DataType::String)
+        .filter_map(|c| {
+            let format = infer_column_date_format(c, order)?;
+            Some(parse_dates_expr(col(c.name().as_str()), &format.format).alias(c.name().as_str()))
+        })
+        .collect();
+    if parsed.is_empty() {
+        return Ok(df);
     }
-    Ok(df)
+    df.lazy()
+        .with_columns(parsed)
+        .collect()
+        .context("Failed to parse dates")
 }
 
 pub fn save_df(df: &mut DataFrame, path: &std::path::Path) -> Result<()> {
@@ -100,20 +414,96 @@ pub fn get_parquet_write_options(lf: &LazyFrame) -> Result<ParquetWriteOptions>
     })
 }
 
+/// Scan a folder of Parquet parts (such as a deduplicated version written by
+/// the lifecycle store) as one frame, parts in file name order
+pub fn scan_parquet_dir(dir: &std::path::Path) -> Result<LazyFrame> {
+    let mut parts: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "parquet"))
+        .collect();
+    if parts.is_empty() {
+        anyhow::bail!("No Parquet files in {}", dir.display());
+    }
+    parts.sort();
+
+    let frames = parts
+        .iter()
+        .map(|part| {
+            LazyFrame::scan_parquet(part, Default::default())
+                .with_context(|| format!("Failed to scan Parquet {}", part.display()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    concat(frames, UnionArgs::default()).context("Failed to combine Parquet parts")
+}
+
 pub fn load_df_lazy(path: &std::path::Path) -> Result<LazyFrame> {
+    Ok(load_df_lazy_with_mapping(path, None)?.0)
+}
+
+/// Like [`load_df_lazy`], also returning how columns were mapped when the
+/// header repeats a name. `policy` defaults to
+/// [`DuplicateColumnPolicy::AutoSuffix`].
+pub fn load_df_lazy_with_mapping(
+    path: &std::path::Path,
+    policy: Option<DuplicateColumnPolicy>,
+) -> Result<(LazyFrame, Option<ColumnMapping>)> {
+    let loaded =
+        load_df_lazy_with_layout(path, policy, LayoutOptions::default(), DateOrder::default())?;
+    Ok((loaded.lf, loaded.column_mapping))
+}
+
+/// Like [`load_df_lazy_with_mapping`] with explicit layout fixes for CSV
+/// files and workbooks, also returning what they changed. Text dates in
+/// workbooks are read in `date_order`.
+pub fn load_df_lazy_with_layout(
+    path: &std::path::Path,
+    policy: Option<DuplicateColumnPolicy>,
+    layout: LayoutOptions,
+    date_order: DateOrder,
+) -> Result<LoadedFrame> {
+    // Workbooks are read whole; text dates are parsed as for CSV
+    if let Some((file, sheet)) = super::excel::workbook_source(path) {
+        let (df, report) = super::excel::read_sheet_with_layout(
+            &file,
+            sheet.as_deref(),
+            layout,
+            policy.unwrap_or_default(),
+        )?;
+        report.log(&path.display().to_string());
+        return Ok(LoadedFrame {
+            lf: try_parse_temporal_columns(df, date_order)?.lazy(),
+            column_mapping: None,
+            layout: report,
+        });
+    }
+
     let ext = path
         .extension()
         .and_then(|s| s.to_str())
         .unwrap_or("")
         .to_lowercase();
 
-    match ext.as_str() {
-        "csv" => LazyCsvReader::new(path)
+    if ext == "csv" {
+        let lf = LazyCsvReader::new(path)
             .with_infer_schema_length(Some(10000))
             .with_has_header(true)
+            .with_separator(csv_separator(path))
             .with_try_parse_dates(true)
             .finish()
-            .context("Failed to scan CSV"),
+            .context("Failed to scan CSV")?;
+        return finish_csv_scan(path, lf, policy.unwrap_or_default(), layout);
+    }
+    scan_non_csv(path, &ext).map(|lf| LoadedFrame {
+        lf,
+        column_mapping: None,
+        layout: LayoutReport::default(),
+    })
+}
+
+fn scan_non_csv(path: &std::path::Path, ext: &str) -> Result<LazyFrame> {
+    match ext {
+        "parquet" if path.is_dir() => scan_parquet_dir(path),
         "parquet" => {
             LazyFrame::scan_parquet(path, Default::default()).context("Failed to scan Parquet")
         }
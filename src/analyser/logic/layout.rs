@@ -0,0 +1,244 @@
+//! Cleanup of spreadsheet layout: merged header cells and footer rows.
+//!
+//! Reports exported from spreadsheets often merge a header cell across
+//! several columns, which leaves the header cells after it blank, and end
+//! with a totals row, notes or blank lines. [`fill_merged_headers`] names the
+//! blank header cells after a merged one and [`footer_rows`] counts the
+//! trailing rows that are not data, so the loaders can drop them. What was
+//! changed is kept in a [`LayoutReport`] for the analyser to show.
+
+use anyhow::Result;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Most trailing rows treated as a footer
+pub const FOOTER_MAX_ROWS: usize = 5;
+
+/// CSV files larger than this are not checked for footer rows, which needs
+/// the whole file in memory. Spreadsheet exports are far smaller.
+pub const LAYOUT_MAX_CSV_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Labels marking a totals row, compared without case or a trailing colon
+const TOTAL_LABELS: [&str; 7] = [
+    "total",
+    "totals",
+    "grand total",
+    "subtotal",
+    "sub-total",
+    "sum",
+    "overall",
+];
+
+/// Which layout fixes loading applies to CSV files and workbooks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutOptions {
+    /// Name blank header cells after a merged header cell
+    pub fill_merged_headers: bool,
+    /// Drop trailing totals and blank rows
+    pub drop_footer_rows: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            fill_merged_headers: true,
+            drop_footer_rows: true,
+        }
+    }
+}
+
+/// A blank header cell that was given a name
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilledHeader {
+    /// Zero-based position in the file
+    pub position: usize,
+    pub name: String,
+}
+
+/// What loading changed about a file's layout
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutReport {
+    #[serde(default)]
+    pub filled_headers: Vec<FilledHeader>,
+    /// Rows dropped from the end of the file
+    #[serde(default)]
+    pub dropped_footer_rows: usize,
+    /// Label of the dropped totals row, if there was one
+    #[serde(default)]
+    pub footer_label: Option<String>,
+}
+
+impl LayoutReport {
+    pub fn is_empty(&self) -> bool {
+        self.filled_headers.is_empty() && self.dropped_footer_rows == 0
+    }
+
+    /// One line per change, for the activity log and the analyser
+    pub fn notices(&self) -> Vec<String> {
+        let mut notices = Vec::new();
+        if !self.filled_headers.is_empty() {
+            let names: Vec<String> = self
+                .filled_headers
+                .iter()
+                .map(|h| format!("'{}' (column {})", h.name, h.position + 1))
+                .collect();
+            notices.push(format!(
+                "Blank header cells after merged headers were named {}.",
+                names.join(", ")
+            ));
+        }
+        if self.dropped_footer_rows > 0 {
+            let rows = if self.dropped_footer_rows == 1 {
+                "row".to_owned()
+            } else {
+                format!("{} rows", self.dropped_footer_rows)
+            };
+            let label = self
+                .footer_label
+                .as_ref()
+                .map(|label| format!(", including a '{label}' row"))
+                .unwrap_or_default();
+            notices.push(format!(
+                "Dropped the last {rows} as a totals or blank footer{label}."
+            ));
+        }
+        notices
+    }
+
+    /// Log every notice under `source`
+    pub fn log(&self, source: &str) {
+        for notice in self.notices() {
+            crate::config::log_event("Analyser", &format!("{source}: {notice}"));
+        }
+    }
+}
+
+/// Name blank header cells that follow a named one, as a header merged
+/// across columns leaves them: `Q1, "", ""` becomes `Q1, Q1_2, Q1_3`. A
+/// column for which `has_data` is false ends the span and stays blank, so
+/// empty trailing columns are not named.
+pub fn fill_merged_headers(
+    headers: &mut [String],
+    has_data: impl Fn(usize) -> bool,
+) -> Vec<FilledHeader> {
+    let mut filled = Vec::new();
+    // Name of the merged cell and how many columns it spans so far
+    let mut span: Option<(String, usize)> = None;
+    for (position, header) in headers.iter_mut().enumerate() {
+        if !header.trim().is_empty() {
+            span = Some((header.trim().to_owned(), 1));
+            continue;
+        }
+        if !has_data(position) {
+            span = None;
+            continue;
+        }
+        let Some((name, count)) = span.as_mut() else {
+            continue;
+        };
+        *count += 1;
+        *header = format!("{name}_{count}");
+        filled.push(FilledHeader {
+            position,
+            name: header.clone(),
+        });
+    }
+    filled
+}
+
+/// Number of trailing rows of `df` that are a footer (at most
+/// [`FOOTER_MAX_ROWS`], and never every row), and the label of the totals
+/// row among them. A footer row is blank, holds a total label such as
+/// `Total` or `Grand total`, or has a blank first (key) column while every
+/// row above the footer has a key.
+pub fn footer_rows(df: &DataFrame) -> Result<(usize, Option<String>)> {
+    let height = df.height();
+    if height < 2 || df.width() == 0 {
+        return Ok((0, None));
+    }
+    let text: Vec<Column> = df
+        .get_columns()
+        .iter()
+        .map(|c| c.cast(&DataType::String))
+        .collect::<PolarsResult<_>>()?;
+    let cells: Vec<&StringChunked> = text
+        .iter()
+        .map(|c| c.as_materialized_series().str())
+        .collect::<PolarsResult<_>>()?;
+
+    let (count, label) = trailing_footer(&cells, height, true);
+    // A blank key only marks a footer if the key column is otherwise complete
+    let key_complete = (0..height - count).all(|row| !is_blank(cells[0], row));
+    if count == 0 || key_complete {
+        return Ok((count, label));
+    }
+    Ok(trailing_footer(&cells, height, false))
+}
+
+fn trailing_footer(
+    cells: &[&StringChunked],
+    height: usize,
+    blank_key_counts: bool,
+) -> (usize, Option<String>) {
+    let mut count = 0;
+    let mut label = None;
+    while count < FOOTER_MAX_ROWS.min(height - 1) {
+        let row = height - 1 - count;
+        let total = cells
+            .iter()
+            .find_map(|ca| ca.get(row).filter(|v| is_total_label(v)));
+        let footer = total.is_some()
+            || cells.iter().all(|ca| is_blank(ca, row))
+            || (blank_key_counts && is_blank(cells[0], row));
+        if !footer {
+            break;
+        }
+        if let Some(total) = total {
+            label = Some(total.trim().to_owned());
+        }
+        count += 1;
+    }
+    (count, label)
+}
+
+fn is_blank(ca: &StringChunked, row: usize) -> bool {
+    ca.get(row).is_none_or(|v| v.trim().is_empty())
+}
+
+fn is_total_label(value: &str) -> bool {
+    let value = value.trim().trim_end_matches(':').to_lowercase();
+    TOTAL_LABELS.contains(&value.as_str())
+        || value.starts_with("total ")
+        || value.starts_with("grand total")
+}
+
+/// Drop the footer rows of `df`, if any. Text columns that only held text
+/// because of a footer label become numeric again.
+pub fn drop_footer_rows(df: DataFrame, report: &mut LayoutReport) -> Result<DataFrame> {
+    let (count, label) = footer_rows(&df)?;
+    if count == 0 {
+        return Ok(df);
+    }
+    report.dropped_footer_rows = count;
+    report.footer_label = label;
+
+    let kept = df.height() - count;
+    // Only columns with a value in the footer can have been made text by it
+    let footer = df.slice(kept as i64, count);
+    let text_columns: Vec<PlSmallStr> = footer
+        .get_columns()
+        .iter()
+        .filter(|c| c.dtype() == &DataType::String && c.null_count() < count)
+        .map(|c| c.name().clone())
+        .collect();
+    let mut df = df.slice(0, kept);
+    for name in text_columns {
+        let series = df.column(&name)?.as_materialized_series().clone();
+        let inferred = super::orientation::infer_numeric(series);
+        if inferred.dtype() != &DataType::String {
+            df.replace(&name, inferred)?;
+        }
+    }
+    Ok(df)
+}
@@ -1,33 +1,72 @@
 use anyhow::{Context as _, Result, anyhow};
 use linfa::prelude::*;
-use linfa_linear::LinearRegression;
-use linfa_logistic::LogisticRegression;
+use linfa_clustering::KMeans;
+use linfa_elasticnet::ElasticNet;
+use linfa_linear::{FittedLinearRegression, LinearRegression};
+use linfa_logistic::{FittedLogisticRegression, LogisticRegression};
 use linfa_trees::DecisionTree;
-use ndarray::Array1;
+use ndarray::{Array1, Array2, ArrayView2, Axis};
 use polars::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
-use super::types::{MlModelKind, MlResults};
+use super::types::{
+    CrossValidationResults, FeatureImportance, FeatureImportanceReport, MlHyperparameters,
+    MlModelKind, MlResults, MlTask, RandomForestConfig, TextVectorMethod, TextVectoriser,
+    ValidationConfig,
+};
 
-#[expect(clippy::too_many_lines)]
 pub fn train_model(
     df: &DataFrame,
     target_col: &str,
     model_kind: MlModelKind,
     progress: &Arc<AtomicU64>,
+) -> Result<MlResults> {
+    train_model_with_params(
+        df,
+        target_col,
+        model_kind,
+        &MlHyperparameters::default(),
+        progress,
+    )
+}
+
+/// Train `model_kind` using the given hyperparameters.
+///
+/// `target_col` is ignored for models that do not use a target (k-means),
+/// other than being excluded from the features when present.
+#[expect(clippy::too_many_lines)]
+pub fn train_model_with_params(
+    df: &DataFrame,
+    target_col: &str,
+    model_kind: MlModelKind,
+    params: &MlHyperparameters,
+    progress: &Arc<AtomicU64>,
 ) -> Result<MlResults> {
     let start = Instant::now();
     progress.store(10, Ordering::SeqCst);
 
     // 0. Filter out rows where the target is null, as we cannot train on them
-    let df = df.filter(
-        &df.column(target_col)
-            .context("Target column not found")?
-            .is_not_null(),
-    )?;
+    let mut df = if model_kind.requires_target() {
+        df.filter(
+            &df.column(target_col)
+                .context("Target column not found")?
+                .is_not_null(),
+        )?
+    } else {
+        df.clone()
+    };
+    if let Some(config) = &params.text_features {
+        let text_cols: Vec<String> = df
+            .get_columns()
+            .iter()
+            .filter(|c| c.dtype() == &DataType::String && c.name().as_str() != target_col)
+            .map(|c| c.name().to_string())
+            .collect();
+        df = vectorise_text(&df, &text_cols, config, true)?;
+    }
     progress.store(20, Ordering::SeqCst);
 
     if df.height() == 0 {
@@ -36,6 +75,12 @@ pub fn train_model(
         ));
     }
 
+    if model_kind == MlModelKind::KMeans {
+        let results = train_kmeans(&df, target_col, params, start)?;
+        progress.store(100, Ordering::SeqCst);
+        return Ok(results);
+    }
+
     // 1. Prepare Features
     let feature_cols: Vec<String> = df
         .get_column_names()
@@ -75,7 +120,7 @@ pub fn train_model(
     // Validate target for classification
     if matches!(
         model_kind,
-        MlModelKind::LogisticRegression | MlModelKind::DecisionTree
+        MlModelKind::LogisticRegression | MlModelKind::DecisionTree | MlModelKind::RandomForest
     ) {
         let n_unique = target_series.n_unique()?;
         if n_unique < 2 {
@@ -88,122 +133,618 @@ pub fn train_model(
         }
     }
 
+    let y = if matches!(
+        model_kind,
+        MlModelKind::LinearRegression | MlModelKind::Ridge | MlModelKind::Lasso
+    ) {
+        Target::Continuous(
+            target_series
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_no_null_iter()
+                .collect(),
+        )
+    } else {
+        Target::Classes(
+            target_series
+                .cast(&DataType::UInt32)?
+                .u32()?
+                .into_no_null_iter()
+                .map(|v| v as usize)
+                .collect(),
+        )
+    };
+
+    let validation = &params.validation;
+    let order = row_order(x.nrows(), validation);
+    let (train_idx, test_idx) = split_rows(&order, validation.test_ratio)?;
+
+    let model = fit_model(
+        model_kind,
+        params,
+        x.select(Axis(0), &train_idx),
+        &y.select(&train_idx),
+    )?;
+    let train_metrics = model.evaluate(x.select(Axis(0), &train_idx), &y.select(&train_idx))?;
+    let test_metrics = model.evaluate(x.select(Axis(0), &test_idx), &y.select(&test_idx))?;
+    progress.store(80, Ordering::SeqCst);
+
+    let cross_validation = validation
+        .cv_folds
+        .map(|folds| cross_validate(model_kind, params, &x, &y, &order, folds))
+        .transpose()?;
+
     let mut results = MlResults {
         model_kind,
         target_column: target_col.to_owned(),
         feature_columns: feature_cols.clone(),
+        r2_score: test_metrics.r2,
+        accuracy: test_metrics.accuracy,
+        mse: test_metrics.mse,
+        train_r2_score: train_metrics.r2,
+        train_accuracy: train_metrics.accuracy,
+        train_mse: train_metrics.mse,
+        train_rows: train_idx.len(),
+        test_rows: test_idx.len(),
+        cross_validation,
+        duration: start.elapsed(),
+        coefficients: None,
+        intercept: None,
+        interpretation: Vec::new(),
+        feature_importances: None,
+        inertia: None,
+        cluster_sizes: None,
+    };
+
+    match &model {
+        FittedModel::Linear(model) => {
+            results.coefficients = Some(named(&feature_cols, model.params().iter().copied()));
+            results.intercept = Some(model.intercept());
+        }
+        FittedModel::ElasticNet(model) => {
+            results.coefficients = Some(named(&feature_cols, model.hyperplane().iter().copied()));
+            results.intercept = Some(model.intercept());
+        }
+        FittedModel::Forest(forest) => {
+            let importances = forest.feature_importances(feature_cols.len());
+            results.feature_importances = Some(named(&feature_cols, importances));
+        }
+        FittedModel::Tree(_) | FittedModel::Logistic(_) => {}
+    }
+    results.duration = start.elapsed();
+    generate_interpretation(&mut results);
+    progress.store(100, Ordering::SeqCst);
+    Ok(results)
+}
+
+fn train_kmeans(
+    df: &DataFrame,
+    target_col: &str,
+    params: &MlHyperparameters,
+    start: Instant,
+) -> Result<MlResults> {
+    let config = &params.kmeans;
+    let feature_cols: Vec<String> = df
+        .get_column_names()
+        .iter()
+        .map(|s| s.to_string())
+        .filter(|s| s != target_col)
+        .filter(|s| {
+            let col = df.column(s).expect("Column exists");
+            col.dtype().is_numeric() || col.dtype().is_bool()
+        })
+        .collect();
+
+    if feature_cols.is_empty() {
+        return Err(anyhow!(
+            "No numeric feature columns found for clustering. Make sure to clean/preprocess your data first."
+        ));
+    }
+
+    let x = df
+        .select(&feature_cols)?
+        .drop_nulls::<String>(None)?
+        .iter()
+        .map(|s| s.cast(&DataType::Float64).map(Column::from))
+        .collect::<PolarsResult<Vec<_>>>()
+        .map(DataFrame::new)?
+        .map_err(|e| anyhow!("Failed to cast features: {e}"))?
+        .to_ndarray::<Float64Type>(IndexOrder::C)
+        .context("Failed to create feature matrix")?;
+
+    if config.n_clusters < 2 || x.nrows() < config.n_clusters {
+        return Err(anyhow!(
+            "K-Means failed: need at least 2 clusters and at least as many complete rows as clusters (found {} rows for {} clusters).",
+            x.nrows(),
+            config.n_clusters
+        ));
+    }
+
+    let dataset = DatasetBase::from(x.clone());
+    let model = KMeans::params(config.n_clusters)
+        .max_n_iterations(config.max_iterations)
+        .tolerance(config.tolerance)
+        .fit(&dataset)
+        .map_err(|e| anyhow!("K-Means training failed: {e}"))?;
+
+    let labels: Array1<usize> = model.predict(&x);
+    let mut cluster_sizes = vec![0; config.n_clusters];
+    for label in &labels {
+        if let Some(size) = cluster_sizes.get_mut(*label) {
+            *size += 1;
+        }
+    }
+
+    let mut results = MlResults {
+        model_kind: MlModelKind::KMeans,
+        target_column: String::new(),
+        feature_columns: feature_cols,
         r2_score: None,
         accuracy: None,
         mse: None,
+        train_r2_score: None,
+        train_accuracy: None,
+        train_mse: None,
+        train_rows: x.nrows(),
+        test_rows: 0,
+        cross_validation: None,
         duration: start.elapsed(),
         coefficients: None,
         intercept: None,
         interpretation: Vec::new(),
+        feature_importances: None,
+        inertia: Some(model.inertia()),
+        cluster_sizes: Some(cluster_sizes),
     };
+    generate_interpretation(&mut results);
+    Ok(results)
+}
 
-    match model_kind {
-        MlModelKind::LinearRegression => {
-            let y: Array1<f64> = target_series
-                .cast(&DataType::Float64)?
-                .f64()?
-                .into_no_null_iter()
+/// Lowercased alphanumeric tokens of `text`, skipping single characters.
+pub fn tokenise(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().nth(1).is_some())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Numeric features for the text `column`: one column per hash bucket
+/// (`<column>_h<i>`) or per TF-IDF term (`<column>_tfidf_<term>`).
+///
+/// Each row is scaled to unit length; null or empty text gives a row of zeros.
+pub fn text_features(df: &DataFrame, column: &str, config: &TextVectoriser) -> Result<DataFrame> {
+    if config.features == 0 {
+        return Err(anyhow!("Text vectorisation needs at least one feature"));
+    }
+    let values = df
+        .column(column)
+        .with_context(|| format!("Column '{column}' not found"))?
+        .str()
+        .with_context(|| format!("Text vectorisation requires a text column, '{column}' is not"))?;
+    let docs: Vec<Vec<String>> = values
+        .into_iter()
+        .map(|text| text.map(tokenise).unwrap_or_default())
+        .collect();
+
+    let (names, mut rows) = match config.method {
+        TextVectorMethod::Hashing => {
+            let buckets = config.features;
+            let rows: Vec<Vec<f64>> = docs
+                .iter()
+                .map(|tokens| {
+                    let mut row = vec![0.0; buckets];
+                    for token in tokens {
+                        row[(fnv1a(token) % buckets as u64) as usize] += 1.0;
+                    }
+                    row
+                })
                 .collect();
+            let names: Vec<String> = (0..buckets).map(|i| format!("{column}_h{i}")).collect();
+            (names, rows)
+        }
+        TextVectorMethod::TfIdf => {
+            let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+            for tokens in &docs {
+                let unique: BTreeSet<&str> = tokens.iter().map(String::as_str).collect();
+                for token in unique {
+                    *doc_freq.entry(token).or_default() += 1;
+                }
+            }
+            // Most widespread terms first, alphabetical among equals
+            let mut terms: Vec<(&str, usize)> = doc_freq.into_iter().collect();
+            terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            terms.truncate(config.features);
 
-            // Split into train (80%) and test (20%)
-            let n_samples = x.nrows();
-            let train_size = (n_samples as f64 * 0.8) as usize;
+            let n_docs = docs.len() as f64;
+            let index: HashMap<&str, usize> = terms
+                .iter()
+                .enumerate()
+                .map(|(i, &(term, _))| (term, i))
+                .collect();
+            let idf: Vec<f64> = terms
+                .iter()
+                .map(|&(_, freq)| ((1.0 + n_docs) / (1.0 + freq as f64)).ln() + 1.0)
+                .collect();
+            let rows: Vec<Vec<f64>> = docs
+                .iter()
+                .map(|tokens| {
+                    let mut row = vec![0.0; terms.len()];
+                    for token in tokens {
+                        if let Some(&i) = index.get(token.as_str()) {
+                            row[i] += idf[i];
+                        }
+                    }
+                    row
+                })
+                .collect();
+            let names: Vec<String> = terms
+                .iter()
+                .map(|(term, _)| format!("{column}_tfidf_{term}"))
+                .collect();
+            (names, rows)
+        }
+    };
 
-            let x_train = x.slice(ndarray::s![..train_size, ..]);
-            let y_train = y.slice(ndarray::s![..train_size]);
-            let x_test = x.slice(ndarray::s![train_size.., ..]);
-            let y_test = y.slice(ndarray::s![train_size..]);
+    for row in &mut rows {
+        let norm = row.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if norm > 0.0 {
+            row.iter_mut().for_each(|v| *v /= norm);
+        }
+    }
 
-            let train_dataset = Dataset::new(x_train.to_owned(), y_train.to_owned());
-            let test_dataset = Dataset::new(x_test.to_owned(), y_test.to_owned());
+    let columns: Vec<Column> = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            Column::new(
+                name.into(),
+                rows.iter().map(|row| row[i]).collect::<Vec<_>>(),
+            )
+        })
+        .collect();
+    Ok(DataFrame::new(columns)?)
+}
 
-            let model = LinearRegression::default()
-                .fit(&train_dataset)
-                .map_err(|e| anyhow!("Linear Regression training failed: {e}"))?;
+/// Append the [`text_features`] of each of `columns` to `df`, optionally
+/// dropping the text columns themselves.
+pub fn vectorise_text(
+    df: &DataFrame,
+    columns: &[String],
+    config: &TextVectoriser,
+    drop_original: bool,
+) -> Result<DataFrame> {
+    let mut out = df.clone();
+    for column in columns {
+        let features = text_features(df, column, config)?;
+        out.hstack_mut(features.get_columns())
+            .with_context(|| format!("Failed to add text features for '{column}'"))?;
+    }
+    if drop_original {
+        out = out.drop_many(columns.iter().map(String::as_str));
+    }
+    Ok(out)
+}
+
+/// 64-bit FNV-1a, used so hash buckets are stable across runs and platforms.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
 
-            let prediction = model.predict(&test_dataset);
-            results.r2_score = Some(prediction.r2(&test_dataset)?);
-            results.mse = Some(prediction.mean_squared_error(&test_dataset)?);
+/// Target values, typed according to the kind of model being trained.
+enum Target {
+    Continuous(Array1<f64>),
+    Classes(Array1<usize>),
+}
+
+impl Target {
+    fn select(&self, rows: &[usize]) -> Self {
+        match self {
+            Self::Continuous(y) => Self::Continuous(y.select(Axis(0), rows)),
+            Self::Classes(y) => Self::Classes(y.select(Axis(0), rows)),
+        }
+    }
+}
+
+/// Scores for one evaluation set; only the fields relevant to the model are set.
+#[derive(Default)]
+struct Metrics {
+    r2: Option<f64>,
+    mse: Option<f64>,
+    accuracy: Option<f64>,
+}
+
+enum FittedModel {
+    Linear(FittedLinearRegression<f64>),
+    ElasticNet(ElasticNet<f64>),
+    Tree(DecisionTree<f64, usize>),
+    Logistic(FittedLogisticRegression<f64, usize>),
+    Forest(RandomForest),
+}
+
+fn fit_model(
+    model_kind: MlModelKind,
+    params: &MlHyperparameters,
+    x: Array2<f64>,
+    y: &Target,
+) -> Result<FittedModel> {
+    match (model_kind, y) {
+        (MlModelKind::LinearRegression, Target::Continuous(y)) => LinearRegression::default()
+            .fit(&Dataset::new(x, y.clone()))
+            .map(FittedModel::Linear)
+            .map_err(|e| anyhow!("Linear Regression training failed: {e}")),
+        (MlModelKind::Ridge | MlModelKind::Lasso, Target::Continuous(y)) => {
+            let config = &params.regularisation;
+            let l1_ratio = if model_kind == MlModelKind::Lasso {
+                1.0
+            } else {
+                0.0
+            };
+            ElasticNet::params()
+                .penalty(config.alpha)
+                .l1_ratio(l1_ratio)
+                .max_iterations(config.max_iterations)
+                .tolerance(config.tolerance)
+                .fit(&Dataset::new(x, y.clone()))
+                .map(FittedModel::ElasticNet)
+                .map_err(|e| anyhow!("{} training failed: {e}", model_kind.as_str()))
+        }
+        (MlModelKind::DecisionTree, Target::Classes(y)) => DecisionTree::params()
+            .fit(&Dataset::new(x, y.clone()))
+            .map(FittedModel::Tree)
+            .map_err(|e| anyhow!("Decision Tree training failed: {e}")),
+        (MlModelKind::LogisticRegression, Target::Classes(y)) => LogisticRegression::default()
+            .fit(&Dataset::new(x, y.clone()))
+            .map(FittedModel::Logistic)
+            .map_err(|e| anyhow!("Logistic Regression training failed: {e}")),
+        (MlModelKind::RandomForest, Target::Classes(y)) => {
+            RandomForest::fit(x, y, &params.random_forest).map(FittedModel::Forest)
+        }
+        _ => Err(anyhow!(
+            "{} cannot be trained on this target",
+            model_kind.as_str()
+        )),
+    }
+}
 
-            let mut coeffs = HashMap::new();
-            for (i, name) in feature_cols.iter().enumerate() {
-                coeffs.insert(name.clone(), model.params()[i]);
+impl FittedModel {
+    fn evaluate(&self, x: Array2<f64>, y: &Target) -> Result<Metrics> {
+        match (self, y) {
+            (Self::Linear(model), Target::Continuous(y)) => {
+                let dataset = Dataset::new(x, y.clone());
+                let prediction = model.predict(&dataset);
+                Ok(Metrics {
+                    r2: Some(prediction.r2(&dataset)?),
+                    mse: Some(prediction.mean_squared_error(&dataset)?),
+                    accuracy: None,
+                })
             }
-            results.coefficients = Some(coeffs);
-            results.intercept = Some(model.intercept());
+            (Self::ElasticNet(model), Target::Continuous(y)) => {
+                let dataset = Dataset::new(x, y.clone());
+                let prediction = model.predict(&dataset);
+                Ok(Metrics {
+                    r2: Some(prediction.r2(&dataset)?),
+                    mse: Some(prediction.mean_squared_error(&dataset)?),
+                    accuracy: None,
+                })
+            }
+            (Self::Tree(model), Target::Classes(y)) => {
+                let dataset = Dataset::new(x, y.clone());
+                let prediction = model.predict(&dataset);
+                let cm = prediction.confusion_matrix(&dataset)?;
+                Ok(classification_metrics(cm.accuracy()))
+            }
+            (Self::Logistic(model), Target::Classes(y)) => {
+                let dataset = Dataset::new(x, y.clone());
+                let prediction = model.predict(&dataset);
+                let cm = prediction.confusion_matrix(&dataset)?;
+                Ok(classification_metrics(cm.accuracy()))
+            }
+            (Self::Forest(forest), Target::Classes(y)) => {
+                let prediction = forest.predict(x.view());
+                let dataset = Dataset::new(x, y.clone());
+                let cm = prediction.confusion_matrix(&dataset)?;
+                Ok(classification_metrics(cm.accuracy()))
+            }
+            _ => Err(anyhow!("Target type does not match the trained model")),
         }
-        MlModelKind::DecisionTree => {
-            let y: Array1<usize> = target_series
-                .cast(&DataType::UInt32)?
-                .u32()?
-                .into_no_null_iter()
-                .map(|v| v as usize)
-                .collect();
+    }
+}
 
-            // Split into train (80%) and test (20%)
-            let n_samples = x.nrows();
-            let train_size = (n_samples as f64 * 0.8) as usize;
+fn classification_metrics(accuracy: f32) -> Metrics {
+    Metrics {
+        accuracy: Some(f64::from(accuracy)),
+        ..Metrics::default()
+    }
+}
 
-            let x_train = x.slice(ndarray::s![..train_size, ..]);
-            let y_train = y.slice(ndarray::s![..train_size]);
-            let x_test = x.slice(ndarray::s![train_size.., ..]);
-            let y_test = y.slice(ndarray::s![train_size..]);
+/// Row indices in the order used for splitting: shuffled with the configured
+/// seed, or in file order when shuffling is disabled.
+fn row_order(n_rows: usize, config: &ValidationConfig) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..n_rows).collect();
+    if config.shuffle {
+        let mut rng = SplitMix64(config.seed);
+        for i in (1..n_rows).rev() {
+            order.swap(i, rng.below(i + 1));
+        }
+    }
+    order
+}
 
-            let train_dataset = Dataset::new(x_train.to_owned(), y_train.to_owned());
-            let test_dataset = Dataset::new(x_test.to_owned(), y_test.to_owned());
+/// Split `order` into train and test rows, keeping at least one row in each.
+fn split_rows(order: &[usize], test_ratio: f64) -> Result<(Vec<usize>, Vec<usize>)> {
+    let n_rows = order.len();
+    if n_rows < 2 {
+        return Err(anyhow!(
+            "Training failed: at least 2 rows are needed to hold out a test set (found {n_rows})."
+        ));
+    }
+    let train_size =
+        ((n_rows as f64 * (1.0 - test_ratio.clamp(0.0, 1.0))) as usize).clamp(1, n_rows - 1);
+    let (train, test) = order.split_at(train_size);
+    Ok((train.to_vec(), test.to_vec()))
+}
+
+/// K-fold cross-validation over all rows, scoring accuracy for classifiers
+/// and R² for regressors on each held-out fold.
+fn cross_validate(
+    model_kind: MlModelKind,
+    params: &MlHyperparameters,
+    x: &Array2<f64>,
+    y: &Target,
+    order: &[usize],
+    folds: usize,
+) -> Result<CrossValidationResults> {
+    if folds < 2 || folds > order.len() {
+        return Err(anyhow!(
+            "Cross-validation needs between 2 and {} folds (requested {folds}).",
+            order.len()
+        ));
+    }
 
-            let model = DecisionTree::params()
-                .fit(&train_dataset)
-                .map_err(|e| anyhow!("Decision Tree training failed: {e}"))?;
+    let mut scores = Vec::with_capacity(folds);
+    for fold in 0..folds {
+        let (test_idx, train_idx): (Vec<usize>, Vec<usize>) = order
+            .iter()
+            .enumerate()
+            .partition(|(pos, _)| pos % folds == fold);
+        let test_idx: Vec<usize> = test_idx.into_iter().map(|(_, row)| *row).collect();
+        let train_idx: Vec<usize> = train_idx.into_iter().map(|(_, row)| *row).collect();
 
-            let prediction = model.predict(&test_dataset);
-            let cm = prediction.confusion_matrix(&test_dataset)?;
-            results.accuracy = Some(cm.accuracy() as f64);
+        let model = fit_model(
+            model_kind,
+            params,
+            x.select(Axis(0), &train_idx),
+            &y.select(&train_idx),
+        )
+        .with_context(|| format!("Cross-validation fold {} failed", fold + 1))?;
+        let metrics = model.evaluate(x.select(Axis(0), &test_idx), &y.select(&test_idx))?;
+        if let Some(score) = metrics.accuracy.or(metrics.r2) {
+            scores.push(score);
         }
-        MlModelKind::LogisticRegression => {
-            let y: Array1<usize> = target_series
-                .cast(&DataType::UInt32)?
-                .u32()?
-                .into_no_null_iter()
-                .map(|v| v as usize)
-                .collect();
+    }
 
-            // Split into train (80%) and test (20%)
-            let n_samples = x.nrows();
-            let train_size = (n_samples as f64 * 0.8) as usize;
+    let mean = scores.iter().sum::<f64>() / scores.len().max(1) as f64;
+    let variance =
+        scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / scores.len().max(1) as f64;
+    Ok(CrossValidationResults {
+        folds,
+        metric: match y {
+            Target::Classes(_) => "accuracy",
+            Target::Continuous(_) => "r2",
+        }
+        .to_owned(),
+        scores,
+        mean,
+        std_dev: variance.sqrt(),
+    })
+}
 
-            let x_train = x.slice(ndarray::s![..train_size, ..]);
-            let y_train = y.slice(ndarray::s![..train_size]);
-            let x_test = x.slice(ndarray::s![train_size.., ..]);
-            let y_test = y.slice(ndarray::s![train_size..]);
+fn named(features: &[String], values: impl IntoIterator<Item = f64>) -> HashMap<String, f64> {
+    features.iter().cloned().zip(values).collect()
+}
 
-            let train_dataset = Dataset::new(x_train.to_owned(), y_train.to_owned());
-            let test_dataset = Dataset::new(x_test.to_owned(), y_test.to_owned());
+/// Bagged ensemble of decision trees, each fitted on a bootstrap sample of
+/// the rows and a random subset of the features.
+struct RandomForest {
+    /// Feature indices used by each tree, alongside the tree itself.
+    trees: Vec<(Vec<usize>, DecisionTree<f64, usize>)>,
+}
 
-            let model = LogisticRegression::default()
-                .fit(&train_dataset)
-                .map_err(|e| anyhow!("Logistic Regression training failed: {e}"))?;
+impl RandomForest {
+    fn fit(x: Array2<f64>, y: &Array1<usize>, config: &RandomForestConfig) -> Result<Self> {
+        let n_rows = x.nrows();
+        let n_features = x.ncols();
+        if n_rows == 0 || n_features == 0 {
+            return Err(anyhow!("Random Forest training failed: no training data"));
+        }
+        let features_per_tree = match config.feature_ratio {
+            Some(ratio) => (n_features as f64 * ratio.clamp(0.0, 1.0)).ceil() as usize,
+            None => (n_features as f64).sqrt().ceil() as usize,
+        }
+        .clamp(1, n_features);
 
-            let prediction = model.predict(&test_dataset);
-            let cm = prediction.confusion_matrix(&test_dataset)?;
-            results.accuracy = Some(cm.accuracy() as f64);
+        let mut rng = SplitMix64(config.seed);
+        let mut trees = Vec::with_capacity(config.n_trees);
+        for _ in 0..config.n_trees.max(1) {
+            let rows: Vec<usize> = (0..n_rows).map(|_| rng.below(n_rows)).collect();
+            let mut features: Vec<usize> = (0..n_features).collect();
+            // Partial Fisher-Yates shuffle to pick the feature subset
+            for i in 0..features_per_tree {
+                let j = i + rng.below(n_features - i);
+                features.swap(i, j);
+            }
+            features.truncate(features_per_tree);
+            features.sort_unstable();
+
+            let x_sample = x.select(Axis(0), &rows).select(Axis(1), &features);
+            let y_sample = y.select(Axis(0), &rows);
+            let tree = DecisionTree::params()
+                .max_depth(config.max_depth)
+                .fit(&Dataset::new(x_sample, y_sample))
+                .map_err(|e| anyhow!("Random Forest training failed: {e}"))?;
+            trees.push((features, tree));
         }
+        Ok(Self { trees })
     }
 
-    results.duration = start.elapsed();
-    generate_interpretation(&mut results);
-    progress.store(100, Ordering::SeqCst);
-    Ok(results)
+    /// Majority vote across trees.
+    fn predict(&self, x: ArrayView2<'_, f64>) -> Array1<usize> {
+        let mut votes: Vec<HashMap<usize, usize>> = vec![HashMap::new(); x.nrows()];
+        for (features, tree) in &self.trees {
+            let prediction: Array1<usize> = tree.predict(&x.select(Axis(1), features));
+            for (row_votes, class) in votes.iter_mut().zip(prediction) {
+                *row_votes.entry(class).or_default() += 1;
+            }
+        }
+        votes
+            .into_iter()
+            .map(|row_votes| {
+                row_votes
+                    .into_iter()
+                    .max_by_key(|&(class, count)| (count, std::cmp::Reverse(class)))
+                    .map_or(0, |(class, _)| class)
+            })
+            .collect()
+    }
+
+    /// Impurity-based importances averaged over trees and normalised to sum to 1.
+    fn feature_importances(&self, n_features: usize) -> Vec<f64> {
+        let mut totals = vec![0.0; n_features];
+        for (features, tree) in &self.trees {
+            for (&feature, importance) in features.iter().zip(tree.feature_importance()) {
+                if let Some(total) = totals.get_mut(feature) {
+                    *total += importance;
+                }
+            }
+        }
+        normalise(totals)
+    }
+}
+
+/// Small deterministic generator (SplitMix64) so that forest sampling is
+/// reproducible for a given seed without pulling in an RNG crate.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
 }
 
 fn generate_interpretation(res: &mut MlResults) {
     let target = &res.target_column;
     match res.model_kind {
-        MlModelKind::LinearRegression => {
+        MlModelKind::LinearRegression | MlModelKind::Ridge | MlModelKind::Lasso => {
             if let Some(r2) = res.r2_score {
                 let pct = (r2 * 100.0).max(0.0);
                 if r2 > 0.7 {
@@ -229,9 +770,39 @@ fn generate_interpretation(res: &mut MlResults) {
                     let direction = if **val > 0.0 { "increase" } else { "decrease" };
                     res.interpretation.push(format!("Primary Driver: A higher '{name}' usually leads to an {direction} in {target}."));
                 }
+
+                if res.model_kind == MlModelKind::Lasso {
+                    let dropped = coeffs.values().filter(|c| c.abs() < 1e-12).count();
+                    if dropped > 0 {
+                        res.interpretation.push(format!(
+                            "The L1 penalty removed {dropped} of {} features entirely; they add little beyond the others.",
+                            coeffs.len()
+                        ));
+                    }
+                }
+            }
+        }
+        MlModelKind::KMeans => {
+            if let Some(sizes) = &res.cluster_sizes {
+                let total: usize = sizes.iter().sum();
+                res.interpretation.push(format!(
+                    "Rows were grouped into {} clusters using {} features.",
+                    sizes.len(),
+                    res.feature_columns.len()
+                ));
+                if let Some(largest) = sizes.iter().max()
+                    && total > 0
+                {
+                    let pct = *largest as f64 / total as f64 * 100.0;
+                    res.interpretation
+                        .push(format!("The largest cluster holds {pct:.1}% of rows."));
+                    if pct > 80.0 {
+                        res.interpretation.push("One cluster dominates; the data may not have a natural grouping at this cluster count.".to_owned());
+                    }
+                }
             }
         }
-        MlModelKind::DecisionTree | MlModelKind::LogisticRegression => {
+        MlModelKind::DecisionTree | MlModelKind::LogisticRegression | MlModelKind::RandomForest => {
             if let Some(acc) = res.accuracy {
                 let pct = acc * 100.0;
                 res.interpretation.push(format!(
@@ -244,6 +815,328 @@ fn generate_interpretation(res: &mut MlResults) {
                     res.interpretation.push("The model is not much better than a coin flip; consider adding more relevant features.".to_owned());
                 }
             }
+
+            if let Some(importances) = &res.feature_importances {
+                let mut sorted: Vec<_> = importances.iter().collect();
+                sorted.sort_by(|a, b| b.1.total_cmp(a.1));
+                for (name, importance) in sorted.iter().take(3) {
+                    res.interpretation.push(format!(
+                        "Key Feature: '{name}' accounts for {:.1}% of the forest's decisions.",
+                        **importance * 100.0
+                    ));
+                }
+            }
         }
     }
+
+    push_validation_interpretation(res);
+}
+
+/// Held-out R² or accuracy this far below the in-sample score suggests the
+/// model has memorised the training rows.
+const OVERFIT_GAP: f64 = 0.1;
+
+fn push_validation_interpretation(res: &mut MlResults) {
+    let in_sample = res.train_accuracy.or(res.train_r2_score);
+    let held_out = res.accuracy.or(res.r2_score);
+    if let (Some(train), Some(test)) = (in_sample, held_out)
+        && train - test > OVERFIT_GAP
+    {
+        res.interpretation.push(format!(
+            "Possible overfitting: the model scores {:.1}% on training rows but {:.1}% on the {} held-out rows.",
+            train * 100.0,
+            test * 100.0,
+            res.test_rows
+        ));
+    }
+
+    if let Some(cv) = &res.cross_validation {
+        res.interpretation.push(format!(
+            "{}-fold cross-validation {}: {:.3} ± {:.3}.",
+            cv.folds, cv.metric, cv.mean, cv.std_dev
+        ));
+    }
+}
+
+/// Number of equal-frequency bins used to discretise continuous values when
+/// estimating mutual information.
+const MI_BINS: usize = 10;
+
+/// Numeric targets with at most this many distinct integer values are
+/// treated as class labels rather than a continuous quantity.
+const MAX_CLASSIFICATION_CLASSES: usize = 20;
+
+/// Rank the numeric features of `df` by how much they tell us about `target`.
+///
+/// Every feature is scored by its mutual information with the target and by
+/// its importance in a fitted model: a decision tree for categorical targets,
+/// or the absolute standardised coefficients of a linear regression for
+/// continuous ones.
+pub fn feature_importance(df: &DataFrame, target: &str) -> Result<FeatureImportanceReport> {
+    let start = Instant::now();
+
+    let df = df.filter(
+        &df.column(target)
+            .context("Target column not found")?
+            .is_not_null(),
+    )?;
+    if df.height() == 0 {
+        return Err(anyhow!(
+            "Feature importance failed: All rows in target column '{target}' are empty (null)."
+        ));
+    }
+
+    let mut feature_cols = Vec::new();
+    let mut feature_data = Vec::new();
+    let mut skipped_columns = Vec::new();
+    for column in df.get_columns() {
+        let name = column.name().to_string();
+        if name == target {
+            continue;
+        }
+        if !(column.dtype().is_numeric() || column.dtype().is_bool()) {
+            skipped_columns.push(name);
+            continue;
+        }
+        // Mean-impute so that sparse features can still be scored
+        let series = column
+            .as_materialized_series()
+            .cast(&DataType::Float64)?
+            .fill_null(FillNullStrategy::Mean)?;
+        if series.null_count() > 0 {
+            skipped_columns.push(name);
+            continue;
+        }
+        feature_cols.push(name);
+        feature_data.push(Column::from(series));
+    }
+
+    if feature_cols.is_empty() {
+        return Err(anyhow!(
+            "No numeric feature columns found for feature importance. Make sure to clean/preprocess your data first."
+        ));
+    }
+
+    let x = DataFrame::new(feature_data)?
+        .to_ndarray::<Float64Type>(IndexOrder::C)
+        .context("Failed to create feature matrix")?;
+
+    let target_series = df
+        .column(target)
+        .context("Target column not found")?
+        .as_materialized_series();
+    let task = infer_task(target_series)?;
+
+    let (target_labels, model_kind, model_importance) = match task {
+        MlTask::Classification => {
+            let y = encode_classes(target_series)?;
+            let dataset = Dataset::new(x.clone(), Array1::from(y.clone()));
+            let importance = DecisionTree::params()
+                .fit(&dataset)
+                .ok()
+                .map(|model| normalise(model.feature_importance()));
+            (y, MlModelKind::DecisionTree, importance)
+        }
+        MlTask::Regression => {
+            let y: Vec<f64> = target_series
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_no_null_iter()
+                .collect();
+            let importance = standardised_coefficients(&x, &y).map(normalise);
+            (
+                discretise(&y, MI_BINS),
+                MlModelKind::LinearRegression,
+                importance,
+            )
+        }
+    };
+
+    let mut features: Vec<FeatureImportance> = feature_cols
+        .into_iter()
+        .enumerate()
+        .map(|(i, feature)| {
+            let values: Vec<f64> = x.column(i).to_vec();
+            FeatureImportance {
+                feature,
+                rank: 0,
+                mutual_information: mutual_information(
+                    &discretise(&values, MI_BINS),
+                    &target_labels,
+                ),
+                model_importance: model_importance
+                    .as_ref()
+                    .and_then(|imp| imp.get(i).copied()),
+            }
+        })
+        .collect();
+
+    features.sort_by(|a, b| {
+        b.mutual_information
+            .total_cmp(&a.mutual_information)
+            .then_with(|| {
+                b.model_importance
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.model_importance.unwrap_or(0.0))
+            })
+    });
+    for (i, feature) in features.iter_mut().enumerate() {
+        feature.rank = i + 1;
+    }
+
+    let mut report = FeatureImportanceReport {
+        target_column: target.to_owned(),
+        task,
+        model_kind,
+        features,
+        skipped_columns,
+        row_count: df.height(),
+        duration: start.elapsed(),
+        interpretation: Vec::new(),
+    };
+    generate_importance_interpretation(&mut report);
+    Ok(report)
+}
+
+fn infer_task(target: &Series) -> Result<MlTask> {
+    let dtype = target.dtype();
+    if !dtype.is_numeric() {
+        return Ok(MlTask::Classification);
+    }
+    let values = target.cast(&DataType::Float64)?;
+    let is_integer = values.f64()?.into_no_null_iter().all(|v| v == v.floor());
+    if is_integer && target.n_unique()? <= MAX_CLASSIFICATION_CLASSES {
+        Ok(MlTask::Classification)
+    } else {
+        Ok(MlTask::Regression)
+    }
+}
+
+/// Map each distinct target value to a class index, in order of appearance.
+fn encode_classes(target: &Series) -> Result<Vec<usize>> {
+    let values = target.cast(&DataType::String)?;
+    let mut classes: HashMap<String, usize> = HashMap::new();
+    Ok(values
+        .str()?
+        .into_no_null_iter()
+        .map(|v| {
+            let next = classes.len();
+            *classes.entry(v.to_owned()).or_insert(next)
+        })
+        .collect())
+}
+
+/// Absolute linear regression coefficients after scaling each feature to
+/// unit variance, so that coefficients are comparable across features.
+fn standardised_coefficients(x: &Array2<f64>, y: &[f64]) -> Option<Vec<f64>> {
+    let mut scaled = x.clone();
+    for mut column in scaled.columns_mut() {
+        let std = column.std(1.0);
+        let mean = column.mean().unwrap_or(0.0);
+        column.mapv_inplace(|v| if std > 0.0 { (v - mean) / std } else { 0.0 });
+    }
+    let dataset = Dataset::new(scaled, Array1::from(y.to_vec()));
+    let model = LinearRegression::default().fit(&dataset).ok()?;
+    Some(model.params().iter().map(|c| c.abs()).collect())
+}
+
+fn normalise(values: Vec<f64>) -> Vec<f64> {
+    let total: f64 = values.iter().filter(|v| v.is_finite()).sum();
+    values
+        .into_iter()
+        .map(|v| {
+            if total > 0.0 && v.is_finite() {
+                v / total
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Assign each value to one of (at most) `bins` equal-frequency bins.
+fn discretise(values: &[f64], bins: usize) -> Vec<usize> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mut cuts: Vec<f64> = (1..bins)
+        .filter_map(|k| sorted.get(k * sorted.len() / bins).copied())
+        .collect();
+    cuts.dedup();
+    values
+        .iter()
+        .map(|v| cuts.partition_point(|c| c <= v))
+        .collect()
+}
+
+/// Mutual information (in nats) between two discrete label sequences.
+fn mutual_information(x: &[usize], y: &[usize]) -> f64 {
+    let n = x.len().min(y.len());
+    if n == 0 {
+        return 0.0;
+    }
+    let mut joint: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut x_counts: HashMap<usize, usize> = HashMap::new();
+    let mut y_counts: HashMap<usize, usize> = HashMap::new();
+    for (&xi, &yi) in x.iter().zip(y) {
+        *joint.entry((xi, yi)).or_default() += 1;
+        *x_counts.entry(xi).or_default() += 1;
+        *y_counts.entry(yi).or_default() += 1;
+    }
+
+    let n = n as f64;
+    joint
+        .iter()
+        .map(|(&(xi, yi), &count)| {
+            let p_xy = count as f64 / n;
+            let p_x = x_counts.get(&xi).copied().unwrap_or(0) as f64 / n;
+            let p_y = y_counts.get(&yi).copied().unwrap_or(0) as f64 / n;
+            p_xy * (p_xy / (p_x * p_y)).ln()
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+fn generate_importance_interpretation(report: &mut FeatureImportanceReport) {
+    let target = &report.target_column;
+    let top: Vec<&str> = report
+        .features
+        .iter()
+        .filter(|f| f.mutual_information > 0.0)
+        .take(3)
+        .map(|f| f.feature.as_str())
+        .collect();
+
+    if top.is_empty() {
+        report.interpretation.push(format!(
+            "None of the numeric features carry measurable information about '{target}'."
+        ));
+    } else {
+        report.interpretation.push(format!(
+            "Most informative features for '{target}': {}.",
+            top.iter()
+                .map(|f| format!("'{f}'"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    let weak: Vec<&str> = report
+        .features
+        .iter()
+        .filter(|f| f.mutual_information < 0.01)
+        .map(|f| f.feature.as_str())
+        .collect();
+    if !weak.is_empty() && weak.len() < report.features.len() {
+        report.interpretation.push(format!(
+            "{} feature(s) show almost no relationship with the target and are candidates for removal.",
+            weak.len()
+        ));
+    }
+
+    if !report.skipped_columns.is_empty() {
+        report.interpretation.push(format!(
+            "{} non-numeric column(s) were not scored; encode them during cleaning to include them.",
+            report.skipped_columns.len()
+        ));
+    }
 }
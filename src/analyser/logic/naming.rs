@@ -1,3 +1,10 @@
+use regex::Regex;
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use super::semantic::{MIN_SEMANTIC_SAMPLES, SEMANTIC_MATCH_THRESHOLD};
+use super::types::{ColumnKind, ColumnStats, ColumnSummary, GeoEncoding, SemanticType};
+
 pub fn sanitize_column_name(name: &str) -> String {
     let mut clean = name.trim().to_lowercase();
 
@@ -37,6 +44,21 @@ pub fn sanitize_column_name(name: &str) -> String {
     }
 }
 
+/// Levenshtein distance over characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+    prev[b.len()]
+}
+
 pub fn sanitize_column_names(names: &[String]) -> Vec<String> {
     let mut cleaned_names = Vec::new();
     let mut seen = std::collections::HashMap::new();
@@ -56,3 +78,148 @@ pub fn sanitize_column_names(names: &[String]) -> Vec<String> {
     }
     cleaned_names
 }
+
+/// Names given to columns without a real header: `Column1`, `F2`,
+/// `column_3`, `Unnamed: 0`, `field 4`, blanks and bare numbers
+static GENERIC_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^(?:(?:col|column|field|f|var|v)[\s_:.-]*\d+|_*(?:unnamed|untitled|duplicated|column)?[\s_:.-]*\d*_*)$",
+    )
+    .expect("valid generic column name regex")
+});
+
+static URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:https?://|www\.)\S+$").expect("valid URL regex"));
+
+static PHONE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\+?\d[\d\s().-]{6,}\d$").expect("valid phone number regex"));
+
+/// `ORD-1042`, `SKU_88`, `AB1234`
+static REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^[A-Za-z]{1,5}[-_/]?\d{2,}$").expect("valid reference code regex")
+});
+
+/// `Ada Lovelace`, `Grace Brewster Hopper`
+static PERSON_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\p{Lu}[\p{L}'-]+(?: \p{Lu}[\p{L}'-]+)+$").expect("valid person name regex")
+});
+
+/// Average length above which text reads as free-form notes
+const DESCRIPTION_MIN_AVG_LENGTH: f64 = 50.0;
+
+/// Whether `name` is a placeholder rather than a real header
+pub fn is_generic_column_name(name: &str) -> bool {
+    GENERIC_NAME.is_match(name.trim())
+}
+
+/// Whether every column has a placeholder name, as when a file has no
+/// header row
+pub fn headers_missing(summaries: &[ColumnSummary]) -> bool {
+    !summaries.is_empty() && summaries.iter().all(|s| is_generic_column_name(&s.name))
+}
+
+/// A name for a column from its content: its semantic type, else the
+/// pattern nearly all of its sampled values follow, else its kind
+pub fn suggest_column_name(summary: &ColumnSummary) -> Option<&'static str> {
+    if summary.kind == ColumnKind::Nested {
+        return None;
+    }
+    if let Some(semantic) = summary.semantic_type {
+        return Some(match semantic {
+            SemanticType::Email => "email_address",
+            SemanticType::CountryCode => "country_code",
+            SemanticType::CurrencyCode => "currency_code",
+            SemanticType::CurrencyAmount => "amount",
+            SemanticType::Postcode => "postcode",
+            SemanticType::IpAddress => "ip_address",
+        });
+    }
+    Some(match &summary.stats {
+        ColumnStats::Temporal(stats) => {
+            if stats.hour_counts.is_empty() {
+                "date"
+            } else {
+                "timestamp"
+            }
+        }
+        ColumnStats::Boolean(_) => "flag",
+        ColumnStats::Numeric(stats) => {
+            let non_null = summary.count.saturating_sub(summary.nulls);
+            let whole_years = stats.is_integer
+                && stats.min.is_some_and(|min| min >= 1900.0)
+                && stats.max.is_some_and(|max| max <= 2100.0);
+            if whole_years {
+                "year"
+            } else if stats.is_integer && stats.is_sorted && stats.distinct_count == non_null {
+                "id"
+            } else if stats.is_integer {
+                "number"
+            } else {
+                "value"
+            }
+        }
+        ColumnStats::Text(stats) => text_pattern_name(&summary.samples).unwrap_or(
+            if stats.avg_length >= DESCRIPTION_MIN_AVG_LENGTH {
+                "description"
+            } else {
+                "text"
+            },
+        ),
+        ColumnStats::Categorical(_) => text_pattern_name(&summary.samples).unwrap_or("category"),
+        ColumnStats::Geo(stats) => match stats.encoding {
+            GeoEncoding::Latitude { .. } => "latitude",
+            GeoEncoding::Longitude { .. } => "longitude",
+            GeoEncoding::Wkt | GeoEncoding::GeoJson => "geometry",
+        },
+    })
+}
+
+/// The pattern nearly all non-empty `samples` follow, as a column name
+fn text_pattern_name(samples: &[String]) -> Option<&'static str> {
+    let values: Vec<&str> = samples
+        .iter()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.len() < MIN_SEMANTIC_SAMPLES {
+        return None;
+    }
+    let patterns: [(&LazyLock<Regex>, &str); 4] = [
+        (&URL, "url"),
+        (&PHONE, "phone_number"),
+        (&REFERENCE, "reference"),
+        (&PERSON_NAME, "full_name"),
+    ];
+    patterns.into_iter().find_map(|(pattern, name)| {
+        let hits = values.iter().filter(|v| pattern.is_match(v)).count();
+        (hits as f64 >= values.len() as f64 * SEMANTIC_MATCH_THRESHOLD).then_some(name)
+    })
+}
+
+/// Fill in [`ColumnSummary::suggested_name`] for columns with placeholder
+/// names. Suggestions are numbered like [`sanitize_column_names`] so they
+/// clash neither with each other nor with the real names.
+pub fn suggest_column_names(summaries: &mut [ColumnSummary]) {
+    let mut taken: HashSet<String> = summaries
+        .iter()
+        .filter(|s| !is_generic_column_name(&s.name))
+        .map(|s| s.standardised_name.clone())
+        .collect();
+    for summary in summaries.iter_mut() {
+        summary.suggested_name = None;
+        if !is_generic_column_name(&summary.name) {
+            continue;
+        }
+        let Some(base) = suggest_column_name(summary) else {
+            continue;
+        };
+        let mut name = base.to_owned();
+        let mut count = 0;
+        while taken.contains(&name) {
+            count += 1;
+            name = format!("{base}_{count}");
+        }
+        taken.insert(name.clone());
+        summary.suggested_name = Some(name);
+    }
+}
@@ -0,0 +1,272 @@
+//! Detection and repair of transposed (record-oriented) files.
+//!
+//! Some exports put each variable on a row and each observation in a column:
+//! the first column holds labels such as `age` or `income` and the header
+//! holds observation ids or dates. Profiling such a file column by column is
+//! meaningless, so [`detect_transposed`] looks for the telltale shape and
+//! [`transpose_df`] swaps rows and columns.
+
+use anyhow::{Context as _, Result};
+use chrono::NaiveDate;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::io::{ColumnMapping, DuplicateColumnPolicy};
+
+/// Files with more rows than this are never treated as transposed, and
+/// [`transpose_df`] refuses to create more columns than this
+pub const TRANSPOSE_MAX_COLUMNS: usize = 500;
+
+/// Default name of the column holding the old column names after a transpose
+pub const TRANSPOSE_NAMES_COLUMN: &str = "field";
+
+/// Why a file looks transposed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransposeDetection {
+    /// First column, whose values look like variable names
+    pub label_column: String,
+    /// Signals that matched, for display
+    pub reasons: Vec<String>,
+    /// Whether rows and columns were swapped before analysis
+    #[serde(default)]
+    pub applied: bool,
+}
+
+impl TransposeDetection {
+    /// Summary for the activity log and the analysis banner
+    pub fn notice(&self) -> String {
+        let reasons = self.reasons.join("; ");
+        if self.applied {
+            format!(
+                "File looks transposed ({reasons}). Rows and columns were swapped before \
+                 analysis, using '{}' values as column names.",
+                self.label_column
+            )
+        } else {
+            format!(
+                "File looks transposed, with variables as rows ({reasons}). Turn on \
+                 auto-transpose in Settings or add a Transpose step to a pipeline."
+            )
+        }
+    }
+}
+
+/// Check whether `df` holds variables as rows and observations as columns.
+///
+/// The first column must be text with a distinct label on every row. Two or
+/// more points are then needed from: more value columns than rows (1), column
+/// names that look like ids or dates (1), and columns mixing numbers and text
+/// while each row holds one kind of value (2).
+pub fn detect_transposed(df: &DataFrame) -> Option<TransposeDetection> {
+    let height = df.height();
+    let width = df.width();
+    if !(2..=TRANSPOSE_MAX_COLUMNS).contains(&height) || width < 3 {
+        return None;
+    }
+
+    let columns = df.get_columns();
+    let labels = columns[0].as_materialized_series().str().ok()?;
+    if labels.null_count() > 0
+        || columns[0].n_unique().ok()? != height
+        || labels.into_no_null_iter().all(looks_numeric)
+    {
+        return None;
+    }
+
+    let mut score = 0;
+    let mut reasons = Vec::new();
+    let values = &columns[1..];
+
+    if values.len() > height {
+        score += 1;
+        reasons.push(format!(
+            "{} value columns but only {height} rows",
+            values.len()
+        ));
+    }
+
+    let id_like = values
+        .iter()
+        .filter(|c| looks_like_identifier(c.name().as_str()))
+        .count();
+    if id_like * 5 >= values.len() * 4 {
+        score += 1;
+        reasons.push("column names look like ids or dates".to_owned());
+    }
+
+    if mixed_columns_uniform_rows(values).unwrap_or(false) {
+        score += 2;
+        reasons.push("columns mix numbers and text while each row holds one kind".to_owned());
+    }
+
+    (score >= 2).then(|| TransposeDetection {
+        label_column: columns[0].name().to_string(),
+        reasons,
+        applied: false,
+    })
+}
+
+/// Detect a transposed file from its first rows and, if `auto_transpose` is
+/// on, swap rows and columns. Returns the (possibly transposed) data and the
+/// detection, if any.
+pub fn apply_orientation(
+    lf: LazyFrame,
+    auto_transpose: bool,
+) -> Result<(LazyFrame, Option<TransposeDetection>)> {
+    // One row past the limit is enough to rule a file out
+    let head = lf
+        .clone()
+        .limit(TRANSPOSE_MAX_COLUMNS as IdxSize + 1)
+        .collect()
+        .context("Failed to read rows for orientation check")?;
+    let Some(mut detection) = detect_transposed(&head) else {
+        return Ok((lf, None));
+    };
+
+    let lf = if auto_transpose {
+        detection.applied = true;
+        transpose_df(&head, Some(&detection.label_column), TRANSPOSE_NAMES_COLUMN)?.lazy()
+    } else {
+        lf
+    };
+    crate::config::log_event("Analyser", &detection.notice());
+    Ok((lf, Some(detection)))
+}
+
+/// Swap rows and columns. Values of `header_column` become the new column
+/// names (`column_1`, `column_2`, ... without one, or for blank labels), and
+/// the old column names go into `names_column`. Columns whose values all
+/// parse as numbers become numeric.
+pub fn transpose_df(
+    df: &DataFrame,
+    header_column: Option<&str>,
+    names_column: &str,
+) -> Result<DataFrame> {
+    let height = df.height();
+    if height > TRANSPOSE_MAX_COLUMNS {
+        anyhow::bail!("Transpose would create {height} columns (limit: {TRANSPOSE_MAX_COLUMNS})");
+    }
+
+    let mut headers = vec![names_column.to_owned()];
+    match header_column {
+        Some(name) => {
+            let labels = df
+                .column(name)
+                .with_context(|| format!("Column '{name}' not found"))?
+                .cast(&DataType::String)?;
+            headers.extend(
+                labels
+                    .as_materialized_series()
+                    .str()?
+                    .into_iter()
+                    .enumerate()
+                    .map(|(row, label)| match label.map(str::trim) {
+                        Some(label) if !label.is_empty() => label.to_owned(),
+                        _ => format!("column_{}", row + 1),
+                    }),
+            );
+        }
+        None => headers.extend((1..=height).map(|row| format!("column_{row}"))),
+    }
+    let headers: Vec<String> = ColumnMapping::resolve(&headers, DuplicateColumnPolicy::AutoSuffix)?
+        .columns
+        .into_iter()
+        .map(|origin| origin.name)
+        .collect();
+
+    let data: Vec<Column> = df
+        .get_columns()
+        .iter()
+        .filter(|c| Some(c.name().as_str()) != header_column)
+        .map(|c| c.cast(&DataType::String))
+        .collect::<PolarsResult<_>>()?;
+    let cells: Vec<&StringChunked> = data
+        .iter()
+        .map(|c| c.as_materialized_series().str())
+        .collect::<PolarsResult<_>>()?;
+
+    let mut columns: Vec<Column> = Vec::with_capacity(headers.len());
+    let field_names: Vec<&str> = data.iter().map(|c| c.name().as_str()).collect();
+    columns.push(Series::new(headers[0].as_str().into(), field_names).into());
+    for (row, name) in headers[1..].iter().enumerate() {
+        let values: Vec<Option<&str>> = cells.iter().map(|ca| ca.get(row)).collect();
+        columns.push(infer_numeric(Series::new(name.as_str().into(), values)).into());
+    }
+
+    DataFrame::new(columns).context("Failed to build transposed data")
+}
+
+/// `series` as Int64 or Float64 if every value parses, otherwise unchanged
+pub(super) fn infer_numeric(series: Series) -> Series {
+    if series.null_count() == series.len() {
+        return series;
+    }
+    [DataType::Int64, DataType::Float64]
+        .iter()
+        .find_map(|dtype| series.strict_cast(dtype).ok())
+        .unwrap_or(series)
+}
+
+fn looks_numeric(value: &str) -> bool {
+    value.trim().parse::<f64>().is_ok()
+}
+
+/// Numbers, dates, and short prefixes followed by digits such as `Q1`,
+/// `obs_12` or `Sample 3`
+fn looks_like_identifier(name: &str) -> bool {
+    let name = name.trim();
+    if looks_numeric(name)
+        || ["%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y"]
+            .iter()
+            .any(|format| NaiveDate::parse_from_str(name, format).is_ok())
+    {
+        return true;
+    }
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    prefix.len() < name.len()
+        && prefix.len() <= 12
+        && prefix
+            .trim_end_matches([' ', '_', '-'])
+            .chars()
+            .all(char::is_alphabetic)
+}
+
+/// Whether most value columns hold both numbers and text while most rows
+/// hold only one of the two
+fn mixed_columns_uniform_rows(values: &[Column]) -> Result<bool> {
+    let text: Vec<Column> = values
+        .iter()
+        .map(|c| c.cast(&DataType::String))
+        .collect::<PolarsResult<_>>()?;
+    let cells: Vec<&StringChunked> = text
+        .iter()
+        .map(|c| c.as_materialized_series().str())
+        .collect::<PolarsResult<_>>()?;
+    let Some(height) = cells.first().map(|ca| ca.len()) else {
+        return Ok(false);
+    };
+
+    // (has numbers, has text) per column and per row
+    let mut by_column = vec![(false, false); cells.len()];
+    let mut by_row = vec![(false, false); height];
+    for (column, ca) in cells.iter().enumerate() {
+        for (row, value) in ca.into_iter().enumerate() {
+            let Some(value) = value.filter(|v| !v.trim().is_empty()) else {
+                continue;
+            };
+            let kinds = if looks_numeric(value) {
+                (true, false)
+            } else {
+                (false, true)
+            };
+            by_column[column].0 |= kinds.0;
+            by_column[column].1 |= kinds.1;
+            by_row[row].0 |= kinds.0;
+            by_row[row].1 |= kinds.1;
+        }
+    }
+
+    let mixed_columns = by_column.iter().filter(|(n, t)| *n && *t).count();
+    let uniform_rows = by_row.iter().filter(|(n, t)| !(*n && *t)).count();
+    Ok(mixed_columns > 0 && mixed_columns * 2 >= cells.len() && uniform_rows * 5 >= height * 4)
+}
@@ -0,0 +1,175 @@
+//! Cleaning presets: saved column cleaning configs reused across files.
+//!
+//! A preset is the config map of one cleaning session, keyed by column
+//! name, saved under `templates/cleaning/<id>.json` in the app data
+//! directory. [`apply_preset`] maps it onto the columns of another file
+//! with [`resolve_columns`]: by exact name first, then by the sanitised name
+//! (so `Customer Name` finds `customer_name`), then by the closest sanitised
+//! name within a small edit distance. Each preset column is used for one
+//! column at most.
+
+use anyhow::{Context as _, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use super::column_match::{ColumnMatchKind, resolve_columns};
+use super::types::ColumnCleanConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleaningPreset {
+    pub id: String,
+    pub name: String,
+    /// Configs by the name of the column they were made for
+    pub configs: HashMap<String, ColumnCleanConfig>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How a file column was matched to a preset column
+pub type PresetMatchKind = ColumnMatchKind;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresetMatch {
+    pub column: String,
+    pub preset_column: String,
+    pub kind: PresetMatchKind,
+}
+
+/// A preset mapped onto the columns of a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppliedPreset {
+    /// Configs for the matched columns, by file column name
+    pub configs: HashMap<String, ColumnCleanConfig>,
+    /// Matches in file column order
+    pub matches: Vec<PresetMatch>,
+    /// File columns the preset has no config for
+    pub unmatched_columns: Vec<String>,
+    /// Preset columns no file column matched
+    pub unused_preset_columns: Vec<String>,
+}
+
+/// Map `preset` onto `columns`, the column names of a file
+pub fn apply_preset(preset: &CleaningPreset, columns: &[String]) -> AppliedPreset {
+    let mut preset_columns: Vec<String> = preset.configs.keys().cloned().collect();
+    preset_columns.sort();
+    let resolution = resolve_columns(&preset_columns, columns);
+
+    AppliedPreset {
+        configs: resolution
+            .matches
+            .iter()
+            .map(|m| (m.column.clone(), preset.configs[&m.expected].clone()))
+            .collect(),
+        matches: resolution
+            .matches
+            .into_iter()
+            .map(|m| PresetMatch {
+                column: m.column,
+                preset_column: m.expected,
+                kind: m.kind,
+            })
+            .collect(),
+        unmatched_columns: resolution.unmatched_columns,
+        unused_preset_columns: resolution.unmatched_expected,
+    }
+}
+
+/// The cleaning presets of one folder
+#[derive(Debug, Clone)]
+pub struct PresetLibrary {
+    dir: PathBuf,
+}
+
+impl PresetLibrary {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The library in the app's templates directory
+    pub fn open_default() -> Self {
+        Self::new(
+            crate::utils::standard_paths()
+                .templates_dir
+                .join("cleaning"),
+        )
+    }
+
+    /// Every preset, by name. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<CleaningPreset>> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()));
+            }
+        };
+        let mut presets: Vec<CleaningPreset> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| std::fs::read_to_string(path).ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        presets.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(presets)
+    }
+
+    pub fn get(&self, id: &str) -> Result<CleaningPreset> {
+        let path = self.preset_path(id)?;
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Cleaning preset not found: {id}"))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Save `configs` as preset `name`, replacing the preset of that name
+    /// (compared without case) if there is one
+    pub fn save(
+        &self,
+        name: &str,
+        configs: HashMap<String, ColumnCleanConfig>,
+    ) -> Result<CleaningPreset> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Preset name is required");
+        }
+        if configs.is_empty() {
+            bail!("Preset has no column configs");
+        }
+        let id = self
+            .list()?
+            .into_iter()
+            .find(|p| p.name.to_lowercase() == name.to_lowercase())
+            .map_or_else(|| Uuid::new_v4().to_string(), |p| p.id);
+        let preset = CleaningPreset {
+            id,
+            name: name.to_owned(),
+            configs,
+            updated_at: Utc::now(),
+        };
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.preset_path(&preset.id)?;
+        let json =
+            serde_json::to_string_pretty(&preset).context("Failed to serialize cleaning preset")?;
+        std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(preset)
+    }
+
+    /// Delete preset `id`. Returns false if no preset has this id.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let path = self.preset_path(id)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    fn preset_path(&self, id: &str) -> Result<PathBuf> {
+        crate::utils::id_path(&self.dir, id, "json", "cleaning preset")
+    }
+}
@@ -15,7 +15,11 @@
 //! The profiling algorithms are designed to work with Polars `LazyFrame` for
 //! memory-efficient processing of datasets that exceed available RAM.
 
-use super::types::{BooleanStats, ColumnKind, ColumnStats, NumericStats, TemporalStats, TextStats};
+use super::interpretation::SKEW_THRESHOLD;
+use super::types::{
+    BooleanStats, ColumnKind, ColumnStats, HistogramOptions, HistogramStrategy, HistogramVariant,
+    NumericStats, TemporalStats, TextStats,
+};
 use anyhow::Result;
 use polars::prelude::*;
 
@@ -59,11 +63,20 @@ pub fn analyse_boolean(col: &Column) -> Result<(ColumnKind, ColumnStats)> {
         ColumnStats::Boolean(BooleanStats {
             true_count,
             false_count,
+            null_count: ca.null_count(),
         }),
     ))
 }
 
 pub fn analyse_numeric(col: &Column, trim_pct: f64) -> Result<(ColumnKind, ColumnStats)> {
+    analyse_numeric_with_options(col, trim_pct, &HistogramOptions::default())
+}
+
+pub fn analyse_numeric_with_options(
+    col: &Column,
+    trim_pct: f64,
+    histogram_options: &HistogramOptions,
+) -> Result<(ColumnKind, ColumnStats)> {
     let series = col.as_materialized_series();
     let ca = series
         .cast(&DataType::Float64)
@@ -88,7 +101,24 @@ pub fn analyse_numeric(col: &Column, trim_pct: f64) -> Result<(ColumnKind, Colum
 
     let skew = calculate_skew(mean, median, q1, q3, std_dev);
     let trimmed_mean = calculate_trimmed_mean(ca, mean, trim_pct);
-    let (bin_width, histogram) = calculate_histogram(ca, min, max, q1, q3);
+    let (histogram_strategy, bin_width, histogram) =
+        calculate_histogram_with_options(ca, min, max, q1, q3, histogram_options);
+    let (log_histogram, zoomed_histogram) = calculate_histogram_variants(
+        ca,
+        VariantInputs {
+            min,
+            max,
+            p05,
+            p95,
+            q1,
+            q3,
+            skew,
+            strategy: histogram_strategy,
+        },
+        histogram_options,
+    );
+
+    let (quantiles, ecdf) = calculate_distribution_tables(ca);
 
     let distinct_count = series.n_unique().unwrap_or(0);
     let zero_count = ca.into_iter().flatten().filter(|&v| v == 0.0).count();
@@ -125,6 +155,11 @@ pub fn analyse_numeric(col: &Column, trim_pct: f64) -> Result<(ColumnKind, Colum
             is_sorted_rev,
             bin_width,
             histogram,
+            histogram_strategy,
+            log_histogram,
+            zoomed_histogram,
+            quantiles,
+            ecdf,
         }),
     ))
 }
@@ -138,15 +173,20 @@ pub fn check_effective_boolean(
     if let (Some(min_val), Some(max_val)) = (min, max) {
         let unique_count = series.n_unique().map_err(|e| anyhow::anyhow!(e))?;
         if unique_count <= 3 && min_val >= 0.0 && max_val <= 1.0 {
-            let true_count = ca.sum().unwrap_or(0.0) as usize;
-            let false_count = (ca.len() - ca.null_count()) - true_count;
-            return Ok(Some((
-                ColumnKind::Boolean,
-                ColumnStats::Boolean(BooleanStats {
-                    true_count,
-                    false_count,
-                }),
-            )));
+            // Only exact 0/1 values (plus nulls) count; 0/0.5/1 is not boolean
+            let true_count = ca.into_iter().flatten().filter(|v| *v == 1.0).count();
+            let false_count = ca.into_iter().flatten().filter(|v| *v == 0.0).count();
+            let null_count = ca.null_count();
+            if true_count + false_count + null_count == ca.len() {
+                return Ok(Some((
+                    ColumnKind::Boolean,
+                    ColumnStats::Boolean(BooleanStats {
+                        true_count,
+                        false_count,
+                        null_count,
+                    }),
+                )));
+            }
         }
     }
     Ok(None)
@@ -193,6 +233,182 @@ pub fn calculate_trimmed_mean(
     sliced.mean()
 }
 
+/// Upper bound on the number of bins any strategy may produce.
+pub const MAX_HISTOGRAM_BINS: usize = 500;
+/// Bin count used by `FixedBins` and `LogScale` when none is requested.
+pub const DEFAULT_HISTOGRAM_BINS: usize = 20;
+
+/// Resolved bin layout for a histogram.
+#[derive(Debug, Clone)]
+pub enum Binning {
+    /// Equal-width bins from `start`; values equal to `end` fall in the last bin.
+    Uniform {
+        start: f64,
+        end: f64,
+        width: f64,
+        count: usize,
+    },
+    /// Bins equally spaced in log10 space between `log_start` and `log_end`.
+    Log {
+        log_start: f64,
+        log_end: f64,
+        step: f64,
+        count: usize,
+    },
+}
+
+impl Binning {
+    pub fn count(&self) -> usize {
+        match self {
+            Self::Uniform { count, .. } | Self::Log { count, .. } => *count,
+        }
+    }
+
+    /// Bin width in the original scale (uniform) or log10 units (log).
+    pub fn width(&self) -> f64 {
+        match self {
+            Self::Uniform { width, .. } => *width,
+            Self::Log { step, .. } => *step,
+        }
+    }
+
+    pub fn index_of(&self, val: f64) -> Option<usize> {
+        let (pos, start, end, width, count) = match self {
+            Self::Uniform {
+                start,
+                end,
+                width,
+                count,
+            } => (val, *start, *end, *width, *count),
+            Self::Log {
+                log_start,
+                log_end,
+                step,
+                count,
+            } => {
+                if val <= 0.0 {
+                    return None;
+                }
+                (val.log10(), *log_start, *log_end, *step, *count)
+            }
+        };
+        if pos < start || count == 0 {
+            return None;
+        }
+        let idx = ((pos - start) / width).floor() as usize;
+        if idx < count {
+            Some(idx)
+        } else if (pos - end).abs() < f64::EPSILON {
+            Some(count - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Start of bin `i`, in the original scale.
+    pub fn bin_start(&self, i: usize) -> f64 {
+        match self {
+            Self::Uniform { start, width, .. } => start + i as f64 * width,
+            Self::Log {
+                log_start, step, ..
+            } => 10f64.powf(log_start + i as f64 * step),
+        }
+    }
+
+    pub fn to_histogram(&self, bins: Vec<usize>) -> Vec<(f64, usize)> {
+        bins.into_iter()
+            .enumerate()
+            .map(|(i, count)| (self.bin_start(i), count))
+            .collect()
+    }
+}
+
+/// Choose bins for a non-constant column according to `options`.
+///
+/// Returns the strategy actually used, which differs from the requested one
+/// when it cannot be applied (e.g. log bins for non-positive data).
+pub fn resolve_binning(
+    min_v: f64,
+    max_v: f64,
+    q1: Option<f64>,
+    q3: Option<f64>,
+    n: usize,
+    options: &HistogramOptions,
+) -> (HistogramStrategy, Binning) {
+    let range = max_v - min_v;
+    let requested = options
+        .bin_count
+        .map(|b| b.clamp(1, MAX_HISTOGRAM_BINS))
+        .unwrap_or(DEFAULT_HISTOGRAM_BINS);
+    let iqr = q3.unwrap_or(max_v) - q1.unwrap_or(min_v);
+    let sturges = ((n.max(1) as f64).log2().ceil() as usize + 1).clamp(1, MAX_HISTOGRAM_BINS);
+
+    let uniform = |count: usize| Binning::Uniform {
+        start: min_v,
+        end: max_v,
+        width: range / count as f64,
+        count,
+    };
+
+    match options.strategy {
+        HistogramStrategy::LogScale if min_v > 0.0 => {
+            let log_start = min_v.log10();
+            let log_end = max_v.log10();
+            (
+                HistogramStrategy::LogScale,
+                Binning::Log {
+                    log_start,
+                    log_end,
+                    step: (log_end - log_start) / requested as f64,
+                    count: requested,
+                },
+            )
+        }
+        HistogramStrategy::FixedBins => (HistogramStrategy::FixedBins, uniform(requested)),
+        HistogramStrategy::Sturges => (HistogramStrategy::Sturges, uniform(sturges)),
+        HistogramStrategy::FreedmanDiaconis => {
+            let h = 2.0 * iqr / (n.max(1) as f64).cbrt();
+            let count = if h > 0.0 {
+                ((range / h).ceil() as usize).clamp(1, MAX_HISTOGRAM_BINS)
+            } else {
+                // Zero IQR (heavily concentrated data): FD is undefined
+                sturges
+            };
+            (HistogramStrategy::FreedmanDiaconis, uniform(count))
+        }
+        HistogramStrategy::Auto | HistogramStrategy::LogScale => {
+            let h = if iqr > 0.0 {
+                2.0 * iqr / (n as f64).cbrt()
+            } else {
+                range / (n as f64).sqrt()
+            };
+            let count = ((range / h).ceil() as usize).clamp(5, 50);
+            (HistogramStrategy::Auto, uniform(count))
+        }
+    }
+}
+
+/// Histogram for a column holding a single distinct value: 20 unit-width bins
+/// with the value's count in the middle bin.
+fn single_value_histogram(value: f64, count: usize) -> (f64, Vec<(f64, usize)>) {
+    let num_bins = 20;
+    let bin_width = 1.0;
+    let mut bins = vec![0; num_bins];
+    if let Some(bin) = bins.get_mut(10) {
+        *bin = count;
+    }
+
+    // The value should be at `value`. If we want it in bin 10,
+    // then bin 10 starts at value and ends at value + bin_width.
+    let start = value - 10.0 * bin_width;
+    let histogram = bins
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (start + i as f64 * bin_width, count))
+        .collect();
+    (bin_width, histogram)
+}
+
 pub fn calculate_histogram(
     ca: &Float64Chunked,
     min: Option<f64>,
@@ -200,56 +416,190 @@ pub fn calculate_histogram(
     q1: Option<f64>,
     q3: Option<f64>,
 ) -> (f64, Vec<(f64, usize)>) {
-    let mut histogram = Vec::new();
-    let mut bin_width = 0.0;
-
-    if let (Some(min_v), Some(max_v)) = (min, max) {
-        if (max_v - min_v).abs() < f64::EPSILON {
-            // Single value case - create 20 bins around the value
-            let num_bins = 20;
-            bin_width = 1.0;
-            let mut bins = vec![0; num_bins];
-            if let Some(bin) = bins.get_mut(10) {
-                *bin = ca.len() - ca.null_count();
-            }
+    let (_, bin_width, histogram) =
+        calculate_histogram_with_options(ca, min, max, q1, q3, &HistogramOptions::default());
+    (bin_width, histogram)
+}
 
-            // The value should be at min_v. If we want it in bin 10,
-            // then bin 10 starts at min_v and ends at min_v + bin_width.
-            let start = min_v - 10.0 * bin_width;
+pub fn calculate_histogram_with_options(
+    ca: &Float64Chunked,
+    min: Option<f64>,
+    max: Option<f64>,
+    q1: Option<f64>,
+    q3: Option<f64>,
+    options: &HistogramOptions,
+) -> (HistogramStrategy, f64, Vec<(f64, usize)>) {
+    let (Some(min_v), Some(max_v)) = (min, max) else {
+        return (HistogramStrategy::Auto, 0.0, Vec::new());
+    };
 
-            for (i, count) in bins.into_iter().enumerate() {
-                histogram.push((start + i as f64 * bin_width, count));
-            }
-        } else {
-            let n = ca.len() - ca.null_count();
-            let iqr = q3.unwrap_or(max_v) - q1.unwrap_or(min_v);
+    if (max_v - min_v).abs() < f64::EPSILON {
+        let (bin_width, histogram) = single_value_histogram(min_v, ca.len() - ca.null_count());
+        return (HistogramStrategy::Auto, bin_width, histogram);
+    }
 
-            let h = if iqr > 0.0 {
-                2.0 * iqr / (n as f64).cbrt()
-            } else {
-                (max_v - min_v) / (n as f64).sqrt()
-            };
+    let n = ca.len() - ca.null_count();
+    let (strategy, binning) = resolve_binning(min_v, max_v, q1, q3, n, options);
+    let mut bins = vec![0; binning.count()];
+    for val in ca.into_iter().flatten() {
+        if let Some(bin) = binning.index_of(val).and_then(|i| bins.get_mut(i)) {
+            *bin += 1;
+        }
+    }
+    (strategy, binning.width(), binning.to_histogram(bins))
+}
 
-            let mut num_bins = ((max_v - min_v) / h).ceil() as usize;
-            num_bins = num_bins.clamp(5, 50);
-            bin_width = (max_v - min_v) / num_bins as f64;
+/// Number of points in the ECDF sample stored in `NumericStats`.
+pub const ECDF_POINTS: usize = 100;
 
-            let mut bins = vec![0; num_bins];
-            for val in ca.into_iter().flatten() {
-                let bin_idx = ((val - min_v) / bin_width).floor() as usize;
-                if bin_idx < num_bins {
-                    bins[bin_idx] += 1;
-                } else if (val - max_v).abs() < f64::EPSILON {
-                    bins[num_bins - 1] += 1;
-                }
-            }
+/// Percentile table (P1..P99, linear interpolation) and an ECDF sampled at
+/// [`ECDF_POINTS`] evenly spaced values, computed from the non-null values.
+pub fn calculate_distribution_tables(ca: &Float64Chunked) -> (Vec<(u8, f64)>, Vec<(f64, f64)>) {
+    let mut sorted: Vec<f64> = ca.into_iter().flatten().filter(|v| !v.is_nan()).collect();
+    if sorted.is_empty() {
+        return (Vec::new(), Vec::new());
+    }
+    sorted.sort_by(f64::total_cmp);
+    let n = sorted.len();
+
+    let quantiles = (1..=99u8)
+        .filter_map(|p| {
+            let pos = f64::from(p) / 100.0 * (n - 1) as f64;
+            let lower = pos.floor() as usize;
+            let frac = pos - lower as f64;
+            let lo = *sorted.get(lower)?;
+            let hi = sorted.get(lower + 1).copied().unwrap_or(lo);
+            Some((p, lo + (hi - lo) * frac))
+        })
+        .collect();
+
+    let (min_v, max_v) = (sorted[0], sorted[n - 1]);
+    let ecdf = if max_v > min_v {
+        (0..ECDF_POINTS)
+            .map(|i| {
+                let x = min_v + (max_v - min_v) * i as f64 / (ECDF_POINTS - 1) as f64;
+                let at_or_below = sorted.partition_point(|v| *v <= x);
+                (x, at_or_below as f64 / n as f64)
+            })
+            .collect()
+    } else {
+        vec![(min_v, 1.0)]
+    };
 
-            for (i, count) in bins.into_iter().enumerate() {
-                histogram.push((min_v + i as f64 * bin_width, count));
-            }
+    (quantiles, ecdf)
+}
+
+/// Columns whose full range exceeds the P05–P95 range by this factor get log
+/// and percentile-zoomed histogram views, as their full-range histogram tends
+/// to collapse into one or two visible bars.
+pub const HISTOGRAM_VARIANT_TAIL_RATIO: f64 = 10.0;
+
+/// Summary statistics needed to decide on and bin the histogram variants.
+#[derive(Debug, Clone, Copy)]
+pub struct VariantInputs {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub p05: Option<f64>,
+    pub p95: Option<f64>,
+    pub q1: Option<f64>,
+    pub q3: Option<f64>,
+    pub skew: Option<f64>,
+    /// Strategy used for the main histogram; no log variant is produced when
+    /// the main histogram is already log-scaled.
+    pub strategy: HistogramStrategy,
+}
+
+/// Compute the log-x and P05–P95 zoomed histograms for a heavily skewed or
+/// long-tailed column. Returns `(None, None)` when the full-range histogram
+/// is adequate.
+pub fn calculate_histogram_variants(
+    ca: &Float64Chunked,
+    inputs: VariantInputs,
+    options: &HistogramOptions,
+) -> (Option<HistogramVariant>, Option<HistogramVariant>) {
+    let VariantInputs {
+        min,
+        max,
+        p05,
+        p95,
+        q1,
+        q3,
+        skew,
+        strategy,
+    } = inputs;
+    let (Some(min_v), Some(max_v)) = (min, max) else {
+        return (None, None);
+    };
+    let long_tailed = match (p05, p95) {
+        (Some(lower), Some(upper)) => {
+            (max_v - min_v) > HISTOGRAM_VARIANT_TAIL_RATIO * (upper - lower)
         }
+        _ => false,
+    };
+    if max_v <= min_v || !(long_tailed || skew.is_some_and(|s| s.abs() > SKEW_THRESHOLD)) {
+        return (None, None);
+    }
+    let n = ca.len() - ca.null_count();
+
+    let log_options = HistogramOptions {
+        strategy: HistogramStrategy::LogScale,
+        bin_count: options.bin_count,
+    };
+    let log_histogram = match resolve_binning(min_v, max_v, q1, q3, n, &log_options) {
+        (HistogramStrategy::LogScale, binning) if strategy != HistogramStrategy::LogScale => {
+            Some(fill_variant(ca, &binning, min_v, max_v))
+        }
+        _ => None,
+    };
+
+    let zoomed_histogram = if let (Some(lower), Some(upper)) = (p05, p95)
+        && upper > lower
+        && (lower > min_v || upper < max_v)
+    {
+        // Log bins suit the full range; the zoomed view uses linear bins
+        let zoom_options = HistogramOptions {
+            strategy: if options.strategy == HistogramStrategy::LogScale {
+                HistogramStrategy::Auto
+            } else {
+                options.strategy
+            },
+            bin_count: options.bin_count,
+        };
+        let in_range = ca
+            .into_iter()
+            .flatten()
+            .filter(|v| (lower..=upper).contains(v))
+            .count();
+        let (_, binning) = resolve_binning(lower, upper, q1, q3, in_range, &zoom_options);
+        Some(fill_variant(ca, &binning, lower, upper))
+    } else {
+        None
+    };
+
+    (log_histogram, zoomed_histogram)
+}
+
+fn fill_variant(
+    ca: &Float64Chunked,
+    binning: &Binning,
+    lower: f64,
+    upper: f64,
+) -> HistogramVariant {
+    let mut bins = vec![0; binning.count()];
+    let mut excluded_count = 0;
+    for val in ca.into_iter().flatten() {
+        match binning.index_of(val).and_then(|i| bins.get_mut(i)) {
+            Some(bin) => *bin += 1,
+            None => excluded_count += 1,
+        }
+    }
+    HistogramVariant {
+        bin_width: binning.width(),
+        histogram: binning.to_histogram(bins),
+        lower,
+        upper,
+        excluded_count,
     }
-    (bin_width, histogram)
 }
 
 pub fn build_histogram_streaming(
@@ -257,6 +607,17 @@ pub fn build_histogram_streaming(
     name: &str,
     config: HistogramConfig,
 ) -> Result<(f64, Vec<(f64, usize)>)> {
+    let (_, bin_width, histogram) =
+        build_histogram_streaming_with_options(lf, name, config, &HistogramOptions::default())?;
+    Ok((bin_width, histogram))
+}
+
+pub fn build_histogram_streaming_with_options(
+    lf: LazyFrame,
+    name: &str,
+    config: HistogramConfig,
+    options: &HistogramOptions,
+) -> Result<(HistogramStrategy, f64, Vec<(f64, usize)>)> {
     let HistogramConfig {
         min,
         max,
@@ -266,82 +627,67 @@ pub fn build_histogram_streaming(
         null_count,
         custom_sample_size,
     } = config;
-    if let (Some(min_v), Some(max_v)) = (min, max) {
-        if (max_v - min_v).abs() < f64::EPSILON {
-            let num_bins = 20;
-            let bin_width = 1.0;
-            let mut bins = vec![0; num_bins];
-            if let Some(bin) = bins.get_mut(10) {
-                *bin = total_count.saturating_sub(null_count);
-            }
-            let start = min_v - 10.0 * bin_width;
-            let mut histogram = Vec::new();
-            for (i, count) in bins.into_iter().enumerate() {
-                histogram.push((start + i as f64 * bin_width, count));
-            }
-            return Ok((bin_width, histogram));
-        }
-
-        let n = total_count.saturating_sub(null_count);
-        let iqr = q3.unwrap_or(max_v) - q1.unwrap_or(min_v);
-        let h = if iqr > 0.0 {
-            2.0 * iqr / (n as f64).cbrt()
-        } else {
-            (max_v - min_v) / (n as f64).sqrt()
-        };
+    let (Some(min_v), Some(max_v)) = (min, max) else {
+        return Ok((HistogramStrategy::Auto, 0.0, Vec::new()));
+    };
 
-        let mut num_bins = ((max_v - min_v) / h).ceil() as usize;
-        num_bins = num_bins.clamp(5, 50);
-        let bin_width = (max_v - min_v) / num_bins as f64;
+    if (max_v - min_v).abs() < f64::EPSILON {
+        let (bin_width, histogram) =
+            single_value_histogram(min_v, total_count.saturating_sub(null_count));
+        return Ok((HistogramStrategy::Auto, bin_width, histogram));
+    }
 
-        let mut bins = vec![0; num_bins];
+    let n = total_count.saturating_sub(null_count);
+    let (strategy, binning) = resolve_binning(min_v, max_v, q1, q3, n, options);
+    let mut bins = vec![0; binning.count()];
 
-        // Process in chunks (up to adaptive sample size as per requirement)
-        let max_rows = get_adaptive_sample_size(total_count, custom_sample_size);
-        let effective_rows = total_count.min(max_rows);
-        let chunk_size = 50_000;
-        let total_chunks = if chunk_size > 0 {
-            effective_rows.div_ceil(chunk_size)
-        } else {
-            0
-        };
+    // Process in chunks (up to adaptive sample size as per requirement)
+    let max_rows = get_adaptive_sample_size(total_count, custom_sample_size);
+    let effective_rows = total_count.min(max_rows);
+    let chunk_size = 50_000;
+    let total_chunks = if chunk_size > 0 {
+        effective_rows.div_ceil(chunk_size)
+    } else {
+        0
+    };
 
-        for i in 0..total_chunks {
-            let offset = (i * chunk_size) as i64;
-            let current_chunk_size = chunk_size.min(effective_rows - i * chunk_size);
+    for i in 0..total_chunks {
+        let offset = (i * chunk_size) as i64;
+        let current_chunk_size = chunk_size.min(effective_rows - i * chunk_size);
 
-            let chunk_df = lf
-                .clone()
-                .slice(offset, current_chunk_size as u32)
-                .select([col(name)])
-                .collect()?;
+        let chunk_df = lf
+            .clone()
+            .slice(offset, current_chunk_size as u32)
+            .select([col(name)])
+            .collect()?;
 
-            let s = chunk_df.column(name)?.as_materialized_series();
-            let ca = s.cast(&DataType::Float64)?;
-            let ca = ca.f64()?;
+        let s = chunk_df.column(name)?.as_materialized_series();
+        let ca = s.cast(&DataType::Float64)?;
+        let ca = ca.f64()?;
 
-            for val in ca.into_iter().flatten() {
-                let bin_idx = ((val - min_v) / bin_width).floor() as usize;
-                if bin_idx < num_bins {
-                    bins[bin_idx] += 1;
-                } else if (val - max_v).abs() < f64::EPSILON {
-                    bins[num_bins - 1] += 1;
-                }
+        for val in ca.into_iter().flatten() {
+            if let Some(bin) = binning.index_of(val).and_then(|i| bins.get_mut(i)) {
+                *bin += 1;
             }
         }
-
-        let mut histogram = Vec::new();
-        for (i, count) in bins.into_iter().enumerate() {
-            histogram.push((min_v + i as f64 * bin_width, count));
-        }
-        Ok((bin_width, histogram))
-    } else {
-        Ok((0.0, Vec::new()))
     }
+
+    Ok((strategy, binning.width(), binning.to_histogram(bins)))
 }
 
+/// Timestamps before 1900-01-01 are treated as implausible (typically
+/// sentinel values or parsing errors).
+pub const PLAUSIBLE_DATE_FLOOR_MS: i64 = -2_208_988_800_000;
+
 pub fn analyse_temporal(col: &Column) -> Result<(ColumnKind, ColumnStats)> {
+    analyse_temporal_at(col, chrono::Utc::now().timestamp_millis())
+}
+
+/// As [`analyse_temporal`], treating `now_ms` as the current time when
+/// counting future dates.
+pub fn analyse_temporal_at(col: &Column, now_ms: i64) -> Result<(ColumnKind, ColumnStats)> {
     let series = col.as_materialized_series();
+    let has_time = matches!(series.dtype(), DataType::Datetime(_, _));
     let ca = series
         .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
         .map_err(|e| anyhow::anyhow!(e))?;
@@ -349,6 +695,13 @@ pub fn analyse_temporal(col: &Column) -> Result<(ColumnKind, ColumnStats)> {
 
     let min = ca.min().map(|v| v.to_string());
     let max = ca.max().map(|v| v.to_string());
+    let TemporalPatterns {
+        weekday_counts,
+        hour_counts,
+        future_count,
+        implausible_count,
+    } = temporal_patterns(ca, has_time, now_ms);
+    let (min_gap_ms, max_gap_ms) = timestamp_gaps(ca);
 
     let mut timeline = Vec::new();
     if let (Some(min_v), Some(max_v)) = (ca.min(), ca.max())
@@ -387,10 +740,71 @@ pub fn analyse_temporal(col: &Column) -> Result<(ColumnKind, ColumnStats)> {
                 .unwrap_or(false),
             bin_width: 0.0,
             histogram: Vec::new(),
+            weekday_counts,
+            hour_counts,
+            future_count,
+            implausible_count,
+            min_gap_ms,
+            max_gap_ms,
+            format: None,
+            format_ambiguous: false,
         }),
     ))
 }
 
+/// Weekday and hour-of-day distributions, plus future and implausibly old
+/// timestamp counts.
+struct TemporalPatterns {
+    weekday_counts: Vec<usize>,
+    hour_counts: Vec<usize>,
+    future_count: usize,
+    implausible_count: usize,
+}
+
+fn temporal_patterns(ca: &DatetimeChunked, has_time: bool, now_ms: i64) -> TemporalPatterns {
+    use chrono::{Datelike as _, Timelike as _};
+
+    let mut patterns = TemporalPatterns {
+        weekday_counts: vec![0; 7],
+        hour_counts: if has_time { vec![0; 24] } else { Vec::new() },
+        future_count: 0,
+        implausible_count: 0,
+    };
+    for ms in ca.into_iter().flatten() {
+        if ms > now_ms {
+            patterns.future_count += 1;
+        }
+        if ms < PLAUSIBLE_DATE_FLOOR_MS {
+            patterns.implausible_count += 1;
+        }
+        let Some(dt) = chrono::DateTime::from_timestamp_millis(ms) else {
+            continue;
+        };
+        if let Some(day) = patterns
+            .weekday_counts
+            .get_mut(dt.weekday().num_days_from_monday() as usize)
+        {
+            *day += 1;
+        }
+        if let Some(hour) = patterns.hour_counts.get_mut(dt.hour() as usize) {
+            *hour += 1;
+        }
+    }
+    patterns
+}
+
+/// Smallest and largest gap between consecutive distinct timestamps.
+fn timestamp_gaps(ca: &DatetimeChunked) -> (Option<f64>, Option<f64>) {
+    let mut values: Vec<i64> = ca.into_iter().flatten().collect();
+    values.sort_unstable();
+    values.dedup();
+    let gaps = values.windows(2).map(|w| (w[1] - w[0]) as f64);
+    (
+        gaps.clone().min_by(f64::total_cmp),
+        gaps.max_by(f64::total_cmp),
+    )
+}
+
 pub fn analyse_text_or_fallback(
     name: &str,
     col: &Column,
@@ -0,0 +1,143 @@
+//! Splitting rows that fail cleaning or quality checks into a separate
+//! reject set, tagged with the reason(s) they were rejected.
+
+use super::cleaning::{
+    apply_dtype_casting_with_dtype, apply_number_extraction, apply_text_cleaning,
+};
+use super::types::{ColumnCleanConfig, ColumnKind};
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+
+/// Column added to reject rows listing why they were rejected
+pub const REJECT_REASON_COLUMN: &str = "reject_reason";
+
+/// A row-level failure: rows where `condition` is true are rejected with `reason`
+#[derive(Debug, Clone)]
+pub struct RejectCondition {
+    pub reason: String,
+    pub condition: Expr,
+}
+
+/// Split `lf` into rows passing every condition and rejected rows. Rejected
+/// rows keep their original values plus a [`REJECT_REASON_COLUMN`] joining
+/// all failed reasons with `"; "`.
+pub fn split_rejects(lf: LazyFrame, conditions: &[RejectCondition]) -> (LazyFrame, LazyFrame) {
+    if conditions.is_empty() {
+        let rejects = lf
+            .clone()
+            .filter(lit(false))
+            .with_column(lit(NULL).cast(DataType::String).alias(REJECT_REASON_COLUMN));
+        return (lf, rejects);
+    }
+
+    let parts: Vec<Expr> = conditions
+        .iter()
+        .map(|c| {
+            when(c.condition.clone().fill_null(lit(false)))
+                .then(lit(c.reason.clone()))
+                .otherwise(lit(NULL))
+        })
+        .collect();
+    let packed = concat_str(parts, "; ", true);
+    let reason = when(packed.clone().eq(lit("")))
+        .then(lit(NULL).cast(DataType::String))
+        .otherwise(packed);
+
+    let kept = lf.clone().filter(reason.clone().is_null());
+    let rejects = lf
+        .with_column(reason.alias(REJECT_REASON_COLUMN))
+        .filter(col(REJECT_REASON_COLUMN).is_not_null());
+    (kept, rejects)
+}
+
+/// Conditions for values that `clean_df_lazy` would silently turn into nulls:
+/// failed type casts and failed number extraction on active columns.
+pub fn cleaning_reject_conditions(
+    schema: &Schema,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    restricted: bool,
+) -> Vec<RejectCondition> {
+    let mut conditions = Vec::new();
+
+    for (name, dtype) in schema.iter() {
+        let Some(config) = configs.get(name.as_str()) else {
+            continue;
+        };
+        let can_fail = config.extract_numbers
+            || matches!(
+                config.target_dtype,
+                Some(ColumnKind::Numeric | ColumnKind::Boolean | ColumnKind::Temporal)
+            );
+        if !config.active || !can_fail {
+            continue;
+        }
+
+        let before = apply_text_cleaning(col(name.as_str()), config, dtype, restricted);
+        let after = apply_dtype_casting_with_dtype(
+            apply_number_extraction(before.clone(), config),
+            config,
+            dtype,
+        );
+        let target = match config.target_dtype {
+            Some(ColumnKind::Boolean) => "boolean",
+            Some(ColumnKind::Temporal) => "date/time",
+            _ => "number",
+        };
+
+        conditions.push(RejectCondition {
+            reason: format!("{name}: not a valid {target}"),
+            condition: before.is_not_null().and(after.is_null()),
+        });
+    }
+
+    conditions
+}
+
+/// Stack reject sets with differing schemas into one frame. Every column is
+/// cast to text and columns missing from a set are filled with nulls, so
+/// rejects from different stages can share one file.
+pub fn union_rejects(frames: Vec<LazyFrame>) -> Result<Option<LazyFrame>> {
+    if frames.is_empty() {
+        return Ok(None);
+    }
+
+    let mut frames = frames;
+    let mut schemas = Vec::with_capacity(frames.len());
+    for lf in &mut frames {
+        schemas.push(lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?);
+    }
+
+    // Reason first, then every other column in first-seen order
+    let mut names: Vec<String> = vec![REJECT_REASON_COLUMN.to_owned()];
+    let mut seen: BTreeSet<String> = names.iter().cloned().collect();
+    for schema in &schemas {
+        for name in schema.iter_names() {
+            if seen.insert(name.to_string()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    let aligned: Vec<LazyFrame> = frames
+        .into_iter()
+        .zip(&schemas)
+        .map(|(lf, schema)| {
+            let exprs: Vec<Expr> = names
+                .iter()
+                .map(|name| {
+                    if schema.contains(name) {
+                        col(name.as_str()).cast(DataType::String)
+                    } else {
+                        lit(NULL).cast(DataType::String).alias(name.as_str())
+                    }
+                })
+                .collect();
+            lf.select(exprs)
+        })
+        .collect();
+
+    concat(aligned, UnionArgs::default())
+        .map(Some)
+        .context("Failed to combine rejected rows")
+}
@@ -0,0 +1,183 @@
+//! Semantic types: what the values of a text column are beyond being text.
+//!
+//! Each [`SemanticType`] has a detector, a regex or a lookup table of ISO
+//! codes, run over a sample of the column. A column gets the type nearly
+//! all of its non-empty values match, which then drives default cleaning,
+//! dictionary categorisation and PII tagging.
+
+use regex::Regex;
+use std::net::IpAddr;
+use std::sync::LazyLock;
+
+use super::types::SemanticType;
+
+/// Non-empty values a sample needs before a type is guessed
+pub const MIN_SEMANTIC_SAMPLES: usize = 3;
+
+/// Share of non-empty values that must match a type
+pub const SEMANTIC_MATCH_THRESHOLD: f64 = 0.9;
+
+static EMAIL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[A-Za-z]{2,}$").expect("valid email regex"));
+
+static UK_POSTCODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^[A-Z]{1,2}\d[A-Z\d]?\s*\d[A-Z]{2}$").expect("valid postcode regex")
+});
+
+static US_ZIP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\d{5}(-\d{4})?$").expect("valid ZIP code regex"));
+
+/// `£1,250.00`, `USD 12`, `-$3.50`
+static CURRENCY_PREFIXED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^[-+]?(?:[$£€¥₹]\s?|[A-Z]{3}\s)\d{1,3}(?:,?\d{3})*(?:\.\d+)?$")
+        .expect("valid currency amount regex")
+});
+
+/// `12,50 €`, `1250 GBP`
+static CURRENCY_SUFFIXED: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^[-+]?\d{1,3}(?:[,.]?\d{3})*(?:[.,]\d+)?(?:\s?[$£€¥₹]|\s[A-Z]{3})$")
+        .expect("valid currency amount regex")
+});
+
+/// ISO 3166-1 alpha-2 country codes
+const COUNTRY_ALPHA2: &str = "AD AE AF AG AI AL AM AO AQ AR AS AT AU AW AX AZ BA BB BD BE BF BG BH \
+    BI BJ BL BM BN BO BQ BR BS BT BV BW BY BZ CA CC CD CF CG CH CI CK CL CM CN CO CR CU CV CW CX \
+    CY CZ DE DJ DK DM DO DZ EC EE EG EH ER ES ET FI FJ FK FM FO FR GA GB GD GE GF GG GH GI GL GM \
+    GN GP GQ GR GS GT GU GW GY HK HM HN HR HT HU ID IE IL IM IN IO IQ IR IS IT JE JM JO JP KE KG \
+    KH KI KM KN KP KR KW KY KZ LA LB LC LI LK LR LS LT LU LV LY MA MC MD ME MF MG MH MK ML MM MN \
+    MO MP MQ MR MS MT MU MV MW MX MY MZ NA NC NE NF NG NI NL NO NP NR NU NZ OM PA PE PF PG PH PK \
+    PL PM PN PR PS PT PW PY QA RE RO RS RU RW SA SB SC SD SE SG SH SI SJ SK SL SM SN SO SR SS ST \
+    SV SX SY SZ TC TD TF TG TH TJ TK TL TM TN TO TR TT TV TW TZ UA UG UM US UY UZ VA VC VE VG VI \
+    VN VU WF WS YE YT ZA ZM ZW";
+
+/// ISO 3166-1 alpha-3 country codes
+const COUNTRY_ALPHA3: &str = "ABW AFG AGO AIA ALA ALB AND ARE ARG ARM ASM ATA ATF ATG AUS AUT AZE \
+    BDI BEL BEN BES BFA BGD BGR BHR BHS BIH BLM BLR BLZ BMU BOL BRA BRB BRN BTN BVT BWA CAF CAN \
+    CCK CHE CHL CHN CIV CMR COD COG COK COL COM CPV CRI CUB CUW CXR CYM CYP CZE DEU DJI DMA DNK \
+    DOM DZA ECU EGY ERI ESH ESP EST ETH FIN FJI FLK FRA FRO FSM GAB GBR GEO GGY GHA GIB GIN GLP \
+    GMB GNB GNQ GRC GRD GRL GTM GUF GUM GUY HKG HMD HND HRV HTI HUN IDN IMN IND IOT IRL IRN IRQ \
+    ISL ISR ITA JAM JEY JOR JPN KAZ KEN KGZ KHM KIR KNA KOR KWT LAO LBN LBR LBY LCA LIE LKA LSO \
+    LTU LUX LVA MAC MAF MAR MCO MDA MDG MDV MEX MHL MKD MLI MLT MMR MNE MNG MNP MOZ MRT MSR MTQ \
+    MUS MWI MYS MYT NAM NCL NER NFK NGA NIC NIU NLD NOR NPL NRU NZL OMN PAK PAN PCN PER PHL PLW \
+    PNG POL PRI PRK PRT PRY PSE PYF QAT REU ROU RUS RWA SAU SDN SEN SGP SGS SHN SJM SLB SLE SLV \
+    SMR SOM SPM SRB SSD STP SUR SVK SVN SWE SWZ SXM SYC SYR TCA TCD TGO THA TJK TKL TKM TLS TON \
+    TTO TUN TUR TUV TWN TZA UGA UKR UMI URY USA UZB VAT VCT VEN VGB VIR VNM VUT WLF WSM YEM ZAF \
+    ZMB ZWE";
+
+/// ISO 4217 currency codes in circulation
+const CURRENCY_CODES: &str = "AED AFN ALL AMD ANG AOA ARS AUD AWG AZN BAM BBD BDT BGN BHD BIF BMD \
+    BND BOB BRL BSD BTN BWP BYN BZD CAD CDF CHF CLP CNY COP CRC CUP CVE CZK DJF DKK DOP DZD EGP \
+    ERN ETB EUR FJD FKP GBP GEL GHS GIP GMD GNF GTQ GYD HKD HNL HTG HUF IDR ILS INR IQD IRR ISK \
+    JMD JOD JPY KES KGS KHR KMF KPW KRW KWD KYD KZT LAK LBP LKR LRD LSL LYD MAD MDL MGA MKD MMK \
+    MNT MOP MRU MUR MVR MWK MXN MYR MZN NAD NGN NIO NOK NPR NZD OMR PAB PEN PGK PHP PKR PLN PYG \
+    QAR RON RSD RUB RWF SAR SBD SCR SDG SEK SGD SHP SLE SOS SRD SSP STN SVC SYP SZL THB TJS TMT \
+    TND TOP TRY TTD TWD TZS UAH UGX USD UYU UZS VES VND VUV WST XAF XCD XOF XPF YER ZAR ZMW ZWL";
+
+fn in_table(table: &str, value: &str) -> bool {
+    value.is_ascii()
+        && table
+            .split_whitespace()
+            .any(|code| code.eq_ignore_ascii_case(value))
+}
+
+impl SemanticType {
+    /// Every type, in the order ties between detectors are broken
+    pub const ALL: [Self; 6] = [
+        Self::Email,
+        Self::IpAddress,
+        Self::CurrencyCode,
+        Self::CountryCode,
+        Self::CurrencyAmount,
+        Self::Postcode,
+    ];
+
+    /// Whether one trimmed value looks like this type
+    pub fn matches(self, value: &str) -> bool {
+        match self {
+            Self::Email => EMAIL.is_match(value),
+            Self::CountryCode => {
+                (value.len() == 2 && in_table(COUNTRY_ALPHA2, value))
+                    || (value.len() == 3 && in_table(COUNTRY_ALPHA3, value))
+            }
+            Self::CurrencyCode => value.len() == 3 && in_table(CURRENCY_CODES, value),
+            Self::CurrencyAmount => {
+                CURRENCY_PREFIXED.is_match(value) || CURRENCY_SUFFIXED.is_match(value)
+            }
+            Self::Postcode => UK_POSTCODE.is_match(value) || US_ZIP.is_match(value),
+            Self::IpAddress => value.parse::<IpAddr>().is_ok(),
+        }
+    }
+}
+
+/// The semantic type nearly all non-empty `values` match, if any. Codes
+/// that are both a country and a currency (none in practice) go to the
+/// earlier type in [`SemanticType::ALL`].
+pub fn detect_semantic_type<'a>(values: impl IntoIterator<Item = &'a str>) -> Option<SemanticType> {
+    let values: Vec<&str> = values
+        .into_iter()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .collect();
+    if values.len() < MIN_SEMANTIC_SAMPLES {
+        return None;
+    }
+
+    let mut best: Option<(SemanticType, usize)> = None;
+    for kind in SemanticType::ALL {
+        let hits = values.iter().filter(|v| kind.matches(v)).count();
+        if best.is_none_or(|(_, most)| hits > most) {
+            best = Some((kind, hits));
+        }
+    }
+    best.filter(|&(_, hits)| hits as f64 >= values.len() as f64 * SEMANTIC_MATCH_THRESHOLD)
+        .map(|(kind, _)| kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_each_type() {
+        let cases: [(&[&str], SemanticType); 7] = [
+            (
+                &["ann@example.com", "bob@mail.co.uk", " cy@x.org "],
+                SemanticType::Email,
+            ),
+            (&["GB", "fr", "USA", "DEU"], SemanticType::CountryCode),
+            (&["GBP", "usd", "EUR"], SemanticType::CurrencyCode),
+            (
+                &["£1,250.00", "$3.50", "USD 12", "12,50 €", "-£40"],
+                SemanticType::CurrencyAmount,
+            ),
+            (&["SW1A 1AA", "m1 1ae", "EC1A1BB"], SemanticType::Postcode),
+            (&["90210", "10001-1234", "02134"], SemanticType::Postcode),
+            (
+                &["192.168.0.1", "10.0.0.255", "::1", "2001:db8::ff00:42:8329"],
+                SemanticType::IpAddress,
+            ),
+        ];
+        for (values, expected) in cases {
+            assert_eq!(
+                detect_semantic_type(values.iter().copied()),
+                Some(expected),
+                "{values:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_needs_nearly_all_values_to_match() {
+        let mostly = ["a@b.com", "c@d.com", "e@f.com", "not an email"];
+        assert_eq!(detect_semantic_type(mostly), None);
+
+        let blanks_ignored = ["a@b.com", "", "  ", "c@d.com", "e@f.com"];
+        assert_eq!(
+            detect_semantic_type(blanks_ignored),
+            Some(SemanticType::Email)
+        );
+
+        assert_eq!(detect_semantic_type(["a@b.com", "c@d.com"]), None);
+        assert_eq!(detect_semantic_type(["apple", "pear", "plum"]), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! Splitting a column into several and merging several into one.
+//!
+//! A split cuts the text of a column on a delimiter or regex and puts each
+//! part in a new column. A merge fills a template such as `"{first} {last}"`
+//! with the values of the columns it names. Both read the cleaned values of
+//! their columns; see [`ColumnSplit`] and [`ColumnMerge`] for the details.
+
+use anyhow::{Context as _, Result, bail};
+use polars::prelude::*;
+
+use super::types::{ColumnMerge, ColumnSplit};
+
+/// Stands in for regex delimiter matches, so the text can be split on it
+const SPLIT_SENTINEL: &str = "\u{1f}";
+
+/// One piece of a merge template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePart {
+    Text(String),
+    Column(String),
+}
+
+/// Parse a merge template into text and `{column}` placeholders
+pub fn parse_template(template: &str) -> Result<Vec<TemplatePart>> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => bail!("Unclosed '{{' in merge template '{template}'"),
+                    }
+                }
+                let name = name.trim();
+                if name.is_empty() {
+                    bail!("Empty '{{}}' in merge template '{template}'");
+                }
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+                parts.push(TemplatePart::Column(name.to_owned()));
+            }
+            '}' => bail!("Unmatched '}}' in merge template '{template}'; use '}}}}' for a brace"),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        parts.push(TemplatePart::Text(text));
+    }
+    if !parts.iter().any(|p| matches!(p, TemplatePart::Column(_))) {
+        bail!("Merge template '{template}' names no columns");
+    }
+    Ok(parts)
+}
+
+/// Columns a merge template names, in order of first use
+pub fn template_columns(template: &str) -> Result<Vec<String>> {
+    let mut columns: Vec<String> = Vec::new();
+    for part in parse_template(template)? {
+        if let TemplatePart::Column(name) = part
+            && !columns.contains(&name)
+        {
+            columns.push(name);
+        }
+    }
+    Ok(columns)
+}
+
+/// The parts of `expr` split by `split`, one expression per new column
+pub fn split_exprs(expr: Expr, split: &ColumnSplit) -> Result<Vec<Expr>> {
+    if split.delimiter.is_empty() {
+        bail!("Split delimiter is empty");
+    }
+    if split.into.is_empty() {
+        bail!("Split has no columns to split into");
+    }
+    let text = expr.cast(DataType::String);
+    let parts = if split.regex {
+        regex::Regex::new(&split.delimiter)
+            .with_context(|| format!("Invalid split regex '{}'", split.delimiter))?;
+        text.str()
+            .replace_all(lit(split.delimiter.as_str()), lit(SPLIT_SENTINEL), false)
+            .str()
+            .split(lit(SPLIT_SENTINEL))
+    } else {
+        text.str().split(lit(split.delimiter.as_str()))
+    };
+
+    Ok(split
+        .into
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            parts
+                .clone()
+                .list()
+                .get(lit(idx as i64), true)
+                .str()
+                .strip_chars(lit(NULL))
+                .alias(name.as_str())
+        })
+        .collect())
+}
+
+/// The column `merge` adds. `resolve` gives the expression for a column the
+/// template names, or `None` if there is no such column.
+pub fn merge_expr(merge: &ColumnMerge, resolve: impl Fn(&str) -> Option<Expr>) -> Result<Expr> {
+    if merge.into.trim().is_empty() {
+        bail!("Merge has no column name to merge into");
+    }
+    let mut exprs = Vec::new();
+    for part in parse_template(&merge.template)? {
+        exprs.push(match part {
+            TemplatePart::Text(text) => lit(text),
+            TemplatePart::Column(name) => resolve(&name)
+                .with_context(|| {
+                    format!(
+                        "Merge template '{}' names unknown column '{name}'",
+                        merge.template
+                    )
+                })?
+                .cast(DataType::String)
+                .fill_null(lit("")),
+        });
+    }
+    let merged = concat_str(exprs, "", false).str().strip_chars(lit(NULL));
+    Ok(when(merged.clone().eq(lit("")))
+        .then(lit(NULL).cast(DataType::String))
+        .otherwise(merged)
+        .alias(merge.into.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() -> Result<()> {
+        assert_eq!(
+            parse_template("{last}, {first} {{x}}")?,
+            [
+                TemplatePart::Column("last".to_owned()),
+                TemplatePart::Text(", ".to_owned()),
+                TemplatePart::Column("first".to_owned()),
+                TemplatePart::Text(" {x}".to_owned()),
+            ]
+        );
+        assert_eq!(template_columns("{a}-{b}-{a}")?, ["a", "b"]);
+        assert!(parse_template("{first").is_err());
+        assert!(parse_template("first}").is_err());
+        assert!(parse_template("no columns").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_and_merge() -> Result<()> {
+        let df = df!(
+            "name" => [Some("Ada, Lovelace"), Some("Grace"), None],
+            "code" => ["GB-1815", "US 1906", "FR--1867"],
+        )?;
+
+        let name_split = ColumnSplit {
+            delimiter: ",".to_owned(),
+            into: vec!["first".to_owned(), "last".to_owned()],
+            ..Default::default()
+        };
+        let code_split = ColumnSplit {
+            delimiter: r"[-\s]+".to_owned(),
+            regex: true,
+            into: vec!["country".to_owned(), "year".to_owned()],
+            ..Default::default()
+        };
+        let mut exprs = split_exprs(col("name"), &name_split)?;
+        exprs.extend(split_exprs(col("code"), &code_split)?);
+        let out = df.lazy().with_columns(exprs).collect()?;
+
+        let last: Vec<_> = out.column("last")?.str()?.into_iter().collect();
+        assert_eq!(last, [Some("Lovelace"), None, None]);
+        let year: Vec<_> = out.column("year")?.str()?.into_iter().collect();
+        assert_eq!(year, [Some("1815"), Some("1906"), Some("1867")]);
+
+        let merge = ColumnMerge {
+            template: "{last} {first} ({country})".to_owned(),
+            into: "label".to_owned(),
+            drop_sources: false,
+        };
+        let merged = merge_expr(&merge, |name| Some(col(name)))?;
+        let out = out.lazy().with_column(merged).collect()?;
+        let label: Vec<_> = out.column("label")?.str()?.into_iter().collect();
+        assert_eq!(
+            label,
+            [Some("Lovelace Ada (GB)"), Some("Grace (US)"), Some("(FR)")]
+        );
+
+        assert!(merge_expr(&merge, |_| None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+//! Fuzzy standardisation of categorical values.
+//!
+//! Free-text categories collect spellings of the same value: `New York`,
+//! `new york `, `New-York`, `N.Y.`. [`cluster_values`] groups them in three
+//! passes: values with the same fingerprint (lower case, punctuation
+//! dropped, words sorted), fingerprints within a normalised edit distance of
+//! each other, and dotted or upper-case abbreviations that spell the
+//! initials of a longer value. Each cluster proposes its most frequent
+//! spelling as the canonical one, and the resulting mapping is applied by
+//! [`ColumnCleanConfig::value_mapping`] in the analyser or a `map_values`
+//! pipeline step.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use super::cleaning::apply_text_cleaning;
+use super::naming::edit_distance;
+use super::types::ColumnCleanConfig;
+
+/// How similar values must be to be clustered
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StandardiseOptions {
+    /// Largest edit distance between fingerprints, as a fraction of the
+    /// longer one, for two values to be clustered. 0 only clusters values
+    /// with the same fingerprint.
+    pub max_distance: f64,
+    /// Most distinct values compared, the most frequent first
+    pub max_values: usize,
+}
+
+impl Default for StandardiseOptions {
+    fn default() -> Self {
+        Self {
+            max_distance: 0.2,
+            max_values: 5000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Spellings taken to be the same value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueCluster {
+    /// The spelling the others are mapped to
+    pub canonical: String,
+    /// Every spelling in the cluster, the most frequent first
+    pub values: Vec<ValueCount>,
+    /// Rows holding any of the values
+    pub total: usize,
+}
+
+/// Clusters found in one column and the mapping they propose
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueStandardisation {
+    pub column: String,
+    /// Distinct non-null values in the column
+    pub distinct_values: usize,
+    /// Whether only the `max_values` most frequent values were compared
+    pub truncated: bool,
+    /// The largest clusters first
+    pub clusters: Vec<ValueCluster>,
+    /// Every non-canonical spelling and the value it maps to
+    pub mapping: BTreeMap<String, String>,
+}
+
+/// Key that spellings differing only in case, punctuation, spacing or word
+/// order share: `N.Y.` gives `ny`, `York, New` gives `new york`
+pub fn fingerprint(value: &str) -> String {
+    let mut words = words(value);
+    words.sort_unstable();
+    words.dedup();
+    words.join(" ")
+}
+
+/// The lower-case words of `value` in order, without punctuation
+fn words(value: &str) -> Vec<String> {
+    let cleaned: String = value
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            '-' | '_' | '/' | ',' | ';' | '&' => Some(' '),
+            c if c.is_alphanumeric() || c.is_whitespace() => Some(c),
+            _ => None,
+        })
+        .collect();
+    cleaned.split_whitespace().map(str::to_owned).collect()
+}
+
+/// Whether `value` is written as an abbreviation: dotted (`N.Y.`) or a
+/// short upper-case word (`NYC`)
+fn is_abbreviation(value: &str) -> bool {
+    let value = value.trim();
+    let letters = value.chars().filter(|c| c.is_alphabetic()).count();
+    (2..=5).contains(&letters)
+        && !value.contains(char::is_whitespace)
+        && (value.contains('.') || value.chars().all(|c| c.is_uppercase() || c == '.'))
+}
+
+/// Group `counts` into clusters of spellings of the same value. Only
+/// clusters of two or more values are returned, the largest first.
+pub fn cluster_values(counts: &[ValueCount], options: &StandardiseOptions) -> Vec<ValueCluster> {
+    let counts = &counts[..counts.len().min(options.max_values)];
+
+    // Pass 1: values sharing a fingerprint
+    let mut by_key: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (idx, vc) in counts.iter().enumerate() {
+        let key = fingerprint(&vc.value);
+        if !key.is_empty() {
+            by_key.entry(key).or_default().push(idx);
+        }
+    }
+    let keys: Vec<(&String, &Vec<usize>)> = by_key.iter().collect();
+    let mut parent: Vec<usize> = (0..keys.len()).collect();
+
+    // Pass 2: fingerprints within the edit distance. Values with digits are
+    // left out, as codes and years differing by one digit are distinct.
+    if options.max_distance > 0.0 {
+        let lengths: Vec<usize> = keys.iter().map(|(key, _)| key.chars().count()).collect();
+        for i in 0..keys.len() {
+            if keys[i].0.contains(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            for j in i + 1..keys.len() {
+                if keys[j].0.contains(|c: char| c.is_ascii_digit()) {
+                    continue;
+                }
+                let longest = lengths[i].max(lengths[j]) as f64;
+                if lengths[i].abs_diff(lengths[j]) as f64 / longest > options.max_distance {
+                    continue;
+                }
+                let distance = edit_distance(keys[i].0, keys[j].0) as f64 / longest;
+                if distance <= options.max_distance {
+                    union(&mut parent, i, j);
+                }
+            }
+        }
+    }
+
+    // Pass 3: abbreviations spelling the initials of exactly one longer value
+    let mut by_initials: HashMap<String, Vec<usize>> = HashMap::new();
+    for (idx, (key, values)) in keys.iter().enumerate() {
+        if !key.contains(' ') {
+            continue;
+        }
+        for &v in *values {
+            let initials: String = words(&counts[v].value)
+                .iter()
+                .filter_map(|w| w.chars().next())
+                .collect();
+            let targets = by_initials.entry(initials).or_default();
+            if !targets.contains(&idx) {
+                targets.push(idx);
+            }
+        }
+    }
+    let mut abbreviations = vec![false; keys.len()];
+    for (idx, (key, values)) in keys.iter().enumerate() {
+        if key.contains(' ') || !values.iter().any(|&v| is_abbreviation(&counts[v].value)) {
+            continue;
+        }
+        if let Some([target]) = by_initials.get(key.as_str()).map(Vec::as_slice) {
+            union(&mut parent, idx, *target);
+            abbreviations[idx] = true;
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for idx in 0..keys.len() {
+        groups.entry(find(&mut parent, idx)).or_default().push(idx);
+    }
+    let mut clusters: Vec<ValueCluster> = groups
+        .into_values()
+        .filter_map(|members| {
+            let mut values: Vec<(&ValueCount, bool)> = members
+                .iter()
+                .flat_map(|&k| {
+                    let abbreviation = abbreviations[k];
+                    keys[k].1.iter().map(move |&v| (&counts[v], abbreviation))
+                })
+                .collect();
+            if values.len() < 2 {
+                return None;
+            }
+            values.sort_by(|(a, _), (b, _)| {
+                b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value))
+            });
+            // Prefer a spelled-out value over an abbreviation of it
+            let canonical = values
+                .iter()
+                .find(|(_, abbreviation)| !abbreviation)
+                .unwrap_or(&values[0])
+                .0
+                .value
+                .clone();
+            Some(ValueCluster {
+                canonical,
+                total: values.iter().map(|(vc, _)| vc.count).sum(),
+                values: values.into_iter().map(|(vc, _)| vc.clone()).collect(),
+            })
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.total
+            .cmp(&a.total)
+            .then_with(|| a.canonical.cmp(&b.canonical))
+    });
+    clusters
+}
+
+fn find(parent: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut idx = idx;
+    while parent[idx] != root {
+        idx = std::mem::replace(&mut parent[idx], root);
+    }
+    root
+}
+
+fn union(parent: &mut [usize], a: usize, b: usize) {
+    let (a, b) = (find(parent, a), find(parent, b));
+    if a != b {
+        parent[a.max(b)] = a.min(b);
+    }
+}
+
+/// Distinct non-null values of `column` as text with their counts, the most
+/// frequent first, and how many distinct values there are in all
+pub fn column_value_counts(
+    lf: LazyFrame,
+    column: &str,
+    max_values: usize,
+) -> Result<(Vec<ValueCount>, usize)> {
+    column_value_counts_of(lf, col(column).cast(DataType::String), max_values)
+        .with_context(|| format!("Failed to count the values of '{column}'"))
+}
+
+fn column_value_counts_of(
+    lf: LazyFrame,
+    expr: Expr,
+    max_values: usize,
+) -> Result<(Vec<ValueCount>, usize)> {
+    let df = lf
+        .select([expr.alias("value")])
+        .filter(col("value").is_not_null())
+        .group_by([col("value")])
+        .agg([len().alias("count")])
+        .sort(
+            ["count", "value"],
+            SortMultipleOptions::default().with_order_descending_multi([true, false]),
+        )
+        .collect()?;
+    let values = df.column("value")?.as_materialized_series().clone();
+    let values = values.str()?;
+    let totals = df
+        .column("count")?
+        .cast(&DataType::UInt64)?
+        .as_materialized_series()
+        .clone();
+    let totals = totals.u64()?;
+    let counts = values
+        .into_iter()
+        .zip(totals)
+        .take(max_values)
+        .filter_map(|(value, count)| {
+            Some(ValueCount {
+                value: value?.to_owned(),
+                count: count? as usize,
+            })
+        })
+        .collect();
+    Ok((counts, df.height()))
+}
+
+/// Cluster the values of `column` and propose a mapping onto the canonical
+/// spelling of each cluster. With `config`, the values are clustered as its
+/// text options (trimming, case, null standardisation) leave them, which is
+/// what its `value_mapping` is matched against.
+pub fn suggest_value_mapping(
+    lf: LazyFrame,
+    column: &str,
+    config: Option<&ColumnCleanConfig>,
+    options: &StandardiseOptions,
+) -> Result<ValueStandardisation> {
+    let mut expr = col(column).cast(DataType::String);
+    if let Some(config) = config {
+        let config = ColumnCleanConfig {
+            value_mapping: BTreeMap::new(),
+            ..config.clone()
+        };
+        expr = apply_text_cleaning(expr, &config, &DataType::String, false);
+    }
+    let (counts, distinct_values) = column_value_counts_of(lf, expr, options.max_values)
+        .with_context(|| format!("Failed to count the values of '{column}'"))?;
+    let clusters = cluster_values(&counts, options);
+    let mapping = clusters
+        .iter()
+        .flat_map(|cluster| {
+            cluster
+                .values
+                .iter()
+                .filter(|vc| vc.value != cluster.canonical)
+                .map(|vc| (vc.value.clone(), cluster.canonical.clone()))
+        })
+        .collect();
+    Ok(ValueStandardisation {
+        column: column.to_owned(),
+        distinct_values,
+        truncated: distinct_values > counts.len(),
+        clusters,
+        mapping,
+    })
+}
+
+/// Replace whole values of the text expression `expr`: each value that is a
+/// key of `mapping` becomes the value it maps to, others are kept
+pub fn map_values<'a>(
+    expr: Expr,
+    mapping: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Expr {
+    let mut by_target: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (from, to) in mapping {
+        if from != to {
+            by_target
+                .entry(to.as_str())
+                .or_default()
+                .push(from.as_str());
+        }
+    }
+    let mut out = expr.clone();
+    for (to, froms) in by_target {
+        let froms = Series::new("from".into(), froms);
+        out = when(expr.clone().is_in(lit(froms)))
+            .then(lit(to))
+            .otherwise(out);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(values: &[(&str, usize)]) -> Vec<ValueCount> {
+        values
+            .iter()
+            .map(|(value, count)| ValueCount {
+                value: (*value).to_owned(),
+                count: *count,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(" New  York "), "new york");
+        assert_eq!(fingerprint("York, New"), "new york");
+        assert_eq!(fingerprint("new-york"), "new york");
+        assert_eq!(fingerprint("N.Y."), "ny");
+    }
+
+    #[test]
+    fn test_cluster_values() {
+        let values = counts(&[
+            ("New York", 10),
+            ("new york", 4),
+            ("N.Y.", 3),
+            ("New Yrk", 1),
+            ("Boston", 8),
+            ("boston ", 2),
+            ("Bristol", 5),
+            ("10001", 2),
+            ("10002", 2),
+        ]);
+        let clusters = cluster_values(&values, &StandardiseOptions::default());
+
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].canonical, "New York");
+        assert_eq!(clusters[0].total, 18);
+        assert_eq!(clusters[0].values.len(), 4);
+        assert_eq!(clusters[1].canonical, "Boston");
+        assert_eq!(clusters[1].values.len(), 2);
+    }
+
+    #[test]
+    fn test_abbreviation_prefers_spelled_out_value() {
+        let values = counts(&[("NY", 9), ("New York", 2), ("Nueva Yolanda", 1)]);
+        // Two values share the initials, so the abbreviation is ambiguous
+        assert!(cluster_values(&values, &StandardiseOptions::default()).is_empty());
+
+        let values = counts(&[("NY", 9), ("New York", 2)]);
+        let clusters = cluster_values(&values, &StandardiseOptions::default());
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].canonical, "New York");
+    }
+
+    #[test]
+    fn test_map_values() -> Result<()> {
+        let df = df!("city" => [Some("N.Y."), Some("new york"), Some("Boston"), None])?;
+        let mapping = BTreeMap::from([
+            ("N.Y.".to_owned(), "New York".to_owned()),
+            ("new york".to_owned(), "New York".to_owned()),
+        ]);
+        let out = df
+            .lazy()
+            .select([map_values(col("city"), &mapping)])
+            .collect()?;
+        let city = out.column("city")?.str()?;
+        assert_eq!(
+            city.iter().collect::<Vec<_>>(),
+            vec![Some("New York"), Some("New York"), Some("Boston"), None]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,433 @@
+//! Table definitions generated from an analysis.
+//!
+//! [`TableDefinition::from_analysis`] turns the column profile of a file into
+//! a Postgres `CREATE TABLE` statement and a dbt model YAML file: sanitised
+//! names, the narrowest type that holds every profiled value, and `NOT NULL`
+//! for columns that held no nulls. With cleaning configs the definition
+//! describes the cleaned output instead, following renames, type casts and
+//! the columns cleaning adds or removes. The profile may come from a sample,
+//! so nullability and uniqueness are what was observed, not guaranteed.
+
+use super::date_parts::date_part_column;
+use super::naming::sanitize_column_name;
+use super::split_merge::template_columns;
+use super::types::{
+    ColumnCleanConfig, ColumnKind, ColumnStats, ColumnSummary, DatePart, EncodingMethod,
+    GeoEncoding, ImputeMode, NormalisationMethod,
+};
+use crate::analyser::db::ddl::quote;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// One column of a generated table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableColumn {
+    pub name: String,
+    /// Postgres type
+    pub sql_type: String,
+    pub nullable: bool,
+    /// Every profiled value was distinct
+    pub unique: bool,
+    pub description: Option<String>,
+}
+
+/// A table to hold analysed or cleaned data
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDefinition {
+    pub schema: Option<String>,
+    pub table: String,
+    /// Rows the profile was computed from
+    pub profiled_rows: usize,
+    pub columns: Vec<TableColumn>,
+    /// Cleaning the generated columns cannot describe, as comments
+    pub notes: Vec<String>,
+}
+
+impl TableDefinition {
+    /// A table for the columns as analysed, under their standardised names
+    pub fn from_analysis(summaries: &[ColumnSummary], schema: Option<&str>, table: &str) -> Self {
+        Self::from_analysis_with_configs(summaries, &HashMap::new(), schema, table)
+    }
+
+    /// A table for the output of cleaning with `configs`. Columns without a
+    /// config keep their standardised name and inferred type.
+    pub fn from_analysis_with_configs(
+        summaries: &[ColumnSummary],
+        configs: &HashMap<String, ColumnCleanConfig>,
+        schema: Option<&str>,
+        table: &str,
+    ) -> Self {
+        let mut definition = Self {
+            schema: schema.filter(|s| !s.trim().is_empty()).map(str::to_owned),
+            table: table.to_owned(),
+            profiled_rows: summaries.iter().map(|s| s.count).max().unwrap_or(0),
+            ..Self::default()
+        };
+        // Merges with invalid templates fail when cleaning; here they
+        // simply drop nothing
+        let merge_sources: HashSet<String> = configs
+            .values()
+            .filter(|c| c.active)
+            .filter_map(|c| c.merge.as_ref())
+            .filter(|m| m.drop_sources)
+            .filter_map(|m| template_columns(&m.template).ok())
+            .flatten()
+            .collect();
+
+        for summary in summaries {
+            let Some(config) = configs.get(&summary.name) else {
+                definition
+                    .columns
+                    .push(inferred_column(summary, &summary.standardised_name));
+                continue;
+            };
+            if !config.active {
+                continue;
+            }
+            let name = if config.new_name.is_empty() {
+                summary.name.as_str()
+            } else {
+                config.new_name.as_str()
+            };
+
+            let keep = !config.split.as_ref().is_some_and(|s| s.drop_original)
+                && !merge_sources.contains(&summary.name);
+            if keep && config.ml_preprocessing && config.one_hot_encode && config.encoding.is_none()
+            {
+                definition.notes.push(format!(
+                    "{name} is one-hot encoded into a column per value, which are not listed"
+                ));
+            } else if keep {
+                definition
+                    .columns
+                    .push(cleaned_column(summary, name, config));
+            }
+
+            for part in &config.date_parts {
+                definition.columns.push(TableColumn {
+                    name: date_part_column(name, *part),
+                    sql_type: if *part == DatePart::IsWeekend {
+                        "BOOLEAN"
+                    } else {
+                        "INTEGER"
+                    }
+                    .to_owned(),
+                    nullable: true,
+                    unique: false,
+                    description: None,
+                });
+            }
+            let added = config
+                .split
+                .iter()
+                .flat_map(|s| s.into.iter())
+                .chain(config.merge.iter().map(|m| &m.into));
+            for into in added {
+                definition.columns.push(TableColumn {
+                    name: into.clone(),
+                    sql_type: "TEXT".to_owned(),
+                    nullable: true,
+                    unique: false,
+                    description: None,
+                });
+            }
+        }
+        definition
+    }
+
+    /// Quoted, schema-qualified table name
+    pub fn qualified_name(&self) -> String {
+        match &self.schema {
+            Some(schema) => format!("{}.{}", quote(schema), quote(&self.table)),
+            None => quote(&self.table),
+        }
+    }
+
+    /// A `CREATE TABLE` statement for Postgres
+    pub fn create_table_sql(&self) -> String {
+        let mut sql = format!(
+            "-- Generated from a profile of {} rows; NOT NULL marks columns that held no nulls.\n",
+            self.profiled_rows
+        );
+        for note in &self.notes {
+            sql.push_str(&format!("-- {note}\n"));
+        }
+        sql.push_str(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (\n",
+            self.qualified_name()
+        ));
+        let columns: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| {
+                let not_null = if c.nullable { "" } else { " NOT NULL" };
+                format!("    {} {}{not_null}", quote(&c.name), c.sql_type)
+            })
+            .collect();
+        sql.push_str(&columns.join(",\n"));
+        sql.push_str("\n);\n");
+        sql
+    }
+
+    /// A dbt `schema.yml` describing the table as a model, with `not_null`
+    /// and `unique` tests for what the profile observed
+    pub fn dbt_yaml(&self) -> String {
+        let mut yaml = format!(
+            "version: 2\n\nmodels:\n  - name: {}\n    columns:\n",
+            yaml_scalar(&self.table)
+        );
+        for column in &self.columns {
+            yaml.push_str(&format!("      - name: {}\n", yaml_scalar(&column.name)));
+            if let Some(description) = &column.description {
+                yaml.push_str(&format!(
+                    "        description: '{}'\n",
+                    description.replace('\'', "''")
+                ));
+            }
+            yaml.push_str(&format!(
+                "        data_type: {}\n",
+                column.sql_type.to_lowercase()
+            ));
+            let tests: Vec<&str> = [(!column.nullable, "not_null"), (column.unique, "unique")]
+                .into_iter()
+                .filter_map(|(applies, test)| applies.then_some(test))
+                .collect();
+            if !tests.is_empty() {
+                yaml.push_str("        data_tests:\n");
+                for test in tests {
+                    yaml.push_str(&format!("          - {test}\n"));
+                }
+            }
+        }
+        yaml
+    }
+}
+
+/// A table name from a file name: its stem, sanitised
+pub fn table_name_for_file(file_name: &str) -> String {
+    let stem = std::path::Path::new(file_name).file_stem().map_or_else(
+        || file_name.to_owned(),
+        |s| s.to_string_lossy().into_owned(),
+    );
+    sanitize_column_name(&stem)
+}
+
+/// The Postgres type that holds every profiled value of `summary`
+pub fn sql_type(summary: &ColumnSummary) -> &'static str {
+    if summary.kind == ColumnKind::Nested {
+        return "JSONB";
+    }
+    match &summary.stats {
+        ColumnStats::Numeric(stats) if stats.is_integer => "BIGINT",
+        ColumnStats::Numeric(_) => "DOUBLE PRECISION",
+        ColumnStats::Temporal(stats) if stats.hour_counts.is_empty() => "DATE",
+        ColumnStats::Temporal(_) => "TIMESTAMPTZ",
+        ColumnStats::Boolean(_) => "BOOLEAN",
+        ColumnStats::Geo(stats) => match stats.encoding {
+            GeoEncoding::Latitude { .. } | GeoEncoding::Longitude { .. } => "DOUBLE PRECISION",
+            GeoEncoding::Wkt | GeoEncoding::GeoJson => "TEXT",
+        },
+        ColumnStats::Text(_) | ColumnStats::Categorical(_) => "TEXT",
+    }
+}
+
+fn inferred_column(summary: &ColumnSummary, name: &str) -> TableColumn {
+    let observed = summary.count > 0 && summary.nulls == 0;
+    TableColumn {
+        name: name.to_owned(),
+        sql_type: sql_type(summary).to_owned(),
+        nullable: !observed,
+        unique: observed && summary.uniqueness_ratio() >= 1.0,
+        description: summary.business_summary.first().cloned(),
+    }
+}
+
+fn cleaned_column(summary: &ColumnSummary, name: &str, config: &ColumnCleanConfig) -> TableColumn {
+    let mut column = inferred_column(summary, name);
+    if config.impute_mode != ImputeMode::None {
+        column.nullable = false;
+    }
+    let kind_type = |kind: ColumnKind| match kind {
+        ColumnKind::Numeric => "DOUBLE PRECISION",
+        ColumnKind::Text | ColumnKind::Categorical => "TEXT",
+        ColumnKind::Temporal => "TIMESTAMPTZ",
+        ColumnKind::Boolean => "BOOLEAN",
+        ColumnKind::Nested => "JSONB",
+    };
+    let retyped = if config.binning.is_some() {
+        Some("TEXT")
+    } else if config.ml_preprocessing
+        && let Some(encoding) = &config.encoding
+    {
+        Some(match encoding.method {
+            EncodingMethod::Ordinal => "BIGINT",
+            EncodingMethod::Target { .. } => "DOUBLE PRECISION",
+        })
+    } else if config.ml_preprocessing && config.normalisation != NormalisationMethod::None {
+        Some("DOUBLE PRECISION")
+    } else {
+        config
+            .target_dtype
+            .filter(|kind| *kind != summary.kind)
+            .map(kind_type)
+    };
+    if let Some(sql_type) = retyped {
+        column.sql_type = sql_type.to_owned();
+        // Distinct values may share a bin, code or cast value
+        column.unique = false;
+    }
+    column
+}
+
+/// `value` bare when YAML reads it as the same string, else single-quoted
+fn yaml_scalar(value: &str) -> String {
+    let plain = value
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !matches!(
+            value.to_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null"
+        );
+    if plain {
+        value.to_owned()
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::logic::types::{NumericStats, TemporalStats, TextStats};
+
+    fn summary(name: &str, nulls: usize, kind: ColumnKind, stats: ColumnStats) -> ColumnSummary {
+        ColumnSummary {
+            name: name.to_owned(),
+            standardised_name: sanitize_column_name(name),
+            kind,
+            count: 100,
+            nulls,
+            has_special: false,
+            stats,
+            interpretation: Vec::new(),
+            business_summary: Vec::new(),
+            ml_advice: Vec::new(),
+            samples: Vec::new(),
+            semantic_type: None,
+            suggested_name: None,
+        }
+    }
+
+    fn profile() -> Vec<ColumnSummary> {
+        vec![
+            summary(
+                "Order ID",
+                0,
+                ColumnKind::Numeric,
+                ColumnStats::Numeric(NumericStats {
+                    distinct_count: 100,
+                    is_integer: true,
+                    ..NumericStats::default()
+                }),
+            ),
+            summary(
+                "Ordered",
+                0,
+                ColumnKind::Temporal,
+                ColumnStats::Temporal(TemporalStats::default()),
+            ),
+            summary(
+                "Customer's Note",
+                40,
+                ColumnKind::Text,
+                ColumnStats::Text(TextStats {
+                    distinct: 30,
+                    ..TextStats::default()
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_create_table_from_analysis() {
+        let definition = TableDefinition::from_analysis(&profile(), Some("sales"), "orders");
+        let sql = definition.create_table_sql();
+        assert!(sql.starts_with("-- Generated from a profile of 100 rows"));
+        assert!(sql.ends_with(
+            "CREATE TABLE IF NOT EXISTS \"sales\".\"orders\" (\n    \
+             \"order_id\" BIGINT NOT NULL,\n    \
+             \"ordered\" DATE NOT NULL,\n    \
+             \"customer_s_note\" TEXT\n);\n"
+        ));
+        assert!(definition.columns[0].unique);
+        assert!(!definition.columns[1].unique);
+    }
+
+    #[test]
+    fn test_create_table_with_configs() {
+        let mut configs = HashMap::new();
+        configs.insert(
+            "Order ID".to_owned(),
+            ColumnCleanConfig {
+                new_name: "id".to_owned(),
+                active: true,
+                ..ColumnCleanConfig::default()
+            },
+        );
+        configs.insert(
+            "Ordered".to_owned(),
+            ColumnCleanConfig {
+                active: false,
+                ..ColumnCleanConfig::default()
+            },
+        );
+        configs.insert(
+            "Customer's Note".to_owned(),
+            ColumnCleanConfig {
+                new_name: "note".to_owned(),
+                active: true,
+                target_dtype: Some(ColumnKind::Categorical),
+                impute_mode: ImputeMode::Mode,
+                ..ColumnCleanConfig::default()
+            },
+        );
+
+        let definition =
+            TableDefinition::from_analysis_with_configs(&profile(), &configs, None, "orders");
+        let columns: Vec<(&str, &str, bool)> = definition
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.sql_type.as_str(), c.nullable))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![("id", "BIGINT", false), ("note", "TEXT", false)]
+        );
+        assert_eq!(definition.qualified_name(), "\"orders\"");
+    }
+
+    #[test]
+    fn test_dbt_yaml() {
+        let mut summaries = profile();
+        summaries[2].business_summary = vec!["Free text, 40% blank".to_owned()];
+        let yaml = TableDefinition::from_analysis(&summaries, None, "2024 orders").dbt_yaml();
+        assert_eq!(
+            yaml,
+            "version: 2\n\nmodels:\n  - name: '2024 orders'\n    columns:\n\
+             \x20     - name: order_id\n        data_type: bigint\n        data_tests:\n\
+             \x20         - not_null\n          - unique\n\
+             \x20     - name: ordered\n        data_type: date\n        data_tests:\n\
+             \x20         - not_null\n\
+             \x20     - name: customer_s_note\n        description: 'Free text, 40% blank'\n\
+             \x20       data_type: text\n"
+        );
+    }
+
+    #[test]
+    fn test_table_name_for_file() {
+        assert_eq!(table_name_for_file("Sales Q1.csv"), "sales_q1");
+        assert_eq!(table_name_for_file("2024.parquet"), "col_2024");
+    }
+}
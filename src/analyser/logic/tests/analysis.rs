@@ -167,9 +167,48 @@ fn test_effective_boolean_detection() -> Result<()> {
     if let ColumnStats::Boolean(stats) = &summaries.first().unwrap().stats {
         assert_eq!(stats.true_count, 2);
         assert_eq!(stats.false_count, 1);
+        assert_eq!(stats.null_count, 1);
     } else {
         panic!("Expected BooleanStats for 0/1 numeric column");
     }
+
+    // Values between 0 and 1 are not a boolean encoding
+    let s = Series::new("score".into(), vec![0.0, 0.5, 1.0, 0.5]);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+    let summaries = analyse_df(&df, 0.0)?;
+    assert_eq!(summaries.first().unwrap().kind.as_str(), "Numeric");
+    Ok(())
+}
+
+#[test]
+fn test_boolean_imbalance_advice() -> Result<()> {
+    let mut values = vec![Some(false); 995];
+    values.extend([Some(true), Some(true), None, None, None]);
+    let s = Series::new("is_fraud".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let summary = summaries.first().unwrap();
+
+    let ColumnStats::Boolean(stats) = &summary.stats else {
+        panic!("Expected BooleanStats");
+    };
+    assert_eq!(stats.null_count, 3);
+    assert!((stats.positive_rate().unwrap() - 2.0 / 997.0).abs() < 1e-12);
+    assert!((stats.null_rate().unwrap() - 0.003).abs() < 1e-12);
+
+    assert!(
+        summary
+            .interpretation
+            .join(" ")
+            .contains("Extremely imbalanced")
+    );
+    assert!(
+        summary
+            .ml_advice
+            .iter()
+            .any(|a| a.contains("class imbalance"))
+    );
+    assert!(summary.ml_advice.iter().any(|a| a.contains("third state")));
     Ok(())
 }
 
@@ -455,3 +494,517 @@ fn test_export_tall_dataset() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_incremental_reanalysis_only_recomputes_changed_columns() -> Result<()> {
+    let df = df!(
+        "Name" => &["  alice ", "bob", "  carol"],
+        "score" => &[1.0, 2.0, 30.0]
+    )?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let old_configs = std::collections::HashMap::new();
+
+    let mut new_configs = std::collections::HashMap::new();
+    new_configs.insert(
+        "Name".to_owned(),
+        ColumnCleanConfig {
+            new_name: "name".to_owned(),
+            trim_whitespace: true,
+            ..Default::default()
+        },
+    );
+
+    let update = reanalyse_changed_columns(
+        df.lazy(),
+        summaries.clone(),
+        old_configs,
+        new_configs,
+        0.0,
+        10_000,
+        &AnalysisOptions::default(),
+    )?;
+
+    assert_eq!(update.changed_columns, vec!["Name".to_owned()]);
+    assert_eq!(update.recomputed, vec!["name".to_owned()]);
+    assert_eq!(update.summary.len(), 2);
+    assert_eq!(update.summary[0].name, "name");
+    // Untouched column keeps its original summary
+    assert_eq!(update.summary[1].name, summaries[1].name);
+    assert_eq!(
+        update.health.score,
+        calculate_file_health(&update.summary).score
+    );
+    Ok(())
+}
+
+#[test]
+fn test_correlation_matrix_options() -> Result<()> {
+    let df = df!(
+        "a" => &[1.0, 2.0, 3.0, 4.0],
+        "b" => &[2.0, 4.0, 6.0, 8.0],
+        "c" => &[100.0, -50.0, 300.0, 0.0],
+        "d" => &[1.0, 1.5, 1.0, 1.5]
+    )?;
+
+    // Explicit column selection
+    let options = CorrelationOptions {
+        columns: vec!["a".to_owned(), "d".to_owned(), "missing".to_owned()],
+        ..Default::default()
+    };
+    let matrix = calculate_correlation_matrix_with_options(df.clone().lazy(), &options)?
+        .expect("Matrix should be computed");
+    assert_eq!(matrix.columns, vec!["a", "d"]);
+    assert!(matrix.omitted_columns.is_empty());
+
+    // Column limit with top-variance selection keeps the most variable columns
+    let options = CorrelationOptions {
+        max_columns: Some(2),
+        select_by_variance: true,
+        ..Default::default()
+    };
+    let matrix = calculate_correlation_matrix_with_options(df.lazy(), &options)?
+        .expect("Matrix should be computed");
+    assert_eq!(matrix.columns, vec!["b", "c"]);
+    assert_eq!(matrix.omitted_columns, vec!["a", "d"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_histogram_strategies() -> Result<()> {
+    let values: Vec<f64> = (1..=1000).map(|i| i as f64).collect();
+    let s = Series::new("col".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+
+    let mut options = AnalysisOptions::default();
+    options.histogram = HistogramOptions {
+        strategy: HistogramStrategy::FixedBins,
+        bin_count: Some(7),
+    };
+    let summaries = analyse_df_lazy_with_options(df.clone().lazy(), 0.0, 10_000, &options)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+    assert_eq!(stats.histogram_strategy, HistogramStrategy::FixedBins);
+    assert_eq!(stats.histogram.len(), 7);
+    assert_eq!(stats.histogram.iter().map(|h| h.1).sum::<usize>(), 1000);
+
+    options.histogram = HistogramOptions {
+        strategy: HistogramStrategy::Sturges,
+        bin_count: None,
+    };
+    let summaries = analyse_df_lazy_with_options(df.clone().lazy(), 0.0, 10_000, &options)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+    // ceil(log2(1000)) + 1
+    assert_eq!(stats.histogram.len(), 11);
+
+    options.histogram = HistogramOptions {
+        strategy: HistogramStrategy::LogScale,
+        bin_count: Some(3),
+    };
+    let summaries = analyse_df_lazy_with_options(df.lazy(), 0.0, 10_000, &options)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+    assert_eq!(stats.histogram_strategy, HistogramStrategy::LogScale);
+    // Decades: [1, 10), [10, 100), [100, 1000]
+    let counts: Vec<usize> = stats.histogram.iter().map(|h| h.1).collect();
+    assert_eq!(counts, vec![9, 90, 901]);
+    Ok(())
+}
+
+#[test]
+fn test_log_histogram_falls_back_for_non_positive_values() {
+    let ca = Float64Chunked::from_slice("col".into(), &[-5.0, 0.0, 5.0, 10.0]);
+    let options = HistogramOptions {
+        strategy: HistogramStrategy::LogScale,
+        bin_count: None,
+    };
+    let (strategy, _, histogram) = profiling::calculate_histogram_with_options(
+        &ca,
+        Some(-5.0),
+        Some(10.0),
+        Some(0.0),
+        Some(5.0),
+        &options,
+    );
+    assert_eq!(strategy, HistogramStrategy::Auto);
+    assert_eq!(histogram.iter().map(|h| h.1).sum::<usize>(), 4);
+}
+
+#[test]
+fn test_histogram_variants_for_skewed_column() -> Result<()> {
+    // Mostly small values with a long right tail
+    let mut values: Vec<f64> = (1..=950).map(|i| f64::from(i % 50 + 1)).collect();
+    values.extend((1..=50).map(|i| f64::from(i) * 10_000.0));
+    let s = Series::new("col".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+
+    let summaries = analyse_df_lazy(df.lazy(), 0.0, 10_000)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+    let log = stats.log_histogram.as_ref().expect("Log variant exists");
+    assert_eq!(log.histogram.iter().map(|h| h.1).sum::<usize>(), 1000);
+
+    let zoom = stats
+        .zoomed_histogram
+        .as_ref()
+        .expect("Zoomed variant exists");
+    assert_eq!(Some(zoom.lower), stats.p05);
+    assert_eq!(Some(zoom.upper), stats.p95);
+    let in_range: usize = zoom.histogram.iter().map(|h| h.1).sum();
+    assert_eq!(in_range + zoom.excluded_count, 1000);
+    assert!(zoom.excluded_count > 0, "Tail values fall outside P05–P95");
+    Ok(())
+}
+
+#[test]
+fn test_no_histogram_variants_for_symmetric_column() -> Result<()> {
+    let values: Vec<f64> = (1..=1000).map(f64::from).collect();
+    let s = Series::new("col".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+
+    let summaries = analyse_df_lazy(df.lazy(), 0.0, 10_000)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+    assert!(stats.log_histogram.is_none());
+    assert!(stats.zoomed_histogram.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_quantile_table_and_ecdf() -> Result<()> {
+    let values: Vec<f64> = (0..=100).map(f64::from).collect();
+    let s = Series::new("col".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+
+    let summaries = analyse_df_lazy(df.lazy(), 0.0, 10_000)?;
+    let ColumnStats::Numeric(stats) = &summaries[0].stats else {
+        panic!("Expected NumericStats");
+    };
+
+    assert_eq!(stats.quantiles.len(), 99);
+    assert!((stats.percentile(1).expect("P1") - 1.0).abs() < 1e-9);
+    assert_eq!(stats.percentile(50), stats.median);
+    assert!((stats.percentile(99).expect("P99") - 99.0).abs() < 1e-9);
+    assert_eq!(stats.percentile(100), None);
+
+    assert_eq!(stats.ecdf.len(), profiling::ECDF_POINTS);
+    assert_eq!(stats.ecdf.last().map(|p| p.1), Some(1.0));
+    assert!(stats.ecdf.windows(2).all(|w| w[0].1 <= w[1].1));
+
+    let above = stats.fraction_above(75.0).expect("ECDF available");
+    assert!((above - 25.0 / 101.0).abs() < 0.02, "got {above}");
+    assert_eq!(stats.fraction_at_or_below(-1.0), Some(0.0));
+    assert_eq!(stats.fraction_above(1_000.0), Some(0.0));
+    Ok(())
+}
+
+#[test]
+fn test_categorical_concentration_metrics() {
+    let freq: std::collections::HashMap<String, usize> =
+        [("a".to_owned(), 50), ("b".to_owned(), 50)].into();
+    let metrics = CategoricalMetrics::from_frequencies(&freq).expect("metrics");
+    assert_eq!(metrics.mode, "a");
+    assert!((metrics.modal_share - 0.5).abs() < 1e-9);
+    assert!((metrics.entropy - 1.0).abs() < 1e-9);
+    assert!((metrics.normalised_entropy - 1.0).abs() < 1e-9);
+    assert!((metrics.hhi - 0.5).abs() < 1e-9);
+    assert!((metrics.gini - 0.5).abs() < 1e-9);
+    assert!(!metrics.effectively_constant);
+
+    let skewed: std::collections::HashMap<String, usize> =
+        [("yes".to_owned(), 97), ("no".to_owned(), 3)].into();
+    let metrics = CategoricalMetrics::from_frequencies(&skewed).expect("metrics");
+    assert_eq!(metrics.mode, "yes");
+    assert!(metrics.effectively_constant);
+    assert!(metrics.hhi > 0.9);
+
+    assert!(CategoricalMetrics::from_frequencies(&std::collections::HashMap::new()).is_none());
+}
+
+#[test]
+fn test_near_constant_categorical_advice() -> Result<()> {
+    let values: Vec<&str> = std::iter::repeat_n("UK", 98).chain(["FR", "DE"]).collect();
+    let s = Series::new("country".into(), values);
+    let df = DataFrame::new(vec![Column::from(s)])?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let summary = summaries.first().unwrap();
+
+    assert_eq!(summary.kind, ColumnKind::Categorical);
+    assert!(
+        summary
+            .categorical_metrics()
+            .is_some_and(|m| m.effectively_constant)
+    );
+    assert!(
+        summary
+            .ml_advice
+            .iter()
+            .any(|a| a.contains("Near-constant")),
+        "Got: {:?}",
+        summary.ml_advice
+    );
+    Ok(())
+}
+
+#[test]
+fn test_temporal_patterns_and_quality_counts() -> Result<()> {
+    let now_ms = 1_704_240_000_000; // 2024-01-03
+    let timestamps: Vec<i64> = vec![
+        1_704_103_200_000,  // Mon 2024-01-01 10:00
+        1_704_196_800_000,  // Tue 2024-01-02 12:00
+        -5_364_662_400_000, // Wed 1800-01-01 00:00
+        4_115_574_000_000,  // Tue 2100-06-01 23:00
+    ];
+    let col = Column::new("ts".into(), timestamps)
+        .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))?;
+
+    let (_, stats) = profiling::analyse_temporal_at(&col, now_ms)?;
+    let ColumnStats::Temporal(stats) = stats else {
+        panic!("Expected TemporalStats");
+    };
+
+    assert_eq!(stats.weekday_counts, vec![1, 2, 1, 0, 0, 0, 0]);
+    assert_eq!(stats.hour_counts.len(), 24);
+    assert_eq!(stats.hour_counts[0], 1);
+    assert_eq!(stats.hour_counts[10], 1);
+    assert_eq!(stats.hour_counts[23], 1);
+    assert_eq!(stats.future_count, 1);
+    assert_eq!(stats.implausible_count, 1);
+    assert_eq!(stats.min_gap_ms, Some(93_600_000.0));
+    assert_eq!(stats.max_gap_ms, Some(7_068_765_600_000.0));
+    Ok(())
+}
+
+#[test]
+fn test_text_dates_are_profiled_and_cleaned_with_their_format() -> Result<()> {
+    let df = df!("placed" => [Some("15/03/2024"), Some("01/02/2024"), Some("28/02/2024"), None])?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let summary = &summaries[0];
+    assert_eq!(summary.kind, ColumnKind::Temporal);
+    let ColumnStats::Temporal(stats) = &summary.stats else {
+        panic!("Expected TemporalStats");
+    };
+    assert_eq!(stats.format.as_deref(), Some("%d/%m/%Y"));
+    assert!(!stats.format_ambiguous);
+    assert_eq!(summary.nulls, 1);
+
+    let mut config = ColumnCleanConfig::default();
+    summary.apply_advice_to_config(&mut config);
+    assert_eq!(config.temporal_format, "%d/%m/%Y");
+    let configs = std::collections::HashMap::from([("placed".to_owned(), config)]);
+    let cleaned = clean_df(df, &configs, false)?;
+    let placed = cleaned.column("placed")?;
+    assert!(placed.dtype().is_temporal());
+    assert_eq!(placed.null_count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_column_deep_dive_parts() -> Result<()> {
+    let df = df! {
+        "status" => &[Some("a"), Some("a"), Some("a"), None, Some("b"), Some("b"), Some("a")],
+        "amount" => &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0],
+    }?;
+    let options = DeepDiveOptions {
+        max_distinct_values: 1,
+        max_runs: 5,
+    };
+
+    let mut parts = Vec::new();
+    let dive = column_deep_dive(df.clone().lazy(), "status", &options, |p| {
+        parts.push(p.clone());
+    })?;
+    assert_eq!(parts.len(), 2);
+    assert_eq!(dive.distinct_values.values, vec![("a".to_owned(), 4)]);
+    assert_eq!(dive.distinct_values.total_distinct, 2);
+    assert!(dive.distinct_values.truncated);
+    let runs: Vec<(&str, usize, usize)> = dive
+        .longest_runs
+        .iter()
+        .map(|r| (r.value.as_str(), r.length, r.start_row))
+        .collect();
+    assert_eq!(runs, vec![("a", 3, 0), ("b", 2, 4)]);
+    assert!(dive.decile_means.is_empty());
+
+    let dive = column_deep_dive(df.lazy(), "amount", &DeepDiveOptions::default(), |_| {})?;
+    assert!(dive.longest_runs.is_empty());
+    let counts: usize = dive.decile_means.iter().map(|d| d.count).sum();
+    assert_eq!(counts, 7);
+    let last = dive.decile_means.last().expect("deciles");
+    assert_eq!((last.decile, last.mean), (10, 7.0));
+    Ok(())
+}
+
+#[test]
+fn test_semantic_types_drive_cleaning_defaults() -> Result<()> {
+    let df = df!(
+        "email" => ["Ann@Example.com", "bob@mail.co.uk", "cy@x.org", "di@y.net"],
+        "country" => ["gb", "FR", "de", "US"],
+        "name" => ["Ann", "Bob", "Cy", "Di"],
+    )?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let kinds: Vec<Option<SemanticType>> = summaries.iter().map(|s| s.semantic_type).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            Some(SemanticType::Email),
+            Some(SemanticType::CountryCode),
+            None
+        ]
+    );
+    assert!(SemanticType::Email.is_pii());
+
+    let mut email = ColumnCleanConfig {
+        remove_special_chars: true,
+        ..Default::default()
+    };
+    summaries[0].apply_advice_to_config(&mut email);
+    assert_eq!(email.text_case, TextCase::Lowercase);
+    assert!(!email.remove_special_chars);
+
+    let mut country = ColumnCleanConfig::default();
+    summaries[1].apply_advice_to_config(&mut country);
+    assert_eq!(country.text_case, TextCase::Uppercase);
+    Ok(())
+}
+
+#[test]
+fn test_suggested_names_for_placeholder_headers() -> Result<()> {
+    assert!(is_generic_column_name("Column1"));
+    assert!(is_generic_column_name("column_12"));
+    assert!(is_generic_column_name("F2"));
+    assert!(is_generic_column_name("Unnamed: 0"));
+    assert!(is_generic_column_name(""));
+    assert!(!is_generic_column_name("email"));
+    assert!(!is_generic_column_name("forecast2"));
+
+    let df = df!(
+        "column_1" => [1, 2, 3, 4],
+        "column_2" => ["ann@example.com", "bob@mail.co.uk", "cy@x.org", "di@y.net"],
+        "column_3" => ["ORD-1001", "ORD-1002", "ORD-1003", "ORD-1004"],
+        "column_4" => ["ann@example.com", "bob@mail.co.uk", "cy@x.org", "di@y.net"],
+    )?;
+    let summaries = analyse_df(&df, 0.0)?;
+    let suggested: Vec<Option<&str>> = summaries
+        .iter()
+        .map(|s| s.suggested_name.as_deref())
+        .collect();
+    assert_eq!(
+        suggested,
+        [
+            Some("id"),
+            Some("email_address"),
+            Some("reference"),
+            Some("email_address_1")
+        ]
+    );
+    assert!(headers_missing(&summaries));
+
+    // Without a header row, auto-clean names columns from their content
+    let configs = generate_auto_clean_configs(df.lazy())?;
+    assert_eq!(configs["column_2"].new_name, "email_address");
+
+    // Real headers are kept, and only placeholders get suggestions
+    let df = df!(
+        "id" => [1, 2, 3],
+        "Column2" => ["ann@example.com", "bob@mail.co.uk", "cy@x.org"],
+    )?;
+    let summaries = analyse_df(&df, 0.0)?;
+    assert_eq!(summaries[0].suggested_name, None);
+    assert_eq!(
+        summaries[1].suggested_name.as_deref(),
+        Some("email_address")
+    );
+    assert!(!headers_missing(&summaries));
+    let configs = generate_auto_clean_configs(df.lazy())?;
+    assert_eq!(configs["Column2"].new_name, "column2");
+    Ok(())
+}
+
+#[test]
+fn test_grid_pages_are_filtered_sorted_and_sliced() -> Result<()> {
+    let df = df!(
+        "city" => [Some("Leeds"), Some("York"), None, Some("leeds east"), Some("Hull")],
+        "sales" => [Some(12.5), Some(3.0), Some(7.0), None, Some(40.0)],
+    )?;
+
+    let page = grid_page(
+        df.clone().lazy(),
+        &GridQuery {
+            limit: 2,
+            sort: Some(GridSort {
+                column: "sales".to_owned(),
+                descending: true,
+            }),
+            ..GridQuery::default()
+        },
+    )?;
+    assert_eq!(page.total_rows, Some(5));
+    assert_eq!(page.columns[1].name, "sales");
+    assert_eq!(
+        page.rows,
+        [
+            [Some("Hull".to_owned()), Some("40.0".to_owned())],
+            [Some("Leeds".to_owned()), Some("12.5".to_owned())],
+        ]
+    );
+
+    // Nulls sort last, and later pages carry on from the offset
+    let last = grid_page(
+        df.clone().lazy(),
+        &GridQuery {
+            offset: 4,
+            sort: Some(GridSort {
+                column: "sales".to_owned(),
+                descending: false,
+            }),
+            count: false,
+            ..GridQuery::default()
+        },
+    )?;
+    assert_eq!(last.total_rows, None);
+    assert_eq!(last.rows, [[Some("leeds east".to_owned()), None]]);
+
+    let filter = |column: &str, op: GridFilterOp, value: &str| GridFilter {
+        column: column.to_owned(),
+        op,
+        value: value.to_owned(),
+    };
+    let filtered = grid_page(
+        df.clone().lazy(),
+        &GridQuery {
+            filters: vec![
+                filter("city", GridFilterOp::Contains, "LEEDS"),
+                filter("sales", GridFilterOp::NotNull, ""),
+            ],
+            ..GridQuery::default()
+        },
+    )?;
+    assert_eq!(filtered.total_rows, Some(1));
+    let above = grid_page(
+        df.clone().lazy(),
+        &GridQuery {
+            filters: vec![filter("sales", GridFilterOp::GreaterThan, "5")],
+            ..GridQuery::default()
+        },
+    )?;
+    assert_eq!(above.total_rows, Some(3));
+
+    let not_a_number = GridQuery {
+        filters: vec![filter("sales", GridFilterOp::LessThan, "lots")],
+        ..GridQuery::default()
+    };
+    assert!(grid_page(df.clone().lazy(), &not_a_number).is_err());
+    let unknown = GridQuery {
+        filters: vec![filter("town", GridFilterOp::IsNull, "")],
+        ..GridQuery::default()
+    };
+    assert!(grid_page(df.lazy(), &unknown).is_err());
+    Ok(())
+}
@@ -292,3 +292,508 @@ fn test_lazy_one_hot_encoding() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_append_completeness_score() -> Result<()> {
+    let df = df!(
+        "a" => &[Some(1), None, None],
+        "b" => &[Some("x"), Some("y"), None],
+        "c" => &[Some(1.0), Some(2.0), Some(3.0)]
+    )?;
+
+    let scored =
+        append_completeness_score(df.clone().lazy(), COMPLETENESS_COLUMN, &HashMap::new())?
+            .collect()?;
+    let score = scored.column(COMPLETENESS_COLUMN)?.f64()?.clone();
+    assert!((score.get(0).unwrap() - 1.0).abs() < 1e-9);
+    assert!((score.get(1).unwrap() - 2.0 / 3.0).abs() < 1e-9);
+    assert!((score.get(2).unwrap() - 1.0 / 3.0).abs() < 1e-9);
+
+    // "a" counts double, "c" is ignored
+    let weights = HashMap::from([("a".to_owned(), 2.0), ("c".to_owned(), 0.0)]);
+    let weighted = append_completeness_score(df.clone().lazy(), "score", &weights)?.collect()?;
+    let score = weighted.column("score")?.f64()?.clone();
+    assert!((score.get(0).unwrap() - 1.0).abs() < 1e-9);
+    assert!((score.get(1).unwrap() - 1.0 / 3.0).abs() < 1e-9);
+    assert!((score.get(2).unwrap() - 0.0).abs() < 1e-9);
+
+    let negative = HashMap::from([("a".to_owned(), -1.0)]);
+    assert!(append_completeness_score(df.lazy(), "score", &negative).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_cleaning_rejects_split_failed_casts() -> Result<()> {
+    let df = df!(
+        "id" => &[1, 2, 3, 4],
+        "amount" => &[Some("10"), Some("abc"), None, Some("N/A")]
+    )?;
+    let mut configs = HashMap::new();
+    configs.insert(
+        "amount".to_owned(),
+        ColumnCleanConfig {
+            target_dtype: Some(ColumnKind::Numeric),
+            standardise_nulls: true,
+            ..Default::default()
+        },
+    );
+
+    let mut lf = df.lazy();
+    let schema = lf.collect_schema()?;
+    let conditions = cleaning_reject_conditions(&schema, &configs, false);
+    assert_eq!(conditions.len(), 1);
+
+    let (kept, rejects) = split_rejects(lf, &conditions);
+    let cleaned = clean_df_lazy(kept, &configs, false)?.collect()?;
+    let rejects = rejects.collect()?;
+
+    // Explicit nulls are not failures, only unparseable values are
+    assert_eq!(cleaned.height(), 3);
+    assert_eq!(rejects.height(), 1);
+    assert_eq!(rejects.column("amount")?.str()?.get(0), Some("abc"));
+    assert_eq!(
+        rejects.column(REJECT_REASON_COLUMN)?.str()?.get(0),
+        Some("amount: not a valid number")
+    );
+
+    let combined = union_rejects(vec![
+        rejects.clone().lazy(),
+        df!("other" => &[1], REJECT_REASON_COLUMN => &["x"])?.lazy(),
+    ])?
+    .unwrap()
+    .collect()?;
+    assert_eq!(combined.height(), 2);
+    assert_eq!(combined.width(), 4);
+    assert_eq!(
+        combined.get_column_names()[0].as_str(),
+        REJECT_REASON_COLUMN
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_preview_clean_pairs_values() -> Result<()> {
+    let df = df!(
+        "code" => &[Some("AB-1"), Some("CD2"), None, Some("EF-3")],
+        "flag" => &["y", "n", "y", "n"],
+        "n" => &[1, 2, 3, 4],
+    )?;
+    let mut configs = HashMap::new();
+    configs.insert(
+        "code".to_owned(),
+        ColumnCleanConfig {
+            new_name: "code_clean".to_owned(),
+            regex_find: "-".to_owned(),
+            regex_replace: String::new(),
+            ..Default::default()
+        },
+    );
+    configs.insert(
+        "flag".to_owned(),
+        ColumnCleanConfig {
+            active: false,
+            ..Default::default()
+        },
+    );
+
+    let preview = preview_clean(df.lazy(), &configs, 3)?;
+    assert_eq!(preview.rows, 3);
+    // Only configured columns are previewed
+    assert_eq!(preview.columns.len(), 2);
+
+    let code = &preview.columns[0];
+    assert_eq!(code.cleaned_name.as_deref(), Some("code_clean"));
+    assert_eq!(code.before[0].as_deref(), Some("AB-1"));
+    assert_eq!(code.after[0].as_deref(), Some("AB1"));
+    assert_eq!(code.after[2], None);
+    assert_eq!(code.changed, 1);
+
+    let flag = &preview.columns[1];
+    assert_eq!(flag.cleaned_name, None);
+    assert!(flag.after.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_cleaning_presets_save_and_apply() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let library = PresetLibrary::new(dir.path());
+    let config = |new_name: &str| ColumnCleanConfig {
+        new_name: new_name.to_owned(),
+        ..Default::default()
+    };
+    let configs = HashMap::from([
+        ("Customer Name".to_owned(), config("customer")),
+        ("order_total".to_owned(), config("total")),
+        ("Region".to_owned(), config("region")),
+        ("id".to_owned(), config("id")),
+    ]);
+    let saved = library.save(" Monthly orders ", configs)?;
+    assert_eq!(saved.name, "Monthly orders");
+    // Saving under the same name replaces the preset
+    let saved = library.save("monthly ORDERS", saved.configs)?;
+    assert_eq!(library.list()?.len(), 1);
+    assert!(library.save("empty", HashMap::new()).is_err());
+
+    let preset = library.get(&saved.id)?;
+    let columns = ["Region", "customer_name", "order_totl", "ix", "notes"].map(str::to_owned);
+    let applied = apply_preset(&preset, &columns);
+    let kinds: Vec<(&str, &str, PresetMatchKind)> = applied
+        .matches
+        .iter()
+        .map(|m| (m.column.as_str(), m.preset_column.as_str(), m.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ("Region", "Region", PresetMatchKind::Exact),
+            ("customer_name", "Customer Name", PresetMatchKind::Sanitised),
+            ("order_totl", "order_total", PresetMatchKind::Fuzzy),
+        ]
+    );
+    assert_eq!(applied.configs["order_totl"].new_name, "total");
+    // Names too short for fuzzy matching are left alone
+    assert_eq!(applied.unmatched_columns, vec!["ix", "notes"]);
+    assert_eq!(applied.unused_preset_columns, vec!["id"]);
+
+    assert!(library.delete(&saved.id)?);
+    assert!(!library.delete(&saved.id)?);
+    assert!(library.get("../escape").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_column_usage_suggestions() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let log = UsageLog::new(dir.path());
+    let config = |new_name: &str, active: bool| ColumnCleanConfig {
+        new_name: new_name.to_owned(),
+        active,
+        ..Default::default()
+    };
+    for i in 0..5 {
+        let configs = HashMap::from([
+            ("Notes".to_owned(), config("notes", i == 0)),
+            ("Cust Name".to_owned(), config("customer", true)),
+            // Renaming to the sanitised name is the default, not a choice
+            ("Region".to_owned(), config("region", true)),
+            (
+                "Segment".to_owned(),
+                ColumnCleanConfig {
+                    ml_preprocessing: true,
+                    one_hot_encode: i < 4,
+                    ..config("segment", true)
+                },
+            ),
+        ]);
+        log.record("orders.csv", &configs)?;
+    }
+
+    let usage = log.get("orders.csv")?;
+    assert_eq!(usage.cleans, 5);
+    assert_eq!(usage.columns["Notes"].dropped, 4);
+    assert!(usage.columns["Region"].renamed_to.is_empty());
+
+    let suggestions = usage.suggestions();
+    let actions: Vec<(&str, &UsageAction)> = suggestions
+        .iter()
+        .map(|s| (s.column.as_str(), &s.action))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            (
+                "Cust Name",
+                &UsageAction::Rename {
+                    to: "customer".to_owned()
+                }
+            ),
+            ("Notes", &UsageAction::Drop),
+            ("Segment", &UsageAction::OneHotEncode),
+        ]
+    );
+    assert_eq!(
+        suggestions[1].message,
+        "'Notes' dropped in 4 of 5 cleans — drop by default?"
+    );
+
+    let mut configs = HashMap::from([
+        ("Notes".to_owned(), config("notes", true)),
+        ("Cust Name".to_owned(), config("cust_name", true)),
+    ]);
+    assert_eq!(apply_usage_suggestions(&mut configs, &suggestions), 2);
+    assert!(!configs["Notes"].active);
+    assert_eq!(configs["Cust Name"].new_name, "customer");
+
+    // Too few cleans suggest nothing
+    assert!(log.get("other.csv")?.suggestions().is_empty());
+    assert!(log.clear("orders.csv")?);
+    assert!(!log.clear("orders.csv")?);
+    assert_eq!(log.get("orders.csv")?.cleans, 0);
+    Ok(())
+}
+
+#[test]
+fn test_value_mapping_after_text_options() -> Result<()> {
+    let df =
+        df!("city" => ["New York", " New York", "new york", "N.Y.", "NY ", "Boston", "boston"])?;
+    let mut config = ColumnCleanConfig {
+        trim_whitespace: true,
+        ..Default::default()
+    };
+
+    let suggested = suggest_value_mapping(
+        df.clone().lazy(),
+        "city",
+        Some(&config),
+        &StandardiseOptions::default(),
+    )?;
+    // Trimming already merged the spellings differing only in whitespace
+    assert_eq!(suggested.distinct_values, 6);
+    assert_eq!(suggested.clusters.len(), 2);
+    assert_eq!(suggested.mapping["new york"], "New York");
+    assert_eq!(suggested.mapping["N.Y."], "New York");
+    assert_eq!(suggested.mapping["NY"], "New York");
+    assert!(!suggested.mapping.contains_key(" New York"));
+
+    config.value_mapping = suggested.mapping;
+    let configs = HashMap::from([("city".to_owned(), config)]);
+    let cleaned = clean_df(df, &configs, false)?;
+    let city = cleaned.column("city")?.str()?;
+    let unique: std::collections::BTreeSet<_> = city.into_iter().flatten().collect();
+    assert_eq!(unique.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_value_mapping_recodes_codes() -> Result<()> {
+    let df = df!(
+        "sex" => ["M", "F", "M", "X"],
+        "status" => [1i64, 0, 1, 2],
+    )?;
+    let configs = HashMap::from([
+        (
+            "sex".to_owned(),
+            ColumnCleanConfig {
+                value_mapping: [("M", "Male"), ("F", "Female")]
+                    .into_iter()
+                    .map(|(from, to)| (from.to_owned(), to.to_owned()))
+                    .collect(),
+                ..Default::default()
+            },
+        ),
+        (
+            "status".to_owned(),
+            ColumnCleanConfig {
+                value_mapping: [("1", "Active"), ("0", "Inactive")]
+                    .into_iter()
+                    .map(|(from, to)| (from.to_owned(), to.to_owned()))
+                    .collect(),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    let cleaned = clean_df(df, &configs, false)?;
+    let sex: Vec<_> = cleaned
+        .column("sex")?
+        .str()?
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(sex, ["Male", "Female", "Male", "X"]);
+    // Numeric codes are recoded by their text form; unmapped codes are kept
+    let status: Vec<_> = cleaned
+        .column("status")?
+        .str()?
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(status, ["Active", "Inactive", "Active", "2"]);
+    Ok(())
+}
+
+#[test]
+fn test_split_and_merge_columns() -> Result<()> {
+    let df = df!(
+        "full_name" => ["  Ada LOVELACE", "Grace Hopper", "Alan"],
+        "city" => ["London", "New York", "Wilmslow"],
+        "country" => ["UK", "US", "UK"],
+    )?;
+    let configs = HashMap::from([
+        (
+            "full_name".to_owned(),
+            ColumnCleanConfig {
+                trim_whitespace: true,
+                text_case: TextCase::Lowercase,
+                split: Some(ColumnSplit {
+                    delimiter: r"\s+".to_owned(),
+                    regex: true,
+                    into: vec!["first".to_owned(), "last".to_owned()],
+                    drop_original: true,
+                }),
+                ..Default::default()
+            },
+        ),
+        (
+            "city".to_owned(),
+            ColumnCleanConfig {
+                text_case: TextCase::Uppercase,
+                merge: Some(ColumnMerge {
+                    template: "{city}, {country}".to_owned(),
+                    into: "location".to_owned(),
+                    drop_sources: true,
+                }),
+                ..Default::default()
+            },
+        ),
+    ]);
+
+    let cleaned = clean_df(df, &configs, false)?;
+    let names: Vec<&str> = cleaned
+        .get_column_names()
+        .iter()
+        .map(|n| n.as_str())
+        .collect();
+    assert_eq!(names, ["first", "last", "location"]);
+    // Splits and merges read the cleaned values
+    let last: Vec<_> = cleaned.column("last")?.str()?.into_iter().collect();
+    assert_eq!(last, [Some("lovelace"), Some("hopper"), None]);
+    let location: Vec<_> = cleaned
+        .column("location")?
+        .str()?
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(location, ["LONDON, UK", "NEW YORK, US", "WILMSLOW, UK"]);
+    Ok(())
+}
+
+#[test]
+fn test_date_parts_columns() -> Result<()> {
+    let df = df!("placed" => ["2024-03-09 14:30:00", "2024-12-31 08:05:00"])?;
+    let configs = HashMap::from([(
+        "placed".to_owned(),
+        ColumnCleanConfig {
+            new_name: "order_date".to_owned(),
+            target_dtype: Some(ColumnKind::Temporal),
+            date_parts: vec![DatePart::Year, DatePart::Weekday, DatePart::Hour],
+            ..Default::default()
+        },
+    )]);
+
+    let cleaned = clean_df(df, &configs, false)?;
+    let names: Vec<&str> = cleaned
+        .get_column_names()
+        .iter()
+        .map(|n| n.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "order_date",
+            "order_date_year",
+            "order_date_weekday",
+            "order_date_hour"
+        ]
+    );
+    let weekday: Vec<_> = cleaned
+        .column("order_date_weekday")?
+        .i32()?
+        .into_iter()
+        .collect();
+    assert_eq!(weekday, [Some(6), Some(2)]);
+    let hour: Vec<_> = cleaned
+        .column("order_date_hour")?
+        .i32()?
+        .into_iter()
+        .collect();
+    assert_eq!(hour, [Some(14), Some(8)]);
+
+    // Text that was never parsed has no date parts
+    let text = df!("placed" => ["2024-03-09"])?;
+    let configs = HashMap::from([(
+        "placed".to_owned(),
+        ColumnCleanConfig {
+            date_parts: vec![DatePart::Month],
+            ..Default::default()
+        },
+    )]);
+    assert!(clean_df(text, &configs, false).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_binning_column() -> Result<()> {
+    let df = df!("age" => [Some("15"), Some("34"), Some("52"), Some("70"), None])?;
+    let configs = HashMap::from([(
+        "age".to_owned(),
+        ColumnCleanConfig {
+            new_name: "age_band".to_owned(),
+            target_dtype: Some(ColumnKind::Numeric),
+            binning: Some(ColumnBinning {
+                strategy: BinStrategy::Custom {
+                    edges: vec![0.0, 18.0, 65.0, 120.0],
+                },
+                labels: vec!["child".to_owned(), "adult".to_owned(), "senior".to_owned()],
+            }),
+            ..Default::default()
+        },
+    )]);
+
+    let cleaned = clean_df(df, &configs, false)?;
+    let bands: Vec<_> = cleaned.column("age_band")?.str()?.into_iter().collect();
+    assert_eq!(
+        bands,
+        [
+            Some("child"),
+            Some("adult"),
+            Some("adult"),
+            Some("senior"),
+            None
+        ]
+    );
+
+    // Text that was never cast to a number cannot be binned
+    let text = df!("age" => ["15"])?;
+    let configs = HashMap::from([(
+        "age".to_owned(),
+        ColumnCleanConfig {
+            binning: Some(ColumnBinning {
+                strategy: BinStrategy::EqualWidth { bins: 2 },
+                labels: Vec::new(),
+            }),
+            ..Default::default()
+        },
+    )]);
+    assert!(clean_df(text, &configs, false).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_clean_encodes_categories_instead_of_one_hot() -> Result<()> {
+    let df = df!("city" => ["york", "bath", "york", "leeds"])?;
+    let configs = HashMap::from([(
+        "city".to_owned(),
+        ColumnCleanConfig {
+            ml_preprocessing: true,
+            one_hot_encode: true,
+            encoding: Some(CategoryEncoding {
+                method: EncodingMethod::Ordinal,
+                mapping: Default::default(),
+                unknown: None,
+            }),
+            ..Default::default()
+        },
+    )]);
+
+    let (lf, encodings) = clean_df_lazy_with_encodings(df.lazy(), &configs, false)?;
+    let cleaned = lf.collect()?;
+    assert_eq!(cleaned.width(), 1);
+    let codes: Vec<_> = cleaned.column("city")?.i64()?.into_iter().collect();
+    assert_eq!(codes, [Some(2), Some(0), Some(2), Some(1)]);
+    assert_eq!(encodings["city"].mapping["leeds"], 1.0);
+    Ok(())
+}
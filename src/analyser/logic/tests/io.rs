@@ -152,3 +152,326 @@ fn test_export_super_massive_columns() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_duplicate_column_policies() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("dupes.csv");
+    std::fs::write(&path, "id,amount,amount,amount_2\n1,10,20,30\n2,11,21,31\n")?;
+    assert_eq!(
+        read_csv_header(&path)?,
+        vec!["id", "amount", "amount", "amount_2"]
+    );
+
+    let (lf, mapping) = load_df_lazy_with_mapping(&path, Some(DuplicateColumnPolicy::AutoSuffix))?;
+    let df = lf.collect()?;
+    let names: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+    assert_eq!(names, vec!["id", "amount", "amount_3", "amount_2"]);
+    assert_eq!(df.column("amount_3")?.get(0)?, AnyValue::Int64(20));
+    let mapping = mapping.expect("mapping for repeated header");
+    assert_eq!(mapping.duplicates, vec!["amount"]);
+    assert_eq!(mapping.columns[2].source_name, "amount");
+    assert_eq!(mapping.columns[2].position, 2);
+    assert!(
+        mapping
+            .notice()
+            .is_some_and(|n| n.contains("'amount_3' (column 3)"))
+    );
+
+    let (lf, mapping) = load_df_lazy_with_mapping(&path, Some(DuplicateColumnPolicy::KeepLast))?;
+    let df = lf.collect()?;
+    assert_eq!(df.width(), 3);
+    assert_eq!(df.column("amount")?.get(0)?, AnyValue::Int64(20));
+    assert_eq!(mapping.map(|m| m.dropped[0].position), Some(1));
+
+    let (lf, _) = load_df_lazy_with_mapping(&path, Some(DuplicateColumnPolicy::KeepFirst))?;
+    assert_eq!(lf.collect()?.column("amount")?.get(0)?, AnyValue::Int64(10));
+
+    assert!(load_df_lazy_with_mapping(&path, Some(DuplicateColumnPolicy::Error)).is_err());
+
+    let clean = dir.path().join("clean.csv");
+    std::fs::write(&clean, "a,b\n1,2\n")?;
+    assert!(
+        load_df_lazy_with_mapping(&clean, Some(DuplicateColumnPolicy::Error))?
+            .1
+            .is_none()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_detect_and_transpose_record_oriented() -> Result<()> {
+    // Variables as rows, one column per survey respondent
+    let df = df!(
+        "question" => &["age", "region", "score"],
+        "R1" => &["34", "north", "7.5"],
+        "R2" => &["51", "south", "6"],
+        "R3" => &["27", "north", "9"],
+        "R4" => &["45", "east", "8"],
+    )?;
+
+    let detection = detect_transposed(&df).expect("should look transposed");
+    assert_eq!(detection.label_column, "question");
+    assert!(!detection.applied);
+
+    let transposed = transpose_df(&df, Some("question"), TRANSPOSE_NAMES_COLUMN)?;
+    assert_eq!(transposed.shape(), (4, 4));
+    assert_eq!(transposed.column("age")?.dtype(), &DataType::Int64);
+    assert_eq!(transposed.column("score")?.dtype(), &DataType::Float64);
+    assert_eq!(transposed.column("region")?.str()?.get(1), Some("south"));
+    assert_eq!(
+        transposed.column(TRANSPOSE_NAMES_COLUMN)?.str()?.get(3),
+        Some("R4")
+    );
+    assert!(detect_transposed(&transposed).is_none());
+
+    // An ordinary table is left alone
+    let normal = df!(
+        "name" => &["a", "b", "c", "d"],
+        "age" => &[34, 51, 27, 45],
+        "region" => &["north", "south", "north", "east"],
+    )?;
+    assert!(detect_transposed(&normal).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_workbook_sheet_paths() {
+    use std::path::{Path, PathBuf};
+
+    let book = std::env::temp_dir().join("beefcake_missing_book.xlsx");
+    let address = sheet_path(&book, "Q2 #2");
+    assert_eq!(
+        excel::workbook_source(Path::new(&address)),
+        Some((book.clone(), Some("Q2 #2".to_owned())))
+    );
+    assert_eq!(excel::workbook_source(&book), Some((book.clone(), None)));
+    assert_eq!(excel::source_file(Path::new(&address)), book);
+    assert!(excel::workbook_source(Path::new("data/notes#1.csv")).is_none());
+    assert!(excel::workbook_source(&PathBuf::from(format!("{}#", book.display()))).is_none());
+}
+
+#[test]
+fn test_sheet_cells_to_frame() -> Result<()> {
+    use calamine::{Data, Range};
+
+    let mut range = Range::new((0, 0), (3, 3));
+    for (col, name) in ["id", "price", "code", ""].into_iter().enumerate() {
+        range.set_value((0, col as u32), Data::String(name.to_owned()));
+    }
+    range.set_value((1, 0), Data::Float(1.0));
+    range.set_value((2, 0), Data::Int(2));
+    range.set_value((3, 0), Data::Float(3.0));
+    range.set_value((1, 1), Data::Float(9.99));
+    range.set_value((3, 1), Data::Int(5));
+    range.set_value((1, 2), Data::String("A1".to_owned()));
+    range.set_value((2, 2), Data::Int(7));
+    range.set_value((3, 3), Data::Bool(true));
+
+    let df = excel::range_to_df(&range)?;
+    assert_eq!(df.shape(), (3, 4));
+    assert_eq!(df.column("id")?.dtype(), &DataType::Int64);
+    assert_eq!(df.column("price")?.dtype(), &DataType::Float64);
+    assert_eq!(df.column("price")?.null_count(), 1);
+    assert_eq!(df.column("code")?.str()?.get(1), Some("7"));
+    assert_eq!(df.column("column_4")?.dtype(), &DataType::Boolean);
+    Ok(())
+}
+
+#[test]
+fn test_workbook_overview_shared_columns() {
+    let sheet = |name: &str, columns: &[&str]| SheetOverview {
+        name: name.to_owned(),
+        row_count: 10,
+        column_count: columns.len(),
+        columns: columns.iter().map(|c| (*c).to_owned()).collect(),
+    };
+    let overview = WorkbookOverview::new(vec![
+        sheet("2023", &["id", "region", "sales"]),
+        sheet("2024", &["id", "region", "sales", "returns"]),
+        sheet("Regions", &["region", "manager"]),
+    ]);
+
+    let shared: Vec<(&str, usize)> = overview
+        .shared_columns
+        .iter()
+        .map(|c| (c.name.as_str(), c.sheets.len()))
+        .collect();
+    assert_eq!(shared, [("region", 3), ("id", 2), ("sales", 2)]);
+    assert_eq!(
+        overview.shared_columns[0].sheets,
+        ["2023", "2024", "Regions"]
+    );
+}
+
+#[test]
+fn test_fill_merged_headers() {
+    let mut headers: Vec<String> = ["region", "Q1", "", "", "Q2", "", ""]
+        .iter()
+        .map(|h| (*h).to_owned())
+        .collect();
+    // The last column is empty, so it is not part of the Q2 span
+    let filled = fill_merged_headers(&mut headers, |position| position != 6);
+    assert_eq!(headers, ["region", "Q1", "Q1_2", "Q1_3", "Q2", "Q2_2", ""]);
+    assert_eq!(filled.len(), 3);
+    assert_eq!(filled[0].position, 2);
+}
+
+#[test]
+fn test_footer_rows() -> Result<()> {
+    let df = df!(
+        "region" => &[Some("North"), Some("South"), Some("Total"), None],
+        "sales" => &[Some("10"), Some("20"), Some("30"), None]
+    )?;
+    assert_eq!(footer_rows(&df)?, (2, Some("Total".to_owned())));
+
+    let mut report = LayoutReport::default();
+    let trimmed = drop_footer_rows(df, &mut report)?;
+    assert_eq!(trimmed.height(), 2);
+    // Only the totals row kept the column as text
+    assert_eq!(trimmed.column("sales")?.dtype(), &DataType::Int64);
+    assert_eq!(report.dropped_footer_rows, 2);
+    assert!(report.notices()[0].contains("'Total'"));
+
+    // A blank key only marks a footer when every other row has one
+    let keyed = df!(
+        "id" => &[Some("a"), Some("b"), None],
+        "amount" => &[1, 2, 3]
+    )?;
+    assert_eq!(footer_rows(&keyed)?.0, 1);
+    let sparse = df!(
+        "id" => &[Some("a"), None, Some("c"), None],
+        "amount" => &[1, 2, 3, 4]
+    )?;
+    assert_eq!(footer_rows(&sparse)?.0, 0);
+    Ok(())
+}
+
+#[test]
+fn test_csv_layout_fixes() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("report.csv");
+    std::fs::write(
+        &path,
+        "region,sales,,notes\nNorth,10,12,a\nSouth,20,22,b\nTotal,30,34,\n",
+    )?;
+
+    let loaded =
+        load_df_lazy_with_layout(&path, None, LayoutOptions::default(), DateOrder::default())?;
+    let df = loaded.lf.collect()?;
+    let names: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+    assert_eq!(names, ["region", "sales", "sales_2", "notes"]);
+    assert_eq!(df.height(), 2);
+    assert!(loaded.column_mapping.is_none());
+    assert_eq!(loaded.layout.filled_headers.len(), 1);
+    assert_eq!(loaded.layout.footer_label.as_deref(), Some("Total"));
+
+    let off = LayoutOptions {
+        fill_merged_headers: false,
+        drop_footer_rows: false,
+    };
+    let loaded = load_df_lazy_with_layout(&path, None, off, DateOrder::default())?;
+    assert!(loaded.layout.is_empty());
+    assert_eq!(loaded.lf.collect()?.height(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_headerless_csv() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("export.csv");
+    std::fs::write(
+        &path,
+        "1,ada@example.com,2024-01-31\n2,grace@example.com,2024-02-29\n3,alan@example.com,2024-03-31\n4,joan@example.com,2024-04-30\n5,kate@example.com,2024-05-31\n",
+    )?;
+
+    // Read with a header, the first row is lost as names
+    let names = load_df_lazy(&path)?
+        .collect_schema()?
+        .iter_names()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>();
+    assert!(header_looks_like_data(&names));
+    assert!(!header_looks_like_data(&[
+        "id".to_owned(),
+        "email".to_owned()
+    ]));
+
+    let df = load_df_lazy_as(&path, Some(&HeaderlessNaming::Auto))?.collect()?;
+    assert_eq!(df.height(), 5);
+    assert_eq!(df.get_column_names()[0].as_str(), "column_1");
+
+    std::fs::write(
+        dir.path().join("columns.txt"),
+        "id\nemail\n# when the order was placed\nordered_on\n",
+    )?;
+    let naming = HeaderlessNaming::Schema {
+        path: "columns.txt".to_owned(),
+    };
+    let df = load_df_lazy_headerless(&path, &naming)?.collect()?;
+    let names: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+    assert_eq!(names, ["id", "email", "ordered_on"]);
+
+    std::fs::write(
+        dir.path().join("short.json"),
+        r#"{"columns": [{"name": "id"}, "email"]}"#,
+    )?;
+    let short = HeaderlessNaming::Schema {
+        path: "short.json".to_owned(),
+    };
+    let err = load_df_lazy_headerless(&path, &short).unwrap_err();
+    assert!(err.to_string().contains("names 2 columns"));
+
+    let df = load_df_lazy_headerless(&path, &HeaderlessNaming::Inferred)?.collect()?;
+    let names: Vec<&str> = df.get_column_names().iter().map(|n| n.as_str()).collect();
+    assert_eq!(names, ["id", "email_address", "date"]);
+    Ok(())
+}
+
+#[test]
+fn test_clean_in_place_keeps_dialect() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("prices.csv");
+    let content = "\u{FEFF}name;price\r\nAda;1,5\r\nGrace;2,25\r\n";
+    std::fs::write(&path, content)?;
+
+    let dialect = detect_csv_dialect(&path)?;
+    assert_eq!(dialect.delimiter, b';');
+    assert_eq!(dialect.encoding, TextEncoding::Utf8Bom);
+    assert_eq!(dialect.line_ending, LineEnding::Crlf);
+    let df = load_df_lazy(&path)?.collect()?;
+    assert_eq!(df.width(), 2);
+
+    // Unchanged data is written back byte for byte
+    let report = write_in_place(&path, load_df_lazy(&path)?, &InPlaceMode::default(), true)?;
+    assert_eq!(
+        std::path::Path::new(&report.output),
+        dir.path().join("prices_cleaned.csv")
+    );
+    assert_eq!(report.rows, 2);
+    assert_eq!(std::fs::read_to_string(&report.output)?, content);
+
+    // A second copy does not overwrite the first
+    let again = write_in_place(&path, load_df_lazy(&path)?, &InPlaceMode::default(), true)?;
+    assert!(again.output.ends_with("prices_cleaned_2.csv"));
+
+    // Replacing keeps a backup of the original
+    let lf = load_df_lazy(&path)?.filter(col("name").eq(lit("Ada")));
+    let report = write_in_place(&path, lf, &InPlaceMode::Replace, true)?;
+    assert_eq!(report.output, path.display().to_string());
+    assert_eq!(
+        std::fs::read_to_string(&path)?,
+        "\u{FEFF}name;price\r\nAda;1,5\r\n"
+    );
+    let backup = report.backup.expect("a backup of the replaced file");
+    assert_eq!(std::fs::read_to_string(backup)?, content);
+
+    // Headerless files stay headerless
+    let raw = dir.path().join("raw.csv");
+    std::fs::write(&raw, "1,a\n2,b\n")?;
+    let lf = load_df_lazy_headerless(&raw, &HeaderlessNaming::Auto)?;
+    let report = write_in_place(&raw, lf, &InPlaceMode::default(), false)?;
+    assert!(!report.header);
+    assert_eq!(std::fs::read_to_string(&report.output)?, "1,a\n2,b\n");
+    Ok(())
+}
@@ -201,6 +201,8 @@ fn test_ml_advice_auto_config() {
         business_summary: vec![],
         ml_advice: vec![],
         samples: vec![],
+        semantic_type: None,
+        suggested_name: None,
     };
     summary.ml_advice = summary.generate_ml_advice();
     assert!(
@@ -233,6 +235,8 @@ fn test_ml_advice_auto_config() {
         business_summary: vec![],
         ml_advice: vec![],
         samples: vec![],
+        semantic_type: None,
+        suggested_name: None,
     };
     summary2.ml_advice = summary2.generate_ml_advice();
     assert!(
@@ -263,6 +267,8 @@ fn test_ml_advice_auto_config() {
         business_summary: vec![],
         ml_advice: vec![],
         samples: vec![],
+        semantic_type: None,
+        suggested_name: None,
     };
     summary3.ml_advice = summary3.generate_ml_advice();
     assert!(
@@ -293,6 +299,8 @@ fn test_ml_advice_auto_config() {
         business_summary: vec![],
         ml_advice: vec![],
         samples: vec![],
+        semantic_type: None,
+        suggested_name: None,
     };
     summary4.ml_advice = summary4.generate_ml_advice();
     assert!(
@@ -322,6 +330,8 @@ fn test_ml_advice_auto_config() {
         business_summary: vec![],
         ml_advice: vec![],
         samples: vec![],
+        semantic_type: None,
+        suggested_name: None,
     };
     let mut config5 = ColumnCleanConfig::default();
     summary5.apply_advice_to_config(&mut config5);
@@ -330,3 +340,280 @@ fn test_ml_advice_auto_config() {
         "Remove special chars should be auto-enabled when special chars are detected"
     );
 }
+
+#[test]
+fn test_feature_importance_classification() -> Result<()> {
+    // `signal` determines the class; `noise` is unrelated
+    let signal: Vec<f64> = (0..200).map(f64::from).collect();
+    let noise: Vec<f64> = (0..200).map(|i| f64::from((i * 37) % 11)).collect();
+    let label: Vec<&str> = (0..200)
+        .map(|i| if i < 100 { "low" } else { "high" })
+        .collect();
+    let df = DataFrame::new(vec![
+        Column::new("signal".into(), signal),
+        Column::new("noise".into(), noise),
+        Column::new("comment".into(), vec!["x"; 200]),
+        Column::new("label".into(), label),
+    ])?;
+
+    let report = ml::feature_importance(&df, "label")?;
+
+    assert_eq!(report.task, MlTask::Classification);
+    assert_eq!(report.model_kind, MlModelKind::DecisionTree);
+    assert_eq!(report.skipped_columns, vec!["comment".to_owned()]);
+    assert_eq!(report.features.len(), 2);
+    assert_eq!(report.features[0].feature, "signal");
+    assert_eq!(report.features[0].rank, 1);
+    assert!(report.features[0].mutual_information > report.features[1].mutual_information);
+    assert!(report.features[0].model_importance.unwrap() > 0.9);
+    assert!(!report.interpretation.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_feature_importance_regression() -> Result<()> {
+    let x: Vec<f64> = (0..200).map(f64::from).collect();
+    let noise: Vec<f64> = (0..200).map(|i| f64::from((i * 37) % 11)).collect();
+    let y: Vec<f64> = x.iter().map(|v| 3.0 * v + 0.5).collect();
+    let df = DataFrame::new(vec![
+        Column::new("x".into(), x),
+        Column::new("noise".into(), noise),
+        Column::new("y".into(), y),
+    ])?;
+
+    let report = ml::feature_importance(&df, "y")?;
+
+    assert_eq!(report.task, MlTask::Regression);
+    assert_eq!(report.model_kind, MlModelKind::LinearRegression);
+    assert_eq!(report.features[0].feature, "x");
+    let total: f64 = report
+        .features
+        .iter()
+        .filter_map(|f| f.model_importance)
+        .sum();
+    assert!((total - 1.0).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[test]
+fn test_feature_importance_missing_target() {
+    let df = DataFrame::new(vec![Column::new("x".into(), vec![1.0, 2.0])]).unwrap();
+    assert!(ml::feature_importance(&df, "missing").is_err());
+}
+
+#[test]
+fn test_ml_training_random_forest() -> Result<()> {
+    // Interleave classes so both appear in the train and test splits
+    let x: Vec<f64> = (0..100)
+        .map(|i| f64::from(i % 2) * 10.0 + f64::from(i % 7))
+        .collect();
+    let y: Vec<u32> = (0..100).map(|i| i % 2).collect();
+    let df = DataFrame::new(vec![Column::new("x".into(), x), Column::new("y".into(), y)])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    let results = ml::train_model(&df, "y", MlModelKind::RandomForest, &progress)?;
+
+    assert!(results.accuracy.unwrap() > 0.9);
+    let importances = results.feature_importances.unwrap();
+    assert!((importances["x"] - 1.0).abs() < 1e-9);
+    assert!(!results.interpretation.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_ml_training_kmeans() -> Result<()> {
+    let mut x = vec![0.0, 0.1, 0.2, 0.1];
+    x.extend([10.0, 10.1, 10.2, 10.1]);
+    let df = DataFrame::new(vec![Column::new("x".into(), x)])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    let mut params = MlHyperparameters::default();
+    params.kmeans.n_clusters = 2;
+    let results = ml::train_model_with_params(&df, "", MlModelKind::KMeans, &params, &progress)?;
+
+    let mut sizes = results.cluster_sizes.unwrap();
+    sizes.sort_unstable();
+    assert_eq!(sizes, vec![4, 4]);
+    assert!(results.inertia.unwrap() < 1.0);
+
+    // Too many clusters for the data
+    params.kmeans.n_clusters = 20;
+    assert!(ml::train_model_with_params(&df, "", MlModelKind::KMeans, &params, &progress).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_ml_training_ridge_and_lasso() -> Result<()> {
+    let x: Vec<f64> = (0..50).map(f64::from).collect();
+    let noise: Vec<f64> = (0..50).map(|i| f64::from((i * 37) % 5)).collect();
+    let y: Vec<f64> = x.iter().map(|v| 2.0 * v + 1.0).collect();
+    let df = DataFrame::new(vec![
+        Column::new("x".into(), x),
+        Column::new("noise".into(), noise),
+        Column::new("y".into(), y),
+    ])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    let mut params = MlHyperparameters::default();
+    params.regularisation.alpha = 0.1;
+
+    let ridge = ml::train_model_with_params(&df, "y", MlModelKind::Ridge, &params, &progress)?;
+    assert!(ridge.r2_score.unwrap() > 0.9);
+    assert!(ridge.coefficients.unwrap()["x"] > 1.5);
+
+    let lasso = ml::train_model_with_params(&df, "y", MlModelKind::Lasso, &params, &progress)?;
+    assert!(lasso.r2_score.unwrap() > 0.9);
+    let coeffs = lasso.coefficients.unwrap();
+    assert!(coeffs["noise"].abs() < coeffs["x"].abs());
+
+    Ok(())
+}
+
+#[test]
+fn test_ml_training_split_and_cross_validation() -> Result<()> {
+    let x: Vec<f64> = (0..100).map(f64::from).collect();
+    let y: Vec<f64> = x.iter().map(|v| 2.0 * v + 1.0).collect();
+    let df = DataFrame::new(vec![Column::new("x".into(), x), Column::new("y".into(), y)])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    let mut params = MlHyperparameters::default();
+    params.validation.test_ratio = 0.25;
+    params.validation.cv_folds = Some(5);
+    let results =
+        ml::train_model_with_params(&df, "y", MlModelKind::LinearRegression, &params, &progress)?;
+
+    assert_eq!(results.train_rows, 75);
+    assert_eq!(results.test_rows, 25);
+    assert!(results.train_r2_score.unwrap() > 0.99);
+    assert!(results.r2_score.unwrap() > 0.99);
+
+    let cv = results.cross_validation.unwrap();
+    assert_eq!(cv.folds, 5);
+    assert_eq!(cv.metric, "r2");
+    assert_eq!(cv.scores.len(), 5);
+    assert!(cv.mean > 0.99);
+
+    // Same seed, same split
+    params.validation.shuffle = true;
+    let results =
+        ml::train_model_with_params(&df, "y", MlModelKind::LinearRegression, &params, &progress)?;
+    let again =
+        ml::train_model_with_params(&df, "y", MlModelKind::LinearRegression, &params, &progress)?;
+    assert_eq!(again.r2_score, results.r2_score);
+
+    params.validation.cv_folds = Some(1);
+    assert!(
+        ml::train_model_with_params(&df, "y", MlModelKind::LinearRegression, &params, &progress)
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_ml_training_default_split_holds_out_last_rows() -> Result<()> {
+    // The last two rows break the line, so only a split that trains on the
+    // first eight fits them exactly and scores badly on the held-out rows
+    let x: Vec<f64> = (0..10).map(f64::from).collect();
+    let mut y: Vec<f64> = x.iter().map(|v| 2.0 * v + 1.0).collect();
+    y[8] = -50.0;
+    y[9] = 60.0;
+    let df = DataFrame::new(vec![Column::new("x".into(), x), Column::new("y".into(), y)])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    assert!(!ValidationConfig::default().shuffle);
+    let results = ml::train_model(&df, "y", MlModelKind::LinearRegression, &progress)?;
+
+    assert_eq!(results.train_rows, 8);
+    assert_eq!(results.test_rows, 2);
+    assert!(results.train_r2_score.unwrap() > 0.999);
+    assert!(results.r2_score.unwrap() < 0.5);
+
+    Ok(())
+}
+
+#[test]
+fn test_text_features() -> Result<()> {
+    assert_eq!(
+        ml::tokenise("Fast delivery, A+ service!"),
+        ["fast", "delivery", "service"]
+    );
+
+    let df = df!(
+        "review" => [Some("late delivery"), Some("late again, very late"), Some(""), None],
+    )?;
+    let tfidf = TextVectoriser {
+        method: TextVectorMethod::TfIdf,
+        features: 2,
+    };
+    let features = ml::text_features(&df, "review", &tfidf)?;
+    let names: Vec<&str> = features
+        .get_column_names()
+        .iter()
+        .map(|n| n.as_str())
+        .collect();
+    assert_eq!(names, ["review_tfidf_late", "review_tfidf_again"]);
+
+    // Rows have unit length, empty text is all zeros
+    let late = features.column("review_tfidf_late")?.f64()?;
+    let again = features.column("review_tfidf_again")?.f64()?;
+    let length = |i: usize| late.get(i).unwrap().hypot(again.get(i).unwrap());
+    assert!((length(0) - 1.0).abs() < 1e-9);
+    assert!((length(1) - 1.0).abs() < 1e-9);
+    assert!(length(2).abs() < 1e-9 && length(3).abs() < 1e-9);
+
+    let hashing = TextVectoriser {
+        method: TextVectorMethod::Hashing,
+        features: 4,
+    };
+    let hashed = ml::text_features(&df, "review", &hashing)?;
+    assert_eq!(hashed.width(), 4);
+    assert!(hashed.equals(&ml::text_features(&df, "review", &hashing)?));
+
+    let numbers = df!("n" => [1, 2])?;
+    assert!(ml::text_features(&numbers, "n", &hashing).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_ml_training_with_text_features() -> Result<()> {
+    let reviews: Vec<&str> = (0..40)
+        .map(|i| {
+            if i % 2 == 0 {
+                "good product"
+            } else {
+                "bad product"
+            }
+        })
+        .collect();
+    let labels: Vec<i32> = (0..40).map(|i| i32::from(i % 2 == 0)).collect();
+    let df = DataFrame::new(vec![
+        Column::new("review".into(), reviews),
+        Column::new("liked".into(), labels),
+    ])?;
+    let progress = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+
+    // Without vectorisation the text column is dropped and nothing is left
+    let mut params = MlHyperparameters::default();
+    assert!(
+        ml::train_model_with_params(&df, "liked", MlModelKind::DecisionTree, &params, &progress)
+            .is_err()
+    );
+
+    params.text_features = Some(TextVectoriser::default());
+    let results =
+        ml::train_model_with_params(&df, "liked", MlModelKind::DecisionTree, &params, &progress)?;
+    assert!(
+        results
+            .feature_columns
+            .contains(&"review_tfidf_good".to_owned())
+    );
+    assert!(results.accuracy.unwrap() > 0.99);
+
+    Ok(())
+}
@@ -1,11 +1,101 @@
+use super::date_format::DateOrder;
+use crate::utils::memory::MemoryBudget;
 use polars::prelude::DataFrame;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct CorrelationMatrix {
     pub columns: Vec<String>,
     pub data: Vec<Vec<f64>>,
+    /// Numeric columns left out of the matrix because of the column limit.
+    #[serde(default)]
+    pub omitted_columns: Vec<String>,
+}
+
+/// User-tunable options that control how a file is analysed.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct AnalysisOptions {
+    pub correlation: CorrelationOptions,
+    pub histogram: HistogramOptions,
+    /// Memory the analysis may use before it streams and shrinks its samples.
+    /// Saved separately in the app settings, so not stored with these options.
+    #[serde(skip)]
+    pub memory_budget: MemoryBudget,
+    /// How ambiguous text dates are read when inferring their format. Also
+    /// an app setting of its own.
+    #[serde(skip)]
+    pub date_order: DateOrder,
+}
+
+/// How numeric histogram bins are chosen.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum HistogramStrategy {
+    /// Freedman–Diaconis width clamped to 5–50 bins (the historical default).
+    #[default]
+    Auto,
+    /// A fixed number of equal-width bins (`HistogramOptions::bin_count`).
+    FixedBins,
+    /// Unclamped Freedman–Diaconis rule: width = 2 × IQR / n^(1/3).
+    FreedmanDiaconis,
+    /// Sturges' rule: ⌈log2(n)⌉ + 1 bins.
+    Sturges,
+    /// Bins equally spaced in log10 space, for heavy-tailed positive data.
+    /// Falls back to `Auto` when the column contains zero or negative values.
+    LogScale,
+}
+
+impl HistogramStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "Auto",
+            Self::FixedBins => "Fixed bins",
+            Self::FreedmanDiaconis => "Freedman–Diaconis",
+            Self::Sturges => "Sturges",
+            Self::LogScale => "Log scale",
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct HistogramOptions {
+    pub strategy: HistogramStrategy,
+    /// Number of bins for `FixedBins` and `LogScale` (default 20, max 500).
+    pub bin_count: Option<usize>,
+}
+
+/// Controls which numeric columns are included in the correlation matrix.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct CorrelationOptions {
+    /// Maximum number of columns to correlate. `None` uses an adaptive limit
+    /// based on how many numeric columns the dataset has.
+    pub max_columns: Option<usize>,
+    /// Explicit columns to correlate. When empty, numeric columns are picked
+    /// automatically.
+    pub columns: Vec<String>,
+    /// When more numeric columns exist than the limit allows, keep the ones
+    /// with the highest variance instead of the first N in schema order.
+    pub select_by_variance: bool,
+}
+
+impl CorrelationOptions {
+    /// Adaptive column limit used when `max_columns` is not set.
+    /// Fewer columns are kept for wide datasets to control memory.
+    pub fn effective_max_columns(&self, numeric_col_count: usize) -> usize {
+        if let Some(max) = self.max_columns {
+            return max.max(2);
+        }
+        if numeric_col_count > 100 {
+            15 // Very wide: limit to 15 cols
+        } else if numeric_col_count > 50 {
+            20 // Wide: limit to 20 cols
+        } else {
+            30 // Normal: limit to 30 cols
+        }
+    }
 }
 
 #[derive(Serialize, Debug)]
@@ -23,6 +113,22 @@ pub struct AnalysisResponse {
     #[serde(skip)]
     pub df: DataFrame,
     pub correlation_matrix: Option<CorrelationMatrix>,
+    /// Which file column each name came from, when the header repeats names
+    pub column_mapping: Option<super::io::ColumnMapping>,
+    /// Set when the file looks like it holds variables as rows
+    #[serde(default)]
+    pub orientation: Option<super::orientation::TransposeDetection>,
+    /// Merged header cells named and footer rows dropped while loading
+    #[serde(default)]
+    pub layout: Option<super::layout::LayoutReport>,
+    /// Set when the header of a CSV file looks like a row of data, so the
+    /// file may have no header row
+    #[serde(default)]
+    pub header_looks_like_data: bool,
+    /// How the columns were named when the CSV file was read without a
+    /// header row; loading the file again needs the same naming
+    #[serde(default)]
+    pub header_names: Option<super::headerless::HeaderlessNaming>,
 }
 
 mod duration_serde {
@@ -54,6 +160,13 @@ pub struct ColumnSummary {
     pub business_summary: Vec<String>,
     pub ml_advice: Vec<String>,
     pub samples: Vec<String>,
+    /// What the values are beyond their type, e.g. email addresses
+    #[serde(default)]
+    pub semantic_type: Option<SemanticType>,
+    /// A name from the content, for columns with placeholder headers such
+    /// as `Column1`
+    #[serde(default)]
+    pub suggested_name: Option<String>,
 }
 
 impl ColumnSummary {
@@ -65,6 +178,14 @@ impl ColumnSummary {
         }
     }
 
+    /// Concentration metrics for categorical columns with a frequency map.
+    pub fn categorical_metrics(&self) -> Option<CategoricalMetrics> {
+        match &self.stats {
+            ColumnStats::Categorical(freq) => CategoricalMetrics::from_frequencies(freq),
+            _ => None,
+        }
+    }
+
     pub fn uniqueness_ratio(&self) -> f64 {
         if self.count == 0 {
             0.0
@@ -131,6 +252,39 @@ impl ColumnSummary {
             config.remove_non_ascii = true;
         }
 
+        match self.semantic_type {
+            Some(SemanticType::Email) => config.text_case = TextCase::Lowercase,
+            Some(
+                SemanticType::CountryCode | SemanticType::CurrencyCode | SemanticType::Postcode,
+            ) => {
+                config.text_case = TextCase::Uppercase;
+            }
+            _ => {}
+        }
+        // The '@', '.', ':' and currency symbols are part of these values
+        if matches!(
+            self.semantic_type,
+            Some(
+                SemanticType::Email
+                    | SemanticType::CurrencyAmount
+                    | SemanticType::Postcode
+                    | SemanticType::IpAddress
+            )
+        ) {
+            config.remove_special_chars = false;
+            config.remove_non_ascii = false;
+        }
+
+        // Text dates are parsed with the format they were read with
+        if let ColumnStats::Temporal(TemporalStats {
+            format: Some(format),
+            ..
+        }) = &self.stats
+        {
+            config.target_dtype = Some(ColumnKind::Temporal);
+            config.temporal_format = format.clone();
+        }
+
         for advice in &self.ml_advice {
             if advice.contains("Outlier Clipping") {
                 config.clip_outliers = true;
@@ -158,7 +312,8 @@ impl ColumnCleanConfig {
                 || self.remove_non_ascii
                 || self.standardise_nulls
                 || self.text_case != TextCase::None
-                || !self.regex_find.is_empty())
+                || !self.regex_find.is_empty()
+                || !self.value_mapping.is_empty())
     }
 }
 
@@ -186,6 +341,13 @@ pub enum MlModelKind {
     LinearRegression,
     DecisionTree,
     LogisticRegression,
+    RandomForest,
+    /// Unsupervised clustering; does not use a target column.
+    KMeans,
+    /// Linear regression with an L2 penalty.
+    Ridge,
+    /// Linear regression with an L1 penalty.
+    Lasso,
 }
 
 impl MlModelKind {
@@ -194,15 +356,153 @@ impl MlModelKind {
             Self::LinearRegression => "Linear Regression",
             Self::DecisionTree => "Decision Tree",
             Self::LogisticRegression => "Logistic Regression",
+            Self::RandomForest => "Random Forest",
+            Self::KMeans => "K-Means Clustering",
+            Self::Ridge => "Ridge Regression",
+            Self::Lasso => "Lasso Regression",
         }
     }
 
+    pub fn requires_target(&self) -> bool {
+        !matches!(self, Self::KMeans)
+    }
+
     pub fn is_suitable_target(&self, kind: ColumnKind) -> bool {
         match self {
-            Self::LinearRegression => kind == ColumnKind::Numeric,
-            Self::DecisionTree | Self::LogisticRegression => {
+            Self::LinearRegression | Self::Ridge | Self::Lasso => kind == ColumnKind::Numeric,
+            Self::DecisionTree | Self::LogisticRegression | Self::RandomForest => {
                 matches!(kind, ColumnKind::Boolean | ColumnKind::Categorical)
             }
+            Self::KMeans => false,
+        }
+    }
+}
+
+/// Hyperparameters for the models that take them. Models ignore the
+/// sections that do not apply to them.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct MlHyperparameters {
+    pub random_forest: RandomForestConfig,
+    pub kmeans: KMeansConfig,
+    pub regularisation: RegularisationConfig,
+    pub validation: ValidationConfig,
+    /// Turn free-text columns into numeric features; `None` drops them.
+    pub text_features: Option<TextVectoriser>,
+}
+
+/// How free-text columns are turned into numeric features.
+#[derive(Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TextVectorMethod {
+    /// Hash tokens into a fixed number of buckets.
+    Hashing,
+    /// TF-IDF weights of the terms found in the most rows.
+    #[default]
+    #[serde(rename = "tfidf")]
+    TfIdf,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+#[serde(default)]
+pub struct TextVectoriser {
+    pub method: TextVectorMethod,
+    /// Number of hash buckets, or of terms kept for TF-IDF.
+    pub features: usize,
+}
+
+impl Default for TextVectoriser {
+    fn default() -> Self {
+        Self {
+            method: TextVectorMethod::default(),
+            features: 20,
+        }
+    }
+}
+
+/// How supervised models are evaluated.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// Fraction of rows held out for testing.
+    pub test_ratio: f64,
+    /// Shuffle rows before splitting. Off by default, so the first rows train
+    /// and the last are held out, as before splits were configurable.
+    pub shuffle: bool,
+    /// Seed for the shuffle.
+    pub seed: u64,
+    /// Number of cross-validation folds; `None` skips cross-validation.
+    pub cv_folds: Option<usize>,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            test_ratio: 0.2,
+            shuffle: false,
+            seed: 42,
+            cv_folds: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct RandomForestConfig {
+    pub n_trees: usize,
+    /// Maximum depth of each tree; `None` grows trees until leaves are pure.
+    pub max_depth: Option<usize>,
+    /// Fraction of features considered by each tree; `None` uses √n.
+    pub feature_ratio: Option<f64>,
+    /// Seed for bootstrap and feature sampling.
+    pub seed: u64,
+}
+
+impl Default for RandomForestConfig {
+    fn default() -> Self {
+        Self {
+            n_trees: 50,
+            max_depth: None,
+            feature_ratio: None,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct KMeansConfig {
+    pub n_clusters: usize,
+    pub max_iterations: u64,
+    pub tolerance: f64,
+}
+
+impl Default for KMeansConfig {
+    fn default() -> Self {
+        Self {
+            n_clusters: 3,
+            max_iterations: 300,
+            tolerance: 1e-4,
+        }
+    }
+}
+
+/// Penalty settings shared by ridge and lasso regression.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(default)]
+pub struct RegularisationConfig {
+    /// Penalty strength; larger values shrink coefficients more.
+    pub alpha: f64,
+    pub max_iterations: u32,
+    pub tolerance: f64,
+}
+
+impl Default for RegularisationConfig {
+    fn default() -> Self {
+        Self {
+            alpha: 1.0,
+            max_iterations: 1000,
+            tolerance: 1e-4,
         }
     }
 }
@@ -212,13 +512,81 @@ pub struct MlResults {
     pub model_kind: MlModelKind,
     pub target_column: String,
     pub feature_columns: Vec<String>,
+    /// Held-out (test split) metrics.
     pub r2_score: Option<f64>,
     pub accuracy: Option<f64>,
     pub mse: Option<f64>,
+    /// In-sample (training split) metrics, for spotting overfitting.
+    #[serde(default)]
+    pub train_r2_score: Option<f64>,
+    #[serde(default)]
+    pub train_accuracy: Option<f64>,
+    #[serde(default)]
+    pub train_mse: Option<f64>,
+    #[serde(default)]
+    pub train_rows: usize,
+    #[serde(default)]
+    pub test_rows: usize,
+    #[serde(default)]
+    pub cross_validation: Option<CrossValidationResults>,
     pub duration: std::time::Duration,
     pub coefficients: Option<HashMap<String, f64>>,
     pub intercept: Option<f64>,
     pub interpretation: Vec<String>,
+    /// Mean impurity-based importance per feature (random forest).
+    #[serde(default)]
+    pub feature_importances: Option<HashMap<String, f64>>,
+    /// Within-cluster sum of squared distances (k-means).
+    #[serde(default)]
+    pub inertia: Option<f64>,
+    /// Number of rows assigned to each cluster (k-means).
+    #[serde(default)]
+    pub cluster_sizes: Option<Vec<usize>>,
+}
+
+/// Per-fold scores from k-fold cross-validation.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct CrossValidationResults {
+    pub folds: usize,
+    /// Name of the scored metric (`accuracy` or `r2`).
+    pub metric: String,
+    pub scores: Vec<f64>,
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+/// Whether a target column is predicted as classes or as a continuous value.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum MlTask {
+    Classification,
+    Regression,
+}
+
+/// How strongly a single feature relates to the target column.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FeatureImportance {
+    pub feature: String,
+    /// 1-based rank, ordered by mutual information then model importance.
+    pub rank: usize,
+    /// Mutual information with the target, in nats.
+    pub mutual_information: f64,
+    /// Importance from the fitted model, normalised to sum to 1 across
+    /// features. `None` when the model could not be fitted.
+    pub model_importance: Option<f64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct FeatureImportanceReport {
+    pub target_column: String,
+    pub task: MlTask,
+    /// Model used to derive `model_importance`.
+    pub model_kind: MlModelKind,
+    pub features: Vec<FeatureImportance>,
+    /// Columns that could not be scored (non-numeric or entirely null).
+    pub skipped_columns: Vec<String>,
+    pub row_count: usize,
+    pub duration: std::time::Duration,
+    pub interpretation: Vec<String>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
@@ -230,7 +598,7 @@ pub enum TextCase {
     TitleCase,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct ColumnCleanConfig {
     pub new_name: String,
     pub target_dtype: Option<ColumnKind>,
@@ -253,6 +621,162 @@ pub struct ColumnCleanConfig {
     pub normalisation: NormalisationMethod,
     pub one_hot_encode: bool,
     pub impute_mode: ImputeMode,
+    /// Whole values replaced after the other text options, e.g. the
+    /// spellings of a value clustered by [`super::standardise`]
+    #[serde(default)]
+    pub value_mapping: BTreeMap<String, String>,
+    /// Split the cleaned value into new columns
+    #[serde(default)]
+    pub split: Option<ColumnSplit>,
+    /// Add a column combining this and other columns
+    #[serde(default)]
+    pub merge: Option<ColumnMerge>,
+    /// Calendar features added as `<column>_<part>` columns, e.g.
+    /// `order_date_month`
+    #[serde(default)]
+    pub date_parts: Vec<DatePart>,
+    /// Bucket the cleaned values, e.g. ages into age bands
+    #[serde(default)]
+    pub binning: Option<ColumnBinning>,
+    /// Replace the values with one number each, for columns with too many
+    /// values to one-hot encode. Applied instead of one-hot encoding.
+    #[serde(default)]
+    pub encoding: Option<CategoryEncoding>,
+}
+
+/// Split a column on a delimiter into new columns, one per part. Parts are
+/// trimmed; parts past the last name are dropped and missing parts are null.
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ColumnSplit {
+    /// Text the value is split on, or a regex if `regex` is set
+    pub delimiter: String,
+    #[serde(default)]
+    pub regex: bool,
+    /// Names of the new columns
+    pub into: Vec<String>,
+    /// Remove the split column from the output
+    #[serde(default)]
+    pub drop_original: bool,
+}
+
+/// Combine columns into a new column with a template
+#[derive(Clone, Default, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct ColumnMerge {
+    /// Text with `{column}` placeholders, e.g. `"{first} {last}"`; `{{`
+    /// and `}}` stand for literal braces. Null values count as empty, and
+    /// the result is trimmed.
+    pub template: String,
+    /// Name of the new column
+    pub into: String,
+    /// Remove the columns the template uses from the output
+    #[serde(default)]
+    pub drop_sources: bool,
+}
+
+/// Replace a numeric column with the label of the bin each value falls in
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ColumnBinning {
+    pub strategy: BinStrategy,
+    /// One label per bin; empty labels bins by their edges, e.g. `18 to 30`
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// Where the edges of the bins go
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BinStrategy {
+    /// `bins` bins of equal width from the minimum to the maximum
+    EqualWidth { bins: usize },
+    /// `bins` bins holding about the same number of rows
+    Quantile { bins: usize },
+    /// Bins between consecutive `edges`, which must increase
+    Custom { edges: Vec<f64> },
+}
+
+impl BinStrategy {
+    /// Number of bins the strategy makes
+    pub fn bins(&self) -> usize {
+        match self {
+            Self::EqualWidth { bins } | Self::Quantile { bins } => *bins,
+            Self::Custom { edges } => edges.len().saturating_sub(1),
+        }
+    }
+}
+
+/// Replace each value of a categorical column with a number, see
+/// [`super::encoding`]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CategoryEncoding {
+    pub method: EncodingMethod,
+    /// Number of each value, learned from the data the encoding was first
+    /// applied to. While empty, it is learned again on every run.
+    #[serde(default)]
+    pub mapping: BTreeMap<String, f64>,
+    /// Number of values missing from `mapping`, and of nulls; ordinal
+    /// encoding uses -1 and target encoding the target's mean by default
+    #[serde(default)]
+    pub unknown: Option<f64>,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EncodingMethod {
+    /// Position of the value in sorted order, from 0
+    Ordinal,
+    /// Mean of the numeric `target` over the rows with the value, pulled
+    /// towards the overall mean as if `smoothing` more rows had it. While
+    /// learning, rows are split into `folds` and encoded from the others.
+    Target {
+        target: String,
+        smoothing: f64,
+        folds: u32,
+    },
+}
+
+/// A calendar feature of a date or time value
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DatePart {
+    Year,
+    /// 1 to 4
+    Quarter,
+    /// 1 to 12
+    Month,
+    /// ISO week of the year, 1 to 53
+    Week,
+    /// Day of the month
+    Day,
+    /// ISO weekday, 1 (Monday) to 7 (Sunday)
+    Weekday,
+    /// 1 to 366
+    DayOfYear,
+    Hour,
+    Minute,
+    /// Saturday or Sunday
+    IsWeekend,
+}
+
+impl DatePart {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Year => "year",
+            Self::Quarter => "quarter",
+            Self::Month => "month",
+            Self::Week => "week",
+            Self::Day => "day",
+            Self::Weekday => "weekday",
+            Self::DayOfYear => "day_of_year",
+            Self::Hour => "hour",
+            Self::Minute => "minute",
+            Self::IsWeekend => "is_weekend",
+        }
+    }
+
+    /// Whether the part needs a time of day, so a plain date cannot give it
+    pub fn needs_time(self) -> bool {
+        matches!(self, Self::Hour | Self::Minute)
+    }
 }
 
 impl Default for ColumnCleanConfig {
@@ -279,6 +803,12 @@ impl Default for ColumnCleanConfig {
             normalisation: NormalisationMethod::None,
             one_hot_encode: false,
             impute_mode: ImputeMode::None,
+            value_mapping: BTreeMap::new(),
+            split: None,
+            merge: None,
+            date_parts: Vec::new(),
+            binning: None,
+            encoding: None,
         }
     }
 }
@@ -290,6 +820,8 @@ pub enum ColumnStats {
     Categorical(HashMap<String, usize>),
     Temporal(TemporalStats),
     Boolean(BooleanStats),
+    /// Coordinates or geometries, see [`super::geo`]
+    Geo(GeoStats),
 }
 
 impl ColumnStats {
@@ -299,6 +831,7 @@ impl ColumnStats {
             Self::Temporal(s) => s.distinct_count,
             Self::Text(s) => s.distinct,
             Self::Categorical(freq) => freq.len(),
+            Self::Geo(s) => s.distinct_count,
             Self::Boolean(s) => {
                 let mut count = 0;
                 if s.true_count > 0 {
@@ -313,10 +846,103 @@ impl ColumnStats {
     }
 }
 
+/// Derived concentration metrics for a categorical frequency map.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct CategoricalMetrics {
+    pub mode: String,
+    /// Fraction of (non-null) values taken by the most frequent category.
+    pub modal_share: f64,
+    /// Shannon entropy in bits.
+    pub entropy: f64,
+    /// Entropy divided by its maximum (log2 of the category count), 0..=1.
+    pub normalised_entropy: f64,
+    /// Herfindahl–Hirschman index: sum of squared category shares, 1/k..=1.
+    pub hhi: f64,
+    /// Gini impurity (1 - HHI): chance two random records differ.
+    pub gini: f64,
+    /// The modal category covers at least
+    /// [`EFFECTIVELY_CONSTANT_SHARE`](super::interpretation::EFFECTIVELY_CONSTANT_SHARE)
+    /// of values.
+    pub effectively_constant: bool,
+}
+
+impl CategoricalMetrics {
+    /// Compute metrics from category counts. Returns `None` when the map is
+    /// empty (e.g. high-cardinality columns where counts were not tracked).
+    pub fn from_frequencies(freq: &HashMap<String, usize>) -> Option<Self> {
+        let counts: Vec<(&String, usize)> = freq
+            .iter()
+            .filter(|(k, _)| k.as_str() != "__TRUNCATED__")
+            .map(|(k, v)| (k, *v))
+            .collect();
+        let total: usize = counts.iter().map(|(_, c)| c).sum();
+        if total == 0 {
+            return None;
+        }
+
+        // Ties are broken by name so the mode is deterministic
+        let (mode, modal_count) = counts
+            .iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+            .map(|(k, c)| ((*k).clone(), *c))?;
+
+        let total = total as f64;
+        let shares = counts.iter().map(|(_, c)| *c as f64 / total);
+        let entropy: f64 = shares
+            .clone()
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum();
+        let hhi: f64 = shares.map(|p| p * p).sum();
+        let normalised_entropy = if counts.len() > 1 {
+            entropy / (counts.len() as f64).log2()
+        } else {
+            0.0
+        };
+        let modal_share = modal_count as f64 / total;
+
+        Some(Self {
+            mode,
+            modal_share,
+            entropy,
+            normalised_entropy,
+            hhi,
+            gini: 1.0 - hhi,
+            effectively_constant: modal_share >= super::interpretation::EFFECTIVELY_CONSTANT_SHARE,
+        })
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
 pub struct BooleanStats {
     pub true_count: usize,
     pub false_count: usize,
+    /// Nulls are a distinct third state, not an implicit `false`.
+    #[serde(default)]
+    pub null_count: usize,
+}
+
+impl BooleanStats {
+    pub fn non_null(&self) -> usize {
+        self.true_count + self.false_count
+    }
+
+    /// Share of non-null values that are `true`.
+    pub fn positive_rate(&self) -> Option<f64> {
+        let n = self.non_null();
+        (n > 0).then(|| self.true_count as f64 / n as f64)
+    }
+
+    /// Share of all values (including nulls) that are null.
+    pub fn null_rate(&self) -> Option<f64> {
+        let n = self.non_null() + self.null_count;
+        (n > 0).then(|| self.null_count as f64 / n as f64)
+    }
+
+    /// Share of non-null values taken by the rarer of `true` and `false`.
+    pub fn minority_share(&self) -> Option<f64> {
+        self.positive_rate().map(|p| p.min(1.0 - p))
+    }
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
@@ -330,6 +956,30 @@ pub struct TemporalStats {
     pub is_sorted_rev: bool,
     pub bin_width: f64,
     pub histogram: Vec<(f64, usize)>, // timestamp (ms) and count
+    /// Records per day of week, Monday first.
+    #[serde(default)]
+    pub weekday_counts: Vec<usize>,
+    /// Records per hour of day (UTC). Empty for date-only columns.
+    #[serde(default)]
+    pub hour_counts: Vec<usize>,
+    /// Timestamps later than the time of analysis.
+    #[serde(default)]
+    pub future_count: usize,
+    /// Timestamps before [`PLAUSIBLE_DATE_FLOOR_MS`](super::profiling::PLAUSIBLE_DATE_FLOOR_MS).
+    #[serde(default)]
+    pub implausible_count: usize,
+    /// Smallest and largest gap (ms) between consecutive distinct timestamps.
+    #[serde(default)]
+    pub min_gap_ms: Option<f64>,
+    #[serde(default)]
+    pub max_gap_ms: Option<f64>,
+    /// strptime format the values were read with, when they are text; see
+    /// [`super::date_format`]
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Whether the text values read as well day first as month first
+    #[serde(default)]
+    pub format_ambiguous: bool,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
@@ -353,6 +1003,73 @@ pub struct NumericStats {
     pub is_sorted_rev: bool,
     pub bin_width: f64,
     pub histogram: Vec<(f64, usize)>, // bin centre and count
+    /// Strategy that produced `histogram`. For `LogScale`, `bin_width` is the
+    /// bin step in log10 units and each bin start is given in the original scale.
+    #[serde(default)]
+    pub histogram_strategy: HistogramStrategy,
+    /// Log10-binned view, present for skewed or long-tailed positive columns.
+    #[serde(default)]
+    pub log_histogram: Option<HistogramVariant>,
+    /// View restricted to the P05–P95 range, present for skewed or long-tailed
+    /// columns.
+    #[serde(default)]
+    pub zoomed_histogram: Option<HistogramVariant>,
+    /// Percentiles P1..P99 as `(percentile, value)`.
+    #[serde(default)]
+    pub quantiles: Vec<(u8, f64)>,
+    /// Empirical CDF at evenly spaced values between min and max, as
+    /// `(value, fraction of values <= value)`.
+    #[serde(default)]
+    pub ecdf: Vec<(f64, f64)>,
+}
+
+impl NumericStats {
+    /// Value at percentile `p` (1..=99) from the quantile table.
+    pub fn percentile(&self, p: u8) -> Option<f64> {
+        self.quantiles
+            .iter()
+            .find(|(pct, _)| *pct == p)
+            .map(|(_, v)| *v)
+    }
+
+    /// Estimated fraction of values at or below `x`, interpolated from the
+    /// ECDF sample.
+    pub fn fraction_at_or_below(&self, x: f64) -> Option<f64> {
+        let (first, last) = (self.ecdf.first()?, self.ecdf.last()?);
+        if x < first.0 {
+            return Some(0.0);
+        }
+        if x >= last.0 {
+            return Some(1.0);
+        }
+        let idx = self.ecdf.partition_point(|(v, _)| *v <= x);
+        let (x0, y0) = self.ecdf.get(idx.checked_sub(1)?)?;
+        let (x1, y1) = self.ecdf.get(idx)?;
+        if x1 > x0 {
+            Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+        } else {
+            Some(*y0)
+        }
+    }
+
+    /// Estimated fraction of values strictly above `x`.
+    pub fn fraction_above(&self, x: f64) -> Option<f64> {
+        self.fraction_at_or_below(x).map(|f| 1.0 - f)
+    }
+}
+
+/// An alternative histogram of the same column, offered alongside the
+/// full-range one so that skewed distributions remain readable.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq)]
+pub struct HistogramVariant {
+    /// Bin width in the original scale, or in log10 units for log views.
+    pub bin_width: f64,
+    /// Bin start (original scale) and count.
+    pub histogram: Vec<(f64, usize)>,
+    pub lower: f64,
+    pub upper: f64,
+    /// Non-null values that fell outside `lower..=upper`.
+    pub excluded_count: usize,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug, Default)]
@@ -364,6 +1081,49 @@ pub struct TextStats {
     pub avg_length: f64,
 }
 
+/// How a column holds locations
+#[derive(Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GeoEncoding {
+    /// Latitudes, paired with the longitudes in column `longitude`
+    Latitude { longitude: String },
+    /// Longitudes, paired with the latitudes in column `latitude`
+    Longitude { latitude: String },
+    /// Well-known text, such as `POINT (151.2 -33.9)`
+    Wkt,
+    /// GeoJSON geometries, features or feature collections
+    GeoJson,
+}
+
+/// Smallest box around a set of coordinates, in degrees
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Debug)]
+pub struct BoundingBox {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+}
+
+/// Locations in a column. Coordinates are taken as WGS 84 degrees, with
+/// latitudes from -90 to 90 and longitudes from -180 to 180.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct GeoStats {
+    pub encoding: GeoEncoding,
+    pub distinct_count: usize,
+    /// Coordinate pairs (rows with both a latitude and a longitude) or
+    /// geometries with every coordinate in range
+    pub valid_count: usize,
+    /// Coordinate pairs out of range, or strings that are not geometries or
+    /// have a coordinate out of range
+    pub invalid_count: usize,
+    /// Box around the valid coordinates; `None` without any
+    pub bounds: Option<BoundingBox>,
+    /// Valid geometries by type, e.g. `Point` or `Polygon`; empty for
+    /// latitude and longitude columns
+    #[serde(default)]
+    pub geometry_types: BTreeMap<String, usize>,
+}
+
 #[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Debug)]
 pub enum ColumnKind {
     Numeric,
@@ -411,12 +1171,79 @@ impl ColumnKind {
     }
 }
 
+/// What a text column holds, recognised from the shape of its values
+#[derive(Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SemanticType {
+    Email,
+    /// ISO 3166 alpha-2 or alpha-3 country code
+    CountryCode,
+    /// ISO 4217 currency code
+    CurrencyCode,
+    /// Amount with a currency symbol or code, e.g. `£1,250.00`
+    CurrencyAmount,
+    /// UK postcode or US ZIP code
+    Postcode,
+    /// IPv4 or IPv6 address
+    IpAddress,
+}
+
+impl std::fmt::Display for SemanticType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+impl SemanticType {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Email => "Email address",
+            Self::CountryCode => "Country code",
+            Self::CurrencyCode => "Currency code",
+            Self::CurrencyAmount => "Currency amount",
+            Self::Postcode => "Postcode",
+            Self::IpAddress => "IP address",
+        }
+    }
+
+    /// Whether values of this type can identify a person
+    pub fn is_pii(&self) -> bool {
+        matches!(self, Self::Email | Self::Postcode | Self::IpAddress)
+    }
+}
+
 #[derive(Clone, Deserialize, Serialize, Debug)]
 pub struct FileHealth {
     pub score: f32,
     pub risks: Vec<String>,
 }
 
+/// Values of one configured column before and after cleaning
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct ColumnCleanPreview {
+    pub name: String,
+    /// Name after cleaning; `None` if cleaning drops the column (inactive)
+    /// or replaces it (one-hot encoding)
+    pub cleaned_name: Option<String>,
+    pub dtype_before: String,
+    pub dtype_after: Option<String>,
+    /// Sample values as text, row by row; nulls are `None`
+    pub before: Vec<Option<String>>,
+    /// Same rows after cleaning; empty when `cleaned_name` is `None`
+    pub after: Vec<Option<String>>,
+    /// Rows whose value changed
+    pub changed: usize,
+}
+
+/// Cleaning applied to the first rows of a file, for checking configs
+/// before running them on all of it
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct CleanPreview {
+    /// Rows in the sample
+    pub rows: usize,
+    pub columns: Vec<ColumnCleanPreview>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -472,6 +1299,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert!(summary.is_compatible_with(ColumnKind::Text));
@@ -496,6 +1325,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert!(summary_num.is_compatible_with(ColumnKind::Numeric));
@@ -518,6 +1349,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
         assert!(summary_date.is_compatible_with(ColumnKind::Temporal));
 
@@ -538,6 +1371,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert!(summary_bool.is_compatible_with(ColumnKind::Numeric));
@@ -557,5 +1392,14 @@ mod tests {
         assert!(!MlModelKind::DecisionTree.is_suitable_target(ColumnKind::Numeric));
         assert!(MlModelKind::DecisionTree.is_suitable_target(ColumnKind::Categorical));
         assert!(MlModelKind::DecisionTree.is_suitable_target(ColumnKind::Boolean));
+
+        assert!(MlModelKind::RandomForest.is_suitable_target(ColumnKind::Categorical));
+        assert!(!MlModelKind::RandomForest.is_suitable_target(ColumnKind::Numeric));
+        assert!(MlModelKind::Ridge.is_suitable_target(ColumnKind::Numeric));
+        assert!(MlModelKind::Lasso.is_suitable_target(ColumnKind::Numeric));
+        assert!(!MlModelKind::Lasso.is_suitable_target(ColumnKind::Boolean));
+
+        assert!(!MlModelKind::KMeans.requires_target());
+        assert!(!MlModelKind::KMeans.is_suitable_target(ColumnKind::Numeric));
     }
 }
@@ -20,6 +20,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert_eq!(summary.null_pct(), 20.0);
@@ -39,6 +41,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert_eq!(summary.null_pct(), 0.0);
@@ -61,6 +65,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert_eq!(summary.uniqueness_ratio(), 0.5);
@@ -80,6 +86,8 @@ mod tests {
             business_summary: vec![],
             ml_advice: vec![],
             samples: vec![],
+            semantic_type: None,
+            suggested_name: None,
         };
 
         assert!(summary.is_compatible_with(ColumnKind::Numeric));
@@ -0,0 +1,258 @@
+//! Column usage: what users do to each column of a dataset across cleans.
+//!
+//! Every clean applied to a dataset is recorded under
+//! `usage/<hash of the source path>.json` in the app data directory,
+//! counting per column how often it was dropped, renamed or one-hot
+//! encoded. Once a column has been seen in enough cleans, a choice made in
+//! most of them becomes a [`UsageSuggestion`], e.g. "dropped in 9 of 10
+//! cleans — drop by default?", which auto-cleaning and the analyser can
+//! apply.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use super::naming::sanitize_column_name;
+use super::types::ColumnCleanConfig;
+
+/// Cleans a column must appear in before its usage suggests anything
+pub const MIN_CLEANS_FOR_SUGGESTIONS: u32 = 3;
+
+/// Share of a column's cleans a choice must be made in to be suggested
+pub const SUGGESTION_THRESHOLD: f64 = 0.8;
+
+/// How one column was cleaned over the cleans it appeared in
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnUsage {
+    pub cleans: u32,
+    pub dropped: u32,
+    /// Deliberate renames by new name. Renames to the sanitised name are
+    /// the default and not counted.
+    pub renamed_to: BTreeMap<String, u32>,
+    pub one_hot_encoded: u32,
+}
+
+/// Column usage of one dataset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatasetUsage {
+    pub dataset: String,
+    pub cleans: u32,
+    pub columns: BTreeMap<String, ColumnUsage>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// A default suggested by column usage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum UsageAction {
+    Drop,
+    Rename { to: String },
+    OneHotEncode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UsageSuggestion {
+    pub column: String,
+    pub action: UsageAction,
+    /// Cleans the action was taken in
+    pub count: u32,
+    /// Cleans the column appeared in
+    pub cleans: u32,
+    pub message: String,
+}
+
+impl DatasetUsage {
+    pub fn new(dataset: impl Into<String>) -> Self {
+        Self {
+            dataset: dataset.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Count one clean made with `configs`, keyed by column name
+    pub fn record(&mut self, configs: &HashMap<String, ColumnCleanConfig>) {
+        self.cleans += 1;
+        self.updated_at = Some(Utc::now());
+        #[expect(clippy::iter_over_hash_type)]
+        for (column, config) in configs {
+            let usage = self.columns.entry(column.clone()).or_default();
+            usage.cleans += 1;
+            if !config.active {
+                usage.dropped += 1;
+                continue;
+            }
+            let new_name = config.new_name.trim();
+            if !new_name.is_empty()
+                && new_name != column
+                && new_name != sanitize_column_name(column)
+            {
+                *usage.renamed_to.entry(new_name.to_owned()).or_default() += 1;
+            }
+            if config.ml_preprocessing && config.one_hot_encode {
+                usage.one_hot_encoded += 1;
+            }
+        }
+    }
+
+    /// Choices made in most cleans of columns seen often enough, by column.
+    /// A column mostly dropped only gets the drop suggestion.
+    pub fn suggestions(&self) -> Vec<UsageSuggestion> {
+        let mut suggestions = Vec::new();
+        for (column, usage) in &self.columns {
+            if usage.cleans < MIN_CLEANS_FOR_SUGGESTIONS {
+                continue;
+            }
+            let frequent =
+                |count: u32| f64::from(count) >= f64::from(usage.cleans) * SUGGESTION_THRESHOLD;
+            let mut suggest = |action: UsageAction, count: u32, done: &str, question: &str| {
+                suggestions.push(UsageSuggestion {
+                    column: column.clone(),
+                    action,
+                    count,
+                    cleans: usage.cleans,
+                    message: format!(
+                        "'{column}' {done} in {count} of {} cleans — {question}?",
+                        usage.cleans
+                    ),
+                });
+            };
+
+            if frequent(usage.dropped) {
+                suggest(
+                    UsageAction::Drop,
+                    usage.dropped,
+                    "dropped",
+                    "drop by default",
+                );
+                continue;
+            }
+            // Most used name first, alphabetical on ties
+            if let Some((to, &count)) = usage
+                .renamed_to
+                .iter()
+                .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                && frequent(count)
+            {
+                suggest(
+                    UsageAction::Rename { to: to.clone() },
+                    count,
+                    &format!("renamed to '{to}'"),
+                    "use this name by default",
+                );
+            }
+            if frequent(usage.one_hot_encoded) {
+                suggest(
+                    UsageAction::OneHotEncode,
+                    usage.one_hot_encoded,
+                    "one-hot encoded",
+                    "encode by default",
+                );
+            }
+        }
+        suggestions
+    }
+}
+
+/// Apply `suggestions` to `configs`, keyed by column name. Columns without
+/// a config are skipped. Returns how many configs changed.
+pub fn apply_usage_suggestions(
+    configs: &mut HashMap<String, ColumnCleanConfig>,
+    suggestions: &[UsageSuggestion],
+) -> usize {
+    let mut changed = 0;
+    for suggestion in suggestions {
+        let Some(config) = configs.get_mut(&suggestion.column) else {
+            continue;
+        };
+        let before = config.clone();
+        match &suggestion.action {
+            UsageAction::Drop => config.active = false,
+            UsageAction::Rename { to } => config.new_name.clone_from(to),
+            UsageAction::OneHotEncode => {
+                config.ml_preprocessing = true;
+                config.one_hot_encode = true;
+            }
+        }
+        if *config != before {
+            changed += 1;
+        }
+    }
+    changed
+}
+
+/// The dataset key of a source file: its canonical path when it exists, so
+/// the same file is one dataset however it was opened
+pub fn usage_key(path: &Path) -> String {
+    std::fs::canonicalize(path)
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The column usage of every dataset, one file each in a folder
+#[derive(Debug, Clone)]
+pub struct UsageLog {
+    dir: PathBuf,
+}
+
+impl UsageLog {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The log in the app data directory
+    pub fn open_default() -> Self {
+        Self::new(crate::utils::standard_paths().base_dir.join("usage"))
+    }
+
+    /// Usage of `dataset`, empty if nothing was recorded for it
+    pub fn get(&self, dataset: &str) -> Result<DatasetUsage> {
+        let path = self.usage_path(dataset);
+        match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(DatasetUsage::new(dataset)),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Count one clean of `dataset` made with `configs`
+    pub fn record(
+        &self,
+        dataset: &str,
+        configs: &HashMap<String, ColumnCleanConfig>,
+    ) -> Result<DatasetUsage> {
+        let mut usage = self.get(dataset)?;
+        usage.record(configs);
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.usage_path(dataset);
+        let json =
+            serde_json::to_string_pretty(&usage).context("Failed to serialize column usage")?;
+        std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(usage)
+    }
+
+    /// Forget the usage of `dataset`. Returns false if none was recorded.
+    pub fn clear(&self, dataset: &str) -> Result<bool> {
+        let path = self.usage_path(dataset);
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    fn usage_path(&self, dataset: &str) -> PathBuf {
+        // Keys are file paths, so hash them into a safe file name
+        let hash = format!("{:x}", Sha256::digest(dataset.as_bytes()));
+        self.dir.join(format!("{hash}.json"))
+    }
+}
@@ -1,22 +1,209 @@
 use anyhow::{Context as _, Result};
+use beefcake::analyser::db::{DdlMode, DdlReport};
 use beefcake::analyser::logic::types::ColumnCleanConfig;
 use beefcake::analyser::logic::{
-    clean_df_lazy, flows, get_parquet_write_options, load_df_lazy, save_df,
+    DEFAULT_IN_PLACE_SUFFIX, HeaderlessNaming, InPlaceMode, PushOptions, TableDefinition, UsageLog,
+    clean_df_lazy, cleaning_reject_conditions, flows, get_parquet_write_options, load_df_lazy_as,
+    save_df, split_rejects, table_name_for_file, usage_key, write_in_place,
 };
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory as _, Parser, Subcommand};
 use polars::prelude::*;
+use serde::Serialize;
 use sqlx::postgres::PgConnectOptions;
 use std::collections::HashMap;
+use std::fmt;
+use std::io::{IsTerminal as _, Write as _};
 use std::path::{Path, PathBuf};
 use std::str::FromStr as _;
 
+/// `println!` for progress and status, unless the output is quiet or JSON
+macro_rules! say {
+    ($out:expr) => {
+        if $out.prose() {
+            println!();
+        }
+    };
+    ($out:expr, $($arg:tt)*) => {
+        if $out.prose() {
+            println!($($arg)*);
+        }
+    };
+}
+
 #[derive(Parser)]
 #[command(name = "beefcake", about = "Data analysis and migration tool")]
 pub struct Cli {
+    /// Print the result as one JSON object on standard output instead of prose
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Print only errors and the command's own output, such as generated SQL
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Ask on the terminal for a missing input file, table or database connection
+    #[arg(long, global = true)]
+    pub interactive: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+/// How a command talks to the user, from the global `--json`, `--quiet`
+/// and `--interactive` flags
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Output {
+    pub json: bool,
+    pub quiet: bool,
+    pub interactive: bool,
+}
+
+impl Output {
+    /// Whether progress and status messages are printed
+    fn prose(self) -> bool {
+        !self.json && !self.quiet
+    }
+
+    /// Whether a missing argument can be asked for. Prompts go to stderr,
+    /// so they never mix with JSON on stdout.
+    fn can_prompt(self) -> bool {
+        self.interactive && std::io::stdin().is_terminal()
+    }
+
+    /// Print `report` if the output is JSON
+    fn report<T: Serialize>(self, report: &T) -> Result<()> {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(report)?);
+        }
+        Ok(())
+    }
+}
+
+/// Exit codes of the CLI, so wrapping scripts can branch on the kind of
+/// failure. Clap exits with 2 for invalid arguments as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitCode {
+    Ok = 0,
+    /// Any failure not covered below
+    Error = 1,
+    /// An invalid pipeline spec, cleaning configuration or argument
+    Validation = 2,
+    /// The data broke a schema contract, or a run had warnings with
+    /// `--fail-on-warnings`
+    DataQuality = 3,
+    /// A file or database could not be read or written
+    Io = 4,
+}
+
+impl From<ExitCode> for std::process::ExitCode {
+    fn from(code: ExitCode) -> Self {
+        Self::from(code as u8)
+    }
+}
+
+/// The exit code for `err`, from the first cause that tells
+fn exit_code_of(err: &anyhow::Error) -> ExitCode {
+    use beefcake::pipeline::{ContractViolations, ValidationFailed};
+
+    for cause in err.chain() {
+        if let Some(failure) = cause.downcast_ref::<Failure>() {
+            return failure.code;
+        }
+        if cause.is::<ContractViolations>() {
+            return ExitCode::DataQuality;
+        }
+        if cause.is::<ValidationFailed>() {
+            return ExitCode::Validation;
+        }
+        if let Some(e) = cause.downcast_ref::<serde_json::Error>() {
+            return if e.is_io() {
+                ExitCode::Io
+            } else {
+                ExitCode::Validation
+            };
+        }
+        if cause.is::<std::io::Error>()
+            || cause.is::<sqlx::Error>()
+            || matches!(
+                cause.downcast_ref::<PolarsError>(),
+                Some(PolarsError::IO { .. })
+            )
+        {
+            return ExitCode::Io;
+        }
+    }
+    ExitCode::Error
+}
+
+/// An error with an exit code chosen where it is raised
+#[derive(Debug)]
+struct Failure {
+    code: ExitCode,
+    message: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+fn fail(code: ExitCode, message: impl Into<String>) -> anyhow::Error {
+    Failure {
+        code,
+        message: message.into(),
+    }
+    .into()
+}
+
+/// What `--json` prints when a command fails
+#[derive(Serialize)]
+struct ErrorReport {
+    error: String,
+    exit_code: u8,
+    kind: ExitCode,
+}
+
+/// What `import --json` prints
+#[derive(Serialize)]
+struct ImportReport {
+    file: PathBuf,
+    schema: String,
+    table: String,
+    /// Table the column profile was appended to
+    profile_table: Option<String>,
+    ddl: Option<DdlReport>,
+    archived_to: PathBuf,
+}
+
+/// What `export --json` and `clean --json` print
+#[derive(Serialize)]
+struct FileReport {
+    input: PathBuf,
+    output: PathBuf,
+    /// Rows written, when known
+    rows: Option<usize>,
+    rejects: Option<PathBuf>,
+    /// Copy of the input when it was replaced in place
+    backup: Option<PathBuf>,
+    /// Where the input was moved after processing
+    archived_to: Option<PathBuf>,
+}
+
+/// What `ddl --json` prints
+#[derive(Serialize)]
+struct DdlOutput {
+    table: String,
+    /// `sql` or `dbt`
+    format: &'static str,
+    /// File the text was written to, if any
+    output: Option<PathBuf>,
+    text: String,
+}
+
 /// Context object for CLI operations, holding shared application state.
 struct CliContext {
     app_config: beefcake::config::AppConfig,
@@ -47,10 +234,164 @@ impl CliContext {
                 .iter()
                 .find(|c| c.id == id)
                 .map(|c| c.settings.connection_string(&c.id))
-                .ok_or_else(|| anyhow::anyhow!("Connection with ID '{id}' not found in config"));
+                .ok_or_else(|| {
+                    fail(
+                        ExitCode::Validation,
+                        format!("Connection with ID '{id}' not found in config"),
+                    )
+                });
         }
 
-        anyhow::bail!("No database URL provided and no active connection set")
+        Err(fail(
+            ExitCode::Validation,
+            "No database URL provided and no active connection set",
+        ))
+    }
+
+    /// Whether the database [`Self::resolve_db_url`] picks is a saved
+    /// read-only connection. An explicit URL is not a saved connection, so
+    /// only the configured one can be read-only.
+    fn is_read_only(&self, explicit_url: Option<&str>, config_id: Option<&str>) -> bool {
+        explicit_url.is_none()
+            && self
+                .app_config
+                .settings
+                .connections
+                .iter()
+                .any(|c| config_id == Some(c.id.as_str()) && c.read_only)
+    }
+}
+
+/// A database picked at the interactive prompt
+enum ConnectionChoice {
+    Saved(String),
+    Url(String),
+}
+
+impl CliContext {
+    /// Ask for a saved connection or a database URL, if prompting is possible
+    fn ask_connection(&self, out: Output) -> Result<Option<ConnectionChoice>> {
+        if !out.can_prompt() {
+            return Ok(None);
+        }
+        let connections = &self.app_config.settings.connections;
+        for (i, connection) in connections.iter().enumerate() {
+            eprintln!("  {}. {}", i + 1, connection.name);
+        }
+        let question = if connections.is_empty() {
+            "Database URL"
+        } else {
+            "Connection (number or database URL)"
+        };
+        let Some(answer) = ask(out, question, None)? else {
+            return Ok(None);
+        };
+        let saved = answer
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|i| connections.get(i));
+        Ok(Some(match saved {
+            Some(connection) => ConnectionChoice::Saved(connection.id.clone()),
+            None => ConnectionChoice::Url(answer),
+        }))
+    }
+}
+
+/// Ask for `what` on the terminal, offering `default` for an empty answer.
+/// `None` if prompting is not possible.
+fn ask(out: Output, what: &str, default: Option<&str>) -> Result<Option<String>> {
+    if !out.can_prompt() {
+        return Ok(None);
+    }
+    loop {
+        match default {
+            Some(default) => eprint!("{what} [{default}]: "),
+            None => eprint!("{what}: "),
+        }
+        std::io::stderr().flush()?;
+        let mut line = String::new();
+        if std::io::stdin()
+            .read_line(&mut line)
+            .context("Failed to read from the terminal")?
+            == 0
+        {
+            return Err(fail(ExitCode::Validation, format!("No {what} given")));
+        }
+        match (line.trim(), default) {
+            ("", Some(default)) => return Ok(Some(default.to_owned())),
+            ("", None) => {}
+            (answer, _) => return Ok(Some(answer.to_owned())),
+        }
+    }
+}
+
+/// How to read a CSV file that has no header row
+#[derive(Args, Clone, Default)]
+pub struct HeaderArgs {
+    /// The CSV file has no header row; columns are named column_1, column_2, ...
+    #[arg(long)]
+    no_header: bool,
+
+    /// Name the columns of a headerless CSV file from this file, one name per line (implies --no-header)
+    #[arg(long, value_name = "FILE")]
+    header_names: Option<PathBuf>,
+
+    /// Name the columns of a headerless CSV file from their content (implies --no-header)
+    #[arg(long, conflicts_with = "header_names")]
+    infer_names: bool,
+}
+
+impl HeaderArgs {
+    fn naming(&self) -> Option<HeaderlessNaming> {
+        if let Some(path) = &self.header_names {
+            let path = std::path::absolute(path).unwrap_or_else(|_| path.clone());
+            Some(HeaderlessNaming::Schema {
+                path: path.to_string_lossy().into_owned(),
+            })
+        } else if self.infer_names {
+            Some(HeaderlessNaming::Inferred)
+        } else {
+            self.no_header.then_some(HeaderlessNaming::Auto)
+        }
+    }
+
+    /// Scan `file`, without a header row if asked
+    fn load(&self, file: &Path) -> Result<LazyFrame> {
+        load_df_lazy_as(file, self.naming().as_ref())
+    }
+}
+
+/// Writing cleaned data back beside the original file
+#[derive(Args, Clone, Default)]
+pub struct InPlaceArgs {
+    /// Write the cleaned file beside the original in its format, CSV delimiter and encoding
+    #[arg(long, conflicts_with = "output")]
+    in_place: bool,
+
+    /// With --in-place, replace the original after copying it to a timestamped .bak file
+    #[arg(long, requires = "in_place")]
+    replace: bool,
+
+    /// With --in-place, the suffix added to the cleaned file's name [default: _cleaned]
+    #[arg(long, requires = "in_place", conflicts_with = "replace")]
+    suffix: Option<String>,
+}
+
+impl InPlaceArgs {
+    fn mode(&self) -> Option<InPlaceMode> {
+        if !self.in_place {
+            None
+        } else if self.replace {
+            Some(InPlaceMode::Replace)
+        } else {
+            Some(InPlaceMode::Suffix {
+                suffix: self
+                    .suffix
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_IN_PLACE_SUFFIX.to_owned()),
+            })
+        }
     }
 }
 
@@ -81,6 +422,21 @@ pub enum Commands {
         /// Path to a JSON cleaning configuration file
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Also append per-column statistics and health metrics to <table>_profile
+        #[arg(long)]
+        profile: bool,
+
+        /// Print suggested indexes and NOT NULL/CHECK constraints for the table
+        #[arg(long)]
+        suggest_ddl: bool,
+
+        /// Execute the suggested indexes and constraints (implies --suggest-ddl)
+        #[arg(long)]
+        apply_ddl: bool,
+
+        #[command(flatten)]
+        header: HeaderArgs,
     },
     /// Export database table or file to a different format
     Export {
@@ -107,6 +463,9 @@ pub enum Commands {
         /// Path to a JSON cleaning configuration file
         #[arg(long)]
         config: Option<PathBuf>,
+
+        #[command(flatten)]
+        header: HeaderArgs,
     },
     /// Clean a file and save the result
     Clean {
@@ -121,36 +480,247 @@ pub enum Commands {
         /// Path to a JSON cleaning configuration file
         #[arg(long)]
         config: Option<PathBuf>,
+
+        /// Write rows whose values fail type conversion to this file instead of the output
+        #[arg(long)]
+        rejects: Option<PathBuf>,
+
+        #[command(flatten)]
+        in_place: InPlaceArgs,
+
+        #[command(flatten)]
+        header: HeaderArgs,
     },
-    /// Run a pipeline specification
-    Run {
-        /// Path to the pipeline spec JSON file
-        #[arg(long, required = true)]
-        spec: PathBuf,
+    /// Run a pipeline specification (same as `pipeline run`)
+    Run(RunArgs),
+    /// Run or check pipeline specifications
+    Pipeline {
+        #[command(subcommand)]
+        command: PipelineCommands,
+    },
+    /// Watch a folder and process each file dropped into it, until Ctrl-C
+    Watch(WatchArgs),
+    /// Compare the schema and statistics of two files, or two versions of a dataset
+    Diff {
+        /// First file, or version id with --dataset
+        a: String,
 
-        /// Path to the input data file
-        #[arg(long, required = true)]
-        input: PathBuf,
+        /// Second file, or version id with --dataset
+        b: String,
 
-        /// Path for the output file (overrides spec `output.path_template`)
+        /// Compare two versions of this lifecycle dataset instead of two files
         #[arg(long)]
-        output: Option<PathBuf>,
+        dataset: Option<String>,
+
+        /// Exit with code 3 on these kinds of change (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fail_on: Vec<DiffCheck>,
 
-        /// Date string for path template substitution (format: YYYY-MM-DD, default: today)
+        /// Percentage a column statistic may move before `--fail-on distribution` fails
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Print a CREATE TABLE statement or dbt model YAML for a file
+    Ddl {
+        /// Input file path. Defaults to first file in the input directory.
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+
+        /// Table name. Defaults to the sanitised filename stem.
+        #[arg(short, long)]
+        table: Option<String>,
+
+        /// Schema to create the table in
         #[arg(long)]
-        date: Option<String>,
+        schema: Option<String>,
 
-        /// Path to write execution log
+        /// Path to a JSON cleaning configuration file, to describe the cleaned output
         #[arg(long)]
-        log: Option<PathBuf>,
+        config: Option<PathBuf>,
 
-        /// Fail with error if warnings are generated
+        /// Generate a dbt model YAML file instead of SQL
         #[arg(long)]
-        fail_on_warnings: bool,
+        dbt: bool,
+
+        /// Write to this file instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[command(flatten)]
+        header: HeaderArgs,
+    },
+    /// Work with data dictionary snapshots
+    Dict {
+        #[command(subcommand)]
+        command: DictCommands,
     },
+    /// Print a shell completion script, e.g. `beefcake completions bash > /etc/bash_completion.d/beefcake`
+    Completions {
+        /// Shell to complete in
+        shell: clap_complete::Shell,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PipelineCommands {
+    /// Run a pipeline specification
+    Run(RunArgs),
+    /// Check a pipeline specification against an input file without running it
+    Validate(SpecArgs),
 }
 
-pub async fn run_command(command: Commands) -> Result<()> {
+/// A pipeline spec and the inputs it runs on
+#[derive(Args)]
+pub struct SpecArgs {
+    /// Path to the pipeline spec JSON file
+    #[arg(long, required = true)]
+    spec: PathBuf,
+
+    /// Path to the input data file
+    #[arg(long, required = true)]
+    input: PathBuf,
+
+    /// Named input for concat/union steps as NAME=PATH (repeatable, overrides spec paths)
+    #[arg(long = "source", value_parser = parse_named_path)]
+    sources: Vec<(String, PathBuf)>,
+
+    /// Date string for path template substitution (format: YYYY-MM-DD, default: today)
+    #[arg(long)]
+    date: Option<String>,
+
+    /// Template parameter as NAME=VALUE, used for `{NAME}` in the spec (repeatable)
+    #[arg(long = "param", value_parser = parse_param)]
+    params: Vec<(String, String)>,
+}
+
+impl SpecArgs {
+    /// Template parameters, `--date` as `date`
+    fn params(&self) -> HashMap<String, String> {
+        self.date
+            .clone()
+            .map(|d| ("date".to_owned(), d))
+            .into_iter()
+            .chain(self.params.iter().cloned())
+            .collect()
+    }
+
+    fn sources(&self) -> HashMap<String, PathBuf> {
+        self.sources.iter().cloned().collect()
+    }
+}
+
+#[derive(Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    spec: SpecArgs,
+
+    /// Path for the output file (overrides spec `output.path_template`)
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Path to write execution log
+    #[arg(long)]
+    log: Option<PathBuf>,
+
+    /// Path to write the run report, with per-step metrics, as JSON
+    #[arg(long)]
+    report: Option<PathBuf>,
+
+    /// Fail with error if warnings are generated
+    #[arg(long)]
+    fail_on_warnings: bool,
+}
+
+/// A kind of change `diff --fail-on` fails on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffCheck {
+    /// Columns added, removed or changed type
+    Schema,
+    /// A column statistic moved by more than `--threshold` percent
+    Distribution,
+}
+
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Folder to watch for CSV and JSON files
+    #[arg(long, required = true)]
+    folder: PathBuf,
+
+    /// Pipeline spec run over each file. Without one, files are analysed into datasets.
+    #[arg(long)]
+    pipeline: Option<PathBuf>,
+
+    /// Folder for pipeline outputs, named after each input (overrides spec `output.path_template`)
+    #[arg(long, requires = "pipeline")]
+    output: Option<PathBuf>,
+
+    /// Template parameter as NAME=VALUE, used for `{NAME}` in the spec (repeatable)
+    #[arg(long = "param", value_parser = parse_param, requires = "pipeline")]
+    params: Vec<(String, String)>,
+
+    /// Also process the files already in the folder
+    #[arg(long)]
+    existing: bool,
+
+    /// Move each processed file to the processed directory
+    #[arg(long)]
+    archive: bool,
+
+    /// Append every event as a JSON line to this file
+    #[arg(long)]
+    events: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub enum DictCommands {
+    /// Search dataset and column names, descriptions, tags and glossary terms
+    Search {
+        /// Words to search for, e.g. "vat rate"
+        #[arg(required = true)]
+        query: Vec<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+
+        /// Also search superseded versions of each dataset
+        #[arg(long)]
+        all_versions: bool,
+
+        /// Dictionary directory. Defaults to the application's.
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+/// Run `command` and report any error as `out` asks, returning the exit code
+pub async fn run(command: Commands, out: Output) -> ExitCode {
+    match run_command(command, out).await {
+        Ok(code) => code,
+        Err(e) => {
+            let code = exit_code_of(&e);
+            tracing::error!("CLI command failed ({code:?}): {e:#}");
+            let report = ErrorReport {
+                error: format!("{e:#}"),
+                exit_code: code as u8,
+                kind: code,
+            };
+            if out.json {
+                match serde_json::to_string_pretty(&report) {
+                    Ok(json) => println!("{json}"),
+                    Err(_) => eprintln!("Error: {}", report.error),
+                }
+            } else {
+                eprintln!("Error: {}", report.error);
+            }
+            code
+        }
+    }
+}
+
+pub async fn run_command(command: Commands, out: Output) -> Result<ExitCode> {
+    let done = |result: Result<()>| result.map(|()| ExitCode::Ok);
     match command {
         Commands::Import {
             file,
@@ -159,7 +729,30 @@ pub async fn run_command(command: Commands) -> Result<()> {
             db_url,
             clean,
             config,
-        } => handle_import(file, table, schema, db_url, clean, config).await,
+            profile,
+            suggest_ddl,
+            apply_ddl,
+            header,
+        } => {
+            let options = PushOptions {
+                profile,
+                ddl: if apply_ddl {
+                    DdlMode::Apply
+                } else if suggest_ddl {
+                    DdlMode::Suggest
+                } else {
+                    DdlMode::Off
+                },
+                read_only: false,
+                header_names: header.naming(),
+            };
+            done(
+                handle_import(
+                    file, table, schema, db_url, clean, config, options, &header, out,
+                )
+                .await,
+            )
+        }
         Commands::Export {
             input,
             output,
@@ -167,20 +760,63 @@ pub async fn run_command(command: Commands) -> Result<()> {
             schema,
             clean,
             config,
-        } => handle_export(input, output, db_url, schema, clean, config).await,
+            header,
+        } => done(handle_export(input, output, db_url, schema, clean, config, &header, out).await),
         Commands::Clean {
             file,
             output,
             config,
-        } => handle_clean(file, output, config).await,
-        Commands::Run {
-            spec,
-            input,
+            rejects,
+            in_place,
+            header,
+        } => done(handle_clean(file, output, config, rejects, &in_place, &header, out).await),
+        Commands::Ddl {
+            file,
+            table,
+            schema,
+            config,
+            dbt,
             output,
-            date: _,
-            log,
-            fail_on_warnings,
-        } => handle_run(spec, input, output, log, fail_on_warnings).await,
+            header,
+        } => done(handle_ddl(file, table, schema, config, dbt, output, &header, out).await),
+        Commands::Run(args)
+        | Commands::Pipeline {
+            command: PipelineCommands::Run(args),
+        } => handle_run(args, out).await,
+        Commands::Pipeline {
+            command: PipelineCommands::Validate(args),
+        } => handle_validate(&args, out),
+        Commands::Watch(args) => done(handle_watch(args, out).await),
+        Commands::Diff {
+            a,
+            b,
+            dataset,
+            fail_on,
+            threshold,
+        } => handle_diff(&a, &b, dataset.as_deref(), &fail_on, threshold, out),
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "beefcake",
+                &mut std::io::stdout(),
+            );
+            Ok(ExitCode::Ok)
+        }
+        Commands::Dict { command } => match command {
+            DictCommands::Search {
+                query,
+                limit,
+                all_versions,
+                dir,
+            } => done(handle_dict_search(
+                &query.join(" "),
+                limit,
+                all_versions,
+                dir,
+                out,
+            )),
+        },
     }
 }
 
@@ -191,34 +827,93 @@ async fn handle_import(
     db_url: Option<String>,
     clean: bool,
     config_path: Option<PathBuf>,
+    mut options: PushOptions,
+    header: &HeaderArgs,
+    out: Output,
 ) -> Result<()> {
     let ctx = CliContext::new();
-    let file = file.unwrap_or(get_default_input_file()?);
-    let table = table.unwrap_or_else(|| {
-        file.file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string()
-    });
-
-    println!(
+    let file = resolve_input_file(file, out)?;
+    let table = match table {
+        Some(table) => table,
+        None => {
+            let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+            ask(out, "Table", Some(&*stem))?.unwrap_or_else(|| stem.into_owned())
+        }
+    };
+
+    say!(
+        out,
         "Importing {0} into table {schema}.{table} (streaming)...",
         file.display()
     );
 
-    let lf = load_df_lazy(&file).context("Failed to load dataframe lazily")?;
-    let configs = resolve_cleaning_config(config_path, clean, lf.clone())?;
-
-    let effective_url =
-        ctx.resolve_db_url(db_url, ctx.app_config.settings.active_import_id.clone())?;
-    let opts =
-        PgConnectOptions::from_str(&effective_url).context("Failed to parse database URL")?;
-
-    flows::push_to_db_flow(file.clone(), opts, schema, table, configs).await?;
-
-    println!("Successfully imported.");
-    archive_and_log(&file, "File archived to")?;
-    Ok(())
+    let lf = header
+        .load(&file)
+        .context("Failed to load dataframe lazily")?;
+    let configs = resolve_cleaning_config(config_path, clean, &file, lf.clone(), out)?;
+
+    let mut db_url = db_url;
+    let mut active_id = ctx.app_config.settings.active_import_id.clone();
+    if db_url.is_none() && active_id.is_none() {
+        match ctx.ask_connection(out)? {
+            Some(ConnectionChoice::Saved(id)) => active_id = Some(id),
+            Some(ConnectionChoice::Url(url)) => db_url = Some(url),
+            None => {}
+        }
+    }
+    options.read_only = ctx.is_read_only(db_url.as_deref(), active_id.as_deref());
+    let effective_url = ctx.resolve_db_url(db_url, active_id)?;
+    let opts = PgConnectOptions::from_str(&effective_url).map_err(|e| {
+        fail(
+            ExitCode::Validation,
+            format!("Failed to parse database URL: {e}"),
+        )
+    })?;
+
+    let profile = options.profile;
+
+    let ddl = flows::push_to_db_flow_with_options(
+        file.clone(),
+        opts,
+        schema.clone(),
+        table.clone(),
+        configs,
+        options,
+    )
+    .await?;
+
+    say!(out, "Successfully imported.");
+    if profile {
+        say!(out, "Column profile appended to {table}_profile.");
+    }
+    if let Some(report) = &ddl
+        && out.prose()
+    {
+        println!("\n{}", report.script);
+        if report.applied {
+            println!(
+                "Applied {} of {} suggested statements.",
+                report
+                    .suggestions
+                    .suggestions
+                    .len()
+                    .saturating_sub(report.failures.len()),
+                report.suggestions.suggestions.len()
+            );
+            for failure in &report.failures {
+                println!("  Failed: {}\n    {}", failure.statement, failure.error);
+            }
+        }
+    }
+    let archived_to = archive_and_log(&file, "File archived to", out)?;
+    out.report(&ImportReport {
+        file,
+        profile_table: profile.then(|| format!("{table}_profile")),
+        schema,
+        table,
+        ddl,
+        archived_to,
+    })
 }
 
 async fn handle_export(
@@ -228,63 +923,172 @@ async fn handle_export(
     _schema: String,
     clean: bool,
     config_path: Option<PathBuf>,
+    header: &HeaderArgs,
+    out: Output,
 ) -> Result<()> {
-    let input_path = input
-        .map(PathBuf::from)
-        .unwrap_or(get_default_input_file()?);
+    let input_path = resolve_input_file(input.map(PathBuf::from), out)?;
 
     if !input_path.exists() {
-        anyhow::bail!("Input file not found. Table export from DB is not yet implemented.");
+        return Err(fail(
+            ExitCode::Io,
+            "Input file not found. Table export from DB is not yet implemented.",
+        ));
     }
 
     let output_path =
         output.unwrap_or_else(|| get_default_output_path(&input_path, "exported", "parquet"));
 
-    println!(
+    say!(
+        out,
         "Converting {0} to {1} (lazily)...",
         input_path.display(),
         output_path.display()
     );
 
-    let lf = load_df_lazy(&input_path).context("Failed to load input file lazily")?;
-    let configs = resolve_cleaning_config(config_path, clean, lf.clone())?;
+    let lf = header
+        .load(&input_path)
+        .context("Failed to load input file lazily")?;
+    let configs = resolve_cleaning_config(config_path, clean, &input_path, lf.clone(), out)?;
 
-    println!("Applying transformations...");
+    say!(out, "Applying transformations...");
     let cleaned_lf = clean_df_lazy(lf, &configs, true)?;
 
-    sink_to_file(cleaned_lf, &output_path)?;
-
-    println!("Successfully exported.");
-    archive_and_log(&input_path, "Input file archived to")?;
-    Ok(())
+    sink_to_file(cleaned_lf, &output_path, out)?;
+
+    say!(out, "Successfully exported.");
+    let archived_to = archive_and_log(&input_path, "Input file archived to", out)?;
+    out.report(&FileReport {
+        input: input_path,
+        output: output_path,
+        rows: None,
+        rejects: None,
+        backup: None,
+        archived_to: Some(archived_to),
+    })
 }
 
 async fn handle_clean(
     file: Option<PathBuf>,
     output: Option<PathBuf>,
     config_path: Option<PathBuf>,
+    rejects_path: Option<PathBuf>,
+    in_place: &InPlaceArgs,
+    header: &HeaderArgs,
+    out: Output,
 ) -> Result<()> {
-    let input_file = file.unwrap_or(get_default_input_file()?);
+    let input_file = resolve_input_file(file, out)?;
+    let lf = header
+        .load(&input_file)
+        .context("Failed to load input file lazily")?;
+
+    // For clean command, always auto-clean if no config provided
+    let configs = resolve_cleaning_config(config_path, true, &input_file, lf.clone(), out)?;
+
+    let lf = if let Some(rejects_path) = &rejects_path {
+        let mut lf = lf;
+        let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+        let conditions = cleaning_reject_conditions(&schema, &configs, true);
+        let (kept, rejects) = split_rejects(lf, &conditions);
+        sink_to_file(rejects, rejects_path, out)?;
+        say!(out, "Rejected rows written to {}", rejects_path.display());
+        kept
+    } else {
+        lf
+    };
+
+    let cleaned_lf = clean_df_lazy(lf, &configs, true)?;
+
+    // Written back beside the original, which stays where it is
+    if let Some(mode) = in_place.mode() {
+        say!(out, "Cleaning {} in place...", input_file.display());
+        let report = write_in_place(&input_file, cleaned_lf, &mode, header.naming().is_none())?;
+        say!(
+            out,
+            "Successfully cleaned {} rows into {}",
+            report.rows,
+            report.output
+        );
+        if let Some(backup) = &report.backup {
+            say!(out, "Original file backed up to {backup}");
+        }
+        return out.report(&FileReport {
+            input: input_file,
+            output: PathBuf::from(report.output),
+            rows: Some(report.rows),
+            rejects: rejects_path,
+            backup: report.backup.map(PathBuf::from),
+            archived_to: None,
+        });
+    }
+
     let output_file =
         output.unwrap_or_else(|| get_default_output_path(&input_file, "cleaned", "parquet"));
-
-    println!(
+    say!(
+        out,
         "Cleaning {0} and saving to {1} (lazily)...",
         input_file.display(),
         output_file.display()
     );
+    sink_to_file(cleaned_lf, &output_file, out)?;
+
+    say!(out, "Successfully cleaned.");
+    let archived_to = archive_and_log(&input_file, "Original file archived to", out)?;
+    out.report(&FileReport {
+        input: input_file,
+        output: output_file,
+        rows: None,
+        rejects: rejects_path,
+        backup: None,
+        archived_to: Some(archived_to),
+    })
+}
 
-    let lf = load_df_lazy(&input_file).context("Failed to load input file lazily")?;
-
-    // For clean command, always auto-clean if no config provided
-    let configs = resolve_cleaning_config(config_path, true, lf.clone())?;
-    let cleaned_lf = clean_df_lazy(lf, &configs, true)?;
-
-    sink_to_file(cleaned_lf, &output_file)?;
-
-    println!("Successfully cleaned.");
-    archive_and_log(&input_file, "Original file archived to")?;
-    Ok(())
+async fn handle_ddl(
+    file: Option<PathBuf>,
+    table: Option<String>,
+    schema: Option<String>,
+    config_path: Option<PathBuf>,
+    dbt: bool,
+    output: Option<PathBuf>,
+    header: &HeaderArgs,
+    out: Output,
+) -> Result<()> {
+    let input_file = resolve_input_file(file, out)?;
+    let analysis = flows::analyze_file_flow_with_header(input_file.clone(), header.naming())
+        .await
+        .context(format!("Failed to analyse {}", input_file.display()))?;
+    let configs = config_path
+        .as_ref()
+        .map(load_config)
+        .transpose()?
+        .unwrap_or_default();
+    let table = table.unwrap_or_else(|| table_name_for_file(&analysis.file_name));
+
+    let definition = TableDefinition::from_analysis_with_configs(
+        &analysis.summary,
+        &configs,
+        schema.as_deref(),
+        &table,
+    );
+    let text = if dbt {
+        definition.dbt_yaml()
+    } else {
+        definition.create_table_sql()
+    };
+    match &output {
+        Some(path) => {
+            std::fs::write(path, &text).context(format!("Failed to write {}", path.display()))?;
+            say!(out, "Written to {}", path.display());
+        }
+        None if !out.json => print!("{text}"),
+        None => {}
+    }
+    out.report(&DdlOutput {
+        table: definition.qualified_name(),
+        format: if dbt { "dbt" } else { "sql" },
+        output,
+        text,
+    })
 }
 
 /// Load cleaning configuration from a JSON file.
@@ -295,6 +1099,20 @@ fn load_config(path: &PathBuf) -> Result<HashMap<String, ColumnCleanConfig>> {
 }
 
 /// Get the first file from the default input directory.
+/// `file`, or else the first file in the input directory; when prompting
+/// is possible, the user is asked with that file as the default
+fn resolve_input_file(file: Option<PathBuf>, out: Output) -> Result<PathBuf> {
+    if let Some(file) = file {
+        return Ok(file);
+    }
+    let default = get_default_input_file();
+    let shown = default.as_ref().ok().map(|f| f.display().to_string());
+    match ask(out, "Input file", shown.as_deref())? {
+        Some(answer) => Ok(PathBuf::from(answer)),
+        None => default,
+    }
+}
+
 fn get_default_input_file() -> Result<PathBuf> {
     let mut entries = std::fs::read_dir(beefcake::utils::DATA_INPUT_DIR)
         .context(format!(
@@ -314,17 +1132,27 @@ fn get_default_input_file() -> Result<PathBuf> {
 }
 
 /// Resolve cleaning configuration from config path or auto-cleaning flag.
+/// Auto-cleaning applies the defaults suggested by past cleans of `input`.
 fn resolve_cleaning_config(
     config_path: Option<PathBuf>,
     auto_clean: bool,
+    input: &Path,
     lf: LazyFrame,
+    out: Output,
 ) -> Result<HashMap<String, ColumnCleanConfig>> {
     if let Some(path) = config_path {
-        println!("Loading config from {}...", path.display());
+        say!(out, "Loading config from {}...", path.display());
         load_config(&path)
     } else if auto_clean {
-        println!("Auto-cleaning (sampling 500k rows for analysis)...");
-        flows::generate_auto_clean_configs(lf)
+        say!(out, "Auto-cleaning (sampling 500k rows for analysis)...");
+        let usage = match UsageLog::open_default().get(&usage_key(input)) {
+            Ok(usage) => Some(usage),
+            Err(e) => {
+                eprintln!("Warning: ignoring column usage: {e:#}");
+                None
+            }
+        };
+        flows::generate_auto_clean_configs_with_usage(lf, usage.as_ref())
     } else {
         Ok(HashMap::new())
     }
@@ -340,7 +1168,7 @@ fn get_default_output_path(input_path: &Path, prefix: &str, extension: &str) ->
 }
 
 /// Sink a `LazyFrame` to a file with appropriate format handling.
-fn sink_to_file(lf: LazyFrame, output_path: &Path) -> Result<()> {
+fn sink_to_file(lf: LazyFrame, output_path: &Path, out: Output) -> Result<()> {
     let ext = output_path
         .extension()
         .and_then(|s| s.to_str())
@@ -351,13 +1179,14 @@ fn sink_to_file(lf: LazyFrame, output_path: &Path) -> Result<()> {
         "parquet" => {
             let options = get_parquet_write_options(&lf)?;
             if let Some(rgs) = options.row_group_size {
-                println!(
+                say!(
+                    out,
                     "Streaming to parquet: {} (adaptive row group size: {})...",
                     output_path.display(),
                     rgs
                 );
             } else {
-                println!("Streaming to parquet: {}...", output_path.display());
+                say!(out, "Streaming to parquet: {}...", output_path.display());
             }
 
             lf.with_streaming(true)
@@ -365,13 +1194,13 @@ fn sink_to_file(lf: LazyFrame, output_path: &Path) -> Result<()> {
                 .context("Failed to sink to parquet")?;
         }
         "csv" => {
-            println!("Streaming to csv: {}...", output_path.display());
+            say!(out, "Streaming to csv: {}...", output_path.display());
             lf.with_streaming(true)
                 .sink_csv(output_path, Default::default(), None)
                 .context("Failed to sink to csv")?;
         }
         _ => {
-            println!("Collecting and saving to {}...", output_path.display());
+            say!(out, "Collecting and saving to {}...", output_path.display());
             let mut df = lf.collect().context("Failed to collect data for saving")?;
             save_df(&mut df, output_path).context("Failed to save file")?;
         }
@@ -380,57 +1209,117 @@ fn sink_to_file(lf: LazyFrame, output_path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Archive the input file and print the result.
-fn archive_and_log(input_path: &Path, message: &str) -> Result<()> {
+/// Archive the input file, print and return where it went.
+fn archive_and_log(input_path: &Path, message: &str, out: Output) -> Result<PathBuf> {
     let archived = beefcake::utils::archive_processed_file(input_path)?;
-    println!("{message}: {}", archived.display());
-    Ok(())
+    say!(out, "{message}: {}", archived.display());
+    Ok(archived)
 }
 
-async fn handle_run(
-    spec_path: PathBuf,
-    input_path: PathBuf,
-    output_path: Option<PathBuf>,
-    log_path: Option<PathBuf>,
-    fail_on_warnings: bool,
-) -> Result<()> {
-    use beefcake::pipeline::{PipelineSpec, run_pipeline};
+/// Parse a `NAME=PATH` argument
+fn parse_named_path(arg: &str) -> Result<(String, PathBuf), String> {
+    match arg.split_once('=') {
+        Some((name, path)) if !name.is_empty() && !path.is_empty() => {
+            Ok((name.to_owned(), PathBuf::from(path)))
+        }
+        _ => Err(format!("Expected NAME=PATH, got '{arg}'")),
+    }
+}
+
+/// Parse a `NAME=VALUE` template parameter
+fn parse_param(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(format!("Expected NAME=VALUE, got '{arg}'")),
+    }
+}
+
+/// Load the spec of `args` with its template parameters, print an outline
+/// and its lint warnings, and check that the input exists
+fn load_spec(args: &SpecArgs, out: Output) -> Result<beefcake::pipeline::PipelineSpec> {
+    use beefcake::pipeline::{PipelineSpec, lint_pipeline};
 
-    println!("Loading pipeline spec from {}...", spec_path.display());
+    say!(out, "Loading pipeline spec from {}...", args.spec.display());
 
-    // Load pipeline spec
-    let spec = PipelineSpec::from_file(&spec_path).context(format!(
+    let mut spec = PipelineSpec::from_file(&args.spec).context(format!(
         "Failed to load pipeline spec: {}",
-        spec_path.display()
+        args.spec.display()
     ))?;
+    spec.params.extend(args.params());
 
-    println!("Pipeline: {}", spec.name);
-    println!("Version: {}", spec.version);
-    println!("Steps: {}", spec.steps.len());
-    println!();
+    say!(out, "Pipeline: {}", spec.name);
+    say!(out, "Version: {}", spec.version);
+    say!(out, "Steps: {}", spec.steps.len());
+    for warning in lint_pipeline(&spec) {
+        say!(out, "Lint: {warning}");
+    }
+    say!(out);
 
-    // Validate input file exists
-    if !input_path.exists() {
-        anyhow::bail!("Input file not found: {}", input_path.display());
+    if !args.input.exists() {
+        return Err(fail(
+            ExitCode::Io,
+            format!("Input file not found: {}", args.input.display()),
+        ));
     }
+    say!(out, "Input: {}", args.input.display());
+    Ok(spec)
+}
 
-    println!("Input: {}", input_path.display());
+async fn handle_run(args: RunArgs, out: Output) -> Result<ExitCode> {
+    use beefcake::pipeline::run_pipeline_with_inputs;
+
+    let RunArgs {
+        spec: spec_args,
+        output: output_path,
+        log: log_path,
+        report: report_path,
+        fail_on_warnings,
+    } = args;
+    let spec = load_spec(&spec_args, out)?;
+    let input_path = &spec_args.input;
 
     // Execute pipeline
-    println!("Running pipeline...");
-    let report = run_pipeline(&spec, &input_path, output_path.as_ref())
-        .context("Pipeline execution failed")?;
+    say!(out, "Running pipeline...");
+    let report = run_pipeline_with_inputs(
+        &spec,
+        input_path,
+        &spec_args.sources(),
+        output_path.as_ref(),
+    )
+    .context("Pipeline execution failed")?;
 
     // Print report
-    println!();
-    println!("=== Pipeline Execution Report ===");
-    println!("{}", report.summary());
+    say!(out);
+    say!(out, "=== Pipeline Execution Report ===");
+    if let Some(idx) = report.resumed_after_step {
+        say!(out, "Resumed from the checkpoint after step {}", idx + 1);
+    }
+    say!(out, "{}", report.summary());
+    for path in &report.extra_outputs {
+        say!(out, "Also wrote: {}", path.display());
+    }
+    for delivery in report.deliveries.iter().filter(|d| d.delivered()) {
+        say!(
+            out,
+            "Delivered to: {}{}",
+            delivery.destination,
+            if delivery.verified { " (verified)" } else { "" }
+        );
+    }
+    if let Some(path) = &report.reject_path {
+        say!(
+            out,
+            "Rejected {} rows to: {}",
+            report.rows_rejected,
+            path.display()
+        );
+    }
 
     if !report.warnings.is_empty() {
-        println!();
-        println!("Warnings:");
+        say!(out);
+        say!(out, "Warnings:");
         for warning in &report.warnings {
-            println!("  - {warning}");
+            say!(out, "  - {warning}");
         }
     }
 
@@ -461,19 +1350,341 @@ async fn handle_run(
         std::fs::write(&log_path, log_content)
             .context(format!("Failed to write log file: {}", log_path.display()))?;
 
-        println!("Log written to: {}", log_path.display());
+        say!(out, "Log written to: {}", log_path.display());
     }
 
+    if let Some(report_path) = report_path {
+        std::fs::write(&report_path, report.to_json()?).context(format!(
+            "Failed to write report file: {}",
+            report_path.display()
+        ))?;
+        say!(out, "Report written to: {}", report_path.display());
+    }
+
+    out.report(&report)?;
+
     // Check fail on warnings
     if fail_on_warnings && !report.warnings.is_empty() {
-        println!();
-        println!("Pipeline completed with warnings and --fail-on-warnings is set.");
-        std::process::exit(3);
+        if !out.json {
+            eprintln!("Pipeline completed with warnings and --fail-on-warnings is set.");
+        }
+        return Ok(ExitCode::DataQuality);
+    }
+
+    say!(out);
+    say!(out, "Pipeline completed successfully!");
+
+    Ok(ExitCode::Ok)
+}
+
+/// What `pipeline validate --json` prints
+#[derive(Serialize)]
+struct ValidateReport {
+    spec: PathBuf,
+    input: PathBuf,
+    valid: bool,
+    /// Findings that stop the pipeline from running
+    errors: Vec<beefcake::pipeline::ValidationError>,
+    /// Lint findings: the pipeline runs, but probably not as intended
+    warnings: Vec<beefcake::pipeline::ValidationError>,
+}
+
+fn handle_validate(args: &SpecArgs, out: Output) -> Result<ExitCode> {
+    use beefcake::pipeline::{lint_pipeline, validate_pipeline_for_input};
+
+    let spec = load_spec(args, out)?;
+    let errors = validate_pipeline_for_input(&spec, &args.input, &args.sources())
+        .context("Failed to validate pipeline")?;
+    let report = ValidateReport {
+        spec: args.spec.clone(),
+        input: args.input.clone(),
+        valid: errors.is_empty(),
+        errors,
+        warnings: lint_pipeline(&spec),
+    };
+    out.report(&report)?;
+
+    if report.valid {
+        say!(out, "Pipeline is valid for this input.");
+        return Ok(ExitCode::Ok);
+    }
+    if !out.json {
+        eprintln!("Pipeline validation failed:");
+        for error in &report.errors {
+            match &error.suggestion {
+                Some(suggestion) => eprintln!("  - {error} ({suggestion})"),
+                None => eprintln!("  - {error}"),
+            }
+        }
+    }
+    Ok(ExitCode::Validation)
+}
+
+/// What `diff --json` prints
+#[derive(Serialize)]
+struct DiffReport {
+    #[serde(flatten)]
+    diff: beefcake::analyser::lifecycle::DiffSummary,
+    /// `--fail-on` checks that failed
+    failed: Vec<DiffCheck>,
+}
+
+fn handle_diff(
+    a: &str,
+    b: &str,
+    dataset: Option<&str>,
+    fail_on: &[DiffCheck],
+    threshold: f64,
+    out: Output,
+) -> Result<ExitCode> {
+    use beefcake::analyser::lifecycle::{DatasetRegistry, compute_file_diff};
+
+    let diff = match dataset {
+        Some(dataset) => {
+            let parse_id = |id: &str| {
+                uuid::Uuid::parse_str(id)
+                    .map_err(|e| fail(ExitCode::Validation, format!("Invalid id '{id}': {e}")))
+            };
+            let registry =
+                DatasetRegistry::new(beefcake::utils::standard_paths().base_dir.join("datasets"))?;
+            registry.compute_diff(&parse_id(dataset)?, &parse_id(a)?, &parse_id(b)?)?
+        }
+        None => {
+            for path in [a, b] {
+                if !Path::new(path).exists() {
+                    return Err(fail(ExitCode::Io, format!("File not found: {path}")));
+                }
+            }
+            compute_file_diff(Path::new(a), Path::new(b))?
+        }
+    };
+
+    say!(
+        out,
+        "Comparing {} -> {}",
+        diff.version1_id,
+        diff.version2_id
+    );
+    say!(
+        out,
+        "Rows: {} -> {}",
+        diff.row_changes.rows_v1,
+        diff.row_changes.rows_v2
+    );
+    let schema = &diff.schema_changes;
+    if !schema.columns_added.is_empty() {
+        say!(out, "Columns added: {}", schema.columns_added.join(", "));
+    }
+    if !schema.columns_removed.is_empty() {
+        say!(
+            out,
+            "Columns removed: {}",
+            schema.columns_removed.join(", ")
+        );
+    }
+    for change in &schema.type_changes {
+        say!(
+            out,
+            "Type changed: {} {} -> {}",
+            change.column,
+            change.old_type,
+            change.new_type
+        );
+    }
+    for change in &diff.statistical_changes {
+        let value = |v: Option<f64>| v.map_or_else(|| "-".to_owned(), |v| format!("{v:.4}"));
+        say!(
+            out,
+            "{} of {}: {} -> {}{}",
+            change.metric,
+            change.column,
+            value(change.value_v1),
+            value(change.value_v2),
+            change
+                .change_percent
+                .map(|pct| format!(" ({pct:+.1}%)"))
+                .unwrap_or_default()
+        );
+    }
+    say!(out, "Summary: {}", diff.summary_text());
+
+    let failed: Vec<DiffCheck> = fail_on
+        .iter()
+        .copied()
+        .filter(|check| match check {
+            DiffCheck::Schema => diff.schema_changed(),
+            DiffCheck::Distribution => !diff.distribution_shifts(threshold).is_empty(),
+        })
+        .collect();
+    let report = DiffReport { diff, failed };
+    out.report(&report)?;
+
+    if report.failed.is_empty() {
+        return Ok(ExitCode::Ok);
+    }
+    if !out.json {
+        if report.failed.contains(&DiffCheck::Schema) {
+            eprintln!("The schema changed.");
+        }
+        if report.failed.contains(&DiffCheck::Distribution) {
+            for change in report.diff.distribution_shifts(threshold) {
+                eprintln!(
+                    "The {} of {} moved by more than {threshold}%.",
+                    change.metric, change.column
+                );
+            }
+        }
+    }
+    Ok(ExitCode::DataQuality)
+}
+
+/// One line of the `watch` event log
+#[derive(Serialize)]
+struct WatchEventLine<'a> {
+    at: String,
+    #[serde(flatten)]
+    event: &'a beefcake::watcher::WatchEvent,
+}
+
+async fn handle_watch(args: WatchArgs, out: Output) -> Result<()> {
+    use beefcake::pipeline::{PipelineSpec, lint_pipeline};
+    use beefcake::watcher::{HeadlessWatch, watch_folder};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let pipeline = match &args.pipeline {
+        Some(path) => {
+            let mut spec = PipelineSpec::from_file(path)
+                .context(format!("Failed to load pipeline spec: {}", path.display()))?;
+            spec.params.extend(args.params);
+            for warning in lint_pipeline(&spec) {
+                say!(out, "Lint: {warning}");
+            }
+            Some(spec)
+        }
+        None => None,
+    };
+    if !args.folder.is_dir() {
+        return Err(fail(
+            ExitCode::Io,
+            format!("Folder not found: {}", args.folder.display()),
+        ));
     }
+    let mut events_file = args
+        .events
+        .as_ref()
+        .map(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .context(format!("Failed to open event log: {}", path.display()))
+        })
+        .transpose()?;
+
+    let watch = HeadlessWatch {
+        folder: args.folder,
+        pipeline,
+        output_dir: args.output,
+        include_existing: args.existing,
+        archive: args.archive,
+    };
+    let stop = Arc::new(AtomicBool::new(false));
+    let (done_tx, mut done_rx) = tokio::sync::oneshot::channel();
+    let watcher_stop = Arc::clone(&stop);
+    // A thread of its own, as ingesting a file starts its own runtime
+    std::thread::Builder::new()
+        .name("watch".to_owned())
+        .spawn(move || {
+            let result = watch_folder(&watch, &watcher_stop, |event| {
+                print_watch_event(event, events_file.as_mut(), out);
+            });
+            let _ = done_tx.send(result);
+        })
+        .context("Failed to start the watcher")?;
+
+    tokio::select! {
+        result = &mut done_rx => return result.context("Watcher stopped unexpectedly")?,
+        _ = tokio::signal::ctrl_c() => {
+            say!(out, "Stopping after the current file...");
+        }
+    }
+    stop.store(true, Ordering::Relaxed);
+    done_rx.await.context("Watcher stopped unexpectedly")?
+}
 
-    println!();
-    println!("Pipeline completed successfully!");
+/// Print `event` as prose, or as a JSON line with `--json`, and append it
+/// to the event log
+fn print_watch_event(
+    event: &beefcake::watcher::WatchEvent,
+    events_file: Option<&mut std::fs::File>,
+    out: Output,
+) {
+    use std::io::Write as _;
+
+    let now = chrono::Local::now();
+    if out.json || events_file.is_some() {
+        let line = WatchEventLine {
+            at: now.to_rfc3339(),
+            event,
+        };
+        match serde_json::to_string(&line) {
+            Ok(json) => {
+                if out.json {
+                    println!("{json}");
+                }
+                if let Some(file) = events_file
+                    && let Err(e) = writeln!(file, "{json}")
+                {
+                    eprintln!("Failed to write event log: {e}");
+                }
+            }
+            Err(e) => eprintln!("Failed to serialise event: {e}"),
+        }
+    }
+    if event.is_failure() && !out.json {
+        eprintln!("[{}] {event}", now.format("%H:%M:%S"));
+    } else {
+        say!(out, "[{}] {event}", now.format("%H:%M:%S"));
+    }
+}
 
+fn handle_dict_search(
+    query: &str,
+    limit: usize,
+    all_versions: bool,
+    dir: Option<PathBuf>,
+    out: Output,
+) -> Result<()> {
+    use beefcake::dictionary::{SearchOptions, default_dictionary_dir, search_dictionaries};
+
+    let dir = dir.unwrap_or_else(default_dictionary_dir);
+    let options = SearchOptions {
+        limit,
+        all_versions,
+    };
+    let hits = search_dictionaries(&dir, query, &options)
+        .with_context(|| format!("Failed to search dictionaries in {}", dir.display()))?;
+
+    if out.json {
+        return out.report(&hits);
+    }
+    if hits.is_empty() {
+        println!("No dictionary entries match '{query}'");
+        return Ok(());
+    }
+    for hit in &hits {
+        let target = hit.column.as_ref().map_or_else(
+            || hit.dataset_name.clone(),
+            |column| format!("{}.{column}", hit.dataset_name),
+        );
+        println!("{:>6.2}  {target}", hit.score);
+        // Skip the snippet when it is just the name that matched
+        let is_name = hit.column.as_ref().unwrap_or(&hit.dataset_name) == &hit.snippet;
+        if !hit.snippet.is_empty() && !is_name {
+            println!("        {}", hit.snippet);
+        }
+    }
     Ok(())
 }
 
@@ -486,4 +1697,176 @@ mod tests {
     fn verify_cli() {
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn test_global_output_flags() {
+        let cli =
+            Cli::try_parse_from(["beefcake", "dict", "search", "vat", "--json", "-q"]).unwrap();
+        assert!(cli.json && cli.quiet);
+    }
+
+    #[test]
+    fn test_pipeline_subcommands() {
+        let cli = Cli::try_parse_from([
+            "beefcake",
+            "pipeline",
+            "validate",
+            "--spec",
+            "s.json",
+            "--input",
+            "in.csv",
+            "--date",
+            "2026-01-31",
+        ])
+        .unwrap();
+        let Some(Commands::Pipeline {
+            command: PipelineCommands::Validate(args),
+        }) = cli.command
+        else {
+            panic!("expected pipeline validate");
+        };
+        assert_eq!(args.params()["date"], "2026-01-31");
+
+        // `run` and `pipeline run` take the same arguments
+        for prefix in [&["run"][..], &["pipeline", "run"]] {
+            let args = ["beefcake"].iter().chain(prefix).chain(&[
+                "--spec",
+                "s.json",
+                "--input",
+                "in.csv",
+                "--output",
+                "out.parquet",
+            ]);
+            assert!(Cli::try_parse_from(args).is_ok());
+        }
+        assert!(
+            Cli::try_parse_from(["beefcake", "pipeline", "validate", "--spec", "s.json"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_watch_output_requires_pipeline() {
+        assert!(
+            Cli::try_parse_from(["beefcake", "watch", "--folder", "in", "--output", "out"])
+                .is_err()
+        );
+        let cli = Cli::try_parse_from([
+            "beefcake",
+            "watch",
+            "--folder",
+            "in",
+            "--pipeline",
+            "spec.json",
+            "--output",
+            "out",
+            "--existing",
+        ])
+        .unwrap();
+        let Some(Commands::Watch(args)) = cli.command else {
+            panic!("expected watch");
+        };
+        assert!(args.existing && !args.archive);
+    }
+
+    #[test]
+    fn test_completions() {
+        let mut script = Vec::new();
+        clap_complete::generate(
+            clap_complete::Shell::Bash,
+            &mut Cli::command(),
+            "beefcake",
+            &mut script,
+        );
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("beefcake"));
+        assert!(script.contains("--interactive"));
+    }
+
+    #[test]
+    fn test_diff_fail_on() {
+        let cli = Cli::try_parse_from([
+            "beefcake",
+            "diff",
+            "a.csv",
+            "b.csv",
+            "--fail-on",
+            "schema,distribution",
+        ])
+        .unwrap();
+        let Some(Commands::Diff {
+            fail_on, threshold, ..
+        }) = cli.command
+        else {
+            panic!("expected diff");
+        };
+        assert_eq!(fail_on, [DiffCheck::Schema, DiffCheck::Distribution]);
+        assert!((threshold - 10.0).abs() < f64::EPSILON);
+        assert!(
+            Cli::try_parse_from(["beefcake", "diff", "a.csv", "b.csv", "--fail-on", "rows"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        use beefcake::pipeline::ContractViolations;
+        use beefcake::pipeline::contract::SchemaViolation;
+
+        let missing = std::fs::read("/no/such/beefcake/file").unwrap_err();
+        let err = anyhow::Error::from(missing).context("Failed to load input file");
+        assert_eq!(exit_code_of(&err), ExitCode::Io);
+
+        let broken = ContractViolations(vec![SchemaViolation::Missing {
+            column: "id".to_owned(),
+        }]);
+        let err = anyhow::Error::from(broken).context("Step 2 failed");
+        assert_eq!(exit_code_of(&err), ExitCode::DataQuality);
+
+        let invalid = serde_json::from_str::<HashMap<String, ColumnCleanConfig>>("{").unwrap_err();
+        let err = anyhow::Error::from(invalid).context("Failed to parse JSON config");
+        assert_eq!(exit_code_of(&err), ExitCode::Validation);
+
+        assert_eq!(exit_code_of(&fail(ExitCode::Io, "gone")), ExitCode::Io);
+        assert_eq!(exit_code_of(&anyhow::anyhow!("boom")), ExitCode::Error);
+    }
+
+    #[tokio::test]
+    async fn test_active_read_only_connection_blocks_import() {
+        let connection = |id: &str, read_only| beefcake::config::DbConnection {
+            id: id.to_owned(),
+            name: id.to_owned(),
+            settings: beefcake::config::DbSettings::default(),
+            read_only,
+        };
+        let ctx = CliContext {
+            app_config: beefcake::config::AppConfig {
+                settings: beefcake::config::AppSettings {
+                    connections: vec![connection("prod", true), connection("local", false)],
+                    ..Default::default()
+                },
+                audit_log: beefcake::config::AuditLog::new(),
+            },
+        };
+        assert!(ctx.is_read_only(None, Some("prod")));
+        assert!(!ctx.is_read_only(None, Some("local")));
+        assert!(!ctx.is_read_only(None, None));
+        assert!(!ctx.is_read_only(Some("postgres://localhost/db"), Some("prod")));
+
+        // Refused before the file is read or the database is contacted
+        let options = PushOptions {
+            read_only: ctx.is_read_only(None, Some("prod")),
+            ..Default::default()
+        };
+        let err = flows::push_to_db_flow_with_options(
+            PathBuf::from("missing.csv"),
+            PgConnectOptions::new().host("127.0.0.1").port(1),
+            "public".to_owned(),
+            "data".to_owned(),
+            HashMap::new(),
+            options,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+    }
 }
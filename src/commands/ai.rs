@@ -1,34 +1,209 @@
 use beefcake::ai::client::AIAssistant;
+use beefcake::ai::context::{AnalysisContext, ContextOptions, build_context};
+use beefcake::ai::conversation::{Conversation, ConversationStore, ConversationSummary};
 use beefcake::config::{AIConfig, load_app_config, save_app_config};
+use beefcake::error::{BeefcakeError, ErrorCode};
 
-#[tauri::command]
-pub async fn ai_send_query(query: String, context: Option<String>) -> Result<String, String> {
-    // Get API key from keyring
-    let api_key = beefcake::utils::get_ai_api_key().ok_or("AI API key not configured")?;
+use super::CommandResult;
+
+/// API key from the keyring, or an empty key for backends that need none
+fn api_key_for(ai_config: &AIConfig) -> CommandResult<String> {
+    if !ai_config.requires_api_key() {
+        return Ok(String::new());
+    }
+    beefcake::utils::get_ai_api_key()
+        .ok_or_else(|| BeefcakeError::Config("AI API key not configured".to_owned()))
+}
 
+/// `err` from the AI assistant under `msg`. Failures of no other category
+/// are AI errors; a cancelled answer stays aborted.
+fn ai_error(err: anyhow::Error, msg: &str) -> BeefcakeError {
+    match BeefcakeError::from_error(err.context(msg.to_owned())) {
+        BeefcakeError::Categorised {
+            code: ErrorCode::Internal,
+            message,
+            detail,
+        } => BeefcakeError::Categorised {
+            code: ErrorCode::Ai,
+            message,
+            detail,
+        },
+        err => err,
+    }
+}
+
+#[tauri::command]
+pub async fn ai_send_query(query: String, context: Option<String>) -> CommandResult<String> {
     // Get AI config from app settings
     let config = load_app_config();
+    let api_key = api_key_for(&config.settings().ai_config)?;
     let ai_config = config.settings().ai_config.clone();
+    let _permit = super::limits::AI
+        .acquire(&config.settings().limits.ai)
+        .await?;
 
     // Create AI assistant
     let assistant = AIAssistant::new(api_key, ai_config)
-        .map_err(|e| format!("Failed to initialize AI assistant: {e}"))?;
+        .map_err(|e| ai_error(e, "Failed to initialize AI assistant"))?
+        .with_retry_policy(config.settings().retry_policy.clone());
 
     // Send query
     assistant
         .send_query(&query, context.as_deref())
         .await
-        .map_err(|e| format!("AI Query failed: {e}"))
+        .map_err(|e| ai_error(e, "AI Query failed"))
 }
 
+#[derive(Clone, serde::Serialize)]
+struct AiTokenPayload {
+    request_id: String,
+    token: String,
+}
+
+/// Channel whose tokens are emitted as `ai:token` events tagged with
+/// `request_id`. Drop the sender and await the task to flush them.
+fn forward_tokens(
+    app: tauri::AppHandle,
+    request_id: String,
+) -> (
+    tokio::sync::mpsc::UnboundedSender<String>,
+    tokio::task::JoinHandle<()>,
+) {
+    use tauri::Emitter as _;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let forwarder = tokio::spawn(async move {
+        while let Some(token) = rx.recv().await {
+            let _ = app.emit(
+                "ai:token",
+                AiTokenPayload {
+                    request_id: request_id.clone(),
+                    token,
+                },
+            );
+        }
+    });
+    (tx, forwarder)
+}
+
+/// Like [`ai_send_query`], emitting each piece of the answer as an `ai:token`
+/// event tagged with `request_id` as it arrives. Cancelled by
+/// `abort_processing`.
 #[tauri::command]
-pub async fn ai_set_api_key(api_key: String) -> Result<(), String> {
-    beefcake::utils::set_ai_api_key(&api_key).map_err(|e| e.to_string())
+pub async fn ai_send_query_stream(
+    app: tauri::AppHandle,
+    request_id: String,
+    query: String,
+    context: Option<String>,
+) -> CommandResult<String> {
+    let config = load_app_config();
+    let api_key = api_key_for(&config.settings().ai_config)?;
+    let ai_config = config.settings().ai_config.clone();
+    let _permit = super::limits::AI
+        .acquire(&config.settings().limits.ai)
+        .await?;
+
+    let assistant = AIAssistant::new(api_key, ai_config)
+        .map_err(|e| ai_error(e, "Failed to initialize AI assistant"))?
+        .with_retry_policy(config.settings().retry_policy.clone());
+
+    beefcake::utils::reset_abort_signal();
+    let (tx, forwarder) = forward_tokens(app, request_id);
+
+    let result = assistant
+        .send_query_streaming(&query, context.as_deref(), &tx)
+        .await;
+    // Let every token reach the frontend before the final answer does
+    drop(tx);
+    let _ = forwarder.await;
+
+    result.map_err(|e| ai_error(e, "AI Query failed"))
 }
 
+/// Continue conversation `conversation_id`, or start one on `data_path`,
+/// streaming the answer as [`ai_send_query_stream`] does. Returns the
+/// conversation with the query and answer appended, as saved.
 #[tauri::command]
-pub async fn ai_delete_api_key() -> Result<(), String> {
-    beefcake::utils::delete_ai_api_key().map_err(|e| e.to_string())
+pub async fn ai_send_conversation_stream(
+    app: tauri::AppHandle,
+    request_id: String,
+    conversation_id: Option<String>,
+    data_path: Option<String>,
+    query: String,
+    context: Option<String>,
+) -> CommandResult<Conversation> {
+    let store = ConversationStore::open_default();
+    let mut conversation = match conversation_id {
+        Some(id) => store.load(&id).map_err(BeefcakeError::from_error)?,
+        None => Conversation::new(data_path),
+    };
+
+    let config = load_app_config();
+    let api_key = api_key_for(&config.settings().ai_config)?;
+    let ai_config = config.settings().ai_config.clone();
+    let _permit = super::limits::AI
+        .acquire(&config.settings().limits.ai)
+        .await?;
+
+    let assistant = AIAssistant::new(api_key, ai_config)
+        .map_err(|e| ai_error(e, "Failed to initialize AI assistant"))?
+        .with_retry_policy(config.settings().retry_policy.clone());
+
+    beefcake::utils::reset_abort_signal();
+    let (tx, forwarder) = forward_tokens(app, request_id);
+
+    let result = assistant
+        .send_conversation_streaming(&mut conversation, &query, context.as_deref(), &tx)
+        .await;
+    drop(tx);
+    let _ = forwarder.await;
+    result.map_err(|e| ai_error(e, "AI Query failed"))?;
+
+    store
+        .save(&conversation)
+        .map_err(|e| BeefcakeError::from_error(e.context("Failed to save conversation")))?;
+    Ok(conversation)
+}
+
+/// Saved conversations, most recent first, optionally only those started on
+/// `data_path`
+#[tauri::command]
+pub fn ai_list_conversations(data_path: Option<String>) -> CommandResult<Vec<ConversationSummary>> {
+    ConversationStore::open_default()
+        .list(data_path.as_deref())
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub fn ai_get_conversation(id: String) -> CommandResult<Conversation> {
+    ConversationStore::open_default()
+        .load(&id)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub fn ai_delete_conversation(id: String) -> CommandResult<bool> {
+    ConversationStore::open_default()
+        .delete(&id)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Prompt context describing `analysis`, with the configured redaction
+/// unless `options` are given
+#[tauri::command]
+pub fn ai_build_context(analysis: AnalysisContext, options: Option<ContextOptions>) -> String {
+    let options = options.unwrap_or_else(|| load_app_config().settings().ai_config.context.clone());
+    build_context(&analysis, &options)
+}
+
+#[tauri::command]
+pub async fn ai_set_api_key(api_key: String) -> CommandResult<()> {
+    beefcake::utils::set_ai_api_key(&api_key).map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn ai_delete_api_key() -> CommandResult<()> {
+    beefcake::utils::delete_ai_api_key().map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
@@ -37,23 +212,25 @@ pub fn ai_has_api_key() -> bool {
 }
 
 #[tauri::command]
-pub async fn ai_test_connection() -> Result<(), String> {
-    // Get API key from keyring
-    let api_key = beefcake::utils::get_ai_api_key().ok_or("AI API key not configured")?;
-
+pub async fn ai_test_connection() -> CommandResult<()> {
     // Get AI config from app settings
     let config = load_app_config();
+    let api_key = api_key_for(&config.settings().ai_config)?;
 
     let ai_config = config.settings().ai_config.clone();
+    let _permit = super::limits::AI
+        .acquire(&config.settings().limits.ai)
+        .await?;
 
     // Create AI assistant and test
     let assistant = AIAssistant::new(api_key, ai_config)
-        .map_err(|e| format!("Failed to initialize AI assistant: {e}"))?;
+        .map_err(|e| ai_error(e, "Failed to initialize AI assistant"))?
+        .with_retry_policy(config.settings().retry_policy.clone());
 
     assistant
         .test_connection()
         .await
-        .map_err(|e| format!("Connection test failed: {e}"))
+        .map_err(|e| ai_error(e, "Connection test failed"))
 }
 
 #[tauri::command]
@@ -64,10 +241,11 @@ pub fn ai_get_config() -> AIConfig {
 }
 
 #[tauri::command]
-pub async fn ai_update_config(ai_config: AIConfig) -> Result<(), String> {
+pub async fn ai_update_config(ai_config: AIConfig) -> CommandResult<()> {
     let mut config = load_app_config();
 
     config.settings_mut().ai_config = ai_config;
 
-    save_app_config(&config).map_err(|e| format!("Failed to save config: {e}"))
+    save_app_config(&config)
+        .map_err(|e| BeefcakeError::from_error(e.context("Failed to save config")))
 }
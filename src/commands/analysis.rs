@@ -1,20 +1,39 @@
-use beefcake::analyser::logic::flows::analyze_file_flow;
-use beefcake::analyser::logic::{AnalysisResponse, ColumnCleanConfig};
+use beefcake::analyser::db::{CleaningView, DdlMode, DdlReport};
+use beefcake::analyser::logic::flows::{analyze_file_flow_with_header, analyze_workbook_flow};
+use beefcake::analyser::logic::{
+    AnalysisResponse, AppliedPreset, CleanPreview, CleaningPreset, ColumnCleanConfig,
+    ColumnDeepDive, ColumnSummary, CorrelationMatrix, CorrelationOptions, DeepDiveOptions,
+    DeepDivePart, FeatureImportanceReport, GridPage, GridQuery, HeaderlessNaming, InPlaceMode,
+    InPlaceReport, IncrementalUpdate, PresetLibrary, PushOptions, StandardiseOptions,
+    TableDefinition, UsageLog, UsageSuggestion, ValueStandardisation, WorkbookAnalysis,
+    apply_preset, load_df_lazy_as, table_name_for_file, usage_key,
+};
+use beefcake::config::policy::{Capability, PathPolicy};
+use beefcake::config::secrets::{ConnectionBundle, ConnectionImportReport};
 use beefcake::config::{load_app_config, push_audit_log, save_app_config};
-use std::collections::HashMap;
+use beefcake::error::BeefcakeError;
+use beefcake::pipeline::PipelineSpec;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::str::FromStr as _;
+use tauri::Emitter as _;
 
+use super::CommandResult;
 use super::system::{ensure_security_acknowledged, run_on_worker_thread};
 use crate::python_runner;
 
+/// Analyse the file at `path`, as a CSV file without a header row when
+/// `header_names` is set
 #[tauri::command]
-pub async fn analyze_file(path: String) -> Result<AnalysisResponse, String> {
+pub async fn analyze_file(
+    path: String,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<AnalysisResponse> {
     tracing::info!("analyze_file command called with path: {}", path);
 
     if path.is_empty() {
         tracing::error!("analyze_file failed: path is empty");
-        return Err("File path is empty".to_owned());
+        return Err(BeefcakeError::Validation("File path is empty".to_owned()));
     }
 
     let mut path_buf = PathBuf::from(&path);
@@ -30,7 +49,7 @@ pub async fn analyze_file(path: String) -> Result<AnalysisResponse, String> {
 
     beefcake::utils::reset_abort_signal();
 
-    match analyze_file_flow(path_buf).await {
+    match analyze_file_flow_with_header(path_buf, header_names).await {
         Ok(response) => {
             tracing::info!(
                 "File analysis completed successfully: {} rows, {} columns",
@@ -41,26 +60,315 @@ pub async fn analyze_file(path: String) -> Result<AnalysisResponse, String> {
         }
         Err(e) => {
             tracing::error!("File analysis failed: {}", e);
-            Err(e.to_string())
+            Err(BeefcakeError::from_error(e))
         }
     }
 }
 
+/// Sheet names of an Excel or OpenDocument workbook, in workbook order
+#[tauri::command]
+pub async fn list_workbook_sheets(path: String) -> CommandResult<Vec<String>> {
+    beefcake::analyser::logic::list_sheets(std::path::Path::new(&path))
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Analyse every sheet of a workbook, with an overview comparing them
+#[tauri::command]
+pub async fn analyze_workbook(path: String) -> CommandResult<WorkbookAnalysis> {
+    if path.is_empty() {
+        return Err(BeefcakeError::Validation("File path is empty".to_owned()));
+    }
+
+    let mut path_buf = PathBuf::from(&path);
+    if path_buf.is_relative()
+        && let Ok(abs_path) = std::env::current_dir()
+    {
+        path_buf = abs_path.join(path_buf);
+    }
+
+    beefcake::config::log_event(
+        "Analyser",
+        &format!("Started analysis of every sheet in {}", path_buf.display()),
+    );
+    beefcake::utils::reset_abort_signal();
+
+    let workbook = analyze_workbook_flow(path_buf)
+        .await
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Analyser",
+        &format!(
+            "Analysed {} sheets ({} failed)",
+            workbook.sheets.len(),
+            workbook.failed.len()
+        ),
+    );
+    Ok(workbook)
+}
+
+#[tauri::command]
+pub async fn reanalyze_columns(
+    path: String,
+    summary: Vec<ColumnSummary>,
+    old_configs: HashMap<String, ColumnCleanConfig>,
+    new_configs: HashMap<String, ColumnCleanConfig>,
+) -> CommandResult<IncrementalUpdate> {
+    run_on_worker_thread("reanalyze-worker", move || async move {
+        let config = load_app_config();
+        let custom_sample_size = config.settings().analysis_sample_size as usize;
+        // Load the file as the analysis did
+        let lf = beefcake::analyser::logic::load_df_lazy_with_layout(
+            std::path::Path::new(&path),
+            Some(config.settings().duplicate_column_policy),
+            config.settings().layout,
+            config.settings().date_order,
+        )
+        .map_err(BeefcakeError::from_error)?
+        .lf;
+        let update = beefcake::analyser::logic::reanalyse_changed_columns(
+            lf,
+            summary,
+            old_configs,
+            new_configs,
+            0.05,
+            custom_sample_size,
+            &config.settings().resolved_analysis_options(),
+        )
+        .map_err(BeefcakeError::from_error)?;
+        tracing::info!(
+            "Incremental re-analysis recomputed {} of {} summaries",
+            update.recomputed.len(),
+            update.summary.len()
+        );
+        Ok(update)
+    })
+    .await
+}
+
+/// Clean the first `n_rows` rows of `path` with `configs` and return each
+/// configured column's values before and after
+#[tauri::command]
+pub async fn preview_clean(
+    path: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+    n_rows: Option<usize>,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<CleanPreview> {
+    run_on_worker_thread("preview-clean-worker", move || async move {
+        let lf = load_df_lazy_as(std::path::Path::new(&path), header_names.as_ref())
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::preview_clean(
+            lf,
+            &configs,
+            n_rows.unwrap_or(beefcake::analyser::logic::CLEAN_PREVIEW_ROWS),
+        )
+        .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_cleaning_presets() -> CommandResult<Vec<CleaningPreset>> {
+    PresetLibrary::open_default()
+        .list()
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Save cleaning configs as a named preset, replacing one of the same name
+#[tauri::command]
+pub async fn save_cleaning_preset(
+    name: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+) -> CommandResult<CleaningPreset> {
+    let preset = PresetLibrary::open_default()
+        .save(&name, configs)
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Analyser",
+        &format!(
+            "Saved cleaning preset '{}' ({} columns)",
+            preset.name,
+            preset.configs.len()
+        ),
+    );
+    Ok(preset)
+}
+
+#[tauri::command]
+pub async fn delete_cleaning_preset(id: String) -> CommandResult<bool> {
+    PresetLibrary::open_default()
+        .delete(&id)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Map a preset onto a file's columns by exact, then fuzzy, name matching
+#[tauri::command]
+pub async fn apply_cleaning_preset(
+    id: String,
+    columns: Vec<String>,
+) -> CommandResult<AppliedPreset> {
+    let preset = PresetLibrary::open_default()
+        .get(&id)
+        .map_err(BeefcakeError::from_error)?;
+    Ok(apply_preset(&preset, &columns))
+}
+
+/// Defaults suggested by how the columns of the file at `path` were cleaned
+/// before
+#[tauri::command]
+pub async fn column_usage_suggestions(path: String) -> CommandResult<Vec<UsageSuggestion>> {
+    let usage = UsageLog::open_default()
+        .get(&usage_key(std::path::Path::new(&path)))
+        .map_err(BeefcakeError::from_error)?;
+    Ok(usage.suggestions())
+}
+
+/// Forget the column usage of the file at `path`
+#[tauri::command]
+pub async fn clear_column_usage(path: String) -> CommandResult<bool> {
+    UsageLog::open_default()
+        .clear(&usage_key(std::path::Path::new(&path)))
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Cluster near-duplicate values of a column and propose a mapping onto one
+/// spelling per cluster. With `config`, values are clustered as its text
+/// options leave them.
+#[tauri::command]
+pub async fn suggest_value_standardisation(
+    path: String,
+    column: String,
+    config: Option<ColumnCleanConfig>,
+    options: Option<StandardiseOptions>,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<ValueStandardisation> {
+    run_on_worker_thread("standardise-worker", move || async move {
+        let lf = load_df_lazy_as(std::path::Path::new(&path), header_names.as_ref())
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::suggest_value_mapping(
+            lf,
+            &column,
+            config.as_ref(),
+            &options.unwrap_or_default(),
+        )
+        .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn compute_correlation_matrix(
+    path: String,
+    options: CorrelationOptions,
+) -> CommandResult<Option<CorrelationMatrix>> {
+    run_on_worker_thread("correlation-worker", move || async move {
+        let lf = beefcake::analyser::logic::load_df_lazy(std::path::Path::new(&path))
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::calculate_correlation_matrix_with_options(lf, &options)
+            .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+/// Rows used for feature importance; decision tree fitting is the bottleneck.
+const FEATURE_IMPORTANCE_MAX_ROWS: u32 = 100_000;
+
+#[tauri::command]
+pub async fn compute_feature_importance(
+    path: String,
+    target: String,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<FeatureImportanceReport> {
+    tracing::info!(
+        "compute_feature_importance command called for target '{}' in {}",
+        target,
+        path
+    );
+    run_on_worker_thread("feature-importance-worker", move || async move {
+        let df = load_df_lazy_as(std::path::Path::new(&path), header_names.as_ref())
+            .map_err(BeefcakeError::from_error)?
+            .limit(FEATURE_IMPORTANCE_MAX_ROWS)
+            .collect()
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::ml::feature_importance(&df, &target)
+            .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+#[derive(Clone, serde::Serialize)]
+struct DeepDivePartPayload {
+    path: String,
+    column: String,
+    #[serde(flatten)]
+    part: DeepDivePart,
+}
+
+/// One page of the rows of `path` for the data grid, filtered and sorted as
+/// `query` says
+#[tauri::command]
+pub async fn get_grid_page(
+    path: String,
+    query: GridQuery,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<GridPage> {
+    run_on_worker_thread("grid-page-worker", move || async move {
+        let lf = load_df_lazy_as(std::path::Path::new(&path), header_names.as_ref())
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::grid_page(lf, &query).map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+/// Deep dive into one column. Each part is also emitted as an
+/// `analysis:deep_dive_part` event as soon as it is ready.
+#[tauri::command]
+pub async fn column_deep_dive(
+    app: tauri::AppHandle,
+    path: String,
+    column: String,
+    options: Option<DeepDiveOptions>,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<ColumnDeepDive> {
+    beefcake::utils::reset_abort_signal();
+    run_on_worker_thread("deep-dive-worker", move || async move {
+        let lf = load_df_lazy_as(std::path::Path::new(&path), header_names.as_ref())
+            .map_err(BeefcakeError::from_error)?;
+        beefcake::analyser::logic::column_deep_dive(
+            lf,
+            &column,
+            &options.unwrap_or_default(),
+            |part| {
+                let _ = app.emit(
+                    "analysis:deep_dive_part",
+                    DeepDivePartPayload {
+                        path: path.clone(),
+                        column: column.clone(),
+                        part: part.clone(),
+                    },
+                );
+            },
+        )
+        .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
 #[tauri::command]
-pub async fn abort_processing() -> Result<(), String> {
+pub async fn abort_processing() -> CommandResult<()> {
     beefcake::config::log_event("App", "User triggered abort signal");
     beefcake::utils::trigger_abort();
     Ok(())
 }
 
 #[tauri::command]
-pub async fn reset_abort_signal() -> Result<(), String> {
+pub async fn reset_abort_signal() -> CommandResult<()> {
     beefcake::utils::reset_abort_signal();
     Ok(())
 }
 
 #[tauri::command]
-pub async fn run_powershell(script: String) -> Result<String, String> {
+pub async fn run_powershell(script: String) -> CommandResult<String> {
     tracing::info!(
         "run_powershell command called, script length: {} chars",
         script.len()
@@ -82,7 +390,7 @@ pub async fn run_powershell(script: String) -> Result<String, String> {
                 script.len(),
                 e
             );
-            Err(e.to_string())
+            Err(BeefcakeError::from_error(e))
         }
     }
 }
@@ -92,7 +400,7 @@ pub async fn run_python(
     script: String,
     data_path: Option<String>,
     configs: Option<HashMap<String, ColumnCleanConfig>>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     tracing::info!(
         "run_python command called, script length: {} chars, data_path: {:?}",
         script.len(),
@@ -103,13 +411,22 @@ pub async fn run_python(
 
     let (actual_data_path, _temp_guard) = python_runner::prepare_data(data_path, configs, "Python")
         .await
-        .map_err(|e| {
-            tracing::error!("Failed to prepare Python data: {}", e);
-            String::from(e)
-        })?;
+        .inspect_err(|e| tracing::error!("Failed to prepare Python data: {}", e))?;
 
     tracing::info!("Python data preparation complete. Executing script...");
 
+    let sandbox = load_app_config().settings.python_sandbox;
+    if sandbox.enabled {
+        return python_runner::execute_python_sandboxed(
+            &script,
+            actual_data_path,
+            &sandbox,
+            "Python",
+        )
+        .await
+        .map(|run| run.output);
+    }
+
     // _temp_guard will automatically clean up the temp file when dropped
     match python_runner::execute_python(&script, actual_data_path, "Python").await {
         Ok(output) => {
@@ -121,17 +438,35 @@ pub async fn run_python(
         }
         Err(e) => {
             tracing::error!("Python execution failed: {}", e);
-            Err(String::from(e))
+            Err(e)
         }
     }
 }
 
+/// Run a Python script in the sandbox whether or not it is enabled in the
+/// settings, using the configured limits, and report which restrictions
+/// took effect
+#[tauri::command]
+pub async fn run_python_sandboxed(
+    script: String,
+    data_path: Option<String>,
+    configs: Option<HashMap<String, ColumnCleanConfig>>,
+) -> CommandResult<python_runner::SandboxedOutput> {
+    ensure_security_acknowledged()?;
+    beefcake::config::log_event("Python", "Executed sandboxed script");
+
+    let (actual_data_path, _temp_guard) =
+        python_runner::prepare_data(data_path, configs, "Python").await?;
+    let sandbox = load_app_config().settings.python_sandbox;
+    python_runner::execute_python_sandboxed(&script, actual_data_path, &sandbox, "Python").await
+}
+
 #[tauri::command]
 pub async fn run_sql(
     query: String,
     data_path: Option<String>,
     configs: Option<HashMap<String, ColumnCleanConfig>>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     tracing::info!(
         "run_sql command called, query length: {} chars, data_path: {:?}",
         query.len(),
@@ -140,25 +475,14 @@ pub async fn run_sql(
     ensure_security_acknowledged()?;
     beefcake::config::log_event("Sql", "Executing Sql query.");
 
-    let (actual_data_path, _temp_guard) = python_runner::prepare_data(data_path, configs, "Sql")
-        .await
-        .map_err(String::from)?;
+    let (actual_data_path, _temp_guard) =
+        python_runner::prepare_data(data_path, configs, "Sql").await?;
 
     tracing::info!("Sql data preparation complete. Generating Python bridge script...");
 
     // Generate the load snippet and indent it properly for the try block
-    let load_snippet = python_runner::python_load_snippet("data_path", "df");
-    let indented_load = load_snippet
-        .lines()
-        .map(|line| {
-            if line.is_empty() {
-                line.to_owned()
-            } else {
-                format!("    {line}")
-            }
-        })
-        .collect::<Vec<_>>()
-        .join("\n");
+    let indented_load =
+        python_runner::indent_python(&python_runner::python_load_snippet("data_path", "df"));
 
     let python_script = format!(
         r#"{}
@@ -210,37 +534,103 @@ except Exception as e:
         }
         Err(e) => {
             tracing::error!("Sql execution failed: {}", e);
-            Err(String::from(e))
+            Err(e)
         }
     }
 }
 
 #[tauri::command]
-pub async fn sanitize_headers(names: Vec<String>) -> Result<Vec<String>, String> {
+pub async fn sanitize_headers(names: Vec<String>) -> CommandResult<Vec<String>> {
     Ok(beefcake::analyser::logic::sanitize_column_names(&names))
 }
 
+/// A Postgres `CREATE TABLE` statement, or with `dbt` a dbt model YAML
+/// file, for the analysed columns, or for their output when cleaned with
+/// `configs`. The table is named after the file unless `table` is given.
+#[tauri::command]
+pub async fn generate_table_ddl(
+    file_name: String,
+    summary: Vec<ColumnSummary>,
+    configs: Option<HashMap<String, ColumnCleanConfig>>,
+    table: Option<String>,
+    schema: Option<String>,
+    dbt: bool,
+) -> CommandResult<String> {
+    let table = table
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| table_name_for_file(&file_name));
+    let definition = TableDefinition::from_analysis_with_configs(
+        &summary,
+        &configs.unwrap_or_default(),
+        schema.as_deref(),
+        &table,
+    );
+    if definition.columns.is_empty() {
+        return Err(BeefcakeError::Validation(
+            "No columns to define: every column is excluded".to_owned(),
+        ));
+    }
+    Ok(if dbt {
+        definition.dbt_yaml()
+    } else {
+        definition.create_table_sql()
+    })
+}
+
+/// Clean `path` with `configs` and write the result back in the file's own
+/// format and CSV dialect, beside it or over it by `mode`. A CSV file read
+/// without a header row by `header_names` is written back without one.
+#[tauri::command]
+pub async fn clean_in_place(
+    path: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+    mode: Option<InPlaceMode>,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<InPlaceReport> {
+    let file = PathBuf::from(&path);
+    PathPolicy::load()
+        .authorise(&file, Capability::Write)
+        .map_err(BeefcakeError::from_error)?;
+    run_on_worker_thread("clean-in-place-worker", move || async move {
+        beefcake::analyser::logic::clean_in_place(
+            &file,
+            &configs,
+            &mode.unwrap_or_default(),
+            header_names.as_ref(),
+        )
+        .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
 pub async fn push_to_db_internal(
     path: String,
     connection_id: String,
     configs: HashMap<String, ColumnCleanConfig>,
-) -> Result<(), String> {
+    options: PushOptions,
+) -> CommandResult<Option<DdlReport>> {
     use sqlx::postgres::PgConnectOptions;
 
     let mut config = load_app_config();
-    let (conn_name, table_name, schema_name) = {
+    let (conn_name, table_name, schema_name, read_only) = {
         let conn = config
             .settings
             .connections
             .iter()
             .find(|c| c.id == connection_id)
-            .ok_or_else(|| "Connection not found".to_owned())?;
+            .ok_or_else(|| BeefcakeError::Config("Connection not found".to_owned()))?;
         (
             conn.name.clone(),
             conn.settings.table.clone(),
             conn.settings.schema.clone(),
+            conn.read_only,
         )
     };
+    if read_only {
+        return Err(BeefcakeError::PermissionDenied(format!(
+            "Connection '{conn_name}' is read-only: pushing data is blocked"
+        )));
+    }
 
     push_audit_log(
         &mut config,
@@ -254,31 +644,94 @@ pub async fn push_to_db_internal(
         .connections
         .iter()
         .find(|c| c.id == connection_id)
-        .ok_or_else(|| "Connection not found".to_owned())?;
+        .ok_or_else(|| BeefcakeError::Config("Connection not found".to_owned()))?;
 
     let url = conn.settings.connection_string(&connection_id);
-    let opts =
-        PgConnectOptions::from_str(&url).map_err(|e| format!("Invalid connection URL: {e}"))?;
+    let opts = PgConnectOptions::from_str(&url)
+        .map_err(|e| BeefcakeError::Config(format!("Invalid connection URL: {e}")))?;
 
-    beefcake::analyser::logic::flows::push_to_db_flow(
+    let _permit = super::limits::DB_PUSH
+        .acquire(&config.settings.limits.db_push)
+        .await?;
+    beefcake::analyser::logic::flows::push_to_db_flow_with_options(
         path.into(),
         opts,
         schema_name,
         table_name,
         configs,
+        PushOptions {
+            read_only,
+            ..options
+        },
     )
     .await
-    .map_err(|e| e.to_string())
+    .map_err(BeefcakeError::from_error)
 }
 
+/// Returns the suggested indexes and constraints when `ddl` is `suggest` or
+/// `apply`
 #[tauri::command]
 pub async fn push_to_db(
     path: String,
     connection_id: String,
     configs: HashMap<String, ColumnCleanConfig>,
-) -> Result<(), String> {
+    include_profile: Option<bool>,
+    ddl: Option<DdlMode>,
+    header_names: Option<HeaderlessNaming>,
+) -> CommandResult<Option<DdlReport>> {
+    let options = PushOptions {
+        profile: include_profile.unwrap_or(false),
+        ddl: ddl.unwrap_or_default(),
+        read_only: false,
+        header_names,
+    };
     run_on_worker_thread("db-push-worker", move || async move {
-        push_to_db_internal(path, connection_id, configs).await
+        push_to_db_internal(path, connection_id, configs, options).await
+    })
+    .await
+}
+
+/// SQL for a view that applies `configs`, `value_maps` and the steps of the
+/// pipeline in `spec_json` to the raw table of the connection. Defaults to a view named `<table>_clean`.
+#[tauri::command]
+pub async fn generate_cleaning_view(
+    path: String,
+    connection_id: String,
+    configs: HashMap<String, ColumnCleanConfig>,
+    value_maps: Option<HashMap<String, BTreeMap<String, String>>>,
+    spec_json: Option<String>,
+    view_name: Option<String>,
+) -> CommandResult<String> {
+    let config = load_app_config();
+    let conn = config
+        .settings
+        .connections
+        .iter()
+        .find(|c| c.id == connection_id)
+        .ok_or_else(|| BeefcakeError::Config("Connection not found".to_owned()))?;
+    let table = conn.settings.table.clone();
+    let schema_name = conn.settings.schema.clone();
+    let steps = match spec_json {
+        Some(json) => serde_json::from_str::<PipelineSpec>(&json)
+            .map_err(BeefcakeError::from_error)?
+            .steps
+            .into_iter()
+            .map(|s| s.step)
+            .collect(),
+        None => Vec::new(),
+    };
+    let view_name = view_name
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| format!("{table}_clean"));
+
+    run_on_worker_thread("cleaning-view-worker", move || async move {
+        let schema = beefcake::analyser::logic::load_df_lazy(std::path::Path::new(&path))
+            .and_then(|mut lf| lf.collect_schema().map_err(|e| anyhow::anyhow!(e)))
+            .map_err(BeefcakeError::from_error)?;
+        let view = CleaningView::new(&schema, Some(&schema_name), &table, &view_name)
+            .with_configs(&configs, &value_maps.unwrap_or_default())
+            .with_steps(&steps);
+        Ok(view.sql())
     })
     .await
 }
@@ -287,7 +740,7 @@ pub async fn push_to_db(
 pub async fn test_connection(
     settings: beefcake::config::DbSettings,
     connection_id: Option<String>,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     use secrecy::ExposeSecret as _;
     let pwd = settings.password.expose_secret();
 
@@ -302,23 +755,93 @@ pub async fn test_connection(
 
     beefcake::analyser::logic::flows::test_connection_flow(settings, actual_pwd)
         .await
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn delete_connection(id: String) -> Result<(), String> {
+pub async fn delete_connection(id: String) -> CommandResult<()> {
     let mut config = load_app_config();
     config.settings.connections.retain(|c| c.id != id);
     let _ = beefcake::utils::delete_db_password(&id);
-    save_app_config(&config).map_err(|e| e.to_string())
+    save_app_config(&config).map_err(BeefcakeError::from_error)
+}
+
+/// Write the saved connections (all, or those in `connection_ids`) to
+/// `path` with their passwords encrypted under `passphrase`. Returns the
+/// number of connections written.
+#[tauri::command]
+pub async fn export_connections(
+    path: String,
+    passphrase: String,
+    connection_ids: Option<Vec<String>>,
+) -> CommandResult<usize> {
+    let mut config = load_app_config();
+    let selected: Vec<_> = config
+        .settings
+        .connections
+        .iter()
+        .filter(|c| {
+            connection_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&c.id))
+        })
+        .cloned()
+        .collect();
+    let bundle = beefcake::config::secrets::export_connections(&selected, &passphrase)
+        .map_err(BeefcakeError::from_error)?;
+    let json = serde_json::to_string_pretty(&bundle).map_err(BeefcakeError::from_error)?;
+    std::fs::write(&path, json).map_err(BeefcakeError::from_error)?;
+
+    push_audit_log(
+        &mut config,
+        "Database",
+        &format!("Exported {} connections to {path}", selected.len()),
+    );
+    let _ = save_app_config(&config).ok();
+    Ok(selected.len())
+}
+
+/// Import connections exported with [`export_connections`], storing their
+/// passwords in this machine's keyring
+#[tauri::command]
+pub async fn import_connections(
+    path: String,
+    passphrase: String,
+    replace_existing: Option<bool>,
+) -> CommandResult<ConnectionImportReport> {
+    let json = std::fs::read_to_string(&path).map_err(BeefcakeError::from_error)?;
+    let bundle: ConnectionBundle = serde_json::from_str(&json)
+        .map_err(|e| BeefcakeError::Parse(format!("Not a connection export: {e}")))?;
+
+    let mut config = load_app_config();
+    let report = beefcake::config::secrets::import_connections(
+        &mut config.settings.connections,
+        &bundle,
+        &passphrase,
+        replace_existing.unwrap_or(false),
+    )
+    .map_err(BeefcakeError::from_error)?;
+
+    push_audit_log(
+        &mut config,
+        "Database",
+        &format!(
+            "Imported connections from {path}: {} added, {} replaced, {} skipped",
+            report.added.len(),
+            report.replaced.len(),
+            report.skipped.len()
+        ),
+    );
+    save_app_config(&config).map_err(BeefcakeError::from_error)?;
+    Ok(report)
 }
 
 #[tauri::command]
-pub async fn install_python_package(package: String) -> Result<String, String> {
-    crate::system::install_python_package(&package).map_err(|e| e.to_string())
+pub async fn install_python_package(package: String) -> CommandResult<String> {
+    crate::system::install_python_package(&package).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn check_python_environment() -> Result<String, String> {
-    crate::system::check_python_environment().map_err(|e| e.to_string())
+pub async fn check_python_environment() -> CommandResult<String> {
+    crate::system::check_python_environment().map_err(BeefcakeError::from_error)
 }
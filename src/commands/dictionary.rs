@@ -1,24 +1,146 @@
+use beefcake::analyser::logic::{ColumnSummary, FileHealth};
 use beefcake::dictionary::storage::SnapshotMetadata;
-use beefcake::dictionary::{DataDictionary, list_snapshots, load_snapshot, save_snapshot};
-use std::path::PathBuf;
+use beefcake::dictionary::template::TemplateMergeReport;
+use beefcake::dictionary::{
+    DataDictionary, DictionaryStorageUsage, Glossary, GlossaryTerm, PruneReport, ReviewStatus,
+    SearchHit, SearchOptions, SnapshotRetention, default_dictionary_dir, list_snapshots,
+    load_snapshot, save_snapshot,
+};
+use beefcake::error::BeefcakeError;
+use std::path::{Path, PathBuf};
+
+use super::CommandResult;
 
 fn get_dictionary_dir() -> PathBuf {
-    beefcake::utils::standard_paths()
-        .base_dir
-        .join("dictionaries")
+    default_dictionary_dir()
 }
 
 #[tauri::command]
-pub async fn dictionary_load_snapshot(snapshot_id: String) -> Result<DataDictionary, String> {
-    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(|e| e.to_string())?;
-    load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(|e| e.to_string())
+pub async fn dictionary_load_snapshot(snapshot_id: String) -> CommandResult<DataDictionary> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
 pub async fn dictionary_list_snapshots(
     dataset_hash: Option<String>,
-) -> Result<Vec<SnapshotMetadata>, String> {
-    list_snapshots(&get_dictionary_dir(), dataset_hash.as_deref()).map_err(|e| e.to_string())
+) -> CommandResult<Vec<SnapshotMetadata>> {
+    list_snapshots(&get_dictionary_dir(), dataset_hash.as_deref())
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Disk used by dictionary snapshots, per dataset
+#[tauri::command]
+pub async fn dictionary_storage_usage() -> CommandResult<DictionaryStorageUsage> {
+    beefcake::dictionary::storage_usage(&get_dictionary_dir()).map_err(BeefcakeError::from_error)
+}
+
+/// Compact dictionary snapshots and apply a retention policy, the one in the
+/// settings unless `policy` is given
+#[tauri::command]
+pub async fn dictionary_prune_snapshots(
+    policy: Option<SnapshotRetention>,
+) -> CommandResult<PruneReport> {
+    let policy = policy.unwrap_or_else(SnapshotRetention::from_settings);
+    let report = beefcake::dictionary::prune_snapshots(&get_dictionary_dir(), &policy)
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Dictionary",
+        &format!(
+            "Pruned {} dictionary snapshots ({} duplicates), {} remain",
+            report.removed(),
+            report.duplicates_removed.len(),
+            report.remaining
+        ),
+    );
+    Ok(report)
+}
+
+/// Datasets and columns whose names, descriptions, tags or glossary terms
+/// match `query`, best first
+#[tauri::command]
+pub async fn dictionary_search(
+    query: String,
+    options: Option<SearchOptions>,
+) -> CommandResult<Vec<SearchHit>> {
+    beefcake::dictionary::search_dictionaries(
+        &get_dictionary_dir(),
+        &query,
+        &options.unwrap_or_default(),
+    )
+    .map_err(BeefcakeError::from_error)
+}
+
+/// Review state of every dataset with a review cadence, most overdue first.
+/// With `overdue_only`, just the datasets due for review; with `notify`, each
+/// of those is also recorded in the audit log.
+#[tauri::command]
+pub async fn dictionary_review_schedule(
+    overdue_only: Option<bool>,
+    notify: Option<bool>,
+) -> CommandResult<Vec<ReviewStatus>> {
+    let today = chrono::Local::now().date_naive();
+    let mut schedule = beefcake::dictionary::review_schedule(&get_dictionary_dir(), today)
+        .map_err(BeefcakeError::from_error)?;
+    if overdue_only.unwrap_or(false) {
+        schedule.retain(ReviewStatus::is_due);
+    }
+    if notify.unwrap_or(false) {
+        for status in schedule.iter().filter(|s| s.is_due()) {
+            beefcake::config::log_event(
+                "Dictionary",
+                &format!(
+                    "{} is due for review since {} (owner: {})",
+                    status.dataset_name,
+                    status.due,
+                    status.owner.as_deref().unwrap_or("none")
+                ),
+            );
+        }
+    }
+    Ok(schedule)
+}
+
+/// Record a review of a dataset, today unless `reviewed_on` (YYYY-MM-DD) is
+/// given. Saves and returns a new version of the snapshot.
+#[tauri::command]
+pub async fn dictionary_mark_reviewed(
+    snapshot_id: String,
+    reviewed_on: Option<String>,
+) -> CommandResult<DataDictionary> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let reviewed_on = match reviewed_on {
+        Some(date) => date
+            .parse::<chrono::NaiveDate>()
+            .map_err(|e| BeefcakeError::Parse(format!("Invalid review date '{date}': {e}")))?,
+        None => chrono::Local::now().date_naive(),
+    };
+    beefcake::dictionary::mark_reviewed(&snapshot_id, &get_dictionary_dir(), reviewed_on)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// The parts of an `AnalysisResponse` a snapshot is built from
+#[derive(serde::Deserialize)]
+pub struct CreateFromAnalysisRequest {
+    pub path: String,
+    pub summary: Vec<ColumnSummary>,
+    pub total_row_count: usize,
+    pub health: FileHealth,
+}
+
+/// Save a baseline dictionary snapshot of an analysed file
+#[tauri::command]
+pub async fn dictionary_create_from_analysis(
+    request: CreateFromAnalysisRequest,
+) -> CommandResult<DataDictionary> {
+    beefcake::dictionary::save_analysis_snapshot(
+        Path::new(&request.path),
+        &request.summary,
+        request.total_row_count,
+        &request.health,
+        &get_dictionary_dir(),
+    )
+    .map_err(BeefcakeError::from_error)
 }
 
 #[derive(serde::Deserialize)]
@@ -32,11 +154,13 @@ pub struct UpdateBusinessMetadataRequest {
 #[tauri::command]
 pub async fn dictionary_update_business_metadata(
     request: UpdateBusinessMetadataRequest,
-) -> Result<String, String> {
-    let snapshot_id = uuid::Uuid::parse_str(&request.snapshot_id).map_err(|e| e.to_string())?;
+) -> CommandResult<String> {
+    let snapshot_id =
+        uuid::Uuid::parse_str(&request.snapshot_id).map_err(BeefcakeError::from_error)?;
     let dictionary_dir = get_dictionary_dir();
 
-    let mut dictionary = load_snapshot(&snapshot_id, &dictionary_dir).map_err(|e| e.to_string())?;
+    let mut dictionary =
+        load_snapshot(&snapshot_id, &dictionary_dir).map_err(BeefcakeError::from_error)?;
 
     if let Some(col) = dictionary
         .columns
@@ -50,13 +174,13 @@ pub async fn dictionary_update_business_metadata(
             col.business.notes = Some(format!("Data Owner: {owner}"));
         }
     } else {
-        return Err(format!(
+        return Err(BeefcakeError::Validation(format!(
             "Column '{}' not found in dictionary",
             request.column_name
-        ));
+        )));
     }
 
-    save_snapshot(&dictionary, &dictionary_dir).map_err(|e| e.to_string())?;
+    save_snapshot(&dictionary, &dictionary_dir).map_err(BeefcakeError::from_error)?;
 
     Ok("Metadata updated successfully".to_owned())
 }
@@ -65,11 +189,145 @@ pub async fn dictionary_update_business_metadata(
 pub async fn dictionary_export_markdown(
     snapshot_id: String,
     output_path: String,
-) -> Result<(), String> {
-    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(|e| e.to_string())?;
+) -> CommandResult<()> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let dictionary =
+        load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let glossary = Glossary::load(&get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let markdown = beefcake::dictionary::render_markdown_with_glossary(&dictionary, &glossary)
+        .map_err(BeefcakeError::from_error)?;
+    std::fs::write(output_path, markdown).map_err(BeefcakeError::from_error)
+}
+
+/// Export a snapshot as a self-contained HTML page
+#[tauri::command]
+pub async fn dictionary_export_html(snapshot_id: String, output_path: String) -> CommandResult<()> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let dictionary =
+        load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let glossary = Glossary::load(&get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let html = beefcake::dictionary::render_html_with_glossary(&dictionary, &glossary)
+        .map_err(BeefcakeError::from_error)?;
+    std::fs::write(output_path, html).map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn dictionary_export_pdf(snapshot_id: String, output_path: String) -> CommandResult<()> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
     let dictionary =
-        load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(|e| e.to_string())?;
+        load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let pdf = beefcake::dictionary::render_pdf(&dictionary).map_err(BeefcakeError::from_error)?;
+    std::fs::write(output_path, pdf).map_err(BeefcakeError::from_error)
+}
+
+/// Write a CSV template of the snapshot's column business metadata, blank
+/// unless `filled`
+#[tauri::command]
+pub async fn dictionary_export_template(
+    snapshot_id: String,
+    output_path: String,
+    filled: Option<bool>,
+) -> CommandResult<()> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let dictionary =
+        load_snapshot(&snapshot_id, &get_dictionary_dir()).map_err(BeefcakeError::from_error)?;
+
+    let csv = beefcake::dictionary::render_template(&dictionary, filled.unwrap_or(false));
+    std::fs::write(output_path, csv).map_err(BeefcakeError::from_error)
+}
+
+/// Merge an edited CSV template into a new version of the snapshot
+#[tauri::command]
+pub async fn dictionary_import_template(
+    snapshot_id: String,
+    input_path: String,
+) -> CommandResult<TemplateMergeReport> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let csv = std::fs::read_to_string(&input_path).map_err(BeefcakeError::from_error)?;
+
+    beefcake::dictionary::import_template(&snapshot_id, &get_dictionary_dir(), &csv)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn dictionary_list_glossary() -> CommandResult<Vec<GlossaryTerm>> {
+    Glossary::load(&get_dictionary_dir())
+        .map(|g| g.terms)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Add a glossary term, or update the term with the same id
+#[tauri::command]
+pub async fn dictionary_save_glossary_term(term: GlossaryTerm) -> CommandResult<GlossaryTerm> {
+    let dictionary_dir = get_dictionary_dir();
+    let mut glossary = Glossary::load(&dictionary_dir).map_err(BeefcakeError::from_error)?;
+    let saved = glossary
+        .upsert(term)
+        .map_err(BeefcakeError::from_error)?
+        .clone();
+    glossary
+        .save(&dictionary_dir)
+        .map_err(BeefcakeError::from_error)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn dictionary_delete_glossary_term(term_id: String) -> CommandResult<bool> {
+    let term_id = uuid::Uuid::parse_str(&term_id).map_err(BeefcakeError::from_error)?;
+    let dictionary_dir = get_dictionary_dir();
+    let mut glossary = Glossary::load(&dictionary_dir).map_err(BeefcakeError::from_error)?;
+    let removed = glossary.remove(&term_id);
+    if removed {
+        glossary
+            .save(&dictionary_dir)
+            .map_err(BeefcakeError::from_error)?;
+    }
+    Ok(removed)
+}
+
+/// Set the glossary terms a column of the snapshot links to, replacing its
+/// current links
+#[tauri::command]
+pub async fn dictionary_link_glossary_terms(
+    snapshot_id: String,
+    column_name: String,
+    term_ids: Vec<String>,
+) -> CommandResult<()> {
+    let snapshot_id = uuid::Uuid::parse_str(&snapshot_id).map_err(BeefcakeError::from_error)?;
+    let dictionary_dir = get_dictionary_dir();
+    let glossary = Glossary::load(&dictionary_dir).map_err(BeefcakeError::from_error)?;
+
+    let mut term_uuids = Vec::with_capacity(term_ids.len());
+    for id in &term_ids {
+        let id = uuid::Uuid::parse_str(id).map_err(BeefcakeError::from_error)?;
+        if glossary.get(&id).is_none() {
+            return Err(BeefcakeError::Validation(format!(
+                "Glossary term '{id}' not found"
+            )));
+        }
+        if !term_uuids.contains(&id) {
+            term_uuids.push(id);
+        }
+    }
+
+    let mut dictionary =
+        load_snapshot(&snapshot_id, &dictionary_dir).map_err(BeefcakeError::from_error)?;
+    let Some(col) = dictionary
+        .columns
+        .iter_mut()
+        .find(|c| c.current_name == column_name)
+    else {
+        return Err(BeefcakeError::Validation(format!(
+            "Column '{column_name}' not found in dictionary"
+        )));
+    };
+    col.business.glossary_terms = term_uuids;
 
-    let markdown = beefcake::dictionary::render_markdown(&dictionary).map_err(|e| e.to_string())?;
-    std::fs::write(output_path, markdown).map_err(|e| e.to_string())
+    save_snapshot(&dictionary, &dictionary_dir).map_err(BeefcakeError::from_error)?;
+    Ok(())
 }
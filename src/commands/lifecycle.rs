@@ -1,13 +1,22 @@
 use beefcake::analyser::lifecycle::transforms::{TransformPipeline, TransformSpec};
-use beefcake::analyser::lifecycle::{DatasetRegistry, LifecycleStage};
+use beefcake::analyser::lifecycle::{
+    ApprovalState, DatasetRegistry, DatasetVersion, LifecycleStage, LineageGraph, PublishMode,
+    VersionFilter, VersionSearchHit, ViewerOptions,
+};
+use beefcake::analyser::logic::{
+    ColumnCleanConfig, HeaderlessNaming, UsageLog, load_df_lazy_headerless, usage_key,
+};
+use beefcake::error::BeefcakeError;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use super::CommandResult;
 use super::system::run_on_worker_thread;
 
 static REGISTRY: std::sync::OnceLock<Arc<DatasetRegistry>> = std::sync::OnceLock::new();
 
-pub fn get_or_create_registry() -> Result<Arc<DatasetRegistry>, String> {
+pub fn get_or_create_registry() -> CommandResult<Arc<DatasetRegistry>> {
     if let Some(registry) = REGISTRY.get() {
         Ok(registry.clone())
     } else {
@@ -15,14 +24,15 @@ pub fn get_or_create_registry() -> Result<Arc<DatasetRegistry>, String> {
         let registry_path = paths.base_dir.join("datasets");
 
         if !registry_path.exists() {
-            std::fs::create_dir_all(&registry_path).map_err(|e| e.to_string())?;
+            std::fs::create_dir_all(&registry_path).map_err(BeefcakeError::from_error)?;
         }
 
-        let registry = DatasetRegistry::new(registry_path).map_err(|e| e.to_string())?;
+        let registry = DatasetRegistry::new(registry_path).map_err(BeefcakeError::from_error)?;
         let registry = Arc::new(registry);
         REGISTRY
             .set(registry.clone())
             .map_err(|_err| "Failed to set registry".to_owned())?;
+        beefcake::watcher::set_lifecycle_registry(Arc::clone(&registry));
         Ok(registry)
     }
 }
@@ -31,14 +41,22 @@ pub fn get_or_create_registry() -> Result<Arc<DatasetRegistry>, String> {
 pub struct CreateDatasetRequest {
     pub name: String,
     pub source_path: String,
+    /// Set when the source is a CSV file read without a header row; its
+    /// data is then stored instead of referenced
+    #[serde(default)]
+    pub header_names: Option<HeaderlessNaming>,
 }
 
 #[tauri::command]
-pub async fn lifecycle_create_dataset(request: CreateDatasetRequest) -> Result<String, String> {
+pub async fn lifecycle_create_dataset(request: CreateDatasetRequest) -> CommandResult<String> {
     let registry = get_or_create_registry()?;
-    let dataset_id = registry
-        .create_dataset(request.name, PathBuf::from(request.source_path))
-        .map_err(|e| e.to_string())?;
+    let source = PathBuf::from(request.source_path);
+    let dataset_id = match &request.header_names {
+        Some(naming) => load_df_lazy_headerless(&source, naming)
+            .and_then(|lf| registry.create_dataset_from_data(request.name, &lf)),
+        None => registry.create_dataset(request.name, source),
+    }
+    .map_err(BeefcakeError::from_error)?;
     Ok(dataset_id.to_string())
 }
 
@@ -50,20 +68,53 @@ pub struct ApplyTransformsRequest {
 }
 
 #[tauri::command]
-pub async fn lifecycle_apply_transforms(request: ApplyTransformsRequest) -> Result<String, String> {
+pub async fn lifecycle_apply_transforms(request: ApplyTransformsRequest) -> CommandResult<String> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
 
     run_on_worker_thread("lifecycle-worker", move || async move {
+        let cleans: Vec<HashMap<String, ColumnCleanConfig>> = request
+            .transforms
+            .iter()
+            .filter(|spec| spec.transform_type == "clean")
+            .filter_map(|spec| spec.parameters.get("configs"))
+            .filter_map(|configs| serde_json::from_value(configs.clone()).ok())
+            .collect();
         let pipeline = TransformPipeline::new(request.transforms);
         let new_version_id = registry
             .apply_transforms(&dataset_id, pipeline, request.next_stage)
-            .map_err(|e: anyhow::Error| e.to_string())?;
+            .map_err(BeefcakeError::from_error)?;
+        for configs in &cleans {
+            record_column_usage(&registry, &dataset_id, configs);
+        }
         Ok(new_version_id.to_string())
     })
     .await
 }
 
+/// Count a clean made with `configs` in the column usage of the dataset's
+/// source file. Usage only shapes suggestions, so failures are logged
+/// rather than returned.
+fn record_column_usage(
+    registry: &DatasetRegistry,
+    dataset_id: &uuid::Uuid,
+    configs: &HashMap<String, ColumnCleanConfig>,
+) {
+    let recorded = registry.get_dataset(dataset_id).and_then(|dataset| {
+        let key = dataset
+            .source_path()
+            .map_or_else(|| dataset.name.clone(), usage_key);
+        UsageLog::open_default().record(&key, configs)
+    });
+    if let Err(e) = recorded {
+        beefcake::config::log_event(
+            "Lifecycle",
+            &format!("Failed to record column usage: {e:#}"),
+        );
+    }
+}
+
 #[derive(serde::Deserialize)]
 pub struct SetActiveVersionRequest {
     pub dataset_id: String,
@@ -71,41 +122,94 @@ pub struct SetActiveVersionRequest {
 }
 
 #[tauri::command]
-pub async fn lifecycle_set_active_version(request: SetActiveVersionRequest) -> Result<(), String> {
+pub async fn lifecycle_set_active_version(request: SetActiveVersionRequest) -> CommandResult<()> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
-    let version_id = uuid::Uuid::parse_str(&request.version_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
 
     registry
         .set_active_version(&dataset_id, &version_id)
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
 }
 
 #[derive(serde::Deserialize)]
 pub struct PublishVersionRequest {
     pub dataset_id: String,
     pub version_id: String,
+    #[serde(default)]
+    pub mode: Option<PublishMode>,
+    /// Who is publishing; defaults to the signed-in OS user
+    #[serde(default)]
+    pub actor: Option<String>,
 }
 
 #[tauri::command]
-pub async fn lifecycle_publish_version(request: PublishVersionRequest) -> Result<String, String> {
+pub async fn lifecycle_publish_version(request: PublishVersionRequest) -> CommandResult<String> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
-    let version_id = uuid::Uuid::parse_str(&request.version_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
 
     run_on_worker_thread("publish-worker", move || async move {
         let published_version_id = registry
-            .publish_version(
+            .publish_version_as(
                 &dataset_id,
                 &version_id,
-                beefcake::analyser::lifecycle::PublishMode::Snapshot,
+                request.mode.unwrap_or(PublishMode::Snapshot),
+                &actor_or_current_user(request.actor),
             )
-            .map_err(|e| e.to_string())?;
+            .map_err(BeefcakeError::from_error)?;
         Ok(published_version_id.to_string())
     })
     .await
 }
 
+#[derive(serde::Deserialize)]
+pub struct TransitionApprovalRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+    pub state: ApprovalState,
+    #[serde(default)]
+    pub actor: Option<String>,
+    #[serde(default)]
+    pub comment: String,
+}
+
+/// Submit a version for review, approve it, or send it back to draft
+#[tauri::command]
+pub async fn lifecycle_transition_approval(
+    request: TransitionApprovalRequest,
+) -> CommandResult<DatasetVersion> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .transition_approval(
+            &dataset_id,
+            &version_id,
+            request.state,
+            &actor_or_current_user(request.actor),
+            &request.comment,
+        )
+        .map_err(BeefcakeError::from_error)
+}
+
+/// `actor` if given, else the OS user name
+fn actor_or_current_user(actor: Option<String>) -> String {
+    actor
+        .map(|a| a.trim().to_owned())
+        .filter(|a| !a.is_empty())
+        .or_else(|| std::env::var("USERNAME").ok())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
 #[derive(serde::Deserialize)]
 pub struct GetVersionDiffRequest {
     pub dataset_id: String,
@@ -116,34 +220,294 @@ pub struct GetVersionDiffRequest {
 #[tauri::command]
 pub async fn lifecycle_get_version_diff(
     request: GetVersionDiffRequest,
-) -> Result<beefcake::analyser::lifecycle::DiffSummary, String> {
+) -> CommandResult<beefcake::analyser::lifecycle::DiffSummary> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
-    let from_id = uuid::Uuid::parse_str(&request.from_version_id).map_err(|e| e.to_string())?;
-    let to_id = uuid::Uuid::parse_str(&request.to_version_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let from_id =
+        uuid::Uuid::parse_str(&request.from_version_id).map_err(BeefcakeError::from_error)?;
+    let to_id = uuid::Uuid::parse_str(&request.to_version_id).map_err(BeefcakeError::from_error)?;
 
     registry
         .compute_diff(&dataset_id, &from_id, &to_id)
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
 }
 
 #[derive(serde::Deserialize)]
 pub struct ListVersionsRequest {
     pub dataset_id: String,
+    /// Only versions matching this filter, newest first
+    #[serde(default)]
+    pub filter: Option<VersionFilter>,
 }
 
 #[tauri::command]
-pub async fn lifecycle_list_versions(request: ListVersionsRequest) -> Result<String, String> {
+pub async fn lifecycle_list_versions(request: ListVersionsRequest) -> CommandResult<String> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+
+    if let Some(filter) = request.filter {
+        let versions = registry
+            .list_versions_filtered(&dataset_id, &filter)
+            .map_err(BeefcakeError::from_error)?;
+        return serde_json::to_string(&versions).map_err(BeefcakeError::from_error);
+    }
 
     let dataset = registry
         .get_dataset(&dataset_id)
-        .map_err(|e| e.to_string())?;
+        .map_err(BeefcakeError::from_error)?;
 
     // Return array of versions, not the entire dataset
     let versions = dataset.versions.list_all();
-    serde_json::to_string(&versions).map_err(|e| e.to_string())
+    serde_json::to_string(&versions).map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteVersionRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+}
+
+#[tauri::command]
+pub async fn lifecycle_delete_version(request: DeleteVersionRequest) -> CommandResult<()> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .delete_version(&dataset_id, &version_id)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RollbackRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+}
+
+/// Returns the ids of the versions deleted by the rollback
+#[tauri::command]
+pub async fn lifecycle_rollback_to(request: RollbackRequest) -> CommandResult<Vec<String>> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    let deleted = registry
+        .rollback_to(&dataset_id, &version_id)
+        .map_err(BeefcakeError::from_error)?;
+    Ok(deleted.iter().map(ToString::to_string).collect())
+}
+
+#[tauri::command]
+pub async fn lifecycle_collect_garbage()
+-> CommandResult<beefcake::analyser::lifecycle::GarbageReport> {
+    let registry = get_or_create_registry()?;
+    registry
+        .collect_garbage()
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct RefreshDatasetRequest {
+    pub dataset_id: String,
+}
+
+/// Re-read the dataset's source file and re-apply the pipelines leading to
+/// its active version
+#[tauri::command]
+pub async fn lifecycle_refresh_dataset(
+    request: RefreshDatasetRequest,
+) -> CommandResult<beefcake::analyser::lifecycle::RefreshReport> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .refresh_dataset(&dataset_id)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct SetSourcePatternRequest {
+    pub dataset_id: String,
+    /// File pattern such as `sales_*.csv`; `None` or blank clears it
+    pub pattern: Option<String>,
+}
+
+/// Set the pattern of new files the watcher adds as raw versions of the
+/// dataset
+#[tauri::command]
+pub async fn lifecycle_set_source_pattern(request: SetSourcePatternRequest) -> CommandResult<()> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .set_source_pattern(&dataset_id, request.pattern)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct TagVersionRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+    pub tag: String,
+}
+
+#[tauri::command]
+pub async fn lifecycle_tag_version(request: TagVersionRequest) -> CommandResult<DatasetVersion> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .tag_version(&dataset_id, &version_id, &request.tag)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn lifecycle_untag_version(request: TagVersionRequest) -> CommandResult<DatasetVersion> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .untag_version(&dataset_id, &version_id, &request.tag)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct AnnotateVersionRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+    pub notes: String,
+}
+
+#[tauri::command]
+pub async fn lifecycle_annotate_version(
+    request: AnnotateVersionRequest,
+) -> CommandResult<DatasetVersion> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    registry
+        .annotate_version(&dataset_id, &version_id, &request.notes)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Versions of every dataset matching the filter, newest first
+#[tauri::command]
+pub async fn lifecycle_search_versions(
+    filter: VersionFilter,
+) -> CommandResult<Vec<VersionSearchHit>> {
+    let registry = get_or_create_registry()?;
+    registry
+        .search_versions(&filter)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct StorageUsageRequest {
+    pub dataset_id: String,
+}
+
+#[tauri::command]
+pub async fn lifecycle_storage_usage(
+    request: StorageUsageRequest,
+) -> CommandResult<beefcake::analyser::lifecycle::StorageUsage> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    registry
+        .storage_usage(&dataset_id)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[derive(serde::Deserialize)]
+pub struct LineageRequest {
+    pub dataset_id: String,
+}
+
+#[tauri::command]
+pub async fn lifecycle_get_lineage(request: LineageRequest) -> CommandResult<LineageGraph> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    registry
+        .lineage(&dataset_id)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// The lineage graph in Graphviz DOT format
+#[tauri::command]
+pub async fn lifecycle_get_lineage_dot(request: LineageRequest) -> CommandResult<String> {
+    lifecycle_get_lineage(request)
+        .await
+        .map(|graph| graph.to_dot())
+}
+
+#[derive(serde::Deserialize)]
+pub struct ExportViewerRequest {
+    pub dataset_id: String,
+    pub version_id: String,
+    pub output_path: String,
+    #[serde(default)]
+    pub options: ViewerOptions,
+}
+
+/// Write a standalone HTML viewer of a version, documented from the newest
+/// dictionary snapshot of the dataset's source file
+#[tauri::command]
+pub async fn lifecycle_export_viewer(request: ExportViewerRequest) -> CommandResult<()> {
+    let registry = get_or_create_registry()?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
+
+    run_on_worker_thread("viewer-export-worker", move || async move {
+        let dataset = registry
+            .get_dataset(&dataset_id)
+            .map_err(BeefcakeError::from_error)?;
+        let dictionary = match dataset.source_path() {
+            Some(source) => beefcake::dictionary::latest_snapshot_for_input(
+                &beefcake::dictionary::default_dictionary_dir(),
+                source,
+            )
+            .map_err(BeefcakeError::from_error)?,
+            None => None,
+        };
+        let html = registry
+            .render_viewer(
+                &dataset_id,
+                &version_id,
+                dictionary.as_ref(),
+                &request.options,
+            )
+            .map_err(BeefcakeError::from_error)?;
+        std::fs::write(&request.output_path, html).map_err(BeefcakeError::from_error)?;
+        beefcake::config::log_event(
+            "Lifecycle",
+            &format!(
+                "Exported viewer of version {version_id} to {}",
+                request.output_path
+            ),
+        );
+        Ok(())
+    })
+    .await
 }
 
 #[derive(serde::Serialize)]
@@ -161,21 +525,22 @@ pub struct GetVersionSchemaRequest {
 #[tauri::command]
 pub async fn lifecycle_get_version_schema(
     request: GetVersionSchemaRequest,
-) -> Result<Vec<ColumnInfo>, String> {
+) -> CommandResult<Vec<ColumnInfo>> {
     let registry = get_or_create_registry()?;
-    let dataset_id = uuid::Uuid::parse_str(&request.dataset_id).map_err(|e| e.to_string())?;
-    let version_id = uuid::Uuid::parse_str(&request.version_id).map_err(|e| e.to_string())?;
+    let dataset_id =
+        uuid::Uuid::parse_str(&request.dataset_id).map_err(BeefcakeError::from_error)?;
+    let version_id =
+        uuid::Uuid::parse_str(&request.version_id).map_err(BeefcakeError::from_error)?;
 
     let version = registry
         .get_version(&dataset_id, &version_id)
-        .map_err(|e| e.to_string())?;
+        .map_err(BeefcakeError::from_error)?;
 
     // Load actual schema from the version's data path
-    let mut lf =
-        polars::prelude::LazyFrame::scan_parquet(version.data_location.path(), Default::default())
-            .map_err(|e| e.to_string())?;
+    let mut lf = beefcake::analyser::logic::load_df_lazy(version.data_location.path())
+        .map_err(BeefcakeError::from_error)?;
 
-    let schema = lf.collect_schema().map_err(|e| e.to_string())?;
+    let schema = lf.collect_schema().map_err(BeefcakeError::from_error)?;
 
     let columns = schema
         .iter()
@@ -0,0 +1,194 @@
+//! Concurrency caps and per-minute rate limits for AI requests and database
+//! pushes, so a batch of commands cannot flood the OpenAI API or saturate
+//! the database. Limits come from `limits` in the app settings and are
+//! re-read on every acquire, so changes apply without a restart.
+
+use beefcake::config::OperationLimit;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::CommandResult;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Limiter shared by all AI commands
+pub static AI: Limiter = Limiter::new("AI request");
+
+/// Limiter shared by all database pushes
+pub static DB_PUSH: Limiter = Limiter::new("Database push");
+
+/// Held for the duration of a limited operation
+pub struct LimitPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+pub struct Limiter {
+    name: &'static str,
+    /// Semaphore and the capacity it was created with
+    slots: Mutex<Option<(Arc<Semaphore>, u32)>>,
+    /// Start times of operations within the last minute
+    started: Mutex<VecDeque<Instant>>,
+}
+
+impl Limiter {
+    const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            slots: Mutex::new(None),
+            started: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Wait for a free slot and for the rate limit, then return a permit
+    /// that frees the slot when dropped
+    pub async fn acquire(&self, limit: &OperationLimit) -> CommandResult<LimitPermit> {
+        let permit = match self.semaphore(limit.max_concurrent)? {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire_owned()
+                    .await
+                    .map_err(|e| format!("{} limiter closed: {e}", self.name))?,
+            ),
+            None => None,
+        };
+        self.wait_for_rate(limit.per_minute).await?;
+        Ok(LimitPermit { _permit: permit })
+    }
+
+    /// The semaphore for `max_concurrent` slots, replacing the current one
+    /// when the setting changed. Permits held on a replaced semaphore
+    /// finish normally.
+    fn semaphore(&self, max_concurrent: u32) -> CommandResult<Option<Arc<Semaphore>>> {
+        if max_concurrent == 0 {
+            return Ok(None);
+        }
+        let mut slots = self
+            .slots
+            .lock()
+            .map_err(|e| format!("Lock poisoned: {e}"))?;
+        match slots.as_ref() {
+            Some((semaphore, capacity)) if *capacity == max_concurrent => {
+                Ok(Some(Arc::clone(semaphore)))
+            }
+            _ => {
+                let semaphore = Arc::new(Semaphore::new(max_concurrent as usize));
+                *slots = Some((Arc::clone(&semaphore), max_concurrent));
+                Ok(Some(semaphore))
+            }
+        }
+    }
+
+    async fn wait_for_rate(&self, per_minute: u32) -> CommandResult<()> {
+        if per_minute == 0 {
+            return Ok(());
+        }
+        loop {
+            let wait = {
+                let mut started = self
+                    .started
+                    .lock()
+                    .map_err(|e| format!("Lock poisoned: {e}"))?;
+                let now = Instant::now();
+                while started
+                    .front()
+                    .is_some_and(|t| now.duration_since(*t) >= RATE_WINDOW)
+                {
+                    started.pop_front();
+                }
+                if started.len() < per_minute as usize {
+                    started.push_back(now);
+                    return Ok(());
+                }
+                started.front().map_or(RATE_WINDOW, |t| {
+                    RATE_WINDOW.saturating_sub(now.duration_since(*t))
+                })
+            };
+            beefcake::config::log_event(
+                "Limits",
+                &format!(
+                    "{} rate limit of {per_minute}/min reached, waiting {:.1}s",
+                    self.name,
+                    wait.as_secs_f64()
+                ),
+            );
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::timeout;
+
+    /// Long enough for a free permit to be granted, far shorter than any wait
+    const SHORT: Duration = Duration::from_millis(50);
+
+    fn limit(max_concurrent: u32, per_minute: u32) -> OperationLimit {
+        OperationLimit {
+            max_concurrent,
+            per_minute,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_concurrent_waits_for_a_permit_to_drop() {
+        let limiter = Limiter::new("Test");
+        let limit = limit(2, 0);
+        let first = limiter.acquire(&limit).await.unwrap();
+        let _second = limiter.acquire(&limit).await.unwrap();
+        assert!(timeout(SHORT, limiter.acquire(&limit)).await.is_err());
+
+        drop(first);
+        assert!(timeout(SHORT, limiter.acquire(&limit)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_per_minute_delays_the_next_start() {
+        let limiter = Limiter::new("Test");
+        let limit = limit(0, 3);
+        for _ in 0..3 {
+            assert!(timeout(SHORT, limiter.acquire(&limit)).await.is_ok());
+        }
+        assert!(timeout(SHORT, limiter.acquire(&limit)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_zero_limits_are_unlimited() {
+        let limiter = Limiter::new("Test");
+        let limit = limit(0, 0);
+        let mut permits = Vec::new();
+        for _ in 0..100 {
+            permits.push(
+                timeout(SHORT, limiter.acquire(&limit))
+                    .await
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+        assert!(limiter.slots.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_changed_capacity_replaces_the_semaphore() {
+        let limiter = Limiter::new("Test");
+        let held = limiter.acquire(&limit(1, 0)).await.unwrap();
+        assert!(timeout(SHORT, limiter.acquire(&limit(1, 0))).await.is_err());
+
+        // The new semaphore starts with all its slots free, and the permit
+        // from the old one still drops cleanly
+        let _first = timeout(SHORT, limiter.acquire(&limit(2, 0)))
+            .await
+            .unwrap()
+            .unwrap();
+        let _second = timeout(SHORT, limiter.acquire(&limit(2, 0)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(timeout(SHORT, limiter.acquire(&limit(2, 0))).await.is_err());
+        drop(held);
+        assert_eq!(limiter.slots.lock().unwrap().as_ref().unwrap().1, 2);
+    }
+}
@@ -3,6 +3,13 @@ pub mod analysis;
 pub mod dictionary;
 pub mod integrity;
 pub mod lifecycle;
+pub mod limits;
 pub mod pipeline;
+pub mod scripts;
+pub mod sql;
 pub mod system;
 pub mod watcher;
+
+/// What every Tauri command returns; the error reaches the frontend as
+/// `{ code, message, detail }`
+pub type CommandResult<T> = Result<T, beefcake::error::BeefcakeError>;
@@ -1,18 +1,31 @@
-use beefcake::pipeline::PipelineSpec;
+use beefcake::analyser::lifecycle::LifecycleStage;
+use beefcake::analyser::logic::ColumnResolution;
+use beefcake::error::BeefcakeError;
+use beefcake::pipeline::{
+    DatasetRunOptions, DatasetRunReport, DryRunReport, HistoryQuery, HistoryStore, PipelineSpec,
+    RunRecord, ValidationError, lint_pipeline, run_pipeline_dry, run_pipeline_with_progress,
+    validate_pipeline_with_inputs,
+};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use tauri::Emitter as _;
+
+use super::CommandResult;
+use super::lifecycle::get_or_create_registry;
+use super::system::run_on_worker_thread;
 
 #[tauri::command]
-pub async fn save_pipeline_spec(spec_json: String, path: String) -> Result<(), String> {
-    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(|e| e.to_string())?;
-    let content = serde_json::to_string_pretty(&spec).map_err(|e| e.to_string())?;
-    std::fs::write(path, content).map_err(|e| e.to_string())
+pub async fn save_pipeline_spec(spec_json: String, path: String) -> CommandResult<()> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
+    let content = serde_json::to_string_pretty(&spec).map_err(BeefcakeError::from_error)?;
+    std::fs::write(path, content).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn load_pipeline_spec(path: String) -> Result<String, String> {
-    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+pub async fn load_pipeline_spec(path: String) -> CommandResult<String> {
+    let content = std::fs::read_to_string(path).map_err(BeefcakeError::from_error)?;
     // Validate it's a real spec
-    let _: PipelineSpec = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let _: PipelineSpec = serde_json::from_str(&content).map_err(BeefcakeError::from_error)?;
     Ok(content)
 }
 
@@ -20,8 +33,8 @@ pub async fn load_pipeline_spec(path: String) -> Result<String, String> {
 pub async fn validate_pipeline_spec(
     spec_json: String,
     input_path: String,
-) -> Result<Vec<String>, String> {
-    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(|e| e.to_string())?;
+) -> CommandResult<Vec<String>> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
     let mut errors = vec![];
 
     if spec.steps.is_empty() {
@@ -30,14 +43,95 @@ pub async fn validate_pipeline_spec(
 
     if !PathBuf::from(&input_path).exists() {
         errors.push(format!("Input file does not exist: {input_path}"));
+    } else {
+        errors.extend(
+            check_spec_against_input(&spec, &input_path)?
+                .iter()
+                .map(ToString::to_string),
+        );
+        let dry_run = run_pipeline_dry(&spec, &input_path).map_err(BeefcakeError::from_error)?;
+        errors.extend(dry_run.failed_steps().map(|s| {
+            format!(
+                "Step {}: would be skipped: {}",
+                s.step_index + 1,
+                s.error.as_deref().unwrap_or_default()
+            )
+        }));
     }
 
     Ok(errors)
 }
 
+/// Schema after every step of the pipeline, computed without reading rows,
+/// for per-step previews in the builder
+#[tauri::command]
+pub async fn dry_run_pipeline_spec(
+    spec_json: String,
+    input_path: String,
+) -> CommandResult<DryRunReport> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
+    run_pipeline_dry(&spec, &input_path).map_err(BeefcakeError::from_error)
+}
+
+/// Like `validate_pipeline_spec` but returns structured errors (code, field,
+/// suggestion, severity) for the builder to highlight and localise, followed
+/// by lint warnings
 #[tauri::command]
-pub async fn generate_powershell(spec_json: String, output_path: String) -> Result<String, String> {
-    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(|e| e.to_string())?;
+pub async fn validate_pipeline_spec_detailed(
+    spec_json: String,
+    input_path: String,
+) -> CommandResult<Vec<ValidationError>> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
+    let mut issues = check_spec_against_input(&spec, &input_path)?;
+    issues.extend(lint_pipeline(&spec));
+    Ok(issues)
+}
+
+/// Input columns matching the columns the pipeline refers to but the input
+/// lacks, for the user to confirm as `input.column_renames`
+#[tauri::command]
+pub async fn resolve_pipeline_columns(
+    spec_json: String,
+    input_path: String,
+) -> CommandResult<ColumnResolution> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
+    let input_schema = beefcake::pipeline::load_input(&spec.input, &PathBuf::from(input_path))
+        .and_then(|mut lf| {
+            lf.collect_schema()
+                .map_err(|e| anyhow::anyhow!("Failed to collect input schema: {e}"))
+        })
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::pipeline::resolve_pipeline_columns(&spec, &input_schema)
+        .map_err(BeefcakeError::from_error)
+}
+
+fn check_spec_against_input(
+    spec: &PipelineSpec,
+    input_path: &str,
+) -> CommandResult<Vec<ValidationError>> {
+    let mut input_lf = beefcake::pipeline::load_input(&spec.input, &PathBuf::from(input_path))
+        .map_err(BeefcakeError::from_error)?;
+    let input_schema = input_lf
+        .collect_schema()
+        .map_err(BeefcakeError::from_error)?;
+
+    // Named inputs without a path (supplied at run time) are only checked for being declared
+    let mut source_schemas = HashMap::new();
+    for source in spec.input.sources.iter().filter(|s| !s.path.is_empty()) {
+        if let Ok(mut lf) = beefcake::analyser::logic::load_df_lazy(&PathBuf::from(&source.path))
+            && let Ok(schema) = lf.collect_schema()
+        {
+            source_schemas.insert(source.name.clone(), schema.as_ref().clone());
+        }
+    }
+
+    validate_pipeline_with_inputs(spec, &input_schema, &source_schemas)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn generate_powershell(spec_json: String, output_path: String) -> CommandResult<String> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
 
     let mut script = format!(
         "# Beefcake Generated Pipeline: {}\n# Generated on: {}\n\n",
@@ -59,43 +153,108 @@ pub async fn pipeline_from_configs(
     name: String,
     configs_json: String,
     output_path: String,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let configs: std::collections::HashMap<String, beefcake::analyser::logic::ColumnCleanConfig> =
-        serde_json::from_str(&configs_json).map_err(|e| e.to_string())?;
+        serde_json::from_str(&configs_json).map_err(BeefcakeError::from_error)?;
 
     let spec = PipelineSpec::from_clean_configs(name, &configs, "csv", &output_path);
 
-    serde_json::to_string_pretty(&spec).map_err(|e| e.to_string())
+    serde_json::to_string_pretty(&spec).map_err(BeefcakeError::from_error)
 }
 
+/// Run a pipeline on a file. Progress is emitted as `pipeline:progress`
+/// events as each step starts, and the run stops between steps when
+/// processing is aborted. Returns the run report as JSON, with `success`
+/// and `summary` added.
 #[tauri::command]
 pub async fn execute_pipeline_spec(
+    app: tauri::AppHandle,
     spec_json: String,
     input_path: String,
-    _output_path: Option<String>,
-) -> Result<String, String> {
-    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(|e| e.to_string())?;
+    output_path: Option<String>,
+) -> CommandResult<String> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
 
     beefcake::config::log_event("Pipeline", &format!("Executing pipeline: {}", spec.name));
+    beefcake::utils::reset_abort_signal();
+    run_on_worker_thread("pipeline-worker", move || async move {
+        let report = run_pipeline_with_progress(
+            &spec,
+            &input_path,
+            &HashMap::new(),
+            output_path.as_ref(),
+            |progress| {
+                let _ = app.emit("pipeline:progress", progress);
+            },
+        )
+        .map_err(BeefcakeError::from_error)?;
+
+        let mut result = serde_json::to_value(&report).map_err(BeefcakeError::from_error)?;
+        if let Some(fields) = result.as_object_mut() {
+            fields.insert("success".to_owned(), true.into());
+            fields.insert("summary".to_owned(), report.summary().into());
+        }
+        serde_json::to_string(&result).map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+/// Run a pipeline on the active version of a lifecycle dataset, optionally
+/// registering the output as a new version at `register_as`
+#[tauri::command]
+pub async fn run_pipeline_on_dataset(
+    spec_json: String,
+    dataset_id: String,
+    output_path: Option<String>,
+    register_as: Option<LifecycleStage>,
+) -> CommandResult<DatasetRunReport> {
+    let spec: PipelineSpec = serde_json::from_str(&spec_json).map_err(BeefcakeError::from_error)?;
+    let dataset_id = uuid::Uuid::parse_str(&dataset_id).map_err(BeefcakeError::from_error)?;
+    let registry = get_or_create_registry()?;
 
-    Ok(format!(
-        "Successfully executed pipeline '{}' on input '{}'",
-        spec.name, input_path
-    ))
+    beefcake::config::log_event(
+        "Pipeline",
+        &format!("Running pipeline '{}' on dataset {dataset_id}", spec.name),
+    );
+    run_on_worker_thread("pipeline-worker", move || async move {
+        let options = DatasetRunOptions {
+            output_path_override: output_path.map(PathBuf::from),
+            register_as,
+            ..Default::default()
+        };
+        beefcake::pipeline::run_pipeline_on_dataset(&spec, &registry, &dataset_id, &options)
+            .map_err(BeefcakeError::from_error)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_pipeline_runs(query: Option<HistoryQuery>) -> CommandResult<Vec<RunRecord>> {
+    HistoryStore::open_default()
+        .list(&query.unwrap_or_default())
+        .map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn list_pipeline_specs() -> Result<String, String> {
+pub async fn get_pipeline_run(id: String) -> CommandResult<RunRecord> {
+    HistoryStore::open_default()
+        .get(&id)
+        .map_err(BeefcakeError::from_error)?
+        .ok_or_else(|| BeefcakeError::Validation(format!("Run not found: {id}")))
+}
+
+#[tauri::command]
+pub async fn list_pipeline_specs() -> CommandResult<String> {
     let paths = beefcake::utils::standard_paths();
     let specs_dir = paths.scripts_dir.join("pipelines");
 
     if !specs_dir.exists() {
-        std::fs::create_dir_all(&specs_dir).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&specs_dir).map_err(BeefcakeError::from_error)?;
     }
 
     let mut specs = vec![];
-    for entry in std::fs::read_dir(specs_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
+    for entry in std::fs::read_dir(specs_dir).map_err(BeefcakeError::from_error)? {
+        let entry = entry.map_err(BeefcakeError::from_error)?;
         let path = entry.path();
         if path.extension().and_then(|s| s.to_str()) == Some("json")
             && let Ok(content) = std::fs::read_to_string(&path)
@@ -109,24 +268,29 @@ pub async fn list_pipeline_specs() -> Result<String, String> {
         }
     }
 
-    serde_json::to_string(&specs).map_err(|e| e.to_string())
+    serde_json::to_string(&specs).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn delete_pipeline_spec(path: String) -> Result<(), String> {
+pub async fn delete_pipeline_spec(path: String) -> CommandResult<()> {
     let p = PathBuf::from(path);
     if !p.exists() {
-        return Err("File not found".to_owned());
+        return Err(BeefcakeError::InvalidPath(format!(
+            "File not found: {}",
+            p.display()
+        )));
     }
 
     let paths = beefcake::utils::standard_paths();
     let specs_dir = paths.scripts_dir.join("pipelines");
 
     if !p.starts_with(specs_dir) {
-        return Err("Cannot delete files outside of pipelines directory".to_owned());
+        return Err(BeefcakeError::PermissionDenied(
+            "Cannot delete files outside of pipelines directory".to_owned(),
+        ));
     }
 
-    std::fs::remove_file(p).map_err(|e| e.to_string())
+    std::fs::remove_file(p).map_err(BeefcakeError::from_error)
 }
 
 #[derive(serde::Serialize)]
@@ -137,7 +301,7 @@ pub struct PipelineTemplate {
 }
 
 #[tauri::command]
-pub async fn list_pipeline_templates() -> Result<String, String> {
+pub async fn list_pipeline_templates() -> CommandResult<String> {
     let templates = vec![
         PipelineTemplate {
             name: "Basic Cleaning".to_owned(),
@@ -154,15 +318,19 @@ pub async fn list_pipeline_templates() -> Result<String, String> {
         },
     ];
 
-    serde_json::to_string(&templates).map_err(|e| e.to_string())
+    serde_json::to_string(&templates).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn load_pipeline_template(template_name: String) -> Result<String, String> {
+pub async fn load_pipeline_template(template_name: String) -> CommandResult<String> {
     let spec = match template_name.as_str() {
         "Basic Cleaning" => PipelineSpec::new("Basic Cleaning"),
-        _ => return Err("Template not found".to_owned()),
+        _ => {
+            return Err(BeefcakeError::Validation(format!(
+                "Template not found: {template_name}"
+            )));
+        }
     };
 
-    serde_json::to_string_pretty(&spec).map_err(|e| e.to_string())
+    serde_json::to_string_pretty(&spec).map_err(BeefcakeError::from_error)
 }
@@ -0,0 +1,53 @@
+use beefcake::error::BeefcakeError;
+use beefcake::scripts::{RenderedScript, ScriptLibrary, ScriptTemplate};
+use std::collections::HashMap;
+
+use super::CommandResult;
+
+#[tauri::command]
+pub async fn list_script_templates() -> CommandResult<Vec<ScriptTemplate>> {
+    ScriptLibrary::open_default()
+        .list()
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Every saved version of a template, oldest first
+#[tauri::command]
+pub async fn list_script_template_versions(id: String) -> CommandResult<Vec<ScriptTemplate>> {
+    ScriptLibrary::open_default()
+        .versions(&id)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Save a template as a new version; templates without an id are created
+#[tauri::command]
+pub async fn save_script_template(template: ScriptTemplate) -> CommandResult<ScriptTemplate> {
+    let saved = ScriptLibrary::open_default()
+        .save(template)
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Scripts",
+        &format!("Saved script template '{}' v{}", saved.name, saved.version),
+    );
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_script_template(id: String) -> CommandResult<bool> {
+    ScriptLibrary::open_default()
+        .delete(&id)
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Fill in a template with form values, ready for `run_python` or
+/// `run_powershell`
+#[tauri::command]
+pub async fn render_script_template(
+    id: String,
+    version: Option<u32>,
+    values: HashMap<String, String>,
+) -> CommandResult<RenderedScript> {
+    ScriptLibrary::open_default()
+        .render(&id, version, &values)
+        .map_err(BeefcakeError::from_error)
+}
@@ -0,0 +1,195 @@
+use beefcake::analyser::logic::ColumnCleanConfig;
+use beefcake::error::BeefcakeError;
+use beefcake::sql::{
+    DEFAULT_PAGE_SIZE, QueryParam, QueryRecord, ResultPage, SavedQuery, SqlWorkspace, bind_params,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use uuid::Uuid;
+
+use super::CommandResult;
+use super::system::ensure_security_acknowledged;
+use crate::python_runner;
+
+/// A recorded execution and the first page of its result
+#[derive(serde::Serialize)]
+pub struct SqlQueryResult {
+    pub record: QueryRecord,
+    pub page: ResultPage,
+}
+
+/// Run `query` against `data_path` (registered as `data`), keeping the full
+/// result for paging and export. Failures are recorded in the history too.
+#[tauri::command]
+pub async fn run_sql_query(
+    query: String,
+    data_path: Option<String>,
+    configs: Option<HashMap<String, ColumnCleanConfig>>,
+    page_size: Option<usize>,
+) -> CommandResult<SqlQueryResult> {
+    ensure_security_acknowledged()?;
+    beefcake::config::log_event("Sql", "Executing Sql query with saved result.");
+
+    let workspace = SqlWorkspace::open_default();
+    let id = Uuid::new_v4().to_string();
+    let result_path = workspace
+        .result_path(&id)
+        .map_err(BeefcakeError::from_error)?;
+    if let Some(parent) = result_path.parent() {
+        std::fs::create_dir_all(parent).map_err(BeefcakeError::from_error)?;
+    }
+
+    let started_at = Utc::now();
+    let timer = Instant::now();
+    let outcome = async {
+        let (actual_data_path, _temp_guard) =
+            python_runner::prepare_data(data_path.clone(), configs, "Sql").await?;
+        let script = format!(
+            r#"{}
+data_path = os.environ.get("BEEFCAKE_DATA_PATH")
+if not data_path:
+    print("Error: No data path provided in environment variable BEEFCAKE_DATA_PATH")
+    sys.exit(1)
+
+query_str = os.environ.get("BEEFCAKE_SQL_QUERY")
+if not query_str:
+    print("Error: No Sql query provided in environment variable BEEFCAKE_SQL_QUERY")
+    sys.exit(1)
+
+try:
+{}
+    ctx = pl.SQLContext()
+    ctx.register("data", lf)
+    result = ctx.execute(query_str)
+{}
+except Exception as e:
+    print(f"Sql Error: {{e}}")
+    sys.exit(1)
+"#,
+            python_runner::python_preamble(),
+            python_runner::indent_python(&python_runner::python_load_snippet("data_path", "lf")),
+            python_runner::indent_python(&python_runner::python_adaptive_sink_snippet(
+                "result",
+                &result_path
+            )),
+        );
+        python_runner::execute_python_with_env(
+            &script,
+            actual_data_path,
+            Some(query.clone()),
+            "Sql",
+        )
+        .await?;
+        Ok::<_, BeefcakeError>(())
+    }
+    .await
+    .and_then(|()| {
+        workspace
+            .result_page(&id, 0, page_size.unwrap_or(DEFAULT_PAGE_SIZE))
+            .map_err(BeefcakeError::from_error)
+    });
+
+    let rows = match &outcome {
+        Ok(page) => Ok(page.total_rows),
+        Err(e) => Err(anyhow::anyhow!("{e}")),
+    };
+    let record = QueryRecord::new(
+        &id,
+        &query,
+        data_path.as_deref(),
+        started_at,
+        timer.elapsed(),
+        &rows,
+    );
+    if let Err(e) = workspace.record(&record) {
+        tracing::warn!("Failed to record Sql history: {e:#}");
+    }
+
+    let page = outcome?;
+    tracing::info!(
+        "Sql query returned {} rows in {} ms",
+        page.total_rows,
+        record.duration_ms
+    );
+    Ok(SqlQueryResult { record, page })
+}
+
+/// Run a saved query with `values` for its parameters, against `data_path`
+/// or, when not given, the file the query was saved with
+#[tauri::command]
+pub async fn run_saved_query(
+    id: String,
+    values: Option<HashMap<String, String>>,
+    data_path: Option<String>,
+    configs: Option<HashMap<String, ColumnCleanConfig>>,
+    page_size: Option<usize>,
+) -> CommandResult<SqlQueryResult> {
+    let saved = SqlWorkspace::open_default()
+        .saved_query(&id)
+        .map_err(BeefcakeError::from_error)?;
+    let query = bind_params(&saved.query, &saved.params, &values.unwrap_or_default())
+        .map_err(BeefcakeError::from_error)?;
+    run_sql_query(query, data_path.or(saved.data_path), configs, page_size).await
+}
+
+#[tauri::command]
+pub async fn get_sql_result_page(
+    query_id: String,
+    offset: usize,
+    limit: Option<usize>,
+) -> CommandResult<ResultPage> {
+    SqlWorkspace::open_default()
+        .result_page(&query_id, offset, limit.unwrap_or(DEFAULT_PAGE_SIZE))
+        .map_err(BeefcakeError::from_error)
+}
+
+/// Write the full result of a query to `path` (CSV, JSON or Parquet by
+/// extension) and return the rows written
+#[tauri::command]
+pub async fn export_sql_result(query_id: String, path: String) -> CommandResult<usize> {
+    let rows = SqlWorkspace::open_default()
+        .export_result(&query_id, Path::new(&path))
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event("Sql", &format!("Exported {rows} rows to {path}"));
+    Ok(rows)
+}
+
+#[tauri::command]
+pub async fn list_sql_history(limit: Option<usize>) -> CommandResult<Vec<QueryRecord>> {
+    SqlWorkspace::open_default()
+        .history(limit)
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn list_saved_queries() -> CommandResult<Vec<SavedQuery>> {
+    SqlWorkspace::open_default()
+        .saved_queries()
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn save_sql_query(
+    name: String,
+    query: String,
+    data_path: Option<String>,
+    params: Option<Vec<QueryParam>>,
+) -> CommandResult<SavedQuery> {
+    SqlWorkspace::open_default()
+        .save_query_with_params(
+            &name,
+            &query,
+            data_path.as_deref(),
+            &params.unwrap_or_default(),
+        )
+        .map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn delete_saved_query(id: String) -> CommandResult<bool> {
+    SqlWorkspace::open_default()
+        .delete_query(&id)
+        .map_err(BeefcakeError::from_error)
+}
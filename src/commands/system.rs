@@ -1,26 +1,31 @@
-use beefcake::config::{AppConfig, load_app_config, save_app_config};
+use beefcake::config::audit::{self, AuditExportFormat, AuditQuery};
+use beefcake::config::policy::{Capability, PathDecision, PathPolicy, PathRule};
+use beefcake::config::{AppConfig, AuditEntry, load_app_config, save_app_config};
+use beefcake::error::BeefcakeError;
+use beefcake::utils::runs::{self, RunLogEntry, RunSummary};
 use std::future::Future;
 use tauri::Manager as _;
 
+use super::CommandResult;
+
 /// Stack size for worker threads (50MB) - used for memory-intensive operations
 pub const WORKER_THREAD_STACK_SIZE: usize = 50 * 1024 * 1024;
 
-/// File size threshold (50MB) for warning about memory-intensive operations
-pub const LARGE_FILE_WARNING_THRESHOLD: u64 = 50 * 1024 * 1024;
-
-pub fn ensure_security_acknowledged() -> Result<(), String> {
+pub fn ensure_security_acknowledged() -> CommandResult<()> {
     let config = load_app_config();
     if config.settings.security_warning_acknowledged {
         Ok(())
     } else {
-        Err("Security warning not acknowledged. Please confirm before running scripts.".to_owned())
+        Err(BeefcakeError::PermissionDenied(
+            "Security warning not acknowledged. Please confirm before running scripts.".to_owned(),
+        ))
     }
 }
 
-pub async fn run_on_worker_thread<F, Fut, R>(name: &str, f: F) -> Result<R, String>
+pub async fn run_on_worker_thread<F, Fut, R>(name: &str, f: F) -> CommandResult<R>
 where
     F: FnOnce() -> Fut + Send + 'static,
-    Fut: Future<Output = Result<R, String>> + Send + 'static,
+    Fut: Future<Output = CommandResult<R>> + Send + 'static,
     R: Send + 'static,
 {
     let thread_name = name.to_owned();
@@ -35,7 +40,7 @@ where
                 std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
                     tauri::async_runtime::block_on(f())
                 }))
-                .unwrap_or_else(|_| Err(format!("{panic_name} panicked")))
+                .unwrap_or_else(|_| Err(format!("{panic_name} panicked").into()))
             })
             .map_err(|e| format!("Failed to spawn {spawn_name}: {e}"))?
             .join()
@@ -46,29 +51,29 @@ where
 }
 
 #[tauri::command]
-pub async fn read_text_file(path: String) -> Result<String, String> {
+pub async fn read_text_file(path: String) -> CommandResult<String> {
     beefcake::config::log_event("File", &format!("Read file: {path}"));
-    crate::system::read_text_file(&path).map_err(|e| e.to_string())
+    crate::system::read_text_file(&path).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn write_text_file(path: String, contents: String) -> Result<(), String> {
+pub async fn write_text_file(path: String, contents: String) -> CommandResult<()> {
     beefcake::config::log_event("File", &format!("Saved file: {path}"));
-    crate::system::write_text_file(&path, &contents).map_err(|e| e.to_string())
+    crate::system::write_text_file(&path, &contents).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn get_app_version() -> Result<String, String> {
+pub async fn get_app_version() -> CommandResult<String> {
     Ok(env!("CARGO_PKG_VERSION").to_owned())
 }
 
 #[tauri::command]
-pub async fn get_config() -> Result<AppConfig, String> {
+pub async fn get_config() -> CommandResult<AppConfig> {
     Ok(load_app_config())
 }
 
 #[tauri::command]
-pub async fn save_config(mut config: AppConfig) -> Result<(), String> {
+pub async fn save_config(mut config: AppConfig) -> CommandResult<()> {
     use beefcake::config::{KEYRING_PLACEHOLDER, push_audit_log};
     use beefcake::utils::set_db_password;
     use secrecy::ExposeSecret as _;
@@ -76,14 +81,14 @@ pub async fn save_config(mut config: AppConfig) -> Result<(), String> {
     for conn in &mut config.settings.connections {
         let pwd = conn.settings.password.expose_secret();
         if pwd != KEYRING_PLACEHOLDER && !pwd.is_empty() {
-            set_db_password(&conn.id, pwd).map_err(|e| e.to_string())?;
+            set_db_password(&conn.id, pwd).map_err(BeefcakeError::from_error)?;
         }
     }
 
     if !config.audit_log().is_empty() {
         push_audit_log(&mut config, "Config", "Updated application settings");
     }
-    save_app_config(&config).map_err(|e| e.to_string())
+    save_app_config(&config).map_err(BeefcakeError::from_error)
 }
 
 #[derive(serde::Serialize)]
@@ -96,7 +101,7 @@ pub struct StandardPathsPayload {
 }
 
 #[tauri::command]
-pub async fn get_standard_paths() -> Result<StandardPathsPayload, String> {
+pub async fn get_standard_paths() -> CommandResult<StandardPathsPayload> {
     let paths = beefcake::utils::standard_paths();
     Ok(StandardPathsPayload {
         base_dir: paths.base_dir.to_string_lossy().to_string(),
@@ -108,47 +113,86 @@ pub async fn get_standard_paths() -> Result<StandardPathsPayload, String> {
 }
 
 #[tauri::command]
-pub async fn open_path(path: String) -> Result<(), String> {
-    crate::system::open_path(&path).map_err(|e| e.to_string())
+pub async fn open_path(path: String) -> CommandResult<()> {
+    crate::system::open_path(&path).map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn list_trusted_paths() -> Result<Vec<String>, String> {
+pub async fn list_trusted_paths() -> CommandResult<Vec<String>> {
     let config = load_app_config();
     Ok(config.settings.trusted_paths.clone())
 }
 
 #[tauri::command]
-pub async fn add_trusted_path(path: String) -> Result<Vec<String>, String> {
+pub async fn add_trusted_path(path: String) -> CommandResult<Vec<String>> {
     let mut config = load_app_config();
     let normalized = normalize_trusted_root(&path)?;
     if !config.settings.trusted_paths.contains(&normalized) {
         config.settings.trusted_paths.push(normalized);
-        save_app_config(&config).map_err(|e| e.to_string())?;
+        save_app_config(&config).map_err(BeefcakeError::from_error)?;
     }
     Ok(config.settings.trusted_paths)
 }
 
 #[tauri::command]
-pub async fn remove_trusted_path(path: String) -> Result<Vec<String>, String> {
+pub async fn remove_trusted_path(path: String) -> CommandResult<Vec<String>> {
     let mut config = load_app_config();
     config.settings.trusted_paths.retain(|p| p != &path);
-    save_app_config(&config).map_err(|e| e.to_string())?;
+    save_app_config(&config).map_err(BeefcakeError::from_error)?;
     Ok(config.settings.trusted_paths)
 }
 
-fn normalize_trusted_root(path: &str) -> Result<String, String> {
+#[tauri::command]
+pub async fn list_path_rules() -> CommandResult<Vec<PathRule>> {
+    Ok(load_app_config().settings.path_rules)
+}
+
+/// Replace the glob allow and deny rules, rejecting the whole list if any
+/// rule is invalid
+#[tauri::command]
+pub async fn save_path_rules(rules: Vec<PathRule>) -> CommandResult<Vec<PathRule>> {
+    for rule in &rules {
+        rule.validate().map_err(BeefcakeError::from_error)?;
+    }
+    let mut config = load_app_config();
+    config.settings.path_rules = rules;
+    beefcake::config::push_audit_log(
+        &mut config,
+        "Config",
+        &format!(
+            "Updated path rules ({} rules)",
+            config.settings.path_rules.len()
+        ),
+    );
+    save_app_config(&config).map_err(BeefcakeError::from_error)?;
+    Ok(config.settings.path_rules)
+}
+
+/// How the path policy treats `capability` on `path`, for previewing rules
+#[tauri::command]
+pub async fn check_path_access(
+    path: String,
+    capability: Capability,
+) -> CommandResult<PathDecision> {
+    Ok(PathPolicy::load().evaluate(std::path::Path::new(&path), capability))
+}
+
+fn normalize_trusted_root(path: &str) -> CommandResult<String> {
     let p = std::path::Path::new(path);
     if !p.exists() {
-        return Err("Path does not exist".to_owned());
+        return Err(BeefcakeError::InvalidPath(format!(
+            "Path does not exist: {path}"
+        )));
     }
     let abs = if p.is_absolute() {
         p.to_path_buf()
     } else {
-        std::env::current_dir().map_err(|e| e.to_string())?.join(p)
+        std::env::current_dir()
+            .map_err(BeefcakeError::from_error)?
+            .join(p)
     };
 
-    let canonical = abs.canonicalize().map_err(|e| e.to_string())?;
+    let canonical = abs.canonicalize().map_err(BeefcakeError::from_error)?;
     Ok(canonical.to_string_lossy().to_string())
 }
 
@@ -162,11 +206,11 @@ pub struct DocFileMetadata {
 #[tauri::command]
 pub async fn list_documentation_files(
     app: tauri::AppHandle,
-) -> Result<Vec<DocFileMetadata>, String> {
+) -> CommandResult<Vec<DocFileMetadata>> {
     let resource_path = app
         .path()
         .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {e}"))?
+        .map_err(|e| BeefcakeError::Config(format!("Failed to get resource dir: {e}")))?
         .join("docs");
 
     if !resource_path.exists() {
@@ -174,21 +218,21 @@ pub async fn list_documentation_files(
     }
 
     let mut docs = vec![];
-    let entries = std::fs::read_dir(resource_path).map_err(|e| e.to_string())?;
+    let entries = std::fs::read_dir(resource_path).map_err(BeefcakeError::from_error)?;
 
     for entry in entries {
-        let entry = entry.map_err(|e| e.to_string())?;
+        let entry = entry.map_err(BeefcakeError::from_error)?;
         let path = entry.path();
         if path.extension().and_then(|s| s.to_str()) == Some("md") {
             let filename = path
                 .file_name()
-                .ok_or_else(|| "Invalid file name".to_owned())?
+                .ok_or_else(|| BeefcakeError::InvalidPath("Invalid file name".to_owned()))?
                 .to_string_lossy();
             if filename == "README.md" {
                 continue;
             }
 
-            let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+            let content = std::fs::read_to_string(&path).map_err(BeefcakeError::from_error)?;
             let title = content
                 .lines()
                 .find(|l| l.starts_with("# "))
@@ -210,19 +254,21 @@ pub async fn list_documentation_files(
 pub async fn read_documentation_file(
     doc_path: String,
     app: tauri::AppHandle,
-) -> Result<String, String> {
+) -> CommandResult<String> {
     let resource_path = app
         .path()
         .resource_dir()
-        .map_err(|e| format!("Failed to get resource dir: {e}"))?
+        .map_err(|e| BeefcakeError::Config(format!("Failed to get resource dir: {e}")))?
         .join("docs")
         .join(doc_path);
 
     if !resource_path.exists() {
-        return Err("Documentation file not found".to_owned());
+        return Err(BeefcakeError::InvalidPath(
+            "Documentation file not found".to_owned(),
+        ));
     }
 
-    std::fs::read_to_string(resource_path).map_err(|e| e.to_string())
+    std::fs::read_to_string(resource_path).map_err(BeefcakeError::from_error)
 }
 
 /// Logs a message from the frontend to the backend log files
@@ -231,7 +277,7 @@ pub async fn log_frontend_error(
     level: String,
     message: String,
     context: Option<serde_json::Value>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let context_str = context
         .map(|c| format!(" | context: {c}"))
         .unwrap_or_default();
@@ -254,7 +300,7 @@ pub async fn log_frontend_event(
     action: String,
     details: String,
     context: Option<serde_json::Value>,
-) -> Result<(), String> {
+) -> CommandResult<()> {
     let context_str = context
         .map(|c| format!(" | context: {c}"))
         .unwrap_or_default();
@@ -273,24 +319,66 @@ pub async fn log_frontend_event(
 
 /// Returns the path to the log directory
 #[tauri::command]
-pub async fn get_log_directory() -> Result<String, String> {
+pub async fn get_log_directory() -> CommandResult<String> {
     beefcake::logging::get_log_dir()
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
 }
 
 /// Returns the path to the current log file
 #[tauri::command]
-pub async fn get_current_log_file() -> Result<String, String> {
+pub async fn get_current_log_file() -> CommandResult<String> {
     beefcake::logging::get_current_log_path()
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
+}
+
+/// The most recent analysis, pipeline and export runs, newest first
+#[tauri::command]
+pub async fn list_runs(limit: Option<usize>) -> CommandResult<Vec<RunSummary>> {
+    let log_dir = beefcake::logging::get_log_dir().map_err(BeefcakeError::from_error)?;
+    runs::list_runs(&log_dir, limit.unwrap_or(50)).map_err(BeefcakeError::from_error)
+}
+
+/// Everything logged during run `run_id`, oldest first
+#[tauri::command]
+pub async fn read_run_log(run_id: String, limit: Option<usize>) -> CommandResult<Vec<RunLogEntry>> {
+    let log_dir = beefcake::logging::get_log_dir().map_err(BeefcakeError::from_error)?;
+    runs::read_run_log(&log_dir, &run_id, limit.unwrap_or(1000)).map_err(BeefcakeError::from_error)
+}
+
+/// Audit entries matching `query` from the audit files, oldest first
+#[tauri::command]
+pub async fn query_audit_log(query: AuditQuery) -> CommandResult<Vec<AuditEntry>> {
+    let log_dir = beefcake::logging::get_log_dir().map_err(BeefcakeError::from_error)?;
+    audit::query(&log_dir, &query).map_err(BeefcakeError::from_error)
+}
+
+/// Writes the audit entries matching `query` to `path` as CSV or JSON lines,
+/// returning how many were written
+#[tauri::command]
+pub async fn export_audit_log(
+    query: AuditQuery,
+    path: String,
+    format: AuditExportFormat,
+) -> CommandResult<usize> {
+    let path = PathPolicy::load()
+        .authorise(std::path::Path::new(&path), Capability::Write)
+        .map_err(BeefcakeError::from_error)?;
+    let log_dir = beefcake::logging::get_log_dir().map_err(BeefcakeError::from_error)?;
+    let entries = audit::query(&log_dir, &query).map_err(BeefcakeError::from_error)?;
+    let written = audit::export(&entries, &path, format).map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Audit",
+        &format!("Exported {written} audit entries to {}", path.display()),
+    );
+    Ok(written)
 }
 
 /// Returns the path to the current error log file
 #[tauri::command]
-pub async fn get_current_error_log_file() -> Result<String, String> {
+pub async fn get_current_error_log_file() -> CommandResult<String> {
     beefcake::logging::get_current_error_log_path()
         .map(|p| p.to_string_lossy().to_string())
-        .map_err(|e| e.to_string())
+        .map_err(BeefcakeError::from_error)
 }
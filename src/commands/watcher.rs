@@ -1,34 +1,63 @@
+use beefcake::error::BeefcakeError;
+
+use super::CommandResult;
+
 #[tauri::command]
-pub async fn watcher_get_state() -> Result<beefcake::watcher::WatcherStatusPayload, String> {
-    beefcake::watcher::get_state().map_err(|e| e.to_string())
+pub async fn watcher_get_state() -> CommandResult<beefcake::watcher::WatcherStatusPayload> {
+    beefcake::watcher::get_state().map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
 pub async fn watcher_start(
     folder: String,
-) -> Result<beefcake::watcher::WatcherStatusPayload, String> {
+) -> CommandResult<beefcake::watcher::WatcherStatusPayload> {
     beefcake::config::log_event("Watcher", &format!("Started watching: {folder}"));
-    beefcake::watcher::start(std::path::PathBuf::from(folder)).map_err(|e| e.to_string())?;
-    beefcake::watcher::get_state().map_err(|e| e.to_string())
+    beefcake::watcher::start(std::path::PathBuf::from(folder))
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::watcher::get_state().map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
-pub async fn watcher_stop() -> Result<beefcake::watcher::WatcherStatusPayload, String> {
+pub async fn watcher_stop() -> CommandResult<beefcake::watcher::WatcherStatusPayload> {
     beefcake::config::log_event("Watcher", "Stopped watching");
-    beefcake::watcher::stop().map_err(|e| e.to_string())?;
-    beefcake::watcher::get_state().map_err(|e| e.to_string())
+    beefcake::watcher::stop().map_err(BeefcakeError::from_error)?;
+    beefcake::watcher::get_state().map_err(BeefcakeError::from_error)
 }
 
 #[tauri::command]
 pub async fn watcher_set_folder(
     folder: String,
-) -> Result<beefcake::watcher::WatcherStatusPayload, String> {
+) -> CommandResult<beefcake::watcher::WatcherStatusPayload> {
     beefcake::config::log_event("Watcher", &format!("Changed folder to: {folder}"));
-    beefcake::watcher::set_folder(std::path::PathBuf::from(folder)).map_err(|e| e.to_string())?;
-    beefcake::watcher::get_state().map_err(|e| e.to_string())
+    beefcake::watcher::set_folder(std::path::PathBuf::from(folder))
+        .map_err(BeefcakeError::from_error)?;
+    beefcake::watcher::get_state().map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn watcher_ingest_now(path: String) -> CommandResult<()> {
+    beefcake::watcher::ingest_now(std::path::PathBuf::from(path)).map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn watcher_list_tables() -> CommandResult<Vec<beefcake::watcher::TableWatch>> {
+    beefcake::watcher::list_table_watches().map_err(BeefcakeError::from_error)
+}
+
+#[tauri::command]
+pub async fn watcher_add_table(
+    watch: beefcake::watcher::TableWatch,
+) -> CommandResult<beefcake::watcher::TableWatch> {
+    let watch = beefcake::watcher::add_table_watch(watch).map_err(BeefcakeError::from_error)?;
+    beefcake::config::log_event(
+        "Watcher",
+        &format!("Polling table: {}", watch.display_name()),
+    );
+    Ok(watch)
 }
 
 #[tauri::command]
-pub async fn watcher_ingest_now(path: String) -> Result<(), String> {
-    beefcake::watcher::ingest_now(std::path::PathBuf::from(path)).map_err(|e| e.to_string())
+pub async fn watcher_remove_table(id: String) -> CommandResult<()> {
+    beefcake::config::log_event("Watcher", &format!("Stopped polling table: {id}"));
+    beefcake::watcher::remove_table_watch(&id).map_err(BeefcakeError::from_error)
 }
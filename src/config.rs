@@ -1,10 +1,18 @@
-use anyhow::Result;
+use crate::analyser::logic::{AnalysisOptions, DateOrder, DuplicateColumnPolicy, LayoutOptions};
+use crate::dictionary::SnapshotRetention;
+use crate::utils::memory::MemoryBudget;
+use crate::utils::retry::RetryPolicy;
+use anyhow::{Context as _, Result};
 use chrono::{DateTime, Utc};
 use secrecy::SecretString;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+pub mod audit;
+pub mod policy;
+pub mod secrets;
+
 pub const KEYRING_PLACEHOLDER: &str = "__KEYRING__";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -12,6 +20,10 @@ pub struct DbConnection {
     pub id: String,
     pub name: String,
     pub settings: DbSettings,
+    /// Refuse pushes, table creation and DDL on this connection whatever
+    /// the credentials allow
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,17 +45,19 @@ impl AuditLog {
         }
     }
 
+    /// Add an entry now, keeping the entries the default retention keeps
     pub fn push(&mut self, action: impl Into<String>, details: impl Into<String>) {
         self.entries.push(AuditEntry {
             timestamp: Utc::now(),
             action: action.into(),
             details: details.into(),
         });
+        self.apply_retention(&audit::AuditRetention::default());
+    }
 
-        // Keep only last 1000 entries
-        if self.entries.len() > 1000 {
-            self.entries.drain(0..self.entries.len() - 1000);
-        }
+    /// Drop the entries `retention` does not keep
+    pub fn apply_retention(&mut self, retention: &audit::AuditRetention) {
+        retention.apply(&mut self.entries, Utc::now());
     }
 
     pub fn entries(&self) -> &[AuditEntry] {
@@ -68,18 +82,146 @@ impl Default for AuditLog {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AIConfig {
     pub enabled: bool,
+    /// Backend that serves chat completions
+    #[serde(default)]
+    pub provider: crate::ai::provider::AIProvider,
+    /// Overrides the provider's default base URL, e.g.
+    /// `https://<resource>.openai.azure.com` or `http://gpu-box:8000/v1`
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Overrides the provider's default way of sending the API key
+    #[serde(default)]
+    pub auth_scheme: Option<crate::ai::provider::AIAuthScheme>,
+    /// Azure OpenAI `api-version`
+    #[serde(default)]
+    pub api_version: Option<String>,
+    /// Model name, or the deployment name for Azure OpenAI
     pub model: String,
     pub temperature: f32,
     pub max_tokens: u32,
+    /// Tokens the model accepts per request, including the answer.
+    /// Conversation history is summarised to stay within it.
+    #[serde(default = "default_context_window")]
+    pub context_window: u32,
+    /// What the analysis context sent with queries includes
+    #[serde(default)]
+    pub context: crate::ai::context::ContextOptions,
+}
+
+fn default_context_window() -> u32 {
+    16_000
 }
 
 impl Default for AIConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            provider: crate::ai::provider::AIProvider::default(),
+            base_url: None,
+            auth_scheme: None,
+            api_version: None,
             model: "gpt-4o".to_owned(),
             temperature: 0.7,
             max_tokens: 2000,
+            context_window: default_context_window(),
+            context: crate::ai::context::ContextOptions::default(),
+        }
+    }
+}
+
+impl AIConfig {
+    /// Configured base URL without a trailing slash, or the provider's
+    /// default
+    pub fn resolved_base_url(&self) -> Result<String> {
+        let url = self
+            .base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .or_else(|| self.provider.default_base_url())
+            .with_context(|| format!("{} needs a base URL", self.provider.label()))?;
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            anyhow::bail!("AI base URL must start with http:// or https://: {url}");
+        }
+        Ok(url.trim_end_matches('/').to_owned())
+    }
+
+    pub fn resolved_auth_scheme(&self) -> crate::ai::provider::AIAuthScheme {
+        self.auth_scheme
+            .unwrap_or_else(|| self.provider.default_auth_scheme())
+    }
+
+    /// Whether requests need an API key from the keyring
+    pub fn requires_api_key(&self) -> bool {
+        self.resolved_auth_scheme() != crate::ai::provider::AIAuthScheme::None
+    }
+}
+
+/// Cap on one kind of operation; 0 disables either limit
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct OperationLimit {
+    /// Operations allowed to run at the same time
+    pub max_concurrent: u32,
+    /// Operations allowed to start in any 60-second window
+    pub per_minute: u32,
+}
+
+impl Default for OperationLimit {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 2,
+            per_minute: 0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct OperationLimits {
+    /// AI assistant requests
+    pub ai: OperationLimit,
+    /// Database pushes (exports and pushes from the analyser)
+    pub db_push: OperationLimit,
+}
+
+impl Default for OperationLimits {
+    fn default() -> Self {
+        Self {
+            ai: OperationLimit {
+                max_concurrent: 2,
+                per_minute: 20,
+            },
+            db_push: OperationLimit::default(),
+        }
+    }
+}
+
+/// Restrictions for Python scripts run from the app. Memory and CPU limits
+/// need the POSIX `resource` module and are reported as not applied on
+/// Windows; the wall-clock limit applies everywhere.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct PythonSandbox {
+    pub enabled: bool,
+    /// Memory cap in MiB; 0 for none
+    pub memory_mb: u64,
+    /// CPU time cap in seconds; 0 for none
+    pub cpu_seconds: u64,
+    /// Wall-clock cap in seconds; 0 for the default Python timeout
+    pub timeout_secs: u64,
+    /// Block sockets in the script and point proxies at a closed port
+    pub block_network: bool,
+}
+
+impl Default for PythonSandbox {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            memory_mb: 4096,
+            cpu_seconds: 300,
+            timeout_secs: 300,
+            block_network: true,
         }
     }
 }
@@ -96,6 +238,10 @@ pub struct AppSettings {
     pub first_run_completed: bool,
     /// User-approved roots for file IO operations
     pub trusted_paths: Vec<String>,
+    /// Glob allow and deny rules scoped to read, write or execute, checked
+    /// together with `trusted_paths` by [`policy::PathPolicy`]
+    #[serde(default)]
+    pub path_rules: Vec<policy::PathRule>,
     /// Maximum number of rows to display in Sql/Python previews (default: 100)
     pub preview_row_limit: u32,
     /// Whether to show security warning on first Python/PowerShell execution
@@ -112,6 +258,49 @@ pub struct AppSettings {
     pub sampling_strategy: String,
     /// AI assistant configuration
     pub ai_config: AIConfig,
+    /// Options controlling correlation and other analysis behaviour
+    #[serde(default)]
+    pub analysis_options: AnalysisOptions,
+    /// Retries for transient database, network and delivery failures
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    /// Concurrency and rate limits for AI requests and database pushes
+    #[serde(default)]
+    pub limits: OperationLimits,
+    /// Whether every analysed file gets a data dictionary snapshot
+    #[serde(default)]
+    pub auto_dictionary_snapshot: bool,
+    /// How many dictionary snapshots are kept per dataset
+    #[serde(default)]
+    pub dictionary_retention: SnapshotRetention,
+    /// Whether datasets due for a documentation review are announced at startup
+    #[serde(default)]
+    pub dictionary_review_reminders: bool,
+    /// Working directory, network and resource limits for Python scripts
+    #[serde(default)]
+    pub python_sandbox: PythonSandbox,
+    /// What loading a CSV does with repeated header names
+    #[serde(default)]
+    pub duplicate_column_policy: DuplicateColumnPolicy,
+    /// Swap rows and columns before analysis when a file looks like it holds
+    /// variables as rows
+    #[serde(default)]
+    pub auto_transpose: bool,
+    /// Fixes for merged header cells and footer rows in CSV files and workbooks
+    #[serde(default)]
+    pub layout: LayoutOptions,
+    /// Memory analysis, cleaning and export may use before they stream,
+    /// sample or spill to disk
+    #[serde(default)]
+    pub memory_budget: MemoryBudget,
+    /// How many audit entries the config keeps, and for how long entries
+    /// are kept in the config and the audit files
+    #[serde(default)]
+    pub audit_retention: audit::AuditRetention,
+    /// Whether dates such as `03/04/2024` are read day or month first when
+    /// their values allow both
+    #[serde(default)]
+    pub date_order: DateOrder,
 }
 
 impl Default for AppSettings {
@@ -125,12 +314,26 @@ impl Default for AppSettings {
             sql_font_size: 14,
             first_run_completed: false,
             trusted_paths: Vec::new(),
+            path_rules: Vec::new(),
             preview_row_limit: 100,
             security_warning_acknowledged: false,
             skip_full_row_count: false,
             analysis_sample_size: 10_000,
             sampling_strategy: "balanced".to_owned(),
             ai_config: AIConfig::default(),
+            analysis_options: AnalysisOptions::default(),
+            retry_policy: RetryPolicy::default(),
+            limits: OperationLimits::default(),
+            auto_dictionary_snapshot: false,
+            dictionary_retention: SnapshotRetention::default(),
+            dictionary_review_reminders: false,
+            python_sandbox: PythonSandbox::default(),
+            duplicate_column_policy: DuplicateColumnPolicy::default(),
+            auto_transpose: false,
+            layout: LayoutOptions::default(),
+            memory_budget: MemoryBudget::default(),
+            audit_retention: audit::AuditRetention::default(),
+            date_order: DateOrder::default(),
         }
     }
 }
@@ -141,6 +344,18 @@ pub struct AppConfig {
     pub audit_log: AuditLog,
 }
 
+impl AppSettings {
+    /// [`Self::analysis_options`] with the memory budget and date order
+    /// filled in
+    pub fn resolved_analysis_options(&self) -> AnalysisOptions {
+        AnalysisOptions {
+            memory_budget: self.memory_budget.clone(),
+            date_order: self.date_order,
+            ..self.analysis_options.clone()
+        }
+    }
+}
+
 impl AppConfig {
     pub fn settings(&self) -> &AppSettings {
         &self.settings
@@ -158,8 +373,17 @@ impl AppConfig {
         self.audit_log = log;
     }
 
+    /// Record an entry in the audit log and the audit files
     pub fn log_event(&mut self, action: impl Into<String>, details: impl Into<String>) {
-        self.audit_log.push(action, details);
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            action: action.into(),
+            details: details.into(),
+        };
+        record_to_file(&entry);
+        self.audit_log.entries.push(entry);
+        self.audit_log
+            .apply_retention(&self.settings.audit_retention);
     }
 
     pub fn connections(&self) -> &[DbConnection] {
@@ -177,12 +401,16 @@ pub fn push_audit_log(config: &mut AppConfig, action: &str, details: &str) {
 
 static PENDING_AUDIT_ENTRIES: Mutex<Vec<AuditEntry>> = Mutex::new(Vec::new());
 
+/// Record `details` under `action` in the audit log, and in the tracing log
+/// with the ID of the run it happened in (see [`crate::utils::runs`])
 pub fn log_event(action: &str, details: &str) {
+    tracing::info!(target: "beefcake::audit", action, "{details}");
     let entry = AuditEntry {
         timestamp: Utc::now(),
         action: action.to_owned(),
         details: details.to_owned(),
     };
+    record_to_file(&entry);
 
     if let Ok(mut pending) = PENDING_AUDIT_ENTRIES.lock() {
         pending.push(entry);
@@ -202,17 +430,20 @@ pub fn flush_pending_audit_entries() {
 
 fn flush_pending_audit_entries_internal(pending: &mut Vec<AuditEntry>) {
     let mut config = load_app_config();
-    for entry in pending.drain(..) {
-        config.audit_log.entries.push(entry);
-    }
-    // Keep only last 1000 entries
-    if config.audit_log.entries.len() > 1000 {
-        let len = config.audit_log.entries.len();
-        config.audit_log.entries.drain(0..len - 1000);
-    }
+    config.audit_log.entries.extend(pending.drain(..));
+    let retention = config.settings.audit_retention;
+    config.audit_log.apply_retention(&retention);
     let _ = save_app_config(&config);
 }
 
+/// Append `entry` to the audit files; a failure is logged, not returned, so
+/// auditing never fails the operation being audited
+fn record_to_file(entry: &AuditEntry) {
+    if let Err(e) = audit::record(entry) {
+        tracing::warn!("Failed to write audit file: {e:#}");
+    }
+}
+
 pub fn get_config_path() -> PathBuf {
     crate::utils::standard_paths().base_dir.join("config.json")
 }
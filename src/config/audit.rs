@@ -0,0 +1,317 @@
+//! Retention, the on-disk audit trail, queries and export of audit entries.
+//!
+//! The audit log in the app config holds the recent entries shown in the
+//! Activity Log, trimmed by [`AuditRetention`]. Every entry is also appended
+//! to `audit.<date>.jsonl` in the logs directory: one file per day, written
+//! only by appending, so the trail outlives the config's limits and a
+//! cleared Activity Log. Files older than `max_age_days` are removed when a
+//! new day's file is started. [`query`] reads entries back from those files
+//! by time range and category (the entry's action), and [`export`] writes
+//! them as CSV or JSON lines.
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::AuditEntry;
+
+/// File name prefix and suffix of the audit files
+pub const AUDIT_FILE_PREFIX: &str = "audit";
+pub const AUDIT_FILE_SUFFIX: &str = "jsonl";
+
+/// Serialises appends from threads of this process
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// How long audit entries are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditRetention {
+    /// Entries kept in the app config; 0 for no limit
+    pub max_entries: usize,
+    /// Days entries are kept, in the app config and on disk; 0 for no limit
+    pub max_age_days: u32,
+}
+
+impl Default for AuditRetention {
+    fn default() -> Self {
+        Self {
+            max_entries: 1000,
+            max_age_days: 90,
+        }
+    }
+}
+
+impl AuditRetention {
+    /// Oldest timestamp kept at `now`, `None` without an age limit
+    pub fn cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (self.max_age_days > 0).then(|| now - chrono::Duration::days(i64::from(self.max_age_days)))
+    }
+
+    /// Drop the entries, oldest first, that the policy does not keep at `now`
+    pub fn apply(&self, entries: &mut Vec<AuditEntry>, now: DateTime<Utc>) {
+        if let Some(cutoff) = self.cutoff(now) {
+            entries.retain(|e| e.timestamp >= cutoff);
+        }
+        if self.max_entries > 0 && entries.len() > self.max_entries {
+            entries.drain(0..entries.len() - self.max_entries);
+        }
+    }
+}
+
+/// Entries to read back from the audit files
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditQuery {
+    /// Earliest timestamp, inclusive
+    pub from: Option<DateTime<Utc>>,
+    /// Latest timestamp, inclusive
+    pub to: Option<DateTime<Utc>>,
+    /// Actions to include, compared without case; all when empty
+    pub categories: Vec<String>,
+    /// Most entries returned, the latest kept; 0 for no limit
+    pub limit: usize,
+}
+
+impl AuditQuery {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.from.is_none_or(|from| entry.timestamp >= from)
+            && self.to.is_none_or(|to| entry.timestamp <= to)
+            && (self.categories.is_empty()
+                || self
+                    .categories
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(&entry.action)))
+    }
+
+    /// Whether the file of `date` can hold matching entries
+    fn covers(&self, date: NaiveDate) -> bool {
+        self.from.is_none_or(|from| date >= from.date_naive())
+            && self.to.is_none_or(|to| date <= to.date_naive())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditExportFormat {
+    Csv,
+    Jsonl,
+}
+
+/// Append `entry` to the audit file of its day in the logs directory
+pub fn record(entry: &AuditEntry) -> Result<()> {
+    let dir = crate::logging::get_log_dir()?;
+    if append(&dir, entry)? {
+        let retention = super::load_app_config().settings.audit_retention;
+        prune_files(&dir, &retention, Utc::now())?;
+    }
+    Ok(())
+}
+
+/// Append `entry` to its day's file in `dir`, returning whether that file
+/// was started by this entry
+fn append(dir: &Path, entry: &AuditEntry) -> Result<bool> {
+    let path = file_for(dir, entry.timestamp.date_naive());
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let _guard = FILE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let started = !path.exists();
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("Failed to append to {}", path.display()))?;
+    Ok(started)
+}
+
+/// Remove the audit files in `dir` older than `retention` keeps at `now`
+pub fn prune_files(dir: &Path, retention: &AuditRetention, now: DateTime<Utc>) -> Result<usize> {
+    let Some(cutoff) = retention.cutoff(now) else {
+        return Ok(0);
+    };
+    let mut removed = 0;
+    for (date, path) in audit_files(dir)? {
+        if date < cutoff.date_naive() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Entries in the audit files in `dir` matching `query`, oldest first
+pub fn query(dir: &Path, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    for (_, path) in audit_files(dir)?
+        .into_iter()
+        .filter(|(date, _)| query.covers(*date))
+    {
+        let reader = BufReader::new(
+            std::fs::File::open(&path)
+                .with_context(|| format!("Failed to open {}", path.display()))?,
+        );
+        for line in reader.lines() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            // A line cut short by a crash is skipped, not fatal
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line)
+                && query.matches(&entry)
+            {
+                entries.push(entry);
+            }
+        }
+    }
+    entries.sort_by_key(|e| e.timestamp);
+    if query.limit > 0 && entries.len() > query.limit {
+        entries.drain(0..entries.len() - query.limit);
+    }
+    Ok(entries)
+}
+
+/// Write `entries` to `path` as `format`, returning how many were written
+pub fn export(entries: &[AuditEntry], path: &Path, format: AuditExportFormat) -> Result<usize> {
+    let mut file = std::fs::File::create(path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    match format {
+        AuditExportFormat::Jsonl => {
+            for entry in entries {
+                serde_json::to_writer(&mut file, entry)?;
+                file.write_all(b"\n")?;
+            }
+        }
+        AuditExportFormat::Csv => {
+            let mut df = df!(
+                "timestamp" => entries.iter().map(|e| e.timestamp.to_rfc3339()).collect::<Vec<_>>(),
+                "action" => entries.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(),
+                "details" => entries.iter().map(|e| e.details.as_str()).collect::<Vec<_>>(),
+            )?;
+            CsvWriter::new(&mut file)
+                .include_header(true)
+                .finish(&mut df)
+                .context("Failed to write CSV file")?;
+        }
+    }
+    Ok(entries.len())
+}
+
+fn file_for(dir: &Path, date: NaiveDate) -> PathBuf {
+    dir.join(format!(
+        "{AUDIT_FILE_PREFIX}.{}.{AUDIT_FILE_SUFFIX}",
+        date.format("%Y-%m-%d")
+    ))
+}
+
+/// Audit files in `dir` with their dates, oldest first
+fn audit_files(dir: &Path) -> Result<Vec<(NaiveDate, PathBuf)>> {
+    let mut files: Vec<(NaiveDate, PathBuf)> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to list {}", dir.display()))?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let date = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(AUDIT_FILE_PREFIX)?
+                .strip_prefix('.')?
+                .strip_suffix(AUDIT_FILE_SUFFIX)?
+                .strip_suffix('.')?;
+            Some((NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?, path))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, action: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp: timestamp.parse().unwrap(),
+            action: action.to_owned(),
+            details: format!("{action} at {timestamp}"),
+        }
+    }
+
+    #[test]
+    fn test_retention_drops_old_and_excess_entries() {
+        let now: DateTime<Utc> = "2026-03-31T00:00:00Z".parse().unwrap();
+        let mut entries = vec![
+            entry("2026-01-01T00:00:00Z", "Export"),
+            entry("2026-03-01T00:00:00Z", "Export"),
+            entry("2026-03-02T00:00:00Z", "Pipeline"),
+            entry("2026-03-03T00:00:00Z", "Export"),
+        ];
+        let retention = AuditRetention {
+            max_entries: 2,
+            max_age_days: 60,
+        };
+        retention.apply(&mut entries, now);
+        let kept: Vec<_> = entries.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(kept, ["Pipeline", "Export"]);
+
+        let mut entries = vec![entry("2020-01-01T00:00:00Z", "Export")];
+        AuditRetention {
+            max_entries: 0,
+            max_age_days: 0,
+        }
+        .apply(&mut entries, now);
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_audit_files_are_queried_pruned_and_exported() {
+        let dir = tempfile::tempdir().unwrap();
+        for e in [
+            entry("2026-03-01T09:00:00Z", "Export"),
+            entry("2026-03-01T10:00:00Z", "Pipeline"),
+            entry("2026-03-02T09:00:00Z", "export"),
+            entry("2026-03-03T09:00:00Z", "Database"),
+        ] {
+            append(dir.path(), &e).unwrap();
+        }
+        assert_eq!(audit_files(dir.path()).unwrap().len(), 3);
+
+        let exports = query(
+            dir.path(),
+            &AuditQuery {
+                categories: vec!["Export".to_owned()],
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(exports.len(), 2);
+
+        let in_range = query(
+            dir.path(),
+            &AuditQuery {
+                from: Some("2026-03-01T10:00:00Z".parse().unwrap()),
+                to: Some("2026-03-02T23:59:59Z".parse().unwrap()),
+                limit: 1,
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(in_range.len(), 1);
+        assert_eq!(in_range[0].action, "export");
+
+        let csv = dir.path().join("audit.csv");
+        assert_eq!(export(&exports, &csv, AuditExportFormat::Csv).unwrap(), 2);
+        let written = std::fs::read_to_string(&csv).unwrap();
+        assert!(written.starts_with("timestamp,action,details"));
+        assert_eq!(written.lines().count(), 3);
+
+        let now = "2026-03-04T12:00:00Z".parse().unwrap();
+        let retention = AuditRetention {
+            max_entries: 0,
+            max_age_days: 2,
+        };
+        assert_eq!(prune_files(dir.path(), &retention, now).unwrap(), 1);
+        assert_eq!(query(dir.path(), &AuditQuery::default()).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,386 @@
+//! Which paths the application may read, write or execute.
+//!
+//! [`PathPolicy`] combines the application's own folders, the flat
+//! `trusted_paths` list and the `path_rules` of the settings into one set of
+//! rules. A rule is a folder or a glob pattern, allows or denies, and may be
+//! scoped to some [`Capability`]s. Deny rules win over allow rules, and a
+//! path no rule allows is not trusted.
+//!
+//! Patterns support `*` and `?` within one path segment and `**` for any
+//! number of segments. A rule matches a path when the pattern matches the
+//! path or a folder above it, so `/data/*/raw` covers everything under each
+//! `raw` folder.
+
+use super::AppSettings;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Capability {
+    Read,
+    Write,
+    /// Running a program, e.g. an external pipeline command
+    Execute,
+}
+
+impl Capability {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Execute => "execute",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEffect {
+    #[default]
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathRule {
+    /// Absolute folder, or glob pattern such as `/data/**/*.csv`
+    pub pattern: String,
+    #[serde(default)]
+    pub effect: RuleEffect,
+    /// Capabilities the rule applies to; empty for all
+    #[serde(default)]
+    pub capabilities: Vec<Capability>,
+}
+
+impl PathRule {
+    pub fn allow(pattern: impl Into<String>, capabilities: &[Capability]) -> Self {
+        Self {
+            pattern: pattern.into(),
+            effect: RuleEffect::Allow,
+            capabilities: capabilities.to_vec(),
+        }
+    }
+
+    pub fn deny(pattern: impl Into<String>, capabilities: &[Capability]) -> Self {
+        Self {
+            pattern: pattern.into(),
+            effect: RuleEffect::Deny,
+            capabilities: capabilities.to_vec(),
+        }
+    }
+
+    /// Reject empty and relative patterns
+    pub fn validate(&self) -> Result<()> {
+        let pattern = self.pattern.trim();
+        if pattern.is_empty() {
+            anyhow::bail!("Path rule pattern is empty");
+        }
+        if !Path::new(pattern).is_absolute() {
+            anyhow::bail!("Path rule pattern must be absolute: {pattern}");
+        }
+        Ok(())
+    }
+
+    fn applies_to(&self, capability: Capability) -> bool {
+        self.capabilities.is_empty() || self.capabilities.contains(&capability)
+    }
+
+    /// Whether the rule covers `path`, which must already be resolved
+    fn matches(&self, path: &Path) -> bool {
+        let pattern = self.pattern.trim();
+        if pattern.is_empty() {
+            return false;
+        }
+        if !has_glob(pattern) {
+            return path.starts_with(resolve(Path::new(pattern)));
+        }
+        let pattern = resolve_glob_base(pattern);
+        path.ancestors().any(|p| glob_match(&pattern, &to_slash(p)))
+    }
+}
+
+/// Outcome of checking one path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum PathDecision {
+    Allowed {
+        pattern: String,
+    },
+    Denied {
+        pattern: String,
+    },
+    /// No rule allows the path
+    NotTrusted,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathPolicy {
+    rules: Vec<PathRule>,
+}
+
+impl PathPolicy {
+    pub fn new(rules: Vec<PathRule>) -> Self {
+        Self { rules }
+    }
+
+    /// The policy of `settings`: the application's data and config folders
+    /// for reading and writing, every trusted path for everything, then the
+    /// configured rules
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        let app_dirs = [dirs::data_local_dir(), dirs::config_dir()]
+            .into_iter()
+            .flatten()
+            .map(|dir| {
+                PathRule::allow(
+                    dir.join("beefcake").to_string_lossy(),
+                    &[Capability::Read, Capability::Write],
+                )
+            });
+        let trusted = settings
+            .trusted_paths
+            .iter()
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| PathRule::allow(p.clone(), &[]));
+        Self::new(
+            app_dirs
+                .chain(trusted)
+                .chain(settings.path_rules.iter().cloned())
+                .collect(),
+        )
+    }
+
+    /// The policy of the saved settings
+    pub fn load() -> Self {
+        Self::from_settings(&super::load_app_config().settings)
+    }
+
+    pub fn evaluate(&self, path: &Path, capability: Capability) -> PathDecision {
+        let path = resolve(path);
+        let matching = |effect: RuleEffect| {
+            self.rules
+                .iter()
+                .filter(|r| r.effect == effect && r.applies_to(capability))
+                .find(|r| r.matches(&path))
+                .map(|r| r.pattern.clone())
+        };
+        if let Some(pattern) = matching(RuleEffect::Deny) {
+            PathDecision::Denied { pattern }
+        } else if let Some(pattern) = matching(RuleEffect::Allow) {
+            PathDecision::Allowed { pattern }
+        } else {
+            PathDecision::NotTrusted
+        }
+    }
+
+    /// The resolved `path` if the policy allows `capability` on it
+    pub fn authorise(&self, path: &Path, capability: Capability) -> Result<PathBuf> {
+        match self.evaluate(path, capability) {
+            PathDecision::Allowed { .. } => Ok(resolve(path)),
+            PathDecision::Denied { pattern } => Err(denied(format!(
+                "{} is blocked for {} by rule '{pattern}'",
+                path.display(),
+                capability.as_str()
+            ))),
+            PathDecision::NotTrusted => Err(denied(format!(
+                "{} is outside the folders trusted for {}",
+                path.display(),
+                capability.as_str()
+            ))),
+        }
+    }
+
+    /// Fail only when a deny rule covers `path`, for paths the user chose
+    /// explicitly such as the watched folder
+    pub fn ensure_not_denied(&self, path: &Path, capability: Capability) -> Result<()> {
+        if let PathDecision::Denied { pattern } = self.evaluate(path, capability) {
+            return Err(denied(format!(
+                "{} is blocked for {} by rule '{pattern}'",
+                path.display(),
+                capability.as_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A denial the command layer reports as [`ErrorCode::PermissionDenied`]
+///
+/// [`ErrorCode::PermissionDenied`]: crate::error::ErrorCode::PermissionDenied
+pub(crate) fn denied(msg: String) -> anyhow::Error {
+    crate::error::BeefcakeError::PermissionDenied(msg).into()
+}
+
+/// Absolute, with `.` and `..` removed and the longest existing prefix
+/// canonicalized, so symlinks and relative segments cannot escape a rule
+pub fn resolve(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    let mut normal = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normal.pop();
+            }
+            other => normal.push(other),
+        }
+    }
+
+    let mut existing = normal.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest
+                .into_iter()
+                .rev()
+                .fold(canonical, |acc, name| acc.join(name));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_owned());
+                existing = parent;
+            }
+            _ => return normal,
+        }
+    }
+}
+
+fn has_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// The pattern with the folders before its first wildcard resolved like
+/// paths are, e.g. `/tmp/*.csv` to `/private/tmp/*.csv` on macOS
+fn resolve_glob_base(pattern: &str) -> String {
+    let pattern = pattern.replace('\\', "/");
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let first_glob = segments
+        .iter()
+        .position(|s| has_glob(s))
+        .unwrap_or(segments.len());
+    let base = segments[..first_glob].join("/");
+    if base.is_empty() {
+        return pattern;
+    }
+    let mut resolved = to_slash(&resolve(Path::new(&base)));
+    for segment in &segments[first_glob..] {
+        resolved.push('/');
+        resolved.push_str(segment);
+    }
+    resolved
+}
+
+fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Whether `path` matches a file pattern. A pattern without a separator,
+/// such as `sales_*.csv`, is matched against the file name only; otherwise
+/// against the whole path.
+pub(crate) fn matches_file_pattern(pattern: &str, path: &Path) -> bool {
+    let pattern = pattern.trim();
+    if pattern.contains(['/', '\\']) {
+        return glob_match(&pattern.replace('\\', "/"), &to_slash(path));
+    }
+    path.file_name()
+        .is_some_and(|name| match_segment(pattern.as_bytes(), name.as_encoded_bytes()))
+}
+
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|s| !s.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => path.split_first().is_some_and(|(first, path_rest)| {
+            match_segment(segment.as_bytes(), first.as_bytes()) && match_segments(rest, path_rest)
+        }),
+    }
+}
+
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some((b'?', rest)) => text
+            .split_first()
+            .is_some_and(|(_, text_rest)| match_segment(rest, text_rest)),
+        Some((c, rest)) => text
+            .split_first()
+            .is_some_and(|(t, text_rest)| t == c && match_segment(rest, text_rest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_globs_deny_rules_and_capabilities() -> Result<()> {
+        let dir = tempdir()?;
+        let root = to_slash(&dir.path().canonicalize()?);
+        std::fs::create_dir_all(dir.path().join("data/secret"))?;
+        let policy = PathPolicy::new(vec![
+            PathRule::allow(format!("{root}/data"), &[Capability::Read]),
+            PathRule::allow(format!("{root}/**/*.csv"), &[Capability::Write]),
+            PathRule::deny(format!("{root}/data/secret"), &[]),
+        ]);
+        let path = |p: &str| dir.path().join(p);
+
+        assert!(matches!(
+            policy.evaluate(&path("data/a.parquet"), Capability::Read),
+            PathDecision::Allowed { .. }
+        ));
+        assert_eq!(
+            policy.evaluate(&path("data/a.parquet"), Capability::Write),
+            PathDecision::NotTrusted
+        );
+        assert!(matches!(
+            policy.evaluate(&path("out/nested/b.csv"), Capability::Write),
+            PathDecision::Allowed { .. }
+        ));
+        assert!(matches!(
+            policy.evaluate(&path("data/secret/c.csv"), Capability::Write),
+            PathDecision::Denied { .. }
+        ));
+        assert_eq!(
+            policy.evaluate(&path("data/a.parquet"), Capability::Execute),
+            PathDecision::NotTrusted
+        );
+
+        // `..` cannot climb out of an allowed folder
+        assert!(
+            policy
+                .authorise(&path("data/missing/../../x.parquet"), Capability::Read)
+                .is_err()
+        );
+        assert!(
+            policy
+                .ensure_not_denied(&path("elsewhere"), Capability::Read)
+                .is_ok()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("/data/**/*.csv", "/data/a.csv"));
+        assert!(glob_match("/data/**/*.csv", "/data/x/y/a.csv"));
+        assert!(!glob_match("/data/**/*.csv", "/data/x/a.json"));
+        assert!(glob_match("/data/*/raw", "/data/sales/raw"));
+        assert!(!glob_match("/data/*/raw", "/data/sales/2024/raw"));
+        assert!(glob_match("/logs/app-??.txt", "/logs/app-01.txt"));
+    }
+}
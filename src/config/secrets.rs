@@ -0,0 +1,393 @@
+//! Moving saved connections and their passwords between machines.
+//!
+//! Passwords live in the OS keyring, which does not travel with the config
+//! file. [`export_connections`] writes the connection definitions together
+//! with their passwords encrypted under a passphrase (AES-256-GCM with a
+//! PBKDF2-SHA256 key), and [`import_connections`] decrypts them and stores
+//! the passwords in the local keyring, leaving only the keyring placeholder
+//! in the config.
+//!
+//! Each password is bound to its whole connection definition as associated
+//! data, so a bundle edited to send the password to another host, user or
+//! database fails to decrypt.
+
+use super::{DbConnection, KEYRING_PLACEHOLDER};
+use aes_gcm::aead::rand_core::RngCore as _;
+use aes_gcm::aead::{Aead as _, AeadCore as _, KeyInit as _, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt::Write as _;
+
+pub const BUNDLE_FORMAT: &str = "beefcake-connections";
+/// Version 1 bound passwords to the connection id only, so its bundles are
+/// refused
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// PBKDF2 rounds for new bundles; stored in the bundle so it can be raised
+/// without breaking older exports
+const KDF_ITERATIONS: u32 = 600_000;
+
+/// Rounds accepted from a bundle: fewer would make the passphrase easy to
+/// guess, more would take minutes to derive the key
+const KDF_ITERATION_RANGE: std::ops::RangeInclusive<u32> = 100_000..=10_000_000;
+
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+const NONCE_LENGTH: usize = 12;
+
+/// A password encrypted with the bundle key, bound to its connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptedSecret {
+    /// Hex-encoded
+    pub nonce: String,
+    /// Hex-encoded, including the authentication tag
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedConnection {
+    /// The saved connection; its password field never holds the password
+    pub connection: DbConnection,
+    /// `None` when no password was saved
+    pub secret: Option<EncryptedSecret>,
+}
+
+/// Connection definitions with passphrase-encrypted passwords
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionBundle {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub kdf_iterations: u32,
+    /// Hex-encoded PBKDF2 salt
+    pub salt: String,
+    pub connections: Vec<ExportedConnection>,
+}
+
+/// Outcome of [`import_connections`], by connection name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionImportReport {
+    pub added: Vec<String>,
+    pub replaced: Vec<String>,
+    /// Already saved here and left unchanged
+    pub skipped: Vec<String>,
+}
+
+/// Bundle `connections` with their passwords, read from the keyring where
+/// saved there, encrypted under `passphrase`
+pub fn export_connections(
+    connections: &[DbConnection],
+    passphrase: &str,
+) -> Result<ConnectionBundle> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        anyhow::bail!("Passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters");
+    }
+    export_connections_with(connections, passphrase, |conn| {
+        conn.settings.get_real_password(&conn.id)
+    })
+}
+
+fn export_connections_with<F>(
+    connections: &[DbConnection],
+    passphrase: &str,
+    mut password: F,
+) -> Result<ConnectionBundle>
+where
+    F: FnMut(&DbConnection) -> String,
+{
+    let mut salt = [0_u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = cipher(passphrase, &salt, KDF_ITERATIONS);
+
+    let mut exported = Vec::with_capacity(connections.len());
+    for conn in connections {
+        let password = password(conn);
+        let mut connection = conn.clone();
+        connection.settings.password = SecretString::new(String::new().into());
+        let secret = if password.is_empty() {
+            None
+        } else {
+            let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+            let ciphertext = cipher
+                .encrypt(
+                    &nonce,
+                    Payload {
+                        msg: password.as_bytes(),
+                        aad: &secret_aad(&connection)?,
+                    },
+                )
+                .map_err(|_| anyhow::anyhow!("Failed to encrypt password of '{}'", conn.name))?;
+            Some(EncryptedSecret {
+                nonce: to_hex(&nonce),
+                ciphertext: to_hex(&ciphertext),
+            })
+        };
+        exported.push(ExportedConnection { connection, secret });
+    }
+
+    Ok(ConnectionBundle {
+        format: BUNDLE_FORMAT.to_owned(),
+        version: BUNDLE_VERSION,
+        created_at: Utc::now(),
+        kdf_iterations: KDF_ITERATIONS,
+        salt: to_hex(&salt),
+        connections: exported,
+    })
+}
+
+impl ConnectionBundle {
+    /// The connections and their plain passwords. Fails if any password
+    /// cannot be decrypted, so a wrong passphrase is reported before any
+    /// connection is imported.
+    pub fn decrypt(&self, passphrase: &str) -> Result<Vec<(DbConnection, Option<String>)>> {
+        if self.format != BUNDLE_FORMAT {
+            anyhow::bail!("Not a connection export (format '{}')", self.format);
+        }
+        if self.version > BUNDLE_VERSION {
+            anyhow::bail!(
+                "Connection export version {} is newer than this app supports ({BUNDLE_VERSION})",
+                self.version
+            );
+        }
+        if self.version < BUNDLE_VERSION {
+            anyhow::bail!(
+                "Connection export version {} is no longer supported; export the connections again",
+                self.version
+            );
+        }
+        if !KDF_ITERATION_RANGE.contains(&self.kdf_iterations) {
+            anyhow::bail!(
+                "Invalid key derivation settings in connection export ({} rounds)",
+                self.kdf_iterations
+            );
+        }
+        let salt = from_hex(&self.salt).context("Invalid salt in connection export")?;
+        let cipher = cipher(passphrase, &salt, self.kdf_iterations);
+
+        self.connections
+            .iter()
+            .map(|exported| {
+                let conn = exported.connection.clone();
+                let Some(secret) = &exported.secret else {
+                    return Ok((conn, None));
+                };
+                let nonce = from_hex(&secret.nonce)?;
+                if nonce.len() != NONCE_LENGTH {
+                    anyhow::bail!("Invalid nonce for '{}'", conn.name);
+                }
+                let ciphertext = from_hex(&secret.ciphertext)?;
+                let plain = cipher
+                    .decrypt(
+                        Nonce::from_slice(&nonce),
+                        Payload {
+                            msg: &ciphertext,
+                            aad: &secret_aad(&conn)?,
+                        },
+                    )
+                    .map_err(|_| {
+                        anyhow::anyhow!("Wrong passphrase or damaged connection export")
+                    })?;
+                let password = String::from_utf8(plain)
+                    .with_context(|| format!("Password of '{}' is not valid UTF-8", conn.name))?;
+                Ok((conn, Some(password)))
+            })
+            .collect()
+    }
+}
+
+/// Add the connections of `bundle` to `connections`, storing their passwords
+/// in the local keyring. Connections already saved under the same id are
+/// replaced when `replace_existing` is set and skipped otherwise.
+pub fn import_connections(
+    connections: &mut Vec<DbConnection>,
+    bundle: &ConnectionBundle,
+    passphrase: &str,
+    replace_existing: bool,
+) -> Result<ConnectionImportReport> {
+    import_connections_with(
+        connections,
+        bundle,
+        passphrase,
+        replace_existing,
+        crate::utils::set_db_password,
+    )
+}
+
+fn import_connections_with<F>(
+    connections: &mut Vec<DbConnection>,
+    bundle: &ConnectionBundle,
+    passphrase: &str,
+    replace_existing: bool,
+    mut store_password: F,
+) -> Result<ConnectionImportReport>
+where
+    F: FnMut(&str, &str) -> Result<()>,
+{
+    let mut report = ConnectionImportReport::default();
+    for (mut conn, password) in bundle.decrypt(passphrase)? {
+        let existing = connections.iter().position(|c| c.id == conn.id);
+        if existing.is_some() && !replace_existing {
+            report.skipped.push(conn.name);
+            continue;
+        }
+
+        let stored = match &password {
+            Some(password) => {
+                store_password(&conn.id, password)
+                    .with_context(|| format!("Failed to save password of '{}'", conn.name))?;
+                KEYRING_PLACEHOLDER
+            }
+            None => "",
+        };
+        conn.settings.password = SecretString::new(stored.into());
+
+        if let Some(idx) = existing {
+            report.replaced.push(conn.name.clone());
+            connections[idx] = conn;
+        } else {
+            report.added.push(conn.name.clone());
+            connections.push(conn);
+        }
+    }
+    Ok(report)
+}
+
+/// Associated data of a connection's password: the connection as exported
+fn secret_aad(connection: &DbConnection) -> Result<Vec<u8>> {
+    serde_json::to_vec(connection).context("Failed to serialise connection")
+}
+
+fn cipher(passphrase: &str, salt: &[u8], iterations: u32) -> Aes256Gcm {
+    let mut key = [0_u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(hex, "{b:02x}");
+    }
+    hex
+}
+
+fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        anyhow::bail!("Invalid hex string");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).context("Invalid hex string"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DbSettings;
+    use secrecy::ExposeSecret as _;
+    use std::collections::HashMap;
+
+    fn connection(id: &str, name: &str) -> DbConnection {
+        DbConnection {
+            id: id.to_owned(),
+            name: name.to_owned(),
+            settings: DbSettings::default(),
+            read_only: false,
+        }
+    }
+
+    #[test]
+    fn test_export_and_import_rekeys_passwords() -> Result<()> {
+        let saved = vec![connection("a", "Production"), connection("b", "Local")];
+        let bundle = export_connections_with(&saved, "correct horse", |c| {
+            if c.id == "a" {
+                "s3cret".to_owned()
+            } else {
+                String::new()
+            }
+        })?;
+
+        let json = serde_json::to_string(&bundle)?;
+        assert!(!json.contains("s3cret"));
+        let bundle: ConnectionBundle = serde_json::from_str(&json)?;
+
+        let mut keyring = HashMap::new();
+        let mut store = |id: &str, pwd: &str| {
+            keyring.insert(id.to_owned(), pwd.to_owned());
+            Ok(())
+        };
+
+        let mut local = Vec::new();
+        assert!(
+            import_connections_with(&mut local, &bundle, "wrong passphrase", false, &mut store)
+                .is_err()
+        );
+        assert!(local.is_empty());
+
+        let report =
+            import_connections_with(&mut local, &bundle, "correct horse", false, &mut store)?;
+        assert_eq!(report.added, vec!["Production", "Local"]);
+        assert_eq!(
+            local[0].settings.password.expose_secret(),
+            KEYRING_PLACEHOLDER
+        );
+        assert_eq!(local[1].settings.password.expose_secret(), "");
+
+        let report =
+            import_connections_with(&mut local, &bundle, "correct horse", false, &mut store)?;
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(local.len(), 2);
+
+        assert_eq!(keyring.get("a").map(String::as_str), Some("s3cret"));
+        assert!(!keyring.contains_key("b"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_only_survives_serialisation() -> Result<()> {
+        let mut saved = connection("a", "Production");
+        saved.read_only = true;
+
+        let json = serde_json::to_string(&saved)?;
+        let loaded: DbConnection = serde_json::from_str(&json)?;
+        assert!(loaded.read_only);
+
+        // Connections saved before the flag existed are writable
+        let mut value = serde_json::to_value(&saved)?;
+        value.as_object_mut().unwrap().remove("read_only");
+        assert!(!serde_json::from_value::<DbConnection>(value)?.read_only);
+
+        let bundle = export_connections_with(&[saved], "correct horse", |_| String::new())?;
+        let bundle: ConnectionBundle = serde_json::from_str(&serde_json::to_string(&bundle)?)?;
+        let mut local = Vec::new();
+        import_connections_with(&mut local, &bundle, "correct horse", false, |_, _| Ok(()))?;
+        assert!(local[0].read_only);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_connection_fails_to_decrypt() -> Result<()> {
+        let saved = vec![connection("a", "Production")];
+        let bundle = export_connections_with(&saved, "correct horse", |_| "s3cret".to_owned())?;
+        assert!(bundle.decrypt("correct horse").is_ok());
+
+        let mut tampered = bundle.clone();
+        tampered.connections[0].connection.settings.host = "attacker.example.com".to_owned();
+        let err = tampered.decrypt("correct horse").unwrap_err();
+        assert!(err.to_string().contains("damaged"));
+
+        let mut tampered = bundle.clone();
+        tampered.connections[0].connection.read_only = true;
+        assert!(tampered.decrypt("correct horse").is_err());
+
+        let mut slow = bundle;
+        slow.kdf_iterations = u32::MAX;
+        let err = slow.decrypt("correct horse").unwrap_err();
+        assert!(err.to_string().contains("key derivation"));
+        Ok(())
+    }
+}
@@ -10,6 +10,20 @@
 //! - **Technical Metadata**: Auto-captured statistics (read-only)
 //! - **Business Metadata**: User-editable semantic layer (descriptions, ownership, etc.)
 //! - **Versioning**: Snapshots link to previous versions via `previous_snapshot_id`
+//! - **Glossary**: Organisation-wide business terms that columns link to, so a
+//!   definition is written once and shared by every dictionary
+//!
+//! Files can also get a baseline snapshot straight from their analysis with
+//! [`save_analysis_snapshot`]; `analyze_file_flow` does this for every file
+//! when `auto_dictionary_snapshot` is enabled in the settings, then prunes
+//! the store by the `dictionary_retention` policy (see [`retention`]).
+//!
+//! [`search_dictionaries`] finds datasets and columns by name, description,
+//! tag or glossary term across every snapshot (see [`search`]).
+//!
+//! Datasets can name an owner and steward and set a review cadence;
+//! [`overdue_reviews`] lists the ones whose documentation is due for review
+//! (see [`review`]).
 //!
 //! ## Usage
 //!
@@ -31,22 +45,146 @@
 //! // Save to disk
 //! save_snapshot(&snapshot, Path::new("data/dictionaries"))?;
 //!
-//! // Export as Markdown
+//! // Export as Markdown, or as HTML and PDF for readers without a Markdown viewer
 //! let markdown = beefcake::dictionary::render_markdown(&snapshot)?;
 //! std::fs::write("dictionary.md", markdown)?;
+//! std::fs::write("dictionary.html", beefcake::dictionary::render_html(&snapshot)?)?;
+//! std::fs::write("dictionary.pdf", beefcake::dictionary::render_pdf(&snapshot)?)?;
 //! # Ok(())
 //! # }
 //! ```
 
+pub mod glossary;
+pub mod html;
 pub mod metadata;
+pub mod pdf;
 pub mod profiler;
 pub mod renderer;
+pub mod retention;
+pub mod review;
+pub mod search;
 pub mod storage;
+pub mod template;
 
+pub use glossary::{Glossary, GlossaryTerm};
+pub use html::{render_html, render_html_with_glossary};
 pub use metadata::{
     ColumnBusinessMetadata, ColumnMetadata, DataDictionary, DatasetBusinessMetadata,
     DatasetMetadata, QualitySummary, TechnicalMetadata,
 };
-pub use profiler::create_snapshot;
-pub use renderer::render_markdown;
-pub use storage::{list_snapshots, load_snapshot, save_snapshot};
+pub use pdf::render_pdf;
+pub use profiler::{create_snapshot, create_snapshot_from_analysis};
+pub use renderer::{render_markdown, render_markdown_with_glossary};
+pub use retention::{
+    DatasetSnapshotUsage, DictionaryStorageUsage, PruneReport, SnapshotRetention, prune_snapshots,
+    storage_usage,
+};
+pub use review::{ReviewStatus, mark_reviewed, overdue_reviews, review_schedule};
+pub use search::{DictionaryIndex, SearchField, SearchHit, SearchOptions, search_dictionaries};
+pub use storage::{
+    latest_snapshot_for_input, latest_snapshots, list_snapshots, load_snapshot, save_snapshot,
+};
+pub use template::{TemplateMergeReport, import_template, render_template};
+
+use crate::analyser::logic::{ColumnSummary, FileHealth};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+/// Base path the application keeps dictionary snapshots under
+pub fn default_dictionary_dir() -> PathBuf {
+    crate::utils::standard_paths().base_dir.join("dictionaries")
+}
+
+/// Create and save a baseline snapshot of an analysed file.
+///
+/// The snapshot links to the newest earlier snapshot of the same file, and
+/// business metadata documented there is carried over for columns that still
+/// exist, so re-profiling a file keeps its documentation.
+pub fn save_analysis_snapshot(
+    input_path: &Path,
+    summary: &[ColumnSummary],
+    row_count: usize,
+    health: &FileHealth,
+    base_path: &Path,
+) -> Result<DataDictionary> {
+    let previous = latest_snapshot_for_input(base_path, input_path)?;
+    let dataset_name = previous.as_ref().map_or_else(
+        || {
+            input_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("dataset")
+                .to_owned()
+        },
+        |p| p.dataset_name.clone(),
+    );
+
+    let mut snapshot = create_snapshot_from_analysis(
+        &dataset_name,
+        input_path,
+        summary,
+        row_count,
+        health,
+        previous.as_ref().map(|p| p.snapshot_id),
+    )?;
+
+    if let Some(previous) = previous {
+        snapshot.dataset_metadata.business = previous.dataset_metadata.business;
+        for column in &mut snapshot.columns {
+            if let Some(old) = previous
+                .columns
+                .iter()
+                .find(|c| c.current_name == column.current_name)
+            {
+                column.business = old.business.clone();
+            }
+        }
+    }
+
+    save_snapshot(&snapshot, base_path)?;
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyser::logic::analyse_df_lazy;
+    use polars::prelude::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_analysis_snapshot_links_and_keeps_docs() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let input = temp_dir.path().join("orders.csv");
+        std::fs::write(&input, "id,amount\n1,9.5\n2,12\n")?;
+        let df = df! {
+            "id" => &[1_i64, 2],
+            "amount" => &[9.5, 12.0],
+        }?;
+        let summary = analyse_df_lazy(df.lazy(), 0.0, 100)?;
+        let health = FileHealth {
+            score: 90.0,
+            risks: vec![],
+        };
+        let base = temp_dir.path().join("dict");
+
+        let mut first = save_analysis_snapshot(&input, &summary, 2, &health, &base)?;
+        assert_eq!(first.dataset_name, "orders");
+        assert_eq!(first.previous_snapshot_id, None);
+        assert_eq!(first.dataset_metadata.technical.row_count, 2);
+        assert_eq!(first.dataset_metadata.technical.export_format, "csv");
+        assert!(first.columns[0].technical.inferred_description.is_some());
+
+        first.columns[1].business.business_definition = Some("Order total".to_owned());
+        save_snapshot(&first, &base)?;
+
+        let second = save_analysis_snapshot(&input, &summary, 2, &health, &base)?;
+        assert_eq!(second.previous_snapshot_id, Some(first.snapshot_id));
+        assert_eq!(
+            second.columns[1].business.business_definition.as_deref(),
+            Some("Order total")
+        );
+        assert_eq!(list_snapshots(&base, None)?.len(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Organisation-wide glossary of business terms.
+//!
+//! A term is defined once, with its owner and synonyms, and columns of any
+//! snapshot link to it through `ColumnBusinessMetadata::glossary_terms`
+//! instead of repeating the definition. Links are by term id, so renaming a
+//! term or editing its definition updates every dictionary that uses it.
+//! The glossary is one JSON file next to the `dictionaries/` directory.
+
+use super::metadata::DataDictionary;
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+pub const GLOSSARY_FILE: &str = "glossary.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlossaryTerm {
+    /// Generated when a new term is submitted without one
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+    pub term: String,
+    pub definition: String,
+    pub owner: Option<String>,
+    /// Other names the business uses for the term
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl GlossaryTerm {
+    pub fn new(term: impl Into<String>, definition: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            term: term.into(),
+            definition: definition.into(),
+            owner: None,
+            synonyms: Vec::new(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Whether `name` is the term or one of its synonyms, ignoring case
+    pub fn is_named(&self, name: &str) -> bool {
+        let name = name.trim();
+        self.term.trim().eq_ignore_ascii_case(name)
+            || self
+                .synonyms
+                .iter()
+                .any(|s| s.trim().eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Glossary {
+    /// Sorted by term
+    pub terms: Vec<GlossaryTerm>,
+}
+
+impl Glossary {
+    pub fn path(base_path: &Path) -> PathBuf {
+        base_path.join(GLOSSARY_FILE)
+    }
+
+    /// The glossary under `base_path`, empty if none has been saved
+    pub fn load(base_path: &Path) -> Result<Self> {
+        let path = Self::path(base_path);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read glossary: {}", path.display()))?;
+        serde_json::from_str(&json).context("Failed to parse glossary")
+    }
+
+    pub fn save(&self, base_path: &Path) -> Result<()> {
+        std::fs::create_dir_all(base_path).context("Failed to create dictionary directory")?;
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize glossary")?;
+        std::fs::write(Self::path(base_path), json).context("Failed to write glossary")
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&GlossaryTerm> {
+        self.terms.iter().find(|t| &t.id == id)
+    }
+
+    /// The term called `name`, or with `name` as a synonym
+    pub fn find(&self, name: &str) -> Option<&GlossaryTerm> {
+        self.terms.iter().find(|t| t.is_named(name))
+    }
+
+    /// Add `term`, or replace the term with the same id. Fails if the term
+    /// or definition is empty, or if its name or a synonym is already used
+    /// by another term.
+    pub fn upsert(&mut self, mut term: GlossaryTerm) -> Result<&GlossaryTerm> {
+        term.term = term.term.trim().to_owned();
+        term.definition = term.definition.trim().to_owned();
+        term.synonyms.retain(|s| !s.trim().is_empty());
+        if term.term.is_empty() {
+            anyhow::bail!("Glossary term name is empty");
+        }
+        if term.definition.is_empty() {
+            anyhow::bail!("Glossary term '{}' has no definition", term.term);
+        }
+        for name in std::iter::once(&term.term).chain(&term.synonyms) {
+            if let Some(other) = self
+                .terms
+                .iter()
+                .find(|t| t.id != term.id && t.is_named(name))
+            {
+                anyhow::bail!("'{name}' is already used by glossary term '{}'", other.term);
+            }
+        }
+
+        term.updated_at = Utc::now();
+        let id = term.id;
+        match self.terms.iter_mut().find(|t| t.id == id) {
+            Some(existing) => *existing = term,
+            None => self.terms.push(term),
+        }
+        self.terms.sort_by_key(|t| t.term.to_lowercase());
+        self.get(&id).context("Glossary term missing after insert")
+    }
+
+    /// Remove a term. Columns linking to it keep the id, which renderers
+    /// skip.
+    pub fn remove(&mut self, id: &Uuid) -> bool {
+        let before = self.terms.len();
+        self.terms.retain(|t| &t.id != id);
+        self.terms.len() != before
+    }
+
+    /// Terms linked from any column of `dict`, in glossary order
+    pub fn linked_terms(&self, dict: &DataDictionary) -> Vec<&GlossaryTerm> {
+        self.terms
+            .iter()
+            .filter(|t| {
+                dict.columns
+                    .iter()
+                    .any(|c| c.business.glossary_terms.contains(&t.id))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_upsert_validates_and_persists() -> Result<()> {
+        let dir = tempdir()?;
+        let mut glossary = Glossary::default();
+
+        let mut customer = GlossaryTerm::new("Customer", "A party that has placed an order");
+        customer.synonyms = vec!["Client".to_owned()];
+        let customer_id = glossary.upsert(customer)?.id;
+        glossary.upsert(GlossaryTerm::new(
+            "Active account",
+            "Logged in within 90 days",
+        ))?;
+
+        assert!(
+            glossary
+                .upsert(GlossaryTerm::new("client", "Duplicate of a synonym"))
+                .is_err()
+        );
+        assert!(glossary.upsert(GlossaryTerm::new("Revenue", " ")).is_err());
+
+        let mut renamed = glossary.get(&customer_id).cloned().expect("customer");
+        renamed.term = "Buyer".to_owned();
+        glossary.upsert(renamed)?;
+        assert_eq!(glossary.find("CLIENT").map(|t| t.id), Some(customer_id));
+
+        glossary.save(dir.path())?;
+        let loaded = Glossary::load(dir.path())?;
+        let names: Vec<&str> = loaded.terms.iter().map(|t| t.term.as_str()).collect();
+        assert_eq!(names, vec!["Active account", "Buyer"]);
+        Ok(())
+    }
+}
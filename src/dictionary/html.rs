@@ -0,0 +1,350 @@
+//! HTML rendering for data dictionary snapshots.
+//!
+//! Produces a single self-contained page: styles are inline and each
+//! column's distribution is drawn as an embedded SVG sparkline, so the file
+//! can be emailed or attached without any other assets.
+
+use super::glossary::Glossary;
+use super::metadata::{ColumnMetadata, DataDictionary};
+use super::renderer::distribution;
+use anyhow::Result;
+use std::fmt::Write as _;
+
+const SPARKLINE_WIDTH: f64 = 120.0;
+const SPARKLINE_HEIGHT: f64 = 28.0;
+
+const STYLE: &str = "body{font-family:-apple-system,'Segoe UI',Helvetica,Arial,sans-serif;\
+max-width:960px;margin:2rem auto;padding:0 1rem;color:#1f2933;line-height:1.5}\
+h1{margin-bottom:.25rem}h2{border-bottom:2px solid #e4e7eb;padding-bottom:.25rem;margin-top:2rem}\
+.meta{color:#616e7c;font-size:.9rem}\
+table{border-collapse:collapse;width:100%;margin:.5rem 0 1rem}\
+th,td{border:1px solid #e4e7eb;padding:.35rem .6rem;text-align:left;vertical-align:top;font-size:.9rem}\
+th{background:#f5f7fa}code{background:#f5f7fa;padding:0 .2rem;border-radius:3px}\
+.warn{color:#b44d12}.muted{color:#9aa5b1;font-style:italic}svg{display:block}";
+
+/// Render a data dictionary as a self-contained HTML document, with the
+/// same sections as [`super::render_markdown`].
+pub fn render_html(dict: &DataDictionary) -> Result<String> {
+    render_html_with_glossary(dict, &Glossary::default())
+}
+
+/// Render a data dictionary as HTML with the glossary terms linked from its
+/// columns, like [`super::renderer::render_markdown_with_glossary`].
+pub fn render_html_with_glossary(dict: &DataDictionary, glossary: &Glossary) -> Result<String> {
+    let tech = &dict.dataset_metadata.technical;
+    let business = &dict.dataset_metadata.business;
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Data Dictionary: {name}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Data Dictionary: {name}</h1>\n\
+         <p class=\"meta\">Snapshot <code>{}</code> &middot; created {} &middot; \
+         documentation {:.1}% complete</p>\n",
+        dict.snapshot_id,
+        dict.export_timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+        dict.documentation_completeness(),
+        name = escape(&dict.dataset_name),
+    );
+
+    // Dataset overview
+    html.push_str("<h2>Dataset Overview</h2>\n");
+    let review = business.review_summary();
+    let overview = [
+        ("Description", &business.description),
+        ("Intended Use", &business.intended_use),
+        ("Owner/Steward", &business.owner_or_steward),
+        ("Owner", &business.owner),
+        ("Steward", &business.steward),
+        ("Review", &review),
+        ("Refresh Cadence", &business.refresh_expectation),
+        ("Sensitivity", &business.sensitivity_classification),
+        ("Known Limitations", &business.known_limitations),
+    ];
+    if overview.iter().all(|(_, value)| value.is_none()) && business.tags.is_empty() {
+        html.push_str("<p class=\"muted\">No dataset-level documentation provided.</p>\n");
+    } else {
+        html.push_str("<table>\n");
+        for (label, value) in overview {
+            if let Some(value) = value {
+                row(&mut html, label, &escape(value));
+            }
+        }
+        if !business.tags.is_empty() {
+            row(&mut html, "Tags", &escape(&business.tags.join(", ")));
+        }
+        html.push_str("</table>\n");
+    }
+
+    // Column catalog
+    let _ = write!(
+        html,
+        "<h2>Column Catalog</h2>\n<p>{} columns, {} rows</p>\n<table>\n\
+         <tr><th>Column</th><th>Type</th><th>Nulls</th><th>Distinct</th>\
+         <th>Distribution</th><th>Definition</th></tr>\n",
+        dict.columns.len(),
+        tech.row_count
+    );
+    for col in &dict.columns {
+        render_column_row(&mut html, col, glossary);
+    }
+    html.push_str("</table>\n");
+
+    // Data quality
+    let quality = &tech.quality_summary;
+    html.push_str("<h2>Data Quality Summary</h2>\n<table>\n");
+    row(
+        &mut html,
+        "Overall Quality Score",
+        &format!("{:.1}%", quality.overall_score),
+    );
+    row(
+        &mut html,
+        "Avg Null %",
+        &format!("{:.2}%", quality.avg_null_percentage),
+    );
+    row(
+        &mut html,
+        "Empty Columns",
+        &quality.empty_column_count.to_string(),
+    );
+    row(
+        &mut html,
+        "Constant Columns",
+        &quality.constant_column_count.to_string(),
+    );
+    if let Some(duplicates) = quality.duplicate_row_count {
+        row(&mut html, "Duplicate Rows", &duplicates.to_string());
+    }
+    html.push_str("</table>\n");
+
+    let warned = dict.columns_with_warnings();
+    if !warned.is_empty() {
+        html.push_str("<h3>Columns with Warnings</h3>\n<ul>\n");
+        for col in warned {
+            let _ = writeln!(
+                html,
+                "<li><strong>{}</strong>: <span class=\"warn\">{}</span></li>",
+                escape(&col.current_name),
+                escape(&col.technical.warnings.join("; "))
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+
+    // Technical metadata
+    html.push_str("<h2>Technical Metadata</h2>\n<table>\n");
+    row(&mut html, "Row Count", &tech.row_count.to_string());
+    row(&mut html, "Column Count", &tech.column_count.to_string());
+    row(
+        &mut html,
+        "Export Format",
+        &format!("<code>{}</code>", escape(&tech.export_format)),
+    );
+    row(
+        &mut html,
+        "Output Hash",
+        &format!("<code>{}</code>", escape(&tech.output_dataset_hash)),
+    );
+    if let Some(input_hash) = &tech.input_dataset_hash {
+        row(
+            &mut html,
+            "Input Hash",
+            &format!("<code>{}</code>", escape(input_hash)),
+        );
+    }
+    if let Some(pipeline_id) = tech.pipeline_id {
+        row(
+            &mut html,
+            "Pipeline ID",
+            &format!("<code>{pipeline_id}</code>"),
+        );
+    }
+    for source in &tech.input_sources {
+        row(
+            &mut html,
+            "Input Source",
+            &format!("<code>{}</code>", escape(&source.path)),
+        );
+    }
+    if let Some(prev_id) = dict.previous_snapshot_id {
+        row(
+            &mut html,
+            "Previous Snapshot",
+            &format!("<code>{prev_id}</code>"),
+        );
+    }
+    html.push_str("</table>\n");
+
+    if let Some(pipeline_json) = &tech.pipeline_json {
+        let _ = write!(
+            html,
+            "<details><summary>Pipeline Specification</summary>\n<pre>{}</pre>\n</details>\n",
+            escape(pipeline_json)
+        );
+    }
+
+    let linked_terms = glossary.linked_terms(dict);
+    if !linked_terms.is_empty() {
+        html.push_str(
+            "<h2 id=\"glossary\">Glossary</h2>\n<table>\n\
+                       <tr><th>Term</th><th>Definition</th><th>Owner</th><th>Synonyms</th></tr>\n",
+        );
+        for term in linked_terms {
+            let _ = writeln!(
+                html,
+                "<tr><td id=\"term-{}\"><strong>{}</strong></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                term.id,
+                escape(&term.term),
+                escape(&term.definition),
+                escape(term.owner.as_deref().unwrap_or("")),
+                escape(&term.synonyms.join(", "))
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+fn render_column_row(html: &mut String, col: &ColumnMetadata, glossary: &Glossary) {
+    let tech = &col.technical;
+    let mut name = format!("<code>{}</code>", escape(&col.current_name));
+    if let Some(original) = &col.original_name {
+        let _ = write!(
+            name,
+            "<br><span class=\"meta\">was {}</span>",
+            escape(original)
+        );
+    }
+
+    let mut definition = match (
+        &col.business.business_definition,
+        &tech.inferred_description,
+    ) {
+        (Some(def), _) => escape(def),
+        (None, Some(inferred)) => format!("<span class=\"muted\">{}</span>", escape(inferred)),
+        (None, None) => "<span class=\"muted\">No definition provided.</span>".to_owned(),
+    };
+    if let Some(sensitivity) = &col.business.sensitivity_tag {
+        let _ = write!(
+            definition,
+            "<br><strong>Sensitivity:</strong> {}",
+            escape(sensitivity)
+        );
+    }
+    let terms: Vec<String> = col
+        .business
+        .glossary_terms
+        .iter()
+        .filter_map(|id| glossary.get(id))
+        .map(|t| format!("<a href=\"#term-{}\">{}</a>", t.id, escape(&t.term)))
+        .collect();
+    if !terms.is_empty() {
+        let _ = write!(
+            definition,
+            "<br><strong>Glossary:</strong> {}",
+            terms.join(", ")
+        );
+    }
+    if let (Some(min), Some(max)) = (&tech.min_value, &tech.max_value) {
+        let _ = write!(
+            definition,
+            "<br><span class=\"meta\">Range {} to {}</span>",
+            escape(min),
+            escape(max)
+        );
+    }
+    if !tech.sample_values.is_empty() {
+        let _ = write!(
+            definition,
+            "<br><span class=\"meta\">e.g. {}</span>",
+            escape(&tech.sample_values.join(", "))
+        );
+    }
+    for warning in &tech.warnings {
+        let _ = write!(
+            definition,
+            "<br><span class=\"warn\">{}</span>",
+            escape(warning)
+        );
+    }
+
+    let _ = writeln!(
+        html,
+        "<tr><td>{name}</td><td>{}</td><td>{:.1}%</td><td>{}</td><td>{}</td><td>{definition}</td></tr>",
+        escape(&tech.data_type),
+        tech.null_percentage,
+        tech.distinct_count,
+        distribution(col).map_or_else(String::new, |counts| sparkline_svg(&counts)),
+    );
+}
+
+/// Inline SVG bar chart of `counts`, scaled to the largest count
+fn sparkline_svg(counts: &[usize]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let bar_width = SPARKLINE_WIDTH / counts.len() as f64;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{SPARKLINE_WIDTH}\" \
+         height=\"{SPARKLINE_HEIGHT}\" viewBox=\"0 0 {SPARKLINE_WIDTH} {SPARKLINE_HEIGHT}\" \
+         role=\"img\" aria-label=\"Distribution\">"
+    );
+    for (i, &count) in counts.iter().enumerate() {
+        let height = (count as f64 / max * SPARKLINE_HEIGHT).max(if count > 0 { 1.0 } else { 0.0 });
+        let _ = write!(
+            svg,
+            "<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{height:.2}\" fill=\"#3e7bfa\"/>",
+            i as f64 * bar_width,
+            SPARKLINE_HEIGHT - height,
+            (bar_width - 1.0).max(0.5),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn row(html: &mut String, label: &str, value: &str) {
+    let _ = writeln!(html, "<tr><th>{label}</th><td>{value}</td></tr>");
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::profiler::create_snapshot;
+    use polars::prelude::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_render_html_with_sparklines() -> Result<()> {
+        let df = df! {
+            "amount" => &[1.0, 2.0, 2.5, 3.0, 10.0],
+            "label" => &["<b>", "a", "a", "b", "b"],
+        }?;
+        let dict = create_snapshot(
+            "Sales & Returns",
+            &df,
+            PathBuf::from("input.csv"),
+            PathBuf::from("output.csv"),
+            None,
+            None,
+        )?;
+
+        let html = render_html(&dict)?;
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Data Dictionary: Sales &amp; Returns</title>"));
+        assert!(html.contains("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(!html.contains("<b>"));
+        assert!(!html.contains("<link") && !html.contains("<script"));
+        Ok(())
+    }
+}
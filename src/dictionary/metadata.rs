@@ -1,9 +1,11 @@
 //! Core data structures for data dictionary snapshots.
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::analyser::logic::SemanticType;
+
 /// Complete data dictionary snapshot for a dataset export.
 ///
 /// Combines technical metadata (immutable) with business metadata (user-editable).
@@ -119,6 +121,43 @@ pub struct DatasetBusinessMetadata {
 
     /// Additional free-form tags
     pub tags: Vec<String>,
+
+    /// Person or team accountable for the dataset
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    /// Person who keeps the dataset and its documentation current
+    #[serde(default)]
+    pub steward: Option<String>,
+
+    /// Days between documentation reviews; `None` if reviews are not tracked
+    #[serde(default)]
+    pub review_cadence_days: Option<u32>,
+
+    /// Date the documentation was last reviewed
+    #[serde(default)]
+    pub last_reviewed: Option<NaiveDate>,
+}
+
+impl DatasetBusinessMetadata {
+    /// When the next review is due, or `None` if reviews are not tracked.
+    /// A dataset that was never reviewed is due on `today`.
+    pub fn next_review(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let cadence = self.review_cadence_days?;
+        Some(self.last_reviewed.map_or(today, |last| {
+            last.checked_add_days(Days::new(u64::from(cadence)))
+                .unwrap_or(NaiveDate::MAX)
+        }))
+    }
+
+    /// The review cadence and last review, for rendering
+    pub fn review_summary(&self) -> Option<String> {
+        let cadence = self.review_cadence_days?;
+        let last = self
+            .last_reviewed
+            .map_or_else(|| "never".to_owned(), |d| d.to_string());
+        Some(format!("Every {cadence} days, last reviewed {last}"))
+    }
 }
 
 /// Per-column metadata combining technical profiling and business context.
@@ -169,10 +208,18 @@ pub struct ColumnTechnicalMetadata {
 
     /// Statistical summary (optional, JSON-encoded `ColumnStats`)
     pub stats_json: Option<String>,
+
+    /// Plain-English description generated by the profiler
+    #[serde(default)]
+    pub inferred_description: Option<String>,
+
+    /// What the values are, e.g. email addresses, when recognised
+    #[serde(default)]
+    pub semantic_type: Option<SemanticType>,
 }
 
 /// User-editable business metadata for a single column.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct ColumnBusinessMetadata {
     /// Plain-English definition of what this column means
     pub business_definition: Option<String>,
@@ -188,6 +235,10 @@ pub struct ColumnBusinessMetadata {
 
     /// Free-form notes for this column
     pub notes: Option<String>,
+
+    /// Ids of the glossary terms this column represents
+    #[serde(default)]
+    pub glossary_terms: Vec<Uuid>,
 }
 
 impl DataDictionary {
@@ -208,7 +259,7 @@ impl DataDictionary {
         if ds_meta.intended_use.is_some() {
             filled += 1;
         }
-        if ds_meta.owner_or_steward.is_some() {
+        if ds_meta.owner_or_steward.is_some() || ds_meta.owner.is_some() {
             filled += 1;
         }
         if ds_meta.refresh_expectation.is_some() {
@@ -0,0 +1,450 @@
+//! PDF rendering for data dictionary snapshots.
+//!
+//! A small PDF 1.4 writer using the standard Helvetica fonts, so no font
+//! files or external tools are needed. Text is wrapped using an average
+//! character width, and characters outside Latin-1 are written as `?`.
+
+use super::metadata::{ColumnMetadata, DataDictionary};
+use super::renderer::distribution;
+use anyhow::Result;
+use std::fmt::Write as _;
+
+/// A4 in points
+const PAGE_WIDTH: f64 = 595.0;
+const PAGE_HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 50.0;
+
+/// Average Helvetica glyph width as a share of the font size
+const AVG_CHAR_WIDTH: f64 = 0.5;
+const LINE_SPACING: f64 = 1.35;
+
+const SPARKLINE_WIDTH: f64 = 160.0;
+const SPARKLINE_HEIGHT: f64 = 24.0;
+
+/// Render a data dictionary as a PDF document, with the same sections as
+/// [`super::render_markdown`].
+pub fn render_pdf(dict: &DataDictionary) -> Result<Vec<u8>> {
+    let tech = &dict.dataset_metadata.technical;
+    let business = &dict.dataset_metadata.business;
+    let mut pdf = PdfWriter::new();
+
+    pdf.paragraph(
+        Font::Bold,
+        20.0,
+        0.0,
+        &format!("Data Dictionary: {}", dict.dataset_name),
+    );
+    pdf.paragraph(
+        Font::Regular,
+        9.0,
+        0.0,
+        &format!(
+            "Snapshot {} \u{b7} created {} \u{b7} documentation {:.1}% complete",
+            dict.snapshot_id,
+            dict.export_timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+            dict.documentation_completeness()
+        ),
+    );
+
+    pdf.heading("Dataset Overview");
+    let review = business.review_summary();
+    let overview = [
+        ("Description", &business.description),
+        ("Intended Use", &business.intended_use),
+        ("Owner/Steward", &business.owner_or_steward),
+        ("Owner", &business.owner),
+        ("Steward", &business.steward),
+        ("Review", &review),
+        ("Refresh Cadence", &business.refresh_expectation),
+        ("Sensitivity", &business.sensitivity_classification),
+        ("Known Limitations", &business.known_limitations),
+    ];
+    if overview.iter().all(|(_, value)| value.is_none()) && business.tags.is_empty() {
+        pdf.paragraph(
+            Font::Regular,
+            10.0,
+            0.0,
+            "No dataset-level documentation provided.",
+        );
+    }
+    for (label, value) in overview {
+        if let Some(value) = value {
+            pdf.field(label, value);
+        }
+    }
+    if !business.tags.is_empty() {
+        pdf.field("Tags", &business.tags.join(", "));
+    }
+
+    pdf.heading("Column Catalog");
+    pdf.paragraph(
+        Font::Regular,
+        10.0,
+        0.0,
+        &format!("{} columns, {} rows", dict.columns.len(), tech.row_count),
+    );
+    for col in &dict.columns {
+        render_column(&mut pdf, col);
+    }
+
+    let quality = &tech.quality_summary;
+    pdf.heading("Data Quality Summary");
+    pdf.field(
+        "Overall Quality Score",
+        &format!("{:.1}%", quality.overall_score),
+    );
+    pdf.field(
+        "Avg Null %",
+        &format!("{:.2}%", quality.avg_null_percentage),
+    );
+    pdf.field("Empty Columns", &quality.empty_column_count.to_string());
+    pdf.field(
+        "Constant Columns",
+        &quality.constant_column_count.to_string(),
+    );
+    if let Some(duplicates) = quality.duplicate_row_count {
+        pdf.field("Duplicate Rows", &duplicates.to_string());
+    }
+
+    pdf.heading("Technical Metadata");
+    pdf.field("Row Count", &tech.row_count.to_string());
+    pdf.field("Column Count", &tech.column_count.to_string());
+    pdf.field("Export Format", &tech.export_format);
+    pdf.field("Output Hash", &tech.output_dataset_hash);
+    if let Some(input_hash) = &tech.input_dataset_hash {
+        pdf.field("Input Hash", input_hash);
+    }
+    if let Some(pipeline_id) = tech.pipeline_id {
+        pdf.field("Pipeline ID", &pipeline_id.to_string());
+    }
+    for source in &tech.input_sources {
+        pdf.field("Input Source", &source.path);
+    }
+    if let Some(prev_id) = dict.previous_snapshot_id {
+        pdf.field("Previous Snapshot", &prev_id.to_string());
+    }
+
+    Ok(pdf.finish())
+}
+
+fn render_column(pdf: &mut PdfWriter, col: &ColumnMetadata) {
+    let tech = &col.technical;
+    pdf.gap(6.0);
+    let mut title = format!("{}  ({})", col.current_name, tech.data_type);
+    if let Some(original) = &col.original_name {
+        let _ = write!(title, ", was {original}");
+    }
+    pdf.paragraph(Font::Bold, 11.0, 0.0, &title);
+
+    let mut facts = format!(
+        "Nulls {:.1}% \u{b7} {} distinct",
+        tech.null_percentage, tech.distinct_count
+    );
+    if let (Some(min), Some(max)) = (&tech.min_value, &tech.max_value) {
+        let _ = write!(facts, " \u{b7} range {min} to {max}");
+    }
+    pdf.paragraph(Font::Regular, 9.0, 12.0, &facts);
+
+    match (
+        &col.business.business_definition,
+        &tech.inferred_description,
+    ) {
+        (Some(def), _) => pdf.paragraph(Font::Regular, 10.0, 12.0, def),
+        (None, Some(inferred)) => {
+            pdf.paragraph(Font::Regular, 10.0, 12.0, &format!("Inferred: {inferred}"));
+        }
+        (None, None) => pdf.paragraph(Font::Regular, 10.0, 12.0, "No definition provided."),
+    }
+    if let Some(sensitivity) = &col.business.sensitivity_tag {
+        pdf.paragraph(
+            Font::Regular,
+            9.0,
+            12.0,
+            &format!("Sensitivity: {sensitivity}"),
+        );
+    }
+    if !tech.sample_values.is_empty() {
+        pdf.paragraph(
+            Font::Regular,
+            9.0,
+            12.0,
+            &format!("e.g. {}", tech.sample_values.join(", ")),
+        );
+    }
+    for warning in &tech.warnings {
+        pdf.paragraph(Font::Regular, 9.0, 12.0, &format!("Warning: {warning}"));
+    }
+    if let Some(counts) = distribution(col) {
+        pdf.sparkline(&counts, 12.0);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Self::Regular => "F1",
+            Self::Bold => "F2",
+        }
+    }
+}
+
+/// Lays out text top to bottom, starting a new page when one is full
+struct PdfWriter {
+    /// Content streams of finished pages
+    pages: Vec<String>,
+    content: String,
+    /// Baseline of the next line, from the bottom of the page
+    y: f64,
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            content: String::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f64) {
+        if self.y - height < MARGIN {
+            self.pages.push(std::mem::take(&mut self.content));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn gap(&mut self, height: f64) {
+        self.y -= height;
+    }
+
+    fn heading(&mut self, text: &str) {
+        // Keep a heading on the same page as the line after it
+        self.ensure_space(50.0);
+        self.gap(10.0);
+        self.paragraph(Font::Bold, 14.0, 0.0, text);
+        let _ = writeln!(
+            self.content,
+            "0.8 0.82 0.85 RG 0.8 w {MARGIN} {:.2} m {:.2} {:.2} l S",
+            self.y - 4.0,
+            PAGE_WIDTH - MARGIN,
+            self.y - 4.0
+        );
+        self.gap(8.0);
+    }
+
+    fn field(&mut self, label: &str, value: &str) {
+        self.paragraph(Font::Regular, 10.0, 0.0, &format!("{label}: {value}"));
+    }
+
+    /// Word-wrapped text; words longer than a line are split
+    fn paragraph(&mut self, font: Font, size: f64, indent: f64, text: &str) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN - indent;
+        let max_chars = ((width / (size * AVG_CHAR_WIDTH)) as usize).max(1);
+        for line in wrap(text, max_chars) {
+            let height = size * LINE_SPACING;
+            self.ensure_space(height);
+            self.y -= height;
+            let _ = writeln!(
+                self.content,
+                "BT /{} {size} Tf {:.2} {:.2} Td ({}) Tj ET",
+                font.resource(),
+                MARGIN + indent,
+                self.y,
+                pdf_string(&line)
+            );
+        }
+    }
+
+    /// Bar chart of `counts`, scaled to the largest count
+    fn sparkline(&mut self, counts: &[usize], indent: f64) {
+        self.ensure_space(SPARKLINE_HEIGHT + 6.0);
+        self.y -= SPARKLINE_HEIGHT + 6.0;
+        let max = counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let bar_width = SPARKLINE_WIDTH / counts.len() as f64;
+        self.content.push_str("0.24 0.48 0.98 rg\n");
+        for (i, &count) in counts.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let height = (count as f64 / max * SPARKLINE_HEIGHT).max(1.0);
+            let _ = writeln!(
+                self.content,
+                "{:.2} {:.2} {:.2} {height:.2} re f",
+                MARGIN + indent + i as f64 * bar_width,
+                self.y,
+                (bar_width - 1.0).max(0.5),
+            );
+        }
+        self.content.push_str("0 g\n");
+    }
+
+    /// The document with page numbers in the footer
+    fn finish(mut self) -> Vec<u8> {
+        if !self.content.is_empty() || self.pages.is_empty() {
+            self.pages.push(std::mem::take(&mut self.content));
+        }
+        let page_count = self.pages.len();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_owned(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+                (0..page_count)
+                    .map(|i| format!("{} 0 R", 5 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_owned(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_owned(),
+        ];
+        for (i, mut content) in self.pages.into_iter().enumerate() {
+            let _ = writeln!(
+                content,
+                "BT /F1 8 Tf {:.2} 30 Td (Page {} of {page_count}) Tj ET",
+                PAGE_WIDTH - MARGIN - 50.0,
+                i + 1
+            );
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}endstream",
+                content.len()
+            ));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            // Content is ASCII only, so string and byte offsets agree
+            offsets.push(out.len());
+            let _ = write!(out, "{} 0 obj\n{object}\nendobj\n", i + 1);
+        }
+        let xref = out.len();
+        let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = write!(out, "{offset:010} 00000 n \n");
+        }
+        let _ = write!(
+            out,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        );
+        out.into_bytes()
+    }
+}
+
+/// Greedy word wrap to at most `max_chars` characters per line
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut line = String::new();
+        let mut line_len = 0;
+        for word in source_line.split_whitespace() {
+            let mut word: Vec<char> = word.chars().collect();
+            while word.len() > max_chars {
+                if line_len > 0 {
+                    lines.push(std::mem::take(&mut line));
+                    line_len = 0;
+                }
+                lines.push(word.drain(..max_chars).collect());
+            }
+            if line_len > 0 && line_len + 1 + word.len() > max_chars {
+                lines.push(std::mem::take(&mut line));
+                line_len = 0;
+            }
+            if line_len > 0 {
+                line.push(' ');
+                line_len += 1;
+            }
+            line_len += word.len();
+            line.extend(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Escape text for a PDF literal string in `WinAnsiEncoding`. Latin-1
+/// characters are written as octal escapes so the content stays ASCII.
+fn pdf_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            ' '..='~' => out.push(c),
+            '\u{a0}'..='\u{ff}' => {
+                let _ = write!(out, "\\{:03o}", u32::from(c));
+            }
+            _ => out.push('?'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::profiler::create_snapshot;
+    use polars::prelude::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_render_pdf_structure() -> Result<()> {
+        let df = df! {
+            "amount" => &[1.0, 2.0, 2.5, 3.0, 10.0],
+            "note (free text)" => &["café", "a", "a", "b", "b"],
+        }?;
+        let dict = create_snapshot(
+            "Sales",
+            &df,
+            PathBuf::from("input.csv"),
+            PathBuf::from("output.csv"),
+            None,
+            None,
+        )?;
+
+        let bytes = render_pdf(&dict)?;
+        let pdf = String::from_utf8(bytes).expect("PDF output is ASCII");
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("(Data Dictionary: Sales) Tj"));
+        assert!(pdf.contains("note \\(free text\\)"));
+        assert!(pdf.contains(" re f\n"));
+
+        // Every cross-reference entry points at its object
+        let xref_start = pdf.find("xref\n").expect("xref table");
+        for (i, entry) in pdf[xref_start..].lines().skip(3).enumerate() {
+            if entry.starts_with("trailer") {
+                break;
+            }
+            let offset: usize = entry[..10].parse()?;
+            assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrap_and_escape() {
+        assert_eq!(
+            wrap("one two three", 7),
+            vec!["one two".to_owned(), "three".to_owned()]
+        );
+        assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert_eq!(pdf_string("a(b)\\ é ✓"), "a\\(b\\)\\\\ \\351 ?");
+    }
+}
@@ -7,7 +7,7 @@ use super::metadata::{
     DatasetBusinessMetadata, DatasetMetadata, InputSource, QualitySummary, TechnicalMetadata,
     column_name_to_uuid,
 };
-use crate::analyser::logic::{AnalysisResponse, ColumnSummary, analyse_df_lazy};
+use crate::analyser::logic::{AnalysisResponse, ColumnSummary, FileHealth, analyse_df_lazy};
 use anyhow::{Context as _, Result};
 use chrono::Utc;
 use polars::prelude::*;
@@ -66,7 +66,7 @@ pub fn create_snapshot(
     let columns = build_column_metadata(&analysis.summary)?;
 
     // Calculate quality summary
-    let quality_summary = calculate_quality_summary(&analysis, &columns);
+    let quality_summary = calculate_quality_summary(analysis.health.score.into(), &columns);
 
     // Build technical metadata
     let technical = TechnicalMetadata {
@@ -97,6 +97,72 @@ pub fn create_snapshot(
     })
 }
 
+/// Create a baseline snapshot of a source file from an existing analysis,
+/// without re-profiling the data.
+///
+/// # Arguments
+///
+/// * `dataset_name` - Human-readable name for the dataset
+/// * `input_path` - The analysed file, which is both input and output
+/// * `summary` - Column summaries from the analysis
+/// * `row_count` - Total rows in the file, not just the analysed sample
+/// * `health` - File health from the analysis
+/// * `previous_snapshot_id` - Optional link to previous snapshot for versioning
+pub fn create_snapshot_from_analysis(
+    dataset_name: &str,
+    input_path: &Path,
+    summary: &[ColumnSummary],
+    row_count: usize,
+    health: &FileHealth,
+    previous_snapshot_id: Option<Uuid>,
+) -> Result<DataDictionary> {
+    let input_hash = calculate_file_hash(input_path).ok();
+    // Nothing was transformed, so the output is the input file; fall back to
+    // the schema when the file can no longer be read
+    let output_dataset_hash = input_hash.clone().unwrap_or_else(|| {
+        use sha2::{Digest as _, Sha256};
+        let kinds: Vec<String> = summary
+            .iter()
+            .map(|c| format!("{}:{}", c.name, c.kind))
+            .collect();
+        format!("{:x}", Sha256::digest(kinds.join(",").as_bytes()))
+    });
+
+    let columns = build_column_metadata(summary)?;
+    let quality_summary = calculate_quality_summary(health.score.into(), &columns);
+
+    let technical = TechnicalMetadata {
+        input_sources: vec![InputSource {
+            path: input_path.to_string_lossy().to_string(),
+            hash: input_hash.clone(),
+        }],
+        pipeline_id: None,
+        pipeline_json: None,
+        input_dataset_hash: input_hash,
+        output_dataset_hash,
+        row_count,
+        column_count: summary.len(),
+        export_format: input_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_lowercase(),
+        quality_summary,
+    };
+
+    Ok(DataDictionary {
+        snapshot_id: Uuid::new_v4(),
+        dataset_name: dataset_name.to_owned(),
+        export_timestamp: Utc::now(),
+        dataset_metadata: DatasetMetadata {
+            technical,
+            business: DatasetBusinessMetadata::default(),
+        },
+        columns,
+        previous_snapshot_id,
+    })
+}
+
 /// Profile a `DataFrame` for dictionary creation (lightweight analysis).
 fn analyse_dataframe_for_dictionary(df: &DataFrame) -> Result<AnalysisResponse> {
     // Convert to LazyFrame for efficient analysis
@@ -122,6 +188,11 @@ fn analyse_dataframe_for_dictionary(df: &DataFrame) -> Result<AnalysisResponse>
         duration: std::time::Duration::from_secs(0),
         df: df.clone(),
         correlation_matrix: None,
+        column_mapping: None,
+        orientation: None,
+        layout: None,
+        header_looks_like_data: false,
+        header_names: None,
     })
 }
 
@@ -151,6 +222,17 @@ fn build_column_metadata(summary: &[ColumnSummary]) -> Result<Vec<ColumnMetadata
                 sample_values: col.samples.clone(),
                 warnings,
                 stats_json,
+                inferred_description: infer_description(col),
+                semantic_type: col.semantic_type,
+            };
+
+            // Values that can identify a person are tagged up front
+            let business = ColumnBusinessMetadata {
+                sensitivity_tag: col
+                    .semantic_type
+                    .filter(|t| t.is_pii())
+                    .map(|_| "PII".to_owned()),
+                ..Default::default()
             };
 
             Ok(ColumnMetadata {
@@ -162,12 +244,24 @@ fn build_column_metadata(summary: &[ColumnSummary]) -> Result<Vec<ColumnMetadata
                     Some(col.standardised_name.clone())
                 },
                 technical,
-                business: ColumnBusinessMetadata::default(),
+                business,
             })
         })
         .collect()
 }
 
+/// The analysis' business summary as one paragraph, without the sampling
+/// note `analyze_file_flow` adds to the first column.
+fn infer_description(col: &ColumnSummary) -> Option<String> {
+    let sentences: Vec<&str> = col
+        .business_summary
+        .iter()
+        .map(String::as_str)
+        .filter(|s| !s.starts_with("NOTE:"))
+        .collect();
+    (!sentences.is_empty()).then(|| sentences.join(" "))
+}
+
 /// Extract min/max values from column stats.
 fn extract_min_max_from_stats(col: &ColumnSummary) -> (Option<String>, Option<String>) {
     use crate::analyser::logic::ColumnStats;
@@ -220,10 +314,7 @@ fn detect_column_warnings(col: &ColumnSummary) -> Vec<String> {
 }
 
 /// Calculate overall quality summary for the dataset.
-fn calculate_quality_summary(
-    analysis: &AnalysisResponse,
-    columns: &[ColumnMetadata],
-) -> QualitySummary {
+fn calculate_quality_summary(health_score: f64, columns: &[ColumnMetadata]) -> QualitySummary {
     let total_columns = columns.len() as f64;
 
     // Calculate average null percentage
@@ -249,15 +340,12 @@ fn calculate_quality_summary(
         .filter(|c| c.technical.distinct_count <= 1)
         .count();
 
-    // Use file health score if available, otherwise calculate
-    let overall_score = analysis.health.score;
-
     QualitySummary {
         avg_null_percentage,
         empty_column_count,
         constant_column_count,
         duplicate_row_count: None, // TODO: Could calculate if needed
-        overall_score: overall_score.into(),
+        overall_score: health_score,
     }
 }
 
@@ -312,6 +400,8 @@ fn calculate_file_hash(path: &Path) -> Result<String> {
 mod tests {
     use super::*;
 
+    use crate::analyser::logic::SemanticType;
+
     #[test]
     fn test_create_snapshot_basic() -> Result<()> {
         let df = df! {
@@ -335,4 +425,33 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_create_snapshot_tags_pii() -> Result<()> {
+        let df = df! {
+            "email" => &["ann@example.com", "bob@example.com", "cy@example.org"],
+            "currency" => &["GBP", "USD", "EUR"],
+        }?;
+
+        let snapshot = create_snapshot(
+            "contacts",
+            &df,
+            PathBuf::from("input.csv"),
+            PathBuf::from("output.csv"),
+            None,
+            None,
+        )?;
+
+        let email = &snapshot.columns[0];
+        assert_eq!(email.technical.semantic_type, Some(SemanticType::Email));
+        assert_eq!(email.business.sensitivity_tag.as_deref(), Some("PII"));
+        let currency = &snapshot.columns[1];
+        assert_eq!(
+            currency.technical.semantic_type,
+            Some(SemanticType::CurrencyCode)
+        );
+        assert_eq!(currency.business.sensitivity_tag, None);
+
+        Ok(())
+    }
 }
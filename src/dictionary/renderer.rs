@@ -1,10 +1,38 @@
 //! Markdown rendering for data dictionary snapshots.
 //!
-//! Generates human-readable documentation from dictionary snapshots.
+//! Generates human-readable documentation from dictionary snapshots. HTML and
+//! PDF output live in the `html` and `pdf` modules.
 
-use super::metadata::DataDictionary;
+use super::glossary::Glossary;
+use super::metadata::{ColumnMetadata, DataDictionary};
+use crate::analyser::logic::ColumnStats;
 use anyhow::Result;
 
+/// Most categories drawn in a distribution sparkline
+const MAX_SPARKLINE_CATEGORIES: usize = 12;
+
+/// Bar heights for a column's distribution sparkline, from its stored stats:
+/// histogram bins for numeric and temporal columns, the most frequent
+/// categories, true and false counts, or counts of geometry types. `None`
+/// when there is nothing to draw.
+pub(super) fn distribution(col: &ColumnMetadata) -> Option<Vec<usize>> {
+    let stats: ColumnStats = serde_json::from_str(col.technical.stats_json.as_ref()?).ok()?;
+    let counts: Vec<usize> = match stats {
+        ColumnStats::Numeric(s) => s.histogram.iter().map(|(_, n)| *n).collect(),
+        ColumnStats::Temporal(s) => s.histogram.iter().map(|(_, n)| *n).collect(),
+        ColumnStats::Categorical(freq) => {
+            let mut counts: Vec<usize> = freq.into_values().collect();
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            counts.truncate(MAX_SPARKLINE_CATEGORIES);
+            counts
+        }
+        ColumnStats::Boolean(s) => vec![s.true_count, s.false_count],
+        ColumnStats::Geo(s) => s.geometry_types.into_values().collect(),
+        ColumnStats::Text(_) => Vec::new(),
+    };
+    (counts.iter().any(|&n| n > 0)).then_some(counts)
+}
+
 /// Render a data dictionary as Markdown documentation.
 ///
 /// Generates a comprehensive Markdown document including:
@@ -13,6 +41,13 @@ use anyhow::Result;
 /// - Data quality summary
 /// - Version lineage information
 pub fn render_markdown(dict: &DataDictionary) -> Result<String> {
+    render_markdown_with_glossary(dict, &Glossary::default())
+}
+
+/// Render a data dictionary as Markdown, listing the glossary terms linked
+/// from its columns and adding a Glossary section with their definitions.
+pub fn render_markdown_with_glossary(dict: &DataDictionary, glossary: &Glossary) -> Result<String> {
+    let linked_terms = glossary.linked_terms(dict);
     let mut md = String::new();
 
     // Title and header
@@ -32,7 +67,11 @@ pub fn render_markdown(dict: &DataDictionary) -> Result<String> {
     md.push_str("1. [Dataset Overview](#dataset-overview)\n");
     md.push_str("2. [Column Catalog](#column-catalog)\n");
     md.push_str("3. [Data Quality Summary](#data-quality-summary)\n");
-    md.push_str("4. [Technical Metadata](#technical-metadata)\n\n");
+    md.push_str("4. [Technical Metadata](#technical-metadata)\n");
+    if !linked_terms.is_empty() {
+        md.push_str("5. [Glossary](#glossary)\n");
+    }
+    md.push('\n');
 
     md.push_str("---\n\n");
 
@@ -51,7 +90,7 @@ pub fn render_markdown(dict: &DataDictionary) -> Result<String> {
 
     for (i, col) in dict.columns.iter().enumerate() {
         md.push_str(&format!("### {} — `{}`\n\n", i + 1, col.current_name));
-        render_column_metadata(&mut md, col);
+        render_column_metadata(&mut md, col, glossary);
         md.push_str("\n---\n\n");
     }
 
@@ -64,6 +103,22 @@ pub fn render_markdown(dict: &DataDictionary) -> Result<String> {
     md.push_str("## Technical Metadata\n\n");
     render_technical_metadata(&mut md, dict);
 
+    // 5. Glossary
+    if !linked_terms.is_empty() {
+        md.push_str("\n---\n\n");
+        md.push_str("## Glossary\n\n");
+        for term in linked_terms {
+            md.push_str(&format!("### {}\n\n{}\n\n", term.term, term.definition));
+            if let Some(owner) = &term.owner {
+                md.push_str(&format!("**Owner:** {owner}  \n"));
+            }
+            if !term.synonyms.is_empty() {
+                md.push_str(&format!("**Synonyms:** {}  \n", term.synonyms.join(", ")));
+            }
+            md.push('\n');
+        }
+    }
+
     // Version lineage
     if let Some(prev_id) = dict.previous_snapshot_id {
         md.push_str("\n---\n\n");
@@ -90,6 +145,18 @@ fn render_dataset_business_metadata(md: &mut String, dict: &DataDictionary) {
         md.push_str(&format!("**Owner/Steward:** {owner}\n\n"));
     }
 
+    if let Some(owner) = &business.owner {
+        md.push_str(&format!("**Owner:** {owner}\n\n"));
+    }
+
+    if let Some(steward) = &business.steward {
+        md.push_str(&format!("**Steward:** {steward}\n\n"));
+    }
+
+    if let Some(review) = business.review_summary() {
+        md.push_str(&format!("**Review:** {review}\n\n"));
+    }
+
     if let Some(refresh) = &business.refresh_expectation {
         md.push_str(&format!("**Refresh Cadence:** {refresh}\n\n"));
     }
@@ -110,18 +177,25 @@ fn render_dataset_business_metadata(md: &mut String, dict: &DataDictionary) {
     if business.description.is_none()
         && business.intended_use.is_none()
         && business.owner_or_steward.is_none()
+        && business.owner.is_none()
     {
         md.push_str("*No dataset-level documentation provided.*\n\n");
     }
 }
 
 /// Render a single column's metadata.
-fn render_column_metadata(md: &mut String, col: &super::metadata::ColumnMetadata) {
+fn render_column_metadata(
+    md: &mut String,
+    col: &super::metadata::ColumnMetadata,
+    glossary: &Glossary,
+) {
     // Business metadata section
     md.push_str("#### Business Definition\n\n");
 
     if let Some(def) = &col.business.business_definition {
         md.push_str(&format!("{def}\n\n"));
+    } else if let Some(inferred) = &col.technical.inferred_description {
+        md.push_str(&format!("*Inferred:* {inferred}\n\n"));
     } else {
         md.push_str("*No business definition provided.*\n\n");
     }
@@ -145,6 +219,17 @@ fn render_column_metadata(md: &mut String, col: &super::metadata::ColumnMetadata
         md.push_str(&format!("**Notes:** {notes}\n\n"));
     }
 
+    let terms: Vec<&str> = col
+        .business
+        .glossary_terms
+        .iter()
+        .filter_map(|id| glossary.get(id))
+        .map(|t| t.term.as_str())
+        .collect();
+    if !terms.is_empty() {
+        md.push_str(&format!("**Glossary Terms:** {}\n\n", terms.join(", ")));
+    }
+
     // Technical metadata section (collapsible)
     md.push_str("<details>\n");
     md.push_str("<summary><strong>Technical Details</strong></summary>\n\n");
@@ -165,6 +250,10 @@ fn render_column_metadata(md: &mut String, col: &super::metadata::ColumnMetadata
         col.technical.distinct_count
     ));
 
+    if let Some(semantic_type) = col.technical.semantic_type {
+        md.push_str(&format!("| **Values** | {semantic_type} |\n"));
+    }
+
     if let Some(min) = &col.technical.min_value {
         md.push_str(&format!("| **Min** | `{min}` |\n"));
     }
@@ -339,6 +428,40 @@ mod tests {
         assert!(markdown.contains("# Data Dictionary: Test Dataset"));
         assert!(markdown.contains("A test dataset"));
         assert!(markdown.contains("## Column Catalog"));
+        assert!(!markdown.contains("## Glossary"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_markdown_with_linked_glossary_terms() -> Result<()> {
+        use super::super::glossary::GlossaryTerm;
+        use super::super::profiler::create_snapshot;
+        use polars::prelude::*;
+        use std::path::PathBuf;
+
+        let df = df! { "cust_id" => &[1_i64, 2] }?;
+        let mut dict = create_snapshot(
+            "orders",
+            &df,
+            PathBuf::from("orders.csv"),
+            PathBuf::from("orders.parquet"),
+            None,
+            None,
+        )?;
+        let mut glossary = Glossary::default();
+        let mut customer = GlossaryTerm::new("Customer", "A party that has placed an order");
+        customer.synonyms = vec!["Client".to_owned()];
+        let customer_id = glossary.upsert(customer)?.id;
+        glossary.upsert(GlossaryTerm::new("Unused", "Linked from no column"))?;
+        dict.columns[0].business.glossary_terms = vec![customer_id, Uuid::new_v4()];
+
+        let markdown = render_markdown_with_glossary(&dict, &glossary)?;
+
+        assert!(markdown.contains("**Glossary Terms:** Customer\n"));
+        assert!(markdown.contains("5. [Glossary](#glossary)"));
+        assert!(markdown.contains("### Customer\n\nA party that has placed an order"));
+        assert!(markdown.contains("**Synonyms:** Client"));
+        assert!(!markdown.contains("Unused"));
         Ok(())
     }
 }
@@ -0,0 +1,348 @@
+//! Retention, compaction and storage usage for dictionary snapshots.
+//!
+//! With `auto_dictionary_snapshot` on, every analysis saves a snapshot, so the
+//! store grows without bound. Snapshots are grouped by dataset hash.
+//! Compaction drops a snapshot that is identical to the one after it apart
+//! from its id, time and link, keeping the newer one. The retention policy
+//! then keeps the newest snapshots of each dataset by count and age. The
+//! newest snapshot of a dataset is never removed, and snapshots that linked to
+//! a removed one are relinked to its nearest kept ancestor so version chains
+//! stay intact.
+
+use super::metadata::DataDictionary;
+use super::storage::{DICTIONARIES_DIR, load_snapshot_from_path, save_snapshot};
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// How many dictionary snapshots are kept per dataset hash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotRetention {
+    /// Newest snapshots kept per dataset hash; `None` keeps all
+    pub keep_last: Option<usize>,
+    /// Snapshots older than this are removed; `None` keeps them
+    pub max_age_days: Option<u32>,
+    /// Remove snapshots identical to the next one of the same dataset
+    pub compact: bool,
+}
+
+impl SnapshotRetention {
+    /// Whether pruning with this policy can remove anything
+    pub fn is_enabled(&self) -> bool {
+        self.compact || self.keep_last.is_some() || self.max_age_days.is_some()
+    }
+
+    /// The policy from the app settings
+    pub fn from_settings() -> Self {
+        crate::config::load_app_config()
+            .settings
+            .dictionary_retention
+    }
+}
+
+/// What [`prune_snapshots`] removed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneReport {
+    /// Removed as identical to a newer snapshot
+    pub duplicates_removed: Vec<Uuid>,
+    /// Removed by the count or age limits
+    pub expired_removed: Vec<Uuid>,
+    /// Kept snapshots whose link to their previous snapshot was rewritten
+    pub relinked: usize,
+    pub bytes_freed: u64,
+    pub remaining: usize,
+}
+
+impl PruneReport {
+    pub fn removed(&self) -> usize {
+        self.duplicates_removed.len() + self.expired_removed.len()
+    }
+}
+
+/// Snapshot storage of one dataset hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DatasetSnapshotUsage {
+    pub output_hash: String,
+    /// Name in the newest snapshot
+    pub dataset_name: String,
+    pub snapshot_count: usize,
+    pub bytes: u64,
+    pub oldest: DateTime<Utc>,
+    pub newest: DateTime<Utc>,
+    /// Snapshots compaction would remove
+    pub duplicates: usize,
+}
+
+/// Disk used by the dictionary store
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DictionaryStorageUsage {
+    pub snapshot_count: usize,
+    pub total_bytes: u64,
+    /// The largest first
+    pub datasets: Vec<DatasetSnapshotUsage>,
+}
+
+struct StoredSnapshot {
+    snapshot: DataDictionary,
+    path: PathBuf,
+    bytes: u64,
+}
+
+/// Every readable snapshot under `base_path`, by dataset hash, oldest first
+fn load_by_dataset(base_path: &Path) -> Result<BTreeMap<String, Vec<StoredSnapshot>>> {
+    let dict_dir = base_path.join(DICTIONARIES_DIR);
+    let mut groups: BTreeMap<String, Vec<StoredSnapshot>> = BTreeMap::new();
+    if !dict_dir.exists() {
+        return Ok(groups);
+    }
+
+    for entry in fs::read_dir(&dict_dir)
+        .context("Failed to read dictionaries directory")?
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(snapshot) = load_snapshot_from_path(&path) else {
+            continue;
+        };
+        let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        groups
+            .entry(
+                snapshot
+                    .dataset_metadata
+                    .technical
+                    .output_dataset_hash
+                    .clone(),
+            )
+            .or_default()
+            .push(StoredSnapshot {
+                snapshot,
+                path,
+                bytes,
+            });
+    }
+    for group in groups.values_mut() {
+        group.sort_by_key(|s| s.snapshot.export_timestamp);
+    }
+    Ok(groups)
+}
+
+/// The snapshot without the fields every save changes, for comparison
+fn content_key(snapshot: &DataDictionary) -> Result<String> {
+    let mut value = serde_json::to_value(snapshot).context("Failed to serialize snapshot")?;
+    if let Some(fields) = value.as_object_mut() {
+        for field in ["snapshot_id", "export_timestamp", "previous_snapshot_id"] {
+            fields.remove(field);
+        }
+    }
+    Ok(value.to_string())
+}
+
+/// Indices of the snapshots in `group` (oldest first) that are identical to
+/// the one after them
+fn duplicate_indices(group: &[StoredSnapshot]) -> Result<Vec<usize>> {
+    let keys = group
+        .iter()
+        .map(|s| content_key(&s.snapshot))
+        .collect::<Result<Vec<_>>>()?;
+    Ok((0..keys.len().saturating_sub(1))
+        .filter(|&i| keys[i] == keys[i + 1])
+        .collect())
+}
+
+/// Report how much space the snapshots under `base_path` take
+pub fn storage_usage(base_path: &Path) -> Result<DictionaryStorageUsage> {
+    let mut usage = DictionaryStorageUsage::default();
+    for (output_hash, group) in load_by_dataset(base_path)? {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let bytes = group.iter().map(|s| s.bytes).sum();
+        usage.snapshot_count += group.len();
+        usage.total_bytes += bytes;
+        usage.datasets.push(DatasetSnapshotUsage {
+            output_hash,
+            dataset_name: last.snapshot.dataset_name.clone(),
+            snapshot_count: group.len(),
+            bytes,
+            oldest: first.snapshot.export_timestamp,
+            newest: last.snapshot.export_timestamp,
+            duplicates: duplicate_indices(&group)?.len(),
+        });
+    }
+    usage.datasets.sort_by_key(|d| std::cmp::Reverse(d.bytes));
+    Ok(usage)
+}
+
+/// Compact the snapshots under `base_path` and apply the count and age limits
+/// of `policy`, relinking the snapshots that are kept
+pub fn prune_snapshots(base_path: &Path, policy: &SnapshotRetention) -> Result<PruneReport> {
+    let mut report = PruneReport::default();
+    if !policy.is_enabled() {
+        report.remaining = storage_usage(base_path)?.snapshot_count;
+        return Ok(report);
+    }
+
+    let cutoff = policy
+        .max_age_days
+        .map(|days| Utc::now() - Duration::days(i64::from(days)));
+    let mut kept: Vec<StoredSnapshot> = Vec::new();
+    let mut removed: Vec<StoredSnapshot> = Vec::new();
+
+    for group in load_by_dataset(base_path)?.into_values() {
+        let duplicates: HashSet<usize> = if policy.compact {
+            duplicate_indices(&group)?.into_iter().collect()
+        } else {
+            HashSet::new()
+        };
+        let mut survivors = Vec::new();
+        for (idx, stored) in group.into_iter().enumerate() {
+            if duplicates.contains(&idx) {
+                report.duplicates_removed.push(stored.snapshot.snapshot_id);
+                removed.push(stored);
+            } else {
+                survivors.push(stored);
+            }
+        }
+
+        // Newest first; the newest one is always kept
+        survivors.reverse();
+        for (rank, stored) in survivors.into_iter().enumerate() {
+            let over_count = policy.keep_last.is_some_and(|n| rank >= n.max(1));
+            let too_old = cutoff.is_some_and(|c| stored.snapshot.export_timestamp < c);
+            if rank > 0 && (over_count || too_old) {
+                report.expired_removed.push(stored.snapshot.snapshot_id);
+                removed.push(stored);
+            } else {
+                kept.push(stored);
+            }
+        }
+    }
+
+    // Link past the removed snapshots to the nearest kept ancestor
+    let removed_links: HashMap<Uuid, Option<Uuid>> = removed
+        .iter()
+        .map(|s| (s.snapshot.snapshot_id, s.snapshot.previous_snapshot_id))
+        .collect();
+    for stored in &mut kept {
+        let original = stored.snapshot.previous_snapshot_id;
+        let mut link = original;
+        for _ in 0..=removed_links.len() {
+            match link.and_then(|id| removed_links.get(&id)) {
+                Some(&previous) => link = previous,
+                None => break,
+            }
+        }
+        if link != original {
+            stored.snapshot.previous_snapshot_id = link;
+            save_snapshot(&stored.snapshot, base_path)?;
+            report.relinked += 1;
+        }
+    }
+
+    for stored in &removed {
+        fs::remove_file(&stored.path)
+            .with_context(|| format!("Failed to delete {}", stored.path.display()))?;
+        report.bytes_freed += stored.bytes;
+    }
+    report.remaining = kept.len();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::metadata::*;
+    use crate::dictionary::storage::load_snapshot;
+    use tempfile::tempdir;
+
+    fn snapshot(hash: &str, rows: usize, age_days: i64, previous: Option<Uuid>) -> DataDictionary {
+        DataDictionary {
+            snapshot_id: Uuid::new_v4(),
+            dataset_name: "orders".to_owned(),
+            export_timestamp: Utc::now() - Duration::days(age_days),
+            dataset_metadata: DatasetMetadata {
+                technical: TechnicalMetadata {
+                    input_sources: vec![],
+                    pipeline_id: None,
+                    pipeline_json: None,
+                    input_dataset_hash: None,
+                    output_dataset_hash: hash.to_owned(),
+                    row_count: rows,
+                    column_count: 2,
+                    export_format: "csv".to_owned(),
+                    quality_summary: QualitySummary {
+                        avg_null_percentage: 0.0,
+                        empty_column_count: 0,
+                        constant_column_count: 0,
+                        duplicate_row_count: None,
+                        overall_score: 100.0,
+                    },
+                },
+                business: DatasetBusinessMetadata::default(),
+            },
+            columns: vec![],
+            previous_snapshot_id: previous,
+        }
+    }
+
+    #[test]
+    fn test_prune_snapshots() -> Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        // a: 10 rows, then the same twice, then 20 rows, oldest first
+        let first = snapshot("a", 10, 40, None);
+        let duplicate = snapshot("a", 10, 30, Some(first.snapshot_id));
+        let repeat = snapshot("a", 10, 20, Some(duplicate.snapshot_id));
+        let latest = snapshot("a", 20, 1, Some(repeat.snapshot_id));
+        let other = snapshot("b", 5, 90, None);
+        for s in [&first, &duplicate, &repeat, &latest, &other] {
+            save_snapshot(s, base)?;
+        }
+
+        let usage = storage_usage(base)?;
+        assert_eq!(usage.snapshot_count, 5);
+        assert_eq!(usage.datasets.len(), 2);
+        let a = usage.datasets.iter().find(|d| d.output_hash == "a");
+        assert_eq!(a.map(|d| d.duplicates), Some(2));
+
+        let report = prune_snapshots(
+            base,
+            &SnapshotRetention {
+                compact: true,
+                ..Default::default()
+            },
+        )?;
+        // The first two equal the one after them, so only the newest copy stays
+        assert_eq!(
+            report.duplicates_removed,
+            vec![first.snapshot_id, duplicate.snapshot_id]
+        );
+        assert_eq!(report.remaining, 3);
+        assert_eq!(report.relinked, 1);
+        let repeat_now = load_snapshot(&repeat.snapshot_id, base)?;
+        assert_eq!(repeat_now.previous_snapshot_id, None);
+
+        let report = prune_snapshots(
+            base,
+            &SnapshotRetention {
+                keep_last: Some(1),
+                max_age_days: Some(30),
+                compact: false,
+            },
+        )?;
+        // `b` is older than the limit but is the newest of its dataset
+        assert_eq!(report.expired_removed, vec![repeat.snapshot_id]);
+        assert_eq!(report.remaining, 2);
+        let latest_now = load_snapshot(&latest.snapshot_id, base)?;
+        assert_eq!(latest_now.previous_snapshot_id, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+//! Review reminders for dataset documentation.
+//!
+//! A dataset whose business metadata has a `review_cadence_days` is due for
+//! review that many days after `last_reviewed`, and straight away if it was
+//! never reviewed. Only the newest version of each dataset counts, so
+//! marking a dataset reviewed (which saves a new version) clears its reminder.
+
+use super::metadata::DataDictionary;
+use super::storage::{latest_snapshots, load_snapshot, update_business_metadata};
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Review state of one dataset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewStatus {
+    pub snapshot_id: Uuid,
+    pub dataset_name: String,
+    pub owner: Option<String>,
+    pub steward: Option<String>,
+    pub review_cadence_days: u32,
+    pub last_reviewed: Option<NaiveDate>,
+    pub due: NaiveDate,
+    /// Days past `due`; negative while the review is not yet due
+    pub days_overdue: i64,
+}
+
+impl ReviewStatus {
+    /// The review state of `snapshot` on `today`, or `None` if its reviews
+    /// are not tracked
+    pub fn of(snapshot: &DataDictionary, today: NaiveDate) -> Option<Self> {
+        let business = &snapshot.dataset_metadata.business;
+        let due = business.next_review(today)?;
+        Some(Self {
+            snapshot_id: snapshot.snapshot_id,
+            dataset_name: snapshot.dataset_name.clone(),
+            // Older snapshots only have the combined field
+            owner: business
+                .owner
+                .clone()
+                .or_else(|| business.owner_or_steward.clone()),
+            steward: business.steward.clone(),
+            review_cadence_days: business.review_cadence_days?,
+            last_reviewed: business.last_reviewed,
+            due,
+            days_overdue: (today - due).num_days(),
+        })
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.days_overdue >= 0
+    }
+}
+
+/// Review state of every dataset with a review cadence, most overdue first
+pub fn review_schedule(base_path: &Path, today: NaiveDate) -> Result<Vec<ReviewStatus>> {
+    let mut schedule: Vec<ReviewStatus> = latest_snapshots(base_path)?
+        .iter()
+        .filter_map(|s| ReviewStatus::of(s, today))
+        .collect();
+    schedule.sort_by(|a, b| {
+        b.days_overdue
+            .cmp(&a.days_overdue)
+            .then_with(|| a.dataset_name.cmp(&b.dataset_name))
+    });
+    Ok(schedule)
+}
+
+/// Datasets due for review on `today`, most overdue first
+pub fn overdue_reviews(base_path: &Path, today: NaiveDate) -> Result<Vec<ReviewStatus>> {
+    let mut schedule = review_schedule(base_path, today)?;
+    schedule.retain(ReviewStatus::is_due);
+    Ok(schedule)
+}
+
+/// Record a review of snapshot `snapshot_id` on `reviewed_on`, saving a new
+/// version of its dictionary
+pub fn mark_reviewed(
+    snapshot_id: &Uuid,
+    base_path: &Path,
+    reviewed_on: NaiveDate,
+) -> Result<DataDictionary> {
+    let snapshot = load_snapshot(snapshot_id, base_path)?;
+    let mut business = snapshot.dataset_metadata.business;
+    business.last_reviewed = Some(reviewed_on);
+    update_business_metadata(snapshot_id, base_path, Some(business), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::metadata::*;
+    use crate::dictionary::save_snapshot;
+    use chrono::Utc;
+    use tempfile::tempdir;
+
+    fn snapshot(name: &str, business: DatasetBusinessMetadata) -> DataDictionary {
+        DataDictionary {
+            snapshot_id: Uuid::new_v4(),
+            dataset_name: name.to_owned(),
+            export_timestamp: Utc::now(),
+            dataset_metadata: DatasetMetadata {
+                technical: TechnicalMetadata {
+                    input_sources: vec![],
+                    pipeline_id: None,
+                    pipeline_json: None,
+                    input_dataset_hash: None,
+                    output_dataset_hash: name.to_owned(),
+                    row_count: 1,
+                    column_count: 0,
+                    export_format: "csv".to_owned(),
+                    quality_summary: QualitySummary {
+                        avg_null_percentage: 0.0,
+                        empty_column_count: 0,
+                        constant_column_count: 0,
+                        duplicate_row_count: None,
+                        overall_score: 100.0,
+                    },
+                },
+                business,
+            },
+            columns: vec![],
+            previous_snapshot_id: None,
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_overdue_reviews() -> Result<()> {
+        let dir = tempdir()?;
+        let base = dir.path();
+        let today = date("2026-06-30");
+
+        let overdue = snapshot(
+            "orders",
+            DatasetBusinessMetadata {
+                owner_or_steward: Some("Finance".to_owned()),
+                review_cadence_days: Some(90),
+                last_reviewed: Some(date("2026-03-01")),
+                ..Default::default()
+            },
+        );
+        let never = snapshot(
+            "customers",
+            DatasetBusinessMetadata {
+                owner: Some("Sales".to_owned()),
+                review_cadence_days: Some(30),
+                ..Default::default()
+            },
+        );
+        let current = snapshot(
+            "products",
+            DatasetBusinessMetadata {
+                review_cadence_days: Some(365),
+                last_reviewed: Some(date("2026-01-01")),
+                ..Default::default()
+            },
+        );
+        let untracked = snapshot("notes", DatasetBusinessMetadata::default());
+        for s in [&overdue, &never, &current, &untracked] {
+            save_snapshot(s, base)?;
+        }
+
+        let schedule = review_schedule(base, today)?;
+        let names: Vec<_> = schedule.iter().map(|s| s.dataset_name.as_str()).collect();
+        assert_eq!(names, ["orders", "customers", "products"]);
+        assert_eq!(schedule[0].due, date("2026-05-30"));
+        assert_eq!(schedule[0].days_overdue, 31);
+        assert_eq!(schedule[0].owner.as_deref(), Some("Finance"));
+        assert_eq!(schedule[1].days_overdue, 0);
+        assert!(!schedule[2].is_due());
+
+        let due = overdue_reviews(base, today)?;
+        assert_eq!(due.len(), 2);
+
+        // The reviewed version replaces the overdue one
+        let reviewed = mark_reviewed(&overdue.snapshot_id, base, today)?;
+        assert_eq!(reviewed.previous_snapshot_id, Some(overdue.snapshot_id));
+        let due = overdue_reviews(base, today)?;
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].dataset_name, "customers");
+        Ok(())
+    }
+}
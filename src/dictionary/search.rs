@@ -0,0 +1,509 @@
+//! Full-text search across dictionary snapshots.
+//!
+//! [`DictionaryIndex`] is an in-memory inverted index over dataset names,
+//! descriptions and tags, column names and definitions, and the glossary
+//! terms columns link to. Each dataset and each column is one document, so a
+//! query such as "which datasets have a column about VAT?" ranks the columns
+//! that mention VAT, with the dataset they belong to.
+//!
+//! Text is split into lowercase words, also at camelCase and letter/digit
+//! boundaries, and a trailing plural `s` is dropped. Query words that are
+//! common question words are ignored, and a query word of three or more
+//! letters also matches longer words it starts, at a lower score. Scores
+//! weight names above tags and glossary terms, and those above free text.
+//!
+//! By default only the newest version of each dataset is indexed: snapshots
+//! that a later snapshot links to as its previous version are skipped.
+
+use super::glossary::Glossary;
+use super::metadata::DataDictionary;
+use super::storage::{latest_snapshots, load_all_snapshots};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+/// Query words that say what is asked rather than what about
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "any", "are", "column", "dataset", "do", "doe", "for", "has",
+    "have", "in", "is", "of", "on", "or", "the", "to", "what", "where", "which", "with",
+];
+
+/// Longest snippet returned with a hit, in characters
+const SNIPPET_LEN: usize = 160;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    DatasetName,
+    /// Dataset description, intended use and known limitations
+    Description,
+    Tag,
+    /// Current and original column name
+    ColumnName,
+    /// Business definition, rules and notes, and the inferred description
+    ColumnDescription,
+    /// Name and synonyms of a linked glossary term
+    GlossaryTerm,
+}
+
+impl SearchField {
+    fn weight(self) -> f64 {
+        match self {
+            Self::DatasetName | Self::ColumnName => 3.0,
+            Self::Tag | Self::GlossaryTerm => 2.0,
+            Self::Description | Self::ColumnDescription => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub limit: usize,
+    /// Index every snapshot, not just the newest version of each dataset
+    pub all_versions: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            limit: 20,
+            all_versions: false,
+        }
+    }
+}
+
+/// A dataset or column matching a query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub snapshot_id: Uuid,
+    pub dataset_name: String,
+    pub output_hash: String,
+    pub export_timestamp: DateTime<Utc>,
+    /// The matching column; `None` when the dataset itself matched
+    pub column: Option<String>,
+    pub score: f64,
+    pub matched_fields: Vec<SearchField>,
+    /// Text of the most important matching field
+    pub snippet: String,
+}
+
+struct IndexedSnapshot {
+    snapshot_id: Uuid,
+    dataset_name: String,
+    output_hash: String,
+    export_timestamp: DateTime<Utc>,
+}
+
+struct Document {
+    snapshot: usize,
+    column: Option<String>,
+    fields: Vec<(SearchField, String)>,
+}
+
+struct Posting {
+    document: usize,
+    field: SearchField,
+    count: u32,
+}
+
+/// Inverted index over a set of dictionary snapshots
+pub struct DictionaryIndex {
+    snapshots: Vec<IndexedSnapshot>,
+    documents: Vec<Document>,
+    /// Sorted, so the words a query word starts can be found by range
+    postings: BTreeMap<String, Vec<Posting>>,
+}
+
+impl DictionaryIndex {
+    /// Index the snapshots under `base_path`, with the glossary stored there
+    pub fn build(base_path: &Path, all_versions: bool) -> Result<Self> {
+        let glossary = Glossary::load(base_path)?;
+        let snapshots = if all_versions {
+            let mut snapshots = load_all_snapshots(base_path)?;
+            snapshots.sort_by(|a, b| b.export_timestamp.cmp(&a.export_timestamp));
+            snapshots
+        } else {
+            latest_snapshots(base_path)?
+        };
+        Ok(Self::from_snapshots(&snapshots, &glossary))
+    }
+
+    /// Index `snapshots`, resolving linked terms in `glossary`
+    pub fn from_snapshots(snapshots: &[DataDictionary], glossary: &Glossary) -> Self {
+        let mut index = Self {
+            snapshots: Vec::with_capacity(snapshots.len()),
+            documents: Vec::new(),
+            postings: BTreeMap::new(),
+        };
+
+        for snapshot in snapshots {
+            let idx = index.snapshots.len();
+            index.snapshots.push(IndexedSnapshot {
+                snapshot_id: snapshot.snapshot_id,
+                dataset_name: snapshot.dataset_name.clone(),
+                output_hash: snapshot
+                    .dataset_metadata
+                    .technical
+                    .output_dataset_hash
+                    .clone(),
+                export_timestamp: snapshot.export_timestamp,
+            });
+
+            let business = &snapshot.dataset_metadata.business;
+            let description = [
+                &business.description,
+                &business.intended_use,
+                &business.known_limitations,
+            ];
+            index.add(Document {
+                snapshot: idx,
+                column: None,
+                fields: vec![
+                    (SearchField::DatasetName, snapshot.dataset_name.clone()),
+                    (SearchField::Description, join_present(description)),
+                    (SearchField::Tag, business.tags.join(", ")),
+                ],
+            });
+
+            for column in &snapshot.columns {
+                let mut names = vec![column.current_name.clone()];
+                names.extend(
+                    column
+                        .original_name
+                        .clone()
+                        .filter(|n| *n != column.current_name),
+                );
+                let details = [
+                    &column.business.business_definition,
+                    &column.business.business_rules,
+                    &column.business.notes,
+                    &column.technical.inferred_description,
+                ];
+                let terms: Vec<String> = column
+                    .business
+                    .glossary_terms
+                    .iter()
+                    .filter_map(|id| glossary.get(id))
+                    .flat_map(|t| std::iter::once(t.term.clone()).chain(t.synonyms.clone()))
+                    .collect();
+                index.add(Document {
+                    snapshot: idx,
+                    column: Some(column.current_name.clone()),
+                    fields: vec![
+                        (SearchField::ColumnName, names.join(" / ")),
+                        (SearchField::ColumnDescription, join_present(details)),
+                        (SearchField::GlossaryTerm, terms.join(", ")),
+                    ],
+                });
+            }
+        }
+        index
+    }
+
+    fn add(&mut self, mut document: Document) {
+        let idx = self.documents.len();
+        document.fields.retain(|(_, text)| !text.trim().is_empty());
+        for (field, text) in &document.fields {
+            let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+            for word in tokenize(text) {
+                *counts.entry(word).or_default() += 1;
+            }
+            for (word, count) in counts {
+                self.postings.entry(word).or_default().push(Posting {
+                    document: idx,
+                    field: *field,
+                    count,
+                });
+            }
+        }
+        self.documents.push(document);
+    }
+
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Datasets plus columns indexed
+    pub fn document_count(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// The best `limit` matches for `query`, best first
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut words = tokenize(query);
+        words.sort();
+        words.dedup();
+        if words.iter().any(|w| !STOP_WORDS.contains(&w.as_str())) {
+            words.retain(|w| !STOP_WORDS.contains(&w.as_str()));
+        }
+        if words.is_empty() || self.documents.is_empty() {
+            return Vec::new();
+        }
+
+        let total = self.documents.len() as f64;
+        // Per document: score, matched query words, matched fields
+        let mut scores: HashMap<usize, (f64, HashSet<usize>, BTreeSet<SearchField>)> =
+            HashMap::new();
+        for (word_idx, word) in words.iter().enumerate() {
+            for (token, postings) in self.postings.range(word.clone()..) {
+                if !token.starts_with(word.as_str()) {
+                    break;
+                }
+                let factor = if token == word {
+                    1.0
+                } else if word.chars().count() >= 3 {
+                    0.5
+                } else {
+                    continue;
+                };
+                let idf = (total / postings.len() as f64).ln_1p();
+                for posting in postings {
+                    let entry = scores.entry(posting.document).or_default();
+                    entry.0 += factor
+                        * idf
+                        * posting.field.weight()
+                        * (1.0 + f64::from(posting.count).ln());
+                    entry.1.insert(word_idx);
+                    entry.2.insert(posting.field);
+                }
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = scores
+            .into_iter()
+            .map(|(idx, (score, matched_words, fields))| {
+                let document = &self.documents[idx];
+                let snapshot = &self.snapshots[document.snapshot];
+                // Documents matching every query word come first
+                let coverage = matched_words.len() as f64 / words.len() as f64;
+                let best = fields
+                    .iter()
+                    .copied()
+                    .max_by(|a, b| a.weight().total_cmp(&b.weight()));
+                let snippet = document
+                    .fields
+                    .iter()
+                    .find(|(field, _)| Some(*field) == best)
+                    .map(|(_, text)| truncate(text))
+                    .unwrap_or_default();
+                SearchHit {
+                    snapshot_id: snapshot.snapshot_id,
+                    dataset_name: snapshot.dataset_name.clone(),
+                    output_hash: snapshot.output_hash.clone(),
+                    export_timestamp: snapshot.export_timestamp,
+                    column: document.column.clone(),
+                    score: score * coverage * coverage,
+                    matched_fields: fields.into_iter().collect(),
+                    snippet,
+                }
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.dataset_name.cmp(&b.dataset_name))
+                .then_with(|| a.column.cmp(&b.column))
+        });
+        hits.truncate(limit);
+        hits
+    }
+}
+
+/// Search the snapshots under `base_path` for `query`
+pub fn search_dictionaries(
+    base_path: &Path,
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SearchHit>> {
+    let index = DictionaryIndex::build(base_path, options.all_versions)?;
+    Ok(index.search(query, options.limit))
+}
+
+fn join_present<const N: usize>(parts: [&Option<String>; N]) -> String {
+    parts
+        .into_iter()
+        .flatten()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= SNIPPET_LEN {
+        return text.to_owned();
+    }
+    let cut: String = text.chars().take(SNIPPET_LEN).collect();
+    format!("{}...", cut.trim_end())
+}
+
+/// Lowercase words of `text`, split at non-alphanumerics, camelCase and
+/// letter/digit boundaries, with a trailing plural `s` dropped
+fn tokenize(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev: Option<char> = None;
+    for c in text.chars() {
+        if !c.is_alphanumeric() {
+            push_word(&mut words, &mut current);
+            prev = None;
+            continue;
+        }
+        if let Some(p) = prev {
+            let boundary = (p.is_lowercase() && c.is_uppercase())
+                || (p.is_alphabetic() && c.is_numeric())
+                || (p.is_numeric() && c.is_alphabetic());
+            if boundary {
+                push_word(&mut words, &mut current);
+            }
+        }
+        current.extend(c.to_lowercase());
+        prev = Some(c);
+    }
+    push_word(&mut words, &mut current);
+    words
+}
+
+fn push_word(words: &mut Vec<String>, current: &mut String) {
+    if current.is_empty() {
+        return;
+    }
+    let mut word = std::mem::take(current);
+    if word.len() > 3 && word.ends_with('s') && !word.ends_with("ss") {
+        word.pop();
+    }
+    words.push(word);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::GlossaryTerm;
+    use crate::dictionary::metadata::*;
+
+    fn column(name: &str, definition: Option<&str>) -> ColumnMetadata {
+        ColumnMetadata {
+            column_id: Uuid::new_v4(),
+            current_name: name.to_owned(),
+            original_name: None,
+            technical: ColumnTechnicalMetadata {
+                data_type: "String".to_owned(),
+                nullable: true,
+                null_percentage: 0.0,
+                distinct_count: 1,
+                min_value: None,
+                max_value: None,
+                sample_values: vec![],
+                warnings: vec![],
+                stats_json: None,
+                inferred_description: None,
+                semantic_type: None,
+            },
+            business: ColumnBusinessMetadata {
+                business_definition: definition.map(str::to_owned),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn snapshot(name: &str, columns: Vec<ColumnMetadata>) -> DataDictionary {
+        DataDictionary {
+            snapshot_id: Uuid::new_v4(),
+            dataset_name: name.to_owned(),
+            export_timestamp: Utc::now(),
+            dataset_metadata: DatasetMetadata {
+                technical: TechnicalMetadata {
+                    input_sources: vec![],
+                    pipeline_id: None,
+                    pipeline_json: None,
+                    input_dataset_hash: None,
+                    output_dataset_hash: name.to_owned(),
+                    row_count: 1,
+                    column_count: columns.len(),
+                    export_format: "csv".to_owned(),
+                    quality_summary: QualitySummary {
+                        avg_null_percentage: 0.0,
+                        empty_column_count: 0,
+                        constant_column_count: 0,
+                        duplicate_row_count: None,
+                        overall_score: 100.0,
+                    },
+                },
+                business: DatasetBusinessMetadata::default(),
+            },
+            columns,
+            previous_snapshot_id: None,
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("orderTotal_VAT2024 Customers' address"),
+            ["order", "total", "vat", "2024", "customer", "address"]
+        );
+    }
+
+    #[test]
+    fn test_search_columns_and_glossary() {
+        let mut tax = GlossaryTerm::new("Value Added Tax", "Tax charged on sales");
+        tax.synonyms = vec!["VAT".to_owned()];
+        let glossary = Glossary {
+            terms: vec![tax.clone()],
+        };
+
+        let mut linked = column("tax_amount", None);
+        linked.business.glossary_terms = vec![tax.id];
+        let invoices = snapshot(
+            "invoices",
+            vec![column("invoice_id", None), column("vat_rate", None), linked],
+        );
+        let customers = snapshot(
+            "customers",
+            vec![column("customer_name", Some("Full name of the customer"))],
+        );
+        let index = DictionaryIndex::from_snapshots(&[invoices, customers], &glossary);
+        assert_eq!(index.snapshot_count(), 2);
+        assert_eq!(index.document_count(), 6);
+
+        let hits = index.search("which datasets have a column about VAT?", 10);
+        let columns: Vec<_> = hits.iter().map(|h| h.column.as_deref()).collect();
+        // The column named VAT outranks the one linked to it in the glossary
+        assert_eq!(columns, [Some("vat_rate"), Some("tax_amount")]);
+        assert!(hits.iter().all(|h| h.dataset_name == "invoices"));
+        assert_eq!(hits[1].matched_fields, [SearchField::GlossaryTerm]);
+
+        // Prefixes match longer words, and the dataset itself is a hit
+        let hits = index.search("cust", 10);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.dataset_name == "customers"));
+
+        assert!(index.search("shipping", 10).is_empty());
+        assert!(index.search("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_build_skips_superseded_versions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let first = snapshot("orders", vec![column("legacy_code", None)]);
+        let mut second = snapshot("orders", vec![column("order_code", None)]);
+        second.previous_snapshot_id = Some(first.snapshot_id);
+        super::super::save_snapshot(&first, dir.path())?;
+        super::super::save_snapshot(&second, dir.path())?;
+
+        let hits = search_dictionaries(dir.path(), "legacy", &SearchOptions::default())?;
+        assert!(hits.is_empty());
+        let options = SearchOptions {
+            all_versions: true,
+            ..Default::default()
+        };
+        let hits = search_dictionaries(dir.path(), "legacy", &options)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].snapshot_id, first.snapshot_id);
+        Ok(())
+    }
+}
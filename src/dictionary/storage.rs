@@ -117,6 +117,50 @@ pub fn list_snapshots(
     Ok(snapshots)
 }
 
+/// Find the newest snapshot whose first input source is `input_path`.
+pub fn latest_snapshot_for_input(
+    base_path: &Path,
+    input_path: &Path,
+) -> Result<Option<DataDictionary>> {
+    let dict_dir = base_path.join(DICTIONARIES_DIR);
+
+    if !dict_dir.exists() {
+        return Ok(None);
+    }
+
+    let input = input_path.to_string_lossy();
+    let mut latest: Option<DataDictionary> = None;
+
+    for entry in fs::read_dir(&dict_dir)
+        .context("Failed to read dictionaries directory")?
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+
+        let Ok(snapshot) = load_snapshot_from_path(&path) else {
+            continue;
+        };
+        let matches = snapshot
+            .dataset_metadata
+            .technical
+            .input_sources
+            .first()
+            .is_some_and(|source| source.path == input);
+        if matches
+            && latest
+                .as_ref()
+                .is_none_or(|l| snapshot.export_timestamp > l.export_timestamp)
+        {
+            latest = Some(snapshot);
+        }
+    }
+
+    Ok(latest)
+}
+
 /// Metadata summary for listing snapshots.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct SnapshotMetadata {
@@ -129,8 +173,44 @@ pub struct SnapshotMetadata {
     pub completeness_pct: f64,
 }
 
+/// Every readable snapshot under `base_path`, in no particular order.
+pub fn load_all_snapshots(base_path: &Path) -> Result<Vec<DataDictionary>> {
+    let dict_dir = base_path.join(DICTIONARIES_DIR);
+    if !dict_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dict_dir)
+        .context("Failed to read dictionaries directory")?
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        if let Ok(snapshot) = load_snapshot_from_path(&path) {
+            snapshots.push(snapshot);
+        }
+    }
+    Ok(snapshots)
+}
+
+/// The newest version of every dataset, newest first: the snapshots no other
+/// snapshot links to as its previous version.
+pub fn latest_snapshots(base_path: &Path) -> Result<Vec<DataDictionary>> {
+    let mut snapshots = load_all_snapshots(base_path)?;
+    let superseded: std::collections::HashSet<Uuid> = snapshots
+        .iter()
+        .filter_map(|s| s.previous_snapshot_id)
+        .collect();
+    snapshots.retain(|s| !superseded.contains(&s.snapshot_id));
+    snapshots.sort_by(|a, b| b.export_timestamp.cmp(&a.export_timestamp));
+    Ok(snapshots)
+}
+
 /// Load a snapshot from a specific file path.
-fn load_snapshot_from_path(path: &Path) -> Result<DataDictionary> {
+pub(super) fn load_snapshot_from_path(path: &Path) -> Result<DataDictionary> {
     let json = fs::read_to_string(path)
         .with_context(|| format!("Failed to read snapshot file: {}", path.display()))?;
 
@@ -0,0 +1,339 @@
+//! Business metadata as a spreadsheet template.
+//!
+//! Analysts often keep column descriptions in a spreadsheet. [`render_template`]
+//! writes one CSV row per column of a snapshot, with the technical type and
+//! inferred description for reference and the business fields blank or
+//! filled, and [`import_template`] merges an edited copy back into a new
+//! snapshot version.
+//!
+//! Empty cells keep the current value, so a partly filled template never
+//! wipes existing documentation. Approved examples are one cell separated by
+//! `;`.
+
+use super::metadata::{ColumnBusinessMetadata, DataDictionary};
+use super::storage::{load_snapshot, update_business_metadata};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+const COLUMN_NAME: &str = "column_name";
+
+/// Header of the template; the first three columns are for reference only
+pub const TEMPLATE_HEADERS: &[&str] = &[
+    COLUMN_NAME,
+    "data_type",
+    "inferred_description",
+    "business_definition",
+    "business_rules",
+    "sensitivity_tag",
+    "approved_examples",
+    "notes",
+];
+
+const EXAMPLE_SEPARATOR: &str = "; ";
+
+/// A template row that could not be applied
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateRowError {
+    /// Spreadsheet row number, the header being row 1
+    pub row: usize,
+    pub message: String,
+}
+
+/// What importing a template changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateMergeReport {
+    /// The new snapshot version, or `None` when nothing changed
+    pub snapshot_id: Option<Uuid>,
+    pub updated_columns: Vec<String>,
+    pub unchanged_columns: Vec<String>,
+    /// Columns of the snapshot with no row in the template
+    pub missing_columns: Vec<String>,
+    /// Header cells that are not template fields, and were ignored
+    pub ignored_headers: Vec<String>,
+    pub errors: Vec<TemplateRowError>,
+}
+
+/// The template for `dict` as CSV, with the current business metadata when
+/// `filled` and blank business fields otherwise. Starts with a UTF-8 byte
+/// order mark so spreadsheet applications detect the encoding.
+pub fn render_template(dict: &DataDictionary, filled: bool) -> String {
+    let mut csv = String::from("\u{feff}");
+    push_record(&mut csv, TEMPLATE_HEADERS.iter().copied());
+    for column in &dict.columns {
+        let technical = &column.technical;
+        let business = &column.business;
+        let examples = business.approved_examples.join(EXAMPLE_SEPARATOR);
+        let business_cells = [
+            business.business_definition.as_deref().unwrap_or(""),
+            business.business_rules.as_deref().unwrap_or(""),
+            business.sensitivity_tag.as_deref().unwrap_or(""),
+            examples.as_str(),
+            business.notes.as_deref().unwrap_or(""),
+        ];
+        let reference_cells = [
+            column.current_name.as_str(),
+            technical.data_type.as_str(),
+            technical.inferred_description.as_deref().unwrap_or(""),
+        ];
+        push_record(
+            &mut csv,
+            reference_cells.into_iter().chain(
+                business_cells
+                    .into_iter()
+                    .map(|c| if filled { c } else { "" }),
+            ),
+        );
+    }
+    csv
+}
+
+/// Merge an edited template into `dict`'s column business metadata. Returns
+/// the merged metadata of every column that changed, keyed by name.
+pub fn merge_template(
+    dict: &DataDictionary,
+    csv: &str,
+) -> Result<(HashMap<String, ColumnBusinessMetadata>, TemplateMergeReport)> {
+    let mut records = parse_csv(csv.trim_start_matches('\u{feff}'))?.into_iter();
+    let Some(header) = records.next() else {
+        anyhow::bail!("The template is empty");
+    };
+    let header: Vec<String> = header.iter().map(|h| h.trim().to_lowercase()).collect();
+    let Some(name_idx) = header.iter().position(|h| h == COLUMN_NAME) else {
+        anyhow::bail!("The template has no '{COLUMN_NAME}' column");
+    };
+
+    let mut report = TemplateMergeReport {
+        ignored_headers: header
+            .iter()
+            .filter(|h| !h.is_empty() && !TEMPLATE_HEADERS.contains(&h.as_str()))
+            .cloned()
+            .collect(),
+        ..TemplateMergeReport::default()
+    };
+    let field = |record: &[String], name: &str| {
+        header
+            .iter()
+            .position(|h| h == name)
+            .and_then(|idx| record.get(idx))
+            .map(|cell| cell.trim().to_owned())
+            .filter(|cell| !cell.is_empty())
+    };
+
+    let mut updates = HashMap::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for (idx, record) in records.enumerate() {
+        let row = idx + 2;
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let name = record.get(name_idx).map_or("", |n| n.trim());
+        if name.is_empty() {
+            report.errors.push(TemplateRowError {
+                row,
+                message: format!("'{COLUMN_NAME}' is empty"),
+            });
+            continue;
+        }
+        if let Some(first) = seen.insert(name.to_owned(), row) {
+            report.errors.push(TemplateRowError {
+                row,
+                message: format!("Column '{name}' already appears on row {first}"),
+            });
+            continue;
+        }
+        let Some(column) = dict.columns.iter().find(|c| c.current_name == name) else {
+            report.errors.push(TemplateRowError {
+                row,
+                message: format!("Column '{name}' is not in the snapshot"),
+            });
+            continue;
+        };
+        if record.len() > header.len() {
+            report.errors.push(TemplateRowError {
+                row,
+                message: format!(
+                    "Row has {} cells but the header has {}",
+                    record.len(),
+                    header.len()
+                ),
+            });
+            continue;
+        }
+
+        let mut business = column.business.clone();
+        let set = |target: &mut Option<String>, value: Option<String>| {
+            if value.is_some() {
+                *target = value;
+            }
+        };
+        set(
+            &mut business.business_definition,
+            field(&record, "business_definition"),
+        );
+        set(
+            &mut business.business_rules,
+            field(&record, "business_rules"),
+        );
+        set(
+            &mut business.sensitivity_tag,
+            field(&record, "sensitivity_tag"),
+        );
+        set(&mut business.notes, field(&record, "notes"));
+        if let Some(examples) = field(&record, "approved_examples") {
+            business.approved_examples = examples
+                .split(';')
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .map(ToOwned::to_owned)
+                .collect();
+        }
+
+        if business == column.business {
+            report.unchanged_columns.push(column.current_name.clone());
+        } else {
+            report.updated_columns.push(column.current_name.clone());
+            updates.insert(column.current_name.clone(), business);
+        }
+    }
+
+    report.missing_columns = dict
+        .columns
+        .iter()
+        .filter(|c| !seen.contains_key(&c.current_name))
+        .map(|c| c.current_name.clone())
+        .collect();
+    Ok((updates, report))
+}
+
+/// Merge an edited template into snapshot `snapshot_id`, saving the result
+/// as a new snapshot version when anything changed. Rows with errors are
+/// left out and listed in the report; the other rows are still applied.
+pub fn import_template(
+    snapshot_id: &Uuid,
+    base_path: &Path,
+    csv: &str,
+) -> Result<TemplateMergeReport> {
+    let dict = load_snapshot(snapshot_id, base_path)?;
+    let (updates, mut report) = merge_template(&dict, csv)?;
+    if !updates.is_empty() {
+        let snapshot = update_business_metadata(snapshot_id, base_path, None, Some(updates))?;
+        report.snapshot_id = Some(snapshot.snapshot_id);
+    }
+    Ok(report)
+}
+
+fn push_record<'a>(csv: &mut String, cells: impl Iterator<Item = &'a str>) {
+    let cells: Vec<String> = cells.map(quote).collect();
+    csv.push_str(&cells.join(","));
+    csv.push_str("\r\n");
+}
+
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_owned()
+    }
+}
+
+/// Records of an RFC 4180 CSV document; quoted cells may span lines
+fn parse_csv(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if cell.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut cell)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n' | '\r', false) => {
+                record.push(std::mem::take(&mut cell));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => cell.push(c),
+        }
+    }
+    if in_quotes {
+        anyhow::bail!("Unterminated quoted cell in template");
+    }
+    if !cell.is_empty() || !record.is_empty() {
+        record.push(cell);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::profiler::create_snapshot;
+    use crate::dictionary::save_snapshot;
+    use polars::prelude::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_template_round_trip_and_merge_report() -> Result<()> {
+        let temp_dir = tempdir()?;
+        let df = df! {
+            "id" => &[1_i64, 2, 3],
+            "region" => &["north", "south", "east"],
+            "amount" => &[9.5, 12.0, 3.25],
+        }?;
+        let mut dict = create_snapshot(
+            "orders",
+            &df,
+            PathBuf::from("orders.csv"),
+            PathBuf::from("orders.parquet"),
+            None,
+            None,
+        )?;
+        dict.columns[0].business.notes = Some("Surrogate key".to_owned());
+        save_snapshot(&dict, temp_dir.path())?;
+
+        let blank = render_template(&dict, false);
+        assert!(blank.starts_with("\u{feff}column_name,data_type,"));
+        assert!(!blank.contains("Surrogate key"));
+        assert!(render_template(&dict, true).contains("Surrogate key"));
+
+        let edited = "column_name,business_definition,approved_examples,owner\r\n\
+                      id,,,\r\n\
+                      region,\"Sales region, as \"\"N/S/E/W\"\"\",north; south,Ops\r\n\
+                      revenue,Total,,\r\n\
+                      ,orphan,,\r\n";
+        let report = import_template(&dict.snapshot_id, temp_dir.path(), edited)?;
+
+        assert_eq!(report.updated_columns, vec!["region"]);
+        assert_eq!(report.unchanged_columns, vec!["id"]);
+        assert_eq!(report.missing_columns, vec!["amount"]);
+        assert_eq!(report.ignored_headers, vec!["owner"]);
+        let rows: Vec<usize> = report.errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![4, 5]);
+
+        let new_id = report.snapshot_id.expect("a new version");
+        let updated = load_snapshot(&new_id, temp_dir.path())?;
+        assert_eq!(updated.previous_snapshot_id, Some(dict.snapshot_id));
+        let region = &updated.columns[1].business;
+        assert_eq!(
+            region.business_definition.as_deref(),
+            Some("Sales region, as \"N/S/E/W\"")
+        );
+        assert_eq!(region.approved_examples, vec!["north", "south"]);
+        assert_eq!(
+            updated.columns[0].business.notes.as_deref(),
+            Some("Surrogate key")
+        );
+        Ok(())
+    }
+}
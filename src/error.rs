@@ -55,19 +55,31 @@
 //!
 //! ## Tauri Integration
 //!
-//! Tauri commands need `String` errors for JSON serialization. We implement
-//! `From<BeefcakeError> for String` to enable this:
+//! Tauri commands return `BeefcakeError` itself. It serializes as an object
+//! with a stable [`ErrorCode`], a message for users and the technical detail,
+//! so the frontend can tell an aborted run from a missing file without
+//! parsing text:
+//!
+//! ```json
+//! { "code": "not_found", "message": "Failed to load data", "detail": "Failed to load data: ..." }
+//! ```
+//!
+//! Errors from lower layers, usually [`anyhow::Error`], are sorted into a
+//! category by [`BeefcakeError::from_error`], which `?` uses too:
 //!
 //! ```no_run
 //! # use beefcake::error::{BeefcakeError, Result};
 //! // Tauri command signature
-//! fn analyze_file(path: String) -> std::result::Result<String, String> {
-//!     // BeefcakeError auto-converts to String
+//! fn analyze_file(path: String) -> Result<String> {
+//!     if path.is_empty() {
+//!         return Err(BeefcakeError::Validation("File path is empty".to_owned()));
+//!     }
+//!     // anyhow::Error converts with its category
 //!     let result = process_file(&path)?;
 //!     Ok(result)
 //! }
 //!
-//! fn process_file(path: &str) -> Result<String> {
+//! fn process_file(path: &str) -> anyhow::Result<String> {
 //!     // ... implementation
 //!     Ok("done".to_string())
 //! }
@@ -77,7 +89,30 @@
 //!
 //! For more on Rust error handling patterns, see `docs/RUST_CONCEPTS.md`.
 
+use serde::ser::SerializeStruct as _;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt;
+use std::io::ErrorKind;
+
+/// Stable category of a [`BeefcakeError`], sent to the frontend with each
+/// failed command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Io,
+    NotFound,
+    PermissionDenied,
+    InvalidPath,
+    Parse,
+    Validation,
+    DataProcessing,
+    Database,
+    Python,
+    Ai,
+    Config,
+    Aborted,
+    Internal,
+}
 
 /// Main error type for beefcake operations.
 #[derive(Debug)]
@@ -100,11 +135,32 @@ pub enum BeefcakeError {
     /// File not found or invalid path
     InvalidPath(String),
 
+    /// Input that cannot be read as its format (JSON, CSV, dates, ...)
+    Parse(String),
+
+    /// A request or specification that breaks a rule, such as an empty path
+    Validation(String),
+
+    /// AI provider errors
+    Ai(String),
+
+    /// Blocked by a path rule, a read-only connection or the operating system
+    PermissionDenied(String),
+
     /// Operation aborted by user
     Aborted,
 
     /// Generic error with context
     Other(String),
+
+    /// An error from a lower layer sorted into a category by
+    /// [`BeefcakeError::from_error`]: `message` is its outermost context and
+    /// `detail` the whole chain of causes
+    Categorised {
+        code: ErrorCode,
+        message: String,
+        detail: String,
+    },
 }
 
 impl fmt::Display for BeefcakeError {
@@ -116,14 +172,143 @@ impl fmt::Display for BeefcakeError {
             Self::Python(msg) => write!(f, "Python execution error: {msg}"),
             Self::Config(msg) => write!(f, "Configuration error: {msg}"),
             Self::InvalidPath(msg) => write!(f, "Invalid path: {msg}"),
+            Self::Parse(msg) => write!(f, "Parse error: {msg}"),
+            Self::Validation(msg) => write!(f, "Validation error: {msg}"),
+            Self::Ai(msg) => write!(f, "AI error: {msg}"),
+            Self::PermissionDenied(msg) => write!(f, "Permission denied: {msg}"),
             Self::Aborted => write!(f, "Operation aborted by user"),
             Self::Other(msg) => write!(f, "{msg}"),
+            Self::Categorised { detail, .. } => write!(f, "{detail}"),
         }
     }
 }
 
 impl std::error::Error for BeefcakeError {}
 
+impl BeefcakeError {
+    /// Sort any error into a category. A `BeefcakeError` anywhere in the
+    /// chain keeps its code; otherwise the innermost recognised cause (I/O,
+    /// Polars, database, JSON, ID or date parsing, or AI provider) decides, and errors saying the
+    /// user aborted are [`ErrorCode::Aborted`].
+    pub fn from_error(err: impl Into<anyhow::Error>) -> Self {
+        let err: anyhow::Error = err.into();
+        if err.chain().count() == 1 {
+            match err.downcast::<Self>() {
+                Ok(own) => return own,
+                Err(other) => return Self::categorise(&other),
+            }
+        }
+        Self::categorise(&err)
+    }
+
+    fn categorise(err: &anyhow::Error) -> Self {
+        let code = err
+            .chain()
+            .filter_map(cause_code)
+            .last()
+            .unwrap_or_else(|| {
+                let text = format!("{err:#}").to_lowercase();
+                if text.contains("aborted by user") || text.contains("cancelled by user") {
+                    ErrorCode::Aborted
+                } else {
+                    ErrorCode::Internal
+                }
+            });
+        if code == ErrorCode::Aborted {
+            return Self::Aborted;
+        }
+        Self::Categorised {
+            code,
+            message: err.to_string(),
+            detail: format!("{err:#}"),
+        }
+    }
+
+    /// Stable category of this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::Io(e) => io_code(e),
+            Self::DataProcessing(_) => ErrorCode::DataProcessing,
+            Self::Database(_) => ErrorCode::Database,
+            Self::Python(_) => ErrorCode::Python,
+            Self::Config(_) => ErrorCode::Config,
+            Self::InvalidPath(_) => ErrorCode::InvalidPath,
+            Self::Parse(_) => ErrorCode::Parse,
+            Self::Validation(_) => ErrorCode::Validation,
+            Self::Ai(_) => ErrorCode::Ai,
+            Self::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            Self::Aborted => ErrorCode::Aborted,
+            Self::Other(_) => ErrorCode::Internal,
+            Self::Categorised { code, .. } => *code,
+        }
+    }
+
+    /// What to show the user, without the chain of causes
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::Io(e) => e.to_string(),
+            Self::DataProcessing(msg)
+            | Self::Database(msg)
+            | Self::Python(msg)
+            | Self::Config(msg)
+            | Self::InvalidPath(msg)
+            | Self::Parse(msg)
+            | Self::Validation(msg)
+            | Self::Ai(msg)
+            | Self::PermissionDenied(msg)
+            | Self::Other(msg) => msg.clone(),
+            Self::Aborted => self.to_string(),
+            Self::Categorised { message, .. } => message.clone(),
+        }
+    }
+
+    /// Everything known about the error, for logs and bug reports
+    pub fn technical_message(&self) -> String {
+        self.to_string()
+    }
+}
+
+fn io_code(err: &std::io::Error) -> ErrorCode {
+    match err.kind() {
+        ErrorKind::NotFound => ErrorCode::NotFound,
+        ErrorKind::PermissionDenied => ErrorCode::PermissionDenied,
+        _ => ErrorCode::Io,
+    }
+}
+
+/// Category of one cause in an error chain, if it is a recognised type
+fn cause_code(cause: &(dyn std::error::Error + 'static)) -> Option<ErrorCode> {
+    if let Some(own) = cause.downcast_ref::<BeefcakeError>() {
+        Some(own.code())
+    } else if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+        Some(io_code(io))
+    } else if cause.is::<polars::error::PolarsError>() {
+        Some(ErrorCode::DataProcessing)
+    } else if cause.is::<sqlx::Error>() {
+        Some(ErrorCode::Database)
+    } else if cause.is::<serde_json::Error>()
+        || cause.is::<uuid::Error>()
+        || cause.is::<chrono::ParseError>()
+    {
+        Some(ErrorCode::Parse)
+    } else if cause.is::<async_openai::error::OpenAIError>() {
+        Some(ErrorCode::Ai)
+    } else {
+        None
+    }
+}
+
+/// Sent to the frontend as `{ code, message, detail }`
+impl Serialize for BeefcakeError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BeefcakeError", 3)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.user_message())?;
+        state.serialize_field("detail", &self.technical_message())?;
+        state.end()
+    }
+}
+
 impl From<std::io::Error> for BeefcakeError {
     fn from(err: std::io::Error) -> Self {
         Self::Io(err)
@@ -132,13 +317,25 @@ impl From<std::io::Error> for BeefcakeError {
 
 impl From<anyhow::Error> for BeefcakeError {
     fn from(err: anyhow::Error) -> Self {
-        Self::Other(err.to_string())
+        Self::from_error(err)
+    }
+}
+
+impl From<String> for BeefcakeError {
+    fn from(msg: String) -> Self {
+        Self::Other(msg)
+    }
+}
+
+impl From<&str> for BeefcakeError {
+    fn from(msg: &str) -> Self {
+        Self::Other(msg.to_owned())
     }
 }
 
 impl From<serde_json::Error> for BeefcakeError {
     fn from(err: serde_json::Error) -> Self {
-        Self::Config(format!("JSON error: {err}"))
+        Self::Parse(format!("JSON error: {err}"))
     }
 }
 
@@ -180,20 +377,26 @@ where
     E: Into<BeefcakeError>,
 {
     fn context(self, msg: impl Into<String>) -> Result<T> {
-        self.map_err(|e| {
-            let err: BeefcakeError = e.into();
-            BeefcakeError::Other(format!("{}: {}", msg.into(), err))
-        })
+        self.map_err(|e| with_message(e.into(), msg.into()))
     }
 
     fn with_context<F>(self, f: F) -> Result<T>
     where
         F: FnOnce() -> String,
     {
-        self.map_err(|e| {
-            let err: BeefcakeError = e.into();
-            BeefcakeError::Other(format!("{}: {}", f(), err))
-        })
+        self.map_err(|e| with_message(e.into(), f()))
+    }
+}
+
+/// `err` under the message `msg`, keeping its code
+fn with_message(err: BeefcakeError, msg: String) -> BeefcakeError {
+    match err {
+        BeefcakeError::Aborted => BeefcakeError::Aborted,
+        err => BeefcakeError::Categorised {
+            code: err.code(),
+            detail: format!("{msg}: {err}"),
+            message: msg,
+        },
     }
 }
 
@@ -214,6 +417,32 @@ mod tests {
         assert_eq!(s, "Operation aborted by user");
     }
 
+    #[test]
+    fn test_error_codes_survive_conversion() {
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "data.csv");
+        let err =
+            BeefcakeError::from_error(anyhow::Error::new(missing).context("Failed to load data"));
+        assert_eq!(err.code(), ErrorCode::NotFound);
+        assert_eq!(err.user_message(), "Failed to load data");
+        assert_eq!(err.technical_message(), "Failed to load data: data.csv");
+
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert_eq!(json["message"], "Failed to load data");
+
+        let err = BeefcakeError::from_error(anyhow::anyhow!("Pipeline aborted by user"));
+        assert_eq!(err.code(), ErrorCode::Aborted);
+
+        let own = anyhow::Error::new(BeefcakeError::Validation("Empty path".to_owned()));
+        assert_eq!(BeefcakeError::from_error(own).code(), ErrorCode::Validation);
+
+        let err: Result<()> =
+            Err(BeefcakeError::Database("timeout".to_owned())).context("Push failed");
+        let err = err.unwrap_err();
+        assert_eq!(err.code(), ErrorCode::Database);
+        assert_eq!(err.to_string(), "Push failed: Database error: timeout");
+    }
+
     #[test]
     fn test_result_context() {
         let result: std::result::Result<(), std::io::Error> = Err(std::io::Error::new(
@@ -1,12 +1,15 @@
 use crate::python_runner::{
     execute_python, python_adaptive_sink_snippet, python_load_snippet, python_preamble,
 };
-use beefcake::analyser::logic::ColumnCleanConfig;
+use beefcake::analyser::logic::types::ImputeMode;
+use beefcake::analyser::logic::{ColumnCleanConfig, HeaderlessNaming};
 use beefcake::error::{BeefcakeError, Result, ResultExt as _};
+use beefcake::utils::memory::{MemoryGovernor, MemoryStrategy};
+use beefcake::utils::runs::{Run, RunKind};
 use polars::prelude::*;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr as _;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
@@ -26,6 +29,10 @@ pub struct ExportSource {
     pub source_type: ExportSourceType,
     pub content: Option<String>,
     pub path: Option<String>,
+    /// For Analyser sources, how the columns of a CSV file read without a
+    /// header row are named
+    #[serde(default)]
+    pub header_names: Option<HeaderlessNaming>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -51,6 +58,20 @@ pub struct ExportOptions {
     pub create_dictionary: bool,
     #[serde(default = "default_create_receipt")]
     pub create_receipt: bool,
+    /// Append a `row_completeness` column after cleaning
+    #[serde(default)]
+    pub completeness_score: bool,
+    /// Optional per-column importance weights for the completeness score
+    #[serde(default)]
+    pub completeness_weights: HashMap<String, f64>,
+    /// Write rows whose values fail type conversion to this file, with a
+    /// `reject_reason` column, instead of exporting them
+    #[serde(default)]
+    pub reject_path: Option<String>,
+    /// For database exports, also append per-column statistics and health
+    /// metrics to `<table>_profile`
+    #[serde(default)]
+    pub profile_table: bool,
 }
 
 fn default_create_dictionary() -> bool {
@@ -70,8 +91,11 @@ pub async fn prepare_export_source(
             let path = source.path.as_ref().ok_or_else(|| {
                 BeefcakeError::InvalidPath("No path provided for Analyser source".to_owned())
             })?;
-            beefcake::analyser::logic::load_df_lazy(&PathBuf::from(path))
-                .context("Failed to load data")
+            beefcake::analyser::logic::load_df_lazy_as(
+                &PathBuf::from(path),
+                source.header_names.as_ref(),
+            )
+            .context("Failed to load data")
         }
         ExportSourceType::Sql => {
             let query = source.content.as_ref().ok_or_else(|| {
@@ -182,6 +206,8 @@ except Exception as e:
     }
 }
 
+/// Write `lf` to the destination of `options`. Files are written in batches
+/// so an abort stops the export between them.
 pub async fn execute_export_destination(
     options: &ExportOptions,
     mut lf: LazyFrame,
@@ -204,26 +230,23 @@ pub async fn execute_export_destination(
 
             beefcake::config::log_event(
                 "Export",
-                &format!("Sinking data to temporary file: {}", temp_path.display()),
+                &format!(
+                    "Writing data to temporary file in batches: {}",
+                    temp_path.display()
+                ),
             );
 
-            match ext.as_str() {
+            let rows = match ext.as_str() {
                 "parquet" => {
                     let options = beefcake::analyser::logic::get_parquet_write_options(&lf)
                         .context("Failed to determine Parquet options")?;
-                    lf.sink_parquet(&temp_path, options, None)
-                        .context("Parquet export failed")?;
-                }
-                "csv" => {
-                    lf.sink_csv(&temp_path, Default::default(), None)
-                        .context("CSV export failed")?;
+                    beefcake::analyser::logic::write_parquet_in_batches(lf, &temp_path, &options)
+                        .context("Parquet export failed")?
                 }
-                _ => {
-                    let mut df = lf.collect().context("Export failed (collect)")?;
-                    beefcake::analyser::logic::save_df(&mut df, &temp_path)
-                        .context("Failed to save file")?;
-                }
-            }
+                // Like `save_df`, anything other than Parquet is written as CSV
+                _ => beefcake::analyser::logic::write_csv_in_batches(lf, &temp_path)
+                    .context("CSV export failed")?,
+            };
 
             // Move temp file to final destination
             if let Some(parent) = final_path.parent() {
@@ -237,23 +260,16 @@ pub async fn execute_export_destination(
 
             beefcake::config::log_event(
                 "Export",
-                &format!("Successfully exported to {}", final_path.display()),
+                &format!(
+                    "Successfully exported {} rows to {}",
+                    beefcake::utils::fmt_count(rows),
+                    final_path.display()
+                ),
             );
             Ok(())
         }
         ExportDestinationType::Database => {
             let connection_id = options.destination.target.clone();
-
-            let temp_dir = std::env::temp_dir();
-            let temp_path = temp_dir.join(format!("beefcake_db_push_{}.csv", Uuid::new_v4()));
-            temp_files.add(temp_path.clone());
-
-            beefcake::config::log_event("Export", "Sinking to temp CSV for database push...");
-
-            lf.sink_csv(&temp_path, Default::default(), None)
-                .context("Failed to prepare database push")?;
-
-            // Resolve connection and call flow
             let config = beefcake::config::load_app_config();
             let conn = config
                 .settings
@@ -261,26 +277,66 @@ pub async fn execute_export_destination(
                 .iter()
                 .find(|c| c.id == connection_id)
                 .ok_or_else(|| BeefcakeError::Database("Connection not found".to_owned()))?;
+            if conn.read_only {
+                return Err(BeefcakeError::Database(format!(
+                    "Connection '{}' is read-only: pushing data is blocked",
+                    conn.name
+                )));
+            }
+
+            let temp_dir = std::env::temp_dir();
+            let temp_path = temp_dir.join(format!("beefcake_db_push_{}.csv", Uuid::new_v4()));
+            temp_files.add(temp_path.clone());
+
+            beefcake::config::log_event("Export", "Writing temp CSV for database push...");
+
+            beefcake::analyser::logic::write_csv_in_batches(lf, &temp_path)
+                .context("Failed to prepare database push")?;
 
             let url = conn.settings.connection_string(&connection_id);
             let opts = sqlx::postgres::PgConnectOptions::from_str(&url)
                 .context("Invalid connection URL")?;
 
-            beefcake::analyser::logic::flows::push_to_db_flow(
+            let _permit = crate::commands::limits::DB_PUSH
+                .acquire(&config.settings.limits.db_push)
+                .await?;
+            let push_options = beefcake::analyser::logic::PushOptions {
+                profile: options.profile_table,
+                read_only: conn.read_only,
+                ..Default::default()
+            };
+            beefcake::analyser::logic::flows::push_to_db_flow_with_options(
                 temp_path,
                 opts,
                 conn.settings.schema.clone(),
                 conn.settings.table.clone(),
                 options.configs.clone(),
+                push_options,
             )
             .await
+            .map(drop)
             .map_err(BeefcakeError::from)
         }
     }
 }
 
+/// Run an export as one export run (see [`beefcake::utils::runs`])
 pub async fn export_data_execution(
     options: ExportOptions,
+    governor: &MemoryGovernor,
+    temp_files: &mut beefcake::utils::TempFileCollection,
+) -> Result<()> {
+    let run = Run::start(RunKind::Export, &options.destination.target);
+    let result = run
+        .instrument(run_export(options, governor, temp_files))
+        .await;
+    run.finish(&result);
+    result
+}
+
+async fn run_export(
+    options: ExportOptions,
+    governor: &MemoryGovernor,
     temp_files: &mut beefcake::utils::TempFileCollection,
 ) -> Result<()> {
     if beefcake::utils::is_aborted() {
@@ -299,12 +355,41 @@ pub async fn export_data_execution(
     beefcake::config::log_event("Export", "Step 1/3: Preparing data source (streaming)...");
     let mut lf = prepare_export_source(&options.source, temp_files).await?;
 
+    let estimate = match (&options.source.source_type, &options.source.path) {
+        (ExportSourceType::Analyser, Some(path)) => {
+            beefcake::utils::memory::estimate_file(Path::new(path))
+        }
+        _ => governor.estimate_lazy(&lf)?,
+    };
+    let high_mem_ops = memory_intensive_ops(&options.configs);
+    let strategy = governor.strategy(estimate, high_mem_ops > 0);
+    if strategy != MemoryStrategy::InMemory {
+        beefcake::config::log_event(
+            "Export",
+            &format!(
+                "Data of about {} exceeds the memory budget ({strategy:?}, {high_mem_ops} \
+                 memory-intensive operations)",
+                beefcake::utils::fmt_bytes(estimate)
+            ),
+        );
+    }
+    // Sql and Python results are already staged in Parquet
+    if strategy == MemoryStrategy::SpillToDisk
+        && matches!(options.source.source_type, ExportSourceType::Analyser)
+    {
+        lf = governor.spill(lf, temp_files)?;
+    }
+
     // 2. Apply cleaning/transformation logic
     if !options.configs.is_empty() {
         beefcake::config::log_event(
             "Export",
             "Step 2/3: Applying optimized streaming cleaning pipeline...",
         );
+        if let Some(reject_path) = &options.reject_path {
+            lf = write_rejects(lf, &options.configs, Path::new(reject_path))?;
+        }
+
         lf = beefcake::analyser::logic::clean_df_lazy(lf, &options.configs, false)
             .context("Failed to apply cleaning")?;
 
@@ -313,8 +398,24 @@ pub async fn export_data_execution(
         }
     }
 
+    if options.completeness_score {
+        lf = beefcake::analyser::logic::append_completeness_score(
+            lf,
+            beefcake::analyser::logic::COMPLETENESS_COLUMN,
+            &options.completeness_weights,
+        )
+        .context("Failed to add completeness score")?;
+    }
+
     // 3. Write to destination
-    execute_export_destination(&options, lf, temp_files).await?;
+    // Batch writers report an abort as a failure
+    if let Err(e) = execute_export_destination(&options, lf, temp_files).await {
+        return Err(if beefcake::utils::is_aborted() {
+            BeefcakeError::Aborted
+        } else {
+            e
+        });
+    }
 
     if beefcake::utils::is_aborted() {
         return Err(BeefcakeError::Aborted);
@@ -347,6 +448,48 @@ pub async fn export_data_execution(
     Ok(())
 }
 
+/// Active cleaning steps that need whole-column statistics, and so read the
+/// data more than once
+fn memory_intensive_ops(configs: &HashMap<String, ColumnCleanConfig>) -> usize {
+    configs
+        .values()
+        .filter(|c| c.active && c.ml_preprocessing)
+        .map(|c| {
+            usize::from(matches!(
+                c.impute_mode,
+                ImputeMode::Median | ImputeMode::Mode
+            )) + usize::from(c.clip_outliers)
+        })
+        .sum()
+}
+
+/// Write rows that would fail type conversion to `path` and return the rest
+fn write_rejects(
+    mut lf: LazyFrame,
+    configs: &HashMap<String, ColumnCleanConfig>,
+    path: &Path,
+) -> Result<LazyFrame> {
+    let schema = lf.collect_schema()?;
+    let conditions = beefcake::analyser::logic::cleaning_reject_conditions(&schema, configs, false);
+    let (kept, rejects) = beefcake::analyser::logic::split_rejects(lf, &conditions);
+
+    let mut rejects_df = rejects
+        .collect()
+        .context("Failed to collect rejected rows")?;
+    beefcake::config::log_event(
+        "Export",
+        &format!(
+            "Writing {} rejected rows to {}",
+            rejects_df.height(),
+            path.display()
+        ),
+    );
+    beefcake::analyser::logic::save_df(&mut rejects_df, path)
+        .context("Failed to write rejected rows")?;
+
+    Ok(kept)
+}
+
 /// Create a data dictionary snapshot for the exported dataset.
 async fn create_dictionary_snapshot(options: &ExportOptions) -> Result<()> {
     beefcake::config::log_event("Export", "Creating data dictionary snapshot...");
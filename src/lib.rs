@@ -31,6 +31,8 @@
 //! - [`dictionary`]: Data dictionary snapshots and metadata management
 //! - [`integrity`]: Export integrity receipts and verification
 //! - [`pipeline`]: Automation and transformation pipeline system
+//! - [`scripts`]: Versioned Python and PowerShell script templates with parameter slots
+//! - [`sql`]: Saved SQL queries, execution history and query results
 //! - [`error`]: Error types and handling utilities
 //! - [`utils`]: Common utility functions
 //! - [`watcher`]: File system watcher service
@@ -112,5 +114,7 @@ pub mod error;
 pub mod integrity;
 pub mod logging;
 pub mod pipeline;
+pub mod scripts;
+pub mod sql;
 pub mod utils;
 pub mod watcher;
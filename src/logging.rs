@@ -9,6 +9,9 @@
 //! - **Structured Logging**: JSON-compatible format with timestamps and context
 //! - **Multiple Targets**: Console (for dev) + Files (for production debugging)
 //! - **Error Tracking**: Separate error.log for easy error identification
+//! - **Run Correlation**: `beefcake.<date>.jsonl` holds every event as JSON
+//!   with the ID of the analysis, pipeline or export run it belongs to
+//!   (see [`crate::utils::runs`])
 //! - **Cross-Platform**: Uses platform-specific app data directories
 //!
 //! ## Usage
@@ -24,6 +27,7 @@
 //! tracing::error!("Something went wrong");
 //! ```
 
+use crate::utils::runs;
 use anyhow::{Context as _, Result};
 use std::path::PathBuf;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
@@ -53,9 +57,10 @@ pub fn get_log_dir() -> Result<PathBuf> {
 
 /// Initializes the logging system with console and file output
 ///
-/// Creates two log files:
+/// Creates three log files:
 /// - `beefcake.log`: All log levels (info, warn, error, debug)
 /// - `error.log`: Only errors and warnings
+/// - `beefcake.jsonl`: All log levels as JSON lines, with run IDs
 ///
 /// Both files rotate daily and when they reach 10MB, keeping 10 old files.
 ///
@@ -83,6 +88,15 @@ pub fn init() -> Result<()> {
         .build(&log_dir)
         .context("Failed to create error-logs file appender")?;
 
+    // Create file appender for structured logs, read back per run
+    let json_logs_appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .max_log_files(10)
+        .filename_prefix(runs::JSON_LOG_PREFIX)
+        .filename_suffix(runs::JSON_LOG_SUFFIX)
+        .build(&log_dir)
+        .context("Failed to create structured-logs file appender")?;
+
     // Create env filter - default to INFO, allow override with RUST_LOG
     let env_filter = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new("info"))
@@ -120,6 +134,7 @@ pub fn init() -> Result<()> {
         .with(stdout_layer)
         .with(all_logs_layer)
         .with(error_logs_layer)
+        .with(runs::json_layer(json_logs_appender))
         .init();
 
     tracing::info!("Logging initialized, log directory: {:?}", log_dir);
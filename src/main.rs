@@ -23,7 +23,7 @@
 //! When run with arguments, operates as a command-line tool:
 //! ```bash
 //! beefcake analyze data.csv
-//! beefcake pipeline execute spec.json
+//! beefcake pipeline run --spec spec.json --input data.csv
 //! ```
 //!
 //! ## GUI Mode
@@ -58,8 +58,9 @@
 //!
 //! ## Error Handling
 //!
-//! Returns `Result<(), Box<dyn std::error::Error>>` which means:
-//! - `()` on success (no meaningful return value)
+//! Returns `Result<ExitCode, Box<dyn std::error::Error>>` which means:
+//! - `ExitCode` on success: the process exit code. CLI commands report their
+//!   own errors and pick a code by the kind of failure (see `cli::ExitCode`)
 //! - `Box<dyn std::error::Error>` on failure (any error type)
 //!
 //! The `?` operator propagates errors to the caller (Rust runtime).
@@ -78,6 +79,7 @@ mod tauri_app;
 
 use anyhow::Result;
 use clap::Parser as _;
+use std::process::ExitCode;
 
 /// Main entry point for the Beefcake application.
 ///
@@ -102,7 +104,7 @@ use clap::Parser as _;
 /// - CLI command fails
 /// - Tokio runtime initialization fails
 /// - Tauri application fails to start
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
     // Initialize comprehensive file and console logging
     // Logs will be written to platform-specific app data directory
     if let Err(e) = beefcake::logging::init() {
@@ -124,9 +126,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         // We need a Tokio runtime because some operations are async
         // (database queries, HTTP requests, etc.)
         tracing::info!("Running CLI command");
-        tokio::runtime::Runtime::new()?.block_on(cli::run_command(command))?;
-        tracing::info!("CLI command completed successfully");
-        return Ok(());
+        let output = cli::Output {
+            json: cli.json,
+            quiet: cli.quiet,
+            interactive: cli.interactive,
+        };
+        let code = tokio::runtime::Runtime::new()?.block_on(cli::run(command, output));
+        tracing::info!("CLI command finished with exit code {}", code as u8);
+        return Ok(code.into());
     }
 
     // GUI mode: Launch Tauri desktop application
@@ -134,5 +141,5 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::info!("Launching Tauri GUI application");
     tauri_app::run();
     tracing::info!("Application shutdown complete");
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
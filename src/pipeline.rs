@@ -5,12 +5,44 @@
 //!
 //! # Overview
 //!
-//! The pipeline system provides 11 transformation steps organized into categories:
-//! - **Column Management**: `drop_columns`, `rename_columns`
-//! - **Text Processing**: `trim_whitespace`, `regex_replace`
+//! The pipeline system provides 32 transformation steps organized into categories:
+//! - **Column Management**: `drop_columns`, `rename_columns`, `split_column`, `merge_columns`
+//! - **Text Processing**: `trim_whitespace`, `regex_replace`, `map_values`
 //! - **Type Conversion**: `cast_types`, `parse_dates`
 //! - **Missing Values**: impute (mean/median/mode/zero)
-//! - **ML Preprocessing**: `normalize_columns`, `one_hot_encode`, `clip_outliers`, `extract_numbers`
+//! - **ML Preprocessing**: `normalize_columns`, `one_hot_encode`, `encode_categories` (ordinal or
+//!   leakage-safe target encoding), `clip_outliers`, `extract_numbers`, `bin` (equal-width,
+//!   quantile or custom buckets), `vectorise_text` (hashing or TF-IDF features from free text)
+//! - **Derived Columns**: `with_column` (see [`expression`] for the syntax), `extract_date_parts`
+//!   (year, month, weekday, hour and other calendar features)
+//! - **Summaries**: `aggregate` (group by with sum/mean/min/max/count/n_unique)
+//! - **Combining Inputs**: `concat`, `union` (stack named inputs from `input.sources`)
+//! - **Row Selection**: `sort`, `limit`, `sample` (seeded, reproducible)
+//! - **Reshaping**: `pivot` (long to wide), `unpivot` (wide to long), `transpose` (swap rows and columns)
+//! - **Data Quality**: `completeness_score` (weighted fraction of non-null fields per row),
+//!   `expect_schema` (fail fast when the columns, types or nulls break a contract, see [`contract`])
+//! - **Outputs**: `write_output` (persist an intermediate stage alongside the final output)
+//! - **External Tools**: `external` (run a trusted command over the data, see [`external`])
+//!
+//! [`lint_pipeline`] flags no-op, conflicting and oddly ordered steps as warnings.
+//! [`run_pipeline_dry`] previews the schema after every step without reading rows.
+//!
+//! `output.deliver` copies the written outputs to network shares or SFTP
+//! servers with retries and size/hash verification; see [`delivery`].
+//!
+//! Every run is recorded in a persistent run history; see [`history`].
+//! [`run_pipeline_on_dataset`] runs a spec on the active version of a
+//! lifecycle dataset and can register the output as a new version; see
+//! [`dataset`].
+//! Steps marked `"checkpoint": true` save their results so a failed run can
+//! resume from the latest checkpoint.
+//!
+//! When a file names the expected columns differently (`customer_id` for
+//! `Customer ID`), [`resolve_pipeline_columns`] suggests matches to confirm;
+//! confirmed ones go in `input.column_renames`, see [`resolution`].
+//!
+//! Any string in a spec can use template variables (`{date}`, `{input_stem}`,
+//! `{env:VAR}` and user `params`); see [`template`].
 //!
 //! # Example: Programmatic Pipeline Creation
 //!
@@ -40,14 +72,40 @@
 //! 7. **Missing Data Handling**: Drop high-missingness columns, impute remaining
 //! 8. **Type Conversion**: Cast types, parse dates with custom formats
 
+mod checkpoint;
+pub mod contract;
+pub mod dataset;
+pub mod delivery;
+pub mod dry_run;
 pub mod executor;
+pub mod expression;
+pub mod external;
+pub mod history;
+pub mod lint;
 pub mod powershell;
+pub mod resolution;
 pub mod spec;
+pub mod template;
 pub mod validation;
 
-pub use executor::{RunReport, run_pipeline};
+pub use contract::ContractViolations;
+pub use dataset::{DatasetRunOptions, DatasetRunReport, run_pipeline_on_dataset};
+pub use delivery::DeliveryStatus;
+pub use dry_run::{DryRunReport, run_pipeline_dry};
+pub use executor::{
+    RunProgress, RunReport, load_input, rename_input_columns, run_pipeline,
+    run_pipeline_with_inputs, run_pipeline_with_progress, validate_pipeline_for_input,
+};
+pub use history::{DatasetRun, HistoryQuery, HistoryStore, RunRecord, RunStatus};
+pub use lint::lint_pipeline;
 pub use powershell::generate_powershell_script;
+pub use resolution::{rename_schema, resolve_pipeline_columns};
 pub use spec::{
-    ImputeStrategy, InputConfig, OutputConfig, PipelineSpec, SPEC_VERSION, SchemaMatchMode, Step,
+    AggregateFunction, Aggregation, DeliveryDestination, DeliveryTarget, DeliveryVerify,
+    ErrorPolicy, ExpectedColumn, ExternalDataFormat, ImputeStrategy, InputConfig, NamedInput,
+    OutputConfig, PipelineSpec, PipelineStep, SPEC_VERSION, SchemaMatchMode, Step, ViolationAction,
+};
+pub use validation::{
+    ErrorCode, Severity, ValidationError, ValidationFailed, validate_pipeline,
+    validate_pipeline_with_inputs,
 };
-pub use validation::{ValidationError, validate_pipeline};
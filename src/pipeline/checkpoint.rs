@@ -0,0 +1,219 @@
+//! Step checkpoints for resuming long pipeline runs.
+//!
+//! After a step marked `"checkpoint": true`, the executor writes the data
+//! (and any rows rejected so far) to Parquet along with a small manifest.
+//! The manifest holds a key hashed from the input files' size and modified
+//! time and the spec up to and including that step, so a checkpoint is only
+//! reused when nothing it depends on has changed. Editing a later step keeps
+//! earlier checkpoints valid. A run that completes removes its checkpoints.
+
+use super::executor::write_output;
+use super::history::canonical_hash;
+use super::spec::{OutputConfig, PipelineSpec};
+use crate::analyser::logic::load_df_lazy;
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Manifest written next to each checkpoint
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    step_index: usize,
+    key: String,
+    has_rejects: bool,
+}
+
+/// Data restored from the latest valid checkpoint
+pub(super) struct Resume {
+    /// Index of the checkpointed step; the run continues after it
+    pub step_index: usize,
+    pub data: LazyFrame,
+    pub rejects: Option<LazyFrame>,
+}
+
+/// Checkpoint locations and keys for one run
+pub(super) struct Checkpoints {
+    dir: PathBuf,
+    /// Step index -> key its checkpoint must carry to be reused
+    keys: BTreeMap<usize, String>,
+}
+
+impl Checkpoints {
+    /// Set up checkpoints for `spec` (already template-resolved), or `None`
+    /// when no step asks for one. `inputs` are the main and named input files.
+    pub fn new(spec: &PipelineSpec, inputs: &[&Path], output_path: &Path) -> Option<Self> {
+        if !spec.steps.iter().any(|s| s.checkpoint) {
+            return None;
+        }
+
+        let dir = if spec.output.checkpoint_dir.is_empty() {
+            output_path
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(".checkpoints")
+                .join(sanitize(&spec.name))
+        } else {
+            PathBuf::from(&spec.output.checkpoint_dir)
+        };
+
+        let spec_value = serde_json::to_value(spec).ok()?;
+        let fingerprints: Vec<String> = inputs.iter().map(|p| fingerprint(p)).collect();
+        let keys = spec
+            .steps
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.checkpoint)
+            .map(|(idx, _)| {
+                let mut prefix = spec_value.clone();
+                if let Some(Value::Array(steps)) = prefix.get_mut("steps") {
+                    steps.truncate(idx + 1);
+                }
+                let key = canonical_hash(json!({ "inputs": fingerprints, "spec": prefix }));
+                (idx, key)
+            })
+            .collect();
+
+        Some(Self { dir, keys })
+    }
+
+    /// Folder the checkpoints are written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn covers(&self, idx: usize) -> bool {
+        self.keys.contains_key(&idx)
+    }
+
+    /// The latest checkpoint whose key still matches, if any
+    pub fn resume(&self) -> Option<Resume> {
+        self.keys.iter().rev().find_map(|(&idx, key)| {
+            let manifest: Manifest =
+                serde_json::from_str(&std::fs::read_to_string(self.manifest_path(idx)).ok()?)
+                    .ok()?;
+            if manifest.step_index != idx || manifest.key != *key {
+                return None;
+            }
+            let data = load_df_lazy(self.data_path(idx)).ok()?;
+            let rejects = if manifest.has_rejects {
+                Some(load_df_lazy(self.rejects_path(idx)).ok()?)
+            } else {
+                None
+            };
+            Some(Resume {
+                step_index: idx,
+                data,
+                rejects,
+            })
+        })
+    }
+
+    /// Write the data after step `idx` and return a frame reading it back,
+    /// which also spares later steps from recomputing the plan
+    pub fn save_data(&self, idx: usize, lf: LazyFrame) -> Result<LazyFrame> {
+        let path = self.data_path(idx);
+        self.write_parquet(lf, &path)?;
+        load_df_lazy(&path)
+    }
+
+    /// Write the rows rejected so far and mark the checkpoint for step `idx`
+    /// complete. Returns the rejects as a single frame read back from disk.
+    pub fn commit(&self, idx: usize, rejects: Option<LazyFrame>) -> Result<Option<LazyFrame>> {
+        let rejects = match rejects {
+            Some(lf) => {
+                let path = self.rejects_path(idx);
+                self.write_parquet(lf, &path)?;
+                Some(load_df_lazy(&path)?)
+            }
+            None => None,
+        };
+
+        let manifest = Manifest {
+            step_index: idx,
+            key: self.keys.get(&idx).cloned().unwrap_or_default(),
+            has_rejects: rejects.is_some(),
+        };
+        let path = self.manifest_path(idx);
+        std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write checkpoint manifest {}", path.display()))?;
+        Ok(rejects)
+    }
+
+    /// Remove this run's checkpoint files, and the folder if it is then empty
+    pub fn clear(&self) {
+        for &idx in self.keys.keys() {
+            for path in [
+                self.manifest_path(idx),
+                self.data_path(idx),
+                self.rejects_path(idx),
+            ] {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        let _ = std::fs::remove_dir(&self.dir);
+    }
+
+    fn write_parquet(&self, lf: LazyFrame, path: &Path) -> Result<()> {
+        // Write under a temporary name so a crash never leaves a truncated
+        // file that looks complete
+        let partial = path.with_extension("tmp.parquet");
+        let config = OutputConfig {
+            format: "parquet".to_owned(),
+            ..OutputConfig::default()
+        };
+        write_output(lf, &partial, &config)
+            .with_context(|| format!("Failed to write checkpoint {}", path.display()))?;
+        std::fs::rename(&partial, path)
+            .with_context(|| format!("Failed to write checkpoint {}", path.display()))
+    }
+
+    fn manifest_path(&self, idx: usize) -> PathBuf {
+        self.dir.join(format!("step_{idx:03}.json"))
+    }
+
+    fn data_path(&self, idx: usize) -> PathBuf {
+        self.dir.join(format!("step_{idx:03}.parquet"))
+    }
+
+    fn rejects_path(&self, idx: usize) -> PathBuf {
+        self.dir.join(format!("step_{idx:03}_rejects.parquet"))
+    }
+}
+
+/// Identify a file's contents cheaply by path, size and modified time
+fn fingerprint(path: &Path) -> String {
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let (len, modified) = std::fs::metadata(path)
+        .map(|m| {
+            let modified = m
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos())
+                .unwrap_or_default();
+            (m.len(), modified)
+        })
+        .unwrap_or_default();
+    format!("{}|{len}|{modified}", resolved.display())
+}
+
+fn sanitize(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "pipeline".to_owned()
+    } else {
+        cleaned
+    }
+}
@@ -0,0 +1,245 @@
+//! Schema contracts: the columns a pipeline expects of its data.
+//!
+//! A [`Step::ExpectSchema`] lists columns with an optional type and whether
+//! they may hold nulls. Types are checked against the lazy schema; nulls
+//! need a pass over the data, so dry runs leave them out. Every mismatch is
+//! collected before reporting, so one run shows everything that changed.
+//!
+//! [`Step::ExpectSchema`]: super::spec::Step::ExpectSchema
+
+use super::spec::ExpectedColumn;
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use std::fmt;
+
+/// Type names a contract column can expect. `Numeric` accepts any number
+/// type, `Integer`/`i64` whole numbers and `Float`/`f64` floating point.
+pub const DTYPE_NAMES: [&str; 11] = [
+    "Numeric",
+    "Integer",
+    "i64",
+    "Float",
+    "f64",
+    "Text",
+    "String",
+    "Categorical",
+    "Boolean",
+    "Temporal",
+    "Date",
+];
+
+/// Whether a column of type `actual` satisfies `expected`, or `None` when
+/// `expected` is not one of [`DTYPE_NAMES`]
+pub fn dtype_matches(expected: &str, actual: &DataType) -> Option<bool> {
+    Some(match expected {
+        "Numeric" => actual.is_numeric(),
+        "Integer" | "i64" => actual.is_integer(),
+        "Float" | "f64" => actual.is_float(),
+        "Text" | "String" => actual == &DataType::String,
+        "Categorical" => matches!(
+            actual,
+            DataType::String | DataType::Categorical(..) | DataType::Enum(..)
+        ),
+        "Boolean" => actual == &DataType::Boolean,
+        "Temporal" => actual.is_temporal(),
+        "Date" => actual == &DataType::Date,
+        _ => return None,
+    })
+}
+
+/// One way the data breaks a contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolation {
+    Missing {
+        column: String,
+    },
+    WrongType {
+        column: String,
+        expected: String,
+        actual: String,
+    },
+    Nulls {
+        column: String,
+        count: usize,
+    },
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing { column } => write!(f, "Column '{column}' is missing"),
+            Self::WrongType {
+                column,
+                expected,
+                actual,
+            } => write!(f, "Column '{column}' is {actual}, expected {expected}"),
+            Self::Nulls { column, count } => {
+                let rows = if *count == 1 { "row" } else { "rows" };
+                write!(f, "Column '{column}' has nulls in {count} {rows}")
+            }
+        }
+    }
+}
+
+/// Every way `lf` breaks the contract `expected`: missing and mistyped
+/// columns in contract order, then columns with nulls. Nulls are only
+/// counted with `check_nulls`, as that reads the data.
+pub fn schema_violations(
+    lf: &LazyFrame,
+    expected: &[ExpectedColumn],
+    check_nulls: bool,
+) -> Result<Vec<SchemaViolation>> {
+    let schema = lf
+        .clone()
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!("Failed to resolve schema: {e}"))?;
+
+    let mut violations = Vec::new();
+    let mut not_null = Vec::new();
+    for column in expected {
+        let Some(actual) = schema.get(&column.name) else {
+            violations.push(SchemaViolation::Missing {
+                column: column.name.clone(),
+            });
+            continue;
+        };
+        if let Some(dtype) = &column.dtype
+            && dtype_matches(dtype, actual) == Some(false)
+        {
+            violations.push(SchemaViolation::WrongType {
+                column: column.name.clone(),
+                expected: dtype.clone(),
+                actual: actual.to_string(),
+            });
+        }
+        if !column.nullable {
+            not_null.push(column.name.as_str());
+        }
+    }
+
+    if check_nulls && !not_null.is_empty() {
+        let counts = lf
+            .clone()
+            .select(
+                not_null
+                    .iter()
+                    .map(|name| col(*name).null_count())
+                    .collect::<Vec<_>>(),
+            )
+            .collect()
+            .context("Failed to count nulls")?;
+        for name in not_null {
+            let count = counts
+                .column(name)?
+                .cast(&DataType::UInt64)?
+                .u64()?
+                .get(0)
+                .unwrap_or(0) as usize;
+            if count > 0 {
+                violations.push(SchemaViolation::Nulls {
+                    column: name.to_owned(),
+                    count,
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// The error of a contract that fails the run, so callers can tell broken
+/// data from other failures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractViolations(pub Vec<SchemaViolation>);
+
+impl fmt::Display for ContractViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&violation_report(&self.0))
+    }
+}
+
+impl std::error::Error for ContractViolations {}
+
+/// A report of `violations` for an error message, one per line
+pub fn violation_report(violations: &[SchemaViolation]) -> String {
+    let mut report = format!(
+        "Data does not match the expected schema ({} {}):",
+        violations.len(),
+        if violations.len() == 1 {
+            "problem"
+        } else {
+            "problems"
+        }
+    );
+    for violation in violations {
+        report.push_str(&format!("\n  - {violation}"));
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(name: &str, dtype: Option<&str>, nullable: bool) -> ExpectedColumn {
+        ExpectedColumn {
+            name: name.to_owned(),
+            dtype: dtype.map(str::to_owned),
+            nullable,
+        }
+    }
+
+    #[test]
+    fn test_schema_violations() {
+        let lf = df!(
+            "id" => [1i64, 2, 3],
+            "price" => ["1.50", "2.00", "3.25"],
+            "email" => [Some("a@b.com"), None, None],
+        )
+        .unwrap()
+        .lazy();
+        let contract = [
+            expected("id", Some("Integer"), false),
+            expected("price", Some("Numeric"), true),
+            expected("email", None, false),
+            expected("region", Some("Text"), true),
+        ];
+
+        let violations = schema_violations(&lf, &contract, true).unwrap();
+        assert_eq!(
+            violations,
+            [
+                SchemaViolation::WrongType {
+                    column: "price".to_owned(),
+                    expected: "Numeric".to_owned(),
+                    actual: "str".to_owned(),
+                },
+                SchemaViolation::Missing {
+                    column: "region".to_owned(),
+                },
+                SchemaViolation::Nulls {
+                    column: "email".to_owned(),
+                    count: 2,
+                },
+            ]
+        );
+        assert!(violation_report(&violations).contains("3 problems"));
+
+        // Without reading rows only the schema is checked
+        assert_eq!(schema_violations(&lf, &contract, false).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dtype_matches() {
+        assert_eq!(dtype_matches("Numeric", &DataType::Float32), Some(true));
+        assert_eq!(dtype_matches("i64", &DataType::Float64), Some(false));
+        assert_eq!(dtype_matches("Categorical", &DataType::String), Some(true));
+        assert_eq!(
+            dtype_matches(
+                "Temporal",
+                &DataType::Datetime(TimeUnit::Milliseconds, None)
+            ),
+            Some(true)
+        );
+        assert_eq!(dtype_matches("Decimal", &DataType::Float64), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! Running pipelines on lifecycle datasets.
+//!
+//! [`run_pipeline_on_dataset`] runs a spec on the active version of a dataset
+//! in a [`DatasetRegistry`] instead of on a file. The run is recorded in the
+//! run history against the dataset and the version it read, and its output
+//! can be registered as a new version of the dataset, so scheduled and
+//! scripted runs feed the lifecycle the same way manual transforms do.
+
+use super::executor::{RunReport, execute_pipeline_on};
+use super::history::{DatasetRun, HistoryStore, RunRecord};
+use super::spec::PipelineSpec;
+use crate::analyser::lifecycle::{
+    DatasetRegistry, LifecycleStage, PIPELINE_RUN_FIELD, VersionMetadata,
+};
+use crate::analyser::logic::load_df_lazy;
+use crate::config::policy::PathPolicy;
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Options for [`run_pipeline_on_dataset`]
+#[derive(Debug, Clone, Default)]
+pub struct DatasetRunOptions {
+    /// Paths overriding those declared in `spec.input.sources`, by name
+    pub source_paths: HashMap<String, PathBuf>,
+    pub output_path_override: Option<PathBuf>,
+    /// Register the output as a new version at this stage and make it active
+    pub register_as: Option<LifecycleStage>,
+}
+
+/// Outcome of [`run_pipeline_on_dataset`]
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetRunReport {
+    /// Id of the run in the run history
+    pub run_id: String,
+    pub dataset: DatasetRun,
+    #[serde(flatten)]
+    pub report: RunReport,
+}
+
+/// Run `spec` on the active version of `dataset_id`. `{input_stem}` resolves
+/// to the dataset name unless `spec.params` sets it. The output is written
+/// as for a file run; with `options.register_as` it is also stored as a new
+/// version derived from the one read. A failure to register fails the run.
+pub fn run_pipeline_on_dataset(
+    spec: &PipelineSpec,
+    registry: &DatasetRegistry,
+    dataset_id: &Uuid,
+    options: &DatasetRunOptions,
+) -> Result<DatasetRunReport> {
+    let started_at = chrono::Utc::now();
+    let dataset = registry.get_dataset(dataset_id)?;
+    let mut run = DatasetRun {
+        dataset_id: *dataset_id,
+        version_id: dataset.active_version_id,
+        output_version_id: None,
+    };
+    // Names the input in the history and checkpoint keys. Versions never
+    // change, so a checkpoint keyed on the version id stays valid.
+    let input_path = PathBuf::from(format!("{}@{}", dataset.name, run.version_id));
+    let mut resolved = spec.clone();
+    resolved
+        .params
+        .entry("input_stem".to_owned())
+        .or_insert_with(|| dataset.name.clone());
+
+    let run_id = Uuid::new_v4().to_string();
+    let result = dataset
+        .get_active_data()
+        .context("Failed to load the active version")
+        .and_then(|lf| {
+            execute_pipeline_on(
+                &resolved,
+                &input_path,
+                lf,
+                &options.source_paths,
+                options.output_path_override.as_deref(),
+                &PathPolicy::load(),
+                &mut |_| {},
+            )
+        });
+    let result = match (result, options.register_as) {
+        (Ok(report), Some(stage)) => {
+            register_output(registry, &run, &report, stage, &spec.name, &run_id).map(|id| {
+                run.output_version_id = Some(id);
+                report
+            })
+        }
+        (result, _) => result,
+    };
+
+    let mut record = RunRecord::new(spec, &input_path, started_at, &result);
+    record.id.clone_from(&run_id);
+    record.dataset = Some(run);
+    if let Err(e) = HistoryStore::open_default().append(&record) {
+        crate::config::log_event("Pipeline", &format!("Failed to record run history: {e}"));
+    }
+
+    Ok(DatasetRunReport {
+        run_id,
+        dataset: run,
+        report: result?,
+    })
+}
+
+/// Store the output of a run as a new version of the dataset it read
+fn register_output(
+    registry: &DatasetRegistry,
+    run: &DatasetRun,
+    report: &RunReport,
+    stage: LifecycleStage,
+    pipeline: &str,
+    run_id: &str,
+) -> Result<Uuid> {
+    let lf = load_df_lazy(&report.output_path).context("Failed to read the pipeline output")?;
+    let mut metadata = VersionMetadata {
+        description: format!("Pipeline: {pipeline}"),
+        row_count: Some(report.rows_after),
+        column_count: Some(report.columns_after),
+        file_size_bytes: std::fs::metadata(&report.output_path).ok().map(|m| m.len()),
+        created_by: "pipeline".to_owned(),
+        ..Default::default()
+    };
+    metadata
+        .custom_fields
+        .insert(PIPELINE_RUN_FIELD.to_owned(), run_id.into());
+
+    let version_id = registry
+        .add_data_version(&run.dataset_id, &run.version_id, &lf, stage, metadata)
+        .context("Failed to register the output as a dataset version")?;
+    crate::config::log_event(
+        "Lifecycle",
+        &format!(
+            "Pipeline '{pipeline}' created {} version {version_id} of dataset {}",
+            stage.as_str(),
+            run.dataset_id
+        ),
+    );
+    Ok(version_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::spec::Step;
+
+    #[test]
+    fn test_run_pipeline_on_dataset_registers_version() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let input = dir.path().join("sales.csv");
+        std::fs::write(&input, "id,region,amount\n1,N,10\n2,S,20\n")?;
+        let registry = DatasetRegistry::new(dir.path().join("datasets"))?;
+        let dataset_id = registry.create_dataset("sales".to_owned(), input)?;
+        let raw_id = registry.get_dataset(&dataset_id)?.active_version_id;
+
+        let mut spec = PipelineSpec::new("drop region");
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["region".to_owned()],
+            }
+            .into(),
+        );
+        let options = DatasetRunOptions {
+            output_path_override: Some(dir.path().join("out.parquet")),
+            register_as: Some(LifecycleStage::Cleaned),
+            ..Default::default()
+        };
+        let run = run_pipeline_on_dataset(&spec, &registry, &dataset_id, &options)?;
+
+        assert_eq!(run.dataset.version_id, raw_id);
+        let new_id = run.dataset.output_version_id.expect("registered version");
+        let dataset = registry.get_dataset(&dataset_id)?;
+        assert_eq!(dataset.active_version_id, new_id);
+        let version = dataset.get_version(&new_id)?;
+        assert_eq!(version.parent_id, Some(raw_id));
+        assert_eq!(version.stage, LifecycleStage::Cleaned);
+        assert!(
+            version
+                .metadata
+                .custom_fields
+                .contains_key(PIPELINE_RUN_FIELD)
+        );
+
+        let df = registry.get_active_data(&dataset_id)?.collect()?;
+        assert_eq!(df.width(), 2);
+        assert_eq!(df.height(), 2);
+        Ok(())
+    }
+}
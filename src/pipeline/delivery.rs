@@ -0,0 +1,413 @@
+//! Delivering pipeline outputs to network shares and SFTP servers.
+//!
+//! Each file is copied under a temporary `.part` name and renamed into place
+//! once complete, so consumers polling the destination never see a partial
+//! file. The copy is then verified by size or SHA-256 hash. Transient
+//! failures are retried with the backoff and jitter of the app's
+//! [`RetryPolicy`], using each target's own retry count and first delay.
+//!
+//! SFTP targets come from pipeline files, which are shared, so their fields
+//! are checked by [`check_sftp_value`] before `sftp` is run: a value starting
+//! with `-` would be read as an option, and a control character would end a
+//! batch command early and start another.
+
+use super::spec::{DeliveryDestination, DeliveryTarget, DeliveryVerify};
+use crate::integrity::compute_file_hash;
+use crate::utils::TempFileGuard;
+use crate::utils::retry::{RetryPolicy, transient};
+use anyhow::{Context as _, Result, anyhow, bail};
+use serde::Serialize;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use uuid::Uuid;
+
+/// Outcome of delivering one file to one target
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryStatus {
+    /// Local file that was delivered
+    pub file: PathBuf,
+    /// Where it was delivered, e.g. `sftp://user@host/dir/file.csv`
+    pub destination: String,
+    /// Attempts made, including the successful one
+    pub attempts: u32,
+    /// The delivered copy passed verification (false when verification is off)
+    pub verified: bool,
+    /// Error from the last attempt if every attempt failed
+    pub error: Option<String>,
+}
+
+impl DeliveryStatus {
+    pub fn delivered(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Copy every file in `files` to every target, retrying transient failures.
+/// Errors are recorded in the returned statuses rather than returned.
+pub fn deliver_outputs(files: &[PathBuf], targets: &[DeliveryTarget]) -> Vec<DeliveryStatus> {
+    let base = RetryPolicy::from_settings();
+    let mut statuses = Vec::with_capacity(files.len() * targets.len());
+    for target in targets {
+        let policy = RetryPolicy {
+            max_attempts: target.retries.saturating_add(1),
+            initial_delay_ms: target.retry_delay_secs.saturating_mul(1000),
+            ..base.clone()
+        };
+        for file in files {
+            statuses.push(deliver_file(file, target, &policy));
+        }
+    }
+    statuses
+}
+
+fn deliver_file(file: &Path, target: &DeliveryTarget, policy: &RetryPolicy) -> DeliveryStatus {
+    let mut status = DeliveryStatus {
+        file: file.to_path_buf(),
+        destination: destination_display(file, &target.destination),
+        attempts: 0,
+        verified: false,
+        error: None,
+    };
+
+    let what = format!("Delivering {} to {}", file.display(), status.destination);
+    let result = policy.retry(&what, || {
+        status.attempts += 1;
+        deliver_once(file, target)
+    });
+    match result {
+        Ok(()) => {
+            status.verified = target.verify != DeliveryVerify::None;
+            crate::config::log_event(
+                "Delivery",
+                &format!("Delivered {} to {}", file.display(), status.destination),
+            );
+        }
+        Err(e) => {
+            crate::config::log_event("Delivery", &format!("{e:#}"));
+            status.error = Some(format!("{e:#}"));
+        }
+    }
+
+    status
+}
+
+fn deliver_once(file: &Path, target: &DeliveryTarget) -> Result<()> {
+    let name = file_name(file)?;
+    match &target.destination {
+        DeliveryDestination::Path { dir } => copy_to_dir(file, Path::new(dir), name, target.verify),
+        DeliveryDestination::Sftp {
+            host,
+            port,
+            user,
+            remote_dir,
+            identity_file,
+        } => {
+            check_sftp_value("host", host)?;
+            check_sftp_value("user", user)?;
+            check_sftp_value("remote_dir", remote_dir)?;
+            if let Some(identity) = identity_file {
+                check_sftp_value("identity_file", identity)?;
+            }
+            let sftp = Sftp {
+                host,
+                port: *port,
+                user,
+                identity_file: identity_file.as_deref(),
+            };
+            let remote = remote_path(remote_dir, name);
+            sftp.upload(file, &remote)?;
+            match target.verify {
+                DeliveryVerify::None => Ok(()),
+                DeliveryVerify::Size => {
+                    let expected = std::fs::metadata(file)?.len();
+                    let actual = sftp.remote_size(&remote)?;
+                    check_size(expected, actual)
+                }
+                DeliveryVerify::Hash => sftp.verify_hash(file, &remote),
+            }
+        }
+    }
+}
+
+fn copy_to_dir(file: &Path, dir: &Path, name: &str, verify: DeliveryVerify) -> Result<()> {
+    if !dir.is_dir() {
+        // Usually a network share that is briefly unreachable
+        return Err(transient(format!(
+            "Destination folder not found: {}",
+            dir.display()
+        )));
+    }
+    let dest = dir.join(name);
+    let partial = dir.join(format!("{name}.part"));
+
+    std::fs::copy(file, &partial)
+        .with_context(|| format!("Failed to copy to {}", partial.display()))?;
+    if let Err(e) = std::fs::rename(&partial, &dest) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e).with_context(|| format!("Failed to rename into {}", dest.display()));
+    }
+
+    match verify {
+        DeliveryVerify::None => Ok(()),
+        DeliveryVerify::Size => check_size(
+            std::fs::metadata(file)?.len(),
+            std::fs::metadata(&dest)?.len(),
+        ),
+        DeliveryVerify::Hash => check_hash(&compute_file_hash(file)?, &compute_file_hash(&dest)?),
+    }
+}
+
+fn check_size(expected: u64, actual: u64) -> Result<()> {
+    // A mismatch means the transfer was damaged, so another attempt may succeed
+    if expected != actual {
+        return Err(transient(format!(
+            "Size mismatch after delivery: expected {expected} bytes, found {actual}"
+        )));
+    }
+    Ok(())
+}
+
+fn check_hash(expected: &str, actual: &str) -> Result<()> {
+    if expected != actual {
+        return Err(transient(format!(
+            "SHA-256 mismatch after delivery: expected {expected}, found {actual}"
+        )));
+    }
+    Ok(())
+}
+
+fn file_name(file: &Path) -> Result<&str> {
+    file.file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("Cannot deliver {}: invalid file name", file.display()))
+}
+
+fn remote_path(remote_dir: &str, name: &str) -> String {
+    let dir = remote_dir.trim_end_matches('/');
+    if dir.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{dir}/{name}")
+    }
+}
+
+fn destination_display(file: &Path, destination: &DeliveryDestination) -> String {
+    let name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match destination {
+        DeliveryDestination::Path { dir } => Path::new(dir).join(name).display().to_string(),
+        DeliveryDestination::Sftp {
+            host,
+            port,
+            user,
+            remote_dir,
+            ..
+        } => {
+            let port = port.map(|p| format!(":{p}")).unwrap_or_default();
+            let path = remote_path(remote_dir, &name);
+            let sep = if path.starts_with('/') { "" } else { "/" };
+            format!("sftp://{user}@{host}{port}{sep}{path}")
+        }
+    }
+}
+
+/// Check that `value`, the SFTP target's `field`, can be passed to `sftp`
+/// as it is
+pub fn check_sftp_value(field: &str, value: &str) -> Result<()> {
+    if value.starts_with('-') {
+        bail!("SFTP {field} '{value}' must not start with '-'");
+    }
+    if value.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        bail!("SFTP {field} {value:?} must not contain spaces or control characters");
+    }
+    Ok(())
+}
+
+/// Quote a path for an `sftp` batch command. Control characters are
+/// refused, as a line break would start another command.
+fn batch_quote(path: &str) -> Result<String> {
+    if path.chars().any(char::is_control) {
+        bail!("Cannot deliver to or from {path:?}: it contains control characters");
+    }
+    Ok(format!(
+        "\"{}\"",
+        path.replace('\\', "\\\\").replace('"', "\\\"")
+    ))
+}
+
+/// Connection details for the system `sftp` client, run in batch mode
+struct Sftp<'a> {
+    host: &'a str,
+    port: Option<u16>,
+    user: &'a str,
+    identity_file: Option<&'a str>,
+}
+
+impl Sftp<'_> {
+    /// Upload `file` to `remote` via a `.part` name, replacing any existing file
+    fn upload(&self, file: &Path, remote: &str) -> Result<()> {
+        let partial = format!("{remote}.part");
+        let (local, partial, remote) = (
+            batch_quote(&file.to_string_lossy())?,
+            batch_quote(&partial)?,
+            batch_quote(remote)?,
+        );
+        // A leading '-' lets the batch continue when the file does not exist yet
+        let batch = format!("put {local} {partial}\n-rm {remote}\nrename {partial} {remote}\n");
+        self.run(&batch).map(drop)
+    }
+
+    fn remote_size(&self, remote: &str) -> Result<u64> {
+        let listing = self.run(&format!("ls -ln {}\n", batch_quote(remote)?))?;
+        // `ls -ln` lines look like: -rw-r--r-- 1 1000 1000 1234 Jan 1 00:00 name
+        listing
+            .lines()
+            .filter(|line| line.starts_with('-'))
+            .find_map(|line| line.split_whitespace().nth(4)?.parse().ok())
+            .ok_or_else(|| anyhow!("Could not read the size of {remote} from the server"))
+    }
+
+    /// Download the delivered file again and compare hashes
+    fn verify_hash(&self, file: &Path, remote: &str) -> Result<()> {
+        let check = TempFileGuard::new(
+            std::env::temp_dir().join(format!("beefcake_delivery_check_{}", Uuid::new_v4())),
+        );
+        let check_path = check.path().unwrap_or_else(|| Path::new(""));
+        self.run(&format!(
+            "get {} {}\n",
+            batch_quote(remote)?,
+            batch_quote(&check_path.to_string_lossy())?
+        ))?;
+        check_hash(&compute_file_hash(file)?, &compute_file_hash(check_path)?)
+    }
+
+    /// Run `batch` commands, returning stdout
+    fn run(&self, batch: &str) -> Result<String> {
+        let mut cmd = Command::new("sftp");
+        cmd.args(["-b", "-", "-o", "BatchMode=yes"]);
+        if let Some(port) = self.port {
+            cmd.arg("-P").arg(port.to_string());
+        }
+        if let Some(identity) = self.identity_file {
+            cmd.arg("-i").arg(identity);
+        }
+        // Nothing after `--` is read as an option
+        cmd.arg("--").arg(format!("{}@{}", self.user, self.host));
+
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to start sftp (is the OpenSSH client installed?)")?;
+        child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Failed to open stdin for sftp"))?
+            .write_all(batch.as_bytes())
+            .context("Failed to send commands to sftp")?;
+
+        let output = child
+            .wait_with_output()
+            .context("Failed to wait for sftp")?;
+        if !output.status.success() {
+            bail!(
+                "sftp to {} failed ({}): {}",
+                self.host,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_target(dir: &Path, retries: u32) -> DeliveryTarget {
+        DeliveryTarget {
+            destination: DeliveryDestination::Path {
+                dir: dir.to_string_lossy().into_owned(),
+            },
+            retries,
+            retry_delay_secs: 0,
+            verify: DeliveryVerify::Hash,
+        }
+    }
+
+    #[test]
+    fn test_deliver_to_folder_verifies_and_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.csv");
+        std::fs::write(&file, "id\n1\n").unwrap();
+        let share = dir.path().join("share");
+        std::fs::create_dir(&share).unwrap();
+
+        let statuses = deliver_outputs(&[file.clone()], &[path_target(&share, 2)]);
+        assert_eq!(statuses.len(), 1);
+        assert!(statuses[0].delivered());
+        assert!(statuses[0].verified);
+        assert_eq!(statuses[0].attempts, 1);
+        assert_eq!(std::fs::read(share.join("out.csv")).unwrap(), b"id\n1\n");
+        assert!(!share.join("out.csv.part").exists());
+
+        let missing = dir.path().join("missing");
+        let statuses = deliver_outputs(&[file], &[path_target(&missing, 2)]);
+        assert!(!statuses[0].delivered());
+        assert_eq!(statuses[0].attempts, 3);
+        assert!(statuses[0].error.as_deref().unwrap().contains("not found"));
+    }
+
+    #[test]
+    fn test_sftp_destination_display() {
+        let destination = DeliveryDestination::Sftp {
+            host: "files.example.com".to_owned(),
+            port: Some(2222),
+            user: "etl".to_owned(),
+            remote_dir: "/inbound/".to_owned(),
+            identity_file: None,
+        };
+        assert_eq!(
+            destination_display(Path::new("/tmp/out.csv"), &destination),
+            "sftp://etl@files.example.com:2222/inbound/out.csv"
+        );
+        assert_eq!(batch_quote(r#"a "b"\c"#).unwrap(), r#""a \"b\"\\c""#);
+    }
+
+    #[test]
+    fn test_sftp_values_that_could_run_commands_are_rejected() {
+        assert!(check_sftp_value("host", "files.example.com").is_ok());
+        assert!(check_sftp_value("remote_dir", "/inbound/2024").is_ok());
+        assert!(check_sftp_value("user", "-oProxyCommand=touch /tmp/x").is_err());
+        assert!(check_sftp_value("host", "-J").is_err());
+        assert!(check_sftp_value("host", "files example.com").is_err());
+        assert!(check_sftp_value("remote_dir", "/inbound\nrm out.csv").is_err());
+        assert!(check_sftp_value("identity_file", "id\trsa").is_err());
+        assert!(check_sftp_value("identity_file", "-i").is_err());
+        assert!(batch_quote("out\n!touch /tmp/x.csv").is_err());
+        assert!(batch_quote("out\r.csv").is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("out.csv");
+        std::fs::write(&file, "id\n1\n").unwrap();
+        let target = DeliveryTarget {
+            destination: DeliveryDestination::Sftp {
+                host: "files.example.com".to_owned(),
+                port: None,
+                user: "-oProxyCommand=touch /tmp/x".to_owned(),
+                remote_dir: "/inbound".to_owned(),
+                identity_file: None,
+            },
+            retries: 0,
+            retry_delay_secs: 0,
+            verify: DeliveryVerify::None,
+        };
+        let error = deliver_once(&file, &target).unwrap_err().to_string();
+        assert!(error.contains("must not start with '-'"), "{error}");
+    }
+}
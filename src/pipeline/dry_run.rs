@@ -0,0 +1,200 @@
+//! Dry runs: propagate the schema through every step without reading rows.
+//!
+//! Each step is applied to the lazy plan and only its output schema is
+//! resolved, so the builder can preview the columns after every step and see
+//! which steps would fail, without the cost of executing the pipeline.
+
+use super::contract::{schema_violations, violation_report};
+use super::executor::{apply_step_with_inputs, load_input, rename_input_columns, step_op};
+use super::spec::{PipelineSpec, Step};
+use super::template::{TemplateVars, resolve_spec};
+use crate::analyser::logic::load_df_lazy;
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A column name and its data type, as shown in previews
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ColumnPreview {
+    pub name: String,
+    pub dtype: String,
+}
+
+/// Schema after one step of a dry run
+#[derive(Debug, Clone, Serialize)]
+pub struct StepPreview {
+    pub step_index: usize,
+    /// The step's `op` tag, e.g. `drop_columns`
+    pub op: String,
+    /// Columns after this step (unchanged from the previous step on error)
+    pub columns: Vec<ColumnPreview>,
+    /// Why the step would fail; the run would skip it
+    pub error: Option<String>,
+    /// The real output columns depend on the data (e.g. pivot, one-hot
+    /// encode), so `columns` is only what an empty input would produce
+    pub data_dependent: bool,
+}
+
+/// Result of [`run_pipeline_dry`]
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRunReport {
+    pub input_columns: Vec<ColumnPreview>,
+    pub steps: Vec<StepPreview>,
+}
+
+impl DryRunReport {
+    /// Steps that would fail during a real run
+    pub fn failed_steps(&self) -> impl Iterator<Item = &StepPreview> {
+        self.steps.iter().filter(|s| s.error.is_some())
+    }
+
+    /// Columns produced by the last step (or the input if there are none)
+    pub fn output_columns(&self) -> &[ColumnPreview] {
+        self.steps
+            .last()
+            .map_or(&self.input_columns, |s| &s.columns)
+    }
+}
+
+/// Propagate the schema of `input_path` through every step of `spec`
+/// without collecting data. External commands are not run and leave the
+/// schema unchanged.
+pub fn run_pipeline_dry(spec: &PipelineSpec, input_path: impl AsRef<Path>) -> Result<DryRunReport> {
+    let vars = TemplateVars::new(input_path.as_ref(), &spec.params);
+    let spec = &resolve_spec(spec, &vars)?;
+
+    let lf = load_input(&spec.input, input_path.as_ref()).context("Failed to load input file")?;
+    let mut lf = rename_input_columns(lf, &spec.input.column_renames)?;
+    let input_columns = preview_columns(&mut lf)?;
+
+    // Named inputs are scanned lazily; missing ones surface as step errors
+    let mut sources = HashMap::new();
+    for source in spec.input.sources.iter().filter(|s| !s.path.is_empty()) {
+        if let Ok(source_lf) = load_df_lazy(Path::new(&source.path)) {
+            sources.insert(source.name.clone(), source_lf);
+        }
+    }
+
+    let mut steps = Vec::with_capacity(spec.steps.len());
+    for (idx, step) in spec.steps.iter().map(|s| &s.step).enumerate() {
+        let data_dependent = matches!(
+            step,
+            Step::OneHotEncode { .. }
+                | Step::EncodeCategories { .. }
+                | Step::Bin { .. }
+                | Step::VectoriseText { .. }
+                | Step::Pivot { .. }
+                | Step::Transpose { .. }
+        );
+        let mut error = None;
+
+        match step {
+            // External commands are not simulated because they run code
+            Step::External { .. } => {}
+            // Only the schema half of a contract can be checked without rows
+            Step::ExpectSchema { columns, .. } => match schema_violations(&lf, columns, false) {
+                Ok(violations) if violations.is_empty() => {}
+                Ok(violations) => error = Some(violation_report(&violations)),
+                Err(e) => error = Some(e.to_string()),
+            },
+            _ => {
+                // Data-dependent steps read unique values, so give them no rows
+                let step_input = if data_dependent {
+                    lf.clone().limit(0)
+                } else {
+                    lf.clone()
+                };
+                match apply_step_with_inputs(step, step_input, &sources)
+                    .and_then(|mut next| preview_columns(&mut next).map(|_| next))
+                {
+                    Ok(next) => lf = next,
+                    Err(e) => error = Some(e.to_string()),
+                }
+            }
+        }
+
+        steps.push(StepPreview {
+            step_index: idx,
+            op: step_op(step),
+            columns: preview_columns(&mut lf)?,
+            error,
+            data_dependent,
+        });
+    }
+
+    Ok(DryRunReport {
+        input_columns,
+        steps,
+    })
+}
+
+fn preview_columns(lf: &mut LazyFrame) -> Result<Vec<ColumnPreview>> {
+    let schema = lf
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!("Failed to resolve schema: {e}"))?;
+    Ok(schema
+        .iter()
+        .map(|(name, dtype)| ColumnPreview {
+            name: name.to_string(),
+            dtype: dtype.to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_pipeline_dry_previews_each_step() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        std::fs::write(&input, "id,name,amount\n1,a,10\n2,b,20\n").unwrap();
+
+        let mut spec = PipelineSpec::new("dry");
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["name".to_owned()],
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::CastTypes {
+                columns: HashMap::from([("amount".to_owned(), "bogus".to_owned())]),
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::WithColumn {
+                name: "double".to_owned(),
+                expression: "amount * 2".to_owned(),
+            }
+            .into(),
+        );
+
+        let report = run_pipeline_dry(&spec, &input).unwrap();
+        assert_eq!(report.input_columns.len(), 3);
+        assert_eq!(report.steps.len(), 3);
+
+        assert_eq!(report.steps[0].op, "drop_columns");
+        let after_drop: Vec<&str> = report.steps[0]
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(after_drop, ["id", "amount"]);
+
+        assert!(report.steps[1].error.is_some());
+        assert_eq!(report.steps[1].columns, report.steps[0].columns);
+
+        let names: Vec<&str> = report
+            .output_columns()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, ["id", "amount", "double"]);
+        assert_eq!(report.failed_steps().count(), 1);
+    }
+}
@@ -3,16 +3,36 @@
 //! Executes pipeline specs against input data, applying transformations sequentially
 //! and generating detailed run reports.
 
-use super::spec::{ImputeStrategy, NormalisationMethod, OutputConfig, PipelineSpec, Step};
-use super::validation::validate_pipeline;
-use crate::analyser::logic::{get_parquet_write_options, load_df_lazy};
+use super::checkpoint::Checkpoints;
+use super::contract::{ContractViolations, schema_violations};
+use super::delivery::{DeliveryStatus, deliver_outputs};
+use super::expression::compile;
+use super::external::run_external;
+use super::history::{HistoryStore, RunRecord};
+use super::spec::{
+    AggregateFunction, DeliveryDestination, ErrorPolicy, ImputeStrategy, InputConfig,
+    NormalisationMethod, OutputConfig, PipelineSpec, Step, ViolationAction,
+};
+use super::template::{TemplateVars, resolve_spec};
+use super::validation::{ValidationError, ValidationFailed, validate_pipeline_with_inputs};
+use crate::analyser::logic::ml::{SplitMix64, vectorise_text};
+use crate::analyser::logic::{
+    CategoryEncoding, ColumnMerge, ColumnSplit, RejectCondition, TRANSPOSE_MAX_COLUMNS,
+    TextVectoriser, append_completeness_score, bin_expr, date_part_exprs, encode_column,
+    get_parquet_write_options, load_df_lazy, load_df_lazy_as, map_values, merge_expr,
+    parse_dates_expr, split_exprs, split_rejects, template_columns, transpose_df, union_rejects,
+};
+use crate::config::policy::{Capability, PathPolicy};
+use crate::utils::runs::{Run, RunKind};
 use anyhow::{Context as _, Result};
 use chrono::Local;
 use polars::prelude::*;
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 const DEFAULT_ONE_HOT_MAX_UNIQUE: usize = 200;
+const PIVOT_MAX_COLUMNS: usize = 500;
 const ONE_HOT_VALUE_MAX_LEN: usize = 32;
 
 fn one_hot_max_unique() -> usize {
@@ -37,7 +57,7 @@ fn sanitize_one_hot_value(value: &str) -> String {
 }
 
 /// Report generated after pipeline execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RunReport {
     /// Number of rows before processing
     pub rows_before: usize,
@@ -54,14 +74,111 @@ pub struct RunReport {
     /// Number of steps successfully applied
     pub steps_applied: usize,
 
+    /// Number of rows moved to the reject file
+    pub rows_rejected: usize,
+
+    /// Where the final output was written
+    pub output_path: PathBuf,
+
+    /// Where rejected rows were written, if any were
+    pub reject_path: Option<PathBuf>,
+
+    /// Files written by `write_output` steps, in step order
+    pub extra_outputs: Vec<PathBuf>,
+
+    /// Index of the checkpointed step this run resumed after, if it did
+    pub resumed_after_step: Option<usize>,
+
     /// Warnings generated during execution
     pub warnings: Vec<String>,
 
+    /// Result of each `output.deliver` copy, per file and target
+    pub deliveries: Vec<DeliveryStatus>,
+
+    /// Per-step metrics, in step order
+    pub steps: Vec<StepMetrics>,
+
     /// Time taken for execution
+    #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
     pub duration: std::time::Duration,
 }
 
+/// Outcome of a single step during a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Applied,
+    /// The step failed and the data was left as it was before it
+    Skipped,
+}
+
+/// Metrics recorded for one step of a run. Counts are `None` where they
+/// were not taken: only checkpointed steps are counted unless the spec sets
+/// `output.step_metrics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepMetrics {
+    pub step_index: usize,
+    /// The step's `op` tag, e.g. `cast_types`
+    pub op: String,
+    pub status: StepStatus,
+    pub rows_before: Option<usize>,
+    pub rows_after: Option<usize>,
+    /// Rows moved to the reject file by this step
+    pub rows_rejected: Option<usize>,
+    /// Null cells across all columns before and after the step
+    pub nulls_before: Option<usize>,
+    pub nulls_after: Option<usize>,
+    /// Time to apply the step and evaluate its result
+    #[serde(rename = "duration_secs", serialize_with = "serialize_secs")]
+    pub duration: std::time::Duration,
+}
+
+impl StepMetrics {
+    /// Rows added or removed by the step
+    pub fn rows_affected(&self) -> Option<usize> {
+        Some(self.rows_before?.abs_diff(self.rows_after?))
+    }
+
+    /// Null cells the step created (e.g. failed casts)
+    pub fn nulls_introduced(&self) -> Option<usize> {
+        Some(self.nulls_after?.saturating_sub(self.nulls_before?))
+    }
+
+    /// Null cells the step filled or dropped (e.g. imputation)
+    pub fn nulls_removed(&self) -> Option<usize> {
+        Some(self.nulls_before?.saturating_sub(self.nulls_after?))
+    }
+}
+
+/// Where a run has got to, as passed to the progress callback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum RunProgress {
+    /// Step `step_index` (zero-based) of `steps` is starting, on `rows`
+    /// rows when they were counted (see [`StepMetrics`])
+    Step {
+        step_index: usize,
+        steps: usize,
+        op: String,
+        rows: Option<usize>,
+    },
+    /// Every step has run; `rows` rows are being written
+    Writing { rows: usize },
+}
+
+fn serialize_secs<S: serde::Serializer>(
+    duration: &std::time::Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
 impl RunReport {
+    /// Serialize the report, including per-step metrics, as pretty JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize run report")
+    }
+
     /// Create a summary message
     pub fn summary(&self) -> String {
         format!(
@@ -95,12 +212,185 @@ pub fn run_pipeline(
     spec: &PipelineSpec,
     input_path: impl AsRef<Path>,
     output_path_override: Option<impl AsRef<Path>>,
+) -> Result<RunReport> {
+    run_pipeline_with_inputs(spec, input_path, &HashMap::new(), output_path_override)
+}
+
+/// Execute a pipeline spec with named secondary inputs. Paths in
+/// `source_paths` override the paths declared in `spec.input.sources`.
+/// Template variables are resolved from `spec.params`; set run-time
+/// values there before calling.
+///
+/// Every run, successful or not, is appended to the run history (see
+/// [`super::history`]).
+pub fn run_pipeline_with_inputs(
+    spec: &PipelineSpec,
+    input_path: impl AsRef<Path>,
+    source_paths: &HashMap<String, PathBuf>,
+    output_path_override: Option<impl AsRef<Path>>,
+) -> Result<RunReport> {
+    run_pipeline_with_progress(spec, input_path, source_paths, output_path_override, |_| {})
+}
+
+/// [`run_pipeline_with_inputs`], calling `on_progress` as each step starts
+/// and before the output is written. The run stops between steps, with an
+/// error, once the user aborts processing (see [`crate::utils::is_aborted`]).
+/// Everything logged during the run carries its run ID (see
+/// [`crate::utils::runs`]).
+///
+/// Files the run reads or writes are checked against the saved path policy
+/// (see [`crate::config::policy`]); a deny rule on any of them fails the run.
+pub fn run_pipeline_with_progress(
+    spec: &PipelineSpec,
+    input_path: impl AsRef<Path>,
+    source_paths: &HashMap<String, PathBuf>,
+    output_path_override: Option<impl AsRef<Path>>,
+    mut on_progress: impl FnMut(&RunProgress),
+) -> Result<RunReport> {
+    let run = Run::start(RunKind::Pipeline, &spec.name);
+    let started_at = chrono::Utc::now();
+    let policy = PathPolicy::load();
+    let result = run.in_scope(|| {
+        execute_pipeline(
+            spec,
+            input_path.as_ref(),
+            source_paths,
+            output_path_override.as_ref().map(AsRef::as_ref),
+            &policy,
+            &mut on_progress,
+        )
+    });
+
+    let record = RunRecord::new(spec, input_path.as_ref(), started_at, &result);
+    if let Err(e) = HistoryStore::open_default().append(&record) {
+        crate::config::log_event("Pipeline", &format!("Failed to record run history: {e}"));
+    }
+    run.finish(&result);
+
+    result
+}
+
+/// Scan the main input of a pipeline, reading a CSV file without a header
+/// row by position when `input.has_header` is off
+pub fn load_input(input: &InputConfig, input_path: &Path) -> Result<LazyFrame> {
+    load_df_lazy_as(
+        input_path,
+        (!input.has_header).then_some(&input.header_names),
+    )
+}
+
+/// `lf`, the main input, with its columns renamed by `input.column_renames`
+pub fn rename_input_columns(lf: LazyFrame, renames: &HashMap<String, String>) -> Result<LazyFrame> {
+    if renames.is_empty() {
+        return Ok(lf);
+    }
+    apply_step(
+        &Step::RenameColumns {
+            mapping: renames.clone(),
+        },
+        lf,
+    )
+}
+
+/// Validate `spec` against its inputs as a run would load them, without
+/// running any step. Paths in `source_paths` override the paths declared in
+/// `spec.input.sources`.
+pub fn validate_pipeline_for_input(
+    spec: &PipelineSpec,
+    input_path: impl AsRef<Path>,
+    source_paths: &HashMap<String, PathBuf>,
+) -> Result<Vec<ValidationError>> {
+    let input_path = input_path.as_ref();
+    let policy = PathPolicy::load();
+    let vars = TemplateVars::new(input_path, &spec.params);
+    let spec = &resolve_spec(spec, &vars)?;
+    policy.ensure_not_denied(input_path, Capability::Read)?;
+    let mut input_lf = load_input(&spec.input, input_path).context("Failed to load input file")?;
+    let input_schema = input_lf
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!("Failed to collect input schema: {e}"))?;
+    let sources = load_sources(spec, source_paths, &policy)?;
+    validate_pipeline_with_inputs(spec, &input_schema, &sources.schemas)
+}
+
+/// The named inputs of a pipeline, scanned
+struct LoadedSources {
+    frames: HashMap<String, LazyFrame>,
+    schemas: HashMap<String, Schema>,
+    paths: Vec<PathBuf>,
+}
+
+/// Scan the named inputs of `spec`, from `source_paths` or the spec's paths
+fn load_sources(
+    spec: &PipelineSpec,
+    source_paths: &HashMap<String, PathBuf>,
+    policy: &PathPolicy,
+) -> Result<LoadedSources> {
+    let mut loaded = LoadedSources {
+        frames: HashMap::new(),
+        schemas: HashMap::new(),
+        paths: Vec::new(),
+    };
+    for source in &spec.input.sources {
+        let path = match source_paths.get(&source.name) {
+            Some(path) => path.clone(),
+            None if !source.path.is_empty() => expand_path_template(&source.path),
+            None => anyhow::bail!("No path given for input '{}'", source.name),
+        };
+        policy.ensure_not_denied(&path, Capability::Read)?;
+        let mut lf = load_df_lazy(&path)
+            .with_context(|| format!("Failed to load input '{}'", source.name))?;
+        let schema = lf
+            .collect_schema()
+            .map_err(|e| anyhow::anyhow!("Failed to collect schema of '{}': {e}", source.name))?;
+        loaded
+            .schemas
+            .insert(source.name.clone(), schema.as_ref().clone());
+        loaded.frames.insert(source.name.clone(), lf);
+        loaded.paths.push(path);
+    }
+    Ok(loaded)
+}
+
+fn execute_pipeline(
+    spec: &PipelineSpec,
+    input_path: &Path,
+    source_paths: &HashMap<String, PathBuf>,
+    output_path_override: Option<&Path>,
+    policy: &PathPolicy,
+    on_progress: &mut dyn FnMut(&RunProgress),
+) -> Result<RunReport> {
+    policy.ensure_not_denied(input_path, Capability::Read)?;
+    let input_lf = load_input(&spec.input, input_path).context("Failed to load input file")?;
+    execute_pipeline_on(
+        spec,
+        input_path,
+        input_lf,
+        source_paths,
+        output_path_override,
+        policy,
+        on_progress,
+    )
+}
+
+/// Run `spec` on data already loaded. `input_path` identifies the input for
+/// template variables and checkpoints; it need not exist. Every other file
+/// the run reads or writes is checked against `policy` first.
+pub(super) fn execute_pipeline_on(
+    spec: &PipelineSpec,
+    input_path: &Path,
+    mut input_lf: LazyFrame,
+    source_paths: &HashMap<String, PathBuf>,
+    output_path_override: Option<&Path>,
+    policy: &PathPolicy,
+    on_progress: &mut dyn FnMut(&RunProgress),
 ) -> Result<RunReport> {
     let start = std::time::Instant::now();
     let mut warnings = Vec::new();
 
-    // Load input data
-    let mut input_lf = load_df_lazy(input_path.as_ref()).context("Failed to load input file")?;
+    // Fill in {date}, {input_stem}, {env:VAR} and params before anything reads the spec
+    let vars = TemplateVars::new(input_path, &spec.params);
+    let spec = &resolve_spec(spec, &vars)?;
 
     let input_schema = input_lf
         .collect_schema()
@@ -108,58 +398,234 @@ pub fn run_pipeline(
 
     let columns_before = input_schema.len();
 
+    let LoadedSources {
+        frames: sources,
+        schemas: source_schemas,
+        paths: source_files,
+    } = load_sources(spec, source_paths, policy)?;
+    let mut input_files = vec![input_path.to_path_buf()];
+    input_files.extend(source_files);
+
     // Validate pipeline
-    let validation_errors = validate_pipeline(spec, &input_schema)?;
+    let validation_errors = validate_pipeline_with_inputs(spec, &input_schema, &source_schemas)?;
     if !validation_errors.is_empty() {
+        return Err(ValidationFailed(validation_errors).into());
+    }
+
+    // Determine output path
+    let output_path = if let Some(override_path) = output_path_override {
+        override_path.to_path_buf()
+    } else if !spec.output.path_template.is_empty() {
+        expand_path_template(&spec.output.path_template)
+    } else {
         return Err(anyhow::anyhow!(
-            "Pipeline validation failed:\n{}",
-            validation_errors
-                .iter()
-                .map(ToString::to_string)
-                .collect::<Vec<_>>()
-                .join("\n")
+            "No output path specified (provide --output or set output.path_template in spec)"
         ));
-    }
+    };
 
-    // Count input rows (streaming)
-    let rows_before = count_rows(&input_lf)?;
+    // Count input rows, and nulls when every step is counted (streaming)
+    let track_steps = spec.output.step_metrics;
+    let mut current = if track_steps {
+        Some(frame_stats(&input_lf)?)
+    } else {
+        None
+    };
+    let rows_before = match current {
+        Some(stats) => stats.rows,
+        None => count_rows(&input_lf)?,
+    };
 
     // Apply transformations
-    let mut lf = input_lf;
+    let mut lf = rename_input_columns(input_lf, &spec.input.column_renames)?;
     let mut steps_applied = 0;
+    let collect_rejects = !spec.output.reject_path_template.is_empty();
+    let mut rejects = Vec::new();
+    let mut extra_outputs = Vec::new();
+    let mut step_metrics = Vec::with_capacity(spec.steps.len());
+
+    // Pick up from the latest checkpoint a previous run left behind
+    let input_refs: Vec<&Path> = input_files.iter().map(PathBuf::as_path).collect();
+    let checkpoints = Checkpoints::new(spec, &input_refs, &output_path);
+    check_write_paths(spec, &output_path, checkpoints.as_ref(), policy)?;
+    let mut resumed_after_step = None;
+    if let Some(resume) = checkpoints.as_ref().and_then(Checkpoints::resume) {
+        lf = resume.data;
+        rejects.extend(resume.rejects);
+        current = Some(frame_stats(&lf)?);
+        resumed_after_step = Some(resume.step_index);
+    }
+    let first_step = resumed_after_step.map_or(0, |idx| idx + 1);
+
+    for (idx, entry) in spec.steps.iter().enumerate().skip(first_step) {
+        let step = &entry.step;
+        crate::utils::check_aborted("Pipeline")
+            .with_context(|| format!("Stopped before step {}", idx + 1))?;
+        let op = step_op(step);
+        on_progress(&RunProgress::Step {
+            step_index: idx,
+            steps: spec.steps.len(),
+            op: op.clone(),
+            rows: current.map(|stats| stats.rows),
+        });
+        let step_start = std::time::Instant::now();
+        let mut metrics = StepMetrics {
+            step_index: idx,
+            op,
+            status: StepStatus::Applied,
+            rows_before: current.map(|stats| stats.rows),
+            rows_after: current.map(|stats| stats.rows),
+            rows_rejected: None,
+            nulls_before: current.map(|stats| stats.nulls),
+            nulls_after: current.map(|stats| stats.nulls),
+            duration: std::time::Duration::ZERO,
+        };
 
-    for (idx, step) in spec.steps.iter().enumerate() {
-        match apply_step(step, lf.clone()) {
-            Ok(new_lf) => {
+        if let Step::WriteOutput {
+            path_template,
+            format,
+        } = step
+        {
+            let path = expand_path_template(path_template);
+            let config = OutputConfig {
+                format: format.clone().unwrap_or_else(|| spec.output.format.clone()),
+                ..spec.output.clone()
+            };
+            write_output(lf.clone(), &path, &config)
+                .with_context(|| format!("Step {}: failed to write {}", idx + 1, path.display()))?;
+            extra_outputs.push(path);
+            steps_applied += 1;
+            metrics.duration = step_start.elapsed();
+            step_metrics.push(metrics);
+            continue;
+        }
+
+        // A contract that only warns records each mismatch and moves on;
+        // one that fails does so through the step's error policy below
+        if let Step::ExpectSchema {
+            columns,
+            on_violation: ViolationAction::Warn,
+        } = step
+        {
+            let violations = schema_violations(&lf, columns, true)
+                .with_context(|| format!("Step {}: failed to check the schema", idx + 1))?;
+            for violation in violations {
+                warnings.push(format!("Step {}: {violation}", idx + 1));
+            }
+            steps_applied += 1;
+            metrics.duration = step_start.elapsed();
+            step_metrics.push(metrics);
+            continue;
+        }
+
+        let policy = entry.on_error;
+        let mut step_input = lf.clone();
+        let mut step_rejects = None;
+        if collect_rejects || policy == ErrorPolicy::Quarantine {
+            let conditions = step_reject_conditions(step, idx, &mut lf)?;
+            if !conditions.is_empty() {
+                let (kept, rejected) = split_rejects(lf.clone(), &conditions);
+                step_input = kept;
+                step_rejects = Some(rejected);
+            }
+        }
+
+        let checkpoint = checkpoints.as_ref().filter(|c| c.covers(idx));
+        let counted = track_steps || checkpoint.is_some();
+
+        // Resolve the step's schema here so errors in the lazy plan are
+        // attributed to it. Its data is only read when it is checkpointed,
+        // writing the checkpoint first so the plan runs once, or counted.
+        let outcome = apply_step_with_inputs(step, step_input, &sources).and_then(|mut new_lf| {
+            new_lf.collect_schema().context("Failed to evaluate step")?;
+            let new_lf = match checkpoint {
+                Some(c) => c.save_data(idx, new_lf)?,
+                None => new_lf,
+            };
+            let stats = if counted {
+                Some(frame_stats(&new_lf)?)
+            } else {
+                None
+            };
+            Ok((stats, new_lf))
+        });
+        match outcome {
+            Ok((stats, new_lf)) => {
                 lf = new_lf;
+                current = stats;
                 steps_applied += 1;
+                if let Some(rejected) = step_rejects {
+                    if counted {
+                        metrics.rows_rejected = Some(count_rows(&rejected)?);
+                    }
+                    rejects.push(rejected);
+                }
+                if let Some(c) = checkpoint {
+                    let saved = c
+                        .commit(idx, union_rejects(std::mem::take(&mut rejects))?)
+                        .with_context(|| format!("Step {}: failed to checkpoint", idx + 1))?;
+                    rejects.extend(saved);
+                }
+            }
+            Err(e) if policy == ErrorPolicy::Fail => {
+                return Err(e.context(format!("Step {} failed", idx + 1)));
             }
             Err(e) => {
+                metrics.status = StepStatus::Skipped;
                 warnings.push(format!("Step {}: {} (skipped)", idx + 1, e));
             }
         }
+
+        metrics.rows_after = current.map(|stats| stats.rows);
+        metrics.nulls_after = current.map(|stats| stats.nulls);
+        metrics.duration = step_start.elapsed();
+        step_metrics.push(metrics);
     }
 
-    // Count output rows
-    let rows_after = count_rows(&lf)?;
+    let rows_after = match current {
+        Some(stats) => stats.rows,
+        None => count_rows(&lf)?,
+    };
     let output_schema = lf
         .collect_schema()
         .map_err(|e| anyhow::anyhow!("Failed to collect output schema: {e}"))?;
     let columns_after = output_schema.len();
 
-    // Determine output path
-    let output_path = if let Some(override_path) = output_path_override {
-        override_path.as_ref().to_path_buf()
-    } else if !spec.output.path_template.is_empty() {
-        expand_path_template(&spec.output.path_template)
+    // Write output
+    crate::utils::check_aborted("Pipeline")?;
+    on_progress(&RunProgress::Writing { rows: rows_after });
+    write_output(lf, &output_path, &spec.output)?;
+
+    let (rows_rejected, reject_path) = if let Some(rejects_lf) = union_rejects(rejects)? {
+        let rejected = count_rows(&rejects_lf)?;
+        let reject_path = if spec.output.reject_path_template.is_empty() {
+            default_reject_path(&output_path)
+        } else {
+            expand_path_template(&spec.output.reject_path_template)
+        };
+        write_output(rejects_lf, &reject_path, &spec.output)?;
+        (rejected, Some(reject_path))
     } else {
-        return Err(anyhow::anyhow!(
-            "No output path specified (provide --output or set output.path_template in spec)"
-        ));
+        (0, None)
     };
 
-    // Write output
-    write_output(lf, &output_path, &spec.output)?;
+    // The run is complete, so its checkpoints are no longer needed
+    if let Some(c) = &checkpoints {
+        c.clear();
+    }
+
+    // Deliver the final and intermediate outputs; failures become warnings
+    let mut delivered_files = vec![output_path.clone()];
+    delivered_files.extend(extra_outputs.iter().cloned());
+    let deliveries = deliver_outputs(&delivered_files, &spec.output.deliver);
+    for status in deliveries.iter().filter(|s| !s.delivered()) {
+        warnings.push(format!(
+            "Delivery of {} to {} failed after {} attempt(s): {}",
+            status.file.display(),
+            status.destination,
+            status.attempts,
+            status.error.as_deref().unwrap_or_default()
+        ));
+    }
 
     let duration = start.elapsed();
 
@@ -169,25 +635,123 @@ pub fn run_pipeline(
         rows_after,
         columns_after,
         steps_applied,
+        rows_rejected,
+        output_path,
+        reject_path,
+        extra_outputs,
+        resumed_after_step,
         warnings,
+        deliveries,
+        steps: step_metrics,
         duration,
     })
 }
 
-/// Apply a single transformation step
-fn apply_step(step: &Step, mut lf: LazyFrame) -> Result<LazyFrame> {
-    match step {
-        Step::DropColumns { columns } => {
-            let cols_to_keep: Vec<_> = lf
-                .collect_schema()
-                .map_err(|e| anyhow::anyhow!(e))?
-                .iter_names()
-                .filter(|name| !columns.contains(&name.to_string()))
-                .map(|name| col(name.as_str()))
-                .collect();
+/// Fail before anything is written when `policy` denies writing to one of
+/// the run's outputs, its checkpoint folder or a delivery folder
+fn check_write_paths(
+    spec: &PipelineSpec,
+    output_path: &Path,
+    checkpoints: Option<&Checkpoints>,
+    policy: &PathPolicy,
+) -> Result<()> {
+    let mut paths = vec![output_path.to_path_buf()];
+    paths.push(if spec.output.reject_path_template.is_empty() {
+        default_reject_path(output_path)
+    } else {
+        expand_path_template(&spec.output.reject_path_template)
+    });
+    paths.extend(spec.steps.iter().filter_map(|entry| match &entry.step {
+        Step::WriteOutput { path_template, .. } => Some(expand_path_template(path_template)),
+        _ => None,
+    }));
+    paths.extend(checkpoints.map(|c| c.dir().to_path_buf()));
+    paths.extend(
+        spec.output
+            .deliver
+            .iter()
+            .filter_map(|target| match &target.destination {
+                DeliveryDestination::Path { dir } => Some(PathBuf::from(dir)),
+                DeliveryDestination::Sftp { .. } => None,
+            }),
+    );
+    for path in &paths {
+        policy.ensure_not_denied(path, Capability::Write)?;
+    }
+    Ok(())
+}
 
-            Ok(lf.select(cols_to_keep))
-        }
+/// Rows a step would silently null out: values that fail a type cast or date
+/// parse. Steps that cannot lose values return no conditions.
+fn step_reject_conditions(
+    step: &Step,
+    idx: usize,
+    lf: &mut LazyFrame,
+) -> Result<Vec<RejectCondition>> {
+    if !matches!(step, Step::CastTypes { .. } | Step::ParseDates { .. }) {
+        return Ok(Vec::new());
+    }
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let targets: Vec<(String, DataType, Expr)> = match step {
+        Step::CastTypes { columns } => columns
+            .iter()
+            .map(|(name, type_str)| {
+                let dtype = parse_type_string(type_str)?;
+                Ok((name.clone(), dtype.clone(), col(name.as_str()).cast(dtype)))
+            })
+            .collect::<Result<_>>()?,
+        Step::ParseDates { columns } => columns
+            .iter()
+            .filter_map(|(name, format)| {
+                let parsed = parse_dates(name, schema.get(name)?, format);
+                Some((
+                    name.clone(),
+                    DataType::Datetime(TimeUnit::Milliseconds, None),
+                    parsed,
+                ))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut conditions: Vec<RejectCondition> = targets
+        .into_iter()
+        .filter(|(name, _, _)| schema.contains(name))
+        .map(|(name, dtype, converted)| RejectCondition {
+            reason: format!("Step {}: '{name}' cannot be converted to {dtype}", idx + 1),
+            condition: col(name.as_str()).is_not_null().and(converted.is_null()),
+        })
+        .collect();
+    conditions.sort_by(|a, b| a.reason.cmp(&b.reason));
+    Ok(conditions)
+}
+
+/// Column `name` of type `dtype` as datetimes: text read as `format`, or
+/// cast when no format is given or the column is not text
+fn parse_dates(name: &str, dtype: &DataType, format: &str) -> Expr {
+    if format.is_empty() || dtype != &DataType::String {
+        col(name).cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+    } else {
+        parse_dates_expr(col(name), format)
+    }
+    .alias(name)
+}
+
+/// Apply a single transformation step that needs no named inputs
+#[cfg(test)]
+fn apply_step(step: &Step, lf: LazyFrame) -> Result<LazyFrame> {
+    apply_step_with_inputs(step, lf, &HashMap::new())
+}
+
+/// Apply a single transformation step, resolving named inputs for
+/// `concat`/`union`
+pub(super) fn apply_step_with_inputs(
+    step: &Step,
+    mut lf: LazyFrame,
+    sources: &HashMap<String, LazyFrame>,
+) -> Result<LazyFrame> {
+    match step {
+        Step::DropColumns { columns } => drop_columns(lf, columns),
 
         Step::RenameColumns { mapping } => {
             let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
@@ -244,16 +808,10 @@ fn apply_step(step: &Step, mut lf: LazyFrame) -> Result<LazyFrame> {
         Step::ParseDates { columns: date_map } => {
             let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
             let exprs: Vec<_> = schema
-                .iter_names()
-                .map(|name| {
-                    if date_map.contains_key(name.as_str()) {
-                        // Cast to datetime (format parsing would require strptime which needs format info)
-                        col(name.as_str())
-                            .cast(DataType::Datetime(TimeUnit::Milliseconds, None))
-                            .alias(name.as_str())
-                    } else {
-                        col(name.as_str())
-                    }
+                .iter()
+                .map(|(name, dtype)| match date_map.get(name.as_str()) {
+                    Some(format) => parse_dates(name, dtype, format),
+                    None => col(name.as_str()),
                 })
                 .collect();
 
@@ -386,6 +944,79 @@ fn apply_step(step: &Step, mut lf: LazyFrame) -> Result<LazyFrame> {
             Ok(lf.select(exprs))
         }
 
+        Step::Bin {
+            column,
+            strategy,
+            labels,
+        } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            if !schema.contains(column) {
+                anyhow::bail!("Column '{column}' not found");
+            }
+            let binned = bin_expr(&lf, col(column.as_str()), column, strategy, labels)?;
+            Ok(lf.with_column(binned.alias(column.as_str())))
+        }
+
+        Step::EncodeCategories {
+            column,
+            encoding,
+            mapping_path,
+        } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            if !schema.contains(column) {
+                anyhow::bail!("Column '{column}' not found");
+            }
+            // A mapping saved by an earlier run is reused so new data gets
+            // the same numbers; the first run learns and saves it
+            let path = mapping_path
+                .as_deref()
+                .filter(|_| encoding.mapping.is_empty())
+                .map(expand_path_template);
+            let saved = match &path {
+                Some(path) if path.exists() => {
+                    let json = std::fs::read_to_string(path).with_context(|| {
+                        format!("Failed to read encoding mapping {}", path.display())
+                    })?;
+                    let saved: CategoryEncoding = serde_json::from_str(&json)
+                        .with_context(|| format!("Invalid encoding mapping {}", path.display()))?;
+                    Some(saved)
+                }
+                _ => None,
+            };
+            let (encoded, learned) = encode_column(lf, column, saved.as_ref().unwrap_or(encoding))?;
+            // Dry runs encode no rows; an empty mapping is not worth keeping
+            if saved.is_none()
+                && !learned.mapping.is_empty()
+                && let Some(path) = path
+            {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let json = serde_json::to_string_pretty(&learned)?;
+                std::fs::write(&path, json).with_context(|| {
+                    format!("Failed to save encoding mapping {}", path.display())
+                })?;
+            }
+            Ok(encoded)
+        }
+
+        Step::VectoriseText {
+            columns,
+            method,
+            features,
+            drop_original,
+        } => {
+            // Vocabularies and term weights depend on every row
+            let df = lf
+                .collect()
+                .context("Failed to collect data for text vectorisation")?;
+            let config = TextVectoriser {
+                method: *method,
+                features: *features,
+            };
+            Ok(vectorise_text(&df, columns, &config, *drop_original)?.lazy())
+        }
+
         Step::RegexReplace {
             columns,
             pattern,
@@ -408,9 +1039,349 @@ fn apply_step(step: &Step, mut lf: LazyFrame) -> Result<LazyFrame> {
 
             Ok(lf.select(exprs))
         }
+
+        Step::MapValues { columns, mapping } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            let exprs: Vec<_> = schema
+                .iter_names()
+                .map(|name| {
+                    if columns.contains(&name.to_string()) {
+                        map_values(col(name.as_str()).cast(DataType::String), mapping)
+                            .alias(name.as_str())
+                    } else {
+                        col(name.as_str())
+                    }
+                })
+                .collect();
+
+            Ok(lf.select(exprs))
+        }
+
+        Step::SplitColumn {
+            column,
+            delimiter,
+            regex,
+            into,
+            drop_original,
+        } => {
+            let split = ColumnSplit {
+                delimiter: delimiter.clone(),
+                regex: *regex,
+                into: into.clone(),
+                drop_original: *drop_original,
+            };
+            let exprs = split_exprs(col(column.as_str()), &split)
+                .with_context(|| format!("Failed to split column '{column}'"))?;
+            let lf = lf.with_columns(exprs);
+            if *drop_original && !into.contains(column) {
+                drop_columns(lf, std::slice::from_ref(column))
+            } else {
+                Ok(lf)
+            }
+        }
+
+        Step::MergeColumns {
+            template,
+            into,
+            drop_sources,
+        } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            let merge = ColumnMerge {
+                template: template.clone(),
+                into: into.clone(),
+                drop_sources: *drop_sources,
+            };
+            let expr = merge_expr(&merge, |name| schema.contains(name).then(|| col(name)))?;
+            let lf = lf.with_column(expr);
+            if *drop_sources {
+                let sources: Vec<String> = template_columns(template)?
+                    .into_iter()
+                    .filter(|name| name != into)
+                    .collect();
+                drop_columns(lf, &sources)
+            } else {
+                Ok(lf)
+            }
+        }
+
+        Step::ExtractDateParts { column, parts } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            let dtype = schema
+                .get(column)
+                .with_context(|| format!("Column '{column}' not found"))?;
+            let exprs = date_part_exprs(col(column.as_str()), dtype, column, parts)?;
+            Ok(lf.with_columns(exprs))
+        }
+
+        Step::WithColumn { name, expression } => {
+            let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+            let expr = compile(expression, &schema)
+                .with_context(|| format!("Invalid expression for column '{name}'"))?;
+            Ok(lf.with_column(expr.alias(name.as_str())))
+        }
+
+        Step::Aggregate {
+            group_by,
+            aggregations,
+        } => {
+            let aggs: Vec<Expr> = aggregations
+                .iter()
+                .map(|agg| {
+                    aggregate_expr(col(agg.column.as_str()), agg.function).alias(agg.output_name())
+                })
+                .collect();
+
+            if group_by.is_empty() {
+                Ok(lf.select(aggs))
+            } else {
+                let keys: Vec<Expr> = group_by.iter().map(|c| col(c.as_str())).collect();
+                // Sort by the keys so output order is deterministic
+                Ok(lf
+                    .group_by(keys)
+                    .agg(aggs)
+                    .sort(group_by.clone(), SortMultipleOptions::default()))
+            }
+        }
+
+        Step::Sort { by, descending } => {
+            let descending = match descending.as_slice() {
+                [] => vec![false; by.len()],
+                [flag] => vec![*flag; by.len()],
+                flags => flags.to_vec(),
+            };
+            Ok(lf.sort(
+                by.clone(),
+                SortMultipleOptions::default()
+                    .with_order_descending_multi(descending)
+                    .with_maintain_order(true),
+            ))
+        }
+
+        Step::Limit { n } => Ok(lf.limit(IdxSize::try_from(*n).unwrap_or(IdxSize::MAX))),
+
+        Step::Sample { fraction, n, seed } => apply_sample(lf, *fraction, *n, *seed),
+
+        Step::Concat { inputs } => stack_inputs(lf, inputs, sources),
+
+        Step::Union { inputs } => {
+            Ok(stack_inputs(lf, inputs, sources)?.unique_stable(None, UniqueKeepStrategy::First))
+        }
+
+        Step::Pivot {
+            index,
+            columns,
+            values,
+            agg,
+        } => apply_pivot(lf, index, columns, values, *agg),
+
+        Step::Unpivot {
+            id_vars,
+            value_vars,
+        } => {
+            let on = if value_vars.is_empty() {
+                let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+                schema
+                    .iter_names()
+                    .filter(|name| !id_vars.iter().any(|id| id == name.as_str()))
+                    .map(|name| name.to_string())
+                    .collect()
+            } else {
+                value_vars.clone()
+            };
+            let args = UnpivotArgsDSL {
+                on: on.iter().map(|c| col(c.as_str()).into()).collect(),
+                index: id_vars.iter().map(|c| col(c.as_str()).into()).collect(),
+                variable_name: None,
+                value_name: None,
+            };
+            Ok(lf.unpivot(args))
+        }
+
+        Step::Transpose {
+            header_column,
+            names_column,
+        } => {
+            // Every row becomes a column, so the data has to be collected
+            let df = lf
+                .limit(TRANSPOSE_MAX_COLUMNS as IdxSize + 1)
+                .collect()
+                .context("Failed to collect data for transpose")?;
+            if df.height() > TRANSPOSE_MAX_COLUMNS {
+                anyhow::bail!("Transpose supports at most {TRANSPOSE_MAX_COLUMNS} rows");
+            }
+            Ok(transpose_df(&df, header_column.as_deref(), names_column)?.lazy())
+        }
+
+        Step::CompletenessScore { name, weights } => append_completeness_score(lf, name, weights),
+
+        Step::ExpectSchema {
+            columns,
+            on_violation,
+        } => {
+            let violations = schema_violations(&lf, columns, true)?;
+            if *on_violation == ViolationAction::Fail && !violations.is_empty() {
+                return Err(ContractViolations(violations).into());
+            }
+            Ok(lf)
+        }
+
+        // Sinks are written by `run_pipeline_with_inputs`; the data passes through
+        Step::WriteOutput { .. } => Ok(lf),
+
+        Step::External {
+            command,
+            args,
+            data_format,
+        } => run_external(lf, command, args, *data_format),
+    }
+}
+
+fn aggregate_expr(expr: Expr, function: AggregateFunction) -> Expr {
+    match function {
+        AggregateFunction::Sum => expr.sum(),
+        AggregateFunction::Mean => expr.mean(),
+        AggregateFunction::Min => expr.min(),
+        AggregateFunction::Max => expr.max(),
+        AggregateFunction::Count => expr.count(),
+        AggregateFunction::NUnique => expr.n_unique(),
     }
 }
 
+/// Stack named inputs below `lf`, matching columns by name and casting to
+/// common supertypes
+fn stack_inputs(
+    mut lf: LazyFrame,
+    inputs: &[String],
+    sources: &HashMap<String, LazyFrame>,
+) -> Result<LazyFrame> {
+    let schema = lf.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+    let order: Vec<Expr> = schema.iter_names().map(|name| col(name.as_str())).collect();
+
+    let mut frames = vec![lf];
+    for name in inputs {
+        let mut source = sources
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown input '{name}'"))?;
+        let source_schema = source.collect_schema().map_err(|e| anyhow::anyhow!(e))?;
+        if source_schema.len() != schema.len()
+            || schema
+                .iter_names()
+                .any(|c| !source_schema.contains(c.as_str()))
+        {
+            anyhow::bail!("Input '{name}' does not have the same columns as the pipeline data");
+        }
+        frames.push(source.select(order.clone()));
+    }
+
+    concat(
+        frames,
+        UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        },
+    )
+    .context("Failed to stack inputs")
+}
+
+/// Keep a seeded random subset of rows in their original order.
+///
+/// Each row gets a pseudo-random key from its position and the seed, so the
+/// sample is reproducible without counting or collecting the input first: a
+/// `fraction` keeps the rows whose key falls below that share of the key
+/// range, and `n` keeps the rows with the `n` lowest keys.
+fn apply_sample(
+    lf: LazyFrame,
+    fraction: Option<f64>,
+    n: Option<usize>,
+    seed: u64,
+) -> Result<LazyFrame> {
+    // Keys are mixed modulo a 31-bit prime so every product fits in a u64
+    const PRIME: u64 = 2_147_483_647;
+    const IDX: &str = "__sample_idx__";
+    const KEY: &str = "__sample_key__";
+
+    let mut rng = SplitMix64(seed);
+    let mut salt = || lit(rng.next_u64() % PRIME);
+    let position = col(IDX).cast(DataType::UInt64) % lit(PRIME);
+    let mixed = (position * lit(48_271u64) + salt()) % lit(PRIME);
+    let mixed = (mixed.clone() * mixed + salt()) % lit(PRIME);
+    let key = (mixed.clone() * mixed + salt()) % lit(PRIME);
+
+    let keyed = lf.with_row_index(IDX, None).with_column(key.alias(KEY));
+    let sampled = match (fraction, n) {
+        (Some(fraction), None) => {
+            let cutoff = (PRIME as f64 * fraction.clamp(0.0, 1.0)).round() as u64;
+            keyed.filter(col(KEY).lt(lit(cutoff)))
+        }
+        (None, Some(n)) => keyed
+            .sort([KEY, IDX], SortMultipleOptions::default())
+            .limit(IdxSize::try_from(n).unwrap_or(IdxSize::MAX))
+            .sort([IDX], SortMultipleOptions::default()),
+        _ => anyhow::bail!("Sample step needs exactly one of 'fraction' or 'n'"),
+    };
+    Ok(sampled.select([col("*").exclude([IDX, KEY])]))
+}
+
+/// Pivot `values` into one column per distinct value of `on`
+fn apply_pivot(
+    lf: LazyFrame,
+    index: &[String],
+    on: &str,
+    values: &str,
+    agg: AggregateFunction,
+) -> Result<LazyFrame> {
+    // Collect to get the distinct pivot keys
+    let df_temp = lf
+        .clone()
+        .select([col(on).cast(DataType::String)])
+        .collect()
+        .context(format!("Failed to collect column {on} for pivot"))?;
+
+    let unique_vals = df_temp.column(on)?.unique()?.drop_nulls();
+    let mut keys: Vec<String> = unique_vals
+        .str()?
+        .into_iter()
+        .flatten()
+        .map(std::borrow::ToOwned::to_owned)
+        .collect();
+    keys.sort();
+
+    if keys.len() > PIVOT_MAX_COLUMNS {
+        return Err(anyhow::anyhow!(
+            "Pivot on column '{on}' would create {} columns (limit: {PIVOT_MAX_COLUMNS})",
+            keys.len()
+        ));
+    }
+
+    let mut used_names: HashSet<String> = index.iter().cloned().collect();
+    let aggs: Vec<Expr> = keys
+        .iter()
+        .map(|key| {
+            let mut name = key.clone();
+            let mut counter = 1;
+            while used_names.contains(&name) {
+                name = format!("{key}_{counter}");
+                counter += 1;
+            }
+            used_names.insert(name.clone());
+
+            let matching = col(values).filter(col(on).cast(DataType::String).eq(lit(key.as_str())));
+            aggregate_expr(matching, agg).alias(name)
+        })
+        .collect();
+
+    if index.is_empty() {
+        return Ok(lf.select(aggs));
+    }
+
+    let group_keys: Vec<Expr> = index.iter().map(|c| col(c.as_str())).collect();
+    Ok(lf
+        .group_by(group_keys)
+        .agg(aggs)
+        .sort(index.to_vec(), SortMultipleOptions::default()))
+}
+
 /// Apply one-hot encoding to a single column
 fn apply_one_hot_encoding(
     mut lf: LazyFrame,
@@ -482,8 +1453,63 @@ fn apply_one_hot_encoding(
     Ok(lf.select(expressions))
 }
 
+/// Row and null-cell counts of a frame
+#[derive(Debug, Clone, Copy)]
+struct FrameStats {
+    rows: usize,
+    nulls: usize,
+}
+
+/// Count rows and null cells in a `LazyFrame` in one streaming pass
+fn frame_stats(lf: &LazyFrame) -> Result<FrameStats> {
+    const ROWS_COLUMN: &str = "__beefcake_rows__";
+    let df = lf
+        .clone()
+        .select([len().alias(ROWS_COLUMN), col("*").null_count()])
+        .with_streaming(true)
+        .collect()
+        .context("Failed to evaluate step")?;
+
+    let mut stats = FrameStats { rows: 0, nulls: 0 };
+    for column in df.get_columns() {
+        let value = column
+            .as_materialized_series()
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap_or(0) as usize;
+        if column.name().as_str() == ROWS_COLUMN {
+            stats.rows = value;
+        } else {
+            stats.nulls += value;
+        }
+    }
+    Ok(stats)
+}
+
+/// `lf` without `columns`
+fn drop_columns(mut lf: LazyFrame, columns: &[String]) -> Result<LazyFrame> {
+    let cols_to_keep: Vec<_> = lf
+        .collect_schema()
+        .map_err(|e| anyhow::anyhow!(e))?
+        .iter_names()
+        .filter(|name| !columns.contains(&name.to_string()))
+        .map(|name| col(name.as_str()))
+        .collect();
+
+    Ok(lf.select(cols_to_keep))
+}
+
+/// The `op` tag a step serializes with
+pub(crate) fn step_op(step: &Step) -> String {
+    serde_json::to_value(step)
+        .ok()
+        .and_then(|v| v.get("op").and_then(|op| op.as_str()).map(str::to_owned))
+        .unwrap_or_default()
+}
+
 /// Count rows in a `LazyFrame` (streaming)
-fn count_rows(lf: &LazyFrame) -> Result<usize> {
+pub(crate) fn count_rows(lf: &LazyFrame) -> Result<usize> {
     let count_df = lf
         .clone()
         .select([len()])
@@ -502,6 +1528,20 @@ fn count_rows(lf: &LazyFrame) -> Result<usize> {
     }
 }
 
+/// Reject file next to the output, used when quarantining without an
+/// explicit `output.reject_path_template`
+fn default_reject_path(output_path: &Path) -> PathBuf {
+    let stem = output_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "output".to_owned());
+    let ext = output_path
+        .extension()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "csv".to_owned());
+    output_path.with_file_name(format!("{stem}_rejects.{ext}"))
+}
+
 /// Expand path template with variables (e.g., {date})
 fn expand_path_template(template: &str) -> PathBuf {
     let today = Local::now().format("%Y-%m-%d").to_string();
@@ -510,7 +1550,7 @@ fn expand_path_template(template: &str) -> PathBuf {
 }
 
 /// Write output to file based on configuration
-fn write_output(lf: LazyFrame, path: &Path, config: &OutputConfig) -> Result<()> {
+pub(crate) fn write_output(lf: LazyFrame, path: &Path, config: &OutputConfig) -> Result<()> {
     // Check if file exists and overwrite setting
     if path.exists() && !config.overwrite {
         return Err(anyhow::anyhow!(
@@ -578,6 +1618,8 @@ fn parse_type_string(type_str: &str) -> Result<DataType> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analyser::logic::{BinStrategy, DatePart, EncodingMethod, TextVectorMethod};
+    use crate::pipeline::spec::{Aggregation, ExpectedColumn};
 
     fn create_test_dataframe() -> DataFrame {
         df!(
@@ -671,6 +1713,870 @@ mod tests {
         assert!(result_df.column("name").is_err());
     }
 
+    #[test]
+    fn test_apply_step_with_column() {
+        let df = create_test_dataframe();
+        let lf = df.lazy();
+
+        let step = Step::WithColumn {
+            name: "monthly_salary".to_owned(),
+            expression: "salary / 12".to_owned(),
+        };
+
+        let result_df = apply_step(&step, lf).unwrap().collect().unwrap();
+        let monthly = result_df.column("monthly_salary").unwrap();
+        assert_eq!(monthly.f64().unwrap().get(1), Some(5000.0));
+        assert_eq!(result_df.width(), 5);
+    }
+
+    #[test]
+    fn test_apply_step_sort_limit_and_sample() {
+        let df = df!(
+            "group" => ["b", "a", "b", "a", "c"],
+            "value" => [1, 2, 3, 4, 5],
+        )
+        .unwrap();
+
+        let sort = Step::Sort {
+            by: vec!["group".to_owned(), "value".to_owned()],
+            descending: vec![false, true],
+        };
+        let limit = Step::Limit { n: 3 };
+        let sorted = apply_step(&sort, df.clone().lazy()).unwrap();
+        let top = apply_step(&limit, sorted).unwrap().collect().unwrap();
+        let values: Vec<Option<i32>> = top
+            .column("value")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(values, vec![Some(4), Some(2), Some(3)]);
+
+        let sample = Step::Sample {
+            fraction: None,
+            n: Some(2),
+            seed: 7,
+        };
+        let first = apply_step(&sample, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let second = apply_step(&sample, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(first.height(), 2);
+        assert_eq!(first.get_column_names(), df.get_column_names());
+        assert!(first.equals(&second));
+        let picked: Vec<i32> = first
+            .column("value")
+            .unwrap()
+            .i32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert!(picked.is_sorted());
+
+        let many = df!("value" => (0..1000).collect::<Vec<i32>>()).unwrap();
+        let share = Step::Sample {
+            fraction: Some(0.3),
+            n: None,
+            seed: 7,
+        };
+        let kept = apply_step(&share, many.lazy()).unwrap().collect().unwrap();
+        assert!(
+            (200..400).contains(&kept.height()),
+            "kept {}",
+            kept.height()
+        );
+
+        let invalid = Step::Sample {
+            fraction: Some(0.5),
+            n: Some(2),
+            seed: 7,
+        };
+        assert!(apply_step(&invalid, df.lazy()).is_err());
+    }
+
+    #[test]
+    fn test_apply_step_pivot_and_unpivot() {
+        let df = df!(
+            "store" => ["a", "a", "b", "b", "a"],
+            "month" => ["jan", "feb", "jan", "feb", "jan"],
+            "sales" => [1.0, 2.0, 3.0, 4.0, 5.0],
+        )
+        .unwrap();
+
+        let pivot = Step::Pivot {
+            index: vec!["store".to_owned()],
+            columns: "month".to_owned(),
+            values: "sales".to_owned(),
+            agg: AggregateFunction::Sum,
+        };
+        let wide = apply_step(&pivot, df.lazy()).unwrap().collect().unwrap();
+
+        assert_eq!(wide.height(), 2);
+        let names: Vec<&str> = wide.get_column_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["store", "feb", "jan"]);
+        let jan = wide.column("jan").unwrap().f64().unwrap();
+        assert_eq!(jan.get(0), Some(6.0));
+        assert_eq!(jan.get(1), Some(3.0));
+
+        let unpivot = Step::Unpivot {
+            id_vars: vec!["store".to_owned()],
+            value_vars: vec![],
+        };
+        let long = apply_step(&unpivot, wide.lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+
+        assert_eq!(long.height(), 4);
+        assert!(long.column("variable").is_ok());
+        assert!(long.column("value").is_ok());
+    }
+
+    #[test]
+    fn test_apply_step_transpose() {
+        let df = df!(
+            "variable" => ["age", "city"],
+            "p1" => ["34", "Leeds"],
+            "p2" => ["51", "York"],
+        )
+        .unwrap();
+
+        let step = Step::Transpose {
+            header_column: Some("variable".to_owned()),
+            names_column: "person".to_owned(),
+        };
+        let out = apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+
+        let names: Vec<&str> = out.get_column_names().iter().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["person", "age", "city"]);
+        assert_eq!(out.column("age").unwrap().i64().unwrap().get(1), Some(51));
+        assert_eq!(
+            out.column("city").unwrap().str().unwrap().get(0),
+            Some("Leeds")
+        );
+    }
+
+    #[test]
+    fn test_apply_step_completeness_score() {
+        let df = df!(
+            "a" => [Some(1), None],
+            "b" => [Some("x"), Some("y")],
+        )
+        .unwrap();
+
+        let step: Step = serde_json::from_str(r#"{"op": "completeness_score"}"#).unwrap();
+        let result_df = apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+
+        let score = result_df.column("row_completeness").unwrap().f64().unwrap();
+        assert_eq!(score.get(0), Some(1.0));
+        assert_eq!(score.get(1), Some(0.5));
+    }
+
+    #[test]
+    fn test_apply_step_split_and_merge() {
+        let df = df!("name" => ["Ada Lovelace", "Grace Hopper"]).unwrap();
+
+        let split: Step = serde_json::from_str(
+            r#"{"op": "split_column", "column": "name", "delimiter": " ", "into": ["first", "last"], "drop_original": true}"#,
+        )
+        .unwrap();
+        let merge: Step = serde_json::from_str(
+            r#"{"op": "merge_columns", "template": "{last}, {first}", "into": "name", "drop_sources": true}"#,
+        )
+        .unwrap();
+        let lf = apply_step(&split, df.lazy()).unwrap();
+        let result_df = apply_step(&merge, lf).unwrap().collect().unwrap();
+
+        let columns: Vec<&str> = result_df
+            .get_column_names()
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        assert_eq!(columns, ["name"]);
+        let names: Vec<_> = result_df
+            .column("name")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(names, [Some("Lovelace, Ada"), Some("Hopper, Grace")]);
+    }
+
+    #[test]
+    fn test_apply_step_extract_date_parts() {
+        // 2024-03-09 14:30 and 2024-12-31 08:05
+        let placed = Series::new("placed".into(), [1_709_994_600_000_i64, 1_735_632_300_000])
+            .cast(&DataType::Datetime(TimeUnit::Milliseconds, None))
+            .unwrap();
+        let df = DataFrame::new(vec![placed.into()]).unwrap();
+
+        let step: Step = serde_json::from_str(
+            r#"{"op": "extract_date_parts", "column": "placed", "parts": ["month", "week", "hour", "is_weekend"]}"#,
+        )
+        .unwrap();
+        let result_df = apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+
+        let int_column = |name: &str| -> Vec<Option<i32>> {
+            result_df
+                .column(name)
+                .unwrap()
+                .i32()
+                .unwrap()
+                .into_iter()
+                .collect()
+        };
+        assert_eq!(int_column("placed_month"), [Some(3), Some(12)]);
+        // 31 December 2024 falls in ISO week 1 of 2025
+        assert_eq!(int_column("placed_week"), [Some(10), Some(1)]);
+        assert_eq!(int_column("placed_hour"), [Some(14), Some(8)]);
+        let weekend: Vec<_> = result_df
+            .column("placed_is_weekend")
+            .unwrap()
+            .bool()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(weekend, [Some(true), Some(false)]);
+
+        let on_text = Step::ExtractDateParts {
+            column: "name".to_owned(),
+            parts: vec![DatePart::Year],
+        };
+        let text_df = df!("name" => ["Ada"]).unwrap();
+        assert!(apply_step(&on_text, text_df.lazy()).is_err());
+    }
+
+    #[test]
+    fn test_apply_step_parse_dates_with_format() {
+        let df = df!("placed" => ["03/04/2024", "12/31/2024", "31/12/2024"]).unwrap();
+        let step = Step::ParseDates {
+            columns: HashMap::from([("placed".to_owned(), "%m/%d/%Y".to_owned())]),
+        };
+        let result_df = apply_step(&step, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let placed = result_df
+            .column("placed")
+            .unwrap()
+            .as_materialized_series()
+            .cast(&DataType::Int64)
+            .unwrap();
+        // 4 March 2024, 31 December 2024, and a day-first date left unread
+        let placed: Vec<_> = placed.i64().unwrap().into_iter().collect();
+        assert_eq!(
+            placed,
+            [Some(1_709_510_400_000), Some(1_735_603_200_000), None]
+        );
+
+        let conditions = step_reject_conditions(&step, 0, &mut df.lazy()).unwrap();
+        assert_eq!(conditions.len(), 1);
+    }
+
+    #[test]
+    fn test_apply_step_bin() {
+        let df = df!("income" => [12_000.0, 30_000.0, 55_000.0, 250_000.0]).unwrap();
+
+        let step: Step = serde_json::from_str(
+            r#"{"op": "bin", "column": "income", "strategy": {"kind": "custom", "edges": [0, 25000, 100000]}, "labels": ["low", "middle"]}"#,
+        )
+        .unwrap();
+        let result_df = apply_step(&step, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let bands: Vec<_> = result_df
+            .column("income")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(bands, [Some("low"), Some("middle"), Some("middle"), None]);
+
+        let quartiles = Step::Bin {
+            column: "income".to_owned(),
+            strategy: BinStrategy::Quantile { bins: 4 },
+            labels: Vec::new(),
+        };
+        let result_df = apply_step(&quartiles, df.lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(
+            result_df
+                .column("income")
+                .unwrap()
+                .as_materialized_series()
+                .n_unique()
+                .unwrap(),
+            4
+        );
+
+        let missing = Step::Bin {
+            column: "age".to_owned(),
+            strategy: BinStrategy::EqualWidth { bins: 3 },
+            labels: Vec::new(),
+        };
+        let other_df = df!("income" => [1.0]).unwrap();
+        assert!(apply_step(&missing, other_df.lazy()).is_err());
+    }
+
+    #[test]
+    fn test_apply_step_encode_categories() {
+        let df = df!("grade" => ["b", "a", "c", "a"]).unwrap();
+
+        let step: Step = serde_json::from_str(
+            r#"{"op": "encode_categories", "column": "grade", "encoding": {"method": {"kind": "ordinal"}}}"#,
+        )
+        .unwrap();
+        let result_df = apply_step(&step, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let codes: Vec<_> = result_df
+            .column("grade")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(codes, [Some(1), Some(0), Some(2), Some(0)]);
+
+        // The first run saves the mapping and later runs reuse it
+        let dir = tempfile::TempDir::new().unwrap();
+        let mapping_path = dir.path().join("grade.json");
+        let step = Step::EncodeCategories {
+            column: "grade".to_owned(),
+            encoding: CategoryEncoding {
+                method: EncodingMethod::Ordinal,
+                mapping: Default::default(),
+                unknown: None,
+            },
+            mapping_path: Some(mapping_path.to_string_lossy().into_owned()),
+        };
+        // No rows, as in a dry run, learn nothing worth saving
+        let empty_df = df.clear();
+        apply_step(&step, empty_df.lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert!(!mapping_path.exists());
+
+        apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+        assert!(mapping_path.exists());
+
+        let new_df = df!("grade" => ["c", "d"]).unwrap();
+        let result_df = apply_step(&step, new_df.lazy()).unwrap().collect().unwrap();
+        let codes: Vec<_> = result_df
+            .column("grade")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(codes, [Some(2), Some(-1)]);
+    }
+
+    #[test]
+    fn test_apply_step_vectorise_text() {
+        let df = df!(
+            "review" => [Some("Great value, great taste"), Some("Poor value"), None],
+            "stars" => [5, 2, 3],
+        )
+        .unwrap();
+
+        let step: Step = serde_json::from_str(
+            r#"{"op": "vectorise_text", "columns": ["review"], "features": 2, "drop_original": true}"#,
+        )
+        .unwrap();
+        let result_df = apply_step(&step, df.clone().lazy())
+            .unwrap()
+            .collect()
+            .unwrap();
+        let names: Vec<&str> = result_df
+            .get_column_names()
+            .iter()
+            .map(|n| n.as_str())
+            .collect();
+        assert_eq!(names, ["stars", "review_tfidf_value", "review_tfidf_great"]);
+
+        let step = Step::VectoriseText {
+            columns: vec!["review".to_owned()],
+            method: TextVectorMethod::Hashing,
+            features: 8,
+            drop_original: false,
+        };
+        let result_df = apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+        assert_eq!(result_df.width(), 10);
+        let null_row: f64 = (0..8)
+            .map(|i| {
+                result_df
+                    .column(&format!("review_h{i}"))
+                    .unwrap()
+                    .f64()
+                    .unwrap()
+                    .get(2)
+                    .unwrap()
+            })
+            .sum();
+        assert_eq!(null_row, 0.0);
+
+        let on_numbers = Step::VectoriseText {
+            columns: vec!["stars".to_owned()],
+            method: TextVectorMethod::Hashing,
+            features: 8,
+            drop_original: false,
+        };
+        let numbers_df = df!("stars" => [1, 2]).unwrap();
+        assert!(apply_step(&on_numbers, numbers_df.lazy()).is_err());
+    }
+
+    #[test]
+    fn test_apply_step_aggregate() {
+        let df = df!(
+            "region" => ["north", "south", "north", "south", "north"],
+            "sales" => [10.0, 20.0, 30.0, 40.0, 50.0],
+        )
+        .unwrap();
+
+        let step = Step::Aggregate {
+            group_by: vec!["region".to_owned()],
+            aggregations: vec![
+                Aggregation {
+                    column: "sales".to_owned(),
+                    function: AggregateFunction::Sum,
+                    alias: None,
+                },
+                Aggregation {
+                    column: "sales".to_owned(),
+                    function: AggregateFunction::Count,
+                    alias: Some("orders".to_owned()),
+                },
+            ],
+        };
+
+        let result_df = apply_step(&step, df.lazy()).unwrap().collect().unwrap();
+
+        assert_eq!(result_df.height(), 2);
+        let sums = result_df.column("sales_sum").unwrap().f64().unwrap();
+        assert_eq!(sums.get(0), Some(90.0));
+        assert_eq!(sums.get(1), Some(60.0));
+        let orders = result_df
+            .column("orders")
+            .unwrap()
+            .cast(&DataType::UInt32)
+            .unwrap();
+        assert_eq!(orders.u32().unwrap().get(0), Some(3));
+    }
+
+    #[test]
+    fn test_run_pipeline_writes_rejects() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        let rejects = dir.path().join("rejects.csv");
+        std::fs::write(&input, "id,amount\n1,10\n2,abc\n3,\n").unwrap();
+
+        let mut spec = PipelineSpec::new("rejects");
+        spec.steps.push(
+            Step::CastTypes {
+                columns: std::collections::HashMap::from([("amount".to_owned(), "f64".to_owned())]),
+            }
+            .into(),
+        );
+        spec.output.reject_path_template = rejects.to_string_lossy().into_owned();
+
+        // Steps are only counted on request
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.rows_after, 2);
+        assert_eq!(report.steps[0].rows_after, None);
+        assert_eq!(report.steps[0].rows_affected(), None);
+
+        spec.output.step_metrics = true;
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.rows_after, 2);
+        assert_eq!(report.rows_rejected, 1);
+        assert_eq!(report.reject_path.as_deref(), Some(rejects.as_path()));
+
+        let rejected = load_df_lazy(&rejects).unwrap().collect().unwrap();
+        assert_eq!(rejected.height(), 1);
+        let reason = rejected
+            .column("reject_reason")
+            .unwrap()
+            .str()
+            .unwrap()
+            .get(0);
+        assert!(reason.unwrap().contains("'amount'"));
+
+        let metrics = &report.steps[0];
+        assert_eq!(metrics.op, "cast_types");
+        assert_eq!(metrics.status, StepStatus::Applied);
+        assert_eq!(
+            (metrics.rows_before, metrics.rows_after),
+            (Some(3), Some(2))
+        );
+        assert_eq!(metrics.rows_rejected, Some(1));
+        assert_eq!(metrics.rows_affected(), Some(1));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["rows_rejected"], 1);
+        assert_eq!(json["steps"][0]["op"], "cast_types");
+        assert_eq!(json["steps"][0]["status"], "applied");
+        assert!(json["duration_secs"].is_f64());
+    }
+
+    #[test]
+    fn test_step_error_policies() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        std::fs::write(&input, "id,amount\n1,10\n2,abc\n").unwrap();
+
+        let mut spec = PipelineSpec::new("policies");
+        spec.steps.push(
+            Step::CastTypes {
+                columns: HashMap::from([("amount".to_owned(), "f64".to_owned())]),
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::CastTypes {
+                columns: HashMap::from([("id".to_owned(), "not_a_type".to_owned())]),
+            }
+            .into(),
+        );
+
+        // Default: the broken step is skipped with a warning
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.reject_path, None);
+
+        // Quarantine writes failed casts next to the output
+        spec.steps[0].on_error = ErrorPolicy::Quarantine;
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.rows_after, 1);
+        assert_eq!(report.rows_rejected, 1);
+        let reject_path = dir.path().join("output_rejects.csv");
+        assert_eq!(report.reject_path.as_deref(), Some(reject_path.as_path()));
+        assert!(reject_path.exists());
+
+        // Fail aborts the run
+        spec.steps[1].on_error = ErrorPolicy::Fail;
+        let err = run_pipeline(&spec, &input, Some(&output)).unwrap_err();
+        assert!(err.to_string().contains("Step 2 failed"));
+    }
+
+    #[test]
+    fn test_run_pipeline_writes_intermediate_outputs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("summary.csv");
+        let detail = dir.path().join("detail.csv");
+        std::fs::write(&input, "region,amount\nnorth,10\nnorth,5\nsouth,7\n").unwrap();
+
+        let mut spec = PipelineSpec::new("multi-output");
+        spec.steps.push(
+            Step::WriteOutput {
+                path_template: detail.to_string_lossy().into_owned(),
+                format: None,
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::Aggregate {
+                group_by: vec!["region".to_owned()],
+                aggregations: vec![Aggregation {
+                    column: "amount".to_owned(),
+                    function: AggregateFunction::Sum,
+                    alias: None,
+                }],
+            }
+            .into(),
+        );
+
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.extra_outputs, vec![detail.clone()]);
+        assert_eq!(report.rows_after, 2);
+
+        let written = load_df_lazy(&detail).unwrap().collect().unwrap();
+        assert_eq!(written.height(), 3);
+        assert_eq!(written.width(), 2);
+    }
+
+    #[test]
+    fn test_run_pipeline_expect_schema() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        std::fs::write(&input, "id,email\n1,a@b.com\n2,\n").unwrap();
+
+        let contract = |on_violation| Step::ExpectSchema {
+            columns: vec![
+                ExpectedColumn {
+                    name: "id".to_owned(),
+                    dtype: Some("Integer".to_owned()),
+                    nullable: false,
+                },
+                ExpectedColumn {
+                    name: "email".to_owned(),
+                    dtype: Some("Text".to_owned()),
+                    nullable: false,
+                },
+            ],
+            on_violation,
+        };
+
+        let mut spec = PipelineSpec::new("contract");
+        spec.steps.push(contract(ViolationAction::Warn).into());
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(
+            report.warnings,
+            ["Step 1: Column 'email' has nulls in 1 row"]
+        );
+
+        spec.steps = vec![contract(ViolationAction::Fail).into()];
+        let err = run_pipeline(&spec, &input, Some(&output)).unwrap_err();
+        assert!(format!("{err:#}").contains("Column 'email' has nulls in 1 row"));
+        // Callers such as the CLI tell broken data from other failures
+        assert!(err.chain().any(|e| e.is::<ContractViolations>()));
+    }
+
+    #[test]
+    fn test_validate_pipeline_for_input() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        std::fs::write(&input, "id,amount\n1,10\n").unwrap();
+
+        let mut spec = PipelineSpec::new("validate");
+        spec.output.path_template = output.display().to_string();
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["amount".to_owned(), "total".to_owned()],
+            }
+            .into(),
+        );
+        let errors = validate_pipeline_for_input(&spec, &input, &HashMap::new()).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'total'"));
+        // Nothing runs
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_run_pipeline_reports_progress() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        std::fs::write(&input, "id,amount\n1,10\n2,20\n3,30\n").unwrap();
+
+        let mut spec = PipelineSpec::new("progress");
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["amount".to_owned()],
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::Sort {
+                by: vec!["id".to_owned()],
+                descending: vec![true],
+            }
+            .into(),
+        );
+
+        let mut progress = Vec::new();
+        run_pipeline_with_progress(&spec, &input, &HashMap::new(), Some(&output), |p| {
+            progress.push(p.clone());
+        })
+        .unwrap();
+        let step = |step_index: usize, op: &str| RunProgress::Step {
+            step_index,
+            steps: 2,
+            op: op.to_owned(),
+            rows: None,
+        };
+        assert_eq!(
+            progress,
+            [
+                step(0, "drop_columns"),
+                step(1, "sort"),
+                RunProgress::Writing { rows: 3 }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_pipeline_resumes_from_checkpoint() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        std::fs::write(&input, "id,amount\n1,10\n2,20\n3,30\n").unwrap();
+
+        let mut spec = PipelineSpec::new("resume");
+        spec.steps.push(
+            Step::Sort {
+                by: vec!["amount".to_owned()],
+                descending: vec![true],
+            }
+            .into(),
+        );
+        spec.steps[0].checkpoint = true;
+        spec.steps.push(
+            Step::External {
+                command: "no-such-tool".to_owned(),
+                args: vec![],
+                data_format: Default::default(),
+            }
+            .into(),
+        );
+        spec.steps[1].on_error = ErrorPolicy::Fail;
+
+        assert!(run_pipeline(&spec, &input, Some(&output)).is_err());
+        let checkpoint_dir = dir.path().join(".checkpoints").join("resume");
+        assert!(checkpoint_dir.join("step_000.parquet").exists());
+
+        // Fix the failing step; the sort is not run again
+        spec.steps[1].step = Step::Limit { n: 2 };
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.resumed_after_step, Some(0));
+        assert_eq!(report.steps_applied, 1);
+        assert_eq!(report.rows_after, 2);
+        assert!(!checkpoint_dir.exists());
+
+        let written = load_df_lazy(&output).unwrap().collect().unwrap();
+        let amounts: Vec<i64> = written
+            .column("amount")
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(amounts, [30, 20]);
+
+        // Without a checkpoint left behind, the next run starts from scratch
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.resumed_after_step, None);
+        assert_eq!(report.steps_applied, 2);
+    }
+
+    #[test]
+    fn test_run_pipeline_delivers_outputs() {
+        use crate::pipeline::spec::{DeliveryDestination, DeliveryTarget, DeliveryVerify};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let output = dir.path().join("output.csv");
+        let share = dir.path().join("share");
+        std::fs::create_dir(&share).unwrap();
+        std::fs::write(&input, "id\n1\n2\n").unwrap();
+
+        let target = |dir: &Path| DeliveryTarget {
+            destination: DeliveryDestination::Path {
+                dir: dir.to_string_lossy().into_owned(),
+            },
+            retries: 0,
+            retry_delay_secs: 0,
+            verify: DeliveryVerify::Size,
+        };
+        let mut spec = PipelineSpec::new("deliver");
+        spec.output.deliver = vec![target(&share), target(&dir.path().join("missing"))];
+
+        let report = run_pipeline(&spec, &input, Some(&output)).unwrap();
+        assert_eq!(report.deliveries.len(), 2);
+        assert!(report.deliveries[0].delivered());
+        assert!(share.join("output.csv").exists());
+        assert!(!report.deliveries[1].delivered());
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].starts_with("Delivery of"));
+    }
+
+    #[test]
+    fn test_path_policy_deny_rule_blocks_pipeline_output() {
+        use crate::config::policy::PathRule;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let input = dir.path().join("input.csv");
+        let blocked = dir.path().join("blocked");
+        std::fs::create_dir(&blocked).unwrap();
+        std::fs::write(&input, "id\n1\n2\n").unwrap();
+        let policy = PathPolicy::new(vec![PathRule::deny(
+            blocked.to_string_lossy(),
+            &[Capability::Write],
+        )]);
+        let spec = PipelineSpec::new("blocked");
+        let run = |spec: &PipelineSpec, output: &Path| {
+            execute_pipeline(
+                spec,
+                &input,
+                &HashMap::new(),
+                Some(output),
+                &policy,
+                &mut |_| {},
+            )
+        };
+
+        let err = run(&spec, &blocked.join("output.csv")).unwrap_err();
+        assert!(format!("{err:#}").contains("blocked for write"));
+        assert!(!blocked.join("output.csv").exists());
+
+        // An intermediate output in a denied folder stops the run before the
+        // final output is written
+        let mut spec = spec.clone();
+        spec.steps.push(
+            Step::WriteOutput {
+                path_template: blocked.join("partial.csv").to_string_lossy().into_owned(),
+                format: None,
+            }
+            .into(),
+        );
+        let output = dir.path().join("output.csv");
+        assert!(run(&spec, &output).is_err());
+        assert!(!output.exists());
+
+        spec.steps.clear();
+        let report = run(&spec, &output).unwrap();
+        assert_eq!(report.rows_after, 2);
+    }
+
+    #[test]
+    fn test_apply_step_concat_and_union() {
+        let main = df!("id" => [1i64, 2], "name" => ["a", "b"]).unwrap();
+        let march = df!("name" => ["b", "c"], "id" => [2i64, 3]).unwrap();
+        let sources = HashMap::from([("march".to_owned(), march.lazy())]);
+
+        let concat_step = Step::Concat {
+            inputs: vec!["march".to_owned()],
+        };
+        let stacked = apply_step_with_inputs(&concat_step, main.clone().lazy(), &sources)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(stacked.height(), 4);
+        assert_eq!(stacked.column("id").unwrap().i64().unwrap().get(2), Some(2));
+
+        let union_step = Step::Union {
+            inputs: vec!["march".to_owned()],
+        };
+        let unioned = apply_step_with_inputs(&union_step, main.clone().lazy(), &sources)
+            .unwrap()
+            .collect()
+            .unwrap();
+        assert_eq!(unioned.height(), 3);
+
+        let missing = Step::Concat {
+            inputs: vec!["april".to_owned()],
+        };
+        assert!(apply_step_with_inputs(&missing, main.lazy(), &sources).is_err());
+    }
+
     #[test]
     fn test_run_report_summary() {
         let report = RunReport {
@@ -679,7 +2585,14 @@ mod tests {
             columns_before: 10,
             columns_after: 8,
             steps_applied: 5,
+            rows_rejected: 0,
+            output_path: PathBuf::from("out.parquet"),
+            reject_path: None,
+            extra_outputs: vec![],
+            resumed_after_step: None,
             warnings: vec![],
+            deliveries: vec![],
+            steps: vec![],
             duration: std::time::Duration::from_secs(2),
         };
 
@@ -698,7 +2611,14 @@ mod tests {
             columns_before: 10,
             columns_after: 10,
             steps_applied: 3,
+            rows_rejected: 0,
+            output_path: PathBuf::from("out.parquet"),
+            reject_path: None,
+            extra_outputs: vec![],
+            resumed_after_step: None,
             warnings: vec![],
+            deliveries: vec![],
+            steps: vec![],
             duration: std::time::Duration::from_millis(500),
         };
 
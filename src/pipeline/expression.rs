@@ -0,0 +1,867 @@
+//! Expression language for derived columns.
+//!
+//! `with_column` steps describe new columns with a small, spreadsheet-like
+//! syntax that is compiled to Polars expressions:
+//!
+//! - Arithmetic: `price * quantity - discount`, `total / 100`, `id % 2`
+//! - String concatenation: `first_name & " " & last_name`
+//! - Comparison and logic: `age >= 18 and not is_blocked`
+//! - Conditionals: `if(score > 50, "pass", "fail")`
+//! - Date differences: `date_diff(shipped_at, ordered_at, "hours")`
+//! - Helpers: `abs(x)`, `round(x, 2)`, `coalesce(a, b, 0)`, `concat(a, "-", b)`
+//!
+//! Column names containing spaces or symbols can be quoted with backticks,
+//! e.g. `` `Order Total` * 1.2 ``.
+//!
+//! [`compile`] checks operands against the schema, so `name * 2` on a text
+//! column is refused before anything runs. Expressions nested more than
+//! [`MAX_DEPTH`] levels are refused too, as they are compiled recursively.
+
+use anyhow::{Result, anyhow, bail};
+use polars::prelude::*;
+use std::collections::HashSet;
+
+/// Deepest nesting of parentheses, calls and operators accepted
+pub const MAX_DEPTH: usize = 100;
+
+/// Parsed expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Ast {
+    Column(String),
+    Integer(i64),
+    Float(f64),
+    Str(String),
+    Bool(bool),
+    Null,
+    Negate(Box<Ast>),
+    Not(Box<Ast>),
+    Binary(BinaryOp, Box<Ast>, Box<Ast>),
+    Call(Function, Vec<Ast>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Concat,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Function {
+    If,
+    Concat,
+    DateDiff,
+    Abs,
+    Round,
+    Coalesce,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "if" => Some(Self::If),
+            "concat" => Some(Self::Concat),
+            "date_diff" => Some(Self::DateDiff),
+            "abs" => Some(Self::Abs),
+            "round" => Some(Self::Round),
+            "coalesce" => Some(Self::Coalesce),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::If => "if",
+            Self::Concat => "concat",
+            Self::DateDiff => "date_diff",
+            Self::Abs => "abs",
+            Self::Round => "round",
+            Self::Coalesce => "coalesce",
+        }
+    }
+
+    /// Accepted argument counts (inclusive).
+    fn arity(self) -> (usize, usize) {
+        match self {
+            Self::If => (3, 3),
+            Self::Concat | Self::Coalesce => (1, usize::MAX),
+            Self::DateDiff => (2, 3),
+            Self::Abs => (1, 1),
+            Self::Round => (1, 2),
+        }
+    }
+}
+
+impl Ast {
+    /// Column names referenced anywhere in the expression.
+    pub fn columns(&self) -> HashSet<String> {
+        let mut columns = HashSet::new();
+        self.collect_columns(&mut columns);
+        columns
+    }
+
+    fn collect_columns(&self, columns: &mut HashSet<String>) {
+        match self {
+            Self::Column(name) => {
+                columns.insert(name.clone());
+            }
+            Self::Negate(inner) | Self::Not(inner) => inner.collect_columns(columns),
+            Self::Binary(_, lhs, rhs) => {
+                lhs.collect_columns(columns);
+                rhs.collect_columns(columns);
+            }
+            Self::Call(_, args) => {
+                for arg in args {
+                    arg.collect_columns(columns);
+                }
+            }
+            Self::Integer(_) | Self::Float(_) | Self::Str(_) | Self::Bool(_) | Self::Null => {}
+        }
+    }
+
+    /// Convert to a Polars expression.
+    pub fn to_expr(&self) -> Result<Expr> {
+        Ok(match self {
+            Self::Column(name) => col(name.as_str()),
+            Self::Integer(v) => lit(*v),
+            Self::Float(v) => lit(*v),
+            Self::Str(v) => lit(v.as_str()),
+            Self::Bool(v) => lit(*v),
+            Self::Null => lit(NULL),
+            Self::Negate(inner) => lit(0) - inner.to_expr()?,
+            Self::Not(inner) => inner.to_expr()?.not(),
+            Self::Binary(op, lhs, rhs) => {
+                let (l, r) = (lhs.to_expr()?, rhs.to_expr()?);
+                match op {
+                    BinaryOp::Add => l + r,
+                    BinaryOp::Subtract => l - r,
+                    BinaryOp::Multiply => l * r,
+                    BinaryOp::Divide => binary_expr(l, Operator::TrueDivide, r),
+                    BinaryOp::Modulo => l % r,
+                    BinaryOp::Concat => concat_strings(vec![l, r]),
+                    BinaryOp::Eq => l.eq(r),
+                    BinaryOp::NotEq => l.neq(r),
+                    BinaryOp::Lt => l.lt(r),
+                    BinaryOp::LtEq => l.lt_eq(r),
+                    BinaryOp::Gt => l.gt(r),
+                    BinaryOp::GtEq => l.gt_eq(r),
+                    BinaryOp::And => l.and(r),
+                    BinaryOp::Or => l.or(r),
+                }
+            }
+            Self::Call(function, args) => call_to_expr(*function, args)?,
+        })
+    }
+}
+
+/// What an expression evaluates to, as far as type checking needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValueKind {
+    Number,
+    Text,
+    Bool,
+    Temporal,
+    /// A null literal, or a column type the checks do not look at
+    Any,
+}
+
+impl ValueKind {
+    fn of(dtype: &DataType) -> Self {
+        match dtype {
+            d if d.is_numeric() => Self::Number,
+            DataType::String | DataType::Categorical(_, _) => Self::Text,
+            DataType::Boolean => Self::Bool,
+            DataType::Date | DataType::Datetime(_, _) | DataType::Time | DataType::Duration(_) => {
+                Self::Temporal
+            }
+            _ => Self::Any,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Number => "a number",
+            Self::Text => "text",
+            Self::Bool => "a boolean",
+            Self::Temporal => "a date",
+            Self::Any => "a value",
+        }
+    }
+}
+
+impl Ast {
+    fn describe(&self) -> String {
+        match self {
+            Self::Column(name) => format!("column '{name}'"),
+            Self::Str(v) => format!("\"{v}\""),
+            _ => "an expression".to_owned(),
+        }
+    }
+
+    /// Check operand types against `schema`, returning the kind of value
+    fn check_types(&self, schema: &Schema) -> Result<ValueKind> {
+        let expect = |ast: &Ast, context: &str, allowed: &[ValueKind]| -> Result<ValueKind> {
+            let kind = ast.check_types(schema)?;
+            if kind != ValueKind::Any && !allowed.contains(&kind) {
+                bail!(
+                    "{context} cannot use {}, which is {}",
+                    ast.describe(),
+                    kind.name()
+                );
+            }
+            Ok(kind)
+        };
+        let numeric = [ValueKind::Number, ValueKind::Bool];
+        Ok(match self {
+            Self::Column(name) => schema.get(name).map_or(ValueKind::Any, ValueKind::of),
+            Self::Integer(_) | Self::Float(_) => ValueKind::Number,
+            Self::Str(_) => ValueKind::Text,
+            Self::Bool(_) => ValueKind::Bool,
+            Self::Null => ValueKind::Any,
+            Self::Negate(inner) => expect(inner, "'-'", &numeric)?,
+            Self::Not(inner) => {
+                inner.check_types(schema)?;
+                ValueKind::Bool
+            }
+            Self::Binary(op, lhs, rhs) => {
+                let symbol = match op {
+                    BinaryOp::Add => "+",
+                    BinaryOp::Subtract => "-",
+                    BinaryOp::Multiply => "*",
+                    BinaryOp::Divide => "/",
+                    BinaryOp::Modulo => "%",
+                    _ => {
+                        lhs.check_types(schema)?;
+                        rhs.check_types(schema)?;
+                        return Ok(if *op == BinaryOp::Concat {
+                            ValueKind::Text
+                        } else {
+                            ValueKind::Bool
+                        });
+                    }
+                };
+                let context = format!("'{symbol}'");
+                // Dates can be moved and subtracted, not multiplied
+                let allowed: &[ValueKind] = if matches!(op, BinaryOp::Add | BinaryOp::Subtract) {
+                    &[ValueKind::Number, ValueKind::Bool, ValueKind::Temporal]
+                } else {
+                    &numeric
+                };
+                let l = expect(lhs, &context, allowed)?;
+                let r = expect(rhs, &context, allowed)?;
+                if l == ValueKind::Temporal || r == ValueKind::Temporal {
+                    ValueKind::Any
+                } else {
+                    ValueKind::Number
+                }
+            }
+            Self::Call(function, args) => {
+                let context = format!("{}()", function.name());
+                let kinds = args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, arg)| match function {
+                        Function::Abs | Function::Round if i == 0 => {
+                            expect(arg, &context, &numeric)
+                        }
+                        // Text is parsed as dates
+                        Function::DateDiff if i < 2 => {
+                            expect(arg, &context, &[ValueKind::Temporal, ValueKind::Text])
+                        }
+                        _ => arg.check_types(schema),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                match function {
+                    Function::Abs => kinds.first().copied().unwrap_or(ValueKind::Any),
+                    Function::Round | Function::DateDiff => ValueKind::Number,
+                    Function::Concat => ValueKind::Text,
+                    Function::If => kinds.get(1).copied().unwrap_or(ValueKind::Any),
+                    Function::Coalesce => kinds
+                        .into_iter()
+                        .find(|k| *k != ValueKind::Any)
+                        .unwrap_or(ValueKind::Any),
+                }
+            }
+        })
+    }
+
+    /// Levels of nesting, counted without recursion
+    fn depth(&self) -> usize {
+        let mut deepest = 0;
+        let mut stack = vec![(self, 1)];
+        while let Some((ast, depth)) = stack.pop() {
+            deepest = deepest.max(depth);
+            match ast {
+                Self::Negate(inner) | Self::Not(inner) => stack.push((inner, depth + 1)),
+                Self::Binary(_, lhs, rhs) => {
+                    stack.push((lhs, depth + 1));
+                    stack.push((rhs, depth + 1));
+                }
+                Self::Call(_, args) => stack.extend(args.iter().map(|arg| (arg, depth + 1))),
+                _ => {}
+            }
+        }
+        deepest
+    }
+}
+
+fn call_to_expr(function: Function, args: &[Ast]) -> Result<Expr> {
+    let exprs = args.iter().map(Ast::to_expr).collect::<Result<Vec<_>>>()?;
+    match (function, exprs.as_slice()) {
+        (Function::If, [cond, then, otherwise]) => Ok(when(cond.clone())
+            .then(then.clone())
+            .otherwise(otherwise.clone())),
+        (Function::Concat, all) => Ok(concat_strings(all.to_vec())),
+        (Function::DateDiff, [end, start, ..]) => {
+            let unit = match args.get(2) {
+                None => "days",
+                Some(Ast::Str(unit)) => unit.as_str(),
+                Some(_) => return Err(anyhow!("date_diff unit must be a string literal")),
+            };
+            let millis = match unit {
+                "days" => 86_400_000.0,
+                "hours" => 3_600_000.0,
+                "minutes" => 60_000.0,
+                "seconds" => 1_000.0,
+                other => {
+                    return Err(anyhow!(
+                        "Unknown date_diff unit '{other}' (expected days, hours, minutes or seconds)"
+                    ));
+                }
+            };
+            Ok(binary_expr(
+                epoch_millis(end.clone()) - epoch_millis(start.clone()),
+                Operator::TrueDivide,
+                lit(millis),
+            ))
+        }
+        (Function::Abs, [value]) => Ok(value.clone().abs()),
+        (Function::Round, [value]) => Ok(value.clone().round(0)),
+        (Function::Round, [value, _]) => {
+            let decimals = match args.get(1) {
+                Some(Ast::Integer(decimals)) => u32::try_from(*decimals).ok(),
+                _ => None,
+            };
+            let decimals =
+                decimals.ok_or_else(|| anyhow!("round decimals must be a non-negative integer"))?;
+            Ok(value.clone().round(decimals))
+        }
+        (Function::Coalesce, _) => Ok(coalesce(&exprs)),
+        _ => Err(anyhow!(
+            "Wrong number of arguments for {}()",
+            function.name()
+        )),
+    }
+}
+
+fn concat_strings(exprs: Vec<Expr>) -> Expr {
+    let exprs: Vec<Expr> = exprs
+        .into_iter()
+        .map(|e| e.cast(DataType::String))
+        .collect();
+    concat_str(exprs, "", false)
+}
+
+fn epoch_millis(expr: Expr) -> Expr {
+    expr.cast(DataType::Datetime(TimeUnit::Milliseconds, None))
+        .cast(DataType::Int64)
+}
+
+/// Parse an expression string into an [`Ast`].
+pub fn parse(source: &str) -> Result<Ast> {
+    let tokens = tokenize(source)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        depth: 0,
+    };
+    let ast = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        bail!("Unexpected {token} after end of expression");
+    }
+    // Chains such as `a + a + ...` are parsed in a loop, so are only
+    // measured once built
+    if ast.depth() > MAX_DEPTH {
+        bail!("Expression is nested too deeply (more than {MAX_DEPTH} levels)");
+    }
+    Ok(ast)
+}
+
+/// Parse `source` and check that every referenced column is in `available`.
+pub fn validate(source: &str, available: &HashSet<String>) -> Result<Ast> {
+    let ast = parse(source)?;
+    let mut missing: Vec<String> = ast
+        .columns()
+        .into_iter()
+        .filter(|c| !available.contains(c))
+        .collect();
+    missing.sort();
+    if missing.is_empty() {
+        Ok(ast)
+    } else {
+        Err(anyhow!("Unknown column(s): {}", missing.join(", ")))
+    }
+}
+
+/// Parse and compile `source` against `schema`, checking operand types.
+pub fn compile(source: &str, schema: &Schema) -> Result<Expr> {
+    let available: HashSet<String> = schema.iter_names().map(|n| n.to_string()).collect();
+    let ast = validate(source, &available)?;
+    ast.check_types(schema)?;
+    ast.to_expr()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    Integer(i64),
+    Float(f64),
+    Str(String),
+    Symbol(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(name) => write!(f, "'{name}'"),
+            Self::QuotedIdent(name) => write!(f, "`{name}`"),
+            Self::Integer(v) => write!(f, "{v}"),
+            Self::Float(v) => write!(f, "{v}"),
+            Self::Str(v) => write!(f, "\"{v}\""),
+            Self::Symbol(s) => write!(f, "'{s}'"),
+        }
+    }
+}
+
+const SYMBOLS: [&str; 16] = [
+    "==", "!=", "<=", ">=", "<", ">", "=", "+", "-", "*", "/", "%", "&", "(", ")", ",",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = source.chars().collect();
+    let mut i = 0;
+    while let Some(&c) = chars.get(i) {
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '"' || c == '\'' || c == '`' {
+            let start = i;
+            i += 1;
+            let mut text = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err(anyhow!("Unterminated quote starting at position {start}")),
+                    Some('\\') if c != '`' => {
+                        if let Some(&escaped) = chars.get(i + 1) {
+                            text.push(escaped);
+                        }
+                        i += 2;
+                    }
+                    Some(&q) if q == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        text.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(if c == '`' {
+                Token::QuotedIdent(text)
+            } else {
+                Token::Str(text)
+            });
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|ch| ch.is_ascii_digit() || *ch == '.')
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            tokens.push(if text.contains('.') {
+                Token::Float(
+                    text.parse()
+                        .map_err(|_| anyhow!("Invalid number '{text}'"))?,
+                )
+            } else {
+                Token::Integer(
+                    text.parse()
+                        .map_err(|_| anyhow!("Invalid number '{text}'"))?,
+                )
+            });
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|ch| ch.is_alphanumeric() || *ch == '_')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = SYMBOLS
+                .into_iter()
+                .find(|s| rest.starts_with(*s))
+                .ok_or_else(|| anyhow!("Unexpected character '{c}' at position {i}"))?;
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive-descent parser. Precedence, lowest first: `or`, `and`, `not`,
+/// comparisons, `+ - &`, `* / %`, unary minus.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Current recursion depth, bounded by [`MAX_DEPTH`] so deeply nested
+    /// input cannot overflow the stack
+    depth: usize,
+}
+
+impl Parser {
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= MAX_DEPTH {
+            bail!("Expression is nested too deeply (more than {MAX_DEPTH} levels)");
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(s)) if s.eq_ignore_ascii_case(keyword)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Ast> {
+        let mut lhs = self.parse_and()?;
+        while self.eat_keyword("or") {
+            let rhs = self.parse_and()?;
+            lhs = Ast::Binary(BinaryOp::Or, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Ast> {
+        let mut lhs = self.parse_not()?;
+        while self.eat_keyword("and") {
+            let rhs = self.parse_not()?;
+            lhs = Ast::Binary(BinaryOp::And, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_not(&mut self) -> Result<Ast> {
+        if self.eat_keyword("not") {
+            Ok(Ast::Not(Box::new(self.nested(Self::parse_not)?)))
+        } else {
+            self.parse_comparison()
+        }
+    }
+
+    fn parse_comparison(&mut self) -> Result<Ast> {
+        let lhs = self.parse_additive()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("==" | "=")) => BinaryOp::Eq,
+            Some(Token::Symbol("!=")) => BinaryOp::NotEq,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::LtEq,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::GtEq,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_additive()?;
+        Ok(Ast::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_additive(&mut self) -> Result<Ast> {
+        let mut lhs = self.parse_term()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinaryOp::Add
+            } else if self.eat_symbol("-") {
+                BinaryOp::Subtract
+            } else if self.eat_symbol("&") {
+                BinaryOp::Concat
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.parse_term()?;
+            lhs = Ast::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<Ast> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinaryOp::Multiply
+            } else if self.eat_symbol("/") {
+                BinaryOp::Divide
+            } else if self.eat_symbol("%") {
+                BinaryOp::Modulo
+            } else {
+                return Ok(lhs);
+            };
+            let rhs = self.parse_unary()?;
+            lhs = Ast::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Ast> {
+        if self.eat_symbol("-") {
+            Ok(match self.nested(Self::parse_unary)? {
+                Ast::Integer(v) => Ast::Integer(-v),
+                Ast::Float(v) => Ast::Float(-v),
+                other => Ast::Negate(Box::new(other)),
+            })
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Ast> {
+        match self.next() {
+            None => Err(anyhow!("Unexpected end of expression")),
+            Some(Token::Integer(v)) => Ok(Ast::Integer(v)),
+            Some(Token::Float(v)) => Ok(Ast::Float(v)),
+            Some(Token::Str(v)) => Ok(Ast::Str(v)),
+            Some(Token::QuotedIdent(name)) => Ok(Ast::Column(name)),
+            Some(Token::Symbol("(")) => {
+                let inner = self.nested(Self::parse_or)?;
+                if !self.eat_symbol(")") {
+                    return Err(anyhow!("Missing closing parenthesis"));
+                }
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => {
+                if self.eat_symbol("(") {
+                    return self.nested(|parser| parser.parse_call(&name));
+                }
+                Ok(match name.to_lowercase().as_str() {
+                    "true" => Ast::Bool(true),
+                    "false" => Ast::Bool(false),
+                    "null" => Ast::Null,
+                    _ => Ast::Column(name),
+                })
+            }
+            Some(token) => Err(anyhow!("Unexpected {token}")),
+        }
+    }
+
+    fn parse_call(&mut self, name: &str) -> Result<Ast> {
+        let function =
+            Function::from_name(name).ok_or_else(|| anyhow!("Unknown function '{name}'"))?;
+        let mut args = Vec::new();
+        if !self.eat_symbol(")") {
+            loop {
+                args.push(self.parse_or()?);
+                if self.eat_symbol(")") {
+                    break;
+                }
+                if !self.eat_symbol(",") {
+                    return Err(anyhow!("Expected ',' or ')' in call to {name}()"));
+                }
+            }
+        }
+        let (min, max) = function.arity();
+        if args.len() < min || args.len() > max {
+            return Err(anyhow!(
+                "{}() takes {} argument(s), got {}",
+                function.name(),
+                if min == max {
+                    min.to_string()
+                } else if max == usize::MAX {
+                    format!("at least {min}")
+                } else {
+                    format!("{min} to {max}")
+                },
+                args.len()
+            ));
+        }
+        Ok(Ast::Call(function, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str) -> DataFrame {
+        let df = df!(
+            "price" => [10.0, 20.0, 30.0],
+            "qty" => [1, 2, 3],
+            "first" => ["Ada", "Alan", "Grace"],
+            "last" => ["Lovelace", "Turing", "Hopper"],
+            "Order Date" => ["2024-01-01", "2024-01-02", "2024-01-05"],
+            "Ship Date" => ["2024-01-03", "2024-01-02", "2024-01-06"],
+        )
+        .unwrap();
+        let mut lf = df.lazy();
+        let schema = lf.collect_schema().unwrap();
+        let expr = compile(source, &schema).unwrap();
+        lf.select([expr.alias("out")]).collect().unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic_precedence() {
+        let out = eval("price * qty + 1");
+        let values: Vec<_> = out
+            .column("out")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values, vec![11.0, 41.0, 91.0]);
+
+        let out = eval("qty / 2");
+        let values: Vec<_> = out
+            .column("out")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values, vec![0.5, 1.0, 1.5]);
+    }
+
+    #[test]
+    fn test_concat_and_conditional() {
+        let out = eval(r#"first & " " & last"#);
+        let out = out.column("out").unwrap();
+        assert_eq!(out.str().unwrap().get(0), Some("Ada Lovelace"));
+
+        let out = eval(r#"if(price >= 20 and qty > 2, "big", "small")"#);
+        let values: Vec<_> = out
+            .column("out")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values, vec!["small", "small", "big"]);
+    }
+
+    #[test]
+    fn test_date_diff_with_quoted_columns() {
+        let out = eval("date_diff(`Ship Date`, `Order Date`)");
+        let values: Vec<_> = out
+            .column("out")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(values, vec![2.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("price *").is_err());
+        assert!(parse("(price + 1").is_err());
+        assert!(parse("unknown_fn(price)").is_err());
+        assert!(parse("if(price > 1, 1)").is_err());
+        assert!(parse("\"unterminated").is_err());
+        assert!(parse("price $ 2").is_err());
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_columns() {
+        let available: HashSet<String> = ["price".to_owned()].into();
+        assert!(validate("price * 2", &available).is_ok());
+        let err = validate("price * qty", &available).unwrap_err();
+        assert!(err.to_string().contains("qty"));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_an_error() {
+        let parens = format!("{}1{}", "(".repeat(10_000), ")".repeat(10_000));
+        assert!(
+            parse(&parens)
+                .unwrap_err()
+                .to_string()
+                .contains("too deeply")
+        );
+        assert!(parse(&"not ".repeat(10_000)).is_err());
+        assert!(parse(&"-".repeat(10_000)).is_err());
+        let chain = vec!["1"; 10_000].join(" + ");
+        assert!(
+            parse(&chain)
+                .unwrap_err()
+                .to_string()
+                .contains("too deeply")
+        );
+        assert!(parse(&format!("{}1{}", "(".repeat(50), ")".repeat(50))).is_ok());
+    }
+
+    #[test]
+    fn test_compile_checks_operand_types() {
+        let schema = Schema::from_iter([
+            Field::new("name".into(), DataType::String),
+            Field::new("price".into(), DataType::Float64),
+            Field::new(
+                "shipped".into(),
+                DataType::Datetime(TimeUnit::Milliseconds, None),
+            ),
+        ]);
+        let err = compile("name * 2", &schema).unwrap_err();
+        assert!(err.to_string().contains("column 'name'"), "{err}");
+        assert!(compile("-name", &schema).is_err());
+        assert!(compile("abs(name)", &schema).is_err());
+        assert!(compile("round(name, 1)", &schema).is_err());
+        assert!(compile("date_diff(price, shipped)", &schema).is_err());
+        assert!(compile("(price & \"x\") + 1", &schema).is_err());
+
+        assert!(compile("price * 2 + 1", &schema).is_ok());
+        assert!(compile("date_diff(shipped, \"2024-01-01\")", &schema).is_ok());
+        assert!(compile("if(price > 1, name, \"cheap\")", &schema).is_ok());
+        assert!(compile("round(price, -1)", &schema).is_err());
+        assert!(compile("round(price, 5000000000)", &schema).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+//! Running external commands as a pipeline step.
+//!
+//! The current data is handed to the command either as a temporary Parquet/CSV
+//! file or as CSV on stdin, and the command's output is read back as the new
+//! pipeline data. Commands only run once the security warning has been
+//! acknowledged, and only from a folder the path policy trusts for execution.
+
+use super::spec::ExternalDataFormat;
+use crate::config::AppSettings;
+use crate::config::policy::{Capability, PathDecision, PathPolicy, denied};
+use crate::utils::TempFileGuard;
+use anyhow::{Context as _, Result, anyhow, bail};
+use polars::prelude::*;
+use std::io::{Cursor, Write as _};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use uuid::Uuid;
+
+/// Placeholder in `args` replaced with the path of the data handed to the command
+pub const INPUT_PLACEHOLDER: &str = "{input}";
+
+/// Placeholder in `args` replaced with the path the command must write its result to
+pub const OUTPUT_PLACEHOLDER: &str = "{output}";
+
+/// Check that `command` may be run under `settings`, returning its resolved path.
+///
+/// The command must be a path to an existing file that the path policy
+/// allows to execute; bare program names are not looked up on `PATH`.
+pub fn authorise_command(command: &str, settings: &AppSettings) -> Result<PathBuf> {
+    if !settings.security_warning_acknowledged {
+        bail!(
+            "Security warning not acknowledged. Please confirm before running external commands."
+        );
+    }
+
+    let path = Path::new(command.trim());
+    if !path.is_file() {
+        bail!("External command not found: {command}");
+    }
+    let resolved = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve external command: {command}"))?;
+
+    match PathPolicy::from_settings(settings).evaluate(&resolved, Capability::Execute) {
+        PathDecision::Allowed { .. } => {}
+        PathDecision::Denied { pattern } => {
+            return Err(denied(format!(
+                "External command {} is blocked by rule '{pattern}'",
+                resolved.display()
+            )));
+        }
+        PathDecision::NotTrusted => {
+            return Err(denied(format!(
+                "External command {} is outside trusted folders",
+                resolved.display()
+            )));
+        }
+    }
+
+    Ok(resolved)
+}
+
+/// Run `command` over the data in `lf` and return its output.
+///
+/// For file formats, `{input}` and `{output}` in `args` are replaced with
+/// temporary file paths; when absent, the paths are appended in that order.
+/// With [`ExternalDataFormat::Stdin`] the data is piped as CSV and the
+/// command's stdout is parsed as CSV.
+pub fn run_external(
+    lf: LazyFrame,
+    command: &str,
+    args: &[String],
+    format: ExternalDataFormat,
+) -> Result<LazyFrame> {
+    let config = crate::config::load_app_config();
+    let program = authorise_command(command, &config.settings)?;
+    crate::config::log_event(
+        "External Command",
+        &format!("Running {} {}", program.display(), args.join(" ")),
+    );
+
+    let mut df = lf
+        .collect()
+        .context("Failed to collect data for external command")?;
+
+    match format {
+        ExternalDataFormat::Stdin => run_with_stdin(&program, args, &mut df),
+        ExternalDataFormat::Parquet | ExternalDataFormat::Csv => {
+            run_with_files(&program, args, &mut df, format)
+        }
+    }
+}
+
+fn run_with_files(
+    program: &Path,
+    args: &[String],
+    df: &mut DataFrame,
+    format: ExternalDataFormat,
+) -> Result<LazyFrame> {
+    let ext = if matches!(format, ExternalDataFormat::Csv) {
+        "csv"
+    } else {
+        "parquet"
+    };
+    let id = Uuid::new_v4();
+    let temp_dir = std::env::temp_dir();
+    let input = TempFileGuard::new(temp_dir.join(format!("beefcake_external_in_{id}.{ext}")));
+    let output = TempFileGuard::new(temp_dir.join(format!("beefcake_external_out_{id}.{ext}")));
+    let input_path = input.path().unwrap_or_else(|| Path::new(""));
+    let output_path = output.path().unwrap_or_else(|| Path::new(""));
+
+    crate::analyser::logic::save_df(df, input_path)
+        .context("Failed to write data for external command")?;
+
+    let input_str = input_path.to_string_lossy();
+    let output_str = output_path.to_string_lossy();
+    let mut full_args: Vec<String> = args
+        .iter()
+        .map(|a| {
+            a.replace(INPUT_PLACEHOLDER, &input_str)
+                .replace(OUTPUT_PLACEHOLDER, &output_str)
+        })
+        .collect();
+    if !args.iter().any(|a| a.contains(INPUT_PLACEHOLDER)) {
+        full_args.push(input_str.to_string());
+    }
+    if !args.iter().any(|a| a.contains(OUTPUT_PLACEHOLDER)) {
+        full_args.push(output_str.to_string());
+    }
+
+    let result = Command::new(program)
+        .args(&full_args)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Failed to start external command: {}", program.display()))?;
+    check_status(program, &result)?;
+
+    if !output_path.exists() {
+        bail!(
+            "External command {} did not write its output file",
+            program.display()
+        );
+    }
+
+    // Read eagerly so the temp files can be removed when the guards drop
+    crate::analyser::logic::load_df_lazy(output_path)?
+        .collect()
+        .context("Failed to read external command output")
+        .map(DataFrame::lazy)
+}
+
+fn run_with_stdin(program: &Path, args: &[String], df: &mut DataFrame) -> Result<LazyFrame> {
+    let mut payload = Vec::new();
+    CsvWriter::new(&mut payload)
+        .include_header(true)
+        .finish(df)
+        .context("Failed to encode data for external command")?;
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start external command: {}", program.display()))?;
+
+    // Write from a separate thread so a command that streams its output
+    // before reading all of stdin cannot deadlock against us
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open stdin for external command"))?;
+    let writer = std::thread::spawn(move || stdin.write_all(&payload));
+
+    let result = child
+        .wait_with_output()
+        .context("Failed to wait for external command")?;
+    check_status(program, &result)?;
+    // A command may exit without reading all of its input; that is not an error
+    let _ = writer.join();
+
+    CsvReadOptions::default()
+        .with_has_header(true)
+        .with_infer_schema_length(Some(10000))
+        .into_reader_with_file_handle(Cursor::new(result.stdout))
+        .finish()
+        .context("Failed to parse external command output as CSV")
+        .map(DataFrame::lazy)
+}
+
+fn check_status(program: &Path, result: &std::process::Output) -> Result<()> {
+    if result.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    bail!(
+        "External command {} failed ({}): {}",
+        program.display(),
+        result.status,
+        stderr.trim()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorise_command_requires_acknowledgement_and_trusted_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("tool.sh");
+        std::fs::write(&tool, "#!/bin/sh\n").unwrap();
+        let tool_str = tool.to_string_lossy().to_string();
+
+        let mut settings = AppSettings {
+            trusted_paths: vec![dir.path().to_string_lossy().to_string()],
+            ..AppSettings::default()
+        };
+        let err = authorise_command(&tool_str, &settings).unwrap_err();
+        assert!(err.to_string().contains("not acknowledged"));
+
+        settings.security_warning_acknowledged = true;
+        assert!(authorise_command(&tool_str, &settings).is_ok());
+        assert!(authorise_command("definitely-not-a-file", &settings).is_err());
+
+        settings.trusted_paths.clear();
+        let err = authorise_command(&tool_str, &settings).unwrap_err();
+        assert!(err.to_string().contains("outside trusted folders"));
+    }
+}
@@ -0,0 +1,312 @@
+//! Persistent history of pipeline runs.
+//!
+//! Every run is appended as one JSON line to `history/pipeline_runs.jsonl`
+//! under the app data directory, recording the spec (by name and content
+//! hash), input, output, timestamps and the full [`RunReport`] or error.
+//! Appending a line is atomic enough for a single desktop user, and the file
+//! stays readable with standard tools.
+//!
+//! [`RunReport`]: super::executor::RunReport
+
+use super::executor::RunReport;
+use super::spec::PipelineSpec;
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest as _, Sha256};
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+/// Outcome of a recorded run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+/// One pipeline run as stored in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    /// Spec name at the time of the run
+    pub pipeline: String,
+    /// SHA-256 of the spec's canonical JSON, to tell edited specs apart
+    pub spec_hash: String,
+    pub input_path: PathBuf,
+    /// Final output file (absent when the run failed)
+    pub output_path: Option<PathBuf>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub status: RunStatus,
+    /// Error chain of a failed run
+    pub error: Option<String>,
+    pub warnings: Vec<String>,
+    /// The serialized [`RunReport`] of a successful run
+    pub report: Option<Value>,
+    /// Set when the run read a lifecycle dataset rather than a file
+    #[serde(default)]
+    pub dataset: Option<DatasetRun>,
+}
+
+/// The lifecycle dataset a run read, for runs started with
+/// [`run_pipeline_on_dataset`](super::dataset::run_pipeline_on_dataset)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetRun {
+    pub dataset_id: Uuid,
+    /// The version that was active when the run started
+    pub version_id: Uuid,
+    /// Version registered from the output, if the run was asked to
+    pub output_version_id: Option<Uuid>,
+}
+
+impl RunRecord {
+    /// Build the record of running `spec` on `input_path`
+    pub fn new(
+        spec: &PipelineSpec,
+        input_path: &Path,
+        started_at: DateTime<Utc>,
+        result: &Result<RunReport>,
+    ) -> Self {
+        let mut record = Self {
+            id: Uuid::new_v4().to_string(),
+            pipeline: spec.name.clone(),
+            spec_hash: spec_hash(spec),
+            input_path: input_path.to_path_buf(),
+            output_path: None,
+            started_at,
+            finished_at: Utc::now(),
+            status: RunStatus::Succeeded,
+            error: None,
+            warnings: Vec::new(),
+            report: None,
+            dataset: None,
+        };
+        match result {
+            Ok(report) => {
+                record.output_path = Some(report.output_path.clone());
+                record.warnings.clone_from(&report.warnings);
+                record.report = serde_json::to_value(report).ok();
+            }
+            Err(e) => {
+                record.status = RunStatus::Failed;
+                record.error = Some(format!("{e:#}"));
+            }
+        }
+        record
+    }
+}
+
+/// Filters for [`HistoryStore::list`]; unset fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Spec name
+    pub pipeline: Option<String>,
+    pub spec_hash: Option<String>,
+    pub status: Option<RunStatus>,
+    /// Only runs on this lifecycle dataset
+    pub dataset_id: Option<Uuid>,
+    /// Only runs started at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Most recent runs to return
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, record: &RunRecord) -> bool {
+        self.pipeline.as_ref().is_none_or(|p| *p == record.pipeline)
+            && self
+                .spec_hash
+                .as_ref()
+                .is_none_or(|h| *h == record.spec_hash)
+            && self.status.is_none_or(|s| s == record.status)
+            && self
+                .dataset_id
+                .is_none_or(|id| record.dataset.is_some_and(|d| d.dataset_id == id))
+            && self.since.is_none_or(|t| record.started_at >= t)
+    }
+}
+
+/// Append-only JSONL store of [`RunRecord`]s
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+}
+
+impl HistoryStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The store in the app data directory
+    pub fn open_default() -> Self {
+        Self::new(
+            crate::utils::app_data_dir()
+                .join("history")
+                .join("pipeline_runs.jsonl"),
+        )
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, record: &RunRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let mut line = serde_json::to_string(record).context("Failed to serialize run record")?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to append to {}", self.path.display()))
+    }
+
+    /// Runs matching `query`, most recent first. Unreadable lines (e.g. from
+    /// an interrupted write) are skipped.
+    pub fn list(&self, query: &HistoryQuery) -> Result<Vec<RunRecord>> {
+        let mut records: Vec<RunRecord> = self
+            .read_all()?
+            .into_iter()
+            .filter(|r| query.matches(r))
+            .collect();
+        records.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        if let Some(limit) = query.limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<RunRecord>> {
+        Ok(self.read_all()?.into_iter().find(|r| r.id == id))
+    }
+
+    fn read_all(&self) -> Result<Vec<RunRecord>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to open {}", self.path.display()));
+            }
+        };
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.with_context(|| format!("Failed to read {}", self.path.display()))?;
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// SHA-256 of the spec's JSON with object keys sorted, so the hash does not
+/// depend on map iteration order
+pub fn spec_hash(spec: &PipelineSpec) -> String {
+    serde_json::to_value(spec)
+        .map(canonical_hash)
+        .unwrap_or_default()
+}
+
+/// SHA-256 of `value` serialized with object keys sorted
+pub(super) fn canonical_hash(value: Value) -> String {
+    let canonical = sort_keys(value).to_string();
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, sort_keys(v)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::spec::Step;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_history_store_append_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::new(dir.path().join("runs.jsonl"));
+        assert!(store.list(&HistoryQuery::default()).unwrap().is_empty());
+
+        let spec = PipelineSpec::new("orders");
+        let failed = RunRecord::new(
+            &spec,
+            Path::new("a.csv"),
+            Utc::now() - chrono::Duration::minutes(5),
+            &Err(anyhow::anyhow!("input missing")),
+        );
+        store.append(&failed).unwrap();
+        let other = RunRecord::new(
+            &PipelineSpec::new("customers"),
+            Path::new("b.csv"),
+            Utc::now(),
+            &Err(anyhow::anyhow!("boom")),
+        );
+        store.append(&other).unwrap();
+
+        let all = store.list(&HistoryQuery::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].pipeline, "customers");
+
+        let orders = store
+            .list(&HistoryQuery {
+                pipeline: Some("orders".to_owned()),
+                ..HistoryQuery::default()
+            })
+            .unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].status, RunStatus::Failed);
+        assert_eq!(orders[0].error.as_deref(), Some("input missing"));
+        assert_eq!(orders[0].spec_hash, spec_hash(&spec));
+
+        let fetched = store.get(&failed.id).unwrap().unwrap();
+        assert_eq!(fetched.input_path, PathBuf::from("a.csv"));
+    }
+
+    #[test]
+    fn test_spec_hash_ignores_map_order() {
+        let mut a = PipelineSpec::new("hash");
+        let mapping: HashMap<String, String> = (0..20)
+            .map(|i| (format!("col{i}"), format!("new{i}")))
+            .collect();
+        a.steps.push(
+            Step::RenameColumns {
+                mapping: mapping.clone(),
+            }
+            .into(),
+        );
+        let mut b = PipelineSpec::new("hash");
+        b.steps.push(
+            Step::RenameColumns {
+                mapping: mapping.into_iter().rev().collect(),
+            }
+            .into(),
+        );
+        assert_eq!(spec_hash(&a), spec_hash(&b));
+
+        b.name = "other".to_owned();
+        assert_ne!(spec_hash(&a), spec_hash(&b));
+    }
+}
@@ -0,0 +1,360 @@
+//! Spec linting: likely mistakes that are not hard errors.
+//!
+//! The lint pass looks only at the spec, following column names through the
+//! steps (including renames), and reports [`Severity::Warning`] findings:
+//!
+//! - **No-op steps**: an empty column list, dropping a column already
+//!   dropped, renaming a column to itself
+//! - **Conflicts**: normalising a column that is later cast to text, casting
+//!   the same column twice
+//! - **Ordering**: imputing or clipping outliers after normalising
+//! - **Policies**: `on_error: quarantine` on a step with no row-level failures
+//!
+//! [`Severity::Warning`]: super::validation::Severity::Warning
+
+use super::spec::{ErrorPolicy, PipelineSpec, PipelineStep, Step};
+use super::validation::{ErrorCode, ValidationError};
+use crate::analyser::logic::date_parts::date_part_column;
+use std::collections::HashMap;
+
+/// Check `spec` for no-op, conflicting and oddly ordered steps. Every
+/// finding has warning severity; see [`super::validate_pipeline`] for errors.
+pub fn lint_pipeline(spec: &PipelineSpec) -> Vec<ValidationError> {
+    let mut linter = Linter::default();
+    for (idx, PipelineStep { step, on_error, .. }) in spec.steps.iter().enumerate() {
+        linter.check(idx, step);
+        if *on_error == ErrorPolicy::Quarantine
+            && !matches!(step, Step::CastTypes { .. } | Step::ParseDates { .. })
+        {
+            linter.warn(
+                ValidationError::step(
+                    idx,
+                    ErrorCode::NoOpStep,
+                    "Quarantine only moves rows for cast_types and parse_dates; this step will be skipped on error",
+                )
+                .field("on_error")
+                .suggest("skip"),
+            );
+        }
+    }
+    linter.warnings
+}
+
+#[derive(Default)]
+struct Linter {
+    warnings: Vec<ValidationError>,
+    /// Column -> step that dropped it
+    dropped: HashMap<String, usize>,
+    /// Column -> step that normalised it
+    normalised: HashMap<String, usize>,
+    /// Column -> (step, type) of its most recent cast
+    cast: HashMap<String, (usize, String)>,
+}
+
+impl Linter {
+    fn check(&mut self, idx: usize, step: &Step) {
+        if let Some(field) = empty_column_list(step) {
+            self.warn(
+                ValidationError::step(
+                    idx,
+                    ErrorCode::NoOpStep,
+                    "Step has no columns selected and does nothing",
+                )
+                .field(field),
+            );
+        }
+
+        match step {
+            Step::DropColumns { columns } => {
+                for col in columns {
+                    if let Some(&earlier) = self.dropped.get(col) {
+                        self.warn(
+                            ValidationError::step(
+                                idx,
+                                ErrorCode::NoOpStep,
+                                format!(
+                                    "Column '{col}' was already dropped by step {}",
+                                    earlier + 1
+                                ),
+                            )
+                            .field("columns")
+                            .param("column", col)
+                            .param("earlier_step", earlier + 1),
+                        );
+                    } else {
+                        self.forget(col);
+                        self.dropped.insert(col.clone(), idx);
+                    }
+                }
+            }
+
+            Step::RenameColumns { mapping } => {
+                let mut pairs: Vec<(&String, &String)> = mapping.iter().collect();
+                pairs.sort();
+                for (from, to) in pairs {
+                    if from == to {
+                        self.warn(
+                            ValidationError::step(
+                                idx,
+                                ErrorCode::NoOpStep,
+                                format!("Column '{from}' is renamed to itself"),
+                            )
+                            .field("mapping")
+                            .param("column", from),
+                        );
+                        continue;
+                    }
+                    self.forget(to);
+                    self.dropped.remove(to);
+                    if let Some(v) = self.normalised.remove(from) {
+                        self.normalised.insert(to.clone(), v);
+                    }
+                    if let Some(v) = self.cast.remove(from) {
+                        self.cast.insert(to.clone(), v);
+                    }
+                }
+            }
+
+            Step::CastTypes { columns } => {
+                let mut targets: Vec<(&String, &String)> = columns.iter().collect();
+                targets.sort();
+                for (col, type_str) in targets {
+                    if let Some(&norm_idx) = self.normalised.get(col)
+                        && matches!(type_str.as_str(), "String" | "Text" | "Categorical")
+                    {
+                        self.warn(
+                            ValidationError::step(
+                                idx,
+                                ErrorCode::ConflictingSteps,
+                                format!(
+                                    "Column '{col}' is normalised in step {} but cast to {type_str} here",
+                                    norm_idx + 1
+                                ),
+                            )
+                            .field("columns")
+                            .param("column", col)
+                            .param("earlier_step", norm_idx + 1),
+                        );
+                    }
+                    if let Some((cast_idx, earlier_type)) = self.cast.get(col)
+                        && earlier_type != type_str
+                    {
+                        self.warn(
+                            ValidationError::step(
+                                idx,
+                                ErrorCode::ConflictingSteps,
+                                format!(
+                                    "Column '{col}' was cast to {earlier_type} in step {} and is cast again to {type_str}",
+                                    cast_idx + 1
+                                ),
+                            )
+                            .field("columns")
+                            .param("column", col)
+                            .param("earlier_step", cast_idx + 1)
+                            .suggest(type_str.clone()),
+                        );
+                    }
+                    self.cast.insert(col.clone(), (idx, type_str.clone()));
+                }
+            }
+
+            Step::NormaliseColumns { columns, .. } => {
+                for col in columns {
+                    self.normalised.insert(col.clone(), idx);
+                }
+            }
+
+            Step::Impute { columns, .. } => {
+                self.after_normalise(idx, columns, "Imputing");
+            }
+
+            Step::ClipOutliers { columns, .. } => {
+                self.after_normalise(idx, columns, "Clipping outliers");
+            }
+
+            Step::Bin { column, .. } => {
+                self.after_normalise(idx, std::slice::from_ref(column), "Binning");
+                self.forget(column);
+            }
+
+            Step::EncodeCategories { column, .. } => {
+                self.forget(column);
+            }
+
+            Step::WithColumn { name, .. }
+            | Step::CompletenessScore { name, .. }
+            | Step::MergeColumns { into: name, .. } => {
+                self.forget(name);
+                self.dropped.remove(name);
+            }
+
+            Step::SplitColumn { into, .. } => {
+                for name in into {
+                    self.forget(name);
+                    self.dropped.remove(name);
+                }
+            }
+
+            Step::ExtractDateParts { column, parts } => {
+                for &part in parts {
+                    let name = date_part_column(column, part);
+                    self.forget(&name);
+                    self.dropped.remove(&name);
+                }
+            }
+
+            // Steps that replace the whole column set reset what we know
+            Step::Aggregate { .. }
+            | Step::Pivot { .. }
+            | Step::Unpivot { .. }
+            | Step::Transpose { .. }
+            | Step::External { .. } => {
+                *self = Self {
+                    warnings: std::mem::take(&mut self.warnings),
+                    ..Self::default()
+                };
+            }
+
+            Step::TrimWhitespace { .. }
+            | Step::ParseDates { .. }
+            | Step::OneHotEncode { .. }
+            | Step::VectoriseText { .. }
+            | Step::ExtractNumbers { .. }
+            | Step::RegexReplace { .. }
+            | Step::MapValues { .. }
+            | Step::Sort { .. }
+            | Step::Limit { .. }
+            | Step::Sample { .. }
+            | Step::Concat { .. }
+            | Step::Union { .. }
+            | Step::ExpectSchema { .. }
+            | Step::WriteOutput { .. } => {}
+        }
+    }
+
+    fn after_normalise(&mut self, idx: usize, columns: &[String], action: &str) {
+        for col in columns {
+            if let Some(&norm_idx) = self.normalised.get(col) {
+                self.warn(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::StepOrder,
+                        format!(
+                            "{action} column '{col}' after it was normalised in step {}",
+                            norm_idx + 1
+                        ),
+                    )
+                    .field("columns")
+                    .param("column", col)
+                    .param("earlier_step", norm_idx + 1),
+                );
+            }
+        }
+    }
+
+    /// Drop what we know about a column that is being replaced
+    fn forget(&mut self, col: &str) {
+        self.normalised.remove(col);
+        self.cast.remove(col);
+    }
+
+    fn warn(&mut self, warning: ValidationError) {
+        self.warnings.push(warning.warning());
+    }
+}
+
+/// The column-list field of a column-wise step that selects no columns
+fn empty_column_list(step: &Step) -> Option<&'static str> {
+    let empty = match step {
+        Step::DropColumns { columns }
+        | Step::TrimWhitespace { columns }
+        | Step::Impute { columns, .. }
+        | Step::OneHotEncode { columns, .. }
+        | Step::VectoriseText { columns, .. }
+        | Step::NormaliseColumns { columns, .. }
+        | Step::ClipOutliers { columns, .. }
+        | Step::ExtractNumbers { columns }
+        | Step::RegexReplace { columns, .. } => columns.is_empty(),
+        Step::RenameColumns { mapping } => mapping.is_empty(),
+        Step::MapValues { columns, mapping } => columns.is_empty() || mapping.is_empty(),
+        Step::CastTypes { columns } | Step::ParseDates { columns } => columns.is_empty(),
+        Step::ExpectSchema { columns, .. } => columns.is_empty(),
+        _ => false,
+    };
+    let mapping_field = matches!(step, Step::RenameColumns { .. })
+        || matches!(step, Step::MapValues { mapping, .. } if mapping.is_empty());
+    let field = if mapping_field { "mapping" } else { "columns" };
+    empty.then_some(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::spec::{ImputeStrategy, NormalisationMethod};
+    use crate::pipeline::validation::Severity;
+
+    #[test]
+    fn test_lint_pipeline() {
+        let mut spec = PipelineSpec::new("lint");
+        spec.steps = vec![
+            Step::DropColumns {
+                columns: vec!["notes".to_owned()],
+            },
+            Step::NormaliseColumns {
+                method: NormalisationMethod::ZScore,
+                columns: vec!["age".to_owned()],
+            },
+            Step::Impute {
+                strategy: ImputeStrategy::Mean,
+                columns: vec!["age".to_owned()],
+            },
+            Step::RenameColumns {
+                mapping: HashMap::from([("age".to_owned(), "age_z".to_owned())]),
+            },
+            Step::CastTypes {
+                columns: HashMap::from([("age_z".to_owned(), "String".to_owned())]),
+            },
+            Step::DropColumns {
+                columns: vec!["notes".to_owned()],
+            },
+            Step::TrimWhitespace { columns: vec![] },
+        ]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        let warnings = lint_pipeline(&spec);
+        let found: Vec<(Option<usize>, ErrorCode)> =
+            warnings.iter().map(|w| (w.step_index, w.code)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(2), ErrorCode::StepOrder),
+                (Some(4), ErrorCode::ConflictingSteps),
+                (Some(5), ErrorCode::NoOpStep),
+                (Some(6), ErrorCode::NoOpStep),
+            ]
+        );
+        assert!(warnings.iter().all(|w| w.severity == Severity::Warning));
+        assert!(warnings[1].message.contains("'age_z'"));
+    }
+
+    #[test]
+    fn test_lint_clean_pipeline_has_no_warnings() {
+        let mut spec = PipelineSpec::new("clean");
+        spec.steps = vec![
+            Step::Impute {
+                strategy: ImputeStrategy::Median,
+                columns: vec!["age".to_owned()],
+            },
+            Step::NormaliseColumns {
+                method: NormalisationMethod::MinMax,
+                columns: vec!["age".to_owned()],
+            },
+        ]
+        .into_iter()
+        .map(Into::into)
+        .collect();
+        assert!(lint_pipeline(&spec).is_empty());
+    }
+}
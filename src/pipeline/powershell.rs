@@ -130,7 +130,7 @@ Write-Info "Log: $LogPath"
 
 # Build beefcake command
 $beefcakeArgs = @(
-    "run",
+    "pipeline", "run",
     "--spec", $SpecPath,
     "--input", $InputPath
 )
@@ -176,9 +176,13 @@ try {{
         Write-Error "Check the pipeline spec and input file schema"
         exit 2
     }} elseif ($exitCode -eq 3) {{
-        Write-Error "Pipeline execution failed (exit code 3)"
+        Write-Error "Data quality check failed (exit code 3)"
         Write-Error "Check the log file for details: $LogPath"
         exit 3
+    }} elseif ($exitCode -eq 4) {{
+        Write-Error "Input or output unavailable (exit code 4)"
+        Write-Error "Check that the input file exists and the output folder is writable"
+        exit 4
     }} else {{
         Write-Error "Pipeline failed with exit code $exitCode"
         exit $exitCode
@@ -261,7 +265,7 @@ mod tests {
         assert!(script.contains("$InputPath"));
         assert!(script.contains("$OutputPath"));
         assert!(script.contains("beefcake"));
-        assert!(script.contains("\"run\""));
+        assert!(script.contains("\"pipeline\", \"run\""));
         assert!(script.contains("--spec"));
         assert!(script.contains("exit"));
     }
@@ -0,0 +1,107 @@
+//! Matching the columns a pipeline refers to against an input that names
+//! them differently.
+//!
+//! A pipeline saved against `Customer ID` fails validation on a file with
+//! `customer_id`. [`resolve_pipeline_columns`] takes the columns the spec
+//! refers to that the input lacks and pairs them with the input's columns
+//! by [`resolve_columns`]: case and spacing first, then a small edit
+//! distance. The result is a table for the user to confirm; confirmed pairs
+//! go in `input.column_renames`, which renames the input's columns before
+//! validation and the first step.
+
+use super::spec::PipelineSpec;
+use super::validation::{ErrorCode, validate_pipeline};
+use crate::analyser::logic::column_match::{ColumnResolution, resolve_columns};
+use anyhow::Result;
+use polars::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Pair the columns `spec` refers to but `input_schema` lacks with the
+/// input's columns. Columns already renamed by `input.column_renames` are
+/// left out; matches are suggestions until confirmed.
+pub fn resolve_pipeline_columns(
+    spec: &PipelineSpec,
+    input_schema: &Schema,
+) -> Result<ColumnResolution> {
+    let mut expected: Vec<String> = Vec::new();
+    for error in validate_pipeline(spec, input_schema)? {
+        if matches!(
+            error.code,
+            ErrorCode::MissingRequiredColumn | ErrorCode::UnknownColumn
+        ) && let Some(column) = error.params.get("column")
+            && !expected.contains(column)
+        {
+            expected.push(column.clone());
+        }
+    }
+
+    let renamed: HashSet<&str> = spec
+        .input
+        .column_renames
+        .values()
+        .map(String::as_str)
+        .collect();
+    let columns: Vec<String> = rename_schema(input_schema, &spec.input.column_renames)
+        .iter_names()
+        .filter(|name| !renamed.contains(name.as_str()))
+        .map(|name| name.to_string())
+        .collect();
+    Ok(resolve_columns(&expected, &columns))
+}
+
+/// `schema` with the columns in `renames` renamed, keeping their order
+pub fn rename_schema(schema: &Schema, renames: &HashMap<String, String>) -> Schema {
+    schema
+        .iter()
+        .map(|(name, dtype)| {
+            let name = renames
+                .get(name.as_str())
+                .map_or_else(|| name.clone(), |new_name| new_name.as_str().into());
+            Field::new(name, dtype.clone())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::spec::Step;
+
+    #[test]
+    fn test_resolved_columns_rename_the_input() {
+        let mut spec = PipelineSpec::new("orders");
+        spec.schema.required_columns = vec!["Customer ID".to_owned()];
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["Order Notes".to_owned()],
+            }
+            .into(),
+        );
+        let schema = Schema::from_iter([
+            Field::new("customer_id".into(), DataType::Int64),
+            Field::new("order_note".into(), DataType::String),
+            Field::new("total".into(), DataType::Float64),
+        ]);
+        assert_eq!(validate_pipeline(&spec, &schema).unwrap().len(), 2);
+
+        let resolution = resolve_pipeline_columns(&spec, &schema).unwrap();
+        let pairs: Vec<(&str, &str)> = resolution
+            .matches
+            .iter()
+            .map(|m| (m.column.as_str(), m.expected.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("customer_id", "Customer ID"),
+                ("order_note", "Order Notes")
+            ]
+        );
+        assert_eq!(resolution.unmatched_columns, ["total"]);
+
+        spec.input.column_renames = resolution.renames();
+        assert!(validate_pipeline(&spec, &schema).unwrap().is_empty());
+        let again = resolve_pipeline_columns(&spec, &schema).unwrap();
+        assert!(again.matches.is_empty());
+    }
+}
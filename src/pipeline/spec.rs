@@ -3,7 +3,10 @@
 //! Defines the JSON schema for pipeline specs, including input/output configuration,
 //! transformation steps, and schema matching rules.
 
-use crate::analyser::logic::types::ColumnCleanConfig;
+use crate::analyser::logic::HeaderlessNaming;
+use crate::analyser::logic::types::{
+    BinStrategy, CategoryEncoding, ColumnCleanConfig, DatePart, TextVectorMethod, TextVectoriser,
+};
 use anyhow::{Context as _, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -28,10 +31,61 @@ pub struct PipelineSpec {
     pub schema: SchemaConfig,
 
     /// Ordered sequence of transformation steps
-    pub steps: Vec<Step>,
+    pub steps: Vec<PipelineStep>,
 
     /// Output file configuration
     pub output: OutputConfig,
+
+    /// Template parameters and their default values, usable as `{name}` in
+    /// any string field. Values passed at run time replace these.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, String>,
+}
+
+/// A step of a pipeline and how the executor treats it. Serialized as the
+/// step's own fields with `on_error` and `checkpoint` alongside.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineStep {
+    #[serde(flatten)]
+    pub step: Step,
+
+    /// What happens when the step fails
+    #[serde(default, skip_serializing_if = "is_default_policy")]
+    pub on_error: ErrorPolicy,
+
+    /// Write a Parquet checkpoint after the step, so a failed run can
+    /// resume from there
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checkpoint: bool,
+}
+
+impl From<Step> for PipelineStep {
+    fn from(step: Step) -> Self {
+        Self {
+            step,
+            on_error: ErrorPolicy::default(),
+            checkpoint: false,
+        }
+    }
+}
+
+/// What the executor does when a step fails
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorPolicy {
+    /// Abort the run
+    Fail,
+    /// Leave the data as it was before the step and record a warning
+    #[default]
+    Skip,
+    /// Move rows the step cannot handle (failed casts and date parses) to the
+    /// reject file and carry on with the rest. A step that fails outright is
+    /// skipped.
+    Quarantine,
+}
+
+fn is_default_policy(policy: &ErrorPolicy) -> bool {
+    *policy == ErrorPolicy::default()
 }
 
 impl PipelineSpec {
@@ -44,6 +98,7 @@ impl PipelineSpec {
             schema: SchemaConfig::default(),
             steps: Vec::new(),
             output: OutputConfig::default(),
+            params: HashMap::new(),
         }
     }
 
@@ -96,28 +151,31 @@ impl PipelineSpec {
             if !config.new_name.is_empty() && config.new_name != *col_name {
                 let mut mapping = HashMap::new();
                 mapping.insert(col_name.clone(), config.new_name.clone());
-                spec.steps.push(Step::RenameColumns { mapping });
+                spec.steps.push(Step::RenameColumns { mapping }.into());
             }
 
             // Trim whitespace
             if config.trim_whitespace {
-                spec.steps.push(Step::TrimWhitespace {
-                    columns: vec![col_name.clone()],
-                });
+                spec.steps.push(
+                    Step::TrimWhitespace {
+                        columns: vec![col_name.clone()],
+                    }
+                    .into(),
+                );
             }
 
             // Cast types
             if let Some(target_dtype) = config.target_dtype {
                 let mut columns = HashMap::new();
                 columns.insert(col_name.clone(), target_dtype.as_str().to_owned());
-                spec.steps.push(Step::CastTypes { columns });
+                spec.steps.push(Step::CastTypes { columns }.into());
             }
 
             // Parse dates (if temporal format specified)
             if !config.temporal_format.is_empty() {
                 let mut columns = HashMap::new();
                 columns.insert(col_name.clone(), config.temporal_format.clone());
-                spec.steps.push(Step::ParseDates { columns });
+                spec.steps.push(Step::ParseDates { columns }.into());
             }
 
             // Imputation
@@ -131,18 +189,88 @@ impl PipelineSpec {
                     crate::analyser::logic::types::ImputeMode::Zero => ImputeStrategy::Zero,
                     crate::analyser::logic::types::ImputeMode::None => continue,
                 };
-                spec.steps.push(Step::Impute {
-                    strategy,
-                    columns: vec![col_name.clone()],
-                });
+                spec.steps.push(
+                    Step::Impute {
+                        strategy,
+                        columns: vec![col_name.clone()],
+                    }
+                    .into(),
+                );
             }
 
-            // One-hot encoding
-            if config.ml_preprocessing && config.one_hot_encode {
-                spec.steps.push(Step::OneHotEncode {
-                    columns: vec![col_name.clone()],
-                    drop_original: true,
-                });
+            let column = if config.new_name.is_empty() {
+                col_name.clone()
+            } else {
+                config.new_name.clone()
+            };
+
+            // Binning comes first so that the bins can be one-hot encoded
+            if let Some(binning) = &config.binning {
+                spec.steps.push(
+                    Step::Bin {
+                        column: column.clone(),
+                        strategy: binning.strategy.clone(),
+                        labels: binning.labels.clone(),
+                    }
+                    .into(),
+                );
+            }
+
+            // Ordinal or target encoding, in place of one-hot encoding
+            if config.ml_preprocessing
+                && let Some(encoding) = &config.encoding
+            {
+                spec.steps.push(
+                    Step::EncodeCategories {
+                        column: column.clone(),
+                        encoding: encoding.clone(),
+                        mapping_path: None,
+                    }
+                    .into(),
+                );
+            } else if config.ml_preprocessing && config.one_hot_encode {
+                spec.steps.push(
+                    Step::OneHotEncode {
+                        columns: vec![col_name.clone()],
+                        drop_original: true,
+                    }
+                    .into(),
+                );
+            }
+
+            // Date features
+            if !config.date_parts.is_empty() {
+                spec.steps.push(
+                    Step::ExtractDateParts {
+                        column: column.clone(),
+                        parts: config.date_parts.clone(),
+                    }
+                    .into(),
+                );
+            }
+
+            // Split and merge
+            if let Some(split) = &config.split {
+                spec.steps.push(
+                    Step::SplitColumn {
+                        column,
+                        delimiter: split.delimiter.clone(),
+                        regex: split.regex,
+                        into: split.into.clone(),
+                        drop_original: split.drop_original,
+                    }
+                    .into(),
+                );
+            }
+            if let Some(merge) = &config.merge {
+                spec.steps.push(
+                    Step::MergeColumns {
+                        template: merge.template.clone(),
+                        into: merge.into.clone(),
+                        drop_sources: merge.drop_sources,
+                    }
+                    .into(),
+                );
             }
         }
 
@@ -161,15 +289,16 @@ impl PipelineSpec {
         let mut trim_cols = Vec::new();
         let mut other_steps = Vec::new();
 
-        for step in self.steps.drain(..) {
-            match step {
+        for entry in self.steps.drain(..) {
+            match entry.step {
                 Step::TrimWhitespace { columns } => trim_cols.extend(columns),
-                other => other_steps.push(other),
+                _ => other_steps.push(entry),
             }
         }
 
         if !trim_cols.is_empty() {
-            self.steps.push(Step::TrimWhitespace { columns: trim_cols });
+            self.steps
+                .push(Step::TrimWhitespace { columns: trim_cols }.into());
         }
         self.steps.extend(other_steps);
     }
@@ -186,6 +315,10 @@ pub struct InputConfig {
     #[serde(default = "default_true")]
     pub has_header: bool,
 
+    /// How to name the columns of a CSV file without a header row
+    #[serde(default)]
+    pub header_names: HeaderlessNaming,
+
     /// CSV delimiter character
     #[serde(default = "default_delimiter")]
     pub delimiter: String,
@@ -193,6 +326,18 @@ pub struct InputConfig {
     /// File encoding
     #[serde(default = "default_encoding")]
     pub encoding: String,
+
+    /// Additional named inputs that `concat`/`union` steps can stack onto
+    /// the main input
+    #[serde(default)]
+    pub sources: Vec<NamedInput>,
+
+    /// New names for main input columns, keyed by their name in the file,
+    /// applied before validation and the first step. Holds the confirmed
+    /// matches of [`super::resolve_pipeline_columns`] for a file that names
+    /// the expected columns differently.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub column_renames: HashMap<String, String>,
 }
 
 impl Default for InputConfig {
@@ -200,12 +345,26 @@ impl Default for InputConfig {
         Self {
             format: default_format(),
             has_header: default_true(),
+            header_names: HeaderlessNaming::default(),
             delimiter: default_delimiter(),
             encoding: default_encoding(),
+            sources: Vec::new(),
+            column_renames: HashMap::new(),
         }
     }
 }
 
+/// A named secondary input file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedInput {
+    /// Name referenced by `concat`/`union` steps
+    pub name: String,
+
+    /// Default path (supports {date} substitution); can be overridden at run time
+    #[serde(default)]
+    pub path: String,
+}
+
 /// Schema validation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SchemaConfig {
@@ -253,6 +412,27 @@ pub struct OutputConfig {
     /// Whether to overwrite existing files
     #[serde(default = "default_true")]
     pub overwrite: bool,
+
+    /// Where to write rows that fail `cast_types`/`parse_dates` (supports
+    /// {date} substitution). Empty keeps the old behaviour of nulling them.
+    #[serde(default)]
+    pub reject_path_template: String,
+
+    /// Folder for step checkpoints (supports template variables). Empty
+    /// uses `.checkpoints/<spec name>` next to the output file.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub checkpoint_dir: String,
+
+    /// Where to copy the outputs once they are written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deliver: Vec<DeliveryTarget>,
+
+    /// Count rows and null cells after every step for the run report. Each
+    /// count evaluates the plan up to that step, so this is off by default
+    /// and only checkpointed steps, whose data is already written, are
+    /// counted.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub step_metrics: bool,
 }
 
 impl Default for OutputConfig {
@@ -261,10 +441,75 @@ impl Default for OutputConfig {
             format: default_parquet_format(),
             path_template: String::new(),
             overwrite: default_true(),
+            reject_path_template: String::new(),
+            checkpoint_dir: String::new(),
+            deliver: Vec::new(),
+            step_metrics: false,
         }
     }
 }
 
+/// A destination that outputs are copied to after a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryTarget {
+    #[serde(flatten)]
+    pub destination: DeliveryDestination,
+
+    /// Extra attempts after a transient failure of the copy or verification
+    #[serde(default = "default_delivery_retries")]
+    pub retries: u32,
+
+    /// Seconds to wait before the first retry; later waits grow by the
+    /// backoff in the app's retry settings
+    #[serde(default = "default_delivery_retry_delay")]
+    pub retry_delay_secs: u64,
+
+    /// How to check the delivered copy
+    #[serde(default)]
+    pub verify: DeliveryVerify,
+}
+
+fn default_delivery_retries() -> u32 {
+    3
+}
+
+fn default_delivery_retry_delay() -> u64 {
+    10
+}
+
+/// Where a [`DeliveryTarget`] copies files to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DeliveryDestination {
+    /// A local folder or network share, e.g. `\\server\share\drop`
+    Path { dir: String },
+
+    /// A folder on an SFTP server, uploaded with the system `sftp` client
+    /// using key-based authentication
+    Sftp {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        user: String,
+        remote_dir: String,
+        /// Private key file; the client's default keys/agent if unset
+        #[serde(default)]
+        identity_file: Option<String>,
+    },
+}
+
+/// Verification of a delivered file
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryVerify {
+    None,
+    /// Compare file sizes
+    Size,
+    /// Compare SHA-256 hashes (SFTP deliveries are downloaded again to hash)
+    #[default]
+    Hash,
+}
+
 /// Transformation step (tagged enum)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "op", rename_all = "snake_case")]
@@ -302,6 +547,19 @@ pub enum Step {
         drop_original: bool,
     },
 
+    /// Replace each value of `column` with a number, for columns with too
+    /// many values to one-hot encode: its position in sorted order or the
+    /// smoothed mean of a numeric target. See
+    /// [`crate::analyser::logic::encoding`]. Without a mapping, one is
+    /// learned from the data; with `mapping_path` the learned encoding is
+    /// saved there as JSON on the first run and reused by later runs.
+    EncodeCategories {
+        column: String,
+        encoding: CategoryEncoding,
+        #[serde(default)]
+        mapping_path: Option<String>,
+    },
+
     /// Normalize numeric columns
     NormaliseColumns {
         method: NormalisationMethod,
@@ -318,12 +576,270 @@ pub enum Step {
     /// Extract numbers from text using regex
     ExtractNumbers { columns: Vec<String> },
 
+    /// Replace the numbers in `column` with the label of the bin each falls
+    /// in, e.g. `18 to 30` or one of `labels`. Values outside the bins become
+    /// null. See [`crate::analyser::logic::binning`].
+    Bin {
+        column: String,
+        strategy: BinStrategy,
+        #[serde(default)]
+        labels: Vec<String>,
+    },
+
+    /// Turn free-text columns into numeric features the built-in models can
+    /// use: hashed token counts (`<column>_h<i>`) or TF-IDF weights of the
+    /// `features` most widespread terms (`<column>_tfidf_<term>`).
+    VectoriseText {
+        columns: Vec<String>,
+        #[serde(default)]
+        method: TextVectorMethod,
+        #[serde(default = "default_text_features")]
+        features: usize,
+        #[serde(default)]
+        drop_original: bool,
+    },
+
     /// Apply regex replacement
     RegexReplace {
         columns: Vec<String>,
         pattern: String,
         replacement: String,
     },
+
+    /// Replace whole values, e.g. the spellings of a category clustered by
+    /// [`crate::analyser::logic::standardise`]. Columns are cast to text;
+    /// values not in `mapping` are kept.
+    MapValues {
+        columns: Vec<String>,
+        mapping: HashMap<String, String>,
+    },
+
+    /// Split `column` on `delimiter` (a regex when `regex` is set) into the
+    /// columns named in `into`. Parts are trimmed; extra parts are dropped
+    /// and missing ones are null.
+    SplitColumn {
+        column: String,
+        delimiter: String,
+        #[serde(default)]
+        regex: bool,
+        into: Vec<String>,
+        #[serde(default)]
+        drop_original: bool,
+    },
+
+    /// Add column `into` by filling `template`, e.g. `"{first} {last}"`, with
+    /// the values of the columns it names. Nulls count as empty text and
+    /// `{{`/`}}` write a literal brace.
+    MergeColumns {
+        template: String,
+        into: String,
+        #[serde(default)]
+        drop_sources: bool,
+    },
+
+    /// Add a column per calendar feature of a date or time `column`, named
+    /// `<column>_<part>`, e.g. `order_date_weekday`. Weekdays run from 1
+    /// (Monday) to 7 (Sunday).
+    ExtractDateParts {
+        column: String,
+        parts: Vec<DatePart>,
+    },
+
+    /// Add (or replace) a column computed from an expression, e.g.
+    /// `price * quantity` or `if(age >= 18, "adult", "minor")`.
+    /// See [`super::expression`] for the syntax.
+    WithColumn { name: String, expression: String },
+
+    /// Group rows and aggregate, producing one row per group. With an empty
+    /// `group_by` the whole table is aggregated into a single row.
+    Aggregate {
+        group_by: Vec<String>,
+        aggregations: Vec<Aggregation>,
+    },
+
+    /// Sort rows by one or more columns. `descending` holds one flag per
+    /// column, or a single flag applied to all; empty means ascending.
+    Sort {
+        by: Vec<String>,
+        #[serde(default)]
+        descending: Vec<bool>,
+    },
+
+    /// Keep only the first `n` rows
+    Limit { n: usize },
+
+    /// Keep a reproducible random sample of rows, either about a `fraction`
+    /// of the input or exactly `n` rows. Sampled rows keep their original
+    /// order.
+    Sample {
+        #[serde(default)]
+        fraction: Option<f64>,
+        #[serde(default)]
+        n: Option<usize>,
+        #[serde(default = "default_sample_seed")]
+        seed: u64,
+    },
+
+    /// Append the rows of named inputs below the current data. Every input
+    /// must have the same column names; columns are matched by name.
+    Concat { inputs: Vec<String> },
+
+    /// Like [`Step::Concat`] but drops duplicate rows from the combined result
+    Union { inputs: Vec<String> },
+
+    /// Reshape long to wide: one row per `index` combination and one column
+    /// per distinct value of `columns`, filled with `agg` of `values`.
+    Pivot {
+        index: Vec<String>,
+        columns: String,
+        values: String,
+        agg: AggregateFunction,
+    },
+
+    /// Reshape wide to long (melt): keep `id_vars` and stack `value_vars` into
+    /// `variable`/`value` columns. An empty `value_vars` stacks every other column.
+    Unpivot {
+        id_vars: Vec<String>,
+        value_vars: Vec<String>,
+    },
+
+    /// Swap rows and columns, for files that hold variables as rows. Values
+    /// of `header_column` become the new column names (`column_1`,
+    /// `column_2`, ... without one) and the old column names go into
+    /// `names_column`. At most 500 rows can be transposed.
+    Transpose {
+        #[serde(default)]
+        header_column: Option<String>,
+        #[serde(default = "default_transpose_names_column")]
+        names_column: String,
+    },
+
+    /// Append a per-row completeness score (weighted fraction of non-null
+    /// fields). Columns missing from `weights` count as 1.0.
+    CompletenessScore {
+        #[serde(default = "default_completeness_column")]
+        name: String,
+        #[serde(default)]
+        weights: HashMap<String, f64>,
+    },
+
+    /// Check the data against a contract: the columns it must have, their
+    /// types and whether they may hold nulls. Placed first, it catches
+    /// upstream files that changed shape before any output is written. See
+    /// [`super::contract`].
+    ExpectSchema {
+        columns: Vec<ExpectedColumn>,
+        #[serde(default)]
+        on_violation: ViolationAction,
+    },
+
+    /// Write the data as it stands at this point to an extra file and carry
+    /// on unchanged. `format` defaults to `output.format`; the path's
+    /// extension takes precedence when it has one.
+    WriteOutput {
+        path_template: String,
+        #[serde(default)]
+        format: Option<String>,
+    },
+
+    /// Hand the current data to an external command and continue with its
+    /// output. `{input}`/`{output}` in `args` are replaced with temp file
+    /// paths. The command must live in a trusted folder.
+    External {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        data_format: ExternalDataFormat,
+    },
+}
+
+/// A column required by a [`Step::ExpectSchema`] contract
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExpectedColumn {
+    pub name: String,
+
+    /// Type the column must have, e.g. `Numeric`, `i64`, `Text` or
+    /// `Temporal`; any type when unset. See [`super::contract::DTYPE_NAMES`].
+    #[serde(default)]
+    pub dtype: Option<String>,
+
+    /// Whether the column may hold nulls
+    #[serde(default = "default_true")]
+    pub nullable: bool,
+}
+
+/// What a [`Step::ExpectSchema`] step does when the data breaks its contract
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationAction {
+    /// Stop the run with a report of every mismatch
+    #[default]
+    Fail,
+    /// Record each mismatch as a warning and carry on
+    Warn,
+}
+
+/// A single aggregation within an [`Step::Aggregate`] step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Aggregation {
+    /// Source column
+    pub column: String,
+
+    pub function: AggregateFunction,
+
+    /// Output column name; defaults to `{column}_{function}`
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+impl Aggregation {
+    /// Name of the column this aggregation produces
+    pub fn output_name(&self) -> String {
+        self.alias
+            .clone()
+            .filter(|a| !a.is_empty())
+            .unwrap_or_else(|| format!("{}_{}", self.column, self.function.as_str()))
+    }
+}
+
+/// Aggregation function for [`Step::Aggregate`] and [`Step::Pivot`]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    /// Count of non-null values
+    Count,
+    NUnique,
+}
+
+impl AggregateFunction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sum => "sum",
+            Self::Mean => "mean",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Count => "count",
+            Self::NUnique => "n_unique",
+        }
+    }
+}
+
+/// How data is exchanged with a [`Step::External`] command
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExternalDataFormat {
+    /// Temporary Parquet files for input and output
+    #[default]
+    Parquet,
+    /// Temporary CSV files for input and output
+    Csv,
+    /// CSV piped to stdin, CSV read back from stdout
+    Stdin,
 }
 
 /// Imputation strategy for missing values
@@ -353,6 +869,22 @@ fn default_parquet_format() -> String {
     "parquet".to_owned()
 }
 
+fn default_transpose_names_column() -> String {
+    crate::analyser::logic::TRANSPOSE_NAMES_COLUMN.to_owned()
+}
+
+fn default_completeness_column() -> String {
+    crate::analyser::logic::COMPLETENESS_COLUMN.to_owned()
+}
+
+fn default_sample_seed() -> u64 {
+    42
+}
+
+fn default_text_features() -> usize {
+    TextVectoriser::default().features
+}
+
 fn default_true() -> bool {
     true
 }
@@ -372,12 +904,18 @@ mod tests {
     #[test]
     fn test_spec_serialization() {
         let mut spec = PipelineSpec::new("test_pipeline");
-        spec.steps.push(Step::DropColumns {
-            columns: vec!["col1".to_owned(), "col2".to_owned()],
-        });
-        spec.steps.push(Step::TrimWhitespace {
-            columns: vec!["name".to_owned()],
-        });
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["col1".to_owned(), "col2".to_owned()],
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::TrimWhitespace {
+                columns: vec!["name".to_owned()],
+            }
+            .into(),
+        );
 
         // Serialize to JSON
         let json = spec.to_json().expect("Failed to serialize");
@@ -391,6 +929,33 @@ mod tests {
         assert_eq!(parsed.steps.len(), 2);
     }
 
+    #[test]
+    fn test_step_error_policy_round_trip() {
+        let json = r#"{
+            "version": "0.1",
+            "name": "policies",
+            "input": {},
+            "schema": {},
+            "steps": [
+                {"op": "limit", "n": 5, "checkpoint": true},
+                {"op": "cast_types", "columns": {"age": "i64"}, "on_error": "quarantine"}
+            ],
+            "output": {}
+        }"#;
+        let spec = PipelineSpec::from_json(json).unwrap();
+        assert_eq!(spec.steps[0].on_error, ErrorPolicy::Skip);
+        assert_eq!(spec.steps[1].on_error, ErrorPolicy::Quarantine);
+        assert!(matches!(spec.steps[0].step, Step::Limit { n: 5 }));
+        assert!(spec.steps[0].checkpoint);
+        assert!(!spec.steps[1].checkpoint);
+
+        let out = spec.to_json().unwrap();
+        assert_eq!(out.matches("on_error").count(), 1);
+        assert_eq!(out.matches("checkpoint").count(), 1);
+        let reparsed = PipelineSpec::from_json(&out).unwrap();
+        assert_eq!(reparsed.to_json().unwrap(), out);
+    }
+
     #[test]
     fn test_from_clean_configs() {
         let mut configs = HashMap::new();
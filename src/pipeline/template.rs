@@ -0,0 +1,243 @@
+//! Template variables for pipeline specs.
+//!
+//! Any string in a spec (paths, step fields, column names) may contain
+//! placeholders that are filled in at run time:
+//!
+//! - `{date}`: the run date as `YYYY-MM-DD` (overridable with a `date` param)
+//! - `{input_stem}`: the main input's file name without its extension
+//! - `{env:VAR}`: the value of environment variable `VAR`
+//! - `{name}`: a parameter declared in `params` or passed at run time
+//!
+//! `{input}` and `{output}` are left alone for [`super::external`] to fill in.
+//! A `$` directly before the brace (as in regex replacements like `${1}`)
+//! also leaves the text untouched.
+
+use super::spec::PipelineSpec;
+use anyhow::{Context as _, Result, bail};
+use chrono::Local;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::LazyLock;
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\$?)\{(env:)?([A-Za-z_][A-Za-z0-9_]*)\}").expect("valid placeholder regex")
+});
+
+/// Names filled in by the pipeline itself rather than by params
+const BUILTIN_VARIABLES: &[&str] = &["date", "input_stem"];
+
+/// Names reserved for other stages and never substituted here
+const PASSTHROUGH_VARIABLES: &[&str] = &["input", "output"];
+
+/// Values available to placeholders during one pipeline run
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    values: HashMap<String, String>,
+}
+
+impl TemplateVars {
+    /// Build the variables for running against `input_path`. `params`
+    /// override the built-in values, so a `date` param pins the run date.
+    pub fn new(input_path: &Path, params: &HashMap<String, String>) -> Self {
+        let mut values = HashMap::new();
+        values.insert(
+            "date".to_owned(),
+            Local::now().format("%Y-%m-%d").to_string(),
+        );
+        values.insert(
+            "input_stem".to_owned(),
+            input_path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        );
+        values.extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Self { values }
+    }
+
+    /// Replace every placeholder in `template`, failing on any that has no value
+    pub fn resolve(&self, template: &str) -> Result<String> {
+        let mut missing = BTreeSet::new();
+        let resolved = PLACEHOLDER.replace_all(template, |caps: &Captures<'_>| {
+            let whole = caps[0].to_owned();
+            let name = &caps[3];
+            if !caps[1].is_empty() {
+                return whole;
+            }
+            if caps.get(2).is_some() {
+                return std::env::var(name).unwrap_or_else(|_| {
+                    missing.insert(format!("env:{name}"));
+                    whole
+                });
+            }
+            if PASSTHROUGH_VARIABLES.contains(&name) {
+                return whole;
+            }
+            self.values.get(name).cloned().unwrap_or_else(|| {
+                missing.insert(name.to_owned());
+                whole
+            })
+        });
+
+        if !missing.is_empty() {
+            bail!(
+                "Unresolved template variables in '{template}': {}",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        }
+        Ok(resolved.into_owned())
+    }
+}
+
+/// Return a copy of `spec` with every placeholder in its strings resolved.
+/// The `params` map itself is copied as-is.
+pub fn resolve_spec(spec: &PipelineSpec, vars: &TemplateVars) -> Result<PipelineSpec> {
+    let mut value = serde_json::to_value(spec).context("Failed to serialize pipeline spec")?;
+    if let Value::Object(map) = &mut value {
+        for (key, field) in map.iter_mut() {
+            if key != "params" {
+                resolve_value(field, vars)?;
+            }
+        }
+    }
+    serde_json::from_value(value).context("Failed to rebuild pipeline spec after templating")
+}
+
+fn resolve_value(value: &mut Value, vars: &TemplateVars) -> Result<()> {
+    match value {
+        Value::String(s) => *s = vars.resolve(s)?,
+        Value::Array(items) => {
+            for item in items {
+                resolve_value(item, vars)?;
+            }
+        }
+        Value::Object(map) => {
+            let entries = std::mem::take(map);
+            for (key, mut field) in entries {
+                resolve_value(&mut field, vars)?;
+                map.insert(vars.resolve(&key)?, field);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+/// Placeholders in `spec` that cannot be resolved before the run: unset
+/// environment variables and names that are neither built in nor declared
+/// in `spec.params`. Run-time params must be declared to pass this check.
+pub fn unresolved_placeholders(spec: &PipelineSpec) -> Vec<String> {
+    let Ok(mut value) = serde_json::to_value(spec) else {
+        return Vec::new();
+    };
+    if let Value::Object(map) = &mut value {
+        map.remove("params");
+    }
+
+    let mut missing = BTreeSet::new();
+    collect_unresolved(&value, spec, &mut missing);
+    missing.into_iter().collect()
+}
+
+fn collect_unresolved(value: &Value, spec: &PipelineSpec, missing: &mut BTreeSet<String>) {
+    match value {
+        Value::String(s) => check_placeholders(s, spec, missing),
+        Value::Array(items) => {
+            for item in items {
+                collect_unresolved(item, spec, missing);
+            }
+        }
+        Value::Object(map) => {
+            for (key, field) in map {
+                check_placeholders(key, spec, missing);
+                collect_unresolved(field, spec, missing);
+            }
+        }
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+}
+
+fn check_placeholders(text: &str, spec: &PipelineSpec, missing: &mut BTreeSet<String>) {
+    for caps in PLACEHOLDER.captures_iter(text) {
+        let name = &caps[3];
+        if !caps[1].is_empty() {
+            continue;
+        }
+        if caps.get(2).is_some() {
+            if std::env::var(name).is_err() {
+                missing.insert(format!("env:{name}"));
+            }
+        } else if !BUILTIN_VARIABLES.contains(&name)
+            && !PASSTHROUGH_VARIABLES.contains(&name)
+            && !spec.params.contains_key(name)
+        {
+            missing.insert(name.to_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::spec::Step;
+
+    #[test]
+    fn test_resolve_builtins_params_and_env() {
+        let params = HashMap::from([("region".to_owned(), "north".to_owned())]);
+        let vars = TemplateVars::new(Path::new("/data/sales_2024.csv"), &params);
+        let today = Local::now().format("%Y-%m-%d").to_string();
+
+        assert_eq!(
+            vars.resolve("out/{input_stem}_{region}_{date}.parquet")
+                .unwrap(),
+            format!("out/sales_2024_north_{today}.parquet")
+        );
+        assert_eq!(vars.resolve("${1}-{input}").unwrap(), "${1}-{input}");
+        assert_eq!(
+            vars.resolve("{env:PATH}").unwrap(),
+            std::env::var("PATH").unwrap()
+        );
+
+        let err = vars
+            .resolve("{missing}/{env:BEEFCAKE_NOT_SET_ANYWHERE}")
+            .unwrap_err();
+        assert!(err.to_string().contains("missing"));
+        assert!(err.to_string().contains("env:BEEFCAKE_NOT_SET_ANYWHERE"));
+
+        let pinned = HashMap::from([("date".to_owned(), "2024-01-31".to_owned())]);
+        let vars = TemplateVars::new(Path::new("in.csv"), &pinned);
+        assert_eq!(vars.resolve("{date}").unwrap(), "2024-01-31");
+    }
+
+    #[test]
+    fn test_resolve_spec_and_unresolved_placeholders() {
+        let mut spec = PipelineSpec::new("templated");
+        spec.params.insert("suffix".to_owned(), "v1".to_owned());
+        spec.steps.push(
+            Step::WithColumn {
+                name: "source_{input_stem}".to_owned(),
+                expression: "\"{suffix}\"".to_owned(),
+            }
+            .into(),
+        );
+        spec.output.path_template = "out/{input_stem}_{suffix}.csv".to_owned();
+        assert!(unresolved_placeholders(&spec).is_empty());
+
+        let vars = TemplateVars::new(Path::new("orders.csv"), &spec.params);
+        let resolved = resolve_spec(&spec, &vars).unwrap();
+        assert_eq!(resolved.output.path_template, "out/orders_v1.csv");
+        let Step::WithColumn { name, expression } = &resolved.steps[0].step else {
+            panic!("expected with_column step");
+        };
+        assert_eq!(name, "source_orders");
+        assert_eq!(expression, "\"v1\"");
+
+        spec.output.path_template = "out/{undeclared}.csv".to_owned();
+        assert_eq!(
+            unresolved_placeholders(&spec),
+            vec!["undeclared".to_owned()]
+        );
+    }
+}
@@ -1,34 +1,125 @@
 //! Pipeline specification validation.
 //!
 //! Validates pipeline specs against input data schemas before execution,
-//! catching errors early with actionable error messages.
+//! catching errors early with actionable error messages. Each finding carries
+//! a stable [`ErrorCode`], the offending field and, where possible, a
+//! suggested fix, so the builder can highlight the exact input and render
+//! its own (localised) message from the code and `params`.
 
-use super::spec::{PipelineSpec, SchemaMatchMode, Step};
+use super::contract::DTYPE_NAMES;
+use super::delivery::check_sftp_value;
+use super::resolution::rename_schema;
+use super::spec::{DeliveryDestination, PipelineSpec, SchemaMatchMode, Step};
+use super::template::unresolved_placeholders;
+use crate::analyser::logic::TextVectorMethod;
+use crate::analyser::logic::binning::check_bins;
+use crate::analyser::logic::date_parts::date_part_column;
+use crate::analyser::logic::encoding::check_encoding;
+use crate::analyser::logic::naming::edit_distance;
+use crate::analyser::logic::split_merge::template_columns;
+use crate::analyser::logic::types::EncodingMethod;
 use anyhow::Result;
 use polars::prelude::*;
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// How serious a validation finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The pipeline cannot run as written
+    Error,
+    /// The pipeline runs but probably not as intended
+    Warning,
+}
+
+/// Stable, machine-readable identifier for a validation finding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    UnsupportedVersion,
+    MissingRequiredColumn,
+    UnexpectedColumns,
+    UnknownColumn,
+    ColumnAlreadyExists,
+    DuplicateOutputColumn,
+    InvalidType,
+    InvalidRegex,
+    InvalidExpression,
+    OutOfRange,
+    MissingValue,
+    ConflictingOptions,
+    UndeclaredInput,
+    InputColumnsMismatch,
+    UnresolvedVariable,
+    /// Lint: the step has no effect
+    NoOpStep,
+    /// Lint: a later step undoes or contradicts an earlier one
+    ConflictingSteps,
+    /// Lint: steps on the same column are in an unusual order
+    StepOrder,
+}
 
 /// Validation error with helpful context
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationError {
     pub step_index: Option<usize>,
+    /// English description, for logs and the CLI
     pub message: String,
+    pub code: ErrorCode,
+    pub severity: Severity,
+    /// Step (or spec) field at fault, e.g. `columns` or `pattern`
+    pub field: Option<String>,
+    /// Suggested fix, e.g. the closest existing column name
+    pub suggestion: Option<String>,
+    /// Values interpolated into the message, keyed by name
+    pub params: BTreeMap<String, String>,
 }
 
 impl ValidationError {
-    fn new(step_index: Option<usize>, message: impl Into<String>) -> Self {
+    fn new(step_index: Option<usize>, code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
             step_index,
             message: message.into(),
+            code,
+            severity: Severity::Error,
+            field: None,
+            suggestion: None,
+            params: BTreeMap::new(),
         }
     }
 
-    fn step(step_index: usize, message: impl Into<String>) -> Self {
-        Self::new(Some(step_index), message)
+    pub(super) fn step(step_index: usize, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(Some(step_index), code, message)
+    }
+
+    pub(super) fn schema(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(None, code, message)
+    }
+
+    pub(super) fn field(mut self, field: &str) -> Self {
+        self.field = Some(field.to_owned());
+        self
+    }
+
+    pub(super) fn param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_owned(), value.to_string());
+        self
     }
 
-    fn schema(message: impl Into<String>) -> Self {
-        Self::new(None, message)
+    pub(super) fn suggest(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    pub(super) fn warning(mut self) -> Self {
+        self.severity = Severity::Warning;
+        self
+    }
+
+    /// Whether this finding blocks execution
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
     }
 }
 
@@ -42,38 +133,246 @@ impl std::fmt::Display for ValidationError {
     }
 }
 
+/// The error of a spec that does not validate against its input
+#[derive(Debug, Clone)]
+pub struct ValidationFailed(pub Vec<ValidationError>);
+
+impl std::fmt::Display for ValidationFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Pipeline validation failed:")?;
+        for error in &self.0 {
+            write!(f, "\n{error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationFailed {}
+
 /// Validate a pipeline spec against an input schema
 pub fn validate_pipeline(
     spec: &PipelineSpec,
     input_schema: &Schema,
+) -> Result<Vec<ValidationError>> {
+    validate_pipeline_with_inputs(spec, input_schema, &HashMap::new())
+}
+
+/// Validate a pipeline spec against the main input schema and the schemas
+/// of its named inputs. Named inputs without a schema are only checked for
+/// being declared.
+pub fn validate_pipeline_with_inputs(
+    spec: &PipelineSpec,
+    input_schema: &Schema,
+    source_schemas: &HashMap<String, Schema>,
 ) -> Result<Vec<ValidationError>> {
     let mut errors = Vec::new();
 
     // Validate spec version
     if spec.version != super::spec::SPEC_VERSION {
-        errors.push(ValidationError::schema(format!(
-            "Unsupported spec version '{}', expected '{}'",
-            spec.version,
-            super::spec::SPEC_VERSION
-        )));
+        errors.push(
+            ValidationError::schema(
+                ErrorCode::UnsupportedVersion,
+                format!(
+                    "Unsupported spec version '{}', expected '{}'",
+                    spec.version,
+                    super::spec::SPEC_VERSION
+                ),
+            )
+            .field("version")
+            .param("version", &spec.version)
+            .suggest(super::spec::SPEC_VERSION),
+        );
     }
 
+    // Confirmed column matches rename the input before anything reads it
+    validate_column_renames(spec, input_schema, &mut errors);
+    let input_schema = &rename_schema(input_schema, &spec.input.column_renames);
+
     // Validate schema requirements
     validate_schema_requirements(spec, input_schema, &mut errors);
 
+    for name in unresolved_placeholders(spec) {
+        errors.push(
+            ValidationError::schema(
+                ErrorCode::UnresolvedVariable,
+                format!(
+                    "Template variable '{{{name}}}' is not set (declare it in params or the environment)"
+                ),
+            )
+            .field("params")
+            .param("variable", &name),
+        );
+    }
+
+    validate_deliveries(spec, &mut errors);
+
     // Simulate step-by-step execution to track schema changes
     let mut current_columns: HashSet<String> = input_schema
         .iter_names()
         .map(|s| s.as_str().to_owned())
         .collect();
 
-    for (idx, step) in spec.steps.iter().enumerate() {
+    for (idx, step) in spec.steps.iter().map(|s| &s.step).enumerate() {
+        if let Step::Concat { inputs } | Step::Union { inputs } = step {
+            validate_stacked_inputs(
+                spec,
+                inputs,
+                idx,
+                &current_columns,
+                source_schemas,
+                &mut errors,
+            );
+        }
         validate_step(step, idx, &mut current_columns, &mut errors);
     }
 
     Ok(errors)
 }
 
+/// Check that every delivery target names a destination, and that SFTP
+/// fields cannot be read by `sftp` as options or commands
+fn validate_deliveries(spec: &PipelineSpec, errors: &mut Vec<ValidationError>) {
+    for (idx, target) in spec.output.deliver.iter().enumerate() {
+        let required: Vec<(&str, &String)> = match &target.destination {
+            DeliveryDestination::Path { dir } => vec![("dir", dir)],
+            DeliveryDestination::Sftp {
+                host,
+                user,
+                remote_dir,
+                ..
+            } => vec![("host", host), ("user", user), ("remote_dir", remote_dir)],
+        };
+        for (field, _) in required.iter().filter(|(_, v)| v.trim().is_empty()) {
+            errors.push(
+                ValidationError::schema(
+                    ErrorCode::MissingValue,
+                    format!("Delivery target {} is missing '{field}'", idx + 1),
+                )
+                .field(&format!("output.deliver.{field}"))
+                .param("target", idx + 1),
+            );
+        }
+
+        if let DeliveryDestination::Sftp { identity_file, .. } = &target.destination {
+            let fields = required
+                .iter()
+                .copied()
+                .chain(identity_file.as_ref().map(|v| ("identity_file", v)));
+            for (field, value) in fields.filter(|(_, v)| !v.trim().is_empty()) {
+                if let Err(e) = check_sftp_value(field, value) {
+                    errors.push(
+                        ValidationError::schema(ErrorCode::OutOfRange, e.to_string())
+                            .field(&format!("output.deliver.{field}"))
+                            .param("target", idx + 1),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Check that stacked inputs are declared and share the current columns
+fn validate_stacked_inputs(
+    spec: &PipelineSpec,
+    inputs: &[String],
+    idx: usize,
+    columns: &HashSet<String>,
+    source_schemas: &HashMap<String, Schema>,
+    errors: &mut Vec<ValidationError>,
+) {
+    if inputs.is_empty() {
+        errors.push(
+            ValidationError::step(
+                idx,
+                ErrorCode::MissingValue,
+                "Concat/union step needs at least one input".to_owned(),
+            )
+            .field("inputs"),
+        );
+    }
+
+    let declared: Vec<&str> = spec.input.sources.iter().map(|s| s.name.as_str()).collect();
+    for name in inputs {
+        if !declared.contains(&name.as_str()) {
+            let mut error = ValidationError::step(
+                idx,
+                ErrorCode::UndeclaredInput,
+                format!("Input '{name}' is not declared in input.sources"),
+            )
+            .field("inputs")
+            .param("input", name);
+            if let Some(closest) = closest_name(name, declared.iter().copied()) {
+                error = error.suggest(closest);
+            }
+            errors.push(error);
+            continue;
+        }
+        let Some(schema) = source_schemas.get(name) else {
+            continue;
+        };
+        let source_cols: HashSet<String> =
+            schema.iter_names().map(|s| s.as_str().to_owned()).collect();
+
+        let mut missing: Vec<&String> = columns.difference(&source_cols).collect();
+        let mut extra: Vec<&String> = source_cols.difference(columns).collect();
+        missing.sort();
+        extra.sort();
+        if !missing.is_empty() {
+            errors.push(
+                ValidationError::step(
+                    idx,
+                    ErrorCode::InputColumnsMismatch,
+                    format!("Input '{name}' is missing columns: {missing:?}"),
+                )
+                .field("inputs")
+                .param("input", name),
+            );
+        }
+        if !extra.is_empty() {
+            errors.push(
+                ValidationError::step(
+                    idx,
+                    ErrorCode::InputColumnsMismatch,
+                    format!("Input '{name}' has unexpected columns: {extra:?}"),
+                )
+                .field("inputs")
+                .param("input", name),
+            );
+        }
+    }
+}
+
+/// Check that `input.column_renames` gives no two input columns one name
+fn validate_column_renames(
+    spec: &PipelineSpec,
+    input_schema: &Schema,
+    errors: &mut Vec<ValidationError>,
+) {
+    let renames = &spec.input.column_renames;
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for name in input_schema.iter_names() {
+        let name = renames
+            .get(name.as_str())
+            .map_or(name.as_str(), String::as_str);
+        *names.entry(name).or_default() += 1;
+    }
+    let mut targets: Vec<(&String, &String)> = renames.iter().collect();
+    targets.sort();
+    for (from, to) in targets {
+        if names.get(to.as_str()).is_some_and(|&count| count > 1) {
+            errors.push(
+                ValidationError::schema(
+                    ErrorCode::ColumnAlreadyExists,
+                    format!("Cannot rename input column '{from}' to '{to}': target already exists"),
+                )
+                .field("column_renames")
+                .param("column", from)
+                .param("target", to),
+            );
+        }
+    }
+}
+
 /// Validate schema matching requirements
 fn validate_schema_requirements(
     spec: &PipelineSpec,
@@ -88,21 +387,34 @@ fn validate_schema_requirements(
     // Check required columns exist
     for required in &spec.schema.required_columns {
         if !input_cols.contains(required) {
-            errors.push(ValidationError::schema(format!(
-                "Required column '{required}' not found in input"
-            )));
+            let mut error = ValidationError::schema(
+                ErrorCode::MissingRequiredColumn,
+                format!("Required column '{required}' not found in input"),
+            )
+            .field("required_columns")
+            .param("column", required);
+            if let Some(closest) = closest_name(required, input_cols.iter().map(String::as_str)) {
+                error = error.suggest(closest);
+            }
+            errors.push(error);
         }
     }
 
     // Strict mode: no extra columns allowed
     if matches!(spec.schema.match_mode, SchemaMatchMode::Strict) {
         let required_set: HashSet<_> = spec.schema.required_columns.iter().cloned().collect();
-        let extra_cols: Vec<_> = input_cols.difference(&required_set).collect();
+        let mut extra_cols: Vec<_> = input_cols.difference(&required_set).collect();
+        extra_cols.sort();
 
         if !extra_cols.is_empty() {
-            errors.push(ValidationError::schema(format!(
-                "Strict mode: unexpected columns found: {extra_cols:?}"
-            )));
+            errors.push(
+                ValidationError::schema(
+                    ErrorCode::UnexpectedColumns,
+                    format!("Strict mode: unexpected columns found: {extra_cols:?}"),
+                )
+                .field("match_mode")
+                .suggest("tolerant"),
+            );
         }
     }
 }
@@ -118,8 +430,11 @@ fn validate_step(
         Step::DropColumns { columns: drop_cols } => {
             for col in drop_cols {
                 if !columns.contains(col) {
-                    errors.push(ValidationError::step(
+                    errors.push(unknown_column(
                         idx,
+                        "columns",
+                        col,
+                        columns,
                         format!("Cannot drop non-existent column '{col}'"),
                     ));
                 } else {
@@ -133,15 +448,24 @@ fn validate_step(
             #[expect(clippy::iter_over_hash_type)]
             for (from, to) in mapping {
                 if !columns.contains(from) {
-                    errors.push(ValidationError::step(
+                    errors.push(unknown_column(
                         idx,
+                        "mapping",
+                        from,
+                        columns,
                         format!("Cannot rename non-existent column '{from}'"),
                     ));
                 } else if columns.contains(to) && from != to {
-                    errors.push(ValidationError::step(
-                        idx,
-                        format!("Cannot rename '{from}' to '{to}': target already exists"),
-                    ));
+                    errors.push(
+                        ValidationError::step(
+                            idx,
+                            ErrorCode::ColumnAlreadyExists,
+                            format!("Cannot rename '{from}' to '{to}': target already exists"),
+                        )
+                        .field("mapping")
+                        .param("column", from)
+                        .param("target", to),
+                    );
                 } else {
                     columns.remove(from);
                     columns.insert(to.clone());
@@ -150,7 +474,14 @@ fn validate_step(
         }
 
         Step::TrimWhitespace { columns: trim_cols } => {
-            validate_columns_exist(trim_cols, columns, idx, "trim whitespace", errors);
+            validate_columns_exist(
+                trim_cols,
+                columns,
+                idx,
+                "trim whitespace",
+                "columns",
+                errors,
+            );
         }
 
         Step::CastTypes { columns: cast_cols } => {
@@ -159,6 +490,7 @@ fn validate_step(
                 columns,
                 idx,
                 "cast type",
+                "columns",
                 errors,
             );
 
@@ -166,10 +498,20 @@ fn validate_step(
             #[expect(clippy::iter_over_hash_type)]
             for (col, type_str) in cast_cols {
                 if !is_valid_type_string(type_str) {
-                    errors.push(ValidationError::step(
+                    let mut error = ValidationError::step(
                         idx,
+                        ErrorCode::InvalidType,
                         format!("Invalid type string '{type_str}' for column '{col}'"),
-                    ));
+                    )
+                    .field("columns")
+                    .param("column", col)
+                    .param("type", type_str);
+                    if let Some(closest) =
+                        closest_name(type_str, VALID_TYPE_STRINGS.iter().copied())
+                    {
+                        error = error.suggest(closest);
+                    }
+                    errors.push(error);
                 }
             }
         }
@@ -180,6 +522,7 @@ fn validate_step(
                 columns,
                 idx,
                 "parse dates",
+                "columns",
                 errors,
             );
         }
@@ -188,14 +531,21 @@ fn validate_step(
             strategy: _,
             columns: impute_cols,
         } => {
-            validate_columns_exist(impute_cols, columns, idx, "impute", errors);
+            validate_columns_exist(impute_cols, columns, idx, "impute", "columns", errors);
         }
 
         Step::OneHotEncode {
             columns: encode_cols,
             drop_original,
         } => {
-            validate_columns_exist(encode_cols, columns, idx, "one-hot encode", errors);
+            validate_columns_exist(
+                encode_cols,
+                columns,
+                idx,
+                "one-hot encode",
+                "columns",
+                errors,
+            );
 
             // After one-hot encoding, original columns are replaced with encoded versions
             if *drop_original {
@@ -211,7 +561,7 @@ fn validate_step(
             method: _,
             columns: norm_cols,
         } => {
-            validate_columns_exist(norm_cols, columns, idx, "normalize", errors);
+            validate_columns_exist(norm_cols, columns, idx, "normalize", "columns", errors);
         }
 
         Step::ClipOutliers {
@@ -219,34 +569,156 @@ fn validate_step(
             lower_quantile,
             upper_quantile,
         } => {
-            validate_columns_exist(clip_cols, columns, idx, "clip outliers", errors);
+            validate_columns_exist(clip_cols, columns, idx, "clip outliers", "columns", errors);
 
             if *lower_quantile < 0.0 || *lower_quantile > 1.0 {
-                errors.push(ValidationError::step(
-                    idx,
-                    format!("Invalid lower_quantile: {lower_quantile} (must be 0-1)"),
-                ));
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::OutOfRange,
+                        format!("Invalid lower_quantile: {lower_quantile} (must be 0-1)"),
+                    )
+                    .field("lower_quantile")
+                    .param("value", lower_quantile)
+                    .suggest(lower_quantile.clamp(0.0, 1.0).to_string()),
+                );
             }
 
             if *upper_quantile < 0.0 || *upper_quantile > 1.0 {
-                errors.push(ValidationError::step(
-                    idx,
-                    format!("Invalid upper_quantile: {upper_quantile} (must be 0-1)"),
-                ));
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::OutOfRange,
+                        format!("Invalid upper_quantile: {upper_quantile} (must be 0-1)"),
+                    )
+                    .field("upper_quantile")
+                    .param("value", upper_quantile)
+                    .suggest(upper_quantile.clamp(0.0, 1.0).to_string()),
+                );
             }
 
             if lower_quantile >= upper_quantile {
-                errors.push(ValidationError::step(
-                    idx,
-                    "lower_quantile must be less than upper_quantile".to_owned(),
-                ));
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::ConflictingOptions,
+                        "lower_quantile must be less than upper_quantile".to_owned(),
+                    )
+                    .field("lower_quantile"),
+                );
             }
         }
 
         Step::ExtractNumbers {
             columns: extract_cols,
         } => {
-            validate_columns_exist(extract_cols, columns, idx, "extract numbers", errors);
+            validate_columns_exist(
+                extract_cols,
+                columns,
+                idx,
+                "extract numbers",
+                "columns",
+                errors,
+            );
+        }
+
+        Step::Bin {
+            column,
+            strategy,
+            labels,
+        } => {
+            validate_columns_exist(
+                std::slice::from_ref(column),
+                columns,
+                idx,
+                "bin",
+                "column",
+                errors,
+            );
+            if let Err(e) = check_bins(strategy, &[]) {
+                errors.push(
+                    ValidationError::step(idx, ErrorCode::OutOfRange, e.to_string())
+                        .field("strategy"),
+                );
+            } else if let Err(e) = check_bins(strategy, labels) {
+                errors.push(
+                    ValidationError::step(idx, ErrorCode::ConflictingOptions, e.to_string())
+                        .field("labels")
+                        .param("bins", strategy.bins()),
+                );
+            }
+        }
+
+        Step::EncodeCategories {
+            column,
+            encoding,
+            mapping_path: _,
+        } => {
+            validate_columns_exist(
+                std::slice::from_ref(column),
+                columns,
+                idx,
+                "encode",
+                "column",
+                errors,
+            );
+            if let EncodingMethod::Target { target, .. } = &encoding.method
+                && !target.is_empty()
+            {
+                validate_columns_exist(
+                    std::slice::from_ref(target),
+                    columns,
+                    idx,
+                    "encode against",
+                    "target",
+                    errors,
+                );
+            }
+            if let Err(e) = check_encoding(encoding) {
+                let missing_target = matches!(
+                    &encoding.method,
+                    EncodingMethod::Target { target, .. } if target.is_empty()
+                );
+                let (code, field) = if missing_target {
+                    (ErrorCode::MissingValue, "target")
+                } else {
+                    (ErrorCode::OutOfRange, "encoding")
+                };
+                errors.push(ValidationError::step(idx, code, e.to_string()).field(field));
+            }
+        }
+
+        Step::VectoriseText {
+            columns: text_cols,
+            method,
+            features,
+            drop_original,
+        } => {
+            validate_columns_exist(text_cols, columns, idx, "vectorise text", "columns", errors);
+            if *features == 0 {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::OutOfRange,
+                        "Invalid features: 0 (must be at least 1)".to_owned(),
+                    )
+                    .field("features")
+                    .param("value", features)
+                    .suggest("20"),
+                );
+            }
+
+            // TF-IDF term columns depend on the data; hash buckets do not
+            if *method == TextVectorMethod::Hashing {
+                for col in text_cols {
+                    columns.extend((0..*features).map(|i| format!("{col}_h{i}")));
+                }
+            }
+            if *drop_original {
+                for col in text_cols {
+                    columns.remove(col);
+                }
+            }
         }
 
         Step::RegexReplace {
@@ -254,15 +726,471 @@ fn validate_step(
             pattern,
             replacement: _,
         } => {
-            validate_columns_exist(regex_cols, columns, idx, "regex replace", errors);
+            validate_columns_exist(regex_cols, columns, idx, "regex replace", "columns", errors);
 
             // Validate regex pattern
             if let Err(e) = regex::Regex::new(pattern) {
-                errors.push(ValidationError::step(
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidRegex,
+                        format!("Invalid regex pattern: {e}"),
+                    )
+                    .field("pattern")
+                    .param("error", e)
+                    .suggest(regex::escape(pattern)),
+                );
+            }
+        }
+
+        Step::MapValues {
+            columns: map_cols,
+            mapping: _,
+        } => {
+            validate_columns_exist(map_cols, columns, idx, "map values", "columns", errors);
+        }
+
+        Step::SplitColumn {
+            column,
+            delimiter,
+            regex,
+            into,
+            drop_original,
+        } => {
+            validate_columns_exist(
+                std::slice::from_ref(column),
+                columns,
+                idx,
+                "split",
+                "column",
+                errors,
+            );
+            if delimiter.is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Split delimiter cannot be empty".to_owned(),
+                    )
+                    .field("delimiter"),
+                );
+            } else if *regex && let Err(e) = regex::Regex::new(delimiter) {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidRegex,
+                        format!("Invalid split regex: {e}"),
+                    )
+                    .field("delimiter")
+                    .param("error", e)
+                    .suggest(regex::escape(delimiter)),
+                );
+            }
+            if into.iter().all(|name| name.trim().is_empty()) {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Split step needs at least one column to split into".to_owned(),
+                    )
+                    .field("into"),
+                );
+            }
+            if *drop_original {
+                columns.remove(column);
+            }
+            columns.extend(into.iter().cloned());
+        }
+
+        Step::MergeColumns {
+            template,
+            into,
+            drop_sources,
+        } => {
+            if into.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Merged column name cannot be empty".to_owned(),
+                    )
+                    .field("into"),
+                );
+            }
+            match template_columns(template) {
+                Ok(sources) => {
+                    validate_columns_exist(&sources, columns, idx, "merge", "template", errors);
+                    if *drop_sources {
+                        for source in &sources {
+                            columns.remove(source);
+                        }
+                    }
+                }
+                Err(e) => errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidExpression,
+                        format!("Invalid merge template: {e}"),
+                    )
+                    .field("template")
+                    .param("error", e),
+                ),
+            }
+            columns.insert(into.clone());
+        }
+
+        Step::ExtractDateParts { column, parts } => {
+            validate_columns_exist(
+                std::slice::from_ref(column),
+                columns,
+                idx,
+                "extract date parts",
+                "column",
+                errors,
+            );
+            if parts.is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Choose at least one date part to extract".to_owned(),
+                    )
+                    .field("parts"),
+                );
+            }
+            columns.extend(parts.iter().map(|&part| date_part_column(column, part)));
+        }
+
+        Step::WithColumn { name, expression } => {
+            if name.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Derived column name cannot be empty".to_owned(),
+                    )
+                    .field("name"),
+                );
+            }
+            if let Err(e) = super::expression::validate(expression, columns) {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidExpression,
+                        format!("Invalid expression for '{name}': {e}"),
+                    )
+                    .field("expression")
+                    .param("column", name)
+                    .param("error", e),
+                );
+            }
+            columns.insert(name.clone());
+        }
+
+        Step::Aggregate {
+            group_by,
+            aggregations,
+        } => {
+            validate_columns_exist(group_by, columns, idx, "group by", "group_by", errors);
+            if aggregations.is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Aggregate step needs at least one aggregation".to_owned(),
+                    )
+                    .field("aggregations"),
+                );
+            }
+            let agg_cols: Vec<String> = aggregations.iter().map(|a| a.column.clone()).collect();
+            validate_columns_exist(&agg_cols, columns, idx, "aggregate", "aggregations", errors);
+
+            // The output holds only the group keys and aggregated columns
+            let mut output: HashSet<String> = group_by.iter().cloned().collect();
+            for agg in aggregations {
+                if !output.insert(agg.output_name()) {
+                    errors.push(
+                        ValidationError::step(
+                            idx,
+                            ErrorCode::DuplicateOutputColumn,
+                            format!("Duplicate output column '{}'", agg.output_name()),
+                        )
+                        .field("aggregations")
+                        .param("column", agg.output_name())
+                        .suggest(format!(
+                            "{}_{}",
+                            agg.column,
+                            agg.function.as_str()
+                        )),
+                    );
+                }
+            }
+            *columns = output;
+        }
+
+        Step::Sort { by, descending } => {
+            if by.is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Sort step needs at least one column".to_owned(),
+                    )
+                    .field("by"),
+                );
+            }
+            validate_columns_exist(by, columns, idx, "sort", "by", errors);
+            if descending.len() > 1 && descending.len() != by.len() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::ConflictingOptions,
+                        format!(
+                            "Sort has {} descending flags for {} columns",
+                            descending.len(),
+                            by.len()
+                        ),
+                    )
+                    .field("descending")
+                    .param("flags", descending.len())
+                    .param("columns", by.len()),
+                );
+            }
+        }
+
+        Step::Limit { .. } | Step::Concat { .. } | Step::Union { .. } => {}
+
+        Step::Sample { fraction, n, .. } => match (fraction, n) {
+            (Some(fraction), None) if !(0.0..=1.0).contains(fraction) => {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::OutOfRange,
+                        format!("Invalid sample fraction: {fraction} (must be 0-1)"),
+                    )
+                    .field("fraction")
+                    .param("value", fraction)
+                    .suggest(fraction.clamp(0.0, 1.0).to_string()),
+                );
+            }
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => errors.push(
+                ValidationError::step(
+                    idx,
+                    ErrorCode::ConflictingOptions,
+                    "Sample step needs exactly one of 'fraction' or 'n'".to_owned(),
+                )
+                .field("fraction"),
+            ),
+        },
+
+        Step::Pivot {
+            index,
+            columns: pivot_col,
+            values,
+            agg: _,
+        } => {
+            validate_columns_exist(index, columns, idx, "pivot index", "index", errors);
+            validate_columns_exist(
+                std::slice::from_ref(pivot_col),
+                columns,
+                idx,
+                "pivot",
+                "columns",
+                errors,
+            );
+            validate_columns_exist(
+                std::slice::from_ref(values),
+                columns,
+                idx,
+                "pivot",
+                "values",
+                errors,
+            );
+            if index.contains(pivot_col) || index.contains(values) || pivot_col == values {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::ConflictingOptions,
+                        "Pivot index, columns and values must be different columns".to_owned(),
+                    )
+                    .field("index"),
+                );
+            }
+
+            // New column names come from the data, so only the index is known here
+            *columns = index.iter().cloned().collect();
+        }
+
+        Step::Unpivot {
+            id_vars,
+            value_vars,
+        } => {
+            validate_columns_exist(id_vars, columns, idx, "unpivot id", "id_vars", errors);
+            validate_columns_exist(value_vars, columns, idx, "unpivot", "value_vars", errors);
+
+            let mut output: HashSet<String> = id_vars.iter().cloned().collect();
+            output.insert("variable".to_owned());
+            output.insert("value".to_owned());
+            *columns = output;
+        }
+
+        Step::Transpose {
+            header_column,
+            names_column,
+        } => {
+            if let Some(header_column) = header_column {
+                validate_columns_exist(
+                    std::slice::from_ref(header_column),
+                    columns,
                     idx,
-                    format!("Invalid regex pattern: {e}"),
-                ));
+                    "transpose on",
+                    "header_column",
+                    errors,
+                );
+            }
+            if names_column.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Transpose names column cannot be empty".to_owned(),
+                    )
+                    .field("names_column"),
+                );
+            }
+
+            // New column names come from the data, so only the names column is known here
+            *columns = HashSet::from([names_column.clone()]);
+        }
+
+        Step::CompletenessScore { name, weights } => {
+            if name.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Completeness column name cannot be empty".to_owned(),
+                    )
+                    .field("name")
+                    .suggest(crate::analyser::logic::COMPLETENESS_COLUMN),
+                );
             }
+            let weighted: Vec<String> = weights.keys().cloned().collect();
+            validate_columns_exist(
+                &weighted,
+                columns,
+                idx,
+                "completeness score",
+                "weights",
+                errors,
+            );
+            if weights.values().any(|w| !w.is_finite() || *w < 0.0) {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::OutOfRange,
+                        "Completeness weights must be non-negative numbers".to_owned(),
+                    )
+                    .field("weights"),
+                );
+            }
+            columns.insert(name.clone());
+        }
+
+        // Whether the data meets the contract is only known at run time
+        Step::ExpectSchema {
+            columns: expected,
+            on_violation: _,
+        } => {
+            let mut seen = HashSet::new();
+            for column in expected {
+                if column.name.trim().is_empty() {
+                    errors.push(
+                        ValidationError::step(
+                            idx,
+                            ErrorCode::MissingValue,
+                            "Expected column name cannot be empty".to_owned(),
+                        )
+                        .field("columns"),
+                    );
+                } else if !seen.insert(column.name.as_str()) {
+                    errors.push(
+                        ValidationError::step(
+                            idx,
+                            ErrorCode::ConflictingOptions,
+                            format!("Column '{}' is expected more than once", column.name),
+                        )
+                        .field("columns")
+                        .param("column", &column.name),
+                    );
+                }
+                if let Some(dtype) = &column.dtype
+                    && !DTYPE_NAMES.contains(&dtype.as_str())
+                {
+                    let mut error = ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidType,
+                        format!(
+                            "Unknown type '{dtype}' for column '{}' (use one of {})",
+                            column.name,
+                            DTYPE_NAMES.join(", ")
+                        ),
+                    )
+                    .field("columns")
+                    .param("column", &column.name)
+                    .param("type", dtype);
+                    if let Some(closest) = closest_name(dtype, DTYPE_NAMES.iter().copied()) {
+                        error = error.suggest(closest);
+                    }
+                    errors.push(error);
+                }
+            }
+        }
+
+        Step::WriteOutput {
+            path_template,
+            format,
+        } => {
+            if path_template.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "Output path cannot be empty".to_owned(),
+                    )
+                    .field("path_template"),
+                );
+            }
+            if let Some(format) = format
+                && !matches!(format.as_str(), "csv" | "json" | "parquet")
+            {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::InvalidType,
+                        format!("Unsupported output format '{format}' (use csv, json or parquet)"),
+                    )
+                    .field("format")
+                    .param("format", format)
+                    .suggest("parquet"),
+                );
+            }
+        }
+
+        Step::External { command, .. } => {
+            if command.trim().is_empty() {
+                errors.push(
+                    ValidationError::step(
+                        idx,
+                        ErrorCode::MissingValue,
+                        "External command cannot be empty".to_owned(),
+                    )
+                    .field("command"),
+                );
+            }
+            // The command decides its own output columns; keep the current ones
+            // so later steps are still checked against something
         }
     }
 }
@@ -273,24 +1201,66 @@ fn validate_columns_exist(
     available_cols: &HashSet<String>,
     step_idx: usize,
     operation: &str,
+    field: &str,
     errors: &mut Vec<ValidationError>,
 ) {
     for col in target_cols {
         if !available_cols.contains(col) {
-            errors.push(ValidationError::step(
+            errors.push(unknown_column(
                 step_idx,
+                field,
+                col,
+                available_cols,
                 format!("Cannot {operation} non-existent column '{col}'"),
             ));
         }
     }
 }
 
+/// Error for a reference to a column that does not exist at this step,
+/// suggesting the closest available name
+fn unknown_column(
+    step_idx: usize,
+    field: &str,
+    column: &str,
+    available_cols: &HashSet<String>,
+    message: String,
+) -> ValidationError {
+    let error = ValidationError::step(step_idx, ErrorCode::UnknownColumn, message)
+        .field(field)
+        .param("column", column);
+    match closest_name(column, available_cols.iter().map(String::as_str)) {
+        Some(closest) => error.suggest(closest),
+        None => error,
+    }
+}
+
+/// The candidate closest to `target` by case-insensitive edit distance, if
+/// any is close enough to be a plausible typo
+fn closest_name<'a>(target: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let target_lower = target.to_lowercase();
+    let max_distance = (target.chars().count() / 3).max(1);
+    candidates
+        .map(|c| (edit_distance(&target_lower, &c.to_lowercase()), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, c)| c.to_owned())
+}
+
+const VALID_TYPE_STRINGS: &[&str] = &[
+    "i64",
+    "f64",
+    "String",
+    "Boolean",
+    "Numeric",
+    "Text",
+    "Categorical",
+    "Temporal",
+];
+
 /// Check if a type string is valid
 fn is_valid_type_string(type_str: &str) -> bool {
-    matches!(
-        type_str,
-        "i64" | "f64" | "String" | "Boolean" | "Numeric" | "Text" | "Categorical" | "Temporal"
-    )
+    VALID_TYPE_STRINGS.contains(&type_str)
 }
 
 #[cfg(test)]
@@ -307,6 +1277,114 @@ mod tests {
         ])
     }
 
+    #[test]
+    fn test_validate_concat_inputs() {
+        let mut spec = PipelineSpec::new("test");
+        spec.input.sources.push(crate::pipeline::spec::NamedInput {
+            name: "march".to_owned(),
+            path: String::new(),
+        });
+        spec.steps.push(
+            Step::Concat {
+                inputs: vec!["march".to_owned(), "april".to_owned()],
+            }
+            .into(),
+        );
+
+        let march = Schema::from_iter(vec![
+            Field::new("id".into(), DataType::Int64),
+            Field::new("name".into(), DataType::String),
+            Field::new("email".into(), DataType::String),
+        ]);
+        let sources = HashMap::from([("march".to_owned(), march)]);
+        let errors = validate_pipeline_with_inputs(&spec, &create_test_schema(), &sources).unwrap();
+
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].message.contains("missing columns: [\"age\"]"));
+        assert!(
+            errors[1]
+                .message
+                .contains("unexpected columns: [\"email\"]")
+        );
+        assert!(errors[2].message.contains("'april' is not declared"));
+    }
+
+    #[test]
+    fn test_validate_unpivot_then_pivot() {
+        let spec = PipelineSpec {
+            version: super::super::spec::SPEC_VERSION.to_owned(),
+            name: "test".to_owned(),
+            input: Default::default(),
+            schema: Default::default(),
+            steps: vec![
+                Step::Unpivot {
+                    id_vars: vec!["id".to_owned()],
+                    value_vars: vec!["name".to_owned(), "age".to_owned()],
+                },
+                Step::Pivot {
+                    index: vec!["id".to_owned()],
+                    columns: "variable".to_owned(),
+                    values: "value".to_owned(),
+                    agg: crate::pipeline::spec::AggregateFunction::Max,
+                },
+                Step::DropColumns {
+                    columns: vec!["name".to_owned()],
+                },
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+            output: Default::default(),
+            params: HashMap::new(),
+        };
+
+        let errors = validate_pipeline(&spec, &create_test_schema()).unwrap();
+
+        // Pivoted column names are data-dependent, so "name" is unknown afterwards
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].step_index, Some(2));
+    }
+
+    #[test]
+    fn test_validation_errors_are_structured() {
+        let mut spec = PipelineSpec::new("test");
+        spec.steps.push(
+            Step::Sort {
+                by: vec!["Age".to_owned()],
+                descending: vec![],
+            }
+            .into(),
+        );
+        spec.steps.push(
+            Step::RegexReplace {
+                columns: vec!["name".to_owned()],
+                pattern: "(".to_owned(),
+                replacement: String::new(),
+            }
+            .into(),
+        );
+
+        let errors = validate_pipeline(&spec, &create_test_schema()).unwrap();
+        assert_eq!(errors.len(), 2);
+
+        assert_eq!(errors[0].code, ErrorCode::UnknownColumn);
+        assert_eq!(errors[0].severity, Severity::Error);
+        assert_eq!(errors[0].field.as_deref(), Some("by"));
+        assert_eq!(errors[0].suggestion.as_deref(), Some("age"));
+        assert_eq!(
+            errors[0].params.get("column").map(String::as_str),
+            Some("Age")
+        );
+
+        assert_eq!(errors[1].step_index, Some(1));
+        assert_eq!(errors[1].code, ErrorCode::InvalidRegex);
+        assert_eq!(errors[1].field.as_deref(), Some("pattern"));
+
+        let json = serde_json::to_value(&errors[0]).unwrap();
+        assert_eq!(json["code"], "unknown_column");
+        assert_eq!(json["severity"], "error");
+    }
+
     #[test]
     fn test_validate_drop_columns() {
         let spec = PipelineSpec {
@@ -316,8 +1394,12 @@ mod tests {
             schema: Default::default(),
             steps: vec![Step::DropColumns {
                 columns: vec!["id".to_owned(), "nonexistent".to_owned()],
-            }],
+            }]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
             output: Default::default(),
+            params: HashMap::new(),
         };
 
         let schema = create_test_schema();
@@ -337,8 +1419,12 @@ mod tests {
             name: "test".to_owned(),
             input: Default::default(),
             schema: Default::default(),
-            steps: vec![Step::RenameColumns { mapping }],
+            steps: vec![Step::RenameColumns { mapping }]
+                .into_iter()
+                .map(Into::into)
+                .collect(),
             output: Default::default(),
+            params: HashMap::new(),
         };
 
         let schema = create_test_schema();
@@ -358,8 +1444,9 @@ mod tests {
                 match_mode: SchemaMatchMode::Strict,
                 required_columns: vec!["id".to_owned(), "missing".to_owned()],
             },
-            steps: vec![],
+            steps: Vec::new(),
             output: Default::default(),
+            params: HashMap::new(),
         };
 
         let schema = create_test_schema();
@@ -370,6 +1457,110 @@ mod tests {
         assert!(errors.iter().any(|e| e.message.contains("missing")));
     }
 
+    #[test]
+    fn test_validate_with_column() {
+        let spec = PipelineSpec {
+            version: super::super::spec::SPEC_VERSION.to_owned(),
+            name: "test".to_owned(),
+            input: Default::default(),
+            schema: Default::default(),
+            steps: vec![
+                Step::WithColumn {
+                    name: "age_next_year".to_owned(),
+                    expression: "age + 1".to_owned(),
+                },
+                Step::WithColumn {
+                    name: "label".to_owned(),
+                    expression: "name & \" (\" & age_next_year & \")\"".to_owned(),
+                },
+                Step::WithColumn {
+                    name: "bad".to_owned(),
+                    expression: "salary * 2".to_owned(),
+                },
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+            output: Default::default(),
+            params: HashMap::new(),
+        };
+
+        let schema = create_test_schema();
+        let errors = validate_pipeline(&spec, &schema).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].step_index, Some(2));
+        assert!(errors[0].message.contains("salary"));
+    }
+
+    #[test]
+    fn test_validate_expect_schema() {
+        let mut spec = PipelineSpec::new("contract");
+        let expected = |name: &str, dtype: &str| super::super::spec::ExpectedColumn {
+            name: name.to_owned(),
+            dtype: Some(dtype.to_owned()),
+            nullable: true,
+        };
+        spec.steps = vec![
+            Step::ExpectSchema {
+                // Columns the input lacks are a run-time violation, not an error
+                columns: vec![
+                    expected("region", "Text"),
+                    expected("age", "Numric"),
+                    expected("age", "Numeric"),
+                ],
+                on_violation: Default::default(),
+            }
+            .into(),
+        ];
+
+        let errors = validate_pipeline(&spec, &create_test_schema()).unwrap();
+        let codes: Vec<ErrorCode> = errors.iter().map(|e| e.code).collect();
+        assert_eq!(
+            codes,
+            [ErrorCode::InvalidType, ErrorCode::ConflictingOptions]
+        );
+        assert_eq!(errors[0].suggestion.as_deref(), Some("Numeric"));
+    }
+
+    #[test]
+    fn test_validate_aggregate_replaces_columns() {
+        use crate::pipeline::spec::{AggregateFunction, Aggregation};
+
+        let spec = PipelineSpec {
+            version: super::super::spec::SPEC_VERSION.to_owned(),
+            name: "test".to_owned(),
+            input: Default::default(),
+            schema: Default::default(),
+            steps: vec![
+                Step::Aggregate {
+                    group_by: vec!["name".to_owned()],
+                    aggregations: vec![Aggregation {
+                        column: "age".to_owned(),
+                        function: AggregateFunction::Mean,
+                        alias: None,
+                    }],
+                },
+                // `id` no longer exists after aggregation
+                Step::DropColumns {
+                    columns: vec!["age_mean".to_owned(), "id".to_owned()],
+                },
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+            output: Default::default(),
+            params: HashMap::new(),
+        };
+
+        let schema = create_test_schema();
+        let errors = validate_pipeline(&spec, &schema).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].step_index, Some(1));
+        assert!(errors[0].message.contains("'id'"));
+    }
+
     #[test]
     fn test_validate_valid_pipeline() {
         let spec = PipelineSpec {
@@ -385,8 +1576,12 @@ mod tests {
                     strategy: ImputeStrategy::Mean,
                     columns: vec!["age".to_owned()],
                 },
-            ],
+            ]
+            .into_iter()
+            .map(Into::into)
+            .collect(),
             output: Default::default(),
+            params: HashMap::new(),
         };
 
         let schema = create_test_schema();
@@ -394,4 +1589,31 @@ mod tests {
 
         assert_eq!(errors.len(), 0);
     }
+
+    #[test]
+    fn test_validate_sftp_delivery_rejects_option_like_values() {
+        let mut spec = PipelineSpec::new("test");
+        spec.output.deliver.push(
+            serde_json::from_str(
+                r#"{"type": "sftp", "host": "-oProxyCommand=sh", "user": "etl",
+                    "remote_dir": "/in\nrm x", "identity_file": "my key"}"#,
+            )
+            .unwrap(),
+        );
+        let errors: Vec<_> = validate_pipeline(&spec, &create_test_schema())
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.field.as_deref().is_some_and(|f| f.starts_with("output.")))
+            .collect();
+        let fields: Vec<_> = errors.iter().filter_map(|e| e.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            [
+                "output.deliver.host",
+                "output.deliver.remote_dir",
+                "output.deliver.identity_file"
+            ]
+        );
+        assert!(errors.iter().all(|e| e.code == ErrorCode::OutOfRange));
+    }
 }
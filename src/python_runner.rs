@@ -1,4 +1,5 @@
 use beefcake::analyser::logic::ColumnCleanConfig;
+use beefcake::config::PythonSandbox;
 use beefcake::error::{BeefcakeError, Result, ResultExt as _};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -11,6 +12,13 @@ use uuid::Uuid;
 
 const DEFAULT_PYTHON_TIMEOUT_SECS: u64 = 300;
 
+/// Written by the sandbox prologue into the working directory
+const SANDBOX_REPORT_FILE: &str = ".beefcake-sandbox.json";
+
+/// Proxy that nothing listens on, so HTTP clients in the script and in any
+/// tools it starts fail instead of reaching the network
+const BLOCKED_PROXY: &str = "http://127.0.0.1:9";
+
 fn python_timeout() -> Duration {
     let timeout = std::env::var("BEEFCAKE_PYTHON_TIMEOUT_SECS")
         .ok()
@@ -46,6 +54,21 @@ else:
     )
 }
 
+/// Indent every non-empty line of `code` by four spaces, e.g. to place a
+/// snippet inside a `try:` block
+pub fn indent_python(code: &str) -> String {
+    code.lines()
+        .map(|line| {
+            if line.is_empty() {
+                line.to_owned()
+            } else {
+                format!("    {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 pub fn python_adaptive_sink_snippet(lf_var: &str, output_path: &Path) -> String {
     format!(
         r#"
@@ -81,6 +104,193 @@ pub async fn execute_python_with_env(
     data_path: Option<String>,
     sql_query: Option<String>,
     log_tag: &str,
+) -> Result<String> {
+    run_python_process(script, data_path, sql_query, log_tag, None).await
+}
+
+/// One restriction of a sandboxed run and whether it took effect
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SandboxRestriction {
+    pub name: String,
+    pub applied: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SandboxedOutput {
+    pub output: String,
+    pub restrictions: Vec<SandboxRestriction>,
+}
+
+/// Run `script` in a fresh temporary working directory, removed afterwards,
+/// with the network and resource limits of `sandbox`. Limits the platform
+/// cannot enforce are listed as not applied rather than failing the run.
+///
+/// The network block is best effort: it patches the `socket` module and the
+/// proxy variables, which a script importing `_socket` directly can bypass.
+pub async fn execute_python_sandboxed(
+    script: &str,
+    data_path: Option<String>,
+    sandbox: &PythonSandbox,
+    log_tag: &str,
+) -> Result<SandboxedOutput> {
+    let dir = SandboxDir::create()?;
+    let output = run_python_process(
+        script,
+        data_path,
+        None,
+        log_tag,
+        Some((dir.path(), sandbox)),
+    )
+    .await?;
+
+    let timeout = sandbox_timeout(sandbox);
+    let mut restrictions = vec![
+        SandboxRestriction {
+            name: "working_dir".to_owned(),
+            applied: true,
+            detail: format!("{} (removed afterwards)", dir.path().display()),
+        },
+        SandboxRestriction {
+            name: "time".to_owned(),
+            applied: true,
+            detail: format!("{} seconds wall clock", timeout.as_secs()),
+        },
+    ];
+    let reported = std::fs::read_to_string(dir.path().join(SANDBOX_REPORT_FILE))
+        .ok()
+        .and_then(|json| serde_json::from_str::<Vec<SandboxRestriction>>(&json).ok());
+    match reported {
+        Some(reported) => restrictions.extend(reported),
+        None => restrictions.push(SandboxRestriction {
+            name: "prologue".to_owned(),
+            applied: false,
+            detail: "No sandbox report was written; limits are unverified".to_owned(),
+        }),
+    }
+
+    let summary: Vec<String> = restrictions
+        .iter()
+        .map(|r| format!("{}={}", r.name, if r.applied { "on" } else { "off" }))
+        .collect();
+    beefcake::config::log_event(
+        log_tag,
+        &format!("Sandboxed run restrictions: {}", summary.join(", ")),
+    );
+    Ok(SandboxedOutput {
+        output,
+        restrictions,
+    })
+}
+
+fn sandbox_timeout(sandbox: &PythonSandbox) -> Duration {
+    if sandbox.timeout_secs > 0 {
+        Duration::from_secs(sandbox.timeout_secs)
+    } else {
+        python_timeout()
+    }
+}
+
+/// Python passed with `-c` to run a sandboxed script: sets the resource
+/// limits, blocks sockets and records what was applied in
+/// [`SANDBOX_REPORT_FILE`], then compiles and runs the script read from stdin
+/// as `__main__`. Running the script as its own compiled source, rather than
+/// appended to this, keeps `from __future__` imports at its top valid, and
+/// the setup is wrapped in a function so none of its names leak into it.
+///
+/// The memory cap limits the data segment where the platform has
+/// `RLIMIT_DATA`, so libraries that reserve large address ranges up front,
+/// like polars and numpy, still start; `RLIMIT_AS` is the fallback.
+fn sandbox_prologue(sandbox: &PythonSandbox) -> String {
+    let memory_bytes = sandbox.memory_mb.saturating_mul(1024 * 1024);
+    let block_network = if sandbox.block_network {
+        "True"
+    } else {
+        "False"
+    };
+    format!(
+        r#"def _beefcake_sandbox(memory_bytes, cpu_seconds, block_network):
+    import json
+    report = []
+    def note(name, applied, detail):
+        report.append({{"name": name, "applied": applied, "detail": detail}})
+    try:
+        import resource
+    except ImportError:
+        resource = None
+
+    if memory_bytes:
+        applied = False
+        for limit in ("RLIMIT_DATA", "RLIMIT_AS"):
+            if resource is None or not hasattr(resource, limit):
+                continue
+            try:
+                resource.setrlimit(getattr(resource, limit), (memory_bytes, memory_bytes))
+                note("memory", True, f"{{memory_bytes // (1024 * 1024)}} MiB ({{limit}})")
+                applied = True
+                break
+            except (ValueError, OSError):
+                pass
+        if not applied:
+            note("memory", False, "Not supported on this platform")
+
+    if cpu_seconds:
+        try:
+            resource.setrlimit(resource.RLIMIT_CPU, (cpu_seconds, cpu_seconds + 5))
+            note("cpu", True, f"{{cpu_seconds}} seconds")
+        except (AttributeError, ValueError, OSError):
+            note("cpu", False, "Not supported on this platform")
+
+    if block_network:
+        import socket
+        def blocked(*args, **kwargs):
+            raise PermissionError("Network access is disabled by the Beefcake sandbox")
+        socket.socket.connect = blocked
+        socket.socket.connect_ex = blocked
+        socket.socket.sendto = blocked
+        socket.create_connection = blocked
+        socket.getaddrinfo = blocked
+        note("network", True, "Sockets and proxies blocked")
+
+    with open("{report}", "w", encoding="utf-8") as f:
+        json.dump(report, f)
+
+_beefcake_sandbox({memory_bytes}, {cpu_seconds}, {block_network})
+del _beefcake_sandbox
+exec(compile(__import__("sys").stdin.read(), "<stdin>", "exec"))
+"#,
+        cpu_seconds = sandbox.cpu_seconds,
+        report = SANDBOX_REPORT_FILE,
+    )
+}
+
+/// Temporary working directory of a sandboxed run, removed on drop
+struct SandboxDir(PathBuf);
+
+impl SandboxDir {
+    fn create() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("beefcake-sandbox-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&path).context("Failed to create sandbox directory")?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SandboxDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+async fn run_python_process(
+    script: &str,
+    data_path: Option<String>,
+    sql_query: Option<String>,
+    log_tag: &str,
+    sandbox: Option<(&Path, &PythonSandbox)>,
 ) -> Result<String> {
     let mut cmd = if cfg!(target_os = "windows") {
         Command::new("python")
@@ -114,6 +324,28 @@ pub async fn execute_python_with_env(
         cmd.env("BEEFCAKE_SQL_QUERY", query);
     }
 
+    if let Some((dir, settings)) = sandbox {
+        cmd.arg("-c").arg(sandbox_prologue(settings));
+        cmd.current_dir(dir);
+        for var in ["TMPDIR", "TEMP", "TMP"] {
+            cmd.env(var, dir);
+        }
+        if settings.block_network {
+            for var in [
+                "HTTP_PROXY",
+                "HTTPS_PROXY",
+                "ALL_PROXY",
+                "http_proxy",
+                "https_proxy",
+                "all_proxy",
+            ] {
+                cmd.env(var, BLOCKED_PROXY);
+            }
+            cmd.env_remove("NO_PROXY").env_remove("no_proxy");
+            cmd.env("PIP_NO_INDEX", "1");
+        }
+    }
+
     beefcake::config::log_event(log_tag, "Spawning Python process...");
 
     let mut child = cmd
@@ -136,7 +368,7 @@ pub async fn execute_python_with_env(
 
     beefcake::config::log_event(log_tag, "Waiting for Python to complete...");
 
-    let timeout_duration = python_timeout();
+    let timeout_duration = sandbox.map_or_else(python_timeout, |(_, s)| sandbox_timeout(s));
     let out = match timeout(timeout_duration, child.wait_with_output()).await {
         Ok(result) => result.context("Failed to wait for python process")?,
         Err(_) => {
@@ -394,4 +626,26 @@ mod tests {
         assert!(f6.contains("'ID $' AS \"ID\""));
         assert!(f6.contains("'ID #' AS \"ID_1\""));
     }
+
+    #[test]
+    fn test_sandbox_prologue_passes_limits() {
+        let sandbox = PythonSandbox {
+            enabled: true,
+            memory_mb: 512,
+            cpu_seconds: 30,
+            timeout_secs: 0,
+            block_network: false,
+        };
+        let prologue = sandbox_prologue(&sandbox);
+        assert!(prologue.contains("_beefcake_sandbox(536870912, 30, False)"));
+        assert!(prologue.contains(SANDBOX_REPORT_FILE));
+        assert!(prologue.find("RLIMIT_DATA") < prologue.find("RLIMIT_AS"));
+        // The script is compiled on its own, not appended to the prologue
+        assert!(
+            prologue
+                .trim_end()
+                .ends_with(r#"exec(compile(__import__("sys").stdin.read(), "<stdin>", "exec"))"#)
+        );
+        assert_eq!(sandbox_timeout(&sandbox), python_timeout());
+    }
 }
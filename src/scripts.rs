@@ -0,0 +1,504 @@
+//! Library of script templates with parameter slots.
+//!
+//! A template is a Python or PowerShell script with `{{name}}` placeholders
+//! for declared parameters, so a form can ask for the values and people who
+//! do not write scripts can still run them. [`ScriptLibrary::render`] checks
+//! each value against its [`ParamType`] and substitutes it as a quoted
+//! literal of the script's language, so values are never pasted in as code.
+//!
+//! Templates are stored under `templates/scripts/<id>.json` in the app data
+//! directory with every saved version, newest last; saving a template again
+//! adds a version instead of overwriting it. A few vetted templates are
+//! built in; they are read-only and can be copied under a new name.
+
+use crate::sql::{ParamType, QueryParam, validate_params};
+use anyhow::{Context as _, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use uuid::Uuid;
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").expect("valid placeholder regex")
+});
+
+const BUILTIN_PREFIX: &str = "builtin-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScriptLanguage {
+    Python,
+    PowerShell,
+}
+
+impl ScriptLanguage {
+    /// `value` as a string literal of the language
+    fn string_literal(self, value: &str) -> String {
+        match self {
+            // A JSON string is a valid Python string literal
+            Self::Python => serde_json::Value::from(value).to_string(),
+            // PowerShell also ends single-quoted strings at typographic quotes
+            Self::PowerShell => {
+                let mut literal = String::with_capacity(value.len() + 2);
+                literal.push('\'');
+                for c in value.chars() {
+                    if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{201b}') {
+                        literal.push(c);
+                    }
+                    literal.push(c);
+                }
+                literal.push('\'');
+                literal
+            }
+        }
+    }
+
+    /// `value` as a literal of `param`'s type in this language
+    fn literal(self, param: &QueryParam, value: &str) -> Result<String> {
+        let value = value.trim();
+        match param.param_type {
+            ParamType::String => Ok(self.string_literal(value)),
+            // The SQL literal of a number is its normalised digits, which
+            // are valid in both languages
+            ParamType::Number => param.literal(value),
+            ParamType::Date => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+                    format!(
+                        "Parameter '{}' must be a date (YYYY-MM-DD), got '{value}'",
+                        param.name
+                    )
+                })?;
+                Ok(self.string_literal(&date.format("%Y-%m-%d").to_string()))
+            }
+        }
+    }
+}
+
+/// One version of a script template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptTemplate {
+    /// Empty for a template not saved yet
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub language: ScriptLanguage,
+    /// Script text with `{{name}}` placeholders
+    pub body: String,
+    #[serde(default)]
+    pub params: Vec<QueryParam>,
+    /// Starts at 1 and goes up by one with every save
+    #[serde(default = "first_version")]
+    pub version: u32,
+    /// Shipped with the app and read-only
+    #[serde(default)]
+    pub builtin: bool,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl ScriptTemplate {
+    /// Placeholder names used in the body, sorted
+    pub fn placeholders(&self) -> BTreeSet<String> {
+        PLACEHOLDER
+            .captures_iter(&self.body)
+            .map(|caps| caps[1].to_owned())
+            .collect()
+    }
+
+    /// Check the name, the parameter declarations, and that every
+    /// placeholder is a declared parameter
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!("Script templates need a name");
+        }
+        validate_params(&self.params)?;
+        let undeclared: Vec<String> = self
+            .placeholders()
+            .into_iter()
+            .filter(|name| !self.params.iter().any(|p| &p.name == name))
+            .collect();
+        if !undeclared.is_empty() {
+            bail!(
+                "Placeholders without a declared parameter: {}",
+                undeclared.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// The body with each placeholder replaced by its value as a literal.
+    /// Values come from `values`, then the parameter's default; a parameter
+    /// with neither is an error. Placeholders should not be quoted: string
+    /// values get their own quotes.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<String> {
+        let mut literals = HashMap::with_capacity(self.params.len());
+        for param in &self.params {
+            let value = values
+                .get(&param.name)
+                .or(param.default.as_ref())
+                .with_context(|| format!("No value given for parameter '{}'", param.name))?;
+            literals.insert(param.name.as_str(), self.language.literal(param, value)?);
+        }
+
+        let mut missing = BTreeSet::new();
+        let rendered = PLACEHOLDER.replace_all(&self.body, |caps: &regex::Captures<'_>| {
+            literals.get(&caps[1]).cloned().unwrap_or_else(|| {
+                missing.insert(caps[1].to_owned());
+                caps[0].to_owned()
+            })
+        });
+        if !missing.is_empty() {
+            bail!(
+                "Placeholders without a declared parameter: {}",
+                missing.into_iter().collect::<Vec<_>>().join(", ")
+            );
+        }
+        Ok(rendered.into_owned())
+    }
+}
+
+/// A template filled in and ready to run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedScript {
+    pub template_id: String,
+    pub version: u32,
+    pub language: ScriptLanguage,
+    pub script: String,
+}
+
+/// The script templates of one folder, plus the built-in ones
+#[derive(Debug, Clone)]
+pub struct ScriptLibrary {
+    dir: PathBuf,
+}
+
+impl ScriptLibrary {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The library in the app's templates directory
+    pub fn open_default() -> Self {
+        Self::new(crate::utils::standard_paths().templates_dir.join("scripts"))
+    }
+
+    /// The newest version of every template, built-in ones first, then by
+    /// name. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<ScriptTemplate>> {
+        let mut saved = Vec::new();
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(builtin_templates()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", self.dir.display()));
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Ok(json) = std::fs::read_to_string(&path)
+                && let Ok(mut versions) = serde_json::from_str::<Vec<ScriptTemplate>>(&json)
+                && let Some(latest) = versions.pop()
+            {
+                saved.push(latest);
+            }
+        }
+        saved.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        let mut templates = builtin_templates();
+        templates.extend(saved);
+        Ok(templates)
+    }
+
+    /// Every saved version of template `id`, oldest first
+    pub fn versions(&self, id: &str) -> Result<Vec<ScriptTemplate>> {
+        if let Some(builtin) = builtin_templates().into_iter().find(|t| t.id == id) {
+            return Ok(vec![builtin]);
+        }
+        let path = self.template_path(id)?;
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Script template not found: {id}"))?;
+        serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Version `version` of template `id`, or its newest version
+    pub fn get(&self, id: &str, version: Option<u32>) -> Result<ScriptTemplate> {
+        let versions = self.versions(id)?;
+        match version {
+            Some(version) => versions
+                .into_iter()
+                .find(|t| t.version == version)
+                .with_context(|| format!("Script template {id} has no version {version}")),
+            None => versions
+                .into_iter()
+                .last()
+                .with_context(|| format!("Script template not found: {id}")),
+        }
+    }
+
+    /// Save `template` as a new version. A template without an id is
+    /// created; one with an id gets the version after its newest.
+    pub fn save(&self, mut template: ScriptTemplate) -> Result<ScriptTemplate> {
+        template.name = template.name.trim().to_owned();
+        template.validate()?;
+        if template.id.starts_with(BUILTIN_PREFIX) {
+            bail!("Built-in templates are read-only; save a copy under a new name");
+        }
+
+        let mut versions = if template.id.is_empty() {
+            template.id = Uuid::new_v4().to_string();
+            Vec::new()
+        } else {
+            self.versions(&template.id)?
+        };
+        template.version = versions.last().map_or(1, |t| t.version + 1);
+        template.builtin = false;
+        template.updated_at = Utc::now();
+        versions.push(template.clone());
+
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.template_path(&template.id)?;
+        let json = serde_json::to_string_pretty(&versions)
+            .context("Failed to serialize script template")?;
+        std::fs::write(&path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(template)
+    }
+
+    /// Delete template `id` with all its versions. Returns false if no
+    /// template has this id.
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        if id.starts_with(BUILTIN_PREFIX) {
+            bail!("Built-in templates cannot be deleted");
+        }
+        let path = self.template_path(id)?;
+        match std::fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(e).with_context(|| format!("Failed to delete {}", path.display())),
+        }
+    }
+
+    /// Template `id` (at `version`, or its newest) filled in with `values`
+    pub fn render(
+        &self,
+        id: &str,
+        version: Option<u32>,
+        values: &HashMap<String, String>,
+    ) -> Result<RenderedScript> {
+        let template = self.get(id, version)?;
+        Ok(RenderedScript {
+            script: template.render(values)?,
+            template_id: template.id,
+            version: template.version,
+            language: template.language,
+        })
+    }
+
+    fn template_path(&self, id: &str) -> Result<PathBuf> {
+        crate::utils::id_path(&self.dir, id, "json", "script template")
+    }
+}
+
+fn param(name: &str, param_type: ParamType, prompt: &str, default: Option<&str>) -> QueryParam {
+    QueryParam {
+        name: name.to_owned(),
+        param_type,
+        prompt: prompt.to_owned(),
+        default: default.map(ToOwned::to_owned),
+    }
+}
+
+/// The vetted templates shipped with the app
+pub fn builtin_templates() -> Vec<ScriptTemplate> {
+    let updated_at = DateTime::<Utc>::UNIX_EPOCH;
+    vec![
+        ScriptTemplate {
+            id: format!("{BUILTIN_PREFIX}sharepoint-upload"),
+            name: "Upload to SharePoint".to_owned(),
+            description: "Copy a file into a SharePoint document library synced with OneDrive"
+                .to_owned(),
+            language: ScriptLanguage::PowerShell,
+            body: r#"$source = {{source_path}}
+$library = {{library_folder}}
+if (-not (Test-Path -LiteralPath $source -PathType Leaf)) {
+    throw "File not found: $source"
+}
+if (-not (Test-Path -LiteralPath $library -PathType Container)) {
+    throw "Library folder not found (is it synced with OneDrive?): $library"
+}
+Copy-Item -LiteralPath $source -Destination $library -Force
+Write-Output "Uploaded $(Split-Path -Leaf $source) to $library"
+"#
+            .to_owned(),
+            params: vec![
+                param("source_path", ParamType::String, "File to upload", None),
+                param(
+                    "library_folder",
+                    ParamType::String,
+                    "Synced SharePoint library folder",
+                    None,
+                ),
+            ],
+            version: 1,
+            builtin: true,
+            updated_at,
+        },
+        ScriptTemplate {
+            id: format!("{BUILTIN_PREFIX}report-email"),
+            name: "Send report email".to_owned(),
+            description: "Email a report file as an attachment over SMTP with STARTTLS. \
+                          The SMTP password is read from BEEFCAKE_SMTP_PASSWORD."
+                .to_owned(),
+            language: ScriptLanguage::Python,
+            body: r#"import os
+import smtplib
+import ssl
+from email.message import EmailMessage
+from pathlib import Path
+
+report = Path({{report_path}})
+message = EmailMessage()
+message["Subject"] = {{subject}}
+message["From"] = {{sender}}
+message["To"] = {{recipients}}
+message.set_content({{message}})
+message.add_attachment(
+    report.read_bytes(),
+    maintype="application",
+    subtype="octet-stream",
+    filename=report.name,
+)
+
+password = os.environ.get("BEEFCAKE_SMTP_PASSWORD")
+with smtplib.SMTP({{smtp_host}}, {{smtp_port}}) as smtp:
+    smtp.starttls(context=ssl.create_default_context())
+    if password:
+        smtp.login({{sender}}, password)
+    smtp.send_message(message)
+print(f"Sent {report.name} to {message['To']}")
+"#
+            .to_owned(),
+            params: vec![
+                param("report_path", ParamType::String, "Report file", None),
+                param("subject", ParamType::String, "Subject", None),
+                param("sender", ParamType::String, "From address", None),
+                param(
+                    "recipients",
+                    ParamType::String,
+                    "To addresses, comma separated",
+                    None,
+                ),
+                param(
+                    "message",
+                    ParamType::String,
+                    "Message",
+                    Some("Please find the latest report attached."),
+                ),
+                param("smtp_host", ParamType::String, "SMTP server", None),
+                param("smtp_port", ParamType::Number, "SMTP port", Some("587")),
+            ],
+            version: 1,
+            builtin: true,
+            updated_at,
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn template(body: &str, language: ScriptLanguage) -> ScriptTemplate {
+        ScriptTemplate {
+            id: String::new(),
+            name: "Archive".to_owned(),
+            description: String::new(),
+            language,
+            body: body.to_owned(),
+            params: vec![
+                param("path", ParamType::String, "", None),
+                param("days", ParamType::Number, "", Some("30")),
+            ],
+            version: 0,
+            builtin: false,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_render_quotes_values_per_language() -> Result<()> {
+        let values = HashMap::from([("path".to_owned(), "it's \"here\"\n".to_owned())]);
+
+        let python = template("archive({{path}}, {{ days }})", ScriptLanguage::Python);
+        assert_eq!(python.render(&values)?, r#"archive("it's \"here\"", 30)"#);
+
+        let powershell = template(
+            "Archive -Path {{path}} -Days {{days}}",
+            ScriptLanguage::PowerShell,
+        );
+        let values = HashMap::from([
+            ("path".to_owned(), "it's’; rm *".to_owned()),
+            ("days".to_owned(), "7".to_owned()),
+        ]);
+        assert_eq!(
+            powershell.render(&values)?,
+            "Archive -Path 'it''s’’; rm *' -Days 7"
+        );
+
+        let bad_number = HashMap::from([
+            ("path".to_owned(), "x".to_owned()),
+            ("days".to_owned(), "7; rm *".to_owned()),
+        ]);
+        assert!(powershell.render(&bad_number).is_err());
+        assert!(python.render(&HashMap::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_library_versions_and_builtins() -> Result<()> {
+        let dir = tempdir()?;
+        let library = ScriptLibrary::new(dir.path());
+        assert!(
+            library
+                .save(template("{{undeclared}}", ScriptLanguage::Python))
+                .is_err()
+        );
+
+        let first = library.save(template("print({{path}})", ScriptLanguage::Python))?;
+        assert_eq!(first.version, 1);
+        let mut edited = first.clone();
+        edited.body = "print({{path}}, {{days}})".to_owned();
+        let second = library.save(edited)?;
+        assert_eq!((second.id.as_str(), second.version), (first.id.as_str(), 2));
+
+        let listed = library.list()?;
+        assert_eq!(listed.len(), builtin_templates().len() + 1);
+        assert_eq!(listed.last().map(|t| t.version), Some(2));
+        let rendered = library.render(
+            &first.id,
+            Some(1),
+            &HashMap::from([("path".to_owned(), "a.csv".to_owned())]),
+        )?;
+        assert_eq!(rendered.script, r#"print("a.csv")"#);
+
+        for builtin in builtin_templates() {
+            builtin.validate()?;
+            assert!(library.save(builtin.clone()).is_err());
+            assert!(library.delete(&builtin.id).is_err());
+        }
+        assert!(library.delete(&first.id)?);
+        assert!(!library.delete(&first.id)?);
+        Ok(())
+    }
+}
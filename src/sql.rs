@@ -0,0 +1,662 @@
+//! SQL workspace: saved queries, execution history and query results.
+//!
+//! Queries run against a data file registered as the `data` table. Each
+//! result is written to `sql/results/<query id>.parquet` under the app data
+//! directory, so the editor can page through it and export it without running
+//! the query again. Only the newest [`MAX_KEPT_RESULTS`] results are kept.
+//! Every execution is appended to `sql/history.jsonl` with its timing, and
+//! named queries are stored in `sql/saved_queries.json`.
+//!
+//! Saved queries can declare typed parameters, written as `{name}` in the
+//! query text. [`bind_params`] checks each value against its type and
+//! substitutes it as a SQL literal, so values are never pasted in as raw SQL.
+
+use crate::pipeline::dry_run::ColumnPreview;
+use crate::pipeline::executor::{count_rows, write_output};
+use crate::pipeline::spec::OutputConfig;
+use anyhow::{Context as _, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Result files kept on disk; older ones are deleted as new queries run
+pub const MAX_KEPT_RESULTS: usize = 20;
+
+/// Rows returned per page when the caller does not ask for a size
+pub const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// A named query kept for reuse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub id: String,
+    pub name: String,
+    pub query: String,
+    /// File the query was written against
+    #[serde(default)]
+    pub data_path: Option<String>,
+    /// Values asked for before the query runs
+    #[serde(default)]
+    pub params: Vec<QueryParam>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamType {
+    String,
+    Number,
+    /// `YYYY-MM-DD`
+    Date,
+}
+
+/// A parameter of a saved query, used as `{name}` in its text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub param_type: ParamType,
+    /// Shown when asking for the value; the name is used when empty
+    #[serde(default)]
+    pub prompt: String,
+    /// Used when no value is given
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+impl QueryParam {
+    /// `value` as a SQL literal of the parameter's type
+    pub fn literal(&self, value: &str) -> Result<String> {
+        let value = value.trim();
+        match self.param_type {
+            ParamType::String => Ok(format!("'{}'", value.replace('\'', "''"))),
+            ParamType::Number => {
+                // Parsed and re-printed so only digits, a sign, a point or an
+                // exponent can reach the query
+                if let Ok(n) = value.parse::<i64>() {
+                    Ok(n.to_string())
+                } else {
+                    match value.parse::<f64>() {
+                        Ok(n) if n.is_finite() => Ok(n.to_string()),
+                        _ => bail!("Parameter '{}' must be a number, got '{value}'", self.name),
+                    }
+                }
+            }
+            ParamType::Date => {
+                let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").with_context(|| {
+                    format!(
+                        "Parameter '{}' must be a date (YYYY-MM-DD), got '{value}'",
+                        self.name
+                    )
+                })?;
+                Ok(format!("CAST('{}' AS DATE)", date.format("%Y-%m-%d")))
+            }
+        }
+    }
+}
+
+/// Check parameter declarations: names are identifiers, unique, and
+/// defaults are valid for their type
+pub fn validate_params(params: &[QueryParam]) -> Result<()> {
+    let mut seen = HashSet::new();
+    for param in params {
+        let valid_name = param
+            .name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && param
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            bail!(
+                "Invalid parameter name '{}': use letters, digits and underscores",
+                param.name
+            );
+        }
+        if !seen.insert(param.name.as_str()) {
+            bail!("Parameter '{}' is declared twice", param.name);
+        }
+        if let Some(default) = &param.default {
+            param.literal(default)?;
+        }
+    }
+    Ok(())
+}
+
+/// Where [`bind_params`] is in the query text
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SqlContext {
+    Code,
+    /// In a `'...'` string
+    String,
+    /// In a `"..."` identifier
+    Identifier,
+    /// In a `-- ...` comment, up to the end of the line
+    LineComment,
+    /// In a `/* ... */` comment
+    BlockComment,
+}
+
+/// Replace each `{name}` of a declared parameter with its value as a SQL
+/// literal. Values come from `values`, then the parameter's default; a
+/// parameter with neither is an error. Braces around other names are left
+/// alone. String values get their own quotes, so a declared placeholder in a
+/// quoted string, quoted identifier or comment is an error: bound there, a
+/// value could end the quotes and run as SQL.
+pub fn bind_params(
+    query: &str,
+    params: &[QueryParam],
+    values: &HashMap<String, String>,
+) -> Result<String> {
+    let mut literals = HashMap::with_capacity(params.len());
+    for param in params {
+        let value = values
+            .get(&param.name)
+            .or(param.default.as_ref())
+            .with_context(|| format!("No value given for parameter '{}'", param.name))?;
+        literals.insert(param.name.as_str(), param.literal(value)?);
+    }
+
+    let mut bound = String::with_capacity(query.len());
+    let mut context = SqlContext::Code;
+    let mut rest = query;
+    while let Some(c) = rest.chars().next() {
+        let next = rest[c.len_utf8()..].chars().next();
+        let mut taken = c.len_utf8();
+        match (context, c) {
+            (SqlContext::Code, '\'') => context = SqlContext::String,
+            (SqlContext::Code, '"') => context = SqlContext::Identifier,
+            (SqlContext::Code, '-') if next == Some('-') => {
+                context = SqlContext::LineComment;
+                taken = 2;
+            }
+            (SqlContext::Code, '/') if next == Some('*') => {
+                context = SqlContext::BlockComment;
+                taken = 2;
+            }
+            // A doubled quote inside quotes is an escaped quote, read as
+            // leaving the quotes and entering them again
+            (SqlContext::String, '\'') | (SqlContext::Identifier, '"') => {
+                context = SqlContext::Code;
+            }
+            (SqlContext::LineComment, '\n') => context = SqlContext::Code,
+            (SqlContext::BlockComment, '*') if next == Some('/') => {
+                context = SqlContext::Code;
+                taken = 2;
+            }
+            (_, '{') => {
+                let literal = rest[1..]
+                    .find('}')
+                    .and_then(|end| Some((end, &rest[1..=end], literals.get(&rest[1..=end])?)));
+                if let Some((end, name, literal)) = literal {
+                    if context != SqlContext::Code {
+                        bail!(
+                            "Parameter '{name}' is inside quotes or a comment; remove the quotes, \
+                             as string values are quoted when bound"
+                        );
+                    }
+                    bound.push_str(literal);
+                    rest = &rest[end + 2..];
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        bound.push_str(&rest[..taken]);
+        rest = &rest[taken..];
+    }
+    Ok(bound)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryStatus {
+    Succeeded,
+    Failed,
+}
+
+/// One execution as stored in the history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryRecord {
+    /// Also names the result file
+    pub id: String,
+    pub query: String,
+    pub data_path: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub status: QueryStatus,
+    /// Rows in the result of a successful query
+    pub row_count: Option<usize>,
+    pub error: Option<String>,
+}
+
+impl QueryRecord {
+    /// Build the record of running `query`; `result` holds the row count
+    pub fn new(
+        id: &str,
+        query: &str,
+        data_path: Option<&str>,
+        started_at: DateTime<Utc>,
+        duration: Duration,
+        result: &Result<usize>,
+    ) -> Self {
+        let (status, row_count, error) = match result {
+            Ok(rows) => (QueryStatus::Succeeded, Some(*rows), None),
+            Err(e) => (QueryStatus::Failed, None, Some(format!("{e:#}"))),
+        };
+        Self {
+            id: id.to_owned(),
+            query: query.to_owned(),
+            data_path: data_path.map(ToOwned::to_owned),
+            started_at,
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            status,
+            row_count,
+            error,
+        }
+    }
+}
+
+/// A slice of a query result, rows as JSON objects keyed by column name
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultPage {
+    pub query_id: String,
+    pub columns: Vec<ColumnPreview>,
+    pub rows: Vec<serde_json::Value>,
+    pub offset: usize,
+    pub total_rows: usize,
+}
+
+/// Saved queries, history and result files in one directory
+#[derive(Debug, Clone)]
+pub struct SqlWorkspace {
+    dir: PathBuf,
+}
+
+impl SqlWorkspace {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The workspace in the app data directory
+    pub fn open_default() -> Self {
+        Self::new(crate::utils::app_data_dir().join("sql"))
+    }
+
+    /// Where the result of query `id` is (or will be) written
+    pub fn result_path(&self, id: &str) -> Result<PathBuf> {
+        crate::utils::id_path(&self.results_dir(), id, "parquet", "query")
+    }
+
+    /// Append `record` to the history and delete the oldest result files
+    /// beyond [`MAX_KEPT_RESULTS`]
+    pub fn record(&self, record: &QueryRecord) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.history_path();
+        let mut line = serde_json::to_string(record).context("Failed to serialize query record")?;
+        line.push('\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| format!("Failed to append to {}", path.display()))?;
+        self.prune_results()
+    }
+
+    /// Executions, most recent first. Unreadable lines are skipped.
+    pub fn history(&self, limit: Option<usize>) -> Result<Vec<QueryRecord>> {
+        let path = self.history_path();
+        let file = match std::fs::File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+        };
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if let Ok(record) = serde_json::from_str::<QueryRecord>(&line) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        if let Some(limit) = limit {
+            records.truncate(limit);
+        }
+        Ok(records)
+    }
+
+    /// Saved queries sorted by name
+    pub fn saved_queries(&self) -> Result<Vec<SavedQuery>> {
+        let path = self.saved_path();
+        let mut queries: Vec<SavedQuery> = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)
+                .with_context(|| format!("Failed to parse {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        queries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(queries)
+    }
+
+    /// Save `query` under `name`, replacing a saved query of the same name
+    pub fn save_query(
+        &self,
+        name: &str,
+        query: &str,
+        data_path: Option<&str>,
+    ) -> Result<SavedQuery> {
+        self.save_query_with_params(name, query, data_path, &[])
+    }
+
+    /// As [`Self::save_query`], declaring the parameters the query uses
+    pub fn save_query_with_params(
+        &self,
+        name: &str,
+        query: &str,
+        data_path: Option<&str>,
+        params: &[QueryParam],
+    ) -> Result<SavedQuery> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Saved queries need a name");
+        }
+        validate_params(params)?;
+        let mut queries = self.saved_queries()?;
+        let now = Utc::now();
+        let saved = match queries.iter_mut().find(|q| q.name == name) {
+            Some(existing) => {
+                existing.query = query.to_owned();
+                existing.data_path = data_path.map(ToOwned::to_owned);
+                existing.params = params.to_vec();
+                existing.updated_at = now;
+                existing.clone()
+            }
+            None => {
+                let saved = SavedQuery {
+                    id: Uuid::new_v4().to_string(),
+                    name: name.to_owned(),
+                    query: query.to_owned(),
+                    data_path: data_path.map(ToOwned::to_owned),
+                    params: params.to_vec(),
+                    created_at: now,
+                    updated_at: now,
+                };
+                queries.push(saved.clone());
+                saved
+            }
+        };
+        self.write_saved(&queries)?;
+        Ok(saved)
+    }
+
+    pub fn saved_query(&self, id: &str) -> Result<SavedQuery> {
+        self.saved_queries()?
+            .into_iter()
+            .find(|q| q.id == id)
+            .with_context(|| format!("Saved query not found: {id}"))
+    }
+
+    /// Returns false if no saved query has this id
+    pub fn delete_query(&self, id: &str) -> Result<bool> {
+        let mut queries = self.saved_queries()?;
+        let before = queries.len();
+        queries.retain(|q| q.id != id);
+        if queries.len() == before {
+            return Ok(false);
+        }
+        self.write_saved(&queries)?;
+        Ok(true)
+    }
+
+    /// `limit` rows of the result of query `id`, starting at `offset`
+    pub fn result_page(&self, id: &str, offset: usize, limit: usize) -> Result<ResultPage> {
+        let mut lf = self.scan_result(id)?;
+        let schema = lf
+            .collect_schema()
+            .map_err(|e| anyhow::anyhow!("Failed to resolve schema: {e}"))?;
+        let columns = schema
+            .iter()
+            .map(|(name, dtype)| ColumnPreview {
+                name: name.to_string(),
+                dtype: dtype.to_string(),
+            })
+            .collect();
+        let total_rows = count_rows(&lf)?;
+
+        let mut df = lf
+            .slice(
+                i64::try_from(offset).unwrap_or(i64::MAX),
+                IdxSize::try_from(limit).unwrap_or(IdxSize::MAX),
+            )
+            .collect()
+            .context("Failed to read query result")?;
+        let mut json = Vec::new();
+        JsonWriter::new(&mut json)
+            .with_json_format(JsonFormat::Json)
+            .finish(&mut df)
+            .context("Failed to convert query result to JSON")?;
+        let rows = serde_json::from_slice(&json).context("Failed to convert query result")?;
+
+        Ok(ResultPage {
+            query_id: id.to_owned(),
+            columns,
+            rows,
+            offset,
+            total_rows,
+        })
+    }
+
+    /// Write the full result of query `id` to `dest` as CSV, JSON or
+    /// Parquet, chosen by extension. Returns the number of rows written.
+    pub fn export_result(&self, id: &str, dest: &Path) -> Result<usize> {
+        let lf = self.scan_result(id)?;
+        let rows = count_rows(&lf)?;
+        write_output(lf, dest, &OutputConfig::default())
+            .with_context(|| format!("Failed to export query result to {}", dest.display()))?;
+        Ok(rows)
+    }
+
+    fn scan_result(&self, id: &str) -> Result<LazyFrame> {
+        let path = self.result_path(id)?;
+        if !path.exists() {
+            bail!("The result of query {id} is no longer available; run the query again");
+        }
+        LazyFrame::scan_parquet(&path, ScanArgsParquet::default())
+            .with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    fn prune_results(&self) -> Result<()> {
+        let dir = self.results_dir();
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(());
+        };
+        let mut files: Vec<(std::time::SystemTime, PathBuf)> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                Some((modified, entry.path()))
+            })
+            .collect();
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, path) in files.into_iter().skip(MAX_KEPT_RESULTS) {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    fn write_saved(&self, queries: &[SavedQuery]) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let path = self.saved_path();
+        let json =
+            serde_json::to_string_pretty(queries).context("Failed to serialize saved queries")?;
+        std::fs::write(&path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    fn results_dir(&self) -> PathBuf {
+        self.dir.join("results")
+    }
+
+    fn history_path(&self) -> PathBuf {
+        self.dir.join("history.jsonl")
+    }
+
+    fn saved_path(&self) -> PathBuf {
+        self.dir.join("saved_queries.json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saved_queries_history_and_paging() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = SqlWorkspace::new(dir.path());
+
+        let first = workspace
+            .save_query("adults", "SELECT * FROM data WHERE age >= 18", None)
+            .unwrap();
+        let updated = workspace
+            .save_query(
+                " adults ",
+                "SELECT name FROM data WHERE age >= 18",
+                Some("a.csv"),
+            )
+            .unwrap();
+        assert_eq!(first.id, updated.id);
+        workspace
+            .save_query("all", "SELECT * FROM data", None)
+            .unwrap();
+        let saved = workspace.saved_queries().unwrap();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[0].query, "SELECT name FROM data WHERE age >= 18");
+        assert!(workspace.delete_query(&first.id).unwrap());
+        assert!(!workspace.delete_query(&first.id).unwrap());
+        assert!(workspace.save_query("  ", "SELECT 1", None).is_err());
+
+        let id = Uuid::new_v4().to_string();
+        let path = workspace.result_path(&id).unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut df = df!("n" => (0..250).collect::<Vec<i64>>()).unwrap();
+        crate::analyser::logic::save_df(&mut df, &path).unwrap();
+        let record = QueryRecord::new(
+            &id,
+            "SELECT n FROM data",
+            None,
+            Utc::now(),
+            Duration::from_millis(12),
+            &Ok(250),
+        );
+        workspace.record(&record).unwrap();
+
+        let history = workspace.history(Some(10)).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].duration_ms, 12);
+        assert_eq!(history[0].row_count, Some(250));
+
+        let page = workspace.result_page(&id, 200, 100).unwrap();
+        assert_eq!(page.total_rows, 250);
+        assert_eq!(page.rows.len(), 50);
+        assert_eq!(page.rows[0]["n"], 200);
+
+        let csv = dir.path().join("out.csv");
+        assert_eq!(workspace.export_result(&id, &csv).unwrap(), 250);
+        assert!(std::fs::read_to_string(&csv).unwrap().starts_with("n\n0\n"));
+
+        assert!(workspace.result_path("../secrets").is_err());
+    }
+
+    #[test]
+    fn test_bind_typed_params() {
+        let params = vec![
+            QueryParam {
+                name: "region".to_owned(),
+                param_type: ParamType::String,
+                prompt: "Region".to_owned(),
+                default: None,
+            },
+            QueryParam {
+                name: "min_total".to_owned(),
+                param_type: ParamType::Number,
+                prompt: String::new(),
+                default: Some("100".to_owned()),
+            },
+            QueryParam {
+                name: "from".to_owned(),
+                param_type: ParamType::Date,
+                prompt: String::new(),
+                default: None,
+            },
+        ];
+        let query = "SELECT * FROM data WHERE region = {region} AND total >= {min_total} \
+                     AND day >= {from} AND note <> '{other}'";
+        let values = HashMap::from([
+            ("region".to_owned(), "O'Brien's".to_owned()),
+            ("from".to_owned(), "2024-07-01".to_owned()),
+        ]);
+
+        assert_eq!(
+            bind_params(query, &params, &values).unwrap(),
+            "SELECT * FROM data WHERE region = 'O''Brien''s' AND total >= 100 \
+             AND day >= CAST('2024-07-01' AS DATE) AND note <> '{other}'"
+        );
+
+        let bad_number = HashMap::from([
+            ("region".to_owned(), "x".to_owned()),
+            ("from".to_owned(), "2024-07-01".to_owned()),
+            ("min_total".to_owned(), "1; DROP TABLE data".to_owned()),
+        ]);
+        assert!(bind_params(query, &params, &bad_number).is_err());
+        let missing = HashMap::from([("region".to_owned(), "x".to_owned())]);
+        let err = bind_params(query, &params, &missing).unwrap_err();
+        assert!(err.to_string().contains("'from'"));
+
+        // Quoted, a value could end the quotes and run as SQL
+        let injection = HashMap::from([("region".to_owned(), "x' OR '1'='1".to_owned())]);
+        for quoted in [
+            "SELECT * FROM data WHERE region = '{region}'",
+            "SELECT * FROM data WHERE region = 'it''s {region}'",
+            "SELECT \"{region}\" FROM data",
+            "SELECT * FROM data -- region {region}\nWHERE 1 = 1",
+            "SELECT * FROM data /* {region} */",
+        ] {
+            let err = bind_params(quoted, &params[..1], &injection).unwrap_err();
+            assert!(err.to_string().contains("inside quotes"), "{quoted}");
+        }
+        assert_eq!(
+            bind_params(
+                "SELECT '--', {region} FROM data -- done\n/* x */ WHERE 1 = 1",
+                &params[..1],
+                &injection
+            )
+            .unwrap(),
+            "SELECT '--', 'x'' OR ''1''=''1' FROM data -- done\n/* x */ WHERE 1 = 1"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = SqlWorkspace::new(dir.path());
+        let saved = workspace
+            .save_query_with_params("by region", query, None, &params)
+            .unwrap();
+        assert_eq!(workspace.saved_query(&saved.id).unwrap().params.len(), 3);
+
+        let mut duplicate = params.clone();
+        duplicate.push(params[0].clone());
+        assert!(validate_params(&duplicate).is_err());
+        let mut bad_default = params;
+        bad_default[2].default = Some("July".to_owned());
+        assert!(validate_params(&bad_default).is_err());
+    }
+}
@@ -1,11 +1,12 @@
 use anyhow::{Result, anyhow};
+use beefcake::config::policy::{Capability, PathPolicy};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
 const ALLOWED_TEXT_EXTENSIONS: &[&str] =
     &["csv", "json", "md", "parquet", "ps1", "py", "sql", "txt"];
 
-fn resolve_text_path(path: &str, require_file: bool) -> Result<PathBuf> {
+fn resolve_text_path(path: &str, capability: Capability) -> Result<PathBuf> {
     let raw = PathBuf::from(path);
     let absolute = to_absolute(&raw)?;
 
@@ -21,22 +22,7 @@ fn resolve_text_path(path: &str, require_file: bool) -> Result<PathBuf> {
         ));
     }
 
-    let candidate = if require_file {
-        absolute.clone()
-    } else {
-        absolute
-            .parent()
-            .ok_or_else(|| anyhow!("Failed to resolve parent directory"))?
-            .to_path_buf()
-    };
-
-    if !is_path_allowed(&candidate)? {
-        return Err(anyhow!(
-            "Access denied: path is outside permitted directories"
-        ));
-    }
-
-    Ok(absolute)
+    PathPolicy::load().authorise(&absolute, capability)
 }
 
 fn to_absolute(path: &Path) -> Result<PathBuf> {
@@ -49,52 +35,12 @@ fn to_absolute(path: &Path) -> Result<PathBuf> {
     }
 }
 
-fn normalize_path(path: &Path) -> PathBuf {
-    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
-}
-
-fn allowed_roots() -> Vec<PathBuf> {
-    let mut roots = Vec::new();
-    if let Some(dir) = dirs::data_local_dir() {
-        roots.push(dir.join("beefcake"));
-    }
-    if let Some(dir) = dirs::config_dir() {
-        roots.push(dir.join("beefcake"));
-    }
-
-    let config = beefcake::config::load_app_config();
-    for entry in config.settings.trusted_paths {
-        if !entry.trim().is_empty() {
-            roots.push(PathBuf::from(entry));
-        }
-    }
-
-    roots
-}
-
-fn is_path_allowed(path: &Path) -> Result<bool> {
-    let absolute = normalize_path(&to_absolute(path)?);
-
-    for root in allowed_roots() {
-        let root_abs = normalize_path(&root);
-        if absolute.starts_with(&root_abs) {
-            return Ok(true);
-        }
-    }
-
-    Ok(false)
-}
-
 pub fn open_path(path: &str) -> Result<()> {
     let absolute = to_absolute(Path::new(path))?;
     if !absolute.exists() {
         return Err(anyhow!("Path not found: {}", absolute.display()));
     }
-    if !is_path_allowed(&absolute)? {
-        return Err(anyhow!(
-            "Access denied: path is outside permitted directories"
-        ));
-    }
+    PathPolicy::load().authorise(&absolute, Capability::Read)?;
 
     let status = if cfg!(target_os = "windows") {
         Command::new("explorer").arg(&absolute).status()
@@ -172,7 +118,7 @@ pub fn install_python_package(package: &str) -> Result<String> {
 }
 
 pub fn read_text_file(path: &str) -> Result<String> {
-    let absolute = resolve_text_path(path, true)?;
+    let absolute = resolve_text_path(path, Capability::Read)?;
     if !absolute.is_file() {
         return Err(anyhow!("File not found: {}", absolute.display()));
     }
@@ -181,7 +127,7 @@ pub fn read_text_file(path: &str) -> Result<String> {
 }
 
 pub fn write_text_file(path: &str, contents: &str) -> Result<()> {
-    let absolute = resolve_text_path(path, false)?;
+    let absolute = resolve_text_path(path, Capability::Write)?;
     if let Some(parent) = absolute.parent()
         && !parent.exists()
     {
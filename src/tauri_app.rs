@@ -24,6 +24,9 @@ pub fn run() {
             commands::system::list_trusted_paths,
             commands::system::add_trusted_path,
             commands::system::remove_trusted_path,
+            commands::system::list_path_rules,
+            commands::system::save_path_rules,
+            commands::system::check_path_access,
             commands::system::list_documentation_files,
             commands::system::read_documentation_file,
             commands::system::log_frontend_error,
@@ -31,17 +34,55 @@ pub fn run() {
             commands::system::get_log_directory,
             commands::system::get_current_log_file,
             commands::system::get_current_error_log_file,
+            commands::system::list_runs,
+            commands::system::read_run_log,
+            commands::system::query_audit_log,
+            commands::system::export_audit_log,
             // Analysis
             commands::analysis::analyze_file,
+            commands::analysis::list_workbook_sheets,
+            commands::analysis::analyze_workbook,
+            commands::analysis::reanalyze_columns,
+            commands::analysis::preview_clean,
+            commands::analysis::list_cleaning_presets,
+            commands::analysis::save_cleaning_preset,
+            commands::analysis::delete_cleaning_preset,
+            commands::analysis::apply_cleaning_preset,
+            commands::analysis::column_usage_suggestions,
+            commands::analysis::clear_column_usage,
+            commands::analysis::suggest_value_standardisation,
+            commands::analysis::compute_correlation_matrix,
+            commands::analysis::compute_feature_importance,
+            commands::analysis::column_deep_dive,
+            commands::analysis::get_grid_page,
             commands::analysis::run_powershell,
             commands::analysis::run_python,
+            commands::analysis::run_python_sandboxed,
             commands::analysis::run_sql,
+            commands::sql::run_sql_query,
+            commands::sql::run_saved_query,
+            commands::sql::get_sql_result_page,
+            commands::sql::export_sql_result,
+            commands::sql::list_sql_history,
+            commands::sql::list_saved_queries,
+            commands::sql::save_sql_query,
+            commands::sql::delete_saved_query,
+            commands::scripts::list_script_templates,
+            commands::scripts::list_script_template_versions,
+            commands::scripts::save_script_template,
+            commands::scripts::delete_script_template,
+            commands::scripts::render_script_template,
             commands::analysis::sanitize_headers,
+            commands::analysis::generate_table_ddl,
+            commands::analysis::clean_in_place,
             commands::analysis::push_to_db,
+            commands::analysis::generate_cleaning_view,
             commands::analysis::abort_processing,
             commands::analysis::reset_abort_signal,
             commands::analysis::test_connection,
             commands::analysis::delete_connection,
+            commands::analysis::export_connections,
+            commands::analysis::import_connections,
             commands::analysis::install_python_package,
             commands::analysis::check_python_environment,
             // Integrity
@@ -52,16 +93,36 @@ pub fn run() {
             commands::lifecycle::lifecycle_apply_transforms,
             commands::lifecycle::lifecycle_set_active_version,
             commands::lifecycle::lifecycle_publish_version,
+            commands::lifecycle::lifecycle_transition_approval,
             commands::lifecycle::lifecycle_get_version_diff,
             commands::lifecycle::lifecycle_list_versions,
             commands::lifecycle::lifecycle_get_version_schema,
+            commands::lifecycle::lifecycle_delete_version,
+            commands::lifecycle::lifecycle_rollback_to,
+            commands::lifecycle::lifecycle_collect_garbage,
+            commands::lifecycle::lifecycle_refresh_dataset,
+            commands::lifecycle::lifecycle_set_source_pattern,
+            commands::lifecycle::lifecycle_storage_usage,
+            commands::lifecycle::lifecycle_tag_version,
+            commands::lifecycle::lifecycle_untag_version,
+            commands::lifecycle::lifecycle_annotate_version,
+            commands::lifecycle::lifecycle_search_versions,
+            commands::lifecycle::lifecycle_get_lineage,
+            commands::lifecycle::lifecycle_get_lineage_dot,
+            commands::lifecycle::lifecycle_export_viewer,
             // Pipeline
             commands::pipeline::save_pipeline_spec,
             commands::pipeline::load_pipeline_spec,
             commands::pipeline::validate_pipeline_spec,
+            commands::pipeline::validate_pipeline_spec_detailed,
+            commands::pipeline::resolve_pipeline_columns,
+            commands::pipeline::dry_run_pipeline_spec,
+            commands::pipeline::list_pipeline_runs,
+            commands::pipeline::get_pipeline_run,
             commands::pipeline::generate_powershell,
             commands::pipeline::pipeline_from_configs,
             commands::pipeline::execute_pipeline_spec,
+            commands::pipeline::run_pipeline_on_dataset,
             commands::pipeline::delete_pipeline_spec,
             commands::pipeline::list_pipeline_specs,
             commands::pipeline::list_pipeline_templates,
@@ -69,16 +130,39 @@ pub fn run() {
             // Dictionary
             commands::dictionary::dictionary_load_snapshot,
             commands::dictionary::dictionary_list_snapshots,
+            commands::dictionary::dictionary_storage_usage,
+            commands::dictionary::dictionary_prune_snapshots,
+            commands::dictionary::dictionary_search,
+            commands::dictionary::dictionary_review_schedule,
+            commands::dictionary::dictionary_mark_reviewed,
             commands::dictionary::dictionary_update_business_metadata,
             commands::dictionary::dictionary_export_markdown,
+            commands::dictionary::dictionary_export_html,
+            commands::dictionary::dictionary_export_pdf,
+            commands::dictionary::dictionary_export_template,
+            commands::dictionary::dictionary_import_template,
+            commands::dictionary::dictionary_list_glossary,
+            commands::dictionary::dictionary_save_glossary_term,
+            commands::dictionary::dictionary_delete_glossary_term,
+            commands::dictionary::dictionary_link_glossary_terms,
+            commands::dictionary::dictionary_create_from_analysis,
             // Watcher
             commands::watcher::watcher_get_state,
             commands::watcher::watcher_start,
             commands::watcher::watcher_stop,
             commands::watcher::watcher_set_folder,
             commands::watcher::watcher_ingest_now,
+            commands::watcher::watcher_list_tables,
+            commands::watcher::watcher_add_table,
+            commands::watcher::watcher_remove_table,
             // AI
             commands::ai::ai_send_query,
+            commands::ai::ai_send_query_stream,
+            commands::ai::ai_send_conversation_stream,
+            commands::ai::ai_list_conversations,
+            commands::ai::ai_get_conversation,
+            commands::ai::ai_delete_conversation,
+            commands::ai::ai_build_context,
             commands::ai::ai_set_api_key,
             commands::ai::ai_delete_api_key,
             commands::ai::ai_has_api_key,
@@ -1,9 +1,14 @@
+use anyhow::Context as _;
 use chrono::Local;
 use keyring::Entry;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+pub mod memory;
+pub mod retry;
+pub mod runs;
+
 pub const DATA_INPUT_DIR: &str = "data/input";
 pub const DATA_PROCESSED_DIR: &str = "data/processed";
 pub const KEYRING_SERVICE: &str = "au.com.ahenderson.beefcake";
@@ -66,6 +71,15 @@ pub fn trigger_abort() {
     ABORT_SIGNAL.store(true, Ordering::SeqCst);
 }
 
+/// Fail with "`what` aborted by user" once the user has aborted, for long
+/// operations to call between their steps
+pub fn check_aborted(what: &str) -> anyhow::Result<()> {
+    if is_aborted() {
+        anyhow::bail!("{what} aborted by user");
+    }
+    Ok(())
+}
+
 pub fn get_db_password(connection_id: &str) -> Option<String> {
     let entry = Entry::new(KEYRING_SERVICE, connection_id).ok()?;
     entry.get_password().ok()
@@ -106,6 +120,14 @@ pub fn delete_ai_api_key() -> anyhow::Result<()> {
     entry.delete_credential().map_err(|e| anyhow::anyhow!(e))
 }
 
+/// Path of the `ext` file in `dir` named by `id`. Ids come from the
+/// frontend, so only a UUID is accepted and none can escape `dir`; `kind`
+/// names what the id is of in the error.
+pub fn id_path(dir: &Path, id: &str, ext: &str, kind: &str) -> anyhow::Result<PathBuf> {
+    let id = uuid::Uuid::parse_str(id).with_context(|| format!("Invalid {kind} id: {id}"))?;
+    Ok(dir.join(format!("{id}.{ext}")))
+}
+
 pub fn archive_processed_file(file_path: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
     let original = file_path.as_ref();
     let processed_dir = Path::new(DATA_PROCESSED_DIR);
@@ -258,6 +280,18 @@ mod tests {
         std::fs::remove_file(&test_file).expect("Remove failed");
     }
 
+    #[test]
+    fn test_id_path_rejects_non_uuid_ids() {
+        let dir = Path::new("store");
+        let id = "6f1c2a3e-0d4b-4f7a-9c8e-1b2a3c4d5e6f";
+        assert_eq!(
+            id_path(dir, id, "json", "preset").unwrap(),
+            dir.join(format!("{id}.json"))
+        );
+        let err = id_path(dir, "../secrets", "json", "preset").unwrap_err();
+        assert_eq!(err.to_string(), "Invalid preset id: ../secrets");
+    }
+
     #[test]
     fn test_temp_file_collection() {
         let temp_dir = std::env::temp_dir();
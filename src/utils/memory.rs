@@ -0,0 +1,258 @@
+//! Keeping work within a memory budget.
+//!
+//! Collecting a large file can exhaust memory long before Polars reports an
+//! error. A [`MemoryGovernor`] (configured under `memory_budget` in the app
+//! settings) estimates what a file or frame takes once loaded and, when that
+//! would exceed the budget, picks a [`MemoryStrategy`]: run the query on the
+//! streaming engine, or stage the data in a temporary Parquet file for work
+//! that reads it more than once. Analysis also uses [`MemoryGovernor::rows_within`]
+//! to size its samples so they fit.
+//!
+//! Estimates are rough: fixed-width values count their width, text counts a
+//! flat 32 bytes per value, and files count a multiple of their size on disk.
+
+use anyhow::{Context as _, Result};
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::TempFileCollection;
+
+/// Bytes assumed per value of a text or binary column
+const VARIABLE_WIDTH_BYTES: u64 = 32;
+
+/// How much memory operations may use for loaded data
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MemoryBudget {
+    /// Budget in MiB; 0 for no limit
+    pub limit_mb: u64,
+    /// Folder for temporary Parquet files; the system temp folder when unset
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self {
+            limit_mb: 2048,
+            spill_dir: None,
+        }
+    }
+}
+
+/// How to process data of a given size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryStrategy {
+    /// The data fits: collect it as usual
+    InMemory,
+    /// Too large: run the query on the streaming engine and sink to files
+    Streaming,
+    /// Too large and read more than once: stage it in a temporary Parquet
+    /// file, so each pass reads only the columns it needs
+    SpillToDisk,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryGovernor {
+    budget: MemoryBudget,
+}
+
+impl MemoryGovernor {
+    pub fn new(budget: MemoryBudget) -> Self {
+        Self { budget }
+    }
+
+    /// The governor for the budget in the app settings
+    pub fn from_settings() -> Self {
+        Self::new(crate::config::load_app_config().settings.memory_budget)
+    }
+
+    /// The budget in bytes, `None` without a limit
+    pub fn limit(&self) -> Option<u64> {
+        (self.budget.limit_mb > 0).then(|| self.budget.limit_mb.saturating_mul(1024 * 1024))
+    }
+
+    pub fn fits(&self, bytes: u64) -> bool {
+        self.limit().is_none_or(|limit| bytes <= limit)
+    }
+
+    /// How to process data estimated at `bytes`; `multi_pass` work, such as
+    /// imputing medians, reads its input more than once
+    pub fn strategy(&self, bytes: u64, multi_pass: bool) -> MemoryStrategy {
+        match (self.fits(bytes), multi_pass) {
+            (true, _) => MemoryStrategy::InMemory,
+            (false, false) => MemoryStrategy::Streaming,
+            (false, true) => MemoryStrategy::SpillToDisk,
+        }
+    }
+
+    /// Rows of `schema` that fit the budget, `None` without a limit
+    pub fn rows_within(&self, schema: &Schema) -> Option<usize> {
+        self.limit()
+            .map(|limit| usize::try_from(limit / row_bytes(schema).max(1)).unwrap_or(usize::MAX))
+    }
+
+    /// Estimated bytes of the rows of `lf`, counted on the streaming engine
+    pub fn estimate_lazy(&self, lf: &LazyFrame) -> Result<u64> {
+        let schema = lf
+            .clone()
+            .collect_schema()
+            .map_err(|e| anyhow::anyhow!(e))?;
+        let rows = lf
+            .clone()
+            .select([len()])
+            .with_streaming(true)
+            .collect()?
+            .column("len")?
+            .as_materialized_series()
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap_or(0);
+        Ok(rows.saturating_mul(row_bytes(&schema)))
+    }
+
+    /// Stage `lf` in a temporary Parquet file, cleaned up with `temp_files`,
+    /// and scan it back
+    pub fn spill(&self, lf: LazyFrame, temp_files: &mut TempFileCollection) -> Result<LazyFrame> {
+        let dir = self
+            .budget
+            .spill_dir
+            .clone()
+            .unwrap_or_else(std::env::temp_dir);
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create spill folder {}", dir.display()))?;
+        let path = dir.join(format!("beefcake_spill_{}.parquet", uuid::Uuid::new_v4()));
+        temp_files.add(path.clone());
+
+        lf.with_streaming(true)
+            .sink_parquet(&path, ParquetWriteOptions::default(), None)
+            .context("Failed to spill data to disk")?;
+        crate::config::log_event(
+            "Memory",
+            &format!(
+                "Spilled data over the memory budget to {} ({})",
+                path.display(),
+                super::fmt_bytes(std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0))
+            ),
+        );
+        LazyFrame::scan_parquet(&path, ScanArgsParquet::default())
+            .context("Failed to scan spilled data")
+    }
+}
+
+/// Estimated bytes of one row of `schema` in memory
+pub fn row_bytes(schema: &Schema) -> u64 {
+    schema.iter().map(|(_, dtype)| dtype_bytes(dtype)).sum()
+}
+
+/// Estimated bytes of `rows` rows of `schema` in memory
+pub fn estimate_frame(schema: &Schema, rows: usize) -> u64 {
+    (rows as u64).saturating_mul(row_bytes(schema))
+}
+
+/// Bytes `df` takes in memory
+pub fn estimate_df(df: &DataFrame) -> u64 {
+    df.estimated_size() as u64
+}
+
+/// Estimated bytes of the file (or folder of Parquet parts) at `path` once
+/// loaded. Compressed formats expand more than text.
+pub fn estimate_file(path: &Path) -> u64 {
+    let on_disk = if path.is_dir() {
+        std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok()?.metadata().ok())
+                    .map(|m| m.len())
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    };
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    let expansion = match ext.as_str() {
+        "parquet" => 4,
+        "xlsx" | "xls" => 5,
+        _ => 2,
+    };
+    on_disk.saturating_mul(expansion)
+}
+
+fn dtype_bytes(dtype: &DataType) -> u64 {
+    match dtype {
+        DataType::Boolean | DataType::Int8 | DataType::UInt8 => 1,
+        DataType::Int16 | DataType::UInt16 => 2,
+        DataType::Int32
+        | DataType::UInt32
+        | DataType::Float32
+        | DataType::Date
+        | DataType::Categorical(..)
+        | DataType::Enum(..) => 4,
+        DataType::String | DataType::Binary => VARIABLE_WIDTH_BYTES,
+        DataType::List(inner) => 4 * dtype_bytes(inner),
+        _ => 8,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_and_rows_within_budget() {
+        let governor = MemoryGovernor::new(MemoryBudget {
+            limit_mb: 1,
+            spill_dir: None,
+        });
+        assert_eq!(governor.strategy(1024, true), MemoryStrategy::InMemory);
+        assert_eq!(
+            governor.strategy(2 * 1024 * 1024, false),
+            MemoryStrategy::Streaming
+        );
+        assert_eq!(
+            governor.strategy(2 * 1024 * 1024, true),
+            MemoryStrategy::SpillToDisk
+        );
+
+        // 8 bytes of Int64 and 32 of text per row
+        let schema = Schema::from_iter([
+            Field::new("id".into(), DataType::Int64),
+            Field::new("name".into(), DataType::String),
+        ]);
+        assert_eq!(row_bytes(&schema), 40);
+        assert_eq!(governor.rows_within(&schema), Some(1024 * 1024 / 40));
+
+        let unlimited = MemoryGovernor::new(MemoryBudget {
+            limit_mb: 0,
+            spill_dir: None,
+        });
+        assert!(unlimited.fits(u64::MAX));
+        assert_eq!(unlimited.rows_within(&schema), None);
+    }
+
+    #[test]
+    fn test_spill_round_trips_through_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let governor = MemoryGovernor::new(MemoryBudget {
+            limit_mb: 1,
+            spill_dir: Some(dir.path().to_path_buf()),
+        });
+        let df = df!("id" => [1i64, 2, 3], "name" => ["a", "b", "c"]).unwrap();
+        assert_eq!(governor.estimate_lazy(&df.clone().lazy()).unwrap(), 3 * 40);
+
+        let mut temp_files = TempFileCollection::new();
+        let spilled = governor.spill(df.clone().lazy(), &mut temp_files).unwrap();
+        assert_eq!(temp_files.paths().len(), 1);
+        assert!(spilled.collect().unwrap().equals(&df));
+
+        drop(temp_files);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+}
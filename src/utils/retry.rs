@@ -0,0 +1,308 @@
+//! Retrying operations that fail for transient reasons.
+//!
+//! A [`RetryPolicy`] (configured under `retry_policy` in the app settings)
+//! reruns an operation with exponentially growing, jittered delays, but only
+//! while [`is_transient`] classifies the error as worth another attempt, so a
+//! bad password or a missing table fails straight away while a dropped
+//! connection during an overnight run does not.
+
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::hash::BuildHasher as _;
+use std::time::Duration;
+
+/// How often and how patiently to retry a failing operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first; 1 disables retrying
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_delay_ms: u64,
+    /// Upper bound for any single delay
+    pub max_delay_ms: u64,
+    /// Factor the delay grows by after each retry
+    pub multiplier: f64,
+    /// Fraction of each delay added or removed at random (0.0-1.0), so
+    /// clients that failed together do not retry in lockstep
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_delay_ms: 1_000,
+            max_delay_ms: 30_000,
+            multiplier: 2.0,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The policy from the app settings
+    pub fn from_settings() -> Self {
+        crate::config::load_app_config().settings.retry_policy
+    }
+
+    /// Delay before retry number `retry` (1 for the first retry)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = i32::try_from(retry.saturating_sub(1)).unwrap_or(i32::MAX);
+        let base = (self.initial_delay_ms as f64 * self.multiplier.max(1.0).powi(exponent))
+            .min(self.max_delay_ms as f64);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = 1.0 + jitter * (2.0 * random_unit() - 1.0);
+        Duration::from_millis((base * factor).max(0.0) as u64)
+    }
+
+    /// Run `op` until it succeeds, fails with a permanent error, or runs out
+    /// of attempts. `what` names the operation in the audit log.
+    pub fn retry<T>(&self, what: &str, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(e) if self.should_retry(attempt, &e) => {
+                    let delay = self.delay(attempt);
+                    log_retry(what, attempt, delay, &e);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(e) => return Err(give_up(what, attempt, e)),
+            }
+        }
+    }
+
+    /// Async form of [`RetryPolicy::retry`]; `op` creates a fresh future for
+    /// each attempt
+    pub async fn retry_async<T, F, Fut>(&self, what: &str, mut op: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match op().await {
+                Ok(value) => return Ok(value),
+                Err(e) if self.should_retry(attempt, &e) => {
+                    let delay = self.delay(attempt);
+                    log_retry(what, attempt, delay, &e);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(give_up(what, attempt, e)),
+            }
+        }
+    }
+
+    fn should_retry(&self, attempt: u32, err: &Error) -> bool {
+        attempt < self.max_attempts && is_transient(err)
+    }
+}
+
+fn give_up(what: &str, attempt: u32, err: Error) -> Error {
+    if attempt > 1 {
+        err.context(format!("{what} failed after {attempt} attempts"))
+    } else {
+        err
+    }
+}
+
+/// Marks an error as transient whatever its underlying type, for callers
+/// that know better than [`is_transient`] can guess
+#[derive(Debug)]
+pub struct Transient(String);
+
+impl std::fmt::Display for Transient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Transient {}
+
+/// A new error that [`is_transient`] will always retry
+pub fn transient(message: impl Into<String>) -> Error {
+    Error::new(Transient(message.into()))
+}
+
+/// Whether `err` is likely to go away on its own: dropped or refused
+/// connections, timeouts, rate limiting, and database errors such as
+/// serialization failures or a server that is starting up
+pub fn is_transient(err: &Error) -> bool {
+    err.chain().any(|cause| {
+        if cause.is::<Transient>() {
+            return true;
+        }
+        if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+            return is_transient_io(io);
+        }
+        if let Some(db) = cause.downcast_ref::<sqlx::Error>() {
+            return is_transient_db(db);
+        }
+        if let Some(api) = cause.downcast_ref::<async_openai::error::OpenAIError>() {
+            return matches!(api, async_openai::error::OpenAIError::Reqwest(_))
+                || mentions_transient(&api.to_string());
+        }
+        // Only the root cause: context messages such as "Failed to connect
+        // (timeout after 10s)" describe the operation, not what went wrong
+        cause.source().is_none() && mentions_transient(&cause.to_string())
+    })
+}
+
+fn is_transient_io(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    matches!(
+        err.kind(),
+        ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::Interrupted
+            | ErrorKind::UnexpectedEof
+            | ErrorKind::WouldBlock
+    )
+}
+
+fn is_transient_db(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut => true,
+        sqlx::Error::Database(db) => db.code().is_some_and(|code| {
+            // Class 08: connection exceptions. The rest: serialization
+            // failure, deadlock, too many connections, and server shutdown
+            // or startup.
+            code.starts_with("08")
+                || matches!(
+                    code.as_ref(),
+                    "40001" | "40P01" | "53300" | "57P01" | "57P02" | "57P03"
+                )
+        }),
+        _ => false,
+    }
+}
+
+/// Fallback for errors only known by their message, such as output from an
+/// external client or an HTTP status wrapped in text
+fn mentions_transient(message: &str) -> bool {
+    const PATTERNS: &[&str] = &[
+        "timed out",
+        "timeout",
+        "connection reset",
+        "connection refused",
+        "connection closed",
+        "broken pipe",
+        "temporarily unavailable",
+        "network is unreachable",
+        "could not resolve",
+        "too many requests",
+        "rate limit",
+        "service unavailable",
+        "bad gateway",
+    ];
+    let message = message.to_lowercase();
+    PATTERNS.iter().any(|p| message.contains(p))
+}
+
+/// A random number in [0, 1) without pulling in an RNG crate; each
+/// `RandomState` is seeded differently
+fn random_unit() -> f64 {
+    let bits = std::collections::hash_map::RandomState::new().hash_one(0_u8);
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+fn log_retry(what: &str, attempt: u32, delay: Duration, err: &Error) {
+    crate::config::log_event(
+        "Retry",
+        &format!(
+            "{what} failed on attempt {attempt}, retrying in {:.1}s: {err:#}",
+            delay.as_secs_f64()
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn quick() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay_ms: 0,
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_retry_stops_on_success_or_permanent_error() {
+        let mut calls = 0;
+        let value = quick()
+            .retry("flaky", || {
+                calls += 1;
+                if calls < 3 {
+                    Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset).into())
+                } else {
+                    Ok(calls)
+                }
+            })
+            .unwrap();
+        assert_eq!(value, 3);
+
+        let mut calls = 0;
+        let err = quick()
+            .retry("bad password", || -> Result<()> {
+                calls += 1;
+                Err(anyhow!("password authentication failed"))
+            })
+            .unwrap_err();
+        assert_eq!(calls, 1);
+        assert_eq!(err.to_string(), "password authentication failed");
+
+        let mut calls = 0;
+        let err = quick()
+            .retry("upload", || -> Result<()> {
+                calls += 1;
+                Err(transient("share offline"))
+            })
+            .unwrap_err();
+        assert_eq!(calls, 3);
+        assert_eq!(
+            format!("{err:#}"),
+            "upload failed after 3 attempts: share offline"
+        );
+    }
+
+    #[test]
+    fn test_delay_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            initial_delay_ms: 100,
+            max_delay_ms: 350,
+            multiplier: 2.0,
+            jitter: 0.0,
+            ..RetryPolicy::default()
+        };
+        let delays: Vec<u128> = (1..=4).map(|n| policy.delay(n).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 350, 350]);
+
+        let jittered = RetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..20 {
+            let ms = jittered.delay(1).as_millis();
+            assert!((50..=150).contains(&ms), "{ms}");
+        }
+
+        assert!(is_transient(
+            &anyhow!(std::io::Error::from(std::io::ErrorKind::TimedOut)).context("COPY failed")
+        ));
+        assert!(is_transient(&anyhow!("HTTP 429 Too Many Requests")));
+        assert!(!is_transient(&anyhow!(
+            "relation \"orders\" does not exist"
+        )));
+    }
+}
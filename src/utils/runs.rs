@@ -0,0 +1,338 @@
+//! Correlation IDs for analysis, pipeline and export runs.
+//!
+//! A [`Run`] gives one run an ID such as `pipeline-1f3a9c02` and a `run`
+//! tracing span carrying it. Every event logged inside the span, including
+//! the entries of [`crate::config::log_event`], is written to the structured
+//! log (`beefcake.<date>.jsonl` in the logs directory, see [`json_layer`])
+//! with the ID, so the log of one run can be read back on its own with
+//! [`read_run_log`] and recent runs listed with [`list_runs`].
+//!
+//! Sync work runs in the span with [`Run::in_scope`] and async work with
+//! [`Run::instrument`]; [`Run::finish`] logs the outcome.
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::io::{BufRead as _, BufReader};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::Instrument as _;
+use tracing::instrument::Instrumented;
+
+/// Target of the events marking the start and end of a run
+pub const RUN_TARGET: &str = "beefcake::run";
+
+/// File name prefix and suffix of the structured log
+pub const JSON_LOG_PREFIX: &str = "beefcake";
+pub const JSON_LOG_SUFFIX: &str = "jsonl";
+
+const RUN_STARTED: &str = "Run started";
+const RUN_FINISHED: &str = "Run finished";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunKind {
+    Analysis,
+    Pipeline,
+    Export,
+}
+
+impl RunKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Analysis => "analysis",
+            Self::Pipeline => "pipeline",
+            Self::Export => "export",
+        }
+    }
+}
+
+/// A run in progress
+#[derive(Debug)]
+pub struct Run {
+    id: String,
+    span: tracing::Span,
+    started: Instant,
+}
+
+impl Run {
+    /// Start a run of `kind` on `label` (a file or pipeline name) with a new
+    /// ID, logging its start
+    pub fn start(kind: RunKind, label: &str) -> Self {
+        let simple = uuid::Uuid::new_v4().simple().to_string();
+        let id = format!("{}-{}", kind.as_str(), &simple[..8]);
+        let span = tracing::info_span!("run", run_id = %id, kind = kind.as_str());
+        span.in_scope(|| tracing::info!(target: RUN_TARGET, label, "{RUN_STARTED}"));
+        Self {
+            id,
+            span,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+
+    /// Call `f` inside the run's span
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.span.in_scope(f)
+    }
+
+    /// `future`, polled inside the run's span
+    pub fn instrument<F: Future>(&self, future: F) -> Instrumented<F> {
+        future.instrument(self.span.clone())
+    }
+
+    /// Log how the run ended
+    pub fn finish<T, E: Display>(self, result: &std::result::Result<T, E>) {
+        let duration_ms = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.span.in_scope(|| match result {
+            Ok(_) => tracing::info!(
+                target: RUN_TARGET,
+                status = "ok",
+                duration_ms,
+                "{RUN_FINISHED}"
+            ),
+            Err(e) => {
+                let error = format!("{e:#}");
+                tracing::warn!(
+                    target: RUN_TARGET,
+                    status = "failed",
+                    duration_ms,
+                    error = error.as_str(),
+                    "{RUN_FINISHED}"
+                );
+            }
+        });
+    }
+}
+
+/// Layer writing events as JSON lines to `writer`, each with the fields of
+/// its spans, so run IDs can be found in the file
+pub fn json_layer<S, W>(writer: W) -> impl tracing_subscriber::Layer<S>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_target(true)
+        .with_writer(writer)
+}
+
+/// One event of the structured log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// The other fields of the event
+    pub fields: Map<String, Value>,
+}
+
+/// A run found in the structured log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub kind: String,
+    /// File or pipeline the run worked on
+    pub label: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// `ok` or `failed`; `None` while running, or if the app stopped
+    /// during the run
+    pub status: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: Option<u64>,
+}
+
+/// Structured log files in `dir`, newest first
+pub fn json_log_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let prefix = format!("{JSON_LOG_PREFIX}.");
+    let suffix = format!(".{JSON_LOG_SUFFIX}");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to list {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(&suffix))
+        })
+        .collect();
+    // Names end in the date, so they sort by age
+    files.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(files)
+}
+
+/// The most recent `limit` runs in the structured log in `dir`, newest
+/// first
+pub fn list_runs(dir: &Path, limit: usize) -> Result<Vec<RunSummary>> {
+    let mut runs: HashMap<String, RunSummary> = HashMap::new();
+    let mut finished: HashMap<String, RunLogEntry> = HashMap::new();
+    for file in json_log_files(dir)? {
+        for_each_event(&file, |run_id, entry| {
+            let Some(run_id) = run_id else { return };
+            if entry.target != RUN_TARGET {
+                return;
+            }
+            match entry.message.as_str() {
+                RUN_STARTED => {
+                    runs.insert(
+                        run_id.to_owned(),
+                        RunSummary {
+                            run_id: run_id.to_owned(),
+                            kind: run_id.split('-').next().unwrap_or_default().to_owned(),
+                            label: string_field(&entry.fields, "label").unwrap_or_default(),
+                            started_at: entry.timestamp,
+                            finished_at: None,
+                            status: None,
+                            error: None,
+                            duration_ms: None,
+                        },
+                    );
+                }
+                RUN_FINISHED => {
+                    finished.insert(run_id.to_owned(), entry);
+                }
+                _ => {}
+            }
+        })?;
+        if runs.len() >= limit {
+            break;
+        }
+    }
+
+    let mut runs: Vec<RunSummary> = runs
+        .into_values()
+        .map(|mut run| {
+            if let Some(end) = finished.remove(&run.run_id) {
+                run.status = string_field(&end.fields, "status");
+                run.error = string_field(&end.fields, "error");
+                run.duration_ms = end.fields.get("duration_ms").and_then(Value::as_u64);
+                run.finished_at = Some(end.timestamp);
+            }
+            run
+        })
+        .collect();
+    runs.sort_unstable_by(|a, b| b.started_at.cmp(&a.started_at));
+    runs.truncate(limit);
+    Ok(runs)
+}
+
+/// The last `limit` events logged in run `run_id`, oldest first
+pub fn read_run_log(dir: &Path, run_id: &str, limit: usize) -> Result<Vec<RunLogEntry>> {
+    let mut entries = Vec::new();
+    // Oldest file first, so events stay in order
+    for file in json_log_files(dir)?.into_iter().rev() {
+        for_each_event(&file, |id, entry| {
+            if id == Some(run_id) {
+                entries.push(entry);
+            }
+        })?;
+    }
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.split_off(skip))
+}
+
+/// Call `f` with the run ID (if any) and contents of each event in `file`,
+/// skipping lines that are not events
+fn for_each_event(file: &Path, mut f: impl FnMut(Option<&str>, RunLogEntry)) -> Result<()> {
+    let reader = BufReader::new(
+        std::fs::File::open(file).with_context(|| format!("Failed to open {}", file.display()))?,
+    );
+    for line in reader.lines() {
+        let line = line.with_context(|| format!("Failed to read {}", file.display()))?;
+        let Ok(Value::Object(event)) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let run_id = run_id_of(&event).map(str::to_owned);
+        let mut fields = match event.get("fields") {
+            Some(Value::Object(fields)) => fields.clone(),
+            _ => Map::new(),
+        };
+        let message = match fields.remove("message") {
+            Some(Value::String(message)) => message,
+            Some(other) => other.to_string(),
+            None => String::new(),
+        };
+        f(
+            run_id.as_deref(),
+            RunLogEntry {
+                timestamp: string_field(&event, "timestamp").unwrap_or_default(),
+                level: string_field(&event, "level").unwrap_or_default(),
+                target: string_field(&event, "target").unwrap_or_default(),
+                message,
+                fields,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// ID of the innermost run span an event was logged in
+fn run_id_of(event: &Map<String, Value>) -> Option<&str> {
+    let in_span = |span: &Value| span.get("run_id").and_then(Value::as_str);
+    event.get("span").and_then(in_span).or_else(|| {
+        event
+            .get("spans")
+            .and_then(Value::as_array)
+            .and_then(|spans| spans.iter().rev().find_map(in_span))
+    })
+}
+
+fn string_field(map: &Map<String, Value>, key: &str) -> Option<String> {
+    map.get(key).and_then(Value::as_str).map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    #[test]
+    fn test_runs_are_read_back_from_the_json_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = std::fs::File::create(dir.path().join("beefcake.2026-01-01.jsonl")).unwrap();
+        let subscriber = tracing_subscriber::registry().with(json_layer(Arc::new(file)));
+
+        let run_id = tracing::subscriber::with_default(subscriber, || {
+            let run = Run::start(RunKind::Pipeline, "orders");
+            run.in_scope(|| {
+                crate::config::log_event("Pipeline", "Step 1: drop columns");
+            });
+            tracing::info!("Not part of the run");
+            let id = run.id().to_owned();
+            run.finish(&Err::<(), _>(anyhow::anyhow!("Missing column")));
+            id
+        });
+
+        let runs = list_runs(dir.path(), 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].run_id, run_id);
+        assert_eq!(runs[0].kind, "pipeline");
+        assert_eq!(runs[0].label, "orders");
+        assert_eq!(runs[0].status.as_deref(), Some("failed"));
+        assert_eq!(runs[0].error.as_deref(), Some("Missing column"));
+
+        let entries = read_run_log(dir.path(), &run_id, 100).unwrap();
+        let messages: Vec<_> = entries.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [RUN_STARTED, "Step 1: drop columns", RUN_FINISHED]
+        );
+        assert_eq!(entries[1].fields["action"], "Pipeline");
+        assert_eq!(read_run_log(dir.path(), &run_id, 1).unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,12 @@
 //! Filesystem watcher module
 //!
 //! Monitors a folder for new CSV/JSON/Parquet files and automatically ingests them
-//! into the dataset lifecycle system.
+//! into the dataset lifecycle system. With `auto_refresh` on, a change to a
+//! file that a lifecycle dataset was ingested from refreshes that dataset
+//! instead. Database tables can also be registered
+//! to be polled on an interval; when a table's row count or latest update
+//! timestamp changes, its rows are landed as Parquet and analysed or run
+//! through a pipeline.
 //!
 //! ## Architecture
 //!
@@ -10,6 +15,7 @@
 //!   │
 //!   ├─> notify::Watcher (filesystem events)
 //!   ├─> StabilityChecker (ensures file is fully written)
+//!   ├─> Table poller (database tables, own thread)
 //!   └─> Event Emission (to frontend via Tauri)
 //! ```
 //!
@@ -21,6 +27,10 @@
 //! - Real-time event emission to frontend via Tauri
 //! - Persistent configuration with auto-start
 //! - Activity feed with retry functionality
+//! - Folders and files denied for reading by a path rule are never watched
+//!   or ingested
+//! - Headless watching for `beefcake watch`, running a pipeline over each
+//!   file (see [`headless`])
 //!
 //! ## Example Usage
 //!
@@ -57,6 +67,15 @@
 //! 7. Dataset created in Raw lifecycle stage
 //! 8. Success/failure event emitted to UI
 //!
+//! When a new file matches the source pattern of datasets in the registry
+//! set with [`set_lifecycle_registry`] (e.g. `sales_*.csv`), step 7 instead
+//! adds it to each of them as a new Raw version, which becomes active.
+//!
+//! When the file is the source of a dataset in the registry set with
+//! [`set_lifecycle_registry`] and `auto_refresh` is on, steps 6-8 are
+//! replaced by [`DatasetRegistry::refresh_from_path`] and a
+//! `watcher:source_refreshed` or `watcher:source_refresh_failed` event.
+//!
 //! ## Configuration
 //!
 //! Configuration is persisted in `config/watcher.json`:
@@ -64,27 +83,47 @@
 //! {
 //!   "enabled": true,
 //!   "folder": "/path/to/watch",
-//!   "stability_window_secs": 2
+//!   "stability_window_secs": 2,
+//!   "auto_refresh": false
 //! }
 //! ```
 
 pub mod config;
 pub mod events;
+pub mod headless;
 pub mod service;
+pub mod table;
 
 pub use config::WatcherConfig;
 pub use events::*;
+pub use headless::{HeadlessWatch, WatchEvent, watch_folder};
 pub use service::{WatcherMessage, WatcherService};
+pub use table::{TableAction, TableWatch};
 
+use crate::analyser::lifecycle::DatasetRegistry;
+use crate::config::policy::{Capability, PathPolicy};
 use anyhow::Result;
 use std::path::PathBuf;
-use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
 use tauri::AppHandle;
 
 /// Global watcher service instance
 static WATCHER_SERVICE: LazyLock<Arc<Mutex<Option<WatcherService>>>> =
     LazyLock::new(|| Arc::new(Mutex::new(None)));
 
+/// Registry whose datasets are refreshed when their source file changes
+static LIFECYCLE_REGISTRY: OnceLock<Arc<DatasetRegistry>> = OnceLock::new();
+
+/// Share the application's dataset registry with the watcher so source
+/// changes can refresh its datasets. Only the first registry set is used.
+pub fn set_lifecycle_registry(registry: Arc<DatasetRegistry>) {
+    let _ = LIFECYCLE_REGISTRY.set(registry);
+}
+
+pub(crate) fn lifecycle_registry() -> Option<&'static Arc<DatasetRegistry>> {
+    LIFECYCLE_REGISTRY.get()
+}
+
 /// Initialize the watcher service
 pub fn init(app: AppHandle) -> Result<()> {
     let config = WatcherConfig::load()?;
@@ -104,8 +143,9 @@ pub fn init(app: AppHandle) -> Result<()> {
     Ok(())
 }
 
-/// Start watching a folder
+/// Start watching a folder, unless a path rule denies reading it
 pub fn start(folder: PathBuf) -> Result<()> {
+    PathPolicy::load().ensure_not_denied(&folder, Capability::Read)?;
     let service = WATCHER_SERVICE
         .lock()
         .map_err(|e| anyhow::anyhow!("Failed to acquire watcher service lock: {e}"))?;
@@ -139,6 +179,7 @@ pub fn stop() -> Result<()> {
 
 /// Set the watched folder
 pub fn set_folder(folder: PathBuf) -> Result<()> {
+    PathPolicy::load().ensure_not_denied(&folder, Capability::Read)?;
     // Update config
     let mut config = WatcherConfig::load()?;
     config.folder = folder.clone();
@@ -189,3 +230,38 @@ pub fn get_state() -> Result<WatcherStatusPayload> {
         message: None,
     })
 }
+
+/// List the database tables registered for polling
+pub fn list_table_watches() -> Result<Vec<TableWatch>> {
+    Ok(WatcherConfig::load()?.tables)
+}
+
+/// Register a table for polling, or update the watch with the same id.
+/// A watch without an id is given a new one. Returns the saved watch.
+pub fn add_table_watch(mut watch: TableWatch) -> Result<TableWatch> {
+    if watch.table.trim().is_empty() {
+        anyhow::bail!("Table name is required");
+    }
+    if watch.connection_id.trim().is_empty() {
+        anyhow::bail!("Database connection is required");
+    }
+    if watch.id.is_empty() {
+        watch.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    let mut config = WatcherConfig::load()?;
+    if let Some(existing) = config.tables.iter_mut().find(|w| w.id == watch.id) {
+        *existing = watch.clone();
+    } else {
+        config.tables.push(watch.clone());
+    }
+    config.save()?;
+    Ok(watch)
+}
+
+/// Stop polling a table
+pub fn remove_table_watch(id: &str) -> Result<()> {
+    let mut config = WatcherConfig::load()?;
+    config.tables.retain(|w| w.id != id);
+    config.save()
+}
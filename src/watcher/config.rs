@@ -1,11 +1,14 @@
 //! Watcher configuration management
 //!
-//! Handles persistent configuration for the folder watcher service.
+//! Handles persistent configuration for the folder watcher service and the
+//! database tables it polls.
 
 use anyhow::{Context as _, Result};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
+use super::table::TableWatch;
+
 /// Watcher configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -16,6 +19,10 @@ pub struct WatcherConfig {
     pub folder: PathBuf,
     /// Whether to automatically ingest new files
     pub auto_ingest: bool,
+    /// Whether to refresh lifecycle datasets when their source file changes
+    pub auto_refresh: bool,
+    /// Database tables polled for changes
+    pub tables: Vec<TableWatch>,
 }
 
 impl Default for WatcherConfig {
@@ -24,6 +31,8 @@ impl Default for WatcherConfig {
             enabled: false,
             folder: PathBuf::new(),
             auto_ingest: true,
+            auto_refresh: false,
+            tables: Vec::new(),
         }
     }
 }
@@ -61,15 +61,67 @@ pub struct IngestStartedPayload {
 pub struct IngestSucceededPayload {
     pub path: String,
     pub dataset_id: String,
+    /// Set when the file was added as a raw version of an existing dataset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rows: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cols: Option<usize>,
 }
 
+/// Pipeline run on a file from the watched folder completed event payload
+#[derive(Debug, Clone, Serialize)]
+pub struct FilePipelinePayload {
+    pub path: String,
+    pub pipeline: String,
+    pub output: String,
+    pub rows: usize,
+    pub warnings: Vec<String>,
+}
+
 /// Ingestion failed event payload
 #[derive(Debug, Clone, Serialize)]
 pub struct IngestFailedPayload {
     pub path: String,
     pub error: String,
 }
+
+/// Dataset refreshed after its source file changed event payload
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceRefreshedPayload {
+    pub path: String,
+    pub dataset_id: String,
+    pub active_version_id: String,
+    /// Versions recreated from the new source data
+    pub versions: usize,
+}
+
+/// Dataset refresh failed event payload
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceRefreshFailedPayload {
+    pub path: String,
+    pub dataset_id: String,
+    pub error: String,
+}
+
+/// Polled table changed event payload
+#[derive(Debug, Clone, Serialize)]
+pub struct TableChangedPayload {
+    pub watch_id: String,
+    pub table: String,
+    pub row_count: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_updated_at: Option<String>,
+    pub detected_at: String, // ISO datetime
+}
+
+/// Pipeline run on a changed table completed event payload
+#[derive(Debug, Clone, Serialize)]
+pub struct TablePipelinePayload {
+    pub watch_id: String,
+    pub table: String,
+    pub pipeline: String,
+    pub rows: usize,
+    pub warnings: Vec<String>,
+}
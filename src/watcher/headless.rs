@@ -0,0 +1,344 @@
+//! Folder watching without the GUI
+//!
+//! [`watch_folder`] runs the folder watcher on the calling thread for
+//! `beefcake watch`, so a server can run beefcake as an ingestion daemon.
+//! Each CSV/JSON file dropped into the folder is waited on until it stops
+//! growing, then run through a pipeline (or, without one, analysed into a
+//! dataset as the GUI watcher does). Files are processed one at a time in
+//! the order they settle, and every step is reported as a [`WatchEvent`].
+
+use anyhow::{Context as _, Result};
+use chrono::Local;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher as _};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::channel;
+
+use super::events::{
+    FileDetectedPayload, FilePipelinePayload, IngestFailedPayload, IngestStartedPayload,
+    IngestSucceededPayload,
+};
+use super::service::{STABILITY_CHECK_INTERVAL, StabilityChecker, WatcherService};
+use crate::config::policy::{Capability, PathPolicy};
+use crate::pipeline::PipelineSpec;
+
+/// What to watch and what to do with the files that arrive
+#[derive(Debug, Clone)]
+pub struct HeadlessWatch {
+    pub folder: PathBuf,
+    /// Spec run over each file; without one files are analysed into datasets
+    pub pipeline: Option<PipelineSpec>,
+    /// Folder for pipeline outputs, each named after its input. Without one
+    /// the spec's `output.path_template` is used (see `{input_stem}`).
+    pub output_dir: Option<PathBuf>,
+    /// Also process the files already in the folder when watching starts
+    pub include_existing: bool,
+    /// Move each processed file to the processed-files folder
+    pub archive: bool,
+}
+
+/// Something that happened while watching, in the order it happened
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    Started {
+        folder: String,
+    },
+    FileDetected(FileDetectedPayload),
+    IngestStarted(IngestStartedPayload),
+    /// The file was analysed into a dataset
+    IngestSucceeded(IngestSucceededPayload),
+    /// The pipeline ran over the file
+    PipelineSucceeded(FilePipelinePayload),
+    IngestFailed(IngestFailedPayload),
+    Archived {
+        path: String,
+        archived_to: String,
+    },
+    Stopped {
+        folder: String,
+    },
+}
+
+impl std::fmt::Display for WatchEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Started { folder } => write!(f, "Watching {folder}"),
+            Self::FileDetected(e) => write!(f, "Detected {}", e.path),
+            Self::IngestStarted(e) => write!(f, "Processing {}", e.path),
+            Self::IngestSucceeded(e) => write!(
+                f,
+                "Ingested {} ({} rows, {} cols) -> dataset {}",
+                e.path,
+                e.rows.unwrap_or_default(),
+                e.cols.unwrap_or_default(),
+                e.dataset_id
+            ),
+            Self::PipelineSucceeded(e) => {
+                write!(
+                    f,
+                    "Ran pipeline '{}' on {}: {} rows -> {}",
+                    e.pipeline, e.path, e.rows, e.output
+                )?;
+                if !e.warnings.is_empty() {
+                    write!(f, " ({} warnings)", e.warnings.len())?;
+                }
+                Ok(())
+            }
+            Self::IngestFailed(e) => write!(f, "Failed {}: {}", e.path, e.error),
+            Self::Archived { path, archived_to } => write!(f, "Archived {path} to {archived_to}"),
+            Self::Stopped { folder } => write!(f, "Stopped watching {folder}"),
+        }
+    }
+}
+
+impl WatchEvent {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::IngestFailed(_))
+    }
+}
+
+/// Watch `watch.folder` until `stop` is set, calling `on_event` for every
+/// event. Only fails if the folder cannot be watched; a file that cannot be
+/// processed is reported as [`WatchEvent::IngestFailed`] and left in place.
+pub fn watch_folder(
+    watch: &HeadlessWatch,
+    stop: &AtomicBool,
+    mut on_event: impl FnMut(&WatchEvent),
+) -> Result<()> {
+    PathPolicy::load().ensure_not_denied(&watch.folder, Capability::Read)?;
+    if !watch.folder.is_dir() {
+        anyhow::bail!("Folder not found: {}", watch.folder.display());
+    }
+    if let Some(output_dir) = &watch.output_dir
+        && same_folder(output_dir, &watch.folder)
+    {
+        anyhow::bail!("The output folder must differ from the watched folder");
+    }
+
+    let (file_tx, file_rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
+        if let Ok(event) = res {
+            let _ = file_tx.send(event);
+        }
+    })
+    .context("Failed to create watcher")?;
+    watcher
+        .watch(&watch.folder, RecursiveMode::NonRecursive)
+        .with_context(|| format!("Failed to watch folder: {}", watch.folder.display()))?;
+
+    let mut emit = |event: WatchEvent| {
+        crate::config::log_event("Watcher", &event.to_string());
+        on_event(&event);
+    };
+    let folder = watch.folder.display().to_string();
+    emit(WatchEvent::Started {
+        folder: folder.clone(),
+    });
+
+    let mut pending: Vec<StabilityChecker> = Vec::new();
+    if watch.include_existing {
+        let mut existing: Vec<PathBuf> = std::fs::read_dir(&watch.folder)
+            .with_context(|| format!("Failed to list {}", watch.folder.display()))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        existing.sort();
+        for path in existing {
+            detect(path, &mut pending, &mut emit);
+        }
+    }
+
+    while !stop.load(Ordering::Relaxed) {
+        while let Ok(event) = file_rx.try_recv() {
+            // Files moved into the folder arrive as renames
+            if matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+            ) {
+                for path in event.paths {
+                    detect(path, &mut pending, &mut emit);
+                }
+            }
+        }
+
+        let mut settled = Vec::new();
+        let mut unsettled = Vec::new();
+        pending.retain_mut(|checker| match checker.check() {
+            Ok(false) => true,
+            Ok(true) => {
+                settled.push(checker.path.clone());
+                false
+            }
+            Err(e) => {
+                unsettled.push((checker.path.clone(), e));
+                false
+            }
+        });
+        for (path, e) in unsettled {
+            emit(WatchEvent::IngestFailed(IngestFailedPayload {
+                path: path.display().to_string(),
+                error: format!("Stability check failed: {e}"),
+            }));
+        }
+        for path in settled {
+            process_file(watch, &path, &mut emit);
+        }
+        std::thread::sleep(STABILITY_CHECK_INTERVAL);
+    }
+
+    emit(WatchEvent::Stopped { folder });
+    crate::config::flush_pending_audit_entries();
+    Ok(())
+}
+
+/// Start waiting for the file at `path` to settle, unless it is not a
+/// supported file or is already waited on
+fn detect(path: PathBuf, pending: &mut Vec<StabilityChecker>, emit: &mut impl FnMut(WatchEvent)) {
+    if !path.is_file()
+        || !WatcherService::is_supported_file(&path)
+        || pending.iter().any(|c| c.path == path)
+    {
+        return;
+    }
+    emit(WatchEvent::FileDetected(FileDetectedPayload {
+        path: path.display().to_string(),
+        file_type: path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default(),
+        detected_at: Local::now().to_rfc3339(),
+    }));
+    pending.push(StabilityChecker::new(path));
+}
+
+/// Run the pipeline over, or ingest, the settled file at `path`
+fn process_file(watch: &HeadlessWatch, path: &Path, emit: &mut impl FnMut(WatchEvent)) {
+    let display = path.display().to_string();
+    emit(WatchEvent::IngestStarted(IngestStartedPayload {
+        path: display.clone(),
+    }));
+
+    let outcome = match &watch.pipeline {
+        Some(spec) => run_on_file(spec, watch.output_dir.as_deref(), path),
+        None => WatcherService::ingest_file(path).map(|(dataset_id, rows, cols)| {
+            WatchEvent::IngestSucceeded(IngestSucceededPayload {
+                path: display.clone(),
+                dataset_id: dataset_id.to_string(),
+                version_id: None,
+                rows: Some(rows),
+                cols: Some(cols),
+            })
+        }),
+    };
+    match outcome {
+        Ok(event) => emit(event),
+        Err(e) => {
+            emit(WatchEvent::IngestFailed(IngestFailedPayload {
+                path: display,
+                error: format!("{e:#}"),
+            }));
+            return;
+        }
+    }
+
+    if watch.archive {
+        emit(match crate::utils::archive_processed_file(path) {
+            Ok(to) => WatchEvent::Archived {
+                path: display,
+                archived_to: to.display().to_string(),
+            },
+            Err(e) => WatchEvent::IngestFailed(IngestFailedPayload {
+                path: display,
+                error: format!("Processed, but failed to archive: {e}"),
+            }),
+        });
+    }
+}
+
+fn run_on_file(spec: &PipelineSpec, output_dir: Option<&Path>, path: &Path) -> Result<WatchEvent> {
+    PathPolicy::load().ensure_not_denied(path, Capability::Read)?;
+    let output = output_dir.map(|dir| {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        dir.join(format!("{stem}.{}", spec.output.format))
+    });
+    let report = crate::pipeline::run_pipeline(spec, path, output.as_ref())?;
+    Ok(WatchEvent::PipelineSucceeded(FilePipelinePayload {
+        path: path.display().to_string(),
+        pipeline: spec.name.clone(),
+        output: report.output_path.display().to_string(),
+        rows: report.rows_after,
+        warnings: report.warnings,
+    }))
+}
+
+fn same_folder(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::Step;
+
+    #[test]
+    fn test_watch_folder_runs_pipeline_on_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let incoming = dir.path().join("incoming");
+        let processed = dir.path().join("processed");
+        std::fs::create_dir_all(&incoming).unwrap();
+        std::fs::create_dir_all(&processed).unwrap();
+        std::fs::write(incoming.join("orders.csv"), "id,amount\n1,10\n2,20\n").unwrap();
+        std::fs::write(incoming.join("notes.txt"), "ignored").unwrap();
+
+        let mut spec = PipelineSpec::new("orders");
+        spec.output.format = "csv".to_owned();
+        spec.steps.push(
+            Step::DropColumns {
+                columns: vec!["amount".to_owned()],
+            }
+            .into(),
+        );
+        let watch = HeadlessWatch {
+            folder: incoming,
+            pipeline: Some(spec),
+            output_dir: Some(processed.clone()),
+            include_existing: true,
+            archive: false,
+        };
+
+        let stop = AtomicBool::new(false);
+        let mut events = Vec::new();
+        watch_folder(&watch, &stop, |event| {
+            events.push(event.clone());
+            if matches!(
+                event,
+                WatchEvent::PipelineSucceeded(_) | WatchEvent::IngestFailed(_)
+            ) {
+                stop.store(true, Ordering::Relaxed);
+            }
+        })
+        .unwrap();
+
+        let kinds: Vec<_> = events
+            .iter()
+            .map(|e| serde_json::to_value(e).unwrap()["event"].clone())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "started",
+                "file_detected",
+                "ingest_started",
+                "pipeline_succeeded",
+                "stopped"
+            ]
+        );
+        let written = std::fs::read_to_string(processed.join("orders.csv")).unwrap();
+        assert_eq!(written.lines().next(), Some("id"));
+    }
+}
@@ -1,6 +1,8 @@
 //! Watcher service implementation
 //!
-//! Monitors a folder for new CSV/JSON files and handles ingestion.
+//! Monitors a folder for new CSV/JSON files and handles ingestion, refreshes
+//! lifecycle datasets whose source file changes, and runs the database table
+//! poller alongside it.
 
 #![allow(
     clippy::unwrap_used,
@@ -13,6 +15,7 @@
 use anyhow::{Context as _, Result};
 use chrono::Local;
 use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Receiver, Sender, channel};
 use std::sync::{Arc, Mutex};
@@ -22,14 +25,15 @@ use tauri::{AppHandle, Emitter as _};
 use super::config::WatcherConfig;
 use super::events::{
     FileDetectedPayload, FileReadyPayload, IngestFailedPayload, IngestStartedPayload,
-    IngestSucceededPayload, WatcherServiceState, WatcherStatusPayload,
+    IngestSucceededPayload, SourceRefreshFailedPayload, SourceRefreshedPayload,
+    WatcherServiceState, WatcherStatusPayload,
 };
 
 /// Maximum time to wait for file stability (30 seconds)
 const STABILITY_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Time between stability checks (500ms)
-const STABILITY_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+pub(super) const STABILITY_CHECK_INTERVAL: Duration = Duration::from_millis(500);
 
 /// Number of consecutive unchanged checks required for stability
 const STABILITY_REQUIRED_CHECKS: u32 = 3;
@@ -46,15 +50,15 @@ pub enum WatcherMessage {
 }
 
 /// File stability checker
-struct StabilityChecker {
-    path: PathBuf,
+pub(super) struct StabilityChecker {
+    pub(super) path: PathBuf,
     last_size: u64,
     unchanged_count: u32,
     start_time: Instant,
 }
 
 impl StabilityChecker {
-    fn new(path: PathBuf) -> Self {
+    pub(super) fn new(path: PathBuf) -> Self {
         Self {
             path,
             last_size: 0,
@@ -64,7 +68,7 @@ impl StabilityChecker {
     }
 
     /// Check if file is stable (size hasn't changed for N checks)
-    fn check(&mut self) -> Result<bool> {
+    pub(super) fn check(&mut self) -> Result<bool> {
         // Check timeout
         if self.start_time.elapsed() > STABILITY_TIMEOUT {
             anyhow::bail!("File stability timeout exceeded");
@@ -120,6 +124,13 @@ impl WatcherService {
             })
             .context("Failed to spawn watcher service thread")?;
 
+        let poller_app = app.clone();
+        let poller_state = Arc::clone(&service.state);
+        std::thread::Builder::new()
+            .name("watcher-table-poller".to_owned())
+            .spawn(move || super::table::run_poller(&poller_app, &poller_state))
+            .context("Failed to spawn table poller thread")?;
+
         Ok(service)
     }
 
@@ -132,6 +143,8 @@ impl WatcherService {
     ) {
         let (file_tx, file_rx) = channel();
         let mut _watcher: Option<Box<dyn Watcher + Send>> = None;
+        // Source files waiting to settle before their datasets are refreshed
+        let pending_refreshes = Arc::new(Mutex::new(HashSet::new()));
 
         // Main event loop
         loop {
@@ -197,10 +210,16 @@ impl WatcherService {
 
             // Check for file events (non-blocking)
             if let Ok(event) = file_rx.try_recv()
-                && let EventKind::Create(_) = event.kind
+                && matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
             {
+                let created = matches!(event.kind, EventKind::Create(_));
                 for path in event.paths {
-                    if Self::is_supported_file(&path) {
+                    if !Self::is_supported_file(&path) {
+                        continue;
+                    }
+                    if Self::is_refresh_source(&config, &path) {
+                        Self::handle_source_change(&app, &pending_refreshes, path);
+                    } else if created {
                         Self::handle_new_file(&app, &config, &state, path);
                     }
                 }
@@ -212,7 +231,7 @@ impl WatcherService {
     }
 
     /// Check if file extension is supported
-    fn is_supported_file(path: &Path) -> bool {
+    pub(super) fn is_supported_file(path: &Path) -> bool {
         if let Some(ext) = path.extension() {
             let ext_str = ext.to_string_lossy().to_lowercase();
             ext_str == "csv" || ext_str == "json"
@@ -221,6 +240,98 @@ impl WatcherService {
         }
     }
 
+    /// Whether `path` is the source of a registered lifecycle dataset that
+    /// should be refreshed rather than ingested again
+    fn is_refresh_source(config: &Arc<Mutex<WatcherConfig>>, path: &Path) -> bool {
+        let auto_refresh = config.lock().map(|cfg| cfg.auto_refresh).unwrap_or(false);
+        auto_refresh
+            && super::lifecycle_registry().is_some_and(|registry| {
+                registry
+                    .datasets_with_source(path)
+                    .is_ok_and(|ids| !ids.is_empty())
+            })
+    }
+
+    /// Refresh the datasets sourced from a changed file once it is stable.
+    /// Further events for the file while it settles are ignored.
+    fn handle_source_change(
+        app: &AppHandle,
+        pending: &Arc<Mutex<HashSet<PathBuf>>>,
+        path: PathBuf,
+    ) {
+        if !pending
+            .lock()
+            .map(|mut p| p.insert(path.clone()))
+            .unwrap_or(false)
+        {
+            return;
+        }
+        let Some(registry) = super::lifecycle_registry() else {
+            return;
+        };
+
+        let app_clone = app.clone();
+        let pending_clone = Arc::clone(pending);
+        std::thread::spawn(move || {
+            let mut checker = StabilityChecker::new(path.clone());
+            let stable = loop {
+                match checker.check() {
+                    Ok(true) => break Ok(()),
+                    Ok(false) => std::thread::sleep(STABILITY_CHECK_INTERVAL),
+                    Err(e) => break Err(e),
+                }
+            };
+            // Writes after this point trigger another refresh
+            if let Ok(mut p) = pending_clone.lock() {
+                p.remove(&path);
+            }
+            if let Err(e) = stable {
+                crate::config::log_event(
+                    "Watcher",
+                    &format!("Skipped refresh from {}: {e}", path.display()),
+                );
+                return;
+            }
+
+            let outcomes = match registry.refresh_from_path(&path) {
+                Ok(outcomes) => outcomes,
+                Err(e) => {
+                    crate::config::log_event("Watcher", &format!("Refresh failed: {e}"));
+                    return;
+                }
+            };
+            for (dataset_id, outcome) in outcomes {
+                match outcome {
+                    Ok(report) => {
+                        let _ = app_clone.emit(
+                            "watcher:source_refreshed",
+                            SourceRefreshedPayload {
+                                path: path.display().to_string(),
+                                dataset_id: dataset_id.to_string(),
+                                active_version_id: report.active_version_id.to_string(),
+                                versions: report.versions.len(),
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        let _ = app_clone.emit(
+                            "watcher:source_refresh_failed",
+                            SourceRefreshFailedPayload {
+                                path: path.display().to_string(),
+                                dataset_id: dataset_id.to_string(),
+                                error: format!("{e:#}"),
+                            },
+                        );
+                        crate::config::log_event(
+                            "Watcher",
+                            &format!("Refresh of dataset {dataset_id} failed: {e:#}"),
+                        );
+                    }
+                }
+            }
+        });
+    }
+
     /// Handle a newly detected file
     fn handle_new_file(
         app: &AppHandle,
@@ -319,26 +430,31 @@ impl WatcherService {
             );
 
             // Perform actual ingestion
-            match Self::ingest_file(&path_clone) {
-                Ok((dataset_id, rows, cols)) => {
+            match Self::ingest_watched_file(&path_clone) {
+                Ok((dataset_id, version_id, rows, cols)) => {
                     let _ = app_clone.emit(
                         "watcher:ingest_succeeded",
                         IngestSucceededPayload {
                             path: path_clone.display().to_string(),
                             dataset_id: dataset_id.to_string(),
+                            version_id: version_id.map(|id| id.to_string()),
                             rows: Some(rows),
                             cols: Some(cols),
                         },
                     );
 
+                    let target = version_id.map_or_else(
+                        || format!("dataset {dataset_id}"),
+                        |id| format!("raw version {id} of dataset {dataset_id}"),
+                    );
                     crate::config::log_event(
                         "Watcher",
                         &format!(
-                            "Successfully ingested {} ({} rows, {} cols) -> dataset {}",
+                            "Successfully ingested {} ({} rows, {} cols) -> {}",
                             path_clone.display(),
                             rows,
                             cols,
-                            dataset_id
+                            target
                         ),
                     );
                 }
@@ -362,13 +478,53 @@ impl WatcherService {
         });
     }
 
+    /// Ingest a file from the watched folder. A file matching the source
+    /// pattern of datasets in the shared registry is added to each as a new
+    /// raw version; any other file becomes a new dataset.
+    /// Returns (`dataset_id`, `version_id` if versioned, `row_count`, `col_count`)
+    fn ingest_watched_file(path: &Path) -> Result<(uuid::Uuid, Option<uuid::Uuid>, usize, usize)> {
+        use crate::analyser::logic::flows::analyze_file_flow;
+        use crate::config::policy::{Capability, PathPolicy};
+
+        let registry = super::lifecycle_registry();
+        let dataset_ids = match registry {
+            Some(registry) => registry.datasets_matching_pattern(path)?,
+            None => Vec::new(),
+        };
+        let (Some(registry), Some(&first_id)) = (registry, dataset_ids.first()) else {
+            let (dataset_id, rows, cols) = Self::ingest_file(path)?;
+            return Ok((dataset_id, None, rows, cols));
+        };
+
+        PathPolicy::load().ensure_not_denied(path, Capability::Read)?;
+        let rt = tokio::runtime::Runtime::new()?;
+        let analysis_response = rt.block_on(analyze_file_flow(path.to_path_buf()))?;
+
+        let mut first_version = None;
+        for dataset_id in &dataset_ids {
+            let version_id = registry
+                .add_source_version(dataset_id, path)
+                .with_context(|| format!("Failed to add a version to dataset {dataset_id}"))?;
+            first_version.get_or_insert(version_id);
+        }
+        Ok((
+            first_id,
+            first_version,
+            analysis_response.total_row_count,
+            analysis_response.column_count,
+        ))
+    }
+
     /// Ingest a file and create a lifecycle dataset
     /// Returns (`dataset_id`, `row_count`, `col_count`)
-    fn ingest_file(path: &Path) -> Result<(uuid::Uuid, usize, usize)> {
+    pub(super) fn ingest_file(path: &Path) -> Result<(uuid::Uuid, usize, usize)> {
         use crate::analyser::lifecycle::{
             DatasetRegistry, stages::LifecycleStage, transforms::TransformPipeline,
         };
         use crate::analyser::logic::flows::analyze_file_flow;
+        use crate::config::policy::{Capability, PathPolicy};
+
+        PathPolicy::load().ensure_not_denied(path, Capability::Read)?;
 
         // Run analysis on the file
         let rt = tokio::runtime::Runtime::new()?;
@@ -407,7 +563,7 @@ impl WatcherService {
     }
 
     /// Emit status event to frontend
-    fn emit_status(
+    pub(super) fn emit_status(
         app: &AppHandle,
         state: &Arc<Mutex<WatcherServiceState>>,
         message: Option<String>,
@@ -0,0 +1,440 @@
+//! Database table polling
+//!
+//! Polls registered PostgreSQL tables on an interval and, when a table's row
+//! count or latest update timestamp changes, lands its rows as a Parquet file
+//! and either analyses it (like a dropped file) or runs a pipeline over it.
+
+use anyhow::{Context as _, Result};
+use chrono::Local;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use sqlx::Connection as _;
+use sqlx::postgres::{PgConnectOptions, PgConnection};
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::str::FromStr as _;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter as _};
+
+use super::config::WatcherConfig;
+use super::events::{
+    IngestFailedPayload, IngestStartedPayload, IngestSucceededPayload, TableChangedPayload,
+    TablePipelinePayload, WatcherServiceState,
+};
+use super::service::WatcherService;
+use crate::analyser::db::ddl::quote;
+
+/// Shortest allowed interval between polls of one table
+pub const MIN_POLL_INTERVAL_SECS: u64 = 10;
+
+/// How often the poller wakes to check for due tables
+const POLL_TICK: Duration = Duration::from_secs(1);
+
+/// How often the poller re-reads the watcher config for added/removed tables
+const CONFIG_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A database table registered for polling
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TableWatch {
+    /// Unique id, assigned when the watch is added
+    pub id: String,
+    /// Whether this table is polled
+    pub enabled: bool,
+    /// Id of a saved database connection
+    pub connection_id: String,
+    /// Schema name (empty for the search path default)
+    pub schema: String,
+    pub table: String,
+    /// Timestamp column whose maximum is compared between polls, in
+    /// addition to the row count, to catch in-place updates
+    pub updated_at_column: Option<String>,
+    /// Seconds between polls (at least [`MIN_POLL_INTERVAL_SECS`])
+    pub interval_secs: u64,
+    /// What to do when the table changes
+    pub action: TableAction,
+}
+
+impl Default for TableWatch {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            enabled: true,
+            connection_id: String::new(),
+            schema: String::new(),
+            table: String::new(),
+            updated_at_column: None,
+            interval_secs: 300,
+            action: TableAction::default(),
+        }
+    }
+}
+
+impl TableWatch {
+    /// `schema.table` for display
+    pub fn display_name(&self) -> String {
+        if self.schema.is_empty() {
+            self.table.clone()
+        } else {
+            format!("{}.{}", self.schema, self.table)
+        }
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs.max(MIN_POLL_INTERVAL_SECS))
+    }
+
+    /// Quoted `"schema"."table"` identifier
+    fn identifier(&self) -> String {
+        if self.schema.is_empty() {
+            quote(&self.table)
+        } else {
+            format!("{}.{}", quote(&self.schema), quote(&self.table))
+        }
+    }
+
+    /// Query returning the row count and latest update timestamp as text
+    fn snapshot_query(&self) -> String {
+        let max_updated = self
+            .updated_at_column
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .map_or_else(
+                || "NULL::text".to_owned(),
+                |c| format!("MAX({})::text", quote(c)),
+            );
+        format!(
+            "SELECT COUNT(*)::bigint, {max_updated} FROM {}",
+            self.identifier()
+        )
+    }
+}
+
+/// Action taken when a polled table changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TableAction {
+    /// Analyse the landed rows and register them as a dataset
+    #[default]
+    Analyse,
+    /// Run a pipeline spec over the landed rows
+    Pipeline {
+        spec_path: PathBuf,
+        /// Overrides the spec's `output.path_template`
+        #[serde(default)]
+        output_path: Option<PathBuf>,
+    },
+}
+
+/// What a poll saw of a table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSnapshot {
+    pub row_count: i64,
+    pub max_updated_at: Option<String>,
+}
+
+/// Tracks when each table is next due and what it looked like last time
+#[derive(Debug, Default)]
+struct PollSchedule {
+    next_due: HashMap<String, Instant>,
+    last_seen: HashMap<String, TableSnapshot>,
+}
+
+impl PollSchedule {
+    /// Enabled watches whose interval has elapsed; each is rescheduled
+    fn take_due(&mut self, watches: &[TableWatch], now: Instant) -> Vec<TableWatch> {
+        self.next_due
+            .retain(|id, _| watches.iter().any(|w| w.enabled && w.id == *id));
+        let mut due = Vec::new();
+        for watch in watches.iter().filter(|w| w.enabled) {
+            let next = self.next_due.entry(watch.id.clone()).or_insert(now);
+            if *next <= now {
+                *next = now + watch.interval();
+                due.push(watch.clone());
+            }
+        }
+        due
+    }
+
+    /// Record a snapshot, returning whether it differs from the previous
+    /// one. The first snapshot of a table is only a baseline.
+    fn record(&mut self, id: &str, snapshot: TableSnapshot) -> bool {
+        match self.last_seen.insert(id.to_owned(), snapshot.clone()) {
+            Some(previous) => previous != snapshot,
+            None => false,
+        }
+    }
+}
+
+/// Poll loop run on its own thread for the lifetime of the watcher service
+pub(super) fn run_poller(app: &AppHandle, state: &Arc<Mutex<WatcherServiceState>>) {
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            crate::config::log_event("Watcher", &format!("Table poller failed to start: {e}"));
+            return;
+        }
+    };
+
+    let mut schedule = PollSchedule::default();
+    let mut watches = Vec::new();
+    let mut last_reload: Option<Instant> = None;
+
+    loop {
+        if last_reload.is_none_or(|t| t.elapsed() >= CONFIG_RELOAD_INTERVAL) {
+            watches = WatcherConfig::load()
+                .map(|config| config.tables)
+                .unwrap_or_default();
+            last_reload = Some(Instant::now());
+        }
+
+        for watch in schedule.take_due(&watches, Instant::now()) {
+            let snapshot = match rt.block_on(take_snapshot(&watch)) {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    crate::config::log_event(
+                        "Watcher",
+                        &format!("Failed to poll {}: {e}", watch.display_name()),
+                    );
+                    continue;
+                }
+            };
+            if schedule.record(&watch.id, snapshot.clone()) {
+                handle_table_change(app, state, &rt, &watch, &snapshot);
+            }
+        }
+
+        std::thread::sleep(POLL_TICK);
+    }
+}
+
+fn handle_table_change(
+    app: &AppHandle,
+    state: &Arc<Mutex<WatcherServiceState>>,
+    rt: &tokio::runtime::Runtime,
+    watch: &TableWatch,
+    snapshot: &TableSnapshot,
+) {
+    let name = watch.display_name();
+    let _ = app.emit(
+        "watcher:table_changed",
+        TableChangedPayload {
+            watch_id: watch.id.clone(),
+            table: name.clone(),
+            row_count: snapshot.row_count,
+            max_updated_at: snapshot.max_updated_at.clone(),
+            detected_at: Local::now().to_rfc3339(),
+        },
+    );
+    crate::config::log_event(
+        "Watcher",
+        &format!("Detected change in {name} ({} rows)", snapshot.row_count),
+    );
+
+    if let Ok(mut s) = state.lock() {
+        *s = WatcherServiceState::Ingesting;
+    }
+    WatcherService::emit_status(app, state, None);
+    let _ = app.emit(
+        "watcher:ingest_started",
+        IngestStartedPayload { path: name.clone() },
+    );
+
+    let result = rt
+        .block_on(land_table(watch))
+        .and_then(|landed| run_action(app, watch, &landed));
+    if let Err(e) = result {
+        let _ = app.emit(
+            "watcher:ingest_failed",
+            IngestFailedPayload {
+                path: name.clone(),
+                error: format!("Table ingestion failed: {e}"),
+            },
+        );
+        crate::config::log_event("Watcher", &format!("Ingestion of {name} failed: {e}"));
+    }
+
+    // Only return to watching if the folder watcher had been running
+    if let Ok(mut s) = state.lock() {
+        *s = if WatcherConfig::load().is_ok_and(|c| c.enabled) {
+            WatcherServiceState::Watching
+        } else {
+            WatcherServiceState::Idle
+        };
+    }
+    WatcherService::emit_status(app, state, None);
+}
+
+fn run_action(app: &AppHandle, watch: &TableWatch, landed: &Path) -> Result<()> {
+    let name = watch.display_name();
+    match &watch.action {
+        TableAction::Analyse => {
+            let (dataset_id, rows, cols) = WatcherService::ingest_file(landed)?;
+            let _ = app.emit(
+                "watcher:ingest_succeeded",
+                IngestSucceededPayload {
+                    path: name.clone(),
+                    dataset_id: dataset_id.to_string(),
+                    version_id: None,
+                    rows: Some(rows),
+                    cols: Some(cols),
+                },
+            );
+            crate::config::log_event(
+                "Watcher",
+                &format!("Ingested {name} ({rows} rows, {cols} cols) -> dataset {dataset_id}"),
+            );
+        }
+        TableAction::Pipeline {
+            spec_path,
+            output_path,
+        } => {
+            let spec = crate::pipeline::PipelineSpec::from_file(spec_path).with_context(|| {
+                format!("Failed to load pipeline spec: {}", spec_path.display())
+            })?;
+            let report = crate::pipeline::run_pipeline(&spec, landed, output_path.as_ref())?;
+            let _ = app.emit(
+                "watcher:pipeline_completed",
+                TablePipelinePayload {
+                    watch_id: watch.id.clone(),
+                    table: name.clone(),
+                    pipeline: spec.name.clone(),
+                    rows: report.rows_after,
+                    warnings: report.warnings.clone(),
+                },
+            );
+            crate::config::log_event(
+                "Watcher",
+                &format!(
+                    "Ran pipeline '{}' on {name}: {}",
+                    spec.name,
+                    report.summary()
+                ),
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn connect(watch: &TableWatch) -> Result<PgConnection> {
+    let config = crate::config::load_app_config();
+    let conn = config
+        .settings
+        .connections
+        .iter()
+        .find(|c| c.id == watch.connection_id)
+        .with_context(|| format!("Connection not found: {}", watch.connection_id))?;
+    let url = conn.settings.connection_string(&conn.id);
+    let opts = PgConnectOptions::from_str(&url).context("Invalid connection URL")?;
+    config
+        .settings
+        .retry_policy
+        .retry_async("Connecting to PostgreSQL", || async {
+            PgConnection::connect_with(&opts)
+                .await
+                .context("Failed to connect to PostgreSQL")
+        })
+        .await
+}
+
+/// Read the current row count and latest update timestamp of a table
+pub async fn take_snapshot(watch: &TableWatch) -> Result<TableSnapshot> {
+    let mut conn = connect(watch).await?;
+    let (row_count, max_updated_at): (i64, Option<String>) =
+        sqlx::query_as(&watch.snapshot_query())
+            .fetch_one(&mut conn)
+            .await
+            .with_context(|| format!("Failed to query {}", watch.display_name()))?;
+    Ok(TableSnapshot {
+        row_count,
+        max_updated_at,
+    })
+}
+
+/// Copy every row of the table into a Parquet file under the input
+/// directory and return its path
+async fn land_table(watch: &TableWatch) -> Result<PathBuf> {
+    let mut conn = connect(watch).await?;
+    let rows: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT row_to_json(t)::text FROM {} AS t",
+        watch.identifier()
+    ))
+    .fetch_all(&mut conn)
+    .await
+    .with_context(|| format!("Failed to read {}", watch.display_name()))?;
+
+    let df = JsonReader::new(Cursor::new(rows.join("\n")))
+        .with_json_format(JsonFormat::JsonLines)
+        .finish()
+        .context("Failed to parse table rows")?;
+    let mut df = crate::analyser::logic::io::try_parse_temporal_columns(
+        df,
+        crate::analyser::logic::DateOrder::from_settings(),
+    )?;
+
+    let landing_dir = crate::utils::standard_paths().input_dir.join("tables");
+    std::fs::create_dir_all(&landing_dir)
+        .with_context(|| format!("Failed to create {}", landing_dir.display()))?;
+    let file_name = format!(
+        "{}_{}.parquet",
+        watch.display_name().replace(['.', '/', '\\'], "_"),
+        Local::now().format("%Y%m%d_%H%M%S")
+    );
+    let path = landing_dir.join(file_name);
+    crate::analyser::logic::save_df(&mut df, &path)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_query_quotes_identifiers() {
+        let mut watch = TableWatch {
+            schema: "sales".to_owned(),
+            table: "orders \"live\"".to_owned(),
+            ..TableWatch::default()
+        };
+        assert_eq!(
+            watch.snapshot_query(),
+            "SELECT COUNT(*)::bigint, NULL::text FROM \"sales\".\"orders \"\"live\"\"\""
+        );
+
+        watch.schema.clear();
+        watch.updated_at_column = Some("updated_at".to_owned());
+        assert_eq!(
+            watch.snapshot_query(),
+            "SELECT COUNT(*)::bigint, MAX(\"updated_at\")::text FROM \"orders \"\"live\"\"\""
+        );
+    }
+
+    #[test]
+    fn test_poll_schedule_detects_changes_after_baseline() {
+        let watch = TableWatch {
+            id: "w1".to_owned(),
+            table: "orders".to_owned(),
+            interval_secs: 1,
+            ..TableWatch::default()
+        };
+        let watches = vec![watch];
+        let mut schedule = PollSchedule::default();
+        let start = Instant::now();
+
+        assert_eq!(schedule.take_due(&watches, start).len(), 1);
+        assert!(schedule.take_due(&watches, start).is_empty());
+        let later = start + Duration::from_secs(MIN_POLL_INTERVAL_SECS);
+        assert_eq!(schedule.take_due(&watches, later).len(), 1);
+
+        let snapshot = |rows, updated: Option<&str>| TableSnapshot {
+            row_count: rows,
+            max_updated_at: updated.map(str::to_owned),
+        };
+        assert!(!schedule.record("w1", snapshot(10, None)));
+        assert!(!schedule.record("w1", snapshot(10, None)));
+        assert!(schedule.record("w1", snapshot(11, None)));
+        assert!(schedule.record("w1", snapshot(11, Some("2024-01-01"))));
+    }
+}